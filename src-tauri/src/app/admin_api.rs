@@ -0,0 +1,330 @@
+//! Usage: Local admin HTTP API for headless mode and the `aio-hub` terminal tool.
+//!
+//! 用法：
+//! - 桌面端与 headless 模式均会启动；仅绑定 `127.0.0.1:<admin_port>`，端口被占用时退回随机端口
+//! - 启动后写入 `<app_data_dir>/admin-api.json`（端口 + 本次运行的随机令牌），退出清理时删除
+//! - 所有 `/api/*` 请求需携带 `Authorization: Bearer <token>`
+//! - `GET /api/status`、`POST /api/gateway/start`（可选 `{ "preferred_port": 37123 }`）、`POST /api/gateway/stop`
+//! - `GET /api/providers?cli_key=claude`、`POST /api/circuits/reset`（`{ "provider_id": 1 }` 或 `{ "cli_key": "codex" }`）
+//! - `GET /api/request-logs?after_id=<id>&limit=<n>`（不带 `after_id` 时返回最近 n 条）、`GET /api/cost/today`
+//! - 处理逻辑直接复用 `commands/*` 中的同名命令，返回 JSON 与前端 `invoke()` 一致
+
+use crate::app_paths;
+use crate::app_state::{DbInitState, GatewayState};
+use crate::commands::{
+    cost as cost_commands, gateway as gateway_commands, providers as provider_commands,
+    request_logs as request_log_commands,
+};
+use crate::shared::cli_key::SUPPORTED_CLI_KEYS;
+use crate::shared::fs::write_file_atomic_private;
+use crate::{gateway, settings};
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::Manager;
+
+pub(crate) const DISCOVERY_FILE_NAME: &str = "admin-api.json";
+
+#[derive(Clone)]
+struct AdminApiState {
+    app: tauri::AppHandle,
+    token: Arc<str>,
+}
+
+#[derive(Debug, Serialize)]
+struct AdminApiDiscovery<'a> {
+    port: u16,
+    token: &'a str,
+    pid: u32,
+    headless: bool,
+    version: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct AdminStatusResponse {
+    app: &'static str,
+    version: &'static str,
+    headless: bool,
+    gateway: gateway::GatewayStatus,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GatewayStartRequest {
+    preferred_port: Option<u16>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProvidersQuery {
+    cli_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CircuitResetRequest {
+    provider_id: Option<i64>,
+    cli_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RequestLogsQuery {
+    after_id: Option<i64>,
+    limit: Option<u32>,
+}
+
+fn error_response(status: StatusCode, err: String) -> Response {
+    (status, Json(serde_json::json!({ "error": err }))).into_response()
+}
+
+fn json_result<T: Serialize>(result: Result<T, String>) -> Response {
+    match result {
+        Ok(value) => Json(value).into_response(),
+        Err(err) if err.starts_with("SEC_INVALID_INPUT") => {
+            error_response(StatusCode::BAD_REQUEST, err)
+        }
+        Err(err) => error_response(StatusCode::INTERNAL_SERVER_ERROR, err),
+    }
+}
+
+async fn require_admin_token(
+    State(state): State<AdminApiState>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let provided = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or_default();
+    if gateway::access::constant_time_eq(provided.as_bytes(), state.token.as_bytes()) {
+        return next.run(req).await;
+    }
+    error_response(
+        StatusCode::UNAUTHORIZED,
+        "ADMIN_TOKEN_INVALID: missing or invalid admin token".to_string(),
+    )
+}
+
+async fn status(State(state): State<AdminApiState>) -> Json<AdminStatusResponse> {
+    let gateway = gateway_commands::gateway_status(state.app.state::<GatewayState>());
+    Json(AdminStatusResponse {
+        app: "aio-coding-hub",
+        version: env!("CARGO_PKG_VERSION"),
+        headless: super::headless::is_headless(),
+        gateway,
+    })
+}
+
+async fn gateway_start(
+    State(state): State<AdminApiState>,
+    body: Option<Json<GatewayStartRequest>>,
+) -> Response {
+    let req = body.map(|Json(v)| v).unwrap_or_default();
+    let app = state.app.clone();
+    json_result(
+        gateway_commands::gateway_start(
+            app.clone(),
+            app.state::<DbInitState>(),
+            req.preferred_port,
+        )
+        .await,
+    )
+}
+
+async fn gateway_stop(State(state): State<AdminApiState>) -> Response {
+    let app = state.app.clone();
+    json_result(gateway_commands::gateway_stop(app.clone(), app.state::<GatewayState>()).await)
+}
+
+async fn providers_list(
+    State(state): State<AdminApiState>,
+    Query(query): Query<ProvidersQuery>,
+) -> Response {
+    let app = state.app.clone();
+    let cli_keys: Vec<String> = match query.cli_key {
+        Some(cli_key) => vec![cli_key],
+        None => SUPPORTED_CLI_KEYS.iter().map(|v| v.to_string()).collect(),
+    };
+
+    let mut out = Vec::new();
+    for cli_key in cli_keys {
+        match provider_commands::providers_list(app.clone(), app.state::<DbInitState>(), cli_key)
+            .await
+        {
+            Ok(items) => out.extend(items),
+            Err(err) => return json_result::<()>(Err(err)),
+        }
+    }
+    Json(out).into_response()
+}
+
+async fn circuits_reset(
+    State(state): State<AdminApiState>,
+    Json(req): Json<CircuitResetRequest>,
+) -> Response {
+    let app = state.app.clone();
+    match (req.provider_id, req.cli_key) {
+        (Some(provider_id), _) => json_result(
+            gateway_commands::gateway_circuit_reset_provider(
+                app.clone(),
+                app.state::<DbInitState>(),
+                provider_id,
+            )
+            .await
+            .map(|_| serde_json::json!({ "reset": 1 })),
+        ),
+        (None, Some(cli_key)) => json_result(
+            gateway_commands::gateway_circuit_reset_cli(
+                app.clone(),
+                app.state::<DbInitState>(),
+                cli_key,
+            )
+            .await
+            .map(|reset| serde_json::json!({ "reset": reset })),
+        ),
+        (None, None) => json_result::<()>(Err(
+            "SEC_INVALID_INPUT: provider_id or cli_key is required".to_string(),
+        )),
+    }
+}
+
+async fn request_logs(
+    State(state): State<AdminApiState>,
+    Query(query): Query<RequestLogsQuery>,
+) -> Response {
+    let app = state.app.clone();
+    let db_state = app.state::<DbInitState>();
+    let result = match query.after_id {
+        Some(after_id) => {
+            request_log_commands::request_logs_list_after_id_all(
+                app.clone(),
+                db_state,
+                after_id,
+                query.limit,
+            )
+            .await
+        }
+        None => {
+            request_log_commands::request_logs_list_all(app.clone(), db_state, query.limit).await
+        }
+    };
+    json_result(result)
+}
+
+async fn cost_today(State(state): State<AdminApiState>) -> Response {
+    let app = state.app.clone();
+    json_result(
+        cost_commands::cost_summary_v1(
+            app.clone(),
+            app.state::<DbInitState>(),
+            "daily".to_string(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await,
+    )
+}
+
+fn build_router(state: AdminApiState) -> Router {
+    Router::new()
+        .route("/api/status", get(status))
+        .route("/api/gateway/start", post(gateway_start))
+        .route("/api/gateway/stop", post(gateway_stop))
+        .route("/api/providers", get(providers_list))
+        .route("/api/circuits/reset", post(circuits_reset))
+        .route("/api/request-logs", get(request_logs))
+        .route("/api/cost/today", get(cost_today))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_admin_token,
+        ))
+        .with_state(state)
+}
+
+fn discovery_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_paths::app_data_dir(app)?.join(DISCOVERY_FILE_NAME))
+}
+
+fn write_discovery_file(app: &tauri::AppHandle, port: u16, token: &str) -> Result<(), String> {
+    let path = discovery_path(app)?;
+    let discovery = AdminApiDiscovery {
+        port,
+        token,
+        pid: std::process::id(),
+        headless: super::headless::is_headless(),
+        version: env!("CARGO_PKG_VERSION"),
+    };
+    let bytes = serde_json::to_vec_pretty(&discovery)
+        .map_err(|e| format!("ADMIN_API_DISCOVERY: failed to serialize: {e}"))?;
+    write_file_atomic_private(&path, &bytes)
+}
+
+pub(crate) fn remove_discovery_file(app: &tauri::AppHandle) {
+    let Ok(path) = discovery_path(app) else {
+        return;
+    };
+    if let Err(err) = std::fs::remove_file(&path) {
+        if err.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("删除管理 API 发现文件失败: {}", err);
+        }
+    }
+}
+
+pub(crate) fn spawn(app: &tauri::AppHandle, port: u16) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let token = match settings::generate_gateway_access_token() {
+            Ok(token) => token,
+            Err(err) => {
+                tracing::error!("管理 API 令牌生成失败: {}", err);
+                return;
+            }
+        };
+
+        let listener = match tokio::net::TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::warn!(port = port, "管理 API 端口不可用，改用随机端口: {}", err);
+                match tokio::net::TcpListener::bind(("127.0.0.1", 0)).await {
+                    Ok(listener) => listener,
+                    Err(err) => {
+                        tracing::error!("管理 API 监听失败: {}", err);
+                        return;
+                    }
+                }
+            }
+        };
+        let bound_port = match listener.local_addr() {
+            Ok(addr) => addr.port(),
+            Err(err) => {
+                tracing::error!("管理 API 获取监听地址失败: {}", err);
+                return;
+            }
+        };
+
+        if let Err(err) = write_discovery_file(&app, bound_port, &token) {
+            tracing::warn!("写入管理 API 发现文件失败: {}", err);
+        }
+        tracing::info!(port = bound_port, "管理 API 已启动");
+
+        let router = build_router(AdminApiState {
+            app,
+            token: Arc::from(token),
+        });
+        if let Err(err) = axum::serve(listener, router).await {
+            tracing::error!("管理 API 异常退出: {}", err);
+        }
+    });
+}
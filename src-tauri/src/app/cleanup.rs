@@ -3,6 +3,7 @@
 use super::app_state::GatewayState;
 use crate::blocking;
 use crate::cli_proxy;
+use crate::gateway;
 use crate::shared::mutex_ext::MutexExt;
 use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::OnceLock;
@@ -33,6 +34,7 @@ pub(crate) async fn cleanup_before_exit(app: &tauri::AppHandle) {
         Ordering::Acquire,
     ) {
         Ok(_) => {
+            super::admin_api::remove_discovery_file(app);
             stop_gateway_best_effort(app).await;
             restore_cli_proxy_keep_state_best_effort(
                 app,
@@ -41,6 +43,8 @@ pub(crate) async fn cleanup_before_exit(app: &tauri::AppHandle) {
                 true,
             )
             .await;
+            // Last step: on Windows the installer takes over and this process exits here.
+            super::updater::install_pending_best_effort(app).await;
 
             CLEANUP_STATE.store(CLEANUP_STATE_DONE, Ordering::Release);
             notify.notify_waiters();
@@ -125,18 +129,42 @@ pub(crate) async fn restore_cli_proxy_keep_state_best_effort(
 }
 
 pub(crate) async fn stop_gateway_best_effort(app: &tauri::AppHandle) {
+    let _ = stop_gateway_with_drain(app, Duration::ZERO).await;
+}
+
+/// Stops the gateway; with a non-zero `drain_timeout`, new requests are rejected first and in-flight
+/// requests (including SSE streams) get up to `drain_timeout` to finish before the server is aborted.
+pub(crate) async fn stop_gateway_with_drain(
+    app: &tauri::AppHandle,
+    drain_timeout: Duration,
+) -> Option<gateway::GatewayDrainReport> {
     let running = {
         let state = app.state::<GatewayState>();
         let mut manager = state.0.lock_or_recover();
         manager.take_running()
     };
 
-    let Some((shutdown, mut task, mut log_task, mut attempt_log_task, mut circuit_task)) = running
+    let Some((shutdown, in_flight, mut task, mut log_task, mut attempt_log_task, mut circuit_task)) =
+        running
     else {
-        return;
+        return None;
     };
 
-    let _ = shutdown.send(());
+    let report = if drain_timeout.is_zero() {
+        let _ = shutdown.send(());
+        None
+    } else {
+        in_flight.start_draining();
+        let _ = shutdown.send(());
+        let report = in_flight.wait_idle(drain_timeout).await;
+        tracing::info!(
+            drained = report.drained,
+            aborted = report.aborted,
+            waited_ms = report.waited_ms,
+            "网关停止：在途请求排空完成"
+        );
+        Some(report)
+    };
 
     let stop_timeout = Duration::from_secs(3);
     let join_all = async {
@@ -163,4 +191,6 @@ pub(crate) async fn stop_gateway_best_effort(app: &tauri::AppHandle) {
         })
         .await;
     }
+
+    report
 }
@@ -0,0 +1,207 @@
+//! Usage: Crash reports (panic hook + background task failure collector).
+//!
+//! Reports are plain-text files under `<app_data_dir>/crash-reports/`, each including a tail of the
+//! newest rolling log so "the gateway just stopped logging" cases leave a trace behind.
+
+use crate::app_paths;
+use crate::shared::time::now_unix_seconds;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+const CRASH_REPORT_SUBDIR: &str = "crash-reports";
+const CRASH_REPORT_FILE_PREFIX: &str = "crash-";
+const CRASH_REPORT_FILE_SUFFIX: &str = ".txt";
+const MAX_CRASH_REPORTS: usize = 20;
+const LOG_TAIL_LINES: usize = 200;
+
+static PANIC_HOOK_INSTALLED: OnceLock<()> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy)]
+enum CrashKind {
+    Panic,
+    TaskFailure,
+}
+
+impl CrashKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Panic => "panic",
+            Self::TaskFailure => "task_failure",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CrashReportSummary {
+    pub(crate) file_name: String,
+    pub(crate) kind: String,
+    pub(crate) created_at: i64,
+    pub(crate) size_bytes: u64,
+}
+
+pub(crate) fn crash_report_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_paths::app_data_dir(app)?.join(CRASH_REPORT_SUBDIR))
+}
+
+pub(crate) fn install_panic_hook(app: &tauri::AppHandle) {
+    PANIC_HOOK_INSTALLED.get_or_init(|| {
+        let app = app.clone();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let thread = std::thread::current();
+            let thread_name = thread.name().unwrap_or("<unnamed>").to_string();
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+                .unwrap_or_else(|| "<unknown>".to_string());
+            let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+                (*s).to_string()
+            } else if let Some(s) = info.payload().downcast_ref::<String>() {
+                s.clone()
+            } else {
+                "<non-string panic payload>".to_string()
+            };
+            let backtrace = std::backtrace::Backtrace::force_capture();
+
+            let detail = format!(
+                "thread: {thread_name}\nlocation: {location}\nmessage: {message}\n\nbacktrace:\n{backtrace}"
+            );
+            let _ = write_report(&app, CrashKind::Panic, "panic", &detail);
+
+            previous(info);
+        }));
+    });
+}
+
+pub(crate) fn record_task_failure(app: &tauri::AppHandle, task: &str, error: &str) {
+    tracing::error!(task = %task, "后台任务失败: {}", error);
+    if let Err(err) = write_report(app, CrashKind::TaskFailure, task, error) {
+        tracing::warn!(task = %task, "崩溃报告写入失败: {}", err);
+    }
+}
+
+fn write_report(
+    app: &tauri::AppHandle,
+    kind: CrashKind,
+    source: &str,
+    detail: &str,
+) -> Result<PathBuf, String> {
+    let dir = crash_report_dir(app)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("failed to create crash report dir {}: {e}", dir.display()))?;
+
+    let now_unix = now_unix_seconds();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let file_name = format!(
+        "{CRASH_REPORT_FILE_PREFIX}{now_unix}-{nanos:09}-{}{CRASH_REPORT_FILE_SUFFIX}",
+        kind.as_str()
+    );
+    let path = dir.join(file_name);
+
+    let log_tail = super::logging::recent_log_tail(app, LOG_TAIL_LINES)
+        .unwrap_or_else(|| "<log unavailable>".to_string());
+    let content = format!(
+        "AIO Coding Hub crash report\nkind: {}\nsource: {source}\ncreated_at: {now_unix}\napp_version: {}\nos: {}\narch: {}\n\n{detail}\n\n--- recent log (last {LOG_TAIL_LINES} lines) ---\n{log_tail}\n",
+        kind.as_str(),
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    );
+
+    std::fs::write(&path, content)
+        .map_err(|e| format!("failed to write crash report {}: {e}", path.display()))?;
+    prune_old_reports(&dir);
+    Ok(path)
+}
+
+fn parse_report_file_name(name: &str) -> Option<(i64, String)> {
+    let rest = name
+        .strip_prefix(CRASH_REPORT_FILE_PREFIX)?
+        .strip_suffix(CRASH_REPORT_FILE_SUFFIX)?;
+    let mut parts = rest.splitn(3, '-');
+    let created_at = parts.next()?.parse::<i64>().ok()?;
+    let _nanos = parts.next()?;
+    let kind = parts.next()?.to_string();
+    Some((created_at, kind))
+}
+
+fn report_paths(dir: &std::path::Path) -> Vec<(PathBuf, String)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut out: Vec<(PathBuf, String)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            parse_report_file_name(&name)?;
+            Some((entry.path(), name))
+        })
+        .collect();
+    // File names embed the unix timestamp, so lexical order == chronological order.
+    out.sort_by(|a, b| b.1.cmp(&a.1));
+    out
+}
+
+fn prune_old_reports(dir: &std::path::Path) {
+    for (path, _) in report_paths(dir).into_iter().skip(MAX_CRASH_REPORTS) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+pub(crate) fn list(app: &tauri::AppHandle) -> Result<Vec<CrashReportSummary>, String> {
+    let dir = crash_report_dir(app)?;
+    Ok(report_paths(&dir)
+        .into_iter()
+        .filter_map(|(path, name)| {
+            let (created_at, kind) = parse_report_file_name(&name)?;
+            let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            Some(CrashReportSummary {
+                file_name: name,
+                kind,
+                created_at,
+                size_bytes,
+            })
+        })
+        .collect())
+}
+
+pub(crate) fn report_files(app: &tauri::AppHandle) -> Result<Vec<(String, PathBuf)>, String> {
+    let dir = crash_report_dir(app)?;
+    Ok(report_paths(&dir)
+        .into_iter()
+        .map(|(path, name)| (name, path))
+        .collect())
+}
+
+pub(crate) fn clear(app: &tauri::AppHandle) -> Result<usize, String> {
+    let dir = crash_report_dir(app)?;
+    let mut deleted = 0usize;
+    for (path, _) in report_paths(&dir) {
+        match std::fs::remove_file(&path) {
+            Ok(()) => deleted = deleted.saturating_add(1),
+            Err(err) => {
+                tracing::warn!(path = %path.display(), "崩溃报告删除失败: {}", err);
+            }
+        }
+    }
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_report_file_name;
+
+    #[test]
+    fn parse_report_file_name_extracts_timestamp_and_kind() {
+        assert_eq!(
+            parse_report_file_name("crash-1700000000-000000123-task_failure.txt"),
+            Some((1700000000, "task_failure".to_string()))
+        );
+        assert_eq!(parse_report_file_name("crash-abc-1-panic.txt"), None);
+        assert_eq!(parse_report_file_name("other.txt"), None);
+    }
+}
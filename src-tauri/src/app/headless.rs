@@ -0,0 +1,132 @@
+//! Usage: Headless daemon mode (gateway + DB + admin API, no window / tray / webview).
+//!
+//! 用法：
+//! - 启动参数 `--headless`（或环境变量 `AIO_CODING_HUB_HEADLESS=1`）启用；适用于服务器 / CI 仅需代理功能的场景
+//! - 启用后走独立的启动路径（`lib.rs::run_headless`）：不注册插件与 IPC 命令，不创建窗口、托盘与 webview，跳过自动更新；网关仍按设置自动启动
+//! - 管理 API（见 `admin_api`）端口由 `--admin-port <port>`（或 `AIO_CODING_HUB_ADMIN_PORT`）指定，默认 37100
+//! - 收到 Ctrl+C / SIGTERM 时走与桌面端相同的退出清理流程
+
+use std::sync::OnceLock;
+
+pub(crate) const DEFAULT_ADMIN_API_PORT: u16 = 37100;
+
+const HEADLESS_FLAG: &str = "--headless";
+const ADMIN_PORT_FLAG: &str = "--admin-port";
+const HEADLESS_ENV: &str = "AIO_CODING_HUB_HEADLESS";
+const ADMIN_PORT_ENV: &str = "AIO_CODING_HUB_ADMIN_PORT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HeadlessOptions {
+    pub(crate) enabled: bool,
+    pub(crate) admin_port: u16,
+}
+
+static OPTIONS: OnceLock<HeadlessOptions> = OnceLock::new();
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_ascii_lowercase())
+        .is_some_and(|v| v == "1" || v == "true" || v == "yes")
+}
+
+fn parse_port(raw: &str) -> Option<u16> {
+    raw.trim().parse::<u16>().ok().filter(|port| *port >= 1024)
+}
+
+fn parse_args<I, S>(args: I, env_enabled: bool, env_admin_port: Option<&str>) -> HeadlessOptions
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut enabled = env_enabled;
+    let mut admin_port = env_admin_port.and_then(parse_port);
+
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        let arg = arg.as_ref();
+        if arg == HEADLESS_FLAG {
+            enabled = true;
+        } else if arg == ADMIN_PORT_FLAG {
+            if let Some(value) = iter.next() {
+                admin_port = parse_port(value.as_ref()).or(admin_port);
+            }
+        } else if let Some(value) = arg.strip_prefix("--admin-port=") {
+            admin_port = parse_port(value).or(admin_port);
+        }
+    }
+
+    HeadlessOptions {
+        enabled,
+        admin_port: admin_port.unwrap_or(DEFAULT_ADMIN_API_PORT),
+    }
+}
+
+pub(crate) fn options() -> HeadlessOptions {
+    *OPTIONS.get_or_init(|| {
+        let env_admin_port = std::env::var(ADMIN_PORT_ENV).ok();
+        parse_args(
+            std::env::args().skip(1),
+            env_flag(HEADLESS_ENV),
+            env_admin_port.as_deref(),
+        )
+    })
+}
+
+pub(crate) fn is_headless() -> bool {
+    options().enabled
+}
+
+/// Headless processes have no tray "退出" entry; map termination signals to `app.exit(0)` so the
+/// regular `ExitRequested` cleanup (stop gateway, restore CLI configs) still runs.
+pub(crate) fn install_signal_handlers(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            let mut terminate = match signal(SignalKind::terminate()) {
+                Ok(sig) => sig,
+                Err(err) => {
+                    tracing::warn!("无法监听 SIGTERM: {}", err);
+                    let _ = tokio::signal::ctrl_c().await;
+                    tracing::info!("收到 Ctrl+C，headless 模式开始退出");
+                    app.exit(0);
+                    return;
+                }
+            };
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => tracing::info!("收到 Ctrl+C，headless 模式开始退出"),
+                _ = terminate.recv() => tracing::info!("收到 SIGTERM，headless 模式开始退出"),
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = tokio::signal::ctrl_c().await;
+            tracing::info!("收到 Ctrl+C，headless 模式开始退出");
+        }
+
+        app.exit(0);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_args, DEFAULT_ADMIN_API_PORT};
+
+    #[test]
+    fn parses_headless_flag_and_admin_port() {
+        let opts = parse_args(["--headless", "--admin-port", "38000"], false, None);
+        assert!(opts.enabled);
+        assert_eq!(opts.admin_port, 38000);
+
+        let opts = parse_args(["--admin-port=80"], true, Some("39000"));
+        assert!(opts.enabled);
+        assert_eq!(opts.admin_port, 39000);
+
+        let opts = parse_args(Vec::<String>::new(), false, None);
+        assert!(!opts.enabled);
+        assert_eq!(opts.admin_port, DEFAULT_ADMIN_API_PORT);
+    }
+}
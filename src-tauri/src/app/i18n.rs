@@ -0,0 +1,216 @@
+//! Usage: Backend message catalog (localized text for notices / gateway logs / gateway errors).
+//!
+//! 用法：
+//! - 调用 `i18n::current_language(app)` 读取设置中的语言
+//! - `i18n::text(lang, MessageKey::X)` 取纯文本；`i18n::format(lang, key, &[("name", value)])` 替换 `{name}` 占位符
+
+use crate::settings::{self, AppLanguage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MessageKey {
+    NoticeTitleInfo,
+    NoticeTitleSuccess,
+    NoticeTitleWarning,
+    NoticeTitleError,
+    CircuitStateClosed,
+    CircuitStateOpen,
+    CircuitOpenedTitle,
+    CircuitRecoveredTitle,
+    CircuitReasonFailureThreshold,
+    CircuitReasonOpenExpired,
+    CircuitReasonRecoveryConfirmed,
+    CircuitLineCli,
+    CircuitLineProvider,
+    CircuitLineBaseUrl,
+    CircuitLineState,
+    CircuitLineFailures,
+    CircuitLineReason,
+    CircuitLineOpenUntil,
+    CircuitLineOpenUntilExpired,
+    CircuitLineOpenUntilNone,
+    CircuitLineTrace,
+    CircuitLineErrorCodes,
+    CircuitLineModels,
+    CircuitLineLastResponse,
+    CircuitActionResetProvider,
+    GatewayPortInUse,
+    GatewayExtraListenerBindFailed,
+    GatewayCliProxyGuardError,
+    GatewayCliProxyReadFailed,
+    GatewayCliProxyDisabled,
+    GatewayPaused,
+    GatewayPausedWithReason,
+    GatewayAllProvidersDownTitle,
+    GatewayAllProvidersDownBody,
+    QuietHoursSummaryTitle,
+    QuietHoursSummaryMore,
+    NoticeTestTitle,
+    NoticeTestBody,
+}
+
+pub(crate) fn current_language(app: &tauri::AppHandle) -> AppLanguage {
+    settings::read(app)
+        .map(|cfg| cfg.ui_language)
+        .unwrap_or_default()
+}
+
+fn template(language: AppLanguage, key: MessageKey) -> &'static str {
+    match language {
+        AppLanguage::ZhCn => template_zh_cn(key),
+        AppLanguage::En => template_en(key),
+    }
+}
+
+fn template_zh_cn(key: MessageKey) -> &'static str {
+    match key {
+        MessageKey::NoticeTitleInfo => "提示",
+        MessageKey::NoticeTitleSuccess => "成功",
+        MessageKey::NoticeTitleWarning => "提醒",
+        MessageKey::NoticeTitleError => "错误",
+        MessageKey::CircuitStateClosed => "正常",
+        MessageKey::CircuitStateOpen => "熔断",
+        MessageKey::CircuitOpenedTitle => "熔断触发：{provider}",
+        MessageKey::CircuitRecoveredTitle => "熔断恢复：{provider}",
+        MessageKey::CircuitReasonFailureThreshold => "失败次数达到阈值",
+        MessageKey::CircuitReasonOpenExpired => "熔断到期自动恢复",
+        MessageKey::CircuitReasonRecoveryConfirmed => "熔断到期后首个请求成功",
+        MessageKey::CircuitLineCli => "CLI：{cli}",
+        MessageKey::CircuitLineProvider => "Provider：{provider} (id={provider_id})",
+        MessageKey::CircuitLineBaseUrl => "Base URL：{base_url}",
+        MessageKey::CircuitLineState => "状态：{prev} → {next}",
+        MessageKey::CircuitLineFailures => "失败：{count} / {threshold}",
+        MessageKey::CircuitLineReason => "原因：{reason}（{code}）",
+        MessageKey::CircuitLineOpenUntil => "熔断至：{until}（约 {minutes} 分钟后）",
+        MessageKey::CircuitLineOpenUntilExpired => "熔断至：{until}（已到期）",
+        MessageKey::CircuitLineOpenUntilNone => "熔断至：—",
+        MessageKey::CircuitLineTrace => "Trace：{trace_id}",
+        MessageKey::CircuitLineErrorCodes => "错误码分布：{codes}",
+        MessageKey::CircuitLineModels => "受影响模型：{models}",
+        MessageKey::CircuitLineLastResponse => "最近上游响应：{status} {summary}",
+        MessageKey::CircuitActionResetProvider => "重置该供应商",
+        MessageKey::GatewayPortInUse => "端口 {requested} 被占用，已自动切换到 {bound}",
+        MessageKey::GatewayExtraListenerBindFailed => {
+            "专用端口 {port}（{cli}）绑定失败，已跳过：端口可能已被占用"
+        }
+        MessageKey::GatewayCliProxyGuardError => {
+            "CLI 代理开关状态读取失败（按未开启处理）cli={cli} trace_id={trace_id} err={err}"
+        }
+        MessageKey::GatewayCliProxyReadFailed => {
+            "CLI 代理状态读取失败（按未开启处理）：{err}；请在首页开启 {cli} 的 CLI 代理开关后重试"
+        }
+        MessageKey::GatewayCliProxyDisabled => {
+            "CLI 代理未开启：请在首页开启 {cli} 的 CLI 代理开关后重试"
+        }
+        MessageKey::GatewayPaused => "网关已被用户暂停（维护模式），请在恢复后重试",
+        MessageKey::GatewayPausedWithReason => {
+            "网关已被用户暂停（维护模式）：{reason}；请在恢复后重试"
+        }
+        MessageKey::GatewayAllProvidersDownTitle => "全部 Provider 不可用：{cli}",
+        MessageKey::GatewayAllProvidersDownBody => {
+            "熔断 {open} 个，冷却 {cooldown} 个；请求将返回 503\nTrace：{trace_id}"
+        }
+        MessageKey::QuietHoursSummaryTitle => "静默时段通知汇总（{count} 条）",
+        MessageKey::QuietHoursSummaryMore => "……另有 {count} 条",
+        MessageKey::NoticeTestTitle => "测试通知：{channel}",
+        MessageKey::NoticeTestBody => "这是一条测试消息，收到即表示通知渠道配置正确。",
+    }
+}
+
+fn template_en(key: MessageKey) -> &'static str {
+    match key {
+        MessageKey::NoticeTitleInfo => "Info",
+        MessageKey::NoticeTitleSuccess => "Success",
+        MessageKey::NoticeTitleWarning => "Warning",
+        MessageKey::NoticeTitleError => "Error",
+        MessageKey::CircuitStateClosed => "closed",
+        MessageKey::CircuitStateOpen => "open",
+        MessageKey::CircuitOpenedTitle => "Circuit opened: {provider}",
+        MessageKey::CircuitRecoveredTitle => "Circuit recovered: {provider}",
+        MessageKey::CircuitReasonFailureThreshold => "failure threshold reached",
+        MessageKey::CircuitReasonOpenExpired => "open period expired",
+        MessageKey::CircuitReasonRecoveryConfirmed => "first request after the open period succeeded",
+        MessageKey::CircuitLineCli => "CLI: {cli}",
+        MessageKey::CircuitLineProvider => "Provider: {provider} (id={provider_id})",
+        MessageKey::CircuitLineBaseUrl => "Base URL: {base_url}",
+        MessageKey::CircuitLineState => "State: {prev} → {next}",
+        MessageKey::CircuitLineFailures => "Failures: {count} / {threshold}",
+        MessageKey::CircuitLineReason => "Reason: {reason} ({code})",
+        MessageKey::CircuitLineOpenUntil => "Open until: {until} (in ~{minutes} min)",
+        MessageKey::CircuitLineOpenUntilExpired => "Open until: {until} (expired)",
+        MessageKey::CircuitLineOpenUntilNone => "Open until: —",
+        MessageKey::CircuitLineTrace => "Trace: {trace_id}",
+        MessageKey::CircuitLineErrorCodes => "Error codes: {codes}",
+        MessageKey::CircuitLineModels => "Affected models: {models}",
+        MessageKey::CircuitLineLastResponse => "Last upstream response: {status} {summary}",
+        MessageKey::CircuitActionResetProvider => "Reset provider",
+        MessageKey::GatewayPortInUse => "Port {requested} is in use; switched to {bound}",
+        MessageKey::GatewayExtraListenerBindFailed => {
+            "Failed to bind dedicated port {port} ({cli}); skipped, the port may be in use"
+        }
+        MessageKey::GatewayCliProxyGuardError => {
+            "failed to read CLI proxy switch (treated as disabled) cli={cli} trace_id={trace_id} err={err}"
+        }
+        MessageKey::GatewayCliProxyReadFailed => {
+            "failed to read CLI proxy state (treated as disabled): {err}; enable the {cli} CLI proxy on the Home page and retry"
+        }
+        MessageKey::GatewayCliProxyDisabled => {
+            "CLI proxy is disabled: enable the {cli} CLI proxy on the Home page and retry"
+        }
+        MessageKey::GatewayPaused => "gateway paused by user (maintenance mode); retry after it is resumed",
+        MessageKey::GatewayPausedWithReason => {
+            "gateway paused by user (maintenance mode): {reason}; retry after it is resumed"
+        }
+        MessageKey::GatewayAllProvidersDownTitle => "All providers unavailable: {cli}",
+        MessageKey::GatewayAllProvidersDownBody => {
+            "{open} open, {cooldown} cooling down; requests will fail with 503\nTrace: {trace_id}"
+        }
+        MessageKey::QuietHoursSummaryTitle => "Quiet hours summary ({count} notices)",
+        MessageKey::QuietHoursSummaryMore => "…and {count} more",
+        MessageKey::NoticeTestTitle => "Test notice: {channel}",
+        MessageKey::NoticeTestBody => {
+            "This is a test message. If you can read it, the notice channel is configured correctly."
+        }
+    }
+}
+
+pub(crate) fn text(language: AppLanguage, key: MessageKey) -> String {
+    template(language, key).to_string()
+}
+
+pub(crate) fn format(language: AppLanguage, key: MessageKey, args: &[(&str, &str)]) -> String {
+    let mut out = template(language, key).to_string();
+    for (name, value) in args {
+        out = out.replace(&format!("{{{name}}}"), value);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format, text, MessageKey};
+    use crate::settings::AppLanguage;
+
+    #[test]
+    fn format_replaces_named_placeholders() {
+        let out = format(
+            AppLanguage::En,
+            MessageKey::GatewayPortInUse,
+            &[("requested", "37123"), ("bound", "37124")],
+        );
+        assert_eq!(out, "Port 37123 is in use; switched to 37124");
+    }
+
+    #[test]
+    fn zh_cn_keeps_legacy_text() {
+        assert_eq!(
+            text(AppLanguage::ZhCn, MessageKey::NoticeTitleWarning),
+            "提醒"
+        );
+        let out = format(
+            AppLanguage::ZhCn,
+            MessageKey::CircuitOpenedTitle,
+            &[("provider", "p1")],
+        );
+        assert_eq!(out, "熔断触发：p1");
+    }
+}
@@ -10,6 +10,7 @@ use tracing_subscriber::layer::SubscriberExt;
 const LOG_SUBDIR: &str = "logs";
 const LOG_FILE_PREFIX: &str = "aio-coding-hub.log";
 const CLEANUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const LOG_TAIL_MAX_READ_BYTES: u64 = 256 * 1024;
 
 static TRACING_GUARD: OnceLock<Mutex<Option<WorkerGuard>>> = OnceLock::new();
 static TRACING_INIT: OnceLock<()> = OnceLock::new();
@@ -102,6 +103,50 @@ fn ensure_log_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(dir)
 }
 
+fn latest_log_file(log_dir: &Path) -> Option<PathBuf> {
+    let entries = std::fs::read_dir(log_dir).ok()?;
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            if !meta.is_file() {
+                return None;
+            }
+            Some((meta.modified().unwrap_or(UNIX_EPOCH), entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+fn tail_lines(content: &str, max_lines: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    lines[start..].join("\n")
+}
+
+// Best-effort tail of the newest rolling log file. Must not log via `tracing` (used in panic hook).
+pub(crate) fn recent_log_tail(app: &tauri::AppHandle, max_lines: usize) -> Option<String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let log_dir = app_paths::app_data_dir(app).ok()?.join(LOG_SUBDIR);
+    let path = latest_log_file(&log_dir)?;
+    let mut file = std::fs::File::open(&path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let offset = len.saturating_sub(LOG_TAIL_MAX_READ_BYTES);
+    file.seek(SeekFrom::Start(offset)).ok()?;
+
+    let mut buf = Vec::with_capacity((len - offset) as usize);
+    file.read_to_end(&mut buf).ok()?;
+    let content = String::from_utf8_lossy(&buf);
+    Some(tail_lines(&content, max_lines))
+}
+
 fn spawn_cleanup_task(app: tauri::AppHandle, log_dir: PathBuf) {
     tauri::async_runtime::spawn(async move {
         let app_for_cleanup = app.clone();
@@ -115,6 +160,9 @@ fn spawn_cleanup_task(app: tauri::AppHandle, log_dir: PathBuf) {
         interval.tick().await;
         loop {
             interval.tick().await;
+            if super::power::background_work_suspended(&app) {
+                continue;
+            }
             let app_for_cleanup = app.clone();
             let log_dir_for_cleanup = log_dir.clone();
             std::mem::drop(tauri::async_runtime::spawn_blocking(move || {
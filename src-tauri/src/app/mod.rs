@@ -1,7 +1,14 @@
-//! Usage: Application layer (Tauri-managed state, tray/window lifecycle, startup wiring).
+//! Usage: Application layer (Tauri-managed state, tray/window lifecycle, headless mode, startup wiring).
 
+pub(crate) mod admin_api;
 pub(crate) mod app_state;
 pub(crate) mod cleanup;
+pub(crate) mod crash_report;
+pub(crate) mod headless;
+pub(crate) mod i18n;
 pub(crate) mod logging;
 pub(crate) mod notice;
+pub(crate) mod power;
+pub(crate) mod quiet_hours;
 pub(crate) mod resident;
+pub(crate) mod updater;
@@ -3,7 +3,17 @@
 //! 用法：
 //! - 前端：`invoke("notice_send", { level, title?, body })` 触发通知
 //! - Rust 后台：调用 `notice::emit(app, payload)` 触发通知事件（由前端统一监听并发送系统通知）
+//! - 默认标题按设置中的语言（`ui_language`）生成
+//! - 需要同时投递到外部渠道（webhook / Telegram / Slack / 飞书 / 钉钉）时调用 `notice::dispatch(app, event, payload)`
+//! - `dispatch` 受静默时段（quiet hours）控制；Error 级别始终立即发送
+//! - 需要附带操作按钮时用 `build(...).with_action(NoticeAction::...)`；仅系统通知 / 应用内提示使用，外部渠道忽略
+//! - 可能含敏感内容的细节（如上游响应摘要）用 `with_local_detail(...)`：只在本机系统通知 / 应用内提示中显示，
+//!   不会投递到外部渠道，也不写入通知历史
 
+use crate::i18n::{self, MessageKey};
+use crate::quiet_hours;
+use crate::settings::{self, AppLanguage, NoticeEventKind};
+use crate::{notice_channels, notice_history};
 use tauri::Emitter;
 
 pub const NOTICE_EVENT_NAME: &str = "notice:notify";
@@ -19,22 +29,57 @@ pub enum NoticeLevel {
     Error,
 }
 
+impl NoticeLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Success => "success",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NoticeAction {
+    ResetProvider { provider_id: i64, label: String },
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct NoticeEventPayload {
     pub level: NoticeLevel,
     pub title: String,
     pub body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<NoticeAction>,
+    // Appended to `body` by the frontend only; never leaves this machine.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_detail: Option<String>,
 }
 
-fn default_title(level: NoticeLevel) -> &'static str {
-    match level {
-        NoticeLevel::Info => "提示",
-        NoticeLevel::Success => "成功",
-        NoticeLevel::Warning => "提醒",
-        NoticeLevel::Error => "错误",
+impl NoticeEventPayload {
+    pub fn with_action(mut self, action: NoticeAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn with_local_detail(mut self, detail: String) -> Self {
+        self.local_detail = Some(detail);
+        self
     }
 }
 
+fn default_title(language: AppLanguage, level: NoticeLevel) -> String {
+    let key = match level {
+        NoticeLevel::Info => MessageKey::NoticeTitleInfo,
+        NoticeLevel::Success => MessageKey::NoticeTitleSuccess,
+        NoticeLevel::Warning => MessageKey::NoticeTitleWarning,
+        NoticeLevel::Error => MessageKey::NoticeTitleError,
+    };
+    i18n::text(language, key)
+}
+
 fn normalize_optional_title(title: Option<String>) -> Option<String> {
     let title = title?;
     let trimmed = title.trim();
@@ -44,16 +89,23 @@ fn normalize_optional_title(title: Option<String>) -> Option<String> {
     Some(trimmed.to_string())
 }
 
-fn format_title(level: NoticeLevel, title: Option<String>) -> String {
-    let title = normalize_optional_title(title).unwrap_or_else(|| default_title(level).to_string());
+fn format_title(language: AppLanguage, level: NoticeLevel, title: Option<String>) -> String {
+    let title = normalize_optional_title(title).unwrap_or_else(|| default_title(language, level));
     format!("{NOTICE_PREFIX} · {title}")
 }
 
-pub fn build(level: NoticeLevel, title: Option<String>, body: String) -> NoticeEventPayload {
+pub fn build(
+    language: AppLanguage,
+    level: NoticeLevel,
+    title: Option<String>,
+    body: String,
+) -> NoticeEventPayload {
     NoticeEventPayload {
         level,
-        title: format_title(level, title),
+        title: format_title(language, level, title),
         body,
+        action: None,
+        local_detail: None,
     }
 }
 
@@ -62,3 +114,63 @@ pub fn emit(app: &tauri::AppHandle, payload: NoticeEventPayload) -> Result<(), S
         .map_err(|e| format!("NOTICE_EMIT: {e}"))?;
     Ok(())
 }
+
+pub fn dispatch(app: &tauri::AppHandle, event: NoticeEventKind, payload: NoticeEventPayload) {
+    if quiet_hours::try_hold(app, event, &payload) {
+        return;
+    }
+    deliver(app, &[event], event, payload);
+}
+
+// `events` selects channels (any match); `event` is the kind reported to webhook receivers.
+pub(crate) fn deliver(
+    app: &tauri::AppHandle,
+    events: &[NoticeEventKind],
+    event: NoticeEventKind,
+    payload: NoticeEventPayload,
+) {
+    let channels: Vec<_> = settings::read(app)
+        .map(|cfg| cfg.notice_channels)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|channel| events.iter().any(|e| channel.accepts(*e)))
+        .collect();
+
+    let system_error = emit(app, payload.clone()).err();
+    if let Some(err) = system_error.as_ref() {
+        tracing::warn!("发送系统通知失败: {}", err);
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut deliveries = Vec::with_capacity(channels.len() + 1);
+        deliveries.push(notice_history::NoticeDeliveryRecord {
+            channel_id: notice_history::SYSTEM_CHANNEL_ID.to_string(),
+            channel_kind: notice_history::SYSTEM_CHANNEL_ID.to_string(),
+            error: system_error,
+        });
+        for channel in channels {
+            let result = notice_channels::deliver(&channel, event, &payload).await;
+            if let Err(err) = result.as_ref() {
+                tracing::warn!(channel_id = %channel.id, "外部通知投递失败: {}", err);
+            }
+            deliveries.push(notice_history::NoticeDeliveryRecord {
+                channel_id: channel.id.clone(),
+                channel_kind: channel.kind.as_str().to_string(),
+                error: result.err(),
+            });
+        }
+
+        notice_history::record(
+            &app,
+            notice_history::NoticeHistoryEntry {
+                event_kind: event.as_str(),
+                level: payload.level.as_str(),
+                title: payload.title,
+                body: payload.body,
+                deliveries,
+            },
+        )
+        .await;
+    });
+}
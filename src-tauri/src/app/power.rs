@@ -0,0 +1,195 @@
+//! Usage: Low-power mode (suspend background work on battery or when the gateway is idle).
+//!
+//! 用法：
+//! - 网关收到请求时调用 `power::mark_activity()`
+//! - 后台任务执行前调用 `power::background_work_suspended(app)`，为 true 时跳过本轮
+//! - 电源状态检测（macOS `pmset` 等）在后台线程刷新，调用方（包括网关请求路径）只读取缓存值
+
+use crate::settings;
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const POWER_SOURCE_CACHE_TTL: Duration = Duration::from_secs(30);
+
+static LAST_ACTIVITY_UNIX: AtomicI64 = AtomicI64::new(0);
+static POWER_SOURCE_CACHE: Mutex<Option<(Instant, Option<bool>)>> = Mutex::new(None);
+static POWER_SOURCE_REFRESHING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct PowerStatus {
+    pub(crate) low_power_enabled: bool,
+    // None = unknown (no battery info available on this platform/machine).
+    pub(crate) on_battery: Option<bool>,
+    pub(crate) idle_seconds: i64,
+    pub(crate) idle: bool,
+    pub(crate) suspended: bool,
+}
+
+pub(crate) fn mark_activity() {
+    LAST_ACTIVITY_UNIX.store(now_unix_seconds(), Ordering::Relaxed);
+}
+
+fn idle_seconds(now_unix: i64) -> i64 {
+    let last = LAST_ACTIVITY_UNIX.load(Ordering::Relaxed);
+    if last <= 0 {
+        // No traffic since startup: count from the first check instead of the unix epoch.
+        let _ =
+            LAST_ACTIVITY_UNIX.compare_exchange(0, now_unix, Ordering::Relaxed, Ordering::Relaxed);
+        return 0;
+    }
+    now_unix.saturating_sub(last).max(0)
+}
+
+fn is_idle(idle_seconds: i64, idle_minutes: u32) -> bool {
+    idle_minutes > 0 && idle_seconds >= i64::from(idle_minutes).saturating_mul(60)
+}
+
+pub(crate) fn status(app: &tauri::AppHandle) -> PowerStatus {
+    let cfg = settings::read(app).unwrap_or_default();
+    let on_battery = on_battery_cached();
+    let idle_seconds = idle_seconds(now_unix_seconds());
+    let idle = is_idle(idle_seconds, cfg.low_power_idle_minutes);
+    let suspended = cfg.enable_low_power_mode && (on_battery == Some(true) || idle);
+
+    PowerStatus {
+        low_power_enabled: cfg.enable_low_power_mode,
+        on_battery,
+        idle_seconds,
+        idle,
+        suspended,
+    }
+}
+
+pub(crate) fn background_work_suspended(app: &tauri::AppHandle) -> bool {
+    let enabled = settings::read(app)
+        .map(|cfg| cfg.enable_low_power_mode)
+        .unwrap_or(false);
+    if !enabled {
+        return false;
+    }
+    status(app).suspended
+}
+
+/// Last detected power source (None until the first detection finishes). A stale value is
+/// refreshed on a blocking thread so callers never wait on `pmset` or hold the lock meanwhile.
+fn on_battery_cached() -> Option<bool> {
+    let (fresh, value) = match *POWER_SOURCE_CACHE.lock_or_recover() {
+        Some((checked_at, value)) => (checked_at.elapsed() < POWER_SOURCE_CACHE_TTL, value),
+        None => (false, None),
+    };
+    if !fresh && !POWER_SOURCE_REFRESHING.swap(true, Ordering::AcqRel) {
+        std::mem::drop(tauri::async_runtime::spawn_blocking(|| {
+            let value = detect_on_battery();
+            *POWER_SOURCE_CACHE.lock_or_recover() = Some((Instant::now(), value));
+            POWER_SOURCE_REFRESHING.store(false, Ordering::Release);
+        }));
+    }
+    value
+}
+
+#[cfg(target_os = "linux")]
+fn detect_on_battery() -> Option<bool> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut has_battery = false;
+    let mut mains_online = false;
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+        match kind.trim() {
+            "Battery" => has_battery = true,
+            "Mains" | "USB" => {
+                let online = std::fs::read_to_string(path.join("online")).unwrap_or_default();
+                if online.trim() == "1" {
+                    mains_online = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    if !has_battery {
+        return None;
+    }
+    Some(!mains_online)
+}
+
+#[cfg(target_os = "macos")]
+fn detect_on_battery() -> Option<bool> {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    if text.contains("'Battery Power'") {
+        return Some(true);
+    }
+    if text.contains("'AC Power'") {
+        return Some(false);
+    }
+    None
+}
+
+#[cfg(windows)]
+fn detect_on_battery() -> Option<bool> {
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+
+    let mut status = SystemPowerStatus {
+        ac_line_status: 255,
+        battery_flag: 255,
+        battery_life_percent: 255,
+        system_status_flag: 0,
+        battery_life_time: 0,
+        battery_full_life_time: 0,
+    };
+    // SAFETY: `status` is a valid, properly laid out SYSTEM_POWER_STATUS out-parameter.
+    let ok = unsafe { GetSystemPowerStatus(&mut status) };
+    if ok == 0 {
+        return None;
+    }
+    // battery_flag 128 = no system battery.
+    if status.battery_flag == 128 {
+        return None;
+    }
+    match status.ac_line_status {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn detect_on_battery() -> Option<bool> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_idle;
+
+    #[test]
+    fn idle_threshold_zero_disables_idle_trigger() {
+        assert!(!is_idle(i64::MAX, 0));
+        assert!(!is_idle(14 * 60, 15));
+        assert!(is_idle(15 * 60, 15));
+    }
+}
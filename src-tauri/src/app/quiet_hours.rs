@@ -0,0 +1,137 @@
+//! Usage: Notice quiet hours (hold non-critical notices, summarize them once the window ends).
+//!
+//! 用法：
+//! - `notice::dispatch` 先调用 `quiet_hours::try_hold(app, event, payload)`；返回 true 表示已暂存/丢弃
+//! - Error 级别通知（例如全部 Provider 不可用）不受静默时段影响
+
+use crate::i18n::{self, MessageKey};
+use crate::notice::{self, NoticeEventPayload, NoticeLevel};
+use crate::settings::{self, NoticeEventKind};
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::{in_daily_window, local_minutes_of_day};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const FLUSH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const MAX_HELD_NOTICES: usize = 200;
+const SUMMARY_MAX_LINES: usize = 20;
+
+struct HeldNotice {
+    event: NoticeEventKind,
+    payload: NoticeEventPayload,
+}
+
+static HELD: Mutex<Vec<HeldNotice>> = Mutex::new(Vec::new());
+static FLUSH_TASK_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn quiet_now(cfg: &settings::AppSettings) -> bool {
+    if !cfg.notice_quiet_hours_enabled {
+        return false;
+    }
+    let (Some(start), Some(end)) = (
+        settings::parse_hhmm_minutes(&cfg.notice_quiet_hours_start),
+        settings::parse_hhmm_minutes(&cfg.notice_quiet_hours_end),
+    ) else {
+        return false;
+    };
+    in_daily_window(local_minutes_of_day(), start, end)
+}
+
+pub(crate) fn try_hold(
+    app: &tauri::AppHandle,
+    event: NoticeEventKind,
+    payload: &NoticeEventPayload,
+) -> bool {
+    if matches!(payload.level, NoticeLevel::Error) {
+        return false;
+    }
+    let cfg = settings::read(app).unwrap_or_default();
+    if !quiet_now(&cfg) {
+        return false;
+    }
+
+    if !cfg.notice_quiet_hours_summary {
+        tracing::info!(title = %payload.title, "静默时段内，通知已忽略");
+        return true;
+    }
+
+    {
+        let mut held = HELD.lock_or_recover();
+        if held.len() >= MAX_HELD_NOTICES {
+            held.remove(0);
+        }
+        held.push(HeldNotice {
+            event,
+            payload: payload.clone(),
+        });
+    }
+    spawn_flush_task(app.clone());
+    true
+}
+
+fn spawn_flush_task(app: tauri::AppHandle) {
+    if FLUSH_TASK_RUNNING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(FLUSH_CHECK_INTERVAL).await;
+            let cfg = settings::read(&app).unwrap_or_default();
+            if quiet_now(&cfg) {
+                continue;
+            }
+            flush_summary(&app);
+            break;
+        }
+        FLUSH_TASK_RUNNING.store(false, Ordering::Release);
+    });
+}
+
+fn summary_body(titles: &[String], language: settings::AppLanguage) -> String {
+    let mut lines: Vec<String> = titles
+        .iter()
+        .take(SUMMARY_MAX_LINES)
+        .map(|t| format!("• {t}"))
+        .collect();
+    if titles.len() > SUMMARY_MAX_LINES {
+        let rest = (titles.len() - SUMMARY_MAX_LINES).to_string();
+        lines.push(i18n::format(
+            language,
+            MessageKey::QuietHoursSummaryMore,
+            &[("count", rest.as_str())],
+        ));
+    }
+    lines.join("\n")
+}
+
+fn flush_summary(app: &tauri::AppHandle) {
+    let held: Vec<HeldNotice> = std::mem::take(&mut *HELD.lock_or_recover());
+    if held.is_empty() {
+        return;
+    }
+
+    let language = i18n::current_language(app);
+    let mut events: Vec<NoticeEventKind> = Vec::new();
+    let mut titles: Vec<String> = Vec::with_capacity(held.len());
+    for item in held {
+        if !events.contains(&item.event) {
+            events.push(item.event);
+        }
+        titles.push(item.payload.title);
+    }
+
+    let count = titles.len().to_string();
+    let title = i18n::format(
+        language,
+        MessageKey::QuietHoursSummaryTitle,
+        &[("count", count.as_str())],
+    );
+    let payload = notice::build(
+        language,
+        NoticeLevel::Info,
+        Some(title),
+        summary_body(&titles, language),
+    );
+    notice::deliver(app, &events, NoticeEventKind::General, payload);
+}
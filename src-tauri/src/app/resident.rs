@@ -6,6 +6,8 @@ const MAIN_WINDOW_LABEL: &str = "main";
 const TRAY_ID: &str = "main-tray";
 const TRAY_MENU_TOGGLE_ID: &str = "tray.toggle";
 const TRAY_MENU_QUIT_ID: &str = "tray.quit";
+const TRAY_MENU_INSTALL_UPDATE_ID: &str = "tray.install_update";
+const TRAY_TOOLTIP: &str = "AIO Coding Hub";
 
 pub struct ResidentState {
     tray_enabled: AtomicBool,
@@ -40,6 +42,9 @@ pub fn show_main_window(_app: &tauri::AppHandle) {}
 #[cfg(not(desktop))]
 pub fn on_window_event(_window: &tauri::Window, _event: &tauri::WindowEvent) {}
 
+#[cfg(not(desktop))]
+pub fn set_update_ready(_app: &tauri::AppHandle, _version: Option<&str>) {}
+
 #[cfg(desktop)]
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
 #[cfg(desktop)]
@@ -48,7 +53,10 @@ use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent}
 use tauri::Manager;
 
 #[cfg(desktop)]
-pub fn setup_tray(app: &tauri::AppHandle) -> Result<(), String> {
+fn build_tray_menu(
+    app: &tauri::AppHandle,
+    update_version: Option<&str>,
+) -> Result<Menu<tauri::Wry>, String> {
     let toggle_item = MenuItem::with_id(app, TRAY_MENU_TOGGLE_ID, "显示/隐藏", true, None::<&str>)
         .map_err(|e| format!("failed to create tray toggle menu item: {e}"))?;
     let quit_item = MenuItem::with_id(app, TRAY_MENU_QUIT_ID, "退出", true, None::<&str>)
@@ -59,8 +67,25 @@ pub fn setup_tray(app: &tauri::AppHandle) -> Result<(), String> {
     let menu = Menu::with_items(app, &[&toggle_item, &separator, &quit_item])
         .map_err(|e| format!("failed to create tray menu: {e}"))?;
 
-    let toggle_id = toggle_item.id().clone();
-    let quit_id = quit_item.id().clone();
+    if let Some(version) = update_version {
+        let install_item = MenuItem::with_id(
+            app,
+            TRAY_MENU_INSTALL_UPDATE_ID,
+            format!("● 重启并安装更新 v{version}"),
+            true,
+            None::<&str>,
+        )
+        .map_err(|e| format!("failed to create tray update menu item: {e}"))?;
+        menu.prepend(&install_item)
+            .map_err(|e| format!("failed to add tray update menu item: {e}"))?;
+    }
+
+    Ok(menu)
+}
+
+#[cfg(desktop)]
+pub fn setup_tray(app: &tauri::AppHandle) -> Result<(), String> {
+    let menu = build_tray_menu(app, None)?;
 
     #[cfg(target_os = "macos")]
     let icon_bytes = include_bytes!("../../icons/trayTemplate.png");
@@ -72,7 +97,7 @@ pub fn setup_tray(app: &tauri::AppHandle) -> Result<(), String> {
 
     let tray_builder = TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
-        .tooltip("AIO Coding Hub")
+        .tooltip(TRAY_TOOLTIP)
         .menu(&menu);
 
     #[cfg(target_os = "macos")]
@@ -80,14 +105,14 @@ pub fn setup_tray(app: &tauri::AppHandle) -> Result<(), String> {
 
     tray_builder
         .show_menu_on_left_click(false)
-        .on_menu_event(move |app, event| {
-            if event.id == quit_id {
-                app.exit(0);
-                return;
-            }
-            if event.id == toggle_id {
-                toggle_main_window(app);
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            TRAY_MENU_QUIT_ID => app.exit(0),
+            TRAY_MENU_TOGGLE_ID => toggle_main_window(app),
+            // `app_restart` runs the exit cleanup, which installs the downloaded update.
+            TRAY_MENU_INSTALL_UPDATE_ID => {
+                let _ = crate::commands::app_restart(app.clone());
             }
+            _ => {}
         })
         .on_tray_icon_event(|tray, event| {
             if let TrayIconEvent::Click {
@@ -107,6 +132,27 @@ pub fn setup_tray(app: &tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Shows (or clears) the "update ready" badge: tray tooltip + a restart-and-install menu entry.
+#[cfg(desktop)]
+pub fn set_update_ready(app: &tauri::AppHandle, version: Option<&str>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+
+    let tooltip = match version {
+        Some(version) => format!("{TRAY_TOOLTIP}（更新 v{version} 已就绪，退出时安装）"),
+        None => TRAY_TOOLTIP.to_string(),
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+
+    match build_tray_menu(app, version) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(err) => tracing::warn!("更新托盘菜单失败: {}", err),
+    }
+}
+
 #[cfg(desktop)]
 pub fn show_main_window(app: &tauri::AppHandle) {
     let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
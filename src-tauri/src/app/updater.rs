@@ -0,0 +1,750 @@
+//! Usage: Updater channel selection (stable / beta) on top of `tauri-plugin-updater`.
+//!
+//! 用法：
+//! - `settings.update_channel = "stable"` 使用 `tauri.conf.json` 中的默认 endpoints（GitHub latest release）
+//! - `"beta"` 使用独立的 beta 发布元数据（`updater-beta` release 下的 `latest.json`）
+//! - 检查结果以 app 级资源表（`AppHandle::resources_table`）的 `rid` 返回；该表与 plugin 命令读取的 webview
+//!   资源表不同，因此前端安装必须走 `updater_download_and_install` / `updater_background_download_start`，
+//!   不能直接调用 `plugin:updater|download_and_install`
+//! - `settings.update_background_download` 开启时，前端发现更新后调用 `start_background_download(rid)`：
+//!   后台下载安装包并通过 `updater:download` 事件推送进度；下载完成后托盘显示「更新已就绪」，
+//!   在下次退出 / 重启的清理流程中安装（`install_pending_best_effort`），不打断当前会话
+//! - 启动时 `record_running_version` 记录上次运行的版本及其数据库 schema 版本；版本变化后可 `rollback_prepare`：
+//!   优先使用升级前保留的上一版本安装包（`updater-bundles/`，下载更新时写入），没有时才从上一版本 release 下载，
+//!   放入待安装槽位，随后重启即完成回滚。新版本已迁移过数据库 schema 时拒绝回滚（旧版本无法打开更高版本的数据库）
+//! - `settings.update_mirror` 配置镜像源：检查与下载都先走主源（GitHub），超时 / 失败后按顺序回退到镜像；
+//!   安装包仍由 plugin 校验签名，镜像无法篡改内容。前台安装走 `updater_download_and_install`，
+//!   通过 Channel 推送 started / progress / finished 事件（started 附带当前下载源）
+
+use crate::app_paths;
+use crate::db;
+use crate::settings::{self, UpdateChannel, UpdateMirrorConfig};
+use crate::shared::fs::write_file_atomic;
+use crate::shared::mutex_ext::MutexExt;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use tauri::Emitter;
+
+pub(crate) const DOWNLOAD_EVENT_NAME: &str = "updater:download";
+
+// Keep in sync with `plugins.updater.endpoints` in tauri.conf.json.
+const STABLE_ENDPOINT: &str =
+    "https://github.com/dyndynjyxa/aio-coding-hub/releases/latest/download/latest.json";
+const BETA_ENDPOINT: &str =
+    "https://github.com/dyndynjyxa/aio-coding-hub/releases/download/updater-beta/latest.json";
+const RELEASE_DOWNLOAD_BASE: &str =
+    "https://github.com/dyndynjyxa/aio-coding-hub/releases/download";
+const VERSION_HISTORY_FILE_NAME: &str = "updater-history.json";
+// Downloaded bundles, one per version (`<version>.bundle`); keeps what a rollback needs.
+const BUNDLE_DIR_NAME: &str = "updater-bundles";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UpdaterCheckInfo {
+    rid: u32,
+    version: String,
+    current_version: String,
+    date: Option<String>,
+    body: Option<String>,
+    channel: UpdateChannel,
+}
+
+pub(crate) fn current_channel(app: &tauri::AppHandle) -> UpdateChannel {
+    settings::read(app)
+        .map(|cfg| cfg.update_channel)
+        .unwrap_or_default()
+}
+
+fn current_mirror_config(app: &tauri::AppHandle) -> UpdateMirrorConfig {
+    settings::read(app)
+        .map(|cfg| cfg.update_mirror)
+        .unwrap_or_default()
+}
+
+/// `{url}` in the mirror is replaced with the original URL; otherwise the URL is appended
+/// (prefix-style GitHub proxies such as `https://ghproxy.example/`).
+fn mirror_url(mirror: &str, url: &str) -> String {
+    let mirror = mirror.trim();
+    if mirror.contains("{url}") {
+        mirror.replace("{url}", url)
+    } else {
+        format!("{}/{}", mirror.trim_end_matches('/'), url)
+    }
+}
+
+/// The primary URL followed by one URL per mirror, in order and without duplicates.
+fn source_urls(url: &str, mirrors: &[String]) -> Vec<String> {
+    let mut out = vec![url.to_string()];
+    for mirror in mirrors.iter().filter(|m| !m.trim().is_empty()) {
+        let candidate = mirror_url(mirror, url);
+        if !out.contains(&candidate) {
+            out.push(candidate);
+        }
+    }
+    out
+}
+
+fn source_label(url: &str) -> String {
+    tauri::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+fn parse_endpoints(urls: &[String]) -> Result<Vec<tauri::Url>, String> {
+    urls.iter()
+        .map(|url| {
+            tauri::Url::parse(url)
+                .map_err(|e| format!("UPDATER_ERROR: invalid endpoint {url}: {e}"))
+        })
+        .collect()
+}
+
+#[cfg(desktop)]
+pub(crate) fn build_updater(
+    app: &tauri::AppHandle,
+    channel: UpdateChannel,
+) -> Result<tauri_plugin_updater::Updater, String> {
+    use tauri_plugin_updater::UpdaterExt;
+
+    if super::headless::is_headless() {
+        return Err("UPDATER_ERROR: updater is disabled in headless mode".to_string());
+    }
+
+    let mirror = current_mirror_config(app);
+    let endpoint = match channel {
+        UpdateChannel::Stable => STABLE_ENDPOINT,
+        UpdateChannel::Beta => BETA_ENDPOINT,
+    };
+    // The plugin tries endpoints in order and moves on when one errors or times out.
+    let endpoints = parse_endpoints(&source_urls(endpoint, &mirror.mirrors))?;
+    app.updater_builder()
+        .endpoints(endpoints)
+        .map_err(|e| format!("UPDATER_ERROR: failed to set endpoints: {e}"))?
+        .timeout(std::time::Duration::from_secs(mirror.timeout_secs as u64))
+        .build()
+        .map_err(|e| format!("UPDATER_ERROR: failed to build updater: {e}"))
+}
+
+#[cfg(desktop)]
+pub(crate) async fn check(app: &tauri::AppHandle) -> Result<Option<UpdaterCheckInfo>, String> {
+    use tauri::Manager;
+
+    let channel = current_channel(app);
+    let updater = build_updater(app, channel)?;
+    let update = updater
+        .check()
+        .await
+        .map_err(|e| format!("UPDATER_ERROR: check failed ({channel:?}): {e}"))?;
+    let Some(update) = update else {
+        return Ok(None);
+    };
+
+    tracing::info!(
+        channel = ?channel,
+        version = %update.version,
+        "检测到可用更新"
+    );
+    let version = update.version.clone();
+    let current_version = update.current_version.clone();
+    let date = update.date.map(|d| d.to_string());
+    let body = update.body.clone();
+    let rid = app.resources_table().add(update);
+    Ok(Some(UpdaterCheckInfo {
+        rid,
+        version,
+        current_version,
+        date,
+        body,
+        channel,
+    }))
+}
+
+#[cfg(not(desktop))]
+pub(crate) async fn check(_app: &tauri::AppHandle) -> Result<Option<UpdaterCheckInfo>, String> {
+    Err("UPDATER_ERROR: updater is only available on desktop".to_string())
+}
+
+#[cfg(desktop)]
+enum DownloadProgress<'a> {
+    // Switched to a new source (the primary URL first, then mirrors); restart byte counters.
+    Source(&'a str),
+    Chunk(usize, Option<u64>),
+}
+
+/// Downloads the signed bundle, falling back to the next source when one fails or stalls for
+/// `timeout_secs` without receiving data.
+#[cfg(desktop)]
+async fn download_with_fallback(
+    update: &tauri_plugin_updater::Update,
+    mirror: &UpdateMirrorConfig,
+    mut on_progress: impl FnMut(DownloadProgress<'_>),
+) -> Result<Vec<u8>, String> {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{Duration, Instant};
+
+    let stall_timeout = Duration::from_secs(mirror.timeout_secs as u64);
+    let mut last_error = String::new();
+    for url in source_urls(update.download_url.as_str(), &mirror.mirrors) {
+        let Ok(download_url) = tauri::Url::parse(&url) else {
+            continue;
+        };
+        let mut attempt = update.clone();
+        attempt.download_url = download_url;
+        // Large bundles take longer than the check timeout; the stall watchdog below replaces it.
+        attempt.timeout = None;
+        on_progress(DownloadProgress::Source(&url));
+
+        let started = Instant::now();
+        let last_progress_ms = AtomicU64::new(0);
+        let download = attempt.download(
+            |chunk_len, content_len| {
+                last_progress_ms.store(started.elapsed().as_millis() as u64, Ordering::Relaxed);
+                on_progress(DownloadProgress::Chunk(chunk_len, content_len));
+            },
+            || {},
+        );
+        let watchdog = async {
+            loop {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                let idle_ms = (started.elapsed().as_millis() as u64)
+                    .saturating_sub(last_progress_ms.load(Ordering::Relaxed));
+                if idle_ms >= stall_timeout.as_millis() as u64 {
+                    break;
+                }
+            }
+        };
+
+        tokio::select! {
+            result = download => match result {
+                Ok(bytes) => return Ok(bytes),
+                Err(err) => last_error = err.to_string(),
+            },
+            _ = watchdog => {
+                last_error = format!("no data received for {}s", mirror.timeout_secs);
+            }
+        }
+        tracing::warn!(
+            source = %source_label(&url),
+            "更新下载失败，尝试下一个下载源: {}",
+            last_error
+        );
+    }
+
+    Err(format!("download failed from all sources: {last_error}"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BackgroundDownloadPhase {
+    Idle,
+    Downloading,
+    Ready,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct BackgroundDownloadState {
+    phase: BackgroundDownloadPhase,
+    version: Option<String>,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+    // Host of the source currently downloading (primary or mirror).
+    source: Option<String>,
+    error: Option<String>,
+}
+
+impl Default for BackgroundDownloadState {
+    fn default() -> Self {
+        Self {
+            phase: BackgroundDownloadPhase::Idle,
+            version: None,
+            downloaded_bytes: 0,
+            total_bytes: None,
+            source: None,
+            error: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct BackgroundSlot {
+    state: BackgroundDownloadState,
+    #[cfg(desktop)]
+    pending: Option<(std::sync::Arc<tauri_plugin_updater::Update>, Vec<u8>)>,
+}
+
+static BACKGROUND: OnceLock<Mutex<BackgroundSlot>> = OnceLock::new();
+
+fn background() -> &'static Mutex<BackgroundSlot> {
+    BACKGROUND.get_or_init(|| Mutex::new(BackgroundSlot::default()))
+}
+
+fn update_state(app: &tauri::AppHandle, f: impl FnOnce(&mut BackgroundDownloadState)) {
+    let snapshot = {
+        let mut slot = background().lock_or_recover();
+        f(&mut slot.state);
+        slot.state.clone()
+    };
+    let _ = app.emit(DOWNLOAD_EVENT_NAME, snapshot);
+}
+
+pub(crate) fn background_download_state() -> BackgroundDownloadState {
+    background().lock_or_recover().state.clone()
+}
+
+#[cfg(desktop)]
+pub(crate) fn start_background_download(
+    app: &tauri::AppHandle,
+    rid: u32,
+) -> Result<BackgroundDownloadState, String> {
+    use tauri::Manager;
+
+    let update = app
+        .resources_table()
+        .get::<tauri_plugin_updater::Update>(rid)
+        .map_err(|e| format!("UPDATER_ERROR: update resource not found (rid={rid}): {e}"))?;
+
+    {
+        let slot = background().lock_or_recover();
+        let same_version = slot.state.version.as_deref() == Some(update.version.as_str());
+        if same_version
+            && matches!(
+                slot.state.phase,
+                BackgroundDownloadPhase::Downloading | BackgroundDownloadPhase::Ready
+            )
+        {
+            return Ok(slot.state.clone());
+        }
+    }
+
+    let version = update.version.clone();
+    update_state(app, |state| {
+        *state = BackgroundDownloadState {
+            phase: BackgroundDownloadPhase::Downloading,
+            version: Some(version.clone()),
+            ..BackgroundDownloadState::default()
+        };
+    });
+    tracing::info!(version = %version, "开始后台下载更新");
+
+    let app_for_task = app.clone();
+    let mirror = current_mirror_config(app);
+    tauri::async_runtime::spawn(async move {
+        let app_for_progress = app_for_task.clone();
+        let mut downloaded: u64 = 0;
+        let mut last_emitted: u64 = 0;
+        let result = download_with_fallback(&update, &mirror, |progress| match progress {
+            DownloadProgress::Source(url) => {
+                downloaded = 0;
+                last_emitted = 0;
+                update_state(&app_for_progress, |state| {
+                    state.downloaded_bytes = 0;
+                    state.total_bytes = None;
+                    state.source = Some(source_label(url));
+                });
+            }
+            DownloadProgress::Chunk(chunk_len, content_len) => {
+                downloaded += chunk_len as u64;
+                // Throttle progress events to roughly every 256 KiB.
+                if downloaded - last_emitted < 256 * 1024 && Some(downloaded) != content_len {
+                    return;
+                }
+                last_emitted = downloaded;
+                update_state(&app_for_progress, |state| {
+                    state.downloaded_bytes = downloaded;
+                    state.total_bytes = content_len;
+                });
+            }
+        })
+        .await;
+
+        match result {
+            Ok(bytes) => {
+                let size = bytes.len() as u64;
+                keep_bundle(&app_for_task, &update.version, &bytes);
+                background().lock_or_recover().pending = Some((update.clone(), bytes));
+                update_state(&app_for_task, |state| {
+                    state.phase = BackgroundDownloadPhase::Ready;
+                    state.downloaded_bytes = size;
+                    state.total_bytes = Some(size);
+                });
+                tracing::info!(version = %update.version, size = size, "更新已在后台下载完成，将在退出时安装");
+                crate::resident::set_update_ready(&app_for_task, Some(&update.version));
+            }
+            Err(err) => {
+                tracing::warn!(version = %update.version, "后台下载更新失败: {}", err);
+                update_state(&app_for_task, |state| {
+                    state.phase = BackgroundDownloadPhase::Failed;
+                    state.error = Some(err);
+                });
+            }
+        }
+    });
+
+    Ok(background_download_state())
+}
+
+#[cfg(not(desktop))]
+pub(crate) fn start_background_download(
+    _app: &tauri::AppHandle,
+    _rid: u32,
+) -> Result<BackgroundDownloadState, String> {
+    Err("UPDATER_ERROR: updater is only available on desktop".to_string())
+}
+
+/// Foreground download + install with mirror fallback. Progress goes to `on_event` in the same shape
+/// as `plugin:updater|download_and_install`; `started` is re-sent (with the source host) on fallback.
+#[cfg(desktop)]
+pub(crate) async fn download_and_install(
+    app: &tauri::AppHandle,
+    rid: u32,
+    on_event: tauri::ipc::Channel<serde_json::Value>,
+) -> Result<(), String> {
+    use tauri::Manager;
+
+    let update = app
+        .resources_table()
+        .get::<tauri_plugin_updater::Update>(rid)
+        .map_err(|e| format!("UPDATER_ERROR: update resource not found (rid={rid}): {e}"))?;
+
+    let mirror = current_mirror_config(app);
+    let mut source: Option<String> = None;
+    let mut started = false;
+    let bytes = download_with_fallback(&update, &mirror, |progress| match progress {
+        DownloadProgress::Source(url) => {
+            source = Some(source_label(url));
+            started = false;
+        }
+        DownloadProgress::Chunk(chunk_len, content_len) => {
+            if !started {
+                started = true;
+                let _ = on_event.send(serde_json::json!({
+                    "event": "started",
+                    "data": { "contentLength": content_len, "source": source },
+                }));
+            }
+            let _ = on_event.send(serde_json::json!({
+                "event": "progress",
+                "data": { "chunkLength": chunk_len },
+            }));
+        }
+    })
+    .await
+    .map_err(|e| format!("UPDATER_ERROR: {e}"))?;
+    let _ = on_event.send(serde_json::json!({ "event": "finished" }));
+    keep_bundle(app, &update.version, &bytes);
+
+    tracing::info!(version = %update.version, source = ?source, "更新下载完成，开始安装");
+    crate::blocking::run("updater_install", move || {
+        update
+            .install(bytes)
+            .map_err(|e| format!("UPDATER_ERROR: install failed: {e}"))
+    })
+    .await
+}
+
+#[cfg(not(desktop))]
+pub(crate) async fn download_and_install(
+    _app: &tauri::AppHandle,
+    _rid: u32,
+    _on_event: tauri::ipc::Channel<serde_json::Value>,
+) -> Result<(), String> {
+    Err("UPDATER_ERROR: updater is only available on desktop".to_string())
+}
+
+/// Installs a background-downloaded update; returns whether anything was installed.
+///
+/// Note: on Windows the installer takes over and the current process exits inside `install`.
+#[cfg(desktop)]
+pub(crate) fn install_pending(app: &tauri::AppHandle) -> Result<bool, String> {
+    let pending = background().lock_or_recover().pending.take();
+    let Some((update, bytes)) = pending else {
+        return Ok(false);
+    };
+    tracing::info!(version = %update.version, "安装后台下载的更新");
+    update
+        .install(bytes)
+        .map_err(|e| format!("UPDATER_ERROR: install failed: {e}"))?;
+    update_state(app, |state| *state = BackgroundDownloadState::default());
+    Ok(true)
+}
+
+#[cfg(not(desktop))]
+pub(crate) fn install_pending(_app: &tauri::AppHandle) -> Result<bool, String> {
+    Ok(false)
+}
+
+pub(crate) async fn install_pending_best_effort(app: &tauri::AppHandle) {
+    let app = app.clone();
+    if let Err(err) =
+        crate::blocking::run("updater_install_pending", move || install_pending(&app)).await
+    {
+        tracing::warn!("退出清理：安装已下载的更新失败: {}", err);
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct VersionHistory {
+    last_run_version: String,
+    previous_version: Option<String>,
+    // DB `user_version` the previous version left behind; `None` when unknown.
+    previous_schema_version: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RollbackInfo {
+    current_version: &'static str,
+    previous_version: Option<String>,
+}
+
+fn version_history_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    Ok(app_paths::app_data_dir(app)?.join(VERSION_HISTORY_FILE_NAME))
+}
+
+fn bundle_path(app: &tauri::AppHandle, version: &str) -> Result<std::path::PathBuf, String> {
+    Ok(app_paths::app_data_dir(app)?
+        .join(BUNDLE_DIR_NAME)
+        .join(format!("{version}.bundle")))
+}
+
+/// Stores a verified bundle so a later rollback to `version` can reuse it; only the running
+/// version's bundle and this one are kept. Best-effort.
+#[cfg_attr(not(desktop), allow(dead_code))]
+fn keep_bundle(app: &tauri::AppHandle, version: &str, bytes: &[u8]) {
+    let result = bundle_path(app, version).and_then(|path| {
+        let keep = [path.clone(), bundle_path(app, env!("CARGO_PKG_VERSION"))?];
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+            for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+                if !keep.contains(&entry.path()) {
+                    let _ = std::fs::remove_file(entry.path());
+                }
+            }
+        }
+        write_file_atomic(&path, bytes)
+    });
+    if let Err(err) = result {
+        tracing::warn!(version = %version, "保留更新安装包失败（回滚时将重新下载）: {}", err);
+    }
+}
+
+/// `Err` when the current build migrated the DB past what the previous version can open.
+#[cfg_attr(not(desktop), allow(dead_code))]
+fn check_rollback_schema(previous_schema_version: Option<i64>, target: &str) -> Result<(), String> {
+    match previous_schema_version {
+        Some(previous) if previous >= db::LATEST_SCHEMA_VERSION => Ok(()),
+        Some(previous) => Err(format!(
+            "UPDATER_ROLLBACK_SCHEMA_MISMATCH: v{target} uses database schema {previous}, but this version upgraded it to {}; v{target} cannot open it",
+            db::LATEST_SCHEMA_VERSION
+        )),
+        None => Err(format!(
+            "UPDATER_ROLLBACK_SCHEMA_MISMATCH: the database schema v{target} used is unknown; rollback could leave it unable to open the database"
+        )),
+    }
+}
+
+fn read_version_history(app: &tauri::AppHandle) -> VersionHistory {
+    version_history_path(app)
+        .ok()
+        .and_then(|path| std::fs::read(path).ok())
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// Records the running version at startup; when it changed since the last run, the old one becomes
+/// the rollback target.
+pub(crate) fn record_running_version(app: &tauri::AppHandle) {
+    let current = env!("CARGO_PKG_VERSION");
+    let mut history = read_version_history(app);
+    if history.last_run_version == current {
+        return;
+    }
+    if !history.last_run_version.is_empty() {
+        tracing::info!(
+            from = %history.last_run_version,
+            to = current,
+            "检测到版本变化，记录上一版本用于回滚"
+        );
+        history.previous_version = Some(history.last_run_version.clone());
+        // Runs before this version's migrations, so the file still carries the previous schema.
+        history.previous_schema_version = db::read_schema_version(app).unwrap_or_else(|err| {
+            tracing::warn!("读取数据库 schema 版本失败: {}", err);
+            None
+        });
+    }
+    history.last_run_version = current.to_string();
+
+    let result = version_history_path(app).and_then(|path| {
+        let bytes = serde_json::to_vec_pretty(&history)
+            .map_err(|e| format!("UPDATER_ERROR: failed to serialize version history: {e}"))?;
+        write_file_atomic(&path, &bytes)
+    });
+    if let Err(err) = result {
+        tracing::warn!("写入版本历史失败: {}", err);
+    }
+}
+
+pub(crate) fn rollback_info(app: &tauri::AppHandle) -> RollbackInfo {
+    let previous_version = read_version_history(app)
+        .previous_version
+        .filter(|v| v != env!("CARGO_PKG_VERSION"));
+    RollbackInfo {
+        current_version: env!("CARGO_PKG_VERSION"),
+        previous_version,
+    }
+}
+
+/// Puts the previous release's signed bundle (kept from the upgrade, else downloaded) into the
+/// pending slot; the caller restarts the app so the exit cleanup installs it. Refuses when this
+/// version already migrated the database past the previous one. Returns the target version.
+#[cfg(desktop)]
+pub(crate) async fn rollback_prepare(app: &tauri::AppHandle) -> Result<String, String> {
+    use tauri_plugin_updater::UpdaterExt;
+
+    if super::headless::is_headless() {
+        return Err("UPDATER_ERROR: updater is disabled in headless mode".to_string());
+    }
+    let Some(target) = rollback_info(app).previous_version else {
+        return Err("UPDATER_ROLLBACK_UNAVAILABLE: no previous version recorded".to_string());
+    };
+    check_rollback_schema(read_version_history(app).previous_schema_version, &target)?;
+
+    // release-please tags are `aio-coding-hub-v<version>`; older releases used `v<version>`.
+    let endpoints = [
+        format!("{RELEASE_DOWNLOAD_BASE}/aio-coding-hub-v{target}/latest.json"),
+        format!("{RELEASE_DOWNLOAD_BASE}/v{target}/latest.json"),
+    ]
+    .iter()
+    .map(|url| tauri::Url::parse(url))
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| format!("UPDATER_ERROR: invalid rollback endpoint: {e}"))?;
+
+    let target_for_cmp = target.clone();
+    let updater = app
+        .updater_builder()
+        .endpoints(endpoints)
+        .map_err(|e| format!("UPDATER_ERROR: failed to set rollback endpoints: {e}"))?
+        .version_comparator(move |current, release| {
+            release.version != current && release.version.to_string() == target_for_cmp
+        })
+        .build()
+        .map_err(|e| format!("UPDATER_ERROR: failed to build updater: {e}"))?;
+
+    let update = updater
+        .check()
+        .await
+        .map_err(|e| format!("UPDATER_ERROR: rollback check failed: {e}"))?
+        .ok_or_else(|| format!("UPDATER_ROLLBACK_UNAVAILABLE: release v{target} not found"))?;
+
+    let kept = bundle_path(app, &target)
+        .ok()
+        .and_then(|path| std::fs::read(path).ok());
+    if let Some(bytes) = kept {
+        tracing::info!(target = %target, "使用升级前保留的安装包回滚");
+        let size = bytes.len() as u64;
+        background().lock_or_recover().pending = Some((std::sync::Arc::new(update), bytes));
+        update_state(app, |state| {
+            *state = BackgroundDownloadState {
+                phase: BackgroundDownloadPhase::Ready,
+                version: Some(target.clone()),
+                downloaded_bytes: size,
+                total_bytes: Some(size),
+                ..BackgroundDownloadState::default()
+            };
+        });
+        return Ok(target);
+    }
+
+    tracing::info!(target = %target, "开始下载回滚版本");
+    update_state(app, |state| {
+        *state = BackgroundDownloadState {
+            phase: BackgroundDownloadPhase::Downloading,
+            version: Some(target.clone()),
+            ..BackgroundDownloadState::default()
+        };
+    });
+    let mirror = current_mirror_config(app);
+    let bytes = match download_with_fallback(&update, &mirror, |progress| {
+        if let DownloadProgress::Source(url) = progress {
+            update_state(app, |state| state.source = Some(source_label(url)));
+        }
+    })
+    .await
+    {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            update_state(app, |state| {
+                state.phase = BackgroundDownloadPhase::Failed;
+                state.error = Some(err.clone());
+            });
+            return Err(format!("UPDATER_ERROR: rollback download failed: {err}"));
+        }
+    };
+
+    let size = bytes.len() as u64;
+    background().lock_or_recover().pending = Some((std::sync::Arc::new(update), bytes));
+    update_state(app, |state| {
+        state.phase = BackgroundDownloadPhase::Ready;
+        state.downloaded_bytes = size;
+        state.total_bytes = Some(size);
+    });
+    Ok(target)
+}
+
+#[cfg(not(desktop))]
+pub(crate) async fn rollback_prepare(_app: &tauri::AppHandle) -> Result<String, String> {
+    Err("UPDATER_ERROR: updater is only available on desktop".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollback_is_refused_once_the_schema_moved_on() {
+        assert!(check_rollback_schema(Some(db::LATEST_SCHEMA_VERSION), "0.15.0").is_ok());
+        let err = check_rollback_schema(Some(db::LATEST_SCHEMA_VERSION - 1), "0.15.0").unwrap_err();
+        assert!(err.starts_with("UPDATER_ROLLBACK_SCHEMA_MISMATCH:"));
+        assert!(check_rollback_schema(None, "0.15.0").is_err());
+    }
+
+    #[test]
+    fn mirror_url_supports_prefix_and_placeholder() {
+        let url = "https://github.com/o/r/releases/latest/download/latest.json";
+        assert_eq!(
+            mirror_url("https://ghproxy.example/", url),
+            format!("https://ghproxy.example/{url}")
+        );
+        assert_eq!(
+            mirror_url(" https://cdn.example/gh?u={url} ", url),
+            format!("https://cdn.example/gh?u={url}")
+        );
+    }
+
+    #[test]
+    fn source_urls_keep_primary_first_and_skip_duplicates() {
+        let url = "https://github.com/o/r/latest.json";
+        let mirrors = vec![
+            "https://a.example".to_string(),
+            " ".to_string(),
+            "https://a.example/".to_string(),
+            "https://b.example/{url}".to_string(),
+        ];
+        assert_eq!(
+            source_urls(url, &mirrors),
+            vec![
+                url.to_string(),
+                format!("https://a.example/{url}"),
+                format!("https://b.example/{url}"),
+            ]
+        );
+        assert_eq!(source_labels(&source_urls(url, &[])), vec!["github.com"]);
+    }
+
+    fn source_labels(urls: &[String]) -> Vec<String> {
+        urls.iter().map(|u| source_label(u)).collect()
+    }
+}
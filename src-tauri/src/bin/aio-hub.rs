@@ -0,0 +1,410 @@
+//! Usage: `aio-hub` terminal companion; talks to a running AIO Coding Hub (desktop or `--headless`)
+//! through its local admin API.
+//!
+//! 用法：
+//! - `aio-hub status`：应用与网关状态
+//! - `aio-hub gateway start [--port <port>]` / `aio-hub gateway stop`
+//! - `aio-hub providers [claude|codex|gemini]`：列出供应商
+//! - `aio-hub circuit reset <provider_id>` / `aio-hub circuit reset --cli <cli_key>`：重置熔断
+//! - `aio-hub logs [-n <count>] [-f]`：查看最近请求日志，`-f` 持续跟随
+//! - `aio-hub cost`：今日请求数与花费
+//! - 任意命令追加 `--json` 输出原始 JSON
+//! - 连接信息读取自 `~/.aio-coding-hub/admin-api.json`（应用启动时写入，遵循 `AIO_CODING_HUB_DOTDIR_NAME`）
+
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const APP_DOTDIR_NAME: &str = ".aio-coding-hub";
+const APP_DOTDIR_NAME_ENV: &str = "AIO_CODING_HUB_DOTDIR_NAME";
+const DISCOVERY_FILE_NAME: &str = "admin-api.json";
+const LOGS_FOLLOW_INTERVAL: Duration = Duration::from_secs(2);
+
+const USAGE: &str = "\
+aio-hub - AIO Coding Hub terminal companion
+
+USAGE:
+    aio-hub <command> [args] [--json]
+
+COMMANDS:
+    status                          Show app and gateway status
+    gateway start [--port <port>]   Start the gateway
+    gateway stop                    Stop the gateway
+    providers [cli_key]             List providers (claude / codex / gemini)
+    circuit reset <provider_id>     Reset one provider's circuit breaker
+    circuit reset --cli <cli_key>   Reset all circuit breakers of a CLI
+    logs [-n <count>] [-f]          Show recent request logs (-f to follow)
+    cost                            Show today's requests and cost
+";
+
+struct AdminClient {
+    http: reqwest::Client,
+    base: String,
+    token: String,
+}
+
+impl AdminClient {
+    fn discover() -> Result<Self, String> {
+        let home = std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .map(PathBuf::from)
+            .ok_or_else(|| "cannot resolve home directory".to_string())?;
+        let dotdir = std::env::var(APP_DOTDIR_NAME_ENV)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| v.starts_with('.') && !v.contains('/') && !v.contains('\\'))
+            .unwrap_or_else(|| APP_DOTDIR_NAME.to_string());
+        let path = home.join(dotdir).join(DISCOVERY_FILE_NAME);
+
+        let raw = std::fs::read_to_string(&path).map_err(|_| {
+            format!(
+                "AIO Coding Hub is not running ({} not found); start the app or `aio-coding-hub --headless` first",
+                path.display()
+            )
+        })?;
+        let discovery: Value =
+            serde_json::from_str(&raw).map_err(|e| format!("invalid {}: {e}", path.display()))?;
+        let port = discovery
+            .get("port")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| format!("invalid {}: missing port", path.display()))?;
+        let token = discovery
+            .get("token")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("invalid {}: missing token", path.display()))?;
+
+        let http = reqwest::Client::builder()
+            .no_proxy()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("failed to build http client: {e}"))?;
+        Ok(Self {
+            http,
+            base: format!("http://127.0.0.1:{port}"),
+            token: token.to_string(),
+        })
+    }
+
+    async fn send(&self, req: reqwest::RequestBuilder) -> Result<Value, String> {
+        let resp = req.bearer_auth(&self.token).send().await.map_err(|e| {
+            if e.is_connect() {
+                "cannot connect to AIO Coding Hub admin API (is the app still running?)".to_string()
+            } else {
+                format!("request failed: {e}")
+            }
+        })?;
+        let status = resp.status();
+        let text = resp
+            .text()
+            .await
+            .map_err(|e| format!("failed to read response: {e}"))?;
+        let value: Value = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        if !status.is_success() {
+            let message = value
+                .get("error")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| value.to_string());
+            return Err(format!("{status}: {message}"));
+        }
+        Ok(value)
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, String)]) -> Result<Value, String> {
+        self.send(self.http.get(format!("{}{path}", self.base)).query(query))
+            .await
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Result<Value, String> {
+        // reqwest is built without its `json` feature; serialize by hand.
+        let req = self
+            .http
+            .post(format!("{}{path}", self.base))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        self.send(req).await
+    }
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or("-")
+}
+
+// UTC "YYYY-MM-DD HH:MM:SS" without pulling a date crate into the binary.
+fn format_unix_seconds(ts: i64) -> String {
+    let days = ts.div_euclid(86_400);
+    let secs = ts.rem_euclid(86_400);
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+fn print_status(value: &Value) {
+    let gateway = value.get("gateway").cloned().unwrap_or(Value::Null);
+    let running = gateway
+        .get("running")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    println!(
+        "AIO Coding Hub {}{}",
+        str_field(value, "version"),
+        if value.get("headless").and_then(Value::as_bool) == Some(true) {
+            " (headless)"
+        } else {
+            ""
+        }
+    );
+    if running {
+        println!(
+            "gateway: running  {}  (listen {})",
+            str_field(&gateway, "base_url"),
+            str_field(&gateway, "listen_addr")
+        );
+    } else {
+        println!("gateway: stopped");
+    }
+}
+
+fn print_providers(value: &Value) {
+    let Some(items) = value.as_array() else {
+        return;
+    };
+    if items.is_empty() {
+        println!("no providers");
+        return;
+    }
+    println!(
+        "{:>5}  {:<7} {:<8} {:>8}  NAME",
+        "ID", "CLI", "STATE", "PRIORITY"
+    );
+    for item in items {
+        let enabled = item
+            .get("enabled")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        println!(
+            "{:>5}  {:<7} {:<8} {:>8}  {}",
+            item.get("id").and_then(Value::as_i64).unwrap_or_default(),
+            str_field(item, "cli_key"),
+            if enabled { "enabled" } else { "disabled" },
+            item.get("priority")
+                .and_then(Value::as_i64)
+                .unwrap_or_default(),
+            str_field(item, "name"),
+        );
+    }
+}
+
+fn print_log_line(item: &Value) {
+    let status = item
+        .get("status")
+        .and_then(Value::as_i64)
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let cost = item
+        .get("cost_usd")
+        .and_then(Value::as_f64)
+        .map(|v| format!("${v:.4}"))
+        .unwrap_or_else(|| "-".to_string());
+    println!(
+        "{}  #{:<6} {:<7} {:>4} {:>7}ms {:>9}  {} -> {}{}",
+        format_unix_seconds(
+            item.get("created_at")
+                .and_then(Value::as_i64)
+                .unwrap_or_default()
+        ),
+        item.get("id").and_then(Value::as_i64).unwrap_or_default(),
+        str_field(item, "cli_key"),
+        status,
+        item.get("duration_ms")
+            .and_then(Value::as_i64)
+            .unwrap_or_default(),
+        cost,
+        str_field(item, "requested_model"),
+        str_field(item, "final_provider_name"),
+        item.get("error_code")
+            .and_then(Value::as_str)
+            .map(|v| format!("  [{v}]"))
+            .unwrap_or_default(),
+    );
+}
+
+fn print_cost(value: &Value) {
+    let int = |key: &str| value.get(key).and_then(Value::as_i64).unwrap_or_default();
+    println!(
+        "today: {} requests ({} ok, {} failed), cost ${:.4}",
+        int("requests_total"),
+        int("requests_success"),
+        int("requests_failed"),
+        value
+            .get("total_cost_usd")
+            .and_then(Value::as_f64)
+            .unwrap_or_default()
+    );
+}
+
+fn flag_value(args: &[String], names: &[&str]) -> Option<String> {
+    args.iter()
+        .position(|a| names.contains(&a.as_str()))
+        .and_then(|idx| args.get(idx + 1).cloned())
+}
+
+// Prints `items` and returns the highest id seen, used as the next `after_id` cursor.
+fn emit_logs(items: &[Value], json: bool, mut last_id: i64) -> i64 {
+    for item in items {
+        if json {
+            println!("{item}");
+        } else {
+            print_log_line(item);
+        }
+        if let Some(id) = item.get("id").and_then(Value::as_i64) {
+            last_id = last_id.max(id);
+        }
+    }
+    last_id
+}
+
+async fn run_logs(client: &AdminClient, args: &[String], json: bool) -> Result<(), String> {
+    let count = flag_value(args, &["-n", "--count"])
+        .map(|v| v.parse::<u32>().map_err(|_| format!("invalid count: {v}")))
+        .transpose()?
+        .unwrap_or(20);
+    let follow = args.iter().any(|a| a == "-f" || a == "--follow");
+
+    let mut latest = client
+        .get("/api/request-logs", &[("limit", count.to_string())])
+        .await?
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    latest.reverse();
+
+    let mut last_id = emit_logs(&latest, json, 0);
+
+    while follow {
+        tokio::time::sleep(LOGS_FOLLOW_INTERVAL).await;
+        let items = client
+            .get(
+                "/api/request-logs",
+                &[
+                    ("after_id", last_id.to_string()),
+                    ("limit", "200".to_string()),
+                ],
+            )
+            .await?
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        last_id = emit_logs(&items, json, last_id);
+    }
+    Ok(())
+}
+
+async fn run(args: Vec<String>) -> Result<(), String> {
+    let json = args.iter().any(|a| a == "--json");
+    let args: Vec<String> = args.into_iter().filter(|a| a != "--json").collect();
+    let command = args.first().map(String::as_str).unwrap_or("help");
+    if matches!(command, "help" | "-h" | "--help") {
+        print!("{USAGE}");
+        return Ok(());
+    }
+
+    let client = AdminClient::discover()?;
+    let output = |value: &Value, pretty: fn(&Value)| {
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(value).unwrap_or_default()
+            );
+        } else {
+            pretty(value);
+        }
+    };
+
+    match (command, args.get(1).map(String::as_str)) {
+        ("status", _) => output(&client.get("/api/status", &[]).await?, print_status),
+        ("gateway", Some("start")) => {
+            let mut body = serde_json::json!({});
+            if let Some(port) = flag_value(&args, &["--port", "-p"]) {
+                let port = port
+                    .parse::<u16>()
+                    .map_err(|_| format!("invalid port: {port}"))?;
+                body["preferred_port"] = Value::from(port);
+            }
+            client.post("/api/gateway/start", body).await?;
+            output(&client.get("/api/status", &[]).await?, print_status);
+        }
+        ("gateway", Some("stop")) => {
+            client
+                .post("/api/gateway/stop", serde_json::json!({}))
+                .await?;
+            output(&client.get("/api/status", &[]).await?, print_status);
+        }
+        ("providers", cli_key) => {
+            let query: Vec<(&str, String)> = cli_key
+                .map(|v| vec![("cli_key", v.to_string())])
+                .unwrap_or_default();
+            output(
+                &client.get("/api/providers", &query).await?,
+                print_providers,
+            );
+        }
+        ("circuit", Some("reset")) => {
+            let body = match flag_value(&args, &["--cli"]) {
+                Some(cli_key) => serde_json::json!({ "cli_key": cli_key }),
+                None => {
+                    let raw = args
+                        .get(2)
+                        .ok_or_else(|| "usage: aio-hub circuit reset <provider_id>".to_string())?;
+                    let provider_id = raw
+                        .parse::<i64>()
+                        .map_err(|_| format!("invalid provider_id: {raw}"))?;
+                    serde_json::json!({ "provider_id": provider_id })
+                }
+            };
+            let value = client.post("/api/circuits/reset", body).await?;
+            output(&value, |v| {
+                println!(
+                    "reset {} circuit(s)",
+                    v.get("reset").and_then(Value::as_i64).unwrap_or_default()
+                )
+            });
+        }
+        ("logs", _) => run_logs(&client, &args[1..], json).await?,
+        ("cost", _) => output(&client.get("/api/cost/today", &[]).await?, print_cost),
+        _ => return Err(format!("unknown command: {}\n\n{USAGE}", args.join(" "))),
+    }
+    Ok(())
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(err) = run(args).await {
+        eprintln!("aio-hub: {err}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_unix_seconds;
+
+    #[test]
+    fn formats_unix_seconds_as_utc() {
+        assert_eq!(format_unix_seconds(0), "1970-01-01 00:00:00");
+        assert_eq!(format_unix_seconds(1_709_251_199), "2024-02-29 23:59:59");
+    }
+}
@@ -55,3 +55,45 @@ pub(crate) fn app_restart(app: tauri::AppHandle) -> Result<bool, String> {
     });
     Ok(true)
 }
+
+#[tauri::command]
+pub(crate) async fn updater_check(
+    app: tauri::AppHandle,
+) -> Result<Option<crate::updater::UpdaterCheckInfo>, String> {
+    crate::updater::check(&app).await
+}
+
+#[tauri::command]
+pub(crate) async fn updater_download_and_install(
+    app: tauri::AppHandle,
+    rid: u32,
+    on_event: tauri::ipc::Channel<serde_json::Value>,
+) -> Result<(), String> {
+    crate::updater::download_and_install(&app, rid, on_event).await
+}
+
+#[tauri::command]
+pub(crate) fn updater_background_download_start(
+    app: tauri::AppHandle,
+    rid: u32,
+) -> Result<crate::updater::BackgroundDownloadState, String> {
+    crate::updater::start_background_download(&app, rid)
+}
+
+#[tauri::command]
+pub(crate) fn updater_background_download_state() -> crate::updater::BackgroundDownloadState {
+    crate::updater::background_download_state()
+}
+
+#[tauri::command]
+pub(crate) fn updater_rollback_info(app: tauri::AppHandle) -> crate::updater::RollbackInfo {
+    crate::updater::rollback_info(&app)
+}
+
+#[tauri::command]
+pub(crate) async fn updater_rollback(app: tauri::AppHandle) -> Result<String, String> {
+    let target = crate::updater::rollback_prepare(&app).await?;
+    tracing::info!(target = %target, "回滚版本已下载，重启以完成安装");
+    app_restart(app)?;
+    Ok(target)
+}
@@ -1,7 +1,7 @@
 //! Usage: Claude provider model validation related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState};
-use crate::{blocking, claude_model_validation, claude_model_validation_history};
+use crate::{blocking, claude_model_validation, claude_model_validation_history, settings};
 
 #[tauri::command]
 pub(crate) async fn claude_provider_validate_model(
@@ -11,9 +11,18 @@ pub(crate) async fn claude_provider_validate_model(
     base_url: String,
     request_json: String,
 ) -> Result<claude_model_validation::ClaudeModelValidationResult, String> {
+    let exclude_reasoning = settings::read(&app)
+        .map(|cfg| cfg.exclude_reasoning_from_logs)
+        .unwrap_or(false);
     let db = ensure_db_ready(app, db_state.inner()).await?;
-    claude_model_validation::validate_provider_model(db, provider_id, &base_url, &request_json)
-        .await
+    claude_model_validation::validate_provider_model(
+        db,
+        provider_id,
+        &base_url,
+        &request_json,
+        exclude_reasoning,
+    )
+    .await
 }
 
 #[tauri::command]
@@ -85,3 +85,36 @@ pub(crate) async fn cli_manager_claude_settings_set(
     })
     .await
 }
+
+#[tauri::command]
+pub(crate) async fn cli_manager_claude_hooks_get(
+    app: tauri::AppHandle,
+) -> Result<claude_settings::ClaudeHooksState, String> {
+    blocking::run("cli_manager_claude_hooks_get", move || {
+        claude_settings::claude_hooks_get(&app)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn cli_manager_claude_hook_create(
+    app: tauri::AppHandle,
+    hook: claude_settings::ClaudeHookInput,
+) -> Result<claude_settings::ClaudeHooksState, String> {
+    blocking::run("cli_manager_claude_hook_create", move || {
+        claude_settings::claude_hook_create(&app, hook)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn cli_manager_claude_hook_set_enabled(
+    app: tauri::AppHandle,
+    hook: claude_settings::ClaudeHookInput,
+    enabled: bool,
+) -> Result<claude_settings::ClaudeHooksState, String> {
+    blocking::run("cli_manager_claude_hook_set_enabled", move || {
+        claude_settings::claude_hook_set_enabled(&app, hook, enabled)
+    })
+    .await
+}
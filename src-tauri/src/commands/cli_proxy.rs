@@ -2,7 +2,7 @@
 
 use crate::app_state::{ensure_db_ready, DbInitState, GatewayState};
 use crate::shared::mutex_ext::MutexExt;
-use crate::{blocking, cli_proxy, settings};
+use crate::{blocking, cli_proxy, gateway, settings};
 use tauri::Emitter;
 use tauri::Manager;
 
@@ -39,8 +39,10 @@ pub(crate) async fn cli_proxy_set_enabled(
                 };
 
                 Ok(status.base_url.unwrap_or_else(|| {
+                    let settings = settings::read(&app).unwrap_or_default();
                     format!(
-                        "http://127.0.0.1:{}",
+                        "{}://127.0.0.1:{}",
+                        gateway::listen::url_scheme(settings.gateway_tls_enabled),
                         status.port.unwrap_or(settings::DEFAULT_GATEWAY_PORT)
                     )
                 }))
@@ -52,7 +54,11 @@ pub(crate) async fn cli_proxy_set_enabled(
             let app = app.clone();
             move || {
                 let settings = settings::read(&app).unwrap_or_default();
-                Ok(format!("http://127.0.0.1:{}", settings.preferred_port))
+                Ok(format!(
+                    "{}://127.0.0.1:{}",
+                    gateway::listen::url_scheme(settings.gateway_tls_enabled),
+                    settings.preferred_port
+                ))
             }
         })
         .await?
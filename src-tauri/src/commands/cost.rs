@@ -177,6 +177,33 @@ pub(crate) async fn cost_top_requests_v1(
     .await
 }
 
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn cost_prefix_affinity_v1(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    period: String,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    cli_key: Option<String>,
+    provider_id: Option<i64>,
+    model: Option<String>,
+) -> Result<cost_stats::CostPrefixAffinityV1, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("cost_prefix_affinity_v1", move || {
+        cost_stats::prefix_affinity_v1(
+            &db,
+            &period,
+            start_ts,
+            end_ts,
+            cli_key.as_deref(),
+            provider_id,
+            model.as_deref(),
+        )
+    })
+    .await
+}
+
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn cost_backfill_missing_v1(
@@ -206,3 +233,16 @@ pub(crate) async fn cost_backfill_missing_v1(
     })
     .await
 }
+
+#[tauri::command]
+pub(crate) async fn cost_provider_spend_v1(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+) -> Result<cost_stats::ProviderSpendReportV1, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("cost_provider_spend_v1", move || {
+        cost_stats::provider_spend_v1(&db, &cli_key)
+    })
+    .await
+}
@@ -0,0 +1,40 @@
+//! Usage: Crash report / diagnostic bundle / issue draft related Tauri commands.
+
+use crate::app_state::{ensure_db_ready, DbInitState};
+use crate::{blocking, crash_report, diagnostic_bundle, issue_draft};
+
+#[tauri::command]
+pub(crate) async fn crash_reports_list(
+    app: tauri::AppHandle,
+) -> Result<Vec<crash_report::CrashReportSummary>, String> {
+    blocking::run("crash_reports_list", move || crash_report::list(&app)).await
+}
+
+#[tauri::command]
+pub(crate) async fn crash_reports_clear(app: tauri::AppHandle) -> Result<usize, String> {
+    blocking::run("crash_reports_clear", move || crash_report::clear(&app)).await
+}
+
+#[tauri::command]
+pub(crate) async fn diagnostic_bundle_export(
+    app: tauri::AppHandle,
+    include_crash_reports: bool,
+) -> Result<diagnostic_bundle::DiagnosticBundleExport, String> {
+    blocking::run("diagnostic_bundle_export", move || {
+        diagnostic_bundle::export(&app, include_crash_reports)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn issue_draft_generate(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    trace_id: String,
+) -> Result<issue_draft::IssueDraft, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("issue_draft_generate", move || {
+        issue_draft::generate(&app, &db, &trace_id)
+    })
+    .await
+}
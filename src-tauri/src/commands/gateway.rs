@@ -2,7 +2,7 @@
 
 use crate::app_state::{ensure_db_ready, DbInitState, GatewayState};
 use crate::shared::mutex_ext::MutexExt;
-use crate::{blocking, cli_proxy, gateway, providers, request_logs, settings, wsl};
+use crate::{blocking, cli_proxy, gateway, gateway_tls, providers, request_logs, settings, wsl};
 use tauri::Emitter;
 use tauri::Manager;
 
@@ -27,6 +27,79 @@ pub(crate) fn gateway_status(state: tauri::State<'_, GatewayState>) -> gateway::
     manager.status()
 }
 
+#[tauri::command]
+pub(crate) fn gateway_recent_events(
+    after_seq: Option<u64>,
+    limit: Option<u32>,
+) -> Vec<gateway::GatewayBufferedEvent> {
+    gateway::event_buffer::recent(after_seq, limit)
+}
+
+#[tauri::command]
+pub(crate) fn gateway_inflight_list(
+    state: tauri::State<'_, GatewayState>,
+) -> Vec<gateway::GatewayInflightRequest> {
+    let manager = state.0.lock_or_recover();
+    manager.inflight_requests()
+}
+
+#[tauri::command]
+pub(crate) fn gateway_request_cancel(
+    state: tauri::State<'_, GatewayState>,
+    trace_id: String,
+) -> bool {
+    let manager = state.0.lock_or_recover();
+    manager.cancel_request(trace_id.trim()) > 0
+}
+
+#[tauri::command]
+pub(crate) fn gateway_pause_set(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, GatewayState>,
+    paused: bool,
+    reason: Option<String>,
+) -> gateway::GatewayStatus {
+    let status = {
+        let manager = state.0.lock_or_recover();
+        let pause = manager.set_paused(paused, reason.as_deref());
+        tracing::info!(
+            paused = pause.paused,
+            reason = pause.reason.as_deref().unwrap_or(""),
+            "网关暂停状态已变更"
+        );
+        manager.status()
+    };
+    let _ = app.emit("gateway:status", status.clone());
+    status
+}
+
+#[tauri::command]
+pub(crate) fn gateway_self_test_get() -> Option<gateway::self_test::GatewaySelfTestReport> {
+    gateway::self_test::last_report()
+}
+
+#[tauri::command]
+pub(crate) async fn gateway_self_test_run(
+    app: tauri::AppHandle,
+) -> Result<gateway::self_test::GatewaySelfTestReport, String> {
+    let status = app.state::<GatewayState>().0.lock_or_recover().status();
+    if !status.running {
+        return Err("GW_NOT_RUNNING: gateway is not running".to_string());
+    }
+    gateway::self_test::run(&app, &status)
+        .await
+        .ok_or_else(|| "SYSTEM_ERROR: gateway self-test failed to start".to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn gateway_network_test(
+    app: tauri::AppHandle,
+    url: String,
+) -> Result<gateway::network_test::NetworkTestResult, String> {
+    let network = settings::read(&app).unwrap_or_default().egress_network;
+    gateway::network_test::run(&network, url.trim()).await
+}
+
 #[tauri::command]
 pub(crate) fn gateway_check_port_available(app: tauri::AppHandle, port: u16) -> bool {
     if port < 1024 {
@@ -140,6 +213,23 @@ pub(crate) async fn gateway_circuit_status(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn gateway_route_explain(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    model: Option<String>,
+    session_id: Option<String>,
+) -> Result<gateway::GatewayRouteExplain, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("gateway_route_explain", move || {
+        let state = app.state::<GatewayState>();
+        let manager = state.0.lock_or_recover();
+        manager.route_explain(&app, &db, &cli_key, model.as_deref(), session_id.as_deref())
+    })
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn gateway_circuit_reset_provider(
     app: tauri::AppHandle,
@@ -171,6 +261,16 @@ pub(crate) async fn gateway_circuit_reset_cli(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn gateway_error_cache_clear(app: tauri::AppHandle) -> Result<usize, String> {
+    blocking::run("gateway_error_cache_clear", move || {
+        let state = app.state::<GatewayState>();
+        let manager = state.0.lock_or_recover();
+        Ok(manager.error_cache_clear())
+    })
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn gateway_start(
     app: tauri::AppHandle,
@@ -207,7 +307,17 @@ pub(crate) async fn gateway_stop(
     app: tauri::AppHandle,
     state: tauri::State<'_, GatewayState>,
 ) -> Result<gateway::GatewayStatus, String> {
-    crate::app::cleanup::stop_gateway_best_effort(&app).await;
+    let drain_timeout_seconds = settings::read(&app)
+        .map(|cfg| cfg.gateway_drain_timeout_seconds)
+        .unwrap_or(0);
+    let report = crate::app::cleanup::stop_gateway_with_drain(
+        &app,
+        std::time::Duration::from_secs(drain_timeout_seconds as u64),
+    )
+    .await;
+    if report.is_some() {
+        state.0.lock_or_recover().set_last_drain(report);
+    }
 
     let status = gateway_status(state);
     let _ = app.emit("gateway:status", status.clone());
@@ -224,3 +334,13 @@ pub(crate) async fn gateway_stop(
 
     Ok(status)
 }
+
+#[tauri::command]
+pub(crate) async fn gateway_tls_ca_export(
+    app: tauri::AppHandle,
+) -> Result<gateway_tls::GatewayTlsCaExport, String> {
+    blocking::run("gateway_tls_ca_export", move || {
+        gateway_tls::ca_export(&app)
+    })
+    .await
+}
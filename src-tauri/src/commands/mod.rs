@@ -9,6 +9,7 @@ pub(crate) mod cli_manager;
 pub(crate) mod cli_proxy;
 pub(crate) mod cost;
 pub(crate) mod data_management;
+pub(crate) mod diagnostics;
 pub(crate) mod gateway;
 pub(crate) mod mcp;
 pub(crate) mod model_prices;
@@ -19,6 +20,7 @@ pub(crate) mod request_logs;
 pub(crate) mod settings;
 pub(crate) mod skills;
 pub(crate) mod sort_modes;
+pub(crate) mod tokenizer;
 pub(crate) mod usage;
 pub(crate) mod wsl;
 
@@ -28,6 +30,7 @@ pub(crate) use cli_manager::*;
 pub(crate) use cli_proxy::*;
 pub(crate) use cost::*;
 pub(crate) use data_management::*;
+pub(crate) use diagnostics::*;
 pub(crate) use gateway::*;
 pub(crate) use mcp::*;
 pub(crate) use model_prices::*;
@@ -38,5 +41,6 @@ pub(crate) use request_logs::*;
 pub(crate) use settings::*;
 pub(crate) use skills::*;
 pub(crate) use sort_modes::*;
+pub(crate) use tokenizer::*;
 pub(crate) use usage::*;
 pub(crate) use wsl::*;
@@ -1,7 +1,7 @@
 //! Usage: Model pricing related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState};
-use crate::{blocking, model_price_aliases, model_prices, model_prices_sync};
+use crate::{blocking, model_price_aliases, model_prices, model_prices_sync, power};
 
 #[tauri::command]
 pub(crate) async fn model_prices_list(
@@ -37,8 +37,12 @@ pub(crate) async fn model_prices_sync_basellm(
     db_state: tauri::State<'_, DbInitState>,
     force: Option<bool>,
 ) -> Result<model_prices_sync::ModelPricesSyncReport, String> {
+    let force = force.unwrap_or(false);
+    if !force && power::background_work_suspended(&app) {
+        return Ok(model_prices_sync::ModelPricesSyncReport::skipped_low_power());
+    }
     let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
-    model_prices_sync::sync_basellm(&app, db, force.unwrap_or(false)).await
+    model_prices_sync::sync_basellm(&app, db, force).await
 }
 
 #[tauri::command]
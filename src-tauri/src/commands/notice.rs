@@ -1,6 +1,8 @@
 //! Usage: Notification-related Tauri commands.
 
-use crate::notice;
+use crate::app_state::{ensure_db_ready, DbInitState};
+use crate::i18n::MessageKey;
+use crate::{blocking, i18n, notice, notice_channels, notice_history, settings};
 
 #[tauri::command]
 pub(crate) fn notice_send(
@@ -8,7 +10,97 @@ pub(crate) fn notice_send(
     level: notice::NoticeLevel,
     title: Option<String>,
     body: String,
+    deliver_to_channels: Option<bool>,
 ) -> Result<bool, String> {
-    notice::emit(&app, notice::build(level, title, body))?;
+    let language = i18n::current_language(&app);
+    let payload = notice::build(language, level, title, body);
+    if deliver_to_channels.unwrap_or(false) {
+        notice::dispatch(&app, settings::NoticeEventKind::General, payload);
+    } else {
+        notice::emit(&app, payload)?;
+    }
     Ok(true)
 }
+
+#[tauri::command]
+pub(crate) async fn notice_history_list(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    limit: Option<u32>,
+    before_id: Option<i64>,
+) -> Result<Vec<notice_history::NoticeHistoryRow>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let limit = limit.unwrap_or(50).clamp(1, 500) as usize;
+    blocking::run("notice_history_list", move || {
+        notice_history::list(&db, limit, before_id)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn notice_history_clear(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+) -> Result<usize, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("notice_history_clear", move || notice_history::clear(&db)).await
+}
+
+#[tauri::command]
+pub(crate) async fn notice_test_channel(
+    app: tauri::AppHandle,
+    channel_id: String,
+) -> Result<bool, String> {
+    let channel_id = channel_id.trim().to_string();
+    let app_for_read = app.clone();
+    let cfg = blocking::run("notice_test_channel_read_settings", move || {
+        settings::read(&app_for_read)
+    })
+    .await?;
+    let Some(channel) = cfg
+        .notice_channels
+        .into_iter()
+        .find(|channel| channel.id.trim() == channel_id)
+    else {
+        return Err(format!("NOTICE_CHANNEL_NOT_FOUND: id={channel_id}"));
+    };
+
+    let language = cfg.ui_language;
+    let channel_label = if channel.name.trim().is_empty() {
+        channel.id.clone()
+    } else {
+        channel.name.trim().to_string()
+    };
+    let payload = notice::build(
+        language,
+        notice::NoticeLevel::Info,
+        Some(i18n::format(
+            language,
+            MessageKey::NoticeTestTitle,
+            &[("channel", channel_label.as_str())],
+        )),
+        i18n::text(language, MessageKey::NoticeTestBody),
+    );
+
+    // Test delivery ignores `enabled` and event filters on purpose.
+    let event = settings::NoticeEventKind::General;
+    let result = notice_channels::deliver(&channel, event, &payload).await;
+
+    notice_history::record(
+        &app,
+        notice_history::NoticeHistoryEntry {
+            event_kind: event.as_str(),
+            level: payload.level.as_str(),
+            title: payload.title,
+            body: payload.body,
+            deliveries: vec![notice_history::NoticeDeliveryRecord {
+                channel_id: channel.id.clone(),
+                channel_kind: channel.kind.as_str().to_string(),
+                error: result.as_ref().err().cloned(),
+            }],
+        },
+    )
+    .await;
+
+    result.map(|()| true)
+}
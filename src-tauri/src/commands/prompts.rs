@@ -1,7 +1,7 @@
 //! Usage: Prompt templates related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState};
-use crate::{blocking, prompts};
+use crate::{blocking, prompt_sync, prompts};
 
 #[tauri::command]
 pub(crate) async fn prompts_list(
@@ -69,3 +69,24 @@ pub(crate) async fn prompt_delete(
     })
     .await
 }
+
+#[tauri::command]
+pub(crate) async fn prompt_codex_project_agents_get(
+    project_dir: String,
+) -> Result<prompt_sync::CodexProjectAgentsState, String> {
+    blocking::run("prompt_codex_project_agents_get", move || {
+        prompt_sync::codex_project_agents_get(&project_dir)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn prompt_codex_project_agents_set(
+    project_dir: String,
+    content: Option<String>,
+) -> Result<prompt_sync::CodexProjectAgentsState, String> {
+    blocking::run("prompt_codex_project_agents_set", move || {
+        prompt_sync::codex_project_agents_set(&project_dir, content)
+    })
+    .await
+}
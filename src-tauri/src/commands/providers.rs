@@ -1,7 +1,9 @@
 //! Usage: Provider configuration related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState};
-use crate::{base_url_probe, blocking, providers};
+use crate::{
+    base_url_probe, blocking, provider_ab_test, provider_canary, provider_import, providers,
+};
 
 #[tauri::command]
 pub(crate) async fn providers_list(
@@ -31,6 +33,21 @@ pub(crate) async fn provider_upsert(
     cost_multiplier: f64,
     priority: Option<i64>,
     claude_models: Option<providers::ClaudeModels>,
+    response_fixer: Option<providers::ResponseFixerOverride>,
+    disable_response_storage: Option<bool>,
+    wire_api: Option<String>,
+    exclude_from_stats: Option<bool>,
+    rpm_limit: Option<i64>,
+    tpm_limit: Option<i64>,
+    daily_quota_usd: Option<f64>,
+    monthly_quota_usd: Option<f64>,
+    context_guard_limit_kb: Option<i64>,
+    upstream_proxy: Option<String>,
+    passthrough: Option<bool>,
+    request_signing: Option<providers::RequestSigning>,
+    upstream_tls: Option<providers::UpstreamTls>,
+    header_rules: Option<Vec<providers::ProviderHeaderRule>>,
+    body_patch_rules: Option<Vec<providers::BodyPatchRule>>,
 ) -> Result<providers::ProviderSummary, String> {
     let db = ensure_db_ready(app, db_state.inner()).await?;
     blocking::run("provider_upsert", move || {
@@ -46,6 +63,21 @@ pub(crate) async fn provider_upsert(
             cost_multiplier,
             priority,
             claude_models,
+            response_fixer,
+            disable_response_storage,
+            wire_api.as_deref(),
+            exclude_from_stats,
+            rpm_limit,
+            tpm_limit,
+            daily_quota_usd,
+            monthly_quota_usd,
+            context_guard_limit_kb,
+            upstream_proxy.as_deref(),
+            passthrough,
+            request_signing,
+            upstream_tls,
+            header_rules,
+            body_patch_rules,
         )
     })
     .await
@@ -65,6 +97,20 @@ pub(crate) async fn provider_set_enabled(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn provider_claude_models_set(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+    claude_models: providers::ClaudeModels,
+) -> Result<providers::ProviderSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_claude_models_set", move || {
+        providers::set_claude_models(&db, provider_id, claude_models)
+    })
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn provider_delete(
     app: tauri::AppHandle,
@@ -79,6 +125,13 @@ pub(crate) async fn provider_delete(
     .await
 }
 
+#[tauri::command]
+pub(crate) fn provider_import_from_text(
+    text: String,
+) -> Result<provider_import::ProviderImportDraft, String> {
+    provider_import::parse(&text)
+}
+
 #[tauri::command]
 pub(crate) async fn providers_reorder(
     app: tauri::AppHandle,
@@ -94,10 +147,182 @@ pub(crate) async fn providers_reorder(
 }
 
 #[tauri::command]
-pub(crate) async fn base_url_ping_ms(base_url: String) -> Result<u64, String> {
+pub(crate) async fn base_url_ping_ms(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    base_url: String,
+    provider_id: Option<i64>,
+) -> Result<u64, String> {
     let client = reqwest::Client::builder()
         .user_agent(format!("aio-coding-hub-ping/{}", env!("CARGO_PKG_VERSION")))
         .build()
         .map_err(|e| format!("PING_HTTP_CLIENT_INIT: {e}"))?;
-    base_url_probe::probe_base_url_ms(&client, &base_url, std::time::Duration::from_secs(3)).await
+    let result =
+        base_url_probe::probe_base_url_ms(&client, &base_url, std::time::Duration::from_secs(3))
+            .await;
+
+    // Best-effort: history failures must never break the ping itself.
+    if !matches!(&result, Err(err) if err.starts_with("SEC_INVALID_INPUT")) {
+        if let Ok(db) = ensure_db_ready(app, db_state.inner()).await {
+            let record = base_url_probe::BaseUrlProbeRecord {
+                provider_id,
+                base_url: base_url.trim().to_string(),
+                result: result.clone(),
+            };
+            let _ = blocking::run("base_url_probe_record", move || {
+                base_url_probe::record_results(&db, "manual", &[record])
+            })
+            .await;
+        }
+    }
+    result
+}
+
+#[tauri::command]
+pub(crate) async fn base_url_probe_history(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: Option<i64>,
+    base_url: Option<String>,
+    hours: Option<u32>,
+    bucket_minutes: Option<u32>,
+) -> Result<Vec<base_url_probe::BaseUrlLatencyPoint>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let hours = hours.unwrap_or(24).clamp(1, 30 * 24) as i64;
+    let bucket_seconds = bucket_minutes.unwrap_or(15).clamp(1, 24 * 60) as i64 * 60;
+    let since_ts = crate::shared::time::now_unix_seconds().saturating_sub(hours * 3600);
+    blocking::run("base_url_probe_history", move || {
+        base_url_probe::latency_series(
+            &db,
+            provider_id,
+            base_url.as_deref(),
+            since_ts,
+            bucket_seconds,
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn providers_speedtest(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    provider_ids: Vec<i64>,
+    model: Option<String>,
+) -> Result<Vec<crate::gateway::speedtest::SpeedtestResult>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    crate::gateway::speedtest::run_speedtest(db, cli_key, provider_ids, model).await
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn provider_canary_start(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    mode_id: Option<i64>,
+    provider_id: i64,
+    traffic_percent: u32,
+    duration_hours: u32,
+    max_error_rate_delta: Option<f64>,
+    min_samples: Option<i64>,
+) -> Result<provider_canary::ProviderCanary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_canary_start", move || {
+        provider_canary::start(
+            &db,
+            provider_canary::StartCanaryInput {
+                cli_key,
+                mode_id,
+                provider_id,
+                traffic_percent,
+                duration_hours,
+                max_error_rate_delta,
+                min_samples,
+            },
+        )
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn provider_canary_cancel(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    canary_id: i64,
+) -> Result<provider_canary::ProviderCanary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_canary_cancel", move || {
+        provider_canary::cancel(&db, canary_id)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn provider_canaries_list(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+) -> Result<Vec<provider_canary::ProviderCanaryView>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_canaries_list", move || {
+        provider_canary::list(&db, &cli_key)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn provider_ab_test_start(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    provider_a_id: i64,
+    provider_b_id: i64,
+    target_sessions: i64,
+) -> Result<provider_ab_test::ProviderAbTest, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_ab_test_start", move || {
+        provider_ab_test::start(&db, &cli_key, provider_a_id, provider_b_id, target_sessions)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn provider_ab_test_cancel(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    test_id: i64,
+) -> Result<provider_ab_test::ProviderAbTest, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_ab_test_cancel", move || {
+        provider_ab_test::cancel(&db, test_id)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn provider_ab_tests_list(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+) -> Result<Vec<provider_ab_test::ProviderAbTest>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_ab_tests_list", move || {
+        provider_ab_test::list(&db, &cli_key)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn provider_ab_test_report(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    test_id: i64,
+) -> Result<provider_ab_test::ProviderAbTestReport, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_ab_test_report", move || {
+        provider_ab_test::report(&db, test_id)
+    })
+    .await
 }
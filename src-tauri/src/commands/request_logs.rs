@@ -1,7 +1,7 @@
 //! Usage: Request logs and trace detail related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState};
-use crate::{blocking, request_attempt_logs, request_logs};
+use crate::{blocking, request_attempt_logs, request_logs, sse_transcript};
 
 #[tauri::command]
 pub(crate) async fn request_logs_list(
@@ -32,6 +32,54 @@ pub(crate) async fn request_logs_list_all(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn request_logs_list_before(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    before_created_at_ms: i64,
+    before_id: i64,
+    limit: Option<u32>,
+) -> Result<Vec<request_logs::RequestLogSummary>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let limit = limit.unwrap_or(50).clamp(1, 500) as usize;
+    blocking::run("request_logs_list_before", move || {
+        request_logs::list_before(&db, &cli_key, before_created_at_ms, before_id, limit)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn request_logs_list_before_all(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    before_created_at_ms: i64,
+    before_id: i64,
+    limit: Option<u32>,
+) -> Result<Vec<request_logs::RequestLogSummary>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let limit = limit.unwrap_or(50).clamp(1, 500) as usize;
+    blocking::run("request_logs_list_before_all", move || {
+        request_logs::list_before_all(&db, before_created_at_ms, before_id, limit)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn request_logs_list_filtered(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    query: request_logs::RequestLogListQuery,
+    limit: Option<u32>,
+) -> Result<Vec<request_logs::RequestLogSummary>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let limit = limit.unwrap_or(50).clamp(1, 500) as usize;
+    blocking::run("request_logs_list_filtered", move || {
+        request_logs::list_filtered(&db, &query, limit)
+    })
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn request_logs_list_after_id(
     app: tauri::AppHandle,
@@ -89,6 +137,21 @@ pub(crate) async fn request_log_get_by_trace_id(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn request_logs_by_client_trace_id(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    client_trace_id: String,
+    limit: Option<u32>,
+) -> Result<Vec<request_logs::RequestLogSummary>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let limit = limit.unwrap_or(50).clamp(1, 200) as usize;
+    blocking::run("request_logs_by_client_trace_id", move || {
+        request_logs::list_by_client_trace_id(&db, &client_trace_id, limit)
+    })
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn request_attempt_logs_by_trace_id(
     app: tauri::AppHandle,
@@ -103,3 +166,61 @@ pub(crate) async fn request_attempt_logs_by_trace_id(
     })
     .await
 }
+
+#[tauri::command]
+pub(crate) async fn request_sse_transcript_get(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    trace_id: String,
+) -> Result<Option<sse_transcript::SseTranscript>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("request_sse_transcript_get", move || {
+        sse_transcript::get_by_trace_id(&db, &trace_id)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn request_logs_client_usage(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    days: Option<u32>,
+) -> Result<Vec<request_logs::ClientUsageAggregate>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let days = days.unwrap_or(30).clamp(1, 365) as i64;
+    let since_ts = crate::shared::time::now_unix_seconds().saturating_sub(days * 24 * 60 * 60);
+    blocking::run("request_logs_client_usage", move || {
+        request_logs::aggregate_by_client(&db, since_ts)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn request_logs_response_fixer_dry_run_summary(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    days: Option<u32>,
+) -> Result<Vec<request_logs::ResponseFixerDryRunAggregate>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let days = days.unwrap_or(7).clamp(1, 365) as i64;
+    let since_ts = crate::shared::time::now_unix_seconds().saturating_sub(days * 24 * 60 * 60);
+    blocking::run("request_logs_response_fixer_dry_run_summary", move || {
+        request_logs::aggregate_response_fixer_dry_run(&db, since_ts)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn request_logs_usage_estimate_drift(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    days: Option<u32>,
+) -> Result<Vec<request_logs::UsageEstimateDriftAggregate>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let days = days.unwrap_or(7).clamp(1, 365) as i64;
+    let since_ts = crate::shared::time::now_unix_seconds().saturating_sub(days * 24 * 60 * 60);
+    blocking::run("request_logs_usage_estimate_drift", move || {
+        request_logs::aggregate_usage_estimate_drift(&db, since_ts)
+    })
+    .await
+}
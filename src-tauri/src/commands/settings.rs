@@ -1,6 +1,6 @@
 //! Usage: Settings-related Tauri commands.
 
-use crate::{blocking, resident, settings};
+use crate::{blocking, power, resident, settings};
 use tauri::Manager;
 
 #[tauri::command]
@@ -131,6 +131,54 @@ pub(crate) async fn settings_set(
             response_fixer_fix_truncated_json,
             response_fixer_max_json_depth: previous.response_fixer_max_json_depth,
             response_fixer_max_fix_size: previous.response_fixer_max_fix_size,
+            ui_language: previous.ui_language,
+            enable_low_power_mode: previous.enable_low_power_mode,
+            low_power_idle_minutes: previous.low_power_idle_minutes,
+            notice_channels: previous.notice_channels,
+            notice_quiet_hours_enabled: previous.notice_quiet_hours_enabled,
+            notice_quiet_hours_start: previous.notice_quiet_hours_start,
+            notice_quiet_hours_end: previous.notice_quiet_hours_end,
+            notice_quiet_hours_summary: previous.notice_quiet_hours_summary,
+            enable_circuit_recovery_notice: previous.enable_circuit_recovery_notice,
+            wsl_distro_overrides: previous.wsl_distro_overrides,
+            gateway_access_token: previous.gateway_access_token,
+            gateway_tls_enabled: previous.gateway_tls_enabled,
+            gateway_tls_hosts: previous.gateway_tls_hosts,
+            gateway_extra_listeners: previous.gateway_extra_listeners,
+            gateway_client_keys: previous.gateway_client_keys,
+            gateway_ip_allowlist: previous.gateway_ip_allowlist,
+            gateway_drain_timeout_seconds: previous.gateway_drain_timeout_seconds,
+            update_channel: previous.update_channel,
+            update_background_download: previous.update_background_download,
+            update_mirror: previous.update_mirror,
+            response_fixer_fix_tool_args: previous.response_fixer_fix_tool_args,
+            response_fixer_dry_run: previous.response_fixer_dry_run,
+            intercept_rules: previous.intercept_rules,
+            inflight_dedup_mode: previous.inflight_dedup_mode,
+            priority_lanes_enabled: previous.priority_lanes_enabled,
+            background_lane_max_concurrency: previous.background_lane_max_concurrency,
+            background_lane_queue_timeout_seconds: previous.background_lane_queue_timeout_seconds,
+            background_lane_provider_id: previous.background_lane_provider_id,
+            header_sanitize_rules: previous.header_sanitize_rules,
+            failover_rules: previous.failover_rules,
+            enable_request_redaction: previous.enable_request_redaction,
+            request_redaction_builtins: previous.request_redaction_builtins,
+            request_redaction_custom_patterns: previous.request_redaction_custom_patterns,
+            latency_probe_interval_minutes: previous.latency_probe_interval_minutes,
+            enable_prompt_cache_injection: previous.enable_prompt_cache_injection,
+            exclude_reasoning_from_logs: previous.exclude_reasoning_from_logs,
+            cli_max_concurrent_streams: previous.cli_max_concurrent_streams,
+            retry_backoff: previous.retry_backoff,
+            forward_trace_headers: previous.forward_trace_headers,
+            capture_sse_events: previous.capture_sse_events,
+            attempt_log_retention: previous.attempt_log_retention,
+            latency_routing: previous.latency_routing,
+            response_cache_ttl_seconds: previous.response_cache_ttl_seconds,
+            response_cache_completions: previous.response_cache_completions,
+            egress_network: previous.egress_network,
+            recent_error_cache: previous.recent_error_cache,
+            hedged_requests: previous.hedged_requests,
+            stream_tool_pause_grace: previous.stream_tool_pause_grace,
         };
 
         let next_settings = settings::write(&app_for_work, &settings)?;
@@ -155,6 +203,8 @@ pub(crate) async fn settings_gateway_rectifier_set(
     response_fixer_fix_truncated_json: bool,
     response_fixer_max_json_depth: u32,
     response_fixer_max_fix_size: u32,
+    response_fixer_fix_tool_args: Option<bool>,
+    response_fixer_dry_run: Option<bool>,
 ) -> Result<settings::AppSettings, String> {
     let app_for_work = app.clone();
     blocking::run("settings_gateway_rectifier_set", move || {
@@ -169,6 +219,12 @@ pub(crate) async fn settings_gateway_rectifier_set(
         settings.response_fixer_fix_truncated_json = response_fixer_fix_truncated_json;
         settings.response_fixer_max_json_depth = response_fixer_max_json_depth;
         settings.response_fixer_max_fix_size = response_fixer_max_fix_size;
+        if let Some(fix_tool_args) = response_fixer_fix_tool_args {
+            settings.response_fixer_fix_tool_args = fix_tool_args;
+        }
+        if let Some(dry_run) = response_fixer_dry_run {
+            settings.response_fixer_dry_run = dry_run;
+        }
 
         settings::write(&app_for_work, &settings)
     })
@@ -204,3 +260,632 @@ pub(crate) async fn settings_codex_session_id_completion_set(
     })
     .await
 }
+
+#[tauri::command]
+pub(crate) async fn settings_circuit_recovery_notice_set(
+    app: tauri::AppHandle,
+    enable_circuit_recovery_notice: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_circuit_recovery_notice_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.enable_circuit_recovery_notice = enable_circuit_recovery_notice;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_ui_language_set(
+    app: tauri::AppHandle,
+    ui_language: settings::AppLanguage,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_ui_language_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.ui_language = ui_language;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_low_power_mode_set(
+    app: tauri::AppHandle,
+    enable_low_power_mode: bool,
+    low_power_idle_minutes: u32,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_low_power_mode_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.enable_low_power_mode = enable_low_power_mode;
+        settings.low_power_idle_minutes = low_power_idle_minutes;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_notice_channels_set(
+    app: tauri::AppHandle,
+    notice_channels: Vec<settings::NoticeChannelConfig>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_notice_channels_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.notice_channels = notice_channels;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_wsl_distro_overrides_set(
+    app: tauri::AppHandle,
+    wsl_distro_overrides: Vec<settings::WslDistroOverride>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_wsl_distro_overrides_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.wsl_distro_overrides = wsl_distro_overrides;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_gateway_access_token_set(
+    app: tauri::AppHandle,
+    gateway_access_token: String,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_gateway_access_token_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.gateway_access_token = gateway_access_token.trim().to_string();
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_gateway_extra_listeners_set(
+    app: tauri::AppHandle,
+    gateway_extra_listeners: Vec<settings::GatewayExtraListener>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_gateway_extra_listeners_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.gateway_extra_listeners = gateway_extra_listeners;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_gateway_client_keys_set(
+    app: tauri::AppHandle,
+    gateway_client_keys: Vec<settings::GatewayClientKey>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_gateway_client_keys_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.gateway_client_keys = gateway_client_keys;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_gateway_ip_allowlist_set(
+    app: tauri::AppHandle,
+    gateway_ip_allowlist: Vec<String>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_gateway_ip_allowlist_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.gateway_ip_allowlist = gateway_ip_allowlist
+            .into_iter()
+            .map(|entry| entry.trim().to_string())
+            .filter(|entry| !entry.is_empty())
+            .collect();
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_intercept_rules_set(
+    app: tauri::AppHandle,
+    intercept_rules: Vec<settings::InterceptRule>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_intercept_rules_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.intercept_rules = intercept_rules
+            .into_iter()
+            .map(|mut rule| {
+                rule.id = rule.id.trim().to_string();
+                rule.name = rule.name.trim().to_string();
+                rule.cli_key = rule.cli_key.trim().to_string();
+                rule.method = rule.method.trim().to_ascii_uppercase();
+                rule.path = rule.path.trim().to_string();
+                rule
+            })
+            .collect();
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_request_redaction_set(
+    app: tauri::AppHandle,
+    enable_request_redaction: bool,
+    request_redaction_builtins: Vec<settings::RedactionBuiltin>,
+    request_redaction_custom_patterns: Vec<String>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_request_redaction_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.enable_request_redaction = enable_request_redaction;
+        settings.request_redaction_builtins = request_redaction_builtins;
+        settings.request_redaction_custom_patterns = request_redaction_custom_patterns
+            .into_iter()
+            .filter(|p| !p.trim().is_empty())
+            .collect();
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_header_sanitize_rules_set(
+    app: tauri::AppHandle,
+    header_sanitize_rules: Vec<settings::HeaderSanitizeRule>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_header_sanitize_rules_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.header_sanitize_rules = header_sanitize_rules
+            .into_iter()
+            .map(|mut rule| {
+                rule.id = rule.id.trim().to_string();
+                rule.name = rule.name.trim().to_string();
+                rule.cli_key = rule.cli_key.trim().to_string();
+                rule.headers = rule
+                    .headers
+                    .into_iter()
+                    .map(|h| h.trim().to_ascii_lowercase())
+                    .filter(|h| !h.is_empty())
+                    .collect();
+                rule
+            })
+            .collect();
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_failover_rules_set(
+    app: tauri::AppHandle,
+    failover_rules: Vec<settings::FailoverRule>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_failover_rules_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.failover_rules = failover_rules
+            .into_iter()
+            .map(|mut rule| {
+                rule.id = rule.id.trim().to_string();
+                rule.name = rule.name.trim().to_string();
+                rule.cli_key = rule.cli_key.trim().to_string();
+                rule.body_regex = rule.body_regex.trim().to_string();
+                rule.statuses.sort_unstable();
+                rule.statuses.dedup();
+                rule
+            })
+            .collect();
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_gateway_drain_timeout_set(
+    app: tauri::AppHandle,
+    gateway_drain_timeout_seconds: u32,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_gateway_drain_timeout_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.gateway_drain_timeout_seconds = gateway_drain_timeout_seconds;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_inflight_dedup_mode_set(
+    app: tauri::AppHandle,
+    inflight_dedup_mode: settings::InflightDedupMode,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_inflight_dedup_mode_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.inflight_dedup_mode = inflight_dedup_mode;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_priority_lanes_set(
+    app: tauri::AppHandle,
+    priority_lanes_enabled: bool,
+    background_lane_max_concurrency: u32,
+    background_lane_queue_timeout_seconds: u32,
+    background_lane_provider_id: Option<i64>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_priority_lanes_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.priority_lanes_enabled = priority_lanes_enabled;
+        settings.background_lane_max_concurrency = background_lane_max_concurrency;
+        settings.background_lane_queue_timeout_seconds = background_lane_queue_timeout_seconds;
+        settings.background_lane_provider_id = background_lane_provider_id;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_latency_probe_interval_set(
+    app: tauri::AppHandle,
+    latency_probe_interval_minutes: u32,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_latency_probe_interval_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.latency_probe_interval_minutes = latency_probe_interval_minutes;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_response_cache_ttl_set(
+    app: tauri::AppHandle,
+    response_cache_ttl_seconds: u32,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_response_cache_ttl_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.response_cache_ttl_seconds = response_cache_ttl_seconds;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_response_cache_completions_set(
+    app: tauri::AppHandle,
+    response_cache_completions: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_response_cache_completions_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.response_cache_completions = response_cache_completions;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_prompt_cache_injection_set(
+    app: tauri::AppHandle,
+    enable_prompt_cache_injection: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_prompt_cache_injection_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.enable_prompt_cache_injection = enable_prompt_cache_injection;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_exclude_reasoning_from_logs_set(
+    app: tauri::AppHandle,
+    exclude_reasoning_from_logs: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_exclude_reasoning_from_logs_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.exclude_reasoning_from_logs = exclude_reasoning_from_logs;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_cli_stream_caps_set(
+    app: tauri::AppHandle,
+    cli_max_concurrent_streams: settings::CliStreamCaps,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_cli_stream_caps_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.cli_max_concurrent_streams = cli_max_concurrent_streams;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_retry_backoff_set(
+    app: tauri::AppHandle,
+    retry_backoff: settings::RetryBackoffConfig,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_retry_backoff_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.retry_backoff = retry_backoff;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_forward_trace_headers_set(
+    app: tauri::AppHandle,
+    forward_trace_headers: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_forward_trace_headers_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.forward_trace_headers = forward_trace_headers;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_capture_sse_events_set(
+    app: tauri::AppHandle,
+    capture_sse_events: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_capture_sse_events_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.capture_sse_events = capture_sse_events;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_attempt_log_retention_set(
+    app: tauri::AppHandle,
+    attempt_log_retention: settings::AttemptLogRetentionConfig,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_attempt_log_retention_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.attempt_log_retention = attempt_log_retention;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_latency_routing_set(
+    app: tauri::AppHandle,
+    latency_routing: settings::LatencyRoutingConfig,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_latency_routing_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.latency_routing = latency_routing;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_recent_error_cache_set(
+    app: tauri::AppHandle,
+    recent_error_cache: settings::RecentErrorCacheConfig,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_recent_error_cache_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.recent_error_cache = recent_error_cache;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_hedged_requests_set(
+    app: tauri::AppHandle,
+    hedged_requests: settings::HedgedRequestsConfig,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_hedged_requests_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.hedged_requests = hedged_requests;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_stream_tool_pause_grace_set(
+    app: tauri::AppHandle,
+    stream_tool_pause_grace: settings::StreamToolPauseGraceConfig,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_stream_tool_pause_grace_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.stream_tool_pause_grace = stream_tool_pause_grace;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_update_channel_set(
+    app: tauri::AppHandle,
+    update_channel: settings::UpdateChannel,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_update_channel_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.update_channel = update_channel;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_update_mirror_set(
+    app: tauri::AppHandle,
+    update_mirror: settings::UpdateMirrorConfig,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_update_mirror_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.update_mirror = update_mirror;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_egress_network_set(
+    app: tauri::AppHandle,
+    egress_network: settings::EgressNetworkConfig,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_egress_network_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.egress_network = egress_network;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_update_background_download_set(
+    app: tauri::AppHandle,
+    update_background_download: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_update_background_download_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.update_background_download = update_background_download;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_gateway_tls_set(
+    app: tauri::AppHandle,
+    gateway_tls_enabled: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_gateway_tls_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.gateway_tls_enabled = gateway_tls_enabled;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn settings_gateway_tls_hosts_set(
+    app: tauri::AppHandle,
+    gateway_tls_hosts: Vec<String>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_gateway_tls_hosts_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.gateway_tls_hosts = gateway_tls_hosts
+            .into_iter()
+            .map(|entry| entry.trim().to_ascii_lowercase())
+            .filter(|entry| !entry.is_empty())
+            .collect();
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) fn gateway_access_token_generate() -> Result<String, String> {
+    settings::generate_gateway_access_token()
+}
+
+#[tauri::command]
+pub(crate) async fn settings_notice_quiet_hours_set(
+    app: tauri::AppHandle,
+    enabled: bool,
+    start: String,
+    end: String,
+    summary: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    blocking::run("settings_notice_quiet_hours_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.notice_quiet_hours_enabled = enabled;
+        settings.notice_quiet_hours_start = start.trim().to_string();
+        settings.notice_quiet_hours_end = end.trim().to_string();
+        settings.notice_quiet_hours_summary = summary;
+        settings::write(&app_for_work, &settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn power_status_get(app: tauri::AppHandle) -> Result<power::PowerStatus, String> {
+    blocking::run("power_status_get", move || Ok(power::status(&app))).await
+}
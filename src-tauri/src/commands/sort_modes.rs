@@ -2,7 +2,7 @@
 
 use crate::app_state::{ensure_db_ready, DbInitState, GatewayState};
 use crate::shared::mutex_ext::MutexExt;
-use crate::{blocking, sort_modes};
+use crate::{blocking, sort_mode_schedules, sort_modes};
 
 #[tauri::command]
 pub(crate) async fn sort_modes_list(
@@ -40,6 +40,45 @@ pub(crate) async fn sort_mode_rename(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn sort_mode_settings_set(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    mode_id: i64,
+    settings: sort_modes::SortModeSettingsOverride,
+) -> Result<sort_modes::SortModeSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_settings_set", move || {
+        sort_modes::set_mode_settings(&db, mode_id, settings)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn sort_mode_strategy_set(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    gateway_state: tauri::State<'_, GatewayState>,
+    mode_id: i64,
+    strategy: sort_modes::SortModeStrategy,
+) -> Result<sort_modes::SortModeSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let summary = blocking::run("sort_mode_strategy_set", move || {
+        sort_modes::set_mode_strategy(&db, mode_id, strategy)
+    })
+    .await?;
+
+    // Sessions keep the order they were bound with; drop bindings so the new strategy applies.
+    {
+        let manager = gateway_state.0.lock_or_recover();
+        for cli_key in crate::shared::cli_key::SUPPORTED_CLI_KEYS {
+            manager.clear_cli_session_bindings(cli_key);
+        }
+    }
+
+    Ok(summary)
+}
+
 #[tauri::command]
 pub(crate) async fn sort_mode_delete(
     app: tauri::AppHandle,
@@ -54,6 +93,46 @@ pub(crate) async fn sort_mode_delete(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn sort_mode_export(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    mode_id: i64,
+) -> Result<String, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_export", move || {
+        sort_modes::export_mode(&db, mode_id)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn sort_mode_import_preview(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    text: String,
+) -> Result<sort_modes::SortModeImportPreview, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_import_preview", move || {
+        sort_modes::preview_import(&db, &text)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn sort_mode_import(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    text: String,
+    name: Option<String>,
+) -> Result<sort_modes::SortModeImportResult, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_import", move || {
+        sort_modes::import_mode(&db, &text, name.as_deref())
+    })
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn sort_mode_active_list(
     app: tauri::AppHandle,
@@ -117,3 +196,74 @@ pub(crate) async fn sort_mode_providers_set_order(
     })
     .await
 }
+
+#[tauri::command]
+pub(crate) async fn sort_mode_provider_weights_list(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    mode_id: i64,
+    cli_key: String,
+) -> Result<Vec<sort_modes::SortModeProviderWeight>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_provider_weights_list", move || {
+        sort_modes::list_mode_provider_weights(&db, mode_id, &cli_key)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn sort_mode_provider_weight_set(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    mode_id: i64,
+    cli_key: String,
+    provider_id: i64,
+    weight: u32,
+) -> Result<Vec<sort_modes::SortModeProviderWeight>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_provider_weight_set", move || {
+        sort_modes::set_mode_provider_weight(&db, mode_id, &cli_key, provider_id, weight)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn sort_mode_schedule_list(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: Option<String>,
+) -> Result<Vec<sort_mode_schedules::SortModeScheduleRule>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_schedule_list", move || {
+        sort_mode_schedules::list_rules(&db, cli_key.as_deref())
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn sort_mode_schedule_set(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    rules: Vec<sort_mode_schedules::SortModeScheduleRuleInput>,
+) -> Result<Vec<sort_mode_schedules::SortModeScheduleRule>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_schedule_set", move || {
+        sort_mode_schedules::set_rules(&db, &cli_key, rules)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn sort_mode_switch_events_list(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<sort_mode_schedules::SortModeSwitchEvent>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_switch_events_list", move || {
+        sort_mode_schedules::list_switch_events(&db, cli_key.as_deref(), limit)
+    })
+    .await
+}
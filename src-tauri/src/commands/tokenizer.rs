@@ -0,0 +1,22 @@
+//! Usage: Local token estimation related Tauri commands.
+
+use crate::{blocking, tokenizer};
+
+const MAX_ESTIMATE_INPUT_BYTES: usize = 16 * 1024 * 1024;
+
+#[tauri::command]
+pub(crate) async fn tokens_estimate(
+    text: String,
+    as_request: Option<bool>,
+    model: Option<String>,
+) -> Result<tokenizer::TokenEstimate, String> {
+    if text.len() > MAX_ESTIMATE_INPUT_BYTES {
+        return Err(format!(
+            "SEC_INVALID_INPUT: text is too large (max {MAX_ESTIMATE_INPUT_BYTES} bytes)"
+        ));
+    }
+    blocking::run("tokens_estimate", move || {
+        tokenizer::estimate(&text, as_request.unwrap_or(false), model.as_deref())
+    })
+    .await
+}
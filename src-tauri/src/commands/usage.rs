@@ -106,3 +106,36 @@ pub(crate) async fn usage_hourly_series(
     })
     .await
 }
+
+#[tauri::command]
+pub(crate) async fn usage_tool_usage(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    range: String,
+    cli_key: Option<String>,
+    group_by: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<usage_stats::UsageToolRow>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let group_by = group_by.unwrap_or_else(|| "tool".to_string());
+    let limit = limit.unwrap_or(100).clamp(1, 1000) as usize;
+    blocking::run("usage_tool_usage", move || {
+        usage_stats::tool_usage(&db, &range, cli_key.as_deref(), &group_by, limit)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn usage_codex_session_completions(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    range: String,
+    limit: Option<u32>,
+) -> Result<usage_stats::CodexSessionCompletionReport, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let limit = limit.unwrap_or(50).clamp(1, 500) as usize;
+    blocking::run("usage_codex_session_completions", move || {
+        usage_stats::codex_session_completions(&db, &range, limit)
+    })
+    .await
+}
@@ -24,8 +24,41 @@ pub(crate) async fn wsl_host_address_get() -> Option<String> {
     .unwrap_or(None)
 }
 
+#[tauri::command]
+pub(crate) async fn wsl_networking_get(app: tauri::AppHandle) -> wsl::WslNetworkingDiagnostic {
+    let home_dir = app.path().home_dir().ok();
+    blocking::run("wsl_networking_get", move || {
+        Ok(wsl::networking_diagnostic(home_dir.as_deref()))
+    })
+    .await
+    .unwrap_or_else(|_| wsl::networking_diagnostic(None))
+}
+
+#[tauri::command]
+pub(crate) async fn wsl_port_proxy_get(app: tauri::AppHandle) -> Option<wsl::WslPortProxyMapping> {
+    blocking::run("wsl_port_proxy_get", move || {
+        wsl::read_port_proxy_state(&app)
+    })
+    .await
+    .unwrap_or(None)
+}
+
+#[tauri::command]
+pub(crate) async fn wsl_port_proxy_remove(app: tauri::AppHandle) -> Result<bool, String> {
+    blocking::run("wsl_port_proxy_remove", move || {
+        wsl::remove_port_proxy(&app)
+    })
+    .await
+}
+
+fn port_proxy_conflicts(mapping: &wsl::WslPortProxyMapping, bind_host: &str, port: u16) -> bool {
+    mapping.listen_port == port
+        && (gateway::listen::is_wildcard_host(bind_host) || mapping.listen_address == bind_host)
+}
+
 #[tauri::command]
 pub(crate) async fn wsl_config_status_get(
+    app: tauri::AppHandle,
     distros: Option<Vec<String>>,
 ) -> Vec<wsl::WslDistroConfigStatus> {
     blocking::run("wsl_config_status_get", move || {
@@ -41,7 +74,12 @@ pub(crate) async fn wsl_config_status_get(
             }
         };
 
-        Ok(wsl::get_config_status(&distros))
+        let mut statuses = wsl::get_config_status(&distros);
+        let states = wsl::read_distro_states(&app).unwrap_or_default();
+        for status in &mut statuses {
+            status.last_configured = states.get(&status.distro).cloned();
+        }
+        Ok(statuses)
     })
     .await
     .unwrap_or_default()
@@ -52,12 +90,18 @@ pub(crate) async fn wsl_configure_clients(
     app: tauri::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     targets: settings::WslTargetCli,
+    distros: Option<Vec<String>>,
+    configure_firewall: Option<bool>,
+    port_proxy: Option<bool>,
 ) -> Result<wsl::WslConfigureReport, String> {
     if !cfg!(windows) {
         return Ok(wsl::WslConfigureReport {
             ok: false,
             message: "WSL configuration is only available on Windows".to_string(),
             distros: Vec::new(),
+            firewall: None,
+            networking: None,
+            port_proxy: None,
         });
     }
 
@@ -69,11 +113,22 @@ pub(crate) async fn wsl_configure_clients(
     })
     .await?;
 
-    if cfg.gateway_listen_mode == settings::GatewayListenMode::Localhost {
+    let networking = wsl::networking_diagnostic(app.path().home_dir().ok().as_deref());
+    let mirrored = networking.mode == wsl::WslNetworkingMode::Mirrored;
+
+    // Mirrored networking shares loopback with Windows, so localhost-only listening still works.
+    // Otherwise a localhost-only gateway is only reachable through a netsh port proxy.
+    let localhost_only =
+        !mirrored && cfg.gateway_listen_mode == settings::GatewayListenMode::Localhost;
+    let use_port_proxy = localhost_only && port_proxy.unwrap_or(false);
+    if localhost_only && !use_port_proxy {
         return Ok(wsl::WslConfigureReport {
             ok: false,
-            message: "监听模式为“仅本地(127.0.0.1)”时，WSL 无法访问网关。请先切换到：WSL 自动检测 / 局域网 / 自定义地址。".to_string(),
+            message: "监听模式为“仅本地(127.0.0.1)”时，WSL 无法访问网关。请先切换到：WSL 自动检测 / 局域网 / 自定义地址，或启用端口转发。".to_string(),
             distros: Vec::new(),
+            firewall: None,
+            networking: Some(networking),
+            port_proxy: None,
         });
     }
 
@@ -83,9 +138,36 @@ pub(crate) async fn wsl_configure_clients(
             ok: false,
             message: "WSL not detected".to_string(),
             distros: Vec::new(),
+            firewall: None,
+            networking: Some(networking),
+            port_proxy: None,
         });
     }
 
+    // Listen mode changed away from port proxy: remove the stale mapping (one UAC prompt) only when
+    // it holds the address / port the gateway is about to bind; otherwise leave it alone.
+    if !use_port_proxy {
+        let app = app.clone();
+        let cfg = cfg.clone();
+        if let Err(err) = blocking::run("wsl_configure_clients_port_proxy_remove", move || {
+            let Some(mapping) = wsl::read_port_proxy_state(&app)? else {
+                return Ok(false);
+            };
+            let Ok((bind_host, fixed_port)) = gateway::listen::bind_target(&cfg) else {
+                return Ok(false);
+            };
+            let port = fixed_port.unwrap_or(cfg.preferred_port);
+            if !port_proxy_conflicts(&mapping, &bind_host, port) {
+                return Ok(false);
+            }
+            wsl::remove_port_proxy(&app)
+        })
+        .await
+        {
+            tracing::warn!("WSL 端口转发清理失败: {}", err);
+        }
+    }
+
     let preferred_port = cfg.preferred_port;
     let status = blocking::run("wsl_configure_clients_ensure_gateway", {
         let app = app.clone();
@@ -103,9 +185,25 @@ pub(crate) async fn wsl_configure_clients(
         .ok_or_else(|| "gateway_start returned no port".to_string())?;
 
     let host = match cfg.gateway_listen_mode {
+        settings::GatewayListenMode::Localhost if use_port_proxy => {
+            match wsl::host_ipv4_best_effort() {
+                Some(ip) => ip,
+                None => {
+                    return Ok(wsl::WslConfigureReport {
+                        ok: false,
+                        message: "未找到 vEthernet (WSL) 网卡地址，无法创建端口转发".to_string(),
+                        distros: Vec::new(),
+                        firewall: None,
+                        networking: Some(networking),
+                        port_proxy: None,
+                    });
+                }
+            }
+        }
         settings::GatewayListenMode::Localhost => "127.0.0.1".to_string(),
+        // The WslAuto bind host itself; a wildcard bind accepts it as well.
         settings::GatewayListenMode::WslAuto | settings::GatewayListenMode::Lan => {
-            wsl::host_ipv4_best_effort().unwrap_or_else(|| "127.0.0.1".to_string())
+            gateway::listen::wsl_host()
         }
         settings::GatewayListenMode::Custom => {
             let parsed = match gateway::listen::parse_custom_listen_address(
@@ -117,23 +215,102 @@ pub(crate) async fn wsl_configure_clients(
                         ok: false,
                         message: format!("自定义监听地址无效：{err}"),
                         distros: Vec::new(),
+                        firewall: None,
+                        networking: Some(networking),
+                        port_proxy: None,
                     });
                 }
             };
             if gateway::listen::is_wildcard_host(&parsed.host) {
-                wsl::host_ipv4_best_effort().unwrap_or_else(|| "127.0.0.1".to_string())
+                gateway::listen::wsl_host()
             } else {
                 parsed.host
             }
         }
     };
 
-    let proxy_origin = format!("http://{}", gateway::listen::format_host_port(&host, port));
-    let distros = detection.distros;
-    let report = blocking::run("wsl_configure_clients", move || {
-        Ok(wsl::configure_clients(&distros, &targets, &proxy_origin))
+    let port_proxy_report = if use_port_proxy {
+        let report = blocking::run("wsl_configure_clients_port_proxy", {
+            let app = app.clone();
+            let host = host.clone();
+            move || Ok(wsl::ensure_port_proxy(&app, &host, port))
+        })
+        .await?;
+        if !report.ok {
+            return Ok(wsl::WslConfigureReport {
+                ok: false,
+                message: report.message.clone(),
+                distros: Vec::new(),
+                firewall: None,
+                networking: Some(networking),
+                port_proxy: Some(report),
+            });
+        }
+        Some(report)
+    } else {
+        None
+    };
+
+    let proxy_origin = format!(
+        "{}://{}",
+        gateway::listen::url_scheme(cfg.gateway_tls_enabled),
+        gateway::listen::format_host_port(&host, port)
+    );
+    // `targets` is the default for distros without an override; an explicit selection also
+    // includes distros whose override is disabled.
+    let mut plan_cfg = cfg.clone();
+    plan_cfg.wsl_target_cli = targets;
+    let plan: Vec<(String, settings::WslTargetCli)> = detection
+        .distros
+        .into_iter()
+        .filter_map(|distro| {
+            let (enabled, targets) = settings::wsl_targets_for_distro(&plan_cfg, &distro);
+            let include = match distros.as_ref() {
+                Some(selected) => selected.iter().any(|d| d.trim() == distro),
+                None => enabled,
+            };
+            include.then_some((distro, targets))
+        })
+        .collect();
+
+    if plan.is_empty() {
+        return Ok(wsl::WslConfigureReport {
+            ok: false,
+            message: "没有需要配置的 distro（均已在单独配置中停用或未选中）".to_string(),
+            distros: Vec::new(),
+            firewall: None,
+            networking: Some(networking),
+            port_proxy: None,
+        });
+    }
+
+    let api_key = if cfg.gateway_access_token.is_empty() {
+        wsl::PLACEHOLDER_API_KEY.to_string()
+    } else {
+        cfg.gateway_access_token.clone()
+    };
+    let mut report = blocking::run("wsl_configure_clients", {
+        let app = app.clone();
+        move || {
+            let report = wsl::configure_clients(&plan, &proxy_origin, &api_key);
+            if let Err(err) = wsl::record_distro_states(&app, &report, &proxy_origin) {
+                tracing::warn!("WSL distro 配置状态保存失败: {}", err);
+            }
+            Ok(report)
+        }
     })
     .await?;
 
+    // Only with explicit consent: creating the rule triggers a UAC prompt.
+    if configure_firewall.unwrap_or(false) {
+        let firewall = blocking::run("wsl_configure_clients_firewall", move || {
+            Ok(wsl::ensure_firewall_rule(port))
+        })
+        .await?;
+        report.firewall = Some(firewall);
+    }
+    report.networking = Some(networking);
+    report.port_proxy = port_proxy_report;
+
     Ok(report)
 }
@@ -32,8 +32,16 @@ pub async fn validate_provider_model(
     provider_id: i64,
     base_url: &str,
     request_json: &str,
+    exclude_reasoning_from_history: bool,
 ) -> Result<ClaudeModelValidationResult, String> {
-    workflow::validate_provider_model(db, provider_id, base_url, request_json).await
+    workflow::validate_provider_model(
+        db,
+        provider_id,
+        base_url,
+        request_json,
+        exclude_reasoning_from_history,
+    )
+    .await
 }
 
 pub async fn get_provider_api_key_plaintext(
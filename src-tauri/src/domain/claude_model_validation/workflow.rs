@@ -1,5 +1,8 @@
 //! Usage: Claude model validation workflow (HTTP execution + roundtrip checks).
 
+use crate::shared::reasoning_redaction::{
+    strip_reasoning_json, strip_reasoning_json_text, strip_reasoning_sse_text,
+};
 use crate::{blocking, claude_model_validation_history, db};
 use std::time::Instant;
 
@@ -93,6 +96,7 @@ pub(super) async fn validate_provider_model(
     provider_id: i64,
     base_url: &str,
     request_json: &str,
+    exclude_reasoning_from_history: bool,
 ) -> Result<ClaudeModelValidationResult, String> {
     let started = Instant::now();
 
@@ -729,7 +733,24 @@ pub(super) async fn validate_provider_model(
     let db_for_history = db.clone();
     let provider_id_for_history = provider.id;
     let request_json_text = sanitized_request_text;
-    let result_json = serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string());
+    let result_json = if exclude_reasoning_from_history {
+        // 隐私设置：thinking / reasoning 内容不落库（仅影响历史记录，不影响本次返回结果）
+        let mut persisted = result.clone();
+        persisted.raw_excerpt = strip_reasoning_sse_text(&persisted.raw_excerpt);
+        serde_json::to_value(&persisted)
+            .map(|mut value| {
+                strip_reasoning_json(&mut value);
+                value.to_string()
+            })
+            .unwrap_or_else(|_| "{}".to_string())
+    } else {
+        serde_json::to_string(&result).unwrap_or_else(|_| "{}".to_string())
+    };
+    let request_json_text = if exclude_reasoning_from_history {
+        strip_reasoning_json_text(&request_json_text)
+    } else {
+        request_json_text
+    };
     let _ = blocking::run("claude_validation_history_insert", move || {
         claude_model_validation_history::insert_run_and_prune(
             &db_for_history,
@@ -2,11 +2,14 @@
 
 use crate::cost;
 use crate::db;
+use crate::usage_rollups;
+use crate::usage_stats;
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::Serialize;
 
 const USD_FEMTO_DENOM: f64 = 1_000_000_000_000_000.0;
-const SQL_MODEL_KEY_EXPR: &str = "COALESCE(NULLIF(TRIM(requested_model), ''), 'Unknown')";
+pub(crate) const SQL_MODEL_KEY_EXPR: &str =
+    "COALESCE(NULLIF(TRIM(requested_model), ''), 'Unknown')";
 
 #[derive(Debug, Clone, Serialize)]
 pub struct CostSummaryV1 {
@@ -72,6 +75,17 @@ pub struct CostScatterCliProviderModelRowV1 {
     pub total_duration_ms: i64,
 }
 
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CostPrefixAffinityV1 {
+    // Successful requests that had a stable prompt prefix (eligible for prefix stickiness).
+    pub requests_eligible: i64,
+    pub requests_hit: i64,
+    pub hit_cache_read_tokens: i64,
+    pub hit_prompt_tokens: i64,
+    pub miss_cache_read_tokens: i64,
+    pub miss_prompt_tokens: i64,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct CostBackfillReportV1 {
     pub scanned: i64,
@@ -84,6 +98,21 @@ pub struct CostBackfillReportV1 {
     pub max_rows: i64,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderSpendRowV1 {
+    pub provider_id: i64,
+    pub daily_cost_usd: f64,
+    pub monthly_cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderSpendReportV1 {
+    // Unix seconds of the next local day / month rollover (quota reset).
+    pub daily_reset_at: i64,
+    pub monthly_reset_at: i64,
+    pub rows: Vec<ProviderSpendRowV1>,
+}
+
 #[derive(Debug, Clone, Copy)]
 enum CostPeriodV1 {
     Daily,
@@ -214,42 +243,29 @@ pub fn summary_v1(
     let model = normalize_model_filter(model);
     let model = model.as_deref();
 
+    let source = usage_rollups::stats_source_sql(&conn, start_ts, end_ts)?;
     let sql = format!(
         r#"
 SELECT
-  COUNT(*) AS requests_total,
-  SUM(CASE WHEN status >= 200 AND status < 300 AND error_code IS NULL THEN 1 ELSE 0 END) AS requests_success,
-  SUM(
-    CASE WHEN (
-      status IS NULL OR
-      status < 200 OR
-      status >= 300 OR
-      error_code IS NOT NULL
-    ) THEN 1 ELSE 0 END
-  ) AS requests_failed,
-  SUM(
-    CASE WHEN (
-      status >= 200 AND status < 300 AND error_code IS NULL AND
-      cost_usd_femto IS NOT NULL
-    ) THEN 1 ELSE 0 END
-  ) AS cost_covered_success,
-  SUM(COALESCE(cost_usd_femto, 0)) AS total_cost_usd_femto
-FROM request_logs
-WHERE excluded_from_stats = 0
-AND (?1 IS NULL OR created_at >= ?1)
+  SUM(requests_total) AS requests_total,
+  SUM(requests_success) AS requests_success,
+  SUM(requests_failed) AS requests_failed,
+  SUM(success_cost_covered) AS cost_covered_success,
+  SUM(cost_usd_femto) AS total_cost_usd_femto
+FROM {source}
+WHERE (?1 IS NULL OR created_at >= ?1)
 AND (?2 IS NULL OR created_at < ?2)
 AND (?3 IS NULL OR cli_key = ?3)
 AND (?4 IS NULL OR final_provider_id = ?4)
-AND (?5 IS NULL OR {model_key_expr} = ?5)
-"#,
-        model_key_expr = SQL_MODEL_KEY_EXPR
+AND (?5 IS NULL OR model_key = ?5)
+"#
     );
 
     conn.query_row(
         &sql,
         params![start_ts, end_ts, cli_key, provider_id, model],
         |row| {
-            let requests_total: i64 = row.get("requests_total")?;
+            let requests_total: i64 = row.get::<_, Option<i64>>("requests_total")?.unwrap_or(0);
             let requests_success: i64 = row.get::<_, Option<i64>>("requests_success")?.unwrap_or(0);
             let requests_failed: i64 = row.get::<_, Option<i64>>("requests_failed")?.unwrap_or(0);
             let cost_covered_success: i64 = row
@@ -311,28 +327,27 @@ pub fn trend_v1(
         ),
     };
 
+    let source = usage_rollups::stats_source_sql(&conn, start_ts, end_ts)?;
     let sql = format!(
         r#"
 SELECT
   {select_fields},
-  COUNT(*) AS requests_success,
-  SUM(CASE WHEN cost_usd_femto IS NOT NULL THEN 1 ELSE 0 END) AS cost_covered_success,
-  SUM(COALESCE(cost_usd_femto, 0)) AS total_cost_usd_femto
-FROM request_logs
-WHERE excluded_from_stats = 0
-AND status >= 200 AND status < 300 AND error_code IS NULL
+  SUM(requests_success) AS requests_success,
+  SUM(success_cost_covered) AS cost_covered_success,
+  SUM(success_cost_usd_femto) AS total_cost_usd_femto
+FROM {source}
+WHERE requests_success > 0
 AND (?1 IS NULL OR created_at >= ?1)
 AND (?2 IS NULL OR created_at < ?2)
 AND (?3 IS NULL OR cli_key = ?3)
 AND (?4 IS NULL OR final_provider_id = ?4)
-AND (?5 IS NULL OR {model_key_expr} = ?5)
+AND (?5 IS NULL OR model_key = ?5)
 GROUP BY {group_by_fields}
 ORDER BY {order_by_fields}
 "#,
         select_fields = select_fields,
         group_by_fields = group_by_fields,
         order_by_fields = order_by_fields,
-        model_key_expr = SQL_MODEL_KEY_EXPR
     );
 
     let mut stmt = conn
@@ -393,29 +408,28 @@ pub fn breakdown_provider_v1(
     let model = model.as_deref();
     let limit = limit.clamp(1, 200) as i64;
 
+    let source = usage_rollups::stats_source_sql(&conn, start_ts, end_ts)?;
     let sql = format!(
         r#"
 SELECT
-  r.cli_key AS cli_key,
-  COALESCE(r.final_provider_id, 0) AS provider_id,
+  s.cli_key AS cli_key,
+  s.final_provider_id AS provider_id,
   COALESCE(p.name, 'Unknown') AS provider_name,
-  COUNT(*) AS requests_success,
-  SUM(CASE WHEN r.cost_usd_femto IS NOT NULL THEN 1 ELSE 0 END) AS cost_covered_success,
-  SUM(COALESCE(r.cost_usd_femto, 0)) AS total_cost_usd_femto
-FROM request_logs r
-LEFT JOIN providers p ON p.id = r.final_provider_id
-WHERE r.excluded_from_stats = 0
-AND r.status >= 200 AND r.status < 300 AND r.error_code IS NULL
-AND (?1 IS NULL OR r.created_at >= ?1)
-AND (?2 IS NULL OR r.created_at < ?2)
-AND (?3 IS NULL OR r.cli_key = ?3)
-AND (?4 IS NULL OR r.final_provider_id = ?4)
-AND (?5 IS NULL OR {model_key_expr} = ?5)
-GROUP BY r.cli_key, provider_id, provider_name
+  SUM(s.requests_success) AS requests_success,
+  SUM(s.success_cost_covered) AS cost_covered_success,
+  SUM(s.success_cost_usd_femto) AS total_cost_usd_femto
+FROM {source} s
+LEFT JOIN providers p ON p.id = s.final_provider_id
+WHERE s.requests_success > 0
+AND (?1 IS NULL OR s.created_at >= ?1)
+AND (?2 IS NULL OR s.created_at < ?2)
+AND (?3 IS NULL OR s.cli_key = ?3)
+AND (?4 IS NULL OR s.final_provider_id = ?4)
+AND (?5 IS NULL OR s.model_key = ?5)
+GROUP BY s.cli_key, provider_id, provider_name
 ORDER BY total_cost_usd_femto DESC, requests_success DESC, provider_name ASC
 LIMIT ?6
-"#,
-        model_key_expr = SQL_MODEL_KEY_EXPR
+"#
     );
 
     let mut stmt = conn
@@ -478,26 +492,25 @@ pub fn breakdown_model_v1(
     let model = model.as_deref();
     let limit = limit.clamp(1, 200) as i64;
 
+    let source = usage_rollups::stats_source_sql(&conn, start_ts, end_ts)?;
     let sql = format!(
         r#"
 SELECT
-  {model_key_expr} AS model_key,
-  COUNT(*) AS requests_success,
-  SUM(CASE WHEN cost_usd_femto IS NOT NULL THEN 1 ELSE 0 END) AS cost_covered_success,
-  SUM(COALESCE(cost_usd_femto, 0)) AS total_cost_usd_femto
-FROM request_logs
-WHERE excluded_from_stats = 0
-AND status >= 200 AND status < 300 AND error_code IS NULL
+  model_key,
+  SUM(requests_success) AS requests_success,
+  SUM(success_cost_covered) AS cost_covered_success,
+  SUM(success_cost_usd_femto) AS total_cost_usd_femto
+FROM {source}
+WHERE requests_success > 0
 AND (?1 IS NULL OR created_at >= ?1)
 AND (?2 IS NULL OR created_at < ?2)
 AND (?3 IS NULL OR cli_key = ?3)
 AND (?4 IS NULL OR final_provider_id = ?4)
-AND (?5 IS NULL OR {model_key_expr} = ?5)
+AND (?5 IS NULL OR model_key = ?5)
 GROUP BY model_key
 ORDER BY total_cost_usd_femto DESC, requests_success DESC, model_key ASC
 LIMIT ?6
-"#,
-        model_key_expr = SQL_MODEL_KEY_EXPR
+"#
     );
 
     let mut stmt = conn
@@ -556,30 +569,28 @@ pub fn scatter_cli_provider_model_v1(
     let model = model.as_deref();
     let limit = limit.clamp(1, 5000) as i64;
 
+    let source = usage_rollups::stats_source_sql(&conn, start_ts, end_ts)?;
     let sql = format!(
         r#"
 SELECT
-  r.cli_key AS cli_key,
+  s.cli_key AS cli_key,
   COALESCE(p.name, 'Unknown') AS provider_name,
-  {model_key_expr} AS model_key,
-  COUNT(*) AS requests_success,
-  SUM(r.cost_usd_femto) AS total_cost_usd_femto,
-  SUM(CASE WHEN r.duration_ms IS NULL OR r.duration_ms < 0 THEN 0 ELSE r.duration_ms END) AS total_duration_ms
-FROM request_logs r
-LEFT JOIN providers p ON p.id = r.final_provider_id
-WHERE r.excluded_from_stats = 0
-AND r.status >= 200 AND r.status < 300 AND r.error_code IS NULL
-AND r.cost_usd_femto IS NOT NULL
-AND (?1 IS NULL OR r.created_at >= ?1)
-AND (?2 IS NULL OR r.created_at < ?2)
-AND (?3 IS NULL OR r.cli_key = ?3)
-AND (?4 IS NULL OR r.final_provider_id = ?4)
-AND (?5 IS NULL OR {model_key_expr} = ?5)
-GROUP BY r.cli_key, provider_name, model_key
+  s.model_key AS model_key,
+  SUM(s.success_cost_covered) AS requests_success,
+  SUM(s.success_cost_usd_femto) AS total_cost_usd_femto,
+  SUM(s.success_costed_duration_ms_sum) AS total_duration_ms
+FROM {source} s
+LEFT JOIN providers p ON p.id = s.final_provider_id
+WHERE s.success_cost_covered > 0
+AND (?1 IS NULL OR s.created_at >= ?1)
+AND (?2 IS NULL OR s.created_at < ?2)
+AND (?3 IS NULL OR s.cli_key = ?3)
+AND (?4 IS NULL OR s.final_provider_id = ?4)
+AND (?5 IS NULL OR s.model_key = ?5)
+GROUP BY s.cli_key, provider_name, model_key
 ORDER BY total_cost_usd_femto DESC, total_duration_ms DESC, requests_success DESC, cli_key ASC, provider_name ASC, model_key ASC
 LIMIT ?6
-"#,
-        model_key_expr = SQL_MODEL_KEY_EXPR
+"#
     );
 
     let mut stmt = conn
@@ -722,6 +733,92 @@ LIMIT ?6
     Ok(out)
 }
 
+pub fn prefix_affinity_v1(
+    db: &db::Db,
+    period: &str,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    cli_key: Option<&str>,
+    provider_id: Option<i64>,
+    model: Option<&str>,
+) -> Result<CostPrefixAffinityV1, String> {
+    let conn = db.open_connection()?;
+
+    let period = parse_period_v1(period)?;
+    let (start_ts, end_ts, _) = compute_bounds_v1(&conn, period, start_ts, end_ts)?;
+    let cli_key = normalize_cli_filter(cli_key)?;
+    let provider_id = normalize_provider_id_filter(provider_id)?;
+    let model = normalize_model_filter(model);
+    let model = model.as_deref();
+
+    let sql = format!(
+        r#"
+SELECT
+  special_settings_json,
+  {effective_input_expr} AS effective_input_tokens,
+  COALESCE(cache_read_input_tokens, 0) AS cache_read_tokens,
+  COALESCE(cache_creation_input_tokens, 0) AS cache_creation_tokens
+FROM request_logs
+WHERE excluded_from_stats = 0
+AND status >= 200 AND status < 300 AND error_code IS NULL
+AND special_settings_json LIKE '%"prefix_affinity"%'
+AND (?1 IS NULL OR created_at >= ?1)
+AND (?2 IS NULL OR created_at < ?2)
+AND (?3 IS NULL OR cli_key = ?3)
+AND (?4 IS NULL OR final_provider_id = ?4)
+AND (?5 IS NULL OR {model_key_expr} = ?5)
+"#,
+        effective_input_expr = usage_stats::SQL_EFFECTIVE_INPUT_TOKENS_EXPR,
+        model_key_expr = SQL_MODEL_KEY_EXPR
+    );
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("DB_ERROR: failed to prepare prefix affinity query: {e}"))?;
+    let rows = stmt
+        .query_map(
+            params![start_ts, end_ts, cli_key, provider_id, model],
+            |row| {
+                Ok((
+                    row.get::<_, Option<String>>("special_settings_json")?,
+                    row.get::<_, i64>("effective_input_tokens")?,
+                    row.get::<_, i64>("cache_read_tokens")?,
+                    row.get::<_, i64>("cache_creation_tokens")?,
+                ))
+            },
+        )
+        .map_err(|e| format!("DB_ERROR: failed to run prefix affinity query: {e}"))?;
+
+    let mut out = CostPrefixAffinityV1::default();
+    for row in rows {
+        let (special_settings_json, effective_input, cache_read, cache_creation) =
+            row.map_err(|e| format!("DB_ERROR: failed to read prefix affinity row: {e}"))?;
+        let Some(raw) = special_settings_json else {
+            continue;
+        };
+        let settings: Vec<serde_json::Value> = serde_json::from_str(&raw).unwrap_or_default();
+        let Some(hit) = settings.iter().find_map(|v| {
+            (v.get("type").and_then(|t| t.as_str()) == Some("prefix_affinity"))
+                .then(|| v.get("hit").and_then(|h| h.as_bool()).unwrap_or(false))
+        }) else {
+            continue;
+        };
+
+        let cache_read = cache_read.max(0);
+        let prompt = effective_input.max(0) + cache_read + cache_creation.max(0);
+        out.requests_eligible += 1;
+        if hit {
+            out.requests_hit += 1;
+            out.hit_cache_read_tokens += cache_read;
+            out.hit_prompt_tokens += prompt;
+        } else {
+            out.miss_cache_read_tokens += cache_read;
+            out.miss_prompt_tokens += prompt;
+        }
+    }
+    Ok(out)
+}
+
 fn has_any_cost_usage(usage: &cost::CostUsage) -> bool {
     usage.input_tokens > 0
         || usage.output_tokens > 0
@@ -731,6 +828,80 @@ fn has_any_cost_usage(usage: &cost::CostUsage) -> bool {
         || usage.cache_creation_1h_input_tokens > 0
 }
 
+/// Spend per final provider over the current local day and month, for provider quotas.
+///
+/// Reads `request_logs` directly (including `excluded_from_stats` rows: test traffic still costs).
+pub fn provider_spend_v1(db: &db::Db, cli_key: &str) -> Result<ProviderSpendReportV1, String> {
+    let conn = db.open_connection()?;
+    let cli_key = normalize_cli_filter(Some(cli_key))?;
+
+    let (day_start, day_end, month_start, month_end) = conn
+        .query_row(
+            r#"
+SELECT
+  CAST(strftime('%s','now','localtime','start of day','utc') AS INTEGER),
+  CAST(strftime('%s','now','localtime','start of day','+1 day','utc') AS INTEGER),
+  CAST(strftime('%s','now','localtime','start of month','utc') AS INTEGER),
+  CAST(strftime('%s','now','localtime','start of month','+1 month','utc') AS INTEGER)
+"#,
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| format!("DB_ERROR: failed to compute quota period bounds: {e}"))?;
+
+    let rows = provider_spend_rows(&conn, cli_key, day_start, month_start)?;
+    Ok(ProviderSpendReportV1 {
+        daily_reset_at: day_end,
+        monthly_reset_at: month_end,
+        rows,
+    })
+}
+
+fn provider_spend_rows(
+    conn: &Connection,
+    cli_key: Option<&str>,
+    day_start: i64,
+    month_start: i64,
+) -> Result<Vec<ProviderSpendRowV1>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  final_provider_id AS provider_id,
+  SUM(CASE WHEN created_at >= ?2 THEN COALESCE(cost_usd_femto, 0) ELSE 0 END) AS daily_cost_usd_femto,
+  SUM(COALESCE(cost_usd_femto, 0)) AS monthly_cost_usd_femto
+FROM request_logs
+WHERE created_at >= ?3
+AND final_provider_id IS NOT NULL
+AND (?1 IS NULL OR cli_key = ?1)
+GROUP BY final_provider_id
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare provider spend query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![cli_key, day_start, month_start], |row| {
+            Ok(ProviderSpendRowV1 {
+                provider_id: row.get("provider_id")?,
+                daily_cost_usd: cost_usd_from_femto(
+                    row.get::<_, Option<i64>>("daily_cost_usd_femto")?
+                        .unwrap_or(0),
+                ),
+                monthly_cost_usd: cost_usd_from_femto(
+                    row.get::<_, Option<i64>>("monthly_cost_usd_femto")?
+                        .unwrap_or(0),
+                ),
+            })
+        })
+        .map_err(|e| format!("DB_ERROR: failed to run provider spend query: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| format!("DB_ERROR: failed to read provider spend row: {e}"))?);
+    }
+    Ok(out)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn backfill_missing_v1(
     db: &db::Db,
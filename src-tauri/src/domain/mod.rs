@@ -9,8 +9,15 @@ pub(crate) mod cost;
 pub(crate) mod cost_stats;
 pub(crate) mod mcp;
 pub(crate) mod prompts;
+pub(crate) mod provider_ab_test;
+pub(crate) mod provider_canary;
+pub(crate) mod provider_import;
+pub(crate) mod provider_snapshot;
 pub(crate) mod providers;
 pub(crate) mod skills;
+pub(crate) mod sort_mode_schedules;
 pub(crate) mod sort_modes;
+pub(crate) mod sse_transcript;
+pub(crate) mod tokenizer;
 pub(crate) mod usage;
 pub(crate) mod usage_stats;
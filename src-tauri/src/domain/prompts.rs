@@ -122,7 +122,7 @@ fn read_prompt_file_utf8(app: &tauri::AppHandle, cli_key: &str) -> Result<Option
     };
 
     String::from_utf8(bytes)
-        .map(Some)
+        .map(|text| Some(prompt_sync::target_prompt_content(cli_key, text)))
         .map_err(|_| format!("PROMPT_SYNC_INVALID_UTF8: cli_key={cli_key}"))
 }
 
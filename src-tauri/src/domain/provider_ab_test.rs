@@ -0,0 +1,548 @@
+//! Usage: A/B comparison between two providers (sessions alternate between arm A / arm B until
+//! `target_sessions` sessions are assigned, then a side-by-side report is produced).
+//!
+//! - Assignment is per session: every request of a session prefers the provider of its arm.
+//! - Report metrics come from `request_logs` joined by `session_id` (intent-to-treat: a request
+//!   counts for its arm even if failover served it from another provider).
+//! - The running test is part of the gateway snapshot (`provider_snapshot`); assigned arms are
+//!   cached in memory, so only a session's first request writes to SQLite.
+
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use crate::{db, provider_snapshot};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+const STATUS_RUNNING: &str = "running";
+const STATUS_COMPLETED: &str = "completed";
+const STATUS_CANCELLED: &str = "cancelled";
+
+const ARM_A: &str = "a";
+const ARM_B: &str = "b";
+
+const MAX_TARGET_SESSIONS: i64 = 10_000;
+const LIST_LIMIT: i64 = 50;
+const USD_FEMTO_DENOM: f64 = 1_000_000_000_000_000.0;
+
+// test id -> session id -> arm. At most `target_sessions` entries per running test; a test's
+// entries are dropped once it stops.
+static SESSION_ARMS: Mutex<Option<HashMap<i64, HashMap<String, &'static str>>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderAbTest {
+    pub id: i64,
+    pub cli_key: String,
+    pub provider_a_id: i64,
+    pub provider_b_id: i64,
+    pub target_sessions: i64,
+    pub status: String,
+    pub started_at: i64,
+    pub completed_at: Option<i64>,
+}
+
+impl ProviderAbTest {
+    pub(crate) fn arm_provider_ids(&self, arm: &str) -> (i64, i64) {
+        if arm == ARM_A {
+            (self.provider_a_id, self.provider_b_id)
+        } else {
+            (self.provider_b_id, self.provider_a_id)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AbArmReport {
+    pub provider_id: i64,
+    pub sessions: i64,
+    pub requests: i64,
+    pub failures: i64,
+    pub error_rate: Option<f64>,
+    pub avg_duration_ms: Option<f64>,
+    pub avg_ttfb_ms: Option<f64>,
+    pub total_cost_usd: f64,
+    pub avg_cost_usd_per_session: Option<f64>,
+    // Extra attempts beyond the first one (retries + failovers).
+    pub retries: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderAbTestReport {
+    pub test: ProviderAbTest,
+    pub arm_a: AbArmReport,
+    pub arm_b: AbArmReport,
+}
+
+fn row_to_test(row: &rusqlite::Row<'_>) -> Result<ProviderAbTest, rusqlite::Error> {
+    Ok(ProviderAbTest {
+        id: row.get("id")?,
+        cli_key: row.get("cli_key")?,
+        provider_a_id: row.get("provider_a_id")?,
+        provider_b_id: row.get("provider_b_id")?,
+        target_sessions: row.get("target_sessions")?,
+        status: row.get("status")?,
+        started_at: row.get("started_at")?,
+        completed_at: row.get("completed_at")?,
+    })
+}
+
+const SELECT_COLUMNS: &str = r#"
+SELECT
+  id,
+  cli_key,
+  provider_a_id,
+  provider_b_id,
+  target_sessions,
+  status,
+  started_at,
+  completed_at
+FROM provider_ab_tests
+"#;
+
+fn get_by_id(conn: &Connection, test_id: i64) -> Result<ProviderAbTest, String> {
+    conn.query_row(
+        &format!("{SELECT_COLUMNS} WHERE id = ?1"),
+        params![test_id],
+        row_to_test,
+    )
+    .optional()
+    .map_err(|e| format!("DB_ERROR: failed to query provider ab test: {e}"))?
+    .ok_or_else(|| "DB_NOT_FOUND: provider ab test not found".to_string())
+}
+
+/// Running test of `cli_key`; loaded into the gateway snapshot, in the snapshot's read transaction.
+pub(crate) fn running_test(
+    conn: &Connection,
+    cli_key: &str,
+) -> Result<Option<ProviderAbTest>, String> {
+    conn.query_row(
+        &format!("{SELECT_COLUMNS} WHERE cli_key = ?1 AND status = ?2"),
+        params![cli_key, STATUS_RUNNING],
+        row_to_test,
+    )
+    .optional()
+    .map_err(|e| format!("DB_ERROR: failed to query running ab test: {e}"))
+}
+
+fn assigned_sessions(conn: &Connection, test_id: i64) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM provider_ab_test_sessions WHERE test_id = ?1",
+        params![test_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("DB_ERROR: failed to count ab test sessions: {e}"))
+}
+
+fn session_arm(
+    conn: &Connection,
+    test_id: i64,
+    session_id: &str,
+) -> Result<Option<&'static str>, String> {
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT arm FROM provider_ab_test_sessions WHERE test_id = ?1 AND session_id = ?2",
+            params![test_id, session_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("DB_ERROR: failed to query ab test session: {e}"))?;
+    Ok(existing.map(|arm| if arm == ARM_A { ARM_A } else { ARM_B }))
+}
+
+/// Sessions alternate A, B, A, B... by assignment order.
+fn arm_for_index(index: i64) -> &'static str {
+    if index % 2 == 0 {
+        ARM_A
+    } else {
+        ARM_B
+    }
+}
+
+fn mark_completed(conn: &Connection, test_id: i64, now: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE provider_ab_tests SET status = ?1, completed_at = ?2 WHERE id = ?3 AND status = ?4",
+        params![STATUS_COMPLETED, now, test_id, STATUS_RUNNING],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to complete provider ab test: {e}"))?;
+    Ok(())
+}
+
+fn cached_arm(test_id: i64, session_id: &str) -> Option<&'static str> {
+    let guard = SESSION_ARMS.lock_or_recover();
+    guard
+        .as_ref()
+        .and_then(|tests| tests.get(&test_id))
+        .and_then(|arms| arms.get(session_id))
+        .copied()
+}
+
+fn cache_arm(test_id: i64, session_id: &str, arm: &'static str) {
+    let mut guard = SESSION_ARMS.lock_or_recover();
+    guard
+        .get_or_insert_with(HashMap::new)
+        .entry(test_id)
+        .or_default()
+        .insert(session_id.to_string(), arm);
+}
+
+/// A test stopped running: drop its cached arms and the snapshots that still carry it.
+fn stopped(test_id: i64) {
+    if let Some(tests) = SESSION_ARMS.lock_or_recover().as_mut() {
+        tests.remove(&test_id);
+    }
+    provider_snapshot::invalidate();
+}
+
+pub fn start(
+    db: &db::Db,
+    cli_key: &str,
+    provider_a_id: i64,
+    provider_b_id: i64,
+    target_sessions: i64,
+) -> Result<ProviderAbTest, String> {
+    let cli_key = cli_key.trim();
+    crate::shared::cli_key::validate_cli_key(cli_key)?;
+    if provider_a_id == provider_b_id {
+        return Err("SEC_INVALID_INPUT: provider_a_id and provider_b_id must differ".to_string());
+    }
+    if !(2..=MAX_TARGET_SESSIONS).contains(&target_sessions) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: target_sessions must be 2-{MAX_TARGET_SESSIONS}"
+        ));
+    }
+
+    let conn = db.open_connection()?;
+    for provider_id in [provider_a_id, provider_b_id] {
+        let exists: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM providers WHERE id = ?1 AND cli_key = ?2",
+                params![provider_id, cli_key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("DB_ERROR: failed to query provider: {e}"))?;
+        if exists.is_none() {
+            return Err(format!(
+                "SEC_INVALID_INPUT: provider_id={provider_id} does not belong to cli_key={cli_key}"
+            ));
+        }
+    }
+    if running_test(&conn, cli_key)?.is_some() {
+        return Err("SEC_INVALID_INPUT: an A/B test is already running for this cli".to_string());
+    }
+
+    conn.execute(
+        r#"
+INSERT INTO provider_ab_tests(
+  cli_key,
+  provider_a_id,
+  provider_b_id,
+  target_sessions,
+  status,
+  started_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+"#,
+        params![
+            cli_key,
+            provider_a_id,
+            provider_b_id,
+            target_sessions,
+            STATUS_RUNNING,
+            now_unix_seconds()
+        ],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to insert provider ab test: {e}"))?;
+    provider_snapshot::invalidate();
+
+    get_by_id(&conn, conn.last_insert_rowid())
+}
+
+pub fn cancel(db: &db::Db, test_id: i64) -> Result<ProviderAbTest, String> {
+    let conn = db.open_connection()?;
+    conn.execute(
+        "UPDATE provider_ab_tests SET status = ?1, completed_at = ?2 WHERE id = ?3 AND status = ?4",
+        params![
+            STATUS_CANCELLED,
+            now_unix_seconds(),
+            test_id,
+            STATUS_RUNNING
+        ],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to cancel provider ab test: {e}"))?;
+    stopped(test_id);
+    get_by_id(&conn, test_id)
+}
+
+pub fn list(db: &db::Db, cli_key: &str) -> Result<Vec<ProviderAbTest>, String> {
+    crate::shared::cli_key::validate_cli_key(cli_key)?;
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{SELECT_COLUMNS} WHERE cli_key = ?1 ORDER BY id DESC LIMIT ?2"
+        ))
+        .map_err(|e| format!("DB_ERROR: failed to prepare provider ab test query: {e}"))?;
+    let rows = stmt
+        .query_map(params![cli_key, LIST_LIMIT], row_to_test)
+        .map_err(|e| format!("DB_ERROR: failed to list provider ab tests: {e}"))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("DB_ERROR: failed to read provider ab test row: {e}"))
+}
+
+fn arm_report(
+    conn: &Connection,
+    test: &ProviderAbTest,
+    arm: &str,
+    provider_id: i64,
+) -> Result<AbArmReport, String> {
+    let sessions: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM provider_ab_test_sessions WHERE test_id = ?1 AND arm = ?2",
+            params![test.id, arm],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("DB_ERROR: failed to count ab test sessions: {e}"))?;
+
+    let (requests, failures, duration_sum, ttfb_sum, ttfb_count, cost_femto): (
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+    ) = conn
+        .query_row(
+            r#"
+SELECT
+  COUNT(*),
+  COALESCE(SUM(CASE WHEN r.status >= 200 AND r.status < 300 AND r.error_code IS NULL THEN 0 ELSE 1 END), 0),
+  COALESCE(SUM(r.duration_ms), 0),
+  COALESCE(SUM(CASE WHEN r.ttfb_ms IS NOT NULL THEN r.ttfb_ms ELSE 0 END), 0),
+  COALESCE(SUM(CASE WHEN r.ttfb_ms IS NOT NULL THEN 1 ELSE 0 END), 0),
+  COALESCE(SUM(COALESCE(r.cost_usd_femto, 0)), 0)
+FROM request_logs r
+JOIN provider_ab_test_sessions s ON s.session_id = r.session_id
+WHERE s.test_id = ?1
+  AND s.arm = ?2
+  AND r.cli_key = ?3
+  AND r.created_at >= s.assigned_at
+"#,
+            params![test.id, arm, test.cli_key],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            },
+        )
+        .map_err(|e| format!("DB_ERROR: failed to aggregate ab test requests: {e}"))?;
+
+    let retries: i64 = conn
+        .query_row(
+            r#"
+SELECT COALESCE(SUM(CASE WHEN a.attempt_index > 0 THEN 1 ELSE 0 END), 0)
+FROM request_attempt_logs a
+JOIN request_logs r ON r.trace_id = a.trace_id
+JOIN provider_ab_test_sessions s ON s.session_id = r.session_id
+WHERE s.test_id = ?1
+  AND s.arm = ?2
+  AND r.cli_key = ?3
+  AND r.created_at >= s.assigned_at
+"#,
+            params![test.id, arm, test.cli_key],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("DB_ERROR: failed to aggregate ab test retries: {e}"))?;
+
+    Ok(build_arm_report(
+        provider_id,
+        sessions,
+        requests,
+        failures,
+        duration_sum,
+        (ttfb_sum, ttfb_count),
+        cost_femto,
+        retries,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_arm_report(
+    provider_id: i64,
+    sessions: i64,
+    requests: i64,
+    failures: i64,
+    duration_sum: i64,
+    (ttfb_sum, ttfb_count): (i64, i64),
+    cost_femto: i64,
+    retries: i64,
+) -> AbArmReport {
+    let ratio = |num: f64, den: i64| (den > 0).then(|| num / den as f64);
+    let total_cost_usd = cost_femto as f64 / USD_FEMTO_DENOM;
+    AbArmReport {
+        provider_id,
+        sessions,
+        requests,
+        failures,
+        error_rate: ratio(failures as f64, requests),
+        avg_duration_ms: ratio(duration_sum as f64, requests),
+        avg_ttfb_ms: ratio(ttfb_sum as f64, ttfb_count),
+        total_cost_usd,
+        avg_cost_usd_per_session: ratio(total_cost_usd, sessions),
+        retries,
+    }
+}
+
+pub fn report(db: &db::Db, test_id: i64) -> Result<ProviderAbTestReport, String> {
+    let conn = db.open_connection()?;
+    let test = get_by_id(&conn, test_id)?;
+    let arm_a = arm_report(&conn, &test, ARM_A, test.provider_a_id)?;
+    let arm_b = arm_report(&conn, &test, ARM_B, test.provider_b_id)?;
+    Ok(ProviderAbTestReport { test, arm_a, arm_b })
+}
+
+/// Read-only variant of [`assign_session`]: the arm `session_id` has in the snapshot's running
+/// `test`, or would get if it sent a request now (nothing is assigned).
+pub(crate) fn peek_session_arm(
+    db: &db::Db,
+    test: &ProviderAbTest,
+    session_id: &str,
+) -> Result<Option<&'static str>, String> {
+    if let Some(arm) = cached_arm(test.id, session_id) {
+        return Ok(Some(arm));
+    }
+    let conn = db.open_connection()?;
+    if let Some(arm) = session_arm(&conn, test.id, session_id)? {
+        return Ok(Some(arm));
+    }
+    let assigned = assigned_sessions(&conn, test.id)?;
+    if assigned >= test.target_sessions {
+        return Ok(None);
+    }
+    Ok(Some(arm_for_index(assigned)))
+}
+
+/// Arm of `session_id` in the snapshot's running `test` (assigning a new session when the test
+/// still needs samples).
+pub(crate) fn assign_session(
+    db: &db::Db,
+    test: &ProviderAbTest,
+    session_id: &str,
+) -> Result<Option<&'static str>, String> {
+    if let Some(arm) = cached_arm(test.id, session_id) {
+        return Ok(Some(arm));
+    }
+
+    let mut conn = db.open_connection()?;
+    // IMMEDIATE: concurrent first requests of different sessions serialize on the write lock, so
+    // the session count the arm alternates on cannot be read by two of them at once.
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
+
+    // The snapshot may predate a cancel / completion committed since.
+    let status: Option<String> = tx
+        .query_row(
+            "SELECT status FROM provider_ab_tests WHERE id = ?1",
+            params![test.id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("DB_ERROR: failed to query provider ab test: {e}"))?;
+    if status.as_deref() != Some(STATUS_RUNNING) {
+        return Ok(None);
+    }
+
+    // Same alternation as `arm_for_index`; a no-op for an assigned session or a full test.
+    let now = now_unix_seconds();
+    tx.execute(
+        r#"
+INSERT INTO provider_ab_test_sessions(test_id, session_id, arm, assigned_at)
+SELECT ?1, ?2, CASE WHEN COUNT(*) % 2 = 0 THEN ?3 ELSE ?4 END, ?5
+FROM provider_ab_test_sessions
+WHERE test_id = ?1
+HAVING COUNT(*) < ?6
+ON CONFLICT(test_id, session_id) DO NOTHING
+"#,
+        params![test.id, session_id, ARM_A, ARM_B, now, test.target_sessions],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to assign ab test session: {e}"))?;
+    let arm = session_arm(&tx, test.id, session_id)?;
+
+    let completed = assigned_sessions(&tx, test.id)? >= test.target_sessions;
+    if completed {
+        // 样本数已满：停止分配新会话，已分配会话仍按会话绑定顺序继续
+        mark_completed(&tx, test.id, now)?;
+    }
+    tx.commit()
+        .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+
+    if completed {
+        stopped(test.id);
+        tracing::info!(
+            test_id = test.id,
+            sessions = test.target_sessions,
+            "Provider A/B 对比已完成"
+        );
+    } else if let Some(arm) = arm {
+        cache_arm(test.id, session_id, arm);
+    }
+    Ok(arm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assign_session_alternates_and_completes_at_target() {
+        let db = db::open_in_memory_for_tests();
+        let test = {
+            let conn = db.open_connection().unwrap();
+            conn.execute(
+                "INSERT INTO provider_ab_tests(cli_key, provider_a_id, provider_b_id, target_sessions, status, started_at) VALUES ('claude', 1, 2, 3, ?1, 0)",
+                params![STATUS_RUNNING],
+            )
+            .unwrap();
+            get_by_id(&conn, conn.last_insert_rowid()).unwrap()
+        };
+
+        assert_eq!(assign_session(&db, &test, "s1").unwrap(), Some(ARM_A));
+        assert_eq!(assign_session(&db, &test, "s1").unwrap(), Some(ARM_A));
+        assert_eq!(assign_session(&db, &test, "s2").unwrap(), Some(ARM_B));
+        // the third session fills the test
+        assert_eq!(assign_session(&db, &test, "s3").unwrap(), Some(ARM_A));
+
+        let conn = db.open_connection().unwrap();
+        assert_eq!(assigned_sessions(&conn, test.id).unwrap(), 3);
+        assert_eq!(get_by_id(&conn, test.id).unwrap().status, STATUS_COMPLETED);
+        drop(conn);
+        // a stale snapshot still carrying the test assigns nothing more
+        assert_eq!(assign_session(&db, &test, "s4").unwrap(), None);
+        assert_eq!(assign_session(&db, &test, "s1").unwrap(), None);
+    }
+
+    #[test]
+    fn sessions_alternate_between_arms() {
+        let arms: Vec<&str> = (0..4).map(arm_for_index).collect();
+        assert_eq!(arms, vec![ARM_A, ARM_B, ARM_A, ARM_B]);
+    }
+
+    #[test]
+    fn arm_report_derives_rates_and_averages() {
+        let report = build_arm_report(7, 4, 10, 2, 5000, (1200, 6), 2_000_000_000_000_000, 3);
+        assert_eq!(report.error_rate, Some(0.2));
+        assert_eq!(report.avg_duration_ms, Some(500.0));
+        assert_eq!(report.avg_ttfb_ms, Some(200.0));
+        assert_eq!(report.total_cost_usd, 2.0);
+        assert_eq!(report.avg_cost_usd_per_session, Some(0.5));
+
+        let empty = build_arm_report(7, 0, 0, 0, 0, (0, 0), 0, 0);
+        assert_eq!(empty.error_rate, None);
+        assert_eq!(empty.avg_cost_usd_per_session, None);
+    }
+}
@@ -0,0 +1,515 @@
+//! Usage: Canary rollout of new providers (traffic percentage within a sort mode + automatic
+//! promotion / demotion against the incumbent when the trial period ends).
+//!
+//! - `mode_id = 0` stands for the default order (no sort mode).
+//! - Error rates come from `request_attempt_logs` within the trial window.
+//! - The running canary of an order is part of the gateway snapshot (`provider_snapshot`); every
+//!   status change invalidates it.
+
+use crate::shared::time::now_unix_seconds;
+use crate::{db, provider_snapshot, providers, sort_modes};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+const STATUS_RUNNING: &str = "running";
+const STATUS_PROMOTED: &str = "promoted";
+const STATUS_DEMOTED: &str = "demoted";
+const STATUS_INCONCLUSIVE: &str = "inconclusive";
+const STATUS_CANCELLED: &str = "cancelled";
+
+const MAX_TRAFFIC_PERCENT: u32 = 50;
+const MAX_DURATION_HOURS: u32 = 14 * 24;
+const DEFAULT_MAX_ERROR_RATE_DELTA: f64 = 0.05;
+const DEFAULT_MIN_SAMPLES: i64 = 20;
+const LIST_LIMIT: i64 = 50;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderCanary {
+    pub id: i64,
+    pub cli_key: String,
+    pub mode_id: Option<i64>,
+    pub provider_id: i64,
+    pub incumbent_provider_id: Option<i64>,
+    pub traffic_percent: u32,
+    pub max_error_rate_delta: f64,
+    pub min_samples: i64,
+    pub status: String,
+    pub started_at: i64,
+    pub ends_at: i64,
+    pub decided_at: Option<i64>,
+    pub decision_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CanaryArmStats {
+    pub requests: i64,
+    pub failures: i64,
+}
+
+impl CanaryArmStats {
+    fn error_rate(self) -> Option<f64> {
+        (self.requests > 0).then(|| self.failures as f64 / self.requests as f64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderCanaryView {
+    pub canary: ProviderCanary,
+    pub canary_stats: CanaryArmStats,
+    pub incumbent_stats: Option<CanaryArmStats>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StartCanaryInput {
+    pub cli_key: String,
+    pub mode_id: Option<i64>,
+    pub provider_id: i64,
+    pub traffic_percent: u32,
+    pub duration_hours: u32,
+    pub max_error_rate_delta: Option<f64>,
+    pub min_samples: Option<i64>,
+}
+
+fn row_to_canary(row: &rusqlite::Row<'_>) -> Result<ProviderCanary, rusqlite::Error> {
+    let mode_id: i64 = row.get("mode_id")?;
+    Ok(ProviderCanary {
+        id: row.get("id")?,
+        cli_key: row.get("cli_key")?,
+        mode_id: (mode_id > 0).then_some(mode_id),
+        provider_id: row.get("provider_id")?,
+        incumbent_provider_id: row.get("incumbent_provider_id")?,
+        traffic_percent: row.get::<_, i64>("traffic_percent")?.clamp(0, 100) as u32,
+        max_error_rate_delta: row.get("max_error_rate_delta")?,
+        min_samples: row.get("min_samples")?,
+        status: row.get("status")?,
+        started_at: row.get("started_at")?,
+        ends_at: row.get("ends_at")?,
+        decided_at: row.get("decided_at")?,
+        decision_reason: row.get("decision_reason")?,
+    })
+}
+
+const SELECT_COLUMNS: &str = r#"
+SELECT
+  id,
+  cli_key,
+  mode_id,
+  provider_id,
+  incumbent_provider_id,
+  traffic_percent,
+  max_error_rate_delta,
+  min_samples,
+  status,
+  started_at,
+  ends_at,
+  decided_at,
+  decision_reason
+FROM provider_canaries
+"#;
+
+fn get_by_id(conn: &Connection, canary_id: i64) -> Result<ProviderCanary, String> {
+    conn.query_row(
+        &format!("{SELECT_COLUMNS} WHERE id = ?1"),
+        params![canary_id],
+        row_to_canary,
+    )
+    .optional()
+    .map_err(|e| format!("DB_ERROR: failed to query provider canary: {e}"))?
+    .ok_or_else(|| "DB_NOT_FOUND: provider canary not found".to_string())
+}
+
+/// Current provider order of the sort mode (or the default order when `mode_id` is None).
+fn current_order(db: &db::Db, cli_key: &str, mode_id: Option<i64>) -> Result<Vec<i64>, String> {
+    match mode_id {
+        Some(mode_id) => sort_modes::list_mode_providers(db, mode_id, cli_key),
+        None => Ok(providers::list_by_cli(db, cli_key)?
+            .into_iter()
+            .map(|p| p.id)
+            .collect()),
+    }
+}
+
+fn write_order(
+    db: &db::Db,
+    cli_key: &str,
+    mode_id: Option<i64>,
+    order: Vec<i64>,
+) -> Result<(), String> {
+    match mode_id {
+        Some(mode_id) => {
+            sort_modes::set_mode_providers_order(db, mode_id, cli_key, order).map(|_| ())
+        }
+        None => providers::reorder(db, cli_key, order).map(|_| ()),
+    }
+}
+
+fn arm_stats(
+    conn: &Connection,
+    cli_key: &str,
+    provider_id: i64,
+    since: i64,
+    until: i64,
+) -> Result<CanaryArmStats, String> {
+    conn.query_row(
+        r#"
+SELECT
+  COUNT(*),
+  COALESCE(SUM(CASE WHEN outcome = 'success' THEN 0 ELSE 1 END), 0)
+FROM request_attempt_logs
+WHERE cli_key = ?1
+  AND provider_id = ?2
+  AND created_at >= ?3
+  AND created_at < ?4
+  AND outcome != 'started'
+"#,
+        params![cli_key, provider_id, since, until],
+        |row| {
+            Ok(CanaryArmStats {
+                requests: row.get(0)?,
+                failures: row.get(1)?,
+            })
+        },
+    )
+    .map_err(|e| format!("DB_ERROR: failed to aggregate canary attempts: {e}"))
+}
+
+/// Decides the outcome of a finished trial: (status, reason).
+fn decide(
+    canary: CanaryArmStats,
+    incumbent: Option<CanaryArmStats>,
+    max_error_rate_delta: f64,
+    min_samples: i64,
+) -> (&'static str, String) {
+    let Some(canary_rate) = canary
+        .error_rate()
+        .filter(|_| canary.requests >= min_samples)
+    else {
+        return (
+            STATUS_INCONCLUSIVE,
+            format!(
+                "canary samples {} < min_samples {min_samples}",
+                canary.requests
+            ),
+        );
+    };
+    let incumbent_rate = incumbent
+        .and_then(CanaryArmStats::error_rate)
+        .unwrap_or(0.0);
+    if canary_rate <= incumbent_rate + max_error_rate_delta {
+        (
+            STATUS_PROMOTED,
+            format!("error rate {canary_rate:.3} vs incumbent {incumbent_rate:.3}"),
+        )
+    } else {
+        (
+            STATUS_DEMOTED,
+            format!(
+                "error rate {canary_rate:.3} exceeds incumbent {incumbent_rate:.3} + {max_error_rate_delta:.3}"
+            ),
+        )
+    }
+}
+
+/// Promotion places the canary right before the incumbent; demotion moves it to the end.
+fn reordered(order: &[i64], canary_id: i64, incumbent_id: Option<i64>, promote: bool) -> Vec<i64> {
+    if !order.contains(&canary_id) {
+        return order.to_vec();
+    }
+    let mut next: Vec<i64> = order
+        .iter()
+        .copied()
+        .filter(|id| *id != canary_id)
+        .collect();
+    let insert_at = if promote {
+        incumbent_id
+            .and_then(|id| next.iter().position(|v| *v == id))
+            .unwrap_or(0)
+    } else {
+        next.len()
+    };
+    next.insert(insert_at, canary_id);
+    next
+}
+
+fn finish(db: &db::Db, canary: &ProviderCanary, now: i64) -> Result<ProviderCanary, String> {
+    let conn = db.open_connection()?;
+    let canary_stats = arm_stats(
+        &conn,
+        &canary.cli_key,
+        canary.provider_id,
+        canary.started_at,
+        canary.ends_at,
+    )?;
+    let incumbent_stats = canary
+        .incumbent_provider_id
+        .map(|id| {
+            arm_stats(
+                &conn,
+                &canary.cli_key,
+                id,
+                canary.started_at,
+                canary.ends_at,
+            )
+        })
+        .transpose()?;
+    let (status, reason) = decide(
+        canary_stats,
+        incumbent_stats,
+        canary.max_error_rate_delta,
+        canary.min_samples,
+    );
+
+    // 先抢占状态，避免并发请求重复调整顺序
+    let claimed = conn
+        .execute(
+            "UPDATE provider_canaries SET status = ?1, decided_at = ?2, decision_reason = ?3 WHERE id = ?4 AND status = ?5",
+            params![status, now, reason, canary.id, STATUS_RUNNING],
+        )
+        .map_err(|e| format!("DB_ERROR: failed to update provider canary: {e}"))?;
+    drop(conn);
+    if claimed > 0 {
+        provider_snapshot::invalidate();
+    }
+
+    if claimed > 0 && status != STATUS_INCONCLUSIVE {
+        let order = current_order(db, &canary.cli_key, canary.mode_id)?;
+        let next = reordered(
+            &order,
+            canary.provider_id,
+            canary.incumbent_provider_id,
+            status == STATUS_PROMOTED,
+        );
+        if next != order {
+            write_order(db, &canary.cli_key, canary.mode_id, next)?;
+        }
+        tracing::info!(
+            canary_id = canary.id,
+            provider_id = canary.provider_id,
+            status,
+            reason = %reason,
+            "Provider 灰度已结束"
+        );
+    }
+
+    let conn = db.open_connection()?;
+    get_by_id(&conn, canary.id)
+}
+
+pub fn start(db: &db::Db, input: StartCanaryInput) -> Result<ProviderCanary, String> {
+    let cli_key = input.cli_key.trim().to_string();
+    crate::shared::cli_key::validate_cli_key(&cli_key)?;
+    if input.traffic_percent == 0 || input.traffic_percent > MAX_TRAFFIC_PERCENT {
+        return Err(format!(
+            "SEC_INVALID_INPUT: traffic_percent must be 1-{MAX_TRAFFIC_PERCENT}"
+        ));
+    }
+    if input.duration_hours == 0 || input.duration_hours > MAX_DURATION_HOURS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: duration_hours must be 1-{MAX_DURATION_HOURS}"
+        ));
+    }
+    let max_error_rate_delta = input
+        .max_error_rate_delta
+        .unwrap_or(DEFAULT_MAX_ERROR_RATE_DELTA);
+    if !(0.0..=1.0).contains(&max_error_rate_delta) {
+        return Err("SEC_INVALID_INPUT: max_error_rate_delta must be 0-1".to_string());
+    }
+    let min_samples = input.min_samples.unwrap_or(DEFAULT_MIN_SAMPLES);
+    if !(1..=100_000).contains(&min_samples) {
+        return Err("SEC_INVALID_INPUT: min_samples must be 1-100000".to_string());
+    }
+    let mode_id = input.mode_id.filter(|id| *id > 0);
+
+    let order = current_order(db, &cli_key, mode_id)?;
+    if !order.contains(&input.provider_id) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: provider_id={} is not in the selected order",
+            input.provider_id
+        ));
+    }
+    let incumbent_provider_id = order.iter().copied().find(|id| *id != input.provider_id);
+
+    let conn = db.open_connection()?;
+    let running: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM provider_canaries WHERE cli_key = ?1 AND mode_id = ?2 AND status = ?3",
+            params![cli_key, mode_id.unwrap_or(0), STATUS_RUNNING],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("DB_ERROR: failed to query running canary: {e}"))?;
+    if running.is_some() {
+        return Err("SEC_INVALID_INPUT: a canary is already running for this order".to_string());
+    }
+
+    let now = now_unix_seconds();
+    let ends_at = now.saturating_add(input.duration_hours as i64 * 3600);
+    conn.execute(
+        r#"
+INSERT INTO provider_canaries(
+  cli_key,
+  mode_id,
+  provider_id,
+  incumbent_provider_id,
+  traffic_percent,
+  max_error_rate_delta,
+  min_samples,
+  status,
+  started_at,
+  ends_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+"#,
+        params![
+            cli_key,
+            mode_id.unwrap_or(0),
+            input.provider_id,
+            incumbent_provider_id,
+            input.traffic_percent as i64,
+            max_error_rate_delta,
+            min_samples,
+            STATUS_RUNNING,
+            now,
+            ends_at
+        ],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to insert provider canary: {e}"))?;
+    provider_snapshot::invalidate();
+
+    get_by_id(&conn, conn.last_insert_rowid())
+}
+
+pub fn cancel(db: &db::Db, canary_id: i64) -> Result<ProviderCanary, String> {
+    let conn = db.open_connection()?;
+    conn.execute(
+        "UPDATE provider_canaries SET status = ?1, decided_at = ?2, decision_reason = ?3 WHERE id = ?4 AND status = ?5",
+        params![
+            STATUS_CANCELLED,
+            now_unix_seconds(),
+            "cancelled by user",
+            canary_id,
+            STATUS_RUNNING
+        ],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to cancel provider canary: {e}"))?;
+    provider_snapshot::invalidate();
+    get_by_id(&conn, canary_id)
+}
+
+pub fn list(db: &db::Db, cli_key: &str) -> Result<Vec<ProviderCanaryView>, String> {
+    crate::shared::cli_key::validate_cli_key(cli_key)?;
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{SELECT_COLUMNS} WHERE cli_key = ?1 ORDER BY id DESC LIMIT ?2"
+        ))
+        .map_err(|e| format!("DB_ERROR: failed to prepare provider canary query: {e}"))?;
+    let rows = stmt
+        .query_map(params![cli_key, LIST_LIMIT], row_to_canary)
+        .map_err(|e| format!("DB_ERROR: failed to list provider canaries: {e}"))?;
+
+    let now = now_unix_seconds();
+    let mut items = Vec::new();
+    for row in rows {
+        let canary =
+            row.map_err(|e| format!("DB_ERROR: failed to read provider canary row: {e}"))?;
+        let until = canary.decided_at.unwrap_or(now).min(canary.ends_at);
+        let canary_stats = arm_stats(
+            &conn,
+            &canary.cli_key,
+            canary.provider_id,
+            canary.started_at,
+            until,
+        )?;
+        let incumbent_stats = canary
+            .incumbent_provider_id
+            .map(|id| arm_stats(&conn, &canary.cli_key, id, canary.started_at, until))
+            .transpose()?;
+        items.push(ProviderCanaryView {
+            canary,
+            canary_stats,
+            incumbent_stats,
+        });
+    }
+    Ok(items)
+}
+
+/// Stable bucketing (FNV-1a of `key` mod 100): the same session keeps hitting the same arm.
+pub(crate) fn in_canary_bucket(traffic_percent: u32, key: &str) -> bool {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in key.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    (hash % 100) < traffic_percent as u64
+}
+
+/// Running canary of an order; loaded into the gateway snapshot, in the snapshot's read transaction.
+pub(crate) fn running_in(
+    conn: &Connection,
+    cli_key: &str,
+    mode_id: Option<i64>,
+) -> Result<Option<ProviderCanary>, String> {
+    conn.query_row(
+        &format!("{SELECT_COLUMNS} WHERE cli_key = ?1 AND mode_id = ?2 AND status = ?3"),
+        params![cli_key, mode_id.unwrap_or(0), STATUS_RUNNING],
+        row_to_canary,
+    )
+    .optional()
+    .map_err(|e| format!("DB_ERROR: failed to query running canary: {e}"))
+}
+
+/// Whether a snapshot's running canary still applies to a request; finishes it first (and so
+/// invalidates the snapshot) when the trial has ended.
+pub(crate) fn still_running(db: &db::Db, canary: &ProviderCanary) -> Result<bool, String> {
+    let now = now_unix_seconds();
+    if now < canary.ends_at {
+        return Ok(true);
+    }
+    finish(db, canary, now)?;
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(requests: i64, failures: i64) -> CanaryArmStats {
+        CanaryArmStats { requests, failures }
+    }
+
+    #[test]
+    fn decide_compares_against_incumbent_with_tolerance() {
+        let (status, _) = decide(stats(100, 6), Some(stats(1000, 20)), 0.05, 20);
+        assert_eq!(status, STATUS_PROMOTED);
+        let (status, _) = decide(stats(100, 10), Some(stats(1000, 20)), 0.05, 20);
+        assert_eq!(status, STATUS_DEMOTED);
+        let (status, _) = decide(stats(5, 0), Some(stats(1000, 20)), 0.05, 20);
+        assert_eq!(status, STATUS_INCONCLUSIVE);
+        let (status, _) = decide(stats(30, 1), None, 0.05, 20);
+        assert_eq!(status, STATUS_PROMOTED);
+    }
+
+    #[test]
+    fn canary_bucket_is_stable_and_roughly_proportional() {
+        assert_eq!(
+            in_canary_bucket(10, "session-a"),
+            in_canary_bucket(10, "session-a")
+        );
+        assert!(!in_canary_bucket(0, "session-a"));
+        assert!(in_canary_bucket(100, "session-a"));
+        let hits = (0..1000)
+            .filter(|i| in_canary_bucket(10, &format!("trace-{i}")))
+            .count();
+        assert!((50..=150).contains(&hits), "hits={hits}");
+    }
+
+    #[test]
+    fn reordered_promotes_before_incumbent_and_demotes_to_end() {
+        assert_eq!(reordered(&[1, 2, 3, 9], 9, Some(1), true), vec![9, 1, 2, 3]);
+        assert_eq!(
+            reordered(&[1, 9, 2, 3], 9, Some(1), false),
+            vec![1, 2, 3, 9]
+        );
+        assert_eq!(reordered(&[1, 2], 9, Some(1), true), vec![1, 2]);
+    }
+}
@@ -0,0 +1,439 @@
+//! Usage: Parse pasted relay-site config snippets into a provider form draft.
+//!
+//! 用法：
+//! - `.env` / shell 片段：`ANTHROPIC_BASE_URL=...`、`export OPENAI_API_KEY="..."`、`set` / `setx` / `$env:`
+//! - Claude `settings.json`（或 cc-switch 导出）：读取 `env` 对象
+//! - one-api / new-api 渠道导出：`{ "type": 14, "base_url": "...", "key": "..." }`，数组只取第一个
+//! - 兜底：从自由文本中提取第一个 http(s) 地址与 `sk-` 开头的密钥
+//! - 只生成草稿（不落库），由前端预填供应商表单后再确认保存
+
+use crate::providers::ClaudeModels;
+use serde::Serialize;
+
+const MAX_INPUT_LEN: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderImportFormat {
+    Env,
+    ClaudeSettings,
+    OneApiChannel,
+    Json,
+    Text,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderImportDraft {
+    pub format: ProviderImportFormat,
+    // None = could not tell; the form keeps the current CLI.
+    pub cli_key: Option<String>,
+    pub name: Option<String>,
+    pub base_urls: Vec<String>,
+    pub api_key: Option<String>,
+    pub claude_models: ClaudeModels,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Collected {
+    name: Option<String>,
+    base_url: Option<(String, Option<&'static str>)>,
+    api_key: Option<(String, Option<&'static str>)>,
+    claude_models: ClaudeModels,
+}
+
+/// `baseUrl` / `base-url` / `BASE_URL` -> `BASE_URL`.
+fn normalize_key(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len() + 4);
+    let mut prev_lower = false;
+    for ch in raw.trim().trim_matches(|c| c == '"' || c == '\'').chars() {
+        if ch.is_ascii_uppercase() && prev_lower {
+            out.push('_');
+        }
+        prev_lower = ch.is_ascii_lowercase() || ch.is_ascii_digit();
+        out.push(if matches!(ch, '-' | '.' | ' ') {
+            '_'
+        } else {
+            ch.to_ascii_uppercase()
+        });
+    }
+    out
+}
+
+fn base_url_key_cli(key: &str) -> Option<Option<&'static str>> {
+    match key {
+        "ANTHROPIC_BASE_URL" => Some(Some("claude")),
+        "OPENAI_BASE_URL" | "OPENAI_API_BASE" | "OPENAI_API_BASE_URL" => Some(Some("codex")),
+        "GOOGLE_GEMINI_BASE_URL" | "GEMINI_BASE_URL" => Some(Some("gemini")),
+        "BASE_URL" | "API_BASE" | "API_BASE_URL" | "BASEURL" | "ENDPOINT" | "API_URL" => Some(None),
+        _ => None,
+    }
+}
+
+fn api_key_key_cli(key: &str) -> Option<Option<&'static str>> {
+    match key {
+        "ANTHROPIC_AUTH_TOKEN" | "ANTHROPIC_API_KEY" => Some(Some("claude")),
+        "OPENAI_API_KEY" | "CODEX_API_KEY" => Some(Some("codex")),
+        "GEMINI_API_KEY" | "GOOGLE_API_KEY" => Some(Some("gemini")),
+        "API_KEY" | "APIKEY" | "KEY" | "TOKEN" | "AUTH_TOKEN" | "ACCESS_TOKEN" | "SECRET_KEY" => {
+            Some(None)
+        }
+        _ => None,
+    }
+}
+
+fn clean_value(raw: &str) -> String {
+    let value = raw.trim().trim_end_matches([';', ',']).trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value);
+    value.trim().to_string()
+}
+
+impl Collected {
+    fn apply(&mut self, raw_key: &str, raw_value: &str) {
+        let key = normalize_key(raw_key);
+        let value = clean_value(raw_value);
+        if value.is_empty() {
+            return;
+        }
+
+        // Provider-specific keys win over generic ones (`BASE_URL` next to `ANTHROPIC_BASE_URL`).
+        if let Some(cli) = base_url_key_cli(&key) {
+            if self.base_url.as_ref().is_none_or(|(_, c)| c.is_none()) {
+                self.base_url = Some((value, cli));
+            }
+            return;
+        }
+        if let Some(cli) = api_key_key_cli(&key) {
+            if self.api_key.as_ref().is_none_or(|(_, c)| c.is_none()) {
+                self.api_key = Some((value, cli));
+            }
+            return;
+        }
+
+        let models = &mut self.claude_models;
+        match key.as_str() {
+            "NAME" | "PROVIDER_NAME" | "CHANNEL_NAME" => self.name = Some(value),
+            "ANTHROPIC_MODEL" => models.main_model = Some(value),
+            "ANTHROPIC_REASONING_MODEL" => models.reasoning_model = Some(value),
+            "ANTHROPIC_DEFAULT_HAIKU_MODEL" | "ANTHROPIC_SMALL_FAST_MODEL" => {
+                models.haiku_model.get_or_insert(value);
+            }
+            "ANTHROPIC_DEFAULT_SONNET_MODEL" => models.sonnet_model = Some(value),
+            "ANTHROPIC_DEFAULT_OPUS_MODEL" => models.opus_model = Some(value),
+            _ => {}
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.base_url.is_none() && self.api_key.is_none()
+    }
+}
+
+/// Splits one env / shell / yaml-ish line into (key, value).
+fn split_assignment(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+        return None;
+    }
+    let lower = line.to_ascii_lowercase();
+    if lower.starts_with("rem ") {
+        return None;
+    }
+    if lower.starts_with("setx ") {
+        let rest = line[5..].trim();
+        let (key, value) = rest.split_once(char::is_whitespace)?;
+        return Some((key, value));
+    }
+
+    let line = ["export ", "set ", "$env:"]
+        .iter()
+        .find(|prefix| lower.starts_with(**prefix))
+        .map(|prefix| line[prefix.len()..].trim_start())
+        .unwrap_or(line);
+    // cmd: `set "KEY=value"`
+    let line = line
+        .strip_prefix('"')
+        .map(|rest| rest.strip_suffix('"').unwrap_or(rest))
+        .unwrap_or(line);
+
+    if let Some((key, value)) = line.split_once('=') {
+        return Some((key, value));
+    }
+    // `KEY: value` (yaml / chat messages); the URL's own `://` must not be mistaken for it.
+    let (key, value) = line.split_once(':')?;
+    if value.starts_with("//") {
+        return None;
+    }
+    Some((key, value))
+}
+
+fn collect_env(text: &str, collected: &mut Collected) {
+    for line in text.lines() {
+        if let Some((key, value)) = split_assignment(line) {
+            collected.apply(key, value);
+        }
+    }
+}
+
+fn collect_object(obj: &serde_json::Map<String, serde_json::Value>, collected: &mut Collected) {
+    for (key, value) in obj {
+        if let Some(value) = value.as_str() {
+            collected.apply(key, value);
+        }
+    }
+}
+
+fn one_api_type_cli(channel_type: i64) -> Option<&'static str> {
+    match channel_type {
+        14 => Some("claude"),
+        1 => Some("codex"),
+        24 => Some("gemini"),
+        _ => None,
+    }
+}
+
+fn collect_json(
+    value: &serde_json::Value,
+    collected: &mut Collected,
+    warnings: &mut Vec<String>,
+) -> Option<ProviderImportFormat> {
+    let obj = match value {
+        serde_json::Value::Array(items) => {
+            let objects: Vec<_> = items.iter().filter(|v| v.is_object()).collect();
+            if objects.len() > 1 {
+                warnings.push(format!("检测到 {} 个渠道，仅导入第一个", objects.len()));
+            }
+            return collect_json(objects.first()?, collected, warnings);
+        }
+        serde_json::Value::Object(obj) => obj,
+        _ => return None,
+    };
+
+    let env = obj.get("env").or_else(|| {
+        obj.get("settingsConfig")
+            .or_else(|| obj.get("settings_config"))
+            .and_then(|v| v.get("env"))
+    });
+    if let Some(env) = env.and_then(|v| v.as_object()) {
+        collect_object(env, collected);
+        if let Some(name) = obj.get("name").and_then(|v| v.as_str()) {
+            collected.apply("name", name);
+        }
+        return Some(ProviderImportFormat::ClaudeSettings);
+    }
+
+    collect_object(obj, collected);
+    let channel_type = obj.get("type").and_then(|v| v.as_i64());
+    let Some(channel_type) = channel_type else {
+        return Some(ProviderImportFormat::Json);
+    };
+
+    let cli = one_api_type_cli(channel_type);
+    if cli.is_none() {
+        warnings.push(format!(
+            "未识别的渠道类型 type={channel_type}，请手动选择 CLI"
+        ));
+    }
+    if let Some((_, slot)) = collected.base_url.as_mut() {
+        *slot = slot.or(cli);
+    } else {
+        warnings.push("渠道未设置 base_url（使用官方地址），请手动填写".to_string());
+    }
+    if let Some((key, slot)) = collected.api_key.as_mut() {
+        // one-api stores multiple keys one per line.
+        if let Some((first, _)) = key.split_once('\n') {
+            *key = first.trim().to_string();
+            warnings.push("渠道包含多个密钥，仅导入第一个".to_string());
+        }
+        *slot = slot.or(cli);
+    }
+    Some(ProviderImportFormat::OneApiChannel)
+}
+
+fn collect_text(text: &str, collected: &mut Collected) {
+    // ASCII `:` stays inside tokens so URLs survive; full-width punctuation separates labels.
+    let mut tokens = text
+        .split(|c: char| c.is_whitespace() || "\"'`,;<>()（）：，".contains(c))
+        .filter(|t| !t.is_empty());
+    if collected.base_url.is_none() {
+        let url = tokens
+            .clone()
+            .find(|t| t.starts_with("http://") || t.starts_with("https://"));
+        if let Some(url) = url {
+            collected.base_url = Some((url.to_string(), None));
+        }
+    }
+    if collected.api_key.is_none() {
+        let key = tokens.find_map(|t| {
+            let t = t.rsplit(':').next().unwrap_or(t);
+            (t.starts_with("sk-") && t.len() > 8).then_some(t)
+        });
+        if let Some(key) = key {
+            collected.api_key = Some((key.to_string(), None));
+        }
+    }
+}
+
+fn normalize_base_url(raw: &str, warnings: &mut Vec<String>) -> Option<String> {
+    let trimmed = raw.trim().trim_end_matches('/');
+    match reqwest::Url::parse(trimmed) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some() => {
+            Some(trimmed.to_string())
+        }
+        _ => {
+            warnings.push(format!("忽略无效的 base_url：{trimmed}"));
+            None
+        }
+    }
+}
+
+fn name_from_base_url(base_url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(base_url).ok()?;
+    let host = url.host_str()?;
+    let host = host
+        .strip_prefix("api.")
+        .or_else(|| host.strip_prefix("www."))
+        .unwrap_or(host);
+    Some(host.to_string())
+}
+
+pub fn parse(text: &str) -> Result<ProviderImportDraft, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("SEC_INVALID_INPUT: text is required".to_string());
+    }
+    if text.len() > MAX_INPUT_LEN {
+        return Err(format!(
+            "SEC_INVALID_INPUT: text must be <= {MAX_INPUT_LEN} bytes"
+        ));
+    }
+
+    let mut collected = Collected::default();
+    let mut warnings = Vec::new();
+    let mut format = match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(value) => collect_json(&value, &mut collected, &mut warnings),
+        Err(_) => None,
+    };
+    if format.is_none() {
+        collect_env(text, &mut collected);
+        if !collected.is_empty() {
+            format = Some(ProviderImportFormat::Env);
+        }
+    }
+    if collected.base_url.is_none() || collected.api_key.is_none() {
+        collect_text(text, &mut collected);
+        if format.is_none() && !collected.is_empty() {
+            format = Some(ProviderImportFormat::Text);
+        }
+    }
+    let Some(format) = format.filter(|_| !collected.is_empty()) else {
+        return Err("SEC_INVALID_INPUT: 未识别到可导入的供应商配置".to_string());
+    };
+
+    let url_cli = collected.base_url.as_ref().and_then(|(_, cli)| *cli);
+    let key_cli = collected.api_key.as_ref().and_then(|(_, cli)| *cli);
+    if let (Some(a), Some(b)) = (url_cli, key_cli) {
+        if a != b {
+            warnings.push(format!(
+                "base_url 与密钥来自不同 CLI（{a} / {b}），已按 base_url 识别"
+            ));
+        }
+    }
+
+    let base_urls: Vec<String> = collected
+        .base_url
+        .as_ref()
+        .and_then(|(url, _)| normalize_base_url(url, &mut warnings))
+        .into_iter()
+        .collect();
+    if collected.api_key.is_none() {
+        warnings.push("未识别到 API Key，请手动填写".to_string());
+    }
+    let name = collected
+        .name
+        .or_else(|| base_urls.first().and_then(|url| name_from_base_url(url)));
+
+    Ok(ProviderImportDraft {
+        format,
+        cli_key: url_cli.or(key_cli).map(str::to_string),
+        name,
+        base_urls,
+        api_key: collected.api_key.map(|(key, _)| key),
+        claude_models: collected.claude_models,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_shell_env_snippet() {
+        let draft = parse(
+            r#"
+# Claude Code
+export ANTHROPIC_BASE_URL="https://api.relay.example/"
+export ANTHROPIC_AUTH_TOKEN=sk-abc123456
+set ANTHROPIC_DEFAULT_SONNET_MODEL=claude-sonnet-4
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(draft.format, ProviderImportFormat::Env);
+        assert_eq!(draft.cli_key.as_deref(), Some("claude"));
+        assert_eq!(draft.base_urls, vec!["https://api.relay.example"]);
+        assert_eq!(draft.api_key.as_deref(), Some("sk-abc123456"));
+        assert_eq!(draft.name.as_deref(), Some("relay.example"));
+        assert_eq!(
+            draft.claude_models.sonnet_model.as_deref(),
+            Some("claude-sonnet-4")
+        );
+        assert!(draft.warnings.is_empty());
+
+        let draft =
+            parse("$env:OPENAI_BASE_URL=\"https://x.example/v1\"\nsetx OPENAI_API_KEY sk-xyz12345")
+                .unwrap();
+        assert_eq!(draft.cli_key.as_deref(), Some("codex"));
+        assert_eq!(draft.base_urls, vec!["https://x.example/v1"]);
+        assert_eq!(draft.api_key.as_deref(), Some("sk-xyz12345"));
+    }
+
+    #[test]
+    fn parses_claude_settings_and_one_api_channel() {
+        let draft = parse(
+            r#"{"env": {"ANTHROPIC_BASE_URL": "https://relay.example", "ANTHROPIC_API_KEY": "sk-1234567890", "ANTHROPIC_MODEL": "glm-4.6"}}"#,
+        )
+        .unwrap();
+        assert_eq!(draft.format, ProviderImportFormat::ClaudeSettings);
+        assert_eq!(draft.cli_key.as_deref(), Some("claude"));
+        assert_eq!(draft.claude_models.main_model.as_deref(), Some("glm-4.6"));
+
+        let draft = parse(
+            r#"[{"name": "中转 A", "type": 24, "base_url": "https://gem.example/", "key": "k-one\nk-two"}, {"name": "B", "type": 1}]"#,
+        )
+        .unwrap();
+        assert_eq!(draft.format, ProviderImportFormat::OneApiChannel);
+        assert_eq!(draft.cli_key.as_deref(), Some("gemini"));
+        assert_eq!(draft.name.as_deref(), Some("中转 A"));
+        assert_eq!(draft.base_urls, vec!["https://gem.example"]);
+        assert_eq!(draft.api_key.as_deref(), Some("k-one"));
+        assert_eq!(draft.warnings.len(), 2);
+    }
+
+    #[test]
+    fn falls_back_to_free_text_and_rejects_noise() {
+        let draft = parse("地址：https://relay.example/v1 ，密钥：sk-freetext001").unwrap();
+        assert_eq!(draft.format, ProviderImportFormat::Text);
+        assert_eq!(draft.cli_key, None);
+        assert_eq!(draft.base_urls, vec!["https://relay.example/v1"]);
+        assert_eq!(draft.api_key.as_deref(), Some("sk-freetext001"));
+
+        assert!(parse("hello world").is_err());
+        assert!(parse("   ").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Usage: In-memory snapshot of what the gateway routes over (providers + sort mode), read per request.
+//!
+//! 用法：
+//! - 网关按 (CLI, 激活模式 / 会话绑定模式) 读取快照：启用的供应商列表、排序模式设置覆盖、负载均衡策略与权重、
+//!   进行中的灰度与 A/B 对比
+//! - 快照在同一个 SQLite 读事务内装载：保存排序 / 重排的过程中不会读到一半新一半旧的结果
+//! - 供应商、排序模式、灰度或 A/B 对比写入提交后调用 `invalidate()`；装载期间发生的写入会使该结果不进入缓存（generation 校验）
+
+use crate::shared::mutex_ext::MutexExt;
+use crate::{db, provider_ab_test, provider_canary, providers, sort_modes};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Selection {
+    // Whatever `sort_mode_active` points at.
+    Active,
+    // An explicit mode (session binding); `None` is the default order.
+    Mode(Option<i64>),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct GatewaySnapshot {
+    pub(crate) sort_mode_id: Option<i64>,
+    pub(crate) providers: Vec<providers::ProviderForGateway>,
+    pub(crate) mode_settings: sort_modes::SortModeSettingsOverride,
+    pub(crate) routing: sort_modes::SortModeRouting,
+    pub(crate) canary: Option<provider_canary::ProviderCanary>,
+    pub(crate) ab_test: Option<provider_ab_test::ProviderAbTest>,
+}
+
+struct Snapshots {
+    generation: u64,
+    entries: Option<HashMap<(String, Selection), Arc<GatewaySnapshot>>>,
+}
+
+static SNAPSHOTS: Mutex<Snapshots> = Mutex::new(Snapshots {
+    generation: 0,
+    entries: None,
+});
+
+/// Snapshot for the active sort mode of `cli_key` (default order when none is active).
+pub(crate) fn active(db: &db::Db, cli_key: &str) -> Result<Arc<GatewaySnapshot>, String> {
+    get_or_load(cli_key, Selection::Active, || {
+        load(db, cli_key, Selection::Active)
+    })
+}
+
+/// Snapshot for an explicit sort mode of `cli_key` (`None` = default order).
+pub(crate) fn in_mode(
+    db: &db::Db,
+    cli_key: &str,
+    sort_mode_id: Option<i64>,
+) -> Result<Arc<GatewaySnapshot>, String> {
+    let selection = Selection::Mode(sort_mode_id);
+    get_or_load(cli_key, selection, || load(db, cli_key, selection))
+}
+
+/// Drops every snapshot; call after a provider / sort-mode write has committed.
+pub(crate) fn invalidate() {
+    let mut guard = SNAPSHOTS.lock_or_recover();
+    guard.generation = guard.generation.wrapping_add(1);
+    guard.entries = None;
+}
+
+fn get_or_load(
+    cli_key: &str,
+    selection: Selection,
+    load: impl FnOnce() -> Result<GatewaySnapshot, String>,
+) -> Result<Arc<GatewaySnapshot>, String> {
+    let key = (cli_key.to_string(), selection);
+    let generation = {
+        let guard = SNAPSHOTS.lock_or_recover();
+        if let Some(hit) = guard.entries.as_ref().and_then(|entries| entries.get(&key)) {
+            return Ok(hit.clone());
+        }
+        guard.generation
+    };
+
+    let snapshot = Arc::new(load()?);
+    let mut guard = SNAPSHOTS.lock_or_recover();
+    // A write committed while loading: serve what was read, but don't keep it.
+    if guard.generation == generation {
+        guard
+            .entries
+            .get_or_insert_with(HashMap::new)
+            .insert(key, snapshot.clone());
+    }
+    Ok(snapshot)
+}
+
+fn load(db: &db::Db, cli_key: &str, selection: Selection) -> Result<GatewaySnapshot, String> {
+    let mut conn = db.open_connection()?;
+    // One read transaction: the active mode, its provider order and its weights come from the same commit.
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
+
+    let sort_mode_id = match selection {
+        Selection::Active => providers::active_sort_mode_id(&tx, cli_key)?,
+        Selection::Mode(mode_id) => mode_id,
+    };
+    let providers = providers::list_enabled_for_gateway_in_mode(&tx, cli_key, sort_mode_id)?;
+    let (mode_settings, routing) = match sort_mode_id {
+        Some(mode_id) => (
+            sort_modes::mode_settings(&tx, mode_id)?,
+            sort_modes::mode_routing(&tx, mode_id, cli_key)?,
+        ),
+        None => Default::default(),
+    };
+    let canary = provider_canary::running_in(&tx, cli_key, sort_mode_id)?;
+    let ab_test = provider_ab_test::running_test(&tx, cli_key)?;
+
+    Ok(GatewaySnapshot {
+        sort_mode_id,
+        providers,
+        mode_settings,
+        routing,
+        canary,
+        ab_test,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalidation_during_load_is_not_cached() {
+        let cli_key = "snapshot-test";
+        let selection = Selection::Mode(Some(-1));
+        let snapshot = |id| GatewaySnapshot {
+            sort_mode_id: Some(id),
+            ..Default::default()
+        };
+
+        let first = get_or_load(cli_key, selection, || {
+            invalidate();
+            Ok(snapshot(1))
+        })
+        .unwrap();
+        assert_eq!(first.sort_mode_id, Some(1));
+
+        // not cached: the next read loads again and keeps that result
+        let second = get_or_load(cli_key, selection, || Ok(snapshot(2))).unwrap();
+        assert_eq!(second.sort_mode_id, Some(2));
+        let third = get_or_load(cli_key, selection, || Ok(snapshot(3))).unwrap();
+        assert_eq!(third.sort_mode_id, Some(2));
+
+        invalidate();
+        let fourth = get_or_load(cli_key, selection, || Ok(snapshot(4))).unwrap();
+        assert_eq!(fourth.sort_mode_id, Some(4));
+    }
+}
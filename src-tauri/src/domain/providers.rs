@@ -1,14 +1,50 @@
 //! Usage: Provider configuration persistence and gateway selection helpers.
 
-use crate::db;
 use crate::shared::sqlite::enabled_to_int;
 use crate::shared::time::now_unix_seconds;
+use crate::{db, provider_snapshot};
 use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
 const DEFAULT_PRIORITY: i64 = 100;
 const MAX_MODEL_NAME_LEN: usize = 200;
+const MAX_RPM_LIMIT: i64 = 100_000;
+const MAX_TPM_LIMIT: i64 = 1_000_000_000;
+const MAX_QUOTA_USD: f64 = 1_000_000.0;
+const MAX_CONTEXT_GUARD_LIMIT_KB: i64 = 256 * 1024;
+const MAX_UPSTREAM_PROXY_LEN: usize = 2048;
+const MAX_REQUEST_SIGNING_SECRET_LEN: usize = 1024;
+const MAX_REQUEST_SIGNING_TEMPLATE_LEN: usize = 512;
+const DEFAULT_REQUEST_SIGNING_HEADER: &str = "x-signature";
+const DEFAULT_REQUEST_SIGNING_TEMPLATE: &str = "{signature}";
+const MAX_UPSTREAM_TLS_PEM_LEN: usize = 1024 * 1024;
+const MAX_HEADER_RULES: usize = 32;
+const MAX_HEADER_RULE_VALUE_LEN: usize = 2048;
+const MAX_BODY_PATCH_RULES: usize = 32;
+const MAX_BODY_PATCH_PATH_LEN: usize = 256;
+const MAX_BODY_PATCH_VALUE_LEN: usize = 16 * 1024;
+pub(crate) const HEADER_RULE_API_KEY_PLACEHOLDER: &str = "{api_key}";
+// Headers a signature must not replace: framing / routing, and the provider auth injected earlier.
+const RESERVED_REQUEST_SIGNING_HEADERS: &[&str] = &[
+    "host",
+    "content-length",
+    "content-type",
+    "content-encoding",
+    "transfer-encoding",
+    "connection",
+    "authorization",
+    "x-api-key",
+    "x-goog-api-key",
+];
+
+// Connection / framing headers the HTTP client owns; header rules must not touch them.
+const RESERVED_HEADER_RULE_HEADERS: &[&str] =
+    &["host", "content-length", "transfer-encoding", "connection"];
+
+// Outbound proxy modes besides an explicit `http(s)://` / `socks5(h)://` proxy URL.
+pub(crate) const UPSTREAM_PROXY_SYSTEM: &str = "system";
+pub(crate) const UPSTREAM_PROXY_NONE: &str = "none";
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ClaudeModels {
@@ -104,6 +140,520 @@ fn claude_models_from_json(raw: &str) -> ClaudeModels {
         .normalized()
 }
 
+/// Per-provider overrides of the global response fixer settings; `None` inherits the global value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseFixerOverride {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix_encoding: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix_sse_format: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix_truncated_json: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix_tool_args: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_json_depth: Option<u32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fix_size: Option<u32>,
+}
+
+impl ResponseFixerOverride {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(depth) = self.max_json_depth {
+            if !(1..=2000).contains(&depth) {
+                return Err(
+                    "SEC_INVALID_INPUT: response_fixer.max_json_depth must be within [1, 2000]"
+                        .to_string(),
+                );
+            }
+        }
+        if let Some(size) = self.max_fix_size {
+            if !(1..=16 * 1024 * 1024).contains(&size) {
+                return Err(
+                    "SEC_INVALID_INPUT: response_fixer.max_fix_size must be within [1, 16777216]"
+                        .to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn response_fixer_from_json(raw: &str) -> ResponseFixerOverride {
+    serde_json::from_str::<ResponseFixerOverride>(raw)
+        .ok()
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestSigningAlgorithm {
+    #[default]
+    None,
+    HmacSha256,
+    HmacSha512,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestSigningEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// HMAC request signing for self-hosted relays: the signature covers `"{timestamp}.{body}"`
+/// (unix seconds, final upstream body bytes) and is sent in `header_name`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestSigning {
+    #[serde(default)]
+    pub algorithm: RequestSigningAlgorithm,
+    // Never returned to the frontend; empty on update keeps the stored secret.
+    #[serde(default)]
+    pub secret: String,
+    #[serde(default)]
+    pub encoding: RequestSigningEncoding,
+    #[serde(default)]
+    pub header_name: String,
+    // Header value with `{signature}` / `{timestamp}` placeholders, e.g. `t={timestamp},v1={signature}`.
+    #[serde(default)]
+    pub header_template: String,
+    // Optional header carrying the timestamp on its own; empty = not sent.
+    #[serde(default)]
+    pub timestamp_header: String,
+}
+
+fn validate_signing_header_name(field: &str, name: &str) -> Result<(), String> {
+    reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+        format!("SEC_INVALID_INPUT: request_signing.{field} is not a valid header name")
+    })?;
+    if RESERVED_REQUEST_SIGNING_HEADERS.contains(&name) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: request_signing.{field} must not be '{name}'"
+        ));
+    }
+    Ok(())
+}
+
+impl RequestSigning {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.algorithm != RequestSigningAlgorithm::None
+    }
+
+    /// Copy safe to hand to the frontend.
+    fn redacted(&self) -> Self {
+        Self {
+            secret: String::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Validates and fills defaults; an empty secret falls back to `existing_secret`.
+    fn normalized(self, existing_secret: Option<&str>) -> Result<Self, String> {
+        if !self.is_enabled() {
+            return Ok(Self::default());
+        }
+
+        let secret = if self.secret.is_empty() {
+            existing_secret.unwrap_or_default().to_string()
+        } else {
+            self.secret
+        };
+        if secret.is_empty() {
+            return Err("SEC_INVALID_INPUT: request_signing.secret is required".to_string());
+        }
+        if secret.len() > MAX_REQUEST_SIGNING_SECRET_LEN {
+            return Err("SEC_INVALID_INPUT: request_signing.secret is too long".to_string());
+        }
+
+        let header_name = match self.header_name.trim().to_ascii_lowercase() {
+            name if name.is_empty() => DEFAULT_REQUEST_SIGNING_HEADER.to_string(),
+            name => name,
+        };
+        validate_signing_header_name("header_name", &header_name)?;
+
+        let header_template = match self.header_template.trim() {
+            "" => DEFAULT_REQUEST_SIGNING_TEMPLATE.to_string(),
+            template => template.to_string(),
+        };
+        if header_template.len() > MAX_REQUEST_SIGNING_TEMPLATE_LEN {
+            return Err(
+                "SEC_INVALID_INPUT: request_signing.header_template is too long".to_string(),
+            );
+        }
+        if !header_template.contains("{signature}") {
+            return Err(
+                "SEC_INVALID_INPUT: request_signing.header_template must contain {signature}"
+                    .to_string(),
+            );
+        }
+        if reqwest::header::HeaderValue::from_str(&header_template).is_err() {
+            return Err(
+                "SEC_INVALID_INPUT: request_signing.header_template is not a valid header value"
+                    .to_string(),
+            );
+        }
+
+        let timestamp_header = self.timestamp_header.trim().to_ascii_lowercase();
+        if !timestamp_header.is_empty() {
+            validate_signing_header_name("timestamp_header", &timestamp_header)?;
+            if timestamp_header == header_name {
+                return Err(
+                    "SEC_INVALID_INPUT: request_signing.timestamp_header must differ from header_name"
+                        .to_string(),
+                );
+            }
+        }
+
+        Ok(Self {
+            algorithm: self.algorithm,
+            secret,
+            encoding: self.encoding,
+            header_name,
+            header_template,
+            timestamp_header,
+        })
+    }
+}
+
+fn request_signing_from_json(raw: &str) -> RequestSigning {
+    serde_json::from_str::<RequestSigning>(raw)
+        .ok()
+        .unwrap_or_default()
+}
+
+/// Custom TLS material for relays behind corporate TLS interception or requiring mTLS.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UpstreamTls {
+    // Extra trusted root CAs (PEM bundle), added on top of the system roots.
+    #[serde(default)]
+    pub ca_bundle_pem: String,
+    // Client certificate chain (PEM) presented for mTLS; requires `client_key_pem`.
+    #[serde(default)]
+    pub client_cert_pem: String,
+    // Never returned to the frontend; empty on update keeps the stored key.
+    #[serde(default)]
+    pub client_key_pem: String,
+}
+
+fn validate_tls_pem_len(field: &str, pem: &str) -> Result<(), String> {
+    if pem.len() > MAX_UPSTREAM_TLS_PEM_LEN {
+        return Err(format!(
+            "SEC_INVALID_INPUT: upstream_tls.{field} is too long"
+        ));
+    }
+    Ok(())
+}
+
+impl UpstreamTls {
+    pub(crate) fn is_configured(&self) -> bool {
+        !self.ca_bundle_pem.is_empty() || !self.client_cert_pem.is_empty()
+    }
+
+    /// Adds the custom roots / client identity to an upstream client builder.
+    pub(crate) fn apply_to(
+        &self,
+        mut builder: reqwest::ClientBuilder,
+    ) -> reqwest::Result<reqwest::ClientBuilder> {
+        if !self.ca_bundle_pem.is_empty() {
+            for cert in reqwest::Certificate::from_pem_bundle(self.ca_bundle_pem.as_bytes())? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        if !self.client_cert_pem.is_empty() {
+            let pem = format!("{}\n{}\n", self.client_cert_pem, self.client_key_pem);
+            builder = builder.identity(reqwest::Identity::from_pem(pem.as_bytes())?);
+        }
+        Ok(builder)
+    }
+
+    /// Copy safe to hand to the frontend.
+    fn redacted(&self) -> Self {
+        Self {
+            client_key_pem: String::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Validates by building a client with the material; an empty key falls back to `existing_key`.
+    fn normalized(self, existing_key: Option<&str>) -> Result<Self, String> {
+        let ca_bundle_pem = self.ca_bundle_pem.trim().to_string();
+        let client_cert_pem = self.client_cert_pem.trim().to_string();
+        let client_key_pem = match self.client_key_pem.trim() {
+            "" if client_cert_pem.is_empty() => String::new(),
+            "" => existing_key.unwrap_or_default().trim().to_string(),
+            key => key.to_string(),
+        };
+        validate_tls_pem_len("ca_bundle_pem", &ca_bundle_pem)?;
+        validate_tls_pem_len("client_cert_pem", &client_cert_pem)?;
+        validate_tls_pem_len("client_key_pem", &client_key_pem)?;
+
+        if !ca_bundle_pem.is_empty()
+            && reqwest::Certificate::from_pem_bundle(ca_bundle_pem.as_bytes())
+                .map_or(true, |certs| certs.is_empty())
+        {
+            return Err(
+                "SEC_INVALID_INPUT: upstream_tls.ca_bundle_pem contains no PEM certificate"
+                    .to_string(),
+            );
+        }
+        if client_cert_pem.is_empty() && !client_key_pem.is_empty() {
+            return Err(
+                "SEC_INVALID_INPUT: upstream_tls.client_key_pem requires client_cert_pem"
+                    .to_string(),
+            );
+        }
+        if !client_cert_pem.is_empty() && client_key_pem.is_empty() {
+            return Err("SEC_INVALID_INPUT: upstream_tls.client_key_pem is required".to_string());
+        }
+
+        let tls = Self {
+            ca_bundle_pem,
+            client_cert_pem,
+            client_key_pem,
+        };
+        if tls.is_configured() {
+            tls.apply_to(reqwest::Client::builder())
+                .and_then(reqwest::ClientBuilder::build)
+                .map_err(|e| format!("SEC_INVALID_INPUT: upstream_tls is invalid: {e}"))?;
+        }
+        Ok(tls)
+    }
+}
+
+fn upstream_tls_from_json(raw: &str) -> UpstreamTls {
+    serde_json::from_str::<UpstreamTls>(raw)
+        .ok()
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderHeaderAction {
+    // Set only when the request doesn't already carry the header.
+    Add,
+    // Always set, replacing any client / auth value.
+    Override,
+    Remove,
+}
+
+/// Outbound header rule of a provider, applied after the provider auth is injected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderHeaderRule {
+    pub action: ProviderHeaderAction,
+    pub name: String,
+    // Header value for add / override; `{api_key}` is replaced with the provider key.
+    #[serde(default)]
+    pub value: String,
+}
+
+fn normalize_header_rules(
+    rules: Vec<ProviderHeaderRule>,
+) -> Result<Vec<ProviderHeaderRule>, String> {
+    if rules.len() > MAX_HEADER_RULES {
+        return Err(format!(
+            "SEC_INVALID_INPUT: header_rules must have at most {MAX_HEADER_RULES} entries"
+        ));
+    }
+
+    let mut out = Vec::with_capacity(rules.len());
+    for rule in rules {
+        let name = rule.name.trim().to_ascii_lowercase();
+        if name.is_empty() {
+            return Err("SEC_INVALID_INPUT: header_rules.name is required".to_string());
+        }
+        reqwest::header::HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+            format!("SEC_INVALID_INPUT: header_rules.name '{name}' is not a valid header name")
+        })?;
+        if RESERVED_HEADER_RULE_HEADERS.contains(&name.as_str()) {
+            return Err(format!(
+                "SEC_INVALID_INPUT: header_rules.name must not be '{name}'"
+            ));
+        }
+
+        let value = match rule.action {
+            ProviderHeaderAction::Remove => String::new(),
+            ProviderHeaderAction::Add | ProviderHeaderAction::Override => {
+                let value = rule.value.trim().to_string();
+                if value.is_empty() {
+                    return Err(format!(
+                        "SEC_INVALID_INPUT: header_rules.value is required for '{name}'"
+                    ));
+                }
+                if value.len() > MAX_HEADER_RULE_VALUE_LEN {
+                    return Err(format!(
+                        "SEC_INVALID_INPUT: header_rules.value is too long for '{name}'"
+                    ));
+                }
+                if reqwest::header::HeaderValue::from_str(&value).is_err() {
+                    return Err(format!(
+                        "SEC_INVALID_INPUT: header_rules.value is not a valid header value for '{name}'"
+                    ));
+                }
+                value
+            }
+        };
+
+        out.push(ProviderHeaderRule {
+            action: rule.action,
+            name,
+            value,
+        });
+    }
+    Ok(out)
+}
+
+fn header_rules_from_json(raw: &str) -> Vec<ProviderHeaderRule> {
+    serde_json::from_str::<Vec<ProviderHeaderRule>>(raw)
+        .ok()
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyPatchOp {
+    Set,
+    Delete,
+    Rename,
+}
+
+/// One request body mutation of a provider, applied to the JSON body before it is forwarded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BodyPatchRule {
+    pub op: BodyPatchOp,
+    // `$.metadata`, `generationConfig.temperature`, `messages[*].cache_control` (`$.` is optional).
+    pub path: String,
+    // New value for `set`.
+    #[serde(default)]
+    pub value: serde_json::Value,
+    // New key (in the same object) for `rename`.
+    #[serde(default)]
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum BodyPatchPathSegment {
+    Key(String),
+    Index(usize),
+    // `[*]`: every element of an array.
+    AnyIndex,
+}
+
+/// Parses a JSONPath-style path: dotted keys, `[n]` indexes and `[*]`; `$` / `$.` prefix optional.
+pub(crate) fn parse_body_patch_path(path: &str) -> Result<Vec<BodyPatchPathSegment>, String> {
+    let invalid = || format!("SEC_INVALID_INPUT: body_patch_rules.path '{path}' is invalid");
+    let rest = path.trim();
+    let mut rest = rest.strip_prefix('$').unwrap_or(rest);
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let index = &after[..end];
+            segments.push(if index == "*" {
+                BodyPatchPathSegment::AnyIndex
+            } else {
+                BodyPatchPathSegment::Index(index.parse().map_err(|_| invalid())?)
+            });
+            rest = &after[end + 1..];
+            continue;
+        }
+        let after = match rest.strip_prefix('.') {
+            Some(after) => after,
+            None if segments.is_empty() => rest,
+            None => return Err(invalid()),
+        };
+        let end = after.find(['.', '[']).unwrap_or(after.len());
+        let key = after[..end].trim();
+        if key.is_empty() {
+            return Err(invalid());
+        }
+        segments.push(BodyPatchPathSegment::Key(key.to_string()));
+        rest = &after[end..];
+    }
+    if segments.is_empty() {
+        return Err(invalid());
+    }
+    Ok(segments)
+}
+
+fn normalize_body_patch_rules(rules: Vec<BodyPatchRule>) -> Result<Vec<BodyPatchRule>, String> {
+    if rules.len() > MAX_BODY_PATCH_RULES {
+        return Err(format!(
+            "SEC_INVALID_INPUT: body_patch_rules must have at most {MAX_BODY_PATCH_RULES} entries"
+        ));
+    }
+
+    let mut out = Vec::with_capacity(rules.len());
+    for rule in rules {
+        let path = rule.path.trim().to_string();
+        if path.len() > MAX_BODY_PATCH_PATH_LEN {
+            return Err("SEC_INVALID_INPUT: body_patch_rules.path is too long".to_string());
+        }
+        let segments = parse_body_patch_path(&path)?;
+        let last = segments.last();
+
+        let (value, to) = match rule.op {
+            BodyPatchOp::Delete => (serde_json::Value::Null, String::new()),
+            BodyPatchOp::Set => {
+                if last == Some(&BodyPatchPathSegment::AnyIndex) {
+                    return Err(format!(
+                        "SEC_INVALID_INPUT: body_patch_rules.path '{path}' must not end with [*] for set"
+                    ));
+                }
+                let len = serde_json::to_string(&rule.value)
+                    .map_err(|e| format!("SYSTEM_ERROR: {e}"))?
+                    .len();
+                if len > MAX_BODY_PATCH_VALUE_LEN {
+                    return Err(format!(
+                        "SEC_INVALID_INPUT: body_patch_rules.value is too long for '{path}'"
+                    ));
+                }
+                (rule.value, String::new())
+            }
+            BodyPatchOp::Rename => {
+                let Some(BodyPatchPathSegment::Key(from)) = last else {
+                    return Err(format!(
+                        "SEC_INVALID_INPUT: body_patch_rules.path '{path}' must end with a key for rename"
+                    ));
+                };
+                let to = rule.to.trim().to_string();
+                if to.is_empty() || to == *from {
+                    return Err(format!(
+                        "SEC_INVALID_INPUT: body_patch_rules.to must be a different key for '{path}'"
+                    ));
+                }
+                (serde_json::Value::Null, to)
+            }
+        };
+
+        out.push(BodyPatchRule {
+            op: rule.op,
+            path,
+            value,
+            to,
+        });
+    }
+    Ok(out)
+}
+
+fn body_patch_rules_from_json(raw: &str) -> Vec<BodyPatchRule> {
+    serde_json::from_str::<Vec<BodyPatchRule>>(raw)
+        .ok()
+        .unwrap_or_default()
+}
+
 #[derive(Debug, Clone, Copy, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ProviderBaseUrlMode {
@@ -128,6 +678,45 @@ impl ProviderBaseUrlMode {
     }
 }
 
+/// Upstream API shape. Anything other than `Native` makes the gateway translate requests and
+/// responses (see `gateway/proxy/protocol_bridge`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderWireApi {
+    #[default]
+    Native,
+    // OpenAI-compatible `/v1/chat/completions`.
+    ChatCompletions,
+}
+
+impl ProviderWireApi {
+    fn parse(input: &str) -> Option<Self> {
+        match input.trim() {
+            "native" => Some(Self::Native),
+            "chat_completions" => Some(Self::ChatCompletions),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Native => "native",
+            Self::ChatCompletions => "chat_completions",
+        }
+    }
+
+    fn supports_cli(self, cli_key: &str) -> bool {
+        match self {
+            Self::Native => true,
+            Self::ChatCompletions => matches!(cli_key, "claude" | "codex"),
+        }
+    }
+}
+
+fn wire_api_from_row(raw: &str) -> ProviderWireApi {
+    ProviderWireApi::parse(raw).unwrap_or_default()
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ProviderSummary {
     pub id: i64,
@@ -136,6 +725,32 @@ pub struct ProviderSummary {
     pub base_urls: Vec<String>,
     pub base_url_mode: ProviderBaseUrlMode,
     pub claude_models: ClaudeModels,
+    pub response_fixer: ResponseFixerOverride,
+    // Codex only: force `store: false` so the upstream never retains conversations.
+    pub disable_response_storage: bool,
+    pub wire_api: ProviderWireApi,
+    // Test / sandbox provider: its traffic is logged but excluded from usage stats.
+    pub exclude_from_stats: bool,
+    // Token-bucket limits enforced by the gateway; 0 = unlimited.
+    pub rpm_limit: i64,
+    pub tpm_limit: i64,
+    // Spend budgets (USD) over the local day / month; 0 = unlimited.
+    pub daily_quota_usd: f64,
+    pub monthly_quota_usd: f64,
+    // Request body limit (KB) the gateway trims conversations down to before forwarding; 0 = off.
+    pub context_guard_limit_kb: i64,
+    // Outbound proxy: `system` (env proxy, loopback bypassed) | `none` | proxy URL.
+    pub upstream_proxy: String,
+    // Strict passthrough: forward the client's body byte-for-byte (no rewriting, no response fixing).
+    pub passthrough: bool,
+    // HMAC signing of the upstream request; the secret is always blank here.
+    pub request_signing: RequestSigning,
+    // Custom CA bundle / mTLS client certificate; the client key is always blank here.
+    pub upstream_tls: UpstreamTls,
+    // Outbound header add / override / remove rules.
+    pub header_rules: Vec<ProviderHeaderRule>,
+    // Request body set / delete / rename rules.
+    pub body_patch_rules: Vec<BodyPatchRule>,
     pub enabled: bool,
     pub priority: i64,
     pub cost_multiplier: f64,
@@ -151,12 +766,21 @@ pub(crate) struct ProviderForGateway {
     pub base_url_mode: ProviderBaseUrlMode,
     pub api_key_plaintext: String,
     pub claude_models: ClaudeModels,
-}
-
-#[derive(Debug, Clone)]
-pub(crate) struct GatewayProvidersSelection {
-    pub sort_mode_id: Option<i64>,
-    pub providers: Vec<ProviderForGateway>,
+    pub response_fixer: ResponseFixerOverride,
+    pub disable_response_storage: bool,
+    pub wire_api: ProviderWireApi,
+    pub exclude_from_stats: bool,
+    pub rpm_limit: i64,
+    pub tpm_limit: i64,
+    pub daily_quota_usd: f64,
+    pub monthly_quota_usd: f64,
+    pub context_guard_limit_kb: i64,
+    pub upstream_proxy: String,
+    pub passthrough: bool,
+    pub request_signing: RequestSigning,
+    pub upstream_tls: UpstreamTls,
+    pub header_rules: Vec<ProviderHeaderRule>,
+    pub body_patch_rules: Vec<BodyPatchRule>,
 }
 
 fn validate_cli_key(cli_key: &str) -> Result<(), String> {
@@ -191,6 +815,41 @@ fn normalize_base_urls(base_urls: Vec<String>) -> Result<Vec<String>, String> {
     Ok(out)
 }
 
+/// Protocol bridging rewrites the body, so a passthrough provider must speak the CLI's native API.
+fn validate_passthrough(passthrough: bool, wire_api: ProviderWireApi) -> Result<(), String> {
+    if passthrough && wire_api != ProviderWireApi::Native {
+        return Err("SEC_INVALID_INPUT: passthrough requires wire_api='native'".to_string());
+    }
+    Ok(())
+}
+
+/// `system` (default) | `none` | `http(s)://` / `socks5(h)://` proxy URL.
+fn normalize_upstream_proxy(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() || trimmed.eq_ignore_ascii_case(UPSTREAM_PROXY_SYSTEM) {
+        return Ok(UPSTREAM_PROXY_SYSTEM.to_string());
+    }
+    if trimmed.eq_ignore_ascii_case(UPSTREAM_PROXY_NONE) {
+        return Ok(UPSTREAM_PROXY_NONE.to_string());
+    }
+    if trimmed.len() > MAX_UPSTREAM_PROXY_LEN {
+        return Err("SEC_INVALID_INPUT: upstream_proxy is too long".to_string());
+    }
+
+    let url = reqwest::Url::parse(trimmed)
+        .map_err(|e| format!("SEC_INVALID_INPUT: invalid upstream_proxy: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+        return Err(
+            "SEC_INVALID_INPUT: upstream_proxy must be 'system', 'none', or an http(s):// / socks5:// URL"
+                .to_string(),
+        );
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err("SEC_INVALID_INPUT: upstream_proxy host is required".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
 fn base_urls_from_row(base_url_fallback: &str, base_urls_json: &str) -> Vec<String> {
     let mut parsed: Vec<String> = serde_json::from_str::<Vec<String>>(base_urls_json)
         .ok()
@@ -220,6 +879,7 @@ fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<ProviderSummary, rusqlite::
     let base_url_fallback: String = row.get("base_url")?;
     let base_urls_json: String = row.get("base_urls_json")?;
     let claude_models_json: String = row.get("claude_models_json")?;
+    let response_fixer_json: String = row.get("response_fixer_json")?;
     let base_url_mode_raw: String = row.get("base_url_mode")?;
     let base_url_mode =
         ProviderBaseUrlMode::parse(&base_url_mode_raw).unwrap_or(ProviderBaseUrlMode::Order);
@@ -235,6 +895,25 @@ fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<ProviderSummary, rusqlite::
         } else {
             ClaudeModels::default()
         },
+        response_fixer: response_fixer_from_json(&response_fixer_json),
+        disable_response_storage: row.get::<_, i64>("disable_response_storage")? != 0,
+        wire_api: wire_api_from_row(&row.get::<_, String>("wire_api")?),
+        exclude_from_stats: row.get::<_, i64>("exclude_from_stats")? != 0,
+        rpm_limit: row.get("rpm_limit")?,
+        tpm_limit: row.get("tpm_limit")?,
+        daily_quota_usd: row.get("daily_quota_usd")?,
+        monthly_quota_usd: row.get("monthly_quota_usd")?,
+        context_guard_limit_kb: row.get("context_guard_limit_kb")?,
+        upstream_proxy: row.get("upstream_proxy")?,
+        passthrough: row.get::<_, i64>("passthrough")? != 0,
+        request_signing: request_signing_from_json(&row.get::<_, String>("request_signing_json")?)
+            .redacted(),
+        upstream_tls: upstream_tls_from_json(&row.get::<_, String>("upstream_tls_json")?)
+            .redacted(),
+        header_rules: header_rules_from_json(&row.get::<_, String>("header_rules_json")?),
+        body_patch_rules: body_patch_rules_from_json(
+            &row.get::<_, String>("body_patch_rules_json")?,
+        ),
         enabled: row.get::<_, i64>("enabled")? != 0,
         priority: row.get("priority")?,
         cost_multiplier: row.get("cost_multiplier")?,
@@ -264,6 +943,21 @@ SELECT
   base_urls_json,
   base_url_mode,
   claude_models_json,
+  response_fixer_json,
+  disable_response_storage,
+  wire_api,
+  exclude_from_stats,
+  rpm_limit,
+  tpm_limit,
+  daily_quota_usd,
+  monthly_quota_usd,
+  context_guard_limit_kb,
+  upstream_proxy,
+  passthrough,
+  request_signing_json,
+  upstream_tls_json,
+  header_rules_json,
+  body_patch_rules_json,
   enabled,
   priority,
   cost_multiplier,
@@ -280,6 +974,23 @@ WHERE id = ?1
     .ok_or_else(|| "DB_NOT_FOUND: provider not found".to_string())
 }
 
+/// `(cli_key, provider_id)` of every enabled provider (gateway health summary).
+pub(crate) fn enabled_ids_by_cli(db: &db::Db) -> Result<Vec<(String, i64)>, String> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare("SELECT cli_key, id FROM providers WHERE enabled = 1")
+        .map_err(|e| format!("DB_ERROR: failed to prepare query: {e}"))?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| format!("DB_ERROR: failed to list enabled providers: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("DB_ERROR: failed to read provider row: {e}"))?);
+    }
+    Ok(items)
+}
+
 pub fn names_by_id(db: &db::Db, provider_ids: &[i64]) -> Result<HashMap<i64, String>, String> {
     let ids: Vec<i64> = provider_ids
         .iter()
@@ -338,6 +1049,21 @@ SELECT
   base_urls_json,
   base_url_mode,
   claude_models_json,
+  response_fixer_json,
+  disable_response_storage,
+  wire_api,
+  exclude_from_stats,
+  rpm_limit,
+  tpm_limit,
+  daily_quota_usd,
+  monthly_quota_usd,
+  context_guard_limit_kb,
+  upstream_proxy,
+  passthrough,
+  request_signing_json,
+  upstream_tls_json,
+  header_rules_json,
+  body_patch_rules_json,
   enabled,
   priority,
   cost_multiplier,
@@ -362,6 +1088,48 @@ ORDER BY sort_order ASC, id DESC
     Ok(items)
 }
 
+fn row_to_gateway_provider(
+    row: &rusqlite::Row<'_>,
+    cli_key: &str,
+) -> Result<ProviderForGateway, rusqlite::Error> {
+    let base_url_fallback: String = row.get("base_url")?;
+    let base_urls_json: String = row.get("base_urls_json")?;
+    let base_url_mode_raw: String = row.get("base_url_mode")?;
+    let claude_models_json: String = row.get("claude_models_json")?;
+    let response_fixer_json: String = row.get("response_fixer_json")?;
+    let base_url_mode =
+        ProviderBaseUrlMode::parse(&base_url_mode_raw).unwrap_or(ProviderBaseUrlMode::Order);
+    Ok(ProviderForGateway {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        base_urls: base_urls_from_row(&base_url_fallback, &base_urls_json),
+        base_url_mode,
+        api_key_plaintext: row.get("api_key_plaintext")?,
+        claude_models: if cli_key == "claude" {
+            claude_models_from_json(&claude_models_json)
+        } else {
+            ClaudeModels::default()
+        },
+        response_fixer: response_fixer_from_json(&response_fixer_json),
+        disable_response_storage: row.get::<_, i64>("disable_response_storage")? != 0,
+        wire_api: wire_api_from_row(&row.get::<_, String>("wire_api")?),
+        exclude_from_stats: row.get::<_, i64>("exclude_from_stats")? != 0,
+        rpm_limit: row.get("rpm_limit")?,
+        tpm_limit: row.get("tpm_limit")?,
+        daily_quota_usd: row.get("daily_quota_usd")?,
+        monthly_quota_usd: row.get("monthly_quota_usd")?,
+        context_guard_limit_kb: row.get("context_guard_limit_kb")?,
+        upstream_proxy: row.get("upstream_proxy")?,
+        passthrough: row.get::<_, i64>("passthrough")? != 0,
+        request_signing: request_signing_from_json(&row.get::<_, String>("request_signing_json")?),
+        upstream_tls: upstream_tls_from_json(&row.get::<_, String>("upstream_tls_json")?),
+        header_rules: header_rules_from_json(&row.get::<_, String>("header_rules_json")?),
+        body_patch_rules: body_patch_rules_from_json(
+            &row.get::<_, String>("body_patch_rules_json")?,
+        ),
+    })
+}
+
 fn list_enabled_for_gateway_in_sort_mode(
     conn: &Connection,
     cli_key: &str,
@@ -377,7 +1145,22 @@ SELECT
   p.base_urls_json,
   p.base_url_mode,
   p.api_key_plaintext,
-  p.claude_models_json
+  p.claude_models_json,
+  p.response_fixer_json,
+  p.disable_response_storage,
+  p.wire_api,
+  p.exclude_from_stats,
+  p.rpm_limit,
+  p.tpm_limit,
+  p.daily_quota_usd,
+  p.monthly_quota_usd,
+  p.context_guard_limit_kb,
+  p.upstream_proxy,
+  p.passthrough,
+  p.request_signing_json,
+  p.upstream_tls_json,
+  p.header_rules_json,
+  p.body_patch_rules_json
 FROM sort_mode_providers mp
 JOIN providers p ON p.id = mp.provider_id
 WHERE mp.mode_id = ?1
@@ -391,24 +1174,7 @@ ORDER BY mp.sort_order ASC
 
     let rows = stmt
         .query_map(params![mode_id, cli_key], |row| {
-            let base_url_fallback: String = row.get("base_url")?;
-            let base_urls_json: String = row.get("base_urls_json")?;
-            let base_url_mode_raw: String = row.get("base_url_mode")?;
-            let claude_models_json: String = row.get("claude_models_json")?;
-            let base_url_mode = ProviderBaseUrlMode::parse(&base_url_mode_raw)
-                .unwrap_or(ProviderBaseUrlMode::Order);
-            Ok(ProviderForGateway {
-                id: row.get("id")?,
-                name: row.get("name")?,
-                base_urls: base_urls_from_row(&base_url_fallback, &base_urls_json),
-                base_url_mode,
-                api_key_plaintext: row.get("api_key_plaintext")?,
-                claude_models: if cli_key == "claude" {
-                    claude_models_from_json(&claude_models_json)
-                } else {
-                    ClaudeModels::default()
-                },
-            })
+            row_to_gateway_provider(row, cli_key)
         })
         .map_err(|e| format!("DB_ERROR: failed to list gateway sort_mode providers: {e}"))?;
 
@@ -433,7 +1199,22 @@ SELECT
   base_urls_json,
   base_url_mode,
   api_key_plaintext,
-  claude_models_json
+  claude_models_json,
+  response_fixer_json,
+  disable_response_storage,
+  wire_api,
+  exclude_from_stats,
+  rpm_limit,
+  tpm_limit,
+  daily_quota_usd,
+  monthly_quota_usd,
+  context_guard_limit_kb,
+  upstream_proxy,
+  passthrough,
+  request_signing_json,
+  upstream_tls_json,
+  header_rules_json,
+  body_patch_rules_json
 FROM providers
 WHERE cli_key = ?1
   AND enabled = 1
@@ -444,24 +1225,7 @@ ORDER BY sort_order ASC, id DESC
 
     let rows = stmt
         .query_map(params![cli_key], |row| {
-            let base_url_fallback: String = row.get("base_url")?;
-            let base_urls_json: String = row.get("base_urls_json")?;
-            let base_url_mode_raw: String = row.get("base_url_mode")?;
-            let claude_models_json: String = row.get("claude_models_json")?;
-            let base_url_mode = ProviderBaseUrlMode::parse(&base_url_mode_raw)
-                .unwrap_or(ProviderBaseUrlMode::Order);
-            Ok(ProviderForGateway {
-                id: row.get("id")?,
-                name: row.get("name")?,
-                base_urls: base_urls_from_row(&base_url_fallback, &base_urls_json),
-                base_url_mode,
-                api_key_plaintext: row.get("api_key_plaintext")?,
-                claude_models: if cli_key == "claude" {
-                    claude_models_from_json(&claude_models_json)
-                } else {
-                    ClaudeModels::default()
-                },
-            })
+            row_to_gateway_provider(row, cli_key)
         })
         .map_err(|e| format!("DB_ERROR: failed to list gateway providers: {e}"))?;
 
@@ -472,50 +1236,84 @@ ORDER BY sort_order ASC, id DESC
     Ok(items)
 }
 
-pub(crate) fn list_enabled_for_gateway_using_active_mode(
-    db: &db::Db,
+/// Sort mode currently active for `cli_key` (`None` = default order).
+pub(crate) fn active_sort_mode_id(conn: &Connection, cli_key: &str) -> Result<Option<i64>, String> {
+    conn.query_row(
+        "SELECT mode_id FROM sort_mode_active WHERE cli_key = ?1",
+        params![cli_key],
+        |row| row.get::<_, Option<i64>>(0),
+    )
+    .optional()
+    .map_err(|e| format!("DB_ERROR: failed to query sort_mode_active: {e}"))
+    .map(Option::flatten)
+}
+
+pub(crate) fn list_enabled_for_gateway_in_mode(
+    conn: &Connection,
     cli_key: &str,
-) -> Result<GatewayProvidersSelection, String> {
+    sort_mode_id: Option<i64>,
+) -> Result<Vec<ProviderForGateway>, String> {
     validate_cli_key(cli_key)?;
-    let conn = db.open_connection()?;
 
-    let active_mode_id: Option<i64> = conn
-        .query_row(
-            "SELECT mode_id FROM sort_mode_active WHERE cli_key = ?1",
-            params![cli_key],
-            |row| row.get::<_, Option<i64>>(0),
-        )
-        .optional()
-        .map_err(|e| format!("DB_ERROR: failed to query sort_mode_active: {e}"))?
-        .flatten();
-
-    if let Some(mode_id) = active_mode_id {
-        let providers = list_enabled_for_gateway_in_sort_mode(&conn, cli_key, mode_id)?;
-        return Ok(GatewayProvidersSelection {
-            sort_mode_id: Some(mode_id),
-            providers,
-        });
+    match sort_mode_id {
+        Some(mode_id) => list_enabled_for_gateway_in_sort_mode(conn, cli_key, mode_id),
+        None => list_enabled_for_gateway_default(conn, cli_key),
     }
-
-    let providers = list_enabled_for_gateway_default(&conn, cli_key)?;
-    Ok(GatewayProvidersSelection {
-        sort_mode_id: None,
-        providers,
-    })
 }
 
-pub(crate) fn list_enabled_for_gateway_in_mode(
+/// Loads providers of `cli_key` by id (enabled or not), keeping the order of `provider_ids`.
+pub(crate) fn list_for_gateway_by_ids(
     db: &db::Db,
     cli_key: &str,
-    sort_mode_id: Option<i64>,
+    provider_ids: &[i64],
 ) -> Result<Vec<ProviderForGateway>, String> {
     validate_cli_key(cli_key)?;
     let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  id,
+  name,
+  base_url,
+  base_urls_json,
+  base_url_mode,
+  api_key_plaintext,
+  claude_models_json,
+  response_fixer_json,
+  disable_response_storage,
+  wire_api,
+  exclude_from_stats,
+  rpm_limit,
+  tpm_limit,
+  daily_quota_usd,
+  monthly_quota_usd,
+  context_guard_limit_kb,
+  upstream_proxy,
+  passthrough,
+  request_signing_json,
+  upstream_tls_json,
+  header_rules_json,
+  body_patch_rules_json
+FROM providers
+WHERE id = ?1
+  AND cli_key = ?2
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare provider query: {e}"))?;
 
-    match sort_mode_id {
-        Some(mode_id) => list_enabled_for_gateway_in_sort_mode(&conn, cli_key, mode_id),
-        None => list_enabled_for_gateway_default(&conn, cli_key),
+    let mut items = Vec::with_capacity(provider_ids.len());
+    for provider_id in provider_ids {
+        let item = stmt
+            .query_row(params![provider_id, cli_key], |row| {
+                row_to_gateway_provider(row, cli_key)
+            })
+            .optional()
+            .map_err(|e| format!("DB_ERROR: failed to query provider: {e}"))?
+            .ok_or_else(|| format!("DB_NOT_FOUND: provider not found: id={provider_id}"))?;
+        items.push(item);
     }
+    Ok(items)
 }
 
 fn next_sort_order(conn: &Connection, cli_key: &str) -> Result<i64, String> {
@@ -540,6 +1338,21 @@ pub fn upsert(
     cost_multiplier: f64,
     priority: Option<i64>,
     claude_models: Option<ClaudeModels>,
+    response_fixer: Option<ResponseFixerOverride>,
+    disable_response_storage: Option<bool>,
+    wire_api: Option<&str>,
+    exclude_from_stats: Option<bool>,
+    rpm_limit: Option<i64>,
+    tpm_limit: Option<i64>,
+    daily_quota_usd: Option<f64>,
+    monthly_quota_usd: Option<f64>,
+    context_guard_limit_kb: Option<i64>,
+    upstream_proxy: Option<&str>,
+    passthrough: Option<bool>,
+    request_signing: Option<RequestSigning>,
+    upstream_tls: Option<UpstreamTls>,
+    header_rules: Option<Vec<ProviderHeaderRule>>,
+    body_patch_rules: Option<Vec<BodyPatchRule>>,
 ) -> Result<ProviderSummary, String> {
     let cli_key = cli_key.trim();
     validate_cli_key(cli_key)?;
@@ -569,6 +1382,67 @@ pub fn upsert(
         }
     }
 
+    if rpm_limit.is_some_and(|v| !(0..=MAX_RPM_LIMIT).contains(&v)) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: rpm_limit must be within [0, {MAX_RPM_LIMIT}]"
+        ));
+    }
+    if tpm_limit.is_some_and(|v| !(0..=MAX_TPM_LIMIT).contains(&v)) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: tpm_limit must be within [0, {MAX_TPM_LIMIT}]"
+        ));
+    }
+
+    for (field, quota) in [
+        ("daily_quota_usd", daily_quota_usd),
+        ("monthly_quota_usd", monthly_quota_usd),
+    ] {
+        if quota.is_some_and(|v| !v.is_finite() || !(0.0..=MAX_QUOTA_USD).contains(&v)) {
+            return Err(format!(
+                "SEC_INVALID_INPUT: {field} must be within [0, {MAX_QUOTA_USD}]"
+            ));
+        }
+    }
+
+    if context_guard_limit_kb.is_some_and(|v| !(0..=MAX_CONTEXT_GUARD_LIMIT_KB).contains(&v)) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: context_guard_limit_kb must be within [0, {MAX_CONTEXT_GUARD_LIMIT_KB}]"
+        ));
+    }
+
+    let upstream_proxy = upstream_proxy.map(normalize_upstream_proxy).transpose()?;
+
+    if let Some(response_fixer) = response_fixer.as_ref() {
+        response_fixer.validate()?;
+    }
+    let response_fixer_json = response_fixer
+        .map(|v| serde_json::to_string(&v).map_err(|e| format!("SYSTEM_ERROR: {e}")))
+        .transpose()?;
+    let header_rules_json = header_rules
+        .map(normalize_header_rules)
+        .transpose()?
+        .map(|v| serde_json::to_string(&v).map_err(|e| format!("SYSTEM_ERROR: {e}")))
+        .transpose()?;
+    let body_patch_rules_json = body_patch_rules
+        .map(normalize_body_patch_rules)
+        .transpose()?
+        .map(|v| serde_json::to_string(&v).map_err(|e| format!("SYSTEM_ERROR: {e}")))
+        .transpose()?;
+
+    let wire_api = wire_api
+        .map(|raw| {
+            ProviderWireApi::parse(raw).ok_or_else(|| {
+                "SEC_INVALID_INPUT: wire_api must be 'native' or 'chat_completions'".to_string()
+            })
+        })
+        .transpose()?;
+    if let Some(wire_api) = wire_api.filter(|v| !v.supports_cli(cli_key)) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: wire_api={} is not supported for cli_key={cli_key}",
+            wire_api.as_str()
+        ));
+    }
+
     let mut conn = db.open_connection()?;
     let now = now_unix_seconds();
 
@@ -577,6 +1451,7 @@ pub fn upsert(
             let priority = priority.unwrap_or(DEFAULT_PRIORITY);
             let api_key =
                 api_key.ok_or_else(|| "SEC_INVALID_INPUT: api_key is required".to_string())?;
+            validate_passthrough(passthrough.unwrap_or(false), wire_api.unwrap_or_default())?;
             let sort_order = next_sort_order(&conn, cli_key)?;
 
             let claude_models = if cli_key == "claude" {
@@ -586,6 +1461,12 @@ pub fn upsert(
             };
             let claude_models_json =
                 serde_json::to_string(&claude_models).map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+            let request_signing_json =
+                serde_json::to_string(&request_signing.unwrap_or_default().normalized(None)?)
+                    .map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+            let upstream_tls_json =
+                serde_json::to_string(&upstream_tls.unwrap_or_default().normalized(None)?)
+                    .map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
 
             conn.execute(
                 r#"
@@ -596,6 +1477,21 @@ INSERT INTO providers(
   base_urls_json,
   base_url_mode,
   claude_models_json,
+  response_fixer_json,
+  disable_response_storage,
+  wire_api,
+  exclude_from_stats,
+  rpm_limit,
+  tpm_limit,
+  daily_quota_usd,
+  monthly_quota_usd,
+  context_guard_limit_kb,
+  upstream_proxy,
+  passthrough,
+  request_signing_json,
+  upstream_tls_json,
+  header_rules_json,
+  body_patch_rules_json,
   supported_models_json,
   model_mapping_json,
   api_key_plaintext,
@@ -605,7 +1501,7 @@ INSERT INTO providers(
   cost_multiplier,
   created_at,
   updated_at
-) VALUES (?1, ?2, ?3, ?4, ?5, ?6, '{}', '{}', ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, '{}', '{}', ?22, ?23, ?24, ?25, ?26, ?27, ?28)
 "#,
                 params![
                     cli_key,
@@ -614,6 +1510,21 @@ INSERT INTO providers(
                     base_urls_json,
                     base_url_mode.as_str(),
                     claude_models_json,
+                    response_fixer_json.as_deref().unwrap_or("{}"),
+                    enabled_to_int(disable_response_storage.unwrap_or(false)),
+                    wire_api.unwrap_or_default().as_str(),
+                    enabled_to_int(exclude_from_stats.unwrap_or(false)),
+                    rpm_limit.unwrap_or(0),
+                    tpm_limit.unwrap_or(0),
+                    daily_quota_usd.unwrap_or(0.0),
+                    monthly_quota_usd.unwrap_or(0.0),
+                    context_guard_limit_kb.unwrap_or(0),
+                    upstream_proxy.as_deref().unwrap_or(UPSTREAM_PROXY_SYSTEM),
+                    enabled_to_int(passthrough.unwrap_or(false)),
+                    request_signing_json,
+                    upstream_tls_json,
+                    header_rules_json.as_deref().unwrap_or("[]"),
+                    body_patch_rules_json.as_deref().unwrap_or("[]"),
                     api_key,
                     sort_order,
                     enabled_to_int(enabled),
@@ -635,6 +1546,7 @@ INSERT INTO providers(
             })?;
 
             let id = conn.last_insert_rowid();
+            provider_snapshot::invalidate();
             get_by_id(&conn, id)
         }
         Some(id) => {
@@ -642,11 +1554,58 @@ INSERT INTO providers(
                 .transaction()
                 .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
 
-            let existing: Option<(String, String, i64, String)> = tx
+            // cli_key, api_key, priority, claude_models_json, response_fixer_json,
+            // disable_response_storage, wire_api, exclude_from_stats, rpm_limit, tpm_limit,
+            // daily_quota_usd, monthly_quota_usd, context_guard_limit_kb, upstream_proxy, passthrough,
+            // request_signing_json, upstream_tls_json, header_rules_json, body_patch_rules_json
+            type ExistingProviderRow = (
+                String,
+                String,
+                i64,
+                String,
+                String,
+                i64,
+                String,
+                i64,
+                i64,
+                i64,
+                f64,
+                f64,
+                i64,
+                String,
+                i64,
+                String,
+                String,
+                String,
+                String,
+            );
+            let existing: Option<ExistingProviderRow> = tx
                 .query_row(
-                    "SELECT cli_key, api_key_plaintext, priority, claude_models_json FROM providers WHERE id = ?1",
+                    "SELECT cli_key, api_key_plaintext, priority, claude_models_json, response_fixer_json, disable_response_storage, wire_api, exclude_from_stats, rpm_limit, tpm_limit, daily_quota_usd, monthly_quota_usd, context_guard_limit_kb, upstream_proxy, passthrough, request_signing_json, upstream_tls_json, header_rules_json, body_patch_rules_json FROM providers WHERE id = ?1",
                     params![id],
-                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                    |row| {
+                        Ok((
+                            row.get(0)?,
+                            row.get(1)?,
+                            row.get(2)?,
+                            row.get(3)?,
+                            row.get(4)?,
+                            row.get(5)?,
+                            row.get(6)?,
+                            row.get(7)?,
+                            row.get(8)?,
+                            row.get(9)?,
+                            row.get(10)?,
+                            row.get(11)?,
+                            row.get(12)?,
+                            row.get(13)?,
+                            row.get(14)?,
+                            row.get(15)?,
+                            row.get(16)?,
+                            row.get(17)?,
+                            row.get(18)?,
+                        ))
+                    },
                 )
                 .optional()
                 .map_err(|e| format!("DB_ERROR: failed to query provider: {e}"))?;
@@ -656,6 +1615,21 @@ INSERT INTO providers(
                 existing_api_key,
                 existing_priority,
                 existing_claude_models_json,
+                existing_response_fixer_json,
+                existing_disable_response_storage,
+                existing_wire_api,
+                existing_exclude_from_stats,
+                existing_rpm_limit,
+                existing_tpm_limit,
+                existing_daily_quota_usd,
+                existing_monthly_quota_usd,
+                existing_context_guard_limit_kb,
+                existing_upstream_proxy,
+                existing_passthrough,
+                existing_request_signing_json,
+                existing_upstream_tls_json,
+                existing_header_rules_json,
+                existing_body_patch_rules_json,
             )) = existing
             else {
                 return Err("DB_NOT_FOUND: provider not found".to_string());
@@ -689,6 +1663,46 @@ INSERT INTO providers(
                 "{}".to_string()
             };
 
+            let next_response_fixer_json =
+                response_fixer_json.unwrap_or(existing_response_fixer_json);
+            let next_disable_response_storage = disable_response_storage
+                .map(enabled_to_int)
+                .unwrap_or(existing_disable_response_storage);
+            let next_wire_api = wire_api.unwrap_or_else(|| wire_api_from_row(&existing_wire_api));
+            let next_exclude_from_stats = exclude_from_stats
+                .map(enabled_to_int)
+                .unwrap_or(existing_exclude_from_stats);
+            let next_rpm_limit = rpm_limit.unwrap_or(existing_rpm_limit);
+            let next_tpm_limit = tpm_limit.unwrap_or(existing_tpm_limit);
+            let next_daily_quota_usd = daily_quota_usd.unwrap_or(existing_daily_quota_usd);
+            let next_monthly_quota_usd = monthly_quota_usd.unwrap_or(existing_monthly_quota_usd);
+            let next_context_guard_limit_kb =
+                context_guard_limit_kb.unwrap_or(existing_context_guard_limit_kb);
+            let next_upstream_proxy = upstream_proxy.unwrap_or(existing_upstream_proxy);
+            let next_passthrough = passthrough
+                .map(enabled_to_int)
+                .unwrap_or(existing_passthrough);
+            validate_passthrough(next_passthrough != 0, next_wire_api)?;
+            let next_request_signing_json = match request_signing {
+                Some(request_signing) => {
+                    let existing = request_signing_from_json(&existing_request_signing_json);
+                    serde_json::to_string(&request_signing.normalized(Some(&existing.secret))?)
+                        .map_err(|e| format!("SYSTEM_ERROR: {e}"))?
+                }
+                None => existing_request_signing_json,
+            };
+            let next_upstream_tls_json = match upstream_tls {
+                Some(upstream_tls) => {
+                    let existing = upstream_tls_from_json(&existing_upstream_tls_json);
+                    serde_json::to_string(&upstream_tls.normalized(Some(&existing.client_key_pem))?)
+                        .map_err(|e| format!("SYSTEM_ERROR: {e}"))?
+                }
+                None => existing_upstream_tls_json,
+            };
+            let next_header_rules_json = header_rules_json.unwrap_or(existing_header_rules_json);
+            let next_body_patch_rules_json =
+                body_patch_rules_json.unwrap_or(existing_body_patch_rules_json);
+
             tx.execute(
                 r#"
 UPDATE providers
@@ -698,14 +1712,29 @@ SET
   base_urls_json = ?3,
   base_url_mode = ?4,
   claude_models_json = ?5,
+  response_fixer_json = ?6,
+  disable_response_storage = ?7,
+  wire_api = ?8,
+  exclude_from_stats = ?9,
+  rpm_limit = ?10,
+  tpm_limit = ?11,
+  daily_quota_usd = ?12,
+  monthly_quota_usd = ?13,
+  context_guard_limit_kb = ?14,
+  upstream_proxy = ?15,
+  passthrough = ?16,
+  request_signing_json = ?17,
+  upstream_tls_json = ?18,
+  header_rules_json = ?19,
+  body_patch_rules_json = ?20,
   supported_models_json = '{}',
   model_mapping_json = '{}',
-  api_key_plaintext = ?6,
-  enabled = ?7,
-  cost_multiplier = ?8,
-  priority = ?9,
-  updated_at = ?10
-WHERE id = ?11
+  api_key_plaintext = ?21,
+  enabled = ?22,
+  cost_multiplier = ?23,
+  priority = ?24,
+  updated_at = ?25
+WHERE id = ?26
 "#,
                 params![
                     name,
@@ -713,6 +1742,21 @@ WHERE id = ?11
                     base_urls_json,
                     base_url_mode.as_str(),
                     next_claude_models_json,
+                    next_response_fixer_json,
+                    next_disable_response_storage,
+                    next_wire_api.as_str(),
+                    next_exclude_from_stats,
+                    next_rpm_limit,
+                    next_tpm_limit,
+                    next_daily_quota_usd,
+                    next_monthly_quota_usd,
+                    next_context_guard_limit_kb,
+                    next_upstream_proxy,
+                    next_passthrough,
+                    next_request_signing_json,
+                    next_upstream_tls_json,
+                    next_header_rules_json,
+                    next_body_patch_rules_json,
                     next_api_key,
                     enabled_to_int(enabled),
                     cost_multiplier,
@@ -730,6 +1774,7 @@ WHERE id = ?11
 
             tx.commit()
                 .map_err(|e| format!("DB_ERROR: failed to commit: {e}"))?;
+            provider_snapshot::invalidate();
 
             get_by_id(&conn, id)
         }
@@ -753,6 +1798,31 @@ pub fn set_enabled(
     if changed == 0 {
         return Err("DB_NOT_FOUND: provider not found".to_string());
     }
+    provider_snapshot::invalidate();
+
+    get_by_id(&conn, provider_id)
+}
+
+pub fn set_claude_models(
+    db: &db::Db,
+    provider_id: i64,
+    claude_models: ClaudeModels,
+) -> Result<ProviderSummary, String> {
+    let claude_models_json = serde_json::to_string(&claude_models.normalized())
+        .map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+    let conn = db.open_connection()?;
+    let now = now_unix_seconds();
+    let changed = conn
+        .execute(
+            "UPDATE providers SET claude_models_json = ?1, updated_at = ?2 WHERE id = ?3 AND cli_key = 'claude'",
+            params![claude_models_json, now, provider_id],
+        )
+        .map_err(|e| format!("DB_ERROR: failed to update provider: {e}"))?;
+
+    if changed == 0 {
+        return Err("DB_NOT_FOUND: claude provider not found".to_string());
+    }
+    provider_snapshot::invalidate();
 
     get_by_id(&conn, provider_id)
 }
@@ -766,6 +1836,7 @@ pub fn delete(db: &db::Db, provider_id: i64) -> Result<(), String> {
     if changed == 0 {
         return Err("DB_NOT_FOUND: provider not found".to_string());
     }
+    provider_snapshot::invalidate();
 
     Ok(())
 }
@@ -832,6 +1903,7 @@ pub fn reorder(
 
     tx.commit()
         .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+    provider_snapshot::invalidate();
 
     list_by_cli(db, cli_key)
 }
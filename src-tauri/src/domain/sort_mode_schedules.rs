@@ -0,0 +1,422 @@
+//! Usage: Sort mode schedules (switch the active sort mode by local time window) + switch history.
+//!
+//! - 规则按 `sort_order` 依次匹配，命中的第一条生效；没有规则命中时保持当前激活模式不变
+//! - `mode_id = NULL` 表示切回默认顺序（不使用排序模式）
+//! - 只在「命中的规则发生变化」时切换一次：窗口内手动切换的模式不会被反复覆盖
+//! - 切换只影响新请求；已绑定会话的请求继续使用绑定时的排序模式
+
+use crate::shared::time::{in_daily_window, local_minutes_of_day, now_unix_seconds};
+use crate::{blocking, db, provider_snapshot, settings, sort_modes};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::Emitter;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_RULES_PER_CLI: usize = 24;
+const SWITCH_EVENTS_DEFAULT_LIMIT: usize = 50;
+const SWITCH_EVENTS_MAX_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SortModeScheduleRule {
+    pub id: i64,
+    pub cli_key: String,
+    pub mode_id: Option<i64>,
+    pub start: String,
+    pub end: String,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SortModeScheduleRuleInput {
+    #[serde(default)]
+    pub mode_id: Option<i64>,
+    pub start: String,
+    pub end: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SortModeSwitchEvent {
+    pub id: i64,
+    pub created_at: i64,
+    pub cli_key: String,
+    pub from_mode_id: Option<i64>,
+    pub to_mode_id: Option<i64>,
+    pub schedule_id: Option<i64>,
+}
+
+fn format_hhmm(minutes: i64) -> String {
+    let minutes = minutes.clamp(0, 24 * 60 - 1);
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn row_to_rule(
+    row: &rusqlite::Row<'_>,
+) -> Result<(SortModeScheduleRule, u32, u32), rusqlite::Error> {
+    let start_minutes: i64 = row.get("start_minutes")?;
+    let end_minutes: i64 = row.get("end_minutes")?;
+    Ok((
+        SortModeScheduleRule {
+            id: row.get("id")?,
+            cli_key: row.get("cli_key")?,
+            mode_id: row.get("mode_id")?,
+            start: format_hhmm(start_minutes),
+            end: format_hhmm(end_minutes),
+            enabled: row.get::<_, i64>("enabled")? != 0,
+            created_at: row.get("created_at")?,
+            updated_at: row.get("updated_at")?,
+        },
+        start_minutes.clamp(0, 24 * 60 - 1) as u32,
+        end_minutes.clamp(0, 24 * 60 - 1) as u32,
+    ))
+}
+
+fn query_rules(
+    conn: &Connection,
+    cli_key: Option<&str>,
+    enabled_only: bool,
+) -> Result<Vec<(SortModeScheduleRule, u32, u32)>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT id, cli_key, mode_id, start_minutes, end_minutes, enabled, created_at, updated_at
+FROM sort_mode_schedules
+WHERE (?1 IS NULL OR cli_key = ?1)
+  AND (?2 = 0 OR enabled = 1)
+ORDER BY cli_key ASC, sort_order ASC, id ASC
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare sort_mode_schedules query: {e}"))?;
+    let rows = stmt
+        .query_map(params![cli_key, enabled_only as i64], row_to_rule)
+        .map_err(|e| format!("DB_ERROR: failed to list sort_mode_schedules: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(
+            row.map_err(|e| format!("DB_ERROR: failed to read sort_mode_schedules row: {e}"))?,
+        );
+    }
+    Ok(items)
+}
+
+pub fn list_rules(db: &db::Db, cli_key: Option<&str>) -> Result<Vec<SortModeScheduleRule>, String> {
+    let cli_key = cli_key.map(str::trim).filter(|v| !v.is_empty());
+    if let Some(cli_key) = cli_key {
+        crate::shared::cli_key::validate_cli_key(cli_key)?;
+    }
+    let conn = db.open_connection()?;
+    Ok(query_rules(&conn, cli_key, false)?
+        .into_iter()
+        .map(|(rule, _, _)| rule)
+        .collect())
+}
+
+/// Replaces all schedule rules of `cli_key` (order = match priority). An empty list disables scheduling.
+pub fn set_rules(
+    db: &db::Db,
+    cli_key: &str,
+    rules: Vec<SortModeScheduleRuleInput>,
+) -> Result<Vec<SortModeScheduleRule>, String> {
+    let cli_key = cli_key.trim();
+    crate::shared::cli_key::validate_cli_key(cli_key)?;
+    if rules.len() > MAX_RULES_PER_CLI {
+        return Err(format!(
+            "SEC_INVALID_INPUT: too many schedule rules (max={MAX_RULES_PER_CLI})"
+        ));
+    }
+
+    let mut conn = db.open_connection()?;
+    let mut parsed = Vec::with_capacity(rules.len());
+    for (idx, rule) in rules.iter().enumerate() {
+        let start = settings::parse_hhmm_minutes(&rule.start)
+            .ok_or_else(|| format!("SEC_INVALID_INPUT: rules[{idx}].start must be HH:MM"))?;
+        let end = settings::parse_hhmm_minutes(&rule.end)
+            .ok_or_else(|| format!("SEC_INVALID_INPUT: rules[{idx}].end must be HH:MM"))?;
+        if start == end {
+            return Err(format!(
+                "SEC_INVALID_INPUT: rules[{idx}] start and end must differ"
+            ));
+        }
+        if let Some(mode_id) = rule.mode_id {
+            sort_modes::ensure_mode_exists(&conn, mode_id)?;
+        }
+        parsed.push((rule.mode_id, start, end, rule.enabled));
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
+    tx.execute(
+        "DELETE FROM sort_mode_schedules WHERE cli_key = ?1",
+        params![cli_key],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to clear sort_mode_schedules: {e}"))?;
+
+    let now = now_unix_seconds();
+    for (idx, (mode_id, start, end, enabled)) in parsed.into_iter().enumerate() {
+        tx.execute(
+            r#"
+INSERT INTO sort_mode_schedules(
+  cli_key,
+  mode_id,
+  start_minutes,
+  end_minutes,
+  enabled,
+  sort_order,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+"#,
+            params![
+                cli_key,
+                mode_id,
+                start as i64,
+                end as i64,
+                enabled as i64,
+                idx as i64,
+                now,
+                now
+            ],
+        )
+        .map_err(|e| format!("DB_ERROR: failed to insert sort_mode_schedule: {e}"))?;
+    }
+
+    tx.commit()
+        .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+
+    list_rules(db, Some(cli_key))
+}
+
+pub fn list_switch_events(
+    db: &db::Db,
+    cli_key: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<SortModeSwitchEvent>, String> {
+    let cli_key = cli_key.map(str::trim).filter(|v| !v.is_empty());
+    let limit = limit
+        .unwrap_or(SWITCH_EVENTS_DEFAULT_LIMIT)
+        .clamp(1, SWITCH_EVENTS_MAX_LIMIT);
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT id, created_at, cli_key, from_mode_id, to_mode_id, schedule_id
+FROM sort_mode_switch_events
+WHERE (?1 IS NULL OR cli_key = ?1)
+ORDER BY id DESC
+LIMIT ?2
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare sort_mode_switch_events query: {e}"))?;
+    let rows = stmt
+        .query_map(params![cli_key, limit as i64], |row| {
+            Ok(SortModeSwitchEvent {
+                id: row.get("id")?,
+                created_at: row.get("created_at")?,
+                cli_key: row.get("cli_key")?,
+                from_mode_id: row.get("from_mode_id")?,
+                to_mode_id: row.get("to_mode_id")?,
+                schedule_id: row.get("schedule_id")?,
+            })
+        })
+        .map_err(|e| format!("DB_ERROR: failed to list sort_mode_switch_events: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(
+            row.map_err(|e| format!("DB_ERROR: failed to read sort_mode_switch_events row: {e}"))?,
+        );
+    }
+    Ok(items)
+}
+
+/// First enabled rule per CLI whose window contains `now_minutes`.
+fn matching_rules(
+    rules: Vec<(SortModeScheduleRule, u32, u32)>,
+    now_minutes: u32,
+) -> HashMap<String, SortModeScheduleRule> {
+    let mut matched: HashMap<String, SortModeScheduleRule> = HashMap::new();
+    for (rule, start, end) in rules {
+        if !rule.enabled || matched.contains_key(&rule.cli_key) {
+            continue;
+        }
+        if in_daily_window(now_minutes, start, end) {
+            matched.insert(rule.cli_key.clone(), rule);
+        }
+    }
+    matched
+}
+
+/// Rule of `cli_key` in effect at the current local time, if any.
+pub(crate) fn current_rule(
+    db: &db::Db,
+    cli_key: &str,
+) -> Result<Option<SortModeScheduleRule>, String> {
+    let conn = db.open_connection()?;
+    let mut matched = matching_rules(
+        query_rules(&conn, Some(cli_key), true)?,
+        local_minutes_of_day(),
+    );
+    Ok(matched.remove(cli_key))
+}
+
+/// Switches the active sort mode of `rule.cli_key` to `rule.mode_id` (no-op when already active).
+fn apply_rule(
+    conn: &mut Connection,
+    rule: &SortModeScheduleRule,
+    now: i64,
+) -> Result<Option<SortModeSwitchEvent>, String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
+    let current: Option<i64> = tx
+        .query_row(
+            "SELECT mode_id FROM sort_mode_active WHERE cli_key = ?1",
+            params![rule.cli_key],
+            |row| row.get::<_, Option<i64>>(0),
+        )
+        .optional()
+        .map_err(|e| format!("DB_ERROR: failed to query sort_mode_active: {e}"))?
+        .flatten();
+    if current == rule.mode_id {
+        return Ok(None);
+    }
+
+    tx.execute(
+        r#"
+INSERT INTO sort_mode_active(
+  cli_key,
+  mode_id,
+  updated_at
+) VALUES (?1, ?2, ?3)
+ON CONFLICT(cli_key) DO UPDATE SET
+  mode_id = excluded.mode_id,
+  updated_at = excluded.updated_at
+"#,
+        params![rule.cli_key, rule.mode_id, now],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to upsert sort_mode_active: {e}"))?;
+    tx.execute(
+        "INSERT INTO sort_mode_switch_events(created_at, cli_key, from_mode_id, to_mode_id, schedule_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![now, rule.cli_key, current, rule.mode_id, rule.id],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to insert sort_mode_switch_event: {e}"))?;
+    let id = tx.last_insert_rowid();
+    tx.commit()
+        .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+    provider_snapshot::invalidate();
+
+    Ok(Some(SortModeSwitchEvent {
+        id,
+        created_at: now,
+        cli_key: rule.cli_key.clone(),
+        from_mode_id: current,
+        to_mode_id: rule.mode_id,
+        schedule_id: Some(rule.id),
+    }))
+}
+
+/// One scheduler pass. `applied` maps cli_key -> rule id applied on the previous pass; returns the
+/// new map and the switches performed.
+fn tick(
+    conn: &mut Connection,
+    applied: &HashMap<String, i64>,
+    now_minutes: u32,
+    now: i64,
+) -> Result<(HashMap<String, i64>, Vec<SortModeSwitchEvent>), String> {
+    let matched = matching_rules(query_rules(conn, None, true)?, now_minutes);
+    let mut next = HashMap::with_capacity(matched.len());
+    let mut events = Vec::new();
+    for (cli_key, rule) in matched {
+        if applied.get(&cli_key) != Some(&rule.id) {
+            if let Some(event) = apply_rule(conn, &rule, now)? {
+                events.push(event);
+            }
+        }
+        next.insert(cli_key, rule.id);
+    }
+    Ok((next, events))
+}
+
+pub(crate) fn spawn_loop(app: tauri::AppHandle, db: db::Db) {
+    tauri::async_runtime::spawn(async move {
+        let mut applied: HashMap<String, i64> = HashMap::new();
+        loop {
+            let now_minutes = local_minutes_of_day();
+            let db = db.clone();
+            let prev = applied.clone();
+            let result = blocking::run("sort_mode_schedule_tick", move || {
+                let mut conn = db.open_connection()?;
+                tick(&mut conn, &prev, now_minutes, now_unix_seconds())
+            })
+            .await;
+            match result {
+                Ok((next, events)) => {
+                    applied = next;
+                    for event in events {
+                        tracing::info!(
+                            cli_key = %event.cli_key,
+                            from_mode_id = ?event.from_mode_id,
+                            to_mode_id = ?event.to_mode_id,
+                            "排序模式已按计划切换"
+                        );
+                        let _ = app.emit("sort_mode:switched", &event);
+                    }
+                }
+                Err(err) => tracing::warn!("排序模式计划执行失败: {}", err),
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: i64, cli_key: &str, mode_id: Option<i64>, enabled: bool) -> SortModeScheduleRule {
+        SortModeScheduleRule {
+            id,
+            cli_key: cli_key.to_string(),
+            mode_id,
+            start: String::new(),
+            end: String::new(),
+            enabled,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn matching_rules_picks_first_enabled_window_per_cli() {
+        let rules = vec![
+            (rule(1, "claude", Some(10), false), 0, 24 * 60 - 1),
+            (rule(2, "claude", Some(11), true), 0, 8 * 60),
+            (rule(3, "claude", Some(12), true), 8 * 60, 0),
+            (rule(4, "codex", None, true), 22 * 60, 6 * 60),
+        ];
+        let matched = matching_rules(rules.clone(), 3 * 60);
+        assert_eq!(matched.get("claude").map(|r| r.id), Some(2));
+        assert_eq!(matched.get("codex").map(|r| r.id), Some(4));
+
+        let matched = matching_rules(rules, 12 * 60);
+        assert_eq!(matched.get("claude").map(|r| r.id), Some(3));
+        assert!(!matched.contains_key("codex"));
+    }
+
+    #[test]
+    fn format_hhmm_pads() {
+        assert_eq!(format_hhmm(0), "00:00");
+        assert_eq!(format_hhmm(8 * 60 + 5), "08:05");
+    }
+}
@@ -1,19 +1,163 @@
 //! Usage: Sort mode persistence and provider ordering configuration helpers.
 
-use crate::db;
 use crate::shared::time::now_unix_seconds;
+use crate::{db, provider_snapshot};
 use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
-use serde::Serialize;
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+mod share;
+
+pub use share::{
+    export_mode, import_mode, preview_import, SortModeImportPreview, SortModeImportResult,
+};
+
+const MAX_PROVIDER_WEIGHT: u32 = 100;
+const DEFAULT_PROVIDER_WEIGHT: u32 = 1;
+const MAX_MODE_NAME_CHARS: usize = 32;
+
+/// How the gateway picks the first provider of a sort mode (failover always walks the rest).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortModeStrategy {
+    #[default]
+    Ordered,
+    Weighted,
+    RoundRobin,
+}
+
+impl SortModeStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ordered => "ordered",
+            Self::Weighted => "weighted",
+            Self::RoundRobin => "round_robin",
+        }
+    }
+
+    fn from_db(raw: &str) -> Self {
+        match raw {
+            "weighted" => Self::Weighted,
+            "round_robin" => Self::RoundRobin,
+            _ => Self::Ordered,
+        }
+    }
+}
+
+/// Gateway settings applied while a sort mode is in effect; `None` inherits the global setting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortModeSettingsOverride {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failover_max_attempts_per_provider: Option<u32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failover_max_providers_to_try: Option<u32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream_first_byte_timeout_seconds: Option<u32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream_stream_idle_timeout_seconds: Option<u32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream_request_timeout_non_streaming_seconds: Option<u32>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_response_fixer: Option<bool>,
+}
+
+impl SortModeSettingsOverride {
+    fn validate(&self) -> Result<(), String> {
+        let ranges = [
+            (
+                "failover_max_attempts_per_provider",
+                self.failover_max_attempts_per_provider,
+                1,
+                20,
+            ),
+            (
+                "failover_max_providers_to_try",
+                self.failover_max_providers_to_try,
+                1,
+                20,
+            ),
+            (
+                "upstream_first_byte_timeout_seconds",
+                self.upstream_first_byte_timeout_seconds,
+                0,
+                60 * 60,
+            ),
+            (
+                "upstream_stream_idle_timeout_seconds",
+                self.upstream_stream_idle_timeout_seconds,
+                0,
+                60 * 60,
+            ),
+            (
+                "upstream_request_timeout_non_streaming_seconds",
+                self.upstream_request_timeout_non_streaming_seconds,
+                0,
+                24 * 60 * 60,
+            ),
+        ];
+        for (field, value, min, max) in ranges {
+            if let Some(value) = value {
+                if !(min..=max).contains(&value) {
+                    return Err(format!(
+                        "SEC_INVALID_INPUT: settings.{field} must be within [{min}, {max}]"
+                    ));
+                }
+            }
+        }
+        if let (Some(attempts), Some(providers)) = (
+            self.failover_max_attempts_per_provider,
+            self.failover_max_providers_to_try,
+        ) {
+            if attempts.saturating_mul(providers) > 100 {
+                return Err(
+                    "SEC_INVALID_INPUT: settings.failover_max_attempts_per_provider * failover_max_providers_to_try must be <= 100"
+                        .to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn settings_from_json(raw: &str) -> SortModeSettingsOverride {
+    serde_json::from_str::<SortModeSettingsOverride>(raw)
+        .ok()
+        .unwrap_or_default()
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SortModeSummary {
     pub id: i64,
     pub name: String,
+    pub settings: SortModeSettingsOverride,
+    pub strategy: SortModeStrategy,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct SortModeProviderWeight {
+    pub provider_id: i64,
+    pub weight: u32,
+}
+
+/// Strategy + per-provider weights used by the gateway to reorder a sort mode's providers.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SortModeRouting {
+    pub(crate) strategy: SortModeStrategy,
+    pub(crate) weights: HashMap<i64, u32>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SortModeActiveRow {
     pub cli_key: String,
@@ -31,8 +175,10 @@ fn validate_mode_name(name: &str) -> Result<String, String> {
         return Err("SEC_INVALID_INPUT: mode name is required".to_string());
     }
 
-    if name.chars().count() > 32 {
-        return Err("SEC_INVALID_INPUT: mode name is too long (max 32 chars)".to_string());
+    if name.chars().count() > MAX_MODE_NAME_CHARS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: mode name is too long (max {MAX_MODE_NAME_CHARS} chars)"
+        ));
     }
 
     let lowered = name.to_ascii_lowercase();
@@ -44,15 +190,18 @@ fn validate_mode_name(name: &str) -> Result<String, String> {
 }
 
 fn row_to_mode_summary(row: &rusqlite::Row<'_>) -> Result<SortModeSummary, rusqlite::Error> {
+    let settings_json: String = row.get("settings_json")?;
     Ok(SortModeSummary {
         id: row.get("id")?,
         name: row.get("name")?,
+        settings: settings_from_json(&settings_json),
+        strategy: SortModeStrategy::from_db(&row.get::<_, String>("strategy")?),
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
 }
 
-fn ensure_mode_exists(conn: &Connection, mode_id: i64) -> Result<(), String> {
+pub(crate) fn ensure_mode_exists(conn: &Connection, mode_id: i64) -> Result<(), String> {
     if mode_id <= 0 {
         return Err("SEC_INVALID_INPUT: invalid mode_id".to_string());
     }
@@ -105,6 +254,8 @@ pub fn list_modes(db: &db::Db) -> Result<Vec<SortModeSummary>, String> {
 SELECT
   id,
   name,
+  settings_json,
+  strategy,
   created_at,
   updated_at
 FROM sort_modes
@@ -154,6 +305,8 @@ INSERT INTO sort_modes(
 SELECT
   id,
   name,
+  settings_json,
+  strategy,
   created_at,
   updated_at
 FROM sort_modes
@@ -189,6 +342,45 @@ pub fn rename_mode(db: &db::Db, mode_id: i64, name: &str) -> Result<SortModeSumm
 SELECT
   id,
   name,
+  settings_json,
+  strategy,
+  created_at,
+  updated_at
+FROM sort_modes
+WHERE id = ?1
+"#,
+        params![mode_id],
+        row_to_mode_summary,
+    )
+    .map_err(|e| format!("DB_ERROR: failed to query sort_mode: {e}"))
+}
+
+pub fn set_mode_settings(
+    db: &db::Db,
+    mode_id: i64,
+    settings: SortModeSettingsOverride,
+) -> Result<SortModeSummary, String> {
+    settings.validate()?;
+    let settings_json =
+        serde_json::to_string(&settings).map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+    let conn = db.open_connection()?;
+    ensure_mode_exists(&conn, mode_id)?;
+    let now = now_unix_seconds();
+
+    conn.execute(
+        "UPDATE sort_modes SET settings_json = ?1, updated_at = ?2 WHERE id = ?3",
+        params![settings_json, now, mode_id],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to update sort_mode settings: {e}"))?;
+    provider_snapshot::invalidate();
+
+    conn.query_row(
+        r#"
+SELECT
+  id,
+  name,
+  settings_json,
+  strategy,
   created_at,
   updated_at
 FROM sort_modes
@@ -200,6 +392,56 @@ WHERE id = ?1
     .map_err(|e| format!("DB_ERROR: failed to query sort_mode: {e}"))
 }
 
+pub fn set_mode_strategy(
+    db: &db::Db,
+    mode_id: i64,
+    strategy: SortModeStrategy,
+) -> Result<SortModeSummary, String> {
+    let conn = db.open_connection()?;
+    ensure_mode_exists(&conn, mode_id)?;
+    let now = now_unix_seconds();
+
+    conn.execute(
+        "UPDATE sort_modes SET strategy = ?1, updated_at = ?2 WHERE id = ?3",
+        params![strategy.as_str(), now, mode_id],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to update sort_mode strategy: {e}"))?;
+    provider_snapshot::invalidate();
+
+    conn.query_row(
+        r#"
+SELECT
+  id,
+  name,
+  settings_json,
+  strategy,
+  created_at,
+  updated_at
+FROM sort_modes
+WHERE id = ?1
+"#,
+        params![mode_id],
+        row_to_mode_summary,
+    )
+    .map_err(|e| format!("DB_ERROR: failed to query sort_mode: {e}"))
+}
+
+/// Settings overrides of a sort mode (empty when the mode no longer exists).
+pub(crate) fn mode_settings(
+    conn: &Connection,
+    mode_id: i64,
+) -> Result<SortModeSettingsOverride, String> {
+    let raw: Option<String> = conn
+        .query_row(
+            "SELECT settings_json FROM sort_modes WHERE id = ?1",
+            params![mode_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("DB_ERROR: failed to query sort_mode settings: {e}"))?;
+    Ok(raw.as_deref().map(settings_from_json).unwrap_or_default())
+}
+
 pub fn delete_mode(db: &db::Db, mode_id: i64) -> Result<(), String> {
     let conn = db.open_connection()?;
     ensure_mode_exists(&conn, mode_id)?;
@@ -210,6 +452,7 @@ pub fn delete_mode(db: &db::Db, mode_id: i64) -> Result<(), String> {
     if changed == 0 {
         return Err("DB_NOT_FOUND: sort_mode not found".to_string());
     }
+    provider_snapshot::invalidate();
     Ok(())
 }
 
@@ -273,6 +516,7 @@ ON CONFLICT(cli_key) DO UPDATE SET
         params![cli_key, mode_id, now],
     )
     .map_err(|e| format!("DB_ERROR: failed to upsert sort_mode_active: {e}"))?;
+    provider_snapshot::invalidate();
 
     read_active_row(&conn, cli_key)
 }
@@ -307,6 +551,128 @@ ORDER BY sort_order ASC
     Ok(items)
 }
 
+fn read_mode_provider_weights(
+    conn: &Connection,
+    mode_id: i64,
+    cli_key: &str,
+) -> Result<Vec<SortModeProviderWeight>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  provider_id,
+  weight
+FROM sort_mode_providers
+WHERE mode_id = ?1
+  AND cli_key = ?2
+ORDER BY sort_order ASC
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare sort_mode_providers query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![mode_id, cli_key], |row| {
+            Ok(SortModeProviderWeight {
+                provider_id: row.get("provider_id")?,
+                weight: row
+                    .get::<_, i64>("weight")?
+                    .clamp(0, MAX_PROVIDER_WEIGHT as i64) as u32,
+            })
+        })
+        .map_err(|e| format!("DB_ERROR: failed to list sort_mode_providers: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(
+            row.map_err(|e| format!("DB_ERROR: failed to read sort_mode_provider row: {e}"))?,
+        );
+    }
+    Ok(items)
+}
+
+pub fn list_mode_provider_weights(
+    db: &db::Db,
+    mode_id: i64,
+    cli_key: &str,
+) -> Result<Vec<SortModeProviderWeight>, String> {
+    let cli_key = cli_key.trim();
+    validate_cli_key(cli_key)?;
+    let conn = db.open_connection()?;
+    ensure_mode_exists(&conn, mode_id)?;
+    read_mode_provider_weights(&conn, mode_id, cli_key)
+}
+
+pub fn set_mode_provider_weight(
+    db: &db::Db,
+    mode_id: i64,
+    cli_key: &str,
+    provider_id: i64,
+    weight: u32,
+) -> Result<Vec<SortModeProviderWeight>, String> {
+    let cli_key = cli_key.trim();
+    validate_cli_key(cli_key)?;
+    if weight > MAX_PROVIDER_WEIGHT {
+        return Err(format!(
+            "SEC_INVALID_INPUT: weight must be within [0, {MAX_PROVIDER_WEIGHT}]"
+        ));
+    }
+
+    let conn = db.open_connection()?;
+    ensure_mode_exists(&conn, mode_id)?;
+    let now = now_unix_seconds();
+
+    let changed = conn
+        .execute(
+            r#"
+UPDATE sort_mode_providers
+SET weight = ?1, updated_at = ?2
+WHERE mode_id = ?3
+  AND cli_key = ?4
+  AND provider_id = ?5
+"#,
+            params![weight as i64, now, mode_id, cli_key, provider_id],
+        )
+        .map_err(|e| format!("DB_ERROR: failed to update sort_mode_provider weight: {e}"))?;
+    if changed == 0 {
+        return Err("DB_NOT_FOUND: sort_mode_provider not found".to_string());
+    }
+    provider_snapshot::invalidate();
+
+    read_mode_provider_weights(&conn, mode_id, cli_key)
+}
+
+/// Routing strategy and weights of a sort mode (ordered / empty when the mode no longer exists).
+pub(crate) fn mode_routing(
+    conn: &Connection,
+    mode_id: i64,
+    cli_key: &str,
+) -> Result<SortModeRouting, String> {
+    let raw: Option<String> = conn
+        .query_row(
+            "SELECT strategy FROM sort_modes WHERE id = ?1",
+            params![mode_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("DB_ERROR: failed to query sort_mode strategy: {e}"))?;
+    let strategy = raw
+        .as_deref()
+        .map(SortModeStrategy::from_db)
+        .unwrap_or_default();
+    if strategy != SortModeStrategy::Weighted {
+        return Ok(SortModeRouting {
+            strategy,
+            weights: HashMap::new(),
+        });
+    }
+
+    let weights = read_mode_provider_weights(conn, mode_id, cli_key)?
+        .into_iter()
+        .map(|item| (item.provider_id, item.weight))
+        .collect();
+    Ok(SortModeRouting { strategy, weights })
+}
+
 fn ensure_providers_belong_to_cli(
     conn: &Connection,
     cli_key: &str,
@@ -369,6 +735,12 @@ pub fn set_mode_providers_order(
     ensure_mode_exists(&conn, mode_id)?;
     ensure_providers_belong_to_cli(&conn, cli_key, &ordered_provider_ids)?;
 
+    // Reordering must not reset weights of providers that stay in the mode.
+    let previous_weights: HashMap<i64, u32> = read_mode_provider_weights(&conn, mode_id, cli_key)?
+        .into_iter()
+        .map(|item| (item.provider_id, item.weight))
+        .collect();
+
     let tx = conn
         .transaction()
         .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
@@ -387,17 +759,30 @@ INSERT INTO sort_mode_providers(
   cli_key,
   provider_id,
   sort_order,
+  weight,
   created_at,
   updated_at
-) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
 "#,
-            params![mode_id, cli_key, provider_id, idx as i64, now, now],
+            params![
+                mode_id,
+                cli_key,
+                provider_id,
+                idx as i64,
+                previous_weights
+                    .get(provider_id)
+                    .copied()
+                    .unwrap_or(DEFAULT_PROVIDER_WEIGHT) as i64,
+                now,
+                now
+            ],
         )
         .map_err(|e| format!("DB_ERROR: failed to insert sort_mode_provider: {e}"))?;
     }
 
     tx.commit()
         .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+    provider_snapshot::invalidate();
 
     list_mode_providers(db, mode_id, cli_key)
 }
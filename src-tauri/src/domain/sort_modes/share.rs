@@ -0,0 +1,563 @@
+//! Usage: Sort mode export / import (share a routing order without sharing provider keys).
+//!
+//! 用法：
+//! - 导出：模式名称、策略、设置覆盖，以及每个 CLI 的 Provider 顺序与权重；Provider 只以名称引用，不含 Base URL / API Key
+//! - 导入：按名称匹配本机同 CLI 的 Provider，依次尝试 精确 → 规范化（忽略大小写 / 空白 / 标点）→ 模糊（包含或编辑距离 ≤ 2）；
+//!   每个 Provider 只匹配一次，模糊匹配存在多个同样接近的候选时视为无法匹配
+//! - 无法匹配的条目跳过；导入前可先预览匹配结果
+//! - 导入总是新建模式，名称冲突时自动追加序号
+
+use super::*;
+use crate::shared::cli_key::SUPPORTED_CLI_KEYS;
+
+const SHARE_FORMAT: &str = "aio-coding-hub.sort-mode";
+const SHARE_VERSION: u32 = 1;
+const MAX_SHARE_TEXT_LEN: usize = 256 * 1024;
+const MAX_SHARE_PROVIDERS_PER_CLI: usize = 200;
+const MAX_FUZZY_DISTANCE: usize = 2;
+const MIN_FUZZY_NAME_CHARS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortModeShareProvider {
+    pub name: String,
+    #[serde(default = "default_share_weight")]
+    pub weight: u32,
+}
+
+fn default_share_weight() -> u32 {
+    DEFAULT_PROVIDER_WEIGHT
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortModeShareCli {
+    pub cli_key: String,
+    #[serde(default)]
+    pub providers: Vec<SortModeShareProvider>,
+}
+
+/// Portable description of a sort mode; providers are referenced by name only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortModeShare {
+    pub format: String,
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub strategy: SortModeStrategy,
+    #[serde(default)]
+    pub settings: SortModeSettingsOverride,
+    #[serde(default)]
+    pub clis: Vec<SortModeShareCli>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortModeImportMatchKind {
+    Exact,
+    Normalized,
+    Fuzzy,
+    Unmatched,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SortModeImportMatch {
+    pub cli_key: String,
+    // Provider name as written in the shared file.
+    pub name: String,
+    pub weight: u32,
+    pub kind: SortModeImportMatchKind,
+    pub provider_id: Option<i64>,
+    pub provider_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SortModeImportPreview {
+    pub name: String,
+    pub strategy: SortModeStrategy,
+    pub settings: SortModeSettingsOverride,
+    pub matches: Vec<SortModeImportMatch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SortModeImportResult {
+    pub mode: SortModeSummary,
+    pub matches: Vec<SortModeImportMatch>,
+}
+
+pub fn export_mode(db: &db::Db, mode_id: i64) -> Result<String, String> {
+    let conn = db.open_connection()?;
+    ensure_mode_exists(&conn, mode_id)?;
+    let mode = conn
+        .query_row(
+            r#"
+SELECT
+  id,
+  name,
+  settings_json,
+  strategy,
+  created_at,
+  updated_at
+FROM sort_modes
+WHERE id = ?1
+"#,
+            params![mode_id],
+            row_to_mode_summary,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to query sort_mode: {e}"))?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  mp.cli_key,
+  p.name,
+  mp.weight
+FROM sort_mode_providers mp
+JOIN providers p ON p.id = mp.provider_id
+WHERE mp.mode_id = ?1
+ORDER BY mp.cli_key ASC, mp.sort_order ASC
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare sort_mode export query: {e}"))?;
+    let rows = stmt
+        .query_map(params![mode_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|e| format!("DB_ERROR: failed to list sort_mode providers: {e}"))?;
+
+    let mut clis: Vec<SortModeShareCli> = Vec::new();
+    for row in rows {
+        let (cli_key, name, weight) =
+            row.map_err(|e| format!("DB_ERROR: failed to read sort_mode provider row: {e}"))?;
+        let provider = SortModeShareProvider {
+            name,
+            weight: weight.clamp(0, MAX_PROVIDER_WEIGHT as i64) as u32,
+        };
+        match clis.last_mut() {
+            Some(cli) if cli.cli_key == cli_key => cli.providers.push(provider),
+            _ => clis.push(SortModeShareCli {
+                cli_key,
+                providers: vec![provider],
+            }),
+        }
+    }
+
+    let share = SortModeShare {
+        format: SHARE_FORMAT.to_string(),
+        version: SHARE_VERSION,
+        name: mode.name,
+        strategy: mode.strategy,
+        settings: mode.settings,
+        clis,
+    };
+    serde_json::to_string_pretty(&share).map_err(|e| format!("SYSTEM_ERROR: {e}"))
+}
+
+fn parse_share(raw: &str) -> Result<SortModeShare, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err("SEC_INVALID_INPUT: sort mode share text is required".to_string());
+    }
+    if raw.len() > MAX_SHARE_TEXT_LEN {
+        return Err("SEC_INVALID_INPUT: sort mode share text is too long".to_string());
+    }
+
+    let share: SortModeShare = serde_json::from_str(raw)
+        .map_err(|e| format!("SEC_INVALID_INPUT: invalid sort mode share JSON: {e}"))?;
+    if share.format != SHARE_FORMAT {
+        return Err("SEC_INVALID_INPUT: not a sort mode share file".to_string());
+    }
+    if share.version > SHARE_VERSION {
+        return Err(format!(
+            "SEC_INVALID_INPUT: unsupported sort mode share version={}",
+            share.version
+        ));
+    }
+    share.settings.validate()?;
+
+    let mut seen = HashSet::new();
+    for cli in &share.clis {
+        validate_cli_key(&cli.cli_key)?;
+        if !seen.insert(cli.cli_key.as_str()) {
+            return Err(format!(
+                "SEC_INVALID_INPUT: duplicate cli_key={} in sort mode share",
+                cli.cli_key
+            ));
+        }
+        if cli.providers.len() > MAX_SHARE_PROVIDERS_PER_CLI {
+            return Err(format!(
+                "SEC_INVALID_INPUT: too many providers for cli_key={}",
+                cli.cli_key
+            ));
+        }
+        if let Some(provider) = cli
+            .providers
+            .iter()
+            .find(|p| p.weight > MAX_PROVIDER_WEIGHT)
+        {
+            return Err(format!(
+                "SEC_INVALID_INPUT: weight of provider '{}' must be within [0, {MAX_PROVIDER_WEIGHT}]",
+                provider.name
+            ));
+        }
+    }
+    Ok(share)
+}
+
+/// Lowercase, letters and digits only: `My-Relay (HK)` -> `myrelayhk`.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            cur[j + 1] = substitution.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// Closeness of two normalized names, or `None` when they are too different to be the same provider.
+fn fuzzy_distance(wanted: &str, candidate: &str) -> Option<usize> {
+    let a: Vec<char> = wanted.chars().collect();
+    let b: Vec<char> = candidate.chars().collect();
+    if a.len().min(b.len()) < MIN_FUZZY_NAME_CHARS {
+        return None;
+    }
+    if wanted.contains(candidate) || candidate.contains(wanted) {
+        return Some(a.len().abs_diff(b.len()));
+    }
+    Some(edit_distance(&a, &b)).filter(|distance| *distance <= MAX_FUZZY_DISTANCE)
+}
+
+/// Matches shared provider names against local `(id, name)` candidates; each candidate is used once.
+fn match_names(
+    wanted: &[String],
+    candidates: &[(i64, String)],
+) -> Vec<Option<(usize, SortModeImportMatchKind)>> {
+    let mut out: Vec<Option<(usize, SortModeImportMatchKind)>> = vec![None; wanted.len()];
+    let mut used = vec![false; candidates.len()];
+    let normalized_candidates: Vec<String> = candidates
+        .iter()
+        .map(|(_, name)| normalize_name(name))
+        .collect();
+
+    // Stricter passes run over every name first, so a fuzzy match can't take an exact one's provider.
+    for kind in [
+        SortModeImportMatchKind::Exact,
+        SortModeImportMatchKind::Normalized,
+        SortModeImportMatchKind::Fuzzy,
+    ] {
+        for (idx, name) in wanted.iter().enumerate() {
+            if out[idx].is_some() {
+                continue;
+            }
+            let normalized = normalize_name(name);
+            let mut best: Option<(usize, usize)> = None;
+            let mut tied = false;
+            for (cand_idx, (_, cand_name)) in candidates.iter().enumerate() {
+                if used[cand_idx] {
+                    continue;
+                }
+                let distance = match kind {
+                    SortModeImportMatchKind::Exact => {
+                        (cand_name.trim() == name.trim()).then_some(0)
+                    }
+                    SortModeImportMatchKind::Normalized => (!normalized.is_empty()
+                        && normalized_candidates[cand_idx] == normalized)
+                        .then_some(0),
+                    _ => fuzzy_distance(&normalized, &normalized_candidates[cand_idx]),
+                };
+                let Some(distance) = distance else {
+                    continue;
+                };
+                match best {
+                    Some((_, best_distance)) if distance > best_distance => {}
+                    Some((_, best_distance)) if distance == best_distance => tied = true,
+                    _ => {
+                        best = Some((cand_idx, distance));
+                        tied = false;
+                    }
+                }
+            }
+            if let Some((cand_idx, _)) = best.filter(|_| !tied) {
+                used[cand_idx] = true;
+                out[idx] = Some((cand_idx, kind));
+            }
+        }
+    }
+    out
+}
+
+fn list_provider_names(conn: &Connection, cli_key: &str) -> Result<Vec<(i64, String)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name FROM providers WHERE cli_key = ?1 ORDER BY sort_order ASC, id ASC",
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare providers query: {e}"))?;
+    let rows = stmt
+        .query_map(params![cli_key], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| format!("DB_ERROR: failed to list providers: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("DB_ERROR: failed to read provider row: {e}"))?);
+    }
+    Ok(items)
+}
+
+fn resolve_matches(
+    conn: &Connection,
+    share: &SortModeShare,
+) -> Result<Vec<SortModeImportMatch>, String> {
+    let mut out = Vec::new();
+    for cli_key in SUPPORTED_CLI_KEYS {
+        let Some(cli) = share.clis.iter().find(|cli| cli.cli_key == cli_key) else {
+            continue;
+        };
+        let candidates = list_provider_names(conn, cli_key)?;
+        let wanted: Vec<String> = cli.providers.iter().map(|p| p.name.clone()).collect();
+        let matched = match_names(&wanted, &candidates);
+
+        for (provider, matched) in cli.providers.iter().zip(matched) {
+            let (kind, provider_id, provider_name) = match matched {
+                Some((cand_idx, kind)) => {
+                    let (id, name) = &candidates[cand_idx];
+                    (kind, Some(*id), Some(name.clone()))
+                }
+                None => (SortModeImportMatchKind::Unmatched, None, None),
+            };
+            out.push(SortModeImportMatch {
+                cli_key: cli_key.to_string(),
+                name: provider.name.clone(),
+                weight: provider.weight,
+                kind,
+                provider_id,
+                provider_name,
+            });
+        }
+    }
+    Ok(out)
+}
+
+pub fn preview_import(db: &db::Db, raw: &str) -> Result<SortModeImportPreview, String> {
+    let share = parse_share(raw)?;
+    let conn = db.open_connection()?;
+    let matches = resolve_matches(&conn, &share)?;
+    Ok(SortModeImportPreview {
+        name: share.name,
+        strategy: share.strategy,
+        settings: share.settings,
+        matches,
+    })
+}
+
+/// First free mode name: `name`, then `name (2)`, `name (3)`...
+fn available_mode_name(conn: &Connection, name: &str) -> Result<String, String> {
+    for n in 1..=100 {
+        let candidate = if n == 1 {
+            name.to_string()
+        } else {
+            let suffix = format!(" ({n})");
+            let keep = MAX_MODE_NAME_CHARS.saturating_sub(suffix.chars().count());
+            format!("{}{suffix}", name.chars().take(keep).collect::<String>())
+        };
+        let taken: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM sort_modes WHERE name = ?1",
+                params![candidate],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("DB_ERROR: failed to query sort_mode: {e}"))?;
+        if taken.is_none() {
+            return Ok(candidate);
+        }
+    }
+    Err(format!(
+        "DB_CONSTRAINT: sort_mode already exists: name={name}"
+    ))
+}
+
+/// Creates a new sort mode from share text; `name` overrides the shared mode name.
+pub fn import_mode(
+    db: &db::Db,
+    raw: &str,
+    name: Option<&str>,
+) -> Result<SortModeImportResult, String> {
+    let share = parse_share(raw)?;
+    let name = validate_mode_name(name.unwrap_or(&share.name))?;
+    let settings_json =
+        serde_json::to_string(&share.settings).map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+
+    let mut conn = db.open_connection()?;
+    let matches = resolve_matches(&conn, &share)?;
+    let name = available_mode_name(&conn, &name)?;
+    let now = now_unix_seconds();
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
+    tx.execute(
+        r#"
+INSERT INTO sort_modes(
+  name,
+  settings_json,
+  strategy,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5)
+"#,
+        params![name, settings_json, share.strategy.as_str(), now, now],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to insert sort_mode: {e}"))?;
+    let mode_id = tx.last_insert_rowid();
+
+    let mut next_sort_order: HashMap<&str, i64> = HashMap::new();
+    for item in &matches {
+        let Some(provider_id) = item.provider_id else {
+            continue;
+        };
+        let sort_order = next_sort_order.entry(item.cli_key.as_str()).or_insert(0);
+        tx.execute(
+            r#"
+INSERT INTO sort_mode_providers(
+  mode_id,
+  cli_key,
+  provider_id,
+  sort_order,
+  weight,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+"#,
+            params![
+                mode_id,
+                item.cli_key,
+                provider_id,
+                *sort_order,
+                item.weight as i64,
+                now,
+                now
+            ],
+        )
+        .map_err(|e| format!("DB_ERROR: failed to insert sort_mode_provider: {e}"))?;
+        *sort_order += 1;
+    }
+
+    let mode = tx
+        .query_row(
+            r#"
+SELECT
+  id,
+  name,
+  settings_json,
+  strategy,
+  created_at,
+  updated_at
+FROM sort_modes
+WHERE id = ?1
+"#,
+            params![mode_id],
+            row_to_mode_summary,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to query inserted sort_mode: {e}"))?;
+    tx.commit()
+        .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+    provider_snapshot::invalidate();
+
+    Ok(SortModeImportResult { mode, matches })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(names: &[&str]) -> Vec<(i64, String)> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (idx as i64 + 1, name.to_string()))
+            .collect()
+    }
+
+    fn wanted(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn match_names_prefers_exact_then_normalized_then_fuzzy() {
+        let local = candidates(&["Relay HK", "relay-hk", "AnyRouter 主线", "Official"]);
+        let matched = match_names(
+            &wanted(&["RelayHK", "Relay HK", "anyrouter", "0fficial", "Missing"]),
+            &local,
+        );
+        assert_eq!(
+            matched,
+            vec![
+                // the exact match took "Relay HK", so the normalized one falls back to "relay-hk"
+                Some((1, SortModeImportMatchKind::Normalized)),
+                Some((0, SortModeImportMatchKind::Exact)),
+                Some((2, SortModeImportMatchKind::Fuzzy)),
+                Some((3, SortModeImportMatchKind::Fuzzy)),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn match_names_skips_ambiguous_and_short_fuzzy_candidates() {
+        let local = candidates(&["relay-a", "relay-b", "ab"]);
+        assert_eq!(
+            match_names(&wanted(&["relay", "a"]), &local),
+            vec![None, None]
+        );
+    }
+
+    #[test]
+    fn parse_share_validates_format_cli_and_weights() {
+        let share = SortModeShare {
+            format: SHARE_FORMAT.to_string(),
+            version: SHARE_VERSION,
+            name: "team".to_string(),
+            strategy: SortModeStrategy::Weighted,
+            settings: SortModeSettingsOverride::default(),
+            clis: vec![SortModeShareCli {
+                cli_key: "claude".to_string(),
+                providers: vec![SortModeShareProvider {
+                    name: "relay".to_string(),
+                    weight: 3,
+                }],
+            }],
+        };
+        let raw = serde_json::to_string(&share).unwrap();
+        assert_eq!(parse_share(&raw).unwrap(), share);
+
+        let mut wrong_format = share.clone();
+        wrong_format.format = "other".to_string();
+        assert!(parse_share(&serde_json::to_string(&wrong_format).unwrap()).is_err());
+
+        let mut unknown_cli = share.clone();
+        unknown_cli.clis[0].cli_key = "opencode".to_string();
+        assert!(parse_share(&serde_json::to_string(&unknown_cli).unwrap())
+            .unwrap_err()
+            .contains("unknown cli_key"));
+
+        let mut heavy = share;
+        heavy.clis[0].providers[0].weight = MAX_PROVIDER_WEIGHT + 1;
+        assert!(parse_share(&serde_json::to_string(&heavy).unwrap()).is_err());
+
+        assert!(parse_share("not json").is_err());
+    }
+}
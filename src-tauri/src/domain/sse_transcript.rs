@@ -0,0 +1,446 @@
+//! Usage: Rebuild the final message (text, thinking, tool calls) from a trace's captured SSE.
+//!
+//! Supported stream shapes:
+//! - Anthropic Messages (`content_block_*` / `message_delta`)
+//! - OpenAI Responses (`response.output_item.added` / `response.*.delta`)
+//! - OpenAI Chat Completions chunks (`choices[].delta`)
+//! - Gemini `streamGenerateContent` (`candidates[].content.parts[]`)
+
+use crate::{db, request_sse_captures};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SseTranscriptBlock {
+    // "text" | "thinking" | "tool_call"
+    pub kind: &'static str,
+    // Text / thinking content, or the tool call arguments (raw JSON text).
+    pub text: String,
+    pub tool_call_id: Option<String>,
+    pub tool_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SseTranscript {
+    pub trace_id: String,
+    pub cli_key: String,
+    pub created_at: i64,
+    // The capture hit its size cap; the tail of the stream is missing.
+    pub truncated: bool,
+    pub event_count: u32,
+    pub model: Option<String>,
+    pub stop_reason: Option<String>,
+    pub blocks: Vec<SseTranscriptBlock>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Default)]
+struct TranscriptBuilder {
+    event_count: u32,
+    model: Option<String>,
+    stop_reason: Option<String>,
+    blocks: Vec<SseTranscriptBlock>,
+    positions: HashMap<String, usize>,
+    errors: Vec<String>,
+}
+
+fn str_at<'a>(value: &'a Value, pointer: &str) -> Option<&'a str> {
+    value.pointer(pointer).and_then(Value::as_str)
+}
+
+fn index_at(value: &Value, key: &str) -> i64 {
+    value.get(key).and_then(Value::as_i64).unwrap_or(0)
+}
+
+impl TranscriptBuilder {
+    fn block(&mut self, key: String, kind: &'static str) -> &mut SseTranscriptBlock {
+        let pos = match self.positions.get(&key) {
+            Some(pos) => *pos,
+            None => {
+                self.blocks.push(SseTranscriptBlock {
+                    kind,
+                    text: String::new(),
+                    tool_call_id: None,
+                    tool_name: None,
+                });
+                let pos = self.blocks.len() - 1;
+                self.positions.insert(key, pos);
+                pos
+            }
+        };
+        &mut self.blocks[pos]
+    }
+
+    fn append(&mut self, key: String, kind: &'static str, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.block(key, kind).text.push_str(text);
+    }
+
+    fn set_model(&mut self, model: Option<&str>) {
+        if let Some(model) = model.map(str::trim).filter(|v| !v.is_empty()) {
+            self.model = Some(model.to_string());
+        }
+    }
+
+    fn ingest_event(&mut self, event: &Value) {
+        self.event_count = self.event_count.saturating_add(1);
+
+        if let Some(kind) = event.get("type").and_then(Value::as_str) {
+            if kind.starts_with("response.") {
+                self.ingest_responses_event(kind, event);
+            } else {
+                self.ingest_anthropic_event(kind, event);
+            }
+            return;
+        }
+        if event.get("candidates").is_some() {
+            self.ingest_gemini_chunk(event);
+            return;
+        }
+        if event.get("choices").is_some() {
+            self.ingest_chat_chunk(event);
+            return;
+        }
+        if let Some(message) = str_at(event, "/error/message") {
+            self.errors.push(message.to_string());
+        }
+    }
+
+    fn ingest_anthropic_event(&mut self, kind: &str, event: &Value) {
+        let key = format!("anthropic:{}", index_at(event, "index"));
+        match kind {
+            "message_start" => self.set_model(str_at(event, "/message/model")),
+            "content_block_start" => {
+                let Some(block) = event.get("content_block") else {
+                    return;
+                };
+                match block.get("type").and_then(Value::as_str) {
+                    Some("text") => {
+                        self.block(key.clone(), "text");
+                        self.append(key, "text", str_at(block, "/text").unwrap_or(""));
+                    }
+                    Some("thinking") | Some("redacted_thinking") => {
+                        self.block(key.clone(), "thinking");
+                        self.append(key, "thinking", str_at(block, "/thinking").unwrap_or(""));
+                    }
+                    Some("tool_use") | Some("server_tool_use") => {
+                        let entry = self.block(key, "tool_call");
+                        entry.tool_call_id = str_at(block, "/id").map(str::to_string);
+                        entry.tool_name = str_at(block, "/name").map(str::to_string);
+                    }
+                    _ => {}
+                }
+            }
+            "content_block_delta" => {
+                let Some(delta) = event.get("delta") else {
+                    return;
+                };
+                match delta.get("type").and_then(Value::as_str) {
+                    Some("text_delta") => {
+                        self.append(key, "text", str_at(delta, "/text").unwrap_or(""))
+                    }
+                    Some("thinking_delta") => {
+                        self.append(key, "thinking", str_at(delta, "/thinking").unwrap_or(""))
+                    }
+                    Some("input_json_delta") => self.append(
+                        key,
+                        "tool_call",
+                        str_at(delta, "/partial_json").unwrap_or(""),
+                    ),
+                    _ => {}
+                }
+            }
+            "message_delta" => {
+                if let Some(reason) = str_at(event, "/delta/stop_reason") {
+                    self.stop_reason = Some(reason.to_string());
+                }
+            }
+            "error" => {
+                let message = str_at(event, "/error/message").unwrap_or("unknown error");
+                self.errors.push(message.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    fn ingest_responses_event(&mut self, kind: &str, event: &Value) {
+        let key = format!("responses:{}", index_at(event, "output_index"));
+        match kind {
+            "response.created" | "response.in_progress" => {
+                self.set_model(str_at(event, "/response/model"))
+            }
+            "response.completed" | "response.incomplete" | "response.failed" => {
+                self.set_model(str_at(event, "/response/model"));
+                let reason = str_at(event, "/response/incomplete_details/reason")
+                    .or_else(|| str_at(event, "/response/status"));
+                if let Some(reason) = reason {
+                    self.stop_reason = Some(reason.to_string());
+                }
+                if let Some(message) = str_at(event, "/response/error/message") {
+                    self.errors.push(message.to_string());
+                }
+            }
+            "response.output_item.added" => {
+                let Some(item) = event.get("item") else {
+                    return;
+                };
+                match item.get("type").and_then(Value::as_str) {
+                    Some("message") => {
+                        self.block(key, "text");
+                    }
+                    Some("reasoning") => {
+                        self.block(key, "thinking");
+                    }
+                    Some("function_call") | Some("custom_tool_call") => {
+                        let entry = self.block(key, "tool_call");
+                        entry.tool_call_id = str_at(item, "/call_id").map(str::to_string);
+                        entry.tool_name = str_at(item, "/name").map(str::to_string);
+                    }
+                    _ => {}
+                }
+            }
+            "response.output_text.delta" => {
+                self.append(key, "text", str_at(event, "/delta").unwrap_or(""))
+            }
+            "response.reasoning_summary_text.delta" | "response.reasoning_text.delta" => {
+                self.append(key, "thinking", str_at(event, "/delta").unwrap_or(""))
+            }
+            "response.function_call_arguments.delta" | "response.custom_tool_call_input.delta" => {
+                self.append(key, "tool_call", str_at(event, "/delta").unwrap_or(""))
+            }
+            "response.error" => {
+                let message = str_at(event, "/error/message")
+                    .or_else(|| str_at(event, "/message"))
+                    .unwrap_or("unknown error");
+                self.errors.push(message.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    fn ingest_chat_chunk(&mut self, event: &Value) {
+        self.set_model(str_at(event, "/model"));
+        let Some(choice) = event.pointer("/choices/0") else {
+            return;
+        };
+        if let Some(delta) = choice.get("delta") {
+            let reasoning =
+                str_at(delta, "/reasoning_content").or_else(|| str_at(delta, "/reasoning"));
+            self.append(
+                "chat:reasoning".to_string(),
+                "thinking",
+                reasoning.unwrap_or(""),
+            );
+            self.append(
+                "chat:content".to_string(),
+                "text",
+                str_at(delta, "/content").unwrap_or(""),
+            );
+            for call in delta
+                .get("tool_calls")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let entry = self.block(
+                    format!("chat:tool:{}", index_at(call, "index")),
+                    "tool_call",
+                );
+                if let Some(id) = str_at(call, "/id") {
+                    entry.tool_call_id = Some(id.to_string());
+                }
+                if let Some(name) = str_at(call, "/function/name") {
+                    entry.tool_name = Some(name.to_string());
+                }
+                entry
+                    .text
+                    .push_str(str_at(call, "/function/arguments").unwrap_or(""));
+            }
+        }
+        if let Some(reason) = str_at(choice, "/finish_reason") {
+            self.stop_reason = Some(reason.to_string());
+        }
+    }
+
+    fn ingest_gemini_chunk(&mut self, event: &Value) {
+        self.set_model(str_at(event, "/modelVersion"));
+        let Some(candidate) = event.pointer("/candidates/0") else {
+            return;
+        };
+        for part in candidate
+            .pointer("/content/parts")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(call) = part.get("functionCall") {
+                let key = format!("gemini:tool:{}", self.blocks.len());
+                let entry = self.block(key, "tool_call");
+                entry.tool_name = str_at(call, "/name").map(str::to_string);
+                entry.text = call.get("args").map(Value::to_string).unwrap_or_default();
+                continue;
+            }
+            let text = str_at(part, "/text").unwrap_or("");
+            if part.get("thought").and_then(Value::as_bool) == Some(true) {
+                self.append("gemini:thinking".to_string(), "thinking", text);
+            } else {
+                self.append("gemini:text".to_string(), "text", text);
+            }
+        }
+        if let Some(reason) = str_at(candidate, "/finishReason") {
+            self.stop_reason = Some(reason.to_string());
+        }
+    }
+}
+
+fn for_each_sse_payload(body: &str, mut f: impl FnMut(&str)) {
+    let mut data = String::new();
+    for line in body.lines().chain(std::iter::once("")) {
+        if line.is_empty() {
+            if !data.is_empty() {
+                f(&data);
+                data.clear();
+            }
+            continue;
+        }
+        let Some(rest) = line.strip_prefix("data:") else {
+            continue;
+        };
+        let rest = rest.strip_prefix(' ').unwrap_or(rest);
+        if rest == "[DONE]" {
+            continue;
+        }
+        if !data.is_empty() {
+            data.push('\n');
+        }
+        data.push_str(rest);
+    }
+}
+
+fn reconstruct(capture: request_sse_captures::RequestSseCapture) -> SseTranscript {
+    let mut builder = TranscriptBuilder::default();
+    for_each_sse_payload(&capture.body, |payload| {
+        // A truncated capture can end mid-event; such payloads are skipped.
+        if let Ok(event) = serde_json::from_str::<Value>(payload) {
+            builder.ingest_event(&event);
+        }
+    });
+
+    SseTranscript {
+        trace_id: capture.trace_id,
+        cli_key: capture.cli_key,
+        created_at: capture.created_at,
+        truncated: capture.truncated,
+        event_count: builder.event_count,
+        model: builder.model,
+        stop_reason: builder.stop_reason,
+        blocks: builder.blocks,
+        errors: builder.errors,
+    }
+}
+
+/// `None` when no SSE was captured for the trace (capture disabled, non-stream response, or pruned).
+pub fn get_by_trace_id(db: &db::Db, trace_id: &str) -> Result<Option<SseTranscript>, String> {
+    Ok(request_sse_captures::get_by_trace_id(db, trace_id)?.map(reconstruct))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::reconstruct;
+    use crate::request_sse_captures::RequestSseCapture;
+
+    fn capture(cli_key: &str, body: &str) -> RequestSseCapture {
+        RequestSseCapture {
+            trace_id: "t1".to_string(),
+            cli_key: cli_key.to_string(),
+            created_at: 100,
+            truncated: false,
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn rebuilds_anthropic_text_thinking_and_tool_use() {
+        let body = r#"event: message_start
+data: {"type":"message_start","message":{"model":"claude-sonnet-4"}}
+
+event: content_block_start
+data: {"type":"content_block_start","index":0,"content_block":{"type":"thinking","thinking":""}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"Let me "}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"check."}}
+
+event: content_block_start
+data: {"type":"content_block_start","index":1,"content_block":{"type":"text","text":""}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":1,"delta":{"type":"text_delta","text":"Reading the file."}}
+
+event: content_block_start
+data: {"type":"content_block_start","index":2,"content_block":{"type":"tool_use","id":"toolu_1","name":"Read","input":{}}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":2,"delta":{"type":"input_json_delta","partial_json":"{\"path\":"}}
+
+event: content_block_delta
+data: {"type":"content_block_delta","index":2,"delta":{"type":"input_json_delta","partial_json":"\"a.rs\"}"}}
+
+event: message_delta
+data: {"type":"message_delta","delta":{"stop_reason":"tool_use"}}
+
+"#;
+        let transcript = reconstruct(capture("claude", body));
+        assert_eq!(transcript.model.as_deref(), Some("claude-sonnet-4"));
+        assert_eq!(transcript.stop_reason.as_deref(), Some("tool_use"));
+        assert_eq!(transcript.event_count, 10);
+        let blocks = &transcript.blocks;
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(
+            (blocks[0].kind, blocks[0].text.as_str()),
+            ("thinking", "Let me check.")
+        );
+        assert_eq!(
+            (blocks[1].kind, blocks[1].text.as_str()),
+            ("text", "Reading the file.")
+        );
+        assert_eq!(blocks[2].kind, "tool_call");
+        assert_eq!(blocks[2].tool_call_id.as_deref(), Some("toolu_1"));
+        assert_eq!(blocks[2].tool_name.as_deref(), Some("Read"));
+        assert_eq!(blocks[2].text, r#"{"path":"a.rs"}"#);
+    }
+
+    #[test]
+    fn rebuilds_responses_and_chat_streams() {
+        let responses = r#"data: {"type":"response.created","response":{"model":"gpt-5"}}
+
+data: {"type":"response.output_item.added","output_index":0,"item":{"type":"function_call","call_id":"call_1","name":"shell"}}
+
+data: {"type":"response.function_call_arguments.delta","output_index":0,"delta":"{\"cmd\":\"ls\"}"}
+
+data: {"type":"response.output_text.delta","output_index":1,"delta":"done"}
+
+data: {"type":"response.completed","response":{"model":"gpt-5","status":"completed"}}
+
+"#;
+        let transcript = reconstruct(capture("codex", responses));
+        assert_eq!(transcript.model.as_deref(), Some("gpt-5"));
+        assert_eq!(transcript.stop_reason.as_deref(), Some("completed"));
+        assert_eq!(transcript.blocks.len(), 2);
+        assert_eq!(transcript.blocks[0].tool_name.as_deref(), Some("shell"));
+        assert_eq!(transcript.blocks[0].text, r#"{"cmd":"ls"}"#);
+        assert_eq!(transcript.blocks[1].text, "done");
+
+        let chat = "data: {\"model\":\"m\",\"choices\":[{\"delta\":{\"content\":\"He\"}}]}\n\ndata: {\"choices\":[{\"delta\":{\"content\":\"llo\"},\"finish_reason\":\"stop\"}]}\n\ndata: [DONE]\n\ndata: {\"choices\":[{\"del";
+        let transcript = reconstruct(capture("codex", chat));
+        assert_eq!(transcript.event_count, 2);
+        assert_eq!(transcript.blocks.len(), 1);
+        assert_eq!(transcript.blocks[0].text, "Hello");
+        assert_eq!(transcript.stop_reason.as_deref(), Some("stop"));
+    }
+}
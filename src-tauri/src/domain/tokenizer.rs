@@ -0,0 +1,297 @@
+//! Usage: Local token estimation (no vocab files, no network) for request introspection.
+//!
+//! 用法：
+//! - `estimate_text`：按字符类别估算纯文本 token 数（英文单词约 4 字符 / token，数字约 3 位 / token，CJK 约 1 字 / token）
+//! - `estimate_request`：遍历 Claude / OpenAI / Gemini 请求体，累加文本、工具定义与每条消息的结构开销；图片 / 文档按固定值计
+//! - `ModelFamily`：按模型族（Claude / OpenAI / Gemini）对基础估算做比例修正，各家 BPE 词表切分粒度不同
+//! - 结果是近似值（BPE 分词器的量级估算），用于上游未返回 usage 时的展示与上下文守卫，不参与计费统计
+
+use serde::Serialize;
+use serde_json::Value;
+
+// 每条会话消息的角色 / 分隔符开销
+const MESSAGE_OVERHEAD_TOKENS: u64 = 4;
+// 单个图片 / 文档块的固定估算值（约等于一张 1092x1092 图片）
+const MEDIA_BLOCK_TOKENS: u64 = 1_600;
+// 不计入估算的字段：模型名、思考签名与缓存控制等不属于提示内容
+const SKIPPED_KEYS: &[&str] = &["model", "signature", "cache_control", "metadata"];
+const MEDIA_BLOCK_TYPES: &[&str] = &[
+    "image",
+    "image_url",
+    "input_image",
+    "document",
+    "input_file",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelFamily {
+    Claude,
+    OpenAi,
+    Gemini,
+    Other,
+}
+
+impl ModelFamily {
+    pub fn from_model(model: Option<&str>) -> Self {
+        let Some(model) = model.map(|m| m.trim().to_ascii_lowercase()) else {
+            return Self::Other;
+        };
+        // strip vendor prefixes such as `anthropic/claude-...` or `models/gemini-...`
+        let name = model.rsplit('/').next().unwrap_or(&model);
+        if name.starts_with("claude") {
+            Self::Claude
+        } else if name.starts_with("gemini") {
+            Self::Gemini
+        } else if ["gpt", "o1", "o3", "o4", "codex", "chatgpt"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+        {
+            Self::OpenAi
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Family-specific scale applied to the base estimate, in per mille.
+    fn scale_permille(self) -> u64 {
+        match self {
+            // Claude's vocabulary splits code and non-English text more finely than o200k
+            Self::Claude => 1_150,
+            Self::OpenAi => 1_000,
+            Self::Gemini => 950,
+            Self::Other => 1_000,
+        }
+    }
+
+    fn scale(self, tokens: u64) -> u64 {
+        tokens.saturating_mul(self.scale_permille()).div_ceil(1_000)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenEstimate {
+    pub tokens: u64,
+    pub chars: u64,
+    pub model_family: ModelFamily,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    AsciiWord,
+    Digit,
+    Space,
+    OtherWord,
+    Cjk,
+    Other,
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF       // Hiragana / Katakana
+        | 0x3400..=0x4DBF     // CJK Extension A
+        | 0x4E00..=0x9FFF     // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF     // Hangul syllables
+        | 0xF900..=0xFAFF     // CJK Compatibility Ideographs
+        | 0x3000..=0x303F     // CJK punctuation
+        | 0xFF00..=0xFFEF     // Fullwidth forms
+    )
+}
+
+fn classify(c: char) -> CharClass {
+    if c.is_ascii_alphabetic() || c == '_' {
+        CharClass::AsciiWord
+    } else if c.is_ascii_digit() {
+        CharClass::Digit
+    } else if c.is_whitespace() {
+        CharClass::Space
+    } else if is_cjk(c) {
+        CharClass::Cjk
+    } else if c.is_alphabetic() {
+        CharClass::OtherWord
+    } else {
+        CharClass::Other
+    }
+}
+
+fn run_tokens(class: CharClass, run: &str, chars: u64) -> u64 {
+    match class {
+        CharClass::AsciiWord => chars.div_ceil(4),
+        CharClass::Digit => chars.div_ceil(3),
+        // a single space merges into the following word; indentation / blank lines cost a token per run
+        CharClass::Space if run == " " => 0,
+        CharClass::Space => chars.div_ceil(8),
+        CharClass::OtherWord => chars.div_ceil(2),
+        CharClass::Cjk => chars,
+        // punctuation is one token per char; emoji and other symbols are split into byte pieces
+        CharClass::Other => run.chars().map(|c| (c.len_utf8() as u64).div_ceil(2)).sum(),
+    }
+}
+
+/// Approximate token count of plain text.
+pub fn estimate_text(text: &str) -> u64 {
+    let mut tokens = 0u64;
+    let mut run_start = 0usize;
+    let mut run_chars = 0u64;
+    let mut run_class: Option<CharClass> = None;
+
+    for (idx, c) in text.char_indices() {
+        let class = classify(c);
+        // every CJK / symbol char is its own run so that counts stay per char
+        let continues =
+            run_class == Some(class) && !matches!(class, CharClass::Cjk | CharClass::Other);
+        if !continues {
+            if let Some(prev) = run_class {
+                tokens += run_tokens(prev, &text[run_start..idx], run_chars);
+            }
+            run_start = idx;
+            run_chars = 0;
+            run_class = Some(class);
+        }
+        run_chars += 1;
+    }
+    if let Some(prev) = run_class {
+        tokens += run_tokens(prev, &text[run_start..], run_chars);
+    }
+    tokens
+}
+
+fn is_media_block(map: &serde_json::Map<String, Value>) -> bool {
+    let typed = map
+        .get("type")
+        .and_then(Value::as_str)
+        .is_some_and(|t| MEDIA_BLOCK_TYPES.contains(&t));
+    // Gemini parts carry media as `inlineData` / `fileData`
+    typed
+        || ["inlineData", "inline_data", "fileData", "file_data"]
+            .iter()
+            .any(|key| map.contains_key(*key))
+}
+
+fn estimate_value(value: &Value) -> u64 {
+    match value {
+        Value::String(text) => estimate_text(text),
+        Value::Array(items) => items.iter().map(estimate_value).sum(),
+        Value::Object(map) if is_media_block(map) => MEDIA_BLOCK_TOKENS,
+        Value::Object(map) => map
+            .iter()
+            .filter(|(key, _)| !SKIPPED_KEYS.contains(&key.as_str()))
+            .map(|(_, v)| estimate_value(v))
+            .sum(),
+        _ => 0,
+    }
+}
+
+/// Approximate prompt tokens of a Claude / OpenAI / Gemini request body for `family`.
+pub fn estimate_request(body: &Value, family: ModelFamily) -> u64 {
+    let messages = ["messages", "input", "contents"]
+        .iter()
+        .filter_map(|key| body.get(*key).and_then(Value::as_array))
+        .map(|items| items.len() as u64)
+        .sum::<u64>();
+    family.scale(estimate_value(body) + messages * MESSAGE_OVERHEAD_TOKENS)
+}
+
+/// Estimates `input`; with `as_request` it must be a JSON request body, otherwise it is plain text.
+/// `model` selects the family; for request bodies it falls back to the body's own `model` field.
+pub fn estimate(
+    input: &str,
+    as_request: bool,
+    model: Option<&str>,
+) -> Result<TokenEstimate, String> {
+    let (tokens, model_family) = if as_request {
+        let body: Value = serde_json::from_str(input)
+            .map_err(|e| format!("SEC_INVALID_INPUT: request body is not valid JSON: {e}"))?;
+        let family =
+            ModelFamily::from_model(model.or_else(|| body.get("model").and_then(Value::as_str)));
+        (estimate_request(&body, family), family)
+    } else {
+        let family = ModelFamily::from_model(model);
+        (family.scale(estimate_text(input)), family)
+    };
+    Ok(TokenEstimate {
+        tokens,
+        chars: input.chars().count() as u64,
+        model_family,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn estimate_text_scales_by_script() {
+        assert_eq!(estimate_text(""), 0);
+        assert_eq!(estimate_text("hello world"), 4);
+        assert_eq!(estimate_text("1234567"), 3);
+        assert_eq!(estimate_text("你好，世界"), 5);
+        assert_eq!(estimate_text("fn main() {}"), 6);
+
+        let prose = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+        let tokens = estimate_text(&prose);
+        // real BPE tokenizers put this sentence at ~10 tokens
+        assert!((900..=1_400).contains(&tokens), "{tokens}");
+    }
+
+    #[test]
+    fn estimate_request_counts_content_and_skips_media_payloads() {
+        let body = json!({
+            "model": "claude-sonnet-4-5-20250929",
+            "system": "be terse",
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "text", "text": "describe"},
+                    {"type": "image", "source": {"type": "base64", "data": "A".repeat(100_000)}},
+                ]},
+                {"role": "assistant", "content": "ok"},
+            ],
+        });
+        let tokens = estimate_request(&body, ModelFamily::OpenAi);
+        assert!(tokens > MEDIA_BLOCK_TOKENS);
+        assert!(tokens < MEDIA_BLOCK_TOKENS + 50, "{tokens}");
+
+        let without_model = json!({"messages": body["messages"], "system": "be terse"});
+        assert_eq!(
+            estimate_request(&without_model, ModelFamily::OpenAi),
+            tokens
+        );
+        assert!(estimate_request(&body, ModelFamily::Claude) > tokens);
+    }
+
+    #[test]
+    fn estimate_rejects_invalid_request_json() {
+        assert!(estimate("not json", true, None)
+            .unwrap_err()
+            .starts_with("SEC_INVALID_INPUT:"));
+        let plain = estimate("not json", false, None).unwrap();
+        assert_eq!(plain.chars, 8);
+        assert_eq!(plain.tokens, 2);
+        assert_eq!(plain.model_family, ModelFamily::Other);
+    }
+
+    #[test]
+    fn model_family_is_detected_from_model_names() {
+        let cases = [
+            ("claude-sonnet-4-5-20250929", ModelFamily::Claude),
+            ("anthropic/claude-opus-4", ModelFamily::Claude),
+            ("gpt-5-codex", ModelFamily::OpenAi),
+            ("o3-mini", ModelFamily::OpenAi),
+            ("models/gemini-2.5-pro", ModelFamily::Gemini),
+            ("deepseek-chat", ModelFamily::Other),
+        ];
+        for (model, family) in cases {
+            assert_eq!(ModelFamily::from_model(Some(model)), family, "{model}");
+        }
+        assert_eq!(ModelFamily::from_model(None), ModelFamily::Other);
+
+        let body = r#"{"model":"claude-sonnet-4","messages":[{"role":"user","content":"hi"}]}"#;
+        let est = estimate(body, true, None).unwrap();
+        assert_eq!(est.model_family, ModelFamily::Claude);
+        assert_eq!(
+            estimate(body, true, Some("gpt-5")).unwrap().model_family,
+            ModelFamily::OpenAi
+        );
+    }
+}
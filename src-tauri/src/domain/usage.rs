@@ -102,6 +102,15 @@ fn extract_model_from_json_value(value: &Value) -> Option<String> {
         return sanitize_model(model);
     }
 
+    // Gemini generateContent: `modelVersion` (top-level or wrapped in `response`).
+    if let Some(model) = value
+        .get("modelVersion")
+        .or_else(|| value.get("response").and_then(|r| r.get("modelVersion")))
+        .and_then(|v| v.as_str())
+    {
+        return sanitize_model(model);
+    }
+
     None
 }
 
@@ -123,6 +132,11 @@ pub fn parse_model_from_json_bytes(body: &[u8]) -> Option<String> {
         }
     }
 
+    // Array root: Gemini `streamGenerateContent` without `alt=sse` returns a JSON array of chunks.
+    if let Some(arr) = value.as_array() {
+        return arr.iter().rev().find_map(extract_model_from_json_value);
+    }
+
     None
 }
 
@@ -263,6 +277,17 @@ fn extract_from_json_value(value: &Value) -> Option<UsageMetrics> {
 
     // Array root: scan items (best-effort).
     if let Some(arr) = value.as_array() {
+        // Gemini `streamGenerateContent` without `alt=sse`: every chunk carries cumulative
+        // usageMetadata, so the last one wins.
+        for item in arr.iter().rev() {
+            let usage_meta = item
+                .get("usageMetadata")
+                .or_else(|| item.get("response").and_then(|r| r.get("usageMetadata")));
+            if let Some(usage_meta) = usage_meta.and_then(extract_usage_metrics) {
+                return Some(usage_meta);
+            }
+        }
+
         for item in arr {
             if let Some(usage) = item.get("usage").and_then(extract_usage_metrics) {
                 return Some(usage);
@@ -318,6 +343,8 @@ pub struct SseUsageTracker {
 
     claude_message_start: Option<UsageMetrics>,
     claude_message_delta: Option<UsageMetrics>,
+    claude_ping_seen: bool,
+    claude_message_delta_seen: bool,
     last_generic: Option<UsageMetrics>,
     last_model: Option<String>,
 }
@@ -345,6 +372,8 @@ impl SseUsageTracker {
             current_data: Vec::new(),
             claude_message_start: None,
             claude_message_delta: None,
+            claude_ping_seen: false,
+            claude_message_delta_seen: false,
             last_generic: None,
             last_model: None,
         }
@@ -440,6 +469,17 @@ impl SseUsageTracker {
 
         // Claude SSE: merge message_start + message_delta usage
         if self.is_claude {
+            let is_event = |name: &str| {
+                event == name.as_bytes() || data.get("type").and_then(Value::as_str) == Some(name)
+            };
+            if is_event("ping") {
+                self.claude_ping_seen = true;
+                return;
+            }
+            if is_event("message_delta") {
+                self.claude_message_delta_seen = true;
+            }
+
             if event == b"message_start" {
                 let usage_value = data
                     .get("message")
@@ -489,6 +529,12 @@ impl SseUsageTracker {
         }
     }
 
+    /// Claude stream that has sent `ping` keep-alives but no `message_delta` yet: the turn is still
+    /// open, e.g. while the upstream runs a server-side tool.
+    pub fn in_tool_pause(&self) -> bool {
+        self.claude_ping_seen && !self.claude_message_delta_seen
+    }
+
     pub fn best_effort_model(&self) -> Option<String> {
         self.last_model.clone()
     }
@@ -31,6 +31,22 @@ fn parse_gemini_usage_metadata() {
     assert_eq!(extract.metrics.cache_read_input_tokens, Some(4));
 }
 
+#[test]
+fn parse_gemini_stream_json_array_uses_last_usage_metadata() {
+    let body = br#"[
+        {"candidates":[],"usageMetadata":{"promptTokenCount":8,"totalTokenCount":8},"modelVersion":"gemini-2.5-pro"},
+        {"candidates":[],"usageMetadata":{"promptTokenCount":8,"candidatesTokenCount":12,"totalTokenCount":20},"modelVersion":"gemini-2.5-pro"}
+    ]"#;
+    let extract = parse_usage_from_json_bytes(body).expect("should parse usage");
+    assert_eq!(extract.metrics.input_tokens, Some(8));
+    assert_eq!(extract.metrics.output_tokens, Some(12));
+    assert_eq!(extract.metrics.total_tokens, Some(20));
+    assert_eq!(
+        parse_model_from_json_bytes(body).as_deref(),
+        Some("gemini-2.5-pro")
+    );
+}
+
 #[test]
 fn parse_claude_sse_merge_message_start_and_delta() {
     let sse = b"event: message_start\n\
@@ -58,6 +74,26 @@ fn parse_claude_sse_merge_message_start_and_delta() {
     assert_eq!(extract.metrics.cache_creation_input_tokens, Some(25));
 }
 
+#[test]
+fn claude_sse_tool_pause_until_message_delta() {
+    let mut tracker = SseUsageTracker::new("claude");
+    tracker.ingest_chunk(
+        b"event: message_start\ndata: {\"type\":\"message_start\",\"message\":{}}\n\n",
+    );
+    assert!(!tracker.in_tool_pause());
+
+    tracker.ingest_chunk(b"event: ping\ndata: {\"type\": \"ping\"}\n\n");
+    assert!(tracker.in_tool_pause());
+
+    tracker
+        .ingest_chunk(b"event: message_delta\ndata: {\"type\":\"message_delta\",\"delta\":{}}\n\n");
+    assert!(!tracker.in_tool_pause());
+
+    let mut codex = SseUsageTracker::new("codex");
+    codex.ingest_chunk(b"event: ping\ndata: {\"type\":\"ping\"}\n\n");
+    assert!(!codex.in_tool_pause());
+}
+
 #[test]
 fn parse_model_top_level() {
     let body = br#"{"model":"claude-opus-4-5-20251101"}"#;
@@ -0,0 +1,148 @@
+//! Usage: Codex session id completion history (what the gateway filled in, per session) and analytics.
+
+use crate::db;
+use rusqlite::{params, Connection};
+
+use super::{
+    compute_start_ts, parse_range, CodexSessionCompletionReport, CodexSessionCompletionSessionRow,
+    CodexSessionCompletionSummary,
+};
+
+pub(crate) struct CodexSessionCompletionRecord {
+    pub(crate) trace_id: String,
+    pub(crate) session_id: String,
+    pub(crate) action: &'static str,
+    pub(crate) source: &'static str,
+    pub(crate) changed_header: bool,
+    pub(crate) changed_body: bool,
+}
+
+pub(crate) fn record(
+    db: &db::Db,
+    created_at: i64,
+    record: &CodexSessionCompletionRecord,
+) -> Result<(), String> {
+    let conn = db.open_connection()?;
+    conn.execute(
+        "INSERT INTO codex_session_completion_events(created_at, trace_id, session_id, action, source, changed_header, changed_body) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            created_at,
+            record.trace_id,
+            record.session_id,
+            record.action,
+            record.source,
+            record.changed_header as i64,
+            record.changed_body as i64,
+        ],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to insert codex_session_completion_events: {e}"))?;
+    Ok(())
+}
+
+/// Totals by completion action plus the most recently completed sessions.
+///
+/// `generated_uuid_v7` starts a synthetic session; every `reused_fingerprint_cache` afterwards is a
+/// request that would otherwise have shown up as yet another session.
+pub fn codex_session_completions(
+    db: &db::Db,
+    range: &str,
+    limit: usize,
+) -> Result<CodexSessionCompletionReport, String> {
+    let conn = db.open_connection()?;
+    let range = parse_range(range)?;
+    let start_ts = compute_start_ts(&conn, range)?;
+    codex_session_completions_with_conn(&conn, start_ts, limit)
+}
+
+pub(super) fn codex_session_completions_with_conn(
+    conn: &Connection,
+    start_ts: Option<i64>,
+    limit: usize,
+) -> Result<CodexSessionCompletionReport, String> {
+    let summary = conn
+        .query_row(
+            r#"
+SELECT
+  COUNT(*) AS completions,
+  COUNT(DISTINCT session_id) AS sessions,
+  COALESCE(SUM(CASE WHEN action = 'generated_uuid_v7' THEN 1 ELSE 0 END), 0) AS generated,
+  COALESCE(SUM(CASE WHEN action = 'reused_fingerprint_cache' THEN 1 ELSE 0 END), 0) AS reused_fingerprint_cache,
+  COALESCE(SUM(CASE WHEN action = 'completed_missing_fields' THEN 1 ELSE 0 END), 0) AS completed_missing_fields,
+  COALESCE(SUM(changed_header), 0) AS header_changes,
+  COALESCE(SUM(changed_body), 0) AS body_changes
+FROM codex_session_completion_events
+WHERE (?1 IS NULL OR created_at >= ?1)
+"#,
+            params![start_ts],
+            |row| {
+                Ok(CodexSessionCompletionSummary {
+                    completions: row.get("completions")?,
+                    sessions: row.get("sessions")?,
+                    generated: row.get("generated")?,
+                    reused_fingerprint_cache: row.get("reused_fingerprint_cache")?,
+                    completed_missing_fields: row.get("completed_missing_fields")?,
+                    header_changes: row.get("header_changes")?,
+                    body_changes: row.get("body_changes")?,
+                })
+            },
+        )
+        .map_err(|e| format!("DB_ERROR: failed to query codex session completion summary: {e}"))?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  e.session_id AS session_id,
+  COUNT(*) AS completions,
+  SUM(CASE WHEN e.action = 'generated_uuid_v7' THEN 1 ELSE 0 END) AS generated,
+  SUM(CASE WHEN e.action = 'reused_fingerprint_cache' THEN 1 ELSE 0 END) AS reused_fingerprint_cache,
+  SUM(CASE WHEN e.action = 'completed_missing_fields' THEN 1 ELSE 0 END) AS completed_missing_fields,
+  MIN(e.created_at) AS first_seen_at,
+  MAX(e.created_at) AS last_seen_at,
+  (
+    SELECT l.action FROM codex_session_completion_events l
+    WHERE l.session_id = e.session_id
+    ORDER BY l.id DESC LIMIT 1
+  ) AS last_action,
+  (
+    SELECT l.source FROM codex_session_completion_events l
+    WHERE l.session_id = e.session_id
+    ORDER BY l.id DESC LIMIT 1
+  ) AS last_source,
+  (
+    SELECT COUNT(*) FROM request_logs r
+    WHERE r.session_id = e.session_id AND r.cli_key = 'codex'
+  ) AS request_count
+FROM codex_session_completion_events e
+WHERE (?1 IS NULL OR e.created_at >= ?1)
+GROUP BY e.session_id
+ORDER BY last_seen_at DESC, e.session_id ASC
+LIMIT ?2
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare codex session completion query: {e}"))?;
+    let rows = stmt
+        .query_map(params![start_ts, limit as i64], |row| {
+            Ok(CodexSessionCompletionSessionRow {
+                session_id: row.get("session_id")?,
+                completions: row.get("completions")?,
+                generated: row.get("generated")?,
+                reused_fingerprint_cache: row.get("reused_fingerprint_cache")?,
+                completed_missing_fields: row.get("completed_missing_fields")?,
+                first_seen_at: row.get("first_seen_at")?,
+                last_seen_at: row.get("last_seen_at")?,
+                last_action: row.get("last_action")?,
+                last_source: row.get("last_source")?,
+                request_count: row.get("request_count")?,
+            })
+        })
+        .map_err(|e| format!("DB_ERROR: failed to query codex session completions: {e}"))?;
+
+    let mut sessions = Vec::new();
+    for row in rows {
+        sessions.push(
+            row.map_err(|e| format!("DB_ERROR: failed to read codex session completion row: {e}"))?,
+        );
+    }
+    Ok(CodexSessionCompletionReport { summary, sessions })
+}
@@ -1,19 +1,31 @@
 //! Usage: Usage analytics queries and aggregation helpers backed by sqlite.
 
 mod bounds;
+mod codex_session_completion;
 mod hourly;
 mod input;
 mod leaderboard_range;
 mod leaderboard_v2;
 mod summary;
 mod tokens;
+mod tool_usage;
 mod types;
 
+pub use codex_session_completion::codex_session_completions;
+pub(crate) use codex_session_completion::{
+    record as record_codex_session_completion, CodexSessionCompletionRecord,
+};
 pub use hourly::hourly_series;
 pub use leaderboard_range::{leaderboard_day, leaderboard_provider};
 pub use leaderboard_v2::leaderboard_v2;
 pub use summary::{summary, summary_v2};
-pub use types::{UsageDayRow, UsageHourlyRow, UsageLeaderboardRow, UsageProviderRow, UsageSummary};
+pub(crate) use tokens::{sql_effective_total_tokens_expr, SQL_EFFECTIVE_INPUT_TOKENS_EXPR};
+pub use tool_usage::tool_usage;
+pub(crate) use tool_usage::{record as record_tool_usage, ToolUsageRecord};
+pub use types::{
+    CodexSessionCompletionReport, CodexSessionCompletionSessionRow, CodexSessionCompletionSummary,
+    UsageDayRow, UsageHourlyRow, UsageLeaderboardRow, UsageProviderRow, UsageSummary, UsageToolRow,
+};
 
 use bounds::{compute_bounds_v2, compute_start_ts, compute_start_ts_last_n_days};
 use input::{
@@ -22,8 +34,8 @@ use input::{
 };
 use leaderboard_range::{extract_final_provider, has_valid_provider_key, ProviderAgg, ProviderKey};
 use tokens::{
-    sql_effective_input_tokens_expr_with_alias, sql_effective_total_tokens_expr,
-    sql_effective_total_tokens_expr_with_alias, token_total, SQL_EFFECTIVE_INPUT_TOKENS_EXPR,
+    sql_effective_input_tokens_expr_with_alias, sql_effective_total_tokens_expr_with_alias,
+    token_total,
 };
 
 #[cfg(test)]
@@ -1,9 +1,9 @@
-use crate::db;
+use crate::{db, usage_rollups};
 use rusqlite::{params, Connection};
 
 use super::{
     compute_bounds_v2, compute_start_ts, normalize_cli_filter, parse_period_v2, parse_range,
-    sql_effective_total_tokens_expr, UsageSummary, SQL_EFFECTIVE_INPUT_TOKENS_EXPR,
+    UsageSummary,
 };
 
 pub(super) fn summary_query(
@@ -12,81 +12,35 @@ pub(super) fn summary_query(
     end_ts: Option<i64>,
     cli_key: Option<&str>,
 ) -> Result<UsageSummary, String> {
-    let effective_input_expr = SQL_EFFECTIVE_INPUT_TOKENS_EXPR;
-    let effective_total_expr = sql_effective_total_tokens_expr();
+    let source = usage_rollups::stats_source_sql(conn, start_ts, end_ts)?;
     let sql = format!(
         r#"
-	SELECT
-	  COUNT(*) AS requests_total,
-	  SUM(
-	    CASE WHEN (
-      total_tokens IS NOT NULL OR
-      input_tokens IS NOT NULL OR
-      output_tokens IS NOT NULL OR
-      cache_read_input_tokens IS NOT NULL OR
-      cache_creation_input_tokens IS NOT NULL OR
-      cache_creation_5m_input_tokens IS NOT NULL OR
-      cache_creation_1h_input_tokens IS NOT NULL OR
-      usage_json IS NOT NULL
-    ) THEN 1 ELSE 0 END
-  ) AS requests_with_usage,
-  SUM(CASE WHEN status >= 200 AND status < 300 AND error_code IS NULL THEN 1 ELSE 0 END) AS requests_success,
-  SUM(
-    CASE WHEN (
-      status IS NULL OR
-      status < 200 OR
-      status >= 300 OR
-      error_code IS NOT NULL
-    ) THEN 1 ELSE 0 END
-	  ) AS requests_failed,
-	  SUM(CASE WHEN status >= 200 AND status < 300 AND error_code IS NULL THEN duration_ms ELSE 0 END) AS success_duration_ms_sum,
-	  SUM(
-	    CASE WHEN (
-	      status >= 200 AND status < 300 AND error_code IS NULL AND
-        ttfb_ms IS NOT NULL AND
-	      ttfb_ms < duration_ms
-	    ) THEN ttfb_ms ELSE 0 END
-	  ) AS success_ttfb_ms_sum,
-	  SUM(
-	    CASE WHEN (
-	      status >= 200 AND status < 300 AND error_code IS NULL AND
-        ttfb_ms IS NOT NULL AND
-	      ttfb_ms < duration_ms
-	    ) THEN 1 ELSE 0 END
-	  ) AS success_ttfb_ms_count,
-	  SUM(
-	    CASE WHEN (
-	      status >= 200 AND status < 300 AND error_code IS NULL AND
-	      output_tokens IS NOT NULL AND
-        ttfb_ms IS NOT NULL AND
-	      ttfb_ms < duration_ms
-	    ) THEN (duration_ms - ttfb_ms) ELSE 0 END
-	  ) AS success_generation_ms_sum,
-	  SUM(
-	    CASE WHEN (
-	      status >= 200 AND status < 300 AND error_code IS NULL AND
-	      output_tokens IS NOT NULL AND
-        ttfb_ms IS NOT NULL AND
-	      ttfb_ms < duration_ms
-	    ) THEN output_tokens ELSE 0 END
-	  ) AS success_output_tokens_for_rate_sum,
-	  SUM({effective_input_expr}) AS input_tokens,
-	  SUM(COALESCE(output_tokens, 0)) AS output_tokens,
-	  SUM({effective_total_expr}) AS total_tokens,
-	  SUM(COALESCE(cache_read_input_tokens, 0)) AS cache_read_input_tokens,
-  SUM(COALESCE(cache_creation_input_tokens, 0)) AS cache_creation_input_tokens,
-  SUM(COALESCE(cache_creation_5m_input_tokens, 0)) AS cache_creation_5m_input_tokens,
-  SUM(COALESCE(cache_creation_1h_input_tokens, 0)) AS cache_creation_1h_input_tokens
-	FROM request_logs
-	WHERE excluded_from_stats = 0
-  AND (?1 IS NULL OR created_at >= ?1)
-  AND (?2 IS NULL OR created_at < ?2)
-	AND (?3 IS NULL OR cli_key = ?3)
-	"#,
-        effective_input_expr = effective_input_expr,
-        effective_total_expr = effective_total_expr.as_str()
+SELECT
+  SUM(requests_total) AS requests_total,
+  SUM(requests_with_usage) AS requests_with_usage,
+  SUM(requests_success) AS requests_success,
+  SUM(requests_failed) AS requests_failed,
+  SUM(success_duration_ms_sum) AS success_duration_ms_sum,
+  SUM(success_ttfb_ms_sum) AS success_ttfb_ms_sum,
+  SUM(success_ttfb_ms_count) AS success_ttfb_ms_count,
+  SUM(success_generation_ms_sum) AS success_generation_ms_sum,
+  SUM(success_output_tokens_for_rate_sum) AS success_output_tokens_for_rate_sum,
+  SUM(input_tokens) AS input_tokens,
+  SUM(output_tokens) AS output_tokens,
+  SUM(total_tokens) AS total_tokens,
+  SUM(cache_read_input_tokens) AS cache_read_input_tokens,
+  SUM(cache_creation_input_tokens) AS cache_creation_input_tokens,
+  SUM(cache_creation_5m_input_tokens) AS cache_creation_5m_input_tokens,
+  SUM(cache_creation_1h_input_tokens) AS cache_creation_1h_input_tokens
+FROM {source}
+WHERE (?1 IS NULL OR created_at >= ?1)
+AND (?2 IS NULL OR created_at < ?2)
+AND (?3 IS NULL OR cli_key = ?3)
+"#
     );
 
+    let upstream_overloaded = upstream_overloaded_count(conn, start_ts, end_ts, cli_key)?;
+
     conn.query_row(&sql, params![start_ts, end_ts, cli_key], |row| {
         let requests_success = row.get::<_, Option<i64>>("requests_success")?.unwrap_or(0);
         let success_duration_ms_sum = row
@@ -129,12 +83,13 @@ pub(super) fn summary_query(
         let io_total_tokens = input_tokens.saturating_add(output_tokens);
 
         Ok(UsageSummary {
-            requests_total: row.get::<_, i64>("requests_total")?,
+            requests_total: row.get::<_, Option<i64>>("requests_total")?.unwrap_or(0),
             requests_with_usage: row
                 .get::<_, Option<i64>>("requests_with_usage")?
                 .unwrap_or(0),
             requests_success,
             requests_failed: row.get::<_, Option<i64>>("requests_failed")?.unwrap_or(0),
+            upstream_overloaded,
             avg_duration_ms,
             avg_ttfb_ms,
             avg_output_tokens_per_second,
@@ -159,6 +114,28 @@ pub(super) fn summary_query(
     .map_err(|e| format!("DB_ERROR: failed to query usage summary: {e}"))
 }
 
+// Read from attempt logs: a 529 that failed over to another provider never reaches request_logs.
+fn upstream_overloaded_count(
+    conn: &Connection,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    cli_key: Option<&str>,
+) -> Result<i64, String> {
+    conn.query_row(
+        r#"
+SELECT COUNT(*)
+FROM request_attempt_logs
+WHERE status = 529
+AND (?1 IS NULL OR created_at >= ?1)
+AND (?2 IS NULL OR created_at < ?2)
+AND (?3 IS NULL OR cli_key = ?3)
+"#,
+        params![start_ts, end_ts, cli_key],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("DB_ERROR: failed to query overloaded attempts: {e}"))
+}
+
 pub fn summary(db: &db::Db, range: &str, cli_key: Option<&str>) -> Result<UsageSummary, String> {
     let conn = db.open_connection()?;
     let range = parse_range(range)?;
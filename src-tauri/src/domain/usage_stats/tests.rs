@@ -1,3 +1,4 @@
+use super::codex_session_completion::codex_session_completions_with_conn;
 use super::leaderboard_v2::leaderboard_v2_with_conn;
 use super::summary::summary_query;
 use super::*;
@@ -33,6 +34,46 @@ fn setup_conn() -> Connection {
 	  excluded_from_stats INTEGER NOT NULL DEFAULT 0,
 	  created_at INTEGER NOT NULL
 	);
+
+	CREATE TABLE usage_rollups_hourly (
+	  hour_ts INTEGER NOT NULL,
+	  cli_key TEXT NOT NULL,
+	  final_provider_id INTEGER NOT NULL,
+	  model_key TEXT NOT NULL,
+	  requests_total INTEGER NOT NULL,
+	  requests_with_usage INTEGER NOT NULL,
+	  requests_success INTEGER NOT NULL,
+	  requests_failed INTEGER NOT NULL,
+	  success_duration_ms_sum INTEGER NOT NULL,
+	  success_ttfb_ms_sum INTEGER NOT NULL,
+	  success_ttfb_ms_count INTEGER NOT NULL,
+	  success_generation_ms_sum INTEGER NOT NULL,
+	  success_output_tokens_for_rate_sum INTEGER NOT NULL,
+	  input_tokens INTEGER NOT NULL,
+	  output_tokens INTEGER NOT NULL,
+	  total_tokens INTEGER NOT NULL,
+	  cache_read_input_tokens INTEGER NOT NULL,
+	  cache_creation_input_tokens INTEGER NOT NULL,
+	  cache_creation_5m_input_tokens INTEGER NOT NULL,
+	  cache_creation_1h_input_tokens INTEGER NOT NULL,
+	  cost_usd_femto INTEGER NOT NULL,
+	  success_cost_covered INTEGER NOT NULL,
+	  success_cost_usd_femto INTEGER NOT NULL,
+	  success_costed_duration_ms_sum INTEGER NOT NULL,
+	  PRIMARY KEY(hour_ts, cli_key, final_provider_id, model_key)
+	);
+
+	CREATE TABLE usage_rollup_state (
+	  id INTEGER PRIMARY KEY,
+	  rolled_until INTEGER NOT NULL
+	);
+
+	CREATE TABLE request_attempt_logs (
+	  cli_key TEXT NOT NULL,
+	  provider_id INTEGER NOT NULL,
+	  status INTEGER,
+	  created_at INTEGER NOT NULL
+	);
 	"#,
     )
     .expect("create schema");
@@ -189,8 +230,22 @@ INSERT INTO request_logs (
     )
     .expect("insert claude");
 
+    conn.execute_batch(
+        r#"
+INSERT INTO request_attempt_logs (cli_key, provider_id, status, created_at) VALUES
+  ('claude', 789, 529, 1000),
+  ('claude', 790, 529, 2000),
+  ('claude', 789, 502, 1000),
+  ('codex', 456, 529, 1000);
+"#,
+    )
+    .expect("insert attempts");
+
     let summary = summary_query(&conn, None, None, None).expect("summary_query");
     assert_eq!(summary.requests_total, 3);
+    assert_eq!(summary.upstream_overloaded, 3);
+    let claude_early = summary_query(&conn, None, Some(1500), Some("claude")).expect("summary");
+    assert_eq!(claude_early.upstream_overloaded, 1);
     assert_eq!(summary.input_tokens, 520);
     assert_eq!(summary.output_tokens, 60);
     assert_eq!(summary.io_total_tokens, 580);
@@ -319,3 +374,87 @@ INSERT INTO request_logs (
     assert_eq!(row.requests_success, 2);
     assert_eq!(row.requests_failed, 0);
 }
+
+#[test]
+fn codex_session_completions_group_by_session() {
+    let conn = Connection::open_in_memory().expect("open in-memory sqlite");
+    conn.execute_batch(
+        r#"
+	CREATE TABLE request_logs (
+	  cli_key TEXT NOT NULL,
+	  session_id TEXT,
+	  created_at INTEGER NOT NULL
+	);
+
+	CREATE TABLE codex_session_completion_events (
+	  id INTEGER PRIMARY KEY AUTOINCREMENT,
+	  created_at INTEGER NOT NULL,
+	  trace_id TEXT NOT NULL,
+	  session_id TEXT NOT NULL,
+	  action TEXT NOT NULL,
+	  source TEXT NOT NULL,
+	  changed_header INTEGER NOT NULL DEFAULT 0,
+	  changed_body INTEGER NOT NULL DEFAULT 0
+	);
+	"#,
+    )
+    .expect("create schema");
+
+    let insert_event = |created_at: i64, session_id: &str, action: &str, source: &str| {
+        conn.execute(
+            "INSERT INTO codex_session_completion_events(created_at, trace_id, session_id, action, source, changed_header, changed_body) VALUES (?1, 't', ?2, ?3, ?4, 1, 1)",
+            params![created_at, session_id, action, source],
+        )
+        .expect("insert completion event");
+    };
+    insert_event(100, "s-generated", "generated_uuid_v7", "generated_uuid_v7");
+    insert_event(
+        110,
+        "s-generated",
+        "reused_fingerprint_cache",
+        "fingerprint_cache",
+    );
+    insert_event(
+        120,
+        "s-generated",
+        "reused_fingerprint_cache",
+        "fingerprint_cache",
+    );
+    insert_event(
+        130,
+        "s-client",
+        "completed_missing_fields",
+        "header_session_id",
+    );
+    insert_event(10, "s-old", "generated_uuid_v7", "generated_uuid_v7");
+    for session_id in ["s-generated", "s-generated", "s-generated", "s-client"] {
+        conn.execute(
+            "INSERT INTO request_logs(cli_key, session_id, created_at) VALUES ('codex', ?1, 100)",
+            params![session_id],
+        )
+        .expect("insert request log");
+    }
+
+    let report = codex_session_completions_with_conn(&conn, Some(50), 10)
+        .expect("codex_session_completions_with_conn");
+    assert_eq!(report.summary.completions, 4);
+    assert_eq!(report.summary.sessions, 2);
+    assert_eq!(report.summary.generated, 1);
+    assert_eq!(report.summary.reused_fingerprint_cache, 2);
+    assert_eq!(report.summary.completed_missing_fields, 1);
+    assert_eq!(report.summary.body_changes, 4);
+
+    assert_eq!(report.sessions.len(), 2);
+    let first = &report.sessions[0];
+    assert_eq!(first.session_id, "s-client");
+    assert_eq!(first.last_action, "completed_missing_fields");
+    assert_eq!(first.request_count, 1);
+    let second = &report.sessions[1];
+    assert_eq!(second.session_id, "s-generated");
+    assert_eq!(second.completions, 3);
+    assert_eq!(second.reused_fingerprint_cache, 2);
+    assert_eq!(second.first_seen_at, 100);
+    assert_eq!(second.last_seen_at, 120);
+    assert_eq!(second.last_source, "fingerprint_cache");
+    assert_eq!(second.request_count, 3);
+}
@@ -5,7 +5,7 @@ pub(super) fn token_total(total: Option<i64>, input: Option<i64>, output: Option
     input.unwrap_or(0).saturating_add(output.unwrap_or(0))
 }
 
-pub(super) const SQL_EFFECTIVE_INPUT_TOKENS_EXPR: &str = "CASE WHEN cli_key IN ('codex','gemini') THEN MAX(COALESCE(input_tokens, 0) - COALESCE(cache_read_input_tokens, 0), 0) ELSE COALESCE(input_tokens, 0) END";
+pub(crate) const SQL_EFFECTIVE_INPUT_TOKENS_EXPR: &str = "CASE WHEN cli_key IN ('codex','gemini') THEN MAX(COALESCE(input_tokens, 0) - COALESCE(cache_read_input_tokens, 0), 0) ELSE COALESCE(input_tokens, 0) END";
 
 pub(super) fn sql_effective_input_tokens_expr_with_alias(alias: &str) -> String {
     format!(
@@ -13,7 +13,7 @@ pub(super) fn sql_effective_input_tokens_expr_with_alias(alias: &str) -> String
     )
 }
 
-pub(super) fn sql_effective_total_tokens_expr() -> String {
+pub(crate) fn sql_effective_total_tokens_expr() -> String {
     format!(
         "({effective_input_expr}) + COALESCE(output_tokens, 0) + COALESCE(cache_creation_input_tokens, 0) + COALESCE(cache_read_input_tokens, 0)",
         effective_input_expr = SQL_EFFECTIVE_INPUT_TOKENS_EXPR
@@ -0,0 +1,109 @@
+//! Usage: Tool-usage analytics (tool calls extracted from request bodies by the gateway).
+
+use crate::db;
+use rusqlite::params;
+
+use super::{compute_start_ts, normalize_cli_filter, parse_range, UsageToolRow};
+
+pub(crate) struct ToolUsageRecord {
+    pub(crate) call_id: String,
+    pub(crate) tool_name: String,
+}
+
+/// Inserts tool calls; calls already recorded (same cli_key + call id, e.g. client retries) are ignored.
+pub(crate) fn record(
+    db: &db::Db,
+    created_at: i64,
+    cli_key: &str,
+    session_id: Option<&str>,
+    calls: &[ToolUsageRecord],
+) -> Result<usize, String> {
+    if calls.is_empty() {
+        return Ok(0);
+    }
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
+    let mut inserted = 0;
+    {
+        let mut stmt = tx
+            .prepare_cached(
+                "INSERT OR IGNORE INTO tool_usage_events(created_at, cli_key, session_id, tool_name, call_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+            )
+            .map_err(|e| format!("DB_ERROR: failed to prepare tool_usage insert: {e}"))?;
+        for call in calls {
+            inserted += stmt
+                .execute(params![
+                    created_at,
+                    cli_key,
+                    session_id,
+                    call.tool_name,
+                    call.call_id
+                ])
+                .map_err(|e| format!("DB_ERROR: failed to insert tool_usage_events: {e}"))?;
+        }
+    }
+    tx.commit()
+        .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+    Ok(inserted)
+}
+
+/// Aggregates tool calls by tool, or by (day, tool) / (session, tool) when `group_by` is "day" / "session".
+pub fn tool_usage(
+    db: &db::Db,
+    range: &str,
+    cli_key: Option<&str>,
+    group_by: &str,
+    limit: usize,
+) -> Result<Vec<UsageToolRow>, String> {
+    let conn = db.open_connection()?;
+    let range = parse_range(range)?;
+    let start_ts = compute_start_ts(&conn, range)?;
+    let cli_key = normalize_cli_filter(cli_key)?;
+    let group_expr = match group_by {
+        "tool" => "NULL",
+        "day" => "strftime('%Y-%m-%d', created_at, 'unixepoch', 'localtime')",
+        "session" => "COALESCE(session_id, '')",
+        _ => return Err(format!("SEC_INVALID_INPUT: unknown group_by={group_by}")),
+    };
+
+    let sql = format!(
+        r#"
+SELECT
+  {group_expr} AS group_key,
+  tool_name,
+  COUNT(*) AS call_count,
+  COUNT(DISTINCT session_id) AS session_count,
+  MAX(created_at) AS last_seen_at
+FROM tool_usage_events
+WHERE (?1 IS NULL OR created_at >= ?1)
+  AND (?2 IS NULL OR cli_key = ?2)
+GROUP BY group_key, tool_name
+ORDER BY group_key DESC, call_count DESC, tool_name ASC
+LIMIT ?3
+"#
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("DB_ERROR: failed to prepare tool_usage query: {e}"))?;
+    let rows = stmt
+        .query_map(params![start_ts, cli_key, limit as i64], |row| {
+            let tool_name: String = row.get("tool_name")?;
+            Ok(UsageToolRow {
+                group_key: row.get("group_key")?,
+                is_mcp: tool_name.starts_with("mcp__"),
+                tool_name,
+                call_count: row.get("call_count")?,
+                session_count: row.get("session_count")?,
+                last_seen_at: row.get("last_seen_at")?,
+            })
+        })
+        .map_err(|e| format!("DB_ERROR: failed to query tool_usage: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("DB_ERROR: failed to read tool_usage row: {e}"))?);
+    }
+    Ok(items)
+}
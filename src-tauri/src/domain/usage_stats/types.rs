@@ -6,6 +6,8 @@ pub struct UsageSummary {
     pub requests_with_usage: i64,
     pub requests_success: i64,
     pub requests_failed: i64,
+    // Upstream 529 overloaded attempts (counted per attempt, including ones that failed over).
+    pub upstream_overloaded: i64,
     pub avg_duration_ms: Option<i64>,
     pub avg_ttfb_ms: Option<i64>,
     pub avg_output_tokens_per_second: Option<f64>,
@@ -81,3 +83,46 @@ pub struct UsageLeaderboardRow {
     pub avg_output_tokens_per_second: Option<f64>,
     pub cost_usd: Option<f64>,
 }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageToolRow {
+    // Day (YYYY-MM-DD) or session id depending on group_by; None when grouped by tool only.
+    pub group_key: Option<String>,
+    pub tool_name: String,
+    pub is_mcp: bool,
+    pub call_count: i64,
+    pub session_count: i64,
+    pub last_seen_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodexSessionCompletionSummary {
+    pub completions: i64,
+    pub sessions: i64,
+    pub generated: i64,
+    pub reused_fingerprint_cache: i64,
+    pub completed_missing_fields: i64,
+    pub header_changes: i64,
+    pub body_changes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodexSessionCompletionSessionRow {
+    pub session_id: String,
+    pub completions: i64,
+    pub generated: i64,
+    pub reused_fingerprint_cache: i64,
+    pub completed_missing_fields: i64,
+    pub first_seen_at: i64,
+    pub last_seen_at: i64,
+    pub last_action: String,
+    pub last_source: String,
+    // Codex request logs carrying this session id (completed or not).
+    pub request_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodexSessionCompletionReport {
+    pub summary: CodexSessionCompletionSummary,
+    pub sessions: Vec<CodexSessionCompletionSessionRow>,
+}
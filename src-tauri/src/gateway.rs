@@ -1,17 +1,45 @@
+pub(crate) mod access;
+mod active_requests;
+mod body_budget;
+mod body_patch;
+mod clients;
 mod codex_session_id;
+mod context_guard;
+mod drain;
+pub(crate) mod event_buffer;
 mod events;
+mod header_sanitize;
+mod health;
+mod intercept;
 pub(crate) mod listen;
 mod manager;
+pub(crate) mod network_test;
+mod pause;
+mod prompt_cache;
 mod proxy;
+mod redaction;
+mod request_signing;
 mod response_fixer;
 mod routes;
+pub(crate) mod self_test;
 pub(crate) mod session_manager;
+pub(crate) mod speedtest;
 mod streams;
 mod thinking_signature_rectifier;
+mod tls;
+mod token_estimate;
+mod tool_usage;
+mod trace_headers;
+mod upstream_clients;
 mod util;
 mod warmup;
 
+pub use active_requests::GatewayInflightRequest;
+pub use drain::GatewayDrainReport;
+pub use event_buffer::GatewayBufferedEvent;
+pub use health::GatewayHealthSummary;
 pub use manager::GatewayManager;
+pub use pause::GatewayPauseStatus;
 
 use serde::Serialize;
 
@@ -21,6 +49,21 @@ pub struct GatewayStatus {
     pub port: Option<u16>,
     pub base_url: Option<String>,
     pub listen_addr: Option<String>,
+    pub extra_listeners: Vec<GatewayListenerStatus>,
+    // Result of the most recent drained stop (None until the gateway has been stopped once).
+    pub last_drain: Option<GatewayDrainReport>,
+    // User-initiated pause (maintenance mode): proxy requests get a local 503 `GW_PAUSED`.
+    pub pause: GatewayPauseStatus,
+    // Aggregate health while running (providers / circuits / log queues / last error).
+    pub health: Option<GatewayHealthSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayListenerStatus {
+    pub cli_key: String,
+    pub port: u16,
+    pub base_url: String,
+    pub listen_addr: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -32,3 +75,38 @@ pub struct GatewayProviderCircuitStatus {
     pub open_until: Option<i64>,
     pub cooldown_until: Option<i64>,
 }
+
+/// Dry-run result of `gateway_route_explain`: the providers a request would try right now.
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayRouteExplain {
+    pub cli_key: String,
+    pub model: Option<String>,
+    pub session_id: Option<String>,
+    pub gateway_running: bool,
+    pub sort_mode_id: Option<i64>,
+    // "session_binding" | "active"
+    pub sort_mode_source: &'static str,
+    pub schedule_rule_id: Option<i64>,
+    pub schedule_mode_id: Option<i64>,
+    pub session_bound_provider_id: Option<i64>,
+    pub max_providers_to_try: u32,
+    // Candidates in try order first, then excluded providers.
+    pub candidates: Vec<GatewayRouteCandidate>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayRouteCandidate {
+    pub provider_id: i64,
+    pub provider_name: String,
+    // 1-based try order; None when the provider would not be tried.
+    pub position: Option<u32>,
+    // Why the provider would be skipped: disabled | not_in_sort_mode | circuit_open |
+    // circuit_cooldown | beyond_max_providers_to_try.
+    pub excluded_reason: Option<&'static str>,
+    // Why it sits where it does: session_bound_provider | canary_selected | canary_fallback |
+    // ab_test_arm.
+    pub notes: Vec<&'static str>,
+    pub circuit_state: String,
+    pub available_at: Option<i64>,
+    pub upstream_model: Option<String>,
+}
@@ -0,0 +1,227 @@
+//! Usage: Gateway access-token check for non-loopback clients (LAN / WSL / remote dev boxes).
+//!
+//! 用法：
+//! - 设置 `gateway_access_token` 后，非本机（非 loopback）请求必须携带该 token
+//! - 支持 `Authorization: Bearer <token>`、`x-api-key`、`x-goog-api-key` 或查询参数 `key=<token>`（对应各 CLI 的 API Key 配置方式）
+//! - 本机请求（127.0.0.1 / ::1）与 `/`、`/health` 不校验；但 WSL 端口转发（netsh portproxy）生效期间，
+//!   WSL 请求经转发后同样来自 127.0.0.1，此时本机请求也需要携带令牌 / 命名 key
+//! - 查询参数 `key=` 先做 URL 解码再比较
+//! - 设置 `gateway_ip_allowlist`（CIDR 列表）后，非本机请求的来源地址必须命中其中一项，否则返回 403
+//!   `GW_IP_NOT_ALLOWED` 并记录网关日志；该检查先于令牌校验，且对 `/`、`/health` 同样生效
+//! - 也可使用 `gateway_client_keys` 中的命名 key；命中时按 key 限流并在 request_logs 中记录 `client_id`
+//!   （本机请求携带命名 key 时同样记录与限流）；未设置 `gateway_access_token` 时命名 key 只用于归属统计与限流，
+//!   不携带 key 的请求照常放行
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::borrow::Cow;
+use std::net::SocketAddr;
+
+use super::clients::{self, GatewayClient};
+use super::events::emit_gateway_log;
+use super::manager::GatewayAppState;
+use super::util::url_decode_component;
+use crate::shared::cidr;
+use crate::{settings, wsl};
+
+fn provided_tokens<'a>(headers: &'a HeaderMap, query: Option<&'a str>) -> Vec<Cow<'a, str>> {
+    let mut out = Vec::new();
+    if let Some(value) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+    {
+        let value = value.trim();
+        let token = value
+            .strip_prefix("Bearer ")
+            .or_else(|| value.strip_prefix("bearer "))
+            .unwrap_or(value);
+        out.push(Cow::Borrowed(token.trim()));
+    }
+    for name in ["x-api-key", "x-goog-api-key"] {
+        if let Some(value) = headers.get(name).and_then(|v| v.to_str().ok()) {
+            out.push(Cow::Borrowed(value.trim()));
+        }
+    }
+    if let Some(query) = query {
+        for pair in query.split('&') {
+            if let Some(value) = pair.strip_prefix("key=") {
+                out.push(Cow::Owned(url_decode_component(value)));
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+pub(super) fn token_matches(expected: &str, headers: &HeaderMap, query: Option<&str>) -> bool {
+    provided_tokens(headers, query)
+        .into_iter()
+        .any(|token| constant_time_eq(token.as_bytes(), expected.as_bytes()))
+}
+
+// A dual-stack listener reports IPv4 clients as `::ffff:127.0.0.1`, which `is_loopback` rejects.
+fn is_loopback_peer(peer: &SocketAddr) -> bool {
+    peer.ip().to_canonical().is_loopback()
+}
+
+/// Non-local access check: only enforced once an access token is configured; a named client key
+/// is accepted in place of the token.
+fn access_granted(
+    expected_token: &str,
+    client_matched: bool,
+    headers: &HeaderMap,
+    query: Option<&str>,
+) -> bool {
+    expected_token.is_empty() || client_matched || token_matches(expected_token, headers, query)
+}
+
+fn ip_allowed(allowlist: &[String], peer: &SocketAddr) -> bool {
+    if allowlist.is_empty() {
+        return true;
+    }
+    cidr::parse_list(allowlist)
+        .iter()
+        .any(|entry| entry.contains(peer.ip()))
+}
+
+pub(super) async fn require_access_token(
+    State(state): State<GatewayAppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    let cfg = settings::read(&state.app).unwrap_or_default();
+    if !is_loopback_peer(&peer) && !ip_allowed(&cfg.gateway_ip_allowlist, &peer) {
+        tracing::warn!(peer = %peer, path = %req.uri().path(), "来源地址不在 IP 白名单中，已拒绝请求");
+        emit_gateway_log(
+            &state.app,
+            "warn",
+            "GW_IP_NOT_ALLOWED",
+            format!("已拒绝来自 {} 的请求：不在 IP 白名单中", peer.ip()),
+        );
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error_code": "GW_IP_NOT_ALLOWED",
+                "message": "client address is not in the gateway IP allowlist",
+            })),
+        )
+            .into_response();
+    }
+
+    if matches!(req.uri().path(), "/" | "/health") {
+        return next.run(req).await;
+    }
+
+    let provided = provided_tokens(req.headers(), req.uri().query());
+    let client = clients::identify(&cfg.gateway_client_keys, &provided).cloned();
+
+    // With a WSL port proxy active, forwarded WSL traffic also arrives from 127.0.0.1.
+    let trusted_local = is_loopback_peer(&peer) && !wsl::port_proxy_active(&state.app);
+    if !trusted_local
+        && !access_granted(
+            &cfg.gateway_access_token,
+            client.is_some(),
+            req.headers(),
+            req.uri().query(),
+        )
+    {
+        tracing::warn!(peer = %peer, path = %req.uri().path(), "网关访问令牌校验失败，已拒绝请求");
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({
+                "error_code": "GW_ACCESS_TOKEN_INVALID",
+                "message": "missing or invalid gateway access token",
+            })),
+        )
+            .into_response();
+    }
+
+    if let Some(client) = client {
+        if let Err(retry_after) = clients::check_rate_limit(&client) {
+            tracing::warn!(client = %client.name, peer = %peer, "客户端 key 超出速率限制，已拒绝请求");
+            let mut resp = (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(serde_json::json!({
+                    "error_code": "GW_CLIENT_RATE_LIMITED",
+                    "message": format!(
+                        "client key '{}' exceeded {} requests per minute",
+                        client.name, client.rate_limit_per_minute
+                    ),
+                })),
+            )
+                .into_response();
+            if let Ok(value) = HeaderValue::from_str(&retry_after.to_string()) {
+                resp.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+            return resp;
+        }
+        req.extensions_mut().insert(GatewayClient {
+            id: client.id,
+            name: client.name,
+        });
+    }
+
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{access_granted, is_loopback_peer, token_matches};
+    use axum::http::{HeaderMap, HeaderValue};
+    use std::net::SocketAddr;
+
+    #[test]
+    fn ipv4_mapped_loopback_counts_as_local() {
+        let peer = |s: &str| s.parse::<SocketAddr>().unwrap();
+        assert!(is_loopback_peer(&peer("127.0.0.1:1")));
+        assert!(is_loopback_peer(&peer("[::1]:1")));
+        assert!(is_loopback_peer(&peer("[::ffff:127.0.0.1]:1")));
+        assert!(!is_loopback_peer(&peer("[::ffff:192.168.1.2]:1")));
+    }
+
+    #[test]
+    fn token_accepted_from_bearer_api_key_or_query() {
+        let token = "0123456789abcdef0123";
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "authorization",
+            HeaderValue::from_static("Bearer 0123456789abcdef0123"),
+        );
+        assert!(token_matches(token, &headers, None));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("wrong"));
+        assert!(!token_matches(token, &headers, None));
+        assert!(token_matches(
+            token,
+            &headers,
+            Some("alt=sse&key=0123456789abcdef0123")
+        ));
+
+        let token = "abc/def+=";
+        assert!(token_matches(token, &headers, Some("key=abc%2Fdef%2B%3D")));
+        assert!(!token_matches(token, &headers, Some("key=abc%2Fdef")));
+    }
+
+    #[test]
+    fn keyless_requests_pass_until_a_token_is_configured() {
+        let headers = HeaderMap::new();
+        assert!(access_granted("", false, &headers, None));
+        assert!(!access_granted("tok", false, &headers, None));
+        assert!(access_granted("tok", true, &headers, None));
+        assert!(access_granted("tok", false, &headers, Some("key=tok")));
+    }
+}
@@ -0,0 +1,370 @@
+//! Usage: Registry of currently executing gateway requests (for the in-flight list in the UI).
+//!
+//! 用法：
+//! - handler 在确定 trace_id 后 `register`，得到 `ActiveRequestGuard`；guard 随响应体结束（或被丢弃）而注销
+//! - failover 循环每次尝试前调用 `begin_attempt` 记录当前供应商与尝试序号
+//! - 响应体经 `hold_until_body_end` 包装后统计已转发给客户端的字节数
+//! - `gateway_inflight_list` 通过 `snapshot` 读取，按开始时间升序返回
+//! - `gateway_request_cancel` 调用 `cancel`：handler 在等待上游时收到信号即返回 499；流式响应则丢弃上游 body（断开连接），
+//!   并为 SSE 追加一条 CLI 原生格式的错误事件后正常结束。取消会写入 `request_cancel` special setting，
+//!   日志据此把 `GW_REQUEST_ABORTED`/`GW_STREAM_ABORTED` 记为 `GW_REQUEST_CANCELLED`
+
+use axum::body::{Body, Bytes, HttpBody};
+use axum::response::Response;
+use futures_core::Stream;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tokio::sync::Notify;
+
+use crate::shared::mutex_ext::MutexExt;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayInflightRequest {
+    pub trace_id: String,
+    pub cli_key: String,
+    pub method: String,
+    pub path: String,
+    pub provider_id: Option<i64>,
+    pub provider_name: Option<String>,
+    pub attempt: u32,
+    /// `connecting` until the response head is ready, then `streaming` while the body is relayed.
+    pub phase: &'static str,
+    pub started_at_ms: i64,
+    pub elapsed_ms: u64,
+    pub bytes_streamed: u64,
+}
+
+#[derive(Default)]
+struct AttemptProgress {
+    provider_id: Option<i64>,
+    provider_name: Option<String>,
+    attempt: u32,
+    streaming: bool,
+}
+
+pub(super) struct ActiveRequest {
+    trace_id: String,
+    cli_key: String,
+    method: String,
+    path: String,
+    started: Instant,
+    started_at_ms: i64,
+    progress: Mutex<AttemptProgress>,
+    bytes_streamed: AtomicU64,
+    special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
+    cancelled: AtomicBool,
+    cancel_notify: Notify,
+}
+
+impl ActiveRequest {
+    pub(super) fn begin_attempt(&self, attempt: u32, provider_id: i64, provider_name: &str) {
+        let mut progress = self.progress.lock_or_recover();
+        progress.attempt = attempt;
+        progress.provider_id = Some(provider_id);
+        progress.provider_name = Some(provider_name.to_string());
+    }
+
+    pub(super) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Resolves once the request is cancelled (immediately if it already was).
+    pub(super) fn cancelled(self: &Arc<Self>) -> impl Future<Output = ()> + Send + 'static {
+        let this = self.clone();
+        async move {
+            loop {
+                let notified = this.cancel_notify.notified();
+                if this.is_cancelled() {
+                    return;
+                }
+                notified.await;
+            }
+        }
+    }
+
+    /// Returns `false` if the request had already been cancelled.
+    fn cancel(&self) -> bool {
+        if self.cancelled.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+        let (phase, bytes_streamed) = {
+            let progress = self.progress.lock_or_recover();
+            (
+                if progress.streaming {
+                    "streaming"
+                } else {
+                    "connecting"
+                },
+                self.bytes_streamed.load(Ordering::Relaxed),
+            )
+        };
+        if let Ok(mut settings) = self.special_settings.lock() {
+            settings.push(serde_json::json!({
+                "type": "request_cancel",
+                "scope": "request",
+                "hit": true,
+                "reason": "cancelled_from_ui",
+                "phase": phase,
+                "elapsed_ms": self.started.elapsed().as_millis().min(u64::MAX as u128) as u64,
+                "bytes_streamed": bytes_streamed,
+            }));
+        }
+        self.cancel_notify.notify_waiters();
+        true
+    }
+
+    fn snapshot(&self) -> GatewayInflightRequest {
+        let progress = self.progress.lock_or_recover();
+        GatewayInflightRequest {
+            trace_id: self.trace_id.clone(),
+            cli_key: self.cli_key.clone(),
+            method: self.method.clone(),
+            path: self.path.clone(),
+            provider_id: progress.provider_id,
+            provider_name: progress.provider_name.clone(),
+            attempt: progress.attempt,
+            phase: if progress.streaming {
+                "streaming"
+            } else {
+                "connecting"
+            },
+            started_at_ms: self.started_at_ms,
+            elapsed_ms: self.started.elapsed().as_millis().min(u64::MAX as u128) as u64,
+            bytes_streamed: self.bytes_streamed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(in crate::gateway) struct ActiveRequests {
+    next_id: AtomicU64,
+    // Keyed by a registry id: recent-trace dedup may let two requests share one trace_id.
+    entries: Mutex<HashMap<u64, Arc<ActiveRequest>>>,
+}
+
+impl ActiveRequests {
+    pub(super) fn register(
+        self: &Arc<Self>,
+        trace_id: &str,
+        cli_key: &str,
+        method: &str,
+        path: &str,
+        started_at_ms: i64,
+        special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
+    ) -> ActiveRequestGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let entry = Arc::new(ActiveRequest {
+            trace_id: trace_id.to_string(),
+            cli_key: cli_key.to_string(),
+            method: method.to_string(),
+            path: path.to_string(),
+            started: Instant::now(),
+            started_at_ms,
+            progress: Mutex::new(AttemptProgress::default()),
+            bytes_streamed: AtomicU64::new(0),
+            special_settings,
+            cancelled: AtomicBool::new(false),
+            cancel_notify: Notify::new(),
+        });
+        self.entries.lock_or_recover().insert(id, entry.clone());
+        ActiveRequestGuard {
+            registry: self.clone(),
+            id,
+            entry,
+        }
+    }
+
+    pub(in crate::gateway) fn snapshot(&self) -> Vec<GatewayInflightRequest> {
+        let mut items: Vec<GatewayInflightRequest> = self
+            .entries
+            .lock_or_recover()
+            .values()
+            .map(|entry| entry.snapshot())
+            .collect();
+        items.sort_by(|a, b| {
+            a.started_at_ms
+                .cmp(&b.started_at_ms)
+                .then_with(|| a.trace_id.cmp(&b.trace_id))
+        });
+        items
+    }
+
+    /// Cancels every in-flight request with this trace id; returns how many were cancelled.
+    pub(in crate::gateway) fn cancel(&self, trace_id: &str) -> usize {
+        let targets: Vec<Arc<ActiveRequest>> = self
+            .entries
+            .lock_or_recover()
+            .values()
+            .filter(|entry| entry.trace_id == trace_id)
+            .cloned()
+            .collect();
+        targets.iter().filter(|entry| entry.cancel()).count()
+    }
+}
+
+/// Whether `gateway_request_cancel` hit this request (see `ActiveRequest::cancel`).
+pub(in crate::gateway) fn was_cancelled(special_settings: &Mutex<Vec<serde_json::Value>>) -> bool {
+    special_settings
+        .lock_or_recover()
+        .iter()
+        .any(|v| v.get("type").and_then(|t| t.as_str()) == Some("request_cancel"))
+}
+
+/// Abort codes are recorded as `GW_REQUEST_CANCELLED` when the abort came from a UI cancel.
+pub(in crate::gateway) fn cancel_aware_error_code(
+    error_code: Option<&'static str>,
+    special_settings: &Mutex<Vec<serde_json::Value>>,
+) -> Option<&'static str> {
+    match error_code {
+        Some("GW_REQUEST_ABORTED" | "GW_STREAM_ABORTED") if was_cancelled(special_settings) => {
+            Some("GW_REQUEST_CANCELLED")
+        }
+        other => other,
+    }
+}
+
+/// Registration of one request; unregisters on drop.
+pub(super) struct ActiveRequestGuard {
+    registry: Arc<ActiveRequests>,
+    id: u64,
+    entry: Arc<ActiveRequest>,
+}
+
+impl Drop for ActiveRequestGuard {
+    fn drop(&mut self) {
+        self.registry.entries.lock_or_recover().remove(&self.id);
+    }
+}
+
+impl ActiveRequestGuard {
+    pub(super) fn entry(&self) -> Arc<ActiveRequest> {
+        self.entry.clone()
+    }
+
+    /// Keeps the request listed until its response body has been relayed, counting relayed bytes.
+    /// Fully buffered responses (exact size) are complete already and unregister right away.
+    ///
+    /// On cancel the upstream body is dropped (closing the connection) and `cancel_tail`, if any,
+    /// is sent as the last chunk so the client sees a clean error instead of a hung stream.
+    pub(super) fn hold_until_body_end(
+        self,
+        resp: Response,
+        cancel_tail: Option<Bytes>,
+    ) -> Response {
+        if resp.body().size_hint().exact().is_some() {
+            return resp;
+        }
+        self.entry.progress.lock_or_recover().streaming = true;
+        let cancelled = Box::pin(self.entry.cancelled());
+        resp.map(|body| {
+            Body::from_stream(CountingBodyStream {
+                inner: Some(body.into_data_stream()),
+                cancelled,
+                cancel_tail,
+                guard: self,
+            })
+        })
+    }
+}
+
+struct CountingBodyStream {
+    inner: Option<axum::body::BodyDataStream>,
+    cancelled: Pin<Box<dyn Future<Output = ()> + Send>>,
+    cancel_tail: Option<Bytes>,
+    guard: ActiveRequestGuard,
+}
+
+impl Stream for CountingBodyStream {
+    type Item = Result<Bytes, axum::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.as_mut().get_mut();
+        if this.inner.is_some() && this.cancelled.as_mut().poll(cx).is_ready() {
+            this.inner = None;
+        }
+        let Some(inner) = this.inner.as_mut() else {
+            return Poll::Ready(this.cancel_tail.take().map(Ok));
+        };
+        let next = Pin::new(inner).poll_next(cx);
+        if let Poll::Ready(Some(Ok(chunk))) = &next {
+            this.guard
+                .entry
+                .bytes_streamed
+                .fetch_add(chunk.len() as u64, Ordering::Relaxed);
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> Arc<Mutex<Vec<serde_json::Value>>> {
+        Arc::new(Mutex::new(Vec::new()))
+    }
+
+    #[test]
+    fn lists_registered_requests_until_guard_drops() {
+        let registry = Arc::new(ActiveRequests::default());
+        let first = registry.register("t1", "claude", "POST", "/v1/messages", 10, settings());
+        let second = registry.register("t1", "claude", "POST", "/v1/messages", 20, settings());
+        first.entry().begin_attempt(2, 7, "relay-a");
+
+        let items = registry.snapshot();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].started_at_ms, 10);
+        assert_eq!(items[0].attempt, 2);
+        assert_eq!(items[0].provider_name.as_deref(), Some("relay-a"));
+        assert_eq!(items[0].phase, "connecting");
+        assert_eq!(items[1].provider_id, None);
+
+        drop(first);
+        assert_eq!(registry.snapshot().len(), 1);
+        drop(second);
+        assert!(registry.snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_wakes_waiters_and_marks_special_settings() {
+        let registry = Arc::new(ActiveRequests::default());
+        let special_settings = settings();
+        let guard = registry.register(
+            "t1",
+            "codex",
+            "POST",
+            "/v1/responses",
+            10,
+            special_settings.clone(),
+        );
+        let other = registry.register("t2", "codex", "POST", "/v1/responses", 20, settings());
+        let waiter = tokio::spawn(guard.entry().cancelled());
+
+        assert_eq!(registry.cancel("missing"), 0);
+        assert_eq!(registry.cancel("t1"), 1);
+        assert_eq!(registry.cancel("t1"), 0);
+        waiter.await.expect("cancel waiter");
+        guard.entry().cancelled().await;
+
+        assert!(guard.entry().is_cancelled());
+        assert!(!other.entry().is_cancelled());
+        assert_eq!(
+            cancel_aware_error_code(Some("GW_STREAM_ABORTED"), &special_settings),
+            Some("GW_REQUEST_CANCELLED")
+        );
+        assert_eq!(
+            cancel_aware_error_code(Some("GW_UPSTREAM_TIMEOUT"), &special_settings),
+            Some("GW_UPSTREAM_TIMEOUT")
+        );
+        assert_eq!(
+            cancel_aware_error_code(Some("GW_REQUEST_ABORTED"), &settings()),
+            Some("GW_REQUEST_ABORTED")
+        );
+    }
+}
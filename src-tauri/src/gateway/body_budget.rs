@@ -0,0 +1,165 @@
+//! Usage: Global memory budget shared by all buffered non-SSE response bodies.
+//!
+//! 用法：
+//! - 非流式成功响应整段读入内存前，按 content-length 调用 `try_reserve`；预算不足时改为直通转发（仅记录耗时）
+//! - `UsageBodyBufferTeeStream` 边转发边缓存用于解析 usage，每个 chunk 调用 `try_grow`；超出预算即丢弃缓存，降级为 timing-only
+//! - `BufferReservation` 在 drop 时归还额度；`hold_until_body_end` 让额度持续到响应体发送完毕
+//!
+//! The per-response cap (`MAX_NON_SSE_BODY_BYTES`) still applies; this budget bounds the sum across
+//! concurrent responses so a burst of large downloads can't OOM the app.
+
+use super::streams;
+use axum::response::Response;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub(in crate::gateway) const GLOBAL_BODY_BUFFER_BUDGET_BYTES: usize = 128 * 1024 * 1024;
+
+static GLOBAL: BufferBudget = BufferBudget::new(GLOBAL_BODY_BUFFER_BUDGET_BYTES);
+
+pub(in crate::gateway) struct BufferBudget {
+    limit: usize,
+    in_use: AtomicUsize,
+}
+
+impl BufferBudget {
+    const fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            in_use: AtomicUsize::new(0),
+        }
+    }
+
+    fn try_take(&self, bytes: usize) -> bool {
+        self.in_use
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                let next = current.checked_add(bytes)?;
+                (next <= self.limit).then_some(next)
+            })
+            .is_ok()
+    }
+
+    fn give_back(&self, bytes: usize) {
+        let _ = self
+            .in_use
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                Some(current.saturating_sub(bytes))
+            });
+    }
+
+    fn in_use(&self) -> usize {
+        self.in_use.load(Ordering::Acquire)
+    }
+}
+
+/// Bytes currently held against the global budget.
+pub(in crate::gateway) fn in_use_bytes() -> usize {
+    GLOBAL.in_use()
+}
+
+/// Reserves `bytes` up front (e.g. a known content-length); `None` when the budget is exhausted.
+pub(in crate::gateway) fn try_reserve(bytes: usize) -> Option<BufferReservation> {
+    let mut reservation = BufferReservation::empty();
+    reservation.try_grow(bytes).then_some(reservation)
+}
+
+/// Budget share held by one buffer; released on drop.
+pub(in crate::gateway) struct BufferReservation {
+    budget: &'static BufferBudget,
+    bytes: usize,
+}
+
+impl BufferReservation {
+    pub(in crate::gateway) fn empty() -> Self {
+        Self::empty_in(&GLOBAL)
+    }
+
+    fn empty_in(budget: &'static BufferBudget) -> Self {
+        Self { budget, bytes: 0 }
+    }
+
+    pub(in crate::gateway) fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub(in crate::gateway) fn try_grow(&mut self, additional: usize) -> bool {
+        if additional == 0 {
+            return true;
+        }
+        if !self.budget.try_take(additional) {
+            return false;
+        }
+        self.bytes += additional;
+        true
+    }
+
+    /// Accounts for memory that is already allocated (e.g. a body that grew after gunzip), even if
+    /// that overshoots the budget; later reservations fail until it is released.
+    pub(in crate::gateway) fn track(&mut self, bytes: usize) {
+        if bytes > self.bytes {
+            self.budget
+                .in_use
+                .fetch_add(bytes - self.bytes, Ordering::AcqRel);
+            self.bytes = bytes;
+        }
+    }
+
+    pub(in crate::gateway) fn release(&mut self) {
+        if self.bytes > 0 {
+            self.budget.give_back(self.bytes);
+            self.bytes = 0;
+        }
+    }
+
+    /// Keeps the reservation until the response body has been sent (or dropped).
+    pub(in crate::gateway) fn hold_until_body_end(self, resp: Response) -> Response {
+        streams::hold_until_body_end(resp, self)
+    }
+}
+
+impl Drop for BufferReservation {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaked_budget(limit: usize) -> &'static BufferBudget {
+        Box::leak(Box::new(BufferBudget::new(limit)))
+    }
+
+    #[test]
+    fn grows_until_budget_is_exhausted() {
+        let budget = leaked_budget(100);
+        let mut first = BufferReservation::empty_in(budget);
+        let mut second = BufferReservation::empty_in(budget);
+
+        assert!(first.try_grow(60));
+        assert!(!second.try_grow(50));
+        assert!(second.try_grow(40));
+        assert_eq!(budget.in_use(), 100);
+        assert!(!first.try_grow(1));
+
+        drop(first);
+        assert_eq!(budget.in_use(), 40);
+        assert!(second.try_grow(60));
+    }
+
+    #[test]
+    fn release_and_track_adjust_usage() {
+        let budget = leaked_budget(100);
+        let mut reservation = BufferReservation::empty_in(budget);
+        assert!(reservation.try_grow(30));
+
+        reservation.track(120);
+        assert_eq!(reservation.bytes(), 120);
+        assert_eq!(budget.in_use(), 120);
+        assert!(!BufferReservation::empty_in(budget).try_grow(1));
+
+        reservation.release();
+        assert_eq!(reservation.bytes(), 0);
+        assert_eq!(budget.in_use(), 0);
+    }
+}
@@ -0,0 +1,223 @@
+//! Usage: Per-provider request body patch rules (set / delete / rename JSON fields before forwarding).
+//!
+//! 用法：
+//! - Provider 配置 `body_patch_rules` 后，每次尝试转发前按顺序作用于 JSON 请求体；非 JSON 请求体原样转发
+//! - 路径为 JSONPath 风格：`$.metadata`、`generationConfig.temperature`、`messages[0].content`、`tools[*].cache_control`
+//! - `set` 覆盖或新增字段（缺失的中间对象会自动创建，数组下标必须已存在）；`delete` 删除字段 / 数组元素；`rename` 在同一对象内改名
+//! - 只有实际改变了请求体的规则才计入结果，调用方据此写入 `special_settings_json`
+
+use crate::providers::{parse_body_patch_path, BodyPatchOp, BodyPatchPathSegment, BodyPatchRule};
+use serde_json::Value;
+
+/// Applies `rules` in order; returns the rules that changed `body`.
+pub(super) fn apply_body_patch_rules<'a>(
+    body: &mut Value,
+    rules: &'a [BodyPatchRule],
+) -> Vec<&'a BodyPatchRule> {
+    rules
+        .iter()
+        .filter(|rule| {
+            // Rules are validated on save; an unparsable path from an older row is skipped.
+            let Ok(segments) = parse_body_patch_path(&rule.path) else {
+                return false;
+            };
+            apply_at(body, &segments, rule)
+        })
+        .collect()
+}
+
+fn apply_at(node: &mut Value, segments: &[BodyPatchPathSegment], rule: &BodyPatchRule) -> bool {
+    let Some((segment, rest)) = segments.split_first() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return apply_last(node, segment, rule);
+    }
+
+    match segment {
+        BodyPatchPathSegment::Key(key) => {
+            let Some(obj) = node.as_object_mut() else {
+                return false;
+            };
+            // Missing intermediate objects are created for `set` (indexes must already exist).
+            let creates = rule.op == BodyPatchOp::Set
+                && matches!(rest.first(), Some(BodyPatchPathSegment::Key(_)));
+            if creates && !obj.contains_key(key) {
+                obj.insert(key.clone(), Value::Object(Default::default()));
+            }
+            obj.get_mut(key)
+                .is_some_and(|child| apply_at(child, rest, rule))
+        }
+        BodyPatchPathSegment::Index(index) => node
+            .as_array_mut()
+            .and_then(|arr| arr.get_mut(*index))
+            .is_some_and(|child| apply_at(child, rest, rule)),
+        BodyPatchPathSegment::AnyIndex => {
+            let Some(arr) = node.as_array_mut() else {
+                return false;
+            };
+            // Every element is visited; `any` would stop at the first change.
+            let mut changed = false;
+            for child in arr.iter_mut() {
+                changed |= apply_at(child, rest, rule);
+            }
+            changed
+        }
+    }
+}
+
+fn apply_last(node: &mut Value, segment: &BodyPatchPathSegment, rule: &BodyPatchRule) -> bool {
+    match (rule.op, segment) {
+        (BodyPatchOp::Set, BodyPatchPathSegment::Key(key)) => {
+            let Some(obj) = node.as_object_mut() else {
+                return false;
+            };
+            if obj.get(key) == Some(&rule.value) {
+                return false;
+            }
+            obj.insert(key.clone(), rule.value.clone());
+            true
+        }
+        (BodyPatchOp::Set, BodyPatchPathSegment::Index(index)) => {
+            match node.as_array_mut().and_then(|arr| arr.get_mut(*index)) {
+                Some(slot) if *slot != rule.value => {
+                    *slot = rule.value.clone();
+                    true
+                }
+                _ => false,
+            }
+        }
+        (BodyPatchOp::Delete, BodyPatchPathSegment::Key(key)) => node
+            .as_object_mut()
+            .is_some_and(|obj| obj.remove(key).is_some()),
+        (BodyPatchOp::Delete, BodyPatchPathSegment::Index(index)) => match node.as_array_mut() {
+            Some(arr) if *index < arr.len() => {
+                arr.remove(*index);
+                true
+            }
+            _ => false,
+        },
+        (BodyPatchOp::Delete, BodyPatchPathSegment::AnyIndex) => match node.as_array_mut() {
+            Some(arr) if !arr.is_empty() => {
+                arr.clear();
+                true
+            }
+            _ => false,
+        },
+        (BodyPatchOp::Rename, BodyPatchPathSegment::Key(from)) => {
+            let Some(obj) = node.as_object_mut() else {
+                return false;
+            };
+            let Some(value) = obj.remove(from) else {
+                return false;
+            };
+            obj.insert(rule.to.clone(), value);
+            true
+        }
+        // `set` on `[*]` and `rename` on an index are rejected when the rule is saved.
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(op: BodyPatchOp, path: &str, value: Value, to: &str) -> BodyPatchRule {
+        BodyPatchRule {
+            op,
+            path: path.to_string(),
+            value,
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn set_delete_and_rename_apply_in_order() {
+        let mut body = json!({
+            "model": "m",
+            "metadata": {"user_id": "u"},
+            "service_tier": "flex",
+            "max_tokens": 100,
+        });
+        let rules = vec![
+            rule(BodyPatchOp::Delete, "$.metadata", Value::Null, ""),
+            rule(BodyPatchOp::Delete, "service_tier", Value::Null, ""),
+            rule(BodyPatchOp::Set, "temperature", json!(0.2), ""),
+            rule(BodyPatchOp::Set, "$.reasoning.effort", json!("high"), ""),
+            rule(
+                BodyPatchOp::Rename,
+                "max_tokens",
+                Value::Null,
+                "max_output_tokens",
+            ),
+        ];
+
+        let applied = apply_body_patch_rules(&mut body, &rules);
+
+        assert_eq!(applied.len(), 5);
+        assert_eq!(
+            body,
+            json!({
+                "model": "m",
+                "temperature": 0.2,
+                "reasoning": {"effort": "high"},
+                "max_output_tokens": 100,
+            })
+        );
+    }
+
+    #[test]
+    fn unchanged_rules_are_not_reported() {
+        let mut body = json!({"temperature": 0.2, "messages": []});
+        let rules = vec![
+            rule(BodyPatchOp::Set, "temperature", json!(0.2), ""),
+            rule(BodyPatchOp::Delete, "metadata", Value::Null, ""),
+            rule(
+                BodyPatchOp::Rename,
+                "max_tokens",
+                Value::Null,
+                "max_output_tokens",
+            ),
+            rule(BodyPatchOp::Set, "messages[0].role", json!("user"), ""),
+        ];
+
+        assert!(apply_body_patch_rules(&mut body, &rules).is_empty());
+        assert_eq!(body, json!({"temperature": 0.2, "messages": []}));
+    }
+
+    #[test]
+    fn wildcard_and_index_paths() {
+        let mut body = json!({
+            "messages": [
+                {"role": "user", "content": "a", "cache_control": {"type": "ephemeral"}},
+                {"role": "assistant", "content": "b"},
+                {"role": "user", "content": "c", "cache_control": {"type": "ephemeral"}},
+            ],
+        });
+        let rules = vec![
+            rule(
+                BodyPatchOp::Delete,
+                "messages[*].cache_control",
+                Value::Null,
+                "",
+            ),
+            rule(BodyPatchOp::Set, "messages[1].content", json!("B"), ""),
+            rule(BodyPatchOp::Delete, "messages[2]", Value::Null, ""),
+        ];
+
+        let applied = apply_body_patch_rules(&mut body, &rules);
+
+        assert_eq!(applied.len(), 3);
+        assert_eq!(
+            body,
+            json!({
+                "messages": [
+                    {"role": "user", "content": "a"},
+                    {"role": "assistant", "content": "B"},
+                ],
+            })
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Usage: Named per-client gateway keys (identification, per-key rate limit, usage attribution).
+//!
+//! 用法：
+//! - `settings.gateway_client_keys` 中每个 key 对应一个客户端；`access` 中间件按请求携带的 token 识别客户端，
+//!   并把 `GatewayClient` 放入请求 extensions
+//! - `rate_limit_per_minute > 0` 时按固定 60 秒窗口限流，超出返回 429 `GW_CLIENT_RATE_LIMITED`
+//! - 代理处理器把 `GatewayClient.id` 记入请求的 `RequestLogAttribution`，随请求写入 `request_logs.client_id`
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::settings::GatewayClientKey;
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GatewayClient {
+    pub(crate) id: String,
+    pub(crate) name: String,
+}
+
+#[derive(Debug, Default)]
+struct RateWindow {
+    started: Option<Instant>,
+    count: u32,
+}
+
+static RATE_WINDOWS: OnceLock<Mutex<HashMap<String, RateWindow>>> = OnceLock::new();
+
+/// Finds the enabled client key matching one of the provided tokens.
+pub(super) fn identify<'a>(
+    keys: &'a [GatewayClientKey],
+    provided: &[Cow<'_, str>],
+) -> Option<&'a GatewayClientKey> {
+    keys.iter().filter(|item| item.enabled).find(|item| {
+        provided
+            .iter()
+            .any(|token| super::access::constant_time_eq(token.as_bytes(), item.key.as_bytes()))
+    })
+}
+
+fn check_window(window: &mut RateWindow, limit: u32, now: Instant) -> Result<(), u64> {
+    let started = match window.started {
+        Some(started) if now.duration_since(started) < RATE_LIMIT_WINDOW => started,
+        _ => {
+            window.started = Some(now);
+            window.count = 0;
+            now
+        }
+    };
+    if window.count >= limit {
+        let elapsed = now.duration_since(started);
+        let retry_after = RATE_LIMIT_WINDOW.saturating_sub(elapsed).as_secs().max(1);
+        return Err(retry_after);
+    }
+    window.count += 1;
+    Ok(())
+}
+
+/// Counts one request against the client's per-minute limit; `Err(retry_after_seconds)` when exceeded.
+pub(super) fn check_rate_limit(client: &GatewayClientKey) -> Result<(), u64> {
+    if client.rate_limit_per_minute == 0 {
+        return Ok(());
+    }
+    let windows = RATE_WINDOWS.get_or_init(|| Mutex::new(HashMap::new()));
+    let Ok(mut windows) = windows.lock() else {
+        return Ok(());
+    };
+    let window = windows.entry(client.id.clone()).or_default();
+    check_window(window, client.rate_limit_per_minute, Instant::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_window, RateWindow, RATE_LIMIT_WINDOW};
+    use std::time::Instant;
+
+    #[test]
+    fn fixed_window_limits_and_resets() {
+        let mut window = RateWindow::default();
+        let now = Instant::now();
+        assert!(check_window(&mut window, 2, now).is_ok());
+        assert!(check_window(&mut window, 2, now).is_ok());
+        assert!(check_window(&mut window, 2, now).is_err());
+        assert!(check_window(&mut window, 2, now + RATE_LIMIT_WINDOW).is_ok());
+    }
+}
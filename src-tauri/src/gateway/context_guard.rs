@@ -0,0 +1,316 @@
+//! Usage: Context guard — shrink oversized conversation bodies to a provider's payload limit.
+//!
+//! 用法：
+//! - Provider 配置 `context_guard_limit_kb`（0 = 关闭）后，序列化后的请求体超过上限时在转发前裁剪，避免上游 413
+//! - 裁剪顺序：先截断较早消息中的工具结果，再从最早开始丢弃消息，最后才截断最新一条消息里的工具结果
+//! - system / developer 消息与最新一条消息始终保留；丢弃后会话仍从一条普通 user 消息开始（不留下孤立的 tool_result）
+//! - 裁剪结果附带本地 `tokenizer` 估算的裁剪前后 token 数，便于在上游未返回 usage 时判断效果
+//! - 支持 `messages`（Claude / OpenAI Chat）、`input`（Responses）、`contents`（Gemini）三种会话结构
+
+use crate::tokenizer;
+use serde_json::Value;
+
+// 截断后的工具结果保留的前缀字节数
+const TOOL_RESULT_KEEP_BYTES: usize = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Messages,
+    Responses,
+    Gemini,
+}
+
+#[derive(Debug)]
+pub(super) struct ContextTrim {
+    pub(super) body: Vec<u8>,
+    pub(super) original_bytes: usize,
+    pub(super) dropped_messages: usize,
+    pub(super) truncated_tool_results: usize,
+    // Local prompt token estimates before / after trimming (upstream usage is not known yet).
+    pub(super) original_tokens: u64,
+    pub(super) trimmed_tokens: u64,
+    // Whether the trimmed body is within the limit; `false` means the guard did what it could.
+    pub(super) fits: bool,
+}
+
+fn conversation_key(root: &serde_json::Map<String, Value>) -> Option<(&'static str, Dialect)> {
+    [
+        ("messages", Dialect::Messages),
+        ("input", Dialect::Responses),
+        ("contents", Dialect::Gemini),
+    ]
+    .into_iter()
+    .find(|(key, _)| root.get(*key).is_some_and(Value::is_array))
+}
+
+fn json_len(value: &Value) -> usize {
+    serde_json::to_vec(value).map(|v| v.len()).unwrap_or(0)
+}
+
+fn role(item: &Value) -> Option<&str> {
+    item.get("role").and_then(Value::as_str)
+}
+
+/// system / developer instructions are never dropped.
+fn is_pinned(item: &Value) -> bool {
+    matches!(role(item), Some("system" | "developer"))
+}
+
+fn has_block_type(content: Option<&Value>, block_type: &str) -> bool {
+    content.and_then(Value::as_array).is_some_and(|blocks| {
+        blocks
+            .iter()
+            .any(|b| b.get("type").and_then(Value::as_str) == Some(block_type))
+    })
+}
+
+/// A conversation may only start at a plain user turn (no dangling tool results).
+fn is_clean_start(dialect: Dialect, item: &Value) -> bool {
+    match dialect {
+        Dialect::Messages => {
+            role(item) == Some("user") && !has_block_type(item.get("content"), "tool_result")
+        }
+        Dialect::Responses => {
+            role(item) == Some("user")
+                && item
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .is_none_or(|t| t == "message")
+        }
+        Dialect::Gemini => {
+            role(item) == Some("user")
+                && !item
+                    .get("parts")
+                    .and_then(Value::as_array)
+                    .is_some_and(|parts| parts.iter().any(|p| p.get("functionResponse").is_some()))
+        }
+    }
+}
+
+fn truncate_text(text: &mut String) -> bool {
+    if text.len() <= TOOL_RESULT_KEEP_BYTES * 2 {
+        return false;
+    }
+    let mut cut = TOOL_RESULT_KEEP_BYTES;
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    let removed = text.len() - cut;
+    text.truncate(cut);
+    text.push_str(&format!(
+        "\n…[aio-coding-hub context guard: truncated {removed} bytes]"
+    ));
+    true
+}
+
+fn truncate_strings(value: &mut Value) -> bool {
+    match value {
+        Value::String(text) => truncate_text(text),
+        Value::Array(items) => items
+            .iter_mut()
+            .fold(false, |acc, item| truncate_strings(item) | acc),
+        Value::Object(map) => map
+            .values_mut()
+            .fold(false, |acc, item| truncate_strings(item) | acc),
+        _ => false,
+    }
+}
+
+/// Truncates the tool results carried by one conversation item; returns how many were shortened.
+fn truncate_tool_results(dialect: Dialect, item: &mut Value) -> usize {
+    let mut truncated = 0;
+    match dialect {
+        Dialect::Messages if role(item) == Some("tool") => {
+            if let Some(content) = item.get_mut("content") {
+                truncated += usize::from(truncate_strings(content));
+            }
+        }
+        Dialect::Messages => {
+            if let Some(blocks) = item.get_mut("content").and_then(Value::as_array_mut) {
+                for block in blocks.iter_mut() {
+                    if block.get("type").and_then(Value::as_str) != Some("tool_result") {
+                        continue;
+                    }
+                    if let Some(content) = block.get_mut("content") {
+                        truncated += usize::from(truncate_strings(content));
+                    }
+                }
+            }
+        }
+        Dialect::Responses => {
+            let is_output = item
+                .get("type")
+                .and_then(Value::as_str)
+                .is_some_and(|t| t.ends_with("call_output"));
+            if let Some(output) = item.get_mut("output").filter(|_| is_output) {
+                truncated += usize::from(truncate_strings(output));
+            }
+        }
+        Dialect::Gemini => {
+            if let Some(parts) = item.get_mut("parts").and_then(Value::as_array_mut) {
+                for part in parts.iter_mut() {
+                    if let Some(response) = part.get_mut("functionResponse") {
+                        truncated += usize::from(truncate_strings(response));
+                    }
+                }
+            }
+        }
+    }
+    truncated
+}
+
+/// Shrinks `body` to at most `limit_bytes`; `None` when it already fits or can't be trimmed.
+pub(super) fn trim_to_limit(body: &[u8], limit_bytes: usize) -> Option<ContextTrim> {
+    let original_bytes = body.len();
+    if original_bytes <= limit_bytes {
+        return None;
+    }
+    let mut root: Value = serde_json::from_slice(body).ok()?;
+    let family = tokenizer::ModelFamily::from_model(root.get("model").and_then(Value::as_str));
+    let original_tokens = tokenizer::estimate_request(&root, family);
+    let (key, dialect) = conversation_key(root.as_object()?)?;
+    let items = root.get_mut(key)?.as_array_mut()?;
+    if items.is_empty() {
+        return None;
+    }
+
+    let mut size = original_bytes;
+    let mut dropped_messages = 0usize;
+    let mut truncated_tool_results = 0usize;
+    let shrink = |item: &mut Value, size: &mut usize| {
+        let before = json_len(item);
+        let n = truncate_tool_results(dialect, item);
+        if n > 0 {
+            *size = size.saturating_sub(before.saturating_sub(json_len(item)));
+        }
+        n
+    };
+
+    // 1) tool results in all but the latest message, oldest first
+    let last = items.len() - 1;
+    for item in items[..last].iter_mut() {
+        if size <= limit_bytes {
+            break;
+        }
+        truncated_tool_results += shrink(item, &mut size);
+    }
+
+    // 2) drop the oldest messages, then keep dropping until the conversation starts cleanly
+    while size > limit_bytes || dropped_messages > 0 {
+        let Some(index) = items.iter().position(|item| !is_pinned(item)) else {
+            break;
+        };
+        if index == items.len() - 1 {
+            break;
+        }
+        if size <= limit_bytes && is_clean_start(dialect, &items[index]) {
+            break;
+        }
+        let removed = items.remove(index);
+        size = size.saturating_sub(json_len(&removed) + 1);
+        dropped_messages += 1;
+    }
+
+    // 3) last resort: the latest message's own tool results
+    if size > limit_bytes {
+        if let Some(item) = items.last_mut() {
+            truncated_tool_results += shrink(item, &mut size);
+        }
+    }
+
+    if dropped_messages == 0 && truncated_tool_results == 0 {
+        return None;
+    }
+    let body = serde_json::to_vec(&root).ok()?;
+    Some(ContextTrim {
+        fits: body.len() <= limit_bytes,
+        body,
+        original_bytes,
+        dropped_messages,
+        truncated_tool_results,
+        original_tokens,
+        trimmed_tokens: tokenizer::estimate_request(&root, family),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn big(n: usize) -> String {
+        "x".repeat(n)
+    }
+
+    #[test]
+    fn truncates_old_tool_results_before_dropping_messages() {
+        let body = json!({
+            "model": "claude-sonnet-4",
+            "messages": [
+                {"role": "user", "content": "list files"},
+                {"role": "assistant", "content": [{"type": "tool_use", "id": "t1", "name": "ls", "input": {}}]},
+                {"role": "user", "content": [{"type": "tool_result", "tool_use_id": "t1", "content": big(50_000)}]},
+                {"role": "assistant", "content": "done"},
+                {"role": "user", "content": "thanks"},
+            ],
+        });
+        let raw = serde_json::to_vec(&body).unwrap();
+        let trim = trim_to_limit(&raw, 10_000).expect("trimmed");
+        assert!(trim.fits);
+        assert_eq!(trim.dropped_messages, 0);
+        assert_eq!(trim.truncated_tool_results, 1);
+
+        let out: Value = serde_json::from_slice(&trim.body).unwrap();
+        let text = out["messages"][2]["content"][0]["content"]
+            .as_str()
+            .unwrap();
+        assert!(text.ends_with("truncated 47952 bytes]"));
+        assert!(trim_to_limit(&trim.body, 10_000).is_none());
+    }
+
+    #[test]
+    fn drops_oldest_turns_but_keeps_system_and_a_clean_user_start() {
+        let body = json!({
+            "model": "gpt-5",
+            "messages": [
+                {"role": "system", "content": "be terse"},
+                {"role": "user", "content": big(6_000)},
+                {"role": "assistant", "content": null, "tool_calls": [{"id": "c1"}]},
+                {"role": "tool", "tool_call_id": "c1", "content": "ok"},
+                {"role": "assistant", "content": big(3_000)},
+                {"role": "user", "content": "next"},
+                {"role": "assistant", "content": "sure"},
+                {"role": "user", "content": "go"},
+            ],
+        });
+        let raw = serde_json::to_vec(&body).unwrap();
+        let trim = trim_to_limit(&raw, 2_000).expect("trimmed");
+        assert!(trim.fits);
+        assert_eq!(trim.dropped_messages, 4);
+        assert!(trim.trimmed_tokens < trim.original_tokens);
+
+        let out: Value = serde_json::from_slice(&trim.body).unwrap();
+        let roles: Vec<&str> = out["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, ["system", "user", "assistant", "user"]);
+        assert_eq!(out["messages"][1]["content"], "next");
+    }
+
+    #[test]
+    fn leaves_small_or_unknown_bodies_alone() {
+        let small =
+            serde_json::to_vec(&json!({"messages": [{"role": "user", "content": "hi"}]})).unwrap();
+        assert!(trim_to_limit(&small, 1024).is_none());
+        let unknown = serde_json::to_vec(&json!({"prompt": big(4_000)})).unwrap();
+        assert!(trim_to_limit(&unknown, 1024).is_none());
+        // a single oversized plain message has nothing to trim
+        let single =
+            serde_json::to_vec(&json!({"messages": [{"role": "user", "content": big(4_000)}]}))
+                .unwrap();
+        assert!(trim_to_limit(&single, 1024).is_none());
+    }
+}
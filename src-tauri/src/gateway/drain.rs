@@ -0,0 +1,146 @@
+//! Usage: In-flight request tracking for graceful gateway stop (drain mode).
+//!
+//! 用法：
+//! - 路由层 `track_in_flight` 为每个请求计数；流式响应（无确定长度的 body）在 body 结束 / 被丢弃时才释放计数
+//! - 停止网关时先 `start_draining()`：新请求直接返回 503 `GW_DRAINING`，再 `wait_idle(timeout)` 等待在途请求结束
+//! - 超时仍未结束的请求计为 aborted，随后由服务器任务中止
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use super::manager::GatewayAppState;
+use super::streams;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayDrainReport {
+    pub drained: usize,
+    pub aborted: usize,
+    pub waited_ms: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct InFlightTracker {
+    count: AtomicUsize,
+    draining: AtomicBool,
+    idle: Notify,
+}
+
+struct InFlightGuard {
+    tracker: Arc<InFlightTracker>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.tracker.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.tracker.idle.notify_waiters();
+        }
+    }
+}
+
+impl InFlightTracker {
+    fn enter(self: &Arc<Self>) -> InFlightGuard {
+        self.count.fetch_add(1, Ordering::AcqRel);
+        InFlightGuard {
+            tracker: self.clone(),
+        }
+    }
+
+    pub(super) fn in_flight(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    pub(super) fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+    }
+
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::Release);
+    }
+
+    /// Waits until no request is in flight or `timeout` elapses; returns the report.
+    pub async fn wait_idle(&self, timeout: Duration) -> GatewayDrainReport {
+        let started = std::time::Instant::now();
+        let initial = self.in_flight();
+        let wait = async {
+            loop {
+                let notified = self.idle.notified();
+                if self.in_flight() == 0 {
+                    break;
+                }
+                notified.await;
+            }
+        };
+        let _ = tokio::time::timeout(timeout, wait).await;
+
+        let aborted = self.in_flight();
+        GatewayDrainReport {
+            drained: initial.saturating_sub(aborted),
+            aborted,
+            waited_ms: started.elapsed().as_millis().min(u64::MAX as u128) as u64,
+        }
+    }
+}
+
+pub(super) async fn track_in_flight(
+    State(state): State<GatewayAppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if state.in_flight.is_draining() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({
+                "error_code": "GW_DRAINING",
+                "message": "gateway is stopping and no longer accepts new requests",
+            })),
+        )
+            .into_response();
+    }
+
+    let guard = state.in_flight.enter();
+    let resp = next.run(req).await;
+    if resp.body().size_hint().exact().is_some() {
+        return resp;
+    }
+
+    // Streaming body: keep the request counted until the body is fully sent or dropped.
+    streams::hold_until_body_end(resp, guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InFlightTracker;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn wait_idle_reports_drained_and_aborted() {
+        let tracker = Arc::new(InFlightTracker::default());
+        let finishing = tracker.enter();
+        let stuck = tracker.enter();
+        tracker.start_draining();
+
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(finishing);
+        });
+        let report = tracker.wait_idle(Duration::from_millis(200)).await;
+        let _ = release.await;
+
+        assert_eq!(report.drained, 1);
+        assert_eq!(report.aborted, 1);
+        drop(stuck);
+        assert_eq!(tracker.in_flight(), 0);
+    }
+}
@@ -0,0 +1,124 @@
+//! Usage: In-memory ring buffer of recent gateway events (request / attempt / circuit).
+//!
+//! 用法：
+//! - `events.rs` 在 emit 的同时调用 `record`，保留最近 `CAPACITY` 条事件
+//! - 前端打开首页或窗口重建（事件丢失）后通过 `gateway_recent_events` 一次性补齐，避免回查 SQLite
+//! - `seq` 单调递增；传入 `after_seq` 可只取增量
+
+use crate::shared::mutex_ext::MutexExt;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+const CAPACITY: usize = 4000;
+const DEFAULT_LIMIT: usize = 1000;
+
+static BUFFER: Mutex<EventBuffer> = Mutex::new(EventBuffer {
+    next_seq: 1,
+    events: VecDeque::new(),
+});
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayBufferedEvent {
+    pub seq: u64,
+    /// Event channel name without the `gateway:` prefix (`request_start` / `attempt` / `request` / `circuit`).
+    pub kind: &'static str,
+    pub ts_ms: i64,
+    pub payload: serde_json::Value,
+}
+
+struct EventBuffer {
+    next_seq: u64,
+    events: VecDeque<GatewayBufferedEvent>,
+}
+
+impl EventBuffer {
+    fn push(&mut self, kind: &'static str, ts_ms: i64, payload: serde_json::Value) {
+        if self.events.len() >= CAPACITY {
+            self.events.pop_front();
+        }
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.saturating_add(1);
+        self.events.push_back(GatewayBufferedEvent {
+            seq,
+            kind,
+            ts_ms,
+            payload,
+        });
+    }
+
+    fn recent(&self, after_seq: Option<u64>, limit: usize) -> Vec<GatewayBufferedEvent> {
+        let after_seq = after_seq.unwrap_or(0);
+        let start = self.events.partition_point(|event| event.seq <= after_seq);
+        let available = self.events.len() - start;
+        // Keep the newest `limit` events, returned oldest first so the UI can replay them in order.
+        let skip = available.saturating_sub(limit);
+        self.events.iter().skip(start + skip).cloned().collect()
+    }
+}
+
+fn now_unix_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+pub(super) fn record<T: Serialize>(kind: &'static str, payload: &T) {
+    let Ok(payload) = serde_json::to_value(payload) else {
+        return;
+    };
+    BUFFER
+        .lock_or_recover()
+        .push(kind, now_unix_millis(), payload);
+}
+
+pub fn recent(after_seq: Option<u64>, limit: Option<u32>) -> Vec<GatewayBufferedEvent> {
+    let limit = limit
+        .map(|v| v as usize)
+        .unwrap_or(DEFAULT_LIMIT)
+        .clamp(1, CAPACITY);
+    BUFFER.lock_or_recover().recent(after_seq, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer_with(n: usize) -> EventBuffer {
+        let mut buffer = EventBuffer {
+            next_seq: 1,
+            events: VecDeque::new(),
+        };
+        for i in 0..n {
+            buffer.push("request", i as i64, serde_json::json!({ "i": i }));
+        }
+        buffer
+    }
+
+    #[test]
+    fn drops_oldest_when_full() {
+        let buffer = buffer_with(CAPACITY + 5);
+        assert_eq!(buffer.events.len(), CAPACITY);
+        assert_eq!(buffer.events.front().map(|e| e.seq), Some(6));
+        assert_eq!(
+            buffer.events.back().map(|e| e.seq),
+            Some(CAPACITY as u64 + 5)
+        );
+    }
+
+    #[test]
+    fn recent_returns_newest_in_order() {
+        let buffer = buffer_with(10);
+        let seqs: Vec<u64> = buffer.recent(None, 3).iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![8, 9, 10]);
+    }
+
+    #[test]
+    fn recent_after_seq_returns_only_newer() {
+        let buffer = buffer_with(10);
+        let seqs: Vec<u64> = buffer.recent(Some(7), 100).iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![8, 9, 10]);
+        assert!(buffer.recent(Some(10), 100).is_empty());
+    }
+}
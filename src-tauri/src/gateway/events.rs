@@ -1,7 +1,18 @@
+use super::event_buffer;
+use super::health;
+use super::proxy::circuit_failures;
+use crate::i18n::{self, MessageKey};
+use crate::shared::mutex_ext::MutexExt;
 use crate::{circuit_breaker, notice, settings, usage};
 use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
 use tauri::Emitter;
 
+// Per-CLI throttle for "all providers unavailable" notices (unix seconds of last notice).
+const ALL_PROVIDERS_DOWN_NOTICE_INTERVAL_SECS: i64 = 10 * 60;
+static ALL_PROVIDERS_DOWN_LAST_NOTICE: Mutex<Option<HashMap<String, i64>>> = Mutex::new(None);
+
 #[derive(Debug, Serialize, Clone)]
 pub(super) struct FailoverAttempt {
     pub(super) provider_id: i64,
@@ -139,6 +150,9 @@ pub(super) fn emit_request_event(
     attempts: Vec<FailoverAttempt>,
     usage: Option<usage::UsageMetrics>,
 ) {
+    if let Some(error_code) = error_code {
+        health::record_error(&cli_key, error_code);
+    }
     let usage = usage.unwrap_or_default();
     let payload = GatewayRequestEvent {
         trace_id,
@@ -161,6 +175,7 @@ pub(super) fn emit_request_event(
         cache_creation_1h_input_tokens: usage.cache_creation_1h_input_tokens,
     };
 
+    event_buffer::record("request", &payload);
     let _ = app.emit("gateway:request", payload);
 }
 
@@ -184,14 +199,17 @@ pub(super) fn emit_request_start_event(
         requested_model,
         ts,
     };
+    event_buffer::record("request_start", &payload);
     let _ = app.emit("gateway:request_start", payload);
 }
 
 pub(super) fn emit_attempt_event(app: &tauri::AppHandle, payload: GatewayAttemptEvent) {
+    event_buffer::record("attempt", &payload);
     let _ = app.emit("gateway:attempt", payload);
 }
 
 pub(super) fn emit_circuit_event(app: &tauri::AppHandle, payload: GatewayCircuitEvent) {
+    event_buffer::record("circuit", &payload);
     let _ = app.emit("gateway:circuit", payload);
 }
 
@@ -224,69 +242,240 @@ pub(super) fn emit_circuit_transition(
 
     emit_circuit_event(app, payload);
 
+    // Both closing paths are recovery notices behind their own toggle: OPEN_EXPIRED (open -> closed,
+    // traffic flows again) and RECOVERY_CONFIRMED (first success after that).
     let enable_notice = settings::read(app)
         .ok()
-        .map(|cfg| cfg.enable_circuit_breaker_notice)
+        .map(|cfg| match transition.reason {
+            "OPEN_EXPIRED" | "RECOVERY_CONFIRMED" => cfg.enable_circuit_recovery_notice,
+            _ => cfg.enable_circuit_breaker_notice,
+        })
         .unwrap_or(false);
     if !enable_notice {
         return;
     }
 
-    let prev_state_text = match transition.prev_state {
-        circuit_breaker::CircuitState::Closed => "正常",
-        circuit_breaker::CircuitState::Open => "熔断",
-    };
-    let next_state_text = match transition.next_state {
-        circuit_breaker::CircuitState::Closed => "正常",
-        circuit_breaker::CircuitState::Open => "熔断",
+    let language = i18n::current_language(app);
+    let state_text = |state: circuit_breaker::CircuitState| {
+        let key = match state {
+            circuit_breaker::CircuitState::Closed => MessageKey::CircuitStateClosed,
+            circuit_breaker::CircuitState::Open => MessageKey::CircuitStateOpen,
+        };
+        i18n::text(language, key)
     };
+    let prev_state_text = state_text(transition.prev_state);
+    let next_state_text = state_text(transition.next_state);
 
-    let (level, title) = match transition.next_state {
-        circuit_breaker::CircuitState::Open => (
-            notice::NoticeLevel::Warning,
-            format!("熔断触发：{provider_name}"),
-        ),
+    let (level, title_key) = match transition.next_state {
+        circuit_breaker::CircuitState::Open => {
+            (notice::NoticeLevel::Warning, MessageKey::CircuitOpenedTitle)
+        }
         circuit_breaker::CircuitState::Closed => (
             notice::NoticeLevel::Success,
-            format!("熔断恢复：{provider_name}"),
+            MessageKey::CircuitRecoveredTitle,
         ),
     };
+    let title = i18n::format(language, title_key, &[("provider", provider_name)]);
 
     let reason_text = match transition.reason {
-        "FAILURE_THRESHOLD_REACHED" => "失败次数达到阈值",
-        "OPEN_EXPIRED" => "熔断到期自动恢复",
-        other => other,
+        "FAILURE_THRESHOLD_REACHED" => {
+            i18n::text(language, MessageKey::CircuitReasonFailureThreshold)
+        }
+        "OPEN_EXPIRED" => i18n::text(language, MessageKey::CircuitReasonOpenExpired),
+        "RECOVERY_CONFIRMED" => i18n::text(language, MessageKey::CircuitReasonRecoveryConfirmed),
+        other => other.to_string(),
     };
 
+    let provider_id_text = provider_id.to_string();
+    let failure_count_text = transition.snapshot.failure_count.to_string();
+    let failure_threshold_text = transition.snapshot.failure_threshold.to_string();
+
     let mut lines: Vec<String> = Vec::with_capacity(10);
-    lines.push(format!("CLI：{cli_key}"));
-    lines.push(format!("Provider：{provider_name} (id={provider_id})"));
-    lines.push(format!("Base URL：{base_url}"));
-    lines.push(format!("状态：{prev_state_text} → {next_state_text}"));
-    lines.push(format!(
-        "失败：{} / {}",
-        transition.snapshot.failure_count, transition.snapshot.failure_threshold
+    lines.push(i18n::format(
+        language,
+        MessageKey::CircuitLineCli,
+        &[("cli", cli_key)],
+    ));
+    lines.push(i18n::format(
+        language,
+        MessageKey::CircuitLineProvider,
+        &[
+            ("provider", provider_name),
+            ("provider_id", provider_id_text.as_str()),
+        ],
+    ));
+    lines.push(i18n::format(
+        language,
+        MessageKey::CircuitLineBaseUrl,
+        &[("base_url", base_url)],
+    ));
+    lines.push(i18n::format(
+        language,
+        MessageKey::CircuitLineState,
+        &[
+            ("prev", prev_state_text.as_str()),
+            ("next", next_state_text.as_str()),
+        ],
     ));
-    lines.push(format!("原因：{reason_text}（{}）", transition.reason));
+    lines.push(i18n::format(
+        language,
+        MessageKey::CircuitLineFailures,
+        &[
+            ("count", failure_count_text.as_str()),
+            ("threshold", failure_threshold_text.as_str()),
+        ],
+    ));
+    lines.push(i18n::format(
+        language,
+        MessageKey::CircuitLineReason,
+        &[
+            ("reason", reason_text.as_str()),
+            ("code", transition.reason),
+        ],
+    ));
+
+    let is_open = transition.next_state == circuit_breaker::CircuitState::Open;
+    let mut last_response_detail: Option<String> = None;
+    if let Some(digest) = is_open
+        .then(|| circuit_failures::digest(provider_id, now_unix))
+        .flatten()
+    {
+        let codes = digest
+            .error_codes
+            .iter()
+            .map(|(code, count)| format!("{code}×{count}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(i18n::format(
+            language,
+            MessageKey::CircuitLineErrorCodes,
+            &[("codes", codes.as_str())],
+        ));
+        if !digest.models.is_empty() {
+            let models = digest.models.join(", ");
+            lines.push(i18n::format(
+                language,
+                MessageKey::CircuitLineModels,
+                &[("models", models.as_str())],
+            ));
+        }
+        if digest.last_status.is_some() || digest.last_summary.is_some() {
+            let status_text = digest
+                .last_status
+                .map(|v| format!("HTTP {v}"))
+                .unwrap_or_default();
+            let line = i18n::format(
+                language,
+                MessageKey::CircuitLineLastResponse,
+                &[
+                    ("status", status_text.as_str()),
+                    ("summary", digest.last_summary.as_deref().unwrap_or("")),
+                ],
+            )
+            .trim_end()
+            .to_string();
+            // The upstream body excerpt may echo prompts or keys: show it locally only.
+            if digest.last_summary.is_some() {
+                last_response_detail = Some(line);
+            } else {
+                lines.push(line);
+            }
+        }
+    }
 
     match transition.snapshot.open_until {
         Some(open_until) => {
             let remaining_secs = open_until.saturating_sub(now_unix);
             let remaining_minutes = remaining_secs.saturating_add(59) / 60;
+            let open_until_text = open_until.to_string();
             if remaining_secs > 0 {
-                lines.push(format!(
-                    "熔断至：{open_until}（约 {remaining_minutes} 分钟后）"
+                let remaining_minutes_text = remaining_minutes.to_string();
+                lines.push(i18n::format(
+                    language,
+                    MessageKey::CircuitLineOpenUntil,
+                    &[
+                        ("until", open_until_text.as_str()),
+                        ("minutes", remaining_minutes_text.as_str()),
+                    ],
                 ));
             } else {
-                lines.push(format!("熔断至：{open_until}（已到期）"));
+                lines.push(i18n::format(
+                    language,
+                    MessageKey::CircuitLineOpenUntilExpired,
+                    &[("until", open_until_text.as_str())],
+                ));
             }
         }
-        None => lines.push("熔断至：—".to_string()),
+        None => lines.push(i18n::text(language, MessageKey::CircuitLineOpenUntilNone)),
+    }
+
+    lines.push(i18n::format(
+        language,
+        MessageKey::CircuitLineTrace,
+        &[("trace_id", trace_id)],
+    ));
+
+    let mut payload = notice::build(language, level, Some(title), lines.join("\n"));
+    if let Some(detail) = last_response_detail {
+        payload = payload.with_local_detail(detail);
     }
+    if is_open {
+        payload = payload.with_action(notice::NoticeAction::ResetProvider {
+            provider_id,
+            label: i18n::text(language, MessageKey::CircuitActionResetProvider),
+        });
+    }
+    notice::dispatch(app, settings::NoticeEventKind::CircuitBreaker, payload);
+}
 
-    lines.push(format!("Trace：{trace_id}"));
+pub(super) fn emit_all_providers_unavailable_notice(
+    app: &tauri::AppHandle,
+    trace_id: &str,
+    cli_key: &str,
+    skipped_open: usize,
+    skipped_cooldown: usize,
+    now_unix: i64,
+) {
+    let enable_notice = settings::read(app)
+        .ok()
+        .map(|cfg| cfg.enable_circuit_breaker_notice)
+        .unwrap_or(false);
+    if !enable_notice {
+        return;
+    }
 
-    if let Err(err) = notice::emit(app, notice::build(level, Some(title), lines.join("\n"))) {
-        tracing::warn!("发送熔断器通知失败: {}", err);
+    {
+        let mut guard = ALL_PROVIDERS_DOWN_LAST_NOTICE.lock_or_recover();
+        let last_by_cli = guard.get_or_insert_with(HashMap::new);
+        if let Some(last) = last_by_cli.get(cli_key) {
+            if now_unix.saturating_sub(*last) < ALL_PROVIDERS_DOWN_NOTICE_INTERVAL_SECS {
+                return;
+            }
+        }
+        last_by_cli.insert(cli_key.to_string(), now_unix);
     }
+
+    let language = i18n::current_language(app);
+    let title = i18n::format(
+        language,
+        MessageKey::GatewayAllProvidersDownTitle,
+        &[("cli", cli_key)],
+    );
+    let open_text = skipped_open.to_string();
+    let cooldown_text = skipped_cooldown.to_string();
+    let body = i18n::format(
+        language,
+        MessageKey::GatewayAllProvidersDownBody,
+        &[
+            ("open", open_text.as_str()),
+            ("cooldown", cooldown_text.as_str()),
+            ("trace_id", trace_id),
+        ],
+    );
+
+    notice::dispatch(
+        app,
+        settings::NoticeEventKind::CircuitBreaker,
+        notice::build(language, notice::NoticeLevel::Error, Some(title), body),
+    );
 }
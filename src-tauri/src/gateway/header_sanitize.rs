@@ -0,0 +1,112 @@
+//! Usage: Request header sanitization rules (strip client headers before forwarding upstream).
+
+use axum::http::{header, HeaderMap, HeaderName};
+
+use crate::settings::HeaderSanitizeRule;
+
+// 网关自身依赖的请求头，规则不允许剥离
+const PROTECTED_HEADERS: &[HeaderName] = &[header::CONTENT_TYPE, header::ACCEPT_ENCODING];
+
+fn pattern_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(&prefix.to_ascii_lowercase()),
+        None => name.eq_ignore_ascii_case(pattern),
+    }
+}
+
+fn rule_applies(rule: &HeaderSanitizeRule, cli_key: &str, provider_id: i64) -> bool {
+    rule.enabled
+        && (rule.cli_key.is_empty() || rule.cli_key == cli_key)
+        && (rule.provider_ids.is_empty() || rule.provider_ids.contains(&provider_id))
+}
+
+/// Removes headers matched by the applicable rules; returns the removed header names (sorted).
+pub(super) fn apply_header_sanitize_rules(
+    rules: &[HeaderSanitizeRule],
+    cli_key: &str,
+    provider_id: i64,
+    headers: &mut HeaderMap,
+) -> Vec<String> {
+    let patterns: Vec<&str> = rules
+        .iter()
+        .filter(|rule| rule_applies(rule, cli_key, provider_id))
+        .flat_map(|rule| rule.headers.iter().map(String::as_str))
+        .collect();
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+
+    let mut removed: Vec<HeaderName> = headers
+        .keys()
+        .filter(|name| !PROTECTED_HEADERS.contains(name))
+        .filter(|name| {
+            patterns
+                .iter()
+                .any(|pattern| pattern_matches(pattern, name.as_str()))
+        })
+        .cloned()
+        .collect();
+    removed.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    removed.dedup();
+    for name in &removed {
+        headers.remove(name);
+    }
+    removed
+        .iter()
+        .map(|name| name.as_str().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn rule(cli_key: &str, provider_ids: Vec<i64>, headers: &[&str]) -> HeaderSanitizeRule {
+        HeaderSanitizeRule {
+            id: "r".to_string(),
+            cli_key: cli_key.to_string(),
+            provider_ids,
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn sample_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for name in [
+            "x-stainless-os",
+            "x-stainless-arch",
+            "x-app",
+            "anthropic-version",
+            "content-type",
+        ] {
+            headers.insert(HeaderName::from_static(name), HeaderValue::from_static("v"));
+        }
+        headers
+    }
+
+    #[test]
+    fn strips_prefix_and_exact_matches_but_keeps_protected_headers() {
+        let rules = [rule("", vec![], &["x-stainless-*", "X-App", "content-*"])];
+        let mut headers = sample_headers();
+        let removed = apply_header_sanitize_rules(&rules, "claude", 1, &mut headers);
+        assert_eq!(removed, ["x-app", "x-stainless-arch", "x-stainless-os"]);
+        assert!(headers.contains_key("anthropic-version"));
+        assert!(headers.contains_key("content-type"));
+    }
+
+    #[test]
+    fn rules_are_scoped_by_cli_and_provider() {
+        let rules = [
+            rule("codex", vec![], &["x-app"]),
+            rule("claude", vec![2], &["x-stainless-*"]),
+        ];
+        let mut headers = sample_headers();
+        assert!(apply_header_sanitize_rules(&rules, "claude", 1, &mut headers).is_empty());
+        assert_eq!(
+            apply_header_sanitize_rules(&rules, "claude", 2, &mut headers).len(),
+            2
+        );
+    }
+}
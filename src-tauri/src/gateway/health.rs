@@ -0,0 +1,143 @@
+//! Usage: Aggregate gateway health attached to `GatewayStatus` (`gateway_status` / `gateway:status`).
+//!
+//! 用法：
+//! - 各 CLI 已启用的供应商数与其中熔断打开（未到期）的数量
+//! - 请求日志 / 尝试日志写入队列的当前积压与容量（积压接近容量说明 SQLite 写入跟不上）
+//! - 最近一次以错误结束的请求（时间、CLI、错误码），由 `events::emit_request_event` 记录，跨网关重启保留
+
+use crate::shared::cli_key::SUPPORTED_CLI_KEYS;
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use tokio::sync::mpsc;
+
+static LAST_ERROR: Mutex<Option<GatewayLastError>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayHealthSummary {
+    pub clis: Vec<GatewayCliHealth>,
+    pub request_log_queue: GatewayQueueDepth,
+    pub attempt_log_queue: GatewayQueueDepth,
+    pub last_error: Option<GatewayLastError>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct GatewayCliHealth {
+    pub cli_key: String,
+    pub enabled_providers: u32,
+    // Enabled providers whose circuit is open right now.
+    pub open_circuits: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+pub struct GatewayQueueDepth {
+    pub queued: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayLastError {
+    pub at: i64,
+    pub cli_key: String,
+    pub error_code: &'static str,
+}
+
+pub(super) fn record_error(cli_key: &str, error_code: &'static str) {
+    *LAST_ERROR.lock_or_recover() = Some(GatewayLastError {
+        at: now_unix_seconds(),
+        cli_key: cli_key.to_string(),
+        error_code,
+    });
+}
+
+pub(super) fn last_error() -> Option<GatewayLastError> {
+    LAST_ERROR.lock_or_recover().clone()
+}
+
+/// Depth of a log channel; a dropped channel (log task gone) reads as empty.
+pub(super) fn queue_depth<T>(tx: &mpsc::WeakSender<T>) -> GatewayQueueDepth {
+    match tx.upgrade() {
+        Some(tx) => GatewayQueueDepth {
+            queued: tx.max_capacity().saturating_sub(tx.capacity()),
+            capacity: tx.max_capacity(),
+        },
+        None => GatewayQueueDepth::default(),
+    }
+}
+
+/// Per-CLI counts over the enabled `(cli_key, provider_id)` pairs, in `SUPPORTED_CLI_KEYS` order.
+pub(super) fn cli_health(enabled: &[(String, i64)], open: &HashSet<i64>) -> Vec<GatewayCliHealth> {
+    SUPPORTED_CLI_KEYS
+        .iter()
+        .map(|cli_key| {
+            let ids = enabled
+                .iter()
+                .filter(|(key, _)| key == cli_key)
+                .map(|(_, id)| *id);
+            let (enabled_providers, open_circuits) = ids
+                .fold((0u32, 0u32), |(total, open_count), id| {
+                    (total + 1, open_count + u32::from(open.contains(&id)))
+                });
+            GatewayCliHealth {
+                cli_key: cli_key.to_string(),
+                enabled_providers,
+                open_circuits,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_health_counts_enabled_and_open_per_cli() {
+        let enabled = vec![
+            ("claude".to_string(), 1),
+            ("claude".to_string(), 2),
+            ("codex".to_string(), 3),
+        ];
+        let open = HashSet::from([2, 3, 99]);
+        let health = cli_health(&enabled, &open);
+        assert_eq!(
+            health,
+            vec![
+                GatewayCliHealth {
+                    cli_key: "claude".to_string(),
+                    enabled_providers: 2,
+                    open_circuits: 1,
+                },
+                GatewayCliHealth {
+                    cli_key: "codex".to_string(),
+                    enabled_providers: 1,
+                    open_circuits: 1,
+                },
+                GatewayCliHealth {
+                    cli_key: "gemini".to_string(),
+                    enabled_providers: 0,
+                    open_circuits: 0,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn queue_depth_reports_backlog() {
+        let (tx, _rx) = mpsc::channel::<u8>(4);
+        let weak = tx.downgrade();
+        tx.send(1).await.unwrap();
+        tx.send(2).await.unwrap();
+        assert_eq!(
+            queue_depth(&weak),
+            GatewayQueueDepth {
+                queued: 2,
+                capacity: 4
+            }
+        );
+        drop(tx);
+        assert_eq!(queue_depth(&weak), GatewayQueueDepth::default());
+    }
+}
@@ -0,0 +1,155 @@
+//! Usage: Request interception rules (path + body predicates -> respond locally without forwarding).
+
+use axum::body::Bytes;
+use axum::http::StatusCode;
+use serde_json::Value;
+
+use crate::settings::{InterceptBodyPredicate, InterceptPredicateOp, InterceptRule};
+
+/// A locally generated response for an intercepted request.
+pub(super) struct InterceptedResponse {
+    pub(super) status: StatusCode,
+    pub(super) content_type: String,
+    pub(super) body: Bytes,
+    pub(super) special_setting: Value,
+    /// Value of the `x-aio-intercepted` response header.
+    pub(super) tag: &'static str,
+    /// Pseudo provider name / base_url recorded in the request log attempts.
+    pub(super) provider_name: &'static str,
+    pub(super) meta_url: &'static str,
+}
+
+pub(super) fn path_matches(pattern: &str, path: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => path.starts_with(prefix),
+        None => pattern == path,
+    }
+}
+
+fn value_len(value: &Value) -> Option<u64> {
+    let len = match value {
+        Value::Array(items) => items.len(),
+        Value::Object(map) => map.len(),
+        Value::String(s) => s.chars().count(),
+        _ => return None,
+    };
+    Some(len as u64)
+}
+
+fn predicate_matches(predicate: &InterceptBodyPredicate, root: &Value) -> bool {
+    let Some(target) = root.pointer(&predicate.pointer) else {
+        return false;
+    };
+    match predicate.op {
+        InterceptPredicateOp::Exists => true,
+        InterceptPredicateOp::Equals => target == &predicate.value,
+        InterceptPredicateOp::EqualsIgnoreCase => match (target.as_str(), predicate.value.as_str())
+        {
+            (Some(actual), Some(expected)) => {
+                actual.trim().to_lowercase() == expected.trim().to_lowercase()
+            }
+            _ => false,
+        },
+        InterceptPredicateOp::Contains => match target {
+            Value::String(s) => predicate
+                .value
+                .as_str()
+                .is_some_and(|needle| s.contains(needle)),
+            Value::Array(items) => items.contains(&predicate.value),
+            _ => false,
+        },
+        InterceptPredicateOp::LenEq => {
+            value_len(target).is_some_and(|len| predicate.value.as_u64() == Some(len))
+        }
+    }
+}
+
+pub(super) fn rule_matches(
+    rule: &InterceptRule,
+    cli_key: &str,
+    method: &str,
+    forwarded_path: &str,
+    body: Option<&Value>,
+) -> bool {
+    if !rule.enabled {
+        return false;
+    }
+    if !rule.cli_key.is_empty() && rule.cli_key != cli_key {
+        return false;
+    }
+    if !rule.method.is_empty() && !rule.method.eq_ignore_ascii_case(method) {
+        return false;
+    }
+    if !path_matches(&rule.path, forwarded_path) {
+        return false;
+    }
+    if rule.body_predicates.is_empty() {
+        return true;
+    }
+    // 有 body 条件但 body 不是 JSON：视为不匹配
+    let Some(root) = body else {
+        return false;
+    };
+    rule.body_predicates
+        .iter()
+        .all(|predicate| predicate_matches(predicate, root))
+}
+
+/// First enabled rule (in configured order) that matches the request.
+pub(super) fn find_matching_rule<'a>(
+    rules: &'a [InterceptRule],
+    cli_key: &str,
+    method: &str,
+    forwarded_path: &str,
+    body: Option<&Value>,
+) -> Option<&'a InterceptRule> {
+    rules
+        .iter()
+        .find(|rule| rule_matches(rule, cli_key, method, forwarded_path, body))
+}
+
+fn placeholder_value(raw: &str, json_escape: bool) -> String {
+    if !json_escape {
+        return raw.to_string();
+    }
+    // 去掉 JSON 字符串两端引号，只保留转义后的内容，便于嵌入模板中的 "..." 里
+    let quoted = serde_json::to_string(raw).unwrap_or_else(|_| "\"\"".to_string());
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+pub(super) fn render_rule_response(
+    rule: &InterceptRule,
+    model: Option<&str>,
+    trace_id: &str,
+    created_at: i64,
+) -> InterceptedResponse {
+    let json_escape = rule.response_content_type.contains("json");
+    let body = rule
+        .response_body
+        .replace("{{trace_id}}", &placeholder_value(trace_id, json_escape))
+        .replace(
+            "{{model}}",
+            &placeholder_value(model.unwrap_or("unknown"), json_escape),
+        )
+        .replace("{{created_at}}", &created_at.to_string());
+
+    InterceptedResponse {
+        status: StatusCode::from_u16(rule.response_status).unwrap_or(StatusCode::OK),
+        content_type: rule.response_content_type.clone(),
+        body: Bytes::from(body),
+        special_setting: serde_json::json!({
+            "type": "intercept_rule",
+            "scope": "request",
+            "hit": true,
+            "ruleId": rule.id,
+            "ruleName": rule.name,
+            "note": "已按拦截规则本地响应，未转发上游；写入日志但排除统计",
+        }),
+        tag: "rule",
+        provider_name: "Intercept",
+        meta_url: "/__aio__/intercept",
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,86 @@
+use super::*;
+use crate::settings::{InterceptBodyPredicate, InterceptPredicateOp, InterceptRule};
+
+fn telemetry_rule() -> InterceptRule {
+    InterceptRule {
+        id: "telemetry".to_string(),
+        name: "Telemetry".to_string(),
+        method: "POST".to_string(),
+        path: "/api/event_logging/*".to_string(),
+        body_predicates: vec![InterceptBodyPredicate {
+            pointer: "/events".to_string(),
+            op: InterceptPredicateOp::Exists,
+            value: Value::Null,
+        }],
+        response_status: 204,
+        response_body: String::new(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn rule_matches_prefix_path_method_and_body_predicate() {
+    let rule = telemetry_rule();
+    let body = serde_json::json!({ "events": [] });
+
+    assert!(rule_matches(
+        &rule,
+        "claude",
+        "post",
+        "/api/event_logging/batch",
+        Some(&body)
+    ));
+    assert!(!rule_matches(
+        &rule,
+        "claude",
+        "GET",
+        "/api/event_logging/batch",
+        Some(&body)
+    ));
+    assert!(!rule_matches(
+        &rule,
+        "claude",
+        "POST",
+        "/v1/messages",
+        Some(&body)
+    ));
+    assert!(!rule_matches(
+        &rule,
+        "claude",
+        "POST",
+        "/api/event_logging/batch",
+        None
+    ));
+
+    let disabled = InterceptRule {
+        enabled: false,
+        ..telemetry_rule()
+    };
+    assert!(find_matching_rule(
+        &[disabled],
+        "claude",
+        "POST",
+        "/api/event_logging/batch",
+        Some(&body)
+    )
+    .is_none());
+}
+
+#[test]
+fn render_rule_response_fills_json_escaped_placeholders() {
+    let rule = InterceptRule {
+        id: "echo".to_string(),
+        path: "/v1/echo".to_string(),
+        response_body: r#"{"id":"{{trace_id}}","model":"{{model}}","at":{{created_at}}}"#
+            .to_string(),
+        ..Default::default()
+    };
+
+    let out = render_rule_response(&rule, Some("a\"b"), "t1", 42);
+    assert_eq!(out.status, StatusCode::OK);
+    let body: Value = serde_json::from_slice(&out.body).unwrap();
+    assert_eq!(body["id"], "t1");
+    assert_eq!(body["model"], "a\"b");
+    assert_eq!(body["at"], 42);
+    assert_eq!(out.special_setting["ruleId"], "echo");
+}
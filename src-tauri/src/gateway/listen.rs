@@ -8,6 +8,69 @@ pub(crate) fn is_wildcard_host(host: &str) -> bool {
     matches!(host.trim(), "0.0.0.0" | "::")
 }
 
+/// Host the gateway binds in `WslAuto` mode and the address WSL clients are pointed at: the
+/// vEthernet (WSL) adapter, or loopback when it is missing.
+pub(crate) fn wsl_host() -> String {
+    crate::wsl::host_ipv4_best_effort().unwrap_or_else(|| "127.0.0.1".to_string())
+}
+
+/// Host the gateway binds for the configured listen mode, plus the port when a custom address
+/// fixes one (otherwise the preferred port or the next free one is used).
+pub(crate) fn bind_target(
+    cfg: &crate::settings::AppSettings,
+) -> Result<(String, Option<u16>), String> {
+    use crate::settings::GatewayListenMode;
+    Ok(match cfg.gateway_listen_mode {
+        GatewayListenMode::Localhost => ("127.0.0.1".to_string(), None),
+        GatewayListenMode::Lan => ("0.0.0.0".to_string(), None),
+        GatewayListenMode::WslAuto => (wsl_host(), None),
+        GatewayListenMode::Custom => {
+            let parsed = parse_custom_listen_address(&cfg.gateway_custom_listen_address)?;
+            (parsed.host, parsed.port)
+        }
+    })
+}
+
+/// Extra hosts the HTTPS certificate (and the local CA's name constraints) must cover besides
+/// loopback: the bind host, the WSL host address, the primary LAN addresses when binding every
+/// interface, and the user-configured `gateway_tls_hosts`.
+pub(crate) fn tls_hosts(cfg: &crate::settings::AppSettings, bind_host: &str) -> Vec<String> {
+    let mut hosts = vec![bind_host.to_string()];
+    hosts.extend(crate::wsl::host_ipv4_best_effort());
+    if is_wildcard_host(bind_host) {
+        hosts.extend(lan_addresses(bind_host.trim() == "::"));
+    }
+    hosts.extend(cfg.gateway_tls_hosts.iter().cloned());
+    hosts
+}
+
+// Local addresses of the default routes. Connecting a UDP socket only selects the route; no packet
+// is sent to the documentation-range probe addresses.
+fn lan_addresses(include_ipv6: bool) -> Vec<String> {
+    fn route_source(bind: &str, probe: &str) -> Option<String> {
+        let socket = std::net::UdpSocket::bind(bind).ok()?;
+        socket.connect(probe).ok()?;
+        let ip = socket.local_addr().ok()?.ip();
+        (!ip.is_loopback() && !ip.is_unspecified()).then(|| ip.to_string())
+    }
+
+    let mut out: Vec<String> = route_source("0.0.0.0:0", "192.0.2.1:9")
+        .into_iter()
+        .collect();
+    if include_ipv6 {
+        out.extend(route_source("[::]:0", "[2001:db8::1]:9"));
+    }
+    out
+}
+
+pub(crate) fn url_scheme(tls_enabled: bool) -> &'static str {
+    if tls_enabled {
+        "https"
+    } else {
+        "http"
+    }
+}
+
 pub(crate) fn format_host_port(host: &str, port: u16) -> String {
     if host.contains(':') {
         format!("[{host}]:{port}")
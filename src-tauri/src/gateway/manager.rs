@@ -1,51 +1,93 @@
+use crate::i18n::{self, MessageKey};
+use crate::shared::mutex_ext::MutexExt;
 use crate::{
-    circuit_breaker, db, provider_circuit_breakers, providers, request_attempt_logs, request_logs,
-    session_manager, settings, wsl,
+    circuit_breaker, crash_report, db, gateway_tls, provider_circuit_breakers, providers,
+    request_attempt_logs, request_logs, session_manager, settings,
 };
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use tauri::Emitter;
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot, watch};
 
+use super::active_requests::{ActiveRequests, GatewayInflightRequest};
 use super::codex_session_id::CodexSessionIdCache;
+use super::drain::{GatewayDrainReport, InFlightTracker};
 use super::events::GatewayLogEvent;
+use super::health::{self, GatewayHealthSummary};
 use super::listen;
-use super::proxy::{ProviderBaseUrlPingCache, RecentErrorCache};
-use super::routes::build_router;
-use super::util::now_unix_seconds;
-use super::{GatewayProviderCircuitStatus, GatewayStatus};
+use super::pause::{GatewayPause, GatewayPauseStatus};
+use super::proxy::{
+    circuit_failures, explain_route, spawn_latency_probe_scheduler, InflightRequests,
+    PriorityLanes, ProviderBaseUrlPingCache, RecentErrorCache, RouteExplainInput, StreamCaps,
+};
+use super::routes::{build_cli_router, build_router};
+use super::tls;
+use super::upstream_clients::UpstreamClients;
+use super::util::{now_unix_millis, now_unix_seconds};
+use super::{
+    GatewayListenerStatus, GatewayProviderCircuitStatus, GatewayRouteExplain, GatewayStatus,
+};
 
 struct RunningGateway {
     port: u16,
     base_url: String,
     listen_addr: String,
+    extra_listeners: Vec<GatewayListenerStatus>,
+    db: db::Db,
     circuit: Arc<circuit_breaker::CircuitBreaker>,
     session: Arc<session_manager::SessionManager>,
+    recent_errors: Arc<Mutex<RecentErrorCache>>,
+    in_flight: Arc<InFlightTracker>,
+    active_requests: Arc<ActiveRequests>,
+    // Weak so the log writers still finish once the gateway state is dropped.
+    log_queue: mpsc::WeakSender<request_logs::RequestLogInsert>,
+    attempt_log_queue: mpsc::WeakSender<request_attempt_logs::RequestAttemptLogInsert>,
     shutdown: oneshot::Sender<()>,
     task: tauri::async_runtime::JoinHandle<()>,
     log_task: tauri::async_runtime::JoinHandle<()>,
     attempt_log_task: tauri::async_runtime::JoinHandle<()>,
     circuit_task: tauri::async_runtime::JoinHandle<()>,
+    latency_probe_task: tauri::async_runtime::JoinHandle<()>,
 }
 
 type RunningGatewayHandles = (
     oneshot::Sender<()>,
+    Arc<InFlightTracker>,
     tauri::async_runtime::JoinHandle<()>,
     tauri::async_runtime::JoinHandle<()>,
     tauri::async_runtime::JoinHandle<()>,
     tauri::async_runtime::JoinHandle<()>,
 );
 
+impl RunningGateway {
+    fn health(&self) -> GatewayHealthSummary {
+        let enabled = providers::enabled_ids_by_cli(&self.db).unwrap_or_else(|err| {
+            tracing::warn!("网关健康摘要读取供应商失败: {}", err);
+            Vec::new()
+        });
+        let open = self.circuit.open_provider_ids(now_unix_seconds() as i64);
+        GatewayHealthSummary {
+            clis: health::cli_health(&enabled, &open),
+            request_log_queue: health::queue_depth(&self.log_queue),
+            attempt_log_queue: health::queue_depth(&self.attempt_log_queue),
+            last_error: health::last_error(),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct GatewayManager {
     running: Option<RunningGateway>,
+    last_drain: Option<GatewayDrainReport>,
+    // Kept across start/stop so a paused gateway stays paused after a restart.
+    pause: Arc<GatewayPause>,
 }
 
 #[derive(Clone)]
 pub(super) struct GatewayAppState {
     pub(super) app: tauri::AppHandle,
     pub(super) db: db::Db,
-    pub(super) client: reqwest::Client,
+    pub(super) upstream_clients: Arc<UpstreamClients>,
     pub(super) log_tx: tokio::sync::mpsc::Sender<request_logs::RequestLogInsert>,
     pub(super) attempt_log_tx:
         tokio::sync::mpsc::Sender<request_attempt_logs::RequestAttemptLogInsert>,
@@ -54,6 +96,12 @@ pub(super) struct GatewayAppState {
     pub(super) codex_session_cache: Arc<Mutex<CodexSessionIdCache>>,
     pub(super) recent_errors: Arc<Mutex<RecentErrorCache>>,
     pub(super) latency_cache: Arc<Mutex<ProviderBaseUrlPingCache>>,
+    pub(super) in_flight: Arc<InFlightTracker>,
+    pub(super) inflight_requests: Arc<InflightRequests>,
+    pub(super) priority_lanes: Arc<PriorityLanes>,
+    pub(super) stream_caps: Arc<StreamCaps>,
+    pub(super) active_requests: Arc<ActiveRequests>,
+    pub(super) pause: Arc<GatewayPause>,
 }
 fn port_candidates(preferred: Option<u16>) -> impl Iterator<Item = u16> {
     let mut candidates = Vec::with_capacity(
@@ -99,6 +147,25 @@ fn bind_first_available(
     ))
 }
 
+async fn serve_listener(
+    listener: tokio::net::TcpListener,
+    router: axum::Router,
+    tls_config: Option<Arc<rustls::ServerConfig>>,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    match tls_config {
+        Some(tls_config) => tls::serve_tls(listener, tls_config, router, shutdown).await,
+        None => {
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(shutdown)
+            .await
+        }
+    }
+}
+
 impl GatewayManager {
     pub fn status(&self) -> GatewayStatus {
         match &self.running {
@@ -107,16 +174,28 @@ impl GatewayManager {
                 port: Some(r.port),
                 base_url: Some(r.base_url.clone()),
                 listen_addr: Some(r.listen_addr.clone()),
+                extra_listeners: r.extra_listeners.clone(),
+                last_drain: self.last_drain.clone(),
+                pause: self.pause.status(),
+                health: Some(r.health()),
             },
             None => GatewayStatus {
                 running: false,
                 port: None,
                 base_url: None,
                 listen_addr: None,
+                extra_listeners: Vec::new(),
+                last_drain: self.last_drain.clone(),
+                pause: self.pause.status(),
+                health: None,
             },
         }
     }
 
+    pub fn set_paused(&self, paused: bool, reason: Option<&str>) -> GatewayPauseStatus {
+        self.pause.set(paused, reason)
+    }
+
     pub fn active_sessions(
         &self,
         now_unix: i64,
@@ -128,6 +207,21 @@ impl GatewayManager {
         }
     }
 
+    pub fn inflight_requests(&self) -> Vec<GatewayInflightRequest> {
+        match &self.running {
+            Some(r) => r.active_requests.snapshot(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Cancels the in-flight request(s) with this trace id; returns how many were cancelled.
+    pub fn cancel_request(&self, trace_id: &str) -> usize {
+        match &self.running {
+            Some(r) => r.active_requests.cancel(trace_id),
+            None => 0,
+        }
+    }
+
     pub fn clear_cli_session_bindings(&self, cli_key: &str) -> usize {
         match &self.running {
             Some(r) => r.session.clear_cli_bindings(cli_key),
@@ -150,19 +244,7 @@ impl GatewayManager {
             .unwrap_or(settings::DEFAULT_GATEWAY_PORT);
 
         let cfg = settings::read(app).unwrap_or_default();
-        let (bind_host, fixed_port) = match cfg.gateway_listen_mode {
-            settings::GatewayListenMode::Localhost => ("127.0.0.1".to_string(), None),
-            settings::GatewayListenMode::Lan => ("0.0.0.0".to_string(), None),
-            settings::GatewayListenMode::WslAuto => (
-                wsl::host_ipv4_best_effort().unwrap_or_else(|| "127.0.0.1".to_string()),
-                None,
-            ),
-            settings::GatewayListenMode::Custom => {
-                let parsed =
-                    listen::parse_custom_listen_address(&cfg.gateway_custom_listen_address)?;
-                (parsed.host, parsed.port)
-            }
-        };
+        let (bind_host, fixed_port) = listen::bind_target(&cfg)?;
 
         let (port, std_listener) = if let Some(port) = fixed_port {
             let listener = bind_host_port(&bind_host, port)
@@ -180,7 +262,56 @@ impl GatewayManager {
             }
             _ => bind_host.clone(),
         };
-        let base_url = format!("http://{}", listen::format_host_port(&base_host, port));
+        let tls_config = if cfg.gateway_tls_enabled {
+            let hosts = listen::tls_hosts(&cfg, &bind_host);
+            Some(gateway_tls::server_config(app, &hosts)?)
+        } else {
+            None
+        };
+        let scheme = listen::url_scheme(tls_config.is_some());
+        let base_url = format!("{scheme}://{}", listen::format_host_port(&base_host, port));
+        if listen::is_wildcard_host(&bind_host) && cfg.gateway_access_token.is_empty() {
+            tracing::warn!(listen_addr = %listen_addr, "网关监听所有网卡但未设置访问令牌，局域网内任何设备均可使用");
+        }
+
+        let mut extra_bound = Vec::new();
+        for item in cfg.gateway_extra_listeners.iter().filter(|l| l.enabled) {
+            let Some(extra_listener) = bind_host_port(&bind_host, item.port) else {
+                tracing::warn!(port = item.port, cli_key = %item.cli_key, "网关附加监听端口绑定失败，已跳过");
+                let payload = GatewayLogEvent {
+                    level: "error",
+                    error_code: "GW_EXTRA_LISTENER_BIND_FAILED",
+                    message: i18n::format(
+                        i18n::current_language(app),
+                        MessageKey::GatewayExtraListenerBindFailed,
+                        &[
+                            ("port", item.port.to_string().as_str()),
+                            ("cli", item.cli_key.as_str()),
+                        ],
+                    ),
+                    requested_port: item.port,
+                    bound_port: 0,
+                    base_url: String::new(),
+                };
+                let _ = app.emit("gateway:log", payload);
+                continue;
+            };
+            let status = GatewayListenerStatus {
+                cli_key: item.cli_key.clone(),
+                port: item.port,
+                base_url: format!(
+                    "{scheme}://{}",
+                    listen::format_host_port(&base_host, item.port)
+                ),
+                listen_addr: listen::format_host_port(&bind_host, item.port),
+            };
+            extra_bound.push((status, extra_listener));
+        }
+        let extra_listeners: Vec<GatewayListenerStatus> = extra_bound
+            .iter()
+            .map(|(status, _)| status.clone())
+            .collect();
+
         let bind_addr = std_listener
             .local_addr()
             .unwrap_or_else(|_| SocketAddr::from(([127, 0, 0, 1], port)));
@@ -196,7 +327,14 @@ impl GatewayManager {
             let payload = GatewayLogEvent {
                 level: "warn",
                 error_code: "GW_PORT_IN_USE",
-                message: format!("端口 {requested_port} 被占用，已自动切换到 {port}"),
+                message: i18n::format(
+                    i18n::current_language(app),
+                    MessageKey::GatewayPortInUse,
+                    &[
+                        ("requested", requested_port.to_string().as_str()),
+                        ("bound", port.to_string().as_str()),
+                    ],
+                ),
                 requested_port,
                 bound_port: port,
                 base_url: base_url.clone(),
@@ -204,13 +342,7 @@ impl GatewayManager {
             let _ = app.emit("gateway:log", payload);
         }
 
-        let client = reqwest::Client::builder()
-            .user_agent(format!(
-                "aio-coding-hub-gateway/{}",
-                env!("CARGO_PKG_VERSION")
-            ))
-            .build()
-            .map_err(|e| format!("GW_HTTP_CLIENT_INIT: {e}"))?;
+        let upstream_clients = Arc::new(UpstreamClients::new(&cfg.egress_network)?);
 
         let (log_tx, log_task) = request_logs::start_buffered_writer(app.clone(), db.clone());
         let (attempt_log_tx, attempt_log_task) =
@@ -219,12 +351,14 @@ impl GatewayManager {
             provider_circuit_breakers::start_buffered_writer(db.clone());
 
         let retention_days = settings::log_retention_days_fail_open(app);
+        let attempt_log_retention = settings::attempt_log_retention_fail_open(app);
         let db_for_cleanup = db.clone();
         std::mem::drop(tauri::async_runtime::spawn_blocking(move || {
             if let Err(err) = request_logs::cleanup_expired(&db_for_cleanup, retention_days) {
                 tracing::warn!("请求日志启动清理失败: {}", err);
             }
-            if let Err(err) = request_attempt_logs::cleanup_expired(&db_for_cleanup, retention_days)
+            if let Err(err) =
+                request_attempt_logs::cleanup_with_policy(&db_for_cleanup, attempt_log_retention)
             {
                 tracing::warn!("尝试日志启动清理失败: {}", err);
             }
@@ -252,24 +386,44 @@ impl GatewayManager {
             Some(circuit_tx),
         ));
         let circuit_for_manager = circuit.clone();
+        let db_for_manager = db.clone();
+        let log_queue = log_tx.downgrade();
+        let attempt_log_queue = attempt_log_tx.downgrade();
         let session = Arc::new(session_manager::SessionManager::new());
         let codex_session_cache = Arc::new(Mutex::new(CodexSessionIdCache::default()));
         let recent_errors = Arc::new(Mutex::new(RecentErrorCache::default()));
         let latency_cache = Arc::new(Mutex::new(ProviderBaseUrlPingCache::default()));
+        let in_flight = Arc::new(InFlightTracker::default());
+        let active_requests = Arc::new(ActiveRequests::default());
 
+        let app_for_task = app.clone();
         let state = GatewayAppState {
             app: app.clone(),
             db,
-            client,
+            upstream_clients,
             log_tx,
             attempt_log_tx,
             circuit,
             session: session.clone(),
             codex_session_cache,
-            recent_errors,
+            recent_errors: recent_errors.clone(),
             latency_cache,
+            in_flight: in_flight.clone(),
+            inflight_requests: Arc::new(InflightRequests::default()),
+            priority_lanes: Arc::new(PriorityLanes::default()),
+            stream_caps: Arc::new(StreamCaps::default()),
+            active_requests: active_requests.clone(),
+            pause: self.pause.clone(),
         };
 
+        let extra_routers: Vec<_> = extra_bound
+            .into_iter()
+            .map(|(status, listener)| {
+                let router = build_cli_router(state.clone(), status.cli_key.clone());
+                (status, listener, router)
+            })
+            .collect();
+        let latency_probe_task = spawn_latency_probe_scheduler(state.clone());
         let app = build_router(state);
         let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
 
@@ -278,16 +432,55 @@ impl GatewayManager {
                 Ok(l) => l,
                 Err(err) => {
                     tracing::error!(bind_addr = %bind_addr, "网关监听器初始化失败: {}", err);
+                    crash_report::record_task_failure(
+                        &app_for_task,
+                        "gateway_listener_init",
+                        &format!("bind_addr={bind_addr} err={err}"),
+                    );
                     return;
                 }
             };
 
-            let serve = axum::serve(listener, app).with_graceful_shutdown(async move {
+            // Extra listeners follow the main listener: they stop once it has shut down.
+            let (stop_tx, stop_rx) = watch::channel(false);
+            let mut extra_tasks = Vec::with_capacity(extra_routers.len());
+            for (status, std_listener, router) in extra_routers {
+                let mut stop_rx = stop_rx.clone();
+                let tls_config = tls_config.clone();
+                extra_tasks.push(tauri::async_runtime::spawn(async move {
+                    let listener = match tokio::net::TcpListener::from_std(std_listener) {
+                        Ok(l) => l,
+                        Err(err) => {
+                            tracing::warn!(listen_addr = %status.listen_addr, "网关附加监听器初始化失败: {}", err);
+                            return;
+                        }
+                    };
+                    let shutdown = async move {
+                        let _ = stop_rx.changed().await;
+                    };
+                    if let Err(err) = serve_listener(listener, router, tls_config, shutdown).await {
+                        tracing::warn!(listen_addr = %status.listen_addr, "网关附加监听器运行错误: {}", err);
+                    }
+                }));
+            }
+
+            let result = serve_listener(listener, app, tls_config, async move {
                 let _ = shutdown_rx.await;
-            });
+            })
+            .await;
+
+            let _ = stop_tx.send(true);
+            for extra_task in extra_tasks {
+                let _ = extra_task.await;
+            }
 
-            if let Err(err) = serve.await {
+            if let Err(err) = result {
                 tracing::error!(bind_addr = %bind_addr, "网关服务器运行错误: {}", err);
+                crash_report::record_task_failure(
+                    &app_for_task,
+                    "gateway_serve",
+                    &format!("bind_addr={bind_addr} err={err}"),
+                );
             }
         });
 
@@ -295,13 +488,21 @@ impl GatewayManager {
             port,
             base_url,
             listen_addr,
+            extra_listeners,
+            db: db_for_manager,
             circuit: circuit_for_manager,
             session,
+            recent_errors,
+            in_flight,
+            active_requests,
+            log_queue,
+            attempt_log_queue,
             shutdown: shutdown_tx,
             task,
             log_task,
             attempt_log_task,
             circuit_task,
+            latency_probe_task,
         });
 
         Ok(self.status())
@@ -384,6 +585,27 @@ impl GatewayManager {
             .collect())
     }
 
+    /// Candidate providers a request would try right now (nothing is sent upstream).
+    pub fn route_explain(
+        &self,
+        app: &tauri::AppHandle,
+        db: &db::Db,
+        cli_key: &str,
+        model: Option<&str>,
+        session_id: Option<&str>,
+    ) -> Result<GatewayRouteExplain, String> {
+        let circuits = self.circuit_status(app, db, cli_key)?;
+        explain_route(RouteExplainInput {
+            app,
+            db,
+            cli_key,
+            model,
+            session_id,
+            session: self.running.as_ref().map(|r| r.session.as_ref()),
+            circuits,
+        })
+    }
+
     pub fn circuit_reset_provider(&self, db: &db::Db, provider_id: i64) -> Result<(), String> {
         if provider_id <= 0 {
             return Err("SEC_INVALID_INPUT: provider_id must be > 0".to_string());
@@ -392,7 +614,11 @@ impl GatewayManager {
         if let Some(r) = &self.running {
             let now_unix = now_unix_seconds() as i64;
             r.circuit.reset(provider_id, now_unix);
+            r.recent_errors
+                .lock_or_recover()
+                .note_circuit_reset(now_unix_millis() as i64);
         }
+        circuit_failures::clear(provider_id);
 
         let _ = provider_circuit_breakers::delete_by_provider_id(db, provider_id)?;
         Ok(())
@@ -413,16 +639,36 @@ impl GatewayManager {
             for provider_id in &provider_ids {
                 r.circuit.reset(*provider_id, now_unix);
             }
+            r.recent_errors
+                .lock_or_recover()
+                .note_circuit_reset(now_unix_millis() as i64);
+        }
+        for provider_id in &provider_ids {
+            circuit_failures::clear(*provider_id);
         }
 
         let _ = provider_circuit_breakers::delete_by_provider_ids(db, &provider_ids)?;
         Ok(provider_ids.len())
     }
 
+    /// Drops the recent-error dedup cache; returns how many cached failures were dropped.
+    pub fn error_cache_clear(&self) -> usize {
+        self.running
+            .as_ref()
+            .map(|r| r.recent_errors.lock_or_recover().clear())
+            .unwrap_or(0)
+    }
+
+    pub fn set_last_drain(&mut self, report: Option<GatewayDrainReport>) {
+        self.last_drain = report;
+    }
+
     pub fn take_running(&mut self) -> Option<RunningGatewayHandles> {
         self.running.take().map(|r| {
+            r.latency_probe_task.abort();
             (
                 r.shutdown,
+                r.in_flight,
                 r.task,
                 r.log_task,
                 r.attempt_log_task,
@@ -0,0 +1,78 @@
+//! Usage: Egress reachability test for the gateway network settings (`gateway_network_test`).
+//!
+//! 用法：
+//! - 按当前 `egress_network` 设置（代理 / hosts 覆盖 / IP 族）构建与网关相同的出站客户端，请求目标 URL
+//! - 返回本地解析结果、出站路径（direct / system / proxy）、HTTP 状态与耗时；收到任意 HTTP 响应即视为可达
+//! - 不依赖网关是否运行，便于保存设置前后验证
+
+use crate::providers::{UpstreamTls, UPSTREAM_PROXY_SYSTEM};
+use crate::settings::EgressNetworkConfig;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use super::upstream_clients::{egress_route, EgressResolver, UpstreamClients};
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct NetworkTestResult {
+    pub(crate) url: String,
+    // `direct` | `system` | `proxy`
+    pub(crate) route: &'static str,
+    // Local resolution with overrides / IP family applied; a proxy may resolve the host itself.
+    pub(crate) resolved_addrs: Vec<String>,
+    pub(crate) dns_error: Option<String>,
+    pub(crate) ok: bool,
+    pub(crate) status: Option<u16>,
+    pub(crate) elapsed_ms: u64,
+    pub(crate) error: Option<String>,
+}
+
+pub(crate) async fn run(
+    network: &EgressNetworkConfig,
+    url: &str,
+) -> Result<NetworkTestResult, String> {
+    let parsed =
+        reqwest::Url::parse(url).map_err(|e| format!("SEC_INVALID_INPUT: invalid url: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("SEC_INVALID_INPUT: url must start with http:// or https://".to_string());
+    }
+    let host = parsed
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| "SEC_INVALID_INPUT: url host is required".to_string())?;
+
+    let (resolved_addrs, dns_error) = match EgressResolver::new(network).lookup(host).await {
+        Ok(ips) => (ips.iter().map(ToString::to_string).collect(), None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+
+    let client = UpstreamClients::new(network)?.for_provider(
+        network,
+        UPSTREAM_PROXY_SYSTEM,
+        url,
+        &UpstreamTls::default(),
+    );
+    let started = Instant::now();
+    let result = client.get(parsed).timeout(TEST_TIMEOUT).send().await;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let (status, error) = match result {
+        Ok(resp) => (Some(resp.status().as_u16()), None),
+        Err(err) if err.is_timeout() => (
+            None,
+            Some(format!("timed out after {}s", TEST_TIMEOUT.as_secs())),
+        ),
+        Err(err) => (None, Some(err.to_string())),
+    };
+    Ok(NetworkTestResult {
+        url: url.to_string(),
+        route: egress_route(network, UPSTREAM_PROXY_SYSTEM, url),
+        resolved_addrs,
+        dns_error,
+        ok: status.is_some(),
+        status,
+        elapsed_ms,
+        error,
+    })
+}
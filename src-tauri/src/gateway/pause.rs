@@ -0,0 +1,87 @@
+//! Usage: User-initiated gateway pause (maintenance mode).
+//!
+//! 用法：
+//! - `GatewayManager::set_paused(true, reason)` 后，监听保持绑定，代理请求直接返回本地 503 `GW_PAUSED`
+//!   （`/health` 不受影响），CLI 立即失败而不是等待超时 / 触发熔断
+//! - 状态保存在 `GatewayManager` 上，网关停止 / 重启后保持；应用重启后恢复为未暂停
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::shared::mutex_ext::MutexExt;
+
+use super::util::now_unix_seconds;
+
+const MAX_PAUSE_REASON_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GatewayPauseStatus {
+    pub paused: bool,
+    pub since: Option<i64>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct GatewayPause {
+    paused: AtomicBool,
+    status: Mutex<GatewayPauseStatus>,
+}
+
+impl GatewayPause {
+    pub(super) fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Acquire)
+    }
+
+    pub(super) fn status(&self) -> GatewayPauseStatus {
+        self.status.lock_or_recover().clone()
+    }
+
+    pub(super) fn set(&self, paused: bool, reason: Option<&str>) -> GatewayPauseStatus {
+        let mut status = self.status.lock_or_recover();
+        if !paused {
+            *status = GatewayPauseStatus::default();
+        } else {
+            let reason = reason
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(|v| v.chars().take(MAX_PAUSE_REASON_CHARS).collect::<String>());
+            let since = match (status.paused, status.since) {
+                (true, Some(since)) => since,
+                _ => now_unix_seconds() as i64,
+            };
+            *status = GatewayPauseStatus {
+                paused: true,
+                since: Some(since),
+                reason,
+            };
+        }
+        self.paused.store(paused, Ordering::Release);
+        status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GatewayPause;
+
+    #[test]
+    fn pause_keeps_since_and_resume_clears_state() {
+        let pause = GatewayPause::default();
+        assert!(!pause.is_paused());
+
+        let first = pause.set(true, Some("  provider maintenance "));
+        assert!(pause.is_paused());
+        assert_eq!(first.reason.as_deref(), Some("provider maintenance"));
+
+        let second = pause.set(true, None);
+        assert_eq!(second.since, first.since);
+        assert_eq!(second.reason, None);
+
+        let resumed = pause.set(false, Some("ignored"));
+        assert!(!pause.is_paused());
+        assert!(!resumed.paused);
+        assert_eq!(resumed.since, None);
+        assert_eq!(resumed.reason, None);
+    }
+}
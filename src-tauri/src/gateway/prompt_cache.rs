@@ -0,0 +1,195 @@
+//! Usage: Automatic prompt cache breakpoints for Claude (`/v1/messages`) requests.
+//!
+//! When the client sent no `cache_control` at all, insert `{"type":"ephemeral"}` at up to
+//! [`MAX_BREAKPOINTS`] stable positions: end of `tools`, end of `system`, and the last cacheable
+//! block of the conversation. Breakpoints whose prefix is too small to be cached are skipped.
+
+use serde_json::{Map, Value};
+
+// Anthropic 单次请求最多 4 个缓存断点
+const MAX_BREAKPOINTS: usize = 4;
+// 约 1024 tokens；更短的前缀上游不会缓存
+const MIN_PREFIX_CHARS: usize = 4096;
+
+#[derive(Debug, Default)]
+pub(super) struct PromptCacheInjection {
+    pub(super) positions: Vec<&'static str>,
+}
+
+fn contains_cache_control(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => {
+            map.contains_key("cache_control") || map.values().any(contains_cache_control)
+        }
+        Value::Array(items) => items.iter().any(contains_cache_control),
+        _ => false,
+    }
+}
+
+fn ephemeral() -> Value {
+    serde_json::json!({ "type": "ephemeral" })
+}
+
+fn approx_chars(value: &Value) -> usize {
+    match value {
+        Value::String(s) => s.len(),
+        Value::Array(items) => items.iter().map(approx_chars).sum(),
+        Value::Object(map) => map.iter().map(|(k, v)| k.len() + approx_chars(v)).sum(),
+        _ => 8,
+    }
+}
+
+/// `thinking` / `redacted_thinking` blocks and empty text blocks cannot carry cache_control.
+fn is_cacheable_block(block: &Value) -> bool {
+    let Some(obj) = block.as_object() else {
+        return false;
+    };
+    match obj.get("type").and_then(Value::as_str) {
+        Some("thinking") | Some("redacted_thinking") => false,
+        Some("text") => obj
+            .get("text")
+            .and_then(Value::as_str)
+            .is_some_and(|text| !text.is_empty()),
+        Some(_) => true,
+        None => false,
+    }
+}
+
+/// Normalizes string content into a single text block so a breakpoint can be attached.
+fn content_blocks(content: &mut Value) -> Option<&mut Vec<Value>> {
+    if let Value::String(text) = content {
+        if text.is_empty() {
+            return None;
+        }
+        *content = Value::Array(vec![serde_json::json!({ "type": "text", "text": text })]);
+    }
+    content.as_array_mut()
+}
+
+fn mark_last_cacheable(blocks: &mut [Value]) -> bool {
+    let Some(block) = blocks.iter_mut().rev().find(|b| is_cacheable_block(b)) else {
+        return false;
+    };
+    match block.as_object_mut() {
+        Some(obj) => {
+            obj.insert("cache_control".to_string(), ephemeral());
+            true
+        }
+        None => false,
+    }
+}
+
+fn inject_into_messages(root: &mut Map<String, Value>, result: &mut PromptCacheInjection) {
+    let Some(messages) = root.get_mut("messages").and_then(Value::as_array_mut) else {
+        return;
+    };
+    // 从最后一条消息往前找第一个可缓存的块，使整段对话历史成为可复用前缀
+    for message in messages.iter_mut().rev() {
+        let Some(content) = message.get_mut("content") else {
+            continue;
+        };
+        let Some(blocks) = content_blocks(content) else {
+            continue;
+        };
+        if mark_last_cacheable(blocks) {
+            result.positions.push("messages");
+            return;
+        }
+    }
+}
+
+/// Inserts cache breakpoints into an Anthropic Messages request body; no-op when the client
+/// already manages caching itself.
+pub(super) fn inject_cache_control(root: &mut Value) -> PromptCacheInjection {
+    let mut result = PromptCacheInjection::default();
+    if contains_cache_control(root) {
+        return result;
+    }
+    let Some(obj) = root.as_object_mut() else {
+        return result;
+    };
+
+    let mut prefix_chars = 0usize;
+
+    if let Some(tools) = obj.get_mut("tools").and_then(Value::as_array_mut) {
+        prefix_chars += tools.iter().map(approx_chars).sum::<usize>();
+        if prefix_chars >= MIN_PREFIX_CHARS {
+            if let Some(tool) = tools.last_mut().and_then(Value::as_object_mut) {
+                tool.insert("cache_control".to_string(), ephemeral());
+                result.positions.push("tools");
+            }
+        }
+    }
+
+    if let Some(system) = obj.get_mut("system") {
+        prefix_chars += approx_chars(system);
+        if prefix_chars >= MIN_PREFIX_CHARS {
+            if let Some(blocks) = content_blocks(system) {
+                if mark_last_cacheable(blocks) {
+                    result.positions.push("system");
+                }
+            }
+        }
+    }
+
+    if result.positions.len() < MAX_BREAKPOINTS {
+        prefix_chars += obj.get("messages").map(approx_chars).unwrap_or(0);
+        if prefix_chars >= MIN_PREFIX_CHARS {
+            inject_into_messages(obj, &mut result);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_text() -> String {
+        "x".repeat(MIN_PREFIX_CHARS)
+    }
+
+    #[test]
+    fn injects_system_and_last_message_breakpoints() {
+        let mut body = serde_json::json!({
+            "model": "claude-sonnet-4-5",
+            "system": long_text(),
+            "messages": [
+                {"role": "user", "content": "hi"},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "...", "signature": "sig"},
+                    {"type": "text", "text": "hello"}
+                ]},
+            ],
+        });
+        let result = inject_cache_control(&mut body);
+        assert_eq!(result.positions, vec!["system", "messages"]);
+        assert_eq!(body["system"][0]["cache_control"]["type"], "ephemeral");
+        assert!(body["messages"][1]["content"][0]
+            .get("cache_control")
+            .is_none());
+        assert_eq!(
+            body["messages"][1]["content"][1]["cache_control"]["type"],
+            "ephemeral"
+        );
+    }
+
+    #[test]
+    fn skips_when_client_sets_cache_control_or_prefix_is_small() {
+        let mut managed = serde_json::json!({
+            "system": [{"type": "text", "text": long_text(), "cache_control": {"type": "ephemeral"}}],
+            "messages": [{"role": "user", "content": long_text()}],
+        });
+        let before = managed.clone();
+        assert!(inject_cache_control(&mut managed).positions.is_empty());
+        assert_eq!(managed, before);
+
+        let mut small = serde_json::json!({
+            "system": "be brief",
+            "messages": [{"role": "user", "content": "hi"}],
+        });
+        assert!(inject_cache_control(&mut small).positions.is_empty());
+        assert_eq!(small["system"], "be brief");
+    }
+}
@@ -1,12 +1,15 @@
-//! Usage: Best-effort drop guard to log client-aborted requests.
+//! Usage: Best-effort drop guard to log client-aborted (or UI-cancelled) requests.
 
+use crate::gateway::active_requests::cancel_aware_error_code;
+use crate::gateway::response_fixer;
 use crate::{db, request_logs};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use super::request_end::{
     emit_request_event_and_spawn_request_log, RequestEndArgs, RequestEndDeps,
 };
-use super::ErrorCategory;
+use super::{ErrorCategory, RequestLogAttribution};
 
 pub(super) struct RequestAbortGuard {
     app: tauri::AppHandle,
@@ -20,6 +23,8 @@ pub(super) struct RequestAbortGuard {
     created_at_ms: i64,
     created_at: i64,
     started: Instant,
+    special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
+    attribution: RequestLogAttribution,
     armed: bool,
 }
 
@@ -37,6 +42,8 @@ impl RequestAbortGuard {
         created_at_ms: i64,
         created_at: i64,
         started: Instant,
+        special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
+        attribution: RequestLogAttribution,
     ) -> Self {
         Self {
             app,
@@ -50,6 +57,8 @@ impl RequestAbortGuard {
             created_at_ms,
             created_at,
             started,
+            special_settings,
+            attribution,
             armed: true,
         }
     }
@@ -66,6 +75,8 @@ impl Drop for RequestAbortGuard {
         }
 
         let duration_ms = self.started.elapsed().as_millis();
+        let error_code =
+            cancel_aware_error_code(Some("GW_REQUEST_ABORTED"), &self.special_settings);
         emit_request_event_and_spawn_request_log(RequestEndArgs {
             deps: RequestEndDeps::new(&self.app, &self.db, &self.log_tx),
             trace_id: self.trace_id.as_str(),
@@ -76,12 +87,12 @@ impl Drop for RequestAbortGuard {
             excluded_from_stats: false,
             status: None,
             error_category: Some(ErrorCategory::ClientAbort.as_str()),
-            error_code: Some("GW_REQUEST_ABORTED"),
+            error_code,
             duration_ms,
             event_ttfb_ms: None,
             log_ttfb_ms: None,
             attempts: &[],
-            special_settings_json: None,
+            special_settings_json: response_fixer::special_settings_json(&self.special_settings),
             session_id: None,
             requested_model: None,
             created_at_ms: self.created_at_ms,
@@ -89,6 +100,7 @@ impl Drop for RequestAbortGuard {
             usage_metrics: None,
             log_usage_metrics: None,
             usage: None,
+            attribution: self.attribution.clone(),
         });
     }
 }
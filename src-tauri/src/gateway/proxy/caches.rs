@@ -5,7 +5,6 @@ use std::collections::HashMap;
 
 const RECENT_ERROR_CACHE_MAX_ENTRIES: usize = 512;
 const RECENT_TRACE_DEDUP_MAX_ENTRIES: usize = 1024;
-pub(super) const RECENT_TRACE_DEDUP_TTL_SECS: i64 = 10;
 
 #[derive(Debug, Clone)]
 pub(super) struct CachedGatewayError {
@@ -16,12 +15,15 @@ pub(super) struct CachedGatewayError {
     pub(super) retry_after_seconds: Option<u64>,
     pub(super) expires_at_unix: i64,
     pub(super) fingerprint_debug: String,
+    // When the failed request started; failures that began before a manual circuit reset are not cached.
+    pub(super) request_started_at_ms: i64,
 }
 
 #[derive(Debug, Default)]
 pub(in crate::gateway) struct RecentErrorCache {
     errors: HashMap<u64, CachedGatewayError>,
     traces: HashMap<u64, CachedTraceId>,
+    circuit_reset_at_ms: i64,
 }
 
 #[derive(Debug, Clone)]
@@ -69,6 +71,9 @@ impl RecentErrorCache {
         entry: CachedGatewayError,
     ) {
         self.prune_expired(now_unix);
+        if entry.request_started_at_ms <= self.circuit_reset_at_ms {
+            return;
+        }
 
         if self.errors.len() >= RECENT_ERROR_CACHE_MAX_ENTRIES {
             if let Some((oldest_key, _)) = self
@@ -88,6 +93,20 @@ impl RecentErrorCache {
         self.errors.remove(&fingerprint_key);
     }
 
+    /// A manual circuit reset: drop cached failures, and refuse those of requests already in flight.
+    pub(in crate::gateway) fn note_circuit_reset(&mut self, now_unix_ms: i64) {
+        self.circuit_reset_at_ms = self.circuit_reset_at_ms.max(now_unix_ms);
+        self.errors.clear();
+    }
+
+    /// Drops every cached failure and trace id; returns how many failures were cached.
+    pub(in crate::gateway) fn clear(&mut self) -> usize {
+        let cleared = self.errors.len();
+        self.errors.clear();
+        self.traces.clear();
+        cleared
+    }
+
     pub(super) fn get_trace_id(
         &mut self,
         now_unix: i64,
@@ -196,3 +215,40 @@ impl ProviderBaseUrlPingCache {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached_error(request_started_at_ms: i64) -> CachedGatewayError {
+        CachedGatewayError {
+            trace_id: "trace".to_string(),
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            error_code: "GW_ALL_PROVIDERS_UNAVAILABLE",
+            message: "no provider available".to_string(),
+            retry_after_seconds: Some(60),
+            expires_at_unix: 160,
+            fingerprint_debug: "fp".to_string(),
+            request_started_at_ms,
+        }
+    }
+
+    #[test]
+    fn circuit_reset_drops_and_refuses_earlier_failures() {
+        let mut cache = RecentErrorCache::default();
+        cache.insert_error(100, 1, cached_error(99_000));
+        assert!(cache.get_error(100, 1, "fp").is_some());
+
+        cache.note_circuit_reset(101_000);
+        assert!(cache.get_error(101, 1, "fp").is_none());
+
+        // started before the reset, finished after it
+        cache.insert_error(102, 1, cached_error(100_500));
+        assert!(cache.get_error(102, 1, "fp").is_none());
+
+        cache.insert_error(102, 1, cached_error(101_500));
+        assert!(cache.get_error(102, 1, "fp").is_some());
+        assert_eq!(cache.clear(), 1);
+        assert!(cache.get_error(102, 1, "fp").is_none());
+    }
+}
@@ -0,0 +1,158 @@
+//! Usage: Recent provider failures kept in memory to enrich circuit-breaker notices.
+//!
+//! 用法：
+//! - 每次计入熔断的 Provider 失败前调用 `record(provider_id, sample)`（错误码 / 状态码 / 模型 / 上游响应摘要）
+//! - 熔断触发通知调用 `digest(provider_id, now_unix)` 汇总错误码分布、受影响模型和最近一次上游响应
+//!   （上游响应摘要只作为本机细节显示，不投递到外部通知渠道）
+//! - 熔断恢复或手动重置后调用 `clear(provider_id)`；状态仅在内存中，重启后重置
+
+use crate::shared::mutex_ext::MutexExt;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+const MAX_SAMPLES_PER_PROVIDER: usize = 32;
+const SAMPLE_WINDOW_SECS: i64 = 30 * 60;
+const MAX_SUMMARY_CHARS: usize = 200;
+const MAX_DIGEST_MODELS: usize = 5;
+
+#[derive(Debug, Clone)]
+pub(in crate::gateway) struct FailureSample {
+    pub(in crate::gateway) ts: i64,
+    pub(in crate::gateway) error_code: &'static str,
+    pub(in crate::gateway) status: Option<u16>,
+    pub(in crate::gateway) model: Option<String>,
+    pub(in crate::gateway) summary: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(in crate::gateway) struct FailureDigest {
+    // Sorted by count desc, then code.
+    pub(in crate::gateway) error_codes: Vec<(&'static str, usize)>,
+    pub(in crate::gateway) models: Vec<String>,
+    pub(in crate::gateway) last_status: Option<u16>,
+    pub(in crate::gateway) last_summary: Option<String>,
+}
+
+static RECENT: Mutex<Option<HashMap<i64, VecDeque<FailureSample>>>> = Mutex::new(None);
+
+/// Collapses whitespace and truncates an upstream body to a single line for the local notice detail.
+pub(in crate::gateway) fn summarize_body(body: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(body);
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.is_empty() {
+        return None;
+    }
+    if collapsed.chars().count() <= MAX_SUMMARY_CHARS {
+        return Some(collapsed);
+    }
+    let mut out: String = collapsed.chars().take(MAX_SUMMARY_CHARS).collect();
+    out.push('…');
+    Some(out)
+}
+
+pub(in crate::gateway) fn record(provider_id: i64, sample: FailureSample) {
+    let mut guard = RECENT.lock_or_recover();
+    let samples = guard
+        .get_or_insert_with(HashMap::new)
+        .entry(provider_id)
+        .or_default();
+    if samples.len() >= MAX_SAMPLES_PER_PROVIDER {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+}
+
+pub(in crate::gateway) fn clear(provider_id: i64) {
+    if let Some(all) = RECENT.lock_or_recover().as_mut() {
+        all.remove(&provider_id);
+    }
+}
+
+fn digest_samples<'a>(
+    samples: impl Iterator<Item = &'a FailureSample>,
+    now_unix: i64,
+) -> Option<FailureDigest> {
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    let mut models: Vec<String> = Vec::new();
+    let mut last: Option<&FailureSample> = None;
+    for sample in samples.filter(|s| now_unix.saturating_sub(s.ts) <= SAMPLE_WINDOW_SECS) {
+        *counts.entry(sample.error_code).or_default() += 1;
+        if let Some(model) = sample.model.as_deref().filter(|m| !m.is_empty()) {
+            if !models.iter().any(|m| m == model) {
+                models.push(model.to_string());
+            }
+        }
+        last = Some(sample);
+    }
+    let last = last?;
+
+    let mut error_codes: Vec<(&'static str, usize)> = counts.into_iter().collect();
+    error_codes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    models.truncate(MAX_DIGEST_MODELS);
+    Some(FailureDigest {
+        error_codes,
+        models,
+        last_status: last.status,
+        last_summary: last.summary.clone(),
+    })
+}
+
+pub(in crate::gateway) fn digest(provider_id: i64, now_unix: i64) -> Option<FailureDigest> {
+    let guard = RECENT.lock_or_recover();
+    let samples = guard.as_ref()?.get(&provider_id)?;
+    digest_samples(samples.iter(), now_unix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(ts: i64, error_code: &'static str, model: Option<&str>) -> FailureSample {
+        FailureSample {
+            ts,
+            error_code,
+            status: Some(502),
+            model: model.map(str::to_string),
+            summary: Some(format!("body@{ts}")),
+        }
+    }
+
+    #[test]
+    fn digest_counts_codes_and_keeps_latest_summary() {
+        let now = 10_000;
+        let samples = [
+            sample(
+                now - SAMPLE_WINDOW_SECS - 1,
+                "GW_UPSTREAM_TIMEOUT",
+                Some("stale"),
+            ),
+            sample(now - 30, "GW_UPSTREAM_5XX", Some("claude-sonnet")),
+            sample(now - 20, "GW_UPSTREAM_429", Some("claude-opus")),
+            sample(now - 10, "GW_UPSTREAM_5XX", Some("claude-sonnet")),
+        ];
+
+        let digest = digest_samples(samples.iter(), now).expect("digest");
+        assert_eq!(
+            digest.error_codes,
+            vec![("GW_UPSTREAM_5XX", 2), ("GW_UPSTREAM_429", 1)]
+        );
+        assert_eq!(digest.models, vec!["claude-sonnet", "claude-opus"]);
+        assert_eq!(digest.last_summary.as_deref(), Some("body@9990"));
+        assert_eq!(digest.last_status, Some(502));
+
+        assert_eq!(digest_samples(samples[..1].iter(), now), None);
+    }
+
+    #[test]
+    fn summarize_body_collapses_and_truncates() {
+        assert_eq!(
+            summarize_body(b"{\n  \"error\": \"overloaded\"\n}").as_deref(),
+            Some("{ \"error\": \"overloaded\" }")
+        );
+        assert_eq!(summarize_body(b" \n\t"), None);
+        let long = "x".repeat(MAX_SUMMARY_CHARS + 10);
+        let out = summarize_body(long.as_bytes()).expect("summary");
+        assert_eq!(out.chars().count(), MAX_SUMMARY_CHARS + 1);
+        assert!(out.ends_with('…'));
+    }
+}
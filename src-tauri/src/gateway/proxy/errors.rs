@@ -1,6 +1,7 @@
 //! Usage: Error classification + standardized gateway error responses.
 
 use axum::{
+    body::Bytes,
     http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
@@ -31,9 +32,22 @@ pub(super) fn classify_reqwest_error(err: &reqwest::Error) -> (ErrorCategory, &'
     (ErrorCategory::SystemError, "GW_INTERNAL_ERROR")
 }
 
+/// Anthropic-style "overloaded" status; relays pass it through as-is.
+pub(super) const UPSTREAM_OVERLOADED_STATUS: u16 = 529;
+const MAX_OVERLOADED_COOLDOWN_SECS: i64 = 600;
+
 pub(super) fn classify_upstream_status(
     status: reqwest::StatusCode,
 ) -> (ErrorCategory, &'static str, FailoverDecision) {
+    if status.as_u16() == UPSTREAM_OVERLOADED_STATUS {
+        // Retrying an overloaded upstream only adds load; move on immediately.
+        return (
+            ErrorCategory::ProviderError,
+            "GW_UPSTREAM_OVERLOADED",
+            FailoverDecision::SwitchProvider,
+        );
+    }
+
     if status.is_server_error() {
         return (
             ErrorCategory::ProviderError,
@@ -82,17 +96,37 @@ pub(super) fn classify_upstream_status(
     }
 }
 
+/// Suggested wait from `retry-after` (delta seconds) or `retry-after-ms`; HTTP-date values are
+/// ignored. Clamped to `[1, MAX_OVERLOADED_COOLDOWN_SECS]`.
+pub(super) fn upstream_retry_after_secs(headers: &reqwest::header::HeaderMap) -> Option<i64> {
+    let header_value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v > 0.0)
+    };
+    let secs = header_value("retry-after")
+        .or_else(|| header_value("retry-after-ms").map(|ms| ms / 1000.0))?;
+    Some((secs.ceil() as i64).clamp(1, MAX_OVERLOADED_COOLDOWN_SECS))
+}
+
 pub(super) fn error_response(
+    cli_key: &str,
     status: StatusCode,
     trace_id: String,
     error_code: &'static str,
     message: String,
     attempts: Vec<FailoverAttempt>,
 ) -> Response {
-    error_response_with_retry_after(status, trace_id, error_code, message, attempts, None)
+    error_response_with_retry_after(
+        cli_key, status, trace_id, error_code, message, attempts, None,
+    )
 }
 
 pub(super) fn error_response_with_retry_after(
+    cli_key: &str,
     status: StatusCode,
     trace_id: String,
     error_code: &'static str,
@@ -100,6 +134,7 @@ pub(super) fn error_response_with_retry_after(
     attempts: Vec<FailoverAttempt>,
     retry_after_seconds: Option<u64>,
 ) -> Response {
+    let native_error = native_error_object(cli_key, status, error_code, &message);
     let payload = GatewayErrorResponse {
         trace_id: trace_id.clone(),
         error_code,
@@ -108,7 +143,12 @@ pub(super) fn error_response_with_retry_after(
         retry_after_seconds,
     };
 
-    let mut resp = (status, Json(payload)).into_response();
+    let mut body = serde_json::to_value(payload).unwrap_or_else(|_| serde_json::json!({}));
+    if let (Some(root), Some(native)) = (body.as_object_mut(), native_error) {
+        root.extend(native);
+    }
+
+    let mut resp = (status, Json(body)).into_response();
 
     if let Ok(v) = HeaderValue::from_str(&trace_id) {
         resp.headers_mut().insert("x-trace-id", v);
@@ -124,10 +164,215 @@ pub(super) fn error_response_with_retry_after(
     resp
 }
 
+/// Protocol-native error fields for each CLI, merged into the gateway error body so that the CLI
+/// renders the real reason instead of a generic failure (gateway fields stay for debugging).
+fn native_error_object(
+    cli_key: &str,
+    status: StatusCode,
+    error_code: &str,
+    message: &str,
+) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let message = format!("[{error_code}] {message}");
+    let value = match cli_key {
+        // Anthropic: {"type":"error","error":{"type":"overloaded_error","message":"..."}}
+        "claude" => {
+            let error_type = match status.as_u16() {
+                400 | 409 | 422 => "invalid_request_error",
+                401 => "authentication_error",
+                403 => "permission_error",
+                404 => "not_found_error",
+                413 => "request_too_large",
+                429 => "rate_limit_error",
+                503 | 529 => "overloaded_error",
+                _ => "api_error",
+            };
+            serde_json::json!({
+                "type": "error",
+                "error": { "type": error_type, "message": message },
+            })
+        }
+        // OpenAI: {"error":{"message":"...","type":"server_error","param":null,"code":"..."}}
+        "codex" => {
+            let error_type = match status.as_u16() {
+                401 => "authentication_error",
+                403 => "permission_error",
+                429 => "rate_limit_error",
+                code if code >= 500 => "server_error",
+                _ => "invalid_request_error",
+            };
+            serde_json::json!({
+                "error": {
+                    "message": message,
+                    "type": error_type,
+                    "param": null,
+                    "code": error_code,
+                },
+            })
+        }
+        // Google APIs: {"error":{"code":503,"message":"...","status":"UNAVAILABLE"}}
+        "gemini" => {
+            let error_status = match status.as_u16() {
+                400 | 413 | 422 => "INVALID_ARGUMENT",
+                401 => "UNAUTHENTICATED",
+                403 => "PERMISSION_DENIED",
+                404 => "NOT_FOUND",
+                409 => "ABORTED",
+                429 => "RESOURCE_EXHAUSTED",
+                503 => "UNAVAILABLE",
+                504 => "DEADLINE_EXCEEDED",
+                _ => "INTERNAL",
+            };
+            serde_json::json!({
+                "error": {
+                    "code": status.as_u16(),
+                    "message": message,
+                    "status": error_status,
+                },
+            })
+        }
+        _ => return None,
+    };
+    match value {
+        serde_json::Value::Object(map) => Some(map),
+        _ => None,
+    }
+}
+
+pub(super) const REQUEST_CANCELLED_CODE: &str = "GW_REQUEST_CANCELLED";
+pub(super) const REQUEST_CANCELLED_MESSAGE: &str = "request cancelled from the gateway UI";
+
+/// Terminal SSE event appended when a streaming request is cancelled mid-stream, in the CLI's own
+/// stream error shape so it stops waiting instead of treating the cut as a network failure.
+pub(super) fn cancelled_sse_event(cli_key: &str) -> Bytes {
+    let status = StatusCode::from_u16(499).unwrap_or(StatusCode::BAD_REQUEST);
+    let native = native_error_object(
+        cli_key,
+        status,
+        REQUEST_CANCELLED_CODE,
+        REQUEST_CANCELLED_MESSAGE,
+    )
+    .map(serde_json::Value::Object)
+    .unwrap_or_else(|| {
+        serde_json::json!({
+            "error_code": REQUEST_CANCELLED_CODE,
+            "message": REQUEST_CANCELLED_MESSAGE,
+        })
+    });
+    let event = match cli_key {
+        "claude" => format!("event: error\ndata: {native}\n\n"),
+        "codex" => {
+            let failed = serde_json::json!({
+                "type": "response.failed",
+                "response": {
+                    "status": "failed",
+                    "error": {
+                        "code": REQUEST_CANCELLED_CODE,
+                        "message": native["error"]["message"],
+                    },
+                },
+            });
+            format!("event: response.failed\ndata: {failed}\n\n")
+        }
+        _ => format!("data: {native}\n\n"),
+    };
+    Bytes::from(event)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{classify_upstream_status, FailoverDecision};
+    use super::{
+        cancelled_sse_event, classify_upstream_status, native_error_object,
+        upstream_retry_after_secs, FailoverDecision,
+    };
     use crate::gateway::proxy::ErrorCategory;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn native_error_shapes_per_cli() {
+        let claude = native_error_object(
+            "claude",
+            StatusCode::SERVICE_UNAVAILABLE,
+            "GW_ALL_PROVIDERS_UNAVAILABLE",
+            "no provider available",
+        )
+        .unwrap();
+        assert_eq!(claude["type"], "error");
+        assert_eq!(claude["error"]["type"], "overloaded_error");
+        assert_eq!(
+            claude["error"]["message"],
+            "[GW_ALL_PROVIDERS_UNAVAILABLE] no provider available"
+        );
+
+        let codex = native_error_object(
+            "codex",
+            StatusCode::BAD_GATEWAY,
+            "GW_UPSTREAM_5XX",
+            "all providers failed",
+        )
+        .unwrap();
+        assert_eq!(codex["error"]["type"], "server_error");
+        assert_eq!(codex["error"]["code"], "GW_UPSTREAM_5XX");
+
+        let gemini = native_error_object(
+            "gemini",
+            StatusCode::TOO_MANY_REQUESTS,
+            "GW_CLI_STREAM_CAP",
+            "limit reached",
+        )
+        .unwrap();
+        assert_eq!(gemini["error"]["code"], 429);
+        assert_eq!(gemini["error"]["status"], "RESOURCE_EXHAUSTED");
+
+        assert!(native_error_object("unknown", StatusCode::BAD_GATEWAY, "X", "y").is_none());
+    }
+
+    #[test]
+    fn cancelled_sse_event_uses_cli_stream_error_shape() {
+        let claude = String::from_utf8(cancelled_sse_event("claude").to_vec()).unwrap();
+        assert!(claude.starts_with("event: error\ndata: {"));
+        assert!(claude.contains("[GW_REQUEST_CANCELLED]"));
+        assert!(claude.ends_with("\n\n"));
+
+        let codex = String::from_utf8(cancelled_sse_event("codex").to_vec()).unwrap();
+        assert!(codex.starts_with("event: response.failed\ndata: "));
+        let data: serde_json::Value =
+            serde_json::from_str(codex.trim_end().split_once("data: ").unwrap().1).unwrap();
+        assert_eq!(data["response"]["status"], "failed");
+        assert_eq!(data["response"]["error"]["code"], "GW_REQUEST_CANCELLED");
+
+        let gemini = String::from_utf8(cancelled_sse_event("gemini").to_vec()).unwrap();
+        assert!(gemini.starts_with("data: {\"error\""));
+    }
+
+    #[test]
+    fn upstream_529_switches_provider_with_dedicated_code() {
+        let status = reqwest::StatusCode::from_u16(529).unwrap();
+        let (category, code, decision) = classify_upstream_status(status);
+        assert!(matches!(category, ErrorCategory::ProviderError));
+        assert_eq!(code, "GW_UPSTREAM_OVERLOADED");
+        assert!(matches!(decision, FailoverDecision::SwitchProvider));
+    }
+
+    #[test]
+    fn upstream_retry_after_parses_seconds_and_millis() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(upstream_retry_after_secs(&headers), None);
+
+        headers.insert("retry-after-ms", "1500".parse().unwrap());
+        assert_eq!(upstream_retry_after_secs(&headers), Some(2));
+
+        headers.insert("retry-after", " 30 ".parse().unwrap());
+        assert_eq!(upstream_retry_after_secs(&headers), Some(30));
+
+        headers.insert("retry-after", "86400".parse().unwrap());
+        assert_eq!(upstream_retry_after_secs(&headers), Some(600));
+
+        headers.insert(
+            "retry-after",
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(upstream_retry_after_secs(&headers), Some(2));
+    }
 
     #[test]
     fn upstream_402_switches_provider() {
@@ -1,7 +1,7 @@
 //! Usage: Failover policy helpers (retry/switch decisions, provider selection, base_url picking).
 
-use crate::providers;
 use crate::shared::mutex_ext::MutexExt;
+use crate::{power, providers, settings};
 use std::collections::HashSet;
 use std::time::Duration;
 
@@ -26,18 +26,42 @@ impl FailoverDecision {
 }
 
 pub(super) fn retry_backoff_delay(
+    cfg: &settings::RetryBackoffConfig,
     status: reqwest::StatusCode,
     retry_index: u32,
 ) -> Option<Duration> {
-    if !matches!(status.as_u16(), 408 | 429) {
+    let mut buf = [0u8; 4];
+    let unit = match getrandom::getrandom(&mut buf) {
+        Ok(()) => u32::from_le_bytes(buf) as f64 / u32::MAX as f64,
+        Err(_) => 0.5,
+    };
+    retry_backoff_delay_with_jitter(cfg, status, retry_index, unit)
+}
+
+// `unit` in [0, 1] picks how much of the jitter share is removed from the delay.
+fn retry_backoff_delay_with_jitter(
+    cfg: &settings::RetryBackoffConfig,
+    status: reqwest::StatusCode,
+    retry_index: u32,
+    unit: f64,
+) -> Option<Duration> {
+    if !cfg.statuses.contains(&status.as_u16()) {
         return None;
     }
 
     let retry_index = retry_index.max(1);
-    let base_ms = 80u64;
-    let max_ms = 800u64;
-    let ms = base_ms.saturating_mul(retry_index as u64).min(max_ms);
-    Some(Duration::from_millis(ms))
+    let base_ms = cfg.base_ms as f64;
+    let raw_ms = match cfg.strategy {
+        settings::RetryBackoffStrategy::Linear => base_ms * retry_index as f64,
+        settings::RetryBackoffStrategy::Exponential => {
+            base_ms * cfg.multiplier.max(1.0).powi(retry_index.min(64) as i32 - 1)
+        }
+    };
+    let capped_ms = raw_ms.min(cfg.max_ms as f64).max(0.0);
+    let jitter = cfg.jitter_percent.min(100) as f64 / 100.0 * unit.clamp(0.0, 1.0);
+    Some(Duration::from_millis(
+        (capped_ms * (1.0 - jitter)).round() as u64
+    ))
 }
 
 pub(super) fn should_reuse_provider(body_json: Option<&serde_json::Value>) -> bool {
@@ -132,13 +156,40 @@ pub(super) async fn select_provider_base_url_for_request(
         }
     }
 
+    // Low-power mode: skip active probing and fall back to the primary base_url.
+    if power::background_work_suspended(&state.app) {
+        return primary;
+    }
+
     let ttl_ms = (cache_ttl_seconds.max(1) as u64).saturating_mul(1000);
     let expires_at_unix_ms = now_unix_ms.saturating_add(ttl_ms);
+
+    probe_provider_base_urls(state, provider, "gateway", expires_at_unix_ms)
+        .await
+        .unwrap_or(primary)
+}
+
+/// Pings every base_url of `provider`, records the results and caches the fastest one until
+/// `expires_at_unix_ms`. Returns `None` when every probe failed.
+pub(super) async fn probe_provider_base_urls(
+    state: &GatewayAppState,
+    provider: &providers::ProviderForGateway,
+    source: &'static str,
+    expires_at_unix_ms: u64,
+) -> Option<String> {
     let timeout = Duration::from_millis(PROVIDER_BASE_URL_PING_TIMEOUT_MS);
 
+    let network = settings::read(&state.app)
+        .map(|cfg| cfg.egress_network)
+        .unwrap_or_default();
     let mut join_set = tokio::task::JoinSet::new();
     for base_url in provider.base_urls.iter().cloned() {
-        let client = state.client.clone();
+        let client = state.upstream_clients.for_provider(
+            &network,
+            &provider.upstream_proxy,
+            &base_url,
+            &provider.upstream_tls,
+        );
         join_set.spawn(async move {
             let result =
                 crate::base_url_probe::probe_base_url_ms(&client, &base_url, timeout).await;
@@ -147,10 +198,17 @@ pub(super) async fn select_provider_base_url_for_request(
     }
 
     let mut best: Option<(String, u64)> = None;
+    let mut probe_records: Vec<crate::base_url_probe::BaseUrlProbeRecord> =
+        Vec::with_capacity(provider.base_urls.len());
     while let Some(joined) = join_set.join_next().await {
         let Ok((base_url, result)) = joined else {
             continue;
         };
+        probe_records.push(crate::base_url_probe::BaseUrlProbeRecord {
+            provider_id: Some(provider.id),
+            base_url: base_url.clone(),
+            result: result.clone(),
+        });
         let Ok(ms) = result else {
             continue;
         };
@@ -161,16 +219,21 @@ pub(super) async fn select_provider_base_url_for_request(
         }
     }
 
-    let Some((best_base_url, _best_latency_ms)) = best else {
-        return primary;
-    };
+    let db = state.db.clone();
+    std::mem::drop(tauri::async_runtime::spawn_blocking(move || {
+        if let Err(err) = crate::base_url_probe::record_results(&db, source, &probe_records) {
+            tracing::warn!("base_url 测速记录写入失败: {}", err);
+        }
+    }));
+
+    let (best_base_url, _best_latency_ms) = best?;
 
     {
         let mut cache = state.latency_cache.lock_or_recover();
         cache.put_best_base_url(provider.id, best_base_url.clone(), expires_at_unix_ms);
     }
 
-    best_base_url
+    Some(best_base_url)
 }
 
 #[cfg(test)]
@@ -1,4 +1,6 @@
-use super::select_next_provider_id_from_order;
+use super::{retry_backoff_delay_with_jitter, select_next_provider_id_from_order};
+use crate::settings::{RetryBackoffConfig, RetryBackoffStrategy};
+use reqwest::StatusCode;
 use std::collections::HashSet;
 
 fn set(ids: &[i64]) -> HashSet<i64> {
@@ -42,3 +44,63 @@ fn select_next_provider_id_starts_from_head_when_bound_missing() {
         Some(30)
     );
 }
+
+fn delay_ms(
+    cfg: &RetryBackoffConfig,
+    status: StatusCode,
+    retry_index: u32,
+    unit: f64,
+) -> Option<u128> {
+    retry_backoff_delay_with_jitter(cfg, status, retry_index, unit).map(|d| d.as_millis())
+}
+
+#[test]
+fn retry_backoff_default_is_linear_on_rate_limit_and_timeout() {
+    let cfg = RetryBackoffConfig::default();
+    assert_eq!(
+        delay_ms(&cfg, StatusCode::TOO_MANY_REQUESTS, 1, 0.7),
+        Some(80)
+    );
+    assert_eq!(
+        delay_ms(&cfg, StatusCode::REQUEST_TIMEOUT, 3, 0.7),
+        Some(240)
+    );
+    assert_eq!(
+        delay_ms(&cfg, StatusCode::TOO_MANY_REQUESTS, 20, 0.7),
+        Some(800)
+    );
+    assert_eq!(delay_ms(&cfg, StatusCode::BAD_GATEWAY, 1, 0.7), None);
+}
+
+#[test]
+fn retry_backoff_exponential_with_full_jitter() {
+    let cfg = RetryBackoffConfig {
+        strategy: RetryBackoffStrategy::Exponential,
+        base_ms: 500,
+        max_ms: 10_000,
+        multiplier: 2.0,
+        jitter_percent: 100,
+        statuses: vec![429, 503],
+    };
+    assert_eq!(
+        delay_ms(&cfg, StatusCode::SERVICE_UNAVAILABLE, 1, 0.0),
+        Some(500)
+    );
+    assert_eq!(
+        delay_ms(&cfg, StatusCode::TOO_MANY_REQUESTS, 3, 0.0),
+        Some(2000)
+    );
+    assert_eq!(
+        delay_ms(&cfg, StatusCode::TOO_MANY_REQUESTS, 3, 0.5),
+        Some(1000)
+    );
+    assert_eq!(
+        delay_ms(&cfg, StatusCode::TOO_MANY_REQUESTS, 3, 1.0),
+        Some(0)
+    );
+    assert_eq!(
+        delay_ms(&cfg, StatusCode::TOO_MANY_REQUESTS, 10, 0.0),
+        Some(10_000)
+    );
+    assert_eq!(delay_ms(&cfg, StatusCode::REQUEST_TIMEOUT, 1, 0.0), None);
+}
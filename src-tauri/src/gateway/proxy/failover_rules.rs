@@ -0,0 +1,165 @@
+//! Usage: User failover rules (settings `failover_rules`) — map an upstream error status / body to a
+//! failover decision, overriding `errors::classify_upstream_status` and the built-in client error rules.
+
+use super::failover::FailoverDecision;
+use crate::settings::{FailoverRule, FailoverRuleDecision};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Error bodies larger than this are not read for `body_regex` rules.
+const MAX_SCAN_BYTES: u64 = 64 * 1024;
+
+fn rule_applies(rule: &FailoverRule, cli_key: &str, provider_id: i64, status: u16) -> bool {
+    rule.enabled
+        && (rule.cli_key.is_empty() || rule.cli_key == cli_key)
+        && (rule.provider_ids.is_empty() || rule.provider_ids.contains(&provider_id))
+        && (rule.statuses.is_empty() || rule.statuses.contains(&status))
+}
+
+/// Returns whether the upstream error body should be read because an applicable rule has a `body_regex`.
+pub(super) fn should_read_body(
+    rules: &[FailoverRule],
+    cli_key: &str,
+    provider_id: i64,
+    status: u16,
+    content_length: Option<u64>,
+) -> bool {
+    content_length.is_none_or(|len| len <= MAX_SCAN_BYTES)
+        && rules.iter().any(|rule| {
+            !rule.body_regex.is_empty() && rule_applies(rule, cli_key, provider_id, status)
+        })
+}
+
+/// First matching rule in configured order; `body_regex` rules never match without a body.
+pub(super) fn match_rule<'a>(
+    rules: &'a [FailoverRule],
+    cli_key: &str,
+    provider_id: i64,
+    status: u16,
+    body: Option<&[u8]>,
+) -> Option<&'a FailoverRule> {
+    let body_text = body.map(String::from_utf8_lossy);
+    rules.iter().find(|rule| {
+        if !rule_applies(rule, cli_key, provider_id, status) {
+            return false;
+        }
+        if rule.body_regex.is_empty() {
+            return true;
+        }
+        let Some(text) = body_text.as_deref() else {
+            return false;
+        };
+        compiled_regex(&rule.body_regex).is_some_and(|regex| regex.is_match(text))
+    })
+}
+
+pub(super) fn decision_of(rule: &FailoverRule) -> FailoverDecision {
+    match rule.decision {
+        FailoverRuleDecision::RetrySameProvider => FailoverDecision::RetrySameProvider,
+        FailoverRuleDecision::SwitchProvider => FailoverDecision::SwitchProvider,
+        FailoverRuleDecision::Abort => FailoverDecision::Abort,
+    }
+}
+
+/// Compiled `body_regex` patterns, cached by pattern text.
+fn compiled_regex(pattern: &str) -> Option<Regex> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Option<Regex>>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut guard = cache.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(compiled) = guard.get(pattern) {
+        return compiled.clone();
+    }
+    // 设置写入时已校验；这里仍跳过无法编译的表达式。规则上限很小，缓存超出时直接清空即可
+    if guard.len() >= 256 {
+        guard.clear();
+    }
+    let compiled = Regex::new(pattern).ok();
+    guard.insert(pattern.to_string(), compiled.clone());
+    compiled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        id: &str,
+        statuses: &[u16],
+        body_regex: &str,
+        decision: FailoverRuleDecision,
+    ) -> FailoverRule {
+        FailoverRule {
+            id: id.to_string(),
+            statuses: statuses.to_vec(),
+            body_regex: body_regex.to_string(),
+            decision,
+            ..FailoverRule::default()
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins_and_scopes_apply() {
+        let mut relay_429 = rule(
+            "relay-429",
+            &[429],
+            "",
+            FailoverRuleDecision::SwitchProvider,
+        );
+        relay_429.provider_ids = vec![7];
+        let mut codex_only = rule("codex-400", &[400], "", FailoverRuleDecision::Abort);
+        codex_only.cli_key = "codex".to_string();
+        let mut disabled = rule("off", &[429], "", FailoverRuleDecision::Abort);
+        disabled.enabled = false;
+        let rules = vec![disabled, relay_429, codex_only];
+
+        let hit = match_rule(&rules, "claude", 7, 429, None).unwrap();
+        assert_eq!(hit.id, "relay-429");
+        assert!(matches!(decision_of(hit), FailoverDecision::SwitchProvider));
+        assert!(match_rule(&rules, "claude", 8, 429, None).is_none());
+        assert!(match_rule(&rules, "claude", 7, 400, None).is_none());
+        assert_eq!(
+            match_rule(&rules, "codex", 1, 400, None).unwrap().id,
+            "codex-400"
+        );
+    }
+
+    #[test]
+    fn body_regex_rules_need_a_matching_body() {
+        let rules = vec![rule(
+            "transient-400",
+            &[400],
+            r"(?i)upstream\s+busy",
+            FailoverRuleDecision::RetrySameProvider,
+        )];
+
+        assert!(should_read_body(&rules, "claude", 1, 400, Some(512)));
+        assert!(should_read_body(&rules, "claude", 1, 400, None));
+        assert!(!should_read_body(
+            &rules,
+            "claude",
+            1,
+            400,
+            Some(MAX_SCAN_BYTES + 1)
+        ));
+        assert!(!should_read_body(&rules, "claude", 1, 429, Some(512)));
+
+        let body = br#"{"error":{"message":"Upstream  BUSY, try again"}}"#;
+        assert!(match_rule(&rules, "claude", 1, 400, Some(body)).is_some());
+        assert!(match_rule(&rules, "claude", 1, 400, Some(b"invalid model")).is_none());
+        assert!(match_rule(&rules, "claude", 1, 400, None).is_none());
+    }
+
+    #[test]
+    fn empty_statuses_match_any_error_status() {
+        let rules = vec![rule(
+            "quota",
+            &[],
+            "insufficient_quota",
+            FailoverRuleDecision::SwitchProvider,
+        )];
+        for status in [400, 403, 429, 500] {
+            assert!(match_rule(&rules, "codex", 3, status, Some(b"insufficient_quota")).is_some());
+        }
+    }
+}
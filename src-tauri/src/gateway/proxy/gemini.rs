@@ -0,0 +1,75 @@
+//! Usage: Gemini native protocol adapter (`generateContent` / `streamGenerateContent` routing).
+//!
+//! 用法：
+//! - `normalize_request`：在 failover 之前规整 Gemini 请求的 path 与 query
+//! - 缺少版本前缀的 `/models/...` 补齐为 `/v1beta/models/...`
+//! - 去掉客户端 `key=` 查询参数（本地网关令牌），上游鉴权统一由 `inject_provider_auth` 通过 `x-goog-api-key` 注入
+//! - `streamGenerateContent` 不强制 `alt=sse`：未带时上游返回 JSON 数组，按非流式路径统计用量
+
+const DEFAULT_API_VERSION_PREFIX: &str = "/v1beta";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct GeminiRequest {
+    pub(super) forwarded_path: String,
+    pub(super) query: Option<String>,
+}
+
+pub(super) fn normalize_request(forwarded_path: &str, query: Option<&str>) -> GeminiRequest {
+    let forwarded_path = if forwarded_path.starts_with("/models/") {
+        format!("{DEFAULT_API_VERSION_PREFIX}{forwarded_path}")
+    } else {
+        forwarded_path.to_string()
+    };
+
+    GeminiRequest {
+        forwarded_path,
+        query: query.and_then(strip_api_key_param),
+    }
+}
+
+fn strip_api_key_param(query: &str) -> Option<String> {
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty() && *pair != "key" && !pair.starts_with("key="))
+        .collect();
+    if kept.is_empty() {
+        None
+    } else {
+        Some(kept.join("&"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_api_version_prefix_to_bare_model_paths() {
+        let req = normalize_request("/models/gemini-2.5-pro:generateContent", None);
+        assert_eq!(
+            req.forwarded_path,
+            "/v1beta/models/gemini-2.5-pro:generateContent"
+        );
+
+        let req = normalize_request("/v1/models/gemini-2.5-pro:streamGenerateContent", None);
+        assert_eq!(
+            req.forwarded_path,
+            "/v1/models/gemini-2.5-pro:streamGenerateContent"
+        );
+    }
+
+    #[test]
+    fn strips_client_api_key_from_query() {
+        let req = normalize_request(
+            "/v1beta/models/gemini-2.5-pro:streamGenerateContent",
+            Some("alt=sse&key=local-token"),
+        );
+        assert_eq!(req.query.as_deref(), Some("alt=sse"));
+
+        let req = normalize_request("/v1beta/models", Some("key=local-token"));
+        assert_eq!(req.query, None);
+
+        let req = normalize_request("/v1beta/models", Some("pageSize=50&keyword=x"));
+        assert_eq!(req.query.as_deref(), Some("pageSize=50&keyword=x"));
+    }
+}
@@ -55,6 +55,8 @@ async fn record_system_failure_and_decide_impl(
         provider_base_url_base,
         provider_index,
         session_reuse,
+        protocol_bridge: _,
+        exclude_from_stats: _,
     } = provider_ctx;
 
     let AttemptCtx {
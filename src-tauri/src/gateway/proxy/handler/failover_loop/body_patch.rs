@@ -0,0 +1,50 @@
+//! Usage: Per-provider request body patch rules (`body_patch_rules`) for failover attempts.
+
+use super::context::{CommonCtx, ProviderCtx};
+use crate::gateway::body_patch::apply_body_patch_rules;
+use crate::providers;
+use axum::body::Bytes;
+
+pub(super) fn apply_if_needed(
+    ctx: CommonCtx<'_>,
+    provider: &providers::ProviderForGateway,
+    provider_ctx: ProviderCtx<'_>,
+    body_bytes: &mut Bytes,
+    strip_request_content_encoding: &mut bool,
+) {
+    if provider.body_patch_rules.is_empty() || body_bytes.is_empty() {
+        return;
+    }
+    let Ok(mut root) = serde_json::from_slice::<serde_json::Value>(body_bytes.as_ref()) else {
+        return;
+    };
+
+    let applied = apply_body_patch_rules(&mut root, &provider.body_patch_rules);
+    if applied.is_empty() {
+        return;
+    }
+    let Ok(next) = serde_json::to_vec(&root) else {
+        return;
+    };
+    *body_bytes = Bytes::from(next);
+    *strip_request_content_encoding = true;
+
+    let applied: Vec<serde_json::Value> = applied
+        .into_iter()
+        .map(|rule| match rule.op {
+            providers::BodyPatchOp::Rename => {
+                serde_json::json!({ "op": rule.op, "path": rule.path, "to": rule.to })
+            }
+            _ => serde_json::json!({ "op": rule.op, "path": rule.path }),
+        })
+        .collect();
+    if let Ok(mut settings) = ctx.special_settings.lock() {
+        settings.push(serde_json::json!({
+            "type": "body_patch",
+            "scope": "attempt",
+            "hit": true,
+            "providerId": provider_ctx.provider_id,
+            "applied": applied,
+        }));
+    }
+}
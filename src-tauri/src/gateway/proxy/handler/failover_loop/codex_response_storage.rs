@@ -0,0 +1,119 @@
+//! Usage: Per-provider `store: false` enforcement for Codex `/v1/responses` attempts.
+
+use super::context::{CommonCtx, ProviderCtx};
+use crate::providers;
+use axum::body::Bytes;
+
+const ENCRYPTED_REASONING_INCLUDE: &str = "reasoning.encrypted_content";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct StorageRewrite {
+    changed: bool,
+    stripped_previous_response_id: bool,
+}
+
+/// Forces `store: false`. A `previous_response_id` chain can't resolve against an unstored
+/// response, so it is dropped; encrypted reasoning is requested instead so multi-turn reasoning
+/// still round-trips through the client.
+fn disable_storage(root: &mut serde_json::Value) -> StorageRewrite {
+    let mut rewrite = StorageRewrite::default();
+    let Some(obj) = root.as_object_mut() else {
+        return rewrite;
+    };
+
+    if obj.get("store").and_then(|v| v.as_bool()) != Some(false) {
+        obj.insert("store".to_string(), serde_json::Value::Bool(false));
+        rewrite.changed = true;
+    }
+    if obj.remove("previous_response_id").is_some() {
+        rewrite.changed = true;
+        rewrite.stripped_previous_response_id = true;
+    }
+    if obj.contains_key("reasoning") {
+        let include = obj
+            .entry("include")
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        if let Some(items) = include.as_array_mut() {
+            if !items
+                .iter()
+                .any(|v| v.as_str() == Some(ENCRYPTED_REASONING_INCLUDE))
+            {
+                items.push(serde_json::Value::String(
+                    ENCRYPTED_REASONING_INCLUDE.to_string(),
+                ));
+                rewrite.changed = true;
+            }
+        }
+    }
+    rewrite
+}
+
+pub(super) fn apply_if_needed(
+    ctx: CommonCtx<'_>,
+    provider: &providers::ProviderForGateway,
+    provider_ctx: ProviderCtx<'_>,
+    introspection_json: Option<&serde_json::Value>,
+    body_bytes: &mut Bytes,
+    strip_request_content_encoding: &mut bool,
+) {
+    if ctx.cli_key != "codex"
+        || !provider.disable_response_storage
+        || !ctx
+            .forwarded_path
+            .trim_end_matches('/')
+            .ends_with("/responses")
+    {
+        return;
+    }
+    let Some(root) = introspection_json else {
+        return;
+    };
+
+    let mut next = root.clone();
+    let rewrite = disable_storage(&mut next);
+    if !rewrite.changed {
+        return;
+    }
+    let Ok(bytes) = serde_json::to_vec(&next) else {
+        return;
+    };
+    *body_bytes = Bytes::from(bytes);
+    *strip_request_content_encoding = true;
+
+    if let Ok(mut settings) = ctx.special_settings.lock() {
+        settings.push(serde_json::json!({
+            "type": "codex_disable_response_storage",
+            "scope": "attempt",
+            "hit": true,
+            "providerId": provider_ctx.provider_id,
+            "strippedPreviousResponseId": rewrite.stripped_previous_response_id,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forces_store_false_and_drops_response_chain() {
+        let mut body = serde_json::json!({
+            "model": "gpt-5.2",
+            "store": true,
+            "previous_response_id": "resp_123",
+            "reasoning": {"effort": "high"},
+            "input": [],
+        });
+        let rewrite = disable_storage(&mut body);
+        assert!(rewrite.changed);
+        assert!(rewrite.stripped_previous_response_id);
+        assert_eq!(body["store"], false);
+        assert!(body.get("previous_response_id").is_none());
+        assert_eq!(
+            body["include"],
+            serde_json::json!([ENCRYPTED_REASONING_INCLUDE])
+        );
+
+        assert_eq!(disable_storage(&mut body), StorageRewrite::default());
+    }
+}
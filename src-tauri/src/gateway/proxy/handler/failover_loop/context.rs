@@ -1,11 +1,14 @@
 //! Usage: Shared context types for `failover_loop` internal submodules.
 
 use super::super::super::abort_guard::RequestAbortGuard;
+use super::super::super::protocol_bridge::ProtocolBridge;
+use super::super::super::RequestLogAttribution;
 use crate::circuit_breaker;
 use crate::gateway::events::FailoverAttempt;
 use crate::gateway::manager::GatewayAppState;
 use crate::gateway::response_fixer;
-use crate::gateway::streams::StreamFinalizeCtx;
+use crate::gateway::streams::{SseCaptureMode, StreamFinalizeCtx};
+use crate::{providers, settings};
 use axum::response::Response;
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
@@ -25,17 +28,27 @@ pub(super) struct CommonCtxArgs<'a> {
     pub(super) created_at: i64,
     pub(super) session_id: &'a Option<String>,
     pub(super) requested_model: &'a Option<String>,
+    pub(super) attribution: &'a RequestLogAttribution,
     pub(super) effective_sort_mode_id: Option<i64>,
+    pub(super) prefix_affinity_key: Option<u64>,
     pub(super) special_settings: &'a Arc<Mutex<Vec<serde_json::Value>>>,
     pub(super) provider_cooldown_secs: i64,
     pub(super) upstream_first_byte_timeout_secs: u32,
     pub(super) upstream_first_byte_timeout: Option<Duration>,
     pub(super) upstream_stream_idle_timeout: Option<Duration>,
+    pub(super) stream_tool_pause_idle_timeout: Option<Duration>,
     pub(super) upstream_request_timeout_non_streaming: Option<Duration>,
+    pub(super) response_cache_ttl: Option<Duration>,
+    // Request fingerprint (`util::compute_request_fingerprint`); keys the response cache.
+    pub(super) fingerprint_key: u64,
+    pub(super) fingerprint_debug: &'a String,
     pub(super) max_attempts_per_provider: u32,
     pub(super) enable_response_fixer: bool,
     pub(super) response_fixer_stream_config: response_fixer::ResponseFixerConfig,
     pub(super) response_fixer_non_stream_config: response_fixer::ResponseFixerConfig,
+    pub(super) retry_backoff: &'a settings::RetryBackoffConfig,
+    pub(super) failover_rules: &'a [settings::FailoverRule],
+    pub(super) sse_capture: SseCaptureMode,
     pub(super) introspection_body: &'a [u8],
 }
 
@@ -52,17 +65,27 @@ pub(super) struct CommonCtx<'a> {
     pub(super) created_at: i64,
     pub(super) session_id: &'a Option<String>,
     pub(super) requested_model: &'a Option<String>,
+    pub(super) attribution: &'a RequestLogAttribution,
     pub(super) effective_sort_mode_id: Option<i64>,
+    pub(super) prefix_affinity_key: Option<u64>,
     pub(super) special_settings: &'a Arc<Mutex<Vec<serde_json::Value>>>,
     pub(super) provider_cooldown_secs: i64,
     pub(super) upstream_first_byte_timeout_secs: u32,
     pub(super) upstream_first_byte_timeout: Option<Duration>,
     pub(super) upstream_stream_idle_timeout: Option<Duration>,
+    pub(super) stream_tool_pause_idle_timeout: Option<Duration>,
     pub(super) upstream_request_timeout_non_streaming: Option<Duration>,
+    pub(super) response_cache_ttl: Option<Duration>,
+    // Request fingerprint (`util::compute_request_fingerprint`); keys the response cache.
+    pub(super) fingerprint_key: u64,
+    pub(super) fingerprint_debug: &'a String,
     pub(super) max_attempts_per_provider: u32,
     pub(super) enable_response_fixer: bool,
     pub(super) response_fixer_stream_config: response_fixer::ResponseFixerConfig,
     pub(super) response_fixer_non_stream_config: response_fixer::ResponseFixerConfig,
+    pub(super) retry_backoff: &'a settings::RetryBackoffConfig,
+    pub(super) failover_rules: &'a [settings::FailoverRule],
+    pub(super) sse_capture: SseCaptureMode,
     pub(super) introspection_body: &'a [u8],
 }
 
@@ -80,22 +103,63 @@ impl<'a> CommonCtx<'a> {
             created_at: args.created_at,
             session_id: args.session_id,
             requested_model: args.requested_model,
+            attribution: args.attribution,
             effective_sort_mode_id: args.effective_sort_mode_id,
+            prefix_affinity_key: args.prefix_affinity_key,
             special_settings: args.special_settings,
             provider_cooldown_secs: args.provider_cooldown_secs,
             upstream_first_byte_timeout_secs: args.upstream_first_byte_timeout_secs,
             upstream_first_byte_timeout: args.upstream_first_byte_timeout,
             upstream_stream_idle_timeout: args.upstream_stream_idle_timeout,
+            stream_tool_pause_idle_timeout: args.stream_tool_pause_idle_timeout,
             upstream_request_timeout_non_streaming: args.upstream_request_timeout_non_streaming,
+            response_cache_ttl: args.response_cache_ttl,
+            fingerprint_key: args.fingerprint_key,
+            fingerprint_debug: args.fingerprint_debug,
             max_attempts_per_provider: args.max_attempts_per_provider,
             enable_response_fixer: args.enable_response_fixer,
             response_fixer_stream_config: args.response_fixer_stream_config,
             response_fixer_non_stream_config: args.response_fixer_non_stream_config,
+            retry_backoff: args.retry_backoff,
+            failover_rules: args.failover_rules,
+            sse_capture: args.sse_capture,
             introspection_body: args.introspection_body,
         }
     }
 }
 
+impl CommonCtx<'_> {
+    /// Returns a copy whose response fixer settings honour the provider's overrides.
+    pub(super) fn with_provider_response_fixer(
+        self,
+        overrides: &providers::ResponseFixerOverride,
+    ) -> Self {
+        if *overrides == providers::ResponseFixerOverride::default() {
+            return self;
+        }
+        let mut non_stream_config = self
+            .response_fixer_non_stream_config
+            .with_override(overrides);
+        non_stream_config.fix_sse_format = false;
+        Self {
+            enable_response_fixer: overrides.enabled.unwrap_or(self.enable_response_fixer),
+            response_fixer_stream_config: self
+                .response_fixer_stream_config
+                .with_override(overrides),
+            response_fixer_non_stream_config: non_stream_config,
+            ..self
+        }
+    }
+
+    /// Returns a copy with response fixing disabled (passthrough providers).
+    pub(super) fn without_response_fixer(self) -> Self {
+        Self {
+            enable_response_fixer: false,
+            ..self
+        }
+    }
+}
+
 impl<'a> From<CommonCtxArgs<'a>> for CommonCtx<'a> {
     fn from(args: CommonCtxArgs<'a>) -> Self {
         Self::new(args)
@@ -114,17 +178,24 @@ pub(super) struct CommonCtxOwned<'a> {
     pub(super) created_at: i64,
     pub(super) session_id: Option<String>,
     pub(super) requested_model: Option<String>,
+    pub(super) attribution: RequestLogAttribution,
     pub(super) effective_sort_mode_id: Option<i64>,
+    pub(super) prefix_affinity_key: Option<u64>,
     pub(super) special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
     pub(super) provider_cooldown_secs: i64,
     pub(super) upstream_first_byte_timeout_secs: u32,
     pub(super) upstream_first_byte_timeout: Option<Duration>,
     pub(super) upstream_stream_idle_timeout: Option<Duration>,
+    pub(super) stream_tool_pause_idle_timeout: Option<Duration>,
     pub(super) upstream_request_timeout_non_streaming: Option<Duration>,
+    pub(super) response_cache_ttl: Option<Duration>,
+    pub(super) fingerprint_key: u64,
+    pub(super) fingerprint_debug: String,
     pub(super) max_attempts_per_provider: u32,
     pub(super) enable_response_fixer: bool,
     pub(super) response_fixer_stream_config: response_fixer::ResponseFixerConfig,
     pub(super) response_fixer_non_stream_config: response_fixer::ResponseFixerConfig,
+    pub(super) sse_capture: SseCaptureMode,
 }
 
 impl<'a> From<CommonCtx<'a>> for CommonCtxOwned<'a> {
@@ -141,17 +212,24 @@ impl<'a> From<CommonCtx<'a>> for CommonCtxOwned<'a> {
             created_at: ctx.created_at,
             session_id: ctx.session_id.clone(),
             requested_model: ctx.requested_model.clone(),
+            attribution: ctx.attribution.clone(),
             effective_sort_mode_id: ctx.effective_sort_mode_id,
+            prefix_affinity_key: ctx.prefix_affinity_key,
             special_settings: Arc::clone(ctx.special_settings),
             provider_cooldown_secs: ctx.provider_cooldown_secs,
             upstream_first_byte_timeout_secs: ctx.upstream_first_byte_timeout_secs,
             upstream_first_byte_timeout: ctx.upstream_first_byte_timeout,
             upstream_stream_idle_timeout: ctx.upstream_stream_idle_timeout,
+            stream_tool_pause_idle_timeout: ctx.stream_tool_pause_idle_timeout,
             upstream_request_timeout_non_streaming: ctx.upstream_request_timeout_non_streaming,
+            response_cache_ttl: ctx.response_cache_ttl,
+            fingerprint_key: ctx.fingerprint_key,
+            fingerprint_debug: ctx.fingerprint_debug.clone(),
             max_attempts_per_provider: ctx.max_attempts_per_provider,
             enable_response_fixer: ctx.enable_response_fixer,
             response_fixer_stream_config: ctx.response_fixer_stream_config,
             response_fixer_non_stream_config: ctx.response_fixer_non_stream_config,
+            sse_capture: ctx.sse_capture,
         }
     }
 }
@@ -163,6 +241,8 @@ pub(super) struct ProviderCtx<'a> {
     pub(super) provider_base_url_base: &'a String,
     pub(super) provider_index: u32,
     pub(super) session_reuse: Option<bool>,
+    pub(super) protocol_bridge: Option<ProtocolBridge>,
+    pub(super) exclude_from_stats: bool,
 }
 
 pub(super) struct ProviderCtxOwned {
@@ -171,6 +251,8 @@ pub(super) struct ProviderCtxOwned {
     pub(super) provider_base_url_base: String,
     pub(super) provider_index: u32,
     pub(super) session_reuse: Option<bool>,
+    pub(super) protocol_bridge: Option<ProtocolBridge>,
+    pub(super) exclude_from_stats: bool,
 }
 
 impl<'a> From<ProviderCtx<'a>> for ProviderCtxOwned {
@@ -181,6 +263,8 @@ impl<'a> From<ProviderCtx<'a>> for ProviderCtxOwned {
             provider_base_url_base: ctx.provider_base_url_base.clone(),
             provider_index: ctx.provider_index,
             session_reuse: ctx.session_reuse,
+            protocol_bridge: ctx.protocol_bridge,
+            exclude_from_stats: ctx.exclude_from_stats,
         }
     }
 }
@@ -203,12 +287,13 @@ pub(super) fn build_stream_finalize_ctx(
         session: ctx.state.session.clone(),
         session_id: ctx.session_id.clone(),
         sort_mode_id: ctx.effective_sort_mode_id,
+        prefix_affinity_key: ctx.prefix_affinity_key,
         trace_id: ctx.trace_id.clone(),
         cli_key: ctx.cli_key.clone(),
         method: ctx.method_hint.clone(),
         path: ctx.forwarded_path.clone(),
         query: ctx.query.clone(),
-        excluded_from_stats: false,
+        excluded_from_stats: provider_ctx.exclude_from_stats,
         special_settings: Arc::clone(&ctx.special_settings),
         status,
         error_category,
@@ -217,12 +302,14 @@ pub(super) fn build_stream_finalize_ctx(
         attempts: attempts.to_vec(),
         attempts_json,
         requested_model: ctx.requested_model.clone(),
+        attribution: ctx.attribution.clone(),
         created_at_ms: ctx.created_at_ms,
         created_at: ctx.created_at,
         provider_cooldown_secs: ctx.provider_cooldown_secs,
         provider_id: provider_ctx.provider_id,
         provider_name: provider_ctx.provider_name_base.clone(),
         base_url: provider_ctx.provider_base_url_base.clone(),
+        sse_capture: ctx.sse_capture,
     }
 }
 
@@ -0,0 +1,52 @@
+//! Usage: Per-provider context guard (`context_guard_limit_kb`) for failover attempts.
+
+use super::context::{CommonCtx, ProviderCtx};
+use crate::gateway::context_guard;
+use crate::providers;
+use axum::body::Bytes;
+
+pub(super) fn apply_if_needed(
+    ctx: CommonCtx<'_>,
+    provider: &providers::ProviderForGateway,
+    provider_ctx: ProviderCtx<'_>,
+    body_bytes: &mut Bytes,
+    strip_request_content_encoding: &mut bool,
+) {
+    if provider.context_guard_limit_kb <= 0 || body_bytes.is_empty() {
+        return;
+    }
+    let limit_bytes = (provider.context_guard_limit_kb as usize).saturating_mul(1024);
+    let Some(trim) = context_guard::trim_to_limit(body_bytes.as_ref(), limit_bytes) else {
+        return;
+    };
+
+    let trimmed_bytes = trim.body.len();
+    *body_bytes = Bytes::from(trim.body);
+    *strip_request_content_encoding = true;
+
+    if !trim.fits {
+        tracing::warn!(
+            trace_id = %ctx.trace_id,
+            provider_id = provider_ctx.provider_id,
+            limit_bytes,
+            trimmed_bytes,
+            "上下文裁剪后请求体仍超过供应商上限"
+        );
+    }
+    if let Ok(mut settings) = ctx.special_settings.lock() {
+        settings.push(serde_json::json!({
+            "type": "context_trim",
+            "scope": "attempt",
+            "hit": true,
+            "providerId": provider_ctx.provider_id,
+            "limitBytes": limit_bytes,
+            "originalBytes": trim.original_bytes,
+            "trimmedBytes": trimmed_bytes,
+            "originalTokensEstimate": trim.original_tokens,
+            "trimmedTokensEstimate": trim.trimmed_tokens,
+            "droppedMessages": trim.dropped_messages,
+            "truncatedToolResults": trim.truncated_tool_results,
+            "fits": trim.fits,
+        }));
+    }
+}
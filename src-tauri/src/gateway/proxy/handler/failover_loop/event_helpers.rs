@@ -26,6 +26,8 @@ pub(super) async fn emit_attempt_event_and_log(
         provider_base_url_base,
         provider_index: _,
         session_reuse,
+        protocol_bridge: _,
+        exclude_from_stats: _,
     } = provider_ctx;
     let AttemptCtx {
         attempt_index,
@@ -91,3 +93,37 @@ pub(super) async fn emit_attempt_event_and_log_with_circuit_before(
     )
     .await;
 }
+
+/// Realtime routing UX: announces the selected provider before awaiting upstream.
+///
+/// Not persisted to attempt_logs (avoid DB noise/IO); completion events still are.
+pub(super) fn emit_attempt_started(
+    ctx: CommonCtx<'_>,
+    provider_ctx: ProviderCtx<'_>,
+    attempt_ctx: AttemptCtx<'_>,
+) {
+    let circuit_before = attempt_ctx.circuit_before;
+    emit_attempt_event(
+        &ctx.state.app,
+        GatewayAttemptEvent {
+            trace_id: ctx.trace_id.clone(),
+            cli_key: ctx.cli_key.clone(),
+            method: ctx.method_hint.clone(),
+            path: ctx.forwarded_path.clone(),
+            query: ctx.query.clone(),
+            attempt_index: attempt_ctx.attempt_index,
+            provider_id: provider_ctx.provider_id,
+            session_reuse: provider_ctx.session_reuse,
+            provider_name: provider_ctx.provider_name_base.clone(),
+            base_url: provider_ctx.provider_base_url_base.clone(),
+            outcome: "started".to_string(),
+            status: None,
+            attempt_started_ms: attempt_ctx.attempt_started_ms,
+            attempt_duration_ms: 0,
+            circuit_state_before: Some(circuit_before.state.as_str()),
+            circuit_state_after: None,
+            circuit_failure_count: Some(circuit_before.failure_count),
+            circuit_failure_threshold: Some(circuit_before.failure_threshold),
+        },
+    );
+}
@@ -3,11 +3,13 @@
 use super::super::super::abort_guard::RequestAbortGuard;
 use super::super::super::caches::CachedGatewayError;
 use super::super::super::errors::{error_response, error_response_with_retry_after};
+use super::super::super::RequestLogAttribution;
 use super::{emit_request_event_and_enqueue_request_log, RequestEndArgs, RequestEndDeps};
-use crate::gateway::events::FailoverAttempt;
+use crate::gateway::events::{emit_all_providers_unavailable_notice, FailoverAttempt};
 use crate::gateway::manager::GatewayAppState;
 use crate::gateway::response_fixer;
 use crate::gateway::util::now_unix_seconds;
+use crate::settings;
 use axum::http::StatusCode;
 use axum::response::Response;
 use std::sync::{Arc, Mutex};
@@ -26,11 +28,15 @@ pub(super) struct AllUnavailableInput<'a> {
     pub(super) created_at: i64,
     pub(super) session_id: Option<String>,
     pub(super) requested_model: Option<String>,
+    pub(super) attribution: RequestLogAttribution,
     pub(super) special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
     pub(super) earliest_available_unix: Option<i64>,
     pub(super) skipped_open: usize,
     pub(super) skipped_cooldown: usize,
-    pub(super) fingerprint_key: u64,
+    pub(super) skipped_rate_limited: usize,
+    pub(super) skipped_quota: usize,
+    // `None` for streamed bodies: their fingerprint only covers the buffered prefix.
+    pub(super) fingerprint_key: Option<u64>,
     pub(super) fingerprint_debug: String,
     pub(super) unavailable_fingerprint_key: u64,
     pub(super) unavailable_fingerprint_debug: String,
@@ -50,10 +56,13 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
         created_at,
         session_id,
         requested_model,
+        attribution,
         special_settings,
         earliest_available_unix,
         skipped_open,
         skipped_cooldown,
+        skipped_rate_limited,
+        skipped_quota,
         fingerprint_key,
         fingerprint_debug,
         unavailable_fingerprint_key,
@@ -67,10 +76,11 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
         .map(|v| v as u64);
 
     let message = format!(
-        "no provider available (skipped: open={skipped_open}, cooldown={skipped_cooldown}) for cli_key={cli_key}",
+        "no provider available (skipped: open={skipped_open}, cooldown={skipped_cooldown}, rate_limited={skipped_rate_limited}, quota_exceeded={skipped_quota}) for cli_key={cli_key}",
     );
 
     let resp = error_response_with_retry_after(
+        &cli_key,
         StatusCode::SERVICE_UNAVAILABLE,
         trace_id.clone(),
         "GW_ALL_PROVIDERS_UNAVAILABLE",
@@ -79,6 +89,15 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
         retry_after_seconds,
     );
 
+    emit_all_providers_unavailable_notice(
+        &state.app,
+        trace_id.as_str(),
+        cli_key.as_str(),
+        skipped_open,
+        skipped_cooldown,
+        now_unix,
+    );
+
     let duration_ms = started.elapsed().as_millis();
     emit_request_event_and_enqueue_request_log(RequestEndArgs {
         deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
@@ -98,6 +117,7 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
         special_settings_json: response_fixer::special_settings_json(&special_settings),
         session_id,
         requested_model,
+        attribution,
         created_at_ms,
         created_at,
         usage_metrics: None,
@@ -106,7 +126,11 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
     })
     .await;
 
-    if let Some(retry_after_seconds) = retry_after_seconds.filter(|v| *v > 0) {
+    let error_max_ttl_seconds = settings::read(&state.app)
+        .map(|cfg| cfg.recent_error_cache.error_max_ttl_seconds)
+        .unwrap_or_else(|_| settings::RecentErrorCacheConfig::default().error_max_ttl_seconds);
+    let cache_ttl_seconds = retry_after_seconds.map(|v| v.min(error_max_ttl_seconds as u64));
+    if let Some(cache_ttl_seconds) = cache_ttl_seconds.filter(|v| *v > 0) {
         if let Ok(mut cache) = state.recent_errors.lock() {
             cache.insert_error(
                 now_unix,
@@ -116,24 +140,28 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
                     status: StatusCode::SERVICE_UNAVAILABLE,
                     error_code: "GW_ALL_PROVIDERS_UNAVAILABLE",
                     message: message.clone(),
-                    retry_after_seconds: Some(retry_after_seconds),
-                    expires_at_unix: now_unix.saturating_add(retry_after_seconds as i64),
+                    retry_after_seconds,
+                    expires_at_unix: now_unix.saturating_add(cache_ttl_seconds as i64),
                     fingerprint_debug: unavailable_fingerprint_debug.clone(),
+                    request_started_at_ms: created_at_ms,
                 },
             );
-            cache.insert_error(
-                now_unix,
-                fingerprint_key,
-                CachedGatewayError {
-                    trace_id: trace_id.clone(),
-                    status: StatusCode::SERVICE_UNAVAILABLE,
-                    error_code: "GW_ALL_PROVIDERS_UNAVAILABLE",
-                    message,
-                    retry_after_seconds: Some(retry_after_seconds),
-                    expires_at_unix: now_unix.saturating_add(retry_after_seconds as i64),
-                    fingerprint_debug: fingerprint_debug.clone(),
-                },
-            );
+            if let Some(fingerprint_key) = fingerprint_key {
+                cache.insert_error(
+                    now_unix,
+                    fingerprint_key,
+                    CachedGatewayError {
+                        trace_id: trace_id.clone(),
+                        status: StatusCode::SERVICE_UNAVAILABLE,
+                        error_code: "GW_ALL_PROVIDERS_UNAVAILABLE",
+                        message,
+                        retry_after_seconds,
+                        expires_at_unix: now_unix.saturating_add(cache_ttl_seconds as i64),
+                        fingerprint_debug: fingerprint_debug.clone(),
+                        request_started_at_ms: created_at_ms,
+                    },
+                );
+            }
         }
     }
 
@@ -157,6 +185,7 @@ pub(super) struct AllFailedInput<'a> {
     pub(super) created_at: i64,
     pub(super) session_id: Option<String>,
     pub(super) requested_model: Option<String>,
+    pub(super) attribution: RequestLogAttribution,
     pub(super) special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
 }
 
@@ -177,16 +206,31 @@ pub(super) async fn all_providers_failed(input: AllFailedInput<'_>) -> Response
         created_at,
         session_id,
         requested_model,
+        attribution,
         special_settings,
     } = input;
 
     let final_error_code = last_error_code.unwrap_or("GW_UPSTREAM_ALL_FAILED");
 
+    let mut message = format!("all providers failed for cli_key={cli_key}");
+    // 附上最后一次尝试的结果，CLI 展示原生错误时能看到真实原因
+    if let Some(last) = attempts.last() {
+        message.push_str(&format!(" (last: provider={}", last.provider_name));
+        if let Some(status) = last.status {
+            message.push_str(&format!(" status={status}"));
+        }
+        if let Some(reason) = last.reason.as_deref().filter(|v| !v.is_empty()) {
+            message.push_str(&format!(" reason={reason}"));
+        }
+        message.push(')');
+    }
+
     let resp = error_response(
+        &cli_key,
         StatusCode::BAD_GATEWAY,
         trace_id.clone(),
         final_error_code,
-        format!("all providers failed for cli_key={cli_key}"),
+        message,
         attempts.clone(),
     );
 
@@ -209,6 +253,7 @@ pub(super) async fn all_providers_failed(input: AllFailedInput<'_>) -> Response
         special_settings_json: response_fixer::special_settings_json(&special_settings),
         session_id,
         requested_model,
+        attribution,
         created_at_ms,
         created_at,
         usage_metrics: None,
@@ -0,0 +1,664 @@
+//! Usage: Hedged requests (race the next provider when the first one is slow to respond).
+//!
+//! 用法：
+//! - 开启 `hedged_requests` 后，首个 Provider 的首次尝试在 `delay_ms` 内没有返回响应头时，向下一个可用 Provider 并行发起同一请求
+//! - 先返回成功响应的一方胜出，另一方被取消（记为 `hedge_cancelled` 尝试）
+//! - 对冲方先失败：记为 `hedge_failed` 并继续等待首个 Provider；首个 Provider 先失败：继续等待对冲方，对冲方成功则采用其响应
+//!   （首个 Provider 记为 `hedge_primary_failed`），否则仍按首个 Provider 的失败走正常故障转移
+//! - 失败的一方与普通失败尝试一样计入熔断器（Provider 错误）并触发冷却
+//! - 仅对已缓冲的请求体生效（流式转发的请求体只能发送一次），且需要最大尝试 Provider 数 ≥ 2
+//! - 两次尝试都写入 request_attempt_logs；对冲结果写入 `special_settings_json`（type = `hedged_request`）
+
+use super::super::super::circuit_failures;
+use super::super::super::errors::{classify_reqwest_error, classify_upstream_status};
+use super::super::super::failover::{select_provider_base_url_for_request, FailoverDecision};
+use super::super::super::protocol_bridge::ProtocolBridge;
+use super::super::super::provider_router;
+use super::super::super::request_context::RequestContext;
+use super::super::super::ErrorCategory;
+use super::context::{AttemptCtx, CommonCtx, ProviderCtx};
+use super::event_helpers::{
+    emit_attempt_event_and_log, emit_attempt_event_and_log_with_circuit_before,
+    emit_attempt_started, AttemptCircuitFields,
+};
+use super::upstream_request::RequestSource;
+use super::{ctx_for_provider, provider_gate, provider_name_base, send, upstream_request};
+use crate::gateway::active_requests::ActiveRequest;
+use crate::gateway::events::FailoverAttempt;
+use crate::gateway::util::{build_target_url, now_unix_seconds};
+use crate::{circuit_breaker, providers, settings};
+use axum::body::Bytes;
+use axum::http::{HeaderMap, Method};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// What the race needs from the request, borrowed field by field so the race future stays `Send`
+/// (see `RequestSource`).
+#[derive(Clone, Copy)]
+struct HedgeRequest<'a> {
+    // Request-level context (without per-provider overrides).
+    ctx: CommonCtx<'a>,
+    source: RequestSource<'a>,
+    providers: &'a [providers::ProviderForGateway],
+    provider_base_url_ping_cache_ttl_seconds: u32,
+    egress_network: &'a settings::EgressNetworkConfig,
+    active_request: &'a ActiveRequest,
+}
+
+pub(super) struct HedgeInput<'a> {
+    request: HedgeRequest<'a>,
+    method: Method,
+    primary_provider_ctx: ProviderCtx<'a>,
+    primary_attempt_ctx: AttemptCtx<'a>,
+    // Position of the primary provider in `providers`; hedge candidates come after it.
+    primary_pos: usize,
+    delay: Duration,
+    quota_exceeded: &'a HashMap<i64, i64>,
+    failed_provider_ids: &'a mut HashSet<i64>,
+    attempts: &'a mut Vec<FailoverAttempt>,
+}
+
+impl<'a> HedgeInput<'a> {
+    /// `None` when the request body is streamed: it can only be sent to one upstream.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn new(
+        ctx: CommonCtx<'a>,
+        input: &'a RequestContext,
+        streamed_body: bool,
+        method: Method,
+        primary_provider_ctx: ProviderCtx<'a>,
+        primary_attempt_ctx: AttemptCtx<'a>,
+        primary_pos: usize,
+        delay: Duration,
+        quota_exceeded: &'a HashMap<i64, i64>,
+        failed_provider_ids: &'a mut HashSet<i64>,
+        attempts: &'a mut Vec<FailoverAttempt>,
+    ) -> Option<Self> {
+        if streamed_body {
+            return None;
+        }
+        Some(Self {
+            request: HedgeRequest {
+                ctx,
+                source: RequestSource::new(input),
+                providers: &input.providers,
+                provider_base_url_ping_cache_ttl_seconds: input
+                    .provider_base_url_ping_cache_ttl_seconds,
+                egress_network: &input.egress_network,
+                active_request: &input.active_request,
+            },
+            method,
+            primary_provider_ctx,
+            primary_attempt_ctx,
+            primary_pos,
+            delay,
+            quota_exceeded,
+            failed_provider_ids,
+            attempts,
+        })
+    }
+}
+
+/// The hedge attempt (owned: it outlives the borrows of the race).
+pub(super) struct HedgeAttempt {
+    pub(super) provider_pos: usize,
+    provider_id: i64,
+    provider_name_base: String,
+    provider_base_url_base: String,
+    provider_index: u32,
+    protocol_bridge: Option<ProtocolBridge>,
+    exclude_from_stats: bool,
+    attempt_index: u32,
+    attempt_started_ms: u128,
+    attempt_started: Instant,
+    pub(super) circuit_before: circuit_breaker::CircuitSnapshot,
+}
+
+impl HedgeAttempt {
+    pub(super) fn provider_ctx(&self) -> ProviderCtx<'_> {
+        ProviderCtx {
+            provider_id: self.provider_id,
+            provider_name_base: &self.provider_name_base,
+            provider_base_url_base: &self.provider_base_url_base,
+            provider_index: self.provider_index,
+            session_reuse: None,
+            protocol_bridge: self.protocol_bridge,
+            exclude_from_stats: self.exclude_from_stats,
+        }
+    }
+
+    pub(super) fn attempt_ctx(&self) -> AttemptCtx<'_> {
+        AttemptCtx {
+            attempt_index: self.attempt_index,
+            retry_index: 1,
+            attempt_started_ms: self.attempt_started_ms,
+            attempt_started: self.attempt_started,
+            circuit_before: &self.circuit_before,
+        }
+    }
+}
+
+pub(super) enum HedgedSend {
+    // The primary's own result (no hedge started, the primary succeeded first, or both failed).
+    Primary(send::SendResult),
+    // The hedge succeeded first, or after the primary failed; the primary is already recorded.
+    Hedge {
+        attempt: HedgeAttempt,
+        resp: reqwest::Response,
+    },
+}
+
+struct HedgeTarget {
+    attempt: HedgeAttempt,
+    client: reqwest::Client,
+    url: reqwest::Url,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+/// Awaits `primary`; once it is slower than the hedge delay, races it against the next provider.
+pub(super) async fn send_hedged(
+    hedge: HedgeInput<'_>,
+    primary: impl Future<Output = send::SendResult>,
+) -> HedgedSend {
+    let HedgeInput {
+        request,
+        method,
+        primary_provider_ctx,
+        primary_attempt_ctx,
+        primary_pos,
+        delay,
+        quota_exceeded,
+        failed_provider_ids,
+        attempts,
+    } = hedge;
+
+    tokio::pin!(primary);
+    tokio::select! {
+        result = &mut primary => return HedgedSend::Primary(result),
+        _ = tokio::time::sleep(delay) => {}
+    }
+
+    let ctx = request.ctx;
+    let Some(target) = start_hedge(
+        request,
+        primary_provider_ctx,
+        primary_attempt_ctx,
+        primary_pos,
+        quota_exceeded,
+        failed_provider_ids,
+    )
+    .await
+    else {
+        return HedgedSend::Primary(primary.await);
+    };
+
+    let HedgeTarget {
+        attempt,
+        client,
+        url,
+        headers,
+        body,
+    } = target;
+    let hedge_provider = &request.providers[attempt.provider_pos];
+    let hedge_ctx = ctx_for_provider(ctx, hedge_provider);
+    let hedge_send = send::send_upstream(
+        hedge_ctx,
+        &client,
+        method,
+        url,
+        headers,
+        reqwest::Body::from(body),
+    );
+    tokio::pin!(hedge_send);
+
+    let primary_result = tokio::select! {
+        biased;
+        result = &mut primary => result,
+        result = &mut hedge_send => {
+            let Some(failure) = HedgeFailure::of(&result) else {
+                let send::SendResult::Ok(resp) = result else {
+                    unreachable!("HedgeFailure::of only passes success responses")
+                };
+                note_hedge(ctx, primary_provider_ctx.provider_id, attempt.provider_id, "hedge");
+                record_attempt(
+                    ctx,
+                    primary_provider_ctx,
+                    primary_attempt_ctx,
+                    attempts,
+                    format!("hedge_cancelled: winner_provider_id={}", attempt.provider_id),
+                    None,
+                    None,
+                    "hedged request: another provider responded first".to_string(),
+                )
+                .await;
+                return HedgedSend::Hedge { attempt, resp };
+            };
+            record_failure(
+                ctx,
+                attempt.provider_ctx(),
+                attempt.attempt_ctx(),
+                attempts,
+                failure,
+                "hedge_failed",
+                "hedged request failed",
+            )
+            .await;
+            // A failed hedge doesn't win: wait for the primary, and don't fail over to it again.
+            failed_provider_ids.insert(attempt.provider_id);
+            note_hedge(ctx, primary_provider_ctx.provider_id, attempt.provider_id, "primary");
+            return HedgedSend::Primary(primary.await);
+        }
+    };
+
+    if HedgeFailure::of(&primary_result).is_none() {
+        note_hedge(
+            ctx,
+            primary_provider_ctx.provider_id,
+            attempt.provider_id,
+            "primary",
+        );
+        record_attempt(
+            ctx,
+            attempt.provider_ctx(),
+            attempt.attempt_ctx(),
+            attempts,
+            format!(
+                "hedge_cancelled: winner_provider_id={}",
+                primary_provider_ctx.provider_id
+            ),
+            None,
+            None,
+            "hedged request: another provider responded first".to_string(),
+        )
+        .await;
+        return HedgedSend::Primary(primary_result);
+    }
+
+    // The primary failed while the hedge is in flight: the hedge may still answer.
+    let hedge_result = hedge_send.await;
+    match HedgeFailure::of(&hedge_result) {
+        None => {
+            if let Some(failure) = HedgeFailure::of(&primary_result) {
+                record_failure(
+                    ctx,
+                    primary_provider_ctx,
+                    primary_attempt_ctx,
+                    attempts,
+                    failure,
+                    "hedge_primary_failed",
+                    "hedged request: primary failed, hedge answered",
+                )
+                .await;
+            }
+            let send::SendResult::Ok(resp) = hedge_result else {
+                unreachable!("HedgeFailure::of only passes success responses")
+            };
+            note_hedge(
+                ctx,
+                primary_provider_ctx.provider_id,
+                attempt.provider_id,
+                "hedge",
+            );
+            HedgedSend::Hedge { attempt, resp }
+        }
+        Some(failure) => {
+            record_failure(
+                ctx,
+                attempt.provider_ctx(),
+                attempt.attempt_ctx(),
+                attempts,
+                failure,
+                "hedge_failed",
+                "hedged request failed",
+            )
+            .await;
+            failed_provider_ids.insert(attempt.provider_id);
+            note_hedge(
+                ctx,
+                primary_provider_ctx.provider_id,
+                attempt.provider_id,
+                "primary",
+            );
+            // Both failed: the primary's failure goes through the normal failover handling.
+            HedgedSend::Primary(primary_result)
+        }
+    }
+}
+
+async fn start_hedge(
+    request: HedgeRequest<'_>,
+    primary_provider_ctx: ProviderCtx<'_>,
+    primary_attempt_ctx: AttemptCtx<'_>,
+    primary_pos: usize,
+    quota_exceeded: &HashMap<i64, i64>,
+    failed_provider_ids: &HashSet<i64>,
+) -> Option<HedgeTarget> {
+    let HedgeRequest {
+        ctx,
+        source,
+        providers,
+        provider_base_url_ping_cache_ttl_seconds,
+        egress_network,
+        active_request,
+    } = request;
+    // Gate bookkeeping for skipped candidates belongs to the main loop; discard it here.
+    let mut earliest_available_unix = None;
+    let (mut skipped_open, mut skipped_cooldown) = (0usize, 0usize);
+    let (mut skipped_rate_limited, mut skipped_quota) = (0usize, 0usize);
+
+    for (provider_pos, provider) in providers.iter().enumerate().skip(primary_pos + 1) {
+        let provider_id = provider.id;
+        if failed_provider_ids.contains(&provider_id) {
+            continue;
+        }
+        let provider_name_base = provider_name_base(provider);
+        let provider_base_url_display = provider.base_urls.first().cloned().unwrap_or_default();
+        let Some(gate_allow) = provider_gate::gate_provider(provider_gate::ProviderGateInput {
+            ctx,
+            provider_id,
+            provider_name_base: &provider_name_base,
+            provider_base_url_display: &provider_base_url_display,
+            rpm_limit: provider.rpm_limit,
+            tpm_limit: provider.tpm_limit,
+            quota_exceeded_until: quota_exceeded.get(&provider_id).copied(),
+            earliest_available_unix: &mut earliest_available_unix,
+            skipped_open: &mut skipped_open,
+            skipped_cooldown: &mut skipped_cooldown,
+            skipped_rate_limited: &mut skipped_rate_limited,
+            skipped_quota: &mut skipped_quota,
+        }) else {
+            continue;
+        };
+
+        let provider_base_url_base = select_provider_base_url_for_request(
+            ctx.state,
+            provider,
+            provider_base_url_ping_cache_ttl_seconds,
+        )
+        .await;
+        let client = ctx.state.upstream_clients.for_provider(
+            egress_network,
+            &provider.upstream_proxy,
+            &provider_base_url_base,
+            &provider.upstream_tls,
+        );
+
+        let mut provider_ctx = ProviderCtx {
+            provider_id,
+            provider_name_base: &provider_name_base,
+            provider_base_url_base: &provider_base_url_base,
+            provider_index: primary_provider_ctx.provider_index.saturating_add(1),
+            session_reuse: None,
+            protocol_bridge: ProtocolBridge::for_request(
+                ctx.cli_key,
+                ctx.forwarded_path,
+                provider.wire_api,
+            )
+            .filter(|_| !provider.passthrough),
+            exclude_from_stats: provider.exclude_from_stats,
+        };
+        let provider_common_ctx = ctx_for_provider(ctx, provider);
+        let upstream =
+            upstream_request::prepare(provider_common_ctx, source, provider, &mut provider_ctx);
+        let Ok(url) = build_target_url(
+            &provider_base_url_base,
+            &upstream.forwarded_path,
+            upstream.query.as_deref(),
+        ) else {
+            continue;
+        };
+        let headers = upstream_request::attempt_headers(
+            provider_common_ctx,
+            provider,
+            provider_ctx,
+            &upstream,
+            Some(upstream.body_bytes.as_ref()),
+            true,
+        );
+
+        let attempt = HedgeAttempt {
+            provider_pos,
+            provider_id,
+            provider_index: provider_ctx.provider_index,
+            protocol_bridge: provider_ctx.protocol_bridge,
+            exclude_from_stats: provider.exclude_from_stats,
+            attempt_index: primary_attempt_ctx.attempt_index.saturating_add(1),
+            attempt_started_ms: ctx.started.elapsed().as_millis(),
+            attempt_started: Instant::now(),
+            circuit_before: gate_allow.circuit_after,
+            provider_name_base,
+            provider_base_url_base,
+        };
+        active_request.begin_attempt(
+            attempt.attempt_index,
+            provider_id,
+            &attempt.provider_name_base,
+        );
+        emit_attempt_started(ctx, attempt.provider_ctx(), attempt.attempt_ctx());
+
+        return Some(HedgeTarget {
+            attempt,
+            client,
+            url,
+            headers,
+            body: upstream.body_bytes,
+        });
+    }
+    None
+}
+
+enum HedgeFailure<'r> {
+    Status(reqwest::StatusCode),
+    Error(&'r reqwest::Error),
+    Timeout,
+}
+
+impl<'r> HedgeFailure<'r> {
+    /// `None` for a success response.
+    fn of(result: &'r send::SendResult) -> Option<Self> {
+        match result {
+            send::SendResult::Ok(resp) if resp.status().is_success() => None,
+            send::SendResult::Ok(resp) => Some(Self::Status(resp.status())),
+            send::SendResult::Err(err) => Some(Self::Error(err)),
+            send::SendResult::Timeout => Some(Self::Timeout),
+        }
+    }
+}
+
+/// Records the failed side of the race like a failed attempt of the main loop: provider errors
+/// count against the circuit breaker, and the provider cools down.
+async fn record_failure(
+    ctx: CommonCtx<'_>,
+    provider_ctx: ProviderCtx<'_>,
+    attempt_ctx: AttemptCtx<'_>,
+    attempts: &mut Vec<FailoverAttempt>,
+    failure: HedgeFailure<'_>,
+    label: &'static str,
+    reason: &str,
+) {
+    // A hedge has no retry budget of its own: a retryable failure switches provider.
+    let (status, category, error_code, decision, detail) = match failure {
+        HedgeFailure::Status(status) => {
+            let (category, error_code, decision) = classify_upstream_status(status);
+            let decision = match decision {
+                FailoverDecision::RetrySameProvider => FailoverDecision::SwitchProvider,
+                other => other,
+            };
+            let detail = format!(
+                "status={} category={} code={}",
+                status.as_u16(),
+                category.as_str(),
+                error_code,
+            );
+            (
+                Some(status.as_u16()),
+                category,
+                error_code,
+                decision,
+                detail,
+            )
+        }
+        HedgeFailure::Error(err) => {
+            let (category, error_code) = classify_reqwest_error(err);
+            let detail = format!(
+                "category={} code={} err={err}",
+                category.as_str(),
+                error_code,
+            );
+            (
+                None,
+                category,
+                error_code,
+                FailoverDecision::SwitchProvider,
+                detail,
+            )
+        }
+        HedgeFailure::Timeout => {
+            let category = ErrorCategory::SystemError;
+            let error_code = "GW_UPSTREAM_TIMEOUT";
+            let detail = format!(
+                "category={} code={} timeout_secs={}",
+                category.as_str(),
+                error_code,
+                ctx.upstream_first_byte_timeout_secs,
+            );
+            (
+                None,
+                category,
+                error_code,
+                FailoverDecision::SwitchProvider,
+                detail,
+            )
+        }
+    };
+    let outcome = format!("{label}: {detail} decision={}", decision.as_str());
+
+    let provider_id = provider_ctx.provider_id;
+    let circuit_before = attempt_ctx.circuit_before;
+    let mut circuit = AttemptCircuitFields {
+        state_before: Some(circuit_before.state.as_str()),
+        state_after: None,
+        failure_count: Some(circuit_before.failure_count),
+        failure_threshold: Some(circuit_before.failure_threshold),
+    };
+    let now_unix = now_unix_seconds() as i64;
+    if matches!(category, ErrorCategory::ProviderError) {
+        circuit_failures::record(
+            provider_id,
+            circuit_failures::FailureSample {
+                ts: now_unix,
+                error_code,
+                status,
+                model: ctx.requested_model.clone(),
+                summary: None,
+            },
+        );
+        let change = provider_router::record_failure_and_emit_transition(
+            provider_router::RecordCircuitArgs::from_state(
+                ctx.state,
+                ctx.trace_id.as_str(),
+                ctx.cli_key.as_str(),
+                provider_id,
+                provider_ctx.provider_name_base.as_str(),
+                provider_ctx.provider_base_url_base.as_str(),
+                now_unix,
+            ),
+        );
+        circuit.state_before = Some(change.before.state.as_str());
+        circuit.state_after = Some(change.after.state.as_str());
+        circuit.failure_count = Some(change.after.failure_count);
+    }
+    if ctx.provider_cooldown_secs > 0
+        && matches!(
+            decision,
+            FailoverDecision::SwitchProvider | FailoverDecision::Abort
+        )
+    {
+        provider_router::trigger_cooldown(
+            ctx.state.circuit.as_ref(),
+            provider_id,
+            now_unix,
+            ctx.provider_cooldown_secs,
+        );
+    }
+
+    attempts.push(FailoverAttempt {
+        provider_id,
+        provider_name: provider_ctx.provider_name_base.clone(),
+        base_url: provider_ctx.provider_base_url_base.clone(),
+        outcome: outcome.clone(),
+        status,
+        provider_index: Some(provider_ctx.provider_index),
+        retry_index: Some(attempt_ctx.retry_index),
+        session_reuse: provider_ctx.session_reuse,
+        error_category: Some(category.as_str()),
+        error_code: Some(error_code),
+        decision: Some(decision.as_str()),
+        reason: Some(reason.to_string()),
+        attempt_started_ms: Some(attempt_ctx.attempt_started_ms),
+        attempt_duration_ms: Some(attempt_ctx.attempt_started.elapsed().as_millis()),
+        circuit_state_before: circuit.state_before,
+        circuit_state_after: circuit.state_after,
+        circuit_failure_count: circuit.failure_count,
+        circuit_failure_threshold: circuit.failure_threshold,
+    });
+
+    emit_attempt_event_and_log(ctx, provider_ctx, attempt_ctx, outcome, status, circuit).await;
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn record_attempt(
+    ctx: CommonCtx<'_>,
+    provider_ctx: ProviderCtx<'_>,
+    attempt_ctx: AttemptCtx<'_>,
+    attempts: &mut Vec<FailoverAttempt>,
+    outcome: String,
+    status: Option<u16>,
+    error: Option<(&'static str, &'static str)>,
+    reason: String,
+) {
+    let circuit_before = attempt_ctx.circuit_before;
+    attempts.push(FailoverAttempt {
+        provider_id: provider_ctx.provider_id,
+        provider_name: provider_ctx.provider_name_base.clone(),
+        base_url: provider_ctx.provider_base_url_base.clone(),
+        outcome: outcome.clone(),
+        status,
+        provider_index: Some(provider_ctx.provider_index),
+        retry_index: Some(attempt_ctx.retry_index),
+        session_reuse: provider_ctx.session_reuse,
+        error_category: error.map(|(category, _)| category),
+        error_code: error.map(|(_, code)| code),
+        decision: None,
+        reason: Some(reason),
+        attempt_started_ms: Some(attempt_ctx.attempt_started_ms),
+        attempt_duration_ms: Some(attempt_ctx.attempt_started.elapsed().as_millis()),
+        circuit_state_before: Some(circuit_before.state.as_str()),
+        circuit_state_after: None,
+        circuit_failure_count: Some(circuit_before.failure_count),
+        circuit_failure_threshold: Some(circuit_before.failure_threshold),
+    });
+
+    emit_attempt_event_and_log_with_circuit_before(ctx, provider_ctx, attempt_ctx, outcome, status)
+        .await;
+}
+
+fn note_hedge(
+    ctx: CommonCtx<'_>,
+    primary_provider_id: i64,
+    hedge_provider_id: i64,
+    winner: &'static str,
+) {
+    if let Ok(mut settings) = ctx.special_settings.lock() {
+        settings.push(serde_json::json!({
+            "type": "hedged_request",
+            "scope": "request",
+            "hit": winner == "hedge",
+            "primaryProviderId": primary_provider_id,
+            "hedgeProviderId": hedge_provider_id,
+            "winner": winner,
+        }));
+    }
+}
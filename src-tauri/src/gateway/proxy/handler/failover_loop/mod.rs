@@ -1,19 +1,28 @@
 //! Usage: Gateway proxy failover loop (provider iteration + retries + upstream response handling).
 
 mod attempt_record;
+mod body_patch;
 mod claude_model_mapping;
+mod codex_response_storage;
 mod context;
+mod context_trim;
 mod event_helpers;
 mod finalize;
+mod hedge;
 mod provider_gate;
 mod request_end_helpers;
+mod request_signing;
+mod response_cache_hit;
 mod send;
 mod send_timeout;
 mod success_event_stream;
 mod success_non_stream;
 mod thinking_signature_rectifier_400;
 mod upstream_error;
+mod upstream_request;
+mod wire_api_bridge;
 
+use super::super::protocol_bridge::{ProtocolBridge, ProtocolBridgeStream};
 use super::super::request_context::RequestContext;
 use attempt_record::{
     record_system_failure_and_decide, record_system_failure_and_decide_no_cooldown,
@@ -21,7 +30,7 @@ use attempt_record::{
 };
 use event_helpers::{
     emit_attempt_event_and_log, emit_attempt_event_and_log_with_circuit_before,
-    AttemptCircuitFields,
+    emit_attempt_started, AttemptCircuitFields,
 };
 use request_end_helpers::{
     emit_request_event_and_enqueue_request_log, RequestEndArgs, RequestEndDeps,
@@ -34,7 +43,7 @@ use super::super::{
         build_response, has_gzip_content_encoding, has_non_identity_content_encoding,
         is_event_stream, maybe_gunzip_response_body_bytes_with_limit,
     },
-    ErrorCategory,
+    provider_quota, ErrorCategory, RequestLogAttribution,
 };
 
 use crate::usage;
@@ -47,16 +56,15 @@ use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
-use crate::gateway::events::{emit_attempt_event, FailoverAttempt, GatewayAttemptEvent};
+use crate::gateway::events::FailoverAttempt;
 use crate::gateway::response_fixer;
 use crate::gateway::streams::{
-    spawn_usage_sse_relay_body, FirstChunkStream, GunzipStream, TimingOnlyTeeStream,
-    UsageBodyBufferTeeStream, UsageSseTeeStream,
+    spawn_usage_sse_relay_body, FirstChunkStream, GunzipStream, StreamIdleTimeouts,
+    TimingOnlyTeeStream, UsageBodyBufferTeeStream, UsageSseTeeStream,
 };
 use crate::gateway::thinking_signature_rectifier;
 use crate::gateway::util::{
-    body_for_introspection, build_target_url, ensure_cli_required_headers, inject_provider_auth,
-    now_unix_seconds, strip_hop_headers,
+    body_for_introspection, build_target_url, now_unix_seconds, strip_hop_headers,
 };
 
 use context::{
@@ -72,6 +80,7 @@ struct FinalizeOwnedCommon {
     trace_id: String,
     session_id: Option<String>,
     requested_model: Option<String>,
+    attribution: RequestLogAttribution,
     special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
 }
 
@@ -84,10 +93,64 @@ fn finalize_owned_from_input(input: &RequestContext) -> FinalizeOwnedCommon {
         trace_id: input.trace_id.clone(),
         session_id: input.session_id.clone(),
         requested_model: input.requested_model.clone(),
+        attribution: input.attribution.clone(),
         special_settings: input.special_settings.clone(),
     }
 }
 
+fn provider_name_base(provider: &crate::providers::ProviderForGateway) -> String {
+    if provider.name.trim().is_empty() {
+        format!("Provider #{} (auto-fixed)", provider.id)
+    } else {
+        provider.name.clone()
+    }
+}
+
+/// Request context with the provider's response-fixer overrides (off for passthrough providers).
+fn ctx_for_provider<'a>(
+    ctx: CommonCtx<'a>,
+    provider: &'a crate::providers::ProviderForGateway,
+) -> CommonCtx<'a> {
+    if provider.passthrough {
+        ctx.without_response_fixer()
+    } else {
+        ctx.with_provider_response_fixer(&provider.response_fixer)
+    }
+}
+
+async fn handle_success_response(
+    ctx: CommonCtx<'_>,
+    provider_ctx: ProviderCtx<'_>,
+    attempt_ctx: AttemptCtx<'_>,
+    loop_state: LoopState<'_>,
+    resp: reqwest::Response,
+) -> LoopControl {
+    let status = resp.status();
+    let response_headers = resp.headers().clone();
+    if is_event_stream(&response_headers) {
+        return success_event_stream::handle_success_event_stream(
+            ctx,
+            provider_ctx,
+            attempt_ctx,
+            loop_state,
+            resp,
+            status,
+            response_headers,
+        )
+        .await;
+    }
+    success_non_stream::handle_success_non_stream(
+        ctx,
+        provider_ctx,
+        attempt_ctx,
+        loop_state,
+        resp,
+        status,
+        response_headers,
+    )
+    .await
+}
+
 pub(super) async fn run(mut input: RequestContext) -> Response {
     let method = input.req_method.clone();
     let started = input.started;
@@ -107,17 +170,26 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
         created_at,
         session_id: &input.session_id,
         requested_model: &input.requested_model,
+        attribution: &input.attribution,
         effective_sort_mode_id: input.effective_sort_mode_id,
+        prefix_affinity_key: input.prefix_affinity_key,
         special_settings: &input.special_settings,
         provider_cooldown_secs: input.provider_cooldown_secs,
         upstream_first_byte_timeout_secs: input.upstream_first_byte_timeout_secs,
         upstream_first_byte_timeout: input.upstream_first_byte_timeout,
         upstream_stream_idle_timeout: input.upstream_stream_idle_timeout,
+        stream_tool_pause_idle_timeout: input.stream_tool_pause_idle_timeout,
         upstream_request_timeout_non_streaming: input.upstream_request_timeout_non_streaming,
+        response_cache_ttl: input.response_cache_ttl,
+        fingerprint_key: input.fingerprint_key,
+        fingerprint_debug: &input.fingerprint_debug,
         max_attempts_per_provider: input.max_attempts_per_provider,
         enable_response_fixer: input.enable_response_fixer,
         response_fixer_stream_config: input.response_fixer_stream_config,
         response_fixer_non_stream_config: input.response_fixer_non_stream_config,
+        retry_backoff: &input.retry_backoff,
+        failover_rules: &input.failover_rules,
+        sse_capture: input.sse_capture,
         introspection_body: introspection_body.as_ref(),
     });
     let mut attempts: Vec<FailoverAttempt> = Vec::new();
@@ -130,18 +202,20 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
     let mut earliest_available_unix: Option<i64> = None;
     let mut skipped_open: usize = 0;
     let mut skipped_cooldown: usize = 0;
-
-    for provider in input.providers.iter() {
+    let mut skipped_rate_limited: usize = 0;
+    let mut skipped_quota: usize = 0;
+    let quota_exceeded =
+        provider_quota::exceeded_providers(&input.state.db, &input.cli_key, &input.providers);
+    let mut streaming_body = input.streaming_body.take();
+    let body_fingerprinted = streaming_body.is_none();
+
+    for (provider_pos, provider) in input.providers.iter().enumerate() {
         if providers_tried >= max_providers_to_try {
             break;
         }
 
         let provider_id = provider.id;
-        let provider_name_base = if provider.name.trim().is_empty() {
-            format!("Provider #{} (auto-fixed)", provider.id)
-        } else {
-            provider.name.clone()
-        };
+        let provider_name_base = provider_name_base(provider);
         let provider_base_url_display = provider
             .base_urls
             .first()
@@ -152,14 +226,30 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
             continue;
         }
 
+        if let Some(resp) = response_cache_hit::try_serve(
+            ctx,
+            provider,
+            &provider_name_base,
+            &mut input.abort_guard,
+        )
+        .await
+        {
+            return resp;
+        }
+
         let Some(gate_allow) = provider_gate::gate_provider(provider_gate::ProviderGateInput {
             ctx,
             provider_id,
             provider_name_base: &provider_name_base,
             provider_base_url_display: &provider_base_url_display,
+            rpm_limit: provider.rpm_limit,
+            tpm_limit: provider.tpm_limit,
+            quota_exceeded_until: quota_exceeded.get(&provider_id).copied(),
             earliest_available_unix: &mut earliest_available_unix,
             skipped_open: &mut skipped_open,
             skipped_cooldown: &mut skipped_cooldown,
+            skipped_rate_limited: &mut skipped_rate_limited,
+            skipped_quota: &mut skipped_quota,
         }) else {
             continue;
         };
@@ -172,6 +262,12 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
             input.provider_base_url_ping_cache_ttl_seconds,
         )
         .await;
+        let upstream_client = input.state.upstream_clients.for_provider(
+            &input.egress_network,
+            &provider.upstream_proxy,
+            &provider_base_url_base,
+            &provider.upstream_tls,
+        );
 
         let mut circuit_snapshot = gate_allow.circuit_after;
 
@@ -181,33 +277,30 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
             Some(id) => (id == provider_id && provider_index == 1).then_some(true),
             None => None,
         };
-        let provider_ctx = ProviderCtx {
+        let mut provider_ctx = ProviderCtx {
             provider_id,
             provider_name_base: &provider_name_base,
             provider_base_url_base: &provider_base_url_base,
             provider_index,
             session_reuse,
+            protocol_bridge: ProtocolBridge::for_request(
+                &input.cli_key,
+                &input.forwarded_path,
+                provider.wire_api,
+            )
+            .filter(|_| !provider.passthrough),
+            exclude_from_stats: provider.exclude_from_stats,
         };
+        let base_ctx = ctx;
+        let ctx = ctx_for_provider(ctx, provider);
 
-        let mut upstream_forwarded_path = input.forwarded_path.clone();
-        let mut upstream_query = input.query.clone();
-        let mut upstream_body_bytes = input.body_bytes.clone();
-        let mut strip_request_content_encoding = input.strip_request_content_encoding_seed;
-        let mut thinking_signature_rectifier_retried = false;
-
-        claude_model_mapping::apply_if_needed(
+        let mut upstream = upstream_request::prepare(
             ctx,
+            upstream_request::RequestSource::new(&input),
             provider,
-            provider_ctx,
-            input.requested_model_location,
-            input.introspection_json.as_ref(),
-            claude_model_mapping::UpstreamRequestMut {
-                forwarded_path: &mut upstream_forwarded_path,
-                query: &mut upstream_query,
-                body_bytes: &mut upstream_body_bytes,
-                strip_request_content_encoding: &mut strip_request_content_encoding,
-            },
+            &mut provider_ctx,
         );
+        let mut thinking_signature_rectifier_retried = false;
 
         for retry_index in 1..=input.max_attempts_per_provider {
             let attempt_index = attempts.len().saturating_add(1) as u32;
@@ -224,8 +317,8 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
 
             let url = match build_target_url(
                 &provider_base_url_base,
-                &upstream_forwarded_path,
-                upstream_query.as_deref(),
+                &upstream.forwarded_path,
+                upstream.query.as_deref(),
             ) {
                 Ok(u) => u,
                 Err(err) => {
@@ -267,88 +360,95 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                 }
             };
 
-            // Realtime routing UX: emit an attempt event as soon as a provider is selected (before awaiting upstream).
-            // This enables the Home page to display the current routed provider immediately, similar to claude-code-hub.
-            //
-            // Note: do NOT enqueue attempt_logs for this "started" event (avoid DB noise/IO); completion events still get persisted.
-            emit_attempt_event(
-                &input.state.app,
-                GatewayAttemptEvent {
-                    trace_id: input.trace_id.clone(),
-                    cli_key: input.cli_key.clone(),
-                    method: input.method_hint.clone(),
-                    path: input.forwarded_path.clone(),
-                    query: input.query.clone(),
-                    attempt_index,
-                    provider_id,
-                    session_reuse,
-                    provider_name: provider_name_base.clone(),
-                    base_url: provider_base_url_base.clone(),
-                    outcome: "started".to_string(),
-                    status: None,
-                    attempt_started_ms,
-                    attempt_duration_ms: 0,
-                    circuit_state_before: Some(circuit_before.state.as_str()),
-                    circuit_state_after: None,
-                    circuit_failure_count: Some(circuit_before.failure_count),
-                    circuit_failure_threshold: Some(circuit_before.failure_threshold),
-                },
-            );
+            input
+                .active_request
+                .begin_attempt(attempt_index, provider_id, &provider_name_base);
 
-            let mut headers = input.base_headers.clone();
-            ensure_cli_required_headers(&input.cli_key, &mut headers);
+            // Realtime routing UX: announce the provider as soon as it is selected.
+            emit_attempt_started(ctx, provider_ctx, attempt_ctx);
 
-            // Always override auth headers to avoid leaking any official OAuth tokens to a third-party relay base_url.
-            inject_provider_auth(
-                &input.cli_key,
-                provider.api_key_plaintext.trim(),
-                &mut headers,
+            let headers = upstream_request::attempt_headers(
+                ctx,
+                provider,
+                provider_ctx,
+                &upstream,
+                streaming_body
+                    .is_none()
+                    .then_some(upstream.body_bytes.as_ref()),
+                retry_index == 1,
             );
-            if strip_request_content_encoding {
-                headers.remove(header::CONTENT_ENCODING);
-            }
-
-            let send_result = send::send_upstream(
+            // Hedging: only the first attempt of the first provider (and a buffered body, see
+            // `HedgeInput::new`).
+            let hedge_delay = input.hedge_delay.filter(|_| {
+                retry_index == 1 && provider_index == 1 && providers_tried < max_providers_to_try
+            });
+            let streamed_body = streaming_body.is_some();
+
+            let upstream_body = match streaming_body.take() {
+                Some(body) => body.into_upstream_body(),
+                None => reqwest::Body::from(upstream.body_bytes.clone()),
+            };
+            let send = send::send_upstream(
                 ctx,
+                &upstream_client,
                 method.clone(),
                 url,
                 headers,
-                upstream_body_bytes.clone(),
-            )
-            .await;
-
-            match send_result {
-                send::SendResult::Ok(resp) => {
-                    let status = resp.status();
-                    let response_headers = resp.headers().clone();
-
-                    if status.is_success() {
-                        if is_event_stream(&response_headers) {
+                upstream_body,
+            );
+            let hedge_input = hedge_delay.and_then(|delay| {
+                hedge::HedgeInput::new(
+                    base_ctx,
+                    &input,
+                    streamed_body,
+                    method.clone(),
+                    provider_ctx,
+                    attempt_ctx,
+                    provider_pos,
+                    delay,
+                    &quota_exceeded,
+                    &mut failed_provider_ids,
+                    &mut attempts,
+                )
+            });
+            let send_result = match hedge_input {
+                None => send.await,
+                Some(hedge_input) => {
+                    let hedged = hedge::send_hedged(hedge_input, send).await;
+                    match hedged {
+                        hedge::HedgedSend::Primary(result) => result,
+                        hedge::HedgedSend::Hedge { attempt, resp } => {
+                            let hedge_provider = &input.providers[attempt.provider_pos];
+                            let mut hedge_circuit = attempt.circuit_before.clone();
                             let loop_state = LoopState::new(
                                 &mut attempts,
                                 &mut failed_provider_ids,
                                 &mut last_error_category,
                                 &mut last_error_code,
-                                &mut circuit_snapshot,
+                                &mut hedge_circuit,
                                 &mut input.abort_guard,
                             );
-                            match success_event_stream::handle_success_event_stream(
-                                ctx,
-                                provider_ctx,
-                                attempt_ctx,
+                            match handle_success_response(
+                                ctx_for_provider(base_ctx, hedge_provider),
+                                attempt.provider_ctx(),
+                                attempt.attempt_ctx(),
                                 loop_state,
                                 resp,
-                                status,
-                                response_headers,
                             )
                             .await
                             {
-                                LoopControl::ContinueRetry => continue,
-                                LoopControl::BreakRetry => break,
                                 LoopControl::Return(resp) => return resp,
+                                // The primary was cancelled: move on to the next provider.
+                                LoopControl::ContinueRetry | LoopControl::BreakRetry => break,
                             }
                         }
+                    }
+                }
+            };
 
+            match send_result {
+                send::SendResult::Ok(resp) => {
+                    if resp.status().is_success() {
                         let loop_state = LoopState::new(
                             &mut attempts,
                             &mut failed_provider_ids,
@@ -357,14 +457,12 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                             &mut circuit_snapshot,
                             &mut input.abort_guard,
                         );
-                        match success_non_stream::handle_success_non_stream(
+                        match handle_success_response(
                             ctx,
                             provider_ctx,
                             attempt_ctx,
                             loop_state,
                             resp,
-                            status,
-                            response_headers,
                         )
                         .await
                         {
@@ -387,11 +485,12 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                         provider_ctx,
                         attempt_ctx,
                         loop_state,
-                        input.enable_thinking_signature_rectifier,
+                        input.enable_thinking_signature_rectifier && !provider.passthrough,
                         resp,
                         upstream_error::UpstreamRequestState {
-                            upstream_body_bytes: &mut upstream_body_bytes,
-                            strip_request_content_encoding: &mut strip_request_content_encoding,
+                            upstream_body_bytes: &mut upstream.body_bytes,
+                            strip_request_content_encoding: &mut upstream
+                                .strip_request_content_encoding,
                             thinking_signature_rectifier_retried:
                                 &mut thinking_signature_rectifier_retried,
                         },
@@ -462,11 +561,14 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
             created_at,
             session_id: owned.session_id,
             requested_model: owned.requested_model,
+            attribution: owned.attribution,
             special_settings: owned.special_settings,
             earliest_available_unix,
             skipped_open,
             skipped_cooldown,
-            fingerprint_key: input.fingerprint_key,
+            skipped_rate_limited,
+            skipped_quota,
+            fingerprint_key: Some(input.fingerprint_key).filter(|_| body_fingerprinted),
             fingerprint_debug: input.fingerprint_debug.clone(),
             unavailable_fingerprint_key: input.unavailable_fingerprint_key,
             unavailable_fingerprint_debug: input.unavailable_fingerprint_debug.clone(),
@@ -491,6 +593,7 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
         created_at,
         session_id: owned.session_id,
         requested_model: owned.requested_model,
+        attribution: owned.attribution,
         special_settings: owned.special_settings,
     })
     .await
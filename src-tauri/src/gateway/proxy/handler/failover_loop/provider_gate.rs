@@ -1,4 +1,4 @@
-//! Usage: Provider gating helpers (circuit allow/skip + event emission).
+//! Usage: Provider gating helpers (circuit / rate-limit / quota allow/skip + event emission).
 
 use super::super::super::provider_router;
 use super::context::CommonCtx;
@@ -10,9 +10,14 @@ pub(super) struct ProviderGateInput<'a> {
     pub(super) provider_id: i64,
     pub(super) provider_name_base: &'a String,
     pub(super) provider_base_url_display: &'a String,
+    pub(super) rpm_limit: i64,
+    pub(super) tpm_limit: i64,
+    pub(super) quota_exceeded_until: Option<i64>,
     pub(super) earliest_available_unix: &'a mut Option<i64>,
     pub(super) skipped_open: &'a mut usize,
     pub(super) skipped_cooldown: &'a mut usize,
+    pub(super) skipped_rate_limited: &'a mut usize,
+    pub(super) skipped_quota: &'a mut usize,
 }
 
 pub(super) struct ProviderGateAllow {
@@ -25,9 +30,14 @@ pub(super) fn gate_provider(input: ProviderGateInput<'_>) -> Option<ProviderGate
         provider_id,
         provider_name_base,
         provider_base_url_display,
+        rpm_limit,
+        tpm_limit,
+        quota_exceeded_until,
         earliest_available_unix,
         skipped_open,
         skipped_cooldown,
+        skipped_rate_limited,
+        skipped_quota,
     } = input;
 
     let now_unix = now_unix_seconds() as i64;
@@ -39,10 +49,15 @@ pub(super) fn gate_provider(input: ProviderGateInput<'_>) -> Option<ProviderGate
         provider_id,
         provider_name: provider_name_base.as_str(),
         provider_base_url_display: provider_base_url_display.as_str(),
+        rpm_limit,
+        tpm_limit,
+        quota_exceeded_until,
         now_unix,
         earliest_available_unix,
         skipped_open,
         skipped_cooldown,
+        skipped_rate_limited,
+        skipped_quota,
     })
     .map(|circuit_after| ProviderGateAllow { circuit_after })
 }
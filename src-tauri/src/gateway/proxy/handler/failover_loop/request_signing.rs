@@ -0,0 +1,59 @@
+//! Usage: Per-provider HMAC request signing (`request_signing`) for failover attempts.
+
+use super::context::{CommonCtx, ProviderCtx};
+use crate::gateway::request_signing;
+use crate::gateway::util::now_unix_seconds;
+use crate::providers;
+use axum::http::HeaderMap;
+
+/// Signs the final upstream body; `body` is `None` when it is streamed and can't be signed.
+pub(super) fn apply_if_needed(
+    ctx: CommonCtx<'_>,
+    provider: &providers::ProviderForGateway,
+    provider_ctx: ProviderCtx<'_>,
+    body: Option<&[u8]>,
+    headers: &mut HeaderMap,
+    record: bool,
+) {
+    let cfg = &provider.request_signing;
+    if !cfg.is_enabled() {
+        return;
+    }
+
+    let signed = match body {
+        Some(body) => request_signing::signature_headers(cfg, now_unix_seconds(), body),
+        None => Err("request body is streamed".to_string()),
+    };
+    let error = match signed {
+        Ok(signature_headers) => {
+            for (name, value) in signature_headers {
+                headers.insert(name, value);
+            }
+            None
+        }
+        Err(err) => {
+            tracing::warn!(
+                trace_id = %ctx.trace_id,
+                provider_id = provider_ctx.provider_id,
+                "请求签名失败，按未签名请求发送: {}",
+                err
+            );
+            Some(err)
+        }
+    };
+
+    if !record {
+        return;
+    }
+    if let Ok(mut settings) = ctx.special_settings.lock() {
+        settings.push(serde_json::json!({
+            "type": "request_signing",
+            "scope": "attempt",
+            "hit": error.is_none(),
+            "providerId": provider_ctx.provider_id,
+            "algorithm": cfg.algorithm,
+            "header": cfg.header_name,
+            "error": error,
+        }));
+    }
+}
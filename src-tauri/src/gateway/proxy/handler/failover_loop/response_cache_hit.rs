@@ -0,0 +1,110 @@
+//! Usage: Serve cached responses (model lists, token counts, opt-in completions) inside `failover_loop::run`.
+
+use super::super::super::abort_guard::RequestAbortGuard;
+use super::super::super::response_cache::{self, RESPONSE_CACHE_HEADER};
+use super::*;
+use crate::providers;
+
+/// Returns the cached response of `provider` for this request, if any; no upstream call is made.
+pub(super) async fn try_serve(
+    ctx: CommonCtx<'_>,
+    provider: &providers::ProviderForGateway,
+    provider_name: &str,
+    abort_guard: &mut RequestAbortGuard,
+) -> Option<Response> {
+    let ttl = ctx.response_cache_ttl?;
+    let lookup = response_cache::lookup(
+        &ctx.state.db,
+        provider.id,
+        ctx.fingerprint_key,
+        ctx.fingerprint_debug.as_str(),
+        ttl,
+    )
+    .await;
+    let Some((cached, source)) = lookup else {
+        if let Ok(mut settings) = ctx.special_settings.lock() {
+            settings.push(serde_json::json!({
+                "type": "response_cache",
+                "scope": "response",
+                "hit": false,
+                "action": "miss",
+                "providerId": provider.id,
+            }));
+        }
+        return None;
+    };
+    let age_secs = cached.age_secs();
+
+    if let Ok(mut settings) = ctx.special_settings.lock() {
+        settings.push(serde_json::json!({
+            "type": "response_cache",
+            "scope": "response",
+            "hit": true,
+            "action": "serve",
+            "source": source,
+            "providerId": provider.id,
+            "ageSeconds": age_secs,
+        }));
+    }
+
+    let attempts = [FailoverAttempt {
+        provider_id: provider.id,
+        provider_name: provider_name.to_string(),
+        base_url: provider.base_urls.first().cloned().unwrap_or_default(),
+        outcome: "success".to_string(),
+        status: Some(cached.status.as_u16()),
+        provider_index: Some(1),
+        retry_index: None,
+        session_reuse: Some(false),
+        error_category: None,
+        error_code: None,
+        decision: Some("success"),
+        reason: Some(format!("response cache hit (age {age_secs}s)")),
+        attempt_started_ms: None,
+        attempt_duration_ms: None,
+        circuit_state_before: None,
+        circuit_state_after: None,
+        circuit_failure_count: None,
+        circuit_failure_threshold: None,
+    }];
+
+    let duration_ms = ctx.started.elapsed().as_millis();
+    emit_request_event_and_enqueue_request_log(RequestEndArgs {
+        deps: RequestEndDeps::new(&ctx.state.app, &ctx.state.db, &ctx.state.log_tx),
+        trace_id: ctx.trace_id.as_str(),
+        cli_key: ctx.cli_key.as_str(),
+        method: ctx.method_hint.as_str(),
+        path: ctx.forwarded_path.as_str(),
+        query: ctx.query.as_deref(),
+        // Nothing was sent upstream: keep cache hits out of usage / cost stats.
+        excluded_from_stats: true,
+        status: Some(cached.status.as_u16()),
+        error_category: None,
+        error_code: None,
+        duration_ms,
+        event_ttfb_ms: Some(duration_ms),
+        log_ttfb_ms: Some(duration_ms),
+        attempts: &attempts,
+        special_settings_json: response_fixer::special_settings_json(ctx.special_settings),
+        session_id: ctx.session_id.clone(),
+        requested_model: ctx.requested_model.clone(),
+        attribution: ctx.attribution.clone(),
+        created_at_ms: ctx.created_at_ms,
+        created_at: ctx.created_at,
+        usage_metrics: Some(usage::UsageMetrics::default()),
+        log_usage_metrics: None,
+        usage: None,
+    })
+    .await;
+
+    let mut resp = build_response(
+        cached.status,
+        &cached.headers,
+        ctx.trace_id.as_str(),
+        Body::from(cached.body),
+    );
+    resp.headers_mut()
+        .insert(RESPONSE_CACHE_HEADER, HeaderValue::from_static("hit"));
+    abort_guard.disarm();
+    Some(resp)
+}
@@ -1,7 +1,6 @@
 //! Usage: Upstream request sending helpers (first-byte timeout aware).
 
 use super::context::CommonCtx;
-use axum::body::Bytes;
 use axum::http::{HeaderMap, Method};
 
 pub(super) enum SendResult {
@@ -12,14 +11,13 @@ pub(super) enum SendResult {
 
 pub(super) async fn send_upstream(
     ctx: CommonCtx<'_>,
+    client: &reqwest::Client,
     method: Method,
     url: reqwest::Url,
     headers: HeaderMap,
-    body: Bytes,
+    body: reqwest::Body,
 ) -> SendResult {
-    let send = ctx
-        .state
-        .client
+    let send = client
         .request(method, url)
         .headers(headers)
         .body(body)
@@ -17,7 +17,10 @@ pub(super) async fn handle_success_event_stream(
     let started = common.started;
     let upstream_first_byte_timeout_secs = common.upstream_first_byte_timeout_secs;
     let upstream_first_byte_timeout = common.upstream_first_byte_timeout;
-    let upstream_stream_idle_timeout = common.upstream_stream_idle_timeout;
+    let stream_idle_timeouts = StreamIdleTimeouts::new(
+        common.upstream_stream_idle_timeout,
+        common.stream_tool_pause_idle_timeout,
+    );
     let max_attempts_per_provider = common.max_attempts_per_provider;
     let enable_response_fixer = common.enable_response_fixer;
     let response_fixer_stream_config = common.response_fixer_stream_config;
@@ -248,6 +251,10 @@ pub(super) async fn handle_success_event_stream(
             response_headers.remove(header::CONTENT_LENGTH);
         }
 
+        if provider_ctx_owned.protocol_bridge.is_some() {
+            response_headers.remove(header::CONTENT_LENGTH);
+        }
+
         let enable_response_fixer_for_this_response =
             enable_response_fixer && !has_non_identity_content_encoding(&response_headers);
 
@@ -255,7 +262,11 @@ pub(super) async fn handle_success_event_stream(
             response_headers.remove(header::CONTENT_LENGTH);
             response_headers.insert(
                 "x-cch-response-fixer",
-                HeaderValue::from_static("processed"),
+                HeaderValue::from_static(if response_fixer_stream_config.dry_run {
+                    "dry-run"
+                } else {
+                    "processed"
+                }),
             );
         }
 
@@ -265,6 +276,11 @@ pub(super) async fn handle_success_event_stream(
             (true, true) => {
                 let upstream =
                     GunzipStream::new(FirstChunkStream::new(first_chunk, resp.bytes_stream()));
+                let upstream = ProtocolBridgeStream::new(
+                    upstream,
+                    provider_ctx_owned.protocol_bridge,
+                    common.requested_model.clone(),
+                );
                 let upstream = response_fixer::ResponseFixerStream::new(
                     upstream,
                     response_fixer_stream_config,
@@ -274,14 +290,14 @@ pub(super) async fn handle_success_event_stream(
                     spawn_usage_sse_relay_body(
                         upstream,
                         ctx,
-                        upstream_stream_idle_timeout,
+                        stream_idle_timeouts,
                         initial_first_byte_ms,
                     )
                 } else {
                     let stream = UsageSseTeeStream::new(
                         upstream,
                         ctx,
-                        upstream_stream_idle_timeout,
+                        stream_idle_timeouts,
                         initial_first_byte_ms,
                     );
                     Body::from_stream(stream)
@@ -289,6 +305,11 @@ pub(super) async fn handle_success_event_stream(
             }
             (true, false) => {
                 let upstream = FirstChunkStream::new(first_chunk, resp.bytes_stream());
+                let upstream = ProtocolBridgeStream::new(
+                    upstream,
+                    provider_ctx_owned.protocol_bridge,
+                    common.requested_model.clone(),
+                );
                 let upstream = response_fixer::ResponseFixerStream::new(
                     upstream,
                     response_fixer_stream_config,
@@ -298,14 +319,14 @@ pub(super) async fn handle_success_event_stream(
                     spawn_usage_sse_relay_body(
                         upstream,
                         ctx,
-                        upstream_stream_idle_timeout,
+                        stream_idle_timeouts,
                         initial_first_byte_ms,
                     )
                 } else {
                     let stream = UsageSseTeeStream::new(
                         upstream,
                         ctx,
-                        upstream_stream_idle_timeout,
+                        stream_idle_timeouts,
                         initial_first_byte_ms,
                     );
                     Body::from_stream(stream)
@@ -314,18 +335,23 @@ pub(super) async fn handle_success_event_stream(
             (false, true) => {
                 let upstream =
                     GunzipStream::new(FirstChunkStream::new(first_chunk, resp.bytes_stream()));
+                let upstream = ProtocolBridgeStream::new(
+                    upstream,
+                    provider_ctx_owned.protocol_bridge,
+                    common.requested_model.clone(),
+                );
                 if use_sse_relay {
                     spawn_usage_sse_relay_body(
                         upstream,
                         ctx,
-                        upstream_stream_idle_timeout,
+                        stream_idle_timeouts,
                         initial_first_byte_ms,
                     )
                 } else {
                     let stream = UsageSseTeeStream::new(
                         upstream,
                         ctx,
-                        upstream_stream_idle_timeout,
+                        stream_idle_timeouts,
                         initial_first_byte_ms,
                     );
                     Body::from_stream(stream)
@@ -333,18 +359,23 @@ pub(super) async fn handle_success_event_stream(
             }
             (false, false) => {
                 let upstream = FirstChunkStream::new(first_chunk, resp.bytes_stream());
+                let upstream = ProtocolBridgeStream::new(
+                    upstream,
+                    provider_ctx_owned.protocol_bridge,
+                    common.requested_model.clone(),
+                );
                 if use_sse_relay {
                     spawn_usage_sse_relay_body(
                         upstream,
                         ctx,
-                        upstream_stream_idle_timeout,
+                        stream_idle_timeouts,
                         initial_first_byte_ms,
                     )
                 } else {
                     let stream = UsageSseTeeStream::new(
                         upstream,
                         ctx,
-                        upstream_stream_idle_timeout,
+                        stream_idle_timeouts,
                         initial_first_byte_ms,
                     );
                     Body::from_stream(stream)
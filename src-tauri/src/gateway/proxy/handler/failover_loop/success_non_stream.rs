@@ -1,7 +1,9 @@
 //! Usage: Handle successful non-SSE upstream responses inside `failover_loop::run`.
 
-use super::super::super::provider_router;
+use super::super::super::{prefix_affinity, provider_router, rate_limit, response_cache};
 use super::*;
+use crate::gateway::body_budget::{self, BufferReservation};
+use crate::settings;
 
 pub(super) async fn handle_success_non_stream(
     ctx: CommonCtx<'_>,
@@ -45,13 +47,49 @@ pub(super) async fn handle_success_non_stream(
         abort_guard,
     } = loop_state;
 
+    let buffer_reservation;
+    let cache_ttl;
     {
         strip_hop_headers(&mut response_headers);
 
         let should_gunzip = has_gzip_content_encoding(&response_headers);
 
-        match resp.content_length() {
-            Some(len) if len > MAX_NON_SSE_BODY_BYTES as u64 => {
+        let content_length = resp.content_length();
+        buffer_reservation = match content_length {
+            Some(len) if len <= MAX_NON_SSE_BODY_BYTES as u64 => {
+                body_budget::try_reserve(len as usize)
+            }
+            _ => None,
+        };
+
+        // Only complete 200 bodies of a bounded size are worth caching, and only when the body
+        // budget can hold them (a known length without a reservation is passed through uncached).
+        cache_ttl = common.response_cache_ttl.filter(|_| {
+            status == StatusCode::OK && (content_length.is_none() || buffer_reservation.is_some())
+        });
+
+        // A bridged body must be translated as a whole (and a cacheable one stored as a whole),
+        // so both always take the buffered path below.
+        let buffer_whole = provider_ctx_owned.protocol_bridge.is_some() || cache_ttl.is_some();
+        match (buffer_whole, content_length) {
+            (false, Some(len))
+                if len > MAX_NON_SSE_BODY_BYTES as u64 || buffer_reservation.is_none() =>
+            {
+                if len <= MAX_NON_SSE_BODY_BYTES as u64 {
+                    // 全局缓存预算耗尽：不整段读入内存，直接转发（仅记录耗时）
+                    if let Ok(mut settings) = common.special_settings.lock() {
+                        settings.push(serde_json::json!({
+                            "type": "body_buffer_budget",
+                            "scope": "response",
+                            "hit": true,
+                            "action": "passthrough",
+                            "contentLength": len,
+                            "inUseBytes": body_budget::in_use_bytes(),
+                            "budgetBytes": body_budget::GLOBAL_BODY_BUFFER_BUDGET_BYTES,
+                        }));
+                    }
+                }
+
                 let outcome = "success".to_string();
 
                 attempts.push(FailoverAttempt {
@@ -130,7 +168,7 @@ pub(super) async fn handle_success_non_stream(
                     body,
                 ));
             }
-            None => {
+            (false, None) => {
                 let outcome = "success".to_string();
 
                 attempts.push(FailoverAttempt {
@@ -223,6 +261,7 @@ pub(super) async fn handle_success_non_stream(
         }
     }
 
+    let mut buffer_reservation = buffer_reservation.unwrap_or_else(BufferReservation::empty);
     let remaining_total =
         upstream_request_timeout_non_streaming.and_then(|t| t.checked_sub(started.elapsed()));
     let bytes_result = match remaining_total {
@@ -230,22 +269,36 @@ pub(super) async fn handle_success_non_stream(
             if remaining.is_zero() {
                 Err("timeout")
             } else {
-                match tokio::time::timeout(remaining, resp.bytes()).await {
-                    Ok(Ok(b)) => Ok(b),
-                    Ok(Err(_)) => Err("read_error"),
+                match tokio::time::timeout(
+                    remaining,
+                    read_body_within_budget(resp, &mut buffer_reservation),
+                )
+                .await
+                {
+                    Ok(result) => result,
                     Err(_) => Err("timeout"),
                 }
             }
         }
-        None => match resp.bytes().await {
-            Ok(b) => Ok(b),
-            Err(_) => Err("read_error"),
-        },
+        None => read_body_within_budget(resp, &mut buffer_reservation).await,
     };
 
     let mut body_bytes = match bytes_result {
         Ok(b) => b,
         Err(kind) => {
+            if kind == "budget_exhausted" {
+                // 必须整段读入（协议转换 / 缓存）但全局缓存预算耗尽：放弃本次读取
+                if let Ok(mut settings) = common.special_settings.lock() {
+                    settings.push(serde_json::json!({
+                        "type": "body_buffer_budget",
+                        "scope": "response",
+                        "hit": true,
+                        "action": "reject",
+                        "inUseBytes": body_budget::in_use_bytes(),
+                        "budgetBytes": body_budget::GLOBAL_BODY_BUFFER_BUDGET_BYTES,
+                    }));
+                }
+            }
             let error_code = if kind == "timeout" {
                 "GW_UPSTREAM_TIMEOUT"
             } else {
@@ -323,6 +376,19 @@ pub(super) async fn handle_success_non_stream(
         &mut response_headers,
         MAX_NON_SSE_BODY_BYTES,
     );
+    if let Some(bridge) = provider_ctx_owned.protocol_bridge {
+        if let Some(translated) =
+            bridge.translate_response_body(&body_bytes, common.requested_model.as_deref())
+        {
+            body_bytes = translated;
+            response_headers.remove(header::CONTENT_LENGTH);
+            response_headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+        }
+    }
+    buffer_reservation.track(body_bytes.len());
 
     let enable_response_fixer_for_this_response =
         enable_response_fixer && !has_non_identity_content_encoding(&response_headers);
@@ -344,6 +410,7 @@ pub(super) async fn handle_success_non_stream(
 
     let usage = usage::parse_usage_from_json_bytes(&body_bytes);
     let usage_metrics = usage.as_ref().map(|u| u.metrics.clone());
+    rate_limit::charge_usage(provider_id, usage_metrics.as_ref());
     let requested_model_for_log = common.requested_model.clone().or_else(|| {
         if body_bytes.is_empty() {
             None
@@ -352,6 +419,34 @@ pub(super) async fn handle_success_non_stream(
         }
     });
 
+    // 响应体经 hold_until_body_end 包装为流后不再带长度提示，这里显式补上
+    if !response_headers.contains_key(header::CONTENT_LENGTH) {
+        response_headers.insert(header::CONTENT_LENGTH, HeaderValue::from(body_bytes.len()));
+    }
+    if let Some(ttl) = cache_ttl {
+        if response_cache::store(
+            &common.state.db,
+            provider_id,
+            common.fingerprint_key,
+            &common.fingerprint_debug,
+            ttl,
+            status,
+            &response_headers,
+            &body_bytes,
+            settings::read(&common.state.app).is_ok_and(|cfg| cfg.exclude_reasoning_from_logs),
+        ) {
+            if let Ok(mut settings) = common.special_settings.lock() {
+                settings.push(serde_json::json!({
+                    "type": "response_cache",
+                    "scope": "response",
+                    "hit": false,
+                    "action": "store",
+                    "providerId": provider_id,
+                    "ttlSeconds": ttl.as_secs(),
+                }));
+            }
+        }
+    }
     let body = Body::from(body_bytes);
     let mut builder = Response::builder().status(status);
     for (k, v) in response_headers.iter() {
@@ -372,6 +467,7 @@ pub(super) async fn handle_success_non_stream(
             fallback
         }
     };
+    let out = buffer_reservation.hold_until_body_end(out);
 
     if out.status() == status {
         let now_unix = now_unix_seconds() as i64;
@@ -401,6 +497,9 @@ pub(super) async fn handle_success_non_stream(
                     now_unix,
                 );
             }
+            if let Some(key) = common.prefix_affinity_key {
+                prefix_affinity::record(key, provider_id, now_unix);
+            }
         }
     }
 
@@ -412,7 +511,7 @@ pub(super) async fn handle_success_non_stream(
         method: common.method_hint.as_str(),
         path: common.forwarded_path.as_str(),
         query: common.query.as_deref(),
-        excluded_from_stats: false,
+        excluded_from_stats: provider_ctx_owned.exclude_from_stats,
         status: Some(status.as_u16()),
         error_category: None,
         error_code: None,
@@ -423,6 +522,7 @@ pub(super) async fn handle_success_non_stream(
         special_settings_json: response_fixer::special_settings_json(&common.special_settings),
         session_id: common.session_id.clone(),
         requested_model: requested_model_for_log,
+        attribution: common.attribution.clone(),
         created_at_ms,
         created_at,
         usage_metrics,
@@ -433,3 +533,20 @@ pub(super) async fn handle_success_non_stream(
     abort_guard.disarm();
     LoopControl::Return(out)
 }
+
+/// Reads the whole body, growing `reservation` past its upfront size as chunks arrive, so buffered
+/// (bridged / cached) bodies stay within the global body budget even without a `content-length`.
+async fn read_body_within_budget(
+    mut resp: reqwest::Response,
+    reservation: &mut BufferReservation,
+) -> Result<Bytes, &'static str> {
+    let mut buf = Vec::with_capacity(reservation.bytes());
+    while let Some(chunk) = resp.chunk().await.map_err(|_| "read_error")? {
+        let needed = (buf.len() + chunk.len()).saturating_sub(reservation.bytes());
+        if !reservation.try_grow(needed) {
+            return Err("budget_exhausted");
+        }
+        buf.extend_from_slice(&chunk);
+    }
+    Ok(Bytes::from(buf))
+}
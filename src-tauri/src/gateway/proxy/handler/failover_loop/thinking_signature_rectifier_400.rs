@@ -31,6 +31,7 @@ pub(super) async fn handle_thinking_signature_rectifier_400(
         created_at,
         session_id,
         requested_model,
+        attribution,
         special_settings,
         enable_response_fixer,
         response_fixer_non_stream_config,
@@ -43,6 +44,8 @@ pub(super) async fn handle_thinking_signature_rectifier_400(
         provider_base_url_base,
         provider_index,
         session_reuse,
+        protocol_bridge: _,
+        exclude_from_stats,
     } = ProviderCtxOwned::from(provider_ctx);
 
     let AttemptCtx {
@@ -68,6 +71,7 @@ pub(super) async fn handle_thinking_signature_rectifier_400(
             Err(err) => {
                 let duration_ms = started.elapsed().as_millis();
                 let resp = error_response(
+                    &cli_key,
                     StatusCode::BAD_GATEWAY,
                     trace_id.clone(),
                     "GW_UPSTREAM_BODY_READ_ERROR",
@@ -81,7 +85,7 @@ pub(super) async fn handle_thinking_signature_rectifier_400(
                     method: method_hint.as_str(),
                     path: forwarded_path.as_str(),
                     query: query.as_deref(),
-                    excluded_from_stats: false,
+                    excluded_from_stats: exclude_from_stats,
                     status: Some(StatusCode::BAD_GATEWAY.as_u16()),
                     error_category: Some(ErrorCategory::SystemError.as_str()),
                     error_code: Some("GW_UPSTREAM_BODY_READ_ERROR"),
@@ -92,6 +96,7 @@ pub(super) async fn handle_thinking_signature_rectifier_400(
                     special_settings_json: None,
                     session_id,
                     requested_model,
+                    attribution,
                     created_at_ms,
                     created_at,
                     usage_metrics: None,
@@ -236,7 +241,7 @@ pub(super) async fn handle_thinking_signature_rectifier_400(
 
         match decision {
             FailoverDecision::RetrySameProvider => {
-                if let Some(delay) = retry_backoff_delay(status, retry_index) {
+                if let Some(delay) = retry_backoff_delay(ctx.retry_backoff, status, retry_index) {
                     tokio::time::sleep(delay).await;
                 }
                 return LoopControl::ContinueRetry;
@@ -286,7 +291,7 @@ pub(super) async fn handle_thinking_signature_rectifier_400(
                     method: method_hint.as_str(),
                     path: forwarded_path.as_str(),
                     query: query.as_deref(),
-                    excluded_from_stats: false,
+                    excluded_from_stats: exclude_from_stats,
                     status: Some(status.as_u16()),
                     error_category: Some(category.as_str()),
                     error_code: Some(error_code),
@@ -297,6 +302,7 @@ pub(super) async fn handle_thinking_signature_rectifier_400(
                     special_settings_json,
                     session_id,
                     requested_model,
+                    attribution,
                     created_at_ms,
                     created_at,
                     usage_metrics: None,
@@ -1,9 +1,12 @@
 //! Usage: Handle upstream non-success responses and reqwest errors inside `failover_loop::run`.
 
+use super::super::super::circuit_failures;
 use super::super::super::errors::{
-    classify_reqwest_error, classify_upstream_status, error_response,
+    classify_reqwest_error, classify_upstream_status, error_response, upstream_retry_after_secs,
+    UPSTREAM_OVERLOADED_STATUS,
 };
 use super::super::super::failover::{retry_backoff_delay, FailoverDecision};
+use super::super::super::failover_rules;
 use super::super::super::http_util::{
     build_response, has_gzip_content_encoding, has_non_identity_content_encoding,
     maybe_gunzip_response_body_bytes_with_limit,
@@ -84,6 +87,8 @@ pub(super) async fn handle_non_success_response(
         provider_base_url_base,
         provider_index,
         session_reuse,
+        protocol_bridge: _,
+        exclude_from_stats,
     } = provider_ctx;
 
     let AttemptCtx {
@@ -110,21 +115,24 @@ pub(super) async fn handle_non_success_response(
     } else {
         base_decision
     };
-    if matches!(decision, FailoverDecision::RetrySameProvider)
-        && retry_index >= max_attempts_per_provider
-    {
-        decision = FailoverDecision::SwitchProvider;
-    }
+
+    let content_length = resp.as_ref().and_then(|r| r.content_length());
+    let builtin_scan = !is_count_tokens
+        && upstream_client_error_rules::should_attempt_non_retryable_match(status, content_length);
+    let failover_rules_scan = !is_count_tokens
+        && failover_rules::should_read_body(
+            ctx.failover_rules,
+            ctx.cli_key.as_str(),
+            provider_id,
+            status.as_u16(),
+            content_length,
+        );
 
     let mut abort_body_bytes: Option<Bytes> = None;
     let mut abort_response_headers: Option<axum::http::HeaderMap> = None;
     let mut matched_rule_id: Option<&'static str> = None;
-    if !is_count_tokens
-        && upstream_client_error_rules::should_attempt_non_retryable_match(
-            status,
-            resp.as_ref().and_then(|r| r.content_length()),
-        )
-    {
+    let mut scanned: Option<(Bytes, axum::http::HeaderMap)> = None;
+    if builtin_scan || failover_rules_scan {
         if let Some(resp) = resp.take() {
             if let Ok(bytes) = resp.bytes().await {
                 let mut headers_for_scan = response_headers.clone();
@@ -134,21 +142,70 @@ pub(super) async fn handle_non_success_response(
                     &mut headers_for_scan,
                     MAX_NON_SSE_BODY_BYTES,
                 );
-                matched_rule_id = upstream_client_error_rules::match_non_retryable_client_error(
-                    ctx.cli_key.as_str(),
-                    status,
-                    body_for_scan.as_ref(),
-                );
-                if matched_rule_id.is_some() {
-                    category = ErrorCategory::NonRetryableClientError;
-                    decision = FailoverDecision::Abort;
-                    abort_body_bytes = Some(body_for_scan);
-                    abort_response_headers = Some(headers_for_scan);
-                }
+                scanned = Some((body_for_scan, headers_for_scan));
+            }
+        }
+    }
+    if builtin_scan {
+        if let Some((body_for_scan, headers_for_scan)) = scanned.as_ref() {
+            matched_rule_id = upstream_client_error_rules::match_non_retryable_client_error(
+                ctx.cli_key.as_str(),
+                status,
+                body_for_scan.as_ref(),
+            );
+            if matched_rule_id.is_some() {
+                category = ErrorCategory::NonRetryableClientError;
+                decision = FailoverDecision::Abort;
+                abort_body_bytes = Some(body_for_scan.clone());
+                abort_response_headers = Some(headers_for_scan.clone());
             }
         }
     }
 
+    // User failover rules take precedence over the built-in classification above.
+    let failover_rule = if is_count_tokens {
+        None
+    } else {
+        failover_rules::match_rule(
+            ctx.failover_rules,
+            ctx.cli_key.as_str(),
+            provider_id,
+            status.as_u16(),
+            scanned.as_ref().map(|(body, _)| body.as_ref()),
+        )
+    };
+    if let Some(rule) = failover_rule {
+        decision = failover_rules::decision_of(rule);
+        if matches!(decision, FailoverDecision::Abort) {
+            if let Some((body_for_scan, headers_for_scan)) = scanned.as_ref() {
+                abort_body_bytes = Some(body_for_scan.clone());
+                abort_response_headers = Some(headers_for_scan.clone());
+            }
+        } else {
+            category = base_category;
+            matched_rule_id = None;
+            abort_body_bytes = None;
+            abort_response_headers = None;
+        }
+        if let Ok(mut settings) = ctx.special_settings.lock() {
+            settings.push(serde_json::json!({
+                "type": "failover_rule",
+                "scope": "attempt",
+                "hit": true,
+                "providerId": provider_id,
+                "ruleId": rule.id,
+                "status": status.as_u16(),
+                "decision": decision.as_str(),
+            }));
+        }
+    }
+
+    if matches!(decision, FailoverDecision::RetrySameProvider)
+        && retry_index >= max_attempts_per_provider
+    {
+        decision = FailoverDecision::SwitchProvider;
+    }
+
     let mut circuit_state_before = Some(circuit_before.state.as_str());
     let mut circuit_state_after: Option<&'static str> = None;
     let mut circuit_failure_count = Some(circuit_before.failure_count);
@@ -156,6 +213,33 @@ pub(super) async fn handle_non_success_response(
 
     let now_unix = now_unix_seconds() as i64;
     if !is_count_tokens && matches!(category, ErrorCategory::ProviderError) {
+        let mut summary: Option<String> = None;
+        if !matches!(decision, FailoverDecision::Abort)
+            && !has_non_identity_content_encoding(&response_headers)
+        {
+            // Retry / switch drops the body anyway; keep its head for the circuit notice.
+            if let Some(mut resp) = resp.take() {
+                summary = resp
+                    .chunk()
+                    .await
+                    .ok()
+                    .flatten()
+                    .and_then(|chunk| circuit_failures::summarize_body(&chunk));
+            } else if let Some((body_for_scan, _)) = scanned.as_ref() {
+                summary = circuit_failures::summarize_body(body_for_scan);
+            }
+        }
+        circuit_failures::record(
+            provider_id,
+            circuit_failures::FailureSample {
+                ts: now_unix,
+                error_code,
+                status: Some(status.as_u16()),
+                model: ctx.requested_model.clone(),
+                summary,
+            },
+        );
+
         let change = provider_router::record_failure_and_emit_transition(
             provider_router::RecordCircuitArgs::from_state(
                 state,
@@ -177,8 +261,13 @@ pub(super) async fn handle_non_success_response(
         }
     }
 
+    // 529 overloaded: honour the upstream's suggested wait instead of the global cooldown.
+    let retry_after_secs = (status.as_u16() == UPSTREAM_OVERLOADED_STATUS)
+        .then(|| upstream_retry_after_secs(&response_headers))
+        .flatten();
+    let cooldown_secs = retry_after_secs.unwrap_or(provider_cooldown_secs);
     if !is_count_tokens
-        && provider_cooldown_secs > 0
+        && cooldown_secs > 0
         && matches!(category, ErrorCategory::ProviderError)
         && matches!(
             decision,
@@ -189,14 +278,16 @@ pub(super) async fn handle_non_success_response(
             state.circuit.as_ref(),
             provider_id,
             now_unix,
-            provider_cooldown_secs,
+            cooldown_secs,
         );
         *circuit_snapshot = snap;
     }
 
-    let reason = match matched_rule_id {
-        Some(rule_id) => format!("status={} rule={rule_id}", status.as_u16()),
-        None => format!("status={}", status.as_u16()),
+    let reason = match (failover_rule, matched_rule_id, retry_after_secs) {
+        (Some(rule), _, _) => format!("status={} failover_rule={}", status.as_u16(), rule.id),
+        (None, Some(rule_id), _) => format!("status={} rule={rule_id}", status.as_u16()),
+        (None, None, Some(secs)) => format!("status={} retry_after={secs}s", status.as_u16()),
+        (None, None, None) => format!("status={}", status.as_u16()),
     };
     let outcome = format!(
         "upstream_error: status={} category={} code={} decision={}",
@@ -247,7 +338,7 @@ pub(super) async fn handle_non_success_response(
 
     match decision {
         FailoverDecision::RetrySameProvider => {
-            if let Some(delay) = retry_backoff_delay(status, retry_index) {
+            if let Some(delay) = retry_backoff_delay(ctx.retry_backoff, status, retry_index) {
                 tokio::time::sleep(delay).await;
             }
             LoopControl::ContinueRetry
@@ -271,6 +362,7 @@ pub(super) async fn handle_non_success_response(
                 created_at,
                 session_id,
                 requested_model,
+                attribution,
                 special_settings,
                 enable_response_fixer,
                 response_fixer_non_stream_config,
@@ -311,7 +403,7 @@ pub(super) async fn handle_non_success_response(
                     method: method_hint.as_str(),
                     path: forwarded_path.as_str(),
                     query: query.as_deref(),
-                    excluded_from_stats: false,
+                    excluded_from_stats: exclude_from_stats,
                     status: Some(status.as_u16()),
                     error_category: Some(category.as_str()),
                     error_code: Some(error_code),
@@ -322,6 +414,7 @@ pub(super) async fn handle_non_success_response(
                     special_settings_json,
                     session_id,
                     requested_model,
+                    attribution,
                     created_at_ms,
                     created_at,
                     usage_metrics: None,
@@ -350,7 +443,7 @@ pub(super) async fn handle_non_success_response(
                 method: method_hint.as_str(),
                 path: forwarded_path.as_str(),
                 query: query.as_deref(),
-                excluded_from_stats: false,
+                excluded_from_stats: exclude_from_stats,
                 status: Some(status.as_u16()),
                 error_category: Some(category.as_str()),
                 error_code: Some(error_code),
@@ -361,6 +454,7 @@ pub(super) async fn handle_non_success_response(
                 special_settings_json,
                 session_id,
                 requested_model,
+                attribution,
                 created_at_ms,
                 created_at,
                 usage_metrics: None,
@@ -382,6 +476,7 @@ pub(super) async fn handle_non_success_response(
 
             let Some(resp) = resp else {
                 return LoopControl::Return(error_response(
+                    &cli_key,
                     axum::http::StatusCode::BAD_GATEWAY,
                     trace_id.clone(),
                     "GW_UPSTREAM_READ_ERROR",
@@ -0,0 +1,209 @@
+//! Usage: Per-provider upstream request preparation (request rewriting + attempt headers).
+
+use super::super::super::request_context::RequestContext;
+use super::context::{CommonCtx, ProviderCtx};
+use super::{
+    body_patch, claude_model_mapping, codex_response_storage, context_trim, request_signing,
+    wire_api_bridge,
+};
+use crate::gateway::header_sanitize;
+use crate::gateway::util::{
+    ensure_cli_required_headers, inject_provider_auth, RequestedModelLocation,
+};
+use crate::{providers, settings};
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap};
+use std::sync::{Arc, Mutex};
+
+/// The request-level inputs of `prepare`, borrowed field by field: `RequestContext` itself is not
+/// `Sync` (it may own a streamed client body), so it must not be held across awaits.
+#[derive(Clone, Copy)]
+pub(super) struct RequestSource<'a> {
+    cli_key: &'a str,
+    forwarded_path: &'a String,
+    query: &'a Option<String>,
+    body_bytes: &'a Bytes,
+    client_body_bytes: &'a Bytes,
+    base_headers: &'a HeaderMap,
+    client_base_headers: &'a HeaderMap,
+    introspection_json: Option<&'a serde_json::Value>,
+    requested_model_location: Option<RequestedModelLocation>,
+    strip_request_content_encoding_seed: bool,
+    special_settings: &'a Arc<Mutex<Vec<serde_json::Value>>>,
+    header_sanitize_rules: &'a [settings::HeaderSanitizeRule],
+}
+
+impl<'a> RequestSource<'a> {
+    pub(super) fn new(input: &'a RequestContext) -> Self {
+        Self {
+            cli_key: &input.cli_key,
+            forwarded_path: &input.forwarded_path,
+            query: &input.query,
+            body_bytes: &input.body_bytes,
+            client_body_bytes: &input.client_body_bytes,
+            base_headers: &input.base_headers,
+            client_base_headers: &input.client_base_headers,
+            introspection_json: input.introspection_json.as_ref(),
+            requested_model_location: input.requested_model_location,
+            strip_request_content_encoding_seed: input.strip_request_content_encoding_seed,
+            special_settings: &input.special_settings,
+            header_sanitize_rules: &input.header_sanitize_rules,
+        }
+    }
+}
+
+/// The request as sent to one provider (before per-attempt auth / signing headers).
+pub(super) struct UpstreamRequest {
+    pub(super) forwarded_path: String,
+    pub(super) query: Option<String>,
+    pub(super) body_bytes: Bytes,
+    pub(super) strip_request_content_encoding: bool,
+    pub(super) base_headers: HeaderMap,
+}
+
+/// Applies the provider's request rewriting; updates `provider_ctx.protocol_bridge`.
+pub(super) fn prepare(
+    ctx: CommonCtx<'_>,
+    input: RequestSource<'_>,
+    provider: &providers::ProviderForGateway,
+    provider_ctx: &mut ProviderCtx<'_>,
+) -> UpstreamRequest {
+    let provider_id = provider_ctx.provider_id;
+    let mut upstream = UpstreamRequest {
+        forwarded_path: input.forwarded_path.clone(),
+        query: input.query.clone(),
+        body_bytes: input.body_bytes.clone(),
+        strip_request_content_encoding: input.strip_request_content_encoding_seed,
+        base_headers: input.base_headers.clone(),
+    };
+
+    if provider.passthrough {
+        // Strict passthrough: the client's original body (only redacted), no per-provider rewriting.
+        upstream.body_bytes = input.client_body_bytes.clone();
+        upstream.strip_request_content_encoding = false;
+        upstream.base_headers = input.client_base_headers.clone();
+        if let Ok(mut settings) = input.special_settings.lock() {
+            settings.push(serde_json::json!({
+                "type": "provider_passthrough",
+                "scope": "attempt",
+                "hit": true,
+                "providerId": provider_id,
+            }));
+        }
+    } else {
+        claude_model_mapping::apply_if_needed(
+            ctx,
+            provider,
+            *provider_ctx,
+            input.requested_model_location,
+            input.introspection_json,
+            claude_model_mapping::UpstreamRequestMut {
+                forwarded_path: &mut upstream.forwarded_path,
+                query: &mut upstream.query,
+                body_bytes: &mut upstream.body_bytes,
+                strip_request_content_encoding: &mut upstream.strip_request_content_encoding,
+            },
+        );
+        codex_response_storage::apply_if_needed(
+            ctx,
+            provider,
+            *provider_ctx,
+            input.introspection_json,
+            &mut upstream.body_bytes,
+            &mut upstream.strip_request_content_encoding,
+        );
+        provider_ctx.protocol_bridge = wire_api_bridge::apply_if_needed(
+            ctx,
+            *provider_ctx,
+            input.base_headers,
+            wire_api_bridge::UpstreamRequestMut {
+                forwarded_path: &mut upstream.forwarded_path,
+                body_bytes: &mut upstream.body_bytes,
+                strip_request_content_encoding: &mut upstream.strip_request_content_encoding,
+            },
+        );
+        context_trim::apply_if_needed(
+            ctx,
+            provider,
+            *provider_ctx,
+            &mut upstream.body_bytes,
+            &mut upstream.strip_request_content_encoding,
+        );
+        // After the built-in rewrites so the provider's rules have the last word on the body.
+        body_patch::apply_if_needed(
+            ctx,
+            provider,
+            *provider_ctx,
+            &mut upstream.body_bytes,
+            &mut upstream.strip_request_content_encoding,
+        );
+    }
+
+    let sanitized_headers = header_sanitize::apply_header_sanitize_rules(
+        input.header_sanitize_rules,
+        input.cli_key,
+        provider_id,
+        &mut upstream.base_headers,
+    );
+    if !sanitized_headers.is_empty() {
+        if let Ok(mut settings) = input.special_settings.lock() {
+            settings.push(serde_json::json!({
+                "type": "header_sanitize",
+                "scope": "attempt",
+                "hit": true,
+                "providerId": provider_id,
+                "removed": sanitized_headers,
+            }));
+        }
+    }
+    if provider.exclude_from_stats {
+        if let Ok(mut settings) = input.special_settings.lock() {
+            settings.push(serde_json::json!({
+                "type": "provider_exclude_from_stats",
+                "scope": "attempt",
+                "hit": true,
+                "providerId": provider_id,
+            }));
+        }
+    }
+
+    upstream
+}
+
+/// Headers for one attempt; `signed_body` is `None` when the body is streamed.
+pub(super) fn attempt_headers(
+    ctx: CommonCtx<'_>,
+    provider: &providers::ProviderForGateway,
+    provider_ctx: ProviderCtx<'_>,
+    upstream: &UpstreamRequest,
+    signed_body: Option<&[u8]>,
+    first_attempt: bool,
+) -> HeaderMap {
+    let mut headers = upstream.base_headers.clone();
+    ensure_cli_required_headers(ctx.cli_key, &mut headers);
+
+    // Always override auth headers to avoid leaking any official OAuth tokens to a third-party relay base_url;
+    // the provider's header rules run right after and may replace / drop them.
+    inject_provider_auth(
+        ctx.cli_key,
+        provider.api_key_plaintext.trim(),
+        &provider.header_rules,
+        &mut headers,
+    );
+    if let Some(bridge) = provider_ctx.protocol_bridge {
+        bridge.prepare_upstream_headers(&mut headers);
+    }
+    if upstream.strip_request_content_encoding {
+        headers.remove(header::CONTENT_ENCODING);
+    }
+    // Signed last: covers the body exactly as sent, with a fresh timestamp per attempt.
+    request_signing::apply_if_needed(
+        ctx,
+        provider,
+        provider_ctx,
+        signed_body,
+        &mut headers,
+        first_attempt,
+    );
+    headers
+}
@@ -0,0 +1,62 @@
+//! Usage: Rewrite an attempt's upstream request for providers with a non-native `wire_api`
+//! (path, body); returns the bridge the response side should apply, if any.
+
+use super::super::super::protocol_bridge::ProtocolBridge;
+use super::context::{CommonCtx, ProviderCtx};
+use crate::gateway::util::body_for_introspection;
+use axum::body::Bytes;
+use axum::http::HeaderMap;
+
+pub(super) struct UpstreamRequestMut<'a> {
+    pub(super) forwarded_path: &'a mut String,
+    pub(super) body_bytes: &'a mut Bytes,
+    pub(super) strip_request_content_encoding: &'a mut bool,
+}
+
+pub(super) fn apply_if_needed(
+    ctx: CommonCtx<'_>,
+    provider_ctx: ProviderCtx<'_>,
+    base_headers: &HeaderMap,
+    upstream: UpstreamRequestMut<'_>,
+) -> Option<ProtocolBridge> {
+    let bridge = provider_ctx.protocol_bridge?;
+    let UpstreamRequestMut {
+        forwarded_path,
+        body_bytes,
+        strip_request_content_encoding,
+    } = upstream;
+
+    // Earlier rewrites (model mapping etc.) leave a plain JSON body and set the strip flag.
+    let parsed = if *strip_request_content_encoding {
+        serde_json::from_slice::<serde_json::Value>(body_bytes)
+    } else {
+        serde_json::from_slice::<serde_json::Value>(&body_for_introspection(
+            base_headers,
+            body_bytes,
+        ))
+    };
+    let translated = parsed
+        .ok()
+        .and_then(|root| bridge.translate_request_body(&root));
+    let applied = translated.is_some();
+    if let Some(bytes) = translated {
+        *body_bytes = Bytes::from(bytes);
+        *strip_request_content_encoding = true;
+        *forwarded_path = bridge.upstream_path().to_string();
+    }
+
+    if let Ok(mut settings) = ctx.special_settings.lock() {
+        settings.push(serde_json::json!({
+            "type": "protocol_bridge",
+            "scope": "attempt",
+            "hit": true,
+            "applied": applied,
+            "providerId": provider_ctx.provider_id,
+            "bridge": bridge.as_str(),
+            "upstreamPath": bridge.upstream_path(),
+        }));
+    }
+
+    // An untranslatable body goes out unchanged, so its response must not be translated either.
+    applied.then_some(bridge)
+}
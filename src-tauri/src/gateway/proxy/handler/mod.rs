@@ -2,35 +2,57 @@
 //!
 //! Note: this module is being split into smaller submodules under `handler/`.
 
-use super::caches::RECENT_TRACE_DEDUP_TTL_SECS;
+use super::inflight_dedup::{InflightEntry, InflightLookup};
+use super::priority_lane::{self, RequestLane};
+use super::protocol_bridge::ProtocolBridge;
+use super::request_body::{self, RequestBody};
 use super::request_context::{RequestContext, RequestContextParts};
 use super::request_end::{
     emit_request_event_and_enqueue_request_log, emit_request_event_and_spawn_request_log,
     RequestEndArgs, RequestEndDeps,
 };
-use super::ErrorCategory;
+use super::response_cache;
+use super::stream_cap::{self, STREAM_CAP_RETRY_AFTER_SECONDS};
 use super::{
     cli_proxy_guard::cli_proxy_enabled_cached,
-    errors::{error_response, error_response_with_retry_after},
+    errors::{
+        cancelled_sse_event, error_response, error_response_with_retry_after,
+        REQUEST_CANCELLED_CODE, REQUEST_CANCELLED_MESSAGE,
+    },
     failover::{select_next_provider_id_from_order, should_reuse_provider},
-    is_claude_count_tokens_request,
+    gemini,
+    http_util::is_event_stream,
+    is_claude_count_tokens_request, latency_routing, load_balance, prefix_affinity,
 };
+use super::{ErrorCategory, RequestLogAttribution};
 
-use crate::{providers, session_manager, settings, usage};
+use crate::i18n::{self, MessageKey};
+use crate::{
+    power, provider_ab_test, provider_canary, provider_snapshot, providers, session_manager,
+    settings, usage, usage_stats,
+};
 use axum::{
-    body::{to_bytes, Body, Bytes},
+    body::{Body, Bytes},
     http::{header, HeaderValue, Request, StatusCode},
     response::{IntoResponse, Response},
-    Json,
 };
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use super::super::clients;
 use super::super::codex_session_id;
 use super::super::events::{emit_gateway_log, emit_request_start_event};
+use super::super::intercept;
 use super::super::manager::GatewayAppState;
+use super::super::prompt_cache;
+use super::super::redaction;
 use super::super::response_fixer;
+use super::super::self_test;
+use super::super::streams::SseCaptureMode;
+use super::super::token_estimate;
+use super::super::tool_usage;
+use super::super::trace_headers;
 use super::super::util::{
     body_for_introspection, compute_all_providers_unavailable_fingerprint,
     compute_request_fingerprint, extract_idempotency_key_hash, infer_requested_model_info,
@@ -48,37 +70,125 @@ pub(in crate::gateway) async fn proxy_impl(
     req: Request<Body>,
 ) -> Response {
     let started = Instant::now();
+    power::mark_activity();
     let mut trace_id = new_trace_id();
     let created_at_ms = now_unix_millis() as i64;
     let created_at = (created_at_ms / 1000).max(0);
     let method = req.method().clone();
     let method_hint = method.to_string();
-    let query = req.uri().query().map(str::to_string);
+    let (forwarded_path, query) = if cli_key == "gemini" {
+        let gemini = gemini::normalize_request(&forwarded_path, req.uri().query());
+        (gemini.forwarded_path, gemini.query)
+    } else {
+        (forwarded_path, req.uri().query().map(str::to_string))
+    };
     let is_claude_count_tokens = is_claude_count_tokens_request(&cli_key, &forwarded_path);
+    let mut attribution = RequestLogAttribution {
+        client_id: req
+            .extensions()
+            .get::<clients::GatewayClient>()
+            .map(|client| client.id.clone()),
+        client_trace_id: trace_headers::client_trace_id(req.headers()),
+        estimated_input_tokens: None,
+    };
+
+    if state.pause.is_paused() {
+        let pause = state.pause.status();
+        let language = i18n::current_language(&state.app);
+        let message = match pause.reason.as_deref() {
+            Some(reason) => i18n::format(
+                language,
+                MessageKey::GatewayPausedWithReason,
+                &[("reason", reason)],
+            ),
+            None => i18n::text(language, MessageKey::GatewayPaused).to_string(),
+        };
+        let resp = error_response(
+            &cli_key,
+            StatusCode::SERVICE_UNAVAILABLE,
+            trace_id.clone(),
+            "GW_PAUSED",
+            message,
+            vec![],
+        );
+
+        let special_settings_json = serde_json::json!([{
+            "type": "gateway_pause",
+            "scope": "request",
+            "hit": true,
+            "since": pause.since,
+            "reason": pause.reason.as_deref(),
+        }])
+        .to_string();
+
+        let duration_ms = started.elapsed().as_millis();
+        emit_request_event_and_enqueue_request_log(RequestEndArgs {
+            deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
+            trace_id: trace_id.as_str(),
+            cli_key: cli_key.as_str(),
+            method: method_hint.as_str(),
+            path: forwarded_path.as_str(),
+            query: query.as_deref(),
+            excluded_from_stats: true,
+            status: Some(StatusCode::SERVICE_UNAVAILABLE.as_u16()),
+            error_category: Some(ErrorCategory::SystemError.as_str()),
+            error_code: Some("GW_PAUSED"),
+            duration_ms,
+            event_ttfb_ms: None,
+            log_ttfb_ms: None,
+            attempts: &[],
+            special_settings_json: Some(special_settings_json),
+            session_id: None,
+            requested_model: None,
+            created_at_ms,
+            created_at,
+            usage_metrics: None,
+            log_usage_metrics: None,
+            usage: None,
+            attribution: attribution.clone(),
+        })
+        .await;
+
+        return resp;
+    }
 
     if crate::shared::cli_key::is_supported_cli_key(cli_key.as_str()) {
         let enabled_snapshot = cli_proxy_enabled_cached(&state.app, &cli_key);
         if !enabled_snapshot.enabled {
+            let language = i18n::current_language(&state.app);
             if !enabled_snapshot.cache_hit {
                 if let Some(err) = enabled_snapshot.error.as_deref() {
                     emit_gateway_log(
                         &state.app,
                         "warn",
                         "GW_CLI_PROXY_GUARD_ERROR",
-                        format!(
-                            "CLI 代理开关状态读取失败（按未开启处理）cli={cli_key} trace_id={trace_id} err={err}"
+                        i18n::format(
+                            language,
+                            MessageKey::GatewayCliProxyGuardError,
+                            &[
+                                ("cli", cli_key.as_str()),
+                                ("trace_id", trace_id.as_str()),
+                                ("err", err),
+                            ],
                         ),
                     );
                 }
             }
 
             let message = match enabled_snapshot.error.as_deref() {
-                Some(err) => format!(
-                    "CLI 代理状态读取失败（按未开启处理）：{err}；请在首页开启 {cli_key} 的 CLI 代理开关后重试"
+                Some(err) => i18n::format(
+                    language,
+                    MessageKey::GatewayCliProxyReadFailed,
+                    &[("err", err), ("cli", cli_key.as_str())],
+                ),
+                None => i18n::format(
+                    language,
+                    MessageKey::GatewayCliProxyDisabled,
+                    &[("cli", cli_key.as_str())],
                 ),
-                None => format!("CLI 代理未开启：请在首页开启 {cli_key} 的 CLI 代理开关后重试"),
             };
             let resp = error_response(
+                &cli_key,
                 StatusCode::FORBIDDEN,
                 trace_id.clone(),
                 "GW_CLI_PROXY_DISABLED",
@@ -121,6 +231,7 @@ pub(in crate::gateway) async fn proxy_impl(
                 usage_metrics: None,
                 log_usage_metrics: None,
                 usage: None,
+                attribution: attribution.clone(),
             })
             .await;
 
@@ -128,15 +239,22 @@ pub(in crate::gateway) async fn proxy_impl(
         }
     }
 
+    if self_test::is_self_test_request(req.headers()) {
+        return self_test::self_test_response(&cli_key, &forwarded_path, &trace_id);
+    }
+
     let (mut headers, body) = {
         let (parts, body) = req.into_parts();
         (parts.headers, body)
     };
 
-    let mut body_bytes = match to_bytes(body, MAX_REQUEST_BODY_BYTES).await {
-        Ok(bytes) => bytes,
+    let (mut body_bytes, streaming_body) = match request_body::read_request_body(body).await {
+        Ok(RequestBody::Buffered(bytes)) => (bytes, None),
+        // 超出缓冲上限：只保留前缀用于嗅探，其余部分在发送时直接流式转发给上游
+        Ok(RequestBody::Streaming(body)) => (Bytes::new(), Some(body)),
         Err(err) => {
             let resp = error_response(
+                &cli_key,
                 StatusCode::PAYLOAD_TOO_LARGE,
                 trace_id.clone(),
                 "GW_BODY_TOO_LARGE",
@@ -168,6 +286,7 @@ pub(in crate::gateway) async fn proxy_impl(
                 usage_metrics: None,
                 log_usage_metrics: None,
                 usage: None,
+                attribution: attribution.clone(),
             })
             .await;
             return resp;
@@ -178,13 +297,21 @@ pub(in crate::gateway) async fn proxy_impl(
         let introspection_body = body_for_introspection(&headers, &body_bytes);
         serde_json::from_slice::<serde_json::Value>(introspection_body.as_ref()).ok()
     };
+    let sniffed_model_json = streaming_body.as_ref().and_then(|body| {
+        let prefix = body_for_introspection(&headers, body.prefix());
+        request_body::sniff_model(prefix.as_ref())
+            .map(|model| serde_json::json!({ "model": model }))
+    });
     let requested_model_info = infer_requested_model_info(
         &forwarded_path,
         query.as_deref(),
-        introspection_json.as_ref(),
+        introspection_json.as_ref().or(sniffed_model_json.as_ref()),
     );
     let requested_model = requested_model_info.model;
     let requested_model_location = requested_model_info.location;
+    attribution.estimated_input_tokens = introspection_json
+        .as_ref()
+        .map(|json| token_estimate::estimate(json, requested_model.as_deref()));
 
     let settings_cfg = settings::read(&state.app).ok();
     let intercept_warmup = settings_cfg
@@ -196,7 +323,7 @@ pub(in crate::gateway) async fn proxy_impl(
         .map(|cfg| cfg.enable_thinking_signature_rectifier)
         .unwrap_or(true);
     let enable_thinking_signature_rectifier =
-        enable_thinking_signature_rectifier && !is_claude_count_tokens;
+        enable_thinking_signature_rectifier && !is_claude_count_tokens && streaming_body.is_none();
     let enable_response_fixer = settings_cfg
         .as_ref()
         .map(|cfg| cfg.enable_response_fixer)
@@ -213,6 +340,14 @@ pub(in crate::gateway) async fn proxy_impl(
         .as_ref()
         .map(|cfg| cfg.response_fixer_fix_truncated_json)
         .unwrap_or(true);
+    let response_fixer_fix_tool_args = settings_cfg
+        .as_ref()
+        .map(|cfg| cfg.response_fixer_fix_tool_args)
+        .unwrap_or(false);
+    let response_fixer_dry_run = settings_cfg
+        .as_ref()
+        .map(|cfg| cfg.response_fixer_dry_run)
+        .unwrap_or(false);
     let response_fixer_max_json_depth = settings_cfg
         .as_ref()
         .map(|cfg| cfg.response_fixer_max_json_depth)
@@ -225,15 +360,45 @@ pub(in crate::gateway) async fn proxy_impl(
         .as_ref()
         .map(|cfg| cfg.provider_base_url_ping_cache_ttl_seconds)
         .unwrap_or(settings::DEFAULT_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS);
+    let response_cache_ttl_seconds = settings_cfg
+        .as_ref()
+        .map(|cfg| cfg.response_cache_ttl_seconds)
+        .unwrap_or(settings::DEFAULT_RESPONSE_CACHE_TTL_SECONDS);
+    let response_cache_completions = settings_cfg
+        .as_ref()
+        .is_some_and(|cfg| cfg.response_cache_completions);
+    // A streamed request body is only fingerprinted by its prefix: never cache it.
+    let response_cache_kind = streaming_body
+        .is_none()
+        .then(|| {
+            response_cache::cacheable_kind(&method, &forwarded_path, introspection_json.as_ref())
+        })
+        .flatten()
+        .filter(|kind| {
+            *kind != response_cache::CacheableKind::Completion || response_cache_completions
+        });
+    let response_cache_ttl = response_cache_kind
+        .map(|_| response_cache_ttl_seconds)
+        .filter(|secs| *secs > 0)
+        .map(|secs| Duration::from_secs(u64::from(secs)));
     let enable_codex_session_id_completion = settings_cfg
         .as_ref()
         .map(|cfg| cfg.enable_codex_session_id_completion)
         .unwrap_or(true);
+    let trace_dedup_ttl_secs = i64::from(
+        settings_cfg
+            .as_ref()
+            .map(|cfg| cfg.recent_error_cache)
+            .unwrap_or_default()
+            .trace_dedup_ttl_seconds,
+    );
 
     let response_fixer_stream_config = response_fixer::ResponseFixerConfig {
         fix_encoding: response_fixer_fix_encoding,
         fix_sse_format: response_fixer_fix_sse_format,
         fix_truncated_json: response_fixer_fix_truncated_json,
+        fix_tool_args: response_fixer_fix_tool_args,
+        dry_run: response_fixer_dry_run,
         max_json_depth: response_fixer_max_json_depth as usize,
         max_fix_size: response_fixer_max_fix_size as usize,
     };
@@ -241,6 +406,8 @@ pub(in crate::gateway) async fn proxy_impl(
         fix_encoding: response_fixer_fix_encoding,
         fix_sse_format: false,
         fix_truncated_json: response_fixer_fix_truncated_json,
+        fix_tool_args: response_fixer_fix_tool_args,
+        dry_run: response_fixer_dry_run,
         max_json_depth: response_fixer_max_json_depth as usize,
         max_fix_size: response_fixer_max_fix_size as usize,
     };
@@ -251,20 +418,36 @@ pub(in crate::gateway) async fn proxy_impl(
     } else {
         false
     };
+    let intercepted = if is_warmup_request {
+        Some(warmup::warmup_intercepted_response(
+            requested_model.as_deref(),
+            &trace_id,
+        ))
+    } else {
+        settings_cfg
+            .as_ref()
+            .and_then(|cfg| {
+                intercept::find_matching_rule(
+                    &cfg.intercept_rules,
+                    &cli_key,
+                    &method_hint,
+                    &forwarded_path,
+                    introspection_json.as_ref(),
+                )
+            })
+            .map(|rule| {
+                intercept::render_rule_response(
+                    rule,
+                    requested_model.as_deref(),
+                    &trace_id,
+                    created_at,
+                )
+            })
+    };
 
-    if is_warmup_request {
+    if let Some(intercepted) = intercepted {
         let duration_ms = started.elapsed().as_millis();
-        let response_body =
-            warmup::build_warmup_response_body(requested_model.as_deref(), &trace_id);
-
-        let special_settings_json = serde_json::json!([{
-            "type": "warmup_intercept",
-            "scope": "request",
-            "hit": true,
-            "reason": "anthropic_warmup_intercepted",
-            "note": "已由 aio-coding-hub 抢答，未转发上游；写入日志但排除统计",
-        }])
-        .to_string();
+        let special_settings_json = serde_json::json!([intercepted.special_setting]).to_string();
 
         emit_request_start_event(
             &state.app,
@@ -276,12 +459,12 @@ pub(in crate::gateway) async fn proxy_impl(
             requested_model.clone(),
             created_at,
         );
-        let warmup_attempts = [super::super::events::FailoverAttempt {
+        let intercept_attempts = [super::super::events::FailoverAttempt {
             provider_id: 0,
-            provider_name: "Warmup".to_string(),
-            base_url: "/__aio__/warmup".to_string(),
+            provider_name: intercepted.provider_name.to_string(),
+            base_url: intercepted.meta_url.to_string(),
             outcome: "success".to_string(),
-            status: Some(StatusCode::OK.as_u16()),
+            status: Some(intercepted.status.as_u16()),
             provider_index: None,
             retry_index: None,
             session_reuse: Some(false),
@@ -305,13 +488,13 @@ pub(in crate::gateway) async fn proxy_impl(
             path: forwarded_path.as_str(),
             query: query.as_deref(),
             excluded_from_stats: true,
-            status: Some(StatusCode::OK.as_u16()),
+            status: Some(intercepted.status.as_u16()),
             error_category: None,
             error_code: None,
             duration_ms,
             event_ttfb_ms: Some(duration_ms),
             log_ttfb_ms: Some(duration_ms),
-            attempts: &warmup_attempts,
+            attempts: &intercept_attempts,
             special_settings_json: Some(special_settings_json),
             session_id: None,
             requested_model: requested_model.clone(),
@@ -328,15 +511,17 @@ pub(in crate::gateway) async fn proxy_impl(
                 cache_creation_1h_input_tokens: Some(0),
             }),
             usage: None,
+            attribution: attribution.clone(),
         });
 
-        let mut resp = (StatusCode::OK, Json(response_body)).into_response();
+        let mut resp = (intercepted.status, intercepted.body).into_response();
+        if let Ok(v) = HeaderValue::from_str(&intercepted.content_type) {
+            resp.headers_mut().insert(header::CONTENT_TYPE, v);
+        }
         resp.headers_mut().insert(
-            header::CONTENT_TYPE,
-            HeaderValue::from_static("application/json; charset=utf-8"),
+            "x-aio-intercepted",
+            HeaderValue::from_static(intercepted.tag),
         );
-        resp.headers_mut()
-            .insert("x-aio-intercepted", HeaderValue::from_static("warmup"));
         resp.headers_mut().insert(
             "x-aio-intercepted-by",
             HeaderValue::from_static("aio-coding-hub"),
@@ -346,14 +531,62 @@ pub(in crate::gateway) async fn proxy_impl(
         }
         resp.headers_mut().insert(
             "x-aio-upstream-meta-url",
-            HeaderValue::from_static("/__aio__/warmup"),
+            HeaderValue::from_static(intercepted.meta_url),
         );
         return resp;
     }
 
     let special_settings: Arc<Mutex<Vec<serde_json::Value>>> = Arc::new(Mutex::new(Vec::new()));
+    if let Some(body) = streaming_body.as_ref() {
+        if let Ok(mut settings) = special_settings.lock() {
+            settings.push(serde_json::json!({
+                "type": "request_body_streaming",
+                "scope": "request",
+                "hit": true,
+                "bufferedBytes": body.prefix().len(),
+                "maxBytes": request_body::MAX_STREAMING_REQUEST_BODY_BYTES,
+                "sniffedModel": requested_model.as_deref(),
+            }));
+        }
+    }
 
     let mut strip_request_content_encoding_seed = false;
+    if let Some(cfg) = settings_cfg
+        .as_ref()
+        .filter(|cfg| cfg.enable_request_redaction)
+    {
+        if let Some(root) = introspection_json.as_mut() {
+            let result = redaction::redact_request_json(
+                root,
+                &cfg.request_redaction_builtins,
+                &cfg.request_redaction_custom_patterns,
+            );
+            if result.total > 0 {
+                if let Ok(next) = serde_json::to_vec(root) {
+                    body_bytes = Bytes::from(next);
+                    strip_request_content_encoding_seed = true;
+                }
+                if let Ok(mut settings) = special_settings.lock() {
+                    settings.push(serde_json::json!({
+                        "type": "request_redaction",
+                        "scope": "request",
+                        "hit": true,
+                        "total": result.total,
+                        "counts": result.counts,
+                    }));
+                }
+            }
+        }
+    }
+
+    // Passthrough providers receive the request as the client sent it, apart from redaction.
+    let mut client_headers = headers.clone();
+    if strip_request_content_encoding_seed {
+        client_headers.remove(header::CONTENT_ENCODING);
+    }
+    let client_body_bytes = body_bytes.clone();
+
+    let mut codex_session_completion: Option<codex_session_id::CodexSessionCompletionResult> = None;
     if cli_key == "codex" && enable_codex_session_id_completion {
         let mut cache = state
             .codex_session_cache
@@ -388,6 +621,35 @@ pub(in crate::gateway) async fn proxy_impl(
                 "changedBody": result.changed_body,
             }));
         }
+
+        if result.applied {
+            codex_session_completion = Some(result);
+        }
+    }
+
+    if cli_key == "claude"
+        && !is_claude_count_tokens
+        && settings_cfg
+            .as_ref()
+            .is_some_and(|cfg| cfg.enable_prompt_cache_injection)
+    {
+        if let Some(root) = introspection_json.as_mut() {
+            let result = prompt_cache::inject_cache_control(root);
+            if !result.positions.is_empty() {
+                if let Ok(next) = serde_json::to_vec(root) {
+                    body_bytes = Bytes::from(next);
+                    strip_request_content_encoding_seed = true;
+                }
+                if let Ok(mut settings) = special_settings.lock() {
+                    settings.push(serde_json::json!({
+                        "type": "prompt_cache_injection",
+                        "scope": "request",
+                        "hit": true,
+                        "positions": result.positions,
+                    }));
+                }
+            }
+        }
     }
 
     let session_id = session_manager::SessionManager::extract_session_id_from_json(
@@ -405,8 +667,26 @@ pub(in crate::gateway) async fn proxy_impl(
         should_reuse_provider(introspection_json.as_ref())
     };
 
+    let lane_classification = settings_cfg
+        .as_ref()
+        .filter(|cfg| cfg.priority_lanes_enabled)
+        .map(|_| priority_lane::classify_request(&cli_key, &headers, introspection_json.as_ref()));
+    let background_lane_provider_id = match lane_classification {
+        Some(c) if c.lane == RequestLane::Background => settings_cfg
+            .as_ref()
+            .and_then(|cfg| cfg.background_lane_provider_id),
+        _ => None,
+    };
+    // 后台请求改走指定供应商时不参与会话粘性，避免把主对话绑定到该供应商
+    let (session_id, allow_session_reuse) = if background_lane_provider_id.is_some() {
+        (None, false)
+    } else {
+        (session_id, allow_session_reuse)
+    };
+
     let respond_invalid_cli_key = |err: String| -> Response {
         let resp = error_response(
+            &cli_key,
             StatusCode::BAD_REQUEST,
             trace_id.clone(),
             "GW_INVALID_CLI_KEY",
@@ -438,6 +718,7 @@ pub(in crate::gateway) async fn proxy_impl(
             usage_metrics: None,
             log_usage_metrics: None,
             usage: None,
+            attribution: attribution.clone(),
         });
 
         resp
@@ -449,27 +730,137 @@ pub(in crate::gateway) async fn proxy_impl(
             .get_bound_sort_mode_id(&cli_key, sid, created_at)
     });
 
-    let (effective_sort_mode_id, mut providers) = match bound_sort_mode_id {
-        Some(sort_mode_id) => {
-            let providers = match providers::list_enabled_for_gateway_in_mode(
-                &state.db,
-                &cli_key,
-                sort_mode_id,
-            ) {
-                Ok(v) => v,
-                Err(err) => return respond_invalid_cli_key(err),
-            };
-            (sort_mode_id, providers)
+    // 供应商 / 排序模式读自内存快照（写入时失效），避免每个请求都查询 SQLite
+    let snapshot = match bound_sort_mode_id {
+        Some(sort_mode_id) => provider_snapshot::in_mode(&state.db, &cli_key, sort_mode_id),
+        None => provider_snapshot::active(&state.db, &cli_key),
+    };
+    let snapshot = match snapshot {
+        Ok(v) => v,
+        Err(err) => return respond_invalid_cli_key(err),
+    };
+    let effective_sort_mode_id = snapshot.sort_mode_id;
+    let mut providers = snapshot.providers.clone();
+
+    // 排序模式可覆盖部分全局设置（重试次数 / 超时 / response fixer）；会话绑定的模式同样适用
+    let sort_mode_settings = snapshot.mode_settings;
+    if !sort_mode_settings.is_empty() {
+        if let Ok(mut settings) = special_settings.lock() {
+            settings.push(serde_json::json!({
+                "type": "sort_mode_settings",
+                "scope": "request",
+                "hit": true,
+                "sortModeId": effective_sort_mode_id,
+                "overrides": sort_mode_settings,
+            }));
         }
-        None => {
-            let selection =
-                match providers::list_enabled_for_gateway_using_active_mode(&state.db, &cli_key) {
-                    Ok(v) => v,
-                    Err(err) => return respond_invalid_cli_key(err),
-                };
-            (selection.sort_mode_id, selection.providers)
+    }
+    let enable_response_fixer = sort_mode_settings
+        .enable_response_fixer
+        .unwrap_or(enable_response_fixer);
+
+    // 排序模式负载均衡（加权随机 / 轮询）：只决定新请求的初始顺序，已绑定会话随后按绑定顺序重排
+    let mut load_balanced = false;
+    if let Some(mode_id) = effective_sort_mode_id {
+        let routing = &snapshot.routing;
+        if load_balance::apply(&cli_key, mode_id, routing, &mut providers) {
+            load_balanced = true;
+            if let Ok(mut settings) = special_settings.lock() {
+                settings.push(serde_json::json!({
+                    "type": "sort_mode_strategy",
+                    "scope": "request",
+                    "hit": true,
+                    "sortModeId": mode_id,
+                    "strategy": routing.strategy.as_str(),
+                    "firstProviderId": providers.first().map(|p| p.id),
+                }));
+            }
         }
-    };
+    }
+
+    // 延迟路由：按近期 TTFB 把最快的 Provider 提前；显式的负载均衡策略优先
+    if let Some(cfg) = settings_cfg.as_ref().filter(|_| !load_balanced) {
+        if let Some(scores) =
+            latency_routing::apply(&state.db, &cli_key, cfg.latency_routing, &mut providers)
+        {
+            if let Ok(mut settings) = special_settings.lock() {
+                settings.push(serde_json::json!({
+                    "type": "latency_routing",
+                    "scope": "request",
+                    "hit": true,
+                    "windowMinutes": cfg.latency_routing.window_minutes,
+                    "firstProviderId": providers.first().map(|p| p.id),
+                    "scoresMs": scores
+                        .iter()
+                        .map(|(id, score)| (id.to_string(), score.round() as i64))
+                        .collect::<std::collections::HashMap<_, _>>(),
+                }));
+            }
+        }
+    }
+
+    // 灰度 Provider：按比例放到首位，其余请求将其排到末尾（仅作兜底）；会话绑定顺序后保持同一分组
+    let canary = snapshot.canary.as_ref().filter(|canary| {
+        provider_canary::still_running(&state.db, canary).unwrap_or_else(|err| {
+            tracing::warn!("Provider 灰度结算失败: {}", err);
+            false
+        })
+    });
+    if let Some(canary) = canary {
+        if let Some(idx) = providers.iter().position(|p| p.id == canary.provider_id) {
+            let bucket_key = session_id.as_deref().unwrap_or(trace_id.as_str());
+            let selected = provider_canary::in_canary_bucket(canary.traffic_percent, bucket_key);
+            let chosen = providers.remove(idx);
+            if selected {
+                providers.insert(0, chosen);
+            } else {
+                providers.push(chosen);
+            }
+            if let Ok(mut settings) = special_settings.lock() {
+                settings.push(serde_json::json!({
+                    "type": "provider_canary",
+                    "scope": "request",
+                    "hit": selected,
+                    "canaryId": canary.id,
+                    "providerId": canary.provider_id,
+                    "trafficPercent": canary.traffic_percent,
+                }));
+            }
+        }
+    }
+
+    // A/B 对比：按会话交替分配到 A / B 两组，本组 Provider 放首位、另一组放末尾
+    if let (Some(sid), Some(test)) = (session_id.as_deref(), snapshot.ab_test.as_ref()) {
+        match provider_ab_test::assign_session(&state.db, test, sid) {
+            Ok(Some(arm)) => {
+                let (preferred_id, other_id) = test.arm_provider_ids(arm);
+                let has_both = providers.iter().any(|p| p.id == preferred_id)
+                    && providers.iter().any(|p| p.id == other_id);
+                if has_both {
+                    if let Some(idx) = providers.iter().position(|p| p.id == other_id) {
+                        let other = providers.remove(idx);
+                        providers.push(other);
+                    }
+                    if let Some(idx) = providers.iter().position(|p| p.id == preferred_id) {
+                        let preferred = providers.remove(idx);
+                        providers.insert(0, preferred);
+                    }
+                    if let Ok(mut settings) = special_settings.lock() {
+                        settings.push(serde_json::json!({
+                            "type": "provider_ab_test",
+                            "scope": "request",
+                            "hit": true,
+                            "testId": test.id,
+                            "arm": arm,
+                            "providerId": preferred_id,
+                        }));
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(err) => tracing::warn!("Provider A/B 对比分配失败: {}", err),
+        }
+    }
 
     let mut bound_provider_order: Option<Vec<i64>> = None;
     if let Some(sid) = session_id.as_deref() {
@@ -516,6 +907,7 @@ pub(in crate::gateway) async fn proxy_impl(
     if providers.is_empty() {
         let message = format!("no enabled provider for cli_key={cli_key}");
         let resp = error_response(
+            &cli_key,
             StatusCode::SERVICE_UNAVAILABLE,
             trace_id.clone(),
             "GW_NO_ENABLED_PROVIDER",
@@ -546,6 +938,60 @@ pub(in crate::gateway) async fn proxy_impl(
             usage_metrics: None,
             log_usage_metrics: None,
             usage: None,
+            attribution: attribution.clone(),
+        })
+        .await;
+        return resp;
+    }
+
+    // 流式 body 无法改写：需要改写 body 的隐私功能启用时拒绝，而不是静默跳过
+    let privacy_rewrite = streaming_body.as_ref().and_then(|_| {
+        request_body::privacy_rewrite_required(
+            settings_cfg
+                .as_ref()
+                .is_some_and(|cfg| cfg.enable_request_redaction),
+            &cli_key,
+            &forwarded_path,
+            &providers,
+        )
+    });
+    if let Some(feature) = privacy_rewrite {
+        let message = format!(
+            "request body exceeds {MAX_REQUEST_BODY_BYTES} bytes and cannot be rewritten for {feature}; shorten the request or disable {feature}"
+        );
+        let resp = error_response(
+            &cli_key,
+            StatusCode::PAYLOAD_TOO_LARGE,
+            trace_id.clone(),
+            "GW_BODY_TOO_LARGE",
+            message,
+            vec![],
+        );
+        let duration_ms = started.elapsed().as_millis();
+        emit_request_event_and_enqueue_request_log(RequestEndArgs {
+            deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
+            trace_id: trace_id.as_str(),
+            cli_key: cli_key.as_str(),
+            method: method_hint.as_str(),
+            path: forwarded_path.as_str(),
+            query: query.as_deref(),
+            excluded_from_stats: false,
+            status: Some(StatusCode::PAYLOAD_TOO_LARGE.as_u16()),
+            error_category: None,
+            error_code: Some("GW_BODY_TOO_LARGE"),
+            duration_ms,
+            event_ttfb_ms: None,
+            log_ttfb_ms: None,
+            attempts: &[],
+            special_settings_json: response_fixer::special_settings_json(&special_settings),
+            session_id,
+            requested_model,
+            created_at_ms,
+            created_at,
+            usage_metrics: None,
+            log_usage_metrics: None,
+            usage: None,
+            attribution: attribution.clone(),
         })
         .await;
         return resp;
@@ -585,6 +1031,40 @@ pub(in crate::gateway) async fn proxy_impl(
         }
     }
 
+    // 前缀粘性：没有会话绑定的 Provider 时，相同 prompt 前缀优先交给上次成功服务它的 Provider（提高上游缓存命中）
+    let prefix_affinity_key = if allow_session_reuse && session_bound_provider_id.is_none() {
+        introspection_json.as_ref().and_then(|root| {
+            prefix_affinity::prefix_key(&cli_key, requested_model.as_deref(), root)
+        })
+    } else {
+        None
+    };
+    if let Some(key) = prefix_affinity_key {
+        let preferred = prefix_affinity::lookup(key, created_at)
+            .and_then(|provider_id| providers.iter().position(|p| p.id == provider_id));
+        if let Some(idx) = preferred.filter(|idx| *idx > 0) {
+            let chosen = providers.remove(idx);
+            providers.insert(0, chosen);
+        }
+        if let Ok(mut settings) = special_settings.lock() {
+            settings.push(serde_json::json!({
+                "type": "prefix_affinity",
+                "scope": "request",
+                "hit": preferred.is_some(),
+                "providerId": preferred.map(|_| providers[0].id),
+            }));
+        }
+    }
+
+    if let Some(provider_id) = background_lane_provider_id {
+        if let Some(idx) = providers.iter().position(|p| p.id == provider_id) {
+            if idx > 0 {
+                let chosen = providers.remove(idx);
+                providers.insert(0, chosen);
+            }
+        }
+    }
+
     let (unavailable_fingerprint_key, unavailable_fingerprint_debug) =
         compute_all_providers_unavailable_fingerprint(
             &cli_key,
@@ -595,7 +1075,11 @@ pub(in crate::gateway) async fn proxy_impl(
 
     let idempotency_key_hash = extract_idempotency_key_hash(&headers);
 
-    let introspection_body = body_for_introspection(&headers, &body_bytes);
+    let fingerprint_body = streaming_body
+        .as_ref()
+        .map(|body| body.prefix())
+        .unwrap_or(&body_bytes);
+    let introspection_body = body_for_introspection(&headers, fingerprint_body);
     let (fingerprint_key, fingerprint_debug) = compute_request_fingerprint(
         &cli_key,
         &method_hint,
@@ -607,17 +1091,22 @@ pub(in crate::gateway) async fn proxy_impl(
         introspection_body.as_ref(),
     );
 
+    // 流式 body 的指纹只覆盖前缀：只看与 body 无关的「全部不可用」缓存，不按请求指纹复用错误与 trace_id
+    let body_fingerprinted = streaming_body.is_none();
     if let Ok(mut cache) = state.recent_errors.lock() {
         let now_unix = now_unix_seconds() as i64;
-        let cached_error = cache
-            .get_error(now_unix, fingerprint_key, &fingerprint_debug)
-            .or_else(|| {
-                cache.get_error(
-                    now_unix,
-                    unavailable_fingerprint_key,
-                    &unavailable_fingerprint_debug,
-                )
-            });
+        let cached_error = if body_fingerprinted {
+            cache.get_error(now_unix, fingerprint_key, &fingerprint_debug)
+        } else {
+            None
+        }
+        .or_else(|| {
+            cache.get_error(
+                now_unix,
+                unavailable_fingerprint_key,
+                &unavailable_fingerprint_debug,
+            )
+        });
 
         if let Some(entry) = cached_error {
             let any_allowed = providers
@@ -625,14 +1114,17 @@ pub(in crate::gateway) async fn proxy_impl(
                 .any(|p| state.circuit.should_allow(p.id, now_unix).allow);
             if !any_allowed {
                 trace_id = entry.trace_id.clone();
-                cache.upsert_trace_id(
-                    now_unix,
-                    fingerprint_key,
-                    trace_id.clone(),
-                    fingerprint_debug.clone(),
-                    RECENT_TRACE_DEDUP_TTL_SECS,
-                );
+                if body_fingerprinted {
+                    cache.upsert_trace_id(
+                        now_unix,
+                        fingerprint_key,
+                        trace_id.clone(),
+                        fingerprint_debug.clone(),
+                        trace_dedup_ttl_secs,
+                    );
+                }
                 return error_response_with_retry_after(
+                    &cli_key,
                     entry.status,
                     entry.trace_id,
                     entry.error_code,
@@ -644,21 +1136,206 @@ pub(in crate::gateway) async fn proxy_impl(
 
             cache.remove_error(fingerprint_key);
             cache.remove_error(unavailable_fingerprint_key);
-        } else if let Some(existing) =
-            cache.get_trace_id(now_unix, fingerprint_key, &fingerprint_debug)
+        } else if let Some(existing) = body_fingerprinted
+            .then(|| cache.get_trace_id(now_unix, fingerprint_key, &fingerprint_debug))
+            .flatten()
         {
             trace_id = existing;
         }
 
-        cache.upsert_trace_id(
-            now_unix,
+        if body_fingerprinted {
+            cache.upsert_trace_id(
+                now_unix,
+                fingerprint_key,
+                trace_id.clone(),
+                fingerprint_debug.clone(),
+                trace_dedup_ttl_secs,
+            );
+        }
+    }
+
+    // 流式 body 的指纹只覆盖前缀，不参与去重
+    let inflight_dedup_mode = settings_cfg
+        .as_ref()
+        .filter(|_| streaming_body.is_none())
+        .map(|cfg| cfg.inflight_dedup_mode)
+        .unwrap_or_default();
+    let inflight_leader = if inflight_dedup_mode == settings::InflightDedupMode::Off {
+        None
+    } else {
+        match state.inflight_requests.register_or_get(
+            inflight_dedup_mode,
             fingerprint_key,
-            trace_id.clone(),
-            fingerprint_debug.clone(),
-            RECENT_TRACE_DEDUP_TTL_SECS,
+            &fingerprint_debug,
+            &trace_id,
+            &special_settings,
+        ) {
+            InflightLookup::Leader(leader) => Some(leader),
+            InflightLookup::Duplicate(entry) => {
+                return respond_inflight_duplicate(
+                    &state,
+                    inflight_dedup_mode,
+                    &entry,
+                    &cli_key,
+                    &forwarded_path,
+                )
+                .await;
+            }
+        }
+    };
+
+    let max_streams = settings_cfg
+        .as_ref()
+        .map(|cfg| cfg.cli_max_concurrent_streams.for_cli(&cli_key))
+        .unwrap_or(0);
+    let stream_slot = if max_streams > 0
+        && stream_cap::is_streaming_request(&forwarded_path, introspection_json.as_ref())
+    {
+        match state.stream_caps.try_acquire(&cli_key, max_streams) {
+            Ok(slot) => Some(slot),
+            Err(active) => {
+                emit_gateway_log(
+                    &state.app,
+                    "warn",
+                    "GW_CLI_STREAM_CAP",
+                    format!(
+                        "并发流已达上限，已本地拒绝 cli={cli_key} path={forwarded_path} active={active} max={max_streams}"
+                    ),
+                );
+                let resp = error_response_with_retry_after(
+                    &cli_key,
+                    StatusCode::TOO_MANY_REQUESTS,
+                    trace_id,
+                    "GW_CLI_STREAM_CAP",
+                    format!("{cli_key} concurrent streams limit reached ({active}/{max_streams})"),
+                    vec![],
+                    Some(STREAM_CAP_RETRY_AFTER_SECONDS),
+                );
+                return match inflight_leader {
+                    Some(leader) => leader.publish(resp),
+                    None => resp,
+                };
+            }
+        }
+    } else {
+        None
+    };
+
+    let background_slot = match lane_classification {
+        Some(c) if c.lane == RequestLane::Background => {
+            let (max_concurrency, queue_timeout_secs) = settings_cfg
+                .as_ref()
+                .map(|cfg| {
+                    (
+                        cfg.background_lane_max_concurrency,
+                        cfg.background_lane_queue_timeout_seconds,
+                    )
+                })
+                .unwrap_or_default();
+            let slot = state
+                .priority_lanes
+                .acquire_background(
+                    max_concurrency,
+                    Duration::from_secs(queue_timeout_secs as u64),
+                )
+                .await;
+            if let Ok(mut settings) = special_settings.lock() {
+                settings.push(serde_json::json!({
+                    "type": "priority_lane",
+                    "scope": "request",
+                    "hit": true,
+                    "lane": c.lane.as_str(),
+                    "reason": c.reason,
+                    "queuedMs": slot.queued_ms,
+                    "queueTimedOut": slot.queue_timed_out,
+                    "preferredProviderId": background_lane_provider_id,
+                }));
+            }
+            if slot.queue_timed_out {
+                emit_gateway_log(
+                    &state.app,
+                    "warn",
+                    "GW_BACKGROUND_LANE_QUEUE_TIMEOUT",
+                    format!(
+                        "后台请求排队超时，已直接转发 cli={cli_key} path={forwarded_path} reason={} queued_ms={}",
+                        c.reason, slot.queued_ms
+                    ),
+                );
+            }
+            Some(slot)
+        }
+        _ => None,
+    };
+
+    let tool_calls = introspection_json
+        .as_ref()
+        .map(tool_usage::extract_completed_tool_calls)
+        .unwrap_or_default();
+    if !tool_calls.is_empty() {
+        let db = state.db.clone();
+        let cli_key = cli_key.clone();
+        let session_id = session_id.clone();
+        std::mem::drop(tauri::async_runtime::spawn_blocking(move || {
+            let records: Vec<usage_stats::ToolUsageRecord> = tool_calls
+                .into_iter()
+                .map(|call| usage_stats::ToolUsageRecord {
+                    call_id: call.call_id,
+                    tool_name: call.tool_name,
+                })
+                .collect();
+            if let Err(err) = usage_stats::record_tool_usage(
+                &db,
+                created_at,
+                &cli_key,
+                session_id.as_deref(),
+                &records,
+            ) {
+                tracing::warn!("工具使用统计写入失败: {}", err);
+            }
+        }));
+    }
+
+    if let Some(result) = codex_session_completion {
+        let completion = usage_stats::CodexSessionCompletionRecord {
+            trace_id: trace_id.clone(),
+            session_id: result.session_id,
+            action: result.action,
+            source: result.source,
+            changed_header: result.changed_headers,
+            changed_body: result.changed_body,
+        };
+        let db = state.db.clone();
+        std::mem::drop(tauri::async_runtime::spawn_blocking(move || {
+            if let Err(err) =
+                usage_stats::record_codex_session_completion(&db, created_at, &completion)
+            {
+                tracing::warn!("codex session_id 补全记录写入失败: {}", err);
+            }
+        }));
+    }
+
+    if settings_cfg
+        .as_ref()
+        .is_some_and(|cfg| cfg.forward_trace_headers)
+    {
+        trace_headers::apply_upstream(
+            &mut headers,
+            &trace_id,
+            attribution.client_trace_id.as_deref(),
         );
     }
 
+    let active_request = state.active_requests.register(
+        &trace_id,
+        &cli_key,
+        &method_hint,
+        &forwarded_path,
+        created_at_ms,
+        special_settings.clone(),
+    );
+    let cancel_cli_key = cli_key.clone();
+    let cancel_trace_id = trace_id.clone();
+
     emit_request_start_event(
         &state.app,
         trace_id.clone(),
@@ -671,8 +1348,8 @@ pub(in crate::gateway) async fn proxy_impl(
     );
 
     let (
-        mut max_attempts_per_provider,
-        mut max_providers_to_try,
+        max_attempts_per_provider,
+        max_providers_to_try,
         provider_cooldown_secs,
         upstream_first_byte_timeout_secs,
         upstream_stream_idle_timeout_secs,
@@ -695,13 +1372,36 @@ pub(in crate::gateway) async fn proxy_impl(
             settings::DEFAULT_UPSTREAM_REQUEST_TIMEOUT_NON_STREAMING_SECONDS,
         ),
     };
+    let mut max_attempts_per_provider = sort_mode_settings
+        .failover_max_attempts_per_provider
+        .unwrap_or(max_attempts_per_provider);
+    let mut max_providers_to_try = sort_mode_settings
+        .failover_max_providers_to_try
+        .unwrap_or(max_providers_to_try);
+    let upstream_first_byte_timeout_secs = sort_mode_settings
+        .upstream_first_byte_timeout_seconds
+        .unwrap_or(upstream_first_byte_timeout_secs);
+    let upstream_stream_idle_timeout_secs = sort_mode_settings
+        .upstream_stream_idle_timeout_seconds
+        .unwrap_or(upstream_stream_idle_timeout_secs);
+    let upstream_request_timeout_non_streaming_secs = sort_mode_settings
+        .upstream_request_timeout_non_streaming_seconds
+        .unwrap_or(upstream_request_timeout_non_streaming_secs);
 
     if is_claude_count_tokens {
         max_attempts_per_provider = 1;
         max_providers_to_try = 1;
     }
+    // 流式 body 只能发送一次，且无法做协议转换：仅尝试首个原生协议的 Provider
+    if streaming_body.is_some() {
+        max_attempts_per_provider = 1;
+        max_providers_to_try = 1;
+        providers.retain(|p| {
+            ProtocolBridge::for_request(&cli_key, &forwarded_path, p.wire_api).is_none()
+        });
+    }
 
-    super::forwarder::forward(RequestContext::from_handler_parts(RequestContextParts {
+    let request_ctx = RequestContext::from_handler_parts(RequestContextParts {
         state,
         cli_key,
         forwarded_path,
@@ -715,21 +1415,33 @@ pub(in crate::gateway) async fn proxy_impl(
         session_id,
         requested_model,
         requested_model_location,
+        attribution,
         effective_sort_mode_id,
         providers,
         session_bound_provider_id,
+        prefix_affinity_key,
         headers,
         body_bytes,
+        client_headers,
+        client_body_bytes,
+        streaming_body,
         introspection_json,
         strip_request_content_encoding_seed,
         special_settings,
         provider_base_url_ping_cache_ttl_seconds,
+        response_cache_ttl,
         max_attempts_per_provider,
         max_providers_to_try,
         provider_cooldown_secs,
         upstream_first_byte_timeout_secs,
         upstream_stream_idle_timeout_secs,
         upstream_request_timeout_non_streaming_secs,
+        hedge_delay: settings_cfg
+            .as_ref()
+            .and_then(|cfg| cfg.hedged_requests.delay()),
+        stream_tool_pause_idle_timeout: settings_cfg
+            .as_ref()
+            .and_then(|cfg| cfg.stream_tool_pause_grace.idle_timeout()),
         fingerprint_key,
         fingerprint_debug,
         unavailable_fingerprint_key,
@@ -738,6 +1450,93 @@ pub(in crate::gateway) async fn proxy_impl(
         enable_response_fixer,
         response_fixer_stream_config,
         response_fixer_non_stream_config,
-    }))
-    .await
+        retry_backoff: settings_cfg
+            .as_ref()
+            .map(|cfg| cfg.retry_backoff.clone())
+            .unwrap_or_default(),
+        sse_capture: SseCaptureMode::from_settings(settings_cfg.as_ref()),
+        header_sanitize_rules: settings_cfg
+            .as_ref()
+            .map(|cfg| cfg.header_sanitize_rules.clone())
+            .unwrap_or_default(),
+        failover_rules: settings_cfg
+            .as_ref()
+            .map(|cfg| cfg.failover_rules.clone())
+            .unwrap_or_default(),
+        egress_network: settings_cfg
+            .map(|cfg| cfg.egress_network)
+            .unwrap_or_default(),
+        active_request: active_request.entry(),
+    });
+    let forward = super::forwarder::forward(request_ctx);
+    // Cancelling drops the forward future: the upstream request is aborted and the abort guard logs it.
+    let resp = tokio::select! {
+        resp = forward => resp,
+        _ = active_request.entry().cancelled() => error_response(
+            &cancel_cli_key,
+            StatusCode::from_u16(499).unwrap_or(StatusCode::BAD_REQUEST),
+            cancel_trace_id,
+            REQUEST_CANCELLED_CODE,
+            REQUEST_CANCELLED_MESSAGE.to_string(),
+            vec![],
+        ),
+    };
+    let cancel_tail = is_event_stream(resp.headers()).then(|| cancelled_sse_event(&cancel_cli_key));
+    let resp = active_request.hold_until_body_end(resp, cancel_tail);
+
+    let resp = match background_slot {
+        Some(slot) => slot.hold_until_body_end(resp),
+        None => resp,
+    };
+    let resp = match stream_slot {
+        Some(slot) => slot.hold_until_body_end(resp),
+        None => resp,
+    };
+    match inflight_leader {
+        Some(leader) => leader.publish(resp),
+        None => resp,
+    }
+}
+
+async fn respond_inflight_duplicate(
+    state: &GatewayAppState,
+    mode: settings::InflightDedupMode,
+    entry: &InflightEntry,
+    cli_key: &str,
+    forwarded_path: &str,
+) -> Response {
+    if mode == settings::InflightDedupMode::Attach {
+        if let Some(resp) = entry.attach().await {
+            emit_gateway_log(
+                &state.app,
+                "info",
+                "GW_INFLIGHT_ATTACHED",
+                format!(
+                    "相同请求仍在进行中，已挂载到原请求的上游响应 cli={cli_key} path={forwarded_path} trace_id={}",
+                    entry.trace_id
+                ),
+            );
+            return resp;
+        }
+    }
+
+    entry.record_rejected();
+    emit_gateway_log(
+        &state.app,
+        "warn",
+        "GW_INFLIGHT_DUPLICATE",
+        format!(
+            "相同请求仍在进行中，已在本地拒绝重复请求 cli={cli_key} path={forwarded_path} trace_id={}",
+            entry.trace_id
+        ),
+    );
+    error_response(
+        &cli_key,
+        StatusCode::CONFLICT,
+        entry.trace_id.clone(),
+        "GW_INFLIGHT_DUPLICATE",
+        "an identical request is still in flight; duplicate rejected to avoid double cost"
+            .to_string(),
+        vec![],
+    )
 }
@@ -0,0 +1,374 @@
+//! Usage: In-flight request deduplication (identical retry while the original is still running).
+//!
+//! The first request with a given fingerprint becomes the leader; identical requests arriving before its
+//! response body finishes are either attached to the same upstream response (replayed + live chunks) or
+//! rejected locally, depending on `settings.inflight_dedup_mode`.
+//!
+//! The leader's upstream body is driven by a spawned task, so a disconnecting leader client does not cut
+//! off attached duplicates; an upstream body error is passed on to every receiver instead of a clean EOF.
+
+use axum::body::{Body, BodyDataStream, Bytes};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use futures_core::Stream;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
+
+use crate::settings::InflightDedupMode;
+
+/// Bytes kept for replaying the leader's response to late attachers; beyond this, new duplicates are rejected.
+const INFLIGHT_REPLAY_MAX_BYTES: usize = 8 * 1024 * 1024;
+/// How long an attached duplicate waits for the leader's response head.
+const ATTACH_WAIT_HEAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+// Upstream body errors are forwarded as text (`axum::Error` is not `Clone`).
+type ChunkResult = Result<Bytes, String>;
+
+#[derive(Default)]
+struct SharedResponse {
+    head: Option<(StatusCode, HeaderMap)>,
+    replay: Vec<Bytes>,
+    replay_bytes: usize,
+    // Replay buffer disabled (reject mode, or exceeded INFLIGHT_REPLAY_MAX_BYTES).
+    replay_disabled: bool,
+    done: bool,
+    subscribers: Vec<mpsc::UnboundedSender<ChunkResult>>,
+}
+
+pub(super) struct InflightEntry {
+    fingerprint_debug: String,
+    pub(super) trace_id: String,
+    special_settings: Arc<Mutex<Vec<Value>>>,
+    shared: Mutex<SharedResponse>,
+    head_ready: Notify,
+}
+
+impl InflightEntry {
+    fn record(&self, action: &'static str) {
+        if let Ok(mut settings) = self.special_settings.lock() {
+            settings.push(serde_json::json!({
+                "type": "inflight_dedup",
+                "scope": "request",
+                "hit": true,
+                "action": action,
+            }));
+        }
+    }
+
+    /// Waits for the leader's response head, then subscribes to its body (replayed from the start).
+    pub(super) async fn attach(&self) -> Option<Response> {
+        let wait = async {
+            loop {
+                let notified = self.head_ready.notified();
+                {
+                    let mut shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+                    if shared.replay_disabled {
+                        return None;
+                    }
+                    if let Some((status, headers)) = shared.head.clone() {
+                        let (tx, rx) = mpsc::unbounded_channel();
+                        for chunk in &shared.replay {
+                            let _ = tx.send(Ok(chunk.clone()));
+                        }
+                        if !shared.done {
+                            shared.subscribers.push(tx);
+                        }
+                        return Some((status, headers, rx));
+                    }
+                    if shared.done {
+                        return None;
+                    }
+                }
+                notified.await;
+            }
+        };
+
+        let (status, headers, rx) = tokio::time::timeout(ATTACH_WAIT_HEAD_TIMEOUT, wait)
+            .await
+            .ok()
+            .flatten()?;
+        self.record("attached");
+
+        let mut resp = Response::new(Body::from_stream(AttachedBodyStream { rx }));
+        *resp.status_mut() = status;
+        *resp.headers_mut() = headers;
+        Some(resp)
+    }
+
+    pub(super) fn record_rejected(&self) {
+        self.record("rejected");
+    }
+}
+
+pub(super) enum InflightLookup {
+    Leader(InflightLeader),
+    Duplicate(Arc<InflightEntry>),
+}
+
+#[derive(Default)]
+pub(in crate::gateway) struct InflightRequests {
+    entries: Mutex<HashMap<u64, Arc<InflightEntry>>>,
+}
+
+impl InflightRequests {
+    pub(super) fn register_or_get(
+        self: &Arc<Self>,
+        mode: InflightDedupMode,
+        fingerprint_key: u64,
+        fingerprint_debug: &str,
+        trace_id: &str,
+        special_settings: &Arc<Mutex<Vec<Value>>>,
+    ) -> InflightLookup {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(existing) = entries.get(&fingerprint_key) {
+            if existing.fingerprint_debug == fingerprint_debug {
+                return InflightLookup::Duplicate(existing.clone());
+            }
+        }
+
+        let entry = Arc::new(InflightEntry {
+            fingerprint_debug: fingerprint_debug.to_string(),
+            trace_id: trace_id.to_string(),
+            special_settings: special_settings.clone(),
+            shared: Mutex::new(SharedResponse {
+                replay_disabled: mode != InflightDedupMode::Attach,
+                ..Default::default()
+            }),
+            head_ready: Notify::new(),
+        });
+        entries.insert(fingerprint_key, entry.clone());
+        InflightLookup::Leader(InflightLeader {
+            registry: self.clone(),
+            fingerprint_key,
+            entry,
+        })
+    }
+}
+
+/// Registration of the first request; dropping it (or finishing its body) releases the fingerprint.
+pub(super) struct InflightLeader {
+    registry: Arc<InflightRequests>,
+    fingerprint_key: u64,
+    entry: Arc<InflightEntry>,
+}
+
+impl InflightLeader {
+    /// Publishes the response head and tees the body to attached duplicates until it completes. The
+    /// leader's own client is just the first subscriber of the spawned `drive` task.
+    pub(super) fn publish(self, resp: Response) -> Response {
+        let (tx, rx) = mpsc::unbounded_channel();
+        {
+            let mut shared = self.entry.shared.lock().unwrap_or_else(|e| e.into_inner());
+            shared.head = Some((resp.status(), resp.headers().clone()));
+            shared.subscribers.push(tx);
+        }
+        self.entry.head_ready.notify_waiters();
+
+        resp.map(|body| {
+            tokio::spawn(self.drive(body.into_data_stream()));
+            Body::from_stream(AttachedBodyStream { rx })
+        })
+    }
+
+    /// Reads the upstream body until it ends, fails, or nobody is listening any more; dropping `self`
+    /// afterwards ends the subscribers' streams and releases the fingerprint.
+    async fn drive(self, mut upstream: BodyDataStream) {
+        while let Some(next) =
+            std::future::poll_fn(|cx| Pin::new(&mut upstream).poll_next(cx)).await
+        {
+            match next {
+                Ok(chunk) => {
+                    if !self.tee(&chunk) {
+                        return;
+                    }
+                }
+                Err(err) => {
+                    self.fail(&err.to_string());
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Sends `chunk` to every subscriber; returns whether any of them is still listening.
+    fn tee(&self, chunk: &Bytes) -> bool {
+        let mut shared = self.entry.shared.lock().unwrap_or_else(|e| e.into_inner());
+        if !shared.replay_disabled {
+            shared.replay_bytes = shared.replay_bytes.saturating_add(chunk.len());
+            if shared.replay_bytes > INFLIGHT_REPLAY_MAX_BYTES {
+                // 超出回放上限：已挂载的副本继续接收实时数据，新的重复请求不再挂载
+                shared.replay_disabled = true;
+                shared.replay.clear();
+            } else {
+                shared.replay.push(chunk.clone());
+            }
+        }
+        shared
+            .subscribers
+            .retain(|tx| tx.send(Ok(chunk.clone())).is_ok());
+        !shared.subscribers.is_empty()
+    }
+
+    fn fail(&self, err: &str) {
+        let mut shared = self.entry.shared.lock().unwrap_or_else(|e| e.into_inner());
+        // 回放内容已不完整，之后的重复请求不再挂载
+        shared.replay_disabled = true;
+        for tx in shared.subscribers.drain(..) {
+            let _ = tx.send(Err(err.to_string()));
+        }
+    }
+}
+
+impl Drop for InflightLeader {
+    fn drop(&mut self) {
+        {
+            let mut shared = self.entry.shared.lock().unwrap_or_else(|e| e.into_inner());
+            shared.done = true;
+            shared.replay.clear();
+            shared.subscribers.clear();
+        }
+        self.entry.head_ready.notify_waiters();
+
+        let mut entries = self
+            .registry
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if entries
+            .get(&self.fingerprint_key)
+            .is_some_and(|current| Arc::ptr_eq(current, &self.entry))
+        {
+            entries.remove(&self.fingerprint_key);
+        }
+    }
+}
+
+struct AttachedBodyStream {
+    rx: mpsc::UnboundedReceiver<ChunkResult>,
+}
+
+impl Stream for AttachedBodyStream {
+    type Item = Result<Bytes, std::io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx
+            .poll_recv(cx)
+            .map(|item| item.map(|chunk| chunk.map_err(std::io::Error::other)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn special_settings() -> Arc<Mutex<Vec<Value>>> {
+        Arc::new(Mutex::new(Vec::new()))
+    }
+
+    #[test]
+    fn register_returns_duplicate_until_leader_dropped() {
+        let registry = Arc::new(InflightRequests::default());
+        let settings = special_settings();
+
+        let InflightLookup::Leader(leader) =
+            registry.register_or_get(InflightDedupMode::Reject, 1, "fp", "t1", &settings)
+        else {
+            panic!("first request should lead");
+        };
+        match registry.register_or_get(InflightDedupMode::Reject, 1, "fp", "t2", &settings) {
+            InflightLookup::Duplicate(entry) => assert_eq!(entry.trace_id, "t1"),
+            InflightLookup::Leader(_) => panic!("identical request should be a duplicate"),
+        }
+        assert!(matches!(
+            registry.register_or_get(InflightDedupMode::Reject, 1, "other", "t3", &settings),
+            InflightLookup::Leader(_)
+        ));
+
+        drop(leader);
+        assert!(registry.entries.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn attached_duplicate_receives_replayed_and_live_chunks() {
+        let registry = Arc::new(InflightRequests::default());
+        let settings = special_settings();
+        let InflightLookup::Leader(leader) =
+            registry.register_or_get(InflightDedupMode::Attach, 7, "fp", "t1", &settings)
+        else {
+            panic!("first request should lead");
+        };
+        let InflightLookup::Duplicate(entry) =
+            registry.register_or_get(InflightDedupMode::Attach, 7, "fp", "t1", &settings)
+        else {
+            panic!("identical request should be a duplicate");
+        };
+
+        assert!(!leader.tee(&Bytes::from_static(b"data: 1\n\n")));
+        {
+            let mut shared = entry.shared.lock().unwrap();
+            shared.head = Some((StatusCode::OK, HeaderMap::new()));
+        }
+        let resp = entry.attach().await.expect("should attach");
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(leader.tee(&Bytes::from_static(b"data: 2\n\n")));
+        drop(leader);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body.as_ref(), b"data: 1\n\ndata: 2\n\n");
+        assert_eq!(settings.lock().unwrap()[0]["action"], "attached");
+    }
+
+    #[tokio::test]
+    async fn followers_outlive_the_leader_client_and_see_upstream_errors() {
+        let registry = Arc::new(InflightRequests::default());
+        let settings = special_settings();
+        let InflightLookup::Leader(leader) =
+            registry.register_or_get(InflightDedupMode::Attach, 9, "fp", "t1", &settings)
+        else {
+            panic!("first request should lead");
+        };
+        let InflightLookup::Duplicate(entry) =
+            registry.register_or_get(InflightDedupMode::Attach, 9, "fp", "t1", &settings)
+        else {
+            panic!("identical request should be a duplicate");
+        };
+
+        let (tx, rx) = mpsc::unbounded_channel::<Result<Bytes, std::io::Error>>();
+        let upstream = Body::from_stream(AttachedUpstream { rx });
+        let leader_resp = leader.publish(Response::new(upstream));
+        let follower = entry.attach().await.expect("should attach");
+        // The leader's client goes away; the follower keeps receiving.
+        drop(leader_resp);
+
+        tx.send(Ok(Bytes::from_static(b"data: 1\n\n"))).unwrap();
+        tx.send(Err(std::io::Error::other("upstream reset")))
+            .unwrap();
+        let mut body = follower.into_body().into_data_stream();
+        let first = std::future::poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await;
+        assert_eq!(first.unwrap().unwrap().as_ref(), b"data: 1\n\n");
+        let second = std::future::poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await;
+        assert!(
+            second.unwrap().is_err(),
+            "upstream error must not look like a clean EOF"
+        );
+    }
+
+    struct AttachedUpstream {
+        rx: mpsc::UnboundedReceiver<Result<Bytes, std::io::Error>>,
+    }
+
+    impl Stream for AttachedUpstream {
+        type Item = Result<Bytes, std::io::Error>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.rx.poll_recv(cx)
+        }
+    }
+}
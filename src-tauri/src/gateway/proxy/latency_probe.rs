@@ -0,0 +1,111 @@
+//! Usage: Scheduled background base_url latency probing.
+//!
+//! When `latency_probe_interval_minutes > 0`, every enabled provider's base_urls are pinged on a
+//! fixed cadence (even without traffic), so ping-mode base_url selection finds a fresh cached
+//! result when a request arrives instead of probing inline.
+
+use crate::shared::cli_key::SUPPORTED_CLI_KEYS;
+use crate::{power, provider_snapshot, providers, settings};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use super::failover::probe_provider_base_urls;
+use crate::gateway::manager::GatewayAppState;
+use crate::gateway::util::now_unix_millis;
+
+// 设置变更在一个检查周期内生效
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+fn is_due(last_run: Option<Instant>, interval: Duration, now: Instant) -> bool {
+    match last_run {
+        Some(last) => now.saturating_duration_since(last) >= interval,
+        None => true,
+    }
+}
+
+fn enabled_providers(state: &GatewayAppState) -> Vec<providers::ProviderForGateway> {
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+    for cli_key in SUPPORTED_CLI_KEYS {
+        match provider_snapshot::in_mode(&state.db, cli_key, None) {
+            Ok(snapshot) => items.extend(
+                snapshot
+                    .providers
+                    .iter()
+                    .filter(|p| seen.insert(p.id))
+                    .cloned(),
+            ),
+            Err(err) => tracing::warn!(cli_key = %cli_key, "定时测速读取 Provider 失败: {}", err),
+        }
+    }
+    items
+}
+
+async fn probe_all(state: &GatewayAppState, interval: Duration, cache_ttl_seconds: u32) {
+    // 缓存至少保留到下一轮探测，避免两轮之间回落到请求内测速
+    let ttl = Duration::from_secs(cache_ttl_seconds.max(1) as u64).max(interval + CHECK_INTERVAL);
+    let expires_at_unix_ms = now_unix_millis().saturating_add(ttl.as_millis() as u64);
+
+    let mut probed = 0usize;
+    for provider in enabled_providers(state) {
+        if provider.base_urls.is_empty() {
+            continue;
+        }
+        let _ = probe_provider_base_urls(state, &provider, "scheduled", expires_at_unix_ms).await;
+        probed += 1;
+    }
+    tracing::debug!(providers = probed, "定时 base_url 测速完成");
+}
+
+/// Spawns the scheduler loop; the returned handle is aborted when the gateway stops.
+pub(in crate::gateway) fn spawn_scheduler(
+    state: GatewayAppState,
+) -> tauri::async_runtime::JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        let mut last_run: Option<Instant> = None;
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            let cfg = settings::read(&state.app).unwrap_or_default();
+            if cfg.latency_probe_interval_minutes == 0 {
+                last_run = None;
+                continue;
+            }
+            // 低功耗模式下暂停后台测速
+            if power::background_work_suspended(&state.app) {
+                continue;
+            }
+            let interval = Duration::from_secs(cfg.latency_probe_interval_minutes as u64 * 60);
+            let now = Instant::now();
+            if !is_due(last_run, interval, now) {
+                continue;
+            }
+            last_run = Some(now);
+            probe_all(
+                &state,
+                interval,
+                cfg.provider_base_url_ping_cache_ttl_seconds,
+            )
+            .await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_due;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn due_on_first_tick_and_after_interval() {
+        let interval = Duration::from_secs(300);
+        let start = Instant::now();
+        assert!(is_due(None, interval, start));
+        assert!(!is_due(
+            Some(start),
+            interval,
+            start + Duration::from_secs(299)
+        ));
+        assert!(is_due(Some(start), interval, start + interval));
+    }
+}
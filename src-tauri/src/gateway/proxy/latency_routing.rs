@@ -0,0 +1,177 @@
+//! Usage: Latency-based provider ordering from recent attempt TTFB.
+//!
+//! 用法：
+//! - 开启 `latency_routing.enabled` 后，按近期成功尝试的耗时（`request_attempt_logs.attempt_duration_ms`）给 Provider 打分
+//! - 只统计 `window_minutes` 内的样本；越新的样本权重越高（线性衰减到窗口边界为 0）
+//! - 分数低者优先；无样本的 Provider 保持原有相对顺序排在其后，不健康的 Provider 仍由熔断 / 冷却跳过
+//! - 分数按 cli_key 缓存，最多每 `REFRESH_INTERVAL` 从数据库重算一次
+//! - 仅作用于 `ordered` 策略的排序模式；加权随机 / 轮询的顺序优先
+
+use crate::request_attempt_logs::{self, AttemptLatencySample};
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use crate::{db, providers, settings};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_SAMPLES: usize = 2000;
+
+struct CachedScores {
+    refreshed_at: Instant,
+    window_minutes: u32,
+    scores: HashMap<i64, f64>,
+}
+
+static SCORE_CACHE: Mutex<Option<HashMap<String, CachedScores>>> = Mutex::new(None);
+
+/// Decay-weighted mean latency (ms) per provider; samples outside the window are ignored.
+fn decayed_scores(
+    samples: &[AttemptLatencySample],
+    now_unix_seconds: i64,
+    window_secs: i64,
+) -> HashMap<i64, f64> {
+    let mut sums: HashMap<i64, (f64, f64)> = HashMap::new();
+    if window_secs <= 0 {
+        return HashMap::new();
+    }
+
+    for sample in samples {
+        let age = now_unix_seconds.saturating_sub(sample.created_at).max(0);
+        if age >= window_secs || sample.duration_ms <= 0 {
+            continue;
+        }
+        let weight = 1.0 - age as f64 / window_secs as f64;
+        let entry = sums.entry(sample.provider_id).or_insert((0.0, 0.0));
+        entry.0 += weight * sample.duration_ms as f64;
+        entry.1 += weight;
+    }
+
+    sums.into_iter()
+        .filter(|(_, (_, weight))| *weight > 0.0)
+        .map(|(provider_id, (sum, weight))| (provider_id, sum / weight))
+        .collect()
+}
+
+/// Fastest first; providers without a score keep their relative order after the scored ones.
+fn latency_order(provider_ids: &[i64], scores: &HashMap<i64, f64>) -> Vec<usize> {
+    let mut scored: Vec<(usize, f64)> = Vec::new();
+    let mut unscored: Vec<usize> = Vec::new();
+    for (idx, provider_id) in provider_ids.iter().enumerate() {
+        match scores.get(provider_id) {
+            Some(score) => scored.push((idx, *score)),
+            None => unscored.push(idx),
+        }
+    }
+    scored.sort_by(|a, b| a.1.total_cmp(&b.1));
+    scored
+        .into_iter()
+        .map(|(idx, _)| idx)
+        .chain(unscored)
+        .collect()
+}
+
+fn cached_scores(db: &db::Db, cli_key: &str, window_minutes: u32) -> Option<HashMap<i64, f64>> {
+    {
+        let guard = SCORE_CACHE.lock_or_recover();
+        if let Some(cached) = guard.as_ref().and_then(|cache| cache.get(cli_key)) {
+            if cached.window_minutes == window_minutes
+                && cached.refreshed_at.elapsed() < REFRESH_INTERVAL
+            {
+                return Some(cached.scores.clone());
+            }
+        }
+    }
+
+    let window_secs = window_minutes as i64 * 60;
+    let now = now_unix_seconds();
+    let samples = match request_attempt_logs::list_success_latency_samples(
+        db,
+        cli_key,
+        now.saturating_sub(window_secs),
+        MAX_SAMPLES,
+    ) {
+        Ok(v) => v,
+        Err(err) => {
+            tracing::warn!(cli_key = %cli_key, "延迟路由读取尝试日志失败: {}", err);
+            return None;
+        }
+    };
+    let scores = decayed_scores(&samples, now, window_secs);
+
+    let mut guard = SCORE_CACHE.lock_or_recover();
+    guard.get_or_insert_with(HashMap::new).insert(
+        cli_key.to_string(),
+        CachedScores {
+            refreshed_at: Instant::now(),
+            window_minutes,
+            scores: scores.clone(),
+        },
+    );
+    Some(scores)
+}
+
+/// Reorders `providers` fastest-first; returns the applied scores, or `None` when nothing changed.
+pub(super) fn apply(
+    db: &db::Db,
+    cli_key: &str,
+    config: settings::LatencyRoutingConfig,
+    providers: &mut Vec<providers::ProviderForGateway>,
+) -> Option<HashMap<i64, f64>> {
+    if !config.enabled || providers.len() < 2 {
+        return None;
+    }
+
+    let scores = cached_scores(db, cli_key, config.window_minutes)?;
+    let provider_ids: Vec<i64> = providers.iter().map(|p| p.id).collect();
+    let order = latency_order(&provider_ids, &scores);
+    if order.iter().enumerate().all(|(pos, idx)| pos == *idx) {
+        return None;
+    }
+
+    let mut slots: Vec<Option<providers::ProviderForGateway>> =
+        providers.drain(..).map(Some).collect();
+    providers.extend(order.iter().filter_map(|idx| slots[*idx].take()));
+    Some(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(provider_id: i64, duration_ms: i64, created_at: i64) -> AttemptLatencySample {
+        AttemptLatencySample {
+            provider_id,
+            duration_ms,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn decayed_scores_weight_recent_samples_and_drop_expired() {
+        let now = 10_000;
+        let samples = [
+            // age 0 -> weight 1.0
+            sample(1, 100, now),
+            // age 300 of 600 -> weight 0.5
+            sample(1, 400, now - 300),
+            // outside the window
+            sample(1, 9_000, now - 600),
+            sample(2, 250, now - 60),
+        ];
+        let scores = decayed_scores(&samples, now, 600);
+
+        assert_eq!(scores.len(), 2);
+        assert!((scores[&1] - 200.0).abs() < 1e-9, "score={}", scores[&1]);
+        assert!((scores[&2] - 250.0).abs() < 1e-9);
+        assert!(decayed_scores(&[sample(3, 100, now - 700)], now, 600).is_empty());
+    }
+
+    #[test]
+    fn latency_order_puts_fastest_first_and_unscored_last() {
+        let scores = HashMap::from([(20, 900.0), (30, 150.0)]);
+        assert_eq!(latency_order(&[10, 20, 30, 40], &scores), vec![2, 1, 0, 3]);
+        assert_eq!(latency_order(&[10, 40], &scores), vec![0, 1]);
+    }
+}
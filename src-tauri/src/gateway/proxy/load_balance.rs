@@ -0,0 +1,155 @@
+//! Usage: Sort-mode load balancing (`weighted` / `round_robin`) over a provider order.
+//!
+//! 用法：
+//! - `ordered`：保持排序模式中的顺序（默认）
+//! - `weighted`：按权重做不放回加权随机，得到完整的 failover 顺序；权重为 0 的 Provider 只作兜底
+//! - `round_robin`：每个 (cli_key, mode_id) 维护游标，每次请求把顺序左旋一位
+//! - 会话绑定在首个请求后固定顺序，因此同一会话不会在 Provider 间来回切换
+
+use crate::shared::mutex_ext::MutexExt;
+use crate::{providers, sort_modes};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+static ROUND_ROBIN_CURSORS: Mutex<Option<HashMap<(String, i64), u64>>> = Mutex::new(None);
+
+fn next_round_robin_cursor(cli_key: &str, mode_id: i64) -> u64 {
+    let mut guard = ROUND_ROBIN_CURSORS.lock_or_recover();
+    let cursors = guard.get_or_insert_with(HashMap::new);
+    let cursor = cursors.entry((cli_key.to_string(), mode_id)).or_insert(0);
+    let current = *cursor;
+    *cursor = cursor.wrapping_add(1);
+    current
+}
+
+fn random_unit() -> f64 {
+    let mut buf = [0u8; 4];
+    match getrandom::getrandom(&mut buf) {
+        Ok(()) => u32::from_le_bytes(buf) as f64 / (u32::MAX as f64 + 1.0),
+        Err(_) => 0.0,
+    }
+}
+
+/// Weighted sampling without replacement; `next_unit` yields values in [0, 1).
+fn weighted_order(weights: &[u32], mut next_unit: impl FnMut() -> f64) -> Vec<usize> {
+    let mut remaining: Vec<usize> = (0..weights.len()).collect();
+    let mut out = Vec::with_capacity(weights.len());
+
+    loop {
+        let total: u64 = remaining.iter().map(|idx| weights[*idx] as u64).sum();
+        if total == 0 {
+            break;
+        }
+        let mut target = (next_unit().clamp(0.0, 1.0) * total as f64) as u64;
+        target = target.min(total - 1);
+        let pos = remaining
+            .iter()
+            .position(|idx| {
+                let weight = weights[*idx] as u64;
+                if target < weight {
+                    true
+                } else {
+                    target -= weight;
+                    false
+                }
+            })
+            .unwrap_or(0);
+        out.push(remaining.remove(pos));
+    }
+
+    // Zero-weight providers keep their relative order as the last fallbacks.
+    out.extend(remaining);
+    out
+}
+
+fn round_robin_order(len: usize, cursor: u64) -> Vec<usize> {
+    if len == 0 {
+        return Vec::new();
+    }
+    let shift = (cursor % len as u64) as usize;
+    (0..len).map(|i| (i + shift) % len).collect()
+}
+
+fn reorder<T>(items: &mut Vec<T>, order: &[usize]) {
+    let mut slots: Vec<Option<T>> = items.drain(..).map(Some).collect();
+    items.extend(order.iter().filter_map(|idx| slots[*idx].take()));
+}
+
+/// Reorders `providers` per the sort mode strategy; returns false for `ordered` (no change).
+pub(super) fn apply(
+    cli_key: &str,
+    mode_id: i64,
+    routing: &sort_modes::SortModeRouting,
+    providers: &mut Vec<providers::ProviderForGateway>,
+) -> bool {
+    if providers.len() < 2 {
+        return false;
+    }
+
+    let order = match routing.strategy {
+        sort_modes::SortModeStrategy::Ordered => return false,
+        sort_modes::SortModeStrategy::Weighted => {
+            let weights: Vec<u32> = providers
+                .iter()
+                .map(|p| routing.weights.get(&p.id).copied().unwrap_or(1))
+                .collect();
+            weighted_order(&weights, random_unit)
+        }
+        sort_modes::SortModeStrategy::RoundRobin => {
+            round_robin_order(providers.len(), next_round_robin_cursor(cli_key, mode_id))
+        }
+    };
+    reorder(providers, &order);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighted_order_follows_weights_and_keeps_zero_weight_last() {
+        let units = [0.0, 0.0];
+        let mut iter = units.iter().copied();
+        assert_eq!(
+            weighted_order(&[0, 3, 1], || iter.next().unwrap_or(0.0)),
+            vec![1, 2, 0]
+        );
+
+        // 0.9 * 4 = 3.6 -> falls into the last unit of weight (index 2).
+        let units = [0.9, 0.0];
+        let mut iter = units.iter().copied();
+        assert_eq!(
+            weighted_order(&[0, 3, 1], || iter.next().unwrap_or(0.0)),
+            vec![2, 1, 0]
+        );
+
+        assert_eq!(weighted_order(&[0, 0], || 0.5), vec![0, 1]);
+    }
+
+    #[test]
+    fn weighted_order_is_roughly_proportional() {
+        let mut seed: u64 = 42;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let first_hits = (0..1000)
+            .filter(|_| weighted_order(&[3, 1], &mut next)[0] == 0)
+            .count();
+        assert!((650..=850).contains(&first_hits), "hits={first_hits}");
+    }
+
+    #[test]
+    fn round_robin_rotates_and_reorders() {
+        assert_eq!(round_robin_order(3, 0), vec![0, 1, 2]);
+        assert_eq!(round_robin_order(3, 4), vec![1, 2, 0]);
+        assert!(round_robin_order(0, 7).is_empty());
+
+        let mut items = vec!["a", "b", "c"];
+        reorder(&mut items, &round_robin_order(3, 2));
+        assert_eq!(items, vec!["c", "a", "b"]);
+    }
+}
@@ -132,6 +132,7 @@ fn request_log_insert_from_args(
         created_at,
         usage_metrics,
         usage,
+        attribution,
     } = args;
 
     if !crate::shared::cli_key::is_supported_cli_key(cli_key.as_str()) {
@@ -172,8 +173,11 @@ fn request_log_insert_from_args(
         cache_creation_input_tokens: metrics.cache_creation_input_tokens,
         cache_creation_5m_input_tokens: metrics.cache_creation_5m_input_tokens,
         cache_creation_1h_input_tokens: metrics.cache_creation_1h_input_tokens,
+        estimated_input_tokens: attribution.estimated_input_tokens,
         usage_json,
         requested_model,
+        client_id: attribution.client_id,
+        client_trace_id: attribution.client_trace_id,
         created_at_ms,
         created_at,
     })
@@ -315,6 +319,7 @@ mod tests {
             created_at: 0,
             usage_metrics: None,
             usage: None,
+            attribution: Default::default(),
         }
     }
 
@@ -377,4 +382,19 @@ mod tests {
         assert_eq!(insert.cache_creation_1h_input_tokens, Some(7));
         assert_eq!(insert.usage_json, Some("{\"input_tokens\":1}".to_string()));
     }
+
+    #[test]
+    fn request_log_insert_records_attribution() {
+        let mut args = base_args();
+        args.attribution = super::super::RequestLogAttribution {
+            client_id: Some("laptop".to_string()),
+            client_trace_id: Some("client-trace".to_string()),
+            estimated_input_tokens: Some(42),
+        };
+
+        let insert = request_log_insert_from_args(args).expect("insert");
+        assert_eq!(insert.client_id.as_deref(), Some("laptop"));
+        assert_eq!(insert.client_trace_id.as_deref(), Some("client-trace"));
+        assert_eq!(insert.estimated_input_tokens, Some(42));
+    }
 }
@@ -2,23 +2,44 @@
 
 mod abort_guard;
 mod caches;
+pub(in crate::gateway) mod circuit_failures;
 mod cli_proxy_guard;
 mod errors;
 mod failover;
+mod failover_rules;
 mod forwarder;
+mod gemini;
 mod handler;
 mod http_util;
+mod inflight_dedup;
+mod latency_probe;
+mod latency_routing;
+mod load_balance;
 mod logging;
 mod model_rewrite;
+pub(in crate::gateway) mod prefix_affinity;
+mod priority_lane;
+mod protocol_bridge;
+mod provider_quota;
 pub(in crate::gateway) mod provider_router;
+pub(in crate::gateway) mod rate_limit;
+mod request_body;
 mod request_context;
 mod request_end;
+mod response_cache;
+mod route_explain;
 pub(in crate::gateway) mod status_override;
+mod stream_cap;
 mod types;
 mod upstream_client_error_rules;
 
 pub(super) use caches::{ProviderBaseUrlPingCache, RecentErrorCache};
+pub(super) use inflight_dedup::InflightRequests;
+pub(super) use latency_probe::spawn_scheduler as spawn_latency_probe_scheduler;
 pub(in crate::gateway) use logging::spawn_enqueue_request_log_with_backpressure;
+pub(super) use priority_lane::PriorityLanes;
+pub(super) use route_explain::{explain_route, RouteExplainInput};
+pub(super) use stream_cap::StreamCaps;
 pub(super) use types::ErrorCategory;
 
 pub(super) use handler::proxy_impl;
@@ -29,6 +50,18 @@ fn is_claude_count_tokens_request(cli_key: &str, forwarded_path: &str) -> bool {
     cli_key == "claude" && forwarded_path == CLAUDE_COUNT_TOKENS_PATH
 }
 
+/// Per-request values recorded on the request log that are known before the outcome; carried
+/// with the request so a reused trace id keeps them.
+#[derive(Debug, Clone, Default)]
+pub(super) struct RequestLogAttribution {
+    // Matched `gateway_client_keys` entry (LAN / WSL clients).
+    pub(super) client_id: Option<String>,
+    // Client-supplied trace id (`trace_headers::client_trace_id`).
+    pub(super) client_trace_id: Option<String>,
+    // Prompt token estimate (`token_estimate::estimate`).
+    pub(super) estimated_input_tokens: Option<i64>,
+}
+
 pub(super) struct RequestLogEnqueueArgs {
     pub(super) trace_id: String,
     pub(super) cli_key: String,
@@ -48,6 +81,7 @@ pub(super) struct RequestLogEnqueueArgs {
     pub(super) created_at: i64,
     pub(super) usage_metrics: Option<crate::usage::UsageMetrics>,
     pub(super) usage: Option<crate::usage::UsageExtract>,
+    pub(super) attribution: RequestLogAttribution,
 }
 
 #[cfg(test)]
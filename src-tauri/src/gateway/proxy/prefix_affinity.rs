@@ -0,0 +1,241 @@
+//! Usage: Prompt-prefix provider affinity (route a repeated prompt prefix to the provider that served it).
+//!
+//! 用法：
+//! - 对请求体的稳定前缀（system / instructions + 前 `PREFIX_MESSAGES` 条消息，去掉 `cache_control` 标记）连同 CLI、模型计算哈希；
+//!   前缀短于 `MIN_PREFIX_BYTES` 时不参与（上游不会为过短的前缀建立缓存）
+//! - 请求成功后记录 前缀 → Provider；之后相同前缀的请求（没有 session_id、或换了新会话）优先路由到该 Provider，提高上游 prompt cache 命中
+//! - 会话绑定优先，仅在没有会话绑定 Provider 时生效；条目在 `ENTRY_TTL_SECS` 内未再成功即过期
+//! - 命中 / 未命中写入 `special_settings_json`（type = `prefix_affinity`），花费页据此统计命中率
+//! - 超过缓冲上限、以流式转发的请求体无法解析，不参与
+
+use crate::shared::mutex_ext::MutexExt;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+const PREFIX_MESSAGES: usize = 1;
+const MIN_PREFIX_BYTES: usize = 2048;
+const ENTRY_TTL_SECS: i64 = 30 * 60;
+const MAX_ENTRIES: usize = 5000;
+
+const SYSTEM_KEYS: &[&str] = &[
+    "system",
+    "instructions",
+    "systemInstruction",
+    "system_instruction",
+];
+const MESSAGE_KEYS: &[&str] = &["messages", "input", "contents"];
+
+#[derive(Debug, Clone, Copy)]
+struct AffinityEntry {
+    provider_id: i64,
+    expires_at: i64,
+}
+
+static AFFINITY: Mutex<Option<HashMap<u64, AffinityEntry>>> = Mutex::new(None);
+
+/// Key of the stable prompt prefix of `body`, or `None` when it has no usable prefix.
+pub(super) fn prefix_key(cli_key: &str, model: Option<&str>, body: &Value) -> Option<u64> {
+    let system = SYSTEM_KEYS.iter().find_map(|key| body.get(*key));
+    let head: Vec<&Value> = match MESSAGE_KEYS.iter().find_map(|key| body.get(*key)) {
+        Some(Value::Array(items)) => {
+            // Chat-style bodies carry the system prompt as leading messages.
+            let leading = items
+                .iter()
+                .take_while(|item| is_system_message(item))
+                .count();
+            if items.len() < leading + PREFIX_MESSAGES {
+                return None;
+            }
+            items.iter().take(leading + PREFIX_MESSAGES).collect()
+        }
+        // Codex `input` may be a plain string.
+        Some(text @ Value::String(_)) => vec![text],
+        _ => return None,
+    };
+
+    let mut prefix = Value::Array(
+        system
+            .into_iter()
+            .chain(head)
+            .cloned()
+            .collect::<Vec<Value>>(),
+    );
+    // Clients move `cache_control` to the latest turn; it must not change the key.
+    strip_cache_control(&mut prefix);
+    let prefix = serde_json::to_string(&prefix).ok()?;
+    if prefix.len() < MIN_PREFIX_BYTES {
+        return None;
+    }
+
+    let mut hasher = DefaultHasher::new();
+    cli_key.hash(&mut hasher);
+    model.unwrap_or_default().hash(&mut hasher);
+    prefix.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+fn is_system_message(item: &Value) -> bool {
+    matches!(
+        item.get("role").and_then(Value::as_str),
+        Some("system" | "developer")
+    )
+}
+
+fn strip_cache_control(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove("cache_control");
+            map.values_mut().for_each(strip_cache_control);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_cache_control),
+        _ => {}
+    }
+}
+
+/// Provider that last served `key` successfully, if still fresh.
+pub(super) fn lookup(key: u64, now_unix: i64) -> Option<i64> {
+    let mut guard = AFFINITY.lock_or_recover();
+    let map = guard.as_mut()?;
+    match map.get(&key) {
+        Some(entry) if entry.expires_at > now_unix => Some(entry.provider_id),
+        Some(_) => {
+            map.remove(&key);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Remembers (and refreshes) the provider that served `key`.
+pub(in crate::gateway) fn record(key: u64, provider_id: i64, now_unix: i64) {
+    if provider_id <= 0 {
+        return;
+    }
+    let mut guard = AFFINITY.lock_or_recover();
+    let map = guard.get_or_insert_with(HashMap::new);
+    if map.len() >= MAX_ENTRIES && !map.contains_key(&key) {
+        map.retain(|_, entry| entry.expires_at > now_unix);
+        if map.len() >= MAX_ENTRIES {
+            map.clear();
+        }
+    }
+    map.insert(
+        key,
+        AffinityEntry {
+            provider_id,
+            expires_at: now_unix.saturating_add(ENTRY_TTL_SECS),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn long_system() -> String {
+        "You are a coding assistant. ".repeat(100)
+    }
+
+    #[test]
+    fn prefix_key_ignores_later_turns_and_cache_control() {
+        let first = json!({
+            "model": "claude-sonnet-4",
+            "system": [{"type": "text", "text": long_system()}],
+            "messages": [
+                {"role": "user", "content": [{"type": "text", "text": "hi", "cache_control": {"type": "ephemeral"}}]},
+            ],
+        });
+        let later = json!({
+            "model": "claude-sonnet-4",
+            "system": [{"type": "text", "text": long_system()}],
+            "messages": [
+                {"role": "user", "content": [{"type": "text", "text": "hi"}]},
+                {"role": "assistant", "content": "hello"},
+                {"role": "user", "content": [{"type": "text", "text": "next", "cache_control": {"type": "ephemeral"}}]},
+            ],
+        });
+        let key = prefix_key("claude", Some("claude-sonnet-4"), &first).expect("key");
+        assert_eq!(
+            prefix_key("claude", Some("claude-sonnet-4"), &later),
+            Some(key)
+        );
+
+        // model, CLI and first message are part of the key
+        assert_ne!(
+            prefix_key("claude", Some("claude-opus-4"), &first),
+            Some(key)
+        );
+        assert_ne!(
+            prefix_key("codex", Some("claude-sonnet-4"), &first),
+            Some(key)
+        );
+        let other = json!({
+            "system": [{"type": "text", "text": long_system()}],
+            "messages": [{"role": "user", "content": "something else"}],
+        });
+        assert_ne!(
+            prefix_key("claude", Some("claude-sonnet-4"), &other),
+            Some(key)
+        );
+    }
+
+    #[test]
+    fn prefix_key_requires_a_long_enough_prefix() {
+        assert!(prefix_key(
+            "claude",
+            None,
+            &json!({"system": "short", "messages": [{"role": "user", "content": "hi"}]})
+        )
+        .is_none());
+        assert!(prefix_key("claude", None, &json!({"system": long_system()})).is_none());
+        assert!(prefix_key(
+            "codex",
+            None,
+            &json!({"instructions": long_system(), "input": "hi"})
+        )
+        .is_some());
+        let chat = |first_user: &str| {
+            json!({"messages": [
+                {"role": "system", "content": long_system()},
+                {"role": "user", "content": first_user},
+            ]})
+        };
+        assert!(prefix_key("codex", None, &chat("a")).is_some());
+        assert_ne!(
+            prefix_key("codex", None, &chat("a")),
+            prefix_key("codex", None, &chat("b"))
+        );
+        assert!(prefix_key(
+            "codex",
+            None,
+            &json!({"messages": [{"role": "system", "content": long_system()}]})
+        )
+        .is_none());
+        assert!(prefix_key(
+            "gemini",
+            None,
+            &json!({
+                "systemInstruction": {"parts": [{"text": long_system()}]},
+                "contents": [{"role": "user", "parts": [{"text": "hi"}]}],
+            })
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn lookup_returns_recorded_provider_until_expired() {
+        let key = 0x5eed_0001;
+        assert_eq!(lookup(key, 100), None);
+        record(key, 7, 100);
+        assert_eq!(lookup(key, 100 + ENTRY_TTL_SECS - 1), Some(7));
+        record(key, 9, 200);
+        assert_eq!(lookup(key, 200), Some(9));
+        assert_eq!(lookup(key, 200 + ENTRY_TTL_SECS), None);
+        record(key, 0, 300);
+        assert_eq!(lookup(key, 300), None);
+    }
+}
@@ -0,0 +1,261 @@
+//! Usage: Priority lanes (interactive vs background requests).
+//!
+//! Background requests (title generation, topic detection, summaries, small-model side calls) are
+//! queued behind a concurrency cap and can be routed to a dedicated provider, so that the main
+//! conversation keeps low latency under load.
+
+use crate::gateway::streams;
+use axum::http::HeaderMap;
+use axum::response::Response;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Explicit lane override sent by the client (`interactive` / `background`).
+const LANE_HEADER: &str = "x-aio-lane";
+
+// Claude Code 侧边请求的 system prompt 特征（标题生成 / 话题检测 / 会话摘要）
+const CLAUDE_BACKGROUND_SYSTEM_MARKERS: &[(&str, &str)] = &[
+    ("isNewTopic", "topic_detection"),
+    ("word title", "title_generation"),
+    ("Summarize this coding conversation", "summary"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RequestLane {
+    Interactive,
+    Background,
+}
+
+impl RequestLane {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Interactive => "interactive",
+            Self::Background => "background",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct LaneClassification {
+    pub(super) lane: RequestLane,
+    pub(super) reason: &'static str,
+}
+
+impl LaneClassification {
+    fn interactive(reason: &'static str) -> Self {
+        Self {
+            lane: RequestLane::Interactive,
+            reason,
+        }
+    }
+
+    fn background(reason: &'static str) -> Self {
+        Self {
+            lane: RequestLane::Background,
+            reason,
+        }
+    }
+}
+
+fn system_text(root: &Value) -> String {
+    match root.get("system") {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+pub(super) fn classify_request(
+    cli_key: &str,
+    headers: &HeaderMap,
+    body: Option<&Value>,
+) -> LaneClassification {
+    if let Some(value) = headers.get(LANE_HEADER).and_then(|v| v.to_str().ok()) {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "background" => return LaneClassification::background("header"),
+            "interactive" => return LaneClassification::interactive("header"),
+            _ => {}
+        }
+    }
+
+    let Some(root) = body else {
+        return LaneClassification::interactive("default");
+    };
+
+    if cli_key == "claude" {
+        let system = system_text(root);
+        for (marker, reason) in CLAUDE_BACKGROUND_SYSTEM_MARKERS {
+            if system.contains(marker) {
+                return LaneClassification::background(reason);
+            }
+        }
+        // 主对话总是携带 tools；无 tools 的 haiku 请求视为后台小模型调用
+        let is_haiku = root
+            .get("model")
+            .and_then(Value::as_str)
+            .is_some_and(|model| model.to_ascii_lowercase().contains("haiku"));
+        let has_tools = root
+            .get("tools")
+            .and_then(Value::as_array)
+            .is_some_and(|tools| !tools.is_empty());
+        if is_haiku && !has_tools {
+            return LaneClassification::background("small_model");
+        }
+    }
+
+    LaneClassification::interactive("default")
+}
+
+/// Result of waiting for a background-lane slot.
+pub(super) struct BackgroundSlot {
+    lanes: Arc<PriorityLanes>,
+    pub(super) queued_ms: u128,
+    pub(super) queue_timed_out: bool,
+}
+
+impl Drop for BackgroundSlot {
+    fn drop(&mut self) {
+        {
+            let mut in_flight = self
+                .lanes
+                .background_in_flight
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            *in_flight = in_flight.saturating_sub(1);
+        }
+        self.lanes.released.notify_one();
+    }
+}
+
+impl BackgroundSlot {
+    /// Keeps the slot occupied until the response body finishes (streams included).
+    pub(super) fn hold_until_body_end(self, resp: Response) -> Response {
+        streams::hold_until_body_end(resp, self)
+    }
+}
+
+#[derive(Default)]
+pub(in crate::gateway) struct PriorityLanes {
+    background_in_flight: Mutex<usize>,
+    released: Notify,
+}
+
+impl PriorityLanes {
+    /// Waits until fewer than `max_concurrency` background requests are in flight.
+    ///
+    /// `max_concurrency == 0` disables queueing; after `queue_timeout` the request proceeds anyway
+    /// (background requests are deprioritized, never dropped).
+    pub(super) async fn acquire_background(
+        self: &Arc<Self>,
+        max_concurrency: u32,
+        queue_timeout: Duration,
+    ) -> BackgroundSlot {
+        let started = Instant::now();
+        let wait = async {
+            loop {
+                let notified = self.released.notified();
+                {
+                    let mut in_flight = self
+                        .background_in_flight
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner());
+                    if max_concurrency == 0 || *in_flight < max_concurrency as usize {
+                        *in_flight += 1;
+                        return;
+                    }
+                }
+                notified.await;
+            }
+        };
+
+        let queue_timed_out = tokio::time::timeout(queue_timeout, wait).await.is_err();
+        if queue_timed_out {
+            let mut in_flight = self
+                .background_in_flight
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            *in_flight += 1;
+        }
+
+        BackgroundSlot {
+            lanes: self.clone(),
+            queued_ms: started.elapsed().as_millis(),
+            queue_timed_out,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn classify_detects_claude_side_requests() {
+        let headers = HeaderMap::new();
+        let title = serde_json::json!({
+            "model": "claude-sonnet-4",
+            "system": [{"type": "text", "text": "Please write a 5-10 word title for the conversation"}],
+        });
+        assert_eq!(
+            classify_request("claude", &headers, Some(&title)),
+            LaneClassification::background("title_generation")
+        );
+
+        let haiku = serde_json::json!({"model": "claude-3-5-haiku-20241022", "messages": []});
+        assert_eq!(
+            classify_request("claude", &headers, Some(&haiku)).lane,
+            RequestLane::Background
+        );
+
+        let main = serde_json::json!({
+            "model": "claude-3-5-haiku-20241022",
+            "tools": [{"name": "Bash"}],
+        });
+        assert_eq!(
+            classify_request("claude", &headers, Some(&main)).lane,
+            RequestLane::Interactive
+        );
+        assert_eq!(
+            classify_request("codex", &headers, Some(&haiku)).lane,
+            RequestLane::Interactive
+        );
+    }
+
+    #[test]
+    fn classify_honors_lane_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(LANE_HEADER, HeaderValue::from_static("Background"));
+        assert_eq!(
+            classify_request("codex", &headers, None),
+            LaneClassification::background("header")
+        );
+    }
+
+    #[tokio::test]
+    async fn background_slots_queue_until_released() {
+        let lanes = Arc::new(PriorityLanes::default());
+        let first = lanes.acquire_background(1, Duration::from_secs(5)).await;
+        assert!(!first.queue_timed_out);
+
+        let timed_out = lanes.acquire_background(1, Duration::from_millis(20)).await;
+        assert!(timed_out.queue_timed_out);
+        drop(timed_out);
+
+        let waiter = {
+            let lanes = lanes.clone();
+            tokio::spawn(async move { lanes.acquire_background(1, Duration::from_secs(5)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(first);
+        let second = waiter.await.unwrap();
+        assert!(!second.queue_timed_out);
+        assert_eq!(*lanes.background_in_flight.lock().unwrap(), 1);
+    }
+}
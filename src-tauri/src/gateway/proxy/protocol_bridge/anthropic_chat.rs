@@ -0,0 +1,716 @@
+//! Usage: Anthropic Messages <-> OpenAI Chat Completions translation (request body, JSON response, SSE stream).
+
+use serde_json::{json, Map, Value};
+
+use super::{write_sse_event as emit, SseDataLines};
+
+fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter(|b| b.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|b| b.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn image_part(block: &Value) -> Option<Value> {
+    let source = block.get("source")?;
+    let url = match source.get("type").and_then(Value::as_str)? {
+        "base64" => format!(
+            "data:{};base64,{}",
+            source.get("media_type").and_then(Value::as_str)?,
+            source.get("data").and_then(Value::as_str)?
+        ),
+        "url" => source.get("url").and_then(Value::as_str)?.to_string(),
+        _ => return None,
+    };
+    Some(json!({"type": "image_url", "image_url": {"url": url}}))
+}
+
+fn push_user_message(blocks: &[Value], out: &mut Vec<Value>) {
+    let mut parts = Vec::new();
+    let mut has_image = false;
+    for block in blocks {
+        match block.get("type").and_then(Value::as_str) {
+            Some("text") => {
+                if let Some(text) = block.get("text").and_then(Value::as_str) {
+                    parts.push(json!({"type": "text", "text": text}));
+                }
+            }
+            Some("image") => {
+                if let Some(part) = image_part(block) {
+                    has_image = true;
+                    parts.push(part);
+                }
+            }
+            // Tool results must directly follow the assistant tool_calls message.
+            Some("tool_result") => {
+                let mut content = block.get("content").map(text_of).unwrap_or_default();
+                if block.get("is_error").and_then(Value::as_bool) == Some(true) {
+                    content = format!("[tool error] {content}");
+                }
+                out.push(json!({
+                    "role": "tool",
+                    "tool_call_id": block.get("tool_use_id").cloned().unwrap_or(Value::Null),
+                    "content": content,
+                }));
+            }
+            _ => {}
+        }
+    }
+    if parts.is_empty() {
+        return;
+    }
+    let content = if has_image {
+        Value::Array(parts)
+    } else {
+        Value::String(text_of(&Value::Array(parts)))
+    };
+    out.push(json!({"role": "user", "content": content}));
+}
+
+fn push_assistant_message(blocks: &[Value], out: &mut Vec<Value>) {
+    let mut text = Vec::new();
+    let mut tool_calls = Vec::new();
+    for block in blocks {
+        match block.get("type").and_then(Value::as_str) {
+            Some("text") => {
+                if let Some(t) = block.get("text").and_then(Value::as_str) {
+                    text.push(t);
+                }
+            }
+            Some("tool_use") => {
+                let input = block.get("input").cloned().unwrap_or_else(|| json!({}));
+                tool_calls.push(json!({
+                    "id": block.get("id").cloned().unwrap_or(Value::Null),
+                    "type": "function",
+                    "function": {
+                        "name": block.get("name").cloned().unwrap_or(Value::Null),
+                        "arguments": input.to_string(),
+                    },
+                }));
+            }
+            // thinking / redacted_thinking carry Anthropic-only signatures; dropped.
+            _ => {}
+        }
+    }
+    let mut message = Map::new();
+    message.insert("role".to_string(), json!("assistant"));
+    message.insert("content".to_string(), json!(text.join("\n")));
+    if !tool_calls.is_empty() {
+        message.insert("tool_calls".to_string(), Value::Array(tool_calls));
+    }
+    out.push(Value::Object(message));
+}
+
+fn map_tool_choice(choice: &Value) -> Option<Value> {
+    match choice.get("type").and_then(Value::as_str)? {
+        "auto" => Some(json!("auto")),
+        "any" => Some(json!("required")),
+        "none" => Some(json!("none")),
+        "tool" => Some(json!({
+            "type": "function",
+            "function": {"name": choice.get("name")?.as_str()?},
+        })),
+        _ => None,
+    }
+}
+
+/// Translates a `/v1/messages` body into a `/v1/chat/completions` body; `None` if it isn't an object.
+pub(super) fn messages_to_chat_request(root: &Value) -> Option<Value> {
+    let obj = root.as_object()?;
+    let mut out = Map::new();
+
+    if let Some(model) = obj.get("model") {
+        out.insert("model".to_string(), model.clone());
+    }
+
+    let mut messages = Vec::new();
+    if let Some(system) = obj.get("system") {
+        let text = text_of(system);
+        if !text.is_empty() {
+            messages.push(json!({"role": "system", "content": text}));
+        }
+    }
+    for message in obj
+        .get("messages")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let role = message
+            .get("role")
+            .and_then(Value::as_str)
+            .unwrap_or("user");
+        match message.get("content") {
+            Some(Value::String(text)) => messages.push(json!({"role": role, "content": text})),
+            Some(Value::Array(blocks)) if role == "assistant" => {
+                push_assistant_message(blocks, &mut messages)
+            }
+            Some(Value::Array(blocks)) => push_user_message(blocks, &mut messages),
+            _ => {}
+        }
+    }
+    out.insert("messages".to_string(), Value::Array(messages));
+
+    for key in ["max_tokens", "temperature", "top_p"] {
+        if let Some(v) = obj.get(key) {
+            out.insert(key.to_string(), v.clone());
+        }
+    }
+    if let Some(stop) = obj
+        .get("stop_sequences")
+        .filter(|v| v.as_array().is_some_and(|items| !items.is_empty()))
+    {
+        out.insert("stop".to_string(), stop.clone());
+    }
+    if obj.get("stream").and_then(Value::as_bool) == Some(true) {
+        out.insert("stream".to_string(), json!(true));
+        out.insert("stream_options".to_string(), json!({"include_usage": true}));
+    }
+
+    // Server tools (web_search etc.) have no input_schema and no Chat Completions equivalent.
+    let tools: Vec<Value> = obj
+        .get("tools")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|tool| {
+            let mut function = Map::new();
+            function.insert("name".to_string(), tool.get("name")?.clone());
+            if let Some(description) = tool.get("description") {
+                function.insert("description".to_string(), description.clone());
+            }
+            function.insert("parameters".to_string(), tool.get("input_schema")?.clone());
+            Some(json!({"type": "function", "function": function}))
+        })
+        .collect();
+    if !tools.is_empty() {
+        out.insert("tools".to_string(), Value::Array(tools));
+        if let Some(choice) = obj.get("tool_choice") {
+            if let Some(mapped) = map_tool_choice(choice) {
+                out.insert("tool_choice".to_string(), mapped);
+            }
+            if choice
+                .get("disable_parallel_tool_use")
+                .and_then(Value::as_bool)
+                == Some(true)
+            {
+                out.insert("parallel_tool_calls".to_string(), json!(false));
+            }
+        }
+    }
+
+    if let Some(user) = obj
+        .get("metadata")
+        .and_then(|v| v.get("user_id"))
+        .filter(|v| v.is_string())
+    {
+        out.insert("user".to_string(), user.clone());
+    }
+
+    Some(Value::Object(out))
+}
+
+fn map_finish_reason(reason: &str) -> &'static str {
+    match reason {
+        "length" => "max_tokens",
+        "tool_calls" | "function_call" => "tool_use",
+        "content_filter" => "refusal",
+        _ => "end_turn",
+    }
+}
+
+fn usage_to_anthropic(usage: &Value) -> Value {
+    let prompt = usage
+        .get("prompt_tokens")
+        .and_then(Value::as_i64)
+        .unwrap_or(0);
+    let completion = usage
+        .get("completion_tokens")
+        .and_then(Value::as_i64)
+        .unwrap_or(0);
+    let cached = usage
+        .get("prompt_tokens_details")
+        .and_then(|v| v.get("cached_tokens"))
+        .and_then(Value::as_i64)
+        .unwrap_or(0);
+    let mut out = json!({
+        "input_tokens": (prompt - cached).max(0),
+        "output_tokens": completion,
+    });
+    if cached > 0 {
+        out["cache_read_input_tokens"] = json!(cached);
+    }
+    out
+}
+
+fn parse_tool_arguments(arguments: Option<&Value>) -> Value {
+    arguments
+        .and_then(Value::as_str)
+        .and_then(|raw| serde_json::from_str::<Value>(raw).ok())
+        .filter(Value::is_object)
+        .unwrap_or_else(|| json!({}))
+}
+
+/// Translates a `chat.completion` JSON body into an Anthropic `message`; `None` if it has no choice.
+pub(super) fn chat_response_to_message(
+    root: &Value,
+    fallback_model: Option<&str>,
+) -> Option<Value> {
+    let choice = root.get("choices")?.as_array()?.first()?;
+    let message = choice.get("message")?;
+
+    let mut content = Vec::new();
+    if let Some(text) = message
+        .get("content")
+        .and_then(Value::as_str)
+        .filter(|t| !t.is_empty())
+    {
+        content.push(json!({"type": "text", "text": text}));
+    }
+    for (index, call) in message
+        .get("tool_calls")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+    {
+        let function = call.get("function");
+        content.push(json!({
+            "type": "tool_use",
+            "id": call
+                .get("id")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("toolu_bridge_{index}")),
+            "name": function.and_then(|f| f.get("name")).cloned().unwrap_or(Value::Null),
+            "input": parse_tool_arguments(function.and_then(|f| f.get("arguments"))),
+        }));
+    }
+
+    Some(json!({
+        "id": root.get("id").and_then(Value::as_str).unwrap_or("msg_bridge"),
+        "type": "message",
+        "role": "assistant",
+        "model": root
+            .get("model")
+            .and_then(Value::as_str)
+            .or(fallback_model)
+            .unwrap_or("unknown"),
+        "content": content,
+        "stop_reason": map_finish_reason(
+            choice.get("finish_reason").and_then(Value::as_str).unwrap_or("stop")
+        ),
+        "stop_sequence": null,
+        "usage": root
+            .get("usage")
+            .map(usage_to_anthropic)
+            .unwrap_or_else(|| json!({"input_tokens": 0, "output_tokens": 0})),
+    }))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpenBlock {
+    Text(u32),
+    Tool { call_index: u64, block_index: u32 },
+}
+
+/// Incremental `chat.completion.chunk` SSE -> Anthropic SSE event converter.
+#[derive(Debug, Default)]
+pub(super) struct ChatToAnthropicSse {
+    lines: SseDataLines,
+    fallback_model: Option<String>,
+    started: bool,
+    finished: bool,
+    next_block_index: u32,
+    open_block: Option<OpenBlock>,
+    stop_reason: Option<&'static str>,
+    usage: Option<Value>,
+}
+
+impl ChatToAnthropicSse {
+    pub(super) fn new(fallback_model: Option<String>) -> Self {
+        Self {
+            fallback_model,
+            ..Self::default()
+        }
+    }
+
+    pub(super) fn push(&mut self, chunk: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for data in self.lines.push(chunk) {
+            self.handle_data(&data, &mut out);
+        }
+        out
+    }
+
+    /// Flushes a trailing partial line and closes the message if upstream never sent `[DONE]`.
+    pub(super) fn finish(&mut self) -> Vec<u8> {
+        let mut out = Vec::new();
+        if let Some(data) = self.lines.finish() {
+            self.handle_data(&data, &mut out);
+        }
+        self.finish_into(&mut out);
+        out
+    }
+
+    fn handle_data(&mut self, data: &str, out: &mut Vec<u8>) {
+        if data == "[DONE]" {
+            self.finish_into(out);
+            return;
+        }
+        if let Ok(value) = serde_json::from_str::<Value>(data) {
+            self.handle_chunk(&value, out);
+        }
+    }
+
+    fn ensure_started(&mut self, chunk: Option<&Value>, out: &mut Vec<u8>) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+        let model = chunk
+            .and_then(|v| v.get("model"))
+            .and_then(Value::as_str)
+            .or(self.fallback_model.as_deref())
+            .unwrap_or("unknown");
+        let id = chunk
+            .and_then(|v| v.get("id"))
+            .and_then(Value::as_str)
+            .unwrap_or("msg_bridge");
+        emit(
+            out,
+            "message_start",
+            json!({
+                "type": "message_start",
+                "message": {
+                    "id": id,
+                    "type": "message",
+                    "role": "assistant",
+                    "model": model,
+                    "content": [],
+                    "stop_reason": null,
+                    "stop_sequence": null,
+                    "usage": {"input_tokens": 0, "output_tokens": 0},
+                },
+            }),
+        );
+    }
+
+    fn close_block(&mut self, out: &mut Vec<u8>) {
+        let index = match self.open_block.take() {
+            Some(OpenBlock::Text(index)) => index,
+            Some(OpenBlock::Tool { block_index, .. }) => block_index,
+            None => return,
+        };
+        emit(
+            out,
+            "content_block_stop",
+            json!({"type": "content_block_stop", "index": index}),
+        );
+    }
+
+    fn open_block(&mut self, block: Value, out: &mut Vec<u8>) -> u32 {
+        self.close_block(out);
+        let index = self.next_block_index;
+        self.next_block_index += 1;
+        emit(
+            out,
+            "content_block_start",
+            json!({"type": "content_block_start", "index": index, "content_block": block}),
+        );
+        index
+    }
+
+    fn handle_text(&mut self, text: &str, out: &mut Vec<u8>) {
+        let index = match self.open_block {
+            Some(OpenBlock::Text(index)) => index,
+            _ => {
+                let index = self.open_block(json!({"type": "text", "text": ""}), out);
+                self.open_block = Some(OpenBlock::Text(index));
+                index
+            }
+        };
+        emit(
+            out,
+            "content_block_delta",
+            json!({
+                "type": "content_block_delta",
+                "index": index,
+                "delta": {"type": "text_delta", "text": text},
+            }),
+        );
+    }
+
+    fn handle_tool_call(&mut self, call: &Value, out: &mut Vec<u8>) {
+        let call_index = call.get("index").and_then(Value::as_u64).unwrap_or(0);
+        let function = call.get("function");
+        let current = match self.open_block {
+            Some(OpenBlock::Tool {
+                call_index: open,
+                block_index,
+            }) if open == call_index => Some(block_index),
+            _ => None,
+        };
+        let block_index = match current {
+            Some(index) => index,
+            None => {
+                // A new tool call starts with its id / name; stray fragments of closed calls are dropped.
+                let Some(name) = function.and_then(|f| f.get("name")).and_then(Value::as_str)
+                else {
+                    return;
+                };
+                let id = call
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("toolu_bridge_{}", self.next_block_index));
+                let index = self.open_block(
+                    json!({"type": "tool_use", "id": id, "name": name, "input": {}}),
+                    out,
+                );
+                self.open_block = Some(OpenBlock::Tool {
+                    call_index,
+                    block_index: index,
+                });
+                index
+            }
+        };
+        if let Some(arguments) = function
+            .and_then(|f| f.get("arguments"))
+            .and_then(Value::as_str)
+            .filter(|v| !v.is_empty())
+        {
+            emit(
+                out,
+                "content_block_delta",
+                json!({
+                    "type": "content_block_delta",
+                    "index": block_index,
+                    "delta": {"type": "input_json_delta", "partial_json": arguments},
+                }),
+            );
+        }
+    }
+
+    fn handle_chunk(&mut self, chunk: &Value, out: &mut Vec<u8>) {
+        if self.finished {
+            return;
+        }
+        if let Some(error) = chunk.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            emit(
+                out,
+                "error",
+                json!({"type": "error", "error": {"type": "api_error", "message": message}}),
+            );
+            self.finished = true;
+            return;
+        }
+
+        self.ensure_started(Some(chunk), out);
+        if let Some(usage) = chunk.get("usage").filter(|v| v.is_object()) {
+            self.usage = Some(usage_to_anthropic(usage));
+        }
+        let Some(choice) = chunk
+            .get("choices")
+            .and_then(Value::as_array)
+            .and_then(|choices| choices.first())
+        else {
+            return;
+        };
+        if let Some(delta) = choice.get("delta") {
+            if let Some(text) = delta
+                .get("content")
+                .and_then(Value::as_str)
+                .filter(|t| !t.is_empty())
+            {
+                self.handle_text(text, out);
+            }
+            for call in delta
+                .get("tool_calls")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                self.handle_tool_call(call, out);
+            }
+        }
+        if let Some(reason) = choice.get("finish_reason").and_then(Value::as_str) {
+            self.stop_reason = Some(map_finish_reason(reason));
+        }
+    }
+
+    fn finish_into(&mut self, out: &mut Vec<u8>) {
+        if self.finished {
+            return;
+        }
+        self.ensure_started(None, out);
+        self.close_block(out);
+        emit(
+            out,
+            "message_delta",
+            json!({
+                "type": "message_delta",
+                "delta": {
+                    "stop_reason": self.stop_reason.unwrap_or("end_turn"),
+                    "stop_sequence": null,
+                },
+                "usage": self
+                    .usage
+                    .take()
+                    .unwrap_or_else(|| json!({"output_tokens": 0})),
+            }),
+        );
+        emit(out, "message_stop", json!({"type": "message_stop"}));
+        self.finished = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(raw: &[u8]) -> Vec<Value> {
+        String::from_utf8_lossy(raw)
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn request_maps_system_tools_and_tool_results() {
+        let body = json!({
+            "model": "claude-sonnet-4",
+            "max_tokens": 1024,
+            "stream": true,
+            "system": [{"type": "text", "text": "be brief"}],
+            "tools": [
+                {"name": "read", "description": "Read a file", "input_schema": {"type": "object"}},
+                {"type": "web_search_20250305", "name": "web_search"}
+            ],
+            "tool_choice": {"type": "any", "disable_parallel_tool_use": true},
+            "messages": [
+                {"role": "user", "content": "hi"},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "...", "signature": "sig"},
+                    {"type": "text", "text": "reading"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "read", "input": {"path": "a"}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": [{"type": "text", "text": "ok"}]},
+                    {"type": "text", "text": "next"}
+                ]}
+            ]
+        });
+        let out = messages_to_chat_request(&body).unwrap();
+        assert_eq!(out["stream_options"]["include_usage"], true);
+        assert_eq!(out["tool_choice"], "required");
+        assert_eq!(out["parallel_tool_calls"], false);
+        assert_eq!(out["tools"].as_array().unwrap().len(), 1);
+        assert_eq!(out["tools"][0]["function"]["parameters"]["type"], "object");
+
+        let messages = out["messages"].as_array().unwrap();
+        assert_eq!(
+            messages[0],
+            json!({"role": "system", "content": "be brief"})
+        );
+        assert_eq!(messages[2]["content"], "reading");
+        assert_eq!(
+            messages[2]["tool_calls"][0]["function"]["arguments"],
+            "{\"path\":\"a\"}"
+        );
+        assert_eq!(
+            messages[3],
+            json!({"role": "tool", "tool_call_id": "toolu_1", "content": "ok"})
+        );
+        assert_eq!(messages[4], json!({"role": "user", "content": "next"}));
+    }
+
+    #[test]
+    fn response_maps_tool_calls_and_usage() {
+        let body = json!({
+            "id": "chatcmpl-1",
+            "model": "gpt-4o",
+            "choices": [{
+                "finish_reason": "tool_calls",
+                "message": {"role": "assistant", "content": null, "tool_calls": [
+                    {"id": "call_1", "type": "function", "function": {"name": "read", "arguments": "{\"path\":\"a\"}"}}
+                ]}
+            }],
+            "usage": {"prompt_tokens": 100, "completion_tokens": 7, "prompt_tokens_details": {"cached_tokens": 40}}
+        });
+        let out = chat_response_to_message(&body, None).unwrap();
+        assert_eq!(out["stop_reason"], "tool_use");
+        assert_eq!(out["content"][0]["input"], json!({"path": "a"}));
+        assert_eq!(
+            out["usage"],
+            json!({"input_tokens": 60, "output_tokens": 7, "cache_read_input_tokens": 40})
+        );
+    }
+
+    #[test]
+    fn stream_converts_text_and_tool_call_deltas() {
+        let upstream = concat!(
+            "data: {\"id\":\"c1\",\"model\":\"gpt-4o\",\"choices\":[{\"delta\":{\"content\":\"He\"}}]}\n\n",
+            "data: {\"id\":\"c1\",\"choices\":[{\"delta\":{\"content\":\"llo\"}}]}\n\n",
+            "data: {\"id\":\"c1\",\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"function\":{\"name\":\"read\",\"arguments\":\"{\\\"pa\"}}]}}]}\n",
+            "\ndata: {\"id\":\"c1\",\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"th\\\":1}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\n",
+            "data: {\"id\":\"c1\",\"choices\":[],\"usage\":{\"prompt_tokens\":10,\"completion_tokens\":3}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let mut sse = ChatToAnthropicSse::new(None);
+        let mut raw = Vec::new();
+        // Split mid-line to exercise buffering.
+        let (a, b) = upstream.as_bytes().split_at(37);
+        raw.extend(sse.push(a));
+        raw.extend(sse.push(b));
+        raw.extend(sse.finish());
+
+        let events = events(&raw);
+        let types: Vec<&str> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+        assert_eq!(
+            types,
+            [
+                "message_start",
+                "content_block_start",
+                "content_block_delta",
+                "content_block_delta",
+                "content_block_stop",
+                "content_block_start",
+                "content_block_delta",
+                "content_block_delta",
+                "content_block_stop",
+                "message_delta",
+                "message_stop",
+            ]
+        );
+        assert_eq!(events[0]["message"]["model"], "gpt-4o");
+        assert_eq!(events[5]["content_block"]["name"], "read");
+        assert_eq!(events[5]["index"], 1);
+        assert_eq!(events[7]["delta"]["partial_json"], "th\":1}");
+        assert_eq!(events[9]["delta"]["stop_reason"], "tool_use");
+        assert_eq!(events[9]["usage"]["input_tokens"], 10);
+    }
+
+    #[test]
+    fn stream_without_done_is_closed_on_finish() {
+        let mut sse = ChatToAnthropicSse::new(Some("fallback".to_string()));
+        let mut raw = sse.push(b"data: {\"choices\":[{\"delta\":{\"content\":\"x\"}}]}");
+        raw.extend(sse.finish());
+        let events = events(&raw);
+        assert_eq!(events[0]["message"]["model"], "fallback");
+        assert_eq!(events.last().unwrap()["type"], "message_stop");
+        assert!(sse.finish().is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+//! Usage: Protocol translation for providers whose `wire_api` differs from the CLI's native API.
+//!
+//! 用法：
+//! - `ProtocolBridge::for_request` 按 CLI / 路径 / provider.wire_api 决定是否需要转换（当前：claude `/v1/messages`、
+//!   codex `/v1/responses` -> OpenAI `/v1/chat/completions`）
+//! - failover loop 在发送前用 `translate_request_body` + `upstream_path` 改写请求、`prepare_upstream_headers` 清理
+//!   Anthropic 专用请求头；成功响应分别经 `translate_response_body`（JSON）或 `ProtocolBridgeStream`（SSE）转换回
+//!   CLI 原生格式，usage / 日志 / response fixer 均基于转换后的内容
+//! - 上游错误响应（非 2xx）原样透传
+
+mod anthropic_chat;
+mod responses_chat;
+
+use axum::body::Bytes;
+use axum::http::HeaderMap;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::providers::ProviderWireApi;
+
+const CLAUDE_MESSAGES_PATH: &str = "/v1/messages";
+const CODEX_RESPONSES_PATH: &str = "/v1/responses";
+const CHAT_COMPLETIONS_PATH: &str = "/v1/chat/completions";
+
+// Anthropic-only request headers that OpenAI-compatible relays don't expect.
+const ANTHROPIC_ONLY_HEADERS: &[&str] = &[
+    "x-api-key",
+    "anthropic-version",
+    "anthropic-beta",
+    "anthropic-dangerous-direct-browser-access",
+];
+
+// Responses-only opt-ins (e.g. `responses=experimental`).
+const RESPONSES_ONLY_HEADERS: &[&str] = &["openai-beta"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ProtocolBridge {
+    AnthropicToChatCompletions,
+    ResponsesToChatCompletions,
+}
+
+impl ProtocolBridge {
+    pub(super) fn for_request(
+        cli_key: &str,
+        forwarded_path: &str,
+        wire_api: ProviderWireApi,
+    ) -> Option<Self> {
+        let path = forwarded_path.trim_end_matches('/');
+        match (cli_key, wire_api) {
+            ("claude", ProviderWireApi::ChatCompletions) if path == CLAUDE_MESSAGES_PATH => {
+                Some(Self::AnthropicToChatCompletions)
+            }
+            ("codex", ProviderWireApi::ChatCompletions) if path == CODEX_RESPONSES_PATH => {
+                Some(Self::ResponsesToChatCompletions)
+            }
+            _ => None,
+        }
+    }
+
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::AnthropicToChatCompletions => "anthropic_to_chat_completions",
+            Self::ResponsesToChatCompletions => "responses_to_chat_completions",
+        }
+    }
+
+    pub(super) fn upstream_path(self) -> &'static str {
+        match self {
+            Self::AnthropicToChatCompletions | Self::ResponsesToChatCompletions => {
+                CHAT_COMPLETIONS_PATH
+            }
+        }
+    }
+
+    pub(super) fn translate_request_body(self, root: &serde_json::Value) -> Option<Vec<u8>> {
+        let translated = match self {
+            Self::AnthropicToChatCompletions => anthropic_chat::messages_to_chat_request(root)?,
+            Self::ResponsesToChatCompletions => responses_chat::responses_to_chat_request(root)?,
+        };
+        serde_json::to_vec(&translated).ok()
+    }
+
+    pub(super) fn prepare_upstream_headers(self, headers: &mut HeaderMap) {
+        let names = match self {
+            Self::AnthropicToChatCompletions => ANTHROPIC_ONLY_HEADERS,
+            Self::ResponsesToChatCompletions => RESPONSES_ONLY_HEADERS,
+        };
+        for name in names {
+            headers.remove(*name);
+        }
+    }
+
+    /// Translates a buffered non-SSE success body; `None` leaves the body untouched.
+    pub(super) fn translate_response_body(
+        self,
+        body: &[u8],
+        fallback_model: Option<&str>,
+    ) -> Option<Bytes> {
+        let root = serde_json::from_slice::<serde_json::Value>(body).ok()?;
+        let translated = match self {
+            Self::AnthropicToChatCompletions => {
+                anthropic_chat::chat_response_to_message(&root, fallback_model)?
+            }
+            Self::ResponsesToChatCompletions => {
+                responses_chat::chat_response_to_response(&root, fallback_model)?
+            }
+        };
+        serde_json::to_vec(&translated).ok().map(Bytes::from)
+    }
+}
+
+/// Splits an SSE byte stream into `data:` payloads, buffering partial lines across chunks.
+#[derive(Debug, Default)]
+struct SseDataLines {
+    buf: Vec<u8>,
+}
+
+impl SseDataLines {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buf.extend_from_slice(chunk);
+        let mut out = Vec::new();
+        while let Some(pos) = self.buf.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buf.drain(..=pos).collect();
+            out.extend(sse_data_payload(&line));
+        }
+        out
+    }
+
+    fn finish(&mut self) -> Option<String> {
+        let line = std::mem::take(&mut self.buf);
+        sse_data_payload(&line)
+    }
+}
+
+fn sse_data_payload(line: &[u8]) -> Option<String> {
+    let line = std::str::from_utf8(line).ok()?;
+    line.trim()
+        .strip_prefix("data:")
+        .map(|data| data.trim().to_string())
+}
+
+fn write_sse_event(out: &mut Vec<u8>, event: &str, data: serde_json::Value) {
+    out.extend_from_slice(format!("event: {event}\ndata: {data}\n\n").as_bytes());
+}
+
+#[derive(Debug)]
+enum SseTranslator {
+    Anthropic(anthropic_chat::ChatToAnthropicSse),
+    Responses(responses_chat::ChatToResponsesSse),
+}
+
+impl SseTranslator {
+    fn push(&mut self, chunk: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Anthropic(inner) => inner.push(chunk),
+            Self::Responses(inner) => inner.push(chunk),
+        }
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        match self {
+            Self::Anthropic(inner) => inner.finish(),
+            Self::Responses(inner) => inner.finish(),
+        }
+    }
+}
+
+/// SSE adapter; with no bridge it passes chunks through unchanged.
+pub(super) struct ProtocolBridgeStream<S>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+{
+    upstream: S,
+    translator: Option<SseTranslator>,
+    queued: Option<Bytes>,
+    pending_error: Option<reqwest::Error>,
+    upstream_done: bool,
+}
+
+impl<S> ProtocolBridgeStream<S>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+{
+    pub(super) fn new(
+        upstream: S,
+        bridge: Option<ProtocolBridge>,
+        fallback_model: Option<String>,
+    ) -> Self {
+        let translator = bridge.map(|bridge| match bridge {
+            ProtocolBridge::AnthropicToChatCompletions => {
+                SseTranslator::Anthropic(anthropic_chat::ChatToAnthropicSse::new(fallback_model))
+            }
+            ProtocolBridge::ResponsesToChatCompletions => {
+                SseTranslator::Responses(responses_chat::ChatToResponsesSse::new(fallback_model))
+            }
+        });
+        Self {
+            upstream,
+            translator,
+            queued: None,
+            pending_error: None,
+            upstream_done: false,
+        }
+    }
+}
+
+impl<S> Stream for ProtocolBridgeStream<S>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+{
+    type Item = Result<Bytes, reqwest::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.as_mut().get_mut();
+
+        loop {
+            if let Some(bytes) = this.queued.take() {
+                return Poll::Ready(Some(Ok(bytes)));
+            }
+
+            if this.upstream_done {
+                if let Some(err) = this.pending_error.take() {
+                    return Poll::Ready(Some(Err(err)));
+                }
+                return Poll::Ready(None);
+            }
+
+            match Pin::new(&mut this.upstream).poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => {
+                    this.upstream_done = true;
+                    if let Some(translator) = this.translator.as_mut() {
+                        let out = translator.finish();
+                        if !out.is_empty() {
+                            this.queued = Some(Bytes::from(out));
+                        }
+                    }
+                }
+                Poll::Ready(Some(Err(err))) => {
+                    // Don't synthesize message_stop: the client should see the stream as broken.
+                    this.upstream_done = true;
+                    this.pending_error = Some(err);
+                }
+                Poll::Ready(Some(Ok(chunk))) => {
+                    let Some(translator) = this.translator.as_mut() else {
+                        return Poll::Ready(Some(Ok(chunk)));
+                    };
+                    let out = translator.push(&chunk);
+                    if !out.is_empty() {
+                        this.queued = Some(Bytes::from(out));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,851 @@
+//! Usage: OpenAI Responses <-> Chat Completions translation (request body, JSON response, SSE stream).
+
+use serde_json::{json, Map, Value};
+
+use super::{write_sse_event as emit, SseDataLines};
+
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter(|p| {
+                matches!(
+                    p.get("type").and_then(Value::as_str),
+                    Some("input_text" | "output_text" | "text")
+                )
+            })
+            .filter_map(|p| p.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn push_message_item(item: &Value, out: &mut Vec<Value>) {
+    let role = match item.get("role").and_then(Value::as_str).unwrap_or("user") {
+        "developer" => "system",
+        role => role,
+    };
+    let Some(content) = item.get("content") else {
+        return;
+    };
+
+    let images: Vec<Value> = content
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|p| p.get("type").and_then(Value::as_str) == Some("input_image"))
+        .filter_map(|p| p.get("image_url").and_then(Value::as_str))
+        .map(|url| json!({"type": "image_url", "image_url": {"url": url}}))
+        .collect();
+    let text = content_text(content);
+    if role == "user" && !images.is_empty() {
+        let mut parts = Vec::new();
+        if !text.is_empty() {
+            parts.push(json!({"type": "text", "text": text}));
+        }
+        parts.extend(images);
+        out.push(json!({"role": role, "content": parts}));
+        return;
+    }
+    out.push(json!({"role": role, "content": text}));
+}
+
+/// Chat Completions wants all calls of one turn on a single assistant message.
+fn push_function_call_item(item: &Value, out: &mut Vec<Value>) {
+    let call = json!({
+        "id": item.get("call_id").cloned().unwrap_or(Value::Null),
+        "type": "function",
+        "function": {
+            "name": item.get("name").cloned().unwrap_or(Value::Null),
+            "arguments": item.get("arguments").and_then(Value::as_str).unwrap_or("{}"),
+        },
+    });
+    if let Some(last) = out
+        .last_mut()
+        .filter(|m| m.get("role").and_then(Value::as_str) == Some("assistant"))
+        .and_then(Value::as_object_mut)
+    {
+        if let Some(calls) = last
+            .entry("tool_calls")
+            .or_insert_with(|| Value::Array(Vec::new()))
+            .as_array_mut()
+        {
+            calls.push(call);
+            return;
+        }
+    }
+    out.push(json!({"role": "assistant", "content": null, "tool_calls": [call]}));
+}
+
+fn map_tool_choice(choice: &Value) -> Option<Value> {
+    match choice {
+        Value::String(mode) => Some(json!(mode)),
+        Value::Object(obj) if obj.get("type").and_then(Value::as_str) == Some("function") => {
+            Some(json!({
+                "type": "function",
+                "function": {"name": obj.get("name")?.as_str()?},
+            }))
+        }
+        _ => None,
+    }
+}
+
+fn map_text_format(text: &Value) -> Option<Value> {
+    let format = text.get("format")?;
+    match format.get("type").and_then(Value::as_str)? {
+        "json_object" => Some(json!({"type": "json_object"})),
+        "json_schema" => {
+            let mut schema = Map::new();
+            for key in ["name", "description", "schema", "strict"] {
+                if let Some(v) = format.get(key) {
+                    schema.insert(key.to_string(), v.clone());
+                }
+            }
+            Some(json!({"type": "json_schema", "json_schema": schema}))
+        }
+        _ => None,
+    }
+}
+
+/// Translates a `/v1/responses` body into a `/v1/chat/completions` body; `None` if it isn't an object.
+pub(super) fn responses_to_chat_request(root: &Value) -> Option<Value> {
+    let obj = root.as_object()?;
+    let mut out = Map::new();
+
+    if let Some(model) = obj.get("model") {
+        out.insert("model".to_string(), model.clone());
+    }
+
+    let mut messages = Vec::new();
+    if let Some(instructions) = obj
+        .get("instructions")
+        .and_then(Value::as_str)
+        .filter(|v| !v.is_empty())
+    {
+        messages.push(json!({"role": "system", "content": instructions}));
+    }
+    match obj.get("input") {
+        Some(Value::String(text)) => messages.push(json!({"role": "user", "content": text})),
+        Some(Value::Array(items)) => {
+            for item in items {
+                match item.get("type").and_then(Value::as_str) {
+                    Some("message") | None => push_message_item(item, &mut messages),
+                    Some("function_call") => push_function_call_item(item, &mut messages),
+                    Some("function_call_output") => messages.push(json!({
+                        "role": "tool",
+                        "tool_call_id": item.get("call_id").cloned().unwrap_or(Value::Null),
+                        "content": item.get("output").map(content_text).unwrap_or_default(),
+                    })),
+                    // reasoning items (encrypted or summarized) have no Chat Completions form.
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    out.insert("messages".to_string(), Value::Array(messages));
+
+    if let Some(v) = obj.get("max_output_tokens") {
+        out.insert("max_tokens".to_string(), v.clone());
+    }
+    for key in ["temperature", "top_p", "parallel_tool_calls", "user"] {
+        if let Some(v) = obj.get(key) {
+            out.insert(key.to_string(), v.clone());
+        }
+    }
+    if let Some(effort) = obj.get("reasoning").and_then(|r| r.get("effort")) {
+        out.insert("reasoning_effort".to_string(), effort.clone());
+    }
+    if let Some(format) = obj.get("text").and_then(map_text_format) {
+        out.insert("response_format".to_string(), format);
+    }
+    if obj.get("stream").and_then(Value::as_bool) == Some(true) {
+        out.insert("stream".to_string(), json!(true));
+        out.insert("stream_options".to_string(), json!({"include_usage": true}));
+    }
+
+    // Built-in tools (web_search, local_shell, custom ...) have no Chat Completions equivalent.
+    let tools: Vec<Value> = obj
+        .get("tools")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|tool| tool.get("type").and_then(Value::as_str) == Some("function"))
+        .filter_map(|tool| {
+            let mut function = Map::new();
+            function.insert("name".to_string(), tool.get("name")?.clone());
+            for key in ["description", "parameters", "strict"] {
+                if let Some(v) = tool.get(key) {
+                    function.insert(key.to_string(), v.clone());
+                }
+            }
+            Some(json!({"type": "function", "function": function}))
+        })
+        .collect();
+    if !tools.is_empty() {
+        out.insert("tools".to_string(), Value::Array(tools));
+        if let Some(choice) = obj.get("tool_choice").and_then(map_tool_choice) {
+            out.insert("tool_choice".to_string(), choice);
+        }
+    }
+
+    Some(Value::Object(out))
+}
+
+fn usage_to_responses(usage: &Value) -> Value {
+    let field = |v: Option<&Value>| v.and_then(Value::as_i64).unwrap_or(0);
+    let input = field(usage.get("prompt_tokens"));
+    let output = field(usage.get("completion_tokens"));
+    json!({
+        "input_tokens": input,
+        "input_tokens_details": {
+            "cached_tokens": field(usage.pointer("/prompt_tokens_details/cached_tokens")),
+        },
+        "output_tokens": output,
+        "output_tokens_details": {
+            "reasoning_tokens": field(usage.pointer("/completion_tokens_details/reasoning_tokens")),
+        },
+        "total_tokens": usage
+            .get("total_tokens")
+            .and_then(Value::as_i64)
+            .unwrap_or(input + output),
+    })
+}
+
+fn message_item(id: &str, text: &str, status: &str) -> Value {
+    json!({
+        "type": "message",
+        "id": id,
+        "status": status,
+        "role": "assistant",
+        "content": if status == "in_progress" {
+            json!([])
+        } else {
+            json!([{"type": "output_text", "text": text, "annotations": []}])
+        },
+    })
+}
+
+fn function_call_item(id: &str, call_id: &str, name: &str, arguments: &str, status: &str) -> Value {
+    json!({
+        "type": "function_call",
+        "id": id,
+        "call_id": call_id,
+        "name": name,
+        "arguments": arguments,
+        "status": status,
+    })
+}
+
+fn response_object(
+    id: &str,
+    created_at: i64,
+    model: &str,
+    status: &str,
+    output: Vec<Value>,
+    usage: Option<Value>,
+) -> Value {
+    let mut response = json!({
+        "id": id,
+        "object": "response",
+        "created_at": created_at,
+        "status": status,
+        "model": model,
+        "output": output,
+    });
+    if status == "incomplete" {
+        response["incomplete_details"] = json!({"reason": "max_output_tokens"});
+    }
+    if let Some(usage) = usage {
+        response["usage"] = usage;
+    }
+    response
+}
+
+fn response_status(finish_reason: Option<&str>) -> &'static str {
+    match finish_reason {
+        Some("length") => "incomplete",
+        _ => "completed",
+    }
+}
+
+/// Translates a `chat.completion` JSON body into a Responses `response`; `None` if it has no choice.
+pub(super) fn chat_response_to_response(
+    root: &Value,
+    fallback_model: Option<&str>,
+) -> Option<Value> {
+    let choice = root.get("choices")?.as_array()?.first()?;
+    let message = choice.get("message")?;
+    let chat_id = root.get("id").and_then(Value::as_str).unwrap_or("bridge");
+
+    let mut output = Vec::new();
+    if let Some(text) = message
+        .get("content")
+        .and_then(Value::as_str)
+        .filter(|t| !t.is_empty())
+    {
+        output.push(message_item(&format!("msg_{chat_id}"), text, "completed"));
+    }
+    for (index, call) in message
+        .get("tool_calls")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+    {
+        let function = call.get("function");
+        let call_id = call
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("call_bridge_{index}"));
+        output.push(function_call_item(
+            &format!("fc_{call_id}"),
+            &call_id,
+            function
+                .and_then(|f| f.get("name"))
+                .and_then(Value::as_str)
+                .unwrap_or_default(),
+            function
+                .and_then(|f| f.get("arguments"))
+                .and_then(Value::as_str)
+                .unwrap_or("{}"),
+            "completed",
+        ));
+    }
+
+    Some(response_object(
+        &format!("resp_{chat_id}"),
+        root.get("created").and_then(Value::as_i64).unwrap_or(0),
+        root.get("model")
+            .and_then(Value::as_str)
+            .or(fallback_model)
+            .unwrap_or("unknown"),
+        response_status(choice.get("finish_reason").and_then(Value::as_str)),
+        output,
+        root.get("usage").map(usage_to_responses),
+    ))
+}
+
+#[derive(Debug)]
+enum OpenItem {
+    Message {
+        output_index: usize,
+        id: String,
+        text: String,
+    },
+    FunctionCall {
+        call_index: u64,
+        output_index: usize,
+        id: String,
+        call_id: String,
+        name: String,
+        arguments: String,
+    },
+}
+
+/// Incremental `chat.completion.chunk` SSE -> Responses `response.*` SSE event converter.
+#[derive(Debug, Default)]
+pub(super) struct ChatToResponsesSse {
+    lines: SseDataLines,
+    fallback_model: Option<String>,
+    response_id: String,
+    model: String,
+    created_at: i64,
+    started: bool,
+    finished: bool,
+    open_item: Option<OpenItem>,
+    output: Vec<Value>,
+    finish_reason: Option<String>,
+    usage: Option<Value>,
+}
+
+impl ChatToResponsesSse {
+    pub(super) fn new(fallback_model: Option<String>) -> Self {
+        Self {
+            fallback_model,
+            ..Self::default()
+        }
+    }
+
+    pub(super) fn push(&mut self, chunk: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for data in self.lines.push(chunk) {
+            self.handle_data(&data, &mut out);
+        }
+        out
+    }
+
+    /// Flushes a trailing partial line and completes the response if upstream never sent `[DONE]`.
+    pub(super) fn finish(&mut self) -> Vec<u8> {
+        let mut out = Vec::new();
+        if let Some(data) = self.lines.finish() {
+            self.handle_data(&data, &mut out);
+        }
+        self.finish_into(&mut out);
+        out
+    }
+
+    fn handle_data(&mut self, data: &str, out: &mut Vec<u8>) {
+        if data == "[DONE]" {
+            self.finish_into(out);
+            return;
+        }
+        if let Ok(value) = serde_json::from_str::<Value>(data) {
+            self.handle_chunk(&value, out);
+        }
+    }
+
+    fn ensure_started(&mut self, chunk: Option<&Value>, out: &mut Vec<u8>) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+        let chat_id = chunk
+            .and_then(|v| v.get("id"))
+            .and_then(Value::as_str)
+            .unwrap_or("bridge");
+        self.response_id = format!("resp_{chat_id}");
+        self.model = chunk
+            .and_then(|v| v.get("model"))
+            .and_then(Value::as_str)
+            .or(self.fallback_model.as_deref())
+            .unwrap_or("unknown")
+            .to_string();
+        self.created_at = chunk
+            .and_then(|v| v.get("created"))
+            .and_then(Value::as_i64)
+            .unwrap_or(0);
+        let response = self.response("in_progress", Vec::new(), None);
+        emit(
+            out,
+            "response.created",
+            json!({"type": "response.created", "response": response}),
+        );
+    }
+
+    fn response(&self, status: &str, output: Vec<Value>, usage: Option<Value>) -> Value {
+        response_object(
+            &self.response_id,
+            self.created_at,
+            &self.model,
+            status,
+            output,
+            usage,
+        )
+    }
+
+    fn close_item(&mut self, out: &mut Vec<u8>) {
+        let item = match self.open_item.take() {
+            Some(OpenItem::Message {
+                output_index,
+                id,
+                text,
+            }) => {
+                emit(
+                    out,
+                    "response.output_text.done",
+                    json!({
+                        "type": "response.output_text.done",
+                        "item_id": id,
+                        "output_index": output_index,
+                        "content_index": 0,
+                        "text": text,
+                    }),
+                );
+                emit(
+                    out,
+                    "response.content_part.done",
+                    json!({
+                        "type": "response.content_part.done",
+                        "item_id": id,
+                        "output_index": output_index,
+                        "content_index": 0,
+                        "part": {"type": "output_text", "text": text, "annotations": []},
+                    }),
+                );
+                (output_index, message_item(&id, &text, "completed"))
+            }
+            Some(OpenItem::FunctionCall {
+                output_index,
+                id,
+                call_id,
+                name,
+                arguments,
+                ..
+            }) => {
+                emit(
+                    out,
+                    "response.function_call_arguments.done",
+                    json!({
+                        "type": "response.function_call_arguments.done",
+                        "item_id": id,
+                        "output_index": output_index,
+                        "arguments": arguments,
+                    }),
+                );
+                (
+                    output_index,
+                    function_call_item(&id, &call_id, &name, &arguments, "completed"),
+                )
+            }
+            None => return,
+        };
+        let (output_index, item) = item;
+        emit(
+            out,
+            "response.output_item.done",
+            json!({"type": "response.output_item.done", "output_index": output_index, "item": item}),
+        );
+        self.output.push(item);
+    }
+
+    fn open_item(&mut self, item: OpenItem, added: Value, out: &mut Vec<u8>) {
+        let output_index = self.output.len();
+        emit(
+            out,
+            "response.output_item.added",
+            json!({"type": "response.output_item.added", "output_index": output_index, "item": added}),
+        );
+        self.open_item = Some(item);
+    }
+
+    fn handle_text(&mut self, text: &str, out: &mut Vec<u8>) {
+        if !matches!(self.open_item, Some(OpenItem::Message { .. })) {
+            self.close_item(out);
+            let output_index = self.output.len();
+            let id = format!("msg_{}_{output_index}", self.response_id);
+            let added = message_item(&id, "", "in_progress");
+            self.open_item(
+                OpenItem::Message {
+                    output_index,
+                    id: id.clone(),
+                    text: String::new(),
+                },
+                added,
+                out,
+            );
+            emit(
+                out,
+                "response.content_part.added",
+                json!({
+                    "type": "response.content_part.added",
+                    "item_id": id,
+                    "output_index": output_index,
+                    "content_index": 0,
+                    "part": {"type": "output_text", "text": "", "annotations": []},
+                }),
+            );
+        }
+        let Some(OpenItem::Message {
+            output_index,
+            id,
+            text: buffered,
+        }) = self.open_item.as_mut()
+        else {
+            return;
+        };
+        buffered.push_str(text);
+        emit(
+            out,
+            "response.output_text.delta",
+            json!({
+                "type": "response.output_text.delta",
+                "item_id": id,
+                "output_index": output_index,
+                "content_index": 0,
+                "delta": text,
+            }),
+        );
+    }
+
+    fn handle_tool_call(&mut self, call: &Value, out: &mut Vec<u8>) {
+        let call_index = call.get("index").and_then(Value::as_u64).unwrap_or(0);
+        let function = call.get("function");
+        let is_open = matches!(
+            self.open_item,
+            Some(OpenItem::FunctionCall { call_index: open, .. }) if open == call_index
+        );
+        if !is_open {
+            // A new tool call starts with its id / name; stray fragments of closed calls are dropped.
+            let Some(name) = function.and_then(|f| f.get("name")).and_then(Value::as_str) else {
+                return;
+            };
+            self.close_item(out);
+            let output_index = self.output.len();
+            let call_id = call
+                .get("id")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("call_bridge_{output_index}"));
+            let id = format!("fc_{call_id}");
+            let added = function_call_item(&id, &call_id, name, "", "in_progress");
+            self.open_item(
+                OpenItem::FunctionCall {
+                    call_index,
+                    output_index,
+                    id,
+                    call_id,
+                    name: name.to_string(),
+                    arguments: String::new(),
+                },
+                added,
+                out,
+            );
+        }
+        let Some(delta) = function
+            .and_then(|f| f.get("arguments"))
+            .and_then(Value::as_str)
+            .filter(|v| !v.is_empty())
+        else {
+            return;
+        };
+        let Some(OpenItem::FunctionCall {
+            output_index,
+            id,
+            arguments,
+            ..
+        }) = self.open_item.as_mut()
+        else {
+            return;
+        };
+        arguments.push_str(delta);
+        emit(
+            out,
+            "response.function_call_arguments.delta",
+            json!({
+                "type": "response.function_call_arguments.delta",
+                "item_id": id,
+                "output_index": output_index,
+                "delta": delta,
+            }),
+        );
+    }
+
+    fn handle_chunk(&mut self, chunk: &Value, out: &mut Vec<u8>) {
+        if self.finished {
+            return;
+        }
+        if let Some(error) = chunk.get("error") {
+            self.ensure_started(None, out);
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            let output = std::mem::take(&mut self.output);
+            let mut response = self.response("failed", output, None);
+            response["error"] = json!({"code": "server_error", "message": message});
+            emit(
+                out,
+                "response.failed",
+                json!({"type": "response.failed", "response": response}),
+            );
+            self.finished = true;
+            return;
+        }
+
+        self.ensure_started(Some(chunk), out);
+        if let Some(usage) = chunk.get("usage").filter(|v| v.is_object()) {
+            self.usage = Some(usage_to_responses(usage));
+        }
+        let Some(choice) = chunk
+            .get("choices")
+            .and_then(Value::as_array)
+            .and_then(|choices| choices.first())
+        else {
+            return;
+        };
+        if let Some(delta) = choice.get("delta") {
+            if let Some(text) = delta
+                .get("content")
+                .and_then(Value::as_str)
+                .filter(|t| !t.is_empty())
+            {
+                self.handle_text(text, out);
+            }
+            for call in delta
+                .get("tool_calls")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                self.handle_tool_call(call, out);
+            }
+        }
+        if let Some(reason) = choice.get("finish_reason").and_then(Value::as_str) {
+            self.finish_reason = Some(reason.to_string());
+        }
+    }
+
+    fn finish_into(&mut self, out: &mut Vec<u8>) {
+        if self.finished {
+            return;
+        }
+        self.ensure_started(None, out);
+        self.close_item(out);
+        let output = std::mem::take(&mut self.output);
+        let usage = self.usage.take();
+        let response = self.response(
+            response_status(self.finish_reason.as_deref()),
+            output,
+            usage,
+        );
+        emit(
+            out,
+            "response.completed",
+            json!({"type": "response.completed", "response": response}),
+        );
+        self.finished = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(raw: &[u8]) -> Vec<Value> {
+        String::from_utf8_lossy(raw)
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn request_maps_instructions_items_and_tools() {
+        let body = json!({
+            "model": "gpt-5-codex",
+            "instructions": "you are codex",
+            "stream": true,
+            "store": false,
+            "reasoning": {"effort": "high", "summary": "auto"},
+            "include": ["reasoning.encrypted_content"],
+            "tools": [
+                {"type": "function", "name": "shell", "parameters": {"type": "object"}, "strict": false},
+                {"type": "web_search"}
+            ],
+            "tool_choice": "auto",
+            "parallel_tool_calls": false,
+            "input": [
+                {"type": "message", "role": "developer", "content": [{"type": "input_text", "text": "env"}]},
+                {"type": "message", "role": "user", "content": [{"type": "input_text", "text": "ls"}]},
+                {"type": "reasoning", "encrypted_content": "xxx"},
+                {"type": "function_call", "call_id": "call_1", "name": "shell", "arguments": "{\"cmd\":\"ls\"}"},
+                {"type": "function_call", "call_id": "call_2", "name": "shell", "arguments": "{}"},
+                {"type": "function_call_output", "call_id": "call_1", "output": "a.txt"},
+                {"type": "message", "role": "assistant", "content": [{"type": "output_text", "text": "done"}]}
+            ]
+        });
+        let out = responses_to_chat_request(&body).unwrap();
+        assert_eq!(out["reasoning_effort"], "high");
+        assert_eq!(out["stream_options"]["include_usage"], true);
+        assert_eq!(out["parallel_tool_calls"], false);
+        assert_eq!(out["tool_choice"], "auto");
+        assert_eq!(out["tools"].as_array().unwrap().len(), 1);
+        assert_eq!(out["tools"][0]["function"]["name"], "shell");
+        assert!(out.get("store").is_none());
+        assert!(out.get("include").is_none());
+
+        let messages = out["messages"].as_array().unwrap();
+        assert_eq!(
+            messages[0],
+            json!({"role": "system", "content": "you are codex"})
+        );
+        assert_eq!(messages[1], json!({"role": "system", "content": "env"}));
+        assert_eq!(messages[2], json!({"role": "user", "content": "ls"}));
+        assert_eq!(messages[3]["role"], "assistant");
+        assert_eq!(messages[3]["tool_calls"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            messages[4],
+            json!({"role": "tool", "tool_call_id": "call_1", "content": "a.txt"})
+        );
+        assert_eq!(messages[5], json!({"role": "assistant", "content": "done"}));
+    }
+
+    #[test]
+    fn response_maps_message_tool_calls_and_usage() {
+        let body = json!({
+            "id": "chatcmpl-1",
+            "created": 1700000000,
+            "model": "gpt-4o",
+            "choices": [{
+                "finish_reason": "tool_calls",
+                "message": {"role": "assistant", "content": "running", "tool_calls": [
+                    {"id": "call_1", "type": "function", "function": {"name": "shell", "arguments": "{}"}}
+                ]}
+            }],
+            "usage": {"prompt_tokens": 100, "completion_tokens": 7, "prompt_tokens_details": {"cached_tokens": 40}}
+        });
+        let out = chat_response_to_response(&body, None).unwrap();
+        assert_eq!(out["status"], "completed");
+        assert_eq!(out["output"][0]["content"][0]["text"], "running");
+        assert_eq!(out["output"][1]["call_id"], "call_1");
+        assert_eq!(out["usage"]["input_tokens"], 100);
+        assert_eq!(out["usage"]["input_tokens_details"]["cached_tokens"], 40);
+        assert_eq!(out["usage"]["total_tokens"], 107);
+    }
+
+    #[test]
+    fn stream_synthesizes_response_events() {
+        let upstream = concat!(
+            "data: {\"id\":\"c1\",\"model\":\"gpt-4o\",\"choices\":[{\"delta\":{\"content\":\"He\"}}]}\n\n",
+            "data: {\"id\":\"c1\",\"choices\":[{\"delta\":{\"content\":\"llo\"}}]}\n\n",
+            "data: {\"id\":\"c1\",\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"function\":{\"name\":\"shell\",\"arguments\":\"{\\\"a\"}}]}}]}\n",
+            "\ndata: {\"id\":\"c1\",\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"\\\":1}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\n",
+            "data: {\"id\":\"c1\",\"choices\":[],\"usage\":{\"prompt_tokens\":10,\"completion_tokens\":3}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let mut sse = ChatToResponsesSse::new(None);
+        let mut raw = Vec::new();
+        let (a, b) = upstream.as_bytes().split_at(41);
+        raw.extend(sse.push(a));
+        raw.extend(sse.push(b));
+        raw.extend(sse.finish());
+
+        let events = events(&raw);
+        let types: Vec<&str> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+        assert_eq!(
+            types,
+            [
+                "response.created",
+                "response.output_item.added",
+                "response.content_part.added",
+                "response.output_text.delta",
+                "response.output_text.delta",
+                "response.output_text.done",
+                "response.content_part.done",
+                "response.output_item.done",
+                "response.output_item.added",
+                "response.function_call_arguments.delta",
+                "response.function_call_arguments.delta",
+                "response.function_call_arguments.done",
+                "response.output_item.done",
+                "response.completed",
+            ]
+        );
+        assert_eq!(events[5]["text"], "Hello");
+        assert_eq!(events[8]["output_index"], 1);
+        assert_eq!(events[11]["arguments"], "{\"a\":1}");
+        let completed = &events[13]["response"];
+        assert_eq!(completed["id"], "resp_c1");
+        assert_eq!(completed["status"], "completed");
+        assert_eq!(completed["output"].as_array().unwrap().len(), 2);
+        assert_eq!(completed["output"][1]["name"], "shell");
+        assert_eq!(completed["usage"]["output_tokens"], 3);
+    }
+
+    #[test]
+    fn stream_error_chunk_fails_response() {
+        let mut sse = ChatToResponsesSse::new(Some("fallback".to_string()));
+        let mut raw = sse.push(b"data: {\"error\":{\"message\":\"quota exceeded\"}}\n\n");
+        raw.extend(sse.finish());
+        let events = events(&raw);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["response"]["model"], "fallback");
+        assert_eq!(events[1]["type"], "response.failed");
+        assert_eq!(events[1]["response"]["error"]["message"], "quota exceeded");
+    }
+}
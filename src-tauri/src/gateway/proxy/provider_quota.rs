@@ -0,0 +1,165 @@
+//! Usage: Per-provider daily / monthly spend quotas for the failover loop.
+//!
+//! 用法：
+//! - Provider 配置 `daily_quota_usd` / `monthly_quota_usd`（0 = 不限）后，按 `request_logs` 中已计算的费用统计当日 / 当月花费
+//! - 花费达到额度后该 Provider 视为不可用（熔断事件原因 `QUOTA_EXCEEDED`），可用时间为下一个本地日 / 月的起点
+//! - 跨日 / 跨月后花费自然清零，Provider 自动恢复，无需改动启用状态
+//! - 额度配置随供应商快照（`provider_snapshot`）读入；花费按 cli_key 缓存，过期后在后台重算，请求路径只读内存
+//!   （额度判定存在最多一个刷新周期的滞后）；仅首次读取与跨日时同步重算一次
+
+use crate::cost_stats::{self, ProviderSpendReportV1};
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use crate::{db, providers};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+struct CachedSpend {
+    refreshed_at: Instant,
+    report: ProviderSpendReportV1,
+    refreshing: bool,
+}
+
+static SPEND_CACHE: Mutex<Option<HashMap<String, CachedSpend>>> = Mutex::new(None);
+
+fn has_quota(provider: &providers::ProviderForGateway) -> bool {
+    provider.daily_quota_usd > 0.0 || provider.monthly_quota_usd > 0.0
+}
+
+/// Returns when the provider becomes available again, or `None` while it is within quota.
+fn quota_exceeded_until(
+    daily_quota_usd: f64,
+    monthly_quota_usd: f64,
+    daily_cost_usd: f64,
+    monthly_cost_usd: f64,
+    report: &ProviderSpendReportV1,
+) -> Option<i64> {
+    let monthly = (monthly_quota_usd > 0.0 && monthly_cost_usd >= monthly_quota_usd)
+        .then_some(report.monthly_reset_at);
+    let daily = (daily_quota_usd > 0.0 && daily_cost_usd >= daily_quota_usd)
+        .then_some(report.daily_reset_at);
+    monthly.max(daily)
+}
+
+fn cached_report(db: &db::Db, cli_key: &str) -> Option<ProviderSpendReportV1> {
+    let now = now_unix_seconds();
+    {
+        let mut guard = SPEND_CACHE.lock_or_recover();
+        if let Some(cached) = guard.as_mut().and_then(|cache| cache.get_mut(cli_key)) {
+            // A day rollover makes the cached spend wrong, not just stale: reload it inline.
+            if now < cached.report.daily_reset_at {
+                if cached.refreshed_at.elapsed() >= REFRESH_INTERVAL && !cached.refreshing {
+                    cached.refreshing = true;
+                    let db = db.clone();
+                    let cli_key = cli_key.to_string();
+                    std::mem::drop(tauri::async_runtime::spawn_blocking(move || {
+                        load_report(&db, &cli_key);
+                    }));
+                }
+                return Some(cached.report.clone());
+            }
+        }
+    }
+    load_report(db, cli_key)
+}
+
+fn load_report(db: &db::Db, cli_key: &str) -> Option<ProviderSpendReportV1> {
+    let loaded = cost_stats::provider_spend_v1(db, cli_key);
+    let mut guard = SPEND_CACHE.lock_or_recover();
+    let cache = guard.get_or_insert_with(HashMap::new);
+    match loaded {
+        Ok(report) => {
+            cache.insert(
+                cli_key.to_string(),
+                CachedSpend {
+                    refreshed_at: Instant::now(),
+                    report: report.clone(),
+                    refreshing: false,
+                },
+            );
+            Some(report)
+        }
+        Err(err) => {
+            tracing::warn!(cli_key = %cli_key, "供应商额度读取花费失败: {}", err);
+            // Keep serving the previous spend; retry after another interval.
+            if let Some(cached) = cache.get_mut(cli_key) {
+                cached.refreshed_at = Instant::now();
+                cached.refreshing = false;
+            }
+            None
+        }
+    }
+}
+
+/// Providers over their spend quota, mapped to the unix second they become available again.
+pub(super) fn exceeded_providers(
+    db: &db::Db,
+    cli_key: &str,
+    providers: &[providers::ProviderForGateway],
+) -> HashMap<i64, i64> {
+    if !providers.iter().any(has_quota) {
+        return HashMap::new();
+    }
+    let Some(report) = cached_report(db, cli_key) else {
+        return HashMap::new();
+    };
+
+    let spend: HashMap<i64, (f64, f64)> = report
+        .rows
+        .iter()
+        .map(|row| (row.provider_id, (row.daily_cost_usd, row.monthly_cost_usd)))
+        .collect();
+    providers
+        .iter()
+        .filter(|p| has_quota(p))
+        .filter_map(|p| {
+            let (daily_cost, monthly_cost) = spend.get(&p.id).copied().unwrap_or_default();
+            quota_exceeded_until(
+                p.daily_quota_usd,
+                p.monthly_quota_usd,
+                daily_cost,
+                monthly_cost,
+                &report,
+            )
+            .map(|until| (p.id, until))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> ProviderSpendReportV1 {
+        ProviderSpendReportV1 {
+            daily_reset_at: 1_000,
+            monthly_reset_at: 50_000,
+            rows: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn quota_exceeded_until_picks_the_later_reset() {
+        let report = report();
+        // within both budgets / unlimited
+        assert_eq!(quota_exceeded_until(5.0, 100.0, 4.99, 50.0, &report), None);
+        assert_eq!(quota_exceeded_until(0.0, 0.0, 999.0, 999.0, &report), None);
+        // daily budget reached -> back tomorrow
+        assert_eq!(
+            quota_exceeded_until(5.0, 100.0, 5.0, 50.0, &report),
+            Some(1_000)
+        );
+        // monthly budget reached -> back next month, even if the daily one is also hit
+        assert_eq!(
+            quota_exceeded_until(0.0, 100.0, 0.0, 120.0, &report),
+            Some(50_000)
+        );
+        assert_eq!(
+            quota_exceeded_until(5.0, 100.0, 6.0, 120.0, &report),
+            Some(50_000)
+        );
+    }
+}
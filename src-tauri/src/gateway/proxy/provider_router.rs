@@ -1,5 +1,6 @@
 //! Usage: Provider routing helpers (gate + record circuit outcomes) for gateway proxy.
 
+use super::{circuit_failures, rate_limit};
 use crate::circuit_breaker;
 use crate::gateway::events::{emit_circuit_event, emit_circuit_transition, GatewayCircuitEvent};
 
@@ -11,10 +12,59 @@ pub(super) struct GateProviderArgs<'a> {
     pub(super) provider_id: i64,
     pub(super) provider_name: &'a str,
     pub(super) provider_base_url_display: &'a str,
+    pub(super) rpm_limit: i64,
+    pub(super) tpm_limit: i64,
+    pub(super) quota_exceeded_until: Option<i64>,
     pub(super) now_unix: i64,
     pub(super) earliest_available_unix: &'a mut Option<i64>,
     pub(super) skipped_open: &'a mut usize,
     pub(super) skipped_cooldown: &'a mut usize,
+    pub(super) skipped_rate_limited: &'a mut usize,
+    pub(super) skipped_quota: &'a mut usize,
+}
+
+fn note_available_at(earliest_available_unix: &mut Option<i64>, until: i64, now_unix: i64) {
+    if until > now_unix {
+        *earliest_available_unix = Some(match *earliest_available_unix {
+            Some(cur) => cur.min(until),
+            None => until,
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn emit_skip_event(
+    app: Option<&tauri::AppHandle>,
+    trace_id: &str,
+    cli_key: &str,
+    provider_id: i64,
+    provider_name: &str,
+    provider_base_url_display: &str,
+    snap: &circuit_breaker::CircuitSnapshot,
+    reason: &'static str,
+    now_unix: i64,
+) {
+    let Some(app) = app else {
+        return;
+    };
+    emit_circuit_event(
+        app,
+        GatewayCircuitEvent {
+            trace_id: trace_id.to_string(),
+            cli_key: cli_key.to_string(),
+            provider_id,
+            provider_name: provider_name.to_string(),
+            base_url: provider_base_url_display.to_string(),
+            prev_state: snap.state.as_str(),
+            next_state: snap.state.as_str(),
+            failure_count: snap.failure_count,
+            failure_threshold: snap.failure_threshold,
+            open_until: snap.open_until,
+            cooldown_until: snap.cooldown_until,
+            reason,
+            ts: now_unix,
+        },
+    );
 }
 
 pub(super) fn gate_provider(
@@ -28,12 +78,35 @@ pub(super) fn gate_provider(
         provider_id,
         provider_name,
         provider_base_url_display,
+        rpm_limit,
+        tpm_limit,
+        quota_exceeded_until,
         now_unix,
         earliest_available_unix,
         skipped_open,
         skipped_cooldown,
+        skipped_rate_limited,
+        skipped_quota,
     } = args;
 
+    // Over-budget providers are skipped before touching the circuit (no half-open probe spent).
+    if let Some(until) = quota_exceeded_until {
+        *skipped_quota = skipped_quota.saturating_add(1);
+        note_available_at(earliest_available_unix, until, now_unix);
+        emit_skip_event(
+            app,
+            trace_id,
+            cli_key,
+            provider_id,
+            provider_name,
+            provider_base_url_display,
+            &circuit.snapshot(provider_id, now_unix),
+            "QUOTA_EXCEEDED",
+            now_unix,
+        );
+        return None;
+    }
+
     let allow = circuit.should_allow(provider_id, now_unix);
     if let (Some(app), Some(t)) = (app, allow.transition.as_ref()) {
         emit_circuit_transition(
@@ -49,7 +122,29 @@ pub(super) fn gate_provider(
     }
 
     if allow.allow {
-        return Some(allow.after.clone());
+        let snap = allow.after;
+        let Err(retry_after_secs) = rate_limit::try_acquire(provider_id, rpm_limit, tpm_limit)
+        else {
+            return Some(snap);
+        };
+        *skipped_rate_limited = skipped_rate_limited.saturating_add(1);
+        note_available_at(
+            earliest_available_unix,
+            now_unix.saturating_add(retry_after_secs as i64),
+            now_unix,
+        );
+        emit_skip_event(
+            app,
+            trace_id,
+            cli_key,
+            provider_id,
+            provider_name,
+            provider_base_url_display,
+            &snap,
+            "SKIP_RATE_LIMITED",
+            now_unix,
+        );
+        return None;
     }
 
     let snap = allow.after;
@@ -62,34 +157,20 @@ pub(super) fn gate_provider(
     };
 
     if let Some(until) = snap.cooldown_until.or(snap.open_until) {
-        if until > now_unix {
-            *earliest_available_unix = Some(match *earliest_available_unix {
-                Some(cur) => cur.min(until),
-                None => until,
-            });
-        }
+        note_available_at(earliest_available_unix, until, now_unix);
     }
 
-    if let Some(app) = app {
-        emit_circuit_event(
-            app,
-            GatewayCircuitEvent {
-                trace_id: trace_id.to_string(),
-                cli_key: cli_key.to_string(),
-                provider_id,
-                provider_name: provider_name.to_string(),
-                base_url: provider_base_url_display.to_string(),
-                prev_state: snap.state.as_str(),
-                next_state: snap.state.as_str(),
-                failure_count: snap.failure_count,
-                failure_threshold: snap.failure_threshold,
-                open_until: snap.open_until,
-                cooldown_until: snap.cooldown_until,
-                reason,
-                ts: now_unix,
-            },
-        );
-    }
+    emit_skip_event(
+        app,
+        trace_id,
+        cli_key,
+        provider_id,
+        provider_name,
+        provider_base_url_display,
+        &snap,
+        reason,
+        now_unix,
+    );
 
     None
 }
@@ -182,6 +263,9 @@ pub(in crate::gateway) fn record_success_and_emit_transition(
     } = args;
 
     let change = circuit.record_success(provider_id, now_unix);
+    if change.transition.is_some() {
+        circuit_failures::clear(provider_id);
+    }
     if let (Some(app), Some(t)) = (app, change.transition.as_ref()) {
         emit_circuit_transition(
             app,
@@ -257,6 +341,8 @@ mod tests {
         let mut earliest: Option<i64> = None;
         let mut skipped_open = 0usize;
         let mut skipped_cooldown = 0usize;
+        let mut skipped_rate_limited = 0usize;
+        let mut skipped_quota = 0usize;
 
         let snap = gate_provider(GateProviderArgs {
             app: None,
@@ -266,10 +352,15 @@ mod tests {
             provider_id: pid,
             provider_name: "p1",
             provider_base_url_display: "https://example.invalid",
+            rpm_limit: 0,
+            tpm_limit: 0,
+            quota_exceeded_until: None,
             now_unix: now,
             earliest_available_unix: &mut earliest,
             skipped_open: &mut skipped_open,
             skipped_cooldown: &mut skipped_cooldown,
+            skipped_rate_limited: &mut skipped_rate_limited,
+            skipped_quota: &mut skipped_quota,
         })
         .expect("should allow");
 
@@ -296,6 +387,8 @@ mod tests {
         let mut earliest: Option<i64> = None;
         let mut skipped_open = 0usize;
         let mut skipped_cooldown = 0usize;
+        let mut skipped_rate_limited = 0usize;
+        let mut skipped_quota = 0usize;
 
         let allowed = gate_provider(GateProviderArgs {
             app: None,
@@ -305,10 +398,15 @@ mod tests {
             provider_id: pid,
             provider_name: "p1",
             provider_base_url_display: "https://example.invalid",
+            rpm_limit: 0,
+            tpm_limit: 0,
+            quota_exceeded_until: None,
             now_unix: now,
             earliest_available_unix: &mut earliest,
             skipped_open: &mut skipped_open,
             skipped_cooldown: &mut skipped_cooldown,
+            skipped_rate_limited: &mut skipped_rate_limited,
+            skipped_quota: &mut skipped_quota,
         });
 
         assert!(allowed.is_none());
@@ -333,6 +431,8 @@ mod tests {
         let mut earliest: Option<i64> = None;
         let mut skipped_open = 0usize;
         let mut skipped_cooldown = 0usize;
+        let mut skipped_rate_limited = 0usize;
+        let mut skipped_quota = 0usize;
 
         let allowed = gate_provider(GateProviderArgs {
             app: None,
@@ -342,10 +442,15 @@ mod tests {
             provider_id: pid,
             provider_name: "p1",
             provider_base_url_display: "https://example.invalid",
+            rpm_limit: 0,
+            tpm_limit: 0,
+            quota_exceeded_until: None,
             now_unix: now,
             earliest_available_unix: &mut earliest,
             skipped_open: &mut skipped_open,
             skipped_cooldown: &mut skipped_cooldown,
+            skipped_rate_limited: &mut skipped_rate_limited,
+            skipped_quota: &mut skipped_quota,
         });
 
         assert!(allowed.is_none());
@@ -368,6 +473,8 @@ mod tests {
         let mut earliest: Option<i64> = None;
         let mut skipped_open = 0usize;
         let mut skipped_cooldown = 0usize;
+        let mut skipped_rate_limited = 0usize;
+        let mut skipped_quota = 0usize;
 
         let snap = gate_provider(GateProviderArgs {
             app: None,
@@ -377,10 +484,15 @@ mod tests {
             provider_id: pid,
             provider_name: "p1",
             provider_base_url_display: "https://example.invalid",
+            rpm_limit: 0,
+            tpm_limit: 0,
+            quota_exceeded_until: None,
             now_unix: open_until,
             earliest_available_unix: &mut earliest,
             skipped_open: &mut skipped_open,
             skipped_cooldown: &mut skipped_cooldown,
+            skipped_rate_limited: &mut skipped_rate_limited,
+            skipped_quota: &mut skipped_quota,
         })
         .expect("should allow after expiry");
 
@@ -390,6 +502,94 @@ mod tests {
         assert_eq!(skipped_cooldown, 0);
     }
 
+    #[test]
+    fn gate_provider_skips_rate_limited_and_updates_earliest() {
+        let cb = breaker(circuit_breaker::CircuitBreakerConfig {
+            failure_threshold: 5,
+            open_duration_secs: 60,
+        });
+        // Buckets are process-wide; use an id no other test touches.
+        let pid = 9_001;
+        let now = 1_000;
+
+        let mut earliest: Option<i64> = None;
+        let mut skipped_open = 0usize;
+        let mut skipped_cooldown = 0usize;
+        let mut skipped_rate_limited = 0usize;
+        let mut skipped_quota = 0usize;
+
+        let mut gate = || {
+            gate_provider(GateProviderArgs {
+                app: None,
+                circuit: &cb,
+                trace_id: "t",
+                cli_key: "claude",
+                provider_id: pid,
+                provider_name: "p1",
+                provider_base_url_display: "https://example.invalid",
+                rpm_limit: 1,
+                tpm_limit: 0,
+                quota_exceeded_until: None,
+                now_unix: now,
+                earliest_available_unix: &mut earliest,
+                skipped_open: &mut skipped_open,
+                skipped_cooldown: &mut skipped_cooldown,
+                skipped_rate_limited: &mut skipped_rate_limited,
+                skipped_quota: &mut skipped_quota,
+            })
+        };
+
+        assert!(gate().is_some());
+        assert!(gate().is_none());
+        assert_eq!(skipped_rate_limited, 1);
+        assert!(earliest.is_some_and(|t| t > now && t <= now + 60));
+        assert_eq!(skipped_open, 0);
+        assert_eq!(skipped_cooldown, 0);
+    }
+
+    #[test]
+    fn gate_provider_skips_quota_exceeded_until_reset() {
+        let cb = breaker(circuit_breaker::CircuitBreakerConfig {
+            failure_threshold: 5,
+            open_duration_secs: 60,
+        });
+        let pid = 1;
+        let now = 1_000;
+        let reset_at = now + 3_600;
+
+        let mut earliest: Option<i64> = None;
+        let mut skipped_open = 0usize;
+        let mut skipped_cooldown = 0usize;
+        let mut skipped_rate_limited = 0usize;
+        let mut skipped_quota = 0usize;
+
+        let allowed = gate_provider(GateProviderArgs {
+            app: None,
+            circuit: &cb,
+            trace_id: "t",
+            cli_key: "claude",
+            provider_id: pid,
+            provider_name: "p1",
+            provider_base_url_display: "https://example.invalid",
+            rpm_limit: 0,
+            tpm_limit: 0,
+            quota_exceeded_until: Some(reset_at),
+            now_unix: now,
+            earliest_available_unix: &mut earliest,
+            skipped_open: &mut skipped_open,
+            skipped_cooldown: &mut skipped_cooldown,
+            skipped_rate_limited: &mut skipped_rate_limited,
+            skipped_quota: &mut skipped_quota,
+        });
+
+        assert!(allowed.is_none());
+        assert_eq!(earliest, Some(reset_at));
+        assert_eq!(skipped_quota, 1);
+        assert_eq!(skipped_open, 0);
+        assert_eq!(skipped_cooldown, 0);
+        assert_eq!(skipped_rate_limited, 0);
+    }
+
     #[test]
     fn record_failure_reports_open_transition_when_threshold_reached() {
         let cb = breaker(circuit_breaker::CircuitBreakerConfig {
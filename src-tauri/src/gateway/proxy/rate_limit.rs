@@ -0,0 +1,204 @@
+//! Usage: Per-provider token-bucket rate limiting (RPM / TPM) for the failover loop.
+//!
+//! 用法：
+//! - Provider 配置 `rpm_limit` / `tpm_limit`（0 = 不限）后，各自拥有一个请求桶 / token 桶
+//! - 桶容量等于每分钟限额，按 `limit / 60` 每秒匀速回填
+//! - 选中 Provider 时扣 1 个请求；token 桶余量不足 1 时同样视为耗尽
+//! - 实际 token 用量在请求结束后按 usage 补扣，允许透支（透支期间该 Provider 被跳过）
+//! - 桶耗尽的 Provider 由故障切换循环以 `SKIP_RATE_LIMITED` 跳过；状态仅在内存中，重启后重置
+
+use crate::shared::mutex_ext::MutexExt;
+use crate::usage::UsageMetrics;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+const REFILL_WINDOW_SECS: f64 = 60.0;
+
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: i64, now: Instant) -> Self {
+        Self {
+            capacity: limit as f64,
+            tokens: limit as f64,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * self.capacity / REFILL_WINDOW_SECS).min(self.capacity);
+        self.updated_at = now;
+    }
+
+    fn seconds_until(&self, amount: f64) -> f64 {
+        let missing = amount - self.tokens;
+        if missing <= 0.0 || self.capacity <= 0.0 {
+            return 0.0;
+        }
+        missing * REFILL_WINDOW_SECS / self.capacity
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProviderBuckets {
+    rpm: Option<TokenBucket>,
+    tpm: Option<TokenBucket>,
+}
+
+static BUCKETS: Mutex<Option<HashMap<i64, ProviderBuckets>>> = Mutex::new(None);
+
+/// Keeps the bucket in sync with the configured limit (`<= 0` removes it) and refills it.
+fn sync_bucket(slot: &mut Option<TokenBucket>, limit: i64, now: Instant) {
+    if limit <= 0 {
+        *slot = None;
+        return;
+    }
+    match slot {
+        Some(bucket) => {
+            bucket.refill(now);
+            let capacity = limit as f64;
+            if bucket.capacity != capacity {
+                bucket.capacity = capacity;
+                bucket.tokens = bucket.tokens.min(capacity);
+            }
+        }
+        None => *slot = Some(TokenBucket::new(limit, now)),
+    }
+}
+
+/// Takes one request from the RPM bucket; on exhaustion returns the seconds until both buckets
+/// can serve a request again.
+fn try_acquire_at(
+    buckets: &mut ProviderBuckets,
+    rpm_limit: i64,
+    tpm_limit: i64,
+    now: Instant,
+) -> Result<(), u64> {
+    sync_bucket(&mut buckets.rpm, rpm_limit, now);
+    sync_bucket(&mut buckets.tpm, tpm_limit, now);
+
+    let wait_secs = buckets
+        .rpm
+        .iter()
+        .chain(buckets.tpm.iter())
+        .map(|bucket| bucket.seconds_until(1.0))
+        .fold(0.0_f64, f64::max);
+    if wait_secs > 0.0 {
+        return Err((wait_secs.ceil() as u64).max(1));
+    }
+
+    if let Some(rpm) = buckets.rpm.as_mut() {
+        rpm.tokens -= 1.0;
+    }
+    Ok(())
+}
+
+fn billable_tokens(metrics: &UsageMetrics) -> i64 {
+    metrics
+        .total_tokens
+        .unwrap_or_else(|| metrics.input_tokens.unwrap_or(0) + metrics.output_tokens.unwrap_or(0))
+}
+
+pub(super) fn try_acquire(provider_id: i64, rpm_limit: i64, tpm_limit: i64) -> Result<(), u64> {
+    let mut guard = BUCKETS.lock_or_recover();
+    let all = guard.get_or_insert_with(HashMap::new);
+    if rpm_limit <= 0 && tpm_limit <= 0 {
+        all.remove(&provider_id);
+        return Ok(());
+    }
+    let buckets = all.entry(provider_id).or_default();
+    try_acquire_at(buckets, rpm_limit, tpm_limit, Instant::now())
+}
+
+/// Charges the reported usage against the provider's TPM bucket (no-op when unlimited).
+pub(in crate::gateway) fn charge_usage(provider_id: i64, usage: Option<&UsageMetrics>) {
+    let Some(tokens) = usage.map(billable_tokens).filter(|v| *v > 0) else {
+        return;
+    };
+    let mut guard = BUCKETS.lock_or_recover();
+    let Some(tpm) = guard
+        .as_mut()
+        .and_then(|all| all.get_mut(&provider_id))
+        .and_then(|buckets| buckets.tpm.as_mut())
+    else {
+        return;
+    };
+    tpm.refill(Instant::now());
+    tpm.tokens -= tokens as f64;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn rpm_bucket_exhausts_and_refills_over_time() {
+        let start = Instant::now();
+        let mut buckets = ProviderBuckets::default();
+
+        assert_eq!(try_acquire_at(&mut buckets, 2, 0, start), Ok(()));
+        assert_eq!(try_acquire_at(&mut buckets, 2, 0, start), Ok(()));
+        // 2 RPM -> one request every 30s
+        assert_eq!(try_acquire_at(&mut buckets, 2, 0, start), Err(30));
+        assert_eq!(
+            try_acquire_at(&mut buckets, 2, 0, start + Duration::from_secs(15)),
+            Err(15)
+        );
+        assert_eq!(
+            try_acquire_at(&mut buckets, 2, 0, start + Duration::from_secs(30)),
+            Ok(())
+        );
+        assert!(buckets.tpm.is_none());
+    }
+
+    #[test]
+    fn tpm_debt_blocks_until_repaid() {
+        let start = Instant::now();
+        let mut buckets = ProviderBuckets::default();
+
+        assert_eq!(try_acquire_at(&mut buckets, 0, 600, start), Ok(()));
+        // a response reported 900 tokens: 300 over budget, refilling at 10 tokens/s
+        buckets.tpm.as_mut().expect("tpm bucket").tokens -= 900.0;
+        assert_eq!(try_acquire_at(&mut buckets, 0, 600, start), Err(31));
+        assert_eq!(
+            try_acquire_at(&mut buckets, 0, 600, start + Duration::from_secs(31)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn lowering_limit_caps_available_tokens() {
+        let start = Instant::now();
+        let mut buckets = ProviderBuckets::default();
+
+        assert_eq!(try_acquire_at(&mut buckets, 60, 0, start), Ok(()));
+        assert_eq!(try_acquire_at(&mut buckets, 1, 0, start), Ok(()));
+        assert_eq!(try_acquire_at(&mut buckets, 1, 0, start), Err(60));
+        assert_eq!(try_acquire_at(&mut buckets, 0, 0, start), Ok(()));
+        assert!(buckets.rpm.is_none());
+    }
+
+    #[test]
+    fn billable_tokens_prefers_total() {
+        let metrics = UsageMetrics {
+            input_tokens: Some(10),
+            output_tokens: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(billable_tokens(&metrics), 15);
+        let metrics = UsageMetrics {
+            total_tokens: Some(42),
+            ..metrics
+        };
+        assert_eq!(billable_tokens(&metrics), 42);
+    }
+}
@@ -0,0 +1,274 @@
+//! Usage: Client request body intake — buffer small bodies, stream large ones to the upstream.
+//!
+//! 用法：
+//! - `read_request_body` 最多缓冲 `MAX_REQUEST_BODY_BYTES`；body 在此之内读完时返回完整 `Bytes`，保留全部改写 / 故障切换能力
+//! - 超出缓冲上限时返回 `StreamingRequestBody`：已读的前缀用于嗅探 model / 计算指纹，其余部分边读边转发给上游
+//! - 流式 body 只能发送一次：只尝试一个 Provider 一次，且不做 body 改写（脱敏 / 缓存注入 / 模型映射 / 协议转换）
+//! - 启用了必须改写 body 的隐私功能（请求脱敏、Codex 强制 `store: false`）时，流式 body 直接以 413 拒绝（`privacy_rewrite_required`）
+//! - 流式 body 的指纹只覆盖前缀：不参与响应缓存、并发去重、近期错误缓存与 trace_id 复用
+//! - 流式转发的总大小上限为 `MAX_STREAMING_REQUEST_BODY_BYTES`，超出时中断上游请求
+
+use super::super::util::MAX_REQUEST_BODY_BYTES;
+use crate::providers;
+use axum::body::{Body, BodyDataStream, Bytes};
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pub(super) const MAX_STREAMING_REQUEST_BODY_BYTES: usize = 256 * 1024 * 1024;
+
+pub(super) enum RequestBody {
+    Buffered(Bytes),
+    Streaming(StreamingRequestBody),
+}
+
+pub(super) struct StreamingRequestBody {
+    prefix: Bytes,
+    rest: BodyDataStream,
+}
+
+impl StreamingRequestBody {
+    /// The buffered head of the body (at least `MAX_REQUEST_BODY_BYTES`).
+    pub(super) fn prefix(&self) -> &Bytes {
+        &self.prefix
+    }
+
+    /// Consumes the body: replays the prefix, then forwards the remaining client chunks.
+    pub(super) fn into_upstream_body(self) -> reqwest::Body {
+        reqwest::Body::wrap_stream(UpstreamBodyStream {
+            sent: 0,
+            prefix: Some(self.prefix),
+            rest: self.rest,
+        })
+    }
+}
+
+/// The body-rewriting privacy feature a streamed body would silently bypass, if any is enabled for
+/// this request.
+pub(super) fn privacy_rewrite_required(
+    request_redaction_enabled: bool,
+    cli_key: &str,
+    forwarded_path: &str,
+    providers: &[providers::ProviderForGateway],
+) -> Option<&'static str> {
+    if request_redaction_enabled {
+        return Some("request_redaction");
+    }
+    let codex_responses =
+        cli_key == "codex" && forwarded_path.trim_end_matches('/').ends_with("/responses");
+    if codex_responses && providers.iter().any(|p| p.disable_response_storage) {
+        return Some("codex_disable_response_storage");
+    }
+    None
+}
+
+async fn next_chunk(stream: &mut BodyDataStream) -> Option<Result<Bytes, axum::Error>> {
+    std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+}
+
+pub(super) async fn read_request_body(body: Body) -> Result<RequestBody, axum::Error> {
+    let mut stream = body.into_data_stream();
+    let mut chunks: Vec<Bytes> = Vec::new();
+    let mut buffered = 0usize;
+
+    while let Some(chunk) = next_chunk(&mut stream).await {
+        let chunk = chunk?;
+        buffered = buffered.saturating_add(chunk.len());
+        chunks.push(chunk);
+        if buffered > MAX_REQUEST_BODY_BYTES {
+            return Ok(RequestBody::Streaming(StreamingRequestBody {
+                prefix: concat_chunks(chunks, buffered),
+                rest: stream,
+            }));
+        }
+    }
+
+    Ok(RequestBody::Buffered(concat_chunks(chunks, buffered)))
+}
+
+fn concat_chunks(mut chunks: Vec<Bytes>, total: usize) -> Bytes {
+    if chunks.len() == 1 {
+        return chunks.pop().unwrap_or_default();
+    }
+    let mut out = Vec::with_capacity(total);
+    for chunk in &chunks {
+        out.extend_from_slice(chunk);
+    }
+    Bytes::from(out)
+}
+
+struct UpstreamBodyStream {
+    sent: usize,
+    prefix: Option<Bytes>,
+    rest: BodyDataStream,
+}
+
+impl Stream for UpstreamBodyStream {
+    type Item = Result<Bytes, std::io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let next = match self.prefix.take() {
+            Some(prefix) => Some(Ok(prefix)),
+            None => match Pin::new(&mut self.rest).poll_next(cx) {
+                Poll::Ready(next) => next.map(|r| r.map_err(std::io::Error::other)),
+                Poll::Pending => return Poll::Pending,
+            },
+        };
+        if let Some(Ok(chunk)) = next.as_ref() {
+            self.sent = self.sent.saturating_add(chunk.len());
+            if self.sent > MAX_STREAMING_REQUEST_BODY_BYTES {
+                return Poll::Ready(Some(Err(std::io::Error::other(format!(
+                    "request body exceeds {MAX_STREAMING_REQUEST_BODY_BYTES} bytes"
+                )))));
+            }
+        }
+        Poll::Ready(next)
+    }
+}
+
+fn skip_ws(buf: &[u8], mut i: usize) -> usize {
+    while buf.get(i).is_some_and(|b| b.is_ascii_whitespace()) {
+        i += 1;
+    }
+    i
+}
+
+/// Index of the closing quote of the JSON string opening at `open`.
+fn string_end(buf: &[u8], open: usize) -> Option<usize> {
+    let mut i = open + 1;
+    while i < buf.len() {
+        match buf[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Finds the top-level `"model"` string in a (possibly truncated) JSON object without parsing it.
+pub(super) fn sniff_model(prefix: &[u8]) -> Option<String> {
+    let mut i = skip_ws(prefix, 0);
+    if prefix.get(i) != Some(&b'{') {
+        return None;
+    }
+
+    let mut depth = 0usize;
+    let mut expect_key = false;
+    while i < prefix.len() {
+        match prefix[i] {
+            b'"' => {
+                let end = string_end(prefix, i)?;
+                if depth == 1 && expect_key {
+                    let colon = skip_ws(prefix, end + 1);
+                    if &prefix[i + 1..end] == b"model" && prefix.get(colon) == Some(&b':') {
+                        let start = skip_ws(prefix, colon + 1);
+                        if prefix.get(start) != Some(&b'"') {
+                            return None;
+                        }
+                        let value_end = string_end(prefix, start)?;
+                        return serde_json::from_slice::<String>(&prefix[start..=value_end]).ok();
+                    }
+                    expect_key = false;
+                }
+                i = end + 1;
+            }
+            b'{' | b'[' => {
+                depth += 1;
+                expect_key = depth == 1;
+                i += 1;
+            }
+            b'}' | b']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return None;
+                }
+                i += 1;
+            }
+            b',' => {
+                expect_key = depth == 1;
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streamed_bodies_are_refused_while_redaction_is_on() {
+        assert_eq!(
+            privacy_rewrite_required(true, "claude", "/v1/messages", &[]),
+            Some("request_redaction")
+        );
+        assert_eq!(
+            privacy_rewrite_required(false, "codex", "/v1/responses", &[]),
+            None
+        );
+    }
+
+    #[test]
+    fn sniff_model_reads_top_level_key_from_truncated_json() {
+        assert_eq!(
+            sniff_model(br#"{"model":"claude-sonnet-4","messages":[{"role":"us"#).as_deref(),
+            Some("claude-sonnet-4")
+        );
+        // nested "model" keys and string values are ignored
+        assert_eq!(
+            sniff_model(
+                br#" {"metadata":{"model":"x"},"note":"\"model\": \"y\"", "model" : "gpt-5-1"}"#
+            )
+            .as_deref(),
+            Some("gpt-5-1")
+        );
+        // the key lies beyond the buffered prefix
+        assert_eq!(sniff_model(br#"{"messages":[{"content":"model""#), None);
+        assert_eq!(sniff_model(br#"["model","x"]"#), None);
+        assert_eq!(sniff_model(br#"{"model":{"name":"x"}}"#), None);
+    }
+
+    #[tokio::test]
+    async fn read_request_body_streams_past_buffer_limit() {
+        let small = Body::from(vec![b'a'; 16]);
+        match read_request_body(small).await.expect("read") {
+            RequestBody::Buffered(bytes) => assert_eq!(bytes.len(), 16),
+            RequestBody::Streaming(_) => panic!("small body should be buffered"),
+        }
+
+        let chunks: Vec<Result<Bytes, std::io::Error>> = (0..3)
+            .map(|_| Ok(Bytes::from(vec![b'b'; MAX_REQUEST_BODY_BYTES / 2 + 1])))
+            .collect();
+        let large = Body::from_stream(Streamed(chunks.into_iter()));
+        let RequestBody::Streaming(body) = read_request_body(large).await.expect("read") else {
+            panic!("large body should stream");
+        };
+        assert_eq!(body.prefix().len(), 2 * (MAX_REQUEST_BODY_BYTES / 2 + 1));
+
+        let mut upstream = UpstreamBodyStream {
+            sent: 0,
+            prefix: Some(body.prefix.clone()),
+            rest: body.rest,
+        };
+        let mut total = 0;
+        while let Some(chunk) =
+            std::future::poll_fn(|cx| Pin::new(&mut upstream).poll_next(cx)).await
+        {
+            total += chunk.expect("chunk").len();
+        }
+        assert_eq!(total, 3 * (MAX_REQUEST_BODY_BYTES / 2 + 1));
+    }
+
+    struct Streamed(std::vec::IntoIter<Result<Bytes, std::io::Error>>);
+
+    impl Stream for Streamed {
+        type Item = Result<Bytes, std::io::Error>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.next())
+        }
+    }
+}
@@ -1,10 +1,14 @@
 //! Usage: Request context (SSOT) for gateway proxy forwarding.
 
 use super::abort_guard::RequestAbortGuard;
+use super::request_body::StreamingRequestBody;
+use super::RequestLogAttribution;
+use crate::gateway::active_requests::ActiveRequest;
 use crate::gateway::manager::GatewayAppState;
 use crate::gateway::response_fixer;
+use crate::gateway::streams::SseCaptureMode;
 use crate::gateway::util::{strip_hop_headers, RequestedModelLocation};
-use crate::providers;
+use crate::{providers, settings};
 use axum::body::Bytes;
 use axum::http::{header, HeaderMap, HeaderValue, Method};
 use std::sync::{Arc, Mutex};
@@ -24,15 +28,26 @@ pub(super) struct RequestContext {
     pub(super) session_id: Option<String>,
     pub(super) requested_model: Option<String>,
     pub(super) requested_model_location: Option<RequestedModelLocation>,
+    pub(super) attribution: RequestLogAttribution,
     pub(super) effective_sort_mode_id: Option<i64>,
     pub(super) providers: Vec<providers::ProviderForGateway>,
     pub(super) session_bound_provider_id: Option<i64>,
+    // Prompt-prefix key (`prefix_affinity::prefix_key`); recorded against the provider that succeeds.
+    pub(super) prefix_affinity_key: Option<u64>,
     pub(super) base_headers: HeaderMap,
     pub(super) body_bytes: Bytes,
+    // The client's headers / body before any request-level rewriting; sent to passthrough providers.
+    pub(super) client_base_headers: HeaderMap,
+    pub(super) client_body_bytes: Bytes,
+    // Set when the client body exceeded the buffer limit; `body_bytes` is empty and the body is
+    // forwarded from this stream on the (single) attempt.
+    pub(super) streaming_body: Option<StreamingRequestBody>,
     pub(super) introspection_json: Option<serde_json::Value>,
     pub(super) strip_request_content_encoding_seed: bool,
     pub(super) special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
     pub(super) provider_base_url_ping_cache_ttl_seconds: u32,
+    // Set only for cacheable requests (see `response_cache::cacheable_kind`) while caching is enabled.
+    pub(super) response_cache_ttl: Option<Duration>,
     pub(super) max_attempts_per_provider: u32,
     pub(super) max_providers_to_try: u32,
     pub(super) provider_cooldown_secs: i64,
@@ -40,6 +55,10 @@ pub(super) struct RequestContext {
     pub(super) upstream_first_byte_timeout: Option<Duration>,
     pub(super) upstream_stream_idle_timeout: Option<Duration>,
     pub(super) upstream_request_timeout_non_streaming: Option<Duration>,
+    // Set when hedged requests are enabled: start the next provider once the first is this slow.
+    pub(super) hedge_delay: Option<Duration>,
+    // Idle timeout while a Claude turn waits on server-side tool use (see `StreamIdleTimeouts`).
+    pub(super) stream_tool_pause_idle_timeout: Option<Duration>,
     pub(super) fingerprint_key: u64,
     pub(super) fingerprint_debug: String,
     pub(super) unavailable_fingerprint_key: u64,
@@ -49,6 +68,12 @@ pub(super) struct RequestContext {
     pub(super) enable_response_fixer: bool,
     pub(super) response_fixer_stream_config: response_fixer::ResponseFixerConfig,
     pub(super) response_fixer_non_stream_config: response_fixer::ResponseFixerConfig,
+    pub(super) retry_backoff: settings::RetryBackoffConfig,
+    pub(super) sse_capture: SseCaptureMode,
+    pub(super) header_sanitize_rules: Vec<settings::HeaderSanitizeRule>,
+    pub(super) failover_rules: Vec<settings::FailoverRule>,
+    pub(super) egress_network: settings::EgressNetworkConfig,
+    pub(super) active_request: Arc<ActiveRequest>,
 }
 
 impl RequestContext {
@@ -67,21 +92,29 @@ impl RequestContext {
             session_id,
             requested_model,
             requested_model_location,
+            attribution,
             effective_sort_mode_id,
             providers,
             session_bound_provider_id,
+            prefix_affinity_key,
             headers,
             body_bytes,
+            client_headers,
+            client_body_bytes,
+            streaming_body,
             introspection_json,
             strip_request_content_encoding_seed,
             special_settings,
             provider_base_url_ping_cache_ttl_seconds,
+            response_cache_ttl,
             max_attempts_per_provider,
             max_providers_to_try,
             provider_cooldown_secs,
             upstream_first_byte_timeout_secs,
             upstream_stream_idle_timeout_secs,
             upstream_request_timeout_non_streaming_secs,
+            hedge_delay,
+            stream_tool_pause_idle_timeout,
             fingerprint_key,
             fingerprint_debug,
             unavailable_fingerprint_key,
@@ -90,6 +123,12 @@ impl RequestContext {
             enable_response_fixer,
             response_fixer_stream_config,
             response_fixer_non_stream_config,
+            retry_backoff,
+            sse_capture,
+            header_sanitize_rules,
+            failover_rules,
+            egress_network,
+            active_request,
         } = parts;
 
         let max_attempts_per_provider = Self::normalize_max_attempts_per_provider(
@@ -119,9 +158,12 @@ impl RequestContext {
             created_at_ms,
             created_at,
             started,
+            special_settings.clone(),
+            attribution.clone(),
         );
 
         let base_headers = build_base_headers(headers);
+        let client_base_headers = build_base_headers(client_headers);
 
         Self {
             state,
@@ -137,15 +179,21 @@ impl RequestContext {
             session_id,
             requested_model,
             requested_model_location,
+            attribution,
             effective_sort_mode_id,
             providers,
             session_bound_provider_id,
+            prefix_affinity_key,
             base_headers,
             body_bytes,
+            client_base_headers,
+            client_body_bytes,
+            streaming_body,
             introspection_json,
             strip_request_content_encoding_seed,
             special_settings,
             provider_base_url_ping_cache_ttl_seconds,
+            response_cache_ttl,
             max_attempts_per_provider,
             max_providers_to_try,
             provider_cooldown_secs,
@@ -153,6 +201,8 @@ impl RequestContext {
             upstream_first_byte_timeout,
             upstream_stream_idle_timeout,
             upstream_request_timeout_non_streaming,
+            hedge_delay,
+            stream_tool_pause_idle_timeout,
             fingerprint_key,
             fingerprint_debug,
             unavailable_fingerprint_key,
@@ -162,6 +212,12 @@ impl RequestContext {
             enable_response_fixer,
             response_fixer_stream_config,
             response_fixer_non_stream_config,
+            retry_backoff,
+            sse_capture,
+            header_sanitize_rules,
+            failover_rules,
+            egress_network,
+            active_request,
         }
     }
 
@@ -223,21 +279,31 @@ pub(super) struct RequestContextParts {
     pub(super) session_id: Option<String>,
     pub(super) requested_model: Option<String>,
     pub(super) requested_model_location: Option<RequestedModelLocation>,
+    pub(super) attribution: RequestLogAttribution,
     pub(super) effective_sort_mode_id: Option<i64>,
     pub(super) providers: Vec<providers::ProviderForGateway>,
     pub(super) session_bound_provider_id: Option<i64>,
+    // Prompt-prefix key (`prefix_affinity::prefix_key`); recorded against the provider that succeeds.
+    pub(super) prefix_affinity_key: Option<u64>,
     pub(super) headers: HeaderMap,
     pub(super) body_bytes: Bytes,
+    pub(super) client_headers: HeaderMap,
+    pub(super) client_body_bytes: Bytes,
+    pub(super) streaming_body: Option<StreamingRequestBody>,
     pub(super) introspection_json: Option<serde_json::Value>,
     pub(super) strip_request_content_encoding_seed: bool,
     pub(super) special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
     pub(super) provider_base_url_ping_cache_ttl_seconds: u32,
+    pub(super) response_cache_ttl: Option<Duration>,
     pub(super) max_attempts_per_provider: u32,
     pub(super) max_providers_to_try: u32,
     pub(super) provider_cooldown_secs: i64,
     pub(super) upstream_first_byte_timeout_secs: u32,
     pub(super) upstream_stream_idle_timeout_secs: u32,
     pub(super) upstream_request_timeout_non_streaming_secs: u32,
+    pub(super) hedge_delay: Option<Duration>,
+    // Idle timeout while a Claude turn waits on server-side tool use (see `StreamIdleTimeouts`).
+    pub(super) stream_tool_pause_idle_timeout: Option<Duration>,
     pub(super) fingerprint_key: u64,
     pub(super) fingerprint_debug: String,
     pub(super) unavailable_fingerprint_key: u64,
@@ -246,4 +312,10 @@ pub(super) struct RequestContextParts {
     pub(super) enable_response_fixer: bool,
     pub(super) response_fixer_stream_config: response_fixer::ResponseFixerConfig,
     pub(super) response_fixer_non_stream_config: response_fixer::ResponseFixerConfig,
+    pub(super) retry_backoff: settings::RetryBackoffConfig,
+    pub(super) sse_capture: SseCaptureMode,
+    pub(super) header_sanitize_rules: Vec<settings::HeaderSanitizeRule>,
+    pub(super) failover_rules: Vec<settings::FailoverRule>,
+    pub(super) egress_network: settings::EgressNetworkConfig,
+    pub(super) active_request: Arc<ActiveRequest>,
 }
@@ -2,7 +2,9 @@
 
 use super::logging::enqueue_request_log_with_backpressure;
 use super::status_override;
-use super::{spawn_enqueue_request_log_with_backpressure, RequestLogEnqueueArgs};
+use super::{
+    spawn_enqueue_request_log_with_backpressure, RequestLogAttribution, RequestLogEnqueueArgs,
+};
 use crate::gateway::events::{emit_request_event, FailoverAttempt};
 use crate::{db, request_logs};
 
@@ -45,6 +47,7 @@ pub(super) struct RequestEndArgs<'a> {
     pub(super) usage_metrics: Option<crate::usage::UsageMetrics>,
     pub(super) log_usage_metrics: Option<crate::usage::UsageMetrics>,
     pub(super) usage: Option<crate::usage::UsageExtract>,
+    pub(super) attribution: RequestLogAttribution,
 }
 
 struct PreparedRequestEnd<'a> {
@@ -89,6 +92,7 @@ fn prepare_request_end(args: RequestEndArgs<'_>) -> PreparedRequestEnd<'_> {
         created_at: args.created_at,
         usage_metrics: args.log_usage_metrics,
         usage: args.usage,
+        attribution: args.attribution,
     };
 
     PreparedRequestEnd {
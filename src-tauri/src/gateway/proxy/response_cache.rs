@@ -0,0 +1,435 @@
+//! Usage: Per-provider response cache for idempotent non-stream requests (in-memory + SQLite).
+//!
+//! 用法：
+//! - 可缓存请求：`GET .../models` 与 `GET .../models/{id}`（模型列表 / 模型详情）；token 计数（Claude
+//!   `/v1/messages/count_tokens`、Codex `/v1/responses/input_tokens`、Gemini `:countTokens`）；开启
+//!   `settings.response_cache_completions` 时还包括相同的非流式补全（`/messages`、`/chat/completions`、`/responses`、`:generateContent`）
+//! - 以 (provider_id, 请求指纹) 为键（指纹见 `util::compute_request_fingerprint`），缓存上游 200 响应（已解压 / 转换后的最终响应体），
+//!   TTL 由 `settings.response_cache_ttl_seconds` 控制（0 = 关闭）
+//! - 先查内存，未命中再查 SQLite（`response_cache` 表，网关重启后仍可命中）；命中时不再请求上游，不消耗 RPM / 额度；
+//!   响应带 `x-aio-response-cache: hit`
+//! - 内存上限：最多 `MAX_ENTRIES` 条，单条响应体不超过 `MAX_ENTRY_BODY_BYTES`
+
+use super::stream_cap;
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::reasoning_redaction;
+use crate::shared::time::now_unix_seconds;
+use crate::{db, response_cache_store};
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub(super) const RESPONSE_CACHE_HEADER: &str = "x-aio-response-cache";
+const MAX_ENTRIES: usize = 256;
+const MAX_ENTRY_BODY_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CacheableKind {
+    Models,
+    CountTokens,
+    // Only cached when `response_cache_completions` is on.
+    Completion,
+}
+
+impl CacheableKind {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Models => "models",
+            Self::CountTokens => "count_tokens",
+            Self::Completion => "completion",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CacheKey {
+    provider_id: i64,
+    fingerprint_key: u64,
+}
+
+#[derive(Clone)]
+pub(super) struct CachedResponse {
+    pub(super) status: StatusCode,
+    pub(super) headers: HeaderMap,
+    pub(super) body: Bytes,
+    fingerprint_debug: String,
+    // Unix seconds.
+    stored_at: i64,
+}
+
+impl CachedResponse {
+    pub(super) fn age_secs(&self) -> u64 {
+        now_unix_seconds().saturating_sub(self.stored_at).max(0) as u64
+    }
+
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        self.age_secs() < ttl.as_secs()
+    }
+}
+
+static RESPONSE_CACHE: Mutex<Option<HashMap<CacheKey, CachedResponse>>> = Mutex::new(None);
+
+/// What kind of cacheable request this is, if any; streamed requests never are.
+pub(super) fn cacheable_kind(
+    method: &Method,
+    forwarded_path: &str,
+    body: Option<&Value>,
+) -> Option<CacheableKind> {
+    let segments: Vec<&str> = forwarded_path
+        .trim_end_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if method == Method::GET {
+        return match segments.as_slice() {
+            [.., "models"] => Some(CacheableKind::Models),
+            [.., "models", id] if !id.contains(':') => Some(CacheableKind::Models),
+            _ => None,
+        };
+    }
+    if method != Method::POST {
+        return None;
+    }
+
+    let action = segments
+        .last()
+        .and_then(|last| last.split_once(':'))
+        .map(|(_, action)| action);
+    match (segments.as_slice(), action) {
+        (_, Some("countTokens"))
+        | ([.., "messages", "count_tokens"], None)
+        | ([.., "responses", "input_tokens"], None) => Some(CacheableKind::CountTokens),
+        (_, Some("generateContent"))
+        | ([.., "messages"], None)
+        | ([.., "chat", "completions"], None)
+        | ([.., "responses"], None)
+            if !stream_cap::is_streaming_request(forwarded_path, body) =>
+        {
+            Some(CacheableKind::Completion)
+        }
+        _ => None,
+    }
+}
+
+fn lookup_memory(
+    provider_id: i64,
+    fingerprint_key: u64,
+    fingerprint_debug: &str,
+    ttl: Duration,
+) -> Option<CachedResponse> {
+    let key = CacheKey {
+        provider_id,
+        fingerprint_key,
+    };
+    let mut guard = RESPONSE_CACHE.lock_or_recover();
+    let cache = guard.as_mut()?;
+    match cache.get(&key) {
+        Some(entry) if entry.is_fresh(ttl) && entry.fingerprint_debug == fingerprint_debug => {
+            Some(entry.clone())
+        }
+        Some(_) => {
+            cache.remove(&key);
+            None
+        }
+        None => None,
+    }
+}
+
+fn store_memory(provider_id: i64, fingerprint_key: u64, ttl: Duration, entry: CachedResponse) {
+    let mut guard = RESPONSE_CACHE.lock_or_recover();
+    let cache = guard.get_or_insert_with(HashMap::new);
+    cache.retain(|_, entry| entry.is_fresh(ttl));
+    if cache.len() >= MAX_ENTRIES {
+        if let Some(oldest) = cache
+            .iter()
+            .min_by_key(|(_, entry)| entry.stored_at)
+            .map(|(key, _)| *key)
+        {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(
+        CacheKey {
+            provider_id,
+            fingerprint_key,
+        },
+        entry,
+    );
+}
+
+fn from_stored(
+    stored: response_cache_store::StoredResponse,
+    fingerprint_debug: &str,
+) -> CachedResponse {
+    let mut headers = HeaderMap::new();
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    CachedResponse {
+        status: StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK),
+        headers,
+        body: Bytes::from(stored.body),
+        fingerprint_debug: fingerprint_debug.to_string(),
+        stored_at: stored.stored_at,
+    }
+}
+
+/// Cached response and where it came from (`memory` / `sqlite`).
+pub(super) async fn lookup(
+    db: &db::Db,
+    provider_id: i64,
+    fingerprint_key: u64,
+    fingerprint_debug: &str,
+    ttl: Duration,
+) -> Option<(CachedResponse, &'static str)> {
+    if let Some(entry) = lookup_memory(provider_id, fingerprint_key, fingerprint_debug, ttl) {
+        return Some((entry, "memory"));
+    }
+
+    let db = db.clone();
+    let debug = fingerprint_debug.to_string();
+    let fresh_after = now_unix_seconds().saturating_sub(ttl.as_secs() as i64);
+    let stored = tauri::async_runtime::spawn_blocking(move || {
+        response_cache_store::get(&db, provider_id, fingerprint_key, &debug, fresh_after)
+    })
+    .await;
+    let stored = match stored {
+        Ok(Ok(stored)) => stored?,
+        Ok(Err(err)) => {
+            tracing::warn!("响应缓存读取失败: {}", err);
+            return None;
+        }
+        Err(_) => return None,
+    };
+
+    let entry = from_stored(stored, fingerprint_debug);
+    store_memory(provider_id, fingerprint_key, ttl, entry.clone());
+    Some((entry, "sqlite"))
+}
+
+/// Masks thinking / reasoning content in a JSON body (`exclude_reasoning_from_logs`); `None` when
+/// there is nothing to mask.
+fn strip_reasoning_body(body: &Bytes) -> Option<Bytes> {
+    let mut value = serde_json::from_slice::<Value>(body).ok()?;
+    if reasoning_redaction::strip_reasoning_json(&mut value) == 0 {
+        return None;
+    }
+    serde_json::to_vec(&value).ok().map(Bytes::from)
+}
+
+/// Stores a successful response in memory and (in the background) SQLite; returns `false` when it is not eligible.
+///
+/// With `strip_reasoning` the persisted copy (and later cache hits) carry masked reasoning, like
+/// every other stored payload under `exclude_reasoning_from_logs`.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn store(
+    db: &db::Db,
+    provider_id: i64,
+    fingerprint_key: u64,
+    fingerprint_debug: &str,
+    ttl: Duration,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &Bytes,
+    strip_reasoning: bool,
+) -> bool {
+    if status != StatusCode::OK || body.len() > MAX_ENTRY_BODY_BYTES {
+        return false;
+    }
+
+    let mut headers = headers.clone();
+    let stripped = if strip_reasoning {
+        strip_reasoning_body(body)
+    } else {
+        None
+    };
+    let body = match stripped {
+        Some(stripped) => {
+            if headers.contains_key(header::CONTENT_LENGTH) {
+                headers.insert(header::CONTENT_LENGTH, HeaderValue::from(stripped.len()));
+            }
+            stripped
+        }
+        None => body.clone(),
+    };
+
+    let stored_at = now_unix_seconds();
+    store_memory(
+        provider_id,
+        fingerprint_key,
+        ttl,
+        CachedResponse {
+            status,
+            headers: headers.clone(),
+            body: body.clone(),
+            fingerprint_debug: fingerprint_debug.to_string(),
+            stored_at,
+        },
+    );
+
+    let stored = response_cache_store::StoredResponse {
+        status: status.as_u16(),
+        headers: headers
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.to_str().ok()?;
+                Some((name.as_str().to_string(), value.to_string()))
+            })
+            .collect(),
+        body: body.to_vec(),
+        stored_at,
+    };
+    let db = db.clone();
+    let debug = fingerprint_debug.to_string();
+    let expired_before = stored_at.saturating_sub(ttl.as_secs() as i64);
+    std::mem::drop(tauri::async_runtime::spawn_blocking(move || {
+        if let Err(err) = response_cache_store::put(
+            &db,
+            provider_id,
+            fingerprint_key,
+            &debug,
+            &stored,
+            expired_before,
+        ) {
+            tracing::warn!("响应缓存写入失败: {}", err);
+        }
+    }));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn cacheable_kind_matches_idempotent_endpoints() {
+        let get = |path: &str| cacheable_kind(&Method::GET, path, None);
+        assert_eq!(get("/v1/models"), Some(CacheableKind::Models));
+        assert_eq!(get("/v1/models/"), Some(CacheableKind::Models));
+        assert_eq!(get("/models"), Some(CacheableKind::Models));
+        assert_eq!(
+            get("/v1/models/claude-sonnet-4"),
+            Some(CacheableKind::Models)
+        );
+        assert_eq!(
+            get("/v1beta/models/gemini-2.5-pro"),
+            Some(CacheableKind::Models)
+        );
+        assert_eq!(get("/v1beta/models/gemini-2.5-pro:generateContent"), None);
+        assert_eq!(get("/v1/messages"), None);
+        assert_eq!(get("/v1/models/x/versions"), None);
+        assert_eq!(get("/"), None);
+
+        let post = |path: &str, body: Value| cacheable_kind(&Method::POST, path, Some(&body));
+        assert_eq!(
+            post("/v1/messages/count_tokens", json!({})),
+            Some(CacheableKind::CountTokens)
+        );
+        assert_eq!(
+            post("/v1/responses/input_tokens", json!({})),
+            Some(CacheableKind::CountTokens)
+        );
+        assert_eq!(
+            post("/v1beta/models/gemini-2.5-pro:countTokens", json!({})),
+            Some(CacheableKind::CountTokens)
+        );
+        assert_eq!(
+            post("/v1/messages", json!({"stream": false})),
+            Some(CacheableKind::Completion)
+        );
+        assert_eq!(
+            post("/v1/chat/completions", json!({})),
+            Some(CacheableKind::Completion)
+        );
+        assert_eq!(
+            post("/v1beta/models/gemini-2.5-pro:generateContent", json!({})),
+            Some(CacheableKind::Completion)
+        );
+        assert_eq!(post("/v1/messages", json!({"stream": true})), None);
+        assert_eq!(post("/v1/responses", json!({"stream": true})), None);
+        assert_eq!(
+            post(
+                "/v1beta/models/gemini-2.5-pro:streamGenerateContent",
+                json!({})
+            ),
+            None
+        );
+        assert_eq!(post("/v1/models", json!({})), None);
+    }
+
+    #[test]
+    fn memory_layer_respects_provider_fingerprint_and_ttl() {
+        let ttl = Duration::from_secs(60);
+        let entry = CachedResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(br#"{"data":[]}"#),
+            fingerprint_debug: "v2|a".to_string(),
+            stored_at: now_unix_seconds(),
+        };
+        store_memory(-7, 42, ttl, entry);
+
+        let hit = lookup_memory(-7, 42, "v2|a", ttl).expect("cached");
+        assert_eq!(hit.body, Bytes::from_static(br#"{"data":[]}"#));
+        assert!(lookup_memory(-7, 43, "v2|a", ttl).is_none());
+        assert!(lookup_memory(-8, 42, "v2|a", ttl).is_none());
+
+        // a debug mismatch (hash collision) misses and evicts
+        assert!(lookup_memory(-7, 42, "v2|b", ttl).is_none());
+        assert!(lookup_memory(-7, 42, "v2|a", ttl).is_none());
+
+        // a shorter TTL at lookup time expires the entry
+        let entry = CachedResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+            fingerprint_debug: "v2|c".to_string(),
+            stored_at: now_unix_seconds(),
+        };
+        store_memory(-7, 44, ttl, entry);
+        assert!(lookup_memory(-7, 44, "v2|c", Duration::ZERO).is_none());
+        assert!(lookup_memory(-7, 44, "v2|c", ttl).is_none());
+    }
+
+    #[test]
+    fn stored_rows_convert_back_to_responses() {
+        let stored = response_cache_store::StoredResponse {
+            status: 200,
+            headers: vec![
+                ("content-type".to_string(), "application/json".to_string()),
+                ("bad header".to_string(), "x".to_string()),
+            ],
+            body: b"{}".to_vec(),
+            stored_at: 1,
+        };
+        let entry = from_stored(stored, "v2|d");
+        assert_eq!(entry.status, StatusCode::OK);
+        assert_eq!(entry.headers.len(), 1);
+        assert_eq!(entry.headers["content-type"], "application/json");
+        assert_eq!(entry.body, Bytes::from_static(b"{}"));
+    }
+
+    #[test]
+    fn strip_reasoning_body_masks_thinking_blocks_only_when_present() {
+        let body = Bytes::from_static(
+            br#"{"content":[{"type":"thinking","thinking":"secret","signature":"s"},{"type":"text","text":"hi"}]}"#,
+        );
+        let stripped = strip_reasoning_body(&body).expect("stripped");
+        let value: Value = serde_json::from_slice(&stripped).expect("json");
+        assert_eq!(value["content"].as_array().map(Vec::len), Some(1));
+        assert_eq!(value["content"][0]["text"], "hi");
+
+        assert!(strip_reasoning_body(&Bytes::from_static(br#"{"content":[]}"#)).is_none());
+        assert!(strip_reasoning_body(&Bytes::from_static(b"not json")).is_none());
+    }
+}
@@ -0,0 +1,277 @@
+//! Usage: Dry-run routing explainer (`gateway_route_explain`).
+//!
+//! 按与 handler 相同的顺序计算候选 Provider（排序模式 / 延迟路由 / 会话绑定 / 灰度 / A/B / 预算 / 熔断 / 最大尝试数），
+//! 但不发请求、不写会话绑定、不分配 A/B 会话。
+//! - 未给 session_id 时，灰度分桶按每个请求随机，结果只标注灰度 Provider 的兜底位置
+//! - 后台请求分流依赖请求体，不在此处体现
+
+use super::failover::select_next_provider_id_from_order;
+use super::latency_routing;
+use super::provider_quota;
+use crate::circuit_breaker::CircuitState;
+use crate::gateway::session_manager::SessionManager;
+use crate::gateway::util::now_unix_seconds;
+use crate::gateway::{GatewayProviderCircuitStatus, GatewayRouteCandidate, GatewayRouteExplain};
+use crate::{
+    provider_ab_test, provider_canary, provider_snapshot, providers, settings, sort_mode_schedules,
+    sort_modes,
+};
+use std::collections::{HashMap, HashSet};
+
+pub(in crate::gateway) struct RouteExplainInput<'a> {
+    pub(in crate::gateway) app: &'a tauri::AppHandle,
+    pub(in crate::gateway) db: &'a crate::db::Db,
+    pub(in crate::gateway) cli_key: &'a str,
+    pub(in crate::gateway) model: Option<&'a str>,
+    pub(in crate::gateway) session_id: Option<&'a str>,
+    pub(in crate::gateway) session: Option<&'a SessionManager>,
+    pub(in crate::gateway) circuits: Vec<GatewayProviderCircuitStatus>,
+}
+
+fn move_to_front(
+    order: &mut Vec<providers::ProviderForGateway>,
+    notes: &mut HashMap<i64, Vec<&'static str>>,
+    provider_id: i64,
+    note: &'static str,
+) {
+    if let Some(idx) = order.iter().position(|p| p.id == provider_id) {
+        let chosen = order.remove(idx);
+        order.insert(0, chosen);
+        notes.entry(provider_id).or_default().push(note);
+    }
+}
+
+fn move_to_back(
+    order: &mut Vec<providers::ProviderForGateway>,
+    notes: &mut HashMap<i64, Vec<&'static str>>,
+    provider_id: i64,
+    note: &'static str,
+) {
+    if let Some(idx) = order.iter().position(|p| p.id == provider_id) {
+        let chosen = order.remove(idx);
+        order.push(chosen);
+        notes.entry(provider_id).or_default().push(note);
+    }
+}
+
+pub(in crate::gateway) fn explain_route(
+    input: RouteExplainInput<'_>,
+) -> Result<GatewayRouteExplain, String> {
+    let RouteExplainInput {
+        app,
+        db,
+        cli_key,
+        model,
+        session_id,
+        session,
+        circuits,
+    } = input;
+    let now_unix = now_unix_seconds() as i64;
+    let session_id = session_id.map(str::trim).filter(|v| !v.is_empty());
+    let model = model.map(str::trim).filter(|v| !v.is_empty());
+
+    let bound_sort_mode_id = match (session, session_id) {
+        (Some(session), Some(sid)) => session.get_bound_sort_mode_id(cli_key, sid, now_unix),
+        _ => None,
+    };
+    let (snapshot, sort_mode_source) = match bound_sort_mode_id {
+        Some(mode_id) => (
+            provider_snapshot::in_mode(db, cli_key, mode_id)?,
+            "session_binding",
+        ),
+        None => (provider_snapshot::active(db, cli_key)?, "active"),
+    };
+    let sort_mode_id = snapshot.sort_mode_id;
+    let mut order = snapshot.providers.clone();
+    let schedule_rule = sort_mode_schedules::current_rule(db, cli_key)?;
+
+    let cfg = settings::read(app).unwrap_or_default();
+    let mut notes: HashMap<i64, Vec<&'static str>> = HashMap::new();
+
+    // Weighted / round-robin modes reshuffle the order per request; the mode order is shown as-is.
+    let mut load_balanced = false;
+    if sort_mode_id.is_some() && snapshot.routing.strategy != sort_modes::SortModeStrategy::Ordered
+    {
+        load_balanced = true;
+        for provider in order.iter() {
+            notes.entry(provider.id).or_default().push("load_balanced");
+        }
+    }
+
+    if !load_balanced {
+        if let Some(scores) = latency_routing::apply(db, cli_key, cfg.latency_routing, &mut order) {
+            for provider_id in scores.keys() {
+                notes
+                    .entry(*provider_id)
+                    .or_default()
+                    .push("latency_ranked");
+            }
+        }
+    }
+
+    let canary = match snapshot.canary.as_ref() {
+        Some(canary) if provider_canary::still_running(db, canary)? => Some(canary),
+        _ => None,
+    };
+    if let Some(canary) = canary {
+        match session_id {
+            Some(sid) if provider_canary::in_canary_bucket(canary.traffic_percent, sid) => {
+                move_to_front(
+                    &mut order,
+                    &mut notes,
+                    canary.provider_id,
+                    "canary_selected",
+                )
+            }
+            _ => move_to_back(
+                &mut order,
+                &mut notes,
+                canary.provider_id,
+                "canary_fallback",
+            ),
+        }
+    }
+
+    if let (Some(sid), Some(test)) = (session_id, snapshot.ab_test.as_ref()) {
+        if let Some(arm) = provider_ab_test::peek_session_arm(db, test, sid)? {
+            let (preferred_id, other_id) = test.arm_provider_ids(arm);
+            let has_both = order.iter().any(|p| p.id == preferred_id)
+                && order.iter().any(|p| p.id == other_id);
+            if has_both {
+                move_to_back(&mut order, &mut notes, other_id, "ab_test_arm");
+                move_to_front(&mut order, &mut notes, preferred_id, "ab_test_arm");
+            }
+        }
+    }
+
+    let mut session_bound_provider_id: Option<i64> = None;
+    if let (Some(session), Some(sid)) = (session, session_id) {
+        let bound_order = session.get_bound_provider_order(cli_key, sid, now_unix);
+        if let Some(bound) = bound_order.as_deref().filter(|o| !o.is_empty()) {
+            let rank: HashMap<i64, usize> = bound
+                .iter()
+                .enumerate()
+                .map(|(idx, id)| (*id, idx))
+                .collect();
+            order.sort_by_key(|p| rank.get(&p.id).copied().unwrap_or(usize::MAX));
+        }
+
+        if let Some(bound_provider_id) = session.get_bound_provider(cli_key, sid, now_unix) {
+            if order.iter().any(|p| p.id == bound_provider_id) {
+                session_bound_provider_id = Some(bound_provider_id);
+                move_to_front(
+                    &mut order,
+                    &mut notes,
+                    bound_provider_id,
+                    "session_bound_provider",
+                );
+            } else if let Some(bound) = bound_order.as_deref() {
+                let current: HashSet<i64> = order.iter().map(|p| p.id).collect();
+                if let Some(next_id) =
+                    select_next_provider_id_from_order(bound_provider_id, bound, &current)
+                {
+                    if let Some(idx) = order.iter().position(|p| p.id == next_id) {
+                        order.rotate_left(idx);
+                    }
+                }
+            }
+        }
+    }
+
+    let mode_settings = snapshot.mode_settings;
+    let max_providers_to_try = mode_settings
+        .failover_max_providers_to_try
+        .unwrap_or(cfg.failover_max_providers_to_try)
+        .max(1);
+
+    let circuits: HashMap<i64, GatewayProviderCircuitStatus> =
+        circuits.into_iter().map(|c| (c.provider_id, c)).collect();
+    let circuit_state = |provider_id: i64| -> (String, Option<&'static str>, Option<i64>) {
+        let Some(c) = circuits.get(&provider_id) else {
+            return (CircuitState::Closed.as_str().to_string(), None, None);
+        };
+        if c.state == CircuitState::Open.as_str() {
+            return (c.state.clone(), Some("circuit_open"), c.open_until);
+        }
+        match c.cooldown_until.filter(|until| *until > now_unix) {
+            Some(until) => (c.state.clone(), Some("circuit_cooldown"), Some(until)),
+            None => (c.state.clone(), None, None),
+        }
+    };
+
+    let quota_exceeded = provider_quota::exceeded_providers(db, cli_key, &order);
+
+    let mut candidates: Vec<GatewayRouteCandidate> = Vec::new();
+    let mut tried: u32 = 0;
+    for provider in order.iter() {
+        let (state, mut circuit_reason, mut available_at) = circuit_state(provider.id);
+        if let Some(until) = quota_exceeded.get(&provider.id) {
+            circuit_reason = Some("quota_exceeded");
+            available_at = Some(*until);
+        }
+        let excluded_reason = match circuit_reason {
+            Some(reason) => Some(reason),
+            None if tried >= max_providers_to_try => Some("beyond_max_providers_to_try"),
+            None => None,
+        };
+        let position = if excluded_reason.is_none() {
+            tried += 1;
+            Some(tried)
+        } else {
+            None
+        };
+        let upstream_model = model.map(|m| {
+            if cli_key == "claude" && provider.claude_models.has_any() {
+                provider.get_effective_claude_model(m, false)
+            } else {
+                m.to_string()
+            }
+        });
+        candidates.push(GatewayRouteCandidate {
+            provider_id: provider.id,
+            provider_name: provider.name.clone(),
+            position,
+            excluded_reason,
+            notes: notes.remove(&provider.id).unwrap_or_default(),
+            circuit_state: state,
+            available_at,
+            upstream_model,
+        });
+    }
+
+    let routed: HashSet<i64> = order.iter().map(|p| p.id).collect();
+    for provider in providers::list_by_cli(db, cli_key)? {
+        if routed.contains(&provider.id) {
+            continue;
+        }
+        let (state, _, available_at) = circuit_state(provider.id);
+        candidates.push(GatewayRouteCandidate {
+            provider_id: provider.id,
+            provider_name: provider.name,
+            position: None,
+            excluded_reason: Some(if provider.enabled {
+                "not_in_sort_mode"
+            } else {
+                "disabled"
+            }),
+            notes: Vec::new(),
+            circuit_state: state,
+            available_at,
+            upstream_model: None,
+        });
+    }
+
+    Ok(GatewayRouteExplain {
+        cli_key: cli_key.to_string(),
+        model: model.map(str::to_string),
+        session_id: session_id.map(str::to_string),
+        gateway_running: session.is_some(),
+        sort_mode_id,
+        sort_mode_source,
+        schedule_rule_id: schedule_rule.as_ref().map(|r| r.id),
+        schedule_mode_id: schedule_rule.and_then(|r| r.mode_id),
+        session_bound_provider_id,
+        max_providers_to_try,
+        candidates,
+    })
+}
@@ -2,14 +2,18 @@
 
 pub(in crate::gateway) fn status_override_for_error_code(error_code: Option<&str>) -> Option<u16> {
     match error_code {
-        Some("GW_REQUEST_ABORTED") | Some("GW_STREAM_ABORTED") => Some(499),
+        Some("GW_REQUEST_ABORTED") | Some("GW_STREAM_ABORTED") | Some("GW_REQUEST_CANCELLED") => {
+            Some(499)
+        }
         Some("GW_UPSTREAM_TIMEOUT") | Some("GW_STREAM_IDLE_TIMEOUT") => Some(524),
         Some("GW_STREAM_ERROR")
         | Some("GW_UPSTREAM_READ_ERROR")
         | Some("GW_UPSTREAM_CONNECT_FAILED")
         | Some("GW_UPSTREAM_BODY_READ_ERROR")
         | Some("GW_UPSTREAM_ALL_FAILED") => Some(502),
-        Some("GW_ALL_PROVIDERS_UNAVAILABLE") | Some("GW_NO_ENABLED_PROVIDER") => Some(503),
+        Some("GW_ALL_PROVIDERS_UNAVAILABLE")
+        | Some("GW_NO_ENABLED_PROVIDER")
+        | Some("GW_PAUSED") => Some(503),
         Some("GW_CLI_PROXY_DISABLED") => Some(403),
         Some("GW_INVALID_CLI_KEY") => Some(400),
         Some("GW_BODY_TOO_LARGE") => Some(413),
@@ -28,7 +32,10 @@ pub(in crate::gateway) fn effective_status(
 }
 
 pub(in crate::gateway) fn is_client_abort(error_code: Option<&str>) -> bool {
-    matches!(error_code, Some("GW_REQUEST_ABORTED" | "GW_STREAM_ABORTED"))
+    matches!(
+        error_code,
+        Some("GW_REQUEST_ABORTED" | "GW_STREAM_ABORTED" | "GW_REQUEST_CANCELLED")
+    )
 }
 
 #[cfg(test)]
@@ -45,6 +52,10 @@ mod tests {
             status_override_for_error_code(Some("GW_STREAM_ABORTED")),
             Some(499)
         );
+        assert_eq!(
+            status_override_for_error_code(Some("GW_REQUEST_CANCELLED")),
+            Some(499)
+        );
         assert_eq!(
             status_override_for_error_code(Some("GW_UPSTREAM_TIMEOUT")),
             Some(524)
@@ -87,6 +98,7 @@ mod tests {
     fn client_abort_detection() {
         assert!(is_client_abort(Some("GW_REQUEST_ABORTED")));
         assert!(is_client_abort(Some("GW_STREAM_ABORTED")));
+        assert!(is_client_abort(Some("GW_REQUEST_CANCELLED")));
         assert!(!is_client_abort(Some("GW_UPSTREAM_TIMEOUT")));
         assert!(!is_client_abort(None));
     }
@@ -0,0 +1,102 @@
+//! Usage: Per-CLI concurrent stream caps.
+//!
+//! Limits how many streaming requests of one CLI may be in flight at the same time; excess requests
+//! are rejected locally (429 + Retry-After) instead of piling onto the relay, so that subagent storms
+//! don't exhaust upstream concurrency limits.
+
+use crate::gateway::streams;
+use axum::response::Response;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// 被拒绝的请求建议客户端等待的秒数
+pub(super) const STREAM_CAP_RETRY_AFTER_SECONDS: u64 = 5;
+
+/// Whether the request asks for a streamed response (`"stream": true` or Gemini `streamGenerateContent`).
+pub(super) fn is_streaming_request(forwarded_path: &str, body: Option<&Value>) -> bool {
+    if forwarded_path.contains(":streamGenerateContent") {
+        return true;
+    }
+    body.and_then(|root| root.get("stream"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Occupied stream slot; released on drop.
+pub(super) struct StreamSlot {
+    caps: Arc<StreamCaps>,
+    cli_key: String,
+}
+
+impl Drop for StreamSlot {
+    fn drop(&mut self) {
+        let mut active = self.caps.active.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = active.get_mut(&self.cli_key) {
+            *count = count.saturating_sub(1);
+        }
+    }
+}
+
+impl StreamSlot {
+    /// Keeps the slot occupied until the response body finishes (streams included).
+    pub(super) fn hold_until_body_end(self, resp: Response) -> Response {
+        streams::hold_until_body_end(resp, self)
+    }
+}
+
+#[derive(Default)]
+pub(in crate::gateway) struct StreamCaps {
+    active: Mutex<HashMap<String, usize>>,
+}
+
+impl StreamCaps {
+    /// Takes a slot when fewer than `max_streams` streams of `cli_key` are active; `max_streams == 0`
+    /// means unlimited. Returns the number of active streams on rejection.
+    pub(super) fn try_acquire(
+        self: &Arc<Self>,
+        cli_key: &str,
+        max_streams: u32,
+    ) -> Result<StreamSlot, usize> {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        let count = active.entry(cli_key.to_string()).or_insert(0);
+        if max_streams > 0 && *count >= max_streams as usize {
+            return Err(*count);
+        }
+        *count += 1;
+        Ok(StreamSlot {
+            caps: self.clone(),
+            cli_key: cli_key.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_are_per_cli_and_released_on_drop() {
+        let caps = Arc::new(StreamCaps::default());
+        let first = caps.try_acquire("codex", 2).unwrap();
+        let _second = caps.try_acquire("codex", 2).unwrap();
+        assert_eq!(caps.try_acquire("codex", 2).err(), Some(2));
+        assert!(caps.try_acquire("claude", 2).is_ok());
+
+        drop(first);
+        assert!(caps.try_acquire("codex", 2).is_ok());
+        assert!(caps.try_acquire("codex", 0).is_ok());
+    }
+
+    #[test]
+    fn detects_streaming_requests() {
+        let stream = serde_json::json!({"model": "gpt-5", "stream": true});
+        let plain = serde_json::json!({"model": "gpt-5"});
+        assert!(is_streaming_request("/v1/responses", Some(&stream)));
+        assert!(!is_streaming_request("/v1/responses", Some(&plain)));
+        assert!(is_streaming_request(
+            "/v1beta/models/gemini-2.5-pro:streamGenerateContent",
+            None
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! Usage: HMAC request signing for self-hosted relays (provider `request_signing`).
+//!
+//! 用法：
+//! - 签名串为 `"{timestamp}.{body}"`：timestamp 为 Unix 秒，body 为最终发往上游的请求体字节（所有改写之后）
+//! - 按 `header_template` 渲染签名头（占位符 `{signature}` / `{timestamp}`），可选额外发送独立的时间戳头
+//! - 每次尝试（含重试）重新签名，时间戳随之刷新
+
+use crate::providers::{RequestSigning, RequestSigningAlgorithm, RequestSigningEncoding};
+use axum::http::{HeaderName, HeaderValue};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+
+fn mac_bytes(
+    algorithm: RequestSigningAlgorithm,
+    secret: &[u8],
+    timestamp: &str,
+    body: &[u8],
+) -> Result<Option<Vec<u8>>, String> {
+    fn compute<M: Mac + hmac::digest::KeyInit>(
+        secret: &[u8],
+        timestamp: &str,
+        body: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let mut mac =
+            <M as Mac>::new_from_slice(secret).map_err(|e| format!("GW_REQUEST_SIGNING: {e}"))?;
+        mac.update(timestamp.as_bytes());
+        mac.update(b".");
+        mac.update(body);
+        Ok(mac.finalize().into_bytes().to_vec())
+    }
+
+    match algorithm {
+        RequestSigningAlgorithm::None => Ok(None),
+        RequestSigningAlgorithm::HmacSha256 => {
+            compute::<Hmac<Sha256>>(secret, timestamp, body).map(Some)
+        }
+        RequestSigningAlgorithm::HmacSha512 => {
+            compute::<Hmac<Sha512>>(secret, timestamp, body).map(Some)
+        }
+    }
+}
+
+fn encode(encoding: RequestSigningEncoding, bytes: &[u8]) -> String {
+    match encoding {
+        RequestSigningEncoding::Hex => bytes.iter().map(|b| format!("{b:02x}")).collect(),
+        RequestSigningEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(bytes),
+    }
+}
+
+/// Headers to add for `body` signed at `timestamp`; empty when signing is off.
+pub(super) fn signature_headers(
+    cfg: &RequestSigning,
+    timestamp: u64,
+    body: &[u8],
+) -> Result<Vec<(HeaderName, HeaderValue)>, String> {
+    let timestamp = timestamp.to_string();
+    let Some(mac) = mac_bytes(cfg.algorithm, cfg.secret.as_bytes(), &timestamp, body)? else {
+        return Ok(Vec::new());
+    };
+    let signature = encode(cfg.encoding, &mac);
+
+    let header = |name: &str, value: &str| -> Result<(HeaderName, HeaderValue), String> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| format!("GW_REQUEST_SIGNING: invalid header name {name}: {e}"))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| format!("GW_REQUEST_SIGNING: invalid header value for {name}: {e}"))?;
+        Ok((name, value))
+    };
+
+    let value = cfg
+        .header_template
+        .replace("{timestamp}", &timestamp)
+        .replace("{signature}", &signature);
+    let mut headers = vec![header(&cfg.header_name, &value)?];
+    if !cfg.timestamp_header.is_empty() {
+        headers.push(header(&cfg.timestamp_header, &timestamp)?);
+    }
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signing(algorithm: RequestSigningAlgorithm) -> RequestSigning {
+        RequestSigning {
+            algorithm,
+            secret: "key".to_string(),
+            encoding: RequestSigningEncoding::Hex,
+            header_name: "x-relay-signature".to_string(),
+            header_template: "t={timestamp},v1={signature}".to_string(),
+            timestamp_header: "x-relay-timestamp".to_string(),
+        }
+    }
+
+    #[test]
+    fn signs_timestamp_and_body_with_template() {
+        // HMAC-SHA256(key, "1700000000.{}")
+        let headers = signature_headers(
+            &signing(RequestSigningAlgorithm::HmacSha256),
+            1_700_000_000,
+            b"{}",
+        )
+        .unwrap();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers[0].0, "x-relay-signature");
+        let value = headers[0].1.to_str().unwrap();
+        let signature = value.strip_prefix("t=1700000000,v1=").unwrap();
+        assert_eq!(signature.len(), 64);
+        assert!(signature.bytes().all(|b| b.is_ascii_hexdigit()));
+        assert_eq!(headers[1].0, "x-relay-timestamp");
+        assert_eq!(headers[1].1, "1700000000");
+
+        // any body change changes the signature
+        let other = signature_headers(
+            &signing(RequestSigningAlgorithm::HmacSha256),
+            1_700_000_000,
+            b"{ }",
+        )
+        .unwrap();
+        assert_ne!(headers[0].1, other[0].1);
+    }
+
+    #[test]
+    fn signature_covers_timestamp_dot_body() {
+        let cfg = RequestSigning {
+            algorithm: RequestSigningAlgorithm::HmacSha256,
+            secret: "Jefe".to_string(),
+            encoding: RequestSigningEncoding::Hex,
+            header_name: "x-signature".to_string(),
+            header_template: "{signature}".to_string(),
+            timestamp_header: String::new(),
+        };
+        let mut expected = Hmac::<Sha256>::new_from_slice(b"Jefe").unwrap();
+        expected.update(b"42.hello");
+        let expected = encode(
+            RequestSigningEncoding::Hex,
+            &expected.finalize().into_bytes(),
+        );
+
+        let headers = signature_headers(&cfg, 42, b"hello").unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0].1.to_str().unwrap(), expected);
+    }
+
+    #[test]
+    fn base64_sha512_and_disabled() {
+        let mut cfg = signing(RequestSigningAlgorithm::HmacSha512);
+        cfg.encoding = RequestSigningEncoding::Base64;
+        cfg.header_template = "{signature}".to_string();
+        let headers = signature_headers(&cfg, 1, b"body").unwrap();
+        // 64-byte MAC → 88 base64 chars
+        assert_eq!(headers[0].1.len(), 88);
+
+        let off = signature_headers(&RequestSigning::default(), 1, b"body").unwrap();
+        assert!(off.is_empty());
+    }
+}
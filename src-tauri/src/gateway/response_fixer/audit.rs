@@ -4,6 +4,7 @@ use std::time::Instant;
 
 use super::encoding::EncodingFixer;
 use super::json::JsonFixer;
+use super::tool_args;
 use super::ResponseFixerConfig;
 
 #[derive(Debug, Default, Clone)]
@@ -14,6 +15,16 @@ pub(super) struct ResponseFixerApplied {
     pub(super) sse_details: Option<&'static str>,
     pub(super) json_applied: bool,
     pub(super) json_details: Option<&'static str>,
+    pub(super) tool_args_repairs: Vec<Value>,
+}
+
+impl ResponseFixerApplied {
+    pub(super) fn hit(&self) -> bool {
+        self.encoding_applied
+            || self.sse_applied
+            || self.json_applied
+            || !self.tool_args_repairs.is_empty()
+    }
 }
 
 fn build_fixers_applied(
@@ -38,11 +49,20 @@ fn build_fixers_applied(
         "applied": applied.json_applied,
         "details": applied.json_details,
     }));
+    if !applied.tool_args_repairs.is_empty() {
+        out.push(serde_json::json!({
+            "fixer": "tool_args",
+            "applied": true,
+            "details": null,
+            "repairs": applied.tool_args_repairs,
+        }));
+    }
     out
 }
 
 pub(super) fn build_special_setting(
     hit: bool,
+    dry_run: bool,
     applied: &ResponseFixerApplied,
     include_sse: bool,
     total_bytes_processed: usize,
@@ -52,6 +72,7 @@ pub(super) fn build_special_setting(
         "type": "response_fixer",
         "scope": "response",
         "hit": hit,
+        "dryRun": dry_run,
         "fixersApplied": build_fixers_applied(applied, include_sse),
         "totalBytesProcessed": total_bytes_processed as u64,
         "processingTimeMs": processing_time_ms,
@@ -65,6 +86,8 @@ pub(super) fn process_non_stream(
     let started = Instant::now();
     let mut applied = ResponseFixerApplied::default();
 
+    // dry-run：照常分析，但最终返回原始 body
+    let original = config.dry_run.then(|| body.clone());
     let mut data = body;
     let total_bytes_processed = data.len();
 
@@ -87,12 +110,21 @@ pub(super) fn process_non_stream(
         data = res.data;
     }
 
-    let audit_hit = applied.encoding_applied || applied.json_applied;
+    if config.fix_tool_args {
+        let fixer = JsonFixer::new(config.max_json_depth, config.max_fix_size);
+        if let Some((fixed, repairs)) = tool_args::repair_non_stream_body(&data, &fixer) {
+            applied.tool_args_repairs = repairs;
+            data = fixed;
+        }
+    }
+
+    let audit_hit = applied.hit();
     let processing_time_ms = started.elapsed().as_millis().min(u64::MAX as u128) as u64;
 
-    let special_setting = if audit_hit {
+    let special_setting = if audit_hit || config.dry_run {
         Some(build_special_setting(
-            true,
+            audit_hit,
+            config.dry_run,
             &applied,
             false,
             total_bytes_processed,
@@ -102,9 +134,16 @@ pub(super) fn process_non_stream(
         None
     };
 
+    let header_value = match (config.dry_run, audit_hit) {
+        (true, true) => "dry-run-would-apply",
+        (true, false) => "dry-run",
+        (false, true) => "applied",
+        (false, false) => "not-applied",
+    };
+
     super::NonStreamFixOutcome {
-        body: data,
-        header_value: if audit_hit { "applied" } else { "not-applied" },
+        body: original.unwrap_or(data),
+        header_value,
         special_setting,
     }
 }
@@ -97,3 +97,209 @@ impl EncodingFixer {
         }
     }
 }
+
+fn utf8_sequence_len(lead: u8) -> Option<usize> {
+    match lead {
+        0xc2..=0xdf => Some(2),
+        0xe0..=0xef => Some(3),
+        0xf0..=0xf4 => Some(4),
+        _ => None,
+    }
+}
+
+fn is_continuation(byte: u8) -> bool {
+    (0x80..=0xbf).contains(&byte)
+}
+
+/// `\uXXXX` escape at `pos` whose code unit lies in `range` (e.g. high / low surrogates).
+fn surrogate_escape_at(line: &[u8], pos: usize, range: std::ops::RangeInclusive<u16>) -> bool {
+    let Some(escape) = line.get(pos..pos + 6) else {
+        return false;
+    };
+    if escape[0] != b'\\' || escape[1] != b'u' {
+        return false;
+    }
+    let Ok(hex) = std::str::from_utf8(&escape[2..]) else {
+        return false;
+    };
+    let Ok(unit) = u16::from_str_radix(hex, 16) else {
+        return false;
+    };
+    if !range.contains(&unit) {
+        return false;
+    }
+    // 前面的反斜杠数量必须为偶数，否则这里的 `\` 本身是被转义的字符。
+    let backslashes = line[..pos]
+        .iter()
+        .rev()
+        .take_while(|b| **b == b'\\')
+        .count();
+    backslashes % 2 == 0
+}
+
+const HIGH_SURROGATES: std::ops::RangeInclusive<u16> = 0xd800..=0xdbff;
+const LOW_SURROGATES: std::ops::RangeInclusive<u16> = 0xdc00..=0xdfff;
+
+#[derive(Debug)]
+enum Utf8Carry {
+    Bytes { bytes: Vec<u8>, missing: usize },
+    HighSurrogate(Vec<u8>),
+}
+
+/// 流式编码修复：上游（中转）把一个多字节 UTF-8 字符或 UTF-16 代理对拆到相邻两个 SSE 事件的 JSON 字符串里时，
+/// 把前一个字符串末尾残缺的字节 / 高位代理暂存，并拼接到下一个 data 行中以对应续字节 / 低位代理开头的字符串前，
+/// 避免逐段有损解码产生替换字符（U+FFFD）。
+///
+/// 输入需按行完整（`\n` 结尾）；找不到可拼接的后续时，残片直接丢弃（与有损解码相比只少一个替换字符）。
+#[derive(Debug, Default)]
+pub(super) struct StreamingUtf8Repair {
+    carry: Option<Utf8Carry>,
+}
+
+impl StreamingUtf8Repair {
+    fn may_need_repair(&self, data: &[u8]) -> bool {
+        if self.carry.is_some() {
+            return true;
+        }
+        if std::str::from_utf8(data).is_err() {
+            return true;
+        }
+        data.windows(3)
+            .any(|w| w[0] == b'\\' && w[1] == b'u' && (w[2] == b'd' || w[2] == b'D'))
+    }
+
+    /// Finds an incomplete trailing sequence right before the last closing quote of a data line.
+    fn split_tail(line: &[u8]) -> Option<(usize, usize, Utf8Carry)> {
+        let quote = line.iter().rposition(|b| *b == b'"')?;
+        if quote >= 6 && surrogate_escape_at(line, quote - 6, HIGH_SURROGATES) {
+            let start = quote - 6;
+            return Some((
+                start,
+                quote,
+                Utf8Carry::HighSurrogate(line[start..quote].to_vec()),
+            ));
+        }
+
+        let trailing = line[..quote]
+            .iter()
+            .rev()
+            .take(3)
+            .take_while(|b| is_continuation(**b))
+            .count();
+        let start = quote.checked_sub(trailing + 1)?;
+        let expected = utf8_sequence_len(line[start])?;
+        let have = trailing + 1;
+        if have >= expected {
+            return None;
+        }
+        Some((
+            start,
+            quote,
+            Utf8Carry::Bytes {
+                bytes: line[start..quote].to_vec(),
+                missing: expected - have,
+            },
+        ))
+    }
+
+    /// Position right after the opening quote of the first string that continues `carry`.
+    fn find_continuation(line: &[u8], carry: &Utf8Carry) -> Option<usize> {
+        line.iter().enumerate().find_map(|(i, b)| {
+            if *b != b'"' {
+                return None;
+            }
+            let pos = i + 1;
+            let matches = match carry {
+                Utf8Carry::Bytes { missing, .. } => {
+                    let rest = line.get(pos..pos + missing)?;
+                    rest.iter().all(|b| is_continuation(*b))
+                        && !line.get(pos + missing).is_some_and(|b| is_continuation(*b))
+                }
+                Utf8Carry::HighSurrogate(_) => surrogate_escape_at(line, pos, LOW_SURROGATES),
+            };
+            matches.then_some(pos)
+        })
+    }
+
+    fn repair_line(&mut self, line: &[u8], out: &mut Vec<u8>) -> bool {
+        if !line.starts_with(b"data:") {
+            out.extend_from_slice(line);
+            return false;
+        }
+
+        let mut joined: Option<Vec<u8>> = None;
+        let mut changed = false;
+        if let Some(carry) = self.carry.take() {
+            changed = true;
+            if let Some(pos) = Self::find_continuation(line, &carry) {
+                let prefix = match carry {
+                    Utf8Carry::Bytes { bytes, .. } => bytes,
+                    Utf8Carry::HighSurrogate(escape) => escape,
+                };
+                let mut v = Vec::with_capacity(line.len() + prefix.len());
+                v.extend_from_slice(&line[..pos]);
+                v.extend_from_slice(&prefix);
+                v.extend_from_slice(&line[pos..]);
+                joined = Some(v);
+            }
+        }
+
+        let line = joined.as_deref().unwrap_or(line);
+        match Self::split_tail(line) {
+            Some((start, end, carry)) => {
+                self.carry = Some(carry);
+                out.extend_from_slice(&line[..start]);
+                out.extend_from_slice(&line[end..]);
+                true
+            }
+            None => {
+                out.extend_from_slice(line);
+                changed
+            }
+        }
+    }
+
+    pub(super) fn repair(&mut self, input: Bytes) -> FixBytesOutcome {
+        if !self.may_need_repair(input.as_ref()) {
+            return FixBytesOutcome {
+                data: input,
+                applied: false,
+                details: None,
+            };
+        }
+
+        let bytes = input.as_ref();
+        let mut out: Vec<u8> = Vec::with_capacity(bytes.len() + 8);
+        let mut changed = false;
+        for segment in bytes.split_inclusive(|b| *b == b'\n') {
+            let (line, newline) = match segment.strip_suffix(b"\n") {
+                Some(line) => (line, true),
+                None => (segment, false),
+            };
+            let (line, cr) = match line.strip_suffix(b"\r") {
+                Some(line) => (line, true),
+                None => (line, false),
+            };
+            changed |= self.repair_line(line, &mut out);
+            if cr {
+                out.push(b'\r');
+            }
+            if newline {
+                out.push(b'\n');
+            }
+        }
+
+        if !changed {
+            return FixBytesOutcome {
+                data: input,
+                applied: false,
+                details: None,
+            };
+        }
+        FixBytesOutcome {
+            data: Bytes::from(out),
+            applied: true,
+            details: Some("rejoined_split_utf8_across_chunks"),
+        }
+    }
+}
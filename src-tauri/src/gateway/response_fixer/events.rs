@@ -0,0 +1,406 @@
+use axum::body::Bytes;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+use super::encoding::FixBytesOutcome;
+use super::json::JsonFixer;
+use super::tool_args;
+
+/// 有状态的 Anthropic SSE 事件序列修复：按完整事件（空行结尾）检查顺序与重复。
+///
+/// - 重复的 `message_start` / `message_stop` / `content_block_start` / `content_block_stop` 直接丢弃
+/// - 新 block 开始、`message_delta`、`message_stop` 前仍有未关闭的 block 时补发 `content_block_stop`
+/// - `text_delta` / `thinking_delta` 先于对应 `content_block_start` 到达时补发 start
+/// - 已关闭 block 的迟到 delta 丢弃
+///
+/// 启用工具参数修复时，另外在 `tool_use` block 结束前检查累积的 `input_json_delta`，
+/// 截断时补发一个 delta 把 JSON 补全。
+///
+/// 非 Anthropic 事件（无匹配 `type`）原样透传。
+pub(super) struct SseEventSequencer {
+    pending: Vec<u8>,
+    max_pending: usize,
+    reorder: bool,
+    tool_args: Option<ToolArgsTracker>,
+    message_started: bool,
+    message_stopped: bool,
+    open_block: Option<u64>,
+    started_blocks: HashSet<u64>,
+    stopped_blocks: HashSet<u64>,
+}
+
+struct ToolArgsTracker {
+    json_fixer: JsonFixer,
+    buffers: HashMap<u64, (Option<String>, String)>,
+    repairs: Vec<Value>,
+}
+
+enum EventAction {
+    Pass,
+    Drop(&'static str),
+    /// `None` reason: only tool args were completed (reported separately, not as an SSE fix).
+    Prepend(Vec<u8>, Option<&'static str>),
+}
+
+struct ParsedEvent {
+    kind: String,
+    index: Option<u64>,
+    delta_type: Option<String>,
+    block_type: Option<String>,
+    tool_name: Option<String>,
+    partial_json: Option<String>,
+}
+
+fn parse_event(raw: &[u8]) -> Option<ParsedEvent> {
+    let text = std::str::from_utf8(raw).ok()?;
+    let mut event_name: Option<&str> = None;
+    let mut data = String::new();
+    for line in text.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if let Some(rest) = line.strip_prefix("event:") {
+            event_name = Some(rest.trim());
+        } else if let Some(rest) = line.strip_prefix("data:") {
+            if !data.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(rest.strip_prefix(' ').unwrap_or(rest));
+        }
+    }
+
+    let value: Option<Value> = serde_json::from_str(data.trim()).ok();
+    let kind = value
+        .as_ref()
+        .and_then(|v| v.get("type"))
+        .and_then(|v| v.as_str())
+        .or(event_name)?
+        .to_string();
+    let index = value
+        .as_ref()
+        .and_then(|v| v.get("index"))
+        .and_then(|v| v.as_u64());
+    let delta_type = value
+        .as_ref()
+        .and_then(|v| v.get("delta"))
+        .and_then(|v| v.get("type"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let block = value.as_ref().and_then(|v| v.get("content_block"));
+    let block_type = block
+        .and_then(|v| v.get("type"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let tool_name = block
+        .and_then(|v| v.get("name"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let partial_json = value
+        .as_ref()
+        .and_then(|v| v.get("delta"))
+        .and_then(|v| v.get("partial_json"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+
+    Some(ParsedEvent {
+        kind,
+        index,
+        delta_type,
+        block_type,
+        tool_name,
+        partial_json,
+    })
+}
+
+fn block_stop_event(index: u64) -> Vec<u8> {
+    format!(
+        "event: content_block_stop\ndata: {{\"type\":\"content_block_stop\",\"index\":{index}}}\n\n"
+    )
+    .into_bytes()
+}
+
+fn input_json_delta_event(index: u64, partial_json: &str) -> Vec<u8> {
+    let payload = serde_json::json!({
+        "type": "content_block_delta",
+        "index": index,
+        "delta": { "type": "input_json_delta", "partial_json": partial_json },
+    });
+    format!("event: content_block_delta\ndata: {payload}\n\n").into_bytes()
+}
+
+fn block_start_event(index: u64, block: Value) -> Vec<u8> {
+    let payload = serde_json::json!({
+        "type": "content_block_start",
+        "index": index,
+        "content_block": block,
+    });
+    format!("event: content_block_start\ndata: {payload}\n\n").into_bytes()
+}
+
+/// Returns the end offset (exclusive) of the first complete event starting at `start`.
+fn find_event_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut line_start = start;
+    while line_start < bytes.len() {
+        let rel = bytes[line_start..].iter().position(|b| *b == b'\n')?;
+        let line_end = line_start + rel;
+        let line = &bytes[line_start..line_end];
+        if line.is_empty() || line == b"\r" {
+            return Some(line_end + 1);
+        }
+        line_start = line_end + 1;
+    }
+    None
+}
+
+impl SseEventSequencer {
+    pub(super) fn new(
+        max_pending: usize,
+        reorder: bool,
+        tool_args_fixer: Option<JsonFixer>,
+    ) -> Self {
+        Self {
+            pending: Vec::new(),
+            max_pending,
+            reorder,
+            tool_args: tool_args_fixer.map(|json_fixer| ToolArgsTracker {
+                json_fixer,
+                buffers: HashMap::new(),
+                repairs: Vec::new(),
+            }),
+            message_started: false,
+            message_stopped: false,
+            open_block: None,
+            started_blocks: HashSet::new(),
+            stopped_blocks: HashSet::new(),
+        }
+    }
+
+    /// Completion delta for a truncated `tool_use` block that is about to stop.
+    fn complete_tool_args(&mut self, index: u64) -> Option<Vec<u8>> {
+        let tracker = self.tool_args.as_mut()?;
+        let (name, buffered) = tracker.buffers.remove(&index)?;
+        let suffix = tool_args::completion_suffix(&buffered, &tracker.json_fixer)?;
+        tracker.repairs.push(serde_json::json!({
+            "format": "anthropic_stream",
+            "tool": name,
+            "action": tool_args::ACTION_COMPLETED,
+        }));
+        Some(input_json_delta_event(index, &suffix))
+    }
+
+    fn track_tool_args(&mut self, event: &ParsedEvent) {
+        let (Some(tracker), Some(index)) = (self.tool_args.as_mut(), event.index) else {
+            return;
+        };
+        match event.kind.as_str() {
+            "content_block_start" if event.block_type.as_deref() == Some("tool_use") => {
+                tracker
+                    .buffers
+                    .insert(index, (event.tool_name.clone(), String::new()));
+            }
+            "content_block_delta" => {
+                if let (Some(partial), Some((_, buffered))) = (
+                    event.partial_json.as_deref(),
+                    tracker.buffers.get_mut(&index),
+                ) {
+                    buffered.push_str(partial);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub(super) fn take_tool_args_repairs(&mut self) -> Vec<Value> {
+        self.tool_args
+            .as_mut()
+            .map(|tracker| std::mem::take(&mut tracker.repairs))
+            .unwrap_or_default()
+    }
+
+    fn close_open_block(&mut self) -> Option<Vec<u8>> {
+        let index = self.open_block.take()?;
+        self.stopped_blocks.insert(index);
+        let mut out = self.complete_tool_args(index).unwrap_or_default();
+        out.extend_from_slice(&block_stop_event(index));
+        Some(out)
+    }
+
+    fn decide(&mut self, event: &ParsedEvent) -> EventAction {
+        let action = if self.reorder {
+            self.decide_order(event)
+        } else {
+            EventAction::Pass
+        };
+        if matches!(action, EventAction::Drop(_)) {
+            return action;
+        }
+        self.track_tool_args(event);
+
+        if event.kind != "content_block_stop" {
+            return action;
+        }
+        let Some(completion) = event.index.and_then(|index| self.complete_tool_args(index)) else {
+            return action;
+        };
+        match action {
+            EventAction::Prepend(mut prefix, reason) => {
+                prefix.extend_from_slice(&completion);
+                EventAction::Prepend(prefix, reason)
+            }
+            _ => EventAction::Prepend(completion, None),
+        }
+    }
+
+    fn decide_order(&mut self, event: &ParsedEvent) -> EventAction {
+        match event.kind.as_str() {
+            "message_start" => {
+                if self.message_started {
+                    return EventAction::Drop("dropped_duplicate_message_start");
+                }
+                self.message_started = true;
+                EventAction::Pass
+            }
+            "content_block_start" => {
+                let Some(index) = event.index else {
+                    return EventAction::Pass;
+                };
+                if self.started_blocks.contains(&index) {
+                    return EventAction::Drop("dropped_duplicate_content_block_start");
+                }
+                let prefix = match self.open_block {
+                    Some(open) if open != index => self.close_open_block(),
+                    _ => None,
+                };
+                self.started_blocks.insert(index);
+                self.open_block = Some(index);
+                match prefix {
+                    Some(prefix) => {
+                        EventAction::Prepend(prefix, Some("inserted_content_block_stop"))
+                    }
+                    None => EventAction::Pass,
+                }
+            }
+            "content_block_delta" => {
+                let Some(index) = event.index else {
+                    return EventAction::Pass;
+                };
+                if self.stopped_blocks.contains(&index) {
+                    return EventAction::Drop("dropped_delta_after_content_block_stop");
+                }
+                if self.started_blocks.contains(&index) {
+                    return EventAction::Pass;
+                }
+                let block = match event.delta_type.as_deref() {
+                    Some("text_delta") => serde_json::json!({ "type": "text", "text": "" }),
+                    Some("thinking_delta") => {
+                        serde_json::json!({ "type": "thinking", "thinking": "" })
+                    }
+                    _ => return EventAction::Pass,
+                };
+                let mut prefix = self.close_open_block().unwrap_or_default();
+                prefix.extend_from_slice(&block_start_event(index, block));
+                self.started_blocks.insert(index);
+                self.open_block = Some(index);
+                EventAction::Prepend(prefix, Some("inserted_content_block_start"))
+            }
+            "content_block_stop" => {
+                let Some(index) = event.index else {
+                    return EventAction::Pass;
+                };
+                if !self.started_blocks.contains(&index) || !self.stopped_blocks.insert(index) {
+                    return EventAction::Drop("dropped_unmatched_content_block_stop");
+                }
+                if self.open_block == Some(index) {
+                    self.open_block = None;
+                }
+                EventAction::Pass
+            }
+            "message_delta" | "message_stop" => {
+                if event.kind == "message_stop" {
+                    if self.message_stopped {
+                        return EventAction::Drop("dropped_duplicate_message_stop");
+                    }
+                    self.message_stopped = true;
+                }
+                match self.close_open_block() {
+                    Some(prefix) => {
+                        EventAction::Prepend(prefix, Some("inserted_content_block_stop"))
+                    }
+                    None => EventAction::Pass,
+                }
+            }
+            _ => EventAction::Pass,
+        }
+    }
+
+    fn apply(action: EventAction, raw: &[u8], out: &mut Vec<u8>) -> Option<&'static str> {
+        match action {
+            EventAction::Pass => {
+                out.extend_from_slice(raw);
+                None
+            }
+            EventAction::Drop(reason) => Some(reason),
+            EventAction::Prepend(prefix, reason) => {
+                out.extend_from_slice(&prefix);
+                out.extend_from_slice(raw);
+                reason
+            }
+        }
+    }
+
+    /// Feeds line-complete bytes; returns the complete events ready to emit.
+    pub(super) fn push(&mut self, input: Bytes) -> FixBytesOutcome {
+        if self.pending.is_empty() && find_event_end(input.as_ref(), 0) == Some(input.len()) {
+            // 常见情况：输入恰好是一个完整事件，无需修复时零拷贝透传。
+            let action = match parse_event(input.as_ref()) {
+                Some(event) => self.decide(&event),
+                None => EventAction::Pass,
+            };
+            if let EventAction::Pass = action {
+                return FixBytesOutcome {
+                    data: input,
+                    applied: false,
+                    details: None,
+                };
+            }
+            let mut out = Vec::with_capacity(input.len());
+            let details = Self::apply(action, input.as_ref(), &mut out);
+            return FixBytesOutcome {
+                data: Bytes::from(out),
+                applied: details.is_some(),
+                details,
+            };
+        }
+
+        self.pending.extend_from_slice(input.as_ref());
+
+        let mut out: Vec<u8> = Vec::with_capacity(self.pending.len());
+        let mut details: Option<&'static str> = None;
+        let mut cursor = 0usize;
+
+        while let Some(end) = find_event_end(&self.pending, cursor) {
+            let action = match parse_event(&self.pending[cursor..end]) {
+                Some(event) => self.decide(&event),
+                None => EventAction::Pass,
+            };
+            let reason = Self::apply(action, &self.pending[cursor..end], &mut out);
+            details = details.or(reason);
+            cursor = end;
+        }
+
+        self.pending.drain(..cursor);
+        if self.pending.len() > self.max_pending {
+            // 安全保护：长时间没有事件边界时不再缓冲，原样输出。
+            out.append(&mut self.pending);
+        }
+
+        FixBytesOutcome {
+            data: Bytes::from(out),
+            applied: details.is_some(),
+            details,
+        }
+    }
+
+    /// Emits whatever is still buffered (stream end / degrade to passthrough).
+    pub(super) fn flush(&mut self) -> Bytes {
+        Bytes::from(std::mem::take(&mut self.pending))
+    }
+}
@@ -1,8 +1,10 @@
 mod audit;
 mod encoding;
+mod events;
 mod json;
 mod sse;
 mod stream;
+mod tool_args;
 
 use axum::body::Bytes;
 use futures_core::Stream;
@@ -11,6 +13,8 @@ use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 
+use crate::providers::ResponseFixerOverride;
+
 pub(super) const DEFAULT_MAX_JSON_DEPTH: usize = 200;
 pub(super) const DEFAULT_MAX_FIX_SIZE: usize = 1024 * 1024;
 
@@ -19,10 +23,36 @@ pub(super) struct ResponseFixerConfig {
     pub(super) fix_encoding: bool,
     pub(super) fix_sse_format: bool,
     pub(super) fix_truncated_json: bool,
+    pub(super) fix_tool_args: bool,
+    /// Analyze and record what would change, but forward the original bytes untouched.
+    pub(super) dry_run: bool,
     pub(super) max_json_depth: usize,
     pub(super) max_fix_size: usize,
 }
 
+impl ResponseFixerConfig {
+    /// Applies per-provider overrides on top of this (global) config; unset fields keep the global value.
+    pub(super) fn with_override(self, overrides: &ResponseFixerOverride) -> Self {
+        Self {
+            fix_encoding: overrides.fix_encoding.unwrap_or(self.fix_encoding),
+            fix_sse_format: overrides.fix_sse_format.unwrap_or(self.fix_sse_format),
+            fix_truncated_json: overrides
+                .fix_truncated_json
+                .unwrap_or(self.fix_truncated_json),
+            fix_tool_args: overrides.fix_tool_args.unwrap_or(self.fix_tool_args),
+            dry_run: overrides.dry_run.unwrap_or(self.dry_run),
+            max_json_depth: overrides
+                .max_json_depth
+                .map(|v| v as usize)
+                .unwrap_or(self.max_json_depth),
+            max_fix_size: overrides
+                .max_fix_size
+                .map(|v| v as usize)
+                .unwrap_or(self.max_fix_size),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(super) struct NonStreamFixOutcome {
     pub(super) body: Bytes,
@@ -9,7 +9,8 @@ use std::time::Instant;
 
 use super::audit::build_special_setting;
 use super::audit::ResponseFixerApplied;
-use super::encoding::EncodingFixer;
+use super::encoding::{EncodingFixer, StreamingUtf8Repair};
+use super::events::SseEventSequencer;
 use super::json::{fix_sse_json_lines, JsonFixer};
 use super::sse::SseFixer;
 use super::ResponseFixerConfig;
@@ -167,6 +168,8 @@ where
     total_bytes_processed: usize,
     applied: ResponseFixerApplied,
     buffer: ChunkBuffer,
+    utf8_repair: StreamingUtf8Repair,
+    sequencer: Option<SseEventSequencer>,
     passthrough: bool,
     queued: VecDeque<Bytes>,
     pending_error: Option<reqwest::Error>,
@@ -191,6 +194,16 @@ where
             total_bytes_processed: 0,
             applied: ResponseFixerApplied::default(),
             buffer: ChunkBuffer::new(),
+            utf8_repair: StreamingUtf8Repair::default(),
+            sequencer: (config.fix_sse_format || config.fix_tool_args).then(|| {
+                SseEventSequencer::new(
+                    config.max_fix_size,
+                    config.fix_sse_format,
+                    config
+                        .fix_tool_args
+                        .then(|| JsonFixer::new(config.max_json_depth, config.max_fix_size)),
+                )
+            }),
             passthrough: false,
             queued: VecDeque::new(),
             pending_error: None,
@@ -205,15 +218,15 @@ where
         }
         self.finalized = true;
 
-        let hit =
-            self.applied.encoding_applied || self.applied.sse_applied || self.applied.json_applied;
-        if !hit {
+        let hit = self.applied.hit();
+        if !hit && !self.config.dry_run {
             return;
         }
 
         let processing_time_ms = self.started.elapsed().as_millis().min(u64::MAX as u128) as u64;
         let special = build_special_setting(
-            true,
+            hit,
+            self.config.dry_run,
             &self.applied,
             true,
             self.total_bytes_processed,
@@ -229,6 +242,14 @@ where
         let mut data = input;
 
         if self.config.fix_encoding {
+            // 先拼接跨事件拆分的多字节字符，再做逐段有损修复，避免把残片替换成 U+FFFD。
+            let res = self.utf8_repair.repair(data);
+            if res.applied {
+                self.applied.encoding_applied = true;
+                self.applied.encoding_details = self.applied.encoding_details.or(res.details);
+            }
+            data = res.data;
+
             let res = EncodingFixer::fix_bytes(data);
             if res.applied {
                 self.applied.encoding_applied = true;
@@ -258,8 +279,34 @@ where
             data = res.data;
         }
 
+        if let Some(sequencer) = self.sequencer.as_mut() {
+            let res = sequencer.push(data);
+            if res.applied {
+                self.applied.sse_applied = true;
+                self.applied.sse_details = self.applied.sse_details.or(res.details);
+            }
+            self.applied
+                .tool_args_repairs
+                .extend(sequencer.take_tool_args_repairs());
+            data = res.data;
+        }
+
         data
     }
+
+    /// Queues fixed output; in dry-run the upstream chunks are forwarded as-is, so it is discarded.
+    fn emit(&mut self, data: Bytes) {
+        if !data.is_empty() && !self.config.dry_run {
+            self.queued.push_back(data);
+        }
+    }
+
+    fn flush_sequencer(&mut self) {
+        if let Some(sequencer) = self.sequencer.as_mut() {
+            let rest = sequencer.flush();
+            self.emit(rest);
+        }
+    }
 }
 
 impl<S> Stream for ResponseFixerStreamInner<S>
@@ -291,12 +338,11 @@ where
                     if this.buffer.len() > 0 && !this.passthrough {
                         let drained = Bytes::from(this.buffer.drain());
                         let fixed = this.process_bytes(drained);
-                        if !fixed.is_empty() {
-                            this.queued.push_back(fixed);
-                        }
+                        this.emit(fixed);
                     } else {
                         this.buffer.clear();
                     }
+                    this.flush_sequencer();
 
                     this.upstream_done = true;
                     this.finalize_if_needed();
@@ -306,12 +352,11 @@ where
                     if this.buffer.len() > 0 && !this.passthrough {
                         let drained = Bytes::from(this.buffer.drain());
                         let fixed = this.process_bytes(drained);
-                        if !fixed.is_empty() {
-                            this.queued.push_back(fixed);
-                        }
+                        this.emit(fixed);
                     } else {
                         this.buffer.clear();
                     }
+                    this.flush_sequencer();
 
                     this.pending_error = Some(err);
                     this.upstream_done = true;
@@ -329,22 +374,27 @@ where
                     // 安全保护：如果长时间无换行，buffer 会持续增长。达到上限后降级为透传，避免内存无界增长。
                     if this.buffer.len().saturating_add(chunk.len()) > this.config.max_fix_size {
                         this.passthrough = true;
+                        this.flush_sequencer();
+                        if this.config.dry_run {
+                            this.buffer.clear();
+                            return Poll::Ready(Some(Ok(chunk)));
+                        }
                         this.buffer.flush_to(&mut this.queued);
                         this.queued.push_back(chunk);
                         continue;
                     }
 
+                    let original = this.config.dry_run.then(|| chunk.clone());
                     this.buffer.push(chunk);
 
                     let end = this.buffer.find_processable_end();
-                    if end == 0 {
-                        continue;
+                    if end > 0 {
+                        let to_process = Bytes::from(this.buffer.take(end));
+                        let fixed = this.process_bytes(to_process);
+                        this.emit(fixed);
                     }
-
-                    let to_process = Bytes::from(this.buffer.take(end));
-                    let fixed = this.process_bytes(to_process);
-                    if !fixed.is_empty() {
-                        this.queued.push_back(fixed);
+                    if let Some(original) = original {
+                        return Poll::Ready(Some(Ok(original)));
                     }
                     continue;
                 }
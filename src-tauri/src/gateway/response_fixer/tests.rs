@@ -1,4 +1,4 @@
-use super::encoding::EncodingFixer;
+use super::encoding::{EncodingFixer, StreamingUtf8Repair};
 use super::json::JsonFixer;
 use super::sse::SseFixer;
 use super::{
@@ -58,6 +58,41 @@ fn encoding_fixer_lossy_fix_invalid_utf8() {
     assert!(std::str::from_utf8(res.data.as_ref()).is_ok());
 }
 
+#[test]
+fn streaming_utf8_repair_rejoins_split_multibyte_char_and_surrogate_pair() {
+    let mut repair = StreamingUtf8Repair::default();
+
+    let mut first = b"data: {\"text\":\"a".to_vec();
+    first.extend_from_slice(&[0xe4, 0xb8]);
+    first.extend_from_slice(b"\"}\n\n");
+    let res = repair.repair(Bytes::from(first));
+    assert!(res.applied);
+    assert_eq!(res.data.as_ref(), b"data: {\"text\":\"a\"}\n\n");
+
+    let mut second = b"data: {\"text\":\"".to_vec();
+    second.push(0xad);
+    second.extend_from_slice(b"b\"}\n\n");
+    let res = repair.repair(Bytes::from(second));
+    assert!(res.applied);
+    assert_eq!(
+        std::str::from_utf8(res.data.as_ref()).unwrap(),
+        "data: {\"text\":\"中b\"}\n\n"
+    );
+
+    let res = repair.repair(Bytes::from_static(b"data: {\"text\":\"x\\ud83d\"}\n\n"));
+    assert_eq!(res.data.as_ref(), b"data: {\"text\":\"x\"}\n\n");
+    let res = repair.repair(Bytes::from_static(b"data: {\"text\":\"\\ude00y\"}\n\n"));
+    assert_eq!(
+        res.data.as_ref(),
+        b"data: {\"text\":\"\\ud83d\\ude00y\"}\n\n"
+    );
+
+    let valid = Bytes::from_static("data: {\"text\":\"中\"}\n\n".as_bytes());
+    let res = repair.repair(valid.clone());
+    assert!(!res.applied);
+    assert_eq!(res.data, valid);
+}
+
 #[test]
 fn sse_fixer_fixes_data_space() {
     let input = Bytes::from_static(b"data:{\"test\":true}\n");
@@ -190,6 +225,8 @@ fn response_fixer_non_stream_writes_special_setting_when_hit() {
         fix_encoding: true,
         fix_sse_format: true,
         fix_truncated_json: true,
+        fix_tool_args: false,
+        dry_run: false,
         max_json_depth: DEFAULT_MAX_JSON_DEPTH,
         max_fix_size: DEFAULT_MAX_FIX_SIZE,
     };
@@ -204,6 +241,77 @@ fn response_fixer_non_stream_writes_special_setting_when_hit() {
     assert!(out.special_setting.is_some());
 }
 
+#[test]
+fn response_fixer_non_stream_repairs_tool_call_arguments_when_enabled() {
+    let config = ResponseFixerConfig {
+        fix_encoding: true,
+        fix_sse_format: false,
+        fix_truncated_json: true,
+        fix_tool_args: true,
+        dry_run: false,
+        max_json_depth: DEFAULT_MAX_JSON_DEPTH,
+        max_fix_size: DEFAULT_MAX_FIX_SIZE,
+    };
+
+    let body = serde_json::json!({
+        "choices": [{
+            "message": {
+                "tool_calls": [
+                    {"function": {"name": "read", "arguments": "{\"path\":\"src/ma"}},
+                    {"function": {"name": "ls", "arguments": "\"{\\\"dir\\\":\\\".\\\"}\""}},
+                    {"function": {"name": "ok", "arguments": "{\"a\":1}"}}
+                ]
+            }
+        }]
+    });
+    let out = process_non_stream(Bytes::from(serde_json::to_vec(&body).unwrap()), config);
+    let fixed: serde_json::Value = serde_json::from_slice(out.body.as_ref()).unwrap();
+    let calls = &fixed["choices"][0]["message"]["tool_calls"];
+    assert_eq!(calls[0]["function"]["arguments"], "{\"path\":\"src/ma\"}");
+    assert_eq!(calls[1]["function"]["arguments"], "{\"dir\":\".\"}");
+    assert_eq!(calls[2]["function"]["arguments"], "{\"a\":1}");
+    assert_eq!(out.header_value, "applied");
+
+    let setting = out.special_setting.expect("special setting");
+    let repairs = setting["fixersApplied"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|v| v["fixer"] == "tool_args")
+        .expect("tool_args entry")["repairs"]
+        .clone();
+    assert_eq!(repairs[0]["action"], "completed_truncated_json");
+    assert_eq!(repairs[1]["action"], "unescaped_double_encoded");
+    assert_eq!(repairs.as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn response_fixer_non_stream_dry_run_reports_without_modifying_body() {
+    let config = ResponseFixerConfig {
+        fix_encoding: true,
+        fix_sse_format: false,
+        fix_truncated_json: true,
+        fix_tool_args: false,
+        dry_run: true,
+        max_json_depth: DEFAULT_MAX_JSON_DEPTH,
+        max_fix_size: DEFAULT_MAX_FIX_SIZE,
+    };
+
+    let out = process_non_stream(Bytes::from_static(br#"{"a":"#), config);
+    assert_eq!(out.body.as_ref(), br#"{"a":"#);
+    assert_eq!(out.header_value, "dry-run-would-apply");
+    let setting = out.special_setting.expect("dry-run always reports");
+    assert_eq!(setting["dryRun"], true);
+    assert_eq!(setting["hit"], true);
+
+    let out = process_non_stream(Bytes::from_static(br#"{"a":1}"#), config);
+    assert_eq!(out.header_value, "dry-run");
+    assert_eq!(
+        out.special_setting.expect("dry-run always reports")["hit"],
+        false
+    );
+}
+
 struct VecBytesStream {
     items: VecDeque<Result<Bytes, reqwest::Error>>,
 }
@@ -257,6 +365,8 @@ async fn response_fixer_stream_fixes_truncated_json_in_data_line_across_chunks()
         fix_encoding: true,
         fix_sse_format: true,
         fix_truncated_json: true,
+        fix_tool_args: false,
+        dry_run: false,
         max_json_depth: DEFAULT_MAX_JSON_DEPTH,
         max_fix_size: DEFAULT_MAX_FIX_SIZE,
     };
@@ -283,6 +393,8 @@ async fn response_fixer_stream_valid_sse_should_not_write_special_settings() {
         fix_encoding: true,
         fix_sse_format: true,
         fix_truncated_json: true,
+        fix_tool_args: false,
+        dry_run: false,
         max_json_depth: DEFAULT_MAX_JSON_DEPTH,
         max_fix_size: DEFAULT_MAX_FIX_SIZE,
     };
@@ -303,6 +415,8 @@ async fn response_fixer_stream_degrades_when_exceeding_max_fix_size_without_newl
         fix_encoding: true,
         fix_sse_format: true,
         fix_truncated_json: true,
+        fix_tool_args: false,
+        dry_run: false,
         max_json_depth: DEFAULT_MAX_JSON_DEPTH,
         max_fix_size: 12,
     };
@@ -325,3 +439,74 @@ async fn response_fixer_stream_degrades_when_exceeding_max_fix_size_without_newl
     let settings = special_settings.lock().unwrap();
     assert!(settings.is_empty());
 }
+
+#[tokio::test]
+async fn response_fixer_stream_repairs_duplicated_and_unclosed_anthropic_events() {
+    let special_settings = Arc::new(Mutex::new(Vec::new()));
+    let config = ResponseFixerConfig {
+        fix_encoding: true,
+        fix_sse_format: true,
+        fix_truncated_json: true,
+        fix_tool_args: false,
+        dry_run: false,
+        max_json_depth: DEFAULT_MAX_JSON_DEPTH,
+        max_fix_size: DEFAULT_MAX_FIX_SIZE,
+    };
+
+    let message_start = "event: message_start\ndata: {\"type\":\"message_start\"}\n\n";
+    let upstream = VecBytesStream::new(vec![
+        Ok(Bytes::from(message_start)),
+        Ok(Bytes::from(message_start)),
+        Ok(Bytes::from_static(
+            b"event: content_block_start\ndata: {\"type\":\"content_block_start\",\"index\":0}\n",
+        )),
+        Ok(Bytes::from_static(
+            b"\nevent: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0}\n\n",
+        )),
+        Ok(Bytes::from_static(
+            b"event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+        )),
+    ]);
+
+    let stream = ResponseFixerStream::new(upstream, config, special_settings.clone());
+    let out = String::from_utf8(collect_ok_bytes(stream).await).unwrap();
+
+    assert_eq!(out.matches("\"type\":\"message_start\"").count(), 1);
+    let stop_pos = out
+        .find("\"type\":\"content_block_stop\",\"index\":0")
+        .expect("content_block_stop inserted");
+    assert!(stop_pos > out.find("content_block_delta").unwrap());
+    assert!(stop_pos < out.find("\"type\":\"message_stop\"").unwrap());
+
+    let settings = special_settings.lock().unwrap();
+    assert_eq!(settings.len(), 1);
+    assert_eq!(settings[0]["fixersApplied"][1]["applied"], true);
+}
+
+#[tokio::test]
+async fn response_fixer_stream_dry_run_forwards_original_chunks() {
+    let special_settings = Arc::new(Mutex::new(Vec::new()));
+    let config = ResponseFixerConfig {
+        fix_encoding: true,
+        fix_sse_format: true,
+        fix_truncated_json: true,
+        fix_tool_args: false,
+        dry_run: true,
+        max_json_depth: DEFAULT_MAX_JSON_DEPTH,
+        max_fix_size: DEFAULT_MAX_FIX_SIZE,
+    };
+
+    let upstream = VecBytesStream::new(vec![
+        Ok(Bytes::from_static(b"data: {\"key\":")),
+        Ok(Bytes::from_static(b"\n\n")),
+    ]);
+
+    let stream = ResponseFixerStream::new(upstream, config, special_settings.clone());
+    let out = collect_ok_bytes(stream).await;
+    assert_eq!(out, b"data: {\"key\":\n\n");
+
+    let settings = special_settings.lock().unwrap();
+    assert_eq!(settings.len(), 1);
+    assert_eq!(settings[0]["dryRun"], true);
+    assert_eq!(settings[0]["hit"], true);
+}
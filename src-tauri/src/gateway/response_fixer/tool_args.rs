@@ -0,0 +1,157 @@
+use axum::body::Bytes;
+use serde_json::Value;
+
+use super::json::JsonFixer;
+
+pub(super) const ACTION_UNESCAPED: &str = "unescaped_double_encoded";
+pub(super) const ACTION_COMPLETED: &str = "completed_truncated_json";
+
+fn parses_as_container(raw: &str) -> bool {
+    matches!(
+        serde_json::from_str::<Value>(raw),
+        Ok(Value::Object(_)) | Ok(Value::Array(_))
+    )
+}
+
+/// Repairs one tool call arguments JSON string; returns `(fixed, action)` only when it changed.
+///
+/// - 双重编码：`"{\"a\":1}"`（整体是 JSON 字符串）或 `{\"a\":1}`（转义但缺少外层引号）→ 反转义
+/// - 截断：`{"path":"src/ma` → 括号 / 引号补全
+pub(super) fn repair_arguments(
+    raw: &str,
+    json_fixer: &JsonFixer,
+) -> Option<(String, &'static str)> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    match serde_json::from_str::<Value>(trimmed) {
+        Ok(Value::String(inner)) if parses_as_container(&inner) => {
+            return Some((inner, ACTION_UNESCAPED));
+        }
+        Ok(_) => return None,
+        Err(_) => {}
+    }
+
+    if trimmed.starts_with("{\\\"") || trimmed.starts_with("[\\\"") {
+        if let Ok(Value::String(inner)) = serde_json::from_str::<Value>(&format!("\"{trimmed}\"")) {
+            if parses_as_container(&inner) {
+                return Some((inner, ACTION_UNESCAPED));
+            }
+        }
+    }
+
+    let res = json_fixer.fix_bytes(Bytes::copy_from_slice(trimmed.as_bytes()));
+    if !res.applied {
+        return None;
+    }
+    let fixed = String::from_utf8(res.data.to_vec()).ok()?;
+    parses_as_container(&fixed).then_some((fixed, ACTION_COMPLETED))
+}
+
+fn repair_record(format: &str, name: Option<&str>, action: &'static str) -> Value {
+    serde_json::json!({
+        "format": format,
+        "tool": name,
+        "action": action,
+    })
+}
+
+fn repair_string_field(
+    container: &mut Value,
+    field: &str,
+    json_fixer: &JsonFixer,
+) -> Option<&'static str> {
+    let raw = container.get(field)?.as_str()?;
+    let (fixed, action) = repair_arguments(raw, json_fixer)?;
+    container[field] = Value::String(fixed);
+    Some(action)
+}
+
+/// Repairs tool call arguments in a non-stream response body (Anthropic / OpenAI Chat / Responses).
+pub(super) fn repair_non_stream_body(
+    body: &Bytes,
+    json_fixer: &JsonFixer,
+) -> Option<(Bytes, Vec<Value>)> {
+    let mut root: Value = serde_json::from_slice(body.as_ref()).ok()?;
+    let mut repairs: Vec<Value> = Vec::new();
+
+    // Anthropic Messages: content[].type == "tool_use" 且 input 被序列化成了字符串
+    if let Some(content) = root.get_mut("content").and_then(|v| v.as_array_mut()) {
+        for block in content.iter_mut() {
+            if block.get("type").and_then(|v| v.as_str()) != Some("tool_use") {
+                continue;
+            }
+            let Some(raw) = block.get("input").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let parsed = match repair_arguments(raw, json_fixer) {
+                Some((fixed, action)) => serde_json::from_str::<Value>(&fixed)
+                    .ok()
+                    .map(|v| (v, action)),
+                None => serde_json::from_str::<Value>(raw)
+                    .ok()
+                    .filter(|v| v.is_object())
+                    .map(|v| (v, ACTION_UNESCAPED)),
+            };
+            if let Some((value, action)) = parsed {
+                let name = block
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+                block["input"] = value;
+                repairs.push(repair_record("anthropic", name.as_deref(), action));
+            }
+        }
+    }
+
+    // OpenAI Chat Completions: choices[].message.tool_calls[].function.arguments
+    if let Some(choices) = root.get_mut("choices").and_then(|v| v.as_array_mut()) {
+        for choice in choices.iter_mut() {
+            let Some(calls) = choice
+                .pointer_mut("/message/tool_calls")
+                .and_then(|v| v.as_array_mut())
+            else {
+                continue;
+            };
+            for call in calls.iter_mut() {
+                let Some(function) = call.get_mut("function") else {
+                    continue;
+                };
+                if let Some(action) = repair_string_field(function, "arguments", json_fixer) {
+                    let name = function.get("name").and_then(|v| v.as_str());
+                    repairs.push(repair_record("openai_chat", name, action));
+                }
+            }
+        }
+    }
+
+    // OpenAI Responses: output[].type == "function_call"
+    if let Some(output) = root.get_mut("output").and_then(|v| v.as_array_mut()) {
+        for item in output.iter_mut() {
+            if item.get("type").and_then(|v| v.as_str()) != Some("function_call") {
+                continue;
+            }
+            if let Some(action) = repair_string_field(item, "arguments", json_fixer) {
+                let name = item.get("name").and_then(|v| v.as_str());
+                repairs.push(repair_record("openai_responses", name, action));
+            }
+        }
+    }
+
+    if repairs.is_empty() {
+        return None;
+    }
+    let bytes = serde_json::to_vec(&root).ok()?;
+    Some((Bytes::from(bytes), repairs))
+}
+
+/// Suffix that completes a truncated streamed `input_json_delta` buffer, if one can be appended.
+pub(super) fn completion_suffix(buffered: &str, json_fixer: &JsonFixer) -> Option<String> {
+    let (fixed, action) = repair_arguments(buffered, json_fixer)?;
+    if action != ACTION_COMPLETED {
+        return None;
+    }
+    fixed.strip_prefix(buffered.trim()).map(str::to_string)
+}
@@ -2,12 +2,15 @@ use axum::{
     body::Body,
     extract::{Path, State},
     http::Request,
+    middleware,
     response::Response,
     routing::{any, get},
     Json, Router,
 };
 use serde::Serialize;
 
+use super::access::require_access_token;
+use super::drain::track_in_flight;
 use super::manager::GatewayAppState;
 use super::proxy::proxy_impl;
 use super::util::now_unix_seconds;
@@ -73,5 +76,50 @@ pub(super) fn build_router(state: GatewayAppState) -> Router {
         .route("/v1", any(proxy_openai_v1_root))
         .route("/v1/*path", any(proxy_openai_v1_any))
         .route("/:cli_key/*path", any(proxy_cli_any))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_access_token,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            track_in_flight,
+        ))
+        .with_state(state)
+}
+
+/// Router for a dedicated listener: every path is forwarded as-is to the fixed `cli_key`, for
+/// clients that cannot send the `/<cli_key>` prefix.
+pub(super) fn build_cli_router(state: GatewayAppState, cli_key: String) -> Router {
+    let root_cli_key = cli_key.clone();
+    Router::new()
+        .route("/health", get(health))
+        .route(
+            "/",
+            any(
+                move |State(state): State<GatewayAppState>, req: Request<Body>| {
+                    let cli_key = root_cli_key.clone();
+                    async move { proxy_impl(state, cli_key, "/".to_string(), req).await }
+                },
+            ),
+        )
+        .route(
+            "/*path",
+            any(
+                move |State(state): State<GatewayAppState>,
+                      Path(path): Path<String>,
+                      req: Request<Body>| {
+                    let cli_key = cli_key.clone();
+                    async move { proxy_impl(state, cli_key, format!("/{path}"), req).await }
+                },
+            ),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_access_token,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            track_in_flight,
+        ))
         .with_state(state)
 }
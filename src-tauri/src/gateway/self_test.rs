@@ -0,0 +1,235 @@
+//! Usage: Startup self-test: loopback requests through the bound gateway, one per enabled CLI proxy.
+//!
+//! 用法：
+//! - 网关自动启动后调用 `run`：先请求 `/health`，再按 CLI 代理已开启的 CLI 各发一个携带
+//!   `x-aio-self-test` 的请求（路径与该 CLI 实际使用的一致，如 `/claude/v1/messages`、`/v1/responses`）
+//! - 代理处理器在暂停 / CLI 代理开关检查之后由 `is_self_test_request` 识别并本地应答，不转发上游、不写请求日志
+//! - 结果通过 `gateway:self_test` 事件推送，并保存为最近一次结果供 `gateway_self_test_get` 查询
+
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::shared::mutex_ext::MutexExt;
+use crate::{cli_proxy, settings};
+
+use super::access::constant_time_eq;
+use super::util::now_unix_seconds;
+use super::GatewayStatus;
+
+const SELF_TEST_HEADER: &str = "x-aio-self-test";
+const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+static NONCE: OnceLock<String> = OnceLock::new();
+static LAST_REPORT: OnceLock<Mutex<Option<GatewaySelfTestReport>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewaySelfTestCheck {
+    // "health" or a cli_key.
+    pub target: String,
+    pub path: String,
+    pub ok: bool,
+    pub status: Option<u16>,
+    pub error_code: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewaySelfTestReport {
+    pub ok: bool,
+    pub base_url: String,
+    pub checked_at: i64,
+    pub health: GatewaySelfTestCheck,
+    pub clis: Vec<GatewaySelfTestCheck>,
+}
+
+/// Per-process secret, so external clients cannot trigger the local self-test answer.
+fn nonce() -> &'static str {
+    NONCE.get_or_init(|| {
+        let mut bytes = [0u8; 16];
+        if getrandom::getrandom(&mut bytes).is_err() {
+            bytes = (now_unix_seconds() as u128 ^ std::process::id() as u128).to_le_bytes();
+        }
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    })
+}
+
+pub(super) fn is_self_test_request(headers: &HeaderMap) -> bool {
+    headers
+        .get(SELF_TEST_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| constant_time_eq(v.trim().as_bytes(), nonce().as_bytes()))
+}
+
+pub(super) fn self_test_response(cli_key: &str, forwarded_path: &str, trace_id: &str) -> Response {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "ok": true,
+            "cli_key": cli_key,
+            "path": forwarded_path,
+            "trace_id": trace_id,
+        })),
+    )
+        .into_response()
+}
+
+/// The path each CLI actually uses once its proxy config points at the gateway (see `cli_proxy`).
+fn cli_probe_path(cli_key: &str) -> Option<&'static str> {
+    match cli_key {
+        "claude" => Some("/claude/v1/messages"),
+        "codex" => Some("/v1/responses"),
+        "gemini" => Some("/gemini/v1beta/models/self-test:generateContent"),
+        _ => None,
+    }
+}
+
+async fn probe(
+    client: &reqwest::Client,
+    base_url: &str,
+    access_token: &str,
+    target: &str,
+    path: &str,
+    self_test: bool,
+) -> GatewaySelfTestCheck {
+    let started = Instant::now();
+    let url = format!("{base_url}{path}");
+    let mut req = if self_test {
+        client
+            .post(url)
+            .header(SELF_TEST_HEADER, nonce())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body("{}")
+    } else {
+        client.get(url)
+    };
+    if !access_token.is_empty() {
+        req = req.bearer_auth(access_token);
+    }
+
+    let (ok, status, error_code, error) = match req.send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            if status.is_success() {
+                (true, Some(status.as_u16()), None, None)
+            } else {
+                let value = serde_json::from_str::<serde_json::Value>(&body).ok();
+                let field = |name: &str| {
+                    value
+                        .as_ref()
+                        .and_then(|v| v.get(name))
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string)
+                };
+                let message = field("message").unwrap_or_else(|| body.chars().take(200).collect());
+                (
+                    false,
+                    Some(status.as_u16()),
+                    field("error_code"),
+                    Some(message),
+                )
+            }
+        }
+        Err(err) => (false, None, None, Some(err.to_string())),
+    };
+
+    GatewaySelfTestCheck {
+        target: target.to_string(),
+        path: path.to_string(),
+        ok,
+        status,
+        error_code,
+        error,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+pub async fn run(app: &tauri::AppHandle, status: &GatewayStatus) -> Option<GatewaySelfTestReport> {
+    let base_url = status.base_url.clone()?;
+    let access_token = settings::read(app)
+        .map(|cfg| cfg.gateway_access_token)
+        .unwrap_or_default();
+    let enabled_clis: Vec<String> = cli_proxy::status_all(app)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|item| item.enabled)
+        .map(|item| item.cli_key)
+        .collect();
+
+    // Loopback to our own listener: skip system proxies and accept the gateway's self-signed cert.
+    let client = match reqwest::Client::builder()
+        .no_proxy()
+        .timeout(SELF_TEST_TIMEOUT)
+        .danger_accept_invalid_certs(true)
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::warn!("网关自检 HTTP 客户端初始化失败: {}", err);
+            return None;
+        }
+    };
+
+    let health = probe(&client, &base_url, "", "health", "/health", false).await;
+    let mut clis = Vec::with_capacity(enabled_clis.len());
+    for cli_key in &enabled_clis {
+        let Some(path) = cli_probe_path(cli_key) else {
+            continue;
+        };
+        clis.push(probe(&client, &base_url, &access_token, cli_key, path, true).await);
+    }
+
+    let report = GatewaySelfTestReport {
+        ok: health.ok && clis.iter().all(|c| c.ok),
+        base_url,
+        checked_at: now_unix_seconds() as i64,
+        health,
+        clis,
+    };
+    if report.ok {
+        tracing::info!(clis = report.clis.len(), "网关自检通过");
+    } else {
+        tracing::warn!(report = ?report, "网关自检失败");
+    }
+
+    *LAST_REPORT
+        .get_or_init(|| Mutex::new(None))
+        .lock_or_recover() = Some(report.clone());
+    let _ = app.emit("gateway:self_test", report.clone());
+    Some(report)
+}
+
+pub fn last_report() -> Option<GatewaySelfTestReport> {
+    LAST_REPORT.get()?.lock_or_recover().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cli_probe_path, is_self_test_request, nonce, SELF_TEST_HEADER};
+    use axum::http::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn self_test_header_requires_process_nonce() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_self_test_request(&headers));
+
+        headers.insert(SELF_TEST_HEADER, HeaderValue::from_static("guess"));
+        assert!(!is_self_test_request(&headers));
+
+        headers.insert(SELF_TEST_HEADER, HeaderValue::from_str(nonce()).unwrap());
+        assert!(is_self_test_request(&headers));
+    }
+
+    #[test]
+    fn every_supported_cli_has_probe_path() {
+        for cli_key in crate::shared::cli_key::SUPPORTED_CLI_KEYS {
+            assert!(cli_probe_path(cli_key).is_some(), "{cli_key}");
+        }
+    }
+}
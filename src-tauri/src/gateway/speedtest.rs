@@ -0,0 +1,297 @@
+//! Usage: Provider speedtest (identical small streaming request -> TTFB / tokens per second).
+//!
+//! Unlike the base_url ping probe (network only), this measures model throughput: every selected
+//! provider receives the same benchmark prompt concurrently and the results are reported
+//! side-by-side.
+
+use crate::{db, providers, usage};
+use axum::http::{HeaderMap, HeaderValue};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use super::util::{build_target_url, inject_provider_auth};
+
+pub(crate) const MAX_SPEEDTEST_PROVIDERS: usize = 16;
+
+const BENCHMARK_PROMPT: &str =
+    "Count from 1 to 60 in English words, separated by spaces. Output only the words.";
+const BENCHMARK_MAX_OUTPUT_TOKENS: u32 = 256;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_ERROR_EXCERPT_BYTES: usize = 512;
+
+fn default_model(cli_key: &str) -> &'static str {
+    match cli_key {
+        "claude" => "claude-haiku-4-5-20251001",
+        "codex" => "gpt-5.2",
+        _ => "gemini-2.5-flash",
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SpeedtestResult {
+    pub(crate) provider_id: i64,
+    pub(crate) provider_name: String,
+    pub(crate) base_url: String,
+    pub(crate) model: String,
+    pub(crate) ok: bool,
+    pub(crate) status: Option<u16>,
+    // Request sent -> first response body chunk.
+    pub(crate) ttfb_ms: Option<u64>,
+    pub(crate) total_ms: u64,
+    pub(crate) output_tokens: Option<i64>,
+    // Output tokens over the generation window (total - ttfb).
+    pub(crate) tokens_per_second: Option<f64>,
+    pub(crate) error: Option<String>,
+}
+
+/// Returns (forwarded path, query, body) of the benchmark request for `cli_key`.
+fn benchmark_request(
+    cli_key: &str,
+    model: &str,
+) -> (String, Option<&'static str>, serde_json::Value) {
+    match cli_key {
+        "claude" => (
+            "/v1/messages".to_string(),
+            None,
+            serde_json::json!({
+                "model": model,
+                "max_tokens": BENCHMARK_MAX_OUTPUT_TOKENS,
+                "stream": true,
+                "messages": [{"role": "user", "content": BENCHMARK_PROMPT}],
+            }),
+        ),
+        "codex" => (
+            "/v1/responses".to_string(),
+            None,
+            serde_json::json!({
+                "model": model,
+                "stream": true,
+                "store": false,
+                "max_output_tokens": BENCHMARK_MAX_OUTPUT_TOKENS,
+                "input": [{"role": "user", "content": BENCHMARK_PROMPT}],
+            }),
+        ),
+        _ => (
+            format!("/v1beta/models/{model}:streamGenerateContent"),
+            Some("alt=sse"),
+            serde_json::json!({
+                "contents": [{"role": "user", "parts": [{"text": BENCHMARK_PROMPT}]}],
+                "generationConfig": {"maxOutputTokens": BENCHMARK_MAX_OUTPUT_TOKENS},
+            }),
+        ),
+    }
+}
+
+fn tokens_per_second(
+    output_tokens: Option<i64>,
+    ttfb_ms: Option<u64>,
+    total_ms: u64,
+) -> Option<f64> {
+    let tokens = output_tokens.filter(|v| *v > 0)?;
+    let generation_ms = total_ms.saturating_sub(ttfb_ms.unwrap_or(0));
+    if generation_ms == 0 {
+        return None;
+    }
+    Some(tokens as f64 * 1000.0 / generation_ms as f64)
+}
+
+async fn speedtest_one(
+    client: reqwest::Client,
+    cli_key: String,
+    provider: providers::ProviderForGateway,
+    model: String,
+) -> SpeedtestResult {
+    let model = if cli_key == "claude" {
+        provider.claude_models.map_model(&model, false)
+    } else {
+        model
+    };
+    let base_url = provider.base_urls.first().cloned().unwrap_or_default();
+    let mut result = SpeedtestResult {
+        provider_id: provider.id,
+        provider_name: provider.name.clone(),
+        base_url: base_url.clone(),
+        model: model.clone(),
+        ok: false,
+        status: None,
+        ttfb_ms: None,
+        total_ms: 0,
+        output_tokens: None,
+        tokens_per_second: None,
+        error: None,
+    };
+
+    let (path, query, body) = benchmark_request(&cli_key, &model);
+    let url = match build_target_url(&base_url, &path, query) {
+        Ok(url) => url,
+        Err(err) => {
+            result.error = Some(err);
+            return result;
+        }
+    };
+    let mut headers = HeaderMap::new();
+    headers.insert("content-type", HeaderValue::from_static("application/json"));
+    headers.insert("accept", HeaderValue::from_static("text/event-stream"));
+    inject_provider_auth(
+        &cli_key,
+        &provider.api_key_plaintext,
+        &provider.header_rules,
+        &mut headers,
+    );
+
+    let body_bytes = match serde_json::to_vec(&body) {
+        Ok(v) => v,
+        Err(err) => {
+            result.error = Some(format!("SYSTEM_ERROR: failed to encode body JSON: {err}"));
+            return result;
+        }
+    };
+
+    let started = Instant::now();
+    let send = client
+        .post(url)
+        .headers(headers)
+        .body(body_bytes)
+        .send()
+        .await;
+    let mut resp = match send {
+        Ok(resp) => resp,
+        Err(err) => {
+            result.total_ms = started.elapsed().as_millis() as u64;
+            result.error = Some(format!("HTTP_ERROR: {err}"));
+            return result;
+        }
+    };
+    let status = resp.status();
+    result.status = Some(status.as_u16());
+
+    let mut usage_tracker = usage::SseUsageTracker::new(&cli_key);
+    let mut error_excerpt = Vec::<u8>::new();
+    loop {
+        match resp.chunk().await {
+            Ok(Some(chunk)) => {
+                if chunk.is_empty() {
+                    continue;
+                }
+                if result.ttfb_ms.is_none() {
+                    result.ttfb_ms = Some(started.elapsed().as_millis() as u64);
+                }
+                if status.is_success() {
+                    usage_tracker.ingest_chunk(chunk.as_ref());
+                } else if error_excerpt.len() < MAX_ERROR_EXCERPT_BYTES {
+                    let remaining = MAX_ERROR_EXCERPT_BYTES - error_excerpt.len();
+                    error_excerpt.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+                }
+            }
+            Ok(None) => break,
+            Err(err) => {
+                result.error = Some(format!("STREAM_READ_ERROR: {err}"));
+                break;
+            }
+        }
+    }
+    result.total_ms = started.elapsed().as_millis() as u64;
+
+    if !status.is_success() {
+        result.error = Some(format!(
+            "UPSTREAM_ERROR: status={} body={}",
+            status.as_u16(),
+            String::from_utf8_lossy(&error_excerpt).trim()
+        ));
+        return result;
+    }
+
+    result.output_tokens = usage_tracker
+        .finalize()
+        .and_then(|extract| extract.metrics.output_tokens);
+    result.tokens_per_second =
+        tokens_per_second(result.output_tokens, result.ttfb_ms, result.total_ms);
+    result.ok = result.error.is_none();
+    result
+}
+
+/// Runs the benchmark against `provider_ids` (same CLI) concurrently; results keep the input order.
+pub(crate) async fn run_speedtest(
+    db: db::Db,
+    cli_key: String,
+    provider_ids: Vec<i64>,
+    model: Option<String>,
+) -> Result<Vec<SpeedtestResult>, String> {
+    if provider_ids.is_empty() {
+        return Err("SEC_INVALID_INPUT: provider_ids is required".to_string());
+    }
+    if provider_ids.len() > MAX_SPEEDTEST_PROVIDERS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: at most {MAX_SPEEDTEST_PROVIDERS} providers per speedtest"
+        ));
+    }
+    let model = model
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| default_model(&cli_key).to_string());
+
+    let cli_key_for_load = cli_key.clone();
+    let providers = crate::blocking::run("providers_speedtest_load", move || {
+        providers::list_for_gateway_by_ids(&db, &cli_key_for_load, &provider_ids)
+    })
+    .await?;
+
+    let client = reqwest::Client::builder()
+        .user_agent(format!(
+            "aio-coding-hub-speedtest/{}",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .timeout(REQUEST_TIMEOUT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .map_err(|e| format!("SPEEDTEST_HTTP_CLIENT_INIT: {e}"))?;
+
+    let tasks: Vec<_> = providers
+        .into_iter()
+        .map(|provider| {
+            tauri::async_runtime::spawn(speedtest_one(
+                client.clone(),
+                cli_key.clone(),
+                provider,
+                model.clone(),
+            ))
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        results.push(
+            task.await
+                .map_err(|e| format!("SYSTEM_ERROR: speedtest task failed: {e}"))?,
+        );
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_per_second_excludes_time_to_first_byte() {
+        assert_eq!(tokens_per_second(Some(100), Some(500), 1500), Some(100.0));
+        assert_eq!(tokens_per_second(None, Some(500), 1500), None);
+        assert_eq!(tokens_per_second(Some(100), Some(1500), 1500), None);
+    }
+
+    #[test]
+    fn benchmark_request_matches_cli_protocol() {
+        let (path, query, body) = benchmark_request("gemini", "gemini-2.5-flash");
+        assert_eq!(
+            path,
+            "/v1beta/models/gemini-2.5-flash:streamGenerateContent"
+        );
+        assert_eq!(query, Some("alt=sse"));
+        assert!(body.get("contents").is_some());
+
+        let (path, _, body) = benchmark_request("codex", "gpt-5.2");
+        assert_eq!(path, "/v1/responses");
+        assert_eq!(body["stream"], true);
+    }
+}
@@ -14,8 +14,14 @@ pub(super) use gunzip::GunzipStream;
 
 mod usage_tee;
 pub(super) use usage_tee::{
-    spawn_usage_sse_relay_body, UsageBodyBufferTeeStream, UsageSseTeeStream,
+    spawn_usage_sse_relay_body, StreamIdleTimeouts, UsageBodyBufferTeeStream, UsageSseTeeStream,
 };
 
 mod timing;
 pub(super) use timing::TimingOnlyTeeStream;
+
+mod sse_capture;
+pub(super) use sse_capture::SseCaptureMode;
+
+mod hold;
+pub(super) use hold::hold_until_body_end;
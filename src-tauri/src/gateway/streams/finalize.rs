@@ -1,6 +1,8 @@
 //! Usage: Shared stream finalize helpers (cooldown/circuit/session).
 
-use super::super::proxy::{provider_router, ErrorCategory};
+use super::super::proxy::{
+    circuit_failures, prefix_affinity, provider_router, status_override, ErrorCategory,
+};
 use super::super::util::now_unix_seconds;
 use super::StreamFinalizeCtx;
 
@@ -8,7 +10,7 @@ pub(super) fn finalize_circuit_and_session(
     ctx: &StreamFinalizeCtx,
     error_code: Option<&'static str>,
 ) -> Option<&'static str> {
-    let effective_error_category = if error_code == Some("GW_STREAM_ABORTED") {
+    let effective_error_category = if status_override::is_client_abort(error_code) {
         Some(ErrorCategory::ClientAbort.as_str())
     } else {
         ctx.error_category
@@ -41,7 +43,20 @@ pub(super) fn finalize_circuit_and_session(
                 now_unix,
             );
         }
+        if let Some(key) = ctx.prefix_affinity_key {
+            prefix_affinity::record(key, ctx.provider_id, now_unix);
+        }
     } else if effective_error_category == Some(ErrorCategory::ProviderError.as_str()) {
+        circuit_failures::record(
+            ctx.provider_id,
+            circuit_failures::FailureSample {
+                ts: now_unix,
+                error_code: error_code.unwrap_or("GW_STREAM_ERROR"),
+                status: Some(ctx.status),
+                model: ctx.requested_model.clone(),
+                summary: None,
+            },
+        );
         let _ = provider_router::record_failure_and_emit_transition(
             provider_router::RecordCircuitArgs::from_stream_ctx(ctx, now_unix),
         );
@@ -0,0 +1,65 @@
+//! Usage: Keep a guard (stream slot, lane slot, buffer reservation) alive until a response body ends.
+
+use axum::body::{Body, BodyDataStream, Bytes};
+use axum::response::Response;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Re-wraps the body of `resp` so `guard` is dropped only once the body has been sent (or dropped),
+/// streamed bodies included.
+pub(in crate::gateway) fn hold_until_body_end<G>(resp: Response, guard: G) -> Response
+where
+    G: Send + Unpin + 'static,
+{
+    resp.map(|body| {
+        Body::from_stream(GuardedBodyStream {
+            inner: body.into_data_stream(),
+            _guard: guard,
+        })
+    })
+}
+
+struct GuardedBodyStream<G> {
+    inner: BodyDataStream,
+    _guard: G,
+}
+
+impl<G: Unpin> Stream for GuardedBodyStream<G> {
+    type Item = Result<Bytes, axum::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn guard_is_released_with_the_body() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let resp = hold_until_body_end(
+            Response::new(Body::from("hello")),
+            DropFlag(dropped.clone()),
+        );
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(bytes, Bytes::from_static(b"hello"));
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+}
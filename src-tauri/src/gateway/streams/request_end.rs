@@ -2,9 +2,10 @@
 
 use super::finalize::finalize_circuit_and_session;
 use super::StreamFinalizeCtx;
+use crate::gateway::active_requests::cancel_aware_error_code;
 use crate::gateway::events::emit_request_event;
 use crate::gateway::proxy::{
-    spawn_enqueue_request_log_with_backpressure, status_override, RequestLogEnqueueArgs,
+    rate_limit, spawn_enqueue_request_log_with_backpressure, status_override, RequestLogEnqueueArgs,
 };
 use crate::gateway::response_fixer;
 
@@ -17,7 +18,9 @@ pub(super) fn emit_request_event_and_spawn_request_log(
     usage: Option<crate::usage::UsageExtract>,
 ) {
     let duration_ms = ctx.started.elapsed().as_millis();
+    let error_code = cancel_aware_error_code(error_code, &ctx.special_settings);
     let effective_error_category = finalize_circuit_and_session(ctx, error_code);
+    rate_limit::charge_usage(ctx.provider_id, usage_metrics.as_ref());
     let effective_status = status_override::effective_status(Some(ctx.status), error_code);
     let effective_excluded_from_stats =
         ctx.excluded_from_stats || status_override::is_client_abort(error_code);
@@ -67,6 +70,7 @@ pub(super) fn emit_request_event_and_spawn_request_log(
             created_at: ctx.created_at,
             usage_metrics: None,
             usage,
+            attribution: ctx.attribution.clone(),
         },
     );
 }
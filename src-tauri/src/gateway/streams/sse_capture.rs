@@ -0,0 +1,98 @@
+//! Usage: Opt-in raw SSE capture for streamed responses (`settings.capture_sse_events`).
+
+use crate::shared::reasoning_redaction;
+use crate::{db, request_sse_captures, settings};
+
+use super::super::body_budget::BufferReservation;
+
+// Per-trace cap; the remaining events are dropped and the capture is marked truncated.
+const MAX_SSE_CAPTURE_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(in crate::gateway) enum SseCaptureMode {
+    #[default]
+    Disabled,
+    Full,
+    // `exclude_reasoning_from_logs` is on: thinking / reasoning payloads are masked before persisting.
+    StripReasoning,
+}
+
+impl SseCaptureMode {
+    pub(in crate::gateway) fn from_settings(cfg: Option<&settings::AppSettings>) -> Self {
+        match cfg {
+            Some(cfg) if cfg.capture_sse_events && cfg.exclude_reasoning_from_logs => {
+                Self::StripReasoning
+            }
+            Some(cfg) if cfg.capture_sse_events => Self::Full,
+            _ => Self::Disabled,
+        }
+    }
+}
+
+pub(super) struct SseCaptureBuffer {
+    mode: SseCaptureMode,
+    buffer: Vec<u8>,
+    reservation: BufferReservation,
+    truncated: bool,
+}
+
+impl SseCaptureBuffer {
+    pub(super) fn new(mode: SseCaptureMode) -> Option<Self> {
+        if mode == SseCaptureMode::Disabled {
+            return None;
+        }
+        Some(Self {
+            mode,
+            buffer: Vec::new(),
+            reservation: BufferReservation::empty(),
+            truncated: false,
+        })
+    }
+
+    pub(super) fn ingest(&mut self, chunk: &[u8]) {
+        if self.truncated {
+            return;
+        }
+        if self.buffer.len().saturating_add(chunk.len()) > MAX_SSE_CAPTURE_BYTES
+            || !self.reservation.try_grow(chunk.len())
+        {
+            self.truncated = true;
+            return;
+        }
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Writes the capture in the background; empty captures are skipped.
+    pub(super) fn persist(self, db: db::Db, trace_id: String, cli_key: String, created_at: i64) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let Self {
+            mode,
+            buffer,
+            reservation,
+            truncated,
+        } = self;
+        std::mem::drop(tauri::async_runtime::spawn_blocking(move || {
+            let body = String::from_utf8_lossy(&buffer);
+            let body = if mode == SseCaptureMode::StripReasoning {
+                reasoning_redaction::strip_reasoning_sse_text(&body)
+            } else {
+                body.into_owned()
+            };
+            drop(buffer);
+            drop(reservation);
+
+            let capture = request_sse_captures::RequestSseCapture {
+                trace_id,
+                cli_key,
+                created_at,
+                truncated,
+                body,
+            };
+            if let Err(err) = request_sse_captures::record(&db, &capture) {
+                tracing::warn!("SSE 捕获写入失败: {}", err);
+            }
+        }));
+    }
+}
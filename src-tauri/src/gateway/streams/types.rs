@@ -5,6 +5,8 @@ use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use super::super::events::FailoverAttempt;
+use super::super::proxy::RequestLogAttribution;
+use super::SseCaptureMode;
 
 pub(in crate::gateway) struct StreamFinalizeCtx {
     pub(in crate::gateway) app: tauri::AppHandle,
@@ -14,6 +16,7 @@ pub(in crate::gateway) struct StreamFinalizeCtx {
     pub(in crate::gateway) session: Arc<session_manager::SessionManager>,
     pub(in crate::gateway) session_id: Option<String>,
     pub(in crate::gateway) sort_mode_id: Option<i64>,
+    pub(in crate::gateway) prefix_affinity_key: Option<u64>,
     pub(in crate::gateway) trace_id: String,
     pub(in crate::gateway) cli_key: String,
     pub(in crate::gateway) method: String,
@@ -28,10 +31,12 @@ pub(in crate::gateway) struct StreamFinalizeCtx {
     pub(in crate::gateway) attempts: Vec<FailoverAttempt>,
     pub(in crate::gateway) attempts_json: String,
     pub(in crate::gateway) requested_model: Option<String>,
+    pub(in crate::gateway) attribution: RequestLogAttribution,
     pub(in crate::gateway) created_at_ms: i64,
     pub(in crate::gateway) created_at: i64,
     pub(in crate::gateway) provider_cooldown_secs: i64,
     pub(in crate::gateway) provider_id: i64,
     pub(in crate::gateway) provider_name: String,
     pub(in crate::gateway) base_url: String,
+    pub(in crate::gateway) sse_capture: SseCaptureMode,
 }
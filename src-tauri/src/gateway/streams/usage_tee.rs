@@ -6,10 +6,12 @@ use futures_core::Stream;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use super::super::body_budget::{self, BufferReservation};
 use super::super::util::now_unix_seconds;
 use super::request_end::emit_request_event_and_spawn_request_log;
+use super::sse_capture::SseCaptureBuffer;
 use super::{RelayBodyStream, StreamFinalizeCtx};
 
 struct NextFuture<'a, S: Stream + Unpin>(&'a mut S);
@@ -26,6 +28,33 @@ async fn next_item<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
     NextFuture(stream).await
 }
 
+/// Idle timeouts for an SSE stream: the regular one, and a longer one while a Claude turn is paused
+/// on server-side tool use (`ping` keep-alives but no `message_delta` yet).
+#[derive(Debug, Clone, Copy)]
+pub(in crate::gateway) struct StreamIdleTimeouts {
+    idle: Option<Duration>,
+    tool_pause: Option<Duration>,
+}
+
+impl StreamIdleTimeouts {
+    /// The tool-pause timeout only applies when an idle timeout is set, and never shortens it.
+    pub(in crate::gateway) fn new(idle: Option<Duration>, tool_pause: Option<Duration>) -> Self {
+        let tool_pause = match (idle, tool_pause) {
+            (Some(idle), Some(tool_pause)) => Some(idle.max(tool_pause)),
+            _ => None,
+        };
+        Self { idle, tool_pause }
+    }
+
+    fn current(&self, in_tool_pause: bool) -> Option<Duration> {
+        if in_tool_pause {
+            self.tool_pause.or(self.idle)
+        } else {
+            self.idle
+        }
+    }
+}
+
 pub(in crate::gateway) struct UsageSseTeeStream<S, B>
 where
     S: Stream<Item = Result<B, reqwest::Error>> + Unpin,
@@ -33,10 +62,14 @@ where
 {
     upstream: S,
     tracker: usage::SseUsageTracker,
+    capture: Option<SseCaptureBuffer>,
     ctx: StreamFinalizeCtx,
     first_byte_ms: Option<u128>,
-    idle_timeout: Option<Duration>,
+    idle_timeouts: StreamIdleTimeouts,
     idle_sleep: Option<Pin<Box<tokio::time::Sleep>>>,
+    last_chunk_at: Instant,
+    // Set when a tool pause outlived the regular idle timeout; recorded in `special_settings_json`.
+    tool_pause_grace_hit: bool,
     finalized: bool,
 }
 
@@ -48,16 +81,21 @@ where
     pub(in crate::gateway) fn new(
         upstream: S,
         ctx: StreamFinalizeCtx,
-        idle_timeout: Option<Duration>,
+        idle_timeouts: StreamIdleTimeouts,
         initial_first_byte_ms: Option<u128>,
     ) -> Self {
         Self {
             upstream,
             tracker: usage::SseUsageTracker::new(&ctx.cli_key),
+            capture: SseCaptureBuffer::new(ctx.sse_capture),
             ctx,
             first_byte_ms: initial_first_byte_ms,
-            idle_timeout,
-            idle_sleep: idle_timeout.map(|d| Box::pin(tokio::time::sleep(d))),
+            idle_timeouts,
+            idle_sleep: idle_timeouts
+                .current(false)
+                .map(|d| Box::pin(tokio::time::sleep(d))),
+            last_chunk_at: Instant::now(),
+            tool_pause_grace_hit: false,
             finalized: false,
         }
     }
@@ -68,6 +106,26 @@ where
         }
         self.finalized = true;
 
+        if self.tool_pause_grace_hit {
+            if let Ok(mut guard) = self.ctx.special_settings.lock() {
+                guard.push(serde_json::json!({
+                    "type": "stream_tool_pause_grace",
+                    "scope": "stream",
+                    "hit": true,
+                    "idleTimeoutSeconds": self.idle_timeouts.tool_pause.map(|d| d.as_secs()),
+                }));
+            }
+        }
+
+        if let Some(capture) = self.capture.take() {
+            capture.persist(
+                self.ctx.db.clone(),
+                self.ctx.trace_id.clone(),
+                self.ctx.cli_key.clone(),
+                self.ctx.created_at,
+            );
+        }
+
         let usage = self.tracker.finalize();
         let usage_metrics = usage.as_ref().map(|u| u.metrics.clone());
         let requested_model = self
@@ -116,10 +174,23 @@ where
                 if this.first_byte_ms.is_none() {
                     this.first_byte_ms = Some(this.ctx.started.elapsed().as_millis());
                 }
-                if let Some(d) = this.idle_timeout {
-                    this.idle_sleep = Some(Box::pin(tokio::time::sleep(d)));
+                let paused_for = this.last_chunk_at.elapsed();
+                this.last_chunk_at = Instant::now();
+                if this.tracker.in_tool_pause()
+                    && this
+                        .idle_timeouts
+                        .idle
+                        .is_some_and(|idle| paused_for > idle)
+                {
+                    this.tool_pause_grace_hit = true;
                 }
                 this.tracker.ingest_chunk(chunk.as_ref());
+                if let Some(capture) = this.capture.as_mut() {
+                    capture.ingest(chunk.as_ref());
+                }
+                if let Some(d) = this.idle_timeouts.current(this.tracker.in_tool_pause()) {
+                    this.idle_sleep = Some(Box::pin(tokio::time::sleep(d)));
+                }
                 Poll::Ready(Some(Ok(chunk)))
             }
             Poll::Ready(Some(Err(err))) => {
@@ -147,7 +218,7 @@ const SSE_RELAY_BUFFER_CAPACITY: usize = 32;
 pub(in crate::gateway) fn spawn_usage_sse_relay_body<S>(
     upstream: S,
     ctx: StreamFinalizeCtx,
-    idle_timeout: Option<Duration>,
+    idle_timeouts: StreamIdleTimeouts,
     initial_first_byte_ms: Option<u128>,
 ) -> Body
 where
@@ -156,7 +227,7 @@ where
     let (tx, rx) =
         tokio::sync::mpsc::channel::<Result<Bytes, reqwest::Error>>(SSE_RELAY_BUFFER_CAPACITY);
 
-    let mut tee = UsageSseTeeStream::new(upstream, ctx, idle_timeout, initial_first_byte_ms);
+    let mut tee = UsageSseTeeStream::new(upstream, ctx, idle_timeouts, initial_first_byte_ms);
 
     tokio::spawn(async move {
         let mut forwarded_chunks: i64 = 0;
@@ -239,8 +310,10 @@ where
     ctx: StreamFinalizeCtx,
     first_byte_ms: Option<u128>,
     buffer: Vec<u8>,
+    reservation: BufferReservation,
     max_bytes: usize,
     truncated: bool,
+    over_budget: bool,
     total_timeout: Option<Duration>,
     total_sleep: Option<Pin<Box<tokio::time::Sleep>>>,
     finalized: bool,
@@ -263,14 +336,21 @@ where
             ctx,
             first_byte_ms: None,
             buffer: Vec::new(),
+            reservation: BufferReservation::empty(),
             max_bytes,
             truncated: false,
+            over_budget: false,
             total_timeout,
             total_sleep: remaining.map(|d| Box::pin(tokio::time::sleep(d))),
             finalized: false,
         }
     }
 
+    fn drop_buffer(&mut self) {
+        self.buffer = Vec::new();
+        self.reservation.release();
+    }
+
     fn finalize(&mut self, error_code: Option<&'static str>) {
         if self.finalized {
             return;
@@ -290,6 +370,19 @@ where
                 usage::parse_model_from_json_bytes(&self.buffer)
             }
         });
+        self.drop_buffer();
+
+        if self.over_budget {
+            if let Ok(mut guard) = self.ctx.special_settings.lock() {
+                guard.push(serde_json::json!({
+                    "type": "body_buffer_budget",
+                    "scope": "response",
+                    "hit": true,
+                    "action": "timing_only",
+                    "budgetBytes": body_budget::GLOBAL_BODY_BUFFER_BUDGET_BYTES,
+                }));
+            }
+        }
 
         emit_request_event_and_spawn_request_log(
             &self.ctx,
@@ -340,11 +433,16 @@ where
                 }
                 if !this.truncated {
                     let bytes = chunk.as_ref();
-                    if this.buffer.len().saturating_add(bytes.len()) <= this.max_bytes {
-                        this.buffer.extend_from_slice(bytes);
-                    } else {
+                    if this.buffer.len().saturating_add(bytes.len()) > this.max_bytes {
+                        this.truncated = true;
+                        this.drop_buffer();
+                    } else if !this.reservation.try_grow(bytes.len()) {
+                        // 全局缓存预算耗尽：继续转发，但放弃解析 usage
                         this.truncated = true;
-                        this.buffer.clear();
+                        this.over_budget = true;
+                        this.drop_buffer();
+                    } else {
+                        this.buffer.extend_from_slice(bytes);
                     }
                 }
                 Poll::Ready(Some(Ok(chunk)))
@@ -0,0 +1,92 @@
+//! Usage: HTTPS accept loop for the gateway (rustls + hyper), used when `gateway_tls_enabled` is on.
+//!
+//! Shuts down like `axum::serve(..).with_graceful_shutdown(..)`: stop accepting, ask every open
+//! connection to finish its in-flight requests, and return once they have all closed.
+
+use axum::{extract::ConnectInfo, Router};
+use hyper::body::Incoming;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio_rustls::TlsAcceptor;
+use tower_service::Service;
+
+pub(super) async fn serve_tls(
+    listener: tokio::net::TcpListener,
+    config: Arc<rustls::ServerConfig>,
+    router: Router,
+    shutdown: impl std::future::Future<Output = ()>,
+) -> std::io::Result<()> {
+    let acceptor = TlsAcceptor::from(config);
+    tokio::pin!(shutdown);
+
+    // `signal_tx` tells open connections to shut down; every connection task holds a `close_rx`
+    // clone, so `close_tx.closed()` resolves once they have all finished.
+    let (signal_tx, signal_rx) = watch::channel(false);
+    let (close_tx, close_rx) = watch::channel(());
+
+    loop {
+        let (stream, peer) = tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => match accepted {
+                Ok(v) => v,
+                Err(err) => {
+                    tracing::warn!("网关 HTTPS 连接接受失败: {}", err);
+                    continue;
+                }
+            },
+        };
+
+        let acceptor = acceptor.clone();
+        let router = router.clone();
+        let mut signal_rx = signal_rx.clone();
+        let close_rx = close_rx.clone();
+        tokio::spawn(async move {
+            let _close_rx = close_rx;
+            let stream = tokio::select! {
+                accepted = acceptor.accept(stream) => match accepted {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        // Usually a client that does not trust the local CA yet.
+                        tracing::debug!(peer = %peer, "网关 TLS 握手失败: {}", err);
+                        return;
+                    }
+                },
+                // No request has been read yet: drop the half-open connection.
+                _ = signal_rx.changed() => return,
+            };
+
+            let service = hyper::service::service_fn(move |mut req: hyper::Request<Incoming>| {
+                // Same extension `into_make_service_with_connect_info` provides for plain HTTP.
+                req.extensions_mut().insert(ConnectInfo(peer));
+                router.clone().call(req)
+            });
+
+            let builder = hyper_util::server::conn::auto::Builder::new(TokioExecutor::new());
+            let conn = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+            tokio::pin!(conn);
+
+            let mut shutting_down = false;
+            loop {
+                tokio::select! {
+                    result = conn.as_mut() => {
+                        if let Err(err) = result {
+                            tracing::debug!(peer = %peer, "网关 HTTPS 连接结束: {}", err);
+                        }
+                        break;
+                    }
+                    _ = signal_rx.changed(), if !shutting_down => {
+                        shutting_down = true;
+                        conn.as_mut().graceful_shutdown();
+                    }
+                }
+            }
+        });
+    }
+
+    drop(listener);
+    let _ = signal_tx.send(true);
+    drop(close_rx);
+    close_tx.closed().await;
+    Ok(())
+}
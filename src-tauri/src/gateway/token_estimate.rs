@@ -0,0 +1,13 @@
+//! Usage: Gateway-side prompt token estimate, recorded on every request log.
+//!
+//! 用法：
+//! - 代理处理器解析请求体后调用 `estimate(body, requested_model)`，用本地 `tokenizer` 按模型族估算输入 token 数
+//! - 结果记入请求的 `RequestLogAttribution`，落库到 `request_logs.estimated_input_tokens`
+//! - 上游未返回 usage（或流被中断）时，用量展示可回退到该估算值
+
+use crate::tokenizer;
+
+pub(super) fn estimate(body: &serde_json::Value, requested_model: Option<&str>) -> i64 {
+    let family = tokenizer::ModelFamily::from_model(requested_model);
+    i64::try_from(tokenizer::estimate_request(body, family)).unwrap_or(i64::MAX)
+}
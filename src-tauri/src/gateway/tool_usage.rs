@@ -0,0 +1,220 @@
+//! Usage: Extract tool calls from request bodies for tool-usage analytics.
+//!
+//! Clients resend the whole conversation on every turn, so only the tool calls whose results are
+//! carried by the trailing message(s) are reported (each call is seen exactly once, when its result
+//! is sent back). Storage additionally dedups by call id.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ToolCall {
+    pub(super) call_id: String,
+    pub(super) tool_name: String,
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+}
+
+fn resolve(result_ids: Vec<&str>, names: &HashMap<&str, &str>) -> Vec<ToolCall> {
+    result_ids
+        .into_iter()
+        .filter_map(|id| {
+            names.get(id).map(|name| ToolCall {
+                call_id: id.to_string(),
+                tool_name: name.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Anthropic Messages: `tool_result` blocks in the last user message, named by earlier `tool_use` blocks.
+fn from_anthropic_messages(messages: &[Value]) -> Vec<ToolCall> {
+    let Some(last) = messages.last() else {
+        return Vec::new();
+    };
+    if str_field(last, "role") != Some("user") {
+        return Vec::new();
+    }
+    let Some(blocks) = last.get("content").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    let result_ids: Vec<&str> = blocks
+        .iter()
+        .filter(|block| str_field(block, "type") == Some("tool_result"))
+        .filter_map(|block| str_field(block, "tool_use_id"))
+        .collect();
+    if result_ids.is_empty() {
+        return Vec::new();
+    }
+
+    // 结果对应的调用位于最近一条 assistant 消息中
+    let mut names = HashMap::new();
+    let previous_assistant = messages
+        .iter()
+        .rev()
+        .skip(1)
+        .find(|m| str_field(m, "role") == Some("assistant"));
+    for block in previous_assistant
+        .and_then(|m| m.get("content"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        if str_field(block, "type") == Some("tool_use") {
+            if let (Some(id), Some(name)) = (str_field(block, "id"), str_field(block, "name")) {
+                names.insert(id, name);
+            }
+        }
+    }
+    resolve(result_ids, &names)
+}
+
+/// OpenAI Chat Completions: trailing `role=tool` messages, named by assistant `tool_calls`.
+fn from_chat_messages(messages: &[Value]) -> Vec<ToolCall> {
+    let result_ids: Vec<&str> = messages
+        .iter()
+        .rev()
+        .take_while(|m| str_field(m, "role") == Some("tool"))
+        .filter_map(|m| str_field(m, "tool_call_id"))
+        .collect();
+    if result_ids.is_empty() {
+        return Vec::new();
+    }
+
+    let mut names = HashMap::new();
+    for message in messages {
+        for call in message
+            .get("tool_calls")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let name = call.get("function").and_then(|f| str_field(f, "name"));
+            if let (Some(id), Some(name)) = (str_field(call, "id"), name) {
+                names.insert(id, name);
+            }
+        }
+    }
+    let mut calls = resolve(result_ids, &names);
+    calls.reverse();
+    calls
+}
+
+/// OpenAI Responses (Codex): trailing `*_call_output` items, named by `function_call` / `custom_tool_call`.
+fn from_responses_input(items: &[Value]) -> Vec<ToolCall> {
+    let result_ids: Vec<&str> = items
+        .iter()
+        .rev()
+        .take_while(|item| str_field(item, "type").is_some_and(|t| t.ends_with("_call_output")))
+        .filter_map(|item| str_field(item, "call_id"))
+        .collect();
+    if result_ids.is_empty() {
+        return Vec::new();
+    }
+
+    let mut names = HashMap::new();
+    for item in items {
+        if matches!(
+            str_field(item, "type"),
+            Some("function_call") | Some("custom_tool_call")
+        ) {
+            if let (Some(id), Some(name)) = (str_field(item, "call_id"), str_field(item, "name")) {
+                names.insert(id, name);
+            }
+        }
+    }
+    let mut calls = resolve(result_ids, &names);
+    calls.reverse();
+    calls
+}
+
+pub(super) fn extract_completed_tool_calls(body: &Value) -> Vec<ToolCall> {
+    if let Some(items) = body.get("input").and_then(Value::as_array) {
+        return from_responses_input(items);
+    }
+    let Some(messages) = body.get("messages").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    let last_role = messages.last().and_then(|m| str_field(m, "role"));
+    if last_role == Some("tool") {
+        from_chat_messages(messages)
+    } else {
+        from_anthropic_messages(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(calls: &[ToolCall]) -> Vec<&str> {
+        calls.iter().map(|c| c.tool_name.as_str()).collect()
+    }
+
+    #[test]
+    fn anthropic_counts_only_results_in_last_user_message() {
+        let body = serde_json::json!({
+            "messages": [
+                {"role": "assistant", "content": [{"type": "tool_use", "id": "old", "name": "Read"}]},
+                {"role": "user", "content": [{"type": "tool_result", "tool_use_id": "old"}]},
+                {"role": "assistant", "content": [
+                    {"type": "text", "text": "running"},
+                    {"type": "tool_use", "id": "a", "name": "Bash"},
+                    {"type": "tool_use", "id": "b", "name": "mcp__github__create_issue"},
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "a"},
+                    {"type": "tool_result", "tool_use_id": "b"},
+                ]},
+            ],
+        });
+        assert_eq!(
+            names(&extract_completed_tool_calls(&body)),
+            ["Bash", "mcp__github__create_issue"]
+        );
+
+        let plain = serde_json::json!({"messages": [{"role": "user", "content": "hi"}]});
+        assert!(extract_completed_tool_calls(&plain).is_empty());
+    }
+
+    #[test]
+    fn responses_and_chat_use_trailing_outputs() {
+        let responses = serde_json::json!({
+            "input": [
+                {"type": "function_call", "call_id": "c1", "name": "shell"},
+                {"type": "function_call_output", "call_id": "c1"},
+                {"type": "message", "role": "assistant"},
+                {"type": "custom_tool_call", "call_id": "c2", "name": "apply_patch"},
+                {"type": "custom_tool_call_output", "call_id": "c2"},
+            ],
+        });
+        let calls = extract_completed_tool_calls(&responses);
+        assert_eq!(
+            calls,
+            [ToolCall {
+                call_id: "c2".to_string(),
+                tool_name: "apply_patch".to_string()
+            }]
+        );
+
+        let chat = serde_json::json!({
+            "messages": [
+                {"role": "assistant", "tool_calls": [
+                    {"id": "t1", "function": {"name": "read_file"}},
+                    {"id": "t2", "function": {"name": "write_file"}},
+                ]},
+                {"role": "tool", "tool_call_id": "t1"},
+                {"role": "tool", "tool_call_id": "t2"},
+            ],
+        });
+        assert_eq!(
+            names(&extract_completed_tool_calls(&chat)),
+            ["read_file", "write_file"]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Usage: Client trace id passthrough (`x-client-trace-id`) and hub trace id forwarding.
+//!
+//! 用法：
+//! - 代理处理器用 `client_trace_id` 读取客户端传入的 trace id，记入请求的 `RequestLogAttribution`，
+//!   随请求落库到 `request_logs.client_trace_id`
+//! - `settings.forward_trace_headers` 开启时，`apply_upstream` 向上游请求写入 `x-aio-trace-id`（网关 trace_id）
+//!   与 `x-client-trace-id`（若客户端提供）
+
+use axum::http::{HeaderMap, HeaderValue};
+
+const CLIENT_TRACE_ID_HEADER: &str = "x-client-trace-id";
+const HUB_TRACE_ID_HEADER: &str = "x-aio-trace-id";
+
+const MAX_CLIENT_TRACE_ID_LEN: usize = 128;
+
+/// Reads `x-client-trace-id`; ignores empty, oversized or non-printable-ASCII values.
+pub(super) fn client_trace_id(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(CLIENT_TRACE_ID_HEADER)?.to_str().ok()?.trim();
+    if value.is_empty()
+        || value.len() > MAX_CLIENT_TRACE_ID_LEN
+        || !value.bytes().all(|b| b.is_ascii_graphic())
+    {
+        return None;
+    }
+    Some(value.to_string())
+}
+
+/// Sets the hub trace id (and the normalized client trace id) on the upstream request headers.
+pub(super) fn apply_upstream(
+    headers: &mut HeaderMap,
+    trace_id: &str,
+    client_trace_id: Option<&str>,
+) {
+    if let Ok(v) = HeaderValue::from_str(trace_id) {
+        headers.insert(HUB_TRACE_ID_HEADER, v);
+    }
+    match client_trace_id.and_then(|id| HeaderValue::from_str(id).ok()) {
+        Some(v) => {
+            headers.insert(CLIENT_TRACE_ID_HEADER, v);
+        }
+        None => {
+            headers.remove(CLIENT_TRACE_ID_HEADER);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_upstream, client_trace_id, CLIENT_TRACE_ID_HEADER, HUB_TRACE_ID_HEADER};
+    use axum::http::{HeaderMap, HeaderValue};
+
+    fn headers_with(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CLIENT_TRACE_ID_HEADER,
+            HeaderValue::from_str(value).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn client_trace_id_is_trimmed_and_validated() {
+        assert_eq!(
+            client_trace_id(&headers_with("  span-42 ")).as_deref(),
+            Some("span-42")
+        );
+        assert_eq!(client_trace_id(&headers_with("a b")), None);
+        assert_eq!(client_trace_id(&headers_with(&"x".repeat(129))), None);
+        assert_eq!(client_trace_id(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn apply_upstream_sets_both_headers() {
+        let mut headers = headers_with(" span-42 ");
+        apply_upstream(&mut headers, "trace-1", Some("span-42"));
+        assert_eq!(headers.get(HUB_TRACE_ID_HEADER).unwrap(), "trace-1");
+        assert_eq!(headers.get(CLIENT_TRACE_ID_HEADER).unwrap(), "span-42");
+
+        let mut headers = headers_with("a b");
+        apply_upstream(&mut headers, "trace-2", None);
+        assert_eq!(headers.get(HUB_TRACE_ID_HEADER).unwrap(), "trace-2");
+        assert!(headers.get(CLIENT_TRACE_ID_HEADER).is_none());
+    }
+}
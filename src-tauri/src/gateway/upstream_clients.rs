@@ -0,0 +1,405 @@
+//! Usage: Outbound HTTP clients for upstream providers (global egress settings + provider `upstream_proxy`).
+//!
+//! 用法：
+//! - 全局出站网络（设置 `egress_network`）：代理、域名 hosts 覆盖与 IPv4 / IPv6 强制，作用于所有上游请求；
+//!   设置变更后在下一次取客户端时重建，无需重启网关
+//! - Provider `upstream_proxy`：
+//!   - `system`（默认）：跟随全局出站代理（全局为 `system` 时即系统代理环境变量 HTTP(S)_PROXY / ALL_PROXY）；
+//!     上游为 127.0.0.1 / localhost 等回环地址时直连，避免“加速器”类 VPN 接管本地中转
+//!   - `none`：始终直连，忽略任何代理
+//!   - `http(s)://…` / `socks5(h)://…`：经指定代理转发；同一代理 URL 复用同一个 `reqwest::Client`（连接池共享）
+//! - hosts 覆盖与 IP 族限制在本地解析时生效；经 HTTP / socks5h 代理时目标域名由代理解析
+//! - Provider `upstream_tls`（自定义根 CA / mTLS 客户端证书）：按 出站路径 + 证书材料 单独构建并缓存客户端；
+//!   构建失败时记录告警并回退到不带证书的客户端
+
+use crate::providers::{UpstreamTls, UPSTREAM_PROXY_NONE, UPSTREAM_PROXY_SYSTEM};
+use crate::settings::{
+    EgressIpFamily, EgressNetworkConfig, EGRESS_PROXY_NONE, EGRESS_PROXY_SYSTEM,
+};
+use crate::shared::mutex_ext::MutexExt;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+
+/// Local resolver applying the egress host overrides and IP family restriction.
+#[derive(Debug, Clone)]
+pub(super) struct EgressResolver {
+    overrides: Arc<HashMap<String, Vec<IpAddr>>>,
+    ip_family: EgressIpFamily,
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+fn filter_family(ips: impl IntoIterator<Item = IpAddr>, family: EgressIpFamily) -> Vec<IpAddr> {
+    ips.into_iter()
+        .filter(|ip| match family {
+            EgressIpFamily::Auto => true,
+            EgressIpFamily::Ipv4 => ip.is_ipv4(),
+            EgressIpFamily::Ipv6 => ip.is_ipv6(),
+        })
+        .collect()
+}
+
+impl EgressResolver {
+    pub(super) fn new(network: &EgressNetworkConfig) -> Self {
+        let overrides = network
+            .host_overrides
+            .iter()
+            .map(|item| {
+                let ips = item
+                    .ips
+                    .iter()
+                    .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+                    .collect::<Vec<_>>();
+                (normalize_host(&item.host), ips)
+            })
+            .filter(|(host, ips)| !host.is_empty() && !ips.is_empty())
+            .collect();
+        Self {
+            overrides: Arc::new(overrides),
+            ip_family: network.ip_family,
+        }
+    }
+
+    fn is_noop(&self) -> bool {
+        self.overrides.is_empty() && self.ip_family == EgressIpFamily::Auto
+    }
+
+    /// Resolves `host` (override first, then the system resolver) within the configured IP family.
+    pub(super) async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>, String> {
+        let host = normalize_host(host);
+        let candidates = match self.overrides.get(&host) {
+            Some(ips) => ips.clone(),
+            None => tokio::net::lookup_host((host.as_str(), 0))
+                .await
+                .map_err(|e| format!("failed to resolve {host}: {e}"))?
+                .map(|addr| addr.ip())
+                .collect(),
+        };
+        let ips = filter_family(candidates, self.ip_family);
+        if ips.is_empty() {
+            return Err(format!(
+                "no address of the required IP family ({:?}) for {host}",
+                self.ip_family
+            ));
+        }
+        Ok(ips)
+    }
+}
+
+impl Resolve for EgressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let ips = resolver.lookup(name.as_str()).await?;
+            let addrs: Addrs = Box::new(ips.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(addrs)
+        })
+    }
+}
+
+fn client_builder(network: &EgressNetworkConfig) -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder().user_agent(format!(
+        "aio-coding-hub-gateway/{}",
+        env!("CARGO_PKG_VERSION")
+    ));
+    let resolver = EgressResolver::new(network);
+    if resolver.is_noop() {
+        builder
+    } else {
+        builder.dns_resolver(Arc::new(resolver))
+    }
+}
+
+fn proxied_client(
+    network: &EgressNetworkConfig,
+    proxy_url: &str,
+) -> reqwest::Result<reqwest::Client> {
+    // an explicit proxy replaces the system proxy environment variables
+    let proxy = reqwest::Proxy::all(proxy_url)?;
+    client_builder(network).proxy(proxy).build()
+}
+
+fn init_error(err: reqwest::Error) -> String {
+    format!("GW_HTTP_CLIENT_INIT: {err}")
+}
+
+fn is_loopback_target(base_url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(base_url) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// How a request for a provider (`upstream_proxy`) to `base_url` leaves the machine.
+pub(super) fn egress_route(
+    network: &EgressNetworkConfig,
+    upstream_proxy: &str,
+    base_url: &str,
+) -> &'static str {
+    match upstream_proxy.trim() {
+        UPSTREAM_PROXY_NONE => "direct",
+        "" | UPSTREAM_PROXY_SYSTEM if is_loopback_target(base_url) => "direct",
+        "" | UPSTREAM_PROXY_SYSTEM => match network.proxy.trim() {
+            "" | EGRESS_PROXY_SYSTEM => "system",
+            EGRESS_PROXY_NONE => "direct",
+            _ => "proxy",
+        },
+        _ => "proxy",
+    }
+}
+
+/// Resolved outbound path of a provider.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Route {
+    Direct,
+    // Follows the global egress proxy.
+    Inherited,
+    Proxy(String),
+}
+
+fn provider_route(upstream_proxy: &str, base_url: &str) -> Route {
+    match upstream_proxy.trim() {
+        UPSTREAM_PROXY_NONE => Route::Direct,
+        "" | UPSTREAM_PROXY_SYSTEM if is_loopback_target(base_url) => Route::Direct,
+        "" | UPSTREAM_PROXY_SYSTEM => Route::Inherited,
+        proxy_url => Route::Proxy(proxy_url.to_string()),
+    }
+}
+
+/// Clients built for one egress configuration.
+struct ClientSet {
+    network: EgressNetworkConfig,
+    // Used by providers that follow the global egress proxy.
+    inherited: reqwest::Client,
+    direct: reqwest::Client,
+    by_proxy: Mutex<HashMap<String, reqwest::Client>>,
+    // Providers with custom TLS material get their own client per route.
+    by_tls: Mutex<HashMap<(Route, UpstreamTls), reqwest::Client>>,
+}
+
+impl ClientSet {
+    fn build(network: &EgressNetworkConfig) -> Result<Self, String> {
+        let direct = client_builder(network)
+            .no_proxy()
+            .build()
+            .map_err(init_error)?;
+        let inherited = match network.proxy.trim() {
+            "" | EGRESS_PROXY_SYSTEM => client_builder(network).build().map_err(init_error)?,
+            EGRESS_PROXY_NONE => direct.clone(),
+            proxy_url => match proxied_client(network, proxy_url) {
+                Ok(client) => client,
+                Err(err) => {
+                    // the proxy URL may carry credentials: log the error only
+                    tracing::warn!("全局出站代理初始化失败，回退到系统代理: {}", err);
+                    client_builder(network).build().map_err(init_error)?
+                }
+            },
+        };
+        Ok(Self {
+            network: network.clone(),
+            inherited,
+            direct,
+            by_proxy: Mutex::new(HashMap::new()),
+            by_tls: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn for_route(&self, route: &Route) -> reqwest::Client {
+        match route {
+            Route::Direct => self.direct.clone(),
+            Route::Inherited => self.inherited.clone(),
+            Route::Proxy(proxy_url) => self.proxied(proxy_url),
+        }
+    }
+
+    fn for_provider(
+        &self,
+        upstream_proxy: &str,
+        base_url: &str,
+        tls: &UpstreamTls,
+    ) -> reqwest::Client {
+        let route = provider_route(upstream_proxy, base_url);
+        if !tls.is_configured() {
+            return self.for_route(&route);
+        }
+
+        let key = (route, tls.clone());
+        let mut cache = self.by_tls.lock_or_recover();
+        if let Some(client) = cache.get(&key) {
+            return client.clone();
+        }
+        let client = match self.build_with_tls(&key.0, tls) {
+            Ok(client) => client,
+            Err(err) => {
+                tracing::warn!("上游 TLS 证书客户端初始化失败，回退到默认证书: {}", err);
+                self.for_route(&key.0)
+            }
+        };
+        cache.insert(key, client.clone());
+        client
+    }
+
+    fn build_with_tls(&self, route: &Route, tls: &UpstreamTls) -> reqwest::Result<reqwest::Client> {
+        let builder = client_builder(&self.network);
+        let builder = match route {
+            Route::Direct => builder.no_proxy(),
+            Route::Inherited => match self.network.proxy.trim() {
+                "" | EGRESS_PROXY_SYSTEM => builder,
+                EGRESS_PROXY_NONE => builder.no_proxy(),
+                proxy_url => builder.proxy(reqwest::Proxy::all(proxy_url)?),
+            },
+            Route::Proxy(proxy_url) => builder.proxy(reqwest::Proxy::all(proxy_url.as_str())?),
+        };
+        tls.apply_to(builder)?.build()
+    }
+
+    fn proxied(&self, proxy_url: &str) -> reqwest::Client {
+        let mut cache = self.by_proxy.lock_or_recover();
+        if let Some(client) = cache.get(proxy_url) {
+            return client.clone();
+        }
+        let client = match proxied_client(&self.network, proxy_url) {
+            Ok(client) => client,
+            Err(err) => {
+                // the proxy URL may carry credentials: log the error only
+                tracing::warn!("上游代理客户端初始化失败，回退到全局出站代理: {}", err);
+                self.inherited.clone()
+            }
+        };
+        cache.insert(proxy_url.to_string(), client.clone());
+        client
+    }
+}
+
+pub(super) struct UpstreamClients {
+    current: Mutex<Arc<ClientSet>>,
+}
+
+impl UpstreamClients {
+    pub(super) fn new(network: &EgressNetworkConfig) -> Result<Self, String> {
+        Ok(Self {
+            current: Mutex::new(Arc::new(ClientSet::build(network)?)),
+        })
+    }
+
+    fn current(&self, network: &EgressNetworkConfig) -> Arc<ClientSet> {
+        let mut current = self.current.lock_or_recover();
+        if current.network != *network {
+            match ClientSet::build(network) {
+                Ok(next) => *current = Arc::new(next),
+                Err(err) => tracing::warn!("出站网络设置变更后重建客户端失败，沿用原配置: {}", err),
+            }
+        }
+        current.clone()
+    }
+
+    /// Client for sending requests of a provider (`upstream_proxy`, `upstream_tls`) to `base_url`.
+    pub(super) fn for_provider(
+        &self,
+        network: &EgressNetworkConfig,
+        upstream_proxy: &str,
+        base_url: &str,
+        tls: &UpstreamTls,
+    ) -> reqwest::Client {
+        self.current(network)
+            .for_provider(upstream_proxy, base_url, tls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::EgressHostOverride;
+
+    #[test]
+    fn loopback_targets_are_detected() {
+        assert!(is_loopback_target("http://127.0.0.1:8080/v1"));
+        assert!(is_loopback_target("http://localhost:3000"));
+        assert!(is_loopback_target("http://[::1]:8080"));
+        assert!(!is_loopback_target("https://api.openai.com/v1"));
+        assert!(!is_loopback_target("http://192.168.1.10:8080"));
+        assert!(!is_loopback_target("not a url"));
+    }
+
+    #[test]
+    fn provider_route_matches_proxy_modes() {
+        assert_eq!(provider_route("none", "https://api.x.ai"), Route::Direct);
+        assert_eq!(
+            provider_route("system", "http://localhost:8080"),
+            Route::Direct
+        );
+        assert_eq!(provider_route("", "https://api.x.ai"), Route::Inherited);
+        assert_eq!(
+            provider_route(" socks5h://127.0.0.1:1080 ", "https://api.x.ai"),
+            Route::Proxy("socks5h://127.0.0.1:1080".to_string())
+        );
+    }
+
+    #[test]
+    fn egress_route_follows_global_proxy_for_system_providers() {
+        let mut network = EgressNetworkConfig::default();
+        assert_eq!(
+            egress_route(&network, "system", "https://api.x.ai"),
+            "system"
+        );
+        assert_eq!(egress_route(&network, "none", "https://api.x.ai"), "direct");
+
+        network.proxy = "socks5h://127.0.0.1:1080".to_string();
+        assert_eq!(
+            egress_route(&network, "system", "https://api.x.ai"),
+            "proxy"
+        );
+        assert_eq!(
+            egress_route(&network, "system", "http://127.0.0.1:8080"),
+            "direct"
+        );
+        network.proxy = "none".to_string();
+        assert_eq!(
+            egress_route(&network, "system", "https://api.x.ai"),
+            "direct"
+        );
+        assert_eq!(
+            egress_route(&network, "http://proxy.lan:3128", "https://api.x.ai"),
+            "proxy"
+        );
+    }
+
+    #[tokio::test]
+    async fn resolver_applies_overrides_and_ip_family() {
+        let network = EgressNetworkConfig {
+            host_overrides: vec![EgressHostOverride {
+                host: " API.Example.com. ".to_string(),
+                ips: vec!["203.0.113.7".to_string(), "2001:db8::7".to_string()],
+            }],
+            ip_family: EgressIpFamily::Ipv6,
+            ..EgressNetworkConfig::default()
+        };
+        let resolver = EgressResolver::new(&network);
+        assert!(!resolver.is_noop());
+
+        let ips = resolver.lookup("api.example.com").await.unwrap();
+        assert_eq!(ips, vec!["2001:db8::7".parse::<IpAddr>().unwrap()]);
+
+        let v4_only = EgressResolver::new(&EgressNetworkConfig {
+            ip_family: EgressIpFamily::Ipv4,
+            ..network
+        });
+        let ips = v4_only.lookup("API.example.com").await.unwrap();
+        assert_eq!(ips, vec!["203.0.113.7".parse::<IpAddr>().unwrap()]);
+
+        assert!(EgressResolver::new(&EgressNetworkConfig::default()).is_noop());
+    }
+}
@@ -1,4 +1,5 @@
-use axum::http::{header, HeaderMap, HeaderValue};
+use crate::providers::{ProviderHeaderAction, ProviderHeaderRule, HEADER_RULE_API_KEY_PLACEHOLDER};
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue};
 use std::borrow::Cow;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -150,7 +151,7 @@ pub(super) fn body_for_introspection<'a>(
     }
 }
 
-fn url_decode_component(input: &str) -> String {
+pub(super) fn url_decode_component(input: &str) -> String {
     let bytes = input.as_bytes();
     let mut out: Vec<u8> = Vec::with_capacity(input.len());
     let mut i = 0usize;
@@ -366,7 +367,13 @@ pub(super) fn build_target_url(
     Ok(url)
 }
 
-pub(super) fn inject_provider_auth(cli_key: &str, api_key: &str, headers: &mut HeaderMap) {
+/// Sets the provider's auth headers, then applies its `header_rules` (which may override / remove them).
+pub(super) fn inject_provider_auth(
+    cli_key: &str,
+    api_key: &str,
+    header_rules: &[ProviderHeaderRule],
+    headers: &mut HeaderMap,
+) {
     headers.remove(header::AUTHORIZATION);
     headers.remove("x-api-key");
     headers.remove("x-goog-api-key");
@@ -424,6 +431,34 @@ pub(super) fn inject_provider_auth(cli_key: &str, api_key: &str, headers: &mut H
         }
         _ => {}
     }
+
+    apply_provider_header_rules(header_rules, api_key, headers);
+}
+
+fn apply_provider_header_rules(
+    rules: &[ProviderHeaderRule],
+    api_key: &str,
+    headers: &mut HeaderMap,
+) {
+    for rule in rules {
+        let Ok(name) = HeaderName::from_bytes(rule.name.as_bytes()) else {
+            continue;
+        };
+        match rule.action {
+            ProviderHeaderAction::Remove => {
+                headers.remove(&name);
+            }
+            ProviderHeaderAction::Add if headers.contains_key(&name) => {}
+            ProviderHeaderAction::Add | ProviderHeaderAction::Override => {
+                let value = rule
+                    .value
+                    .replace(HEADER_RULE_API_KEY_PLACEHOLDER, api_key.trim());
+                if let Ok(value) = HeaderValue::from_str(&value) {
+                    headers.insert(name, value);
+                }
+            }
+        }
+    }
 }
 
 pub(super) fn ensure_cli_required_headers(cli_key: &str, headers: &mut HeaderMap) {
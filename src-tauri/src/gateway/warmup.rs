@@ -1,56 +1,65 @@
-use serde_json::json;
+use axum::body::Bytes;
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use std::sync::OnceLock;
 
-pub(super) fn is_anthropic_warmup_request(forwarded_path: &str, body_bytes: &[u8]) -> bool {
-    if forwarded_path != "/v1/messages" {
-        return false;
-    }
-
-    let Ok(root) = serde_json::from_slice::<serde_json::Value>(body_bytes) else {
-        return false;
-    };
-
-    let Some(messages) = root.get("messages").and_then(|v| v.as_array()) else {
-        return false;
-    };
-    if messages.len() != 1 {
-        return false;
-    }
+use super::intercept::{self, InterceptedResponse};
+use crate::settings::{InterceptBodyPredicate, InterceptPredicateOp, InterceptRule};
 
-    let Some(first_message) = messages.first().and_then(|v| v.as_object()) else {
-        return false;
-    };
-    if first_message.get("role").and_then(|v| v.as_str()) != Some("user") {
-        return false;
-    }
-
-    let Some(content) = first_message.get("content").and_then(|v| v.as_array()) else {
-        return false;
-    };
-    if content.len() != 1 {
-        return false;
+fn predicate(pointer: &str, op: InterceptPredicateOp, value: Value) -> InterceptBodyPredicate {
+    InterceptBodyPredicate {
+        pointer: pointer.to_string(),
+        op,
+        value,
     }
+}
 
-    let Some(first_block) = content.first().and_then(|v| v.as_object()) else {
-        return false;
-    };
-    if first_block.get("type").and_then(|v| v.as_str()) != Some("text") {
-        return false;
-    }
+/// Built-in rule: a single user text block "warmup" marked with ephemeral cache_control.
+fn anthropic_warmup_rule() -> &'static InterceptRule {
+    static RULE: OnceLock<InterceptRule> = OnceLock::new();
+    RULE.get_or_init(|| InterceptRule {
+        id: "builtin_anthropic_warmup".to_string(),
+        name: "Anthropic Warmup".to_string(),
+        cli_key: "claude".to_string(),
+        path: "/v1/messages".to_string(),
+        body_predicates: vec![
+            predicate("/messages", InterceptPredicateOp::LenEq, json!(1)),
+            predicate(
+                "/messages/0/role",
+                InterceptPredicateOp::Equals,
+                json!("user"),
+            ),
+            predicate("/messages/0/content", InterceptPredicateOp::LenEq, json!(1)),
+            predicate(
+                "/messages/0/content/0/type",
+                InterceptPredicateOp::Equals,
+                json!("text"),
+            ),
+            predicate(
+                "/messages/0/content/0/text",
+                InterceptPredicateOp::EqualsIgnoreCase,
+                json!("warmup"),
+            ),
+            predicate(
+                "/messages/0/content/0/cache_control/type",
+                InterceptPredicateOp::Equals,
+                json!("ephemeral"),
+            ),
+        ],
+        ..Default::default()
+    })
+}
 
-    let text = first_block
-        .get("text")
-        .and_then(|v| v.as_str())
-        .unwrap_or_default()
-        .trim()
-        .to_lowercase();
-    if text != "warmup" {
+pub(super) fn is_anthropic_warmup_request(forwarded_path: &str, body_bytes: &[u8]) -> bool {
+    let rule = anthropic_warmup_rule();
+    if !intercept::path_matches(&rule.path, forwarded_path) {
         return false;
     }
 
-    let Some(cache_control) = first_block.get("cache_control").and_then(|v| v.as_object()) else {
+    let Ok(root) = serde_json::from_slice::<Value>(body_bytes) else {
         return false;
     };
-    cache_control.get("type").and_then(|v| v.as_str()) == Some("ephemeral")
+    intercept::rule_matches(rule, &rule.cli_key, "POST", forwarded_path, Some(&root))
 }
 
 pub(super) fn build_warmup_response_body(model: Option<&str>, trace_id: &str) -> serde_json::Value {
@@ -76,5 +85,27 @@ pub(super) fn build_warmup_response_body(model: Option<&str>, trace_id: &str) ->
     })
 }
 
+pub(super) fn warmup_intercepted_response(
+    model: Option<&str>,
+    trace_id: &str,
+) -> InterceptedResponse {
+    let body = build_warmup_response_body(model, trace_id);
+    InterceptedResponse {
+        status: StatusCode::OK,
+        content_type: "application/json; charset=utf-8".to_string(),
+        body: Bytes::from(serde_json::to_vec(&body).unwrap_or_default()),
+        special_setting: json!({
+            "type": "warmup_intercept",
+            "scope": "request",
+            "hit": true,
+            "reason": "anthropic_warmup_intercepted",
+            "note": "已由 aio-coding-hub 抢答，未转发上游；写入日志但排除统计",
+        }),
+        tag: "warmup",
+        provider_name: "Warmup",
+        meta_url: "/__aio__/warmup",
+    }
+}
+
 #[cfg(test)]
 mod tests;
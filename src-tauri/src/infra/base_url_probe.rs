@@ -1,5 +1,9 @@
-//! Usage: Network probe helpers (HTTP HEAD/GET latency measurement).
+//! Usage: Network probe helpers (HTTP HEAD/GET latency measurement) and persisted probe history.
 
+use crate::db;
+use crate::shared::time::now_unix_seconds;
+use rusqlite::params;
+use serde::Serialize;
 use std::time::{Duration, Instant};
 
 pub(crate) async fn probe_base_url_ms(
@@ -31,3 +35,139 @@ pub(crate) async fn probe_base_url_ms(
 
     Ok(started.elapsed().as_millis() as u64)
 }
+
+/// Probe rows older than this are pruned on insert.
+const PROBE_HISTORY_RETENTION_SECS: i64 = 30 * 86400;
+const MAX_SERIES_BUCKETS: i64 = 2000;
+
+#[derive(Debug, Clone)]
+pub(crate) struct BaseUrlProbeRecord {
+    pub(crate) provider_id: Option<i64>,
+    pub(crate) base_url: String,
+    pub(crate) result: Result<u64, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct BaseUrlLatencyPoint {
+    pub(crate) base_url: String,
+    // Bucket start (unix seconds).
+    pub(crate) bucket_ts: i64,
+    pub(crate) samples: i64,
+    pub(crate) failures: i64,
+    pub(crate) avg_ms: Option<f64>,
+    pub(crate) min_ms: Option<i64>,
+    pub(crate) max_ms: Option<i64>,
+}
+
+/// Persists probe results; `source` is "gateway" (ping-mode selection) or "manual" (UI ping).
+pub(crate) fn record_results(
+    db: &db::Db,
+    source: &str,
+    records: &[BaseUrlProbeRecord],
+) -> Result<(), String> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    let now = now_unix_seconds();
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
+    {
+        let mut stmt = tx
+            .prepare_cached(
+                "INSERT INTO base_url_probe_history(created_at, provider_id, base_url, latency_ms, error, source) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )
+            .map_err(|e| format!("DB_ERROR: failed to prepare base_url_probe_history insert: {e}"))?;
+        for record in records {
+            let (latency_ms, error) = match &record.result {
+                Ok(ms) => (Some(*ms as i64), None),
+                Err(err) => (None, Some(err.as_str())),
+            };
+            stmt.execute(params![
+                now,
+                record.provider_id,
+                record.base_url,
+                latency_ms,
+                error,
+                source
+            ])
+            .map_err(|e| format!("DB_ERROR: failed to insert base_url_probe_history: {e}"))?;
+        }
+    }
+    tx.execute(
+        "DELETE FROM base_url_probe_history WHERE created_at < ?1",
+        params![now.saturating_sub(PROBE_HISTORY_RETENTION_SECS)],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to prune base_url_probe_history: {e}"))?;
+    tx.commit()
+        .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+    Ok(())
+}
+
+/// Latency-over-time series bucketed by `bucket_seconds`, one series per base_url.
+pub(crate) fn latency_series(
+    db: &db::Db,
+    provider_id: Option<i64>,
+    base_url: Option<&str>,
+    since_ts: i64,
+    bucket_seconds: i64,
+) -> Result<Vec<BaseUrlLatencyPoint>, String> {
+    if provider_id.is_none() && base_url.is_none() {
+        return Err("SEC_INVALID_INPUT: provider_id or base_url is required".to_string());
+    }
+    if bucket_seconds < 60 {
+        return Err("SEC_INVALID_INPUT: bucket_seconds must be >= 60".to_string());
+    }
+
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  base_url,
+  (created_at / ?4) * ?4 AS bucket_ts,
+  COUNT(*) AS samples,
+  SUM(CASE WHEN latency_ms IS NULL THEN 1 ELSE 0 END) AS failures,
+  AVG(latency_ms) AS avg_ms,
+  MIN(latency_ms) AS min_ms,
+  MAX(latency_ms) AS max_ms
+FROM base_url_probe_history
+WHERE created_at >= ?1
+  AND (?2 IS NULL OR provider_id = ?2)
+  AND (?3 IS NULL OR base_url = ?3)
+GROUP BY base_url, bucket_ts
+ORDER BY bucket_ts ASC, base_url ASC
+LIMIT ?5
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare base_url latency query: {e}"))?;
+    let rows = stmt
+        .query_map(
+            params![
+                since_ts,
+                provider_id,
+                base_url,
+                bucket_seconds,
+                MAX_SERIES_BUCKETS
+            ],
+            |row| {
+                Ok(BaseUrlLatencyPoint {
+                    base_url: row.get("base_url")?,
+                    bucket_ts: row.get("bucket_ts")?,
+                    samples: row.get("samples")?,
+                    failures: row.get("failures")?,
+                    avg_ms: row.get("avg_ms")?,
+                    min_ms: row.get("min_ms")?,
+                    max_ms: row.get("max_ms")?,
+                })
+            },
+        )
+        .map_err(|e| format!("DB_ERROR: failed to query base_url latency: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("DB_ERROR: failed to read base_url latency row: {e}"))?);
+    }
+    Ok(items)
+}
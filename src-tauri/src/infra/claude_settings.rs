@@ -1,5 +1,12 @@
 //! Usage: Read / patch Claude Code global `settings.json` (~/.claude/settings.json).
 
+mod hooks;
+
+pub use hooks::{
+    claude_hook_create, claude_hook_set_enabled, claude_hooks_get, ClaudeHookInput,
+    ClaudeHooksState,
+};
+
 use crate::shared::fs::{read_optional_file, write_file_atomic_if_changed};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -0,0 +1,548 @@
+//! Usage: Manage Claude Code hooks (`hooks` in ~/.claude/settings.json).
+//!
+//! 用法：
+//! - `claude_hooks_get(app)`：列出 settings.json 中全部 command hook，以及内置模板是否已安装
+//! - `claude_hook_create(app, hook)`：校验后新增一条 PreToolUse / PostToolUse / Stop hook（已存在则报错）
+//! - `claude_hook_set_enabled(app, hook, enabled)`：启用 = 写入（幂等），停用 = 从 settings.json 移除；模板开关也走这里
+//! - 每次写入前把原 settings.json 备份到 `<app_data>/claude-hooks/backups/`（保留最近 `MAX_BACKUPS` 份）
+//! - settings.json 无法解析或 hooks 结构不是预期格式时拒绝写入，避免覆盖用户手写内容
+
+use super::{claude_settings_path, is_symlink, json_to_bytes};
+use crate::app_paths;
+use crate::shared::fs::{read_optional_file, write_file_atomic, write_file_atomic_if_changed};
+use crate::shared::time::now_unix_seconds;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const HOOK_EVENTS: &[&str] = &["PreToolUse", "PostToolUse", "Stop"];
+
+const MAX_COMMAND_CHARS: usize = 4096;
+const MAX_TIMEOUT_SECS: u64 = 3600;
+const MAX_BACKUPS: usize = 10;
+const BACKUP_DIR_NAME: &str = "claude-hooks";
+
+struct HookTemplate {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    event: &'static str,
+    matcher: Option<&'static str>,
+    command: &'static str,
+}
+
+// Commands read the hook payload (JSON) from stdin; exit code 2 blocks the tool call and feeds
+// stderr back to Claude.
+const TEMPLATES: &[HookTemplate] = &[
+    HookTemplate {
+        id: "block_dangerous_rm",
+        name: "拦截危险删除",
+        description: "拦截 rm -rf / 或 rm -rf ~ 之类的 Bash 命令",
+        event: "PreToolUse",
+        matcher: Some("Bash"),
+        command: r#"grep -Eq 'rm[[:space:]]+-[a-zA-Z]*[rf][a-zA-Z]*[[:space:]]+(/|~)([[:space:]"]|$)' && { echo '已拦截：危险的 rm -rf 命令' >&2; exit 2; } || exit 0"#,
+    },
+    HookTemplate {
+        id: "block_force_push",
+        name: "拦截强制推送",
+        description: "拦截 git push --force / -f（允许 --force-with-lease）",
+        event: "PreToolUse",
+        matcher: Some("Bash"),
+        command: r#"grep -Eq 'git[[:space:]]+push[^"]*(--force|[[:space:]]-f)([[:space:]"]|$)' && { echo '已拦截：禁止 git push --force' >&2; exit 2; } || exit 0"#,
+    },
+    HookTemplate {
+        id: "protect_env_files",
+        name: "保护 .env 文件",
+        description: "禁止 Edit / Write 修改 .env、.env.local 等文件",
+        event: "PreToolUse",
+        matcher: Some("Edit|MultiEdit|Write"),
+        command: r#"grep -Eq '"file_path"[[:space:]]*:[[:space:]]*"[^"]*\.env(\.[^"/]*)?"' && { echo '已拦截：禁止修改 .env 文件' >&2; exit 2; } || exit 0"#,
+    },
+    HookTemplate {
+        id: "stop_bell",
+        name: "完成提示音",
+        description: "Claude 结束回复时在终端响铃",
+        event: "Stop",
+        matcher: None,
+        command: r#"printf '\a' > /dev/tty 2>/dev/null || true"#,
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaudeHookInput {
+    pub event: String,
+    pub matcher: Option<String>,
+    pub command: String,
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeHookEntry {
+    pub event: String,
+    pub matcher: Option<String>,
+    pub command: String,
+    pub timeout_secs: Option<u64>,
+    pub template_id: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeHookTemplateState {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub event: &'static str,
+    pub matcher: Option<&'static str>,
+    pub command: &'static str,
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaudeHooksState {
+    pub settings_path: String,
+    pub backup_dir: String,
+    pub hooks: Vec<ClaudeHookEntry>,
+    pub templates: Vec<ClaudeHookTemplateState>,
+}
+
+fn template_id_for(event: &str, matcher: Option<&str>, command: &str) -> Option<&'static str> {
+    TEMPLATES
+        .iter()
+        .find(|t| t.event == event && t.matcher == matcher && t.command == command)
+        .map(|t| t.id)
+}
+
+fn group_matcher(group: &serde_json::Value) -> Option<String> {
+    group
+        .get("matcher")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+fn parse_hooks(root: &serde_json::Value) -> Vec<ClaudeHookEntry> {
+    let Some(events) = root.get("hooks").and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+
+    let mut out = Vec::new();
+    for (event, groups) in events {
+        let Some(groups) = groups.as_array() else {
+            continue;
+        };
+        for group in groups {
+            let matcher = group_matcher(group);
+            let Some(hooks) = group.get("hooks").and_then(|v| v.as_array()) else {
+                continue;
+            };
+            for hook in hooks {
+                if hook.get("type").and_then(|v| v.as_str()) != Some("command") {
+                    continue;
+                }
+                let Some(command) = hook.get("command").and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                out.push(ClaudeHookEntry {
+                    event: event.clone(),
+                    matcher: matcher.clone(),
+                    command: command.to_string(),
+                    timeout_secs: hook.get("timeout").and_then(|v| v.as_u64()),
+                    template_id: template_id_for(event, matcher.as_deref(), command),
+                });
+            }
+        }
+    }
+    out
+}
+
+fn validate_hook(input: ClaudeHookInput) -> Result<ClaudeHookInput, String> {
+    let event = input.event.trim().to_string();
+    if !HOOK_EVENTS.contains(&event.as_str()) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: unsupported hook event={event} (expected one of {})",
+            HOOK_EVENTS.join(", ")
+        ));
+    }
+
+    let matcher = input
+        .matcher
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    if let Some(matcher) = matcher.as_deref() {
+        if event == "Stop" {
+            return Err("SEC_INVALID_INPUT: Stop hooks do not take a matcher".to_string());
+        }
+        if matcher != "*" {
+            regex::Regex::new(matcher)
+                .map_err(|e| format!("SEC_INVALID_INPUT: invalid hook matcher regex: {e}"))?;
+        }
+    }
+
+    let command = input.command.trim().to_string();
+    if command.is_empty() {
+        return Err("SEC_INVALID_INPUT: hook command is required".to_string());
+    }
+    if command.chars().count() > MAX_COMMAND_CHARS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: hook command must be at most {MAX_COMMAND_CHARS} chars"
+        ));
+    }
+
+    if let Some(timeout) = input.timeout_secs {
+        if timeout == 0 || timeout > MAX_TIMEOUT_SECS {
+            return Err(format!(
+                "SEC_INVALID_INPUT: hook timeout must be 1-{MAX_TIMEOUT_SECS} seconds"
+            ));
+        }
+    }
+
+    Ok(ClaudeHookInput {
+        event,
+        matcher,
+        command,
+        timeout_secs: input.timeout_secs,
+    })
+}
+
+fn hook_value(hook: &ClaudeHookInput) -> serde_json::Value {
+    let mut value = serde_json::json!({ "type": "command", "command": hook.command });
+    if let Some(timeout) = hook.timeout_secs {
+        value["timeout"] = serde_json::Value::from(timeout);
+    }
+    value
+}
+
+fn is_same_command(value: &serde_json::Value, command: &str) -> bool {
+    value.get("type").and_then(|v| v.as_str()) == Some("command")
+        && value.get("command").and_then(|v| v.as_str()) == Some(command)
+}
+
+/// Returns the event's group list, creating `hooks` / the event key when missing.
+fn event_groups_mut<'a>(
+    root: &'a mut serde_json::Value,
+    event: &str,
+) -> Result<&'a mut Vec<serde_json::Value>, String> {
+    let obj = root
+        .as_object_mut()
+        .ok_or_else(|| "SEC_INVALID_INPUT: settings.json root must be a JSON object".to_string())?;
+    let hooks = obj
+        .entry("hooks".to_string())
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or_else(|| "SEC_INVALID_INPUT: settings.json hooks must be an object".to_string())?;
+    hooks
+        .entry(event.to_string())
+        .or_insert_with(|| serde_json::json!([]))
+        .as_array_mut()
+        .ok_or_else(|| format!("SEC_INVALID_INPUT: settings.json hooks.{event} must be an array"))
+}
+
+/// Adds the hook (or refreshes its timeout). Returns false when an identical hook already exists.
+fn insert_hook(root: &mut serde_json::Value, hook: &ClaudeHookInput) -> Result<bool, String> {
+    let groups = event_groups_mut(root, &hook.event)?;
+    let group = groups
+        .iter_mut()
+        .filter(|g| g.is_object())
+        .find(|g| group_matcher(g) == hook.matcher);
+
+    let Some(group) = group else {
+        let mut group = serde_json::json!({ "hooks": [hook_value(hook)] });
+        if let Some(matcher) = hook.matcher.as_deref() {
+            group["matcher"] = serde_json::Value::from(matcher);
+        }
+        groups.push(group);
+        return Ok(true);
+    };
+
+    let entries = group
+        .as_object_mut()
+        .expect("group is object")
+        .entry("hooks".to_string())
+        .or_insert_with(|| serde_json::json!([]))
+        .as_array_mut()
+        .ok_or_else(|| {
+            format!(
+                "SEC_INVALID_INPUT: settings.json hooks.{}[].hooks must be an array",
+                hook.event
+            )
+        })?;
+    match entries
+        .iter_mut()
+        .find(|v| is_same_command(v, &hook.command))
+    {
+        Some(existing) => {
+            let next = hook_value(hook);
+            let changed = *existing != next;
+            *existing = next;
+            Ok(changed)
+        }
+        None => {
+            entries.push(hook_value(hook));
+            Ok(true)
+        }
+    }
+}
+
+/// Removes matching hooks, dropping groups / events / `hooks` left empty. Returns whether anything was removed.
+fn remove_hook(root: &mut serde_json::Value, hook: &ClaudeHookInput) -> bool {
+    let Some(hooks) = root.get_mut("hooks").and_then(|v| v.as_object_mut()) else {
+        return false;
+    };
+    let Some(groups) = hooks.get_mut(&hook.event).and_then(|v| v.as_array_mut()) else {
+        return false;
+    };
+
+    let mut removed = false;
+    for group in groups.iter_mut() {
+        if group_matcher(group) != hook.matcher {
+            continue;
+        }
+        if let Some(entries) = group.get_mut("hooks").and_then(|v| v.as_array_mut()) {
+            let before = entries.len();
+            entries.retain(|v| !is_same_command(v, &hook.command));
+            removed |= entries.len() != before;
+        }
+    }
+    if !removed {
+        return false;
+    }
+
+    groups.retain(|g| {
+        g.get("hooks")
+            .and_then(|v| v.as_array())
+            .map(|entries| !entries.is_empty())
+            .unwrap_or(true)
+    });
+    if groups.is_empty() {
+        hooks.remove(&hook.event);
+    }
+    if hooks.is_empty() {
+        if let Some(obj) = root.as_object_mut() {
+            obj.remove("hooks");
+        }
+    }
+    true
+}
+
+fn backup_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_paths::app_data_dir(app)?
+        .join(BACKUP_DIR_NAME)
+        .join("backups"))
+}
+
+fn write_backup(dir: &Path, bytes: &[u8]) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+    let name = format!("settings-{}.json", now_unix_seconds());
+    write_file_atomic(&dir.join(name), bytes)?;
+
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("failed to read {}: {e}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("settings-") && n.ends_with(".json"))
+        })
+        .collect();
+    if backups.len() > MAX_BACKUPS {
+        backups.sort();
+        for stale in &backups[..backups.len() - MAX_BACKUPS] {
+            let _ = std::fs::remove_file(stale);
+        }
+    }
+    Ok(())
+}
+
+fn state_from_root(
+    app: &tauri::AppHandle,
+    root: &serde_json::Value,
+) -> Result<ClaudeHooksState, String> {
+    let hooks = parse_hooks(root);
+    let templates = TEMPLATES
+        .iter()
+        .map(|t| ClaudeHookTemplateState {
+            id: t.id,
+            name: t.name,
+            description: t.description,
+            event: t.event,
+            matcher: t.matcher,
+            command: t.command,
+            installed: hooks.iter().any(|h| h.template_id == Some(t.id)),
+        })
+        .collect();
+    Ok(ClaudeHooksState {
+        settings_path: claude_settings_path(app)?.to_string_lossy().to_string(),
+        backup_dir: backup_dir(app)?.to_string_lossy().to_string(),
+        hooks,
+        templates,
+    })
+}
+
+fn read_settings_root(path: &Path) -> Result<(Option<Vec<u8>>, serde_json::Value), String> {
+    let current = read_optional_file(path)?;
+    let root = match current.as_deref() {
+        Some(bytes) if !bytes.iter().all(u8::is_ascii_whitespace) => {
+            serde_json::from_slice::<serde_json::Value>(bytes).map_err(|e| {
+                format!(
+                    "SEC_INVALID_INPUT: {} is not valid JSON; fix it before managing hooks: {e}",
+                    path.display()
+                )
+            })?
+        }
+        _ => serde_json::json!({}),
+    };
+    Ok((current, root))
+}
+
+fn modify_hooks(
+    app: &tauri::AppHandle,
+    apply: impl FnOnce(&mut serde_json::Value) -> Result<bool, String>,
+) -> Result<ClaudeHooksState, String> {
+    let path = claude_settings_path(app)?;
+    if path.exists() && is_symlink(&path)? {
+        return Err(format!(
+            "SEC_INVALID_INPUT: refusing to modify symlink path={}",
+            path.display()
+        ));
+    }
+
+    let (current, mut root) = read_settings_root(&path)?;
+    if apply(&mut root)? {
+        if let Some(bytes) = current.as_deref() {
+            write_backup(&backup_dir(app)?, bytes)?;
+        }
+        let bytes = json_to_bytes(&root, "claude/settings.json")?;
+        let _ = write_file_atomic_if_changed(&path, &bytes)?;
+    }
+    state_from_root(app, &root)
+}
+
+pub fn claude_hooks_get(app: &tauri::AppHandle) -> Result<ClaudeHooksState, String> {
+    let (_, root) = read_settings_root(&claude_settings_path(app)?)?;
+    state_from_root(app, &root)
+}
+
+pub fn claude_hook_create(
+    app: &tauri::AppHandle,
+    hook: ClaudeHookInput,
+) -> Result<ClaudeHooksState, String> {
+    let hook = validate_hook(hook)?;
+    modify_hooks(app, |root| {
+        let exists = parse_hooks(root).iter().any(|h| {
+            h.event == hook.event && h.matcher == hook.matcher && h.command == hook.command
+        });
+        if exists {
+            return Err("SEC_INVALID_INPUT: an identical hook already exists".to_string());
+        }
+        insert_hook(root, &hook)
+    })
+}
+
+pub fn claude_hook_set_enabled(
+    app: &tauri::AppHandle,
+    hook: ClaudeHookInput,
+    enabled: bool,
+) -> Result<ClaudeHooksState, String> {
+    let hook = validate_hook(hook)?;
+    modify_hooks(app, |root| {
+        if enabled {
+            insert_hook(root, &hook)
+        } else {
+            Ok(remove_hook(root, &hook))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(event: &str, matcher: Option<&str>, command: &str) -> ClaudeHookInput {
+        ClaudeHookInput {
+            event: event.to_string(),
+            matcher: matcher.map(str::to_string),
+            command: command.to_string(),
+            timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn insert_reuses_matcher_group_and_keeps_other_settings() {
+        let mut root = serde_json::json!({
+            "model": "opus",
+            "hooks": {
+                "PreToolUse": [
+                    { "matcher": "Bash", "hooks": [{ "type": "command", "command": "echo a" }] }
+                ]
+            }
+        });
+
+        assert!(insert_hook(&mut root, &hook("PreToolUse", Some("Bash"), "echo b")).unwrap());
+        assert!(!insert_hook(&mut root, &hook("PreToolUse", Some("Bash"), "echo b")).unwrap());
+        assert!(insert_hook(&mut root, &hook("Stop", None, "echo done")).unwrap());
+
+        assert_eq!(root["model"], "opus");
+        assert_eq!(root["hooks"]["PreToolUse"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            root["hooks"]["PreToolUse"][0]["hooks"][1]["command"],
+            "echo b"
+        );
+        assert!(root["hooks"]["Stop"][0].get("matcher").is_none());
+
+        let listed = parse_hooks(&root);
+        assert_eq!(listed.len(), 3);
+        assert!(listed.iter().all(|h| h.template_id.is_none()));
+    }
+
+    #[test]
+    fn remove_drops_empty_groups_and_hooks_object() {
+        let mut root = serde_json::json!({});
+        let h = hook("PostToolUse", Some("Edit|Write"), "npx prettier --write");
+        insert_hook(&mut root, &h).unwrap();
+
+        assert!(!remove_hook(
+            &mut root,
+            &hook("PostToolUse", None, &h.command)
+        ));
+        assert!(remove_hook(&mut root, &h));
+        assert_eq!(root, serde_json::json!({}));
+    }
+
+    #[test]
+    fn insert_refuses_malformed_hooks() {
+        let mut root = serde_json::json!({ "hooks": { "Stop": "echo" } });
+        let err = insert_hook(&mut root, &hook("Stop", None, "echo")).unwrap_err();
+        assert!(err.starts_with("SEC_INVALID_INPUT:"), "{err}");
+    }
+
+    #[test]
+    fn validate_hook_checks_event_matcher_and_timeout() {
+        assert!(validate_hook(hook("Notification", None, "echo")).is_err());
+        assert!(validate_hook(hook("Stop", Some("Bash"), "echo")).is_err());
+        assert!(validate_hook(hook("PreToolUse", Some("(unclosed"), "echo")).is_err());
+        assert!(validate_hook(hook("PreToolUse", Some("Bash"), "  ")).is_err());
+        assert!(validate_hook(ClaudeHookInput {
+            timeout_secs: Some(0),
+            ..hook("PreToolUse", None, "echo")
+        })
+        .is_err());
+
+        let ok = validate_hook(hook(" PreToolUse ", Some(" "), " echo hi ")).unwrap();
+        assert_eq!(ok, hook("PreToolUse", None, "echo hi"));
+        assert!(validate_hook(hook("PreToolUse", Some("*"), "echo")).is_ok());
+    }
+
+    #[test]
+    fn templates_are_recognized_when_listed() {
+        let mut root = serde_json::json!({});
+        for t in TEMPLATES {
+            let input = hook(t.event, t.matcher, t.command);
+            let input = validate_hook(input).expect("template must validate");
+            insert_hook(&mut root, &input).unwrap();
+        }
+        let listed = parse_hooks(&root);
+        assert_eq!(listed.len(), TEMPLATES.len());
+        for t in TEMPLATES {
+            assert!(listed.iter().any(|h| h.template_id == Some(t.id)));
+        }
+    }
+}
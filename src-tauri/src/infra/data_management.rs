@@ -1,7 +1,7 @@
 //! Usage: App data and DB disk-management helpers (reset, usage stats, cleanup).
 
 use crate::app_paths;
-use crate::db;
+use crate::{db, usage_rollups};
 use rusqlite::TransactionBehavior;
 use serde::Serialize;
 use std::io;
@@ -84,6 +84,9 @@ pub fn request_logs_clear_all(db: &db::Db) -> Result<ClearRequestLogsResult, Str
     let request_logs_deleted = tx
         .execute("DELETE FROM request_logs", [])
         .map_err(|e| format!("DB_ERROR: failed to clear request_logs: {e}"))?;
+    tx.execute("DELETE FROM request_sse_captures", [])
+        .map_err(|e| format!("DB_ERROR: failed to clear request_sse_captures: {e}"))?;
+    usage_rollups::clear_all(&tx)?;
 
     tx.commit()
         .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
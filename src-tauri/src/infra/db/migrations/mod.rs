@@ -21,9 +21,40 @@ mod v25_to_v26;
 mod v26_to_v27;
 mod v27_to_v28;
 mod v28_to_v29;
+mod v29_to_v30;
 mod v2_to_v3;
+mod v30_to_v31;
+mod v31_to_v32;
+mod v32_to_v33;
+mod v33_to_v34;
+mod v34_to_v35;
+mod v35_to_v36;
+mod v36_to_v37;
+mod v37_to_v38;
+mod v38_to_v39;
+mod v39_to_v40;
 mod v3_to_v4;
+mod v40_to_v41;
+mod v41_to_v42;
+mod v42_to_v43;
+mod v43_to_v44;
+mod v44_to_v45;
+mod v45_to_v46;
+mod v46_to_v47;
+mod v47_to_v48;
+mod v48_to_v49;
+mod v49_to_v50;
 mod v4_to_v5;
+mod v50_to_v51;
+mod v51_to_v52;
+mod v52_to_v53;
+mod v53_to_v54;
+mod v54_to_v55;
+mod v55_to_v56;
+mod v56_to_v57;
+mod v57_to_v58;
+mod v58_to_v59;
+mod v59_to_v60;
 mod v5_to_v6;
 mod v6_to_v7;
 mod v7_to_v8;
@@ -32,7 +63,7 @@ mod v9_to_v10;
 
 use rusqlite::Connection;
 
-const LATEST_SCHEMA_VERSION: i64 = 29;
+pub(super) const LATEST_SCHEMA_VERSION: i64 = 60;
 
 pub(super) fn apply_migrations(conn: &mut Connection) -> Result<(), String> {
     let mut user_version = read_user_version(conn)?;
@@ -80,6 +111,37 @@ pub(super) fn apply_migrations(conn: &mut Connection) -> Result<(), String> {
             26 => v26_to_v27::migrate_v26_to_v27(conn)?,
             27 => v27_to_v28::migrate_v27_to_v28(conn)?,
             28 => v28_to_v29::migrate_v28_to_v29(conn)?,
+            29 => v29_to_v30::migrate_v29_to_v30(conn)?,
+            30 => v30_to_v31::migrate_v30_to_v31(conn)?,
+            31 => v31_to_v32::migrate_v31_to_v32(conn)?,
+            32 => v32_to_v33::migrate_v32_to_v33(conn)?,
+            33 => v33_to_v34::migrate_v33_to_v34(conn)?,
+            34 => v34_to_v35::migrate_v34_to_v35(conn)?,
+            35 => v35_to_v36::migrate_v35_to_v36(conn)?,
+            36 => v36_to_v37::migrate_v36_to_v37(conn)?,
+            37 => v37_to_v38::migrate_v37_to_v38(conn)?,
+            38 => v38_to_v39::migrate_v38_to_v39(conn)?,
+            39 => v39_to_v40::migrate_v39_to_v40(conn)?,
+            40 => v40_to_v41::migrate_v40_to_v41(conn)?,
+            41 => v41_to_v42::migrate_v41_to_v42(conn)?,
+            42 => v42_to_v43::migrate_v42_to_v43(conn)?,
+            43 => v43_to_v44::migrate_v43_to_v44(conn)?,
+            44 => v44_to_v45::migrate_v44_to_v45(conn)?,
+            45 => v45_to_v46::migrate_v45_to_v46(conn)?,
+            46 => v46_to_v47::migrate_v46_to_v47(conn)?,
+            47 => v47_to_v48::migrate_v47_to_v48(conn)?,
+            48 => v48_to_v49::migrate_v48_to_v49(conn)?,
+            49 => v49_to_v50::migrate_v49_to_v50(conn)?,
+            50 => v50_to_v51::migrate_v50_to_v51(conn)?,
+            51 => v51_to_v52::migrate_v51_to_v52(conn)?,
+            52 => v52_to_v53::migrate_v52_to_v53(conn)?,
+            53 => v53_to_v54::migrate_v53_to_v54(conn)?,
+            54 => v54_to_v55::migrate_v54_to_v55(conn)?,
+            55 => v55_to_v56::migrate_v55_to_v56(conn)?,
+            56 => v56_to_v57::migrate_v56_to_v57(conn)?,
+            57 => v57_to_v58::migrate_v57_to_v58(conn)?,
+            58 => v58_to_v59::migrate_v58_to_v59(conn)?,
+            59 => v59_to_v60::migrate_v59_to_v60(conn)?,
             v => {
                 return Err(format!(
                     "unsupported sqlite schema version: user_version={v} (expected 0..={LATEST_SCHEMA_VERSION})"
@@ -0,0 +1,45 @@
+//! Usage: SQLite migration v29->v30 - Add notice_history table.
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v29_to_v30(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 30;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS notice_history (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  created_at INTEGER NOT NULL,
+  event_kind TEXT NOT NULL,
+  level TEXT NOT NULL,
+  title TEXT NOT NULL,
+  body TEXT NOT NULL,
+  channel_id TEXT NOT NULL,
+  channel_kind TEXT NOT NULL,
+  status TEXT NOT NULL,
+  error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_notice_history_created_at ON notice_history(created_at);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v29->v30: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Usage: SQLite migration v30->v31 - Add request_logs.client_id (per-client key attribution).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v30_to_v31(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 31;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE request_logs ADD COLUMN client_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_request_logs_client_id_created_at
+  ON request_logs(client_id, created_at);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v30->v31: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Usage: SQLite migration v32->v33 - Add tool_usage_events table (tool calls extracted from request bodies).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v32_to_v33(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 33;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS tool_usage_events (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  created_at INTEGER NOT NULL,
+  cli_key TEXT NOT NULL,
+  session_id TEXT,
+  tool_name TEXT NOT NULL,
+  call_id TEXT NOT NULL,
+  UNIQUE(cli_key, call_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_usage_events_created_at ON tool_usage_events(created_at);
+CREATE INDEX IF NOT EXISTS idx_tool_usage_events_session_id ON tool_usage_events(session_id);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v32->v33: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
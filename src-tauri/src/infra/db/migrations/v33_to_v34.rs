@@ -0,0 +1,43 @@
+//! Usage: SQLite migration v33->v34 - Add base_url_probe_history table (persisted base_url latency probes).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v33_to_v34(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 34;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS base_url_probe_history (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  created_at INTEGER NOT NULL,
+  provider_id INTEGER,
+  base_url TEXT NOT NULL,
+  latency_ms INTEGER,
+  error TEXT,
+  source TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_base_url_probe_history_provider_created_at ON base_url_probe_history(provider_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_base_url_probe_history_base_url_created_at ON base_url_probe_history(base_url, created_at);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v33->v34: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
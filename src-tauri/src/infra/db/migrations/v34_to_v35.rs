@@ -0,0 +1,48 @@
+//! Usage: SQLite migration v34->v35 - Add provider_canaries table (canary rollout of new providers within a sort mode).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v34_to_v35(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 35;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS provider_canaries (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  cli_key TEXT NOT NULL,
+  mode_id INTEGER NOT NULL DEFAULT 0,
+  provider_id INTEGER NOT NULL,
+  incumbent_provider_id INTEGER,
+  traffic_percent INTEGER NOT NULL,
+  max_error_rate_delta REAL NOT NULL,
+  min_samples INTEGER NOT NULL,
+  status TEXT NOT NULL,
+  started_at INTEGER NOT NULL,
+  ends_at INTEGER NOT NULL,
+  decided_at INTEGER,
+  decision_reason TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_canaries_cli_mode_status ON provider_canaries(cli_key, mode_id, status);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v34->v35: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
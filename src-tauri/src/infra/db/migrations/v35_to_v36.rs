@@ -0,0 +1,51 @@
+//! Usage: SQLite migration v35->v36 - Add provider_ab_tests / provider_ab_test_sessions tables (A/B comparison of two providers by session).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v35_to_v36(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 36;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS provider_ab_tests (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  cli_key TEXT NOT NULL,
+  provider_a_id INTEGER NOT NULL,
+  provider_b_id INTEGER NOT NULL,
+  target_sessions INTEGER NOT NULL,
+  status TEXT NOT NULL,
+  started_at INTEGER NOT NULL,
+  completed_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_ab_tests_cli_status ON provider_ab_tests(cli_key, status);
+
+CREATE TABLE IF NOT EXISTS provider_ab_test_sessions (
+  test_id INTEGER NOT NULL,
+  session_id TEXT NOT NULL,
+  arm TEXT NOT NULL,
+  assigned_at INTEGER NOT NULL,
+  PRIMARY KEY(test_id, session_id)
+);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v35->v36: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
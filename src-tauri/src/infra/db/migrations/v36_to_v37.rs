@@ -0,0 +1,32 @@
+//! Usage: SQLite migration v36->v37 - Add providers.disable_response_storage (force store=false on Codex /v1/responses requests).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v36_to_v37(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 37;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE providers ADD COLUMN disable_response_storage INTEGER NOT NULL DEFAULT 0;
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v36->v37: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+//! Usage: SQLite migration v37->v38 - Add request_logs(cli_key, id) index for keyset pagination by id.
+//!
+//! `WHERE cli_key = ? AND id > ?` previously walked the primary key and filtered by cli_key; for a CLI
+//! with little traffic that is a near full-table scan. With this index the matching ids are found by a
+//! range search on `(cli_key, id)`; the rows themselves are still read from the table by rowid.
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v37_to_v38(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 38;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE INDEX IF NOT EXISTS idx_request_logs_cli_key_id ON request_logs(cli_key, id);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v37->v38: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! Usage: SQLite migration v38->v39 - Add hourly usage/cost rollup table, its watermark and maintenance triggers.
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v38_to_v39(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 39;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS usage_rollups_hourly (
+  hour_ts INTEGER NOT NULL,
+  cli_key TEXT NOT NULL,
+  final_provider_id INTEGER NOT NULL,
+  model_key TEXT NOT NULL,
+  requests_total INTEGER NOT NULL DEFAULT 0,
+  requests_with_usage INTEGER NOT NULL DEFAULT 0,
+  requests_success INTEGER NOT NULL DEFAULT 0,
+  requests_failed INTEGER NOT NULL DEFAULT 0,
+  success_duration_ms_sum INTEGER NOT NULL DEFAULT 0,
+  success_ttfb_ms_sum INTEGER NOT NULL DEFAULT 0,
+  success_ttfb_ms_count INTEGER NOT NULL DEFAULT 0,
+  success_generation_ms_sum INTEGER NOT NULL DEFAULT 0,
+  success_output_tokens_for_rate_sum INTEGER NOT NULL DEFAULT 0,
+  input_tokens INTEGER NOT NULL DEFAULT 0,
+  output_tokens INTEGER NOT NULL DEFAULT 0,
+  total_tokens INTEGER NOT NULL DEFAULT 0,
+  cache_read_input_tokens INTEGER NOT NULL DEFAULT 0,
+  cache_creation_input_tokens INTEGER NOT NULL DEFAULT 0,
+  cache_creation_5m_input_tokens INTEGER NOT NULL DEFAULT 0,
+  cache_creation_1h_input_tokens INTEGER NOT NULL DEFAULT 0,
+  cost_usd_femto INTEGER NOT NULL DEFAULT 0,
+  success_cost_covered INTEGER NOT NULL DEFAULT 0,
+  success_cost_usd_femto INTEGER NOT NULL DEFAULT 0,
+  success_costed_duration_ms_sum INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY(hour_ts, cli_key, final_provider_id, model_key)
+);
+
+CREATE TABLE IF NOT EXISTS usage_rollup_state (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  rolled_until INTEGER NOT NULL
+);
+INSERT OR IGNORE INTO usage_rollup_state(id, rolled_until) VALUES (1, 0);
+
+CREATE TRIGGER IF NOT EXISTS trg_request_logs_rollup_insert
+AFTER INSERT ON request_logs
+WHEN NEW.created_at < (SELECT rolled_until FROM usage_rollup_state WHERE id = 1)
+BEGIN
+  UPDATE usage_rollup_state
+  SET rolled_until = MIN(rolled_until, (NEW.created_at / 3600) * 3600)
+  WHERE id = 1;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_request_logs_rollup_update
+AFTER UPDATE ON request_logs
+WHEN MIN(OLD.created_at, NEW.created_at) < (SELECT rolled_until FROM usage_rollup_state WHERE id = 1)
+BEGIN
+  UPDATE usage_rollup_state
+  SET rolled_until = MIN(rolled_until, (MIN(OLD.created_at, NEW.created_at) / 3600) * 3600)
+  WHERE id = 1;
+END;
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v38->v39: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
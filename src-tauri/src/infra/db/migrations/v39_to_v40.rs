@@ -0,0 +1,44 @@
+//! Usage: SQLite migration v39->v40 - Add codex_session_completion_events table (history of codex session id completions).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v39_to_v40(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 40;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS codex_session_completion_events (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  created_at INTEGER NOT NULL,
+  trace_id TEXT NOT NULL,
+  session_id TEXT NOT NULL,
+  action TEXT NOT NULL,
+  source TEXT NOT NULL,
+  changed_header INTEGER NOT NULL DEFAULT 0,
+  changed_body INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_codex_session_completion_events_created_at ON codex_session_completion_events(created_at);
+CREATE INDEX IF NOT EXISTS idx_codex_session_completion_events_session_id ON codex_session_completion_events(session_id);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v39->v40: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+//! Usage: SQLite migration v40->v41 - Add sort_modes.settings_json (per-sort-mode gateway settings overrides).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v40_to_v41(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 41;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE sort_modes ADD COLUMN settings_json TEXT NOT NULL DEFAULT '{}';
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v40->v41: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! Usage: SQLite migration v41->v42 - Add sort mode schedules (time-window based active sort mode switching) + switch events.
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v41_to_v42(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 42;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS sort_mode_schedules (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  cli_key TEXT NOT NULL,
+  mode_id INTEGER,
+  start_minutes INTEGER NOT NULL,
+  end_minutes INTEGER NOT NULL,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  sort_order INTEGER NOT NULL,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  FOREIGN KEY(mode_id) REFERENCES sort_modes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_sort_mode_schedules_cli_sort_order
+  ON sort_mode_schedules(cli_key, sort_order);
+
+CREATE TABLE IF NOT EXISTS sort_mode_switch_events (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  created_at INTEGER NOT NULL,
+  cli_key TEXT NOT NULL,
+  from_mode_id INTEGER,
+  to_mode_id INTEGER,
+  schedule_id INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_sort_mode_switch_events_created_at
+  ON sort_mode_switch_events(created_at);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v41->v42: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Usage: SQLite migration v42->v43 - Add request_logs.client_trace_id (client-supplied `x-client-trace-id`).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v42_to_v43(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 43;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE request_logs ADD COLUMN client_trace_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_request_logs_client_trace_id
+  ON request_logs(client_trace_id);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v42->v43: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+//! Usage: SQLite migration v43->v44 - Add request_sse_captures (opt-in raw SSE per trace).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v43_to_v44(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 44;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS request_sse_captures (
+  trace_id TEXT PRIMARY KEY,
+  cli_key TEXT NOT NULL,
+  created_at INTEGER NOT NULL,
+  truncated INTEGER NOT NULL DEFAULT 0,
+  body TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_request_sse_captures_created_at
+  ON request_sse_captures(created_at);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v43->v44: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
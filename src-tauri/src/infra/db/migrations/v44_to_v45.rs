@@ -0,0 +1,32 @@
+//! Usage: SQLite migration v44->v45 - Add providers.wire_api (upstream protocol; translate when it differs from the CLI native API).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v44_to_v45(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 45;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE providers ADD COLUMN wire_api TEXT NOT NULL DEFAULT 'native';
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v44->v45: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
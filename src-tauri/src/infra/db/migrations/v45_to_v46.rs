@@ -0,0 +1,32 @@
+//! Usage: SQLite migration v45->v46 - Add providers.exclude_from_stats (test / sandbox providers; their request logs are excluded from usage stats).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v45_to_v46(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 46;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE providers ADD COLUMN exclude_from_stats INTEGER NOT NULL DEFAULT 0;
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v45->v46: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
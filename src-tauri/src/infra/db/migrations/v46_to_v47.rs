@@ -0,0 +1,33 @@
+//! Usage: SQLite migration v46->v47 - Add sort_modes.strategy (ordered / weighted / round_robin) and sort_mode_providers.weight.
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v46_to_v47(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 47;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE sort_modes ADD COLUMN strategy TEXT NOT NULL DEFAULT 'ordered';
+ALTER TABLE sort_mode_providers ADD COLUMN weight INTEGER NOT NULL DEFAULT 1;
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v46->v47: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+//! Usage: SQLite migration v47->v48 - Add providers.rpm_limit / providers.tpm_limit (per-provider token-bucket rate limits; 0 = unlimited).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v47_to_v48(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 48;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE providers ADD COLUMN rpm_limit INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE providers ADD COLUMN tpm_limit INTEGER NOT NULL DEFAULT 0;
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v47->v48: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+//! Usage: SQLite migration v48->v49 - Add providers.daily_quota_usd / providers.monthly_quota_usd (spend budgets; 0 = unlimited).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v48_to_v49(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 49;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE providers ADD COLUMN daily_quota_usd REAL NOT NULL DEFAULT 0;
+ALTER TABLE providers ADD COLUMN monthly_quota_usd REAL NOT NULL DEFAULT 0;
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v48->v49: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
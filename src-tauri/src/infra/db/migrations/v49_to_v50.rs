@@ -0,0 +1,32 @@
+//! Usage: SQLite migration v49->v50 - Add providers.context_guard_limit_kb (request body limit the gateway trims conversations to; 0 = off).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v49_to_v50(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 50;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE providers ADD COLUMN context_guard_limit_kb INTEGER NOT NULL DEFAULT 0;
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v49->v50: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
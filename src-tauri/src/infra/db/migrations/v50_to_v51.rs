@@ -0,0 +1,32 @@
+//! Usage: SQLite migration v50->v51 - Add request_logs.estimated_input_tokens (local prompt token estimate of the request body).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v50_to_v51(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 51;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE request_logs ADD COLUMN estimated_input_tokens INTEGER;
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v50->v51: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
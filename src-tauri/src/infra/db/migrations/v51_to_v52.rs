@@ -0,0 +1,32 @@
+//! Usage: SQLite migration v51->v52 - Add providers.upstream_proxy (outbound proxy: `system` | `none` | proxy URL).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v51_to_v52(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 52;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE providers ADD COLUMN upstream_proxy TEXT NOT NULL DEFAULT 'system';
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v51->v52: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
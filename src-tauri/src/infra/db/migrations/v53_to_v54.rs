@@ -0,0 +1,32 @@
+//! Usage: SQLite migration v53->v54 - Add providers.request_signing_json (HMAC signing for self-hosted relays).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v53_to_v54(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 54;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE providers ADD COLUMN request_signing_json TEXT NOT NULL DEFAULT '{}';
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v53->v54: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
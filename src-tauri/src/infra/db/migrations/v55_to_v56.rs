@@ -0,0 +1,44 @@
+//! Usage: SQLite migration v55->v56 - Add response_cache (persisted layer of the gateway response cache).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v55_to_v56(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 56;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS response_cache (
+  provider_id INTEGER NOT NULL,
+  fingerprint_key INTEGER NOT NULL,
+  fingerprint_debug TEXT NOT NULL,
+  status INTEGER NOT NULL,
+  headers_json TEXT NOT NULL,
+  body BLOB NOT NULL,
+  stored_at INTEGER NOT NULL,
+  PRIMARY KEY (provider_id, fingerprint_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_response_cache_stored_at
+  ON response_cache(stored_at);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v55->v56: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
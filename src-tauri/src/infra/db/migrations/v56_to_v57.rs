@@ -0,0 +1,32 @@
+//! Usage: SQLite migration v56->v57 - Add providers.header_rules_json (per-provider outbound header add / override / remove rules).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v56_to_v57(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 57;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE providers ADD COLUMN header_rules_json TEXT NOT NULL DEFAULT '[]';
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v56->v57: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+//! Usage: SQLite migration v57->v58 - Add providers.body_patch_rules_json (per-provider request body set / delete / rename rules).
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+
+pub(super) fn migrate_v57_to_v58(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 58;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE providers ADD COLUMN body_patch_rules_json TEXT NOT NULL DEFAULT '[]';
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v57->v58: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
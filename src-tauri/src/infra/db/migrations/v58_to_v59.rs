@@ -0,0 +1,89 @@
+//! Usage: SQLite migration v58->v59 - Add request_logs.final_provider_name and provider / cost listing indexes.
+
+use crate::shared::time::now_unix_seconds;
+use rusqlite::Connection;
+use serde::Deserialize;
+
+pub(super) fn migrate_v58_to_v59(conn: &mut Connection) -> Result<(), String> {
+    const VERSION: i64 = 59;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("failed to start sqlite transaction: {e}"))?;
+
+    tx.execute_batch(
+        r#"
+ALTER TABLE request_logs ADD COLUMN final_provider_name TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_request_logs_final_provider_created_at_ms
+  ON request_logs(final_provider_id, created_at_ms);
+CREATE INDEX IF NOT EXISTS idx_request_logs_cost_usd_femto
+  ON request_logs(cost_usd_femto);
+"#,
+    )
+    .map_err(|e| format!("failed to migrate v58->v59: {e}"))?;
+
+    // Backfill final_provider_name with the same pick as final_provider_id (v23->v24):
+    // the last success attempt, otherwise the last attempt.
+    #[derive(Debug, Deserialize)]
+    struct AttemptRow {
+        provider_id: i64,
+        provider_name: String,
+        outcome: String,
+    }
+
+    fn final_provider_name_from_attempts_json(attempts_json: &str) -> Option<String> {
+        let attempts: Vec<AttemptRow> = serde_json::from_str(attempts_json).unwrap_or_default();
+        let picked = attempts
+            .iter()
+            .rev()
+            .find(|a| a.outcome == "success")
+            .or_else(|| attempts.last());
+        picked
+            .filter(|a| a.provider_id > 0)
+            .map(|a| a.provider_name.clone())
+    }
+
+    {
+        let mut select_stmt = tx
+            .prepare(
+                "SELECT id, attempts_json FROM request_logs WHERE final_provider_id IS NOT NULL",
+            )
+            .map_err(|e| format!("failed to prepare request_logs backfill query: {e}"))?;
+        let mut update_stmt = tx
+            .prepare("UPDATE request_logs SET final_provider_name = ?1 WHERE id = ?2")
+            .map_err(|e| format!("failed to prepare request_logs backfill update: {e}"))?;
+
+        let mut rows = select_stmt
+            .query([])
+            .map_err(|e| format!("failed to run request_logs backfill query: {e}"))?;
+        while let Some(row) = rows
+            .next()
+            .map_err(|e| format!("failed to read request_logs backfill row: {e}"))?
+        {
+            let id: i64 = row
+                .get("id")
+                .map_err(|e| format!("failed to read request_logs.id for backfill: {e}"))?;
+            let attempts_json: String = row.get("attempts_json").unwrap_or_default();
+            let Some(provider_name) = final_provider_name_from_attempts_json(&attempts_json) else {
+                continue;
+            };
+            update_stmt
+                .execute(rusqlite::params![provider_name, id])
+                .map_err(|e| format!("failed to backfill request_logs.final_provider_name: {e}"))?;
+        }
+    }
+
+    let now = now_unix_seconds();
+    tx.execute(
+        "INSERT INTO schema_migrations (version, applied_at) VALUES (?, ?)",
+        [VERSION, now],
+    )
+    .map_err(|e| format!("failed to insert schema_migrations row for v{VERSION}: {e}"))?;
+
+    super::set_user_version(&tx, VERSION)?;
+
+    tx.commit()
+        .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
+
+    Ok(())
+}
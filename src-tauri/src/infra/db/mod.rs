@@ -10,7 +10,11 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 const DB_FILE_NAME: &str = "aio-coding-hub.db";
-const BUSY_TIMEOUT: Duration = Duration::from_millis(2000);
+// 网关日志写入与 UI 统计查询并发时，给写锁留足等待时间，避免 "database is locked"
+const BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+
+/// Schema version this build migrates to; older builds refuse databases stamped with a higher one.
+pub(crate) const LATEST_SCHEMA_VERSION: i64 = migrations::LATEST_SCHEMA_VERSION;
 
 #[derive(Clone)]
 pub(crate) struct Db {
@@ -46,6 +50,19 @@ pub fn db_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(app_paths::app_data_dir(app)?.join(DB_FILE_NAME))
 }
 
+/// Reads the database's `user_version` without migrating it; `None` when the file does not exist yet.
+pub(crate) fn read_schema_version(app: &tauri::AppHandle) -> Result<Option<i64>, String> {
+    let path = db_path(app)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open_with_flags(&path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("DB_ERROR: failed to open db {}: {e}", path.display()))?;
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+        .map(Some)
+        .map_err(|e| format!("DB_ERROR: failed to read sqlite user_version: {e}"))
+}
+
 pub fn init(app: &tauri::AppHandle) -> Result<Db, String> {
     let path = db_path(app)?;
     let path_hint = path.to_string_lossy();
@@ -66,11 +83,27 @@ pub fn init(app: &tauri::AppHandle) -> Result<Db, String> {
     Ok(Db { pool })
 }
 
+/// Migrated in-memory database for tests; one pooled connection so every caller sees the same data.
+#[cfg(test)]
+pub(crate) fn open_in_memory_for_tests() -> Db {
+    let manager = SqliteConnectionManager::memory().with_init(|conn| configure_connection(conn));
+    let pool = Pool::builder()
+        .max_size(1)
+        .build(manager)
+        .expect("create in-memory db pool");
+    migrations::apply_migrations(&mut pool.get().expect("get in-memory connection"))
+        .expect("migrate in-memory db");
+    Db { pool }
+}
+
 fn configure_connection(conn: &Connection) -> rusqlite::Result<()> {
     conn.execute_batch(
         r#"
 PRAGMA journal_mode = WAL;
+PRAGMA synchronous = NORMAL;
 PRAGMA foreign_keys = ON;
+PRAGMA temp_store = MEMORY;
+PRAGMA journal_size_limit = 67108864;
 "#,
     )?;
 
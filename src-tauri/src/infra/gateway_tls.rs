@@ -0,0 +1,255 @@
+//! Usage: Local CA + gateway server certificate for serving the gateway over HTTPS.
+//!
+//! 用法：
+//! - 首次启用 HTTPS 时在应用数据目录 `tls/` 下生成本地 CA（长期有效，用户可导出并加入信任）
+//! - CA 带名称约束（Name Constraints），只能为 localhost / 回环地址 / 当前监听地址 / WSL 宿主机地址 /
+//!   监听所有网卡时的局域网地址 / 用户配置的 `gateway_tls_hosts` 签发；这些地址变化时重新生成 CA，
+//!   需要重新导出并信任（局域网地址不固定时建议在 `gateway_tls_hosts` 中填写固定主机名）
+//! - 每次网关启动时用该 CA 重新签发服务端证书（SAN 与名称约束覆盖同一组地址）
+//! - CA 私钥仅当前用户可读（unix 0600），服务端私钥只在内存中
+//! - `ca_export` 返回 CA 证书路径与 PEM，供客户端导入信任
+
+use crate::app_paths;
+use crate::shared::fs::{read_optional_file, write_file_atomic, write_file_atomic_private};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, CidrSubnet, DistinguishedName, DnType,
+    ExtendedKeyUsagePurpose, GeneralSubtree, IsCa, KeyPair, KeyUsagePurpose, NameConstraints,
+    SanType,
+};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use serde::Serialize;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const TLS_SUBDIR: &str = "tls";
+const CA_CERT_FILE_NAME: &str = "aio-coding-hub-ca.crt";
+const CA_KEY_FILE_NAME: &str = "aio-coding-hub-ca.key";
+// Hosts the CA's name constraints permit, one per line.
+const CA_NAMES_FILE_NAME: &str = "aio-coding-hub-ca.names";
+const LOOPBACK_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "::1"];
+const CA_COMMON_NAME: &str = "AIO Coding Hub Local CA";
+const SERVER_COMMON_NAME: &str = "AIO Coding Hub Gateway";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayTlsCaExport {
+    pub path: String,
+    pub pem: String,
+}
+
+fn tls_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app_paths::app_data_dir(app)?.join(TLS_SUBDIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("failed to create tls dir {}: {e}", dir.display()))?;
+    Ok(dir)
+}
+
+/// Loopback names plus `hosts`, trimmed and deduplicated; wildcard listen addresses are skipped.
+fn certificate_hosts(hosts: &[String]) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    LOOPBACK_HOSTS
+        .into_iter()
+        .map(str::to_string)
+        .chain(hosts.iter().map(|h| h.trim().to_string()))
+        .filter(|host| {
+            !host.is_empty() && host != "0.0.0.0" && host != "::" && seen.insert(host.clone())
+        })
+        .collect()
+}
+
+fn ca_params(permitted_hosts: &[String]) -> Result<CertificateParams, String> {
+    let mut params =
+        CertificateParams::new(Vec::<String>::new()).map_err(|e| format!("TLS_CA_PARAMS: {e}"))?;
+    let mut dn = DistinguishedName::new();
+    dn.push(DnType::CommonName, CA_COMMON_NAME);
+    dn.push(DnType::OrganizationName, "AIO Coding Hub");
+    params.distinguished_name = dn;
+    params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    params.not_before = rcgen::date_time_ymd(2024, 1, 1);
+    params.not_after = rcgen::date_time_ymd(2044, 1, 1);
+    // A leaked CA key must not be usable to impersonate arbitrary sites.
+    params.name_constraints = Some(NameConstraints {
+        permitted_subtrees: permitted_hosts
+            .iter()
+            .map(|host| match host.parse::<IpAddr>() {
+                Ok(ip) => {
+                    let prefix = if ip.is_ipv4() { 32 } else { 128 };
+                    GeneralSubtree::IpAddress(CidrSubnet::from_addr_prefix(ip, prefix))
+                }
+                Err(_) => GeneralSubtree::DnsName(host.clone()),
+            })
+            .collect(),
+        excluded_subtrees: Vec::new(),
+    });
+    Ok(params)
+}
+
+// The persisted CA key is the trust anchor; re-signing the same DN with it yields an equivalent
+// issuer for signing leaves, so only the PEM written on creation is ever exported.
+// `hosts: None` reuses whatever CA exists (creating a loopback-only one if there is none);
+// `Some` regenerates the CA when its name constraints do not list exactly these hosts.
+fn load_or_create_ca(
+    app: &tauri::AppHandle,
+    hosts: Option<&[String]>,
+) -> Result<(Certificate, KeyPair, String), String> {
+    let dir = tls_dir(app)?;
+    let cert_path = dir.join(CA_CERT_FILE_NAME);
+    let key_path = dir.join(CA_KEY_FILE_NAME);
+    let names_path = dir.join(CA_NAMES_FILE_NAME);
+
+    let existing_cert = read_optional_file(&cert_path)?;
+    let existing_key = read_optional_file(&key_path)?;
+    // CAs written before name constraints existed have no names file and are replaced.
+    let existing_names = read_optional_file(&names_path)?.map(|raw| {
+        String::from_utf8_lossy(&raw)
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    });
+    if let (Some(cert_pem), Some(key_pem), Some(names)) =
+        (existing_cert, existing_key, existing_names)
+    {
+        if hosts.is_none_or(|hosts| certificate_hosts(hosts) == names) {
+            // Keys written by older versions used default permissions.
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let _ = std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o600));
+            }
+            let key_pem = String::from_utf8_lossy(&key_pem).to_string();
+            let key =
+                KeyPair::from_pem(&key_pem).map_err(|e| format!("TLS_CA_KEY_INVALID: {e}"))?;
+            let issuer = ca_params(&names)?
+                .self_signed(&key)
+                .map_err(|e| format!("TLS_CA_SIGN: {e}"))?;
+            return Ok((issuer, key, String::from_utf8_lossy(&cert_pem).to_string()));
+        }
+    }
+
+    let names = certificate_hosts(hosts.unwrap_or_default());
+    let key = KeyPair::generate().map_err(|e| format!("TLS_CA_KEYGEN: {e}"))?;
+    let cert = ca_params(&names)?
+        .self_signed(&key)
+        .map_err(|e| format!("TLS_CA_SIGN: {e}"))?;
+    let cert_pem = cert.pem();
+    write_file_atomic_private(&key_path, key.serialize_pem().as_bytes())?;
+    write_file_atomic(&cert_path, cert_pem.as_bytes())?;
+    write_file_atomic(&names_path, names.join("\n").as_bytes())?;
+    tracing::info!(
+        path = %cert_path.display(),
+        names = %names.join(", "),
+        "已生成网关本地 CA 证书（需重新导出并信任）"
+    );
+    Ok((cert, key, cert_pem))
+}
+
+fn server_params(hosts: &[String]) -> Result<CertificateParams, String> {
+    let mut params = CertificateParams::new(Vec::<String>::new())
+        .map_err(|e| format!("TLS_SERVER_PARAMS: {e}"))?;
+    let mut dn = DistinguishedName::new();
+    dn.push(DnType::CommonName, SERVER_COMMON_NAME);
+    params.distinguished_name = dn;
+    params.key_usages = vec![
+        KeyUsagePurpose::DigitalSignature,
+        KeyUsagePurpose::KeyEncipherment,
+    ];
+    params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+
+    for host in certificate_hosts(hosts) {
+        let san = match host.parse::<IpAddr>() {
+            Ok(ip) => SanType::IpAddress(ip),
+            Err(_) => SanType::DnsName(
+                host.as_str()
+                    .try_into()
+                    .map_err(|e| format!("TLS_SERVER_SAN: invalid host {host}: {e}"))?,
+            ),
+        };
+        params.subject_alt_names.push(san);
+    }
+    Ok(params)
+}
+
+/// Builds the rustls server config for the gateway; `hosts` are extra SAN entries (listen host,
+/// WSL host address, LAN addresses, `gateway_tls_hosts`), see `listen::tls_hosts`.
+pub(crate) fn server_config(
+    app: &tauri::AppHandle,
+    hosts: &[String],
+) -> Result<Arc<rustls::ServerConfig>, String> {
+    let (issuer, issuer_key, _) = load_or_create_ca(app, Some(hosts))?;
+    let key = KeyPair::generate().map_err(|e| format!("TLS_SERVER_KEYGEN: {e}"))?;
+    let cert = server_params(hosts)?
+        .signed_by(&key, &issuer, &issuer_key)
+        .map_err(|e| format!("TLS_SERVER_SIGN: {e}"))?;
+
+    // Leaf only: clients anchor on the exported CA (same DN + key), not on the re-signed copy.
+    let chain: Vec<CertificateDer<'static>> = vec![cert.der().clone()];
+    let key_der = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.serialize_der()));
+
+    let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| format!("TLS_SERVER_CONFIG: {e}"))?
+    .with_no_client_auth()
+    .with_single_cert(chain, key_der)
+    .map_err(|e| format!("TLS_SERVER_CONFIG: {e}"))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+pub(crate) fn ca_export(app: &tauri::AppHandle) -> Result<GatewayTlsCaExport, String> {
+    let (_, _, pem) = load_or_create_ca(app, None)?;
+    let path = tls_dir(app)?.join(CA_CERT_FILE_NAME);
+    Ok(GatewayTlsCaExport {
+        path: path.to_string_lossy().to_string(),
+        pem,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ca_params, certificate_hosts, server_params};
+    use rcgen::{GeneralSubtree, SanType};
+
+    #[test]
+    fn server_sans_skip_wildcards_and_duplicates() {
+        let params = server_params(&[
+            "0.0.0.0".to_string(),
+            "127.0.0.1".to_string(),
+            "172.28.112.1".to_string(),
+            "devbox.local".to_string(),
+        ])
+        .unwrap();
+        let ips = params
+            .subject_alt_names
+            .iter()
+            .filter(|san| matches!(san, SanType::IpAddress(_)))
+            .count();
+        assert_eq!(ips, 3);
+        assert_eq!(params.subject_alt_names.len(), 5);
+    }
+
+    #[test]
+    fn ca_name_constraints_cover_only_certificate_hosts() {
+        let hosts = certificate_hosts(&["0.0.0.0".to_string(), "192.168.1.20".to_string()]);
+        assert_eq!(hosts, ["localhost", "127.0.0.1", "::1", "192.168.1.20"]);
+
+        let params = ca_params(&hosts).unwrap();
+        let constraints = params.name_constraints.unwrap();
+        assert!(constraints.excluded_subtrees.is_empty());
+        assert_eq!(
+            constraints.permitted_subtrees,
+            vec![
+                GeneralSubtree::DnsName("localhost".to_string()),
+                GeneralSubtree::IpAddress("127.0.0.1/32".parse().unwrap()),
+                GeneralSubtree::IpAddress("::1/128".parse().unwrap()),
+                GeneralSubtree::IpAddress("192.168.1.20/32".parse().unwrap()),
+            ]
+        );
+    }
+}
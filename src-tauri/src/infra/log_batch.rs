@@ -0,0 +1,61 @@
+//! Usage: Time-windowed batch receive shared by the request / attempt log writers.
+//!
+//! The writer blocks for the first item, then keeps collecting for up to [`FLUSH_INTERVAL`] (or until
+//! the batch is full) so that bursts of agent traffic are persisted in one transaction instead of
+//! one transaction per row.
+
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+pub(super) const FLUSH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Fills `buffer` with up to `max` items; returns `false` once the channel is closed and drained.
+pub(super) fn recv_batch<T: Send>(
+    rx: &mut mpsc::Receiver<T>,
+    buffer: &mut Vec<T>,
+    max: usize,
+) -> bool {
+    let Some(first) = rx.blocking_recv() else {
+        return false;
+    };
+    buffer.push(first);
+
+    let deadline = tokio::time::Instant::now() + FLUSH_INTERVAL;
+    tauri::async_runtime::block_on(async {
+        while buffer.len() < max {
+            let limit = max - buffer.len();
+            match tokio::time::timeout_at(deadline, rx.recv_many(buffer, limit)).await {
+                // 0 = 通道已关闭；超时 = 窗口结束，先落盘已收集的部分
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
+    });
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_until_full_or_closed() {
+        let (tx, mut rx) = mpsc::channel::<u32>(16);
+        for i in 0..5 {
+            tx.try_send(i).unwrap();
+        }
+
+        let mut buffer = Vec::new();
+        assert!(recv_batch(&mut rx, &mut buffer, 3));
+        assert_eq!(buffer, vec![0, 1, 2]);
+
+        buffer.clear();
+        drop(tx);
+        assert!(recv_batch(&mut rx, &mut buffer, 3));
+        assert_eq!(buffer, vec![3, 4]);
+
+        buffer.clear();
+        assert!(!recv_batch(&mut rx, &mut buffer, 3));
+        assert!(buffer.is_empty());
+    }
+}
@@ -9,13 +9,22 @@ pub(crate) mod codex_config;
 pub(crate) mod codex_paths;
 pub(crate) mod data_management;
 pub(crate) mod db;
+pub(crate) mod diagnostic_bundle;
+pub(crate) mod gateway_tls;
+pub(crate) mod issue_draft;
+mod log_batch;
 pub(crate) mod mcp_sync;
 pub(crate) mod model_price_aliases;
 pub(crate) mod model_prices;
 pub(crate) mod model_prices_sync;
+pub(crate) mod notice_channels;
+pub(crate) mod notice_history;
 pub(crate) mod prompt_sync;
 pub(crate) mod provider_circuit_breakers;
 pub(crate) mod request_attempt_logs;
 pub(crate) mod request_logs;
+pub(crate) mod request_sse_captures;
+pub(crate) mod response_cache_store;
 pub(crate) mod settings;
+pub(crate) mod usage_rollups;
 pub(crate) mod wsl;
@@ -21,6 +21,18 @@ pub struct ModelPricesSyncReport {
     pub total: u32,
 }
 
+impl ModelPricesSyncReport {
+    pub fn skipped_low_power() -> Self {
+        Self {
+            status: "skipped_low_power".to_string(),
+            inserted: 0,
+            updated: 0,
+            skipped: 0,
+            total: 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 struct BasellmCacheMeta {
@@ -0,0 +1,252 @@
+//! Usage: External notice delivery (webhook / Telegram / Slack / Feishu / DingTalk).
+//!
+//! 用法：
+//! - `notice::dispatch` 负责按事件类型筛选渠道并异步投递；这里只负责单个渠道的请求构造与发送
+//! - 投递失败只记录日志，不影响系统通知
+
+use crate::notice::NoticeEventPayload;
+use crate::settings::{NoticeChannelConfig, NoticeChannelKind, NoticeEventKind};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const TELEGRAM_DEFAULT_API_BASE: &str = "https://api.telegram.org";
+const ERROR_BODY_MAX_CHARS: usize = 300;
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+fn client() -> reqwest::Client {
+    CLIENT
+        .get_or_init(|| {
+            reqwest::Client::builder()
+                .timeout(DELIVERY_TIMEOUT)
+                .build()
+                .unwrap_or_else(|_| reqwest::Client::new())
+        })
+        .clone()
+}
+
+fn plain_text(payload: &NoticeEventPayload) -> String {
+    if payload.body.trim().is_empty() {
+        return payload.title.clone();
+    }
+    format!("{}\n{}", payload.title, payload.body)
+}
+
+fn hmac_sha256_base64(key: &[u8], message: &[u8]) -> Result<String, String> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key).map_err(|e| format!("NOTICE_CHANNEL_SIGN: {e}"))?;
+    mac.update(message);
+    Ok(base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes()))
+}
+
+fn percent_encode_base64(value: &str) -> String {
+    value
+        .replace('+', "%2B")
+        .replace('/', "%2F")
+        .replace('=', "%3D")
+}
+
+fn feishu_sign(secret: &str, timestamp_secs: i64) -> Result<String, String> {
+    // Feishu: key = "{timestamp}\n{secret}", message = empty.
+    hmac_sha256_base64(format!("{timestamp_secs}\n{secret}").as_bytes(), b"")
+}
+
+fn dingtalk_sign(secret: &str, timestamp_ms: i64) -> Result<String, String> {
+    // DingTalk: key = secret, message = "{timestamp_ms}\n{secret}".
+    let sign = hmac_sha256_base64(
+        secret.as_bytes(),
+        format!("{timestamp_ms}\n{secret}").as_bytes(),
+    )?;
+    Ok(percent_encode_base64(&sign))
+}
+
+fn truncate_for_error(text: &str) -> String {
+    text.chars().take(ERROR_BODY_MAX_CHARS).collect()
+}
+
+fn build_request(
+    channel: &NoticeChannelConfig,
+    event: NoticeEventKind,
+    payload: &NoticeEventPayload,
+    now_unix_ms: i64,
+) -> Result<(String, serde_json::Value), String> {
+    let url = channel.url.trim().to_string();
+    let text = plain_text(payload);
+
+    match channel.kind {
+        NoticeChannelKind::Webhook => Ok((
+            url,
+            serde_json::json!({
+                "event": event.as_str(),
+                "level": payload.level,
+                "title": payload.title,
+                "body": payload.body,
+                "ts": now_unix_ms / 1000,
+            }),
+        )),
+        NoticeChannelKind::Slack => Ok((url, serde_json::json!({ "text": text }))),
+        NoticeChannelKind::Telegram => {
+            let base = if url.is_empty() {
+                TELEGRAM_DEFAULT_API_BASE.to_string()
+            } else {
+                url.trim_end_matches('/').to_string()
+            };
+            Ok((
+                format!("{base}/bot{}/sendMessage", channel.bot_token.trim()),
+                serde_json::json!({
+                    "chat_id": channel.chat_id.trim(),
+                    "text": text,
+                    "disable_web_page_preview": true,
+                }),
+            ))
+        }
+        NoticeChannelKind::Feishu => {
+            let mut body = serde_json::json!({
+                "msg_type": "text",
+                "content": { "text": text },
+            });
+            let secret = channel.secret.trim();
+            if !secret.is_empty() {
+                let timestamp = now_unix_ms / 1000;
+                body["timestamp"] = serde_json::Value::String(timestamp.to_string());
+                body["sign"] = serde_json::Value::String(feishu_sign(secret, timestamp)?);
+            }
+            Ok((url, body))
+        }
+        NoticeChannelKind::Dingtalk => {
+            let secret = channel.secret.trim();
+            let url = if secret.is_empty() {
+                url
+            } else {
+                let sign = dingtalk_sign(secret, now_unix_ms)?;
+                let sep = if url.contains('?') { '&' } else { '?' };
+                format!("{url}{sep}timestamp={now_unix_ms}&sign={sign}")
+            };
+            Ok((
+                url,
+                serde_json::json!({
+                    "msgtype": "text",
+                    "text": { "content": text },
+                }),
+            ))
+        }
+    }
+}
+
+// Feishu / DingTalk return HTTP 200 with a non-zero code on failure.
+fn check_response_body(kind: NoticeChannelKind, body: &str) -> Result<(), String> {
+    let code_field = match kind {
+        NoticeChannelKind::Feishu => "code",
+        NoticeChannelKind::Dingtalk => "errcode",
+        NoticeChannelKind::Telegram => {
+            let value: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+            if value.get("ok").and_then(|v| v.as_bool()) == Some(false) {
+                return Err(format!(
+                    "NOTICE_CHANNEL_REJECTED: {}",
+                    truncate_for_error(body)
+                ));
+            }
+            return Ok(());
+        }
+        NoticeChannelKind::Webhook | NoticeChannelKind::Slack => return Ok(()),
+    };
+
+    let value: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+    match value.get(code_field).and_then(|v| v.as_i64()) {
+        Some(0) | None => Ok(()),
+        Some(_) => Err(format!(
+            "NOTICE_CHANNEL_REJECTED: {}",
+            truncate_for_error(body)
+        )),
+    }
+}
+
+pub(crate) async fn deliver(
+    channel: &NoticeChannelConfig,
+    event: NoticeEventKind,
+    payload: &NoticeEventPayload,
+) -> Result<(), String> {
+    let now_unix_ms = crate::shared::time::now_unix_millis();
+    let (url, body) = build_request(channel, event, payload, now_unix_ms)?;
+
+    let resp = client()
+        .post(&url)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("NOTICE_CHANNEL_SEND: {e}"))?;
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(format!(
+            "NOTICE_CHANNEL_HTTP_{}: {}",
+            status.as_u16(),
+            truncate_for_error(&text)
+        ));
+    }
+    check_response_body(channel.kind, &text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_request, check_response_body, dingtalk_sign};
+    use crate::notice::{NoticeEventPayload, NoticeLevel};
+    use crate::settings::{NoticeChannelConfig, NoticeChannelKind, NoticeEventKind};
+
+    fn payload() -> NoticeEventPayload {
+        NoticeEventPayload {
+            level: NoticeLevel::Warning,
+            title: "AIO Coding Hub · t".to_string(),
+            body: "b".to_string(),
+            action: None,
+            local_detail: None,
+        }
+    }
+
+    #[test]
+    fn telegram_request_uses_bot_token_path() {
+        let channel = NoticeChannelConfig {
+            id: "tg".to_string(),
+            enabled: true,
+            kind: NoticeChannelKind::Telegram,
+            bot_token: "123:abc".to_string(),
+            chat_id: "42".to_string(),
+            ..Default::default()
+        };
+        let (url, body) = build_request(&channel, NoticeEventKind::General, &payload(), 0).unwrap();
+        assert_eq!(url, "https://api.telegram.org/bot123:abc/sendMessage");
+        assert_eq!(body["chat_id"], "42");
+        assert_eq!(body["text"], "AIO Coding Hub · t\nb");
+    }
+
+    #[test]
+    fn local_detail_is_never_delivered() {
+        let payload = payload().with_local_detail("upstream body excerpt".to_string());
+        for kind in [NoticeChannelKind::Webhook, NoticeChannelKind::Slack] {
+            let channel = NoticeChannelConfig {
+                kind,
+                url: "https://hooks.example.com/x".to_string(),
+                ..Default::default()
+            };
+            let (_, body) = build_request(&channel, NoticeEventKind::General, &payload, 0).unwrap();
+            assert!(!body.to_string().contains("upstream body excerpt"));
+        }
+    }
+
+    #[test]
+    fn dingtalk_sign_is_url_safe() {
+        let sign = dingtalk_sign("SECxxx", 1_700_000_000_000).unwrap();
+        assert!(!sign.contains('+') && !sign.contains('/') && !sign.contains('='));
+    }
+
+    #[test]
+    fn feishu_non_zero_code_is_error() {
+        assert!(check_response_body(NoticeChannelKind::Feishu, r#"{"code":0}"#).is_ok());
+        assert!(check_response_body(NoticeChannelKind::Feishu, r#"{"code":19021}"#).is_err());
+        assert!(check_response_body(NoticeChannelKind::Dingtalk, r#"{"errcode":310000}"#).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+//! Usage: Notice history persistence (one row per delivery target: system notification or external channel).
+
+use crate::app_state::{ensure_db_ready, DbInitState};
+use crate::shared::time::now_unix_seconds;
+use crate::{blocking, db};
+use rusqlite::params;
+use serde::Serialize;
+use tauri::Manager;
+
+const KEEP_ROWS: i64 = 2000;
+pub(crate) const SYSTEM_CHANNEL_ID: &str = "system";
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct NoticeHistoryRow {
+    pub(crate) id: i64,
+    pub(crate) created_at: i64,
+    pub(crate) event_kind: String,
+    pub(crate) level: String,
+    pub(crate) title: String,
+    pub(crate) body: String,
+    pub(crate) channel_id: String,
+    pub(crate) channel_kind: String,
+    pub(crate) status: String,
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct NoticeDeliveryRecord {
+    pub(crate) channel_id: String,
+    pub(crate) channel_kind: String,
+    pub(crate) error: Option<String>,
+}
+
+pub(crate) struct NoticeHistoryEntry {
+    pub(crate) event_kind: &'static str,
+    pub(crate) level: &'static str,
+    pub(crate) title: String,
+    pub(crate) body: String,
+    pub(crate) deliveries: Vec<NoticeDeliveryRecord>,
+}
+
+fn insert_entry(db: &db::Db, entry: &NoticeHistoryEntry) -> Result<(), String> {
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
+
+    let now = now_unix_seconds();
+    for delivery in &entry.deliveries {
+        let status = if delivery.error.is_some() {
+            "failed"
+        } else {
+            "delivered"
+        };
+        tx.execute(
+            r#"
+INSERT INTO notice_history(
+  created_at,
+  event_kind,
+  level,
+  title,
+  body,
+  channel_id,
+  channel_kind,
+  status,
+  error
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+"#,
+            params![
+                now,
+                entry.event_kind,
+                entry.level,
+                entry.title,
+                entry.body,
+                delivery.channel_id,
+                delivery.channel_kind,
+                status,
+                delivery.error,
+            ],
+        )
+        .map_err(|e| format!("DB_ERROR: failed to insert notice_history: {e}"))?;
+    }
+
+    tx.execute(
+        "DELETE FROM notice_history WHERE id <= (SELECT id FROM notice_history ORDER BY id DESC LIMIT 1 OFFSET ?1)",
+        params![KEEP_ROWS],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to prune notice_history: {e}"))?;
+
+    tx.commit()
+        .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+    Ok(())
+}
+
+// Best-effort: history failures must never break notice delivery.
+pub(crate) async fn record(app: &tauri::AppHandle, entry: NoticeHistoryEntry) {
+    if entry.deliveries.is_empty() {
+        return;
+    }
+    let db_state = app.state::<DbInitState>();
+    let db = match ensure_db_ready(app.clone(), db_state.inner()).await {
+        Ok(db) => db,
+        Err(err) => {
+            tracing::warn!("通知历史写入失败（数据库不可用）: {}", err);
+            return;
+        }
+    };
+    let result = blocking::run("notice_history_record", move || insert_entry(&db, &entry)).await;
+    if let Err(err) = result {
+        tracing::warn!("通知历史写入失败: {}", err);
+    }
+}
+
+pub(crate) fn list(
+    db: &db::Db,
+    limit: usize,
+    before_id: Option<i64>,
+) -> Result<Vec<NoticeHistoryRow>, String> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  id,
+  created_at,
+  event_kind,
+  level,
+  title,
+  body,
+  channel_id,
+  channel_kind,
+  status,
+  error
+FROM notice_history
+WHERE ?1 IS NULL OR id < ?1
+ORDER BY id DESC
+LIMIT ?2
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare notice_history query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![before_id, limit as i64], |row| {
+            Ok(NoticeHistoryRow {
+                id: row.get("id")?,
+                created_at: row.get("created_at")?,
+                event_kind: row.get("event_kind")?,
+                level: row.get("level")?,
+                title: row.get("title")?,
+                body: row.get("body")?,
+                channel_id: row.get("channel_id")?,
+                channel_kind: row.get("channel_kind")?,
+                status: row.get("status")?,
+                error: row.get("error")?,
+            })
+        })
+        .map_err(|e| format!("DB_ERROR: failed to query notice_history: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("DB_ERROR: failed to read notice_history row: {e}"))?);
+    }
+    Ok(items)
+}
+
+pub(crate) fn clear(db: &db::Db) -> Result<usize, String> {
+    let conn = db.open_connection()?;
+    conn.execute("DELETE FROM notice_history", [])
+        .map_err(|e| format!("DB_ERROR: failed to clear notice_history: {e}"))
+}
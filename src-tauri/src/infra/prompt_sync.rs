@@ -1,4 +1,13 @@
 //! Usage: Sync/backup/restore prompt instruction files for supported CLIs (infra adapter).
+//!
+//! Codex `AGENTS.md` is only touched inside a managed block (see `codex_agents`), so user-authored
+//! content around it survives enable / disable.
+
+mod codex_agents;
+
+pub use codex_agents::{
+    codex_project_agents_get, codex_project_agents_set, CodexProjectAgentsState,
+};
 
 use crate::app_paths;
 use crate::codex_paths;
@@ -115,6 +124,14 @@ fn try_migrate_legacy_prompt_sync_dir(
     Ok(false)
 }
 
+/// The prompt as imported from the target file: for Codex only the managed block when present.
+pub fn target_prompt_content(cli_key: &str, file_content: String) -> String {
+    if cli_key != "codex" {
+        return file_content;
+    }
+    codex_agents::managed_content(&file_content).unwrap_or(file_content)
+}
+
 pub fn read_target_bytes(app: &tauri::AppHandle, cli_key: &str) -> Result<Option<Vec<u8>>, String> {
     let path = prompt_target_path(app, cli_key)?;
     read_optional_file(&path)
@@ -275,13 +292,21 @@ fn restore_from_manifest(
     let cli_key = manifest.cli_key.as_str();
     validate_cli_key(cli_key)?;
 
+    let target_path = PathBuf::from(&manifest.file.path);
+    if cli_key == "codex" {
+        codex_agents::remove_block(&target_path, !manifest.file.existed)?;
+        // A file written by the former whole-file sync is left empty: fall back to its backup.
+        if !manifest.file.existed || !codex_agents::is_blank(&target_path)? {
+            return Ok(());
+        }
+    }
+
     let root = prompt_sync_root_dir(app, cli_key)?;
     let files_dir = prompt_sync_files_dir(&root);
     let safety_dir = prompt_sync_safety_dir(&root);
     std::fs::create_dir_all(&safety_dir)
         .map_err(|e| format!("failed to create {}: {e}", safety_dir.display()))?;
 
-    let target_path = PathBuf::from(&manifest.file.path);
     let ts = now_unix_seconds();
 
     if manifest.file.existed {
@@ -371,8 +396,12 @@ pub fn apply_enabled_prompt(
     let target_path = prompt_target_path(app, cli_key)?;
     manifest.file.path = target_path.to_string_lossy().to_string();
 
-    let bytes = prompt_content_to_bytes(content);
-    write_file_atomic_if_changed(&target_path, &bytes)?;
+    if cli_key == "codex" {
+        codex_agents::write_block(&target_path, content)?;
+    } else {
+        let bytes = prompt_content_to_bytes(content);
+        write_file_atomic_if_changed(&target_path, &bytes)?;
+    }
 
     manifest.enabled = true;
     manifest.applied_prompt_id = Some(prompt_id);
@@ -395,22 +424,28 @@ pub fn restore_disabled_prompt(app: &tauri::AppHandle, cli_key: &str) -> Result<
         let target_path = prompt_target_path(app, cli_key)?;
         let ts = now_unix_seconds();
 
-        let backup_rel = target_path
-            .file_name()
-            .and_then(|v| v.to_str())
-            .and_then(|file_name| {
-                let name = file_name.to_string();
-                let backup_path = files_dir.join(&name);
-                if !backup_path.exists() {
-                    return None;
-                }
-
-                let bytes = std::fs::read(&backup_path).ok()?;
-                write_file_atomic(&target_path, &bytes).ok()?;
-                Some(name)
-            });
-
-        if backup_rel.is_none() && target_path.exists() {
+        let backup_rel = if cli_key == "codex" {
+            // Only the managed block is ours; the rest of AGENTS.md stays untouched.
+            codex_agents::remove_block(&target_path, false)?;
+            None
+        } else {
+            target_path
+                .file_name()
+                .and_then(|v| v.to_str())
+                .and_then(|file_name| {
+                    let name = file_name.to_string();
+                    let backup_path = files_dir.join(&name);
+                    if !backup_path.exists() {
+                        return None;
+                    }
+
+                    let bytes = std::fs::read(&backup_path).ok()?;
+                    write_file_atomic(&target_path, &bytes).ok()?;
+                    Some(name)
+                })
+        };
+
+        if backup_rel.is_none() && cli_key != "codex" && target_path.exists() {
             if let Ok(bytes) = std::fs::read(&target_path) {
                 let safe_name = format!("{ts}_prompt_keep_current_no_manifest");
                 let safe_path = safety_dir.join(safe_name);
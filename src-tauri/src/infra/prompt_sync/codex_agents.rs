@@ -0,0 +1,204 @@
+//! Usage: Managed block inside Codex `AGENTS.md` (global `$CODEX_HOME/AGENTS.md` and per-project).
+//!
+//! 用法：
+//! - 启用的 Codex 提示词只写入 `MANAGED_BEGIN` / `MANAGED_END` 标记之间，标记之外的用户内容原样保留
+//! - 停用时只移除托管块；文件在启用前不存在且移除后为空时删除文件
+//! - 文件内容与提示词完全相同（未加标记的旧同步结果）时直接收编为托管块，避免内容重复
+//! - `codex_project_agents_get/set(project_dir, ..)`：读写项目根目录 `AGENTS.md` 的托管块（不入库，不做备份）
+
+use crate::shared::fs::{read_optional_file, write_file_atomic_if_changed};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+const MANAGED_BEGIN: &str =
+    "<!-- aio-coding-hub:begin (managed, edits inside this block are overwritten) -->";
+const MANAGED_END: &str = "<!-- aio-coding-hub:end -->";
+const AGENTS_MD_FILE_NAME: &str = "AGENTS.md";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodexProjectAgentsState {
+    pub path: String,
+    pub exists: bool,
+    pub managed_content: Option<String>,
+    // Non-whitespace content outside the managed block.
+    pub has_user_content: bool,
+}
+
+/// Byte range of the managed block: from the begin marker through the end marker's line break.
+fn block_range(text: &str) -> Option<(usize, usize)> {
+    let start = text.find(MANAGED_BEGIN)?;
+    let end_marker = start + text[start..].find(MANAGED_END)?;
+    let mut end = end_marker + MANAGED_END.len();
+    if text[end..].starts_with("\r\n") {
+        end += 2;
+    } else if text[end..].starts_with('\n') {
+        end += 1;
+    }
+    Some((start, end))
+}
+
+pub(super) fn managed_content(text: &str) -> Option<String> {
+    let (start, end) = block_range(text)?;
+    let inner = &text[start + MANAGED_BEGIN.len()..end];
+    let inner = inner.trim_end().strip_suffix(MANAGED_END).unwrap_or(inner);
+    Some(inner.trim().to_string())
+}
+
+fn render_block(content: &str) -> String {
+    format!(
+        "{MANAGED_BEGIN}\n{}\n{MANAGED_END}\n",
+        content.trim_matches('\u{feff}').trim()
+    )
+}
+
+/// Writes `content` into the managed block, keeping everything outside of it.
+pub(super) fn upsert_block(existing: &str, content: &str) -> String {
+    let block = render_block(content);
+    if let Some((start, end)) = block_range(existing) {
+        return format!("{}{block}{}", &existing[..start], &existing[end..]);
+    }
+
+    let existing = existing.trim_matches('\u{feff}');
+    if existing.trim().is_empty() || existing.trim() == content.trim() {
+        return block;
+    }
+    format!("{}\n\n{block}", existing.trim_end())
+}
+
+/// Removes the managed block; surrounding user content is kept as-is.
+pub(super) fn strip_block(text: &str) -> String {
+    let Some((start, end)) = block_range(text) else {
+        return text.to_string();
+    };
+    let before = text[..start].trim_end();
+    let after = text[end..].trim_start_matches(['\r', '\n']);
+    match (before.is_empty(), after.is_empty()) {
+        (true, _) => after.to_string(),
+        (false, true) => format!("{before}\n"),
+        (false, false) => format!("{before}\n\n{after}"),
+    }
+}
+
+fn read_utf8(path: &Path) -> Result<Option<String>, String> {
+    let Some(bytes) = read_optional_file(path)? else {
+        return Ok(None);
+    };
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|_| format!("PROMPT_SYNC_INVALID_UTF8: {}", path.display()))
+}
+
+/// Applies the block to `path`.
+pub(super) fn write_block(path: &Path, content: &str) -> Result<(), String> {
+    let existing = read_utf8(path)?.unwrap_or_default();
+    write_file_atomic_if_changed(path, upsert_block(&existing, content).as_bytes())?;
+    Ok(())
+}
+
+/// Removes the block from `path`; deletes the file when nothing else is left and `remove_if_empty`.
+pub(super) fn remove_block(path: &Path, remove_if_empty: bool) -> Result<(), String> {
+    let Some(existing) = read_utf8(path)? else {
+        return Ok(());
+    };
+    let next = strip_block(&existing);
+    if next.trim().is_empty() && remove_if_empty {
+        return std::fs::remove_file(path)
+            .map_err(|e| format!("failed to remove {}: {e}", path.display()));
+    }
+    write_file_atomic_if_changed(path, next.as_bytes())?;
+    Ok(())
+}
+
+pub(super) fn is_blank(path: &Path) -> Result<bool, String> {
+    Ok(read_utf8(path)?.is_none_or(|text| text.trim().is_empty()))
+}
+
+fn project_agents_path(project_dir: &str) -> Result<PathBuf, String> {
+    let project_dir = project_dir.trim();
+    if project_dir.is_empty() {
+        return Err("SEC_INVALID_INPUT: project_dir is required".to_string());
+    }
+    let dir = PathBuf::from(project_dir);
+    if !dir.is_absolute() {
+        return Err(format!(
+            "SEC_INVALID_INPUT: project_dir must be an absolute path: {project_dir}"
+        ));
+    }
+    if !dir.is_dir() {
+        return Err(format!(
+            "SEC_INVALID_INPUT: project_dir is not a directory: {project_dir}"
+        ));
+    }
+    Ok(dir.join(AGENTS_MD_FILE_NAME))
+}
+
+pub fn codex_project_agents_get(project_dir: &str) -> Result<CodexProjectAgentsState, String> {
+    let path = project_agents_path(project_dir)?;
+    let text = read_utf8(&path)?;
+    Ok(CodexProjectAgentsState {
+        path: path.to_string_lossy().to_string(),
+        exists: text.is_some(),
+        managed_content: text.as_deref().and_then(managed_content),
+        has_user_content: text
+            .as_deref()
+            .is_some_and(|text| !strip_block(text).trim().is_empty()),
+    })
+}
+
+/// `content = None` (or blank) removes the managed block.
+pub fn codex_project_agents_set(
+    project_dir: &str,
+    content: Option<String>,
+) -> Result<CodexProjectAgentsState, String> {
+    let path = project_agents_path(project_dir)?;
+    match content.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(content) => write_block(&path, content)?,
+        None => remove_block(&path, true)?,
+    }
+    codex_project_agents_get(project_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upsert_and_strip_preserve_user_content() {
+        let user = "# Team rules\n\nUse pnpm.\n";
+        let applied = upsert_block(user, "Be terse.");
+        assert_eq!(
+            applied,
+            format!("# Team rules\n\nUse pnpm.\n\n{MANAGED_BEGIN}\nBe terse.\n{MANAGED_END}\n")
+        );
+        assert_eq!(managed_content(&applied).as_deref(), Some("Be terse."));
+
+        // edits after the block survive a re-apply
+        let edited = format!("{applied}\nLocal notes\n");
+        let reapplied = upsert_block(&edited, "Be very terse.");
+        assert!(reapplied.starts_with("# Team rules\n\nUse pnpm.\n\n"));
+        assert!(reapplied.ends_with(&format!("{MANAGED_END}\n\nLocal notes\n")));
+        assert_eq!(
+            managed_content(&reapplied).as_deref(),
+            Some("Be very terse.")
+        );
+
+        assert_eq!(
+            strip_block(&reapplied),
+            "# Team rules\n\nUse pnpm.\n\nLocal notes\n"
+        );
+        assert_eq!(strip_block(&applied), user);
+        assert_eq!(strip_block(user), user);
+    }
+
+    #[test]
+    fn upsert_adopts_unmarked_copy_of_the_prompt() {
+        let block = upsert_block("Be terse.\n", "Be terse.");
+        assert_eq!(
+            block,
+            format!("{MANAGED_BEGIN}\nBe terse.\n{MANAGED_END}\n")
+        );
+        assert_eq!(upsert_block("", "Be terse."), block);
+        assert_eq!(strip_block(&block), "");
+        assert_eq!(managed_content("no markers"), None);
+    }
+}
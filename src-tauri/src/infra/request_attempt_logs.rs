@@ -1,8 +1,9 @@
 //! Usage: Attempt log persistence (sqlite buffered writer, queries, and cleanup).
 
+use super::log_batch;
 use crate::shared::time::now_unix_seconds;
 use crate::{db, settings};
-use rusqlite::{params, ErrorCode};
+use rusqlite::{params, ErrorCode, TransactionBehavior};
 use serde::Serialize;
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
@@ -133,37 +134,21 @@ fn writer_loop(app: tauri::AppHandle, db: db::Db, mut rx: mpsc::Receiver<Request
     let mut last_cleanup = now.checked_sub(CLEANUP_MIN_INTERVAL).unwrap_or(now);
     let mut cleanup_due = last_cleanup == now;
 
-    while let Some(item) = rx.blocking_recv() {
-        buffer.push(item);
-
-        while buffer.len() < WRITE_BATCH_MAX {
-            match rx.try_recv() {
-                Ok(next) => buffer.push(next),
-                Err(tokio::sync::mpsc::error::TryRecvError::Empty) => break,
-                Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => break,
-            }
-        }
-
+    while log_batch::recv_batch(&mut rx, &mut buffer, WRITE_BATCH_MAX) {
         if let Err(err) = insert_batch_with_retries(&db, &buffer) {
             tracing::error!(error = %err.message, "尝试日志批量插入失败");
         }
         buffer.clear();
 
         if cleanup_due || last_cleanup.elapsed() >= CLEANUP_MIN_INTERVAL {
-            let retention_days = settings::log_retention_days_fail_open(&app);
-            if let Err(err) = cleanup_expired(&db, retention_days) {
+            let policy = settings::attempt_log_retention_fail_open(&app);
+            if let Err(err) = cleanup_with_policy(&db, policy) {
                 tracing::warn!("尝试日志清理失败: {}", err);
             }
             cleanup_due = false;
             last_cleanup = Instant::now();
         }
     }
-
-    if !buffer.is_empty() {
-        if let Err(err) = insert_batch_with_retries(&db, &buffer) {
-            tracing::error!(error = %err.message, "尝试日志最终批量插入失败");
-        }
-    }
 }
 
 fn insert_batch_with_retries(
@@ -191,8 +176,10 @@ fn insert_batch_once(db: &db::Db, items: &[RequestAttemptLogInsert]) -> Result<(
     }
 
     let mut conn = db.open_connection().map_err(DbWriteError::other)?;
+    // IMMEDIATE: take the write lock up front so SQLITE_BUSY surfaces here (and is retried)
+    // instead of failing mid-batch when a deferred read transaction tries to upgrade.
     let tx = conn
-        .transaction()
+        .transaction_with_behavior(TransactionBehavior::Immediate)
         .map_err(|e| DbWriteError::from_rusqlite("failed to start transaction", e))?;
 
     {
@@ -258,7 +245,7 @@ ON CONFLICT(trace_id, attempt_index) DO UPDATE SET
     Ok(())
 }
 
-pub fn cleanup_expired(db: &db::Db, retention_days: u32) -> Result<u64, String> {
+fn cleanup_expired(db: &db::Db, retention_days: u32) -> Result<u64, String> {
     if retention_days == 0 {
         return Err("SEC_INVALID_INPUT: log_retention_days must be >= 1".to_string());
     }
@@ -277,6 +264,47 @@ pub fn cleanup_expired(db: &db::Db, retention_days: u32) -> Result<u64, String>
     Ok(changed as u64)
 }
 
+/// Applies the attempt log retention policy: expire by `retention_days`, then compact attempts
+/// older than `compact_after_days` that belong to successful traces.
+pub fn cleanup_with_policy(
+    db: &db::Db,
+    policy: settings::AttemptLogRetentionConfig,
+) -> Result<u64, String> {
+    let mut changed = cleanup_expired(db, policy.retention_days)?;
+    if policy.compact_after_days == 0 || policy.compact_after_days >= policy.retention_days {
+        return Ok(changed);
+    }
+
+    let now = now_unix_seconds();
+    let cutoff = now.saturating_sub((policy.compact_after_days as i64).saturating_mul(86400));
+
+    let conn = db.open_connection()?;
+    changed = changed.saturating_add(compact_successful_before(&conn, cutoff)?);
+    Ok(changed)
+}
+
+fn compact_successful_before(conn: &rusqlite::Connection, cutoff: i64) -> Result<u64, String> {
+    // Successful traces keep their attempt summary in `request_logs.attempts_json`; only failed
+    // traces need the per-attempt rows for troubleshooting.
+    let changed = conn
+        .execute(
+            r#"
+DELETE FROM request_attempt_logs
+WHERE created_at < ?1
+  AND EXISTS (
+    SELECT 1 FROM request_logs r
+    WHERE r.trace_id = request_attempt_logs.trace_id
+      AND r.status BETWEEN 200 AND 299
+      AND r.error_code IS NULL
+  )
+"#,
+            params![cutoff],
+        )
+        .map_err(|e| format!("DB_ERROR: failed to compact request_attempt_logs: {e}"))?;
+
+    Ok(changed as u64)
+}
+
 fn row_to_log(row: &rusqlite::Row<'_>) -> Result<RequestAttemptLog, rusqlite::Error> {
     Ok(RequestAttemptLog {
         id: row.get("id")?,
@@ -347,3 +375,89 @@ LIMIT ?2
     }
     Ok(out)
 }
+
+/// Latency of one successful attempt (stream: time to response headers; non-stream: full body).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttemptLatencySample {
+    pub provider_id: i64,
+    pub duration_ms: i64,
+    pub created_at: i64,
+}
+
+pub fn list_success_latency_samples(
+    db: &db::Db,
+    cli_key: &str,
+    since_unix_seconds: i64,
+    limit: usize,
+) -> Result<Vec<AttemptLatencySample>, String> {
+    validate_cli_key(cli_key)?;
+
+    let limit = limit.clamp(1, 5000);
+    let conn = db.open_connection()?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT provider_id, attempt_duration_ms, created_at
+FROM request_attempt_logs
+WHERE cli_key = ?1
+  AND created_at >= ?2
+  AND outcome = 'success'
+  AND attempt_duration_ms > 0
+ORDER BY created_at DESC
+LIMIT ?3
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare latency sample query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![cli_key, since_unix_seconds, limit as i64], |row| {
+            Ok(AttemptLatencySample {
+                provider_id: row.get(0)?,
+                duration_ms: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })
+        .map_err(|e| format!("DB_ERROR: failed to query latency samples: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| format!("DB_ERROR: failed to read latency sample: {e}"))?);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compact_successful_before;
+    use rusqlite::{params, Connection};
+
+    #[test]
+    fn compaction_keeps_failed_and_recent_traces() {
+        let conn = Connection::open_in_memory().expect("open");
+        conn.execute_batch(
+            r#"
+CREATE TABLE request_logs (trace_id TEXT NOT NULL, status INTEGER, error_code TEXT);
+CREATE TABLE request_attempt_logs (trace_id TEXT NOT NULL, created_at INTEGER NOT NULL);
+INSERT INTO request_logs VALUES ('ok_old', 200, NULL), ('ok_new', 200, NULL);
+INSERT INTO request_logs VALUES ('fail_old', 502, 'GW_UPSTREAM_5XX'), ('err_old', 200, 'GW_STREAM_ABORTED');
+INSERT INTO request_attempt_logs VALUES ('ok_old', 10), ('ok_old', 11), ('ok_new', 200);
+INSERT INTO request_attempt_logs VALUES ('fail_old', 10), ('err_old', 10), ('orphan', 10);
+"#,
+        )
+        .expect("seed");
+
+        let removed = compact_successful_before(&conn, 100).expect("compact");
+        assert_eq!(removed, 2);
+
+        let mut stmt = conn
+            .prepare("SELECT trace_id FROM request_attempt_logs ORDER BY trace_id")
+            .expect("prepare");
+        let left: Vec<String> = stmt
+            .query_map(params![], |row| row.get(0))
+            .expect("query")
+            .collect::<Result<_, _>>()
+            .expect("rows");
+        assert_eq!(left, vec!["err_old", "fail_old", "ok_new", "orphan"]);
+    }
+}
@@ -1,16 +1,18 @@
 //! Usage: Request log persistence (sqlite buffered writer, queries, and cleanup).
 
+use super::log_batch;
 use crate::shared::time::now_unix_seconds;
 use crate::{cost, db, model_price_aliases, settings};
-use rusqlite::{params, params_from_iter, ErrorCode, OptionalExtension};
+use rusqlite::{params, params_from_iter, ErrorCode, OptionalExtension, TransactionBehavior};
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 mod types;
 pub use types::{
-    RequestLogDetail, RequestLogInsert, RequestLogRouteHop, RequestLogSummary,
-    SessionStatsAggregate,
+    ClientUsageAggregate, RequestLogDetail, RequestLogInsert, RequestLogListQuery,
+    RequestLogRouteHop, RequestLogSort, RequestLogSummary, ResponseFixerDryRunAggregate,
+    SessionStatsAggregate, UsageEstimateDriftAggregate,
 };
 
 mod costing;
@@ -22,10 +24,11 @@ pub use cleanup::cleanup_expired;
 mod queries;
 use queries::{final_provider_from_attempts, parse_attempts, validate_cli_key};
 pub use queries::{
-    get_by_id, get_by_trace_id, list_after_id, list_after_id_all, list_recent, list_recent_all,
+    get_by_id, get_by_trace_id, list_after_id, list_after_id_all, list_before, list_before_all,
+    list_by_client_trace_id, list_filtered, list_recent, list_recent_all,
 };
 
-const WRITE_BUFFER_CAPACITY: usize = 512;
+const WRITE_BUFFER_CAPACITY: usize = 1024;
 const WRITE_BATCH_MAX: usize = 50;
 const CLEANUP_MIN_INTERVAL: Duration = Duration::from_secs(10 * 60);
 const INSERT_RETRY_MAX_ATTEMPTS: u32 = 8;
@@ -36,6 +39,11 @@ const COST_MULTIPLIER_CACHE_MAX_ENTRIES: usize = 256;
 const MODEL_PRICE_CACHE_MAX_ENTRIES: usize = 512;
 const CACHE_TTL_SECS: i64 = 5 * 60;
 
+// Small prompts are dominated by hidden upstream overhead (tool schemas, system wrappers).
+const USAGE_DRIFT_MIN_ESTIMATE_TOKENS: i64 = 1_000;
+const USAGE_DRIFT_INFLATED_RATIO: f64 = 1.5;
+const USAGE_DRIFT_MIN_SAMPLES: i64 = 20;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DbWriteErrorKind {
     Busy,
@@ -201,17 +209,7 @@ fn writer_loop(app: tauri::AppHandle, db: db::Db, mut rx: mpsc::Receiver<Request
     let mut cleanup_due = last_cleanup == now;
     let mut cache = InsertBatchCache::default();
 
-    while let Some(item) = rx.blocking_recv() {
-        buffer.push(item);
-
-        while buffer.len() < WRITE_BATCH_MAX {
-            match rx.try_recv() {
-                Ok(next) => buffer.push(next),
-                Err(tokio::sync::mpsc::error::TryRecvError::Empty) => break,
-                Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => break,
-            }
-        }
-
+    while log_batch::recv_batch(&mut rx, &mut buffer, WRITE_BATCH_MAX) {
         if let Err(err) = insert_batch_with_retries(&app, &db, &buffer, &mut cache) {
             tracing::error!(error = %err.message, "请求日志批量插入失败");
         }
@@ -226,12 +224,6 @@ fn writer_loop(app: tauri::AppHandle, db: db::Db, mut rx: mpsc::Receiver<Request
             last_cleanup = Instant::now();
         }
     }
-
-    if !buffer.is_empty() {
-        if let Err(err) = insert_batch_with_retries(&app, &db, &buffer, &mut cache) {
-            tracing::error!(error = %err.message, "请求日志最终批量插入失败");
-        }
-    }
 }
 
 fn insert_batch_with_retries(
@@ -268,8 +260,10 @@ fn insert_batch_once(
     let now_unix = now_unix_seconds();
     let price_aliases = model_price_aliases::read_fail_open(app);
     let mut conn = db.open_connection().map_err(DbWriteError::other)?;
+    // IMMEDIATE: take the write lock up front so SQLITE_BUSY surfaces here (and is retried)
+    // instead of failing mid-batch when a deferred read transaction tries to upgrade.
     let tx = conn
-        .transaction()
+        .transaction_with_behavior(TransactionBehavior::Immediate)
         .map_err(|e| DbWriteError::from_rusqlite("failed to start transaction", e))?;
 
     {
@@ -312,8 +306,12 @@ fn insert_batch_once(
 		  cost_multiplier,
 		  created_at_ms,
 		  created_at,
-		  final_provider_id
-		) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27)
+		  final_provider_id,
+		  client_id,
+		  client_trace_id,
+		  estimated_input_tokens,
+		  final_provider_name
+		) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31)
 		ON CONFLICT(trace_id) DO UPDATE SET
 		  method = excluded.method,
 		  path = excluded.path,
@@ -342,7 +340,11 @@ fn insert_batch_once(
 		    ELSE request_logs.created_at_ms
 		  END,
 		  created_at = CASE WHEN request_logs.created_at = 0 THEN excluded.created_at ELSE request_logs.created_at END,
-		  final_provider_id = excluded.final_provider_id
+		  final_provider_id = excluded.final_provider_id,
+		  final_provider_name = excluded.final_provider_name,
+		  client_id = COALESCE(excluded.client_id, request_logs.client_id),
+		  client_trace_id = COALESCE(excluded.client_trace_id, request_logs.client_trace_id),
+		  estimated_input_tokens = COALESCE(excluded.estimated_input_tokens, request_logs.estimated_input_tokens)
 		"#,
             )
             .map_err(|e| DbWriteError::from_rusqlite("failed to prepare insert", e))?;
@@ -354,8 +356,10 @@ fn insert_batch_once(
             validate_cli_key(&item.cli_key).map_err(DbWriteError::other)?;
 
             let attempts = parse_attempts(&item.attempts_json);
-            let (final_provider_id, _) = final_provider_from_attempts(&attempts);
+            let (final_provider_id, final_provider_name) = final_provider_from_attempts(&attempts);
+            // Denormalized so listings filter / sort by provider without parsing attempts_json.
             let final_provider_id_db = (final_provider_id > 0).then_some(final_provider_id);
+            let final_provider_name_db = (final_provider_id > 0).then_some(final_provider_name);
 
             let cost_multiplier = if final_provider_id > 0 {
                 if let Some(v) = batch_multiplier.get(&final_provider_id) {
@@ -465,7 +469,11 @@ fn insert_batch_once(
                 cost_multiplier,
                 item.created_at_ms,
                 item.created_at,
-                final_provider_id_db
+                final_provider_id_db,
+                item.client_id,
+                item.client_trace_id,
+                item.estimated_input_tokens,
+                final_provider_name_db
             ])
             .map_err(|e| DbWriteError::from_rusqlite("failed to insert request_log", e))?;
         }
@@ -477,6 +485,226 @@ fn insert_batch_once(
     Ok(())
 }
 
+/// Per client key usage since `since_ts` (unix seconds); requests without a client key are skipped.
+pub fn aggregate_by_client(
+    db: &db::Db,
+    since_ts: i64,
+) -> Result<Vec<ClientUsageAggregate>, String> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  client_id,
+  COUNT(1) AS request_count,
+  SUM(COALESCE(total_tokens, 0)) AS total_tokens,
+  SUM(COALESCE(cost_usd_femto, 0)) AS total_cost_usd_femto,
+  MAX(created_at) AS last_seen_at
+FROM request_logs
+WHERE client_id IS NOT NULL
+  AND created_at >= ?1
+  AND excluded_from_stats = 0
+GROUP BY client_id
+ORDER BY total_cost_usd_femto DESC
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare client aggregate query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![since_ts], |row| {
+            let femto: i64 = row.get("total_cost_usd_femto")?;
+            Ok(ClientUsageAggregate {
+                client_id: row.get("client_id")?,
+                request_count: row.get("request_count")?,
+                total_tokens: row.get("total_tokens")?,
+                total_cost_usd: costing::cost_usd_from_femto(Some(femto)).unwrap_or(0.0),
+                last_seen_at: row.get("last_seen_at")?,
+            })
+        })
+        .map_err(|e| format!("DB_ERROR: failed to query client aggregates: {e}"))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("DB_ERROR: failed to read client aggregate row: {e}"))
+}
+
+pub fn aggregate_response_fixer_dry_run(
+    db: &db::Db,
+    since_ts: i64,
+) -> Result<Vec<ResponseFixerDryRunAggregate>, String> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  attempts_json,
+  special_settings_json,
+  created_at
+FROM request_logs
+WHERE created_at >= ?1
+  AND special_settings_json LIKE '%"dryRun":true%'
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare response fixer dry-run query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![since_ts], |row| {
+            Ok((
+                row.get::<_, String>("attempts_json")?,
+                row.get::<_, Option<String>>("special_settings_json")?,
+                row.get::<_, i64>("created_at")?,
+            ))
+        })
+        .map_err(|e| format!("DB_ERROR: failed to query response fixer dry-run logs: {e}"))?;
+
+    let mut by_provider: HashMap<i64, ResponseFixerDryRunAggregate> = HashMap::new();
+    for row in rows {
+        let (attempts_json, special_settings_json, created_at) =
+            row.map_err(|e| format!("DB_ERROR: failed to read response fixer dry-run row: {e}"))?;
+        let Some(raw) = special_settings_json else {
+            continue;
+        };
+        let settings: Vec<serde_json::Value> = serde_json::from_str(&raw).unwrap_or_default();
+        let Some(report) = settings.iter().find(|v| {
+            v.get("type").and_then(|t| t.as_str()) == Some("response_fixer")
+                && v.get("dryRun").and_then(|t| t.as_bool()) == Some(true)
+        }) else {
+            continue;
+        };
+
+        let (provider_id, provider_name) =
+            final_provider_from_attempts(&parse_attempts(&attempts_json));
+        let entry =
+            by_provider
+                .entry(provider_id)
+                .or_insert_with(|| ResponseFixerDryRunAggregate {
+                    provider_id,
+                    provider_name,
+                    analyzed_count: 0,
+                    would_apply_count: 0,
+                    fixer_counts: Default::default(),
+                    last_seen_at: created_at,
+                });
+        entry.analyzed_count += 1;
+        entry.last_seen_at = entry.last_seen_at.max(created_at);
+        if report.get("hit").and_then(|v| v.as_bool()) != Some(true) {
+            continue;
+        }
+        entry.would_apply_count += 1;
+        let fixers = report
+            .get("fixersApplied")
+            .and_then(|v| v.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for fixer in fixers {
+            if fixer.get("applied").and_then(|v| v.as_bool()) != Some(true) {
+                continue;
+            }
+            if let Some(name) = fixer.get("fixer").and_then(|v| v.as_str()) {
+                *entry.fixer_counts.entry(name.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut out: Vec<ResponseFixerDryRunAggregate> = by_provider.into_values().collect();
+    out.sort_by(|a, b| {
+        b.would_apply_count
+            .cmp(&a.would_apply_count)
+            .then(b.analyzed_count.cmp(&a.analyzed_count))
+    });
+    Ok(out)
+}
+
+/// Compares upstream-reported prompt tokens with `estimated_input_tokens` per final provider.
+///
+/// Claude reports cache reads / writes separately from `input_tokens`; Codex / Gemini include them.
+pub fn aggregate_usage_estimate_drift(
+    db: &db::Db,
+    since_ts: i64,
+) -> Result<Vec<UsageEstimateDriftAggregate>, String> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  s.provider_id,
+  COALESCE(p.name, '') AS provider_name,
+  s.cli_key,
+  COUNT(1) AS sample_count,
+  SUM(s.reported) AS reported_input_tokens,
+  SUM(s.estimated) AS estimated_input_tokens,
+  SUM(CASE WHEN s.reported > s.estimated * ?3 THEN 1 ELSE 0 END) AS inflated_count,
+  MAX(s.created_at) AS last_seen_at
+FROM (
+  SELECT
+    final_provider_id AS provider_id,
+    cli_key,
+    estimated_input_tokens AS estimated,
+    CASE WHEN cli_key = 'claude' THEN
+      input_tokens
+      + COALESCE(cache_read_input_tokens, 0)
+      + COALESCE(
+          cache_creation_input_tokens,
+          COALESCE(cache_creation_5m_input_tokens, 0) + COALESCE(cache_creation_1h_input_tokens, 0)
+        )
+    ELSE input_tokens END AS reported,
+    created_at
+  FROM request_logs
+  WHERE created_at >= ?1
+    AND excluded_from_stats = 0
+    AND status >= 200 AND status < 300
+    AND final_provider_id IS NOT NULL
+    AND input_tokens IS NOT NULL
+    AND estimated_input_tokens >= ?2
+) s
+LEFT JOIN providers p ON p.id = s.provider_id
+GROUP BY s.provider_id, s.cli_key
+"#,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to prepare usage drift query: {e}"))?;
+
+    let rows = stmt
+        .query_map(
+            params![
+                since_ts,
+                USAGE_DRIFT_MIN_ESTIMATE_TOKENS,
+                USAGE_DRIFT_INFLATED_RATIO
+            ],
+            |row| {
+                let sample_count: i64 = row.get("sample_count")?;
+                let reported: i64 = row.get("reported_input_tokens")?;
+                let estimated: i64 = row.get("estimated_input_tokens")?;
+                let inflated_count: i64 = row.get("inflated_count")?;
+                let ratio = if estimated > 0 {
+                    reported as f64 / estimated as f64
+                } else {
+                    0.0
+                };
+                Ok(UsageEstimateDriftAggregate {
+                    provider_id: row.get("provider_id")?,
+                    provider_name: row.get("provider_name")?,
+                    cli_key: row.get("cli_key")?,
+                    sample_count,
+                    reported_input_tokens: reported,
+                    estimated_input_tokens: estimated,
+                    ratio,
+                    inflated_count,
+                    // most samples inflated, not just a few outliers (e.g. hidden tool prompts)
+                    flagged: sample_count >= USAGE_DRIFT_MIN_SAMPLES
+                        && ratio >= USAGE_DRIFT_INFLATED_RATIO
+                        && inflated_count * 2 >= sample_count,
+                    last_seen_at: row.get("last_seen_at")?,
+                })
+            },
+        )
+        .map_err(|e| format!("DB_ERROR: failed to query usage drift: {e}"))?;
+
+    let mut out = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("DB_ERROR: failed to read usage drift row: {e}"))?;
+    out.sort_by(|a, b| b.flagged.cmp(&a.flagged).then(b.ratio.total_cmp(&a.ratio)));
+    Ok(out)
+}
+
 pub fn aggregate_by_session_ids(
     db: &db::Db,
     session_ids: &[String],
@@ -563,3 +791,6 @@ GROUP BY cli_key, session_id
 
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests;
@@ -1,7 +1,7 @@
 //! Usage: Request log retention cleanup.
 
-use crate::db;
 use crate::shared::time::now_unix_seconds;
+use crate::{db, usage_rollups};
 use rusqlite::params;
 
 pub fn cleanup_expired(db: &db::Db, retention_days: u32) -> Result<u64, String> {
@@ -12,13 +12,29 @@ pub fn cleanup_expired(db: &db::Db, retention_days: u32) -> Result<u64, String>
     let now = now_unix_seconds();
     let cutoff = now.saturating_sub((retention_days as i64).saturating_mul(86400));
 
-    let conn = db.open_connection()?;
+    let mut conn = db.open_connection()?;
     let changed = conn
         .execute(
             "DELETE FROM request_logs WHERE created_at < ?1",
             params![cutoff],
         )
         .map_err(|e| format!("DB_ERROR: failed to cleanup request_logs: {e}"))?;
+    conn.execute(
+        "DELETE FROM tool_usage_events WHERE created_at < ?1",
+        params![cutoff],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to cleanup tool_usage_events: {e}"))?;
+    conn.execute(
+        "DELETE FROM codex_session_completion_events WHERE created_at < ?1",
+        params![cutoff],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to cleanup codex_session_completion_events: {e}"))?;
+    conn.execute(
+        "DELETE FROM request_sse_captures WHERE created_at < ?1",
+        params![cutoff],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to cleanup request_sse_captures: {e}"))?;
+    usage_rollups::prune_before(&mut conn, cutoff)?;
 
     Ok(changed as u64)
 }
@@ -10,6 +10,10 @@ pub(super) fn cost_usd_from_femto(cost_usd_femto: Option<i64>) -> Option<f64> {
         .map(|v| v as f64 / 1_000_000_000_000_000.0)
 }
 
+pub(super) fn cost_usd_to_femto(cost_usd: f64) -> i64 {
+    (cost_usd * 1_000_000_000_000_000.0).round() as i64
+}
+
 pub(super) fn is_success_status(status: Option<i64>, error_code: Option<&str>) -> bool {
     status.is_some_and(|v| (200..300).contains(&v)) && error_code.is_none()
 }
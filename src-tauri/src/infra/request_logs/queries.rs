@@ -1,11 +1,36 @@
 //! Usage: Request log queries and attempts decoding.
+//!
+//! Listings are keyset-paginated, never OFFSET-based:
+//! - newest page / older pages: `(created_at_ms, id) < (?, ?) ORDER BY created_at_ms DESC, id DESC`,
+//!   served by `idx_request_logs_created_at_ms_id_cost` / `idx_request_logs_cli_created_at_ms`
+//!   (rowid is the tie-break)
+//! - incremental refresh: `id > ? ORDER BY id`, served by the primary key / `idx_request_logs_cli_key_id`
+//!
+//! Each page is an index range seek, so its cost does not grow with page depth the way `OFFSET` does.
+//! The equivalent `a < ? OR (a = ? AND id < ?)` form cannot be turned into an index range by SQLite;
+//! keep the row-value comparison.
+//!
+//! `list_filtered` seeks one composite index per filter combination (v59->v60):
+//! - cli / provider / cli + provider, newest: `(cli_key, created_at_ms)`, `(final_provider_id,
+//!   created_at_ms)`, `(cli_key, final_provider_id, created_at_ms)`
+//! - cost desc (with or without a min cost): `(cost_usd_femto)`, `(cli_key, cost_usd_femto)`,
+//!   `(final_provider_id, cost_usd_femto)`
+//! - min cost, newest: the cost seek above re-sorted by time when cli / provider narrows it;
+//!   alone, a walk of `(created_at_ms, id, cost_usd_femto)` that never reads table rows
+//!
+//! `request_logs::tests::list_filtered_benchmark_1m_rows` (ignored; run with `--ignored --nocapture`)
+//! seeds 1M rows and times every combination. Last run, release build: every page 25-65µs (up to
+//! ~0.4ms with a min cost and a deep cursor), except a bare min cost matching almost nothing, which
+//! walks the whole covering index (~45ms; ~300ms before v60).
 
 use crate::db;
-use rusqlite::{params, OptionalExtension};
+use rusqlite::{params, params_from_iter, OptionalExtension};
 use serde::Deserialize;
 
-use super::costing::cost_usd_from_femto;
-use super::{RequestLogDetail, RequestLogRouteHop, RequestLogSummary};
+use super::costing::{cost_usd_from_femto, cost_usd_to_femto};
+use super::{
+    RequestLogDetail, RequestLogListQuery, RequestLogRouteHop, RequestLogSort, RequestLogSummary,
+};
 
 /// Common SELECT fields for request_logs queries (summary view).
 const REQUEST_LOG_SUMMARY_FIELDS: &str = "
@@ -27,10 +52,15 @@ const REQUEST_LOG_SUMMARY_FIELDS: &str = "
   cache_creation_input_tokens,
   cache_creation_5m_input_tokens,
   cache_creation_1h_input_tokens,
+  estimated_input_tokens,
   cost_usd_femto,
   cost_multiplier,
   created_at_ms,
-  created_at
+  created_at,
+  client_id,
+  client_trace_id,
+  final_provider_id,
+  final_provider_name
 ";
 
 /// Common SELECT fields for request_logs queries (detail view).
@@ -55,12 +85,15 @@ const REQUEST_LOG_DETAIL_FIELDS: &str = "
   cache_creation_input_tokens,
   cache_creation_5m_input_tokens,
   cache_creation_1h_input_tokens,
+  estimated_input_tokens,
   usage_json,
   requested_model,
   cost_usd_femto,
   cost_multiplier,
   created_at_ms,
-  created_at
+  created_at,
+  client_id,
+  client_trace_id
 ";
 
 pub(super) fn validate_cli_key(cli_key: &str) -> Result<(), String> {
@@ -165,7 +198,14 @@ fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<RequestLogSummary, rusqlite
     let attempt_count = attempts.len() as i64;
     let has_failover = attempt_count > 1;
     let (start_provider_id, start_provider_name) = start_provider_from_attempts(&attempts);
-    let (final_provider_id, final_provider_name) = final_provider_from_attempts(&attempts);
+    // Denormalized at insert time; rows written before v59 without a final provider fall back to attempts.
+    let (final_provider_id, final_provider_name) = match (
+        row.get::<_, Option<i64>>("final_provider_id")?,
+        row.get::<_, Option<String>>("final_provider_name")?,
+    ) {
+        (Some(id), Some(name)) => (id, name),
+        _ => final_provider_from_attempts(&attempts),
+    };
     let route = route_from_attempts(&attempts);
     let session_reuse = attempts
         .iter()
@@ -198,10 +238,13 @@ fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<RequestLogSummary, rusqlite
         cache_creation_input_tokens: row.get("cache_creation_input_tokens")?,
         cache_creation_5m_input_tokens: row.get("cache_creation_5m_input_tokens")?,
         cache_creation_1h_input_tokens: row.get("cache_creation_1h_input_tokens")?,
+        estimated_input_tokens: row.get("estimated_input_tokens")?,
         cost_usd,
         cost_multiplier: row.get("cost_multiplier")?,
         created_at_ms: row.get("created_at_ms")?,
         created_at: row.get("created_at")?,
+        client_id: row.get("client_id")?,
+        client_trace_id: row.get("client_trace_id")?,
     })
 }
 
@@ -251,6 +294,151 @@ pub fn list_recent_all(db: &db::Db, limit: usize) -> Result<Vec<RequestLogSummar
     Ok(items)
 }
 
+pub fn list_before(
+    db: &db::Db,
+    cli_key: &str,
+    before_created_at_ms: i64,
+    before_id: i64,
+    limit: usize,
+) -> Result<Vec<RequestLogSummary>, String> {
+    validate_cli_key(cli_key)?;
+    let conn = db.open_connection()?;
+
+    let sql = format!(
+        "SELECT{}FROM request_logs WHERE cli_key = ?1 AND (created_at_ms, id) < (?2, ?3) ORDER BY created_at_ms DESC, id DESC LIMIT ?4",
+        REQUEST_LOG_SUMMARY_FIELDS
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("DB_ERROR: failed to prepare query: {e}"))?;
+
+    let rows = stmt
+        .query_map(
+            params![cli_key, before_created_at_ms, before_id, limit as i64],
+            row_to_summary,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to list request_logs: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("DB_ERROR: failed to read request_log row: {e}"))?);
+    }
+    Ok(items)
+}
+
+pub fn list_before_all(
+    db: &db::Db,
+    before_created_at_ms: i64,
+    before_id: i64,
+    limit: usize,
+) -> Result<Vec<RequestLogSummary>, String> {
+    let conn = db.open_connection()?;
+
+    let sql = format!(
+        "SELECT{}FROM request_logs WHERE (created_at_ms, id) < (?1, ?2) ORDER BY created_at_ms DESC, id DESC LIMIT ?3",
+        REQUEST_LOG_SUMMARY_FIELDS
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("DB_ERROR: failed to prepare query: {e}"))?;
+
+    let rows = stmt
+        .query_map(
+            params![before_created_at_ms, before_id, limit as i64],
+            row_to_summary,
+        )
+        .map_err(|e| format!("DB_ERROR: failed to list request_logs: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("DB_ERROR: failed to read request_log row: {e}"))?);
+    }
+    Ok(items)
+}
+
+/// Provider-filtered / cost-sorted listing over the denormalized `final_provider_id` and
+/// `cost_usd_femto` columns. Every filter combination seeks a composite index (see the module
+/// docs); `request_logs::tests` pins the plans.
+///
+/// Keyset-paginated like the other listings; the cursor row's sort key is looked up by id in the
+/// same statement so callers only pass back the last id they received.
+pub fn list_filtered(
+    db: &db::Db,
+    query: &RequestLogListQuery,
+    limit: usize,
+) -> Result<Vec<RequestLogSummary>, String> {
+    let (sql, values) = list_filtered_sql(query, limit)?;
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("DB_ERROR: failed to prepare query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params_from_iter(values), row_to_summary)
+        .map_err(|e| format!("DB_ERROR: failed to list request_logs: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("DB_ERROR: failed to read request_log row: {e}"))?);
+    }
+    Ok(items)
+}
+
+pub(super) fn list_filtered_sql(
+    query: &RequestLogListQuery,
+    limit: usize,
+) -> Result<(String, Vec<rusqlite::types::Value>), String> {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+
+    if let Some(cli_key) = query.cli_key.as_deref() {
+        validate_cli_key(cli_key)?;
+        clauses.push("cli_key = ?".to_string());
+        values.push(cli_key.to_string().into());
+    }
+    if let Some(provider_id) = query.final_provider_id {
+        if provider_id <= 0 {
+            return Err("SEC_INVALID_INPUT: final_provider_id must be > 0".to_string());
+        }
+        clauses.push("final_provider_id = ?".to_string());
+        values.push(provider_id.into());
+    }
+    if let Some(min_cost_usd) = query.min_cost_usd {
+        if !min_cost_usd.is_finite() || min_cost_usd < 0.0 {
+            return Err("SEC_INVALID_INPUT: min_cost_usd must be >= 0".to_string());
+        }
+        clauses.push("cost_usd_femto >= ?".to_string());
+        values.push(cost_usd_to_femto(min_cost_usd).into());
+    }
+
+    let sort_key = match query.sort {
+        RequestLogSort::Newest => "created_at_ms",
+        RequestLogSort::CostDesc => {
+            // NULL costs (failed / unpriced requests) cannot take part in a keyset comparison.
+            clauses.push("cost_usd_femto IS NOT NULL".to_string());
+            "cost_usd_femto"
+        }
+    };
+    if let Some(before_id) = query.before_id {
+        clauses.push(format!(
+            "({sort_key}, id) < (SELECT {sort_key}, id FROM request_logs WHERE id = ?)"
+        ));
+        values.push(before_id.into());
+    }
+    values.push((limit as i64).into());
+
+    let where_sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {} ", clauses.join(" AND "))
+    };
+    let sql = format!(
+        "SELECT{}FROM request_logs {where_sql}ORDER BY {sort_key} DESC, id DESC LIMIT ?",
+        REQUEST_LOG_SUMMARY_FIELDS
+    );
+    Ok((sql, values))
+}
+
 pub fn list_after_id(
     db: &db::Db,
     cli_key: &str,
@@ -337,12 +525,15 @@ pub fn get_by_id(db: &db::Db, log_id: i64) -> Result<RequestLogDetail, String> {
             cache_creation_input_tokens: row.get("cache_creation_input_tokens")?,
             cache_creation_5m_input_tokens: row.get("cache_creation_5m_input_tokens")?,
             cache_creation_1h_input_tokens: row.get("cache_creation_1h_input_tokens")?,
+            estimated_input_tokens: row.get("estimated_input_tokens")?,
             usage_json: row.get("usage_json")?,
             requested_model: row.get("requested_model")?,
             cost_usd,
             cost_multiplier: row.get("cost_multiplier")?,
             created_at_ms: row.get("created_at_ms")?,
             created_at: row.get("created_at")?,
+            client_id: row.get("client_id")?,
+            client_trace_id: row.get("client_trace_id")?,
         })
     })
     .optional()
@@ -384,14 +575,48 @@ pub fn get_by_trace_id(db: &db::Db, trace_id: &str) -> Result<Option<RequestLogD
             cache_creation_input_tokens: row.get("cache_creation_input_tokens")?,
             cache_creation_5m_input_tokens: row.get("cache_creation_5m_input_tokens")?,
             cache_creation_1h_input_tokens: row.get("cache_creation_1h_input_tokens")?,
+            estimated_input_tokens: row.get("estimated_input_tokens")?,
             usage_json: row.get("usage_json")?,
             requested_model: row.get("requested_model")?,
             cost_usd,
             cost_multiplier: row.get("cost_multiplier")?,
             created_at_ms: row.get("created_at_ms")?,
             created_at: row.get("created_at")?,
+            client_id: row.get("client_id")?,
+            client_trace_id: row.get("client_trace_id")?,
         })
     })
     .optional()
     .map_err(|e| format!("DB_ERROR: failed to query request_log: {e}"))
 }
+
+/// Hub logs for a client-supplied `x-client-trace-id` (a client may reuse it across retries).
+pub fn list_by_client_trace_id(
+    db: &db::Db,
+    client_trace_id: &str,
+    limit: usize,
+) -> Result<Vec<RequestLogSummary>, String> {
+    let client_trace_id = client_trace_id.trim();
+    if client_trace_id.is_empty() {
+        return Err("SEC_INVALID_INPUT: client_trace_id is required".to_string());
+    }
+
+    let conn = db.open_connection()?;
+    let sql = format!(
+        "SELECT{}FROM request_logs WHERE client_trace_id = ?1 ORDER BY created_at_ms DESC, id DESC LIMIT ?2",
+        REQUEST_LOG_SUMMARY_FIELDS
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("DB_ERROR: failed to prepare query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![client_trace_id, limit as i64], row_to_summary)
+        .map_err(|e| format!("DB_ERROR: failed to list request_logs: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| format!("DB_ERROR: failed to read request_log row: {e}"))?);
+    }
+    Ok(items)
+}
@@ -0,0 +1,182 @@
+use super::queries::list_filtered_sql;
+use super::{list_filtered, RequestLogListQuery, RequestLogSort};
+use crate::db;
+use rusqlite::params_from_iter;
+use std::time::{Duration, Instant};
+
+const PAGE: usize = 50;
+
+// Every filter / sort / cursor combination `list_filtered` can build.
+fn filtered_queries(
+    cli_key: &str,
+    provider_id: i64,
+    min_cost_usd: f64,
+    before_id: i64,
+) -> Vec<RequestLogListQuery> {
+    let mut out = Vec::new();
+    for mask in 0..32u8 {
+        out.push(RequestLogListQuery {
+            cli_key: (mask & 1 != 0).then(|| cli_key.to_string()),
+            final_provider_id: (mask & 2 != 0).then_some(provider_id),
+            min_cost_usd: (mask & 4 != 0).then_some(min_cost_usd),
+            sort: if mask & 8 != 0 {
+                RequestLogSort::CostDesc
+            } else {
+                RequestLogSort::Newest
+            },
+            before_id: (mask & 16 != 0).then_some(before_id),
+        });
+    }
+    out
+}
+
+fn describe(query: &RequestLogListQuery) -> String {
+    format!(
+        "cli={} provider={} min_cost={} sort={:?} cursor={}",
+        query.cli_key.is_some(),
+        query.final_provider_id.is_some(),
+        query.min_cost_usd.is_some(),
+        query.sort,
+        query.before_id.is_some(),
+    )
+}
+
+fn query_plan(conn: &rusqlite::Connection, query: &RequestLogListQuery) -> String {
+    let (sql, values) = list_filtered_sql(query, PAGE).expect("build list_filtered sql");
+    let mut stmt = conn
+        .prepare(&format!("EXPLAIN QUERY PLAN {sql}"))
+        .expect("prepare query plan");
+    let rows = stmt
+        .query_map(params_from_iter(values), |row| row.get::<_, String>(3))
+        .expect("explain list_filtered");
+    rows.map(|row| row.expect("read plan row"))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+// No full-table scan, and the only allowed sort is a min-cost seek re-ordered by time (bounded by
+// the rows above the threshold, not by the table).
+fn assert_indexed_plan(query: &RequestLogListQuery, plan: &str) {
+    let label = describe(query);
+    assert!(
+        plan.contains("USING INDEX idx_request_logs_"),
+        "{label}: plan does not seek a request_logs index: {plan}"
+    );
+    assert!(
+        !plan.split(" | ").any(|step| step == "SCAN request_logs"),
+        "{label}: plan scans the table: {plan}"
+    );
+    if plan.contains("USE TEMP B-TREE") {
+        assert!(
+            query.min_cost_usd.is_some() && query.sort == RequestLogSort::Newest,
+            "{label}: plan sorts in a temp b-tree: {plan}"
+        );
+    }
+}
+
+#[test]
+fn list_filtered_plans_seek_composite_indexes() {
+    let db = db::open_in_memory_for_tests();
+    let conn = db.open_connection().expect("open connection");
+
+    for query in filtered_queries("claude", 3, 0.5, 1) {
+        let plan = query_plan(&conn, &query);
+        assert_indexed_plan(&query, &plan);
+    }
+
+    let cases = [
+        (
+            RequestLogListQuery {
+                cli_key: Some("claude".to_string()),
+                sort: RequestLogSort::CostDesc,
+                ..Default::default()
+            },
+            "idx_request_logs_cli_cost_usd_femto",
+        ),
+        (
+            RequestLogListQuery {
+                final_provider_id: Some(3),
+                sort: RequestLogSort::CostDesc,
+                ..Default::default()
+            },
+            "idx_request_logs_final_provider_cost_usd_femto",
+        ),
+        (
+            RequestLogListQuery {
+                cli_key: Some("claude".to_string()),
+                final_provider_id: Some(3),
+                ..Default::default()
+            },
+            "idx_request_logs_cli_final_provider_created_at_ms",
+        ),
+        (
+            RequestLogListQuery {
+                min_cost_usd: Some(0.5),
+                ..Default::default()
+            },
+            "idx_request_logs_created_at_ms_id_cost",
+        ),
+    ];
+    for (query, index) in cases {
+        let plan = query_plan(&conn, &query);
+        assert!(
+            plan.contains(index),
+            "{}: expected {index}: {plan}",
+            describe(&query)
+        );
+    }
+}
+
+// Reproducible benchmark for the filtered listing. Seeds 1M rows (60% claude / 30% codex /
+// 10% gemini, 20 providers, 10% unpriced) and prints the median of 5 page reads per combination:
+//
+//   cargo test --release --lib list_filtered_benchmark_1m_rows -- --ignored --nocapture
+#[test]
+#[ignore]
+fn list_filtered_benchmark_1m_rows() {
+    const ROWS: i64 = 1_000_000;
+
+    let db = db::open_in_memory_for_tests();
+    {
+        let conn = db.open_connection().expect("open connection");
+        let started = Instant::now();
+        conn.execute_batch(&format!(
+            r#"
+WITH RECURSIVE seq(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM seq WHERE i < {ROWS})
+INSERT INTO request_logs (trace_id, cli_key, method, path, status, duration_ms, attempts_json,
+  created_at, created_at_ms, cost_usd_femto, final_provider_id, final_provider_name)
+SELECT
+  't' || i,
+  CASE WHEN i % 10 < 6 THEN 'claude' WHEN i % 10 < 9 THEN 'codex' ELSE 'gemini' END,
+  'POST', '/v1/messages',
+  CASE WHEN i % 20 = 0 THEN 502 ELSE 200 END,
+  1000 + (i % 5000),
+  '[]',
+  1700000000 + i * 2,
+  1700000000000 + i * 2000,
+  CASE WHEN i % 10 = 0 THEN NULL ELSE (i * 7919) % 50000000000000000 END,
+  1 + (i % 20), 'provider'
+FROM seq;
+"#
+        ))
+        .expect("seed request_logs");
+        println!("seeded {ROWS} rows in {:?}", started.elapsed());
+
+        // gemini rows only carry providers 10 and 20; a cursor ~99% of the way back in time.
+        for query in filtered_queries("gemini", 10, 49.99, ROWS / 100) {
+            assert_indexed_plan(&query, &query_plan(&conn, &query));
+        }
+    }
+
+    for query in filtered_queries("gemini", 10, 49.99, ROWS / 100) {
+        let mut timings: Vec<Duration> = (0..5)
+            .map(|_| {
+                let started = Instant::now();
+                list_filtered(&db, &query, PAGE).expect("list_filtered");
+                started.elapsed()
+            })
+            .collect();
+        timings.sort();
+        println!("{:<80} median={:?}", describe(&query), timings[2]);
+    }
+}
@@ -1,6 +1,7 @@
 //! Usage: Request log DTOs and insertion payloads.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
 pub struct RequestLogInsert {
@@ -24,10 +25,16 @@ pub struct RequestLogInsert {
     pub cache_creation_input_tokens: Option<i64>,
     pub cache_creation_5m_input_tokens: Option<i64>,
     pub cache_creation_1h_input_tokens: Option<i64>,
+    // Local prompt token estimate of the request body (`tokenizer`); kept even when upstream omits usage.
+    pub estimated_input_tokens: Option<i64>,
     pub usage_json: Option<String>,
     pub requested_model: Option<String>,
     pub created_at_ms: i64,
     pub created_at: i64,
+    // Named client key (LAN / remote device) that sent the request; None = master token / local.
+    pub client_id: Option<String>,
+    // Client-supplied `x-client-trace-id`, for correlating external traces with hub logs.
+    pub client_trace_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -72,10 +79,42 @@ pub struct RequestLogSummary {
     pub cache_creation_input_tokens: Option<i64>,
     pub cache_creation_5m_input_tokens: Option<i64>,
     pub cache_creation_1h_input_tokens: Option<i64>,
+    // Local prompt token estimate of the request body (`tokenizer`); kept even when upstream omits usage.
+    pub estimated_input_tokens: Option<i64>,
     pub cost_usd: Option<f64>,
     pub cost_multiplier: f64,
     pub created_at_ms: i64,
     pub created_at: i64,
+    // Named client key (LAN / remote device) that sent the request; None = master token / local.
+    pub client_id: Option<String>,
+    // Client-supplied `x-client-trace-id`, for correlating external traces with hub logs.
+    pub client_trace_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestLogSort {
+    // created_at_ms DESC
+    #[default]
+    Newest,
+    // cost_usd_femto DESC; only rows with a computed cost
+    CostDesc,
+}
+
+/// Server-side filters for `list_filtered`, served by the denormalized final provider / cost columns.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RequestLogListQuery {
+    #[serde(default)]
+    pub cli_key: Option<String>,
+    #[serde(default)]
+    pub final_provider_id: Option<i64>,
+    #[serde(default)]
+    pub min_cost_usd: Option<f64>,
+    #[serde(default)]
+    pub sort: RequestLogSort,
+    // Keyset cursor: id of the last row of the previous page (in the same sort).
+    #[serde(default)]
+    pub before_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -100,12 +139,18 @@ pub struct RequestLogDetail {
     pub cache_creation_input_tokens: Option<i64>,
     pub cache_creation_5m_input_tokens: Option<i64>,
     pub cache_creation_1h_input_tokens: Option<i64>,
+    // Local prompt token estimate of the request body (`tokenizer`); kept even when upstream omits usage.
+    pub estimated_input_tokens: Option<i64>,
     pub usage_json: Option<String>,
     pub requested_model: Option<String>,
     pub cost_usd: Option<f64>,
     pub cost_multiplier: f64,
     pub created_at_ms: i64,
     pub created_at: i64,
+    // Named client key (LAN / remote device) that sent the request; None = master token / local.
+    pub client_id: Option<String>,
+    // Client-supplied `x-client-trace-id`, for correlating external traces with hub logs.
+    pub client_trace_id: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -116,3 +161,42 @@ pub struct SessionStatsAggregate {
     pub total_cost_usd_femto: i64,
     pub total_duration_ms: i64,
 }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientUsageAggregate {
+    pub client_id: String,
+    pub request_count: i64,
+    pub total_tokens: i64,
+    pub total_cost_usd: f64,
+    pub last_seen_at: i64,
+}
+
+/// Per-provider summary of response fixer dry-run reports (what the fixer would have changed).
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseFixerDryRunAggregate {
+    pub provider_id: i64,
+    pub provider_name: String,
+    pub analyzed_count: i64,
+    pub would_apply_count: i64,
+    // fixer name -> number of traces it would have modified
+    pub fixer_counts: BTreeMap<String, i64>,
+    pub last_seen_at: i64,
+}
+
+/// Per-provider comparison of upstream-reported prompt tokens with the local estimate.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageEstimateDriftAggregate {
+    pub provider_id: i64,
+    pub provider_name: String,
+    pub cli_key: String,
+    pub sample_count: i64,
+    pub reported_input_tokens: i64,
+    pub estimated_input_tokens: i64,
+    // reported / estimated over all samples
+    pub ratio: f64,
+    // samples whose reported usage exceeds the estimate by more than the inflation threshold
+    pub inflated_count: i64,
+    // reported usage consistently exceeds the estimate (possible padding)
+    pub flagged: bool,
+    pub last_seen_at: i64,
+}
@@ -0,0 +1,60 @@
+//! Usage: Raw SSE captures of streamed responses, one row per trace (opt-in via `capture_sse_events`).
+
+use crate::db;
+use rusqlite::{params, OptionalExtension};
+
+#[derive(Debug, Clone)]
+pub struct RequestSseCapture {
+    pub trace_id: String,
+    pub cli_key: String,
+    pub created_at: i64,
+    pub truncated: bool,
+    pub body: String,
+}
+
+pub fn record(db: &db::Db, capture: &RequestSseCapture) -> Result<(), String> {
+    let conn = db.open_connection()?;
+    conn.execute(
+        r#"
+INSERT INTO request_sse_captures(trace_id, cli_key, created_at, truncated, body)
+VALUES (?1, ?2, ?3, ?4, ?5)
+ON CONFLICT(trace_id) DO UPDATE SET
+  cli_key = excluded.cli_key,
+  created_at = excluded.created_at,
+  truncated = excluded.truncated,
+  body = excluded.body
+"#,
+        params![
+            capture.trace_id,
+            capture.cli_key,
+            capture.created_at,
+            capture.truncated as i64,
+            capture.body,
+        ],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to insert request_sse_captures: {e}"))?;
+    Ok(())
+}
+
+pub fn get_by_trace_id(db: &db::Db, trace_id: &str) -> Result<Option<RequestSseCapture>, String> {
+    if trace_id.trim().is_empty() {
+        return Err("SEC_INVALID_INPUT: trace_id is required".to_string());
+    }
+
+    let conn = db.open_connection()?;
+    conn.query_row(
+        "SELECT trace_id, cli_key, created_at, truncated, body FROM request_sse_captures WHERE trace_id = ?1",
+        params![trace_id],
+        |row| {
+            Ok(RequestSseCapture {
+                trace_id: row.get("trace_id")?,
+                cli_key: row.get("cli_key")?,
+                created_at: row.get("created_at")?,
+                truncated: row.get::<_, i64>("truncated")? != 0,
+                body: row.get("body")?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| format!("DB_ERROR: failed to query request_sse_captures: {e}"))
+}
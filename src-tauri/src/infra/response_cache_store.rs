@@ -0,0 +1,106 @@
+//! Usage: Persisted layer of the gateway response cache (`response_cache` table), keyed by (provider, request fingerprint).
+
+use crate::db;
+use rusqlite::{params, OptionalExtension};
+
+// Oldest rows beyond this are dropped on every write.
+const MAX_PERSISTED_ENTRIES: i64 = 256;
+
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub stored_at: i64,
+}
+
+/// Entry for `fingerprint_key` stored after `fresh_after` (unix seconds); a debug mismatch (hash collision) is a miss.
+pub fn get(
+    db: &db::Db,
+    provider_id: i64,
+    fingerprint_key: u64,
+    fingerprint_debug: &str,
+    fresh_after: i64,
+) -> Result<Option<StoredResponse>, String> {
+    let conn = db.open_connection()?;
+    let row = conn
+        .query_row(
+            "SELECT fingerprint_debug, status, headers_json, body, stored_at FROM response_cache WHERE provider_id = ?1 AND fingerprint_key = ?2 AND stored_at > ?3",
+            params![provider_id, fingerprint_key as i64, fresh_after],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Vec<u8>>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| format!("DB_ERROR: failed to query response_cache: {e}"))?;
+
+    let Some((debug, status, headers_json, body, stored_at)) = row else {
+        return Ok(None);
+    };
+    if debug != fingerprint_debug {
+        return Ok(None);
+    }
+    let Ok(status) = u16::try_from(status) else {
+        return Ok(None);
+    };
+    Ok(Some(StoredResponse {
+        status,
+        headers: serde_json::from_str(&headers_json).unwrap_or_default(),
+        body,
+        stored_at,
+    }))
+}
+
+/// Upserts an entry, dropping rows stored at or before `expired_before` and the oldest beyond the cap.
+pub fn put(
+    db: &db::Db,
+    provider_id: i64,
+    fingerprint_key: u64,
+    fingerprint_debug: &str,
+    response: &StoredResponse,
+    expired_before: i64,
+) -> Result<(), String> {
+    let headers_json =
+        serde_json::to_string(&response.headers).map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+
+    let conn = db.open_connection()?;
+    conn.execute(
+        "DELETE FROM response_cache WHERE stored_at <= ?1",
+        params![expired_before],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to prune response_cache: {e}"))?;
+    conn.execute(
+        r#"
+INSERT INTO response_cache(provider_id, fingerprint_key, fingerprint_debug, status, headers_json, body, stored_at)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+ON CONFLICT(provider_id, fingerprint_key) DO UPDATE SET
+  fingerprint_debug = excluded.fingerprint_debug,
+  status = excluded.status,
+  headers_json = excluded.headers_json,
+  body = excluded.body,
+  stored_at = excluded.stored_at
+"#,
+        params![
+            provider_id,
+            fingerprint_key as i64,
+            fingerprint_debug,
+            i64::from(response.status),
+            headers_json,
+            response.body,
+            response.stored_at,
+        ],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to insert response_cache: {e}"))?;
+    conn.execute(
+        "DELETE FROM response_cache WHERE rowid IN (SELECT rowid FROM response_cache ORDER BY stored_at DESC LIMIT -1 OFFSET ?1)",
+        params![MAX_PERSISTED_ENTRIES],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to trim response_cache: {e}"))?;
+    Ok(())
+}
@@ -8,7 +8,7 @@ use std::sync::{OnceLock, RwLock};
 use std::time::{Duration, Instant};
 use tauri::Manager;
 
-pub const SCHEMA_VERSION: u32 = 13;
+pub const SCHEMA_VERSION: u32 = 52;
 const SCHEMA_VERSION_DISABLE_UPSTREAM_TIMEOUTS: u32 = 7;
 const SCHEMA_VERSION_ADD_GATEWAY_RECTIFIERS: u32 = 8;
 const SCHEMA_VERSION_ADD_CIRCUIT_BREAKER_NOTICE: u32 = 9;
@@ -16,11 +16,52 @@ const SCHEMA_VERSION_ADD_PROVIDER_BASE_URL_PING_CACHE_TTL: u32 = 10;
 const SCHEMA_VERSION_ADD_CODEX_SESSION_ID_COMPLETION: u32 = 11;
 const SCHEMA_VERSION_ADD_GATEWAY_NETWORK_SETTINGS: u32 = 12;
 const SCHEMA_VERSION_ADD_RESPONSE_FIXER_LIMITS: u32 = 13;
+const SCHEMA_VERSION_ADD_UI_LANGUAGE: u32 = 14;
+const SCHEMA_VERSION_ADD_LOW_POWER_MODE: u32 = 15;
+const SCHEMA_VERSION_ADD_NOTICE_CHANNELS: u32 = 16;
+const SCHEMA_VERSION_ADD_NOTICE_QUIET_HOURS: u32 = 17;
+const SCHEMA_VERSION_ADD_CIRCUIT_RECOVERY_NOTICE: u32 = 18;
+const SCHEMA_VERSION_ADD_WSL_DISTRO_OVERRIDES: u32 = 19;
+const SCHEMA_VERSION_ADD_GATEWAY_ACCESS_TOKEN: u32 = 20;
+const SCHEMA_VERSION_ADD_GATEWAY_TLS: u32 = 21;
+const SCHEMA_VERSION_ADD_GATEWAY_EXTRA_LISTENERS: u32 = 22;
+const SCHEMA_VERSION_ADD_GATEWAY_CLIENT_KEYS: u32 = 23;
+const SCHEMA_VERSION_ADD_GATEWAY_IP_ALLOWLIST: u32 = 24;
+const SCHEMA_VERSION_ADD_GATEWAY_DRAIN_TIMEOUT: u32 = 25;
+const SCHEMA_VERSION_ADD_UPDATE_CHANNEL: u32 = 26;
+const SCHEMA_VERSION_ADD_UPDATE_BACKGROUND_DOWNLOAD: u32 = 27;
+const SCHEMA_VERSION_ADD_RESPONSE_FIXER_TOOL_ARGS: u32 = 28;
+const SCHEMA_VERSION_ADD_RESPONSE_FIXER_DRY_RUN: u32 = 29;
+const SCHEMA_VERSION_ADD_INTERCEPT_RULES: u32 = 30;
+const SCHEMA_VERSION_ADD_INFLIGHT_DEDUP: u32 = 31;
+const SCHEMA_VERSION_ADD_PRIORITY_LANES: u32 = 32;
+const SCHEMA_VERSION_ADD_HEADER_SANITIZE_RULES: u32 = 33;
+const SCHEMA_VERSION_ADD_REQUEST_REDACTION: u32 = 34;
+const SCHEMA_VERSION_ADD_SCHEDULED_LATENCY_PROBE: u32 = 35;
+const SCHEMA_VERSION_ADD_PROMPT_CACHE_INJECTION: u32 = 36;
+const SCHEMA_VERSION_ADD_EXCLUDE_REASONING_FROM_LOGS: u32 = 37;
+const SCHEMA_VERSION_ADD_CLI_STREAM_CAPS: u32 = 38;
+const SCHEMA_VERSION_ADD_RETRY_BACKOFF: u32 = 39;
+const SCHEMA_VERSION_ADD_FORWARD_TRACE_HEADERS: u32 = 40;
+const SCHEMA_VERSION_ADD_CAPTURE_SSE_EVENTS: u32 = 41;
+const SCHEMA_VERSION_ADD_ATTEMPT_LOG_RETENTION: u32 = 42;
+const SCHEMA_VERSION_ADD_LATENCY_ROUTING: u32 = 43;
+const SCHEMA_VERSION_ADD_UPDATE_MIRROR: u32 = 44;
+const SCHEMA_VERSION_ADD_RESPONSE_CACHE: u32 = 45;
+const SCHEMA_VERSION_ADD_EGRESS_NETWORK: u32 = 46;
+const SCHEMA_VERSION_ADD_RESPONSE_CACHE_COMPLETIONS: u32 = 47;
+const SCHEMA_VERSION_ADD_RECENT_ERROR_CACHE: u32 = 48;
+const SCHEMA_VERSION_ADD_HEDGED_REQUESTS: u32 = 49;
+const SCHEMA_VERSION_ADD_FAILOVER_RULES: u32 = 50;
+const SCHEMA_VERSION_ADD_STREAM_TOOL_PAUSE_GRACE: u32 = 51;
+const SCHEMA_VERSION_ADD_GATEWAY_TLS_HOSTS: u32 = 52;
 pub const DEFAULT_GATEWAY_PORT: u16 = 37123;
 pub const MAX_GATEWAY_PORT: u16 = 37199;
 const DEFAULT_LOG_RETENTION_DAYS: u32 = 30;
+const DEFAULT_GATEWAY_DRAIN_TIMEOUT_SECONDS: u32 = 30;
 pub const DEFAULT_PROVIDER_COOLDOWN_SECONDS: u32 = 30;
 pub const DEFAULT_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS: u32 = 60;
+pub const DEFAULT_RESPONSE_CACHE_TTL_SECONDS: u32 = 300;
 pub const DEFAULT_UPSTREAM_FIRST_BYTE_TIMEOUT_SECONDS: u32 = 0;
 pub const DEFAULT_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS: u32 = 0;
 pub const DEFAULT_UPSTREAM_REQUEST_TIMEOUT_NON_STREAMING_SECONDS: u32 = 0;
@@ -29,6 +70,7 @@ const DEFAULT_FAILOVER_MAX_PROVIDERS_TO_TRY: u32 = 5;
 const DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 5;
 const DEFAULT_CIRCUIT_BREAKER_OPEN_DURATION_MINUTES: u32 = 30;
 const DEFAULT_ENABLE_CIRCUIT_BREAKER_NOTICE: bool = false;
+const DEFAULT_ENABLE_CIRCUIT_RECOVERY_NOTICE: bool = false;
 const DEFAULT_INTERCEPT_ANTHROPIC_WARMUP_REQUESTS: bool = false;
 const DEFAULT_ENABLE_THINKING_SIGNATURE_RECTIFIER: bool = true;
 const DEFAULT_ENABLE_CODEX_SESSION_ID_COMPLETION: bool = true;
@@ -38,8 +80,25 @@ const DEFAULT_RESPONSE_FIXER_FIX_SSE_FORMAT: bool = true;
 const DEFAULT_RESPONSE_FIXER_FIX_TRUNCATED_JSON: bool = true;
 const DEFAULT_RESPONSE_FIXER_MAX_JSON_DEPTH: u32 = 200;
 const DEFAULT_RESPONSE_FIXER_MAX_FIX_SIZE: u32 = 1024 * 1024;
+const DEFAULT_ENABLE_LOW_POWER_MODE: bool = false;
+const DEFAULT_LOW_POWER_IDLE_MINUTES: u32 = 15;
+const DEFAULT_NOTICE_QUIET_HOURS_ENABLED: bool = false;
+const DEFAULT_NOTICE_QUIET_HOURS_START: &str = "23:00";
+const DEFAULT_NOTICE_QUIET_HOURS_END: &str = "08:00";
+const DEFAULT_NOTICE_QUIET_HOURS_SUMMARY: bool = true;
+const DEFAULT_PRIORITY_LANES_ENABLED: bool = false;
+const DEFAULT_BACKGROUND_LANE_MAX_CONCURRENCY: u32 = 2;
+const DEFAULT_BACKGROUND_LANE_QUEUE_TIMEOUT_SECONDS: u32 = 30;
+const DEFAULT_LATENCY_PROBE_INTERVAL_MINUTES: u32 = 0;
+const DEFAULT_ENABLE_PROMPT_CACHE_INJECTION: bool = false;
+const DEFAULT_EXCLUDE_REASONING_FROM_LOGS: bool = false;
+const DEFAULT_FORWARD_TRACE_HEADERS: bool = false;
+const DEFAULT_CAPTURE_SSE_EVENTS: bool = false;
+const DEFAULT_RESPONSE_CACHE_COMPLETIONS: bool = false;
+const DEFAULT_ENABLE_REQUEST_REDACTION: bool = false;
 const MAX_PROVIDER_COOLDOWN_SECONDS: u32 = 60 * 60;
 const MAX_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS: u32 = 60 * 60;
+const MAX_RESPONSE_CACHE_TTL_SECONDS: u32 = 24 * 60 * 60;
 const MAX_UPSTREAM_FIRST_BYTE_TIMEOUT_SECONDS: u32 = 60 * 60;
 const MAX_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS: u32 = 60 * 60;
 const MAX_UPSTREAM_REQUEST_TIMEOUT_NON_STREAMING_SECONDS: u32 = 24 * 60 * 60;
@@ -50,6 +109,59 @@ const MAX_CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 50;
 const MAX_CIRCUIT_BREAKER_OPEN_DURATION_MINUTES: u32 = 24 * 60;
 const MAX_RESPONSE_FIXER_MAX_JSON_DEPTH: u32 = 2000;
 const MAX_RESPONSE_FIXER_MAX_FIX_SIZE: u32 = 16 * 1024 * 1024;
+const MAX_LOW_POWER_IDLE_MINUTES: u32 = 24 * 60;
+const MAX_BACKGROUND_LANE_MAX_CONCURRENCY: u32 = 64;
+const MAX_BACKGROUND_LANE_QUEUE_TIMEOUT_SECONDS: u32 = 10 * 60;
+const MAX_LATENCY_PROBE_INTERVAL_MINUTES: u32 = 24 * 60;
+const MAX_CLI_MAX_CONCURRENT_STREAMS: u32 = 256;
+const DEFAULT_RETRY_BACKOFF_BASE_MS: u32 = 80;
+const DEFAULT_RETRY_BACKOFF_MAX_MS: u32 = 800;
+const DEFAULT_RETRY_BACKOFF_MULTIPLIER: f64 = 2.0;
+const MAX_RETRY_BACKOFF_MS: u32 = 120_000;
+const MAX_RETRY_BACKOFF_MULTIPLIER: f64 = 10.0;
+const MAX_RETRY_BACKOFF_STATUSES: usize = 16;
+const DEFAULT_ATTEMPT_LOG_COMPACT_AFTER_DAYS: u32 = 7;
+const MAX_ATTEMPT_LOG_DAYS: u32 = 3650;
+const DEFAULT_LATENCY_ROUTING_WINDOW_MINUTES: u32 = 30;
+const MAX_LATENCY_ROUTING_WINDOW_MINUTES: u32 = 1440;
+const DEFAULT_RECENT_ERROR_MAX_TTL_SECONDS: u32 = 60 * 60;
+const MAX_RECENT_ERROR_MAX_TTL_SECONDS: u32 = 24 * 60 * 60;
+const DEFAULT_RECENT_TRACE_DEDUP_TTL_SECONDS: u32 = 10;
+const MAX_RECENT_TRACE_DEDUP_TTL_SECONDS: u32 = 5 * 60;
+const DEFAULT_HEDGE_DELAY_MS: u32 = 2000;
+const MIN_HEDGE_DELAY_MS: u32 = 100;
+const MAX_HEDGE_DELAY_MS: u32 = 60_000;
+const DEFAULT_STREAM_TOOL_PAUSE_IDLE_TIMEOUT_SECONDS: u32 = 10 * 60;
+const MIN_STREAM_TOOL_PAUSE_IDLE_TIMEOUT_SECONDS: u32 = 10;
+const DEFAULT_UPDATE_MIRROR_TIMEOUT_SECS: u32 = 15;
+const MIN_UPDATE_MIRROR_TIMEOUT_SECS: u32 = 3;
+const MAX_UPDATE_MIRROR_TIMEOUT_SECS: u32 = 300;
+const MAX_UPDATE_MIRRORS: usize = 8;
+pub const EGRESS_PROXY_SYSTEM: &str = "system";
+pub const EGRESS_PROXY_NONE: &str = "none";
+const MAX_EGRESS_PROXY_LEN: usize = 2048;
+const MAX_EGRESS_HOST_OVERRIDES: usize = 64;
+const MAX_EGRESS_HOST_OVERRIDE_IPS: usize = 16;
+const MAX_NOTICE_CHANNELS: usize = 20;
+const MAX_WSL_DISTRO_OVERRIDES: usize = 64;
+const MAX_GATEWAY_EXTRA_LISTENERS: usize = 8;
+const MAX_GATEWAY_CLIENT_KEYS: usize = 32;
+const MAX_GATEWAY_CLIENT_KEY_NAME_LEN: usize = 64;
+const MAX_GATEWAY_IP_ALLOWLIST: usize = 64;
+const MAX_GATEWAY_TLS_HOSTS: usize = 32;
+const MAX_INTERCEPT_RULES: usize = 32;
+const MAX_INTERCEPT_RULE_PREDICATES: usize = 16;
+const MAX_INTERCEPT_RULE_RESPONSE_BODY_BYTES: usize = 64 * 1024;
+const MAX_HEADER_SANITIZE_RULES: usize = 32;
+const MAX_HEADER_SANITIZE_RULE_HEADERS: usize = 64;
+const MAX_REQUEST_REDACTION_CUSTOM_PATTERNS: usize = 32;
+const MAX_REQUEST_REDACTION_PATTERN_LEN: usize = 512;
+const MAX_FAILOVER_RULES: usize = 32;
+const MAX_FAILOVER_RULE_STATUSES: usize = 32;
+const MAX_FAILOVER_RULE_BODY_REGEX_LEN: usize = 512;
+const MAX_GATEWAY_DRAIN_TIMEOUT_SECONDS: u32 = 10 * 60;
+const MIN_GATEWAY_ACCESS_TOKEN_LEN: usize = 16;
+const MAX_GATEWAY_ACCESS_TOKEN_LEN: usize = 256;
 const LEGACY_IDENTIFIER: &str = "io.aio.gateway";
 const DEFAULT_UPDATE_RELEASES_URL: &str = "https://github.com/dyndynjyxa/aio-coding-hub/releases";
 const CACHE_TTL: Duration = Duration::from_secs(5);
@@ -79,6 +191,39 @@ impl Default for GatewayListenMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InflightDedupMode {
+    #[default]
+    Off,
+    // Stream the original upstream response to the duplicate client as well.
+    Attach,
+    // Reject the duplicate locally (409).
+    Reject,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AppLanguage {
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en")]
+    En,
+}
+
+impl Default for AppLanguage {
+    fn default() -> Self {
+        Self::ZhCn
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(default)]
 pub struct WslTargetCli {
@@ -97,261 +242,2645 @@ impl Default for WslTargetCli {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CliStreamCaps {
+    pub claude: u32,
+    pub codex: u32,
+    pub gemini: u32,
+}
+
+impl CliStreamCaps {
+    /// Cap for `cli_key`; 0 (or an unknown cli) = unlimited.
+    pub fn for_cli(&self, cli_key: &str) -> u32 {
+        match cli_key {
+            "claude" => self.claude,
+            "codex" => self.codex,
+            "gemini" => self.gemini,
+            _ => 0,
+        }
+    }
+
+    fn clamp(&mut self, max: u32) -> bool {
+        let mut changed = false;
+        for value in [&mut self.claude, &mut self.codex, &mut self.gemini] {
+            if *value > max {
+                *value = max;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryBackoffStrategy {
+    // base * attempt, capped at max.
+    #[default]
+    Linear,
+    // base * multiplier^(attempt - 1), capped at max.
+    Exponential,
+}
+
+/// Wait before retrying the same provider after a retryable upstream status (rate limit / timeout).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryBackoffConfig {
+    pub strategy: RetryBackoffStrategy,
+    pub base_ms: u32,
+    pub max_ms: u32,
+    // Exponential only.
+    pub multiplier: f64,
+    // Random share removed from each delay: 0 = fixed delays, 100 = full jitter (uniform in [0, delay]).
+    pub jitter_percent: u32,
+    // Upstream statuses that back off before the retry; other retryable failures retry immediately.
+    pub statuses: Vec<u16>,
+}
+
+impl Default for RetryBackoffConfig {
+    fn default() -> Self {
+        Self {
+            strategy: RetryBackoffStrategy::Linear,
+            base_ms: DEFAULT_RETRY_BACKOFF_BASE_MS,
+            max_ms: DEFAULT_RETRY_BACKOFF_MAX_MS,
+            multiplier: DEFAULT_RETRY_BACKOFF_MULTIPLIER,
+            jitter_percent: 0,
+            statuses: vec![408, 429],
+        }
+    }
+}
+
+impl RetryBackoffConfig {
+    fn sanitize(&mut self) -> bool {
+        let before = self.clone();
+        self.max_ms = self.max_ms.min(MAX_RETRY_BACKOFF_MS);
+        self.base_ms = self.base_ms.min(self.max_ms);
+        if !self.multiplier.is_finite() {
+            self.multiplier = DEFAULT_RETRY_BACKOFF_MULTIPLIER;
+        }
+        self.multiplier = self.multiplier.clamp(1.0, MAX_RETRY_BACKOFF_MULTIPLIER);
+        self.jitter_percent = self.jitter_percent.min(100);
+        let mut seen = std::collections::HashSet::new();
+        self.statuses
+            .retain(|status| (400..=599).contains(status) && seen.insert(*status));
+        self.statuses.truncate(MAX_RETRY_BACKOFF_STATUSES);
+        *self != before
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.max_ms > MAX_RETRY_BACKOFF_MS {
+            return Err(format!(
+                "retry_backoff.max_ms must be <= {MAX_RETRY_BACKOFF_MS}"
+            ));
+        }
+        if self.base_ms > self.max_ms {
+            return Err("retry_backoff.base_ms must be <= retry_backoff.max_ms".to_string());
+        }
+        if !self.multiplier.is_finite()
+            || !(1.0..=MAX_RETRY_BACKOFF_MULTIPLIER).contains(&self.multiplier)
+        {
+            return Err(format!(
+                "retry_backoff.multiplier must be between 1 and {MAX_RETRY_BACKOFF_MULTIPLIER}"
+            ));
+        }
+        if self.jitter_percent > 100 {
+            return Err("retry_backoff.jitter_percent must be <= 100".to_string());
+        }
+        if self.statuses.len() > MAX_RETRY_BACKOFF_STATUSES {
+            return Err(format!(
+                "retry_backoff.statuses must have <= {MAX_RETRY_BACKOFF_STATUSES} entries"
+            ));
+        }
+        if let Some(status) = self.statuses.iter().find(|s| !(400..=599).contains(*s)) {
+            return Err(format!(
+                "retry_backoff.statuses contains invalid status {status} (expected 400..=599)"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// `request_attempt_logs` retention, independent of `log_retention_days`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttemptLogRetentionConfig {
+    // 0 = follow `log_retention_days`.
+    pub retention_days: u32,
+    // Older attempts are kept only for failed traces (successful traces keep `attempts_json` in
+    // request_logs); 0 = never compact.
+    pub compact_after_days: u32,
+}
+
+impl Default for AttemptLogRetentionConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: 0,
+            compact_after_days: DEFAULT_ATTEMPT_LOG_COMPACT_AFTER_DAYS,
+        }
+    }
+}
+
+impl AttemptLogRetentionConfig {
+    /// Resolves `retention_days = 0` against the request log retention.
+    pub fn resolved(self, log_retention_days: u32) -> Self {
+        Self {
+            retention_days: if self.retention_days == 0 {
+                log_retention_days
+            } else {
+                self.retention_days
+            },
+            compact_after_days: self.compact_after_days,
+        }
+    }
+
+    fn sanitize(&mut self) -> bool {
+        let before = *self;
+        self.retention_days = self.retention_days.min(MAX_ATTEMPT_LOG_DAYS);
+        self.compact_after_days = self.compact_after_days.min(MAX_ATTEMPT_LOG_DAYS);
+        *self != before
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.retention_days > MAX_ATTEMPT_LOG_DAYS {
+            return Err(format!(
+                "attempt_log_retention.retention_days must be <= {MAX_ATTEMPT_LOG_DAYS}"
+            ));
+        }
+        if self.compact_after_days > MAX_ATTEMPT_LOG_DAYS {
+            return Err(format!(
+                "attempt_log_retention.compact_after_days must be <= {MAX_ATTEMPT_LOG_DAYS}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Latency-based provider ordering driven by recent attempt TTFB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LatencyRoutingConfig {
+    pub enabled: bool,
+    // Samples older than the window are ignored; newer samples weigh more (linear decay).
+    pub window_minutes: u32,
+}
+
+impl Default for LatencyRoutingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_minutes: DEFAULT_LATENCY_ROUTING_WINDOW_MINUTES,
+        }
+    }
+}
+
+impl LatencyRoutingConfig {
+    fn sanitize(&mut self) -> bool {
+        let before = *self;
+        self.window_minutes = self
+            .window_minutes
+            .clamp(1, MAX_LATENCY_ROUTING_WINDOW_MINUTES);
+        *self != before
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.window_minutes == 0 || self.window_minutes > MAX_LATENCY_ROUTING_WINDOW_MINUTES {
+            return Err(format!(
+                "latency_routing.window_minutes must be between 1 and {MAX_LATENCY_ROUTING_WINDOW_MINUTES}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Gateway-side dedup of recent failures (replayed `GW_ALL_PROVIDERS_UNAVAILABLE`, shared trace ids).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecentErrorCacheConfig {
+    // Upper bound for replaying a cached "all providers unavailable" (otherwise its Retry-After). 0 = never replay.
+    pub error_max_ttl_seconds: u32,
+    // Identical requests within this window share one trace id.
+    pub trace_dedup_ttl_seconds: u32,
+}
+
+impl Default for RecentErrorCacheConfig {
+    fn default() -> Self {
+        Self {
+            error_max_ttl_seconds: DEFAULT_RECENT_ERROR_MAX_TTL_SECONDS,
+            trace_dedup_ttl_seconds: DEFAULT_RECENT_TRACE_DEDUP_TTL_SECONDS,
+        }
+    }
+}
+
+impl RecentErrorCacheConfig {
+    fn sanitize(&mut self) -> bool {
+        let before = *self;
+        self.error_max_ttl_seconds = self
+            .error_max_ttl_seconds
+            .min(MAX_RECENT_ERROR_MAX_TTL_SECONDS);
+        self.trace_dedup_ttl_seconds = self
+            .trace_dedup_ttl_seconds
+            .clamp(1, MAX_RECENT_TRACE_DEDUP_TTL_SECONDS);
+        *self != before
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.error_max_ttl_seconds > MAX_RECENT_ERROR_MAX_TTL_SECONDS {
+            return Err(format!(
+                "recent_error_cache.error_max_ttl_seconds must be <= {MAX_RECENT_ERROR_MAX_TTL_SECONDS}"
+            ));
+        }
+        if self.trace_dedup_ttl_seconds == 0
+            || self.trace_dedup_ttl_seconds > MAX_RECENT_TRACE_DEDUP_TTL_SECONDS
+        {
+            return Err(format!(
+                "recent_error_cache.trace_dedup_ttl_seconds must be between 1 and {MAX_RECENT_TRACE_DEDUP_TTL_SECONDS}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Hedged requests: race the second provider when the first one is slow to respond.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HedgedRequestsConfig {
+    pub enabled: bool,
+    // Without response headers from the first provider after this long, the next one is started too.
+    pub delay_ms: u32,
+}
+
+impl Default for HedgedRequestsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay_ms: DEFAULT_HEDGE_DELAY_MS,
+        }
+    }
+}
+
+impl HedgedRequestsConfig {
+    /// Hedge delay when hedging is enabled.
+    pub fn delay(&self) -> Option<Duration> {
+        self.enabled
+            .then(|| Duration::from_millis(u64::from(self.delay_ms)))
+    }
+
+    fn sanitize(&mut self) -> bool {
+        let before = *self;
+        self.delay_ms = self.delay_ms.clamp(MIN_HEDGE_DELAY_MS, MAX_HEDGE_DELAY_MS);
+        *self != before
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(MIN_HEDGE_DELAY_MS..=MAX_HEDGE_DELAY_MS).contains(&self.delay_ms) {
+            return Err(format!(
+                "hedged_requests.delay_ms must be between {MIN_HEDGE_DELAY_MS} and {MAX_HEDGE_DELAY_MS}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Longer stream idle timeout while a Claude turn is paused on server-side tool use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamToolPauseGraceConfig {
+    pub enabled: bool,
+    // Idle timeout once the stream has sent `ping` but no `message_delta` yet; applies only when
+    // `upstream_stream_idle_timeout_seconds` is set and never shortens it.
+    pub idle_timeout_seconds: u32,
+}
+
+impl Default for StreamToolPauseGraceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_timeout_seconds: DEFAULT_STREAM_TOOL_PAUSE_IDLE_TIMEOUT_SECONDS,
+        }
+    }
+}
+
+impl StreamToolPauseGraceConfig {
+    /// Tool-pause idle timeout when the grace is enabled.
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.enabled
+            .then(|| Duration::from_secs(u64::from(self.idle_timeout_seconds)))
+    }
+
+    fn sanitize(&mut self) -> bool {
+        let before = *self;
+        self.idle_timeout_seconds = self.idle_timeout_seconds.clamp(
+            MIN_STREAM_TOOL_PAUSE_IDLE_TIMEOUT_SECONDS,
+            MAX_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS,
+        );
+        *self != before
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(MIN_STREAM_TOOL_PAUSE_IDLE_TIMEOUT_SECONDS..=MAX_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS)
+            .contains(&self.idle_timeout_seconds)
+        {
+            return Err(format!(
+                "stream_tool_pause_grace.idle_timeout_seconds must be between {MIN_STREAM_TOOL_PAUSE_IDLE_TIMEOUT_SECONDS} and {MAX_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Fallback sources for the updater when GitHub is slow or unreachable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateMirrorConfig {
+    // Tried in order after the primary source. A `{url}` placeholder is replaced with the original
+    // GitHub URL; otherwise the original URL is appended (prefix-style GitHub proxies).
+    pub mirrors: Vec<String>,
+    // Check timeout / download stall timeout before falling back to the next source.
+    pub timeout_secs: u32,
+}
+
+impl Default for UpdateMirrorConfig {
+    fn default() -> Self {
+        Self {
+            mirrors: Vec::new(),
+            timeout_secs: DEFAULT_UPDATE_MIRROR_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl UpdateMirrorConfig {
+    fn sanitize(&mut self) -> bool {
+        let before = self.clone();
+        let mut seen = std::collections::HashSet::new();
+        self.mirrors = std::mem::take(&mut self.mirrors)
+            .into_iter()
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty() && seen.insert(m.clone()))
+            .take(MAX_UPDATE_MIRRORS)
+            .collect();
+        self.timeout_secs = self.timeout_secs.clamp(
+            MIN_UPDATE_MIRROR_TIMEOUT_SECS,
+            MAX_UPDATE_MIRROR_TIMEOUT_SECS,
+        );
+        *self != before
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.mirrors.len() > MAX_UPDATE_MIRRORS {
+            return Err(format!(
+                "update_mirror.mirrors must contain <= {MAX_UPDATE_MIRRORS} items"
+            ));
+        }
+        for mirror in &self.mirrors {
+            let mirror = mirror.trim();
+            if !(mirror.starts_with("http://") || mirror.starts_with("https://")) {
+                return Err(format!(
+                    "update_mirror.mirrors: {mirror} must start with http:// or https://"
+                ));
+            }
+        }
+        if !(MIN_UPDATE_MIRROR_TIMEOUT_SECS..=MAX_UPDATE_MIRROR_TIMEOUT_SECS)
+            .contains(&self.timeout_secs)
+        {
+            return Err(format!(
+                "update_mirror.timeout_secs must be between {MIN_UPDATE_MIRROR_TIMEOUT_SECS} and {MAX_UPDATE_MIRROR_TIMEOUT_SECS}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EgressIpFamily {
+    #[default]
+    Auto,
+    Ipv4,
+    Ipv6,
+}
+
+/// Pins a provider host to fixed addresses, like a hosts file entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EgressHostOverride {
+    pub host: String,
+    pub ips: Vec<String>,
+}
+
+/// How the gateway reaches upstream providers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EgressNetworkConfig {
+    // `system` (proxy env vars) | `none` | http(s):// / socks5(h):// URL. Providers whose own
+    // `upstream_proxy` is `system` follow this proxy.
+    pub proxy: String,
+    pub host_overrides: Vec<EgressHostOverride>,
+    // Restricts resolved addresses (including host overrides) to one IP family.
+    pub ip_family: EgressIpFamily,
+}
+
+impl Default for EgressNetworkConfig {
+    fn default() -> Self {
+        Self {
+            proxy: EGRESS_PROXY_SYSTEM.to_string(),
+            host_overrides: Vec::new(),
+            ip_family: EgressIpFamily::Auto,
+        }
+    }
+}
+
+impl EgressNetworkConfig {
+    fn sanitize(&mut self) -> bool {
+        let before = self.clone();
+        let proxy = self.proxy.trim();
+        self.proxy = if proxy.is_empty() {
+            EGRESS_PROXY_SYSTEM.to_string()
+        } else {
+            proxy.to_string()
+        };
+        let mut seen = std::collections::HashSet::new();
+        self.host_overrides = std::mem::take(&mut self.host_overrides)
+            .into_iter()
+            .map(|item| EgressHostOverride {
+                host: item.host.trim().trim_end_matches('.').to_ascii_lowercase(),
+                ips: item
+                    .ips
+                    .iter()
+                    .map(|ip| ip.trim().to_string())
+                    .filter(|ip| !ip.is_empty())
+                    .collect(),
+            })
+            .filter(|item| {
+                !item.host.is_empty() && !item.ips.is_empty() && seen.insert(item.host.clone())
+            })
+            .take(MAX_EGRESS_HOST_OVERRIDES)
+            .collect();
+        *self != before
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let proxy = self.proxy.trim();
+        if !proxy.is_empty() && proxy != EGRESS_PROXY_SYSTEM && proxy != EGRESS_PROXY_NONE {
+            if proxy.len() > MAX_EGRESS_PROXY_LEN {
+                return Err("egress_network.proxy is too long".to_string());
+            }
+            let valid = reqwest::Url::parse(proxy).is_ok_and(|url| {
+                matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h")
+                    && url.host_str().is_some_and(|host| !host.is_empty())
+            });
+            if !valid {
+                return Err(
+                    "egress_network.proxy must be 'system', 'none', or an http(s):// / socks5:// URL"
+                        .to_string(),
+                );
+            }
+        }
+        if self.host_overrides.len() > MAX_EGRESS_HOST_OVERRIDES {
+            return Err(format!(
+                "egress_network.host_overrides must contain <= {MAX_EGRESS_HOST_OVERRIDES} items"
+            ));
+        }
+        for item in &self.host_overrides {
+            let host = item.host.trim();
+            if host.is_empty() || host.contains(['/', ':', ' ']) {
+                return Err(format!(
+                    "egress_network.host_overrides: invalid host '{host}'"
+                ));
+            }
+            if item.ips.is_empty() || item.ips.len() > MAX_EGRESS_HOST_OVERRIDE_IPS {
+                return Err(format!(
+                    "egress_network.host_overrides: {host} must have 1..={MAX_EGRESS_HOST_OVERRIDE_IPS} ips"
+                ));
+            }
+            if let Some(ip) = item
+                .ips
+                .iter()
+                .find(|ip| ip.trim().parse::<std::net::IpAddr>().is_err())
+            {
+                return Err(format!(
+                    "egress_network.host_overrides: {host} has invalid ip '{ip}'"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
-pub struct AppSettings {
-    pub schema_version: u32,
-    pub preferred_port: u16,
-    // Gateway listen mode (aligned with code-switch-r): localhost / wsl_auto / lan / custom.
-    pub gateway_listen_mode: GatewayListenMode,
-    // Custom listen address input (host or host:port).
-    pub gateway_custom_listen_address: String,
-    // WSL auto-config enable switch and target CLI selection.
-    pub wsl_auto_config: bool,
-    pub wsl_target_cli: WslTargetCli,
-    pub auto_start: bool,
-    pub tray_enabled: bool,
-    pub log_retention_days: u32,
-    pub provider_cooldown_seconds: u32,
-    pub provider_base_url_ping_cache_ttl_seconds: u32,
-    pub upstream_first_byte_timeout_seconds: u32,
-    pub upstream_stream_idle_timeout_seconds: u32,
-    pub upstream_request_timeout_non_streaming_seconds: u32,
-    pub update_releases_url: String,
-    pub failover_max_attempts_per_provider: u32,
-    pub failover_max_providers_to_try: u32,
-    pub circuit_breaker_failure_threshold: u32,
-    pub circuit_breaker_open_duration_minutes: u32,
-    // Circuit breaker notice toggle (default disabled).
-    pub enable_circuit_breaker_notice: bool,
-    // CCH-aligned gateway feature toggles (warmup default disabled; others default enabled).
-    pub intercept_anthropic_warmup_requests: bool,
-    pub enable_thinking_signature_rectifier: bool,
-    // Codex Session ID completion (default enabled).
-    pub enable_codex_session_id_completion: bool,
-    // Response fixer (default enabled).
-    pub enable_response_fixer: bool,
-    pub response_fixer_fix_encoding: bool,
-    pub response_fixer_fix_sse_format: bool,
-    pub response_fixer_fix_truncated_json: bool,
-    pub response_fixer_max_json_depth: u32,
-    pub response_fixer_max_fix_size: u32,
+pub struct WslDistroOverride {
+    pub distro: String,
+    // false = skip this distro unless it is explicitly selected.
+    pub enabled: bool,
+    pub targets: WslTargetCli,
 }
 
-impl Default for AppSettings {
+impl Default for WslDistroOverride {
     fn default() -> Self {
         Self {
-            schema_version: SCHEMA_VERSION,
-            preferred_port: DEFAULT_GATEWAY_PORT,
-            gateway_listen_mode: GatewayListenMode::Localhost,
-            gateway_custom_listen_address: String::new(),
-            wsl_auto_config: false,
-            wsl_target_cli: WslTargetCli::default(),
-            auto_start: false,
-            tray_enabled: true,
-            log_retention_days: DEFAULT_LOG_RETENTION_DAYS,
-            provider_cooldown_seconds: DEFAULT_PROVIDER_COOLDOWN_SECONDS,
-            provider_base_url_ping_cache_ttl_seconds:
-                DEFAULT_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS,
-            upstream_first_byte_timeout_seconds: DEFAULT_UPSTREAM_FIRST_BYTE_TIMEOUT_SECONDS,
-            upstream_stream_idle_timeout_seconds: DEFAULT_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS,
-            upstream_request_timeout_non_streaming_seconds:
-                DEFAULT_UPSTREAM_REQUEST_TIMEOUT_NON_STREAMING_SECONDS,
-            update_releases_url: DEFAULT_UPDATE_RELEASES_URL.to_string(),
-            failover_max_attempts_per_provider: DEFAULT_FAILOVER_MAX_ATTEMPTS_PER_PROVIDER,
-            failover_max_providers_to_try: DEFAULT_FAILOVER_MAX_PROVIDERS_TO_TRY,
-            circuit_breaker_failure_threshold: DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD,
-            circuit_breaker_open_duration_minutes: DEFAULT_CIRCUIT_BREAKER_OPEN_DURATION_MINUTES,
-            enable_circuit_breaker_notice: DEFAULT_ENABLE_CIRCUIT_BREAKER_NOTICE,
-            intercept_anthropic_warmup_requests: DEFAULT_INTERCEPT_ANTHROPIC_WARMUP_REQUESTS,
-            enable_thinking_signature_rectifier: DEFAULT_ENABLE_THINKING_SIGNATURE_RECTIFIER,
-            enable_codex_session_id_completion: DEFAULT_ENABLE_CODEX_SESSION_ID_COMPLETION,
-            enable_response_fixer: DEFAULT_ENABLE_RESPONSE_FIXER,
-            response_fixer_fix_encoding: DEFAULT_RESPONSE_FIXER_FIX_ENCODING,
-            response_fixer_fix_sse_format: DEFAULT_RESPONSE_FIXER_FIX_SSE_FORMAT,
-            response_fixer_fix_truncated_json: DEFAULT_RESPONSE_FIXER_FIX_TRUNCATED_JSON,
-            response_fixer_max_json_depth: DEFAULT_RESPONSE_FIXER_MAX_JSON_DEPTH,
-            response_fixer_max_fix_size: DEFAULT_RESPONSE_FIXER_MAX_FIX_SIZE,
+            distro: String::new(),
+            enabled: true,
+            targets: WslTargetCli::default(),
         }
     }
 }
 
-fn sanitize_failover_settings(settings: &mut AppSettings) -> bool {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GatewayExtraListener {
+    pub port: u16,
+    pub cli_key: String,
+    pub enabled: bool,
+}
+
+impl Default for GatewayExtraListener {
+    fn default() -> Self {
+        Self {
+            port: 0,
+            cli_key: String::new(),
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GatewayClientKey {
+    pub id: String,
+    pub name: String,
+    pub key: String,
+    pub enabled: bool,
+    // Requests per minute for this key; 0 = unlimited.
+    pub rate_limit_per_minute: u32,
+}
+
+impl Default for GatewayClientKey {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            key: String::new(),
+            enabled: true,
+            rate_limit_per_minute: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterceptPredicateOp {
+    // Pointer resolves to any value (including null).
+    Exists,
+    Equals,
+    // Strings compared after trim + lowercase.
+    EqualsIgnoreCase,
+    // String contains `value`, or array contains an element equal to `value`.
+    Contains,
+    // Array / object / string length equals `value`.
+    LenEq,
+}
+
+impl Default for InterceptPredicateOp {
+    fn default() -> Self {
+        Self::Exists
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InterceptBodyPredicate {
+    // JSON pointer into the request body (RFC 6901), e.g. "/messages/0/role"; "" = the whole body.
+    pub pointer: String,
+    pub op: InterceptPredicateOp,
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InterceptRule {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    // Empty = any CLI.
+    pub cli_key: String,
+    // Empty = any method.
+    pub method: String,
+    // Exact forwarded path; a trailing `*` matches by prefix.
+    pub path: String,
+    // All predicates must match; empty = match on path / method only.
+    pub body_predicates: Vec<InterceptBodyPredicate>,
+    pub response_status: u16,
+    pub response_content_type: String,
+    // Placeholders: {{trace_id}} / {{model}} / {{created_at}}.
+    pub response_body: String,
+}
+
+impl Default for InterceptRule {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            enabled: true,
+            cli_key: String::new(),
+            method: String::new(),
+            path: String::new(),
+            body_predicates: Vec::new(),
+            response_status: 200,
+            response_content_type: "application/json; charset=utf-8".to_string(),
+            response_body: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeaderSanitizeRule {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    // Empty = any CLI.
+    pub cli_key: String,
+    // Empty = every provider.
+    pub provider_ids: Vec<i64>,
+    // Header names (case-insensitive); a trailing `*` matches by prefix, e.g. "x-stainless-*".
+    pub headers: Vec<String>,
+}
+
+impl Default for HeaderSanitizeRule {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            enabled: true,
+            cli_key: String::new(),
+            provider_ids: Vec::new(),
+            headers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailoverRuleDecision {
+    RetrySameProvider,
+    SwitchProvider,
+    Abort,
+}
+
+/// Overrides the built-in status classification of an upstream error response; first match wins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FailoverRule {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    // Empty = any CLI.
+    pub cli_key: String,
+    // Empty = every provider.
+    pub provider_ids: Vec<i64>,
+    // Upstream status codes (400-599); empty = any error status.
+    pub statuses: Vec<u16>,
+    // Regex searched in the upstream error body; empty = status match only.
+    pub body_regex: String,
+    pub decision: FailoverRuleDecision,
+}
+
+impl Default for FailoverRule {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            enabled: true,
+            cli_key: String::new(),
+            provider_ids: Vec::new(),
+            statuses: Vec::new(),
+            body_regex: String::new(),
+            decision: FailoverRuleDecision::SwitchProvider,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionBuiltin {
+    // Provider API keys / tokens (sk-..., ghp_..., AIza..., xox?-...).
+    ApiKey,
+    // AWS access key ids and secret access keys.
+    AwsSecret,
+    Email,
+}
+
+impl RedactionBuiltin {
+    pub fn all() -> &'static [Self] {
+        &[Self::ApiKey, Self::AwsSecret, Self::Email]
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ApiKey => "api_key",
+            Self::AwsSecret => "aws_secret",
+            Self::Email => "email",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoticeChannelKind {
+    Webhook,
+    Telegram,
+    Slack,
+    Feishu,
+    Dingtalk,
+}
+
+impl NoticeChannelKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Webhook => "webhook",
+            Self::Telegram => "telegram",
+            Self::Slack => "slack",
+            Self::Feishu => "feishu",
+            Self::Dingtalk => "dingtalk",
+        }
+    }
+}
+
+impl Default for NoticeChannelKind {
+    fn default() -> Self {
+        Self::Webhook
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoticeEventKind {
+    CircuitBreaker,
+    Budget,
+    General,
+}
+
+impl NoticeEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CircuitBreaker => "circuit_breaker",
+            Self::Budget => "budget",
+            Self::General => "general",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoticeChannelConfig {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub kind: NoticeChannelKind,
+    // Webhook / Slack / Feishu / DingTalk: target URL. Telegram: optional API base (default api.telegram.org).
+    pub url: String,
+    pub bot_token: String,
+    pub chat_id: String,
+    // Feishu / DingTalk signing secret (optional).
+    pub secret: String,
+    // Empty = all event kinds.
+    pub events: Vec<NoticeEventKind>,
+}
+
+impl NoticeChannelConfig {
+    pub fn accepts(&self, event: NoticeEventKind) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub schema_version: u32,
+    pub preferred_port: u16,
+    // Gateway listen mode (aligned with code-switch-r): localhost / wsl_auto / lan / custom.
+    pub gateway_listen_mode: GatewayListenMode,
+    // Custom listen address input (host or host:port).
+    pub gateway_custom_listen_address: String,
+    // WSL auto-config enable switch and target CLI selection.
+    pub wsl_auto_config: bool,
+    pub wsl_target_cli: WslTargetCli,
+    pub auto_start: bool,
+    pub tray_enabled: bool,
+    pub log_retention_days: u32,
+    pub provider_cooldown_seconds: u32,
+    pub provider_base_url_ping_cache_ttl_seconds: u32,
+    pub upstream_first_byte_timeout_seconds: u32,
+    pub upstream_stream_idle_timeout_seconds: u32,
+    pub upstream_request_timeout_non_streaming_seconds: u32,
+    pub update_releases_url: String,
+    pub failover_max_attempts_per_provider: u32,
+    pub failover_max_providers_to_try: u32,
+    pub circuit_breaker_failure_threshold: u32,
+    pub circuit_breaker_open_duration_minutes: u32,
+    // Circuit breaker notice toggle (default disabled).
+    pub enable_circuit_breaker_notice: bool,
+    // CCH-aligned gateway feature toggles (warmup default disabled; others default enabled).
+    pub intercept_anthropic_warmup_requests: bool,
+    pub enable_thinking_signature_rectifier: bool,
+    // Codex Session ID completion (default enabled).
+    pub enable_codex_session_id_completion: bool,
+    // Response fixer (default enabled).
+    pub enable_response_fixer: bool,
+    pub response_fixer_fix_encoding: bool,
+    pub response_fixer_fix_sse_format: bool,
+    pub response_fixer_fix_truncated_json: bool,
+    pub response_fixer_max_json_depth: u32,
+    pub response_fixer_max_fix_size: u32,
+    // Language used for backend-emitted text (notices, gateway logs/errors).
+    pub ui_language: AppLanguage,
+    // Low-power mode: suspend background work (base_url pings, price sync, log cleanup) on battery or when idle.
+    pub enable_low_power_mode: bool,
+    pub low_power_idle_minutes: u32,
+    // External notice channels (webhook / Telegram / Slack / Feishu / DingTalk), filtered per event kind.
+    pub notice_channels: Vec<NoticeChannelConfig>,
+    // Notice quiet hours (local time, HH:MM). Non-error notices are held and optionally summarized afterwards.
+    pub notice_quiet_hours_enabled: bool,
+    pub notice_quiet_hours_start: String,
+    pub notice_quiet_hours_end: String,
+    pub notice_quiet_hours_summary: bool,
+    // Notice when a provider's circuit closes again (open period expired) and when the first request
+    // after that succeeds.
+    pub enable_circuit_recovery_notice: bool,
+    // Per-distro WSL target overrides; distros not listed use `wsl_target_cli`.
+    pub wsl_distro_overrides: Vec<WslDistroOverride>,
+    // Bearer token required from non-loopback clients (LAN / WSL). Empty = disabled.
+    pub gateway_access_token: String,
+    // Serve the gateway over HTTPS with a certificate issued by the local CA.
+    pub gateway_tls_enabled: bool,
+    // Extra hostnames / IPs (LAN name, static LAN address, ...) added to the TLS certificate SANs
+    // and the local CA's name constraints.
+    pub gateway_tls_hosts: Vec<String>,
+    // Additional listeners on the same host; each port maps every path to one fixed cli_key.
+    pub gateway_extra_listeners: Vec<GatewayExtraListener>,
+    // Named per-client keys for non-loopback clients (per-key rate limit + usage attribution).
+    pub gateway_client_keys: Vec<GatewayClientKey>,
+    // CIDR allowlist for non-loopback clients; empty = allow any address.
+    pub gateway_ip_allowlist: Vec<String>,
+    // Seconds `gateway_stop` waits for in-flight requests / streams to finish; 0 = stop immediately.
+    pub gateway_drain_timeout_seconds: u32,
+    // Updater release channel; beta follows separate release metadata.
+    pub update_channel: UpdateChannel,
+    // Download updates in the background and install them on the next quit / restart.
+    pub update_background_download: bool,
+    // Mirror / fallback download sources for the updater (China-friendly networks).
+    pub update_mirror: UpdateMirrorConfig,
+    // Response fixer: repair truncated / double-escaped tool call arguments (opt-in, default disabled).
+    pub response_fixer_fix_tool_args: bool,
+    // Response fixer dry-run: analyze and record what would change, but forward the original bytes.
+    pub response_fixer_dry_run: bool,
+    // User-configurable interception rules (path + body predicates -> local response); evaluated in order.
+    pub intercept_rules: Vec<InterceptRule>,
+    // Identical request (same fingerprint) arriving while the original is still in flight: off / attach / reject.
+    pub inflight_dedup_mode: InflightDedupMode,
+    // Classify requests into interactive / background lanes (title generation, summaries, haiku side calls).
+    pub priority_lanes_enabled: bool,
+    // Max concurrent background-lane requests; extra ones queue. 0 = no queueing.
+    pub background_lane_max_concurrency: u32,
+    // Max seconds a background request waits in the queue before being forwarded anyway.
+    pub background_lane_queue_timeout_seconds: u32,
+    // Provider tried first for background-lane requests (e.g. a cheaper one); None = same order as interactive.
+    pub background_lane_provider_id: Option<i64>,
+    // Client headers stripped before forwarding (telemetry / fingerprinting); evaluated per upstream attempt.
+    pub header_sanitize_rules: Vec<HeaderSanitizeRule>,
+    // User mapping of upstream status / error body to retry / switch / abort, checked before the built-in rules.
+    pub failover_rules: Vec<FailoverRule>,
+    // Mask secrets / PII in outgoing request bodies before forwarding (opt-in, default disabled).
+    pub enable_request_redaction: bool,
+    // Built-in redaction patterns applied when request redaction is enabled.
+    pub request_redaction_builtins: Vec<RedactionBuiltin>,
+    // Extra user regexes; every match is replaced with [REDACTED:custom].
+    pub request_redaction_custom_patterns: Vec<String>,
+    // Ping all enabled providers' base_urls every N minutes even without traffic. 0 = disabled.
+    pub latency_probe_interval_minutes: u32,
+    // Insert `cache_control: ephemeral` breakpoints into Claude requests that carry none (opt-in).
+    pub enable_prompt_cache_injection: bool,
+    // Strip thinking blocks / reasoning summaries from captured request & response bodies before persisting.
+    pub exclude_reasoning_from_logs: bool,
+    // Max simultaneous in-flight requests per CLI; extra ones get a local 429 with Retry-After. 0 = unlimited.
+    pub cli_max_concurrent_streams: CliStreamCaps,
+    // Backoff before retrying the same provider (strategy, delays, jitter, qualifying statuses).
+    pub retry_backoff: RetryBackoffConfig,
+    // Send `x-aio-trace-id` (and the client's `x-client-trace-id`, if any) to upstream providers.
+    pub forward_trace_headers: bool,
+    // Persist the raw SSE of streamed responses per trace so the final message can be reconstructed.
+    pub capture_sse_events: bool,
+    pub attempt_log_retention: AttemptLogRetentionConfig,
+    pub latency_routing: LatencyRoutingConfig,
+    // Per-provider cache TTL for idempotent non-stream responses (model lists, count_tokens). 0 = disabled.
+    pub response_cache_ttl_seconds: u32,
+    // Also cache identical non-streaming completions (same request fingerprint) within that TTL.
+    pub response_cache_completions: bool,
+    pub egress_network: EgressNetworkConfig,
+    pub recent_error_cache: RecentErrorCacheConfig,
+    pub hedged_requests: HedgedRequestsConfig,
+    pub stream_tool_pause_grace: StreamToolPauseGraceConfig,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            preferred_port: DEFAULT_GATEWAY_PORT,
+            gateway_listen_mode: GatewayListenMode::Localhost,
+            gateway_custom_listen_address: String::new(),
+            wsl_auto_config: false,
+            wsl_target_cli: WslTargetCli::default(),
+            auto_start: false,
+            tray_enabled: true,
+            log_retention_days: DEFAULT_LOG_RETENTION_DAYS,
+            provider_cooldown_seconds: DEFAULT_PROVIDER_COOLDOWN_SECONDS,
+            provider_base_url_ping_cache_ttl_seconds:
+                DEFAULT_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS,
+            upstream_first_byte_timeout_seconds: DEFAULT_UPSTREAM_FIRST_BYTE_TIMEOUT_SECONDS,
+            upstream_stream_idle_timeout_seconds: DEFAULT_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS,
+            upstream_request_timeout_non_streaming_seconds:
+                DEFAULT_UPSTREAM_REQUEST_TIMEOUT_NON_STREAMING_SECONDS,
+            update_releases_url: DEFAULT_UPDATE_RELEASES_URL.to_string(),
+            failover_max_attempts_per_provider: DEFAULT_FAILOVER_MAX_ATTEMPTS_PER_PROVIDER,
+            failover_max_providers_to_try: DEFAULT_FAILOVER_MAX_PROVIDERS_TO_TRY,
+            circuit_breaker_failure_threshold: DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+            circuit_breaker_open_duration_minutes: DEFAULT_CIRCUIT_BREAKER_OPEN_DURATION_MINUTES,
+            enable_circuit_breaker_notice: DEFAULT_ENABLE_CIRCUIT_BREAKER_NOTICE,
+            intercept_anthropic_warmup_requests: DEFAULT_INTERCEPT_ANTHROPIC_WARMUP_REQUESTS,
+            enable_thinking_signature_rectifier: DEFAULT_ENABLE_THINKING_SIGNATURE_RECTIFIER,
+            enable_codex_session_id_completion: DEFAULT_ENABLE_CODEX_SESSION_ID_COMPLETION,
+            enable_response_fixer: DEFAULT_ENABLE_RESPONSE_FIXER,
+            response_fixer_fix_encoding: DEFAULT_RESPONSE_FIXER_FIX_ENCODING,
+            response_fixer_fix_sse_format: DEFAULT_RESPONSE_FIXER_FIX_SSE_FORMAT,
+            response_fixer_fix_truncated_json: DEFAULT_RESPONSE_FIXER_FIX_TRUNCATED_JSON,
+            response_fixer_max_json_depth: DEFAULT_RESPONSE_FIXER_MAX_JSON_DEPTH,
+            response_fixer_max_fix_size: DEFAULT_RESPONSE_FIXER_MAX_FIX_SIZE,
+            ui_language: AppLanguage::ZhCn,
+            enable_low_power_mode: DEFAULT_ENABLE_LOW_POWER_MODE,
+            low_power_idle_minutes: DEFAULT_LOW_POWER_IDLE_MINUTES,
+            notice_channels: Vec::new(),
+            notice_quiet_hours_enabled: DEFAULT_NOTICE_QUIET_HOURS_ENABLED,
+            notice_quiet_hours_start: DEFAULT_NOTICE_QUIET_HOURS_START.to_string(),
+            notice_quiet_hours_end: DEFAULT_NOTICE_QUIET_HOURS_END.to_string(),
+            notice_quiet_hours_summary: DEFAULT_NOTICE_QUIET_HOURS_SUMMARY,
+            enable_circuit_recovery_notice: DEFAULT_ENABLE_CIRCUIT_RECOVERY_NOTICE,
+            wsl_distro_overrides: Vec::new(),
+            gateway_access_token: String::new(),
+            gateway_tls_enabled: false,
+            gateway_tls_hosts: Vec::new(),
+            gateway_extra_listeners: Vec::new(),
+            gateway_client_keys: Vec::new(),
+            gateway_ip_allowlist: Vec::new(),
+            gateway_drain_timeout_seconds: DEFAULT_GATEWAY_DRAIN_TIMEOUT_SECONDS,
+            update_channel: UpdateChannel::default(),
+            update_background_download: true,
+            update_mirror: UpdateMirrorConfig::default(),
+            response_fixer_fix_tool_args: false,
+            response_fixer_dry_run: false,
+            intercept_rules: Vec::new(),
+            inflight_dedup_mode: InflightDedupMode::default(),
+            priority_lanes_enabled: DEFAULT_PRIORITY_LANES_ENABLED,
+            background_lane_max_concurrency: DEFAULT_BACKGROUND_LANE_MAX_CONCURRENCY,
+            background_lane_queue_timeout_seconds: DEFAULT_BACKGROUND_LANE_QUEUE_TIMEOUT_SECONDS,
+            background_lane_provider_id: None,
+            header_sanitize_rules: Vec::new(),
+            failover_rules: Vec::new(),
+            enable_request_redaction: DEFAULT_ENABLE_REQUEST_REDACTION,
+            request_redaction_builtins: RedactionBuiltin::all().to_vec(),
+            request_redaction_custom_patterns: Vec::new(),
+            latency_probe_interval_minutes: DEFAULT_LATENCY_PROBE_INTERVAL_MINUTES,
+            enable_prompt_cache_injection: DEFAULT_ENABLE_PROMPT_CACHE_INJECTION,
+            exclude_reasoning_from_logs: DEFAULT_EXCLUDE_REASONING_FROM_LOGS,
+            cli_max_concurrent_streams: CliStreamCaps::default(),
+            retry_backoff: RetryBackoffConfig::default(),
+            forward_trace_headers: DEFAULT_FORWARD_TRACE_HEADERS,
+            capture_sse_events: DEFAULT_CAPTURE_SSE_EVENTS,
+            attempt_log_retention: AttemptLogRetentionConfig::default(),
+            latency_routing: LatencyRoutingConfig::default(),
+            response_cache_ttl_seconds: DEFAULT_RESPONSE_CACHE_TTL_SECONDS,
+            response_cache_completions: DEFAULT_RESPONSE_CACHE_COMPLETIONS,
+            egress_network: EgressNetworkConfig::default(),
+            recent_error_cache: RecentErrorCacheConfig::default(),
+            hedged_requests: HedgedRequestsConfig::default(),
+            stream_tool_pause_grace: StreamToolPauseGraceConfig::default(),
+        }
+    }
+}
+
+fn sanitize_failover_settings(settings: &mut AppSettings) -> bool {
+    let mut changed = false;
+
+    if settings.failover_max_attempts_per_provider == 0 {
+        settings.failover_max_attempts_per_provider = DEFAULT_FAILOVER_MAX_ATTEMPTS_PER_PROVIDER;
+        changed = true;
+    }
+    if settings.failover_max_providers_to_try == 0 {
+        settings.failover_max_providers_to_try = DEFAULT_FAILOVER_MAX_PROVIDERS_TO_TRY;
+        changed = true;
+    }
+
+    if settings.failover_max_attempts_per_provider > MAX_FAILOVER_MAX_ATTEMPTS_PER_PROVIDER {
+        settings.failover_max_attempts_per_provider = MAX_FAILOVER_MAX_ATTEMPTS_PER_PROVIDER;
+        changed = true;
+    }
+
+    if settings.failover_max_providers_to_try > MAX_FAILOVER_MAX_PROVIDERS_TO_TRY {
+        settings.failover_max_providers_to_try = MAX_FAILOVER_MAX_PROVIDERS_TO_TRY;
+        changed = true;
+    }
+
+    let providers = settings.failover_max_providers_to_try.max(1);
+    let max_attempts_for_providers = (MAX_FAILOVER_TOTAL_ATTEMPTS / providers).max(1);
+    if settings.failover_max_attempts_per_provider > max_attempts_for_providers {
+        settings.failover_max_attempts_per_provider = max_attempts_for_providers;
+        changed = true;
+    }
+
+    changed
+}
+
+fn sanitize_circuit_breaker_settings(settings: &mut AppSettings) -> bool {
+    let mut changed = false;
+
+    if settings.circuit_breaker_failure_threshold == 0 {
+        settings.circuit_breaker_failure_threshold = DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD;
+        changed = true;
+    }
+    if settings.circuit_breaker_open_duration_minutes == 0 {
+        settings.circuit_breaker_open_duration_minutes =
+            DEFAULT_CIRCUIT_BREAKER_OPEN_DURATION_MINUTES;
+        changed = true;
+    }
+
+    if settings.circuit_breaker_failure_threshold > MAX_CIRCUIT_BREAKER_FAILURE_THRESHOLD {
+        settings.circuit_breaker_failure_threshold = MAX_CIRCUIT_BREAKER_FAILURE_THRESHOLD;
+        changed = true;
+    }
+    if settings.circuit_breaker_open_duration_minutes > MAX_CIRCUIT_BREAKER_OPEN_DURATION_MINUTES {
+        settings.circuit_breaker_open_duration_minutes = MAX_CIRCUIT_BREAKER_OPEN_DURATION_MINUTES;
+        changed = true;
+    }
+
+    changed
+}
+
+fn sanitize_provider_cooldown_seconds(settings: &mut AppSettings) -> bool {
+    if settings.provider_cooldown_seconds > MAX_PROVIDER_COOLDOWN_SECONDS {
+        settings.provider_cooldown_seconds = MAX_PROVIDER_COOLDOWN_SECONDS;
+        return true;
+    }
+    false
+}
+
+fn sanitize_provider_base_url_ping_cache_ttl_seconds(settings: &mut AppSettings) -> bool {
+    let mut changed = false;
+
+    if settings.provider_base_url_ping_cache_ttl_seconds == 0 {
+        settings.provider_base_url_ping_cache_ttl_seconds =
+            DEFAULT_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS;
+        changed = true;
+    }
+
+    if settings.provider_base_url_ping_cache_ttl_seconds
+        > MAX_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS
+    {
+        settings.provider_base_url_ping_cache_ttl_seconds =
+            MAX_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn sanitize_upstream_timeouts(settings: &mut AppSettings) -> bool {
+    let mut changed = false;
+
+    if settings.upstream_first_byte_timeout_seconds > MAX_UPSTREAM_FIRST_BYTE_TIMEOUT_SECONDS {
+        settings.upstream_first_byte_timeout_seconds = MAX_UPSTREAM_FIRST_BYTE_TIMEOUT_SECONDS;
+        changed = true;
+    }
+    if settings.upstream_stream_idle_timeout_seconds > MAX_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS {
+        settings.upstream_stream_idle_timeout_seconds = MAX_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS;
+        changed = true;
+    }
+    if settings.upstream_request_timeout_non_streaming_seconds
+        > MAX_UPSTREAM_REQUEST_TIMEOUT_NON_STREAMING_SECONDS
+    {
+        settings.upstream_request_timeout_non_streaming_seconds =
+            MAX_UPSTREAM_REQUEST_TIMEOUT_NON_STREAMING_SECONDS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn sanitize_response_fixer_limits(settings: &mut AppSettings) -> bool {
+    let mut changed = false;
+
+    if settings.response_fixer_max_json_depth == 0 {
+        settings.response_fixer_max_json_depth = DEFAULT_RESPONSE_FIXER_MAX_JSON_DEPTH;
+        changed = true;
+    }
+    if settings.response_fixer_max_json_depth > MAX_RESPONSE_FIXER_MAX_JSON_DEPTH {
+        settings.response_fixer_max_json_depth = MAX_RESPONSE_FIXER_MAX_JSON_DEPTH;
+        changed = true;
+    }
+
+    if settings.response_fixer_max_fix_size == 0 {
+        settings.response_fixer_max_fix_size = DEFAULT_RESPONSE_FIXER_MAX_FIX_SIZE;
+        changed = true;
+    }
+    if settings.response_fixer_max_fix_size > MAX_RESPONSE_FIXER_MAX_FIX_SIZE {
+        settings.response_fixer_max_fix_size = MAX_RESPONSE_FIXER_MAX_FIX_SIZE;
+        changed = true;
+    }
+
+    changed
+}
+
+fn sanitize_low_power_idle_minutes(settings: &mut AppSettings) -> bool {
+    // 0 = idle trigger disabled (battery trigger only).
+    if settings.low_power_idle_minutes > MAX_LOW_POWER_IDLE_MINUTES {
+        settings.low_power_idle_minutes = MAX_LOW_POWER_IDLE_MINUTES;
+        return true;
+    }
+    false
+}
+
+fn sanitize_background_lane_limits(settings: &mut AppSettings) -> bool {
+    let mut changed = false;
+    if settings.background_lane_max_concurrency > MAX_BACKGROUND_LANE_MAX_CONCURRENCY {
+        settings.background_lane_max_concurrency = MAX_BACKGROUND_LANE_MAX_CONCURRENCY;
+        changed = true;
+    }
+    if settings.background_lane_queue_timeout_seconds > MAX_BACKGROUND_LANE_QUEUE_TIMEOUT_SECONDS {
+        settings.background_lane_queue_timeout_seconds = MAX_BACKGROUND_LANE_QUEUE_TIMEOUT_SECONDS;
+        changed = true;
+    }
+    changed
+}
+
+fn sanitize_cli_stream_caps(settings: &mut AppSettings) -> bool {
+    settings
+        .cli_max_concurrent_streams
+        .clamp(MAX_CLI_MAX_CONCURRENT_STREAMS)
+}
+
+fn sanitize_retry_backoff(settings: &mut AppSettings) -> bool {
+    settings.retry_backoff.sanitize()
+}
+
+fn sanitize_attempt_log_retention(settings: &mut AppSettings) -> bool {
+    settings.attempt_log_retention.sanitize()
+}
+
+fn sanitize_latency_routing(settings: &mut AppSettings) -> bool {
+    settings.latency_routing.sanitize()
+}
+
+fn sanitize_update_mirror(settings: &mut AppSettings) -> bool {
+    settings.update_mirror.sanitize()
+}
+
+fn sanitize_egress_network(settings: &mut AppSettings) -> bool {
+    settings.egress_network.sanitize()
+}
+
+fn sanitize_recent_error_cache(settings: &mut AppSettings) -> bool {
+    settings.recent_error_cache.sanitize()
+}
+
+fn sanitize_hedged_requests(settings: &mut AppSettings) -> bool {
+    settings.hedged_requests.sanitize()
+}
+
+fn sanitize_stream_tool_pause_grace(settings: &mut AppSettings) -> bool {
+    settings.stream_tool_pause_grace.sanitize()
+}
+
+fn sanitize_response_cache_ttl_seconds(settings: &mut AppSettings) -> bool {
+    if settings.response_cache_ttl_seconds > MAX_RESPONSE_CACHE_TTL_SECONDS {
+        settings.response_cache_ttl_seconds = MAX_RESPONSE_CACHE_TTL_SECONDS;
+        return true;
+    }
+    false
+}
+
+fn sanitize_latency_probe_interval_minutes(settings: &mut AppSettings) -> bool {
+    if settings.latency_probe_interval_minutes > MAX_LATENCY_PROBE_INTERVAL_MINUTES {
+        settings.latency_probe_interval_minutes = MAX_LATENCY_PROBE_INTERVAL_MINUTES;
+        return true;
+    }
+    false
+}
+
+pub fn parse_hhmm_minutes(value: &str) -> Option<u32> {
+    let (h, m) = value.trim().split_once(':')?;
+    let h: u32 = h.trim().parse().ok()?;
+    let m: u32 = m.trim().parse().ok()?;
+    if h > 23 || m > 59 {
+        return None;
+    }
+    Some(h * 60 + m)
+}
+
+fn sanitize_notice_quiet_hours(settings: &mut AppSettings) -> bool {
+    let mut changed = false;
+
+    if parse_hhmm_minutes(&settings.notice_quiet_hours_start).is_none() {
+        settings.notice_quiet_hours_start = DEFAULT_NOTICE_QUIET_HOURS_START.to_string();
+        changed = true;
+    }
+    if parse_hhmm_minutes(&settings.notice_quiet_hours_end).is_none() {
+        settings.notice_quiet_hours_end = DEFAULT_NOTICE_QUIET_HOURS_END.to_string();
+        changed = true;
+    }
+
+    changed
+}
+
+fn validate_gateway_access_token(token: &str) -> Result<(), String> {
+    if token.is_empty() {
+        return Ok(());
+    }
+    if token.len() < MIN_GATEWAY_ACCESS_TOKEN_LEN || token.len() > MAX_GATEWAY_ACCESS_TOKEN_LEN {
+        return Err(format!(
+            "gateway_access_token length must be {MIN_GATEWAY_ACCESS_TOKEN_LEN}-{MAX_GATEWAY_ACCESS_TOKEN_LEN}"
+        ));
+    }
+    if !token.chars().all(|c| c.is_ascii_graphic()) {
+        return Err("gateway_access_token must contain printable ASCII only".to_string());
+    }
+    Ok(())
+}
+
+pub fn generate_gateway_access_token() -> Result<String, String> {
+    let mut bytes = [0u8; 24];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| format!("SYSTEM_ERROR: failed to generate access token: {e}"))?;
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    Ok(format!("aio-{hex}"))
+}
+
+fn validate_wsl_distro_overrides(overrides: &[WslDistroOverride]) -> Result<(), String> {
+    if overrides.len() > MAX_WSL_DISTRO_OVERRIDES {
+        return Err(format!(
+            "wsl_distro_overrides must contain <= {MAX_WSL_DISTRO_OVERRIDES} items"
+        ));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for item in overrides {
+        let distro = item.distro.trim();
+        if distro.is_empty() {
+            return Err("wsl_distro_overrides: distro is required".to_string());
+        }
+        if !seen.insert(distro.to_ascii_lowercase()) {
+            return Err(format!("wsl_distro_overrides: duplicate distro={distro}"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_gateway_extra_listeners(settings: &AppSettings) -> Result<(), String> {
+    let listeners = &settings.gateway_extra_listeners;
+    if listeners.len() > MAX_GATEWAY_EXTRA_LISTENERS {
+        return Err(format!(
+            "gateway_extra_listeners must contain <= {MAX_GATEWAY_EXTRA_LISTENERS} items"
+        ));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for item in listeners {
+        if item.port < 1024 {
+            return Err("gateway_extra_listeners: port must be between 1024 and 65535".to_string());
+        }
+        if item.port == settings.preferred_port {
+            return Err(format!(
+                "gateway_extra_listeners: port={} conflicts with preferred_port",
+                item.port
+            ));
+        }
+        crate::shared::cli_key::validate_cli_key(&item.cli_key)?;
+        if !seen.insert(item.port) {
+            return Err(format!(
+                "gateway_extra_listeners: duplicate port={}",
+                item.port
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_gateway_client_keys(keys: &[GatewayClientKey]) -> Result<(), String> {
+    if keys.len() > MAX_GATEWAY_CLIENT_KEYS {
+        return Err(format!(
+            "gateway_client_keys must contain <= {MAX_GATEWAY_CLIENT_KEYS} items"
+        ));
+    }
+
+    let mut seen_ids = std::collections::HashSet::new();
+    let mut seen_keys = std::collections::HashSet::new();
+    for item in keys {
+        let id = item.id.trim();
+        if id.is_empty() {
+            return Err("gateway_client_keys: id is required".to_string());
+        }
+        if !seen_ids.insert(id) {
+            return Err(format!("gateway_client_keys: duplicate id={id}"));
+        }
+        if item.name.trim().is_empty()
+            || item.name.chars().count() > MAX_GATEWAY_CLIENT_KEY_NAME_LEN
+        {
+            return Err(format!(
+                "gateway_client_keys: name length must be 1-{MAX_GATEWAY_CLIENT_KEY_NAME_LEN}"
+            ));
+        }
+        if item.key.is_empty() {
+            return Err(format!("gateway_client_keys: key is required (id={id})"));
+        }
+        validate_gateway_access_token(&item.key)
+            .map_err(|e| format!("gateway_client_keys: {e} (id={id})"))?;
+        if !seen_keys.insert(item.key.as_str()) {
+            return Err(format!("gateway_client_keys: duplicate key (id={id})"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_header_sanitize_rules(rules: &[HeaderSanitizeRule]) -> Result<(), String> {
+    if rules.len() > MAX_HEADER_SANITIZE_RULES {
+        return Err(format!(
+            "header_sanitize_rules must contain <= {MAX_HEADER_SANITIZE_RULES} items"
+        ));
+    }
+
+    let mut seen_ids = std::collections::HashSet::new();
+    for rule in rules {
+        let id = rule.id.trim();
+        if id.is_empty() {
+            return Err("header_sanitize_rules: id is required".to_string());
+        }
+        if !seen_ids.insert(id) {
+            return Err(format!("header_sanitize_rules: duplicate id={id}"));
+        }
+        if !rule.cli_key.is_empty() {
+            crate::shared::cli_key::validate_cli_key(&rule.cli_key)
+                .map_err(|e| format!("header_sanitize_rules: {e} (id={id})"))?;
+        }
+        if rule.headers.len() > MAX_HEADER_SANITIZE_RULE_HEADERS {
+            return Err(format!(
+                "header_sanitize_rules: headers must contain <= {MAX_HEADER_SANITIZE_RULE_HEADERS} items (id={id})"
+            ));
+        }
+        for pattern in &rule.headers {
+            let name = pattern.strip_suffix('*').unwrap_or(pattern);
+            if name.is_empty()
+                || !name
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"-_.".contains(&b))
+            {
+                return Err(format!(
+                    "header_sanitize_rules: invalid header pattern {pattern:?} (id={id})"
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_failover_rules(rules: &[FailoverRule]) -> Result<(), String> {
+    if rules.len() > MAX_FAILOVER_RULES {
+        return Err(format!(
+            "failover_rules must contain <= {MAX_FAILOVER_RULES} items"
+        ));
+    }
+
+    let mut seen_ids = std::collections::HashSet::new();
+    for rule in rules {
+        let id = rule.id.trim();
+        if id.is_empty() {
+            return Err("failover_rules: id is required".to_string());
+        }
+        if !seen_ids.insert(id) {
+            return Err(format!("failover_rules: duplicate id={id}"));
+        }
+        if !rule.cli_key.is_empty() {
+            crate::shared::cli_key::validate_cli_key(&rule.cli_key)
+                .map_err(|e| format!("failover_rules: {e} (id={id})"))?;
+        }
+        if rule.statuses.len() > MAX_FAILOVER_RULE_STATUSES {
+            return Err(format!(
+                "failover_rules: statuses must contain <= {MAX_FAILOVER_RULE_STATUSES} items (id={id})"
+            ));
+        }
+        if let Some(status) = rule.statuses.iter().find(|s| !(400..=599).contains(*s)) {
+            return Err(format!(
+                "failover_rules: status {status} must be between 400 and 599 (id={id})"
+            ));
+        }
+        if rule.body_regex.len() > MAX_FAILOVER_RULE_BODY_REGEX_LEN {
+            return Err(format!(
+                "failover_rules: body_regex must be <= {MAX_FAILOVER_RULE_BODY_REGEX_LEN} chars (id={id})"
+            ));
+        }
+        if !rule.body_regex.is_empty() {
+            regex::Regex::new(&rule.body_regex)
+                .map_err(|e| format!("failover_rules: invalid body_regex (id={id}): {e}"))?;
+        }
+        if rule.statuses.is_empty() && rule.body_regex.is_empty() {
+            return Err(format!(
+                "failover_rules: statuses or body_regex is required (id={id})"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_request_redaction_patterns(patterns: &[String]) -> Result<(), String> {
+    if patterns.len() > MAX_REQUEST_REDACTION_CUSTOM_PATTERNS {
+        return Err(format!(
+            "request_redaction_custom_patterns must contain <= {MAX_REQUEST_REDACTION_CUSTOM_PATTERNS} items"
+        ));
+    }
+    for pattern in patterns {
+        if pattern.trim().is_empty() || pattern.len() > MAX_REQUEST_REDACTION_PATTERN_LEN {
+            return Err(format!(
+                "request_redaction_custom_patterns: pattern must be 1-{MAX_REQUEST_REDACTION_PATTERN_LEN} chars"
+            ));
+        }
+        regex::Regex::new(pattern).map_err(|e| {
+            format!("request_redaction_custom_patterns: invalid regex {pattern:?}: {e}")
+        })?;
+    }
+    Ok(())
+}
+
+fn validate_intercept_rules(rules: &[InterceptRule]) -> Result<(), String> {
+    if rules.len() > MAX_INTERCEPT_RULES {
+        return Err(format!(
+            "intercept_rules must contain <= {MAX_INTERCEPT_RULES} items"
+        ));
+    }
+
+    let mut seen_ids = std::collections::HashSet::new();
+    for rule in rules {
+        let id = rule.id.trim();
+        if id.is_empty() {
+            return Err("intercept_rules: id is required".to_string());
+        }
+        if !seen_ids.insert(id) {
+            return Err(format!("intercept_rules: duplicate id={id}"));
+        }
+        if !rule.cli_key.is_empty() {
+            crate::shared::cli_key::validate_cli_key(&rule.cli_key)
+                .map_err(|e| format!("intercept_rules: {e} (id={id})"))?;
+        }
+        if !rule.path.starts_with('/') {
+            return Err(format!(
+                "intercept_rules: path must start with '/' (id={id})"
+            ));
+        }
+        if rule.body_predicates.len() > MAX_INTERCEPT_RULE_PREDICATES {
+            return Err(format!(
+                "intercept_rules: body_predicates must contain <= {MAX_INTERCEPT_RULE_PREDICATES} items (id={id})"
+            ));
+        }
+        for predicate in &rule.body_predicates {
+            if !predicate.pointer.is_empty() && !predicate.pointer.starts_with('/') {
+                return Err(format!(
+                    "intercept_rules: pointer must be empty or start with '/' (id={id})"
+                ));
+            }
+        }
+        if !(100..=599).contains(&rule.response_status) {
+            return Err(format!(
+                "intercept_rules: response_status must be 100-599 (id={id})"
+            ));
+        }
+        if rule.response_body.len() > MAX_INTERCEPT_RULE_RESPONSE_BODY_BYTES {
+            return Err(format!(
+                "intercept_rules: response_body must be <= {MAX_INTERCEPT_RULE_RESPONSE_BODY_BYTES} bytes (id={id})"
+            ));
+        }
+        if rule.response_content_type.trim().is_empty()
+            || rule.response_content_type.chars().any(|c| c.is_control())
+        {
+            return Err(format!(
+                "intercept_rules: invalid response_content_type (id={id})"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_gateway_ip_allowlist(entries: &[String]) -> Result<(), String> {
+    if entries.len() > MAX_GATEWAY_IP_ALLOWLIST {
+        return Err(format!(
+            "gateway_ip_allowlist must contain <= {MAX_GATEWAY_IP_ALLOWLIST} items"
+        ));
+    }
+    for entry in entries {
+        crate::shared::cidr::IpCidr::parse(entry)
+            .map_err(|_| format!("gateway_ip_allowlist: invalid CIDR entry={entry}"))?;
+    }
+    Ok(())
+}
+
+fn validate_gateway_tls_hosts(entries: &[String]) -> Result<(), String> {
+    if entries.len() > MAX_GATEWAY_TLS_HOSTS {
+        return Err(format!(
+            "gateway_tls_hosts must contain <= {MAX_GATEWAY_TLS_HOSTS} items"
+        ));
+    }
+    for entry in entries {
+        let valid = match entry.parse::<std::net::IpAddr>() {
+            Ok(ip) => !ip.is_unspecified(),
+            Err(_) => {
+                !entry.is_empty()
+                    && entry.len() <= 253
+                    && entry.split('.').all(|label| {
+                        !label.is_empty()
+                            && label.len() <= 63
+                            && !label.starts_with('-')
+                            && !label.ends_with('-')
+                            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                    })
+            }
+        };
+        if !valid {
+            return Err(format!("gateway_tls_hosts: invalid host entry={entry}"));
+        }
+    }
+    Ok(())
+}
+
+/// Resolves `(enabled, targets)` for one distro; distros without an override use `wsl_target_cli`.
+pub fn wsl_targets_for_distro(settings: &AppSettings, distro: &str) -> (bool, WslTargetCli) {
+    settings
+        .wsl_distro_overrides
+        .iter()
+        .find(|item| item.distro.trim().eq_ignore_ascii_case(distro.trim()))
+        .map(|item| (item.enabled, item.targets))
+        .unwrap_or((true, settings.wsl_target_cli))
+}
+
+fn validate_notice_channels(channels: &[NoticeChannelConfig]) -> Result<(), String> {
+    if channels.len() > MAX_NOTICE_CHANNELS {
+        return Err(format!(
+            "notice_channels must contain <= {MAX_NOTICE_CHANNELS} items"
+        ));
+    }
+
+    let mut seen_ids = std::collections::HashSet::new();
+    for channel in channels {
+        let id = channel.id.trim();
+        if id.is_empty() {
+            return Err("notice_channels: id is required".to_string());
+        }
+        if !seen_ids.insert(id.to_string()) {
+            return Err(format!("notice_channels: duplicate id={id}"));
+        }
+
+        let url = channel.url.trim();
+        let url_is_http = url.starts_with("http://") || url.starts_with("https://");
+        match channel.kind {
+            NoticeChannelKind::Telegram => {
+                if channel.bot_token.trim().is_empty() || channel.chat_id.trim().is_empty() {
+                    return Err(format!(
+                        "notice_channels: id={id} telegram requires bot_token and chat_id"
+                    ));
+                }
+                if !url.is_empty() && !url_is_http {
+                    return Err(format!(
+                        "notice_channels: id={id} url must start with http:// or https://"
+                    ));
+                }
+            }
+            _ => {
+                if !url_is_http {
+                    return Err(format!(
+                        "notice_channels: id={id} url must start with http:// or https://"
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn migrate_disable_upstream_timeouts(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v7: Align defaults with "0 = disabled" semantics and migrate existing configs to disabled.
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_DISABLE_UPSTREAM_TIMEOUTS
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If the schema version is missing, force a write to persist the current schema_version so we
+    // don't re-run migrations on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_DISABLE_UPSTREAM_TIMEOUTS {
+        settings.schema_version = SCHEMA_VERSION_DISABLE_UPSTREAM_TIMEOUTS;
+        changed = true;
+    }
+
+    if settings.upstream_first_byte_timeout_seconds != 0 {
+        settings.upstream_first_byte_timeout_seconds = 0;
+        changed = true;
+    }
+    if settings.upstream_stream_idle_timeout_seconds != 0 {
+        settings.upstream_stream_idle_timeout_seconds = 0;
+        changed = true;
+    }
+    if settings.upstream_request_timeout_non_streaming_seconds != 0 {
+        settings.upstream_request_timeout_non_streaming_seconds = 0;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_gateway_rectifiers(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v8: Add CCH v0.4.1-aligned gateway rectifier toggles (default disabled).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_RECTIFIERS {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_RECTIFIERS {
+        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_RECTIFIERS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_circuit_breaker_notice(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v9: Add circuit breaker notice toggle (default disabled).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_CIRCUIT_BREAKER_NOTICE
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_CIRCUIT_BREAKER_NOTICE {
+        settings.schema_version = SCHEMA_VERSION_ADD_CIRCUIT_BREAKER_NOTICE;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_provider_base_url_ping_cache_ttl(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v10: Add provider ping cache ttl (seconds), default 60.
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_PROVIDER_BASE_URL_PING_CACHE_TTL
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_PROVIDER_BASE_URL_PING_CACHE_TTL {
+        settings.schema_version = SCHEMA_VERSION_ADD_PROVIDER_BASE_URL_PING_CACHE_TTL;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_codex_session_id_completion(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v11: Add Codex Session ID completion toggle (default disabled).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_CODEX_SESSION_ID_COMPLETION
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_CODEX_SESSION_ID_COMPLETION {
+        settings.schema_version = SCHEMA_VERSION_ADD_CODEX_SESSION_ID_COMPLETION;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_gateway_network_settings(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v12: Add gateway listen mode + WSL network settings (default disabled / all CLI enabled).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_NETWORK_SETTINGS
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_NETWORK_SETTINGS {
+        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_NETWORK_SETTINGS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_response_fixer_limits(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v13: Add response fixer config limits (max_json_depth / max_fix_size).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_RESPONSE_FIXER_LIMITS
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_RESPONSE_FIXER_LIMITS {
+        settings.schema_version = SCHEMA_VERSION_ADD_RESPONSE_FIXER_LIMITS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_ui_language(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v14: Add backend message language (default zh-CN, matching previous hardcoded text).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_UI_LANGUAGE {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_UI_LANGUAGE {
+        settings.schema_version = SCHEMA_VERSION_ADD_UI_LANGUAGE;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_low_power_mode(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v15: Add low-power mode (default disabled; idle threshold default 15 minutes).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_LOW_POWER_MODE {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_LOW_POWER_MODE {
+        settings.schema_version = SCHEMA_VERSION_ADD_LOW_POWER_MODE;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_notice_channels(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v16: Add external notice channels (default none).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_NOTICE_CHANNELS {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_NOTICE_CHANNELS {
+        settings.schema_version = SCHEMA_VERSION_ADD_NOTICE_CHANNELS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_notice_quiet_hours(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v17: Add notice quiet hours (default disabled, 23:00-08:00, summary enabled).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_NOTICE_QUIET_HOURS {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_NOTICE_QUIET_HOURS {
+        settings.schema_version = SCHEMA_VERSION_ADD_NOTICE_QUIET_HOURS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_circuit_recovery_notice(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v18: Add circuit recovery notice toggle (default disabled).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_CIRCUIT_RECOVERY_NOTICE
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_CIRCUIT_RECOVERY_NOTICE {
+        settings.schema_version = SCHEMA_VERSION_ADD_CIRCUIT_RECOVERY_NOTICE;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_wsl_distro_overrides(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v19: add per-distro WSL overrides (default empty = every distro uses wsl_target_cli).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_WSL_DISTRO_OVERRIDES
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_WSL_DISTRO_OVERRIDES {
+        settings.schema_version = SCHEMA_VERSION_ADD_WSL_DISTRO_OVERRIDES;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_gateway_access_token(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v20: add gateway access token (default empty = no token check).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_ACCESS_TOKEN
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_ACCESS_TOKEN {
+        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_ACCESS_TOKEN;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_gateway_tls(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v21: add gateway HTTPS toggle (default false = plain HTTP).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_TLS {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_TLS {
+        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_TLS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_gateway_extra_listeners(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v22: add extra gateway listeners (dedicated port per cli_key).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_EXTRA_LISTENERS
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_EXTRA_LISTENERS {
+        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_EXTRA_LISTENERS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_gateway_client_keys(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v23: add named per-client gateway keys.
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_CLIENT_KEYS {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_CLIENT_KEYS {
+        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_CLIENT_KEYS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_gateway_ip_allowlist(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v24: add gateway IP allowlist (CIDR).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_IP_ALLOWLIST
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_IP_ALLOWLIST {
+        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_IP_ALLOWLIST;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_gateway_drain_timeout(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v25: add graceful stop drain timeout.
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_DRAIN_TIMEOUT
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_DRAIN_TIMEOUT {
+        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_DRAIN_TIMEOUT;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_update_channel(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v26: add updater channel (stable / beta).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_UPDATE_CHANNEL {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_UPDATE_CHANNEL {
+        settings.schema_version = SCHEMA_VERSION_ADD_UPDATE_CHANNEL;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_update_background_download(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v27: add background update download (install on next quit/restart).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_UPDATE_BACKGROUND_DOWNLOAD
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_UPDATE_BACKGROUND_DOWNLOAD {
+        settings.schema_version = SCHEMA_VERSION_ADD_UPDATE_BACKGROUND_DOWNLOAD;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_response_fixer_tool_args(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v28: add response_fixer_fix_tool_args (opt-in tool_use / function_call arguments repair).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_RESPONSE_FIXER_TOOL_ARGS
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_RESPONSE_FIXER_TOOL_ARGS {
+        settings.schema_version = SCHEMA_VERSION_ADD_RESPONSE_FIXER_TOOL_ARGS;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_response_fixer_dry_run(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v29: add response_fixer_dry_run (analyze and report without modifying response bytes).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_RESPONSE_FIXER_DRY_RUN
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_RESPONSE_FIXER_DRY_RUN {
+        settings.schema_version = SCHEMA_VERSION_ADD_RESPONSE_FIXER_DRY_RUN;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_intercept_rules(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v30: add intercept_rules (user-configurable local responses for junk requests).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_INTERCEPT_RULES {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_INTERCEPT_RULES {
+        settings.schema_version = SCHEMA_VERSION_ADD_INTERCEPT_RULES;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_inflight_dedup(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v31: add inflight_dedup_mode (attach / reject identical requests while the original is in flight).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_INFLIGHT_DEDUP {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_INFLIGHT_DEDUP {
+        settings.schema_version = SCHEMA_VERSION_ADD_INFLIGHT_DEDUP;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_priority_lanes(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v32: add priority lanes (background requests queued / routed separately from interactive ones).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_PRIORITY_LANES {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_PRIORITY_LANES {
+        settings.schema_version = SCHEMA_VERSION_ADD_PRIORITY_LANES;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_header_sanitize_rules(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v33: add header_sanitize_rules (strip client headers before forwarding, per CLI / provider).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_HEADER_SANITIZE_RULES
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_HEADER_SANITIZE_RULES {
+        settings.schema_version = SCHEMA_VERSION_ADD_HEADER_SANITIZE_RULES;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_request_redaction(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v34: add request redaction (mask secrets / PII in request bodies before forwarding).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_REQUEST_REDACTION {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_REQUEST_REDACTION {
+        settings.schema_version = SCHEMA_VERSION_ADD_REQUEST_REDACTION;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_scheduled_latency_probe(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v35: scheduled background base_url latency probing (default disabled).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_SCHEDULED_LATENCY_PROBE
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_SCHEDULED_LATENCY_PROBE {
+        settings.schema_version = SCHEMA_VERSION_ADD_SCHEDULED_LATENCY_PROBE;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_prompt_cache_injection(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v36: automatic cache_control injection for Claude requests (default disabled).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_PROMPT_CACHE_INJECTION
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
+        changed = true;
+    }
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_PROMPT_CACHE_INJECTION {
+        settings.schema_version = SCHEMA_VERSION_ADD_PROMPT_CACHE_INJECTION;
+        changed = true;
+    }
+
+    changed
+}
+
+fn migrate_add_exclude_reasoning_from_logs(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v37: strip thinking / reasoning content before persisting captured bodies (default disabled).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_EXCLUDE_REASONING_FROM_LOGS
+    {
+        return false;
+    }
+
     let mut changed = false;
 
-    if settings.failover_max_attempts_per_provider == 0 {
-        settings.failover_max_attempts_per_provider = DEFAULT_FAILOVER_MAX_ATTEMPTS_PER_PROVIDER;
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
         changed = true;
     }
-    if settings.failover_max_providers_to_try == 0 {
-        settings.failover_max_providers_to_try = DEFAULT_FAILOVER_MAX_PROVIDERS_TO_TRY;
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_EXCLUDE_REASONING_FROM_LOGS {
+        settings.schema_version = SCHEMA_VERSION_ADD_EXCLUDE_REASONING_FROM_LOGS;
         changed = true;
     }
 
-    if settings.failover_max_attempts_per_provider > MAX_FAILOVER_MAX_ATTEMPTS_PER_PROVIDER {
-        settings.failover_max_attempts_per_provider = MAX_FAILOVER_MAX_ATTEMPTS_PER_PROVIDER;
-        changed = true;
+    changed
+}
+
+fn migrate_add_cli_stream_caps(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v38: per-CLI concurrent stream caps (default unlimited).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_CLI_STREAM_CAPS {
+        return false;
     }
 
-    if settings.failover_max_providers_to_try > MAX_FAILOVER_MAX_PROVIDERS_TO_TRY {
-        settings.failover_max_providers_to_try = MAX_FAILOVER_MAX_PROVIDERS_TO_TRY;
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
         changed = true;
     }
 
-    let providers = settings.failover_max_providers_to_try.max(1);
-    let max_attempts_for_providers = (MAX_FAILOVER_TOTAL_ATTEMPTS / providers).max(1);
-    if settings.failover_max_attempts_per_provider > max_attempts_for_providers {
-        settings.failover_max_attempts_per_provider = max_attempts_for_providers;
+    if settings.schema_version != SCHEMA_VERSION_ADD_CLI_STREAM_CAPS {
+        settings.schema_version = SCHEMA_VERSION_ADD_CLI_STREAM_CAPS;
         changed = true;
     }
 
     changed
 }
 
-fn sanitize_circuit_breaker_settings(settings: &mut AppSettings) -> bool {
+fn migrate_add_retry_backoff(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v39: configurable retry backoff (defaults keep the previous 80ms..800ms linear backoff on 408 / 429).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_RETRY_BACKOFF {
+        return false;
+    }
+
     let mut changed = false;
 
-    if settings.circuit_breaker_failure_threshold == 0 {
-        settings.circuit_breaker_failure_threshold = DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD;
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
         changed = true;
     }
-    if settings.circuit_breaker_open_duration_minutes == 0 {
-        settings.circuit_breaker_open_duration_minutes =
-            DEFAULT_CIRCUIT_BREAKER_OPEN_DURATION_MINUTES;
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_RETRY_BACKOFF {
+        settings.schema_version = SCHEMA_VERSION_ADD_RETRY_BACKOFF;
         changed = true;
     }
 
-    if settings.circuit_breaker_failure_threshold > MAX_CIRCUIT_BREAKER_FAILURE_THRESHOLD {
-        settings.circuit_breaker_failure_threshold = MAX_CIRCUIT_BREAKER_FAILURE_THRESHOLD;
+    changed
+}
+
+fn migrate_add_forward_trace_headers(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v40: forward hub / client trace ids to upstream providers (default disabled).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_FORWARD_TRACE_HEADERS
+    {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
         changed = true;
     }
-    if settings.circuit_breaker_open_duration_minutes > MAX_CIRCUIT_BREAKER_OPEN_DURATION_MINUTES {
-        settings.circuit_breaker_open_duration_minutes = MAX_CIRCUIT_BREAKER_OPEN_DURATION_MINUTES;
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_FORWARD_TRACE_HEADERS {
+        settings.schema_version = SCHEMA_VERSION_ADD_FORWARD_TRACE_HEADERS;
         changed = true;
     }
 
     changed
 }
 
-fn sanitize_provider_cooldown_seconds(settings: &mut AppSettings) -> bool {
-    if settings.provider_cooldown_seconds > MAX_PROVIDER_COOLDOWN_SECONDS {
-        settings.provider_cooldown_seconds = MAX_PROVIDER_COOLDOWN_SECONDS;
-        return true;
+fn migrate_add_capture_sse_events(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v41: opt-in raw SSE capture for streamed responses (default disabled).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_CAPTURE_SSE_EVENTS {
+        return false;
     }
-    false
-}
 
-fn sanitize_provider_base_url_ping_cache_ttl_seconds(settings: &mut AppSettings) -> bool {
     let mut changed = false;
 
-    if settings.provider_base_url_ping_cache_ttl_seconds == 0 {
-        settings.provider_base_url_ping_cache_ttl_seconds =
-            DEFAULT_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS;
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
         changed = true;
     }
 
-    if settings.provider_base_url_ping_cache_ttl_seconds
-        > MAX_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS
-    {
-        settings.provider_base_url_ping_cache_ttl_seconds =
-            MAX_PROVIDER_BASE_URL_PING_CACHE_TTL_SECONDS;
+    if settings.schema_version != SCHEMA_VERSION_ADD_CAPTURE_SSE_EVENTS {
+        settings.schema_version = SCHEMA_VERSION_ADD_CAPTURE_SSE_EVENTS;
         changed = true;
     }
 
     changed
 }
 
-fn sanitize_upstream_timeouts(settings: &mut AppSettings) -> bool {
+fn migrate_add_attempt_log_retention(
+    settings: &mut AppSettings,
+    schema_version_present: bool,
+) -> bool {
+    // v42: separate attempt log retention + compaction (default: follow request logs, compact after 7 days).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_ATTEMPT_LOG_RETENTION
+    {
+        return false;
+    }
+
     let mut changed = false;
 
-    if settings.upstream_first_byte_timeout_seconds > MAX_UPSTREAM_FIRST_BYTE_TIMEOUT_SECONDS {
-        settings.upstream_first_byte_timeout_seconds = MAX_UPSTREAM_FIRST_BYTE_TIMEOUT_SECONDS;
-        changed = true;
-    }
-    if settings.upstream_stream_idle_timeout_seconds > MAX_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS {
-        settings.upstream_stream_idle_timeout_seconds = MAX_UPSTREAM_STREAM_IDLE_TIMEOUT_SECONDS;
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
         changed = true;
     }
-    if settings.upstream_request_timeout_non_streaming_seconds
-        > MAX_UPSTREAM_REQUEST_TIMEOUT_NON_STREAMING_SECONDS
-    {
-        settings.upstream_request_timeout_non_streaming_seconds =
-            MAX_UPSTREAM_REQUEST_TIMEOUT_NON_STREAMING_SECONDS;
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_ATTEMPT_LOG_RETENTION {
+        settings.schema_version = SCHEMA_VERSION_ADD_ATTEMPT_LOG_RETENTION;
         changed = true;
     }
 
     changed
 }
 
-fn sanitize_response_fixer_limits(settings: &mut AppSettings) -> bool {
+fn migrate_add_latency_routing(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v43: latency-based provider ordering (default disabled, 30 minute window).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_LATENCY_ROUTING {
+        return false;
+    }
+
     let mut changed = false;
 
-    if settings.response_fixer_max_json_depth == 0 {
-        settings.response_fixer_max_json_depth = DEFAULT_RESPONSE_FIXER_MAX_JSON_DEPTH;
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
         changed = true;
     }
-    if settings.response_fixer_max_json_depth > MAX_RESPONSE_FIXER_MAX_JSON_DEPTH {
-        settings.response_fixer_max_json_depth = MAX_RESPONSE_FIXER_MAX_JSON_DEPTH;
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_LATENCY_ROUTING {
+        settings.schema_version = SCHEMA_VERSION_ADD_LATENCY_ROUTING;
         changed = true;
     }
 
-    if settings.response_fixer_max_fix_size == 0 {
-        settings.response_fixer_max_fix_size = DEFAULT_RESPONSE_FIXER_MAX_FIX_SIZE;
+    changed
+}
+
+fn migrate_add_update_mirror(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v44: updater mirror sources (default: none, 15s timeout).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_UPDATE_MIRROR {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
         changed = true;
     }
-    if settings.response_fixer_max_fix_size > MAX_RESPONSE_FIXER_MAX_FIX_SIZE {
-        settings.response_fixer_max_fix_size = MAX_RESPONSE_FIXER_MAX_FIX_SIZE;
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_UPDATE_MIRROR {
+        settings.schema_version = SCHEMA_VERSION_ADD_UPDATE_MIRROR;
         changed = true;
     }
 
     changed
 }
 
-fn migrate_disable_upstream_timeouts(
-    settings: &mut AppSettings,
-    schema_version_present: bool,
-) -> bool {
-    // v7: Align defaults with "0 = disabled" semantics and migrate existing configs to disabled.
-    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_DISABLE_UPSTREAM_TIMEOUTS
-    {
+fn migrate_add_response_cache(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v45: per-provider response cache for model endpoints (default 300s TTL).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_RESPONSE_CACHE {
         return false;
     }
 
     let mut changed = false;
 
-    // If the schema version is missing, force a write to persist the current schema_version so we
-    // don't re-run migrations on every startup.
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
     if !schema_version_present {
         changed = true;
     }
 
-    if settings.schema_version != SCHEMA_VERSION_DISABLE_UPSTREAM_TIMEOUTS {
-        settings.schema_version = SCHEMA_VERSION_DISABLE_UPSTREAM_TIMEOUTS;
+    if settings.schema_version != SCHEMA_VERSION_ADD_RESPONSE_CACHE {
+        settings.schema_version = SCHEMA_VERSION_ADD_RESPONSE_CACHE;
         changed = true;
     }
 
-    if settings.upstream_first_byte_timeout_seconds != 0 {
-        settings.upstream_first_byte_timeout_seconds = 0;
-        changed = true;
+    changed
+}
+
+fn migrate_add_egress_network(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v46: gateway egress network settings (default: system proxy, no overrides, any IP family).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_EGRESS_NETWORK {
+        return false;
     }
-    if settings.upstream_stream_idle_timeout_seconds != 0 {
-        settings.upstream_stream_idle_timeout_seconds = 0;
+
+    let mut changed = false;
+
+    // If schema_version is missing, force a write to persist schema_version so we don't keep "migrating"
+    // on every startup.
+    if !schema_version_present {
         changed = true;
     }
-    if settings.upstream_request_timeout_non_streaming_seconds != 0 {
-        settings.upstream_request_timeout_non_streaming_seconds = 0;
+
+    if settings.schema_version != SCHEMA_VERSION_ADD_EGRESS_NETWORK {
+        settings.schema_version = SCHEMA_VERSION_ADD_EGRESS_NETWORK;
         changed = true;
     }
 
     changed
 }
 
-fn migrate_add_gateway_rectifiers(
+fn migrate_add_response_cache_completions(
     settings: &mut AppSettings,
     schema_version_present: bool,
 ) -> bool {
-    // v8: Add CCH v0.4.1-aligned gateway rectifier toggles (default disabled).
-    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_RECTIFIERS {
+    // v47: opt-in response cache for identical non-streaming completions (default disabled).
+    if schema_version_present
+        && settings.schema_version >= SCHEMA_VERSION_ADD_RESPONSE_CACHE_COMPLETIONS
+    {
         return false;
     }
 
@@ -363,22 +2892,20 @@ fn migrate_add_gateway_rectifiers(
         changed = true;
     }
 
-    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_RECTIFIERS {
-        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_RECTIFIERS;
+    if settings.schema_version != SCHEMA_VERSION_ADD_RESPONSE_CACHE_COMPLETIONS {
+        settings.schema_version = SCHEMA_VERSION_ADD_RESPONSE_CACHE_COMPLETIONS;
         changed = true;
     }
 
     changed
 }
 
-fn migrate_add_circuit_breaker_notice(
+fn migrate_add_recent_error_cache(
     settings: &mut AppSettings,
     schema_version_present: bool,
 ) -> bool {
-    // v9: Add circuit breaker notice toggle (default disabled).
-    if schema_version_present
-        && settings.schema_version >= SCHEMA_VERSION_ADD_CIRCUIT_BREAKER_NOTICE
-    {
+    // v48: configurable recent-error dedup (1 hour replay cap, 10 second trace dedup window).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_RECENT_ERROR_CACHE {
         return false;
     }
 
@@ -390,22 +2917,17 @@ fn migrate_add_circuit_breaker_notice(
         changed = true;
     }
 
-    if settings.schema_version != SCHEMA_VERSION_ADD_CIRCUIT_BREAKER_NOTICE {
-        settings.schema_version = SCHEMA_VERSION_ADD_CIRCUIT_BREAKER_NOTICE;
+    if settings.schema_version != SCHEMA_VERSION_ADD_RECENT_ERROR_CACHE {
+        settings.schema_version = SCHEMA_VERSION_ADD_RECENT_ERROR_CACHE;
         changed = true;
     }
 
     changed
 }
 
-fn migrate_add_provider_base_url_ping_cache_ttl(
-    settings: &mut AppSettings,
-    schema_version_present: bool,
-) -> bool {
-    // v10: Add provider ping cache ttl (seconds), default 60.
-    if schema_version_present
-        && settings.schema_version >= SCHEMA_VERSION_ADD_PROVIDER_BASE_URL_PING_CACHE_TTL
-    {
+fn migrate_add_hedged_requests(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v49: hedged requests (disabled by default, 2 second hedge delay).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_HEDGED_REQUESTS {
         return false;
     }
 
@@ -417,22 +2939,17 @@ fn migrate_add_provider_base_url_ping_cache_ttl(
         changed = true;
     }
 
-    if settings.schema_version != SCHEMA_VERSION_ADD_PROVIDER_BASE_URL_PING_CACHE_TTL {
-        settings.schema_version = SCHEMA_VERSION_ADD_PROVIDER_BASE_URL_PING_CACHE_TTL;
+    if settings.schema_version != SCHEMA_VERSION_ADD_HEDGED_REQUESTS {
+        settings.schema_version = SCHEMA_VERSION_ADD_HEDGED_REQUESTS;
         changed = true;
     }
 
     changed
 }
 
-fn migrate_add_codex_session_id_completion(
-    settings: &mut AppSettings,
-    schema_version_present: bool,
-) -> bool {
-    // v11: Add Codex Session ID completion toggle (default disabled).
-    if schema_version_present
-        && settings.schema_version >= SCHEMA_VERSION_ADD_CODEX_SESSION_ID_COMPLETION
-    {
+fn migrate_add_failover_rules(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v50: add failover_rules (user status / error body -> retry / switch / abort mapping).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_FAILOVER_RULES {
         return false;
     }
 
@@ -444,21 +2961,21 @@ fn migrate_add_codex_session_id_completion(
         changed = true;
     }
 
-    if settings.schema_version != SCHEMA_VERSION_ADD_CODEX_SESSION_ID_COMPLETION {
-        settings.schema_version = SCHEMA_VERSION_ADD_CODEX_SESSION_ID_COMPLETION;
+    if settings.schema_version != SCHEMA_VERSION_ADD_FAILOVER_RULES {
+        settings.schema_version = SCHEMA_VERSION_ADD_FAILOVER_RULES;
         changed = true;
     }
 
     changed
 }
 
-fn migrate_add_gateway_network_settings(
+fn migrate_add_stream_tool_pause_grace(
     settings: &mut AppSettings,
     schema_version_present: bool,
 ) -> bool {
-    // v12: Add gateway listen mode + WSL network settings (default disabled / all CLI enabled).
+    // v51: stream_tool_pause_grace (enabled by default, 10 minute tool-pause idle timeout).
     if schema_version_present
-        && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_NETWORK_SETTINGS
+        && settings.schema_version >= SCHEMA_VERSION_ADD_STREAM_TOOL_PAUSE_GRACE
     {
         return false;
     }
@@ -471,21 +2988,17 @@ fn migrate_add_gateway_network_settings(
         changed = true;
     }
 
-    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_NETWORK_SETTINGS {
-        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_NETWORK_SETTINGS;
+    if settings.schema_version != SCHEMA_VERSION_ADD_STREAM_TOOL_PAUSE_GRACE {
+        settings.schema_version = SCHEMA_VERSION_ADD_STREAM_TOOL_PAUSE_GRACE;
         changed = true;
     }
 
     changed
 }
 
-fn migrate_add_response_fixer_limits(
-    settings: &mut AppSettings,
-    schema_version_present: bool,
-) -> bool {
-    // v13: Add response fixer config limits (max_json_depth / max_fix_size).
-    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_RESPONSE_FIXER_LIMITS
-    {
+fn migrate_add_gateway_tls_hosts(settings: &mut AppSettings, schema_version_present: bool) -> bool {
+    // v52: extra TLS certificate hosts (default empty).
+    if schema_version_present && settings.schema_version >= SCHEMA_VERSION_ADD_GATEWAY_TLS_HOSTS {
         return false;
     }
 
@@ -497,8 +3010,8 @@ fn migrate_add_response_fixer_limits(
         changed = true;
     }
 
-    if settings.schema_version != SCHEMA_VERSION_ADD_RESPONSE_FIXER_LIMITS {
-        settings.schema_version = SCHEMA_VERSION_ADD_RESPONSE_FIXER_LIMITS;
+    if settings.schema_version != SCHEMA_VERSION_ADD_GATEWAY_TLS_HOSTS {
+        settings.schema_version = SCHEMA_VERSION_ADD_GATEWAY_TLS_HOSTS;
         changed = true;
     }
 
@@ -569,12 +3082,69 @@ pub fn read(app: &tauri::AppHandle) -> Result<AppSettings, String> {
                 migrate_add_codex_session_id_completion(&mut settings, schema_version_present);
             repaired |= migrate_add_gateway_network_settings(&mut settings, schema_version_present);
             repaired |= migrate_add_response_fixer_limits(&mut settings, schema_version_present);
+            repaired |= migrate_add_ui_language(&mut settings, schema_version_present);
+            repaired |= migrate_add_low_power_mode(&mut settings, schema_version_present);
+            repaired |= migrate_add_notice_channels(&mut settings, schema_version_present);
+            repaired |= migrate_add_notice_quiet_hours(&mut settings, schema_version_present);
+            repaired |= migrate_add_circuit_recovery_notice(&mut settings, schema_version_present);
+            repaired |= migrate_add_wsl_distro_overrides(&mut settings, schema_version_present);
+            repaired |= migrate_add_gateway_access_token(&mut settings, schema_version_present);
+            repaired |= migrate_add_gateway_tls(&mut settings, schema_version_present);
+            repaired |= migrate_add_gateway_extra_listeners(&mut settings, schema_version_present);
+            repaired |= migrate_add_gateway_client_keys(&mut settings, schema_version_present);
+            repaired |= migrate_add_gateway_ip_allowlist(&mut settings, schema_version_present);
+            repaired |= migrate_add_gateway_drain_timeout(&mut settings, schema_version_present);
+            repaired |= migrate_add_update_channel(&mut settings, schema_version_present);
+            repaired |=
+                migrate_add_update_background_download(&mut settings, schema_version_present);
+            repaired |= migrate_add_response_fixer_tool_args(&mut settings, schema_version_present);
+            repaired |= migrate_add_response_fixer_dry_run(&mut settings, schema_version_present);
+            repaired |= migrate_add_intercept_rules(&mut settings, schema_version_present);
+            repaired |= migrate_add_inflight_dedup(&mut settings, schema_version_present);
+            repaired |= migrate_add_priority_lanes(&mut settings, schema_version_present);
+            repaired |= migrate_add_header_sanitize_rules(&mut settings, schema_version_present);
+            repaired |= migrate_add_request_redaction(&mut settings, schema_version_present);
+            repaired |= migrate_add_scheduled_latency_probe(&mut settings, schema_version_present);
+            repaired |= migrate_add_prompt_cache_injection(&mut settings, schema_version_present);
+            repaired |=
+                migrate_add_exclude_reasoning_from_logs(&mut settings, schema_version_present);
+            repaired |= migrate_add_cli_stream_caps(&mut settings, schema_version_present);
+            repaired |= migrate_add_retry_backoff(&mut settings, schema_version_present);
+            repaired |= migrate_add_forward_trace_headers(&mut settings, schema_version_present);
+            repaired |= migrate_add_capture_sse_events(&mut settings, schema_version_present);
+            repaired |= migrate_add_attempt_log_retention(&mut settings, schema_version_present);
+            repaired |= migrate_add_latency_routing(&mut settings, schema_version_present);
+            repaired |= migrate_add_update_mirror(&mut settings, schema_version_present);
+            repaired |= migrate_add_response_cache(&mut settings, schema_version_present);
+            repaired |= migrate_add_egress_network(&mut settings, schema_version_present);
+            repaired |=
+                migrate_add_response_cache_completions(&mut settings, schema_version_present);
+            repaired |= migrate_add_recent_error_cache(&mut settings, schema_version_present);
+            repaired |= migrate_add_hedged_requests(&mut settings, schema_version_present);
+            repaired |= migrate_add_failover_rules(&mut settings, schema_version_present);
+            repaired |= migrate_add_stream_tool_pause_grace(&mut settings, schema_version_present);
+            repaired |= migrate_add_gateway_tls_hosts(&mut settings, schema_version_present);
+            repaired |= migrate_add_gateway_tls_hosts(&mut settings, schema_version_present);
             repaired |= sanitize_failover_settings(&mut settings);
             repaired |= sanitize_circuit_breaker_settings(&mut settings);
             repaired |= sanitize_provider_cooldown_seconds(&mut settings);
             repaired |= sanitize_provider_base_url_ping_cache_ttl_seconds(&mut settings);
             repaired |= sanitize_upstream_timeouts(&mut settings);
             repaired |= sanitize_response_fixer_limits(&mut settings);
+            repaired |= sanitize_low_power_idle_minutes(&mut settings);
+            repaired |= sanitize_background_lane_limits(&mut settings);
+            repaired |= sanitize_latency_probe_interval_minutes(&mut settings);
+            repaired |= sanitize_response_cache_ttl_seconds(&mut settings);
+            repaired |= sanitize_recent_error_cache(&mut settings);
+            repaired |= sanitize_hedged_requests(&mut settings);
+            repaired |= sanitize_stream_tool_pause_grace(&mut settings);
+            repaired |= sanitize_egress_network(&mut settings);
+            repaired |= sanitize_cli_stream_caps(&mut settings);
+            repaired |= sanitize_retry_backoff(&mut settings);
+            repaired |= sanitize_attempt_log_retention(&mut settings);
+            repaired |= sanitize_latency_routing(&mut settings);
+            repaired |= sanitize_update_mirror(&mut settings);
+            repaired |= sanitize_notice_quiet_hours(&mut settings);
             if repaired {
                 // best-effort: persist sanitized defaults
             }
@@ -623,12 +3193,65 @@ pub fn read(app: &tauri::AppHandle) -> Result<AppSettings, String> {
     repaired |= migrate_add_codex_session_id_completion(&mut settings, schema_version_present);
     repaired |= migrate_add_gateway_network_settings(&mut settings, schema_version_present);
     repaired |= migrate_add_response_fixer_limits(&mut settings, schema_version_present);
+    repaired |= migrate_add_ui_language(&mut settings, schema_version_present);
+    repaired |= migrate_add_low_power_mode(&mut settings, schema_version_present);
+    repaired |= migrate_add_notice_channels(&mut settings, schema_version_present);
+    repaired |= migrate_add_notice_quiet_hours(&mut settings, schema_version_present);
+    repaired |= migrate_add_circuit_recovery_notice(&mut settings, schema_version_present);
+    repaired |= migrate_add_wsl_distro_overrides(&mut settings, schema_version_present);
+    repaired |= migrate_add_gateway_access_token(&mut settings, schema_version_present);
+    repaired |= migrate_add_gateway_tls(&mut settings, schema_version_present);
+    repaired |= migrate_add_gateway_extra_listeners(&mut settings, schema_version_present);
+    repaired |= migrate_add_gateway_client_keys(&mut settings, schema_version_present);
+    repaired |= migrate_add_gateway_ip_allowlist(&mut settings, schema_version_present);
+    repaired |= migrate_add_gateway_drain_timeout(&mut settings, schema_version_present);
+    repaired |= migrate_add_update_channel(&mut settings, schema_version_present);
+    repaired |= migrate_add_update_background_download(&mut settings, schema_version_present);
+    repaired |= migrate_add_response_fixer_tool_args(&mut settings, schema_version_present);
+    repaired |= migrate_add_response_fixer_dry_run(&mut settings, schema_version_present);
+    repaired |= migrate_add_intercept_rules(&mut settings, schema_version_present);
+    repaired |= migrate_add_inflight_dedup(&mut settings, schema_version_present);
+    repaired |= migrate_add_priority_lanes(&mut settings, schema_version_present);
+    repaired |= migrate_add_header_sanitize_rules(&mut settings, schema_version_present);
+    repaired |= migrate_add_request_redaction(&mut settings, schema_version_present);
+    repaired |= migrate_add_scheduled_latency_probe(&mut settings, schema_version_present);
+    repaired |= migrate_add_prompt_cache_injection(&mut settings, schema_version_present);
+    repaired |= migrate_add_exclude_reasoning_from_logs(&mut settings, schema_version_present);
+    repaired |= migrate_add_cli_stream_caps(&mut settings, schema_version_present);
+    repaired |= migrate_add_retry_backoff(&mut settings, schema_version_present);
+    repaired |= migrate_add_forward_trace_headers(&mut settings, schema_version_present);
+    repaired |= migrate_add_capture_sse_events(&mut settings, schema_version_present);
+    repaired |= migrate_add_attempt_log_retention(&mut settings, schema_version_present);
+    repaired |= migrate_add_latency_routing(&mut settings, schema_version_present);
+    repaired |= migrate_add_update_mirror(&mut settings, schema_version_present);
+    repaired |= migrate_add_response_cache(&mut settings, schema_version_present);
+    repaired |= migrate_add_egress_network(&mut settings, schema_version_present);
+    repaired |= migrate_add_response_cache_completions(&mut settings, schema_version_present);
+    repaired |= migrate_add_recent_error_cache(&mut settings, schema_version_present);
+    repaired |= migrate_add_hedged_requests(&mut settings, schema_version_present);
+    repaired |= migrate_add_failover_rules(&mut settings, schema_version_present);
+    repaired |= migrate_add_stream_tool_pause_grace(&mut settings, schema_version_present);
+    repaired |= migrate_add_gateway_tls_hosts(&mut settings, schema_version_present);
     repaired |= sanitize_failover_settings(&mut settings);
     repaired |= sanitize_circuit_breaker_settings(&mut settings);
     repaired |= sanitize_provider_cooldown_seconds(&mut settings);
     repaired |= sanitize_provider_base_url_ping_cache_ttl_seconds(&mut settings);
     repaired |= sanitize_upstream_timeouts(&mut settings);
     repaired |= sanitize_response_fixer_limits(&mut settings);
+    repaired |= sanitize_low_power_idle_minutes(&mut settings);
+    repaired |= sanitize_background_lane_limits(&mut settings);
+    repaired |= sanitize_latency_probe_interval_minutes(&mut settings);
+    repaired |= sanitize_response_cache_ttl_seconds(&mut settings);
+    repaired |= sanitize_recent_error_cache(&mut settings);
+    repaired |= sanitize_hedged_requests(&mut settings);
+    repaired |= sanitize_stream_tool_pause_grace(&mut settings);
+    repaired |= sanitize_egress_network(&mut settings);
+    repaired |= sanitize_cli_stream_caps(&mut settings);
+    repaired |= sanitize_retry_backoff(&mut settings);
+    repaired |= sanitize_attempt_log_retention(&mut settings);
+    repaired |= sanitize_latency_routing(&mut settings);
+    repaired |= sanitize_update_mirror(&mut settings);
+    repaired |= sanitize_notice_quiet_hours(&mut settings);
     if repaired {
         // Best-effort: persist repaired values while keeping read semantics.
         let _ = write(app, &settings);
@@ -660,6 +3283,14 @@ pub fn log_retention_days_fail_open(app: &tauri::AppHandle) -> u32 {
     }
 }
 
+pub fn attempt_log_retention_fail_open(app: &tauri::AppHandle) -> AttemptLogRetentionConfig {
+    match read(app) {
+        Ok(cfg) => cfg.attempt_log_retention.resolved(cfg.log_retention_days),
+        // `log_retention_days_fail_open` already warns once about the read failure.
+        Err(_) => AttemptLogRetentionConfig::default().resolved(DEFAULT_LOG_RETENTION_DAYS),
+    }
+}
+
 pub fn write(app: &tauri::AppHandle, settings: &AppSettings) -> Result<AppSettings, String> {
     if settings.preferred_port < 1024 {
         return Err("preferred_port must be between 1024 and 65535".to_string());
@@ -715,6 +3346,69 @@ pub fn write(app: &tauri::AppHandle, settings: &AppSettings) -> Result<AppSettin
             "response_fixer_max_fix_size must be <= {MAX_RESPONSE_FIXER_MAX_FIX_SIZE}"
         ));
     }
+    if settings.low_power_idle_minutes > MAX_LOW_POWER_IDLE_MINUTES {
+        return Err(format!(
+            "low_power_idle_minutes must be <= {MAX_LOW_POWER_IDLE_MINUTES}"
+        ));
+    }
+    if settings.background_lane_max_concurrency > MAX_BACKGROUND_LANE_MAX_CONCURRENCY {
+        return Err(format!(
+            "background_lane_max_concurrency must be <= {MAX_BACKGROUND_LANE_MAX_CONCURRENCY}"
+        ));
+    }
+    if settings.background_lane_queue_timeout_seconds > MAX_BACKGROUND_LANE_QUEUE_TIMEOUT_SECONDS {
+        return Err(format!(
+            "background_lane_queue_timeout_seconds must be <= {MAX_BACKGROUND_LANE_QUEUE_TIMEOUT_SECONDS}"
+        ));
+    }
+    if settings.latency_probe_interval_minutes > MAX_LATENCY_PROBE_INTERVAL_MINUTES {
+        return Err(format!(
+            "latency_probe_interval_minutes must be <= {MAX_LATENCY_PROBE_INTERVAL_MINUTES}"
+        ));
+    }
+    if settings.response_cache_ttl_seconds > MAX_RESPONSE_CACHE_TTL_SECONDS {
+        return Err(format!(
+            "response_cache_ttl_seconds must be <= {MAX_RESPONSE_CACHE_TTL_SECONDS}"
+        ));
+    }
+    let caps = settings.cli_max_concurrent_streams;
+    if [caps.claude, caps.codex, caps.gemini]
+        .iter()
+        .any(|v| *v > MAX_CLI_MAX_CONCURRENT_STREAMS)
+    {
+        return Err(format!(
+            "cli_max_concurrent_streams must be <= {MAX_CLI_MAX_CONCURRENT_STREAMS}"
+        ));
+    }
+    settings.retry_backoff.validate()?;
+    settings.attempt_log_retention.validate()?;
+    settings.latency_routing.validate()?;
+    settings.recent_error_cache.validate()?;
+    settings.hedged_requests.validate()?;
+    settings.stream_tool_pause_grace.validate()?;
+    settings.update_mirror.validate()?;
+    settings.egress_network.validate()?;
+    validate_notice_channels(&settings.notice_channels)?;
+    validate_wsl_distro_overrides(&settings.wsl_distro_overrides)?;
+    validate_gateway_access_token(&settings.gateway_access_token)?;
+    validate_gateway_extra_listeners(settings)?;
+    validate_gateway_client_keys(&settings.gateway_client_keys)?;
+    validate_gateway_ip_allowlist(&settings.gateway_ip_allowlist)?;
+    validate_gateway_tls_hosts(&settings.gateway_tls_hosts)?;
+    validate_intercept_rules(&settings.intercept_rules)?;
+    validate_request_redaction_patterns(&settings.request_redaction_custom_patterns)?;
+    validate_header_sanitize_rules(&settings.header_sanitize_rules)?;
+    validate_failover_rules(&settings.failover_rules)?;
+    if settings.gateway_drain_timeout_seconds > MAX_GATEWAY_DRAIN_TIMEOUT_SECONDS {
+        return Err(format!(
+            "gateway_drain_timeout_seconds must be <= {MAX_GATEWAY_DRAIN_TIMEOUT_SECONDS}"
+        ));
+    }
+    if parse_hhmm_minutes(&settings.notice_quiet_hours_start).is_none()
+        || parse_hhmm_minutes(&settings.notice_quiet_hours_end).is_none()
+    {
+        return Err("notice_quiet_hours_start/end must be HH:MM (00:00-23:59)".to_string());
+    }
     if settings.failover_max_attempts_per_provider == 0 {
         return Err("failover_max_attempts_per_provider must be >= 1".to_string());
     }
@@ -0,0 +1,305 @@
+//! Usage: Hourly usage/cost rollups of `request_logs` and the hybrid source that stats queries read.
+//!
+//! 用法：
+//! - `usage_rollups_hourly` 按 (UTC 小时, cli_key, final_provider_id, model_key) 预聚合请求数 / 耗时 / tokens / 花费
+//! - `usage_rollup_state.rolled_until` 是水位线：其之前的整点小时已汇总完毕
+//! - request_logs 上的触发器在写入 / 更新水位线之前的行时（流式请求晚落库、花费回填）回退水位线，下一轮刷新重算这些小时
+//! - 统计查询通过 `stats_source_sql` 读取：范围内水位线之前的整点小时读汇总表，首尾不完整的小时与水位线之后读原始日志，
+//!   结果与直接扫描 request_logs 一致
+//!
+//! Note: trend buckets use the rollup hour as timestamp, so in time zones with a non-whole-hour UTC offset
+//! (e.g. +05:30) a rolled-up hour is attributed to the local hour it starts in.
+
+use crate::shared::time::now_unix_seconds;
+use crate::{blocking, cost_stats, db, power, usage_stats};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use std::time::Duration;
+
+const HOUR_SECS: i64 = 3600;
+// 单个事务最多汇总 7 天，避免首次升级时长时间占用写锁
+const MAX_HOURS_PER_REFRESH: i64 = 24 * 7;
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+const ROLLUP_MEASURES: &[&str] = &[
+    "requests_total",
+    "requests_with_usage",
+    "requests_success",
+    "requests_failed",
+    "success_duration_ms_sum",
+    "success_ttfb_ms_sum",
+    "success_ttfb_ms_count",
+    "success_generation_ms_sum",
+    "success_output_tokens_for_rate_sum",
+    "input_tokens",
+    "output_tokens",
+    "total_tokens",
+    "cache_read_input_tokens",
+    "cache_creation_input_tokens",
+    "cache_creation_5m_input_tokens",
+    "cache_creation_1h_input_tokens",
+    "cost_usd_femto",
+    "success_cost_covered",
+    "success_cost_usd_femto",
+    "success_costed_duration_ms_sum",
+];
+
+const SQL_SUCCESS_EXPR: &str = "(status >= 200 AND status < 300 AND error_code IS NULL)";
+
+fn hour_floor(ts: i64) -> i64 {
+    ts.div_euclid(HOUR_SECS) * HOUR_SECS
+}
+
+fn hour_ceil(ts: i64) -> i64 {
+    hour_floor(ts.saturating_add(HOUR_SECS - 1))
+}
+
+/// Per-row measure expressions over `request_logs`, in `ROLLUP_MEASURES` order.
+fn row_measure_exprs() -> Vec<String> {
+    let success = SQL_SUCCESS_EXPR;
+    let ttfb_valid = "(ttfb_ms IS NOT NULL AND ttfb_ms < duration_ms)";
+    vec![
+        "1".to_string(),
+        r#"CASE WHEN (
+  total_tokens IS NOT NULL OR
+  input_tokens IS NOT NULL OR
+  output_tokens IS NOT NULL OR
+  cache_read_input_tokens IS NOT NULL OR
+  cache_creation_input_tokens IS NOT NULL OR
+  cache_creation_5m_input_tokens IS NOT NULL OR
+  cache_creation_1h_input_tokens IS NOT NULL OR
+  usage_json IS NOT NULL
+) THEN 1 ELSE 0 END"#
+            .to_string(),
+        format!("CASE WHEN {success} THEN 1 ELSE 0 END"),
+        format!("CASE WHEN {success} THEN 0 ELSE 1 END"),
+        format!("CASE WHEN {success} THEN duration_ms ELSE 0 END"),
+        format!("CASE WHEN {success} AND {ttfb_valid} THEN ttfb_ms ELSE 0 END"),
+        format!("CASE WHEN {success} AND {ttfb_valid} THEN 1 ELSE 0 END"),
+        format!(
+            "CASE WHEN {success} AND output_tokens IS NOT NULL AND {ttfb_valid} THEN (duration_ms - ttfb_ms) ELSE 0 END"
+        ),
+        format!(
+            "CASE WHEN {success} AND output_tokens IS NOT NULL AND {ttfb_valid} THEN output_tokens ELSE 0 END"
+        ),
+        usage_stats::SQL_EFFECTIVE_INPUT_TOKENS_EXPR.to_string(),
+        "COALESCE(output_tokens, 0)".to_string(),
+        usage_stats::sql_effective_total_tokens_expr(),
+        "COALESCE(cache_read_input_tokens, 0)".to_string(),
+        "COALESCE(cache_creation_input_tokens, 0)".to_string(),
+        "COALESCE(cache_creation_5m_input_tokens, 0)".to_string(),
+        "COALESCE(cache_creation_1h_input_tokens, 0)".to_string(),
+        "COALESCE(cost_usd_femto, 0)".to_string(),
+        format!("CASE WHEN {success} AND cost_usd_femto IS NOT NULL THEN 1 ELSE 0 END"),
+        format!("CASE WHEN {success} THEN COALESCE(cost_usd_femto, 0) ELSE 0 END"),
+        format!(
+            "CASE WHEN {success} AND cost_usd_femto IS NOT NULL AND duration_ms >= 0 THEN duration_ms ELSE 0 END"
+        ),
+    ]
+}
+
+fn read_rolled_until(conn: &Connection) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT rolled_until FROM usage_rollup_state WHERE id = 1",
+        [],
+        |row| row.get::<_, i64>(0),
+    )
+    .optional()
+    .map_err(|e| format!("DB_ERROR: failed to read usage rollup watermark: {e}"))
+    .map(|v| v.unwrap_or(0))
+}
+
+fn set_rolled_until(conn: &Connection, rolled_until: i64) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO usage_rollup_state(id, rolled_until) VALUES (1, ?1) ON CONFLICT(id) DO UPDATE SET rolled_until = excluded.rolled_until",
+        params![rolled_until],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to update usage rollup watermark: {e}"))?;
+    Ok(())
+}
+
+/// Recomputes rollup rows for hours in `[from, to)` from `request_logs`.
+fn rebuild_hours(conn: &Connection, from: i64, to: i64) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM usage_rollups_hourly WHERE hour_ts >= ?1 AND hour_ts < ?2",
+        params![from, to],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to clear usage rollups: {e}"))?;
+
+    let sums = row_measure_exprs()
+        .iter()
+        .map(|expr| format!("SUM({expr})"))
+        .collect::<Vec<_>>()
+        .join(",\n  ");
+    let sql = format!(
+        r#"
+INSERT INTO usage_rollups_hourly(
+  hour_ts,
+  cli_key,
+  final_provider_id,
+  model_key,
+  {columns}
+)
+SELECT
+  (created_at / {HOUR_SECS}) * {HOUR_SECS} AS hour_ts,
+  cli_key,
+  COALESCE(final_provider_id, 0) AS provider_key,
+  {model_key_expr} AS model_key,
+  {sums}
+FROM request_logs
+WHERE excluded_from_stats = 0
+AND created_at >= ?1
+AND created_at < ?2
+GROUP BY hour_ts, cli_key, provider_key, model_key
+"#,
+        columns = ROLLUP_MEASURES.join(",\n  "),
+        model_key_expr = cost_stats::SQL_MODEL_KEY_EXPR,
+        sums = sums,
+    );
+    conn.execute(&sql, params![from, to])
+        .map_err(|e| format!("DB_ERROR: failed to rebuild usage rollups: {e}"))?;
+    Ok(())
+}
+
+/// Rolls up at most `MAX_HOURS_PER_REFRESH` complete hours past the watermark; returns hours rolled.
+fn refresh_with_conn(conn: &mut Connection, now_unix: i64) -> Result<i64, String> {
+    let target = hour_floor(now_unix);
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
+
+    let rolled_until = read_rolled_until(&tx)?;
+    if rolled_until >= target {
+        return Ok(0);
+    }
+
+    let from = if rolled_until > 0 {
+        rolled_until
+    } else {
+        let min_created_at: Option<i64> = tx
+            .query_row(
+                "SELECT MIN(created_at) FROM request_logs WHERE excluded_from_stats = 0",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("DB_ERROR: failed to read oldest request_log: {e}"))?;
+        min_created_at.map(hour_floor).unwrap_or(target).min(target)
+    };
+    let to = target.min(from.saturating_add(MAX_HOURS_PER_REFRESH * HOUR_SECS));
+
+    if from < to {
+        rebuild_hours(&tx, from, to)?;
+    }
+    set_rolled_until(&tx, to)?;
+
+    tx.commit()
+        .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+    Ok((to - from) / HOUR_SECS)
+}
+
+pub fn refresh(db: &db::Db) -> Result<i64, String> {
+    let mut conn = db.open_connection()?;
+    let now = now_unix_seconds();
+    let mut total = 0;
+    loop {
+        let rolled = refresh_with_conn(&mut conn, now)?;
+        if rolled == 0 {
+            return Ok(total);
+        }
+        total += rolled;
+    }
+}
+
+/// Keeps rollups aligned after retention cleanup deleted `request_logs` rows with `created_at < cutoff`.
+pub fn prune_before(conn: &mut Connection, cutoff: i64) -> Result<(), String> {
+    let cutoff_hour = hour_floor(cutoff);
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| format!("DB_ERROR: failed to start transaction: {e}"))?;
+
+    tx.execute(
+        "DELETE FROM usage_rollups_hourly WHERE hour_ts < ?1",
+        params![cutoff_hour],
+    )
+    .map_err(|e| format!("DB_ERROR: failed to prune usage rollups: {e}"))?;
+    // The hour containing the cutoff lost only part of its rows.
+    if cutoff_hour < cutoff && cutoff_hour < read_rolled_until(&tx)? {
+        rebuild_hours(&tx, cutoff_hour, cutoff_hour + HOUR_SECS)?;
+    }
+
+    tx.commit()
+        .map_err(|e| format!("DB_ERROR: failed to commit transaction: {e}"))?;
+    Ok(())
+}
+
+/// Drops all rollups (used when request_logs are cleared); the caller owns the transaction.
+pub fn clear_all(conn: &Connection) -> Result<(), String> {
+    conn.execute("DELETE FROM usage_rollups_hourly", [])
+        .map_err(|e| format!("DB_ERROR: failed to clear usage rollups: {e}"))?;
+    set_rolled_until(conn, 0)
+}
+
+/// Subquery yielding stats rows for `[start_ts, end_ts)`: one row per rolled-up hour group plus one row
+/// per raw request outside the rolled-up window. Columns: `created_at`, `cli_key`, `final_provider_id`
+/// (0 when unknown), `model_key` and every rollup measure. Excluded-from-stats rows are already dropped;
+/// callers still apply their own time / cli / provider / model filters on top.
+pub fn stats_source_sql(
+    conn: &Connection,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+) -> Result<String, String> {
+    let rolled_until = read_rolled_until(conn)?;
+    let rollup_start = start_ts.map(hour_ceil).unwrap_or(0).max(0);
+    let rollup_end = end_ts.map(hour_floor).unwrap_or(i64::MAX).min(rolled_until);
+    let (rollup_start, rollup_end) = if rollup_start < rollup_end {
+        (rollup_start, rollup_end)
+    } else {
+        (0, 0)
+    };
+
+    let measures = ROLLUP_MEASURES.join(", ");
+    let raw_measures = row_measure_exprs()
+        .iter()
+        .zip(ROLLUP_MEASURES)
+        .map(|(expr, name)| format!("{expr} AS {name}"))
+        .collect::<Vec<_>>()
+        .join(",\n    ");
+
+    Ok(format!(
+        r#"(
+  SELECT hour_ts AS created_at, cli_key, final_provider_id, model_key, {measures}
+  FROM usage_rollups_hourly
+  WHERE hour_ts >= {rollup_start} AND hour_ts < {rollup_end}
+  UNION ALL
+  SELECT
+    created_at,
+    cli_key,
+    COALESCE(final_provider_id, 0) AS final_provider_id,
+    {model_key_expr} AS model_key,
+    {raw_measures}
+  FROM request_logs
+  WHERE excluded_from_stats = 0
+  AND (created_at < {rollup_start} OR created_at >= {rollup_end})
+)"#,
+        model_key_expr = cost_stats::SQL_MODEL_KEY_EXPR,
+    ))
+}
+
+pub(crate) fn spawn_refresh_loop(app: tauri::AppHandle, db: db::Db) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // 低功耗模式下暂停后台汇总；查询仍可回落到原始日志，结果不受影响
+            if !power::background_work_suspended(&app) {
+                let db = db.clone();
+                match blocking::run("usage_rollups_refresh", move || refresh(&db)).await {
+                    Ok(hours) if hours > 0 => tracing::debug!(hours, "用量汇总表已刷新"),
+                    Ok(_) => {}
+                    Err(err) => tracing::warn!("用量汇总表刷新失败: {}", err),
+                }
+            }
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests;
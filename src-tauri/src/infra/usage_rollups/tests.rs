@@ -0,0 +1,203 @@
+use super::*;
+use rusqlite::{params, Connection};
+
+const T0: i64 = 1_700_000_000 - 1_700_000_000 % HOUR_SECS;
+
+fn setup_conn() -> Connection {
+    let conn = Connection::open_in_memory().expect("open in-memory sqlite");
+    conn.execute_batch(
+        r#"
+CREATE TABLE request_logs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  cli_key TEXT NOT NULL,
+  final_provider_id INTEGER,
+  requested_model TEXT,
+  status INTEGER,
+  error_code TEXT,
+  duration_ms INTEGER NOT NULL,
+  ttfb_ms INTEGER,
+  input_tokens INTEGER,
+  output_tokens INTEGER,
+  total_tokens INTEGER,
+  cache_read_input_tokens INTEGER,
+  cache_creation_input_tokens INTEGER,
+  cache_creation_5m_input_tokens INTEGER,
+  cache_creation_1h_input_tokens INTEGER,
+  cost_usd_femto INTEGER,
+  usage_json TEXT,
+  excluded_from_stats INTEGER NOT NULL DEFAULT 0,
+  created_at INTEGER NOT NULL
+);
+"#,
+    )
+    .expect("create request_logs");
+
+    let columns = ROLLUP_MEASURES
+        .iter()
+        .map(|name| format!("{name} INTEGER NOT NULL DEFAULT 0,"))
+        .collect::<Vec<_>>()
+        .join("\n  ");
+    conn.execute_batch(&format!(
+        r#"
+CREATE TABLE usage_rollups_hourly (
+  hour_ts INTEGER NOT NULL,
+  cli_key TEXT NOT NULL,
+  final_provider_id INTEGER NOT NULL,
+  model_key TEXT NOT NULL,
+  {columns}
+  PRIMARY KEY(hour_ts, cli_key, final_provider_id, model_key)
+);
+
+CREATE TABLE usage_rollup_state (
+  id INTEGER PRIMARY KEY CHECK (id = 1),
+  rolled_until INTEGER NOT NULL
+);
+INSERT INTO usage_rollup_state(id, rolled_until) VALUES (1, 0);
+
+CREATE TRIGGER trg_request_logs_rollup_insert
+AFTER INSERT ON request_logs
+WHEN NEW.created_at < (SELECT rolled_until FROM usage_rollup_state WHERE id = 1)
+BEGIN
+  UPDATE usage_rollup_state
+  SET rolled_until = MIN(rolled_until, (NEW.created_at / 3600) * 3600)
+  WHERE id = 1;
+END;
+
+CREATE TRIGGER trg_request_logs_rollup_update
+AFTER UPDATE ON request_logs
+WHEN MIN(OLD.created_at, NEW.created_at) < (SELECT rolled_until FROM usage_rollup_state WHERE id = 1)
+BEGIN
+  UPDATE usage_rollup_state
+  SET rolled_until = MIN(rolled_until, (MIN(OLD.created_at, NEW.created_at) / 3600) * 3600)
+  WHERE id = 1;
+END;
+"#
+    ))
+    .expect("create rollup schema");
+    conn
+}
+
+fn insert_log(conn: &Connection, created_at: i64, status: i64, cost_usd_femto: Option<i64>) {
+    conn.execute(
+        "INSERT INTO request_logs(cli_key, final_provider_id, requested_model, status, duration_ms, ttfb_ms, input_tokens, output_tokens, cost_usd_femto, created_at) VALUES ('claude', 1, 'm1', ?1, 1000, 100, 10, 5, ?2, ?3)",
+        params![status, cost_usd_femto, created_at],
+    )
+    .expect("insert request_log");
+}
+
+fn totals(conn: &Connection, start_ts: Option<i64>, end_ts: Option<i64>) -> (i64, i64, i64, i64) {
+    let source = stats_source_sql(conn, start_ts, end_ts).expect("stats_source_sql");
+    conn.query_row(
+        &format!(
+            "SELECT SUM(requests_total), SUM(requests_success), SUM(total_tokens), SUM(success_cost_usd_femto) FROM {source} WHERE (?1 IS NULL OR created_at >= ?1) AND (?2 IS NULL OR created_at < ?2)"
+        ),
+        params![start_ts, end_ts],
+        |row| {
+            Ok((
+                row.get::<_, Option<i64>>(0)?.unwrap_or(0),
+                row.get::<_, Option<i64>>(1)?.unwrap_or(0),
+                row.get::<_, Option<i64>>(2)?.unwrap_or(0),
+                row.get::<_, Option<i64>>(3)?.unwrap_or(0),
+            ))
+        },
+    )
+    .expect("query totals")
+}
+
+fn rollup_rows(conn: &Connection) -> i64 {
+    conn.query_row("SELECT COUNT(*) FROM usage_rollups_hourly", [], |row| {
+        row.get(0)
+    })
+    .expect("count rollups")
+}
+
+#[test]
+fn rolled_up_totals_match_raw_logs() {
+    let mut conn = setup_conn();
+    for hour in 0..5 {
+        for i in 0..3 {
+            let status = if i == 2 { 500 } else { 200 };
+            insert_log(&conn, T0 + hour * HOUR_SECS + i * 600, status, Some(7));
+        }
+    }
+    insert_log(&conn, T0 + 5 * HOUR_SECS + 10, 200, Some(7));
+
+    let ranges = [
+        (None, None),
+        (Some(T0 + 1000), Some(T0 + 3 * HOUR_SECS + 1300)),
+        (Some(T0 + HOUR_SECS), None),
+    ];
+    let raw: Vec<_> = ranges.iter().map(|(s, e)| totals(&conn, *s, *e)).collect();
+
+    let rolled = refresh_with_conn(&mut conn, T0 + 5 * HOUR_SECS + 60).expect("refresh");
+    assert_eq!(rolled, 5);
+    assert_eq!(
+        read_rolled_until(&conn).expect("watermark"),
+        T0 + 5 * HOUR_SECS
+    );
+    assert_eq!(rollup_rows(&conn), 5);
+
+    for ((s, e), expected) in ranges.iter().zip(raw) {
+        assert_eq!(totals(&conn, *s, *e), expected);
+    }
+    assert_eq!(totals(&conn, None, None), (16, 11, 240, 77));
+}
+
+#[test]
+fn late_insert_and_backfill_rewind_watermark() {
+    let mut conn = setup_conn();
+    insert_log(&conn, T0 + 10, 200, None);
+    insert_log(&conn, T0 + 2 * HOUR_SECS + 10, 200, None);
+    refresh_with_conn(&mut conn, T0 + 3 * HOUR_SECS).expect("refresh");
+    assert_eq!(
+        read_rolled_until(&conn).expect("watermark"),
+        T0 + 3 * HOUR_SECS
+    );
+
+    // A stream that started in hour 1 lands after the hour was rolled up.
+    insert_log(&conn, T0 + HOUR_SECS + 30, 200, Some(5));
+    assert_eq!(read_rolled_until(&conn).expect("watermark"), T0 + HOUR_SECS);
+    assert_eq!(totals(&conn, None, None).0, 3);
+
+    refresh_with_conn(&mut conn, T0 + 3 * HOUR_SECS).expect("refresh");
+    // Cost backfill on an already rolled-up row.
+    conn.execute(
+        "UPDATE request_logs SET cost_usd_femto = 9 WHERE created_at = ?1",
+        params![T0 + 10],
+    )
+    .expect("backfill cost");
+    assert_eq!(read_rolled_until(&conn).expect("watermark"), T0);
+    assert_eq!(totals(&conn, None, None), (3, 3, 45, 14));
+
+    refresh_with_conn(&mut conn, T0 + 3 * HOUR_SECS).expect("refresh");
+    assert_eq!(
+        read_rolled_until(&conn).expect("watermark"),
+        T0 + 3 * HOUR_SECS
+    );
+    assert_eq!(totals(&conn, None, None), (3, 3, 45, 14));
+}
+
+#[test]
+fn prune_before_rebuilds_partial_hour() {
+    let mut conn = setup_conn();
+    for hour in 0..3 {
+        insert_log(&conn, T0 + hour * HOUR_SECS + 10, 200, Some(1));
+        insert_log(&conn, T0 + hour * HOUR_SECS + 2000, 200, Some(1));
+    }
+    refresh_with_conn(&mut conn, T0 + 3 * HOUR_SECS).expect("refresh");
+
+    let cutoff = T0 + HOUR_SECS + 1000;
+    conn.execute(
+        "DELETE FROM request_logs WHERE created_at < ?1",
+        params![cutoff],
+    )
+    .expect("delete old logs");
+    prune_before(&mut conn, cutoff).expect("prune");
+
+    assert_eq!(rollup_rows(&conn), 2);
+    assert_eq!(totals(&conn, None, None).0, 3);
+
+    clear_all(&conn).expect("clear");
+    assert_eq!(rollup_rows(&conn), 0);
+    assert_eq!(read_rolled_until(&conn).expect("watermark"), 0);
+}
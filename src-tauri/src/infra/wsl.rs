@@ -1,8 +1,22 @@
 //! Usage: Windows WSL detection and per-distro client configuration helpers.
 
+use crate::app_paths;
 use crate::settings;
-use serde::Serialize;
+use crate::shared::fs::{read_optional_file, write_file_atomic};
+use crate::shared::time::now_unix_seconds;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+pub const PLACEHOLDER_API_KEY: &str = "aio-coding-hub";
+const DISTRO_STATE_FILE_NAME: &str = "wsl-distro-state.json";
+const PORT_PROXY_STATE_FILE_NAME: &str = "wsl-port-proxy.json";
+// Mirrors whether the state file records a mapping; loaded once, then kept in sync by
+// `ensure_port_proxy` / `remove_port_proxy` (read per request by the gateway access check).
+static PORT_PROXY_ACTIVE: OnceLock<AtomicBool> = OnceLock::new();
 
 #[derive(Debug, Clone, Serialize)]
 pub struct WslDetection {
@@ -16,6 +30,18 @@ pub struct WslDistroConfigStatus {
     pub claude: bool,
     pub codex: bool,
     pub gemini: bool,
+    pub last_configured: Option<WslDistroConfiguredState>,
+}
+
+// Last successful `wsl_configure_clients` result for one distro (persisted in app data dir).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WslDistroConfiguredState {
+    pub proxy_origin: String,
+    pub configured_at: i64,
+    pub claude: bool,
+    pub codex: bool,
+    pub gemini: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -37,6 +63,47 @@ pub struct WslConfigureReport {
     pub ok: bool,
     pub message: String,
     pub distros: Vec<WslConfigureDistroReport>,
+    pub firewall: Option<WslFirewallReport>,
+    pub networking: Option<WslNetworkingDiagnostic>,
+    pub port_proxy: Option<WslPortProxyReport>,
+}
+
+// netsh v4tov4 mapping created by us; persisted so it can be removed when the address/port changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WslPortProxyMapping {
+    pub listen_address: String,
+    pub listen_port: u16,
+    pub connect_address: String,
+    pub connect_port: u16,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WslPortProxyReport {
+    pub ok: bool,
+    pub message: String,
+    pub mapping: Option<WslPortProxyMapping>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WslNetworkingMode {
+    Nat,
+    Mirrored,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WslNetworkingDiagnostic {
+    pub mode: WslNetworkingMode,
+    pub wslconfig_path: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WslFirewallReport {
+    pub ok: bool,
+    pub message: String,
+    pub rule_name: String,
+    pub remote_subnet: Option<String>,
 }
 
 #[cfg(windows)]
@@ -110,16 +177,23 @@ pub fn detect() -> WslDetection {
     out
 }
 
-pub fn host_ipv4_best_effort() -> Option<String> {
+// Returns (ipv4, subnet mask) of the host-side "vEthernet (WSL)" adapter.
+fn wsl_adapter_ipv4() -> Option<(std::net::Ipv4Addr, Option<std::net::Ipv4Addr>)> {
     if !cfg!(windows) {
         return None;
     }
 
     let output = hide_window_cmd("ipconfig").output().ok()?;
     let text = String::from_utf8_lossy(&output.stdout).to_string();
+    parse_wsl_adapter_ipv4(&text)
+}
+
+fn parse_wsl_adapter_ipv4(text: &str) -> Option<(std::net::Ipv4Addr, Option<std::net::Ipv4Addr>)> {
     use std::net::Ipv4Addr;
 
     let mut in_wsl_adapter = false;
+    let mut ip: Option<Ipv4Addr> = None;
+    let mut mask: Option<Ipv4Addr> = None;
     for raw_line in text.lines() {
         let line = raw_line.trim();
 
@@ -140,19 +214,218 @@ pub fn host_ipv4_best_effort() -> Option<String> {
             continue;
         }
 
+        let Some((_, tail)) = line.rsplit_once(':') else {
+            continue;
+        };
+        let value = tail.trim();
         if line.contains("IPv4") || line.contains("IP Address") {
-            let (_, tail) = line.rsplit_once(':')?;
-            let ip = tail.trim();
-            if ip.is_empty() || ip.contains(':') {
-                continue;
-            }
-            if ip.parse::<Ipv4Addr>().is_ok() {
-                return Some(ip.to_string());
+            if ip.is_none() {
+                ip = value.parse::<Ipv4Addr>().ok();
             }
+        } else if line.contains("Subnet Mask") || line.contains("子网掩码") {
+            mask = value.parse::<Ipv4Addr>().ok();
         }
+
+        if ip.is_some() && mask.is_some() {
+            break;
+        }
+    }
+
+    ip.map(|ip| (ip, mask))
+}
+
+// `.wslconfig` is INI-like: `[wsl2]` section, `networkingMode=mirrored` (keys are case-insensitive).
+fn parse_wslconfig_networking_mode(text: &str) -> Option<WslNetworkingMode> {
+    let mut in_wsl2 = false;
+    let mut mode = None;
+    for raw_line in text.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            in_wsl2 = line[1..line.len() - 1].trim().eq_ignore_ascii_case("wsl2");
+            continue;
+        }
+        if !in_wsl2 {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if !key.trim().eq_ignore_ascii_case("networkingMode") {
+            continue;
+        }
+        let value = value
+            .split(['#', ';'])
+            .next()
+            .unwrap_or("")
+            .trim()
+            .trim_matches('"');
+        mode = Some(if value.eq_ignore_ascii_case("mirrored") {
+            WslNetworkingMode::Mirrored
+        } else {
+            WslNetworkingMode::Nat
+        });
+    }
+    mode
+}
+
+pub fn networking_diagnostic(home_dir: Option<&std::path::Path>) -> WslNetworkingDiagnostic {
+    let path = home_dir.map(|dir| dir.join(".wslconfig"));
+    let wslconfig_path = path.as_ref().map(|p| p.to_string_lossy().to_string());
+
+    let text = path.as_ref().and_then(|p| std::fs::read_to_string(p).ok());
+    let Some(text) = text else {
+        return WslNetworkingDiagnostic {
+            mode: WslNetworkingMode::Nat,
+            wslconfig_path,
+            message: "未找到 .wslconfig，按默认 NAT 网络模式处理：WSL 通过宿主机 vEthernet (WSL) 地址访问网关".to_string(),
+        };
+    };
+
+    match parse_wslconfig_networking_mode(&text) {
+        Some(WslNetworkingMode::Mirrored) => WslNetworkingDiagnostic {
+            mode: WslNetworkingMode::Mirrored,
+            wslconfig_path,
+            message: ".wslconfig 中 networkingMode=mirrored：WSL 与 Windows 共享网络，直接通过 127.0.0.1 访问网关".to_string(),
+        },
+        Some(WslNetworkingMode::Nat) => WslNetworkingDiagnostic {
+            mode: WslNetworkingMode::Nat,
+            wslconfig_path,
+            message: ".wslconfig 中 networkingMode 不是 mirrored，按 NAT 网络模式处理：WSL 通过宿主机 vEthernet (WSL) 地址访问网关".to_string(),
+        },
+        None => WslNetworkingDiagnostic {
+            mode: WslNetworkingMode::Nat,
+            wslconfig_path,
+            message: ".wslconfig 未设置 networkingMode，按默认 NAT 网络模式处理：WSL 通过宿主机 vEthernet (WSL) 地址访问网关".to_string(),
+        },
+    }
+}
+
+pub fn host_ipv4_best_effort() -> Option<String> {
+    wsl_adapter_ipv4().map(|(ip, _)| ip.to_string())
+}
+
+// WSL2 NAT uses a /20 by default; fall back to it when the mask is missing/localized.
+const WSL_DEFAULT_PREFIX_LEN: u32 = 20;
+
+fn subnet_cidr(ip: std::net::Ipv4Addr, mask: Option<std::net::Ipv4Addr>) -> String {
+    let prefix_len = mask
+        .map(|m| u32::from(m).leading_ones())
+        .filter(|len| (8..=30).contains(len))
+        .unwrap_or(WSL_DEFAULT_PREFIX_LEN);
+    let mask_bits = u32::MAX << (32 - prefix_len);
+    let network = std::net::Ipv4Addr::from(u32::from(ip) & mask_bits);
+    format!("{network}/{prefix_len}")
+}
+
+// Runs `cmd.exe /c {cmdline}` elevated (UAC prompt) and waits for it.
+fn run_elevated_cmd(cmdline: &str) -> Result<(), String> {
+    let ps_script = format!(
+        "$p = Start-Process -FilePath cmd.exe -ArgumentList '/c {}' -Verb RunAs -WindowStyle Hidden -Wait -PassThru; exit $p.ExitCode",
+        cmdline.replace('\'', "''")
+    );
+
+    let output = hide_window_cmd("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-ExecutionPolicy",
+            "Bypass",
+            "-Command",
+            &ps_script,
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("无法启动 PowerShell：{e}"))?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if stderr.is_empty() {
+        Err("执行失败（可能已取消管理员授权）".to_string())
+    } else {
+        Err(stderr)
+    }
+}
+
+pub const FIREWALL_RULE_NAME: &str = "AIO Coding Hub Gateway (WSL)";
+
+fn firewall_rule_matches(port: u16, remote_subnet: &str) -> bool {
+    let output = hide_window_cmd("netsh")
+        .args([
+            "advfirewall",
+            "firewall",
+            "show",
+            "rule",
+            &format!("name={FIREWALL_RULE_NAME}"),
+        ])
+        .stdin(Stdio::null())
+        .output();
+    let Ok(output) = output else {
+        return false;
+    };
+    if !output.status.success() {
+        return false;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let port_ok = text.lines().any(|line| {
+        let line = line.trim();
+        line.starts_with("LocalPort") && line.ends_with(&port.to_string())
+    });
+    // netsh prints the subnet as "a.b.c.d/m.m.m.m" on most builds; only the network part is stable.
+    let network = remote_subnet.split('/').next().unwrap_or(remote_subnet);
+    port_ok && text.contains(network)
+}
+
+/// Creates (or replaces) the inbound firewall rule that lets the WSL subnet reach the gateway port.
+///
+/// Requires elevation: a UAC prompt is shown, so only call this after the user has opted in.
+pub fn ensure_firewall_rule(port: u16) -> WslFirewallReport {
+    let mut report = WslFirewallReport {
+        ok: false,
+        message: String::new(),
+        rule_name: FIREWALL_RULE_NAME.to_string(),
+        remote_subnet: None,
+    };
+
+    if !cfg!(windows) {
+        report.message = "Firewall configuration is only available on Windows".to_string();
+        return report;
+    }
+
+    let Some((ip, mask)) = wsl_adapter_ipv4() else {
+        report.message = "未找到 vEthernet (WSL) 网卡，无法确定 WSL 子网".to_string();
+        return report;
+    };
+    let remote_subnet = subnet_cidr(ip, mask);
+    report.remote_subnet = Some(remote_subnet.clone());
+
+    if firewall_rule_matches(port, &remote_subnet) {
+        report.ok = true;
+        report.message = "防火墙规则已存在".to_string();
+        return report;
     }
 
-    None
+    // Delete + add in one elevated cmd so the user only sees a single UAC prompt.
+    let netsh_cmds = format!(
+        "netsh advfirewall firewall delete rule name=\"{FIREWALL_RULE_NAME}\" & netsh advfirewall firewall add rule name=\"{FIREWALL_RULE_NAME}\" dir=in action=allow protocol=TCP localport={port} remoteip={remote_subnet} profile=any"
+    );
+    if let Err(err) = run_elevated_cmd(&netsh_cmds) {
+        tracing::warn!("WSL 防火墙规则创建失败: {}", err);
+        report.message = format!("防火墙规则创建失败：{err}");
+        return report;
+    }
+
+    if firewall_rule_matches(port, &remote_subnet) {
+        report.ok = true;
+        report.message = format!("已允许 {remote_subnet} 访问端口 {port}");
+    } else {
+        report.message = "防火墙规则未生效（可能已取消管理员授权）".to_string();
+    }
+    report
 }
 
 fn run_wsl_bash_script(distro: &str, script: &str) -> Result<(), String> {
@@ -190,10 +463,10 @@ fn run_wsl_bash_script(distro: &str, script: &str) -> Result<(), String> {
     })
 }
 
-fn configure_wsl_claude(distro: &str, proxy_origin: &str) -> Result<(), String> {
+fn configure_wsl_claude(distro: &str, proxy_origin: &str, api_key: &str) -> Result<(), String> {
     let base_url = format!("{proxy_origin}/claude");
     let base_url = bash_single_quote(&base_url);
-    let auth_token = bash_single_quote("aio-coding-hub");
+    let auth_token = bash_single_quote(api_key);
 
     let script = format!(
         r#"
@@ -328,11 +601,11 @@ trap - EXIT
     run_wsl_bash_script(distro, &script)
 }
 
-fn configure_wsl_codex(distro: &str, proxy_origin: &str) -> Result<(), String> {
+fn configure_wsl_codex(distro: &str, proxy_origin: &str, api_key: &str) -> Result<(), String> {
     let base_url = format!("{proxy_origin}/v1");
     let base_url = bash_single_quote(&base_url);
     let provider_key = bash_single_quote("aio");
-    let api_key = bash_single_quote("aio-coding-hub");
+    let api_key = bash_single_quote(api_key);
 
     let script = format!(
         r#"
@@ -590,10 +863,10 @@ exit 1
     run_wsl_bash_script(distro, &script)
 }
 
-fn configure_wsl_gemini(distro: &str, proxy_origin: &str) -> Result<(), String> {
+fn configure_wsl_gemini(distro: &str, proxy_origin: &str, api_key: &str) -> Result<(), String> {
     let base_url = format!("{proxy_origin}/gemini");
     let base_url = bash_single_quote(&base_url);
-    let api_key = bash_single_quote("aio-coding-hub");
+    let api_key = bash_single_quote(api_key);
 
     let script = format!(
         r#"
@@ -809,22 +1082,218 @@ printf '%s%s%s\n' "$claude" "$codex" "$gemini"
             claude,
             codex,
             gemini,
+            last_configured: None,
         });
     }
 
     out
 }
 
+fn port_proxy_state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_paths::app_data_dir(app)?.join(PORT_PROXY_STATE_FILE_NAME))
+}
+
+pub fn read_port_proxy_state(
+    app: &tauri::AppHandle,
+) -> Result<Option<WslPortProxyMapping>, String> {
+    let path = port_proxy_state_path(app)?;
+    let Some(content) = read_optional_file(&path)? else {
+        return Ok(None);
+    };
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|e| format!("failed to parse {PORT_PROXY_STATE_FILE_NAME}: {e}"))
+}
+
+/// Whether a port proxy mapping created by us is in effect; forwarded WSL traffic then reaches the
+/// gateway from 127.0.0.1.
+pub fn port_proxy_active(app: &tauri::AppHandle) -> bool {
+    PORT_PROXY_ACTIVE
+        .get_or_init(|| AtomicBool::new(read_port_proxy_state(app).ok().flatten().is_some()))
+        .load(Ordering::Acquire)
+}
+
+fn set_port_proxy_active(active: bool) {
+    PORT_PROXY_ACTIVE
+        .get_or_init(|| AtomicBool::new(active))
+        .store(active, Ordering::Release);
+}
+
+fn port_proxy_delete_cmd(mapping: &WslPortProxyMapping) -> String {
+    format!(
+        "netsh interface portproxy delete v4tov4 listenaddress={} listenport={}",
+        mapping.listen_address, mapping.listen_port
+    )
+}
+
+fn port_proxy_listed(text: &str, mapping: &WslPortProxyMapping) -> bool {
+    let listen_port = mapping.listen_port.to_string();
+    let connect_port = mapping.connect_port.to_string();
+    text.lines().any(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        cols.len() == 4
+            && cols[0] == mapping.listen_address
+            && cols[1] == listen_port
+            && cols[2] == mapping.connect_address
+            && cols[3] == connect_port
+    })
+}
+
+fn port_proxy_exists(mapping: &WslPortProxyMapping) -> bool {
+    let output = hide_window_cmd("netsh")
+        .args(["interface", "portproxy", "show", "v4tov4"])
+        .stdin(Stdio::null())
+        .output();
+    let Ok(output) = output else {
+        return false;
+    };
+    port_proxy_listed(&String::from_utf8_lossy(&output.stdout), mapping)
+}
+
+/// Maps `{listen_address}:{port}` to `127.0.0.1:{port}` via `netsh interface portproxy`, so WSL
+/// can reach a localhost-only gateway. A previously created mapping is removed in the same
+/// elevated call (address/port changed).
+pub fn ensure_port_proxy(
+    app: &tauri::AppHandle,
+    listen_address: &str,
+    port: u16,
+) -> WslPortProxyReport {
+    let mapping = WslPortProxyMapping {
+        listen_address: listen_address.to_string(),
+        listen_port: port,
+        connect_address: "127.0.0.1".to_string(),
+        connect_port: port,
+    };
+    let mut report = WslPortProxyReport {
+        ok: false,
+        message: String::new(),
+        mapping: Some(mapping.clone()),
+    };
+
+    if !cfg!(windows) {
+        report.message = "Port proxy is only available on Windows".to_string();
+        return report;
+    }
+
+    let previous = read_port_proxy_state(app).ok().flatten();
+    if previous.as_ref() == Some(&mapping) && port_proxy_exists(&mapping) {
+        set_port_proxy_active(true);
+        report.ok = true;
+        report.message = "端口转发已存在".to_string();
+        return report;
+    }
+
+    let mut cmds = Vec::new();
+    if let Some(previous) = previous.as_ref().filter(|p| **p != mapping) {
+        cmds.push(port_proxy_delete_cmd(previous));
+    }
+    cmds.push(port_proxy_delete_cmd(&mapping));
+    cmds.push(format!(
+        "netsh interface portproxy add v4tov4 listenaddress={} listenport={} connectaddress={} connectport={}",
+        mapping.listen_address, mapping.listen_port, mapping.connect_address, mapping.connect_port
+    ));
+
+    if let Err(err) = run_elevated_cmd(&cmds.join(" & ")) {
+        tracing::warn!("WSL 端口转发创建失败: {}", err);
+        report.message = format!("端口转发创建失败：{err}");
+        return report;
+    }
+    if !port_proxy_exists(&mapping) {
+        report.message = "端口转发未生效（可能已取消管理员授权）".to_string();
+        return report;
+    }
+    set_port_proxy_active(true);
+
+    let saved = serde_json::to_vec_pretty(&mapping)
+        .map_err(|e| format!("failed to serialize {PORT_PROXY_STATE_FILE_NAME}: {e}"))
+        .and_then(|bytes| write_file_atomic(&port_proxy_state_path(app)?, &bytes));
+    if let Err(err) = saved {
+        tracing::warn!("WSL 端口转发状态保存失败: {}", err);
+    }
+
+    report.ok = true;
+    report.message = format!(
+        "已将 {}:{} 转发到 127.0.0.1:{}",
+        mapping.listen_address, mapping.listen_port, mapping.connect_port
+    );
+    report
+}
+
+/// Removes the mapping created by `ensure_port_proxy`; returns false when none was recorded.
+pub fn remove_port_proxy(app: &tauri::AppHandle) -> Result<bool, String> {
+    let Some(mapping) = read_port_proxy_state(app)? else {
+        return Ok(false);
+    };
+    if cfg!(windows) && port_proxy_exists(&mapping) {
+        run_elevated_cmd(&port_proxy_delete_cmd(&mapping))
+            .map_err(|e| format!("端口转发删除失败：{e}"))?;
+    }
+    let path = port_proxy_state_path(app)?;
+    std::fs::remove_file(&path).map_err(|e| format!("failed to remove {}: {e}", path.display()))?;
+    set_port_proxy_active(false);
+    Ok(true)
+}
+
+fn distro_state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_paths::app_data_dir(app)?.join(DISTRO_STATE_FILE_NAME))
+}
+
+pub fn read_distro_states(
+    app: &tauri::AppHandle,
+) -> Result<BTreeMap<String, WslDistroConfiguredState>, String> {
+    let path = distro_state_path(app)?;
+    let Some(content) = read_optional_file(&path)? else {
+        return Ok(BTreeMap::new());
+    };
+    serde_json::from_slice(&content)
+        .map_err(|e| format!("failed to parse {DISTRO_STATE_FILE_NAME}: {e}"))
+}
+
+/// Merges successful per-CLI results of `report` into the persisted per-distro state.
+pub fn record_distro_states(
+    app: &tauri::AppHandle,
+    report: &WslConfigureReport,
+    proxy_origin: &str,
+) -> Result<(), String> {
+    let mut states = read_distro_states(app).unwrap_or_default();
+    let now = now_unix_seconds();
+    for distro in &report.distros {
+        let ok_for = |cli_key: &str| distro.results.iter().any(|r| r.ok && r.cli_key == cli_key);
+        if !distro.results.iter().any(|r| r.ok) {
+            continue;
+        }
+        let entry = states.entry(distro.distro.clone()).or_default();
+        if entry.proxy_origin != proxy_origin {
+            // Gateway address changed: previously configured CLIs now point elsewhere.
+            *entry = WslDistroConfiguredState::default();
+        }
+        entry.proxy_origin = proxy_origin.to_string();
+        entry.configured_at = now;
+        entry.claude |= ok_for("claude");
+        entry.codex |= ok_for("codex");
+        entry.gemini |= ok_for("gemini");
+    }
+
+    let bytes = serde_json::to_vec_pretty(&states)
+        .map_err(|e| format!("failed to serialize {DISTRO_STATE_FILE_NAME}: {e}"))?;
+    write_file_atomic(&distro_state_path(app)?, &bytes)
+}
+
+/// `api_key` is written as the CLI API key: the gateway access token when set (WSL clients are not
+/// loopback peers), otherwise the placeholder.
 pub fn configure_clients(
-    distros: &[String],
-    targets: &settings::WslTargetCli,
+    distros: &[(String, settings::WslTargetCli)],
     proxy_origin: &str,
+    api_key: &str,
 ) -> WslConfigureReport {
     if !cfg!(windows) {
         return WslConfigureReport {
             ok: false,
             message: "WSL configuration is only available on Windows".to_string(),
             distros: Vec::new(),
+            firewall: None,
+            networking: None,
+            port_proxy: None,
         };
     }
 
@@ -832,11 +1301,11 @@ pub fn configure_clients(
     let mut success_ops = 0usize;
     let mut error_ops = 0usize;
 
-    for distro in distros {
+    for (distro, targets) in distros {
         let mut results = Vec::new();
 
         if targets.claude {
-            match configure_wsl_claude(distro, proxy_origin) {
+            match configure_wsl_claude(distro, proxy_origin, api_key) {
                 Ok(()) => results.push(WslConfigureCliReport {
                     cli_key: "claude".to_string(),
                     ok: true,
@@ -851,7 +1320,7 @@ pub fn configure_clients(
         }
 
         if targets.codex {
-            match configure_wsl_codex(distro, proxy_origin) {
+            match configure_wsl_codex(distro, proxy_origin, api_key) {
                 Ok(()) => results.push(WslConfigureCliReport {
                     cli_key: "codex".to_string(),
                     ok: true,
@@ -866,7 +1335,7 @@ pub fn configure_clients(
         }
 
         if targets.gemini {
-            match configure_wsl_gemini(distro, proxy_origin) {
+            match configure_wsl_gemini(distro, proxy_origin, api_key) {
                 Ok(()) => results.push(WslConfigureCliReport {
                     cli_key: "gemini".to_string(),
                     ok: true,
@@ -903,5 +1372,59 @@ pub fn configure_clients(
         ok: success_ops > 0,
         message,
         distros: distro_reports,
+        firewall: None,
+        networking: None,
+        port_proxy: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_wsl_adapter_ipv4, parse_wslconfig_networking_mode, port_proxy_listed, subnet_cidr,
+        WslNetworkingMode, WslPortProxyMapping,
+    };
+
+    #[test]
+    fn wsl_adapter_subnet_from_ipconfig() {
+        let text = "Ethernet adapter vEthernet (WSL):\r\n\r\n   IPv4 Address. . . . . . . . . . . : 172.28.112.1\r\n   Subnet Mask . . . . . . . . . . . : 255.255.240.0\r\n\r\nWireless LAN adapter Wi-Fi:\r\n   IPv4 Address. . . . . . . . . . . : 192.168.1.5\r\n";
+        let (ip, mask) = parse_wsl_adapter_ipv4(text).unwrap();
+        assert_eq!(ip.to_string(), "172.28.112.1");
+        assert_eq!(subnet_cidr(ip, mask), "172.28.112.0/20");
+        assert_eq!(subnet_cidr(ip, None), "172.28.112.0/20");
+    }
+
+    #[test]
+    fn wslconfig_mirrored_mode_is_detected() {
+        let text = "[wsl2]\r\nmemory=8GB\r\nnetworkingMode = Mirrored # win11\r\n";
+        assert_eq!(
+            parse_wslconfig_networking_mode(text),
+            Some(WslNetworkingMode::Mirrored)
+        );
+        assert_eq!(
+            parse_wslconfig_networking_mode("[experimental]\nnetworkingMode=mirrored\n"),
+            None
+        );
+        assert_eq!(
+            parse_wslconfig_networking_mode("[wsl2]\nnetworkingMode=NAT\n"),
+            Some(WslNetworkingMode::Nat)
+        );
+    }
+
+    #[test]
+    fn port_proxy_listed_matches_netsh_table() {
+        let text = "Listen on ipv4:             Connect to ipv4:\r\n\r\nAddress         Port        Address         Port\r\n--------------- ----------  --------------- ----------\r\n172.28.112.1    37123       127.0.0.1       37123\r\n";
+        let mapping = WslPortProxyMapping {
+            listen_address: "172.28.112.1".to_string(),
+            listen_port: 37123,
+            connect_address: "127.0.0.1".to_string(),
+            connect_port: 37123,
+        };
+        assert!(port_proxy_listed(text, &mapping));
+        let other = WslPortProxyMapping {
+            listen_port: 37124,
+            ..mapping
+        };
+        assert!(!port_proxy_listed(text, &other));
     }
 }
@@ -5,16 +5,22 @@ mod gateway;
 mod infra;
 mod shared;
 
-pub(crate) use app::{app_state, notice, resident};
+pub(crate) use app::{
+    admin_api, app_state, crash_report, headless, i18n, notice, power, quiet_hours, resident,
+    updater,
+};
 pub(crate) use domain::{
     claude_model_validation, claude_model_validation_history, cost, cost_stats, mcp, prompts,
-    providers, skills, sort_modes, usage, usage_stats,
+    provider_ab_test, provider_canary, provider_import, provider_snapshot, providers, skills,
+    sort_mode_schedules, sort_modes, sse_transcript, tokenizer, usage, usage_stats,
 };
 pub(crate) use gateway::session_manager;
 pub(crate) use infra::{
     app_paths, base_url_probe, claude_settings, cli_manager, cli_proxy, codex_config, codex_paths,
-    data_management, db, mcp_sync, model_price_aliases, model_prices, model_prices_sync,
-    prompt_sync, provider_circuit_breakers, request_attempt_logs, request_logs, settings, wsl,
+    data_management, db, diagnostic_bundle, gateway_tls, issue_draft, mcp_sync,
+    model_price_aliases, model_prices, model_prices_sync, notice_channels, notice_history,
+    prompt_sync, provider_circuit_breakers, request_attempt_logs, request_logs,
+    request_sse_captures, response_cache_store, settings, usage_rollups, wsl,
 };
 pub(crate) use shared::{blocking, circuit_breaker};
 
@@ -29,6 +35,41 @@ static EXIT_CLEANUP_SPAWNED: AtomicBool = AtomicBool::new(false);
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let headless_options = headless::options();
+    let context = tauri::generate_context!();
+    if headless_options.enabled {
+        run_headless(context, headless_options);
+    } else {
+        run_desktop(context, headless_options);
+    }
+}
+
+/// Gateway-only startup: no plugins, windows, tray or IPC commands, so no webview is ever created.
+fn run_headless(mut context: tauri::Context, options: headless::HeadlessOptions) {
+    // Windows declared in tauri.conf.json are created on startup; drop them for headless runs.
+    context.config_mut().app.windows.clear();
+
+    let app = tauri::Builder::default()
+        .manage(DbInitState::default())
+        .manage(GatewayState::default())
+        .manage(resident::ResidentState::default())
+        .setup(move |app| {
+            setup_common(app, options.admin_port);
+            tracing::info!(
+                admin_port = options.admin_port,
+                "以 headless 模式启动（不创建窗口、托盘与 webview）"
+            );
+            headless::install_signal_handlers(app.handle());
+            spawn_startup_tasks(app.handle().clone());
+            Ok(())
+        })
+        .build(context)
+        .expect("error while building tauri application");
+
+    app.run(on_run_event);
+}
+
+fn run_desktop(context: tauri::Context, options: headless::HeadlessOptions) {
     let builder = tauri::Builder::default()
         .manage(DbInitState::default())
         .manage(GatewayState::default())
@@ -45,8 +86,8 @@ pub fn run() {
 
     let app = builder
         .on_window_event(resident::on_window_event)
-        .setup(|app| {
-            crate::app::logging::init(app.handle());
+        .setup(move |app| {
+            setup_common(app, options.admin_port);
 
             #[cfg(desktop)]
             {
@@ -62,96 +103,65 @@ pub fn run() {
                 }
             }
 
-            #[cfg(debug_assertions)]
-            {
-                let enabled = std::env::var("AIO_CODING_HUB_DEV_DIAGNOSTICS")
-                    .ok()
-                    .map(|v| v.trim().to_ascii_lowercase())
-                    .is_some_and(|v| v == "1" || v == "true" || v == "yes");
-                if enabled {
-                    let identifier = &app.config().identifier;
-                    let product_name = app.config().product_name.as_deref().unwrap_or("<missing>");
-                    tracing::info!(identifier = %identifier, "[dev] tauri identifier");
-                    tracing::info!(product_name = %product_name, "[dev] productName");
-                    if let Ok(dotdir_name) = std::env::var("AIO_CODING_HUB_DOTDIR_NAME") {
-                        tracing::info!(dotdir_name = %dotdir_name, "[dev] AIO_CODING_HUB_DOTDIR_NAME");
-                    }
-                    if let Ok(dir) = app_paths::app_data_dir(app.handle()) {
-                        tracing::info!(dir = %dir.display(), "[dev] app data dir");
-                    }
-                }
-            }
-
-            let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                let db_state = app_handle.state::<DbInitState>();
-                let db = match ensure_db_ready(app_handle.clone(), db_state.inner()).await {
-                    Ok(db) => db,
-                    Err(err) => {
-                        tracing::error!("数据库初始化失败: {}", err);
-                        return;
-                    }
-                };
-
-                // M1: auto-start gateway on app launch (required for seamless CLI proxy experience).
-                // Port conflicts are handled by the gateway's bind-first-available strategy.
-                let settings = match blocking::run("startup_read_settings", {
-                    let app_handle = app_handle.clone();
-                    move || Ok(settings::read(&app_handle).unwrap_or_default())
-                })
-                .await
-                {
-                    Ok(cfg) => cfg,
-                    Err(err) => {
-                        tracing::warn!("配置读取失败，使用默认值: {}", err);
-                        settings::AppSettings::default()
-                    }
-                };
-
-                app_handle
-                    .state::<resident::ResidentState>()
-                    .set_tray_enabled(settings.tray_enabled);
-
-                let status = match blocking::run("startup_gateway_autostart", {
-                    let app_handle = app_handle.clone();
-                    let db = db.clone();
-                    move || {
-                        let state = app_handle.state::<GatewayState>();
-                        let mut manager = state.0.lock_or_recover();
-                        manager.start(&app_handle, db, Some(settings.preferred_port))
-                    }
-                })
-                .await
-                {
-                    Ok(status) => status,
-                    Err(err) => {
-                        tracing::error!("网关自动启动失败: {}", err);
-                        return;
-                    }
-                };
-
-                let _ = app_handle.emit("gateway:status", status.clone());
-                if let Some(base_origin) = status.base_url.as_deref() {
-                    // Best-effort: if any CLI proxy is enabled, keep its config aligned with the actual gateway port.
-                    let base_origin = base_origin.to_string();
-                    let _ = blocking::run("startup_cli_proxy_sync_enabled", {
-                        let app_handle = app_handle.clone();
-                        move || cli_proxy::sync_enabled(&app_handle, &base_origin)
-                    })
-                    .await;
-                }
-            });
-
+            spawn_startup_tasks(app.handle().clone());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             settings_get,
             app_about_get,
             notice_send,
+            notice_history_list,
+            notice_history_clear,
+            notice_test_channel,
             settings_set,
             settings_gateway_rectifier_set,
             settings_circuit_breaker_notice_set,
             settings_codex_session_id_completion_set,
+            settings_circuit_recovery_notice_set,
+            settings_ui_language_set,
+            settings_low_power_mode_set,
+            settings_notice_channels_set,
+            settings_wsl_distro_overrides_set,
+            settings_gateway_access_token_set,
+            gateway_access_token_generate,
+            settings_gateway_tls_set,
+            settings_gateway_tls_hosts_set,
+            settings_gateway_extra_listeners_set,
+            settings_gateway_client_keys_set,
+            settings_gateway_ip_allowlist_set,
+            settings_intercept_rules_set,
+            settings_header_sanitize_rules_set,
+            settings_failover_rules_set,
+            settings_request_redaction_set,
+            settings_inflight_dedup_mode_set,
+            settings_priority_lanes_set,
+            settings_latency_probe_interval_set,
+            settings_response_cache_ttl_set,
+            settings_response_cache_completions_set,
+            settings_prompt_cache_injection_set,
+            settings_exclude_reasoning_from_logs_set,
+            settings_cli_stream_caps_set,
+            settings_retry_backoff_set,
+            settings_forward_trace_headers_set,
+            settings_capture_sse_events_set,
+            settings_attempt_log_retention_set,
+            settings_latency_routing_set,
+            settings_recent_error_cache_set,
+            settings_hedged_requests_set,
+            settings_stream_tool_pause_grace_set,
+            settings_gateway_drain_timeout_set,
+            settings_update_channel_set,
+            updater_check,
+            updater_download_and_install,
+            updater_background_download_start,
+            updater_background_download_state,
+            updater_rollback_info,
+            updater_rollback,
+            settings_update_background_download_set,
+            settings_update_mirror_set,
+            settings_egress_network_set,
+            settings_notice_quiet_hours_set,
+            power_status_get,
             cli_manager_claude_info_get,
             cli_manager_codex_info_get,
             cli_manager_codex_config_get,
@@ -160,21 +170,39 @@ pub fn run() {
             cli_manager_claude_env_set,
             cli_manager_claude_settings_get,
             cli_manager_claude_settings_set,
+            cli_manager_claude_hooks_get,
+            cli_manager_claude_hook_create,
+            cli_manager_claude_hook_set_enabled,
             gateway_start,
             gateway_stop,
             gateway_status,
             gateway_check_port_available,
+            gateway_tls_ca_export,
             wsl_detect,
             wsl_host_address_get,
+            wsl_networking_get,
+            wsl_port_proxy_get,
+            wsl_port_proxy_remove,
             wsl_config_status_get,
             wsl_configure_clients,
             gateway_sessions_list,
             providers_list,
             provider_upsert,
             provider_set_enabled,
+            provider_claude_models_set,
             provider_delete,
+            provider_import_from_text,
             providers_reorder,
             base_url_ping_ms,
+            base_url_probe_history,
+            providers_speedtest,
+            provider_canary_start,
+            provider_canary_cancel,
+            provider_canaries_list,
+            provider_ab_test_start,
+            provider_ab_test_cancel,
+            provider_ab_tests_list,
+            provider_ab_test_report,
             claude_provider_validate_model,
             claude_provider_get_api_key_plaintext,
             claude_validation_history_list,
@@ -182,11 +210,21 @@ pub fn run() {
             sort_modes_list,
             sort_mode_create,
             sort_mode_rename,
+            sort_mode_settings_set,
+            sort_mode_strategy_set,
             sort_mode_delete,
+            sort_mode_export,
+            sort_mode_import_preview,
+            sort_mode_import,
             sort_mode_active_list,
             sort_mode_active_set,
             sort_mode_providers_list,
             sort_mode_providers_set_order,
+            sort_mode_provider_weights_list,
+            sort_mode_provider_weight_set,
+            sort_mode_schedule_list,
+            sort_mode_schedule_set,
+            sort_mode_switch_events_list,
             model_prices_list,
             model_price_upsert,
             model_prices_sync_basellm,
@@ -197,6 +235,8 @@ pub fn run() {
             prompt_upsert,
             prompt_set_enabled,
             prompt_delete,
+            prompt_codex_project_agents_get,
+            prompt_codex_project_agents_set,
             mcp_servers_list,
             mcp_server_upsert,
             mcp_server_set_enabled,
@@ -216,70 +256,195 @@ pub fn run() {
             skills_paths_get,
             request_logs_list,
             request_logs_list_all,
+            request_logs_list_before,
+            request_logs_list_before_all,
+            request_logs_list_filtered,
             request_logs_list_after_id,
             request_logs_list_after_id_all,
             request_log_get,
             request_log_get_by_trace_id,
+            request_logs_by_client_trace_id,
             request_attempt_logs_by_trace_id,
+            request_sse_transcript_get,
+            request_logs_client_usage,
+            request_logs_response_fixer_dry_run_summary,
+            request_logs_usage_estimate_drift,
             app_data_dir_get,
             db_disk_usage_get,
             request_logs_clear_all,
             app_data_reset,
+            crash_reports_list,
+            crash_reports_clear,
+            diagnostic_bundle_export,
+            issue_draft_generate,
             app_exit,
             app_restart,
             gateway_circuit_status,
+            gateway_route_explain,
+            gateway_recent_events,
+            gateway_inflight_list,
+            gateway_request_cancel,
+            gateway_pause_set,
+            gateway_self_test_get,
+            gateway_self_test_run,
+            gateway_network_test,
             gateway_circuit_reset_provider,
             gateway_circuit_reset_cli,
+            gateway_error_cache_clear,
             usage_summary,
             usage_summary_v2,
             usage_leaderboard_provider,
             usage_leaderboard_day,
             usage_leaderboard_v2,
             usage_hourly_series,
+            usage_tool_usage,
+            usage_codex_session_completions,
+            tokens_estimate,
             cost_summary_v1,
             cost_trend_v1,
             cost_breakdown_provider_v1,
             cost_breakdown_model_v1,
             cost_scatter_cli_provider_model_v1,
             cost_top_requests_v1,
+            cost_prefix_affinity_v1,
             cost_backfill_missing_v1,
+            cost_provider_spend_v1,
             cli_proxy_status_all,
             cli_proxy_set_enabled,
             cli_proxy_sync_enabled
         ])
-        .build(tauri::generate_context!())
+        .build(context)
         .expect("error while building tauri application");
 
-    app.run(|app_handle, event| {
-        if let tauri::RunEvent::ExitRequested { api, code, .. } = &event {
-            // Note: `prevent_exit` is ignored for restart requests.
-            // For app_restart we run cleanup explicitly before requesting restart.
-            if *code != Some(tauri::RESTART_EXIT_CODE) {
-                api.prevent_exit();
+    app.run(on_run_event);
+}
 
-                if EXIT_CLEANUP_SPAWNED.swap(true, Ordering::SeqCst) {
-                    return;
-                }
+fn setup_common(app: &tauri::App, admin_port: u16) {
+    crate::app::logging::init(app.handle());
+    crash_report::install_panic_hook(app.handle());
+    admin_api::spawn(app.handle(), admin_port);
+    updater::record_running_version(app.handle());
 
-                tracing::info!("收到退出请求，开始清理...");
-                let app_handle = app_handle.clone();
-                tauri::async_runtime::spawn(async move {
-                    crate::app::cleanup::cleanup_before_exit(&app_handle).await;
-                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                    std::process::exit(0);
-                });
+    #[cfg(debug_assertions)]
+    {
+        let enabled = std::env::var("AIO_CODING_HUB_DEV_DIAGNOSTICS")
+            .ok()
+            .map(|v| v.trim().to_ascii_lowercase())
+            .is_some_and(|v| v == "1" || v == "true" || v == "yes");
+        if enabled {
+            let identifier = &app.config().identifier;
+            let product_name = app.config().product_name.as_deref().unwrap_or("<missing>");
+            tracing::info!(identifier = %identifier, "[dev] tauri identifier");
+            tracing::info!(product_name = %product_name, "[dev] productName");
+            if let Ok(dotdir_name) = std::env::var("AIO_CODING_HUB_DOTDIR_NAME") {
+                tracing::info!(dotdir_name = %dotdir_name, "[dev] AIO_CODING_HUB_DOTDIR_NAME");
+            }
+            if let Ok(dir) = app_paths::app_data_dir(app.handle()) {
+                tracing::info!(dir = %dir.display(), "[dev] app data dir");
             }
         }
+    }
+}
+
+/// DB init, background loops and gateway autostart; shared by desktop and headless runs.
+fn spawn_startup_tasks(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let db_state = app_handle.state::<DbInitState>();
+        let db = match ensure_db_ready(app_handle.clone(), db_state.inner()).await {
+            Ok(db) => db,
+            Err(err) => {
+                tracing::error!("数据库初始化失败: {}", err);
+                crash_report::record_task_failure(&app_handle, "startup_db_init", &err);
+                return;
+            }
+        };
+
+        usage_rollups::spawn_refresh_loop(app_handle.clone(), db.clone());
+        sort_mode_schedules::spawn_loop(app_handle.clone(), db.clone());
 
-        #[cfg(target_os = "macos")]
-        if let tauri::RunEvent::Reopen {
-            has_visible_windows,
-            ..
-        } = event
+        // M1: auto-start gateway on app launch (required for seamless CLI proxy experience).
+        // Port conflicts are handled by the gateway's bind-first-available strategy.
+        let settings = match blocking::run("startup_read_settings", {
+            let app_handle = app_handle.clone();
+            move || Ok(settings::read(&app_handle).unwrap_or_default())
+        })
+        .await
+        {
+            Ok(cfg) => cfg,
+            Err(err) => {
+                tracing::warn!("配置读取失败，使用默认值: {}", err);
+                settings::AppSettings::default()
+            }
+        };
+
+        app_handle
+            .state::<resident::ResidentState>()
+            .set_tray_enabled(settings.tray_enabled);
+
+        let status = match blocking::run("startup_gateway_autostart", {
+            let app_handle = app_handle.clone();
+            let db = db.clone();
+            move || {
+                let state = app_handle.state::<GatewayState>();
+                let mut manager = state.0.lock_or_recover();
+                manager.start(&app_handle, db, Some(settings.preferred_port))
+            }
+        })
+        .await
         {
-            if !has_visible_windows {
-                resident::show_main_window(app_handle);
+            Ok(status) => status,
+            Err(err) => {
+                tracing::error!("网关自动启动失败: {}", err);
+                crash_report::record_task_failure(&app_handle, "startup_gateway_autostart", &err);
+                return;
             }
+        };
+
+        let _ = app_handle.emit("gateway:status", status.clone());
+        if let Some(base_origin) = status.base_url.as_deref() {
+            // Best-effort: if any CLI proxy is enabled, keep its config aligned with the actual gateway port.
+            let base_origin = base_origin.to_string();
+            let _ = blocking::run("startup_cli_proxy_sync_enabled", {
+                let app_handle = app_handle.clone();
+                move || cli_proxy::sync_enabled(&app_handle, &base_origin)
+            })
+            .await;
         }
+
+        // Loopback self-test per enabled CLI; result goes to `gateway:self_test`.
+        let _ = gateway::self_test::run(&app_handle, &status).await;
     });
 }
+
+fn on_run_event(app_handle: &tauri::AppHandle, event: tauri::RunEvent) {
+    if let tauri::RunEvent::ExitRequested { api, code, .. } = &event {
+        // Note: `prevent_exit` is ignored for restart requests.
+        // For app_restart we run cleanup explicitly before requesting restart.
+        if *code != Some(tauri::RESTART_EXIT_CODE) {
+            api.prevent_exit();
+
+            if EXIT_CLEANUP_SPAWNED.swap(true, Ordering::SeqCst) {
+                return;
+            }
+
+            tracing::info!("收到退出请求，开始清理...");
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                crate::app::cleanup::cleanup_before_exit(&app_handle).await;
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                std::process::exit(0);
+            });
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    if let tauri::RunEvent::Reopen {
+        has_visible_windows,
+        ..
+    } = event
+    {
+        if !has_visible_windows {
+            resident::show_main_window(app_handle);
+        }
+    }
+}
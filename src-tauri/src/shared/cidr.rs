@@ -0,0 +1,91 @@
+//! Usage: Minimal CIDR parsing / matching for the gateway IP allowlist.
+
+use std::net::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Parses `10.0.0.0/8`, `fd00::/8` or a bare address (treated as a single host).
+    pub(crate) fn parse(input: &str) -> Result<Self, String> {
+        let raw = input.trim();
+        let (addr_raw, prefix_raw) = match raw.split_once('/') {
+            Some((addr, prefix)) => (addr.trim(), Some(prefix.trim())),
+            None => (raw, None),
+        };
+        let addr: IpAddr = addr_raw
+            .parse()
+            .map_err(|_| format!("SEC_INVALID_INPUT: invalid CIDR address={raw}"))?;
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_raw {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("SEC_INVALID_INPUT: invalid CIDR prefix={raw}"))?,
+            None => max_len,
+        };
+        Ok(Self { addr, prefix_len })
+    }
+
+    pub(crate) fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => prefix_matches(
+                u32::from(net) as u128,
+                u32::from(ip) as u128,
+                32,
+                self.prefix_len,
+            ),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(net), u128::from(ip), 128, self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(net: u128, ip: u128, bits: u32, prefix_len: u8) -> bool {
+    if prefix_len == 0 {
+        return true;
+    }
+    let shift = bits - prefix_len as u32;
+    (net >> shift) == (ip >> shift)
+}
+
+/// Parses every entry; invalid entries are skipped (settings validation rejects them on save).
+pub(crate) fn parse_list(entries: &[String]) -> Vec<IpCidr> {
+    entries
+        .iter()
+        .filter_map(|entry| IpCidr::parse(entry).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cidr_matches_v4_v6_and_mapped_addresses() {
+        let lan = IpCidr::parse("192.168.1.0/24").unwrap();
+        assert!(lan.contains("192.168.1.42".parse().unwrap()));
+        assert!(!lan.contains("192.168.2.1".parse().unwrap()));
+        assert!(lan.contains("::ffff:192.168.1.7".parse().unwrap()));
+
+        let host = IpCidr::parse("10.0.0.5").unwrap();
+        assert!(host.contains("10.0.0.5".parse().unwrap()));
+        assert!(!host.contains("10.0.0.6".parse().unwrap()));
+
+        let ula = IpCidr::parse("fd00::/8").unwrap();
+        assert!(ula.contains("fd12::1".parse().unwrap()));
+        assert!(!ula.contains("192.168.1.1".parse().unwrap()));
+
+        assert!(IpCidr::parse("0.0.0.0/0")
+            .unwrap()
+            .contains("8.8.8.8".parse().unwrap()));
+        assert!(IpCidr::parse("10.0.0.0/33").is_err());
+        assert!(IpCidr::parse("lan").is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //! Usage: In-memory circuit breaker to protect providers from repeated failures.
 
 use super::mutex_ext::MutexExt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 use tokio::sync::mpsc;
 
@@ -92,6 +92,8 @@ struct ProviderHealth {
     open_until: Option<i64>,
     cooldown_until: Option<i64>,
     updated_at: i64,
+    // Set when an open circuit expires; cleared by the first success (recovery confirmed) or re-open.
+    probing: bool,
 }
 
 impl ProviderHealth {
@@ -104,6 +106,7 @@ impl ProviderHealth {
                 open_until: None,
                 cooldown_until: None,
                 updated_at: now_unix,
+                probing: false,
             },
         )
     }
@@ -132,6 +135,7 @@ impl CircuitBreaker {
                     open_until: item.open_until,
                     cooldown_until: None,
                     updated_at: item.updated_at,
+                    probing: false,
                 },
             );
         }
@@ -176,6 +180,7 @@ impl CircuitBreaker {
                     entry.failure_count = 0;
                     entry.open_until = None;
                     entry.updated_at = now_unix;
+                    entry.probing = true;
 
                     let t = CircuitTransition {
                         prev_state: prev,
@@ -208,6 +213,7 @@ impl CircuitBreaker {
 
     pub fn record_success(&self, provider_id: i64, now_unix: i64) -> CircuitChange {
         let mut upsert: Option<CircuitPersistedState> = None;
+        let mut transition: Option<CircuitTransition> = None;
 
         let (before, after) = {
             let mut guard = self.health.lock_or_recover();
@@ -225,6 +231,15 @@ impl CircuitBreaker {
                         entry.updated_at = now_unix;
                         upsert = Some(self.persisted_from_health(provider_id, entry));
                     }
+                    if entry.probing {
+                        entry.probing = false;
+                        transition = Some(CircuitTransition {
+                            prev_state: CircuitState::Closed,
+                            next_state: CircuitState::Closed,
+                            reason: "RECOVERY_CONFIRMED",
+                            snapshot: self.snapshot_from_health(provider_id, entry),
+                        });
+                    }
                 }
                 CircuitState::Open => {}
             }
@@ -240,7 +255,7 @@ impl CircuitBreaker {
         CircuitChange {
             before,
             after,
-            transition: None,
+            transition,
         }
     }
 
@@ -264,6 +279,7 @@ impl CircuitBreaker {
                     if entry.failure_count >= self.config.failure_threshold {
                         let prev = entry.state;
                         entry.state = CircuitState::Open;
+                        entry.probing = false;
                         entry.open_until =
                             Some(now_unix.saturating_add(self.config.open_duration_secs));
 
@@ -369,6 +385,7 @@ impl CircuitBreaker {
             entry.open_until = None;
             entry.cooldown_until = None;
             entry.updated_at = now_unix;
+            entry.probing = false;
 
             let after = self.snapshot_from_health(provider_id, entry);
             let upsert = self.persisted_from_health(provider_id, entry);
@@ -380,6 +397,19 @@ impl CircuitBreaker {
         after
     }
 
+    /// Providers whose circuit is open and not yet expired (read-only; no OPEN_EXPIRED transition).
+    pub fn open_provider_ids(&self, now_unix: i64) -> HashSet<i64> {
+        let guard = self.health.lock_or_recover();
+        guard
+            .iter()
+            .filter(|(_, health)| {
+                health.state == CircuitState::Open
+                    && health.open_until.is_some_and(|t| now_unix < t)
+            })
+            .map(|(provider_id, _)| *provider_id)
+            .collect()
+    }
+
     fn try_persist(&self, item: CircuitPersistedState) {
         if let Some(tx) = &self.persist_tx {
             let _ = tx.try_send(item);
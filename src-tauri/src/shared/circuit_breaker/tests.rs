@@ -40,6 +40,26 @@ fn open_expires_to_closed() {
     assert!(check.transition.is_some());
 }
 
+#[test]
+fn first_success_after_open_expired_confirms_recovery() {
+    let cb = breaker();
+    let pid = 1;
+    let now = 1_000;
+    for i in 1..=DEFAULT_FAILURE_THRESHOLD {
+        cb.record_failure(pid, now + i as i64);
+    }
+    let open_until = cb.snapshot(pid, now + 10).open_until.expect("open_until");
+    cb.should_allow(pid, open_until);
+
+    let change = cb.record_success(pid, open_until + 1);
+    let t = change.transition.expect("recovery transition");
+    assert_eq!(t.reason, "RECOVERY_CONFIRMED");
+    assert_eq!(t.next_state, CircuitState::Closed);
+
+    let change = cb.record_success(pid, open_until + 2);
+    assert!(change.transition.is_none());
+}
+
 #[test]
 fn success_clears_failure_count() {
     let cb = breaker();
@@ -76,3 +96,19 @@ fn reset_clears_open_and_cooldown() {
     let allow = cb.should_allow(pid, now + 21);
     assert!(allow.allow);
 }
+
+#[test]
+fn open_provider_ids_lists_only_unexpired_open_circuits() {
+    let cb = breaker();
+    let now = 1_000;
+    for i in 1..=DEFAULT_FAILURE_THRESHOLD {
+        cb.record_failure(1, now + i as i64);
+    }
+    cb.record_failure(2, now);
+
+    let open_until = cb.snapshot(1, now + 10).open_until.expect("open_until");
+    assert_eq!(cb.open_provider_ids(now + 10), HashSet::from([1]));
+    assert!(cb.open_provider_ids(open_until).is_empty());
+    // read-only: the circuit is still open until `should_allow` observes the expiry
+    assert_eq!(cb.snapshot(1, open_until).state, CircuitState::Open);
+}
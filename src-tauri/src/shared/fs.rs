@@ -82,6 +82,47 @@ pub(crate) fn write_file_atomic(path: &Path, bytes: &[u8]) -> Result<(), String>
     Ok(())
 }
 
+/// Like [`write_file_atomic`], but the file is created owner-only (0600) on unix so secrets
+/// (tokens, private keys) are never readable by other local users, not even briefly.
+pub(crate) fn write_file_atomic_private(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create dir {}: {e}", parent.display()))?;
+    }
+
+    let file_name = path.file_name().and_then(|v| v.to_str()).unwrap_or("file");
+    let tmp_path = path.with_file_name(format!("{file_name}.aio-tmp"));
+    // `mode` only applies on creation, so never reuse a leftover temp file.
+    let _ = std::fs::remove_file(&tmp_path);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&tmp_path)
+        .map_err(|e| format!("failed to create temp file {}: {e}", tmp_path.display()))?;
+    file.write_all(bytes)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("failed to write temp file {}: {e}", tmp_path.display()))?;
+    drop(file);
+
+    // Windows rename requires target not to exist.
+    if path.exists() {
+        let _ = std::fs::remove_file(path);
+    }
+
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("failed to finalize file {}: {e}", path.display()))?;
+
+    Ok(())
+}
+
 pub(crate) fn write_file_atomic_if_changed(path: &Path, bytes: &[u8]) -> Result<bool, String> {
     if let Ok(existing) = std::fs::read(path) {
         if existing == bytes {
@@ -146,6 +187,26 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn write_file_atomic_private_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = unique_tmp_dir();
+        let path = dir.join("secret.json");
+        std::fs::write(&path, b"old").expect("seed");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).expect("chmod");
+
+        write_file_atomic_private(&path, b"token").expect("write_file_atomic_private");
+        assert_eq!(std::fs::read(&path).expect("read"), b"token");
+        let mode = std::fs::metadata(&path)
+            .expect("metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn write_file_atomic_if_changed_is_false_when_unchanged() {
         let dir = unique_tmp_dir();
@@ -1,10 +1,12 @@
 //! Usage: Cross-cutting utilities shared across domains (low-level helpers, pure logic).
 
 pub(crate) mod blocking;
+pub(crate) mod cidr;
 pub(crate) mod circuit_breaker;
 pub(crate) mod cli_key;
 pub(crate) mod fs;
 pub(crate) mod mutex_ext;
+pub(crate) mod reasoning_redaction;
 pub(crate) mod sqlite;
 pub(crate) mod text;
 pub(crate) mod time;
@@ -0,0 +1,162 @@
+//! Usage: Strip thinking / reasoning content from JSON payloads and SSE excerpts before they are
+//! persisted (enabled by `exclude_reasoning_from_logs`).
+//!
+//! Covers Anthropic `thinking` / `redacted_thinking` blocks and deltas, OpenAI Responses
+//! `reasoning` items and reasoning summary events, and `reasoning_content` style fields.
+
+use serde_json::Value;
+
+pub(crate) const REDACTED_PLACEHOLDER: &str = "[REDACTED:reasoning]";
+
+const REASONING_BLOCK_TYPES: &[&str] = &["thinking", "redacted_thinking", "reasoning"];
+const REASONING_STRING_KEYS: &[&str] = &[
+    "thinking",
+    "reasoning",
+    "reasoning_content",
+    "reasoning_text",
+    "thinking_preview",
+    "thinking_full",
+];
+// 块 / 事件内保留的结构字段（其余内容一律替换）
+const PRESERVED_BLOCK_KEYS: &[&str] = &["type", "index", "id", "item_id", "output_index"];
+
+fn type_of(value: &Value) -> Option<&str> {
+    value.get("type").and_then(Value::as_str)
+}
+
+fn is_reasoning_block(value: &Value) -> bool {
+    type_of(value).is_some_and(|t| REASONING_BLOCK_TYPES.contains(&t))
+}
+
+fn is_reasoning_event(value: &Value) -> bool {
+    type_of(value).is_some_and(|t| t.starts_with("response.reasoning"))
+}
+
+fn redact_block_fields(obj: &mut serde_json::Map<String, Value>) -> usize {
+    let mut count = 0;
+    for (key, value) in obj.iter_mut() {
+        if PRESERVED_BLOCK_KEYS.contains(&key.as_str()) || value.is_null() {
+            continue;
+        }
+        *value = Value::String(REDACTED_PLACEHOLDER.to_string());
+        count += 1;
+    }
+    count
+}
+
+/// Removes reasoning blocks from arrays and masks reasoning text elsewhere; returns the number of
+/// removed / masked items.
+pub(crate) fn strip_reasoning_json(value: &mut Value) -> usize {
+    match value {
+        Value::Array(items) => {
+            let before = items.len();
+            items.retain(|item| !is_reasoning_block(item));
+            let removed = before - items.len();
+            removed + items.iter_mut().map(strip_reasoning_json).sum::<usize>()
+        }
+        Value::Object(_) if is_reasoning_block(value) || is_reasoning_event(value) => {
+            value.as_object_mut().map(redact_block_fields).unwrap_or(0)
+        }
+        Value::Object(obj) => {
+            let mut count = 0;
+            for (key, child) in obj.iter_mut() {
+                let masks_string = REASONING_STRING_KEYS.contains(&key.as_str())
+                    && child.as_str().is_some_and(|s| !s.is_empty());
+                if masks_string {
+                    *child = Value::String(REDACTED_PLACEHOLDER.to_string());
+                    count += 1;
+                } else {
+                    count += strip_reasoning_json(child);
+                }
+            }
+            count
+        }
+        _ => 0,
+    }
+}
+
+/// Same as [`strip_reasoning_json`] for a serialized JSON document; non-JSON input is returned as-is.
+pub(crate) fn strip_reasoning_json_text(text: &str) -> String {
+    match serde_json::from_str::<Value>(text) {
+        Ok(mut value) => {
+            if strip_reasoning_json(&mut value) == 0 {
+                return text.to_string();
+            }
+            serde_json::to_string(&value).unwrap_or_else(|_| text.to_string())
+        }
+        Err(_) => text.to_string(),
+    }
+}
+
+/// Line-based variant for raw SSE excerpts: every `data:` payload is stripped; payloads that cannot
+/// be parsed (e.g. a truncated last line) are masked when they mention reasoning.
+pub(crate) fn strip_reasoning_sse_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (idx, line) in text.split('\n').enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        let Some(payload) = line.strip_prefix("data:") else {
+            out.push_str(line);
+            continue;
+        };
+        match serde_json::from_str::<Value>(payload.trim()) {
+            Ok(mut value) => {
+                if strip_reasoning_json(&mut value) == 0 {
+                    out.push_str(line);
+                } else {
+                    out.push_str("data: ");
+                    out.push_str(&serde_json::to_string(&value).unwrap_or_default());
+                }
+            }
+            Err(_) if payload.contains("thinking") || payload.contains("reasoning") => {
+                out.push_str("data: ");
+                out.push_str(REDACTED_PLACEHOLDER);
+            }
+            Err(_) => out.push_str(line),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_thinking_blocks_and_reasoning_fields() {
+        let mut value = serde_json::json!({
+            "thinking": {"type": "enabled", "budget_tokens": 1024},
+            "messages": [{
+                "role": "assistant",
+                "content": [
+                    {"type": "thinking", "thinking": "secret plan", "signature": "sig"},
+                    {"type": "text", "text": "answer"}
+                ]
+            }],
+            "signals": {"thinking_preview": "secret plan"},
+            "choices": [{"delta": {"reasoning_content": "secret"}}],
+        });
+        assert_eq!(strip_reasoning_json(&mut value), 3);
+        let text = value.to_string();
+        assert!(!text.contains("secret"));
+        assert_eq!(value["thinking"]["budget_tokens"], 1024);
+        assert_eq!(value["messages"][0]["content"][0]["text"], "answer");
+    }
+
+    #[test]
+    fn strips_sse_deltas_and_masks_truncated_lines() {
+        let sse = concat!(
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"secret\"}}\n",
+            "data: {\"type\":\"response.reasoning_summary_text.delta\",\"item_id\":\"rs_1\",\"delta\":\"secret\"}\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"text_delta\",\"text\":\"ok\"}}\n",
+            "data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"sec"
+        );
+        let out = strip_reasoning_sse_text(sse);
+        assert!(!out.contains("secret"));
+        assert!(!out.contains("\"sec"));
+        assert!(out.contains("\"text\":\"ok\""));
+        assert!(out.starts_with("event: content_block_delta\n"));
+    }
+}
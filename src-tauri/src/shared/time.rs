@@ -1,3 +1,4 @@
+use chrono::Timelike;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn now_unix_seconds() -> i64 {
@@ -6,3 +7,50 @@ pub fn now_unix_seconds() -> i64 {
         .map(|d| d.as_secs() as i64)
         .unwrap_or(0)
 }
+
+pub fn now_unix_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Whether `now_minutes` (minutes since local midnight) falls in `[start, end)`; windows may wrap
+/// midnight (e.g. 23:00-08:00) and `start == end` is an empty window.
+pub fn in_daily_window(now_minutes: u32, start: u32, end: u32) -> bool {
+    if start == end {
+        return false;
+    }
+    if start < end {
+        now_minutes >= start && now_minutes < end
+    } else {
+        now_minutes >= start || now_minutes < end
+    }
+}
+
+// Local wall-clock minutes since midnight.
+pub fn local_minutes_of_day() -> u32 {
+    let now = chrono::Local::now();
+    now.hour() * 60 + now.minute()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::in_daily_window;
+
+    #[test]
+    fn in_daily_window_handles_midnight_wrap() {
+        let (start, end) = (23 * 60, 8 * 60);
+        assert!(in_daily_window(23 * 60 + 30, start, end));
+        assert!(in_daily_window(3 * 60, start, end));
+        assert!(!in_daily_window(8 * 60, start, end));
+        assert!(!in_daily_window(12 * 60, start, end));
+    }
+
+    #[test]
+    fn in_daily_window_same_day_and_empty() {
+        assert!(in_daily_window(13 * 60, 12 * 60, 14 * 60));
+        assert!(!in_daily_window(14 * 60, 12 * 60, 14 * 60));
+        assert!(!in_daily_window(10 * 60, 10 * 60, 10 * 60));
+    }
+}
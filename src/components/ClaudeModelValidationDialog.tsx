@@ -13,7 +13,18 @@ import {
   type ClaudeModelValidationRunRow,
 } from "../services/claudeModelValidationHistory";
 import { modelPricesList, type ModelPriceSummary } from "../services/modelPrices";
-import { baseUrlPingMs, providersList, type ProviderSummary } from "../services/providers";
+import {
+  baseUrlPingMs,
+  providerClaudeModelsSet,
+  providersList,
+  type ClaudeModels,
+  type ProviderSummary,
+} from "../services/providers";
+import {
+  diffClaudeModelSlots,
+  suggestClaudeModelSlots,
+  type ClaudeModelValidationOutcome,
+} from "../services/claudeModelSlots";
 import {
   DEFAULT_CLAUDE_VALIDATION_TEMPLATE_KEY,
   buildClaudeValidationRequestJson,
@@ -59,12 +70,14 @@ import {
   Activity,
   Copy,
   FileJson,
+  Wand2,
 } from "lucide-react";
 
 type ClaudeModelValidationDialogProps = {
   open: boolean;
   onOpenChange: (open: boolean) => void;
   provider: ProviderSummary | null;
+  onProviderUpdated?: (provider: ProviderSummary) => void;
 };
 
 type SuiteMeta = {
@@ -210,6 +223,7 @@ export function ClaudeModelValidationDialog({
   open,
   onOpenChange,
   provider,
+  onProviderUpdated,
 }: ClaudeModelValidationDialogProps) {
   const providerRef = useRef(provider);
   useEffect(() => {
@@ -247,6 +261,13 @@ export function ClaudeModelValidationDialog({
   const historyReqSeqRef = useRef(0);
   const [historyClearing, setHistoryClearing] = useState(false);
   const [confirmClearOpen, setConfirmClearOpen] = useState(false);
+  const [slotSuggestOpen, setSlotSuggestOpen] = useState(false);
+  const [slotApplying, setSlotApplying] = useState(false);
+  // 本次打开期间已保存的槽位（provider prop 由父组件持有，不随保存刷新）
+  const [savedClaudeModels, setSavedClaudeModels] = useState<{
+    providerId: number;
+    models: ClaudeModels;
+  } | null>(null);
 
   const [modelPrices, setModelPrices] = useState<ModelPriceSummary[]>([]);
   const [modelPricesLoading, setModelPricesLoading] = useState(false);
@@ -327,6 +348,10 @@ export function ClaudeModelValidationDialog({
 
   function handleOpenChange(nextOpen: boolean) {
     // 防止确认弹层打开时误关主 Dialog（ESC/点遮罩/点右上角关闭等）。
+    if (!nextOpen && slotSuggestOpen) {
+      if (!slotApplying) setSlotSuggestOpen(false);
+      return;
+    }
     if (!nextOpen && confirmClearOpen) {
       setConfirmClearOpen(false);
       return;
@@ -793,6 +818,42 @@ export function ClaudeModelValidationDialog({
     }
   }
 
+  async function applySlotSuggestion() {
+    if (slotApplying) return;
+
+    const curProvider = providerRef.current;
+    if (!open || !curProvider || slotChanges.length === 0) return;
+
+    const current =
+      savedClaudeModels?.providerId === curProvider.id
+        ? savedClaudeModels.models
+        : curProvider.claude_models;
+    const nextModels = { ...current };
+    for (const change of slotChanges) nextModels[change.key] = change.next;
+
+    setSlotApplying(true);
+    try {
+      const updated = await providerClaudeModelsSet(curProvider.id, nextModels);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      logToConsole("info", "按验证结果填充模型槽位", {
+        provider_id: updated.id,
+        claude_models: updated.claude_models,
+      });
+      setSavedClaudeModels({ providerId: updated.id, models: updated.claude_models });
+      toast(`已更新 ${slotChanges.length} 个模型槽位`);
+      onProviderUpdated?.(updated);
+      setSlotSuggestOpen(false);
+    } catch (err) {
+      logToConsole("error", "填充模型槽位失败", { error: String(err) });
+      toast(`填充模型槽位失败：${String(err)}`);
+    } finally {
+      setSlotApplying(false);
+    }
+  }
+
   async function clearProviderHistory() {
     if (historyClearing) return;
 
@@ -938,6 +999,32 @@ export function ClaudeModelValidationDialog({
     return out.sort((a, b) => b.latestRunId - a.latestRunId);
   }, [historyRuns]);
 
+  // 按验证结果推荐模型槽位：每个模型只看最近一次验证（套件全部通过才算通过）
+  const slotChanges = useMemo(() => {
+    if (!provider) return [];
+    const outcomes: ClaudeModelValidationOutcome[] = historyGroups.flatMap((group) => {
+      const model = group.runs.find((r) => r.evaluation.derived.requestedModel)?.evaluation
+        .derived.requestedModel;
+      if (!model) return [];
+      return [
+        {
+          model,
+          pass: group.overallPass,
+          thinking: group.runs.some(
+            (r) =>
+              r.evaluation.derived.thinkingChars > 0 ||
+              (r.run.parsed_result?.signals as any)?.thinking_block_seen === true
+          ),
+        },
+      ];
+    });
+    const current =
+      savedClaudeModels?.providerId === provider.id
+        ? savedClaudeModels.models
+        : provider.claude_models;
+    return diffClaudeModelSlots(current, suggestClaudeModelSlots(outcomes));
+  }, [historyGroups, provider, savedClaudeModels]);
+
   const selectedHistoryGroup = useMemo(() => {
     if (!selectedHistoryKey) return null;
     return historyGroups.find((g) => g.key === selectedHistoryKey) ?? null;
@@ -1106,6 +1193,16 @@ export function ClaudeModelValidationDialog({
                     <span className="text-sm font-semibold text-slate-900">历史记录</span>
                   </div>
                   <div className="flex items-center gap-1">
+                    <Button
+                      onClick={() => setSlotSuggestOpen(true)}
+                      variant="ghost"
+                      size="sm"
+                      className="h-8 w-8 p-0"
+                      disabled={historyLoading || slotChanges.length === 0}
+                      title="按验证结果填充模型槽位"
+                    >
+                      <Wand2 className="h-4 w-4" />
+                    </Button>
                     <Button
                       onClick={() => void refreshHistory({ selectLatest: false })}
                       variant="ghost"
@@ -1551,6 +1648,65 @@ export function ClaudeModelValidationDialog({
             document.body
           )
         : null}
+
+      {slotSuggestOpen && typeof document !== "undefined"
+        ? createPortal(
+            <div className="fixed inset-0 z-[60]">
+              <div
+                className="absolute inset-0 bg-black/40"
+                onClick={() => {
+                  if (slotApplying) return;
+                  setSlotSuggestOpen(false);
+                }}
+              />
+              <div className="absolute inset-0 flex items-center justify-center p-4">
+                <div className="w-full max-w-md overflow-hidden rounded-2xl border border-slate-200 bg-white shadow-card">
+                  <div className="border-b border-slate-200 px-5 py-4">
+                    <div className="text-sm font-semibold text-slate-900">填充模型槽位？</div>
+                    <div className="mt-1 text-xs text-slate-600">
+                      根据最近通过验证的模型，更新{" "}
+                      <span className="font-medium text-slate-900">
+                        {provider?.name ?? "当前 Provider"}
+                      </span>{" "}
+                      的以下槽位（未列出的保持不变）：
+                    </div>
+                  </div>
+                  <div className="space-y-2 px-5 py-4 text-xs">
+                    {slotChanges.map((change) => (
+                      <div key={change.key} className="flex items-center justify-between gap-3">
+                        <span className="shrink-0 text-slate-500">{change.label}</span>
+                        <span className="truncate font-mono">
+                          <span className="text-slate-400">{change.prev ?? "未设置"}</span>
+                          <span className="mx-1 text-slate-400">→</span>
+                          <span className="text-slate-900">{change.next}</span>
+                        </span>
+                      </div>
+                    ))}
+                  </div>
+                  <div className="flex items-center justify-end gap-2 border-t border-slate-100 px-5 py-4">
+                    <Button
+                      variant="secondary"
+                      size="md"
+                      disabled={slotApplying}
+                      onClick={() => setSlotSuggestOpen(false)}
+                    >
+                      取消
+                    </Button>
+                    <Button
+                      variant="primary"
+                      size="md"
+                      disabled={slotApplying || slotChanges.length === 0}
+                      onClick={() => void applySlotSuggestion()}
+                    >
+                      {slotApplying ? "保存中…" : "应用"}
+                    </Button>
+                  </div>
+                </div>
+              </div>
+            </div>,
+            document.body
+          )
+        : null}
     </Dialog>
   );
 }
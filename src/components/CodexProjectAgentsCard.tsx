@@ -0,0 +1,147 @@
+// Usage: Edit the aio-coding-hub managed block of a project's `AGENTS.md` (Codex). Content outside the block is preserved.
+
+import { useState } from "react";
+import { toast } from "sonner";
+import { FolderGit2 } from "lucide-react";
+import { logToConsole } from "../services/consoleLog";
+import {
+  promptCodexProjectAgentsGet,
+  promptCodexProjectAgentsSet,
+  type CodexProjectAgentsState,
+  type PromptSummary,
+} from "../services/prompts";
+import { Button } from "../ui/Button";
+import { Card } from "../ui/Card";
+import { Input } from "../ui/Input";
+import { Textarea } from "../ui/Textarea";
+
+export type CodexProjectAgentsCardProps = {
+  // the currently enabled Codex prompt, offered as a starting point
+  enabledPrompt: PromptSummary | null;
+};
+
+export function CodexProjectAgentsCard({ enabledPrompt }: CodexProjectAgentsCardProps) {
+  const [projectDir, setProjectDir] = useState("");
+  const [state, setState] = useState<CodexProjectAgentsState | null>(null);
+  const [draft, setDraft] = useState("");
+  const [busy, setBusy] = useState(false);
+
+  function applyState(next: CodexProjectAgentsState) {
+    setState(next);
+    setDraft(next.managed_content ?? "");
+  }
+
+  async function load() {
+    if (busy || !projectDir.trim()) return;
+    setBusy(true);
+    try {
+      const next = await promptCodexProjectAgentsGet(projectDir.trim());
+      if (!next) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      applyState(next);
+    } catch (err) {
+      logToConsole("error", "读取项目 AGENTS.md 失败", { error: String(err), projectDir });
+      toast(`读取失败：${String(err)}`);
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function save(content: string | null) {
+    if (busy || !state) return;
+    setBusy(true);
+    try {
+      const next = await promptCodexProjectAgentsSet(projectDir.trim(), content);
+      if (!next) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      applyState(next);
+      const action = content ? "写入" : "移除";
+      logToConsole("info", `${action}项目 AGENTS.md 托管块`, { path: next.path });
+      toast(`已${action}托管块`);
+    } catch (err) {
+      logToConsole("error", "写入项目 AGENTS.md 失败", { error: String(err), projectDir });
+      toast(`写入失败：${String(err)}`);
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <Card>
+      <div className="mb-4 border-b border-slate-100 pb-4">
+        <div className="text-lg font-semibold text-slate-900 flex items-center gap-2">
+          <FolderGit2 className="h-5 w-5 text-blue-500" />
+          项目级 AGENTS.md
+        </div>
+        <p className="mt-1 text-sm text-slate-500">
+          仅读写项目根目录 AGENTS.md 中由 aio-coding-hub 标记包裹的托管块，块外的手写内容保持不变。
+          全局 ~/.codex/AGENTS.md 由上方启用的提示词以同样方式同步。
+        </p>
+      </div>
+
+      <div className="flex gap-2">
+        <Input
+          mono
+          value={projectDir}
+          placeholder="项目绝对路径，如 /Users/me/work/my-app"
+          onChange={(e) => {
+            setProjectDir(e.currentTarget.value);
+            setState(null);
+          }}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") void load();
+          }}
+          disabled={busy}
+        />
+        <Button onClick={() => void load()} disabled={busy || !projectDir.trim()}>
+          读取
+        </Button>
+      </div>
+
+      {state ? (
+        <div className="mt-4 space-y-3">
+          <div className="text-xs text-slate-500">
+            {state.path}
+            {!state.exists
+              ? "（文件不存在，保存时创建）"
+              : state.has_user_content
+                ? "（包含托管块外的手写内容）"
+                : null}
+          </div>
+          <Textarea
+            mono
+            value={draft}
+            placeholder="托管块内容（为空表示未写入）"
+            onChange={(e) => setDraft(e.currentTarget.value)}
+            rows={10}
+            className="text-xs"
+            disabled={busy}
+          />
+          <div className="flex flex-wrap items-center gap-2">
+            <Button
+              variant="primary"
+              onClick={() => void save(draft.trim() ? draft : null)}
+              disabled={busy || draft.trim() === (state.managed_content ?? "")}
+            >
+              保存
+            </Button>
+            {enabledPrompt ? (
+              <Button onClick={() => setDraft(enabledPrompt.content)} disabled={busy}>
+                填入已启用的提示词（{enabledPrompt.name}）
+              </Button>
+            ) : null}
+            {state.managed_content != null ? (
+              <Button variant="danger" onClick={() => void save(null)} disabled={busy}>
+                移除托管块
+              </Button>
+            ) : null}
+          </div>
+        </div>
+      ) : null}
+    </Card>
+  );
+}
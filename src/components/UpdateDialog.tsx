@@ -4,6 +4,7 @@ import { AIO_RELEASES_URL } from "../constants/urls";
 import { logToConsole } from "../services/consoleLog";
 import { appRestart } from "../services/dataManagement";
 import {
+  updateBackgroundDownloadStart,
   updateDialogSetOpen,
   updateDownloadAndInstall,
   useUpdateMeta,
@@ -17,6 +18,12 @@ export function UpdateDialog() {
   const updateCandidate = meta.updateCandidate;
   const about = meta.about;
   const isPortable = about?.run_mode === "portable";
+  const background =
+    meta.backgroundDownload && meta.backgroundDownload.version === updateCandidate?.version
+      ? meta.backgroundDownload
+      : null;
+  const backgroundReady = background?.phase === "ready";
+  const backgroundDownloading = background?.phase === "downloading";
 
   async function openReleases() {
     try {
@@ -30,6 +37,26 @@ export function UpdateDialog() {
     }
   }
 
+  async function startBackgroundDownload() {
+    const state = await updateBackgroundDownloadStart();
+    if (!state) {
+      toast("后台下载失败：请稍后重试");
+      return;
+    }
+    toast("已开始后台下载，完成后将在下次退出 / 重启时安装");
+    updateDialogSetOpen(false);
+  }
+
+  async function restartToInstall() {
+    try {
+      const ok = await appRestart();
+      if (!ok) toast("重启失败：请手动退出应用以完成安装");
+    } catch (err) {
+      logToConsole("error", "重启安装更新失败", { error: String(err) });
+      toast("重启失败：请手动退出应用以完成安装");
+    }
+  }
+
   async function installUpdate() {
     if (!updateCandidate) return;
     if (meta.installingUpdate) return;
@@ -118,10 +145,34 @@ export function UpdateDialog() {
             <div className="mt-1 font-mono text-xs text-slate-500">
               {formatBytes(meta.installDownloadedBytes)}
               {meta.installTotalBytes != null ? ` / ${formatBytes(meta.installTotalBytes)}` : ""}
+              {meta.installSource ? ` · ${meta.installSource}` : ""}
             </div>
           </div>
         ) : null}
 
+        {background && backgroundDownloading ? (
+          <div className="rounded-lg border border-slate-200 bg-white p-3 text-sm text-slate-700">
+            <div className="font-medium">后台下载中…</div>
+            <div className="mt-1 font-mono text-xs text-slate-500">
+              {formatBytes(background.downloaded_bytes)}
+              {background.total_bytes != null ? ` / ${formatBytes(background.total_bytes)}` : ""}
+              {background.source ? ` · ${background.source}` : ""}
+            </div>
+          </div>
+        ) : null}
+
+        {backgroundReady ? (
+          <div className="rounded-lg border border-emerald-200 bg-emerald-50 p-3 text-sm text-emerald-800">
+            更新已在后台下载完成，将在下次退出或重启时自动安装。
+          </div>
+        ) : null}
+
+        {background?.phase === "failed" ? (
+          <div className="rounded-lg border border-rose-200 bg-rose-50 p-3 text-xs text-rose-700">
+            后台下载失败：{background.error ?? "未知错误"}
+          </div>
+        ) : null}
+
         {meta.installError ? (
           <div className="rounded-lg border border-rose-200 bg-rose-50 p-3 text-xs text-rose-700">
             安装失败：{meta.installError}
@@ -146,15 +197,29 @@ export function UpdateDialog() {
             >
               打开下载页
             </Button>
-          ) : (
-            <Button
-              type="button"
-              variant="primary"
-              onClick={installUpdate}
-              disabled={!updateCandidate || meta.installingUpdate}
-            >
-              {meta.installingUpdate ? "安装中…" : "下载并安装"}
+          ) : backgroundReady ? (
+            <Button type="button" variant="primary" onClick={restartToInstall}>
+              立即重启并安装
             </Button>
+          ) : (
+            <>
+              <Button
+                type="button"
+                variant="secondary"
+                onClick={startBackgroundDownload}
+                disabled={!updateCandidate || meta.installingUpdate || backgroundDownloading}
+              >
+                {backgroundDownloading ? "后台下载中…" : "后台下载，退出时安装"}
+              </Button>
+              <Button
+                type="button"
+                variant="primary"
+                onClick={installUpdate}
+                disabled={!updateCandidate || meta.installingUpdate}
+              >
+                {meta.installingUpdate ? "安装中…" : "下载并安装"}
+              </Button>
+            </>
           )}
         </div>
       </div>
@@ -0,0 +1,141 @@
+import { useEffect, useState } from "react";
+import type { CliKey } from "../services/providers";
+import {
+  usageToolUsage,
+  type UsagePeriod,
+  type UsageRange,
+  type UsageToolGroupBy,
+  type UsageToolRow,
+} from "../services/usage";
+import { Card } from "../ui/Card";
+import { cn } from "../utils/cn";
+import { formatUnknownError } from "../utils/errors";
+import { formatInteger, formatUnixSeconds } from "../utils/formatters";
+
+export type UsageToolsCardProps = {
+  period: UsagePeriod;
+  cliKey: CliKey | null;
+  reloadSeq: number;
+};
+
+const GROUP_ITEMS: { key: UsageToolGroupBy; label: string }[] = [
+  { key: "tool", label: "按工具" },
+  { key: "day", label: "按天" },
+  { key: "session", label: "按会话" },
+];
+
+function rangeForPeriod(period: UsagePeriod): UsageRange | null {
+  switch (period) {
+    case "daily":
+      return "today";
+    case "weekly":
+      return "last7";
+    case "monthly":
+      return "month";
+    case "allTime":
+      return "all";
+    default:
+      return null;
+  }
+}
+
+const TH_CLASS = "border-b border-slate-200 bg-slate-50/60 px-3 py-2.5";
+const TD_CLASS = "border-b border-slate-200 px-3 py-2 text-xs text-slate-800";
+
+export function UsageToolsCard({ period, cliKey, reloadSeq }: UsageToolsCardProps) {
+  const [groupBy, setGroupBy] = useState<UsageToolGroupBy>("tool");
+  const [rows, setRows] = useState<UsageToolRow[]>([]);
+  const [errorText, setErrorText] = useState<string | null>(null);
+  const range = rangeForPeriod(period);
+
+  useEffect(() => {
+    if (!range) {
+      setRows([]);
+      return;
+    }
+    let cancelled = false;
+    usageToolUsage(range, { cliKey, groupBy, limit: 200 })
+      .then((list) => {
+        if (cancelled) return;
+        setErrorText(null);
+        setRows(list ?? []);
+      })
+      .catch((err) => {
+        if (!cancelled) setErrorText(formatUnknownError(err));
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [cliKey, groupBy, range, reloadSeq]);
+
+  return (
+    <Card padding="none">
+      <div className="flex items-center justify-between gap-4 px-6 pb-0 pt-5">
+        <div className="text-sm font-semibold text-slate-900">工具使用</div>
+        <div className="flex gap-1">
+          {GROUP_ITEMS.map((item) => (
+            <button
+              key={item.key}
+              type="button"
+              onClick={() => setGroupBy(item.key)}
+              className={cn(
+                "rounded-md px-2 py-1 text-xs",
+                groupBy === item.key
+                  ? "bg-slate-900 text-white"
+                  : "text-slate-600 hover:bg-slate-100"
+              )}
+            >
+              {item.label}
+            </button>
+          ))}
+        </div>
+      </div>
+
+      <div className="mt-4 overflow-x-auto">
+        {!range ? (
+          <div className="px-6 pb-5 text-sm text-slate-500">工具使用统计暂不支持自定义区间</div>
+        ) : errorText ? (
+          <div className="px-6 pb-5 text-sm text-rose-600">{errorText}</div>
+        ) : rows.length === 0 ? (
+          <div className="px-6 pb-5 text-sm text-slate-500">暂无工具调用记录</div>
+        ) : (
+          <table className="w-full border-separate border-spacing-0 text-left text-sm">
+            <thead>
+              <tr className="text-xs font-medium uppercase tracking-wide text-slate-500">
+                {groupBy !== "tool" ? (
+                  <th className={TH_CLASS}>{groupBy === "day" ? "日期" : "会话"}</th>
+                ) : null}
+                <th className={TH_CLASS}>工具</th>
+                <th className={TH_CLASS}>调用次数</th>
+                <th className={TH_CLASS}>会话数</th>
+                <th className={TH_CLASS}>最近使用</th>
+              </tr>
+            </thead>
+            <tbody>
+              {rows.map((row) => (
+                <tr key={`${row.group_key ?? ""}:${row.tool_name}`}>
+                  {groupBy !== "tool" ? (
+                    <td className={cn(TD_CLASS, "font-mono")}>{row.group_key || "—"}</td>
+                  ) : null}
+                  <td className={cn(TD_CLASS, "font-mono")}>
+                    {row.tool_name}
+                    {row.is_mcp ? (
+                      <span className="ml-2 rounded bg-indigo-50 px-1.5 py-0.5 text-[10px] text-indigo-600">
+                        MCP
+                      </span>
+                    ) : null}
+                  </td>
+                  <td className={cn(TD_CLASS, "tabular-nums")}>{formatInteger(row.call_count)}</td>
+                  <td className={cn(TD_CLASS, "tabular-nums")}>
+                    {formatInteger(row.session_count)}
+                  </td>
+                  <td className={TD_CLASS}>{formatUnixSeconds(row.last_seen_at)}</td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </div>
+    </Card>
+  );
+}
@@ -0,0 +1,274 @@
+// Usage: Manage Claude Code hooks (PreToolUse / PostToolUse / Stop) in ~/.claude/settings.json.
+
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import {
+  cliManagerClaudeHookCreate,
+  cliManagerClaudeHookSetEnabled,
+  cliManagerClaudeHooksGet,
+  type ClaudeHookEntry,
+  type ClaudeHookEvent,
+  type ClaudeHookInput,
+  type ClaudeHooksState,
+} from "../../services/cliManager";
+import { logToConsole } from "../../services/consoleLog";
+import { Button } from "../../ui/Button";
+import { Card } from "../../ui/Card";
+import { Input } from "../../ui/Input";
+import { Select } from "../../ui/Select";
+import { Switch } from "../../ui/Switch";
+import { Anchor, Trash2 } from "lucide-react";
+
+const HOOK_EVENTS: ClaudeHookEvent[] = ["PreToolUse", "PostToolUse", "Stop"];
+const MAX_TIMEOUT_SECS = 3600;
+
+function toInput(hook: {
+  event: string;
+  matcher: string | null;
+  command: string;
+  timeout_secs?: number | null;
+}): ClaudeHookInput {
+  return {
+    event: hook.event,
+    matcher: hook.matcher,
+    command: hook.command,
+    timeout_secs: hook.timeout_secs ?? null,
+  };
+}
+
+function hookKey(hook: ClaudeHookEntry) {
+  return `${hook.event}\u0000${hook.matcher ?? ""}\u0000${hook.command}`;
+}
+
+export function ClaudeHooksCard() {
+  const [state, setState] = useState<ClaudeHooksState | null>(null);
+  const [loading, setLoading] = useState(true);
+  const [saving, setSaving] = useState(false);
+  const [event, setEvent] = useState<ClaudeHookEvent>("PreToolUse");
+  const [matcher, setMatcher] = useState("");
+  const [command, setCommand] = useState("");
+  const [timeoutText, setTimeoutText] = useState("");
+
+  useEffect(() => {
+    let cancelled = false;
+    cliManagerClaudeHooksGet()
+      .then((next) => {
+        if (!cancelled) setState(next);
+      })
+      .catch((err) => {
+        logToConsole("error", "读取 Claude Code hooks 失败", { error: String(err) });
+        toast(`读取 Claude Code hooks 失败：${String(err)}`);
+      })
+      .finally(() => {
+        if (!cancelled) setLoading(false);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  async function setEnabled(hook: ClaudeHookInput, enabled: boolean, label: string) {
+    if (saving) return;
+    setSaving(true);
+    try {
+      const next = await cliManagerClaudeHookSetEnabled(hook, enabled);
+      if (!next) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      setState(next);
+      logToConsole("info", enabled ? "启用 Claude Code hook" : "移除 Claude Code hook", {
+        event: hook.event,
+        matcher: hook.matcher,
+        command: hook.command,
+      });
+      toast(enabled ? `已启用：${label}` : `已移除：${label}`);
+    } catch (err) {
+      logToConsole("error", "更新 Claude Code hook 失败", { error: String(err) });
+      toast(`更新 Claude Code hook 失败：${String(err)}`);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  async function createHook() {
+    if (saving) return;
+    const trimmedCommand = command.trim();
+    if (!trimmedCommand) {
+      toast("请填写 hook 命令");
+      return;
+    }
+    let timeoutSecs: number | null = null;
+    if (timeoutText.trim()) {
+      const n = Number(timeoutText.trim());
+      if (!Number.isInteger(n) || n <= 0 || n > MAX_TIMEOUT_SECS) {
+        toast(`超时必须是 1-${MAX_TIMEOUT_SECS} 之间的整数（秒）`);
+        return;
+      }
+      timeoutSecs = n;
+    }
+    const trimmedMatcher = matcher.trim();
+
+    setSaving(true);
+    try {
+      const next = await cliManagerClaudeHookCreate({
+        event,
+        matcher: event === "Stop" || !trimmedMatcher ? null : trimmedMatcher,
+        command: trimmedCommand,
+        timeout_secs: timeoutSecs,
+      });
+      if (!next) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      setState(next);
+      setCommand("");
+      setMatcher("");
+      setTimeoutText("");
+      logToConsole("info", "新增 Claude Code hook", { event, command: trimmedCommand });
+      toast("已新增 hook（已备份原 settings.json）");
+    } catch (err) {
+      logToConsole("error", "新增 Claude Code hook 失败", { error: String(err) });
+      toast(`新增 Claude Code hook 失败：${String(err)}`);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <Card>
+      <div className="mb-4 border-b border-slate-100 pb-4">
+        <div className="text-lg font-semibold text-slate-900 flex items-center gap-2">
+          <Anchor className="h-5 w-5 text-[#D97757]" />
+          Hooks
+        </div>
+        <p className="mt-1 text-sm text-slate-500">
+          在工具调用前后或会话结束时执行命令（写入 settings.json 的 hooks 字段）。每次修改前自动备份
+          settings.json；PreToolUse 命令以退出码 2 拦截工具调用。
+        </p>
+      </div>
+
+      {loading ? (
+        <div className="text-sm text-slate-500">加载中…</div>
+      ) : !state ? (
+        <div className="text-sm text-slate-600 bg-slate-50 p-4 rounded-lg">
+          仅在 Tauri Desktop 环境可用
+        </div>
+      ) : (
+        <div className="space-y-6">
+          <div>
+            <div className="mb-2 text-sm font-medium text-slate-700">内置模板</div>
+            <div className="divide-y divide-slate-100 rounded-lg border border-slate-100">
+              {state.templates.map((template) => (
+                <div key={template.id} className="flex items-start justify-between gap-4 p-3">
+                  <div className="min-w-0">
+                    <div className="text-sm font-medium text-slate-900">
+                      {template.name}
+                      <span className="ml-2 text-xs font-normal text-slate-400">
+                        {template.event}
+                        {template.matcher ? ` · ${template.matcher}` : ""}
+                      </span>
+                    </div>
+                    <div className="mt-0.5 text-xs text-slate-500">{template.description}</div>
+                  </div>
+                  <Switch
+                    checked={template.installed}
+                    onCheckedChange={(checked) =>
+                      void setEnabled(toInput(template), checked, template.name)
+                    }
+                    disabled={saving}
+                  />
+                </div>
+              ))}
+            </div>
+          </div>
+
+          <div>
+            <div className="mb-2 text-sm font-medium text-slate-700">
+              已配置的 hooks（{state.hooks.length}）
+            </div>
+            {state.hooks.length === 0 ? (
+              <div className="text-sm text-slate-500 bg-slate-50 p-3 rounded-lg">暂无 hooks</div>
+            ) : (
+              <div className="divide-y divide-slate-100 rounded-lg border border-slate-100">
+                {state.hooks.map((hook) => (
+                  <div key={hookKey(hook)} className="flex items-start justify-between gap-4 p-3">
+                    <div className="min-w-0">
+                      <div className="text-xs text-slate-500">
+                        {hook.event}
+                        {hook.matcher ? ` · ${hook.matcher}` : ""}
+                        {hook.timeout_secs != null ? ` · ${hook.timeout_secs}s` : ""}
+                      </div>
+                      <div className="mt-0.5 break-all font-mono text-xs text-slate-800">
+                        {hook.command}
+                      </div>
+                    </div>
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      onClick={() => void setEnabled(toInput(hook), false, hook.event)}
+                      disabled={saving}
+                      title="移除"
+                    >
+                      <Trash2 className="h-4 w-4" />
+                    </Button>
+                  </div>
+                ))}
+              </div>
+            )}
+          </div>
+
+          <div>
+            <div className="mb-2 text-sm font-medium text-slate-700">新增 hook</div>
+            <div className="grid gap-2 md:grid-cols-[10rem_1fr_8rem]">
+              <Select
+                value={event}
+                onChange={(e) => setEvent(e.currentTarget.value as ClaudeHookEvent)}
+                disabled={saving}
+              >
+                {HOOK_EVENTS.map((v) => (
+                  <option key={v} value={v}>
+                    {v}
+                  </option>
+                ))}
+              </Select>
+              <Input
+                mono
+                value={matcher}
+                placeholder={
+                  event === "Stop" ? "Stop 不支持 matcher" : "matcher，如 Bash|Edit（留空匹配全部）"
+                }
+                onChange={(e) => setMatcher(e.currentTarget.value)}
+                disabled={saving || event === "Stop"}
+              />
+              <Input
+                type="number"
+                value={timeoutText}
+                placeholder="超时（秒）"
+                onChange={(e) => setTimeoutText(e.currentTarget.value)}
+                disabled={saving}
+              />
+            </div>
+            <div className="mt-2 flex gap-2">
+              <Input
+                mono
+                value={command}
+                placeholder="命令，如 npx prettier --write ."
+                onChange={(e) => setCommand(e.currentTarget.value)}
+                disabled={saving}
+              />
+              <Button
+                variant="primary"
+                onClick={() => void createHook()}
+                disabled={saving || !command.trim()}
+              >
+                添加
+              </Button>
+            </div>
+            <div className="mt-2 text-xs text-slate-400">备份目录：{state.backup_dir}</div>
+          </div>
+        </div>
+      )}
+    </Card>
+  );
+}
@@ -0,0 +1,86 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings, CliStreamCaps } from "../../services/settings";
+import { settingsCliStreamCapsSet } from "../../services/settingsStreamCaps";
+import { logToConsole } from "../../services/consoleLog";
+import { Input } from "../../ui/Input";
+import { SettingsRow } from "../../ui/SettingsRow";
+
+const MAX_STREAMS = 256;
+const DEFAULT_CAPS: CliStreamCaps = { claude: 0, codex: 0, gemini: 0 };
+const CLI_KEYS: { key: keyof CliStreamCaps; label: string }[] = [
+  { key: "claude", label: "Claude" },
+  { key: "codex", label: "Codex" },
+  { key: "gemini", label: "Gemini" },
+];
+
+export type CliStreamCapsRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function CliStreamCapsRow({ available, settings }: CliStreamCapsRowProps) {
+  const { claude, codex, gemini } = settings?.cli_max_concurrent_streams ?? DEFAULT_CAPS;
+  const persisted: CliStreamCaps = { claude, codex, gemini };
+  const [caps, setCaps] = useState<CliStreamCaps>(persisted);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setCaps({ claude, codex, gemini });
+  }, [claude, codex, gemini]);
+
+  async function commit(key: keyof CliStreamCaps, value: number) {
+    if (!available || saving || value === persisted[key]) return;
+    if (!Number.isInteger(value) || value < 0 || value > MAX_STREAMS) {
+      toast(`并发流上限必须为 0-${MAX_STREAMS}`);
+      setCaps(persisted);
+      return;
+    }
+    setSaving(true);
+    try {
+      const updated = await settingsCliStreamCapsSet({ ...persisted, [key]: value });
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setCaps(persisted);
+        return;
+      }
+      setCaps(updated.cli_max_concurrent_streams ?? DEFAULT_CAPS);
+      logToConsole("info", "更新并发流上限", { caps: updated.cli_max_concurrent_streams });
+    } catch (err) {
+      logToConsole("error", "更新并发流上限失败", { error: String(err) });
+      toast(`更新并发流上限失败：${String(err)}`);
+      setCaps(persisted);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="并发流上限">
+      <div className="flex items-center gap-2">
+        {CLI_KEYS.map(({ key, label }) => (
+          <label key={key} className="flex items-center gap-1 text-sm text-slate-500">
+            {label}
+            <Input
+              type="number"
+              value={caps[key]}
+              onChange={(e) => {
+                const next = e.currentTarget.valueAsNumber;
+                if (Number.isFinite(next)) setCaps((prev) => ({ ...prev, [key]: next }));
+              }}
+              onBlur={(e) => void commit(key, e.currentTarget.valueAsNumber)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") e.currentTarget.blur();
+              }}
+              style={{ width: "4rem" }}
+              min={0}
+              max={MAX_STREAMS}
+              disabled={!available || saving}
+              title="同一 CLI 同时进行的流式请求上限，超出时本地返回 429 + Retry-After；0 表示不限制"
+            />
+          </label>
+        ))}
+      </div>
+    </SettingsRow>
+  );
+}
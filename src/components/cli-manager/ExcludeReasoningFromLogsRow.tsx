@@ -0,0 +1,57 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings } from "../../services/settings";
+import { settingsExcludeReasoningFromLogsSet } from "../../services/settingsReasoningLogs";
+import { logToConsole } from "../../services/consoleLog";
+import { SettingsRow } from "../../ui/SettingsRow";
+import { Switch } from "../../ui/Switch";
+
+export type ExcludeReasoningFromLogsRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function ExcludeReasoningFromLogsRow({
+  available,
+  settings,
+}: ExcludeReasoningFromLogsRowProps) {
+  const persisted = settings?.exclude_reasoning_from_logs ?? false;
+  const [enabled, setEnabled] = useState(persisted);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setEnabled(persisted);
+  }, [persisted]);
+
+  async function persist(next: boolean) {
+    if (!available || saving) return;
+    setEnabled(next);
+    setSaving(true);
+    try {
+      const updated = await settingsExcludeReasoningFromLogsSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setEnabled(persisted);
+        return;
+      }
+      setEnabled(updated.exclude_reasoning_from_logs ?? false);
+      logToConsole("info", "更新思考内容落库设置", { exclude: next });
+    } catch (err) {
+      logToConsole("error", "更新思考内容落库设置失败", { error: String(err) });
+      toast(`更新思考内容落库设置失败：${String(err)}`);
+      setEnabled(persisted);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="记录中排除思考 / 推理内容">
+      <Switch
+        checked={enabled}
+        onCheckedChange={(checked) => void persist(checked)}
+        disabled={!available || saving}
+      />
+    </SettingsRow>
+  );
+}
@@ -0,0 +1,118 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings, FailoverRule } from "../../services/settings";
+import { settingsFailoverRulesSet } from "../../services/settingsFailoverRules";
+import { logToConsole } from "../../services/consoleLog";
+import { Card } from "../../ui/Card";
+import { Textarea } from "../../ui/Textarea";
+import { Shuffle } from "lucide-react";
+
+export type FailoverRulesCardProps = {
+  available: boolean;
+  saving: boolean;
+  settings: AppSettings;
+};
+
+const PLACEHOLDER = `[
+  {
+    "id": "relay-429-switch",
+    "name": "中转站限流立即切换",
+    "enabled": true,
+    "cli_key": "claude",
+    "provider_ids": [],
+    "statuses": [429],
+    "body_regex": "",
+    "decision": "switch_provider"
+  }
+]`;
+
+function formatRules(rules: FailoverRule[]) {
+  return rules.length ? JSON.stringify(rules, null, 2) : "";
+}
+
+export function FailoverRulesCard({
+  available,
+  saving,
+  settings,
+}: FailoverRulesCardProps) {
+  const [saved, setSaved] = useState<FailoverRule[]>(settings.failover_rules ?? []);
+  const [draft, setDraft] = useState<string>(formatRules(settings.failover_rules ?? []));
+  const [committing, setCommitting] = useState(false);
+
+  useEffect(() => {
+    setSaved(settings.failover_rules ?? []);
+    setDraft(formatRules(settings.failover_rules ?? []));
+  }, [settings.failover_rules]);
+
+  async function commitRules() {
+    if (!available || committing) return;
+    if (draft.trim() === formatRules(saved).trim()) return;
+
+    let rules: FailoverRule[];
+    try {
+      const parsed: unknown = draft.trim() ? JSON.parse(draft) : [];
+      if (!Array.isArray(parsed)) {
+        toast("故障转移规则必须是 JSON 数组");
+        return;
+      }
+      rules = parsed as FailoverRule[];
+    } catch (err) {
+      toast(`故障转移规则 JSON 解析失败：${String(err)}`);
+      return;
+    }
+
+    setCommitting(true);
+    try {
+      const updated = await settingsFailoverRulesSet(rules);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      const next = updated.failover_rules ?? [];
+      setSaved(next);
+      setDraft(formatRules(next));
+      logToConsole("info", "更新故障转移规则", { count: next.length });
+      toast(
+        next.length ? `已保存 ${next.length} 条故障转移规则` : "已清空故障转移规则"
+      );
+    } catch (err) {
+      logToConsole("error", "更新故障转移规则失败", { error: String(err) });
+      toast(`更新故障转移规则失败：${String(err)}`);
+    } finally {
+      setCommitting(false);
+    }
+  }
+
+  return (
+    <Card className="md:col-span-2">
+      <div className="mb-4 border-b border-slate-100 pb-4">
+        <div className="text-lg font-semibold text-slate-900 flex items-center gap-2">
+          <Shuffle className="h-5 w-5 text-blue-500" />
+          故障转移规则
+        </div>
+        <p className="mt-1 text-sm text-slate-500">
+          按状态码 / 错误响应正则覆盖内置的失败处理：retry_same_provider 重试当前供应商、
+          switch_provider 切换供应商、abort 直接返回错误。按顺序匹配，首条命中生效；
+          cli_key、provider_ids、statuses 为空表示不限，body_regex 仅扫描 64KB 以内的响应体。
+        </p>
+      </div>
+
+      {!available ? (
+        <div className="text-sm text-slate-600 bg-slate-50 p-4 rounded-lg">
+          仅在 Tauri Desktop 环境可用
+        </div>
+      ) : (
+        <Textarea
+          mono
+          value={draft}
+          placeholder={PLACEHOLDER}
+          onChange={(e) => setDraft(e.currentTarget.value)}
+          onBlur={() => void commitRules()}
+          disabled={saving || committing}
+          rows={8}
+          className="text-xs"
+        />
+      )}
+    </Card>
+  );
+}
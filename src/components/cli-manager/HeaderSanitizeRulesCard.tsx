@@ -0,0 +1,115 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings, HeaderSanitizeRule } from "../../services/settings";
+import { settingsHeaderSanitizeRulesSet } from "../../services/settingsHeaderSanitizeRules";
+import { logToConsole } from "../../services/consoleLog";
+import { Card } from "../../ui/Card";
+import { Textarea } from "../../ui/Textarea";
+import { EyeOff } from "lucide-react";
+
+export type HeaderSanitizeRulesCardProps = {
+  available: boolean;
+  saving: boolean;
+  settings: AppSettings;
+};
+
+const PLACEHOLDER = `[
+  {
+    "id": "strip-fingerprint",
+    "name": "去除客户端指纹",
+    "enabled": true,
+    "cli_key": "",
+    "provider_ids": [],
+    "headers": ["x-stainless-*", "x-app", "user-agent"]
+  }
+]`;
+
+function formatRules(rules: HeaderSanitizeRule[]) {
+  return rules.length ? JSON.stringify(rules, null, 2) : "";
+}
+
+export function HeaderSanitizeRulesCard({
+  available,
+  saving,
+  settings,
+}: HeaderSanitizeRulesCardProps) {
+  const [saved, setSaved] = useState<HeaderSanitizeRule[]>(settings.header_sanitize_rules ?? []);
+  const [draft, setDraft] = useState<string>(formatRules(settings.header_sanitize_rules ?? []));
+  const [committing, setCommitting] = useState(false);
+
+  useEffect(() => {
+    setSaved(settings.header_sanitize_rules ?? []);
+    setDraft(formatRules(settings.header_sanitize_rules ?? []));
+  }, [settings.header_sanitize_rules]);
+
+  async function commitRules() {
+    if (!available || committing) return;
+    if (draft.trim() === formatRules(saved).trim()) return;
+
+    let rules: HeaderSanitizeRule[];
+    try {
+      const parsed: unknown = draft.trim() ? JSON.parse(draft) : [];
+      if (!Array.isArray(parsed)) {
+        toast("请求头清理规则必须是 JSON 数组");
+        return;
+      }
+      rules = parsed as HeaderSanitizeRule[];
+    } catch (err) {
+      toast(`请求头清理规则 JSON 解析失败：${String(err)}`);
+      return;
+    }
+
+    setCommitting(true);
+    try {
+      const updated = await settingsHeaderSanitizeRulesSet(rules);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      const next = updated.header_sanitize_rules ?? [];
+      setSaved(next);
+      setDraft(formatRules(next));
+      logToConsole("info", "更新请求头清理规则", { count: next.length });
+      toast(
+        next.length ? `已保存 ${next.length} 条请求头清理规则` : "已清空请求头清理规则"
+      );
+    } catch (err) {
+      logToConsole("error", "更新请求头清理规则失败", { error: String(err) });
+      toast(`更新请求头清理规则失败：${String(err)}`);
+    } finally {
+      setCommitting(false);
+    }
+  }
+
+  return (
+    <Card className="md:col-span-2">
+      <div className="mb-4 border-b border-slate-100 pb-4">
+        <div className="text-lg font-semibold text-slate-900 flex items-center gap-2">
+          <EyeOff className="h-5 w-5 text-blue-500" />
+          请求头清理规则
+        </div>
+        <p className="mt-1 text-sm text-slate-500">
+          转发上游前删除匹配的客户端请求头（遥测 ID、x-stainless-* 等），避免第三方中转站识别本机。
+          provider_ids 为空表示作用于全部供应商；末尾 * 表示前缀匹配；认证头始终由网关重写。
+        </p>
+      </div>
+
+      {!available ? (
+        <div className="text-sm text-slate-600 bg-slate-50 p-4 rounded-lg">
+          仅在 Tauri Desktop 环境可用
+        </div>
+      ) : (
+        <Textarea
+          mono
+          value={draft}
+          placeholder={PLACEHOLDER}
+          onChange={(e) => setDraft(e.currentTarget.value)}
+          onBlur={() => void commitRules()}
+          disabled={saving || committing}
+          rows={8}
+          className="text-xs"
+        />
+      )}
+    </Card>
+  );
+}
@@ -0,0 +1,95 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings, HedgedRequestsConfig } from "../../services/settings";
+import { settingsHedgedRequestsSet } from "../../services/settingsHedgedRequests";
+import { logToConsole } from "../../services/consoleLog";
+import { Input } from "../../ui/Input";
+import { SettingsRow } from "../../ui/SettingsRow";
+import { Switch } from "../../ui/Switch";
+
+const DEFAULT_DELAY_MS = 2000;
+const MIN_DELAY_MS = 100;
+const MAX_DELAY_MS = 60_000;
+
+export type HedgedRequestsRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function HedgedRequestsRow({ available, settings }: HedgedRequestsRowProps) {
+  const enabled = settings?.hedged_requests?.enabled ?? false;
+  const persistedDelay = settings?.hedged_requests?.delay_ms ?? DEFAULT_DELAY_MS;
+  const [delayMs, setDelayMs] = useState(persistedDelay);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setDelayMs(persistedDelay);
+  }, [persistedDelay]);
+
+  async function persist(next: HedgedRequestsConfig) {
+    if (!available || saving) return;
+    if (
+      !Number.isInteger(next.delay_ms) ||
+      next.delay_ms < MIN_DELAY_MS ||
+      next.delay_ms > MAX_DELAY_MS
+    ) {
+      toast(`对冲延迟必须为 ${MIN_DELAY_MS}-${MAX_DELAY_MS} 毫秒`);
+      setDelayMs(persistedDelay);
+      return;
+    }
+    setSaving(true);
+    try {
+      const updated = await settingsHedgedRequestsSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setDelayMs(persistedDelay);
+        return;
+      }
+      setDelayMs(updated.hedged_requests?.delay_ms ?? next.delay_ms);
+      logToConsole("info", "更新对冲请求设置", updated.hedged_requests ?? next);
+    } catch (err) {
+      logToConsole("error", "更新对冲请求设置失败", { error: String(err) });
+      toast(`更新对冲请求设置失败：${String(err)}`);
+      setDelayMs(persistedDelay);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="对冲请求">
+      <div className="flex items-center gap-2">
+        <Input
+          type="number"
+          value={delayMs}
+          onChange={(e) => {
+            const next = e.currentTarget.valueAsNumber;
+            if (Number.isFinite(next)) setDelayMs(next);
+          }}
+          onBlur={(e) => {
+            const next = e.currentTarget.valueAsNumber;
+            if (next === persistedDelay) return;
+            void persist({ enabled, delay_ms: next });
+          }}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") e.currentTarget.blur();
+          }}
+          style={{ width: "6rem" }}
+          min={MIN_DELAY_MS}
+          max={MAX_DELAY_MS}
+          step={100}
+          disabled={!available || saving}
+          title="首个 Provider 超过该时长仍未响应时，并行请求下一个 Provider，先响应者胜出、另一方取消"
+        />
+        <span className="w-8 text-sm text-slate-500">毫秒</span>
+        <Switch
+          checked={enabled}
+          onCheckedChange={(checked) =>
+            void persist({ enabled: checked, delay_ms: persistedDelay })
+          }
+          disabled={!available || saving}
+        />
+      </div>
+    </SettingsRow>
+  );
+}
@@ -0,0 +1,59 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings, InflightDedupMode } from "../../services/settings";
+import { settingsInflightDedupModeSet } from "../../services/settingsInflightDedup";
+import { logToConsole } from "../../services/consoleLog";
+import { Select } from "../../ui/Select";
+import { SettingsRow } from "../../ui/SettingsRow";
+
+export type InflightDedupModeRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function InflightDedupModeRow({ available, settings }: InflightDedupModeRowProps) {
+  const [mode, setMode] = useState<InflightDedupMode>(settings?.inflight_dedup_mode ?? "off");
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setMode(settings?.inflight_dedup_mode ?? "off");
+  }, [settings?.inflight_dedup_mode]);
+
+  async function commitMode(next: InflightDedupMode) {
+    if (!available || saving || next === mode) return;
+    const previous = mode;
+    setMode(next);
+    setSaving(true);
+    try {
+      const updated = await settingsInflightDedupModeSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setMode(previous);
+        return;
+      }
+      setMode(updated.inflight_dedup_mode ?? "off");
+      logToConsole("info", "更新重复请求处理方式", { mode: updated.inflight_dedup_mode });
+    } catch (err) {
+      logToConsole("error", "更新重复请求处理方式失败", { error: String(err) });
+      toast(`更新重复请求处理方式失败：${String(err)}`);
+      setMode(previous);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="进行中重复请求">
+      <Select
+        value={mode}
+        onChange={(e) => void commitMode(e.currentTarget.value as InflightDedupMode)}
+        disabled={!available || saving}
+        className="w-56"
+      >
+        <option value="off">不处理（照常转发）</option>
+        <option value="attach">挂载到原请求的响应</option>
+        <option value="reject">本地拒绝（409）</option>
+      </Select>
+    </SettingsRow>
+  );
+}
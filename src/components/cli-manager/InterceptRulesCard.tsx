@@ -0,0 +1,113 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings, InterceptRule } from "../../services/settings";
+import { settingsInterceptRulesSet } from "../../services/settingsInterceptRules";
+import { logToConsole } from "../../services/consoleLog";
+import { Card } from "../../ui/Card";
+import { Textarea } from "../../ui/Textarea";
+import { Filter } from "lucide-react";
+
+export type InterceptRulesCardProps = {
+  available: boolean;
+  saving: boolean;
+  settings: AppSettings;
+};
+
+const PLACEHOLDER = `[
+  {
+    "id": "telemetry",
+    "name": "遥测上报",
+    "enabled": true,
+    "cli_key": "claude",
+    "method": "POST",
+    "path": "/api/event_logging/*",
+    "body_predicates": [{ "pointer": "/events", "op": "exists" }],
+    "response_status": 200,
+    "response_content_type": "application/json; charset=utf-8",
+    "response_body": "{}"
+  }
+]`;
+
+function formatRules(rules: InterceptRule[]) {
+  return rules.length ? JSON.stringify(rules, null, 2) : "";
+}
+
+export function InterceptRulesCard({ available, saving, settings }: InterceptRulesCardProps) {
+  const [saved, setSaved] = useState<InterceptRule[]>(settings.intercept_rules ?? []);
+  const [draft, setDraft] = useState<string>(formatRules(settings.intercept_rules ?? []));
+  const [committing, setCommitting] = useState(false);
+
+  useEffect(() => {
+    setSaved(settings.intercept_rules ?? []);
+    setDraft(formatRules(settings.intercept_rules ?? []));
+  }, [settings.intercept_rules]);
+
+  async function commitRules() {
+    if (!available || committing) return;
+    if (draft.trim() === formatRules(saved).trim()) return;
+
+    let rules: InterceptRule[];
+    try {
+      const parsed: unknown = draft.trim() ? JSON.parse(draft) : [];
+      if (!Array.isArray(parsed)) {
+        toast("拦截规则必须是 JSON 数组");
+        return;
+      }
+      rules = parsed as InterceptRule[];
+    } catch (err) {
+      toast(`拦截规则 JSON 解析失败：${String(err)}`);
+      return;
+    }
+
+    setCommitting(true);
+    try {
+      const updated = await settingsInterceptRulesSet(rules);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      const next = updated.intercept_rules ?? [];
+      setSaved(next);
+      setDraft(formatRules(next));
+      logToConsole("info", "更新请求拦截规则", { count: next.length });
+      toast(next.length ? `已保存 ${next.length} 条拦截规则` : "已清空拦截规则");
+    } catch (err) {
+      logToConsole("error", "更新请求拦截规则失败", { error: String(err) });
+      toast(`更新拦截规则失败：${String(err)}`);
+    } finally {
+      setCommitting(false);
+    }
+  }
+
+  return (
+    <Card className="md:col-span-2">
+      <div className="mb-4 border-b border-slate-100 pb-4">
+        <div className="text-lg font-semibold text-slate-900 flex items-center gap-2">
+          <Filter className="h-5 w-5 text-blue-500" />
+          请求拦截规则
+        </div>
+        <p className="mt-1 text-sm text-slate-500">
+          按路径与请求体条件匹配，命中后由网关直接返回模板响应（不转发上游，日志排除统计）。
+          模板支持 {"{{trace_id}}"} / {"{{model}}"} / {"{{created_at}}"} 占位符。
+        </p>
+      </div>
+
+      {!available ? (
+        <div className="text-sm text-slate-600 bg-slate-50 p-4 rounded-lg">
+          仅在 Tauri Desktop 环境可用
+        </div>
+      ) : (
+        <Textarea
+          mono
+          value={draft}
+          placeholder={PLACEHOLDER}
+          onChange={(e) => setDraft(e.currentTarget.value)}
+          onBlur={() => void commitRules()}
+          disabled={saving || committing}
+          rows={8}
+          className="text-xs"
+        />
+      )}
+    </Card>
+  );
+}
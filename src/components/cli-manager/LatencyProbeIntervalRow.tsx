@@ -0,0 +1,77 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings } from "../../services/settings";
+import { settingsLatencyProbeIntervalSet } from "../../services/settingsLatencyProbe";
+import { logToConsole } from "../../services/consoleLog";
+import { Input } from "../../ui/Input";
+import { SettingsRow } from "../../ui/SettingsRow";
+
+const MAX_INTERVAL_MINUTES = 24 * 60;
+
+export type LatencyProbeIntervalRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function LatencyProbeIntervalRow({ available, settings }: LatencyProbeIntervalRowProps) {
+  const persisted = settings?.latency_probe_interval_minutes ?? 0;
+  const [minutes, setMinutes] = useState(persisted);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setMinutes(persisted);
+  }, [persisted]);
+
+  async function commit(next: number) {
+    if (!available || saving || next === persisted) return;
+    if (!Number.isInteger(next) || next < 0 || next > MAX_INTERVAL_MINUTES) {
+      toast(`定时测速间隔必须为 0-${MAX_INTERVAL_MINUTES} 分钟`);
+      setMinutes(persisted);
+      return;
+    }
+    setSaving(true);
+    try {
+      const updated = await settingsLatencyProbeIntervalSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setMinutes(persisted);
+        return;
+      }
+      setMinutes(updated.latency_probe_interval_minutes);
+      logToConsole("info", "更新定时测速间隔", {
+        minutes: updated.latency_probe_interval_minutes,
+      });
+    } catch (err) {
+      logToConsole("error", "更新定时测速间隔失败", { error: String(err) });
+      toast(`更新定时测速间隔失败：${String(err)}`);
+      setMinutes(persisted);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="定时 Base URL 测速">
+      <div className="flex items-center gap-2">
+        <Input
+          type="number"
+          value={minutes}
+          onChange={(e) => {
+            const next = e.currentTarget.valueAsNumber;
+            if (Number.isFinite(next)) setMinutes(next);
+          }}
+          onBlur={(e) => void commit(e.currentTarget.valueAsNumber)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") e.currentTarget.blur();
+          }}
+          style={{ width: "5rem" }}
+          min={0}
+          max={MAX_INTERVAL_MINUTES}
+          disabled={!available || saving}
+          title="0 表示关闭；开启后即使无流量也会定期测速所有已启用 Provider"
+        />
+        <span className="w-8 text-sm text-slate-500">分钟</span>
+      </div>
+    </SettingsRow>
+  );
+}
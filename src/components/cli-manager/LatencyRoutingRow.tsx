@@ -0,0 +1,93 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings, LatencyRoutingConfig } from "../../services/settings";
+import { settingsLatencyRoutingSet } from "../../services/settingsLatencyRouting";
+import { logToConsole } from "../../services/consoleLog";
+import { Input } from "../../ui/Input";
+import { SettingsRow } from "../../ui/SettingsRow";
+import { Switch } from "../../ui/Switch";
+
+const DEFAULT_WINDOW_MINUTES = 30;
+const MAX_WINDOW_MINUTES = 24 * 60;
+
+export type LatencyRoutingRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function LatencyRoutingRow({ available, settings }: LatencyRoutingRowProps) {
+  const enabled = settings?.latency_routing?.enabled ?? false;
+  const persistedWindow = settings?.latency_routing?.window_minutes ?? DEFAULT_WINDOW_MINUTES;
+  const [windowMinutes, setWindowMinutes] = useState(persistedWindow);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setWindowMinutes(persistedWindow);
+  }, [persistedWindow]);
+
+  async function persist(next: LatencyRoutingConfig) {
+    if (!available || saving) return;
+    if (
+      !Number.isInteger(next.window_minutes) ||
+      next.window_minutes < 1 ||
+      next.window_minutes > MAX_WINDOW_MINUTES
+    ) {
+      toast(`延迟统计窗口必须为 1-${MAX_WINDOW_MINUTES} 分钟`);
+      setWindowMinutes(persistedWindow);
+      return;
+    }
+    setSaving(true);
+    try {
+      const updated = await settingsLatencyRoutingSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setWindowMinutes(persistedWindow);
+        return;
+      }
+      setWindowMinutes(updated.latency_routing?.window_minutes ?? next.window_minutes);
+      logToConsole("info", "更新延迟路由设置", updated.latency_routing ?? next);
+    } catch (err) {
+      logToConsole("error", "更新延迟路由设置失败", { error: String(err) });
+      toast(`更新延迟路由设置失败：${String(err)}`);
+      setWindowMinutes(persistedWindow);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="按延迟优先路由">
+      <div className="flex items-center gap-2">
+        <Input
+          type="number"
+          value={windowMinutes}
+          onChange={(e) => {
+            const next = e.currentTarget.valueAsNumber;
+            if (Number.isFinite(next)) setWindowMinutes(next);
+          }}
+          onBlur={(e) => {
+            const next = e.currentTarget.valueAsNumber;
+            if (next === persistedWindow) return;
+            void persist({ enabled, window_minutes: next });
+          }}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") e.currentTarget.blur();
+          }}
+          style={{ width: "5rem" }}
+          min={1}
+          max={MAX_WINDOW_MINUTES}
+          disabled={!available || saving}
+          title="统计窗口：只参考该时间内成功请求的首字耗时，越新的样本权重越高"
+        />
+        <span className="w-8 text-sm text-slate-500">分钟</span>
+        <Switch
+          checked={enabled}
+          onCheckedChange={(checked) =>
+            void persist({ enabled: checked, window_minutes: persistedWindow })
+          }
+          disabled={!available || saving}
+        />
+      </div>
+    </SettingsRow>
+  );
+}
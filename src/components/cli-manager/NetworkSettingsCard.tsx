@@ -1,16 +1,39 @@
 import { useEffect, useMemo, useState } from "react";
 import { toast } from "sonner";
-import type { AppSettings, GatewayListenMode } from "../../services/settings";
+import type {
+  AppSettings,
+  GatewayClientKey,
+  GatewayExtraListener,
+  GatewayListenMode,
+} from "../../services/settings";
+import type { CliKey } from "../../services/providers";
 import { gatewayStart, gatewayStop } from "../../services/gateway";
 import { wslHostAddressGet } from "../../services/wsl";
+import {
+  gatewayAccessTokenGenerate,
+  settingsGatewayAccessTokenSet,
+} from "../../services/settingsGatewayAccessToken";
+import {
+  gatewayTlsCaExport,
+  settingsGatewayTlsHostsSet,
+  settingsGatewayTlsSet,
+} from "../../services/settingsGatewayTls";
+import { settingsGatewayExtraListenersSet } from "../../services/settingsGatewayExtraListeners";
+import { settingsGatewayClientKeysSet } from "../../services/settingsGatewayClientKeys";
+import { settingsGatewayIpAllowlistSet } from "../../services/settingsGatewayIpAllowlist";
+import { settingsGatewayDrainTimeoutSet } from "../../services/settingsGatewayDrain";
+import { requestLogsClientUsage, type ClientUsageAggregate } from "../../services/requestLogs";
 import { logToConsole } from "../../services/consoleLog";
 import { useGatewayMeta } from "../../hooks/useGatewayMeta";
 import { Card } from "../../ui/Card";
+import { Button } from "../../ui/Button";
 import { Input } from "../../ui/Input";
 import { Select } from "../../ui/Select";
 import { SettingsRow } from "../../ui/SettingsRow";
+import { Switch } from "../../ui/Switch";
+import { Textarea } from "../../ui/Textarea";
 import { cn } from "../../utils/cn";
-import { AlertTriangle, Network } from "lucide-react";
+import { AlertTriangle, Network, Plus, Trash2 } from "lucide-react";
 
 function parseCustomAddress(input: string, fallbackPort: number) {
   const raw = input.trim();
@@ -77,6 +100,24 @@ export function NetworkSettingsCard({
     settings.gateway_custom_listen_address
   );
   const [wslHost, setWslHost] = useState<string | null>(null);
+  const [accessToken, setAccessToken] = useState<string>(settings.gateway_access_token ?? "");
+  const [savedAccessToken, setSavedAccessToken] = useState<string>(
+    settings.gateway_access_token ?? ""
+  );
+
+  const [tlsEnabled, setTlsEnabled] = useState<boolean>(settings.gateway_tls_enabled ?? false);
+  const [tlsHosts, setTlsHosts] = useState<string>((settings.gateway_tls_hosts ?? []).join("\n"));
+  const [caPath, setCaPath] = useState<string | null>(null);
+  const [extraListeners, setExtraListeners] = useState<GatewayExtraListener[]>(
+    settings.gateway_extra_listeners ?? []
+  );
+  const [clientKeys, setClientKeys] = useState<GatewayClientKey[]>(
+    settings.gateway_client_keys ?? []
+  );
+  const [clientUsage, setClientUsage] = useState<ClientUsageAggregate[]>([]);
+  const [ipAllowlist, setIpAllowlist] = useState<string>(
+    (settings.gateway_ip_allowlist ?? []).join("\n")
+  );
 
   useEffect(() => {
     setListenMode(settings.gateway_listen_mode);
@@ -86,6 +127,48 @@ export function NetworkSettingsCard({
     setCustomAddress(settings.gateway_custom_listen_address);
   }, [settings.gateway_custom_listen_address]);
 
+  useEffect(() => {
+    setAccessToken(settings.gateway_access_token ?? "");
+    setSavedAccessToken(settings.gateway_access_token ?? "");
+  }, [settings.gateway_access_token]);
+
+  useEffect(() => {
+    setTlsEnabled(settings.gateway_tls_enabled ?? false);
+  }, [settings.gateway_tls_enabled]);
+
+  useEffect(() => {
+    setTlsHosts((settings.gateway_tls_hosts ?? []).join("\n"));
+  }, [settings.gateway_tls_hosts]);
+
+  useEffect(() => {
+    setExtraListeners(settings.gateway_extra_listeners ?? []);
+  }, [settings.gateway_extra_listeners]);
+
+  useEffect(() => {
+    setClientKeys(settings.gateway_client_keys ?? []);
+  }, [settings.gateway_client_keys]);
+
+  useEffect(() => {
+    setIpAllowlist((settings.gateway_ip_allowlist ?? []).join("\n"));
+  }, [settings.gateway_ip_allowlist]);
+
+  useEffect(() => {
+    if (!available || clientKeys.length === 0) return;
+    let cancelled = false;
+    requestLogsClientUsage(30)
+      .then((rows) => {
+        if (cancelled) return;
+        setClientUsage(rows ?? []);
+      })
+      .catch(() => {
+        if (cancelled) return;
+        setClientUsage([]);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [available, clientKeys.length]);
+
   useEffect(() => {
     if (!available) return;
     if (listenMode !== "wsl_auto") return;
@@ -197,6 +280,238 @@ export function NetworkSettingsCard({
     }
   }
 
+  async function commitAccessToken(next: string) {
+    if (!available) return;
+    const token = next.trim();
+    if (token === savedAccessToken) return;
+    if (token && token.length < 16) {
+      toast("访问令牌至少 16 个字符");
+      setAccessToken(savedAccessToken);
+      return;
+    }
+
+    try {
+      const updated = await settingsGatewayAccessTokenSet(token);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setAccessToken(savedAccessToken);
+        return;
+      }
+      setAccessToken(updated.gateway_access_token);
+      setSavedAccessToken(updated.gateway_access_token);
+      logToConsole("info", "更新网关访问令牌", { enabled: Boolean(updated.gateway_access_token) });
+      toast(updated.gateway_access_token ? "访问令牌已保存" : "已关闭访问令牌校验");
+    } catch (err) {
+      logToConsole("error", "更新网关访问令牌失败", { error: String(err) });
+      toast("更新访问令牌失败：请稍后重试");
+      setAccessToken(savedAccessToken);
+    }
+  }
+
+  async function generateAccessToken() {
+    if (!available) return;
+    try {
+      const token = await gatewayAccessTokenGenerate();
+      if (!token) return;
+      setAccessToken(token);
+      await commitAccessToken(token);
+    } catch (err) {
+      logToConsole("error", "生成网关访问令牌失败", { error: String(err) });
+      toast("生成访问令牌失败：请稍后重试");
+    }
+  }
+
+  async function commitTlsEnabled(next: boolean) {
+    if (!available) return;
+    setTlsEnabled(next);
+
+    try {
+      const updated = await settingsGatewayTlsSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setTlsEnabled(settings.gateway_tls_enabled ?? false);
+        return;
+      }
+
+      logToConsole("info", "更新网关 HTTPS", { enabled: next, running: gateway?.running ?? false });
+      await restartGatewayIfRunning(updated.preferred_port);
+    } catch (err) {
+      logToConsole("error", "更新网关 HTTPS 失败", { error: String(err), next });
+      toast("更新 HTTPS 设置失败：请稍后重试");
+      setTlsEnabled(settings.gateway_tls_enabled ?? false);
+    }
+  }
+
+  async function commitTlsHosts() {
+    if (!available) return;
+    const previous = settings.gateway_tls_hosts ?? [];
+    const entries = tlsHosts
+      .split(/[\s,]+/)
+      .map((v) => v.trim())
+      .filter(Boolean);
+    if (entries.join("\n") === previous.join("\n")) return;
+
+    try {
+      const updated = await settingsGatewayTlsHostsSet(entries);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setTlsHosts(previous.join("\n"));
+        return;
+      }
+      setTlsHosts(updated.gateway_tls_hosts.join("\n"));
+      logToConsole("info", "更新网关 HTTPS 证书地址", { hosts: updated.gateway_tls_hosts });
+      toast("证书地址已保存：CA 会重新生成，需重新导出并信任");
+      await restartGatewayIfRunning(updated.preferred_port);
+    } catch (err) {
+      logToConsole("error", "更新网关 HTTPS 证书地址失败", { error: String(err) });
+      toast(`更新证书地址失败：${String(err)}`);
+      setTlsHosts(previous.join("\n"));
+    }
+  }
+
+  async function commitExtraListeners(next: GatewayExtraListener[]) {
+    if (!available) return;
+    const previous = settings.gateway_extra_listeners ?? [];
+    setExtraListeners(next);
+
+    try {
+      const updated = await settingsGatewayExtraListenersSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setExtraListeners(previous);
+        return;
+      }
+      setExtraListeners(updated.gateway_extra_listeners);
+      logToConsole("info", "更新网关专用端口", { listeners: updated.gateway_extra_listeners });
+      await restartGatewayIfRunning(updated.preferred_port);
+    } catch (err) {
+      logToConsole("error", "更新网关专用端口失败", { error: String(err) });
+      toast(`更新专用端口失败：${String(err)}`);
+      setExtraListeners(previous);
+    }
+  }
+
+  function addExtraListener() {
+    const used = new Set([settings.preferred_port, ...extraListeners.map((l) => l.port)]);
+    let port = settings.preferred_port + 10;
+    while (used.has(port) && port < 65535) port += 1;
+    void commitExtraListeners([...extraListeners, { port, cli_key: "codex", enabled: true }]);
+  }
+
+  function updateExtraListener(index: number, patch: Partial<GatewayExtraListener>) {
+    const next = extraListeners.map((item, i) => (i === index ? { ...item, ...patch } : item));
+    void commitExtraListeners(next);
+  }
+
+  async function commitClientKeys(next: GatewayClientKey[]) {
+    if (!available) return;
+    const previous = settings.gateway_client_keys ?? [];
+    setClientKeys(next);
+
+    try {
+      const updated = await settingsGatewayClientKeysSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setClientKeys(previous);
+        return;
+      }
+      setClientKeys(updated.gateway_client_keys);
+      logToConsole("info", "更新网关客户端 Key", { count: updated.gateway_client_keys.length });
+    } catch (err) {
+      logToConsole("error", "更新网关客户端 Key 失败", { error: String(err) });
+      toast(`更新客户端 Key 失败：${String(err)}`);
+      setClientKeys(previous);
+    }
+  }
+
+  async function commitIpAllowlist() {
+    if (!available) return;
+    const previous = settings.gateway_ip_allowlist ?? [];
+    const entries = ipAllowlist
+      .split(/[\s,]+/)
+      .map((v) => v.trim())
+      .filter(Boolean);
+    if (entries.join("\n") === previous.join("\n")) return;
+
+    try {
+      const updated = await settingsGatewayIpAllowlistSet(entries);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setIpAllowlist(previous.join("\n"));
+        return;
+      }
+      setIpAllowlist(updated.gateway_ip_allowlist.join("\n"));
+      logToConsole("info", "更新网关 IP 白名单", { entries: updated.gateway_ip_allowlist });
+      toast(updated.gateway_ip_allowlist.length ? "IP 白名单已保存" : "已关闭 IP 白名单");
+    } catch (err) {
+      logToConsole("error", "更新网关 IP 白名单失败", { error: String(err) });
+      toast(`更新 IP 白名单失败：${String(err)}`);
+      setIpAllowlist(previous.join("\n"));
+    }
+  }
+
+  async function commitDrainTimeout(raw: string, input: HTMLInputElement) {
+    if (!available) return;
+    const previous = settings.gateway_drain_timeout_seconds ?? 30;
+    const seconds = Number(raw);
+    if (seconds === previous) return;
+    if (!Number.isInteger(seconds) || seconds < 0 || seconds > 600) {
+      toast("排空超时需在 0-600 秒之间");
+      input.value = String(previous);
+      return;
+    }
+
+    try {
+      const updated = await settingsGatewayDrainTimeoutSet(seconds);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        input.value = String(previous);
+        return;
+      }
+      logToConsole("info", "更新网关排空超时", { seconds });
+    } catch (err) {
+      logToConsole("error", "更新网关排空超时失败", { error: String(err) });
+      toast("更新排空超时失败：请稍后重试");
+      input.value = String(previous);
+    }
+  }
+
+  async function addClientKey() {
+    if (!available) return;
+    try {
+      const key = await gatewayAccessTokenGenerate();
+      if (!key) return;
+      const id = `client-${Date.now().toString(36)}`;
+      const name = `设备 ${clientKeys.length + 1}`;
+      await commitClientKeys([
+        ...clientKeys,
+        { id, name, key, enabled: true, rate_limit_per_minute: 0 },
+      ]);
+    } catch (err) {
+      logToConsole("error", "生成网关客户端 Key 失败", { error: String(err) });
+      toast("生成客户端 Key 失败：请稍后重试");
+    }
+  }
+
+  function updateClientKey(index: number, patch: Partial<GatewayClientKey>) {
+    const next = clientKeys.map((item, i) => (i === index ? { ...item, ...patch } : item));
+    void commitClientKeys(next);
+  }
+
+  async function exportCa() {
+    if (!available) return;
+    try {
+      const exported = await gatewayTlsCaExport();
+      if (!exported) return;
+      setCaPath(exported.path);
+      await navigator.clipboard.writeText(exported.pem);
+      toast("已复制 CA 证书（PEM）");
+    } catch (err) {
+      logToConsole("error", "导出网关 CA 证书失败", { error: String(err) });
+      toast("导出 CA 证书失败：请稍后重试");
+    }
+  }
+
   return (
     <Card className="md:col-span-2 relative overflow-hidden">
       <div className="absolute top-0 right-0 p-4 opacity-5">
@@ -255,13 +570,282 @@ export function NetworkSettingsCard({
               </div>
             </SettingsRow>
 
+            <SettingsRow label="HTTPS">
+              <div className="flex items-center gap-2">
+                <Switch
+                  checked={tlsEnabled}
+                  onCheckedChange={(checked) => void commitTlsEnabled(checked)}
+                  disabled={saving}
+                />
+                <Button
+                  variant="secondary"
+                  size="sm"
+                  onClick={() => void exportCa()}
+                  disabled={!tlsEnabled}
+                >
+                  导出 CA 证书
+                </Button>
+              </div>
+            </SettingsRow>
+
+            {tlsEnabled && listenMode !== "localhost" ? (
+              <SettingsRow label="证书地址">
+                <Textarea
+                  mono
+                  value={tlsHosts}
+                  placeholder={"局域网访问用的主机名或 IP（自动包含回环与当前网卡地址）\n例如 devbox.local"}
+                  onChange={(e) => setTlsHosts(e.currentTarget.value)}
+                  onBlur={() => void commitTlsHosts()}
+                  disabled={saving}
+                  rows={3}
+                  className="w-64 text-xs"
+                />
+              </SettingsRow>
+            ) : null}
+
+            {tlsEnabled && caPath ? (
+              <div className="text-xs text-slate-500 break-all px-1 pb-2">
+                CA 证书位置：<span className="font-mono">{caPath}</span>
+                （需加入系统或 CLI 的信任列表，例如 NODE_EXTRA_CA_CERTS / SSL_CERT_FILE）
+              </div>
+            ) : null}
+
+            <SettingsRow label="停止时排空（秒）">
+              <Input
+                type="number"
+                defaultValue={settings.gateway_drain_timeout_seconds ?? 30}
+                min={0}
+                max={600}
+                title="停止网关时等待进行中的请求 / 流式响应结束的最长时间，0 = 立即停止"
+                onBlur={(e) => void commitDrainTimeout(e.currentTarget.value, e.currentTarget)}
+                disabled={saving}
+                className="font-mono w-24"
+              />
+            </SettingsRow>
+
+            <SettingsRow label="专用端口">
+              <div className="flex flex-col items-end gap-2">
+                {extraListeners.map((item, index) => {
+                  const active = gateway?.extra_listeners?.find((l) => l.port === item.port);
+                  return (
+                    <div key={`${item.port}-${index}`} className="flex items-center gap-2">
+                      <Select
+                        value={item.cli_key}
+                        onChange={(e) =>
+                          updateExtraListener(index, { cli_key: e.currentTarget.value as CliKey })
+                        }
+                        disabled={saving}
+                        className="w-28"
+                      >
+                        <option value="claude">claude</option>
+                        <option value="codex">codex</option>
+                        <option value="gemini">gemini</option>
+                      </Select>
+                      <Input
+                        type="number"
+                        defaultValue={item.port}
+                        min={1024}
+                        max={65535}
+                        onBlur={(e) => {
+                          const port = Number(e.currentTarget.value);
+                          if (port === item.port) return;
+                          if (!Number.isInteger(port) || port < 1024 || port > 65535) {
+                            toast("端口需在 1024-65535 之间");
+                            e.currentTarget.value = String(item.port);
+                            return;
+                          }
+                          updateExtraListener(index, { port });
+                        }}
+                        disabled={saving}
+                        className="font-mono w-24"
+                      />
+                      <Switch
+                        checked={item.enabled}
+                        onCheckedChange={(checked) =>
+                          updateExtraListener(index, { enabled: checked })
+                        }
+                        disabled={saving}
+                      />
+                      <span className="font-mono text-xs text-slate-500 w-44 truncate">
+                        {active
+                          ? active.base_url
+                          : gateway?.running && item.enabled
+                            ? "绑定失败（端口被占用？）"
+                            : "—"}
+                      </span>
+                      <Button
+                        variant="secondary"
+                        size="sm"
+                        onClick={() =>
+                          void commitExtraListeners(extraListeners.filter((_, i) => i !== index))
+                        }
+                        disabled={saving}
+                      >
+                        <Trash2 className="h-3.5 w-3.5" />
+                      </Button>
+                    </div>
+                  );
+                })}
+                <Button
+                  variant="secondary"
+                  size="sm"
+                  onClick={addExtraListener}
+                  disabled={saving || extraListeners.length >= 8}
+                >
+                  <Plus className="h-3.5 w-3.5" />
+                  添加专用端口（根路径直接映射到指定 CLI）
+                </Button>
+              </div>
+            </SettingsRow>
+
+            {listenMode !== "localhost" ? (
+              <SettingsRow label="访问令牌">
+                <div className="flex items-center gap-2">
+                  <Input
+                    type="password"
+                    value={accessToken}
+                    placeholder="留空则不校验（仅本机请求免校验）"
+                    onChange={(e) => setAccessToken(e.currentTarget.value)}
+                    onBlur={() => void commitAccessToken(accessToken)}
+                    disabled={saving}
+                    className="font-mono w-64"
+                  />
+                  <Button
+                    variant="secondary"
+                    size="sm"
+                    onClick={() => void generateAccessToken()}
+                    disabled={saving}
+                  >
+                    生成
+                  </Button>
+                  <Button
+                    variant="secondary"
+                    size="sm"
+                    onClick={() => {
+                      if (!savedAccessToken) return;
+                      void navigator.clipboard
+                        .writeText(savedAccessToken)
+                        .then(() => toast("已复制访问令牌"));
+                    }}
+                    disabled={!savedAccessToken}
+                  >
+                    复制
+                  </Button>
+                </div>
+              </SettingsRow>
+            ) : null}
+
+            {listenMode !== "localhost" ? (
+              <SettingsRow label="IP 白名单">
+                <Textarea
+                  mono
+                  value={ipAllowlist}
+                  placeholder={"留空则不限制来源地址\n例如 192.168.1.0/24"}
+                  onChange={(e) => setIpAllowlist(e.currentTarget.value)}
+                  onBlur={() => void commitIpAllowlist()}
+                  disabled={saving}
+                  rows={3}
+                  className="w-64 text-xs"
+                />
+              </SettingsRow>
+            ) : null}
+
+            {listenMode !== "localhost" ? (
+              <SettingsRow label="客户端 Key">
+                <div className="flex flex-col items-end gap-2">
+                  {clientKeys.map((item, index) => {
+                    const usage = clientUsage.find((u) => u.client_id === item.id);
+                    return (
+                      <div key={item.id} className="flex items-center gap-2">
+                        <Input
+                          defaultValue={item.name}
+                          maxLength={64}
+                          onBlur={(e) => {
+                            const name = e.currentTarget.value.trim();
+                            if (name === item.name) return;
+                            if (!name) {
+                              e.currentTarget.value = item.name;
+                              return;
+                            }
+                            updateClientKey(index, { name });
+                          }}
+                          disabled={saving}
+                          className="w-32"
+                        />
+                        <Input
+                          type="number"
+                          defaultValue={item.rate_limit_per_minute}
+                          min={0}
+                          title="每分钟请求上限（0 = 不限）"
+                          onBlur={(e) => {
+                            const limit = Number(e.currentTarget.value);
+                            if (limit === item.rate_limit_per_minute) return;
+                            if (!Number.isInteger(limit) || limit < 0) {
+                              toast("速率限制需为非负整数");
+                              e.currentTarget.value = String(item.rate_limit_per_minute);
+                              return;
+                            }
+                            updateClientKey(index, { rate_limit_per_minute: limit });
+                          }}
+                          disabled={saving}
+                          className="font-mono w-20"
+                        />
+                        <Switch
+                          checked={item.enabled}
+                          onCheckedChange={(checked) =>
+                            updateClientKey(index, { enabled: checked })
+                          }
+                          disabled={saving}
+                        />
+                        <span className="text-xs text-slate-500 w-36 truncate">
+                          {usage
+                            ? `${usage.request_count} 次 · $${usage.total_cost_usd.toFixed(2)}`
+                            : "30 天内无请求"}
+                        </span>
+                        <Button
+                          variant="secondary"
+                          size="sm"
+                          onClick={() =>
+                            void navigator.clipboard
+                              .writeText(item.key)
+                              .then(() => toast(`已复制「${item.name}」的 Key`))
+                          }
+                        >
+                          复制
+                        </Button>
+                        <Button
+                          variant="secondary"
+                          size="sm"
+                          onClick={() =>
+                            void commitClientKeys(clientKeys.filter((_, i) => i !== index))
+                          }
+                          disabled={saving}
+                        >
+                          <Trash2 className="h-3.5 w-3.5" />
+                        </Button>
+                      </div>
+                    );
+                  })}
+                  <Button
+                    variant="secondary"
+                    size="sm"
+                    onClick={() => void addClientKey()}
+                    disabled={saving || clientKeys.length >= 32}
+                  >
+                    <Plus className="h-3.5 w-3.5" />
+                    添加客户端 Key（按设备限流并统计用量）
+                  </Button>
+                </div>
+              </SettingsRow>
+            ) : null}
+
             {listenMode === "lan" ? (
               <div className="mt-3 rounded-lg bg-amber-50 p-3 text-sm text-amber-800 border border-amber-100 flex items-start gap-2">
                 <AlertTriangle className="h-4 w-4 mt-0.5 shrink-0" />
                 <div>
                   <div className="font-medium">安全提示</div>
                   <div className="text-xs mt-0.5 text-amber-700">
-                    局域网模式会将网关暴露在本机网络接口上。请确保防火墙与访问控制策略符合你的安全要求。
+                    局域网模式会将网关暴露在本机网络接口上。建议设置访问令牌：其他设备需将其作为 API Key（Bearer / x-api-key）发送。
                   </div>
                 </div>
               </div>
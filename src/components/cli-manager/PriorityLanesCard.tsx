@@ -0,0 +1,190 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { Layers } from "lucide-react";
+import type { AppSettings } from "../../services/settings";
+import { providersList, type ProviderSummary } from "../../services/providers";
+import {
+  settingsPriorityLanesSet,
+  type PriorityLanesInput,
+} from "../../services/settingsPriorityLanes";
+import { logToConsole } from "../../services/consoleLog";
+import { Card } from "../../ui/Card";
+import { Input } from "../../ui/Input";
+import { Select } from "../../ui/Select";
+import { SettingsRow } from "../../ui/SettingsRow";
+import { Switch } from "../../ui/Switch";
+
+export type PriorityLanesCardProps = {
+  available: boolean;
+  settings: AppSettings;
+};
+
+function fromSettings(settings: AppSettings): PriorityLanesInput {
+  return {
+    priorityLanesEnabled: settings.priority_lanes_enabled ?? false,
+    backgroundLaneMaxConcurrency: settings.background_lane_max_concurrency ?? 2,
+    backgroundLaneQueueTimeoutSeconds: settings.background_lane_queue_timeout_seconds ?? 30,
+    backgroundLaneProviderId: settings.background_lane_provider_id ?? null,
+  };
+}
+
+export function PriorityLanesCard({ available, settings }: PriorityLanesCardProps) {
+  const [draft, setDraft] = useState<PriorityLanesInput>(() => fromSettings(settings));
+  const [providers, setProviders] = useState<ProviderSummary[]>([]);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setDraft(fromSettings(settings));
+  }, [settings]);
+
+  useEffect(() => {
+    if (!available) return;
+    let cancelled = false;
+    Promise.all([providersList("claude"), providersList("codex"), providersList("gemini")])
+      .then((lists) => {
+        if (!cancelled) setProviders(lists.flatMap((list) => list ?? []));
+      })
+      .catch((err) => {
+        logToConsole("error", "读取供应商列表失败", { error: String(err) });
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [available]);
+
+  async function commit(patch: Partial<PriorityLanesInput>) {
+    if (!available || saving) return;
+    const previous = draft;
+    const next = { ...draft, ...patch };
+    if (next.backgroundLaneMaxConcurrency < 0 || next.backgroundLaneMaxConcurrency > 64) {
+      toast("后台并发上限必须为 0-64");
+      setDraft(previous);
+      return;
+    }
+    if (
+      next.backgroundLaneQueueTimeoutSeconds < 0 ||
+      next.backgroundLaneQueueTimeoutSeconds > 600
+    ) {
+      toast("排队超时必须为 0-600 秒");
+      setDraft(previous);
+      return;
+    }
+
+    setDraft(next);
+    setSaving(true);
+    try {
+      const updated = await settingsPriorityLanesSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setDraft(previous);
+        return;
+      }
+      setDraft(fromSettings(updated));
+      logToConsole("info", "更新请求优先级通道", next);
+    } catch (err) {
+      logToConsole("error", "更新请求优先级通道失败", { error: String(err) });
+      toast(`更新请求优先级通道失败：${String(err)}`);
+      setDraft(previous);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  const disabled = !available || saving;
+
+  return (
+    <Card className="md:col-span-2">
+      <div className="mb-4 border-b border-slate-100 pb-4">
+        <div className="text-lg font-semibold text-slate-900 flex items-center gap-2">
+          <Layers className="h-5 w-5 text-blue-500" />
+          请求优先级通道
+        </div>
+        <p className="mt-1 text-sm text-slate-500">
+          将标题生成、话题检测、摘要等后台请求（或携带 x-aio-lane: background 请求头的请求）
+          单独排队并可改走指定供应商，高负载时保证主对话的响应速度。
+        </p>
+      </div>
+
+      {!available ? (
+        <div className="text-sm text-slate-600 bg-slate-50 p-4 rounded-lg">
+          仅在 Tauri Desktop 环境可用
+        </div>
+      ) : (
+        <div className="space-y-1">
+          <SettingsRow label="启用优先级通道">
+            <Switch
+              checked={draft.priorityLanesEnabled}
+              onCheckedChange={(checked) => void commit({ priorityLanesEnabled: checked })}
+              disabled={disabled}
+            />
+          </SettingsRow>
+          <SettingsRow label="后台并发上限（0=不排队）">
+            <Input
+              type="number"
+              value={draft.backgroundLaneMaxConcurrency}
+              onChange={(e) => {
+                const next = e.currentTarget.valueAsNumber;
+                if (Number.isFinite(next)) {
+                  setDraft((prev) => ({ ...prev, backgroundLaneMaxConcurrency: next }));
+                }
+              }}
+              onBlur={(e) => {
+                const next = e.currentTarget.valueAsNumber;
+                if (next === settings.background_lane_max_concurrency) return;
+                void commit({ backgroundLaneMaxConcurrency: next });
+              }}
+              style={{ width: "5rem" }}
+              min={0}
+              max={64}
+              disabled={disabled}
+            />
+          </SettingsRow>
+          <SettingsRow label="排队超时（超时后直接转发）">
+            <div className="flex items-center gap-2">
+              <Input
+                type="number"
+                value={draft.backgroundLaneQueueTimeoutSeconds}
+                onChange={(e) => {
+                  const next = e.currentTarget.valueAsNumber;
+                  if (Number.isFinite(next)) {
+                    setDraft((prev) => ({ ...prev, backgroundLaneQueueTimeoutSeconds: next }));
+                  }
+                }}
+                onBlur={(e) => {
+                  const next = e.currentTarget.valueAsNumber;
+                  if (next === settings.background_lane_queue_timeout_seconds) return;
+                  void commit({ backgroundLaneQueueTimeoutSeconds: next });
+                }}
+                style={{ width: "5rem" }}
+                min={0}
+                max={600}
+                disabled={disabled}
+              />
+              <span className="w-8 text-sm text-slate-500">秒</span>
+            </div>
+          </SettingsRow>
+          <SettingsRow label="后台请求优先供应商">
+            <Select
+              value={
+                draft.backgroundLaneProviderId == null ? "" : String(draft.backgroundLaneProviderId)
+              }
+              onChange={(e) => {
+                const value = e.currentTarget.value;
+                void commit({ backgroundLaneProviderId: value ? Number(value) : null });
+              }}
+              disabled={disabled}
+              className="w-56"
+            >
+              <option value="">与主对话相同顺序</option>
+              {providers.map((provider) => (
+                <option key={provider.id} value={String(provider.id)}>
+                  {provider.name}（{provider.cli_key}）
+                </option>
+              ))}
+            </Select>
+          </SettingsRow>
+        </div>
+      )}
+    </Card>
+  );
+}
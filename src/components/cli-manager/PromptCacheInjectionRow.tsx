@@ -0,0 +1,54 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings } from "../../services/settings";
+import { settingsPromptCacheInjectionSet } from "../../services/settingsPromptCache";
+import { logToConsole } from "../../services/consoleLog";
+import { SettingsRow } from "../../ui/SettingsRow";
+import { Switch } from "../../ui/Switch";
+
+export type PromptCacheInjectionRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function PromptCacheInjectionRow({ available, settings }: PromptCacheInjectionRowProps) {
+  const persisted = settings?.enable_prompt_cache_injection ?? false;
+  const [enabled, setEnabled] = useState(persisted);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setEnabled(persisted);
+  }, [persisted]);
+
+  async function persist(next: boolean) {
+    if (!available || saving) return;
+    setEnabled(next);
+    setSaving(true);
+    try {
+      const updated = await settingsPromptCacheInjectionSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setEnabled(persisted);
+        return;
+      }
+      setEnabled(updated.enable_prompt_cache_injection ?? false);
+      logToConsole("info", "更新自动缓存断点注入", { enabled: next });
+    } catch (err) {
+      logToConsole("error", "更新自动缓存断点注入失败", { error: String(err) });
+      toast(`更新自动缓存断点注入失败：${String(err)}`);
+      setEnabled(persisted);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="自动注入 Prompt 缓存断点">
+      <Switch
+        checked={enabled}
+        onCheckedChange={(checked) => void persist(checked)}
+        disabled={!available || saving}
+      />
+    </SettingsRow>
+  );
+}
@@ -0,0 +1,162 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { gatewayErrorCacheClear } from "../../services/gateway";
+import type { AppSettings, RecentErrorCacheConfig } from "../../services/settings";
+import { settingsRecentErrorCacheSet } from "../../services/settingsRecentErrorCache";
+import { logToConsole } from "../../services/consoleLog";
+import { Button } from "../../ui/Button";
+import { Input } from "../../ui/Input";
+import { SettingsRow } from "../../ui/SettingsRow";
+
+const DEFAULT_CONFIG: RecentErrorCacheConfig = {
+  error_max_ttl_seconds: 60 * 60,
+  trace_dedup_ttl_seconds: 10,
+};
+const MAX_ERROR_TTL_SECONDS = 24 * 60 * 60;
+const MAX_TRACE_DEDUP_TTL_SECONDS = 5 * 60;
+
+export type RecentErrorCacheRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function RecentErrorCacheRow({ available, settings }: RecentErrorCacheRowProps) {
+  const persisted = settings?.recent_error_cache ?? DEFAULT_CONFIG;
+  const [errorTtl, setErrorTtl] = useState(persisted.error_max_ttl_seconds);
+  const [traceTtl, setTraceTtl] = useState(persisted.trace_dedup_ttl_seconds);
+  const [saving, setSaving] = useState(false);
+  const [clearing, setClearing] = useState(false);
+
+  useEffect(() => {
+    setErrorTtl(persisted.error_max_ttl_seconds);
+    setTraceTtl(persisted.trace_dedup_ttl_seconds);
+  }, [persisted.error_max_ttl_seconds, persisted.trace_dedup_ttl_seconds]);
+
+  function reset() {
+    setErrorTtl(persisted.error_max_ttl_seconds);
+    setTraceTtl(persisted.trace_dedup_ttl_seconds);
+  }
+
+  async function commit(next: RecentErrorCacheConfig) {
+    if (!available || saving) return;
+    if (
+      next.error_max_ttl_seconds === persisted.error_max_ttl_seconds &&
+      next.trace_dedup_ttl_seconds === persisted.trace_dedup_ttl_seconds
+    ) {
+      return;
+    }
+    if (
+      !Number.isInteger(next.error_max_ttl_seconds) ||
+      next.error_max_ttl_seconds < 0 ||
+      next.error_max_ttl_seconds > MAX_ERROR_TTL_SECONDS
+    ) {
+      toast(`错误缓存上限必须为 0-${MAX_ERROR_TTL_SECONDS} 秒`);
+      reset();
+      return;
+    }
+    if (
+      !Number.isInteger(next.trace_dedup_ttl_seconds) ||
+      next.trace_dedup_ttl_seconds < 1 ||
+      next.trace_dedup_ttl_seconds > MAX_TRACE_DEDUP_TTL_SECONDS
+    ) {
+      toast(`重复请求合并窗口必须为 1-${MAX_TRACE_DEDUP_TTL_SECONDS} 秒`);
+      reset();
+      return;
+    }
+    setSaving(true);
+    try {
+      const updated = await settingsRecentErrorCacheSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        reset();
+        return;
+      }
+      const saved = updated.recent_error_cache ?? next;
+      setErrorTtl(saved.error_max_ttl_seconds);
+      setTraceTtl(saved.trace_dedup_ttl_seconds);
+      logToConsole("info", "更新错误缓存设置", saved);
+    } catch (err) {
+      logToConsole("error", "更新错误缓存设置失败", { error: String(err) });
+      toast(`更新错误缓存设置失败：${String(err)}`);
+      reset();
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  async function clearCache() {
+    if (!available || clearing) return;
+    setClearing(true);
+    try {
+      const cleared = await gatewayErrorCacheClear();
+      if (cleared == null) {
+        toast("清除错误缓存失败");
+        return;
+      }
+      toast(cleared > 0 ? `已清除 ${cleared} 条缓存的错误` : "当前没有缓存的错误");
+    } finally {
+      setClearing(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="错误缓存">
+      <div className="flex items-center gap-2">
+        <Input
+          type="number"
+          value={errorTtl}
+          onChange={(e) => {
+            const next = e.currentTarget.valueAsNumber;
+            if (Number.isFinite(next)) setErrorTtl(next);
+          }}
+          onBlur={(e) =>
+            void commit({
+              error_max_ttl_seconds: e.currentTarget.valueAsNumber,
+              trace_dedup_ttl_seconds: persisted.trace_dedup_ttl_seconds,
+            })
+          }
+          onKeyDown={(e) => {
+            if (e.key === "Enter") e.currentTarget.blur();
+          }}
+          style={{ width: "5rem" }}
+          min={0}
+          max={MAX_ERROR_TTL_SECONDS}
+          disabled={!available || saving}
+          title="所有 Provider 均不可用时，相同请求在该时长内（且不超过 Retry-After）直接返回缓存的错误；手动重置熔断后失效；0 表示不缓存"
+        />
+        <span className="w-8 text-sm text-slate-500">秒</span>
+        <Input
+          type="number"
+          value={traceTtl}
+          onChange={(e) => {
+            const next = e.currentTarget.valueAsNumber;
+            if (Number.isFinite(next)) setTraceTtl(next);
+          }}
+          onBlur={(e) =>
+            void commit({
+              error_max_ttl_seconds: persisted.error_max_ttl_seconds,
+              trace_dedup_ttl_seconds: e.currentTarget.valueAsNumber,
+            })
+          }
+          onKeyDown={(e) => {
+            if (e.key === "Enter") e.currentTarget.blur();
+          }}
+          style={{ width: "4rem" }}
+          min={1}
+          max={MAX_TRACE_DEDUP_TTL_SECONDS}
+          disabled={!available || saving}
+          title="重复请求合并窗口：该时间内的相同请求共用一个 trace id"
+        />
+        <span className="w-8 text-sm text-slate-500">秒</span>
+        <Button
+          variant="secondary"
+          size="sm"
+          onClick={() => void clearCache()}
+          disabled={!available || clearing}
+        >
+          清除
+        </Button>
+      </div>
+    </SettingsRow>
+  );
+}
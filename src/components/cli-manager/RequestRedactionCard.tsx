@@ -0,0 +1,145 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { ShieldCheck } from "lucide-react";
+import type { AppSettings, RedactionBuiltin } from "../../services/settings";
+import {
+  settingsRequestRedactionSet,
+  type RequestRedactionInput,
+} from "../../services/settingsRequestRedaction";
+import { logToConsole } from "../../services/consoleLog";
+import { Card } from "../../ui/Card";
+import { SettingsRow } from "../../ui/SettingsRow";
+import { Switch } from "../../ui/Switch";
+import { Textarea } from "../../ui/Textarea";
+import { ExcludeReasoningFromLogsRow } from "./ExcludeReasoningFromLogsRow";
+
+export type RequestRedactionCardProps = {
+  available: boolean;
+  settings: AppSettings;
+};
+
+const BUILTINS: { key: RedactionBuiltin; label: string }[] = [
+  { key: "api_key", label: "API Key / Token" },
+  { key: "aws_secret", label: "AWS 密钥" },
+  { key: "email", label: "邮箱地址" },
+];
+
+function fromSettings(settings: AppSettings): RequestRedactionInput {
+  return {
+    enableRequestRedaction: settings.enable_request_redaction ?? false,
+    requestRedactionBuiltins: settings.request_redaction_builtins ?? [
+      "api_key",
+      "aws_secret",
+      "email",
+    ],
+    requestRedactionCustomPatterns: settings.request_redaction_custom_patterns ?? [],
+  };
+}
+
+export function RequestRedactionCard({ available, settings }: RequestRedactionCardProps) {
+  const [saved, setSaved] = useState<RequestRedactionInput>(() => fromSettings(settings));
+  const [patternsDraft, setPatternsDraft] = useState<string>(
+    saved.requestRedactionCustomPatterns.join("\n")
+  );
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    const next = fromSettings(settings);
+    setSaved(next);
+    setPatternsDraft(next.requestRedactionCustomPatterns.join("\n"));
+  }, [settings]);
+
+  async function commit(patch: Partial<RequestRedactionInput>) {
+    if (!available || saving) return;
+    const next = { ...saved, ...patch };
+    setSaving(true);
+    try {
+      const updated = await settingsRequestRedactionSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      const normalized = fromSettings(updated);
+      setSaved(normalized);
+      setPatternsDraft(normalized.requestRedactionCustomPatterns.join("\n"));
+      logToConsole("info", "更新请求脱敏设置", next);
+    } catch (err) {
+      logToConsole("error", "更新请求脱敏设置失败", { error: String(err) });
+      toast(`更新请求脱敏设置失败：${String(err)}`);
+      setPatternsDraft(saved.requestRedactionCustomPatterns.join("\n"));
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  function toggleBuiltin(key: RedactionBuiltin, checked: boolean) {
+    const rest = saved.requestRedactionBuiltins.filter((item) => item !== key);
+    void commit({ requestRedactionBuiltins: checked ? [...rest, key] : rest });
+  }
+
+  function commitPatterns() {
+    const patterns = patternsDraft
+      .split("\n")
+      .map((line) => line.trim())
+      .filter(Boolean);
+    if (patterns.join("\n") === saved.requestRedactionCustomPatterns.join("\n")) return;
+    void commit({ requestRedactionCustomPatterns: patterns });
+  }
+
+  const disabled = !available || saving;
+
+  return (
+    <Card className="md:col-span-2">
+      <div className="mb-4 border-b border-slate-100 pb-4">
+        <div className="text-lg font-semibold text-slate-900 flex items-center gap-2">
+          <ShieldCheck className="h-5 w-5 text-blue-500" />
+          请求脱敏
+        </div>
+        <p className="mt-1 text-sm text-slate-500">
+          转发前扫描请求体中的字符串，将密钥、邮箱等敏感内容替换为 [REDACTED:类型]，
+          命中次数记录在请求日志的特殊设置中。签名与加密字段不会被修改。
+        </p>
+      </div>
+
+      {!available ? (
+        <div className="text-sm text-slate-600 bg-slate-50 p-4 rounded-lg">
+          仅在 Tauri Desktop 环境可用
+        </div>
+      ) : (
+        <div className="space-y-3">
+          <SettingsRow label="启用请求脱敏">
+            <Switch
+              checked={saved.enableRequestRedaction}
+              onCheckedChange={(checked) => void commit({ enableRequestRedaction: checked })}
+              disabled={disabled}
+            />
+          </SettingsRow>
+          <div className="flex flex-wrap items-center gap-4">
+            {BUILTINS.map((item) => (
+              <label key={item.key} className="flex items-center gap-2 text-sm text-slate-700">
+                <input
+                  type="checkbox"
+                  checked={saved.requestRedactionBuiltins.includes(item.key)}
+                  onChange={(e) => toggleBuiltin(item.key, e.currentTarget.checked)}
+                  disabled={disabled || !saved.enableRequestRedaction}
+                />
+                {item.label}
+              </label>
+            ))}
+          </div>
+          <Textarea
+            mono
+            value={patternsDraft}
+            placeholder={"自定义正则（每行一个），例如：\nINTERNAL-\\d+"}
+            onChange={(e) => setPatternsDraft(e.currentTarget.value)}
+            onBlur={commitPatterns}
+            disabled={disabled || !saved.enableRequestRedaction}
+            rows={4}
+            className="text-xs"
+          />
+          <ExcludeReasoningFromLogsRow available={available} settings={settings} />
+        </div>
+      )}
+    </Card>
+  );
+}
@@ -0,0 +1,60 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings } from "../../services/settings";
+import { settingsResponseCacheCompletionsSet } from "../../services/settingsResponseCache";
+import { logToConsole } from "../../services/consoleLog";
+import { SettingsRow } from "../../ui/SettingsRow";
+import { Switch } from "../../ui/Switch";
+
+export type ResponseCacheCompletionsRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function ResponseCacheCompletionsRow({
+  available,
+  settings,
+}: ResponseCacheCompletionsRowProps) {
+  const persisted = settings?.response_cache_completions ?? false;
+  const cacheEnabled = (settings?.response_cache_ttl_seconds ?? 300) > 0;
+  const [enabled, setEnabled] = useState(persisted);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setEnabled(persisted);
+  }, [persisted]);
+
+  async function persist(next: boolean) {
+    if (!available || saving) return;
+    setEnabled(next);
+    setSaving(true);
+    try {
+      const updated = await settingsResponseCacheCompletionsSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setEnabled(persisted);
+        return;
+      }
+      setEnabled(updated.response_cache_completions ?? false);
+      logToConsole("info", "更新补全响应缓存设置", { enabled: next });
+    } catch (err) {
+      logToConsole("error", "更新补全响应缓存设置失败", { error: String(err) });
+      toast(`更新补全响应缓存设置失败：${String(err)}`);
+      setEnabled(persisted);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="缓存相同的非流式补全">
+      <span title="相同请求体的非流式 /messages、/chat/completions、/responses、generateContent 直接返回缓存结果（时长同响应缓存）；流式请求不缓存">
+        <Switch
+          checked={enabled}
+          onCheckedChange={(checked) => void persist(checked)}
+          disabled={!available || saving || !cacheEnabled}
+        />
+      </span>
+    </SettingsRow>
+  );
+}
@@ -0,0 +1,78 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings } from "../../services/settings";
+import { settingsResponseCacheTtlSet } from "../../services/settingsResponseCache";
+import { logToConsole } from "../../services/consoleLog";
+import { Input } from "../../ui/Input";
+import { SettingsRow } from "../../ui/SettingsRow";
+
+const DEFAULT_TTL_SECONDS = 300;
+const MAX_TTL_SECONDS = 24 * 60 * 60;
+
+export type ResponseCacheTtlRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function ResponseCacheTtlRow({ available, settings }: ResponseCacheTtlRowProps) {
+  const persisted = settings?.response_cache_ttl_seconds ?? DEFAULT_TTL_SECONDS;
+  const [seconds, setSeconds] = useState(persisted);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setSeconds(persisted);
+  }, [persisted]);
+
+  async function commit(next: number) {
+    if (!available || saving || next === persisted) return;
+    if (!Number.isInteger(next) || next < 0 || next > MAX_TTL_SECONDS) {
+      toast(`响应缓存时长必须为 0-${MAX_TTL_SECONDS} 秒`);
+      setSeconds(persisted);
+      return;
+    }
+    setSaving(true);
+    try {
+      const updated = await settingsResponseCacheTtlSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setSeconds(persisted);
+        return;
+      }
+      setSeconds(updated.response_cache_ttl_seconds ?? next);
+      logToConsole("info", "更新响应缓存时长", {
+        seconds: updated.response_cache_ttl_seconds,
+      });
+    } catch (err) {
+      logToConsole("error", "更新响应缓存时长失败", { error: String(err) });
+      toast(`更新响应缓存时长失败：${String(err)}`);
+      setSeconds(persisted);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="响应缓存">
+      <div className="flex items-center gap-2">
+        <Input
+          type="number"
+          value={seconds}
+          onChange={(e) => {
+            const next = e.currentTarget.valueAsNumber;
+            if (Number.isFinite(next)) setSeconds(next);
+          }}
+          onBlur={(e) => void commit(e.currentTarget.valueAsNumber)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") e.currentTarget.blur();
+          }}
+          style={{ width: "5rem" }}
+          min={0}
+          max={MAX_TTL_SECONDS}
+          disabled={!available || saving}
+          title="按 Provider + 请求指纹缓存模型列表 / 模型详情与 token 计数响应（内存 + 本地数据库），命中时不请求上游；0 表示关闭"
+        />
+        <span className="w-8 text-sm text-slate-500">秒</span>
+      </div>
+    </SettingsRow>
+  );
+}
@@ -0,0 +1,97 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppSettings, StreamToolPauseGraceConfig } from "../../services/settings";
+import { settingsStreamToolPauseGraceSet } from "../../services/settingsStreamToolPauseGrace";
+import { logToConsole } from "../../services/consoleLog";
+import { Input } from "../../ui/Input";
+import { SettingsRow } from "../../ui/SettingsRow";
+import { Switch } from "../../ui/Switch";
+
+const DEFAULT_IDLE_TIMEOUT_SECONDS = 600;
+const MIN_IDLE_TIMEOUT_SECONDS = 10;
+const MAX_IDLE_TIMEOUT_SECONDS = 3600;
+
+export type StreamToolPauseGraceRowProps = {
+  available: boolean;
+  settings: AppSettings | null;
+};
+
+export function StreamToolPauseGraceRow({ available, settings }: StreamToolPauseGraceRowProps) {
+  const enabled = settings?.stream_tool_pause_grace?.enabled ?? true;
+  const persistedSeconds =
+    settings?.stream_tool_pause_grace?.idle_timeout_seconds ?? DEFAULT_IDLE_TIMEOUT_SECONDS;
+  const [seconds, setSeconds] = useState(persistedSeconds);
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    setSeconds(persistedSeconds);
+  }, [persistedSeconds]);
+
+  async function persist(next: StreamToolPauseGraceConfig) {
+    if (!available || saving) return;
+    if (
+      !Number.isInteger(next.idle_timeout_seconds) ||
+      next.idle_timeout_seconds < MIN_IDLE_TIMEOUT_SECONDS ||
+      next.idle_timeout_seconds > MAX_IDLE_TIMEOUT_SECONDS
+    ) {
+      toast(`工具等待空闲超时必须为 ${MIN_IDLE_TIMEOUT_SECONDS}-${MAX_IDLE_TIMEOUT_SECONDS} 秒`);
+      setSeconds(persistedSeconds);
+      return;
+    }
+    setSaving(true);
+    try {
+      const updated = await settingsStreamToolPauseGraceSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setSeconds(persistedSeconds);
+        return;
+      }
+      setSeconds(
+        updated.stream_tool_pause_grace?.idle_timeout_seconds ?? next.idle_timeout_seconds
+      );
+      logToConsole("info", "更新工具等待空闲超时", updated.stream_tool_pause_grace ?? next);
+    } catch (err) {
+      logToConsole("error", "更新工具等待空闲超时失败", { error: String(err) });
+      toast(`更新工具等待空闲超时失败：${String(err)}`);
+      setSeconds(persistedSeconds);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  return (
+    <SettingsRow label="工具等待空闲超时">
+      <div className="flex items-center gap-2">
+        <Input
+          type="number"
+          value={seconds}
+          onChange={(e) => {
+            const next = e.currentTarget.valueAsNumber;
+            if (Number.isFinite(next)) setSeconds(next);
+          }}
+          onBlur={(e) => {
+            const next = e.currentTarget.valueAsNumber;
+            if (next === persistedSeconds) return;
+            void persist({ enabled, idle_timeout_seconds: next });
+          }}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") e.currentTarget.blur();
+          }}
+          style={{ width: "5rem" }}
+          min={MIN_IDLE_TIMEOUT_SECONDS}
+          max={MAX_IDLE_TIMEOUT_SECONDS}
+          disabled={!available || saving}
+          title="Claude 流已收到 ping 但尚无 message_delta（等待服务端工具执行）时改用该空闲超时；仅在设置了流式空闲超时时生效，且不会短于它"
+        />
+        <span className="w-8 text-sm text-slate-500">秒</span>
+        <Switch
+          checked={enabled}
+          onCheckedChange={(checked) =>
+            void persist({ enabled: checked, idle_timeout_seconds: persistedSeconds })
+          }
+          disabled={!available || saving}
+        />
+      </div>
+    </SettingsRow>
+  );
+}
@@ -1,6 +1,7 @@
 import { useEffect, useMemo, useState } from "react";
 import { toast } from "sonner";
-import type { AppSettings, WslTargetCli } from "../../services/settings";
+import type { AppSettings, WslDistroOverride, WslTargetCli } from "../../services/settings";
+import { settingsWslDistroOverridesSet } from "../../services/settingsWslDistroOverrides";
 import { logToConsole } from "../../services/consoleLog";
 import { appAboutGet } from "../../services/appAbout";
 import {
@@ -8,7 +9,9 @@ import {
   wslConfigureClients,
   wslDetect,
   wslHostAddressGet,
+  wslNetworkingGet,
   type WslDetection,
+  type WslNetworkingDiagnostic,
   type WslDistroConfigStatus,
   type WslConfigureReport,
 } from "../../services/wsl";
@@ -38,15 +41,27 @@ export function WslSettingsCard({
 }: WslSettingsCardProps) {
   const [aboutOs, setAboutOs] = useState<string | null>(null);
   const [hostIp, setHostIp] = useState<string | null>(null);
+  const [networking, setNetworking] = useState<WslNetworkingDiagnostic | null>(null);
   const [detection, setDetection] = useState<WslDetection | null>(null);
   const [statusRows, setStatusRows] = useState<WslDistroConfigStatus[] | null>(null);
   const [checkedOnce, setCheckedOnce] = useState(false);
   const [loading, setLoading] = useState(false);
   const [configuring, setConfiguring] = useState(false);
   const [lastReport, setLastReport] = useState<WslConfigureReport | null>(null);
+  const [configureFirewall, setConfigureFirewall] = useState(false);
+  const [portProxy, setPortProxy] = useState(false);
+  const [distroOverrides, setDistroOverrides] = useState<WslDistroOverride[]>(
+    settings.wsl_distro_overrides ?? []
+  );
+
+  useEffect(() => {
+    setDistroOverrides(settings.wsl_distro_overrides ?? []);
+  }, [settings.wsl_distro_overrides]);
 
   const wslSupported = useMemo(() => aboutOs === "windows", [aboutOs]);
-  const listenModeOk = settings.gateway_listen_mode !== "localhost";
+  const localhostOnly =
+    settings.gateway_listen_mode === "localhost" && networking?.mode !== "mirrored";
+  const listenModeOk = !localhostOnly || portProxy;
   const wslDetected = Boolean(detection?.detected);
   const distros = detection?.distros ?? [];
 
@@ -90,12 +105,14 @@ export function WslSettingsCard({
         return;
       }
 
-      const [ip, statuses] = await Promise.all([
+      const [ip, statuses, net] = await Promise.all([
         wslHostAddressGet(),
         wslConfigStatusGet(det.distros),
+        wslNetworkingGet(),
       ]);
       setHostIp(ip ?? null);
       setStatusRows(statuses ?? null);
+      setNetworking(net ?? null);
     } catch (err) {
       logToConsole("error", "刷新 WSL 状态失败", { error: String(err) });
       toast("刷新 WSL 状态失败：请稍后重试");
@@ -141,6 +158,36 @@ export function WslSettingsCard({
     }
   }
 
+  function distroEntry(distro: string): WslDistroOverride {
+    return (
+      distroOverrides.find((o) => o.distro.toLowerCase() === distro.toLowerCase()) ?? {
+        distro,
+        enabled: true,
+        targets: settings.wsl_target_cli,
+      }
+    );
+  }
+
+  async function commitDistroOverride(next: WslDistroOverride) {
+    if (!available) return;
+    const rest = distroOverrides.filter(
+      (o) => o.distro.toLowerCase() !== next.distro.toLowerCase()
+    );
+    const nextList = [...rest, next];
+    try {
+      const updated = await settingsWslDistroOverridesSet(nextList);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      setDistroOverrides(updated.wsl_distro_overrides);
+      logToConsole("info", "更新 WSL 发行版单独配置", next);
+    } catch (err) {
+      logToConsole("error", "更新 WSL 发行版单独配置失败", { error: String(err), next });
+      toast("更新失败：请稍后重试");
+    }
+  }
+
   async function configureNow() {
     if (!available) return;
     if (configuring) return;
@@ -160,7 +207,11 @@ export function WslSettingsCard({
     setConfiguring(true);
     setLastReport(null);
     try {
-      const report = await wslConfigureClients({ targets: settings.wsl_target_cli });
+      const report = await wslConfigureClients({
+        targets: settings.wsl_target_cli,
+        configureFirewall,
+        portProxy: localhostOnly && portProxy,
+      });
       if (!report) {
         toast("仅在 Tauri Desktop 环境可用");
         return;
@@ -168,6 +219,12 @@ export function WslSettingsCard({
       setLastReport(report);
       logToConsole("info", "WSL 一键配置", report);
       toast(report.message || (report.ok ? "配置成功" : "配置失败"));
+      if (report.port_proxy) {
+        toast(`端口转发：${report.port_proxy.message}`);
+      }
+      if (report.firewall) {
+        toast(`防火墙：${report.firewall.message}`);
+      }
       await refreshAll();
     } catch (err) {
       logToConsole("error", "WSL 一键配置失败", { error: String(err) });
@@ -222,6 +279,17 @@ export function WslSettingsCard({
             </div>
           </SettingsRow>
 
+          {networking ? (
+            <SettingsRow label="WSL 网络模式">
+              <div className="text-right">
+                <div className="text-sm text-slate-700">
+                  {networking.mode === "mirrored" ? "镜像模式（mirrored）" : "NAT 模式"}
+                </div>
+                <div className="text-xs text-slate-500">{networking.message}</div>
+              </div>
+            </SettingsRow>
+          ) : null}
+
           <SettingsRow label="WSL 状态">
             <div className="flex items-center gap-2">
               <span
@@ -247,15 +315,55 @@ export function WslSettingsCard({
 
           {wslDetected && distros.length > 0 ? (
             <SettingsRow label="发行版">
-              <div className="flex flex-wrap gap-2">
-                {distros.map((d) => (
-                  <span
-                    key={d}
-                    className="rounded-full bg-slate-100 px-2.5 py-1 text-xs text-slate-700 border border-slate-200/60"
-                  >
-                    {d}
-                  </span>
-                ))}
+              <div className="space-y-2">
+                {distros.map((d) => {
+                  const entry = distroEntry(d);
+                  const configured = statusRows?.find((r) => r.distro === d)?.last_configured;
+                  return (
+                    <div
+                      key={d}
+                      className="flex flex-wrap items-center gap-3 rounded-lg bg-slate-50 px-3 py-2 border border-slate-100"
+                    >
+                      <label className="flex items-center gap-2 text-sm font-medium text-slate-800">
+                        <input
+                          type="checkbox"
+                          checked={entry.enabled}
+                          onChange={(e) =>
+                            void commitDistroOverride({
+                              ...entry,
+                              enabled: e.currentTarget.checked,
+                            })
+                          }
+                          disabled={saving || configuring}
+                        />
+                        {d}
+                      </label>
+                      {(["claude", "codex", "gemini"] as const).map((key) => (
+                        <label key={key} className="flex items-center gap-1 text-xs text-slate-600">
+                          <input
+                            type="checkbox"
+                            checked={entry.targets[key]}
+                            onChange={(e) =>
+                              void commitDistroOverride({
+                                ...entry,
+                                targets: toggleTarget(entry.targets, key, e.currentTarget.checked),
+                              })
+                            }
+                            disabled={saving || configuring || !entry.enabled}
+                          />
+                          {key}
+                        </label>
+                      ))}
+                      <span className="text-xs text-slate-500">
+                        {configured
+                          ? `已配置 → ${configured.proxy_origin}（${new Date(
+                              configured.configured_at * 1000
+                            ).toLocaleString()}）`
+                          : "未配置"}
+                      </span>
+                    </div>
+                  );
+                })}
               </div>
             </SettingsRow>
           ) : null}
@@ -309,10 +417,40 @@ export function WslSettingsCard({
             </SettingsRow>
           ) : null}
 
+          {settings.wsl_auto_config && localhostOnly ? (
+            <SettingsRow label="端口转发">
+              <label className="flex items-center gap-2 text-sm text-slate-700">
+                <input
+                  type="checkbox"
+                  checked={portProxy}
+                  onChange={(e) => setPortProxy(e.currentTarget.checked)}
+                  disabled={configuring}
+                />
+                通过 netsh portproxy 将 WSL 宿主机地址转发到 127.0.0.1（需管理员授权）
+              </label>
+            </SettingsRow>
+          ) : null}
+
+          {settings.wsl_auto_config ? (
+            <SettingsRow label="防火墙规则">
+              <label className="flex items-center gap-2 text-sm text-slate-700">
+                <input
+                  type="checkbox"
+                  checked={configureFirewall}
+                  onChange={(e) => setConfigureFirewall(e.currentTarget.checked)}
+                  disabled={configuring}
+                />
+                配置时同时放行 WSL 子网访问网关端口（需管理员授权）
+              </label>
+            </SettingsRow>
+          ) : null}
+
           {settings.wsl_auto_config ? (
             <div className="mt-3 flex items-start justify-between gap-3">
               <div className="text-xs text-slate-500">
-                {listenModeOk ? null : "提示：监听模式为“仅本地(127.0.0.1)”时，WSL 无法访问网关。"}
+                {listenModeOk
+                  ? null
+                  : "提示：监听模式为“仅本地(127.0.0.1)”时，WSL 无法访问网关（可启用端口转发）。"}
                 {statusRows ? (
                   <div className="mt-1">
                     已检测配置文件：
@@ -8,6 +8,7 @@ import type {
   ClaudeSettingsState,
 } from "../../../services/cliManager";
 import { cn } from "../../../utils/cn";
+import { ClaudeHooksCard } from "../ClaudeHooksCard";
 import { Button } from "../../../ui/Button";
 import { Card } from "../../../ui/Card";
 import { Input } from "../../../ui/Input";
@@ -887,6 +888,8 @@ export function CliManagerClaudeTab({
           </div>
         )}
       </Card>
+
+      <ClaudeHooksCard />
     </div>
   );
 }
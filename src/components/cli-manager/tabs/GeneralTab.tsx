@@ -13,6 +13,21 @@ import { Input } from "../../../ui/Input";
 import { SettingsRow } from "../../../ui/SettingsRow";
 import { Switch } from "../../../ui/Switch";
 import { NetworkSettingsCard } from "../NetworkSettingsCard";
+import { InflightDedupModeRow } from "../InflightDedupModeRow";
+import { CliStreamCapsRow } from "../CliStreamCapsRow";
+import { LatencyProbeIntervalRow } from "../LatencyProbeIntervalRow";
+import { LatencyRoutingRow } from "../LatencyRoutingRow";
+import { PromptCacheInjectionRow } from "../PromptCacheInjectionRow";
+import { ResponseCacheCompletionsRow } from "../ResponseCacheCompletionsRow";
+import { RecentErrorCacheRow } from "../RecentErrorCacheRow";
+import { HedgedRequestsRow } from "../HedgedRequestsRow";
+import { StreamToolPauseGraceRow } from "../StreamToolPauseGraceRow";
+import { ResponseCacheTtlRow } from "../ResponseCacheTtlRow";
+import { HeaderSanitizeRulesCard } from "../HeaderSanitizeRulesCard";
+import { FailoverRulesCard } from "../FailoverRulesCard";
+import { InterceptRulesCard } from "../InterceptRulesCard";
+import { PriorityLanesCard } from "../PriorityLanesCard";
+import { RequestRedactionCard } from "../RequestRedactionCard";
 import { WslSettingsCard } from "../WslSettingsCard";
 import { AlertTriangle, Shield, TrendingDown } from "lucide-react";
 
@@ -121,6 +136,10 @@ export function CliManagerGeneralTab({
                     disabled={rectifierSaving || rectifierAvailable !== "available"}
                   />
                 </SettingsRow>
+                <InflightDedupModeRow
+                  available={rectifierAvailable === "available"}
+                  settings={appSettings}
+                />
                 <SettingsRow label="Thinking 签名整流器">
                   <Switch
                     checked={rectifier.enable_thinking_signature_rectifier}
@@ -130,6 +149,10 @@ export function CliManagerGeneralTab({
                     disabled={rectifierSaving || rectifierAvailable !== "available"}
                   />
                 </SettingsRow>
+                <PromptCacheInjectionRow
+                  available={rectifierAvailable === "available"}
+                  settings={appSettings}
+                />
                 <div className="rounded-lg bg-slate-50 p-4 border border-slate-100">
                   <SettingsRow label="响应整流（FluxFix）">
                     <Switch
@@ -169,6 +192,24 @@ export function CliManagerGeneralTab({
                           disabled={rectifierSaving || rectifierAvailable !== "available"}
                         />
                       </SettingsRow>
+                      <SettingsRow label="修复工具调用参数（实验）">
+                        <Switch
+                          checked={rectifier.response_fixer_fix_tool_args}
+                          onCheckedChange={(checked) =>
+                            void onPersistRectifier({ response_fixer_fix_tool_args: checked })
+                          }
+                          disabled={rectifierSaving || rectifierAvailable !== "available"}
+                        />
+                      </SettingsRow>
+                      <SettingsRow label="仅诊断（不修改响应）">
+                        <Switch
+                          checked={rectifier.response_fixer_dry_run}
+                          onCheckedChange={(checked) =>
+                            void onPersistRectifier({ response_fixer_dry_run: checked })
+                          }
+                          disabled={rectifierSaving || rectifierAvailable !== "available"}
+                        />
+                      </SettingsRow>
                     </div>
                   )}
                 </div>
@@ -286,6 +327,29 @@ export function CliManagerGeneralTab({
               settings={appSettings}
               onPersistSettings={onPersistCommonSettings}
             />
+            <InterceptRulesCard
+              available={rectifierAvailable === "available"}
+              saving={commonSettingsSaving}
+              settings={appSettings}
+            />
+            <HeaderSanitizeRulesCard
+              available={rectifierAvailable === "available"}
+              saving={commonSettingsSaving}
+              settings={appSettings}
+            />
+            <FailoverRulesCard
+              available={rectifierAvailable === "available"}
+              saving={commonSettingsSaving}
+              settings={appSettings}
+            />
+            <RequestRedactionCard
+              available={rectifierAvailable === "available"}
+              settings={appSettings}
+            />
+            <PriorityLanesCard
+              available={rectifierAvailable === "available"}
+              settings={appSettings}
+            />
           </>
         ) : null}
 
@@ -364,6 +428,10 @@ export function CliManagerGeneralTab({
                   <span className="w-8 text-sm text-slate-500">秒</span>
                 </div>
               </SettingsRow>
+              <StreamToolPauseGraceRow
+                available={rectifierAvailable === "available"}
+                settings={appSettings}
+              />
 
               <SettingsRow label="非流式总超时（0=禁用）">
                 <div className="flex items-center gap-2">
@@ -476,6 +544,34 @@ export function CliManagerGeneralTab({
                   <span className="w-8 text-sm text-slate-500">秒</span>
                 </div>
               </SettingsRow>
+              <LatencyProbeIntervalRow
+                available={rectifierAvailable === "available"}
+                settings={appSettings}
+              />
+              <LatencyRoutingRow
+                available={rectifierAvailable === "available"}
+                settings={appSettings}
+              />
+              <CliStreamCapsRow
+                available={rectifierAvailable === "available"}
+                settings={appSettings}
+              />
+              <ResponseCacheTtlRow
+                available={rectifierAvailable === "available"}
+                settings={appSettings}
+              />
+              <ResponseCacheCompletionsRow
+                available={rectifierAvailable === "available"}
+                settings={appSettings}
+              />
+              <RecentErrorCacheRow
+                available={rectifierAvailable === "available"}
+                settings={appSettings}
+              />
+              <HedgedRequestsRow
+                available={rectifierAvailable === "available"}
+                settings={appSettings}
+              />
 
               <SettingsRow label="熔断阈值">
                 <div className="flex items-center gap-2">
@@ -11,12 +11,14 @@ import type { CliKey } from "../../services/providers";
 import {
   costBreakdownModelV1,
   costBreakdownProviderV1,
+  costPrefixAffinityV1,
   costScatterCliProviderModelV1,
   costSummaryV1,
   costTopRequestsV1,
   costTrendV1,
   type CostModelBreakdownRowV1,
   type CostPeriod,
+  type CostPrefixAffinityV1,
   type CostProviderBreakdownRowV1,
   type CostScatterCliProviderModelRowV1,
   type CostSummaryV1,
@@ -111,6 +113,18 @@ function StatCard({
   );
 }
 
+function prefixAffinityHint(stats: CostPrefixAffinityV1 | null) {
+  if (!stats || stats.requests_eligible === 0) return "暂无可复用前缀的请求";
+  const share = (cacheRead: number, prompt: number) =>
+    prompt > 0 ? formatPercent(cacheRead / prompt, 0) : "—";
+  return `${formatInteger(stats.requests_hit)} / ${formatInteger(
+    stats.requests_eligible
+  )} 命中 · 缓存读取 ${share(stats.hit_cache_read_tokens, stats.hit_prompt_tokens)} vs ${share(
+    stats.miss_cache_read_tokens,
+    stats.miss_prompt_tokens
+  )}`;
+}
+
 function StatCardSkeleton({ className }: { className?: string }) {
   return (
     <Card padding="md" className={cn("h-full animate-pulse", className)}>
@@ -167,6 +181,7 @@ export function HomeCostPanel({ onSelectLogId }: HomeCostPanelProps) {
   const [modelRows, setModelRows] = useState<CostModelBreakdownRowV1[]>([]);
   const [scatterRows, setScatterRows] = useState<CostScatterCliProviderModelRowV1[]>([]);
   const [topRequests, setTopRequests] = useState<CostTopRequestRowV1[]>([]);
+  const [prefixAffinity, setPrefixAffinity] = useState<CostPrefixAffinityV1 | null>(null);
 
   const [scatterCliFilter, setScatterCliFilter] = useState<CliFilter>("all");
 
@@ -192,6 +207,7 @@ export function HomeCostPanel({ onSelectLogId }: HomeCostPanelProps) {
         setModelRows([]);
         setScatterRows([]);
         setTopRequests([]);
+        setPrefixAffinity(null);
         setLoading(false);
         setTauriAvailable(null);
         return;
@@ -200,17 +216,18 @@ export function HomeCostPanel({ onSelectLogId }: HomeCostPanelProps) {
       setErrorText(null);
       setLoading(true);
       try {
-        const [sum, trend, providers, models, scatter, top] = await Promise.all([
+        const [sum, trend, providers, models, scatter, top, affinity] = await Promise.all([
           costSummaryV1(period, filters),
           costTrendV1(period, filters),
           costBreakdownProviderV1(period, { ...filters, limit: 120 }),
           costBreakdownModelV1(period, { ...filters, limit: 120 }),
           costScatterCliProviderModelV1(period, { ...filters, limit: 500 }),
           costTopRequestsV1(period, { ...filters, limit: 50 }),
+          costPrefixAffinityV1(period, filters),
         ]);
         if (cancelled) return;
 
-        if (!sum || !trend || !providers || !models || !scatter || !top) {
+        if (!sum || !trend || !providers || !models || !scatter || !top || !affinity) {
           setTauriAvailable(false);
           setSummary(null);
           setTrendRows([]);
@@ -218,6 +235,7 @@ export function HomeCostPanel({ onSelectLogId }: HomeCostPanelProps) {
           setModelRows([]);
           setScatterRows([]);
           setTopRequests([]);
+          setPrefixAffinity(null);
           return;
         }
 
@@ -228,6 +246,7 @@ export function HomeCostPanel({ onSelectLogId }: HomeCostPanelProps) {
         setModelRows(models);
         setScatterRows(scatter);
         setTopRequests(top);
+        setPrefixAffinity(affinity);
       } catch (err) {
         if (cancelled) return;
         setTauriAvailable(true);
@@ -676,8 +695,16 @@ export function HomeCostPanel({ onSelectLogId }: HomeCostPanelProps) {
             : formatUsd(summary.avg_cost_usd_per_covered_success),
         hint: avgCostHint,
       },
+      {
+        title: "前缀粘性命中率",
+        value:
+          prefixAffinity && prefixAffinity.requests_eligible > 0
+            ? formatPercent(prefixAffinity.requests_hit / prefixAffinity.requests_eligible, 1)
+            : "—",
+        hint: prefixAffinityHint(prefixAffinity),
+      },
     ];
-  }, [coverage, summary]);
+  }, [coverage, prefixAffinity, summary]);
 
   const providerSelectValue = providerId == null ? "all" : String(providerId);
   const modelSelectValue = model == null ? "all" : model;
@@ -865,7 +892,7 @@ export function HomeCostPanel({ onSelectLogId }: HomeCostPanelProps) {
 
         <div className="lg:col-span-5 grid grid-cols-2 gap-3 content-start">
           {loading ? (
-            Array.from({ length: 3 }).map((_, idx) => <StatCardSkeleton key={idx} />)
+            Array.from({ length: 4 }).map((_, idx) => <StatCardSkeleton key={idx} />)
           ) : summaryCards.length === 0 ? (
             <Card padding="md">
               <div className="text-sm text-slate-600">
@@ -13,20 +13,27 @@ const ERROR_CODE_LABELS: Record<string, string> = {
   GW_UPSTREAM_CONNECT_FAILED: "连接失败",
   GW_UPSTREAM_5XX: "上游5XX",
   GW_UPSTREAM_4XX: "上游4XX",
+  GW_UPSTREAM_OVERLOADED: "上游过载",
   GW_UPSTREAM_READ_ERROR: "读取错误",
   GW_STREAM_ERROR: "流错误",
   GW_STREAM_ABORTED: "流中断",
   GW_STREAM_IDLE_TIMEOUT: "流空闲超时",
   GW_REQUEST_ABORTED: "请求中断",
+  GW_REQUEST_CANCELLED: "已取消",
   GW_INTERNAL_ERROR: "内部错误",
   GW_BODY_TOO_LARGE: "请求过大",
   GW_INVALID_CLI_KEY: "无效CLI",
   GW_INVALID_BASE_URL: "无效URL",
   GW_PORT_IN_USE: "端口占用",
+  GW_EXTRA_LISTENER_BIND_FAILED: "专用端口占用",
   GW_RESPONSE_BUILD_ERROR: "响应构建错误",
 };
 
-const CLIENT_ABORT_ERROR_CODES = new Set(["GW_STREAM_ABORTED", "GW_REQUEST_ABORTED"]);
+const CLIENT_ABORT_ERROR_CODES = new Set([
+  "GW_STREAM_ABORTED",
+  "GW_REQUEST_ABORTED",
+  "GW_REQUEST_CANCELLED",
+]);
 
 const SESSION_REUSE_TOOLTIP =
   "同一 session_id 在 5 分钟 TTL 内优先复用上一次成功 provider，减少抖动/提升缓存命中";
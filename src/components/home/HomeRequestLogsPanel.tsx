@@ -348,9 +348,18 @@ export function HomeRequestLogsPanel({
                           {/* Row 1: 输入 | 缓存创建 | 首字 | 花费 */}
                           <div className="flex items-center gap-1 h-4" title="Input Tokens">
                             <span className="text-slate-400 shrink-0">输入</span>
-                            <span className="font-mono tabular-nums text-slate-600">
-                              {formatInteger(effectiveInputTokens)}
-                            </span>
+                            {effectiveInputTokens == null && log.estimated_input_tokens != null ? (
+                              <span
+                                className="font-mono tabular-nums text-slate-400"
+                                title="上游未返回 usage，按请求体本地估算"
+                              >
+                                ≈{formatInteger(log.estimated_input_tokens)}
+                              </span>
+                            ) : (
+                              <span className="font-mono tabular-nums text-slate-600">
+                                {formatInteger(effectiveInputTokens)}
+                              </span>
+                            )}
                           </div>
                           <div className="flex items-center gap-1 h-4" title="Cache Write">
                             <span className="text-slate-400 shrink-0">缓存创建</span>
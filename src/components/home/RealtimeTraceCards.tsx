@@ -1,9 +1,12 @@
 // Usage:
 // - Render in Home page "概览 / 使用记录" area to show up-to-date in-flight traces.
 // - Accepts a list of `TraceSession` candidates; component applies its own visibility + exit animation logic.
+// - In-progress cards offer a cancel button (`gateway_request_cancel`) for requests stuck on a hung provider.
 
 import { useEffect, useMemo, useState } from "react";
+import { toast } from "sonner";
 import { cliBadgeTone, cliShortLabel } from "../../constants/clis";
+import { gatewayRequestCancel } from "../../services/gateway";
 import type { TraceSession } from "../../services/traceStore";
 import { cn } from "../../utils/cn";
 import {
@@ -13,7 +16,16 @@ import {
   formatTokensPerSecond,
   sanitizeTtfbMs,
 } from "../../utils/formatters";
-import { Clock, Server, Loader2, Cpu, Terminal, CheckCircle2, XCircle } from "lucide-react";
+import {
+  Clock,
+  Server,
+  Loader2,
+  Cpu,
+  Terminal,
+  CheckCircle2,
+  XCircle,
+  CircleStop,
+} from "lucide-react";
 import {
   computeEffectiveInputTokens,
   computeStatusBadge,
@@ -38,6 +50,22 @@ export function RealtimeTraceCards({
   showCustomTooltip,
 }: RealtimeTraceCardsProps) {
   const [nowMs, setNowMs] = useState(() => Date.now());
+  const [cancelling, setCancelling] = useState<Record<string, boolean>>({});
+
+  async function cancelTrace(traceId: string) {
+    if (cancelling[traceId]) return;
+    setCancelling((cur) => ({ ...cur, [traceId]: true }));
+    try {
+      const ok = await gatewayRequestCancel(traceId);
+      if (ok == null) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      toast(ok ? "已取消请求" : "请求已结束，无需取消");
+    } finally {
+      setCancelling((cur) => ({ ...cur, [traceId]: false }));
+    }
+  }
 
   useEffect(() => {
     if (traces.length === 0) return;
@@ -296,6 +324,18 @@ export function RealtimeTraceCards({
                     <Clock className="h-3 w-3" />
                     {formatUnixSeconds(Math.floor(trace.first_seen_ms / 1000))}
                   </span>
+
+                  {isInProgress && (
+                    <button
+                      type="button"
+                      onClick={() => void cancelTrace(trace.trace_id)}
+                      disabled={Boolean(cancelling[trace.trace_id])}
+                      className="inline-flex items-center rounded p-0.5 text-slate-400 hover:bg-rose-50 hover:text-rose-600 disabled:opacity-50 shrink-0"
+                      title="取消请求（断开上游连接）"
+                    >
+                      <CircleStop className="h-3.5 w-3.5" />
+                    </button>
+                  )}
                 </div>
 
                 {/* Row 2: Provider + Stats Grid (2 rows x 4 cols for alignment) */}
@@ -5,6 +5,7 @@
 import { toast } from "sonner";
 import { cliBadgeTone, cliShortLabel } from "../../constants/clis";
 import { logToConsole } from "../../services/consoleLog";
+import { issueDraftGenerate } from "../../services/diagnostics";
 import type { RequestAttemptLog, RequestLogDetail } from "../../services/requestLogs";
 import { Button } from "../../ui/Button";
 import { Card } from "../../ui/Card";
@@ -159,6 +160,30 @@ export function RequestLogDetailDialog({
                 >
                   复制 trace_id
                 </Button>
+                {selectedLog.error_code ||
+                (selectedLog.status != null && selectedLog.status >= 400) ? (
+                  <Button
+                    onClick={async () => {
+                      try {
+                        const draft = await issueDraftGenerate(selectedLog.trace_id);
+                        if (!draft) {
+                          toast("仅在 Tauri Desktop 环境可用");
+                          return;
+                        }
+                        await navigator.clipboard.writeText(`# ${draft.title}\n\n${draft.body}`);
+                        toast("已复制 Issue 草稿（设置已脱敏）");
+                      } catch (err) {
+                        logToConsole("error", "生成 Issue 草稿失败", {
+                          error: String(err),
+                        });
+                        toast(`生成 Issue 草稿失败：${String(err)}`);
+                      }
+                    }}
+                    variant="secondary"
+                  >
+                    复制 Issue 草稿
+                  </Button>
+                ) : null}
               </div>
             </div>
           </Card>
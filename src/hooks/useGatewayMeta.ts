@@ -24,6 +24,10 @@ const listeners = new Set<Listener>();
 let started = false;
 let starting: Promise<void> | null = null;
 let unlistenStatus: (() => void) | null = null;
+let healthTimer: ReturnType<typeof setInterval> | null = null;
+
+// `gateway:status` only fires on lifecycle changes; poll so the health summary stays current.
+const HEALTH_POLL_MS = 15_000;
 
 function emit() {
   for (const listener of listeners) listener();
@@ -43,6 +47,23 @@ export function gatewayMetaSetPreferredPort(port: number) {
 
 function gatewayMetaSetGateway(status: GatewayStatus) {
   setSnapshot({ gatewayAvailable: "available", gateway: status });
+  syncHealthPolling(status.running);
+}
+
+function syncHealthPolling(running: boolean) {
+  if (!running) {
+    if (healthTimer) clearInterval(healthTimer);
+    healthTimer = null;
+    return;
+  }
+  if (healthTimer) return;
+  healthTimer = setInterval(() => {
+    gatewayStatus()
+      .then((status) => {
+        if (status) gatewayMetaSetGateway(status);
+      })
+      .catch(() => {});
+  }, HEALTH_POLL_MS);
 }
 
 async function ensureStarted() {
@@ -3,12 +3,18 @@ import { toast } from "sonner";
 import { logToConsole } from "../services/consoleLog";
 import { appAboutGet, type AppAboutInfo } from "../services/appAbout";
 import {
+  listenUpdaterBackgroundDownload,
+  updaterBackgroundDownloadStart,
+  updaterBackgroundDownloadStateGet,
   updaterCheck,
   updaterDownloadAndInstall,
+  type UpdaterBackgroundDownloadState,
   type UpdaterCheckUpdate,
   type UpdaterDownloadEvent,
 } from "../services/updater";
+import { settingsGet } from "../services/settings";
 import { hasTauriRuntime } from "../services/tauriInvoke";
+import { powerStatusGet } from "../services/settingsLowPowerMode";
 
 const STORAGE_KEY_LAST_CHECKED_AT_MS = "updater.lastCheckedAtMs";
 const AUTO_CHECK_DELAY_MS = 2000;
@@ -25,6 +31,10 @@ export type UpdateMeta = {
   installError: string | null;
   installTotalBytes: number | null;
   installDownloadedBytes: number;
+  // host of the current download source (GitHub or a mirror)
+  installSource: string | null;
+
+  backgroundDownload: UpdaterBackgroundDownloadState | null;
 };
 
 type Listener = () => void;
@@ -39,6 +49,9 @@ let snapshot: UpdateMeta = {
   installError: null,
   installTotalBytes: null,
   installDownloadedBytes: 0,
+  installSource: null,
+
+  backgroundDownload: null,
 };
 
 const listeners = new Set<Listener>();
@@ -95,6 +108,14 @@ async function ensureStarted() {
       setSnapshot({ about: null });
     }
 
+    try {
+      const backgroundDownload = await updaterBackgroundDownloadStateGet();
+      setSnapshot({ backgroundDownload });
+      await listenUpdaterBackgroundDownload((state) => setSnapshot({ backgroundDownload: state }));
+    } catch {
+      // ignore: background download state unavailable
+    }
+
     scheduleAutoCheck();
 
     started = true;
@@ -108,7 +129,31 @@ async function autoCheckIfDue() {
   const last = readLastCheckedAtMs();
   const now = Date.now();
   if (last != null && now - last < AUTO_CHECK_INTERVAL_MS) return;
-  await updateCheckNow({ silent: true, openDialogIfUpdate: false });
+  const power = await powerStatusGet().catch(() => null);
+  if (power?.suspended) return;
+  const update = await updateCheckNow({ silent: true, openDialogIfUpdate: false });
+  if (update) await maybeStartBackgroundDownload(update);
+}
+
+async function maybeStartBackgroundDownload(update: UpdaterCheckUpdate) {
+  if (snapshot.about?.run_mode === "portable") return;
+  const settings = await settingsGet().catch(() => null);
+  if (!settings?.update_background_download) return;
+  await updateBackgroundDownloadStart(update);
+}
+
+export async function updateBackgroundDownloadStart(update?: UpdaterCheckUpdate) {
+  const candidate = update ?? snapshot.updateCandidate;
+  if (!candidate) return null;
+  try {
+    const state = await updaterBackgroundDownloadStart(candidate.rid);
+    if (state) setSnapshot({ backgroundDownload: state });
+    logToConsole("info", "后台下载更新", { version: candidate.version, phase: state?.phase });
+    return state;
+  } catch (err) {
+    logToConsole("error", "后台下载更新失败", { error: String(err) });
+    return null;
+  }
 }
 
 async function autoCheckOnStartup() {
@@ -133,6 +178,7 @@ async function autoCheckOnStartup() {
       date: update.date,
       rid: update.rid,
     });
+    await maybeStartBackgroundDownload(update);
     return;
   }
 
@@ -180,6 +226,7 @@ export async function updateCheckNow(options: {
           installError: null,
           installDownloadedBytes: 0,
           installTotalBytes: null,
+          installSource: null,
           installingUpdate: false,
         });
       }
@@ -208,7 +255,12 @@ export async function updateCheckNow(options: {
 function onUpdaterDownloadEvent(evt: UpdaterDownloadEvent) {
   if (evt.event === "started") {
     const total = evt.data?.contentLength;
-    setSnapshot({ installTotalBytes: typeof total === "number" ? total : null });
+    // Re-sent when the download falls back to another source: restart the byte counter.
+    setSnapshot({
+      installTotalBytes: typeof total === "number" ? total : null,
+      installDownloadedBytes: 0,
+      installSource: evt.data?.source ?? null,
+    });
     return;
   }
   if (evt.event === "progress") {
@@ -229,6 +281,7 @@ export async function updateDownloadAndInstall(): Promise<boolean | null> {
     installError: null,
     installDownloadedBytes: 0,
     installTotalBytes: null,
+    installSource: null,
     installingUpdate: true,
   });
 
@@ -263,6 +316,7 @@ export function updateDialogSetOpen(open: boolean) {
       installError: null,
       installDownloadedBytes: 0,
       installTotalBytes: null,
+      installSource: null,
       installingUpdate: false,
     });
   }
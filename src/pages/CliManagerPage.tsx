@@ -55,6 +55,8 @@ const DEFAULT_RECTIFIER: GatewayRectifierSettingsPatch = {
   response_fixer_fix_truncated_json: true,
   response_fixer_max_json_depth: 200,
   response_fixer_max_fix_size: 1024 * 1024,
+  response_fixer_fix_tool_args: false,
+  response_fixer_dry_run: false,
 };
 
 const LazyClaudeTab = lazy(() =>
@@ -151,6 +153,8 @@ export function CliManagerPage() {
           response_fixer_fix_truncated_json: settings.response_fixer_fix_truncated_json,
           response_fixer_max_json_depth: settings.response_fixer_max_json_depth,
           response_fixer_max_fix_size: settings.response_fixer_max_fix_size,
+          response_fixer_fix_tool_args: settings.response_fixer_fix_tool_args ?? false,
+          response_fixer_dry_run: settings.response_fixer_dry_run ?? false,
         });
         setCircuitBreakerNoticeEnabled(settings.enable_circuit_breaker_notice ?? false);
         setCodexSessionIdCompletionEnabled(settings.enable_codex_session_id_completion ?? true);
@@ -229,6 +233,8 @@ export function CliManagerPage() {
         response_fixer_fix_truncated_json: updated.response_fixer_fix_truncated_json,
         response_fixer_max_json_depth: updated.response_fixer_max_json_depth,
         response_fixer_max_fix_size: updated.response_fixer_max_fix_size,
+        response_fixer_fix_tool_args: updated.response_fixer_fix_tool_args ?? false,
+        response_fixer_dry_run: updated.response_fixer_dry_run ?? false,
       });
     } catch (err) {
       logToConsole("error", "更新网关整流配置失败", { error: String(err) });
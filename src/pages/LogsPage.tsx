@@ -1,7 +1,7 @@
 // Usage:
 // - Logs page aligned with claude-code-hub `/dashboard/logs` (status codes like 499/524).
 // - Entry: Home "日志" button -> `/#/logs`.
-// - Backend commands: `request_logs_list_all`, `request_logs_list_after_id_all`, `request_log_get`, `request_attempt_logs_by_trace_id`.
+// - Backend commands: `request_logs_list_all`, `request_logs_list_after_id_all`, `request_logs_list_before_all`, `request_log_get`, `request_attempt_logs_by_trace_id`.
 
 import { useEffect, useMemo, useRef, useState } from "react";
 import { toast } from "sonner";
@@ -14,11 +14,13 @@ import {
   requestLogGet,
   requestLogsListAfterIdAll,
   requestLogsListAll,
+  requestLogsListBeforeAll,
   type RequestAttemptLog,
   type RequestLogDetail,
   type RequestLogSummary,
 } from "../services/requestLogs";
 import { hasTauriRuntime } from "../services/tauriInvoke";
+import { Button } from "../ui/Button";
 import { Card } from "../ui/Card";
 import { Input } from "../ui/Input";
 import { PageHeader } from "../ui/PageHeader";
@@ -26,6 +28,7 @@ import { Switch } from "../ui/Switch";
 import { TabList } from "../ui/TabList";
 
 const LOGS_PAGE_LIMIT = 200;
+const LOGS_MAX_LOADED = 5000;
 const AUTO_REFRESH_INTERVAL_MS = 2000;
 
 type StatusPredicate = (status: number | null) => boolean;
@@ -82,6 +85,16 @@ function computeRequestLogsCursorId(rows: RequestLogSummary[]) {
   return maxId;
 }
 
+// Oldest loaded row by (created_at_ms, id): the keyset cursor for `request_logs_list_before_all`.
+function computeRequestLogsOldestCursor(rows: RequestLogSummary[]) {
+  let oldest: RequestLogSummary | null = null;
+  for (const row of rows) {
+    if (!oldest || sortRequestLogsDesc(row, oldest) > 0) oldest = row;
+  }
+  if (!oldest) return null;
+  return { createdAtMs: requestLogCreatedAtMs(oldest), id: oldest.id };
+}
+
 function mergeRequestLogs(prev: RequestLogSummary[], incoming: RequestLogSummary[], limit: number) {
   const byId = new Map<number, RequestLogSummary>();
   for (const row of incoming) byId.set(row.id, row);
@@ -106,9 +119,12 @@ export function LogsPage() {
   const [requestLogsLoading, setRequestLogsLoading] = useState(false);
   const [requestLogsRefreshing, setRequestLogsRefreshing] = useState(false);
   const [requestLogsAvailable, setRequestLogsAvailable] = useState<boolean | null>(null);
+  const [olderLoading, setOlderLoading] = useState(false);
+  const [olderExhausted, setOlderExhausted] = useState(false);
 
   const requestLogsRef = useRef<RequestLogSummary[]>([]);
   const requestLogsInFlightRef = useRef(false);
+  const loadedLimitRef = useRef(LOGS_PAGE_LIMIT);
 
   const [selectedLogId, setSelectedLogId] = useState<number | null>(null);
   const [selectedLog, setSelectedLog] = useState<RequestLogDetail | null>(null);
@@ -163,6 +179,8 @@ export function LogsPage() {
       }
       setRequestLogsAvailable(true);
       const next = (items ?? []).slice().sort(sortRequestLogsDesc);
+      loadedLimitRef.current = LOGS_PAGE_LIMIT;
+      setOlderExhausted(next.length < LOGS_PAGE_LIMIT);
       setRequestLogs(next);
     } catch (err) {
       setRequestLogsAvailable(true);
@@ -199,7 +217,7 @@ export function LogsPage() {
       setRequestLogsAvailable(true);
       const incoming = items ?? [];
       if (incoming.length === 0) return;
-      setRequestLogs((cur) => mergeRequestLogs(cur, incoming, LOGS_PAGE_LIMIT));
+      setRequestLogs((cur) => mergeRequestLogs(cur, incoming, loadedLimitRef.current));
     } catch (err) {
       logToConsole("warn", "增量刷新日志失败", { error: String(err) });
     } finally {
@@ -208,6 +226,32 @@ export function LogsPage() {
     }
   }
 
+  async function loadOlderRequestLogs() {
+    if (!hasTauriRuntime()) return;
+
+    const cursor = computeRequestLogsOldestCursor(requestLogsRef.current);
+    if (!cursor) return;
+    if (requestLogsInFlightRef.current) return;
+    requestLogsInFlightRef.current = true;
+    setOlderLoading(true);
+
+    try {
+      const items = await requestLogsListBeforeAll(cursor.createdAtMs, cursor.id, LOGS_PAGE_LIMIT);
+      if (!items) return;
+      if (items.length < LOGS_PAGE_LIMIT) setOlderExhausted(true);
+      if (items.length === 0) return;
+      loadedLimitRef.current = Math.min(loadedLimitRef.current + items.length, LOGS_MAX_LOADED);
+      if (loadedLimitRef.current >= LOGS_MAX_LOADED) setOlderExhausted(true);
+      setRequestLogs((cur) => mergeRequestLogs(cur, items, loadedLimitRef.current));
+    } catch (err) {
+      logToConsole("warn", "加载更早日志失败", { error: String(err) });
+      toast("加载更早日志失败：请查看控制台日志");
+    } finally {
+      requestLogsInFlightRef.current = false;
+      setOlderLoading(false);
+    }
+  }
+
   useEffect(() => {
     setSelectedLogId(null);
     setSelectedLog(null);
@@ -392,6 +436,19 @@ export function LogsPage() {
         onSelectLogId={setSelectedLogId}
       />
 
+      {requestLogsAvailable && requestLogs.length > 0 && !olderExhausted ? (
+        <div className="flex justify-center">
+          <Button
+            size="sm"
+            variant="secondary"
+            onClick={() => void loadOlderRequestLogs()}
+            disabled={olderLoading || requestLogsLoading}
+          >
+            {olderLoading ? "加载中…" : "加载更早的日志"}
+          </Button>
+        </div>
+      ) : null}
+
       <RequestLogDetailDialog
         selectedLogId={selectedLogId}
         onSelectLogId={setSelectedLogId}
@@ -3,6 +3,7 @@
 import { useEffect, useMemo, useState } from "react";
 import { toast } from "sonner";
 import { Pencil, Trash2 } from "lucide-react";
+import { CodexProjectAgentsCard } from "../components/CodexProjectAgentsCard";
 import { CLIS, cliLongLabel } from "../constants/clis";
 import { logToConsole } from "../services/consoleLog";
 import {
@@ -30,7 +31,7 @@ function promptFileHint(cliKey: CliKey) {
     case "claude":
       return "~/.claude/CLAUDE.md";
     case "codex":
-      return "~/.codex/AGENTS.md";
+      return "~/.codex/AGENTS.md（托管块）";
     case "gemini":
       return "~/.gemini/GEMINI.md";
     default:
@@ -321,6 +322,10 @@ export function PromptsPage() {
         </div>
       )}
 
+      {activeCli === "codex" ? (
+        <CodexProjectAgentsCard enabledPrompt={items.find((p) => p.enabled) ?? null} />
+      ) : null}
+
       <Dialog
         open={dialogOpen}
         title={editTarget ? "编辑提示词" : "添加提示词"}
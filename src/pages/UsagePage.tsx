@@ -13,6 +13,7 @@ import {
 import { CLI_FILTER_ITEMS, type CliFilterKey } from "../constants/clis";
 import { PERIOD_ITEMS } from "../constants/periods";
 import { useCustomDateRange } from "../hooks/useCustomDateRange";
+import { UsageToolsCard } from "../components/UsageToolsCard";
 import { Button } from "../ui/Button";
 import { Card } from "../ui/Card";
 import { PageHeader } from "../ui/PageHeader";
@@ -630,6 +631,11 @@ export function UsagePage() {
                         <div className="mt-0.5 text-xs text-slate-500">
                           仅统计成功请求（{formatInteger(summary.requests_success)}）
                         </div>
+                        {summary.upstream_overloaded > 0 ? (
+                          <div className="mt-0.5 text-xs text-amber-600">
+                            上游过载（529）{formatInteger(summary.upstream_overloaded)} 次
+                          </div>
+                        ) : null}
                       </td>
                       <td className="border-b border-slate-200 px-3 py-3 font-mono text-xs font-medium tabular-nums text-slate-900">
                         {formatInteger(summary.requests_total)}
@@ -677,6 +683,12 @@ export function UsagePage() {
           )}
         </div>
       </Card>
+
+      <UsageToolsCard
+        period={period}
+        cliKey={cliKey === "all" ? null : cliKey}
+        reloadSeq={reloadSeq}
+      />
     </div>
   );
 }
@@ -16,12 +16,15 @@ export type BaseUrlEditorProps = {
   newRow: (url?: string) => BaseUrlRow;
   disabled?: boolean;
   placeholder?: string;
+  // Edit mode only: manual pings are recorded into the provider's probe history.
+  providerId?: number | null;
 };
 
 async function pingBaseUrlRow(
   rowId: string,
   url: string,
-  setRows: Dispatch<SetStateAction<BaseUrlRow[]>>
+  setRows: Dispatch<SetStateAction<BaseUrlRow[]>>,
+  providerId?: number | null
 ) {
   const baseUrl = url.trim();
   if (!baseUrl) {
@@ -34,7 +37,7 @@ async function pingBaseUrlRow(
   );
 
   try {
-    const ms = await baseUrlPingMs(baseUrl);
+    const ms = await baseUrlPingMs(baseUrl, providerId);
     if (ms == null) {
       toast("仅在 Tauri Desktop 环境可用");
       setRows((prev) =>
@@ -69,13 +72,14 @@ async function pingBaseUrlRow(
 async function pingAllBaseUrlRows(
   rowsSnapshot: BaseUrlRow[],
   setRows: Dispatch<SetStateAction<BaseUrlRow[]>>,
-  setPingingAll: Dispatch<SetStateAction<boolean>>
+  setPingingAll: Dispatch<SetStateAction<boolean>>,
+  providerId?: number | null
 ) {
   if (rowsSnapshot.length === 0) return;
   setPingingAll(true);
   try {
     for (const row of rowsSnapshot) {
-      await pingBaseUrlRow(row.id, row.url, setRows);
+      await pingBaseUrlRow(row.id, row.url, setRows, providerId);
     }
   } finally {
     setPingingAll(false);
@@ -90,6 +94,7 @@ export function BaseUrlEditor({
   newRow,
   disabled,
   placeholder,
+  providerId,
 }: BaseUrlEditorProps) {
   return (
     <div className="space-y-2">
@@ -174,7 +179,7 @@ export function BaseUrlEditor({
             </div>
 
             <Button
-              onClick={() => void pingBaseUrlRow(row.id, row.url, setRows)}
+              onClick={() => void pingBaseUrlRow(row.id, row.url, setRows, providerId)}
               variant="secondary"
               size="sm"
               disabled={pinging || pingingAll || disabled}
@@ -209,7 +214,7 @@ export function BaseUrlEditor({
           + 添加
         </Button>
         <Button
-          onClick={() => void pingAllBaseUrlRows(rows, setRows, setPingingAll)}
+          onClick={() => void pingAllBaseUrlRows(rows, setRows, setPingingAll, providerId)}
           variant="secondary"
           size="sm"
           disabled={pingingAll || rows.length === 0 || disabled}
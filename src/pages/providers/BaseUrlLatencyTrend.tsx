@@ -0,0 +1,128 @@
+// Usage: Used by ProviderEditorDialog (edit mode) to show recent base_url latency trends.
+
+import { useEffect, useMemo, useState } from "react";
+import { baseUrlProbeHistory, type BaseUrlLatencyPoint } from "../../services/providers";
+import { cn } from "../../utils/cn";
+import { formatUnknownError } from "../../utils/errors";
+
+export type BaseUrlLatencyTrendProps = {
+  providerId: number;
+  reloadSeq?: number;
+};
+
+const HOURS = 24;
+const BUCKET_MINUTES = 60;
+
+type UrlTrend = {
+  baseUrl: string;
+  points: BaseUrlLatencyPoint[];
+  samples: number;
+  failures: number;
+  avgMs: number | null;
+};
+
+function summarize(points: BaseUrlLatencyPoint[]): UrlTrend[] {
+  const byUrl = new Map<string, BaseUrlLatencyPoint[]>();
+  for (const point of points) {
+    const list = byUrl.get(point.base_url) ?? [];
+    list.push(point);
+    byUrl.set(point.base_url, list);
+  }
+
+  return Array.from(byUrl, ([baseUrl, list]) => {
+    let samples = 0;
+    let failures = 0;
+    let weightedSum = 0;
+    let okSamples = 0;
+    for (const point of list) {
+      samples += point.samples;
+      failures += point.failures;
+      if (point.avg_ms != null) {
+        const ok = point.samples - point.failures;
+        weightedSum += point.avg_ms * ok;
+        okSamples += ok;
+      }
+    }
+    return {
+      baseUrl,
+      points: list,
+      samples,
+      failures,
+      avgMs: okSamples > 0 ? weightedSum / okSamples : null,
+    };
+  });
+}
+
+export function BaseUrlLatencyTrend({ providerId, reloadSeq }: BaseUrlLatencyTrendProps) {
+  const [points, setPoints] = useState<BaseUrlLatencyPoint[] | null>(null);
+  const [errorText, setErrorText] = useState<string | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    baseUrlProbeHistory({ providerId, hours: HOURS, bucketMinutes: BUCKET_MINUTES })
+      .then((list) => {
+        if (cancelled) return;
+        setErrorText(null);
+        setPoints(list ?? []);
+      })
+      .catch((err) => {
+        if (cancelled) return;
+        setErrorText(formatUnknownError(err));
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [providerId, reloadSeq]);
+
+  const trends = useMemo(() => summarize(points ?? []), [points]);
+
+  if (errorText) {
+    return <div className="text-xs text-rose-500">延迟历史加载失败：{errorText}</div>;
+  }
+  if (points == null || trends.length === 0) {
+    return <div className="text-xs text-slate-400">近 24 小时暂无探测记录</div>;
+  }
+
+  return (
+    <div className="space-y-1.5">
+      {trends.map((trend) => {
+        const maxAvg = Math.max(1, ...trend.points.map((p) => p.avg_ms ?? 0));
+        const barHeight = (avgMs: number | null) =>
+          avgMs == null ? 100 : Math.max(10, (avgMs / maxAvg) * 100);
+        return (
+          <div key={trend.baseUrl} className="flex items-center gap-3 text-xs">
+            <span
+              className="min-w-0 flex-1 truncate font-mono text-slate-600"
+              title={trend.baseUrl}
+            >
+              {trend.baseUrl}
+            </span>
+            <div className="flex h-4 w-32 shrink-0 items-end gap-px" title="每小时平均延迟">
+              {trend.points.map((point) => (
+                <span
+                  key={point.bucket_ts}
+                  className={cn(
+                    "flex-1 rounded-sm",
+                    point.avg_ms == null ? "bg-rose-300" : "bg-emerald-400"
+                  )}
+                  style={{ height: `${barHeight(point.avg_ms)}%` }}
+                />
+              ))}
+            </div>
+            <span className="w-16 shrink-0 text-right font-mono text-slate-700">
+              {trend.avgMs == null ? "—" : `${Math.round(trend.avgMs)}ms`}
+            </span>
+            <span
+              className={cn(
+                "w-20 shrink-0 text-right",
+                trend.failures > 0 ? "text-rose-500" : "text-slate-400"
+              )}
+            >
+              失败 {trend.failures}/{trend.samples}
+            </span>
+          </div>
+        );
+      })}
+    </div>
+  );
+}
@@ -0,0 +1,132 @@
+// Usage: Used by ProviderEditorDialog to edit a provider's request body patch rules.
+
+import type { Dispatch, SetStateAction } from "react";
+import type { BodyPatchOp, BodyPatchRule } from "../../services/providers";
+import { Button } from "../../ui/Button";
+import { Input } from "../../ui/Input";
+import { Select } from "../../ui/Select";
+
+// `value` is kept as JSON text while editing and parsed on save.
+export type BodyPatchRuleDraft = {
+  op: BodyPatchOp;
+  path: string;
+  value: string;
+  to: string;
+};
+
+export type BodyPatchRulesEditorProps = {
+  rules: BodyPatchRuleDraft[];
+  setRules: Dispatch<SetStateAction<BodyPatchRuleDraft[]>>;
+  disabled?: boolean;
+};
+
+const BODY_PATCH_OP_LABELS: Record<BodyPatchOp, string> = {
+  set: "设置",
+  delete: "删除",
+  rename: "重命名",
+};
+
+export function toBodyPatchRuleDrafts(rules: BodyPatchRule[]): BodyPatchRuleDraft[] {
+  return rules.map((rule) => ({
+    op: rule.op,
+    path: rule.path,
+    value: rule.op === "set" ? JSON.stringify(rule.value) : "",
+    to: rule.to,
+  }));
+}
+
+// Returns the rules to save, or an error message for the first invalid row.
+export function parseBodyPatchRuleDrafts(
+  drafts: BodyPatchRuleDraft[]
+): { rules: BodyPatchRule[] } | { error: string } {
+  const rules: BodyPatchRule[] = [];
+  for (const draft of drafts) {
+    const path = draft.path.trim();
+    if (!path) return { error: "请填写字段路径" };
+    if (draft.op === "rename" && !draft.to.trim()) {
+      return { error: `请填写「${path}」的新字段名` };
+    }
+    let value: unknown = null;
+    if (draft.op === "set") {
+      try {
+        value = JSON.parse(draft.value);
+      } catch {
+        return { error: `「${path}」的值不是合法 JSON（字符串需加引号）` };
+      }
+    }
+    rules.push({ op: draft.op, path, value, to: draft.op === "rename" ? draft.to.trim() : "" });
+  }
+  return { rules };
+}
+
+export function BodyPatchRulesEditor({ rules, setRules, disabled }: BodyPatchRulesEditorProps) {
+  function updateRule(index: number, patch: Partial<BodyPatchRuleDraft>) {
+    setRules((prev) => prev.map((rule, i) => (i === index ? { ...rule, ...patch } : rule)));
+  }
+
+  return (
+    <div className="space-y-2">
+      {rules.map((rule, index) => (
+        <div key={index} className="flex items-center gap-2">
+          <Select
+            value={rule.op}
+            onChange={(e) => updateRule(index, { op: e.currentTarget.value as BodyPatchOp })}
+            disabled={disabled}
+            className="h-8 w-24 py-1 text-sm"
+          >
+            {(Object.keys(BODY_PATCH_OP_LABELS) as BodyPatchOp[]).map((op) => (
+              <option key={op} value={op}>
+                {BODY_PATCH_OP_LABELS[op]}
+              </option>
+            ))}
+          </Select>
+          <Input
+            mono
+            value={rule.path}
+            onChange={(e) => updateRule(index, { path: e.currentTarget.value })}
+            placeholder="$.metadata"
+            disabled={disabled}
+            className="h-8 w-52 py-1 text-sm"
+          />
+          {rule.op === "rename" ? (
+            <Input
+              mono
+              value={rule.to}
+              onChange={(e) => updateRule(index, { to: e.currentTarget.value })}
+              placeholder="max_output_tokens"
+              disabled={disabled}
+              className="h-8 flex-1 py-1 text-sm"
+            />
+          ) : (
+            <Input
+              mono
+              value={rule.op === "set" ? rule.value : ""}
+              onChange={(e) => updateRule(index, { value: e.currentTarget.value })}
+              placeholder={rule.op === "set" ? '0.2 / "text" / {}' : ""}
+              disabled={disabled || rule.op !== "set"}
+              className="h-8 flex-1 py-1 text-sm"
+            />
+          )}
+          <Button
+            onClick={() => setRules((prev) => prev.filter((_, i) => i !== index))}
+            variant="secondary"
+            size="sm"
+            disabled={disabled}
+            className="hover:!bg-rose-50 hover:!text-rose-600 h-8"
+          >
+            ×
+          </Button>
+        </div>
+      ))}
+
+      <Button
+        onClick={() => setRules((prev) => [...prev, { op: "delete", path: "", value: "", to: "" }])}
+        variant="secondary"
+        size="sm"
+        disabled={disabled}
+      >
+        + 添加规则
+      </Button>
+    </div>
+  );
+}
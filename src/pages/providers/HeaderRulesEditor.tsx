@@ -0,0 +1,82 @@
+// Usage: Used by ProviderEditorDialog to edit a provider's outbound header rules.
+
+import type { Dispatch, SetStateAction } from "react";
+import type { ProviderHeaderAction, ProviderHeaderRule } from "../../services/providers";
+import { Button } from "../../ui/Button";
+import { Input } from "../../ui/Input";
+import { Select } from "../../ui/Select";
+
+export type HeaderRulesEditorProps = {
+  rules: ProviderHeaderRule[];
+  setRules: Dispatch<SetStateAction<ProviderHeaderRule[]>>;
+  disabled?: boolean;
+};
+
+const HEADER_ACTION_LABELS: Record<ProviderHeaderAction, string> = {
+  add: "添加",
+  override: "覆盖",
+  remove: "删除",
+};
+
+export function HeaderRulesEditor({ rules, setRules, disabled }: HeaderRulesEditorProps) {
+  function updateRule(index: number, patch: Partial<ProviderHeaderRule>) {
+    setRules((prev) => prev.map((rule, i) => (i === index ? { ...rule, ...patch } : rule)));
+  }
+
+  return (
+    <div className="space-y-2">
+      {rules.map((rule, index) => (
+        <div key={index} className="flex items-center gap-2">
+          <Select
+            value={rule.action}
+            onChange={(e) =>
+              updateRule(index, { action: e.currentTarget.value as ProviderHeaderAction })
+            }
+            disabled={disabled}
+            className="h-8 w-24 py-1 text-sm"
+          >
+            {(Object.keys(HEADER_ACTION_LABELS) as ProviderHeaderAction[]).map((action) => (
+              <option key={action} value={action}>
+                {HEADER_ACTION_LABELS[action]}
+              </option>
+            ))}
+          </Select>
+          <Input
+            mono
+            value={rule.name}
+            onChange={(e) => updateRule(index, { name: e.currentTarget.value })}
+            placeholder="x-api-version"
+            disabled={disabled}
+            className="h-8 w-44 py-1 text-sm"
+          />
+          <Input
+            mono
+            value={rule.action === "remove" ? "" : rule.value}
+            onChange={(e) => updateRule(index, { value: e.currentTarget.value })}
+            placeholder={rule.action === "remove" ? "" : "Token {api_key}"}
+            disabled={disabled || rule.action === "remove"}
+            className="h-8 flex-1 py-1 text-sm"
+          />
+          <Button
+            onClick={() => setRules((prev) => prev.filter((_, i) => i !== index))}
+            variant="secondary"
+            size="sm"
+            disabled={disabled}
+            className="hover:!bg-rose-50 hover:!text-rose-600 h-8"
+          >
+            ×
+          </Button>
+        </div>
+      ))}
+
+      <Button
+        onClick={() => setRules((prev) => [...prev, { action: "override", name: "", value: "" }])}
+        variant="secondary"
+        size="sm"
+        disabled={disabled}
+      >
+        + 添加规则
+      </Button>
+    </div>
+  );
+}
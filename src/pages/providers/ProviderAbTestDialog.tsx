@@ -0,0 +1,258 @@
+// Usage: Used by ProvidersView to run A/B comparisons between two providers and view reports.
+
+import { useCallback, useEffect, useState } from "react";
+import { toast } from "sonner";
+import { logToConsole } from "../../services/consoleLog";
+import {
+  providerAbTestCancel,
+  providerAbTestReport,
+  providerAbTestStart,
+  providerAbTestsList,
+  type AbArmReport,
+  type ProviderAbTest,
+  type ProviderAbTestReport,
+  type ProviderAbTestStatus,
+} from "../../services/providerAbTest";
+import type { CliKey, ProviderSummary } from "../../services/providers";
+import { Button } from "../../ui/Button";
+import { Dialog } from "../../ui/Dialog";
+import { FormField } from "../../ui/FormField";
+import { Input } from "../../ui/Input";
+import { Select } from "../../ui/Select";
+import { cn } from "../../utils/cn";
+import {
+  formatDurationMs,
+  formatInteger,
+  formatPercent,
+  formatUnixSeconds,
+  formatUsd,
+} from "../../utils/formatters";
+
+export type ProviderAbTestDialogProps = {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  cliKey: CliKey;
+  providers: ProviderSummary[];
+};
+
+const STATUS_LABELS: Record<ProviderAbTestStatus, { label: string; tone: string }> = {
+  running: { label: "进行中", tone: "bg-sky-50 text-sky-700" },
+  completed: { label: "已完成", tone: "bg-emerald-50 text-emerald-700" },
+  cancelled: { label: "已取消", tone: "bg-slate-100 text-slate-600" },
+};
+
+const REPORT_ROWS: { label: string; render: (arm: AbArmReport) => string }[] = [
+  { label: "会话数", render: (arm) => formatInteger(arm.sessions) },
+  { label: "请求数", render: (arm) => formatInteger(arm.requests) },
+  { label: "错误率", render: (arm) => formatPercent(arm.error_rate) },
+  { label: "平均耗时", render: (arm) => formatDurationMs(arm.avg_duration_ms) },
+  { label: "平均 TTFB", render: (arm) => formatDurationMs(arm.avg_ttfb_ms) },
+  { label: "总花费", render: (arm) => formatUsd(arm.total_cost_usd) },
+  { label: "每会话花费", render: (arm) => formatUsd(arm.avg_cost_usd_per_session) },
+  { label: "重试次数", render: (arm) => formatInteger(arm.retries) },
+];
+
+export function ProviderAbTestDialog({
+  open,
+  onOpenChange,
+  cliKey,
+  providers,
+}: ProviderAbTestDialogProps) {
+  const [items, setItems] = useState<ProviderAbTest[]>([]);
+  const [providerAId, setProviderAId] = useState<number | null>(null);
+  const [providerBId, setProviderBId] = useState<number | null>(null);
+  const [targetSessions, setTargetSessions] = useState(20);
+  const [report, setReport] = useState<ProviderAbTestReport | null>(null);
+  const [saving, setSaving] = useState(false);
+
+  const providerName = useCallback(
+    (id: number) => providers.find((p) => p.id === id)?.name ?? `#${id}`,
+    [providers]
+  );
+
+  const refresh = useCallback(async () => {
+    try {
+      setItems((await providerAbTestsList(cliKey)) ?? []);
+    } catch (err) {
+      logToConsole("error", "读取 Provider A/B 对比失败", { error: String(err) });
+      toast(`读取 A/B 对比失败：${String(err)}`);
+    }
+  }, [cliKey]);
+
+  useEffect(() => {
+    if (!open) return;
+    setProviderAId(providers[0]?.id ?? null);
+    setProviderBId(providers[1]?.id ?? null);
+    setReport(null);
+    void refresh();
+  }, [open, cliKey, providers, refresh]);
+
+  const canStart = providerAId != null && providerBId != null && providerAId !== providerBId;
+
+  async function start() {
+    if (saving || providerAId == null || providerBId == null || providerAId === providerBId) return;
+    setSaving(true);
+    try {
+      const started = await providerAbTestStart({
+        cliKey,
+        providerAId,
+        providerBId,
+        targetSessions,
+      });
+      if (!started) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      logToConsole("info", "开始 Provider A/B 对比", { test_id: started.id });
+      toast("A/B 对比已开始");
+      await refresh();
+    } catch (err) {
+      logToConsole("error", "开始 Provider A/B 对比失败", { error: String(err) });
+      toast(`开始 A/B 对比失败：${String(err)}`);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  async function cancel(testId: number) {
+    try {
+      await providerAbTestCancel(testId);
+      await refresh();
+    } catch (err) {
+      toast(`取消 A/B 对比失败：${String(err)}`);
+    }
+  }
+
+  async function loadReport(testId: number) {
+    try {
+      setReport(await providerAbTestReport(testId));
+    } catch (err) {
+      logToConsole("error", "读取 Provider A/B 报告失败", { error: String(err) });
+      toast(`读取报告失败：${String(err)}`);
+    }
+  }
+
+  return (
+    <Dialog
+      open={open}
+      onOpenChange={onOpenChange}
+      title="Provider A/B 对比"
+      description="新会话依次交替分配给 A / B 两个 Provider，达到目标会话数后生成延迟、错误率、花费与重试对比报告"
+      className="max-w-4xl"
+    >
+      <div className="space-y-4">
+        <div className="grid gap-3 sm:grid-cols-3">
+          <FormField label="Provider A">
+            <Select
+              value={providerAId ?? ""}
+              onChange={(e) => setProviderAId(Number(e.currentTarget.value) || null)}
+              disabled={saving}
+            >
+              {providers.map((p) => (
+                <option key={p.id} value={p.id}>
+                  {p.name}
+                </option>
+              ))}
+            </Select>
+          </FormField>
+          <FormField label="Provider B">
+            <Select
+              value={providerBId ?? ""}
+              onChange={(e) => setProviderBId(Number(e.currentTarget.value) || null)}
+              disabled={saving}
+            >
+              {providers.map((p) => (
+                <option key={p.id} value={p.id}>
+                  {p.name}
+                </option>
+              ))}
+            </Select>
+          </FormField>
+          <FormField label="目标会话数">
+            <Input
+              type="number"
+              value={targetSessions}
+              min={2}
+              max={10000}
+              onChange={(e) => setTargetSessions(e.currentTarget.valueAsNumber || 2)}
+              disabled={saving}
+            />
+          </FormField>
+        </div>
+        <div className="flex justify-end">
+          <Button
+            onClick={() => void start()}
+            variant="primary"
+            size="sm"
+            disabled={saving || !canStart}
+          >
+            {saving ? "处理中…" : "开始对比"}
+          </Button>
+        </div>
+
+        {items.length === 0 ? (
+          <div className="text-xs text-slate-400">暂无 A/B 对比记录</div>
+        ) : (
+          <div className="space-y-2">
+            {items.map((test) => {
+              const status = STATUS_LABELS[test.status] ?? STATUS_LABELS.cancelled;
+              return (
+                <div
+                  key={test.id}
+                  className="flex items-center gap-3 rounded-lg border border-slate-200 px-3 py-2 text-xs"
+                >
+                  <span className={cn("rounded px-1.5 py-0.5", status.tone)}>{status.label}</span>
+                  <div className="min-w-0 flex-1">
+                    <div className="font-medium text-slate-800">
+                      {providerName(test.provider_a_id)} vs {providerName(test.provider_b_id)}
+                    </div>
+                    <div className="mt-0.5 text-slate-500">
+                      目标 {test.target_sessions} 个会话 · 开始于 {formatUnixSeconds(test.started_at)}
+                    </div>
+                  </div>
+                  <Button onClick={() => void loadReport(test.id)} variant="secondary" size="sm">
+                    报告
+                  </Button>
+                  {test.status === "running" ? (
+                    <Button onClick={() => void cancel(test.id)} variant="secondary" size="sm">
+                      取消
+                    </Button>
+                  ) : null}
+                </div>
+              );
+            })}
+          </div>
+        )}
+
+        {report ? (
+          <table className="w-full text-xs">
+            <thead>
+              <tr className="border-b border-slate-200 text-left text-slate-500">
+                <th className="py-1.5 font-normal">指标</th>
+                <th className="py-1.5 text-right font-normal">
+                  A · {providerName(report.arm_a.provider_id)}
+                </th>
+                <th className="py-1.5 text-right font-normal">
+                  B · {providerName(report.arm_b.provider_id)}
+                </th>
+              </tr>
+            </thead>
+            <tbody>
+              {REPORT_ROWS.map((row) => (
+                <tr key={row.label} className="border-b border-slate-100">
+                  <td className="py-1.5 text-slate-600">{row.label}</td>
+                  <td className="py-1.5 text-right font-mono text-slate-800">
+                    {row.render(report.arm_a)}
+                  </td>
+                  <td className="py-1.5 text-right font-mono text-slate-800">
+                    {row.render(report.arm_b)}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        ) : null}
+      </div>
+    </Dialog>
+  );
+}
@@ -0,0 +1,230 @@
+// Usage: Used by ProvidersView to start / monitor canary rollouts of new providers.
+
+import { useCallback, useEffect, useState } from "react";
+import { toast } from "sonner";
+import { logToConsole } from "../../services/consoleLog";
+import {
+  providerCanariesList,
+  providerCanaryCancel,
+  providerCanaryStart,
+  type CanaryArmStats,
+  type ProviderCanaryStatus,
+  type ProviderCanaryView,
+} from "../../services/providerCanary";
+import type { CliKey, ProviderSummary } from "../../services/providers";
+import { sortModesList, type SortModeSummary } from "../../services/sortModes";
+import { Button } from "../../ui/Button";
+import { Dialog } from "../../ui/Dialog";
+import { FormField } from "../../ui/FormField";
+import { Input } from "../../ui/Input";
+import { Select } from "../../ui/Select";
+import { cn } from "../../utils/cn";
+import { formatUnixSeconds } from "../../utils/formatters";
+
+export type ProviderCanaryDialogProps = {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  cliKey: CliKey;
+  providers: ProviderSummary[];
+  onFinished?: () => void;
+};
+
+const STATUS_LABELS: Record<ProviderCanaryStatus, { label: string; tone: string }> = {
+  running: { label: "灰度中", tone: "bg-sky-50 text-sky-700" },
+  promoted: { label: "已晋升", tone: "bg-emerald-50 text-emerald-700" },
+  demoted: { label: "已降级", tone: "bg-rose-50 text-rose-700" },
+  inconclusive: { label: "样本不足", tone: "bg-amber-50 text-amber-700" },
+  cancelled: { label: "已取消", tone: "bg-slate-100 text-slate-600" },
+};
+
+function formatStats(stats: CanaryArmStats | null) {
+  if (!stats || stats.requests === 0) return "—";
+  const rate = (stats.failures / stats.requests) * 100;
+  return `${stats.failures}/${stats.requests}（${rate.toFixed(1)}%）`;
+}
+
+export function ProviderCanaryDialog({
+  open,
+  onOpenChange,
+  cliKey,
+  providers,
+  onFinished,
+}: ProviderCanaryDialogProps) {
+  const [items, setItems] = useState<ProviderCanaryView[]>([]);
+  const [sortModes, setSortModes] = useState<SortModeSummary[]>([]);
+  const [providerId, setProviderId] = useState<number | null>(null);
+  const [modeId, setModeId] = useState<number | null>(null);
+  const [trafficPercent, setTrafficPercent] = useState(5);
+  const [durationHours, setDurationHours] = useState(24);
+  const [saving, setSaving] = useState(false);
+
+  const providerName = useCallback(
+    (id: number | null) =>
+      id == null ? "—" : (providers.find((p) => p.id === id)?.name ?? `#${id}`),
+    [providers]
+  );
+
+  const refresh = useCallback(async () => {
+    try {
+      const [list, modes] = await Promise.all([providerCanariesList(cliKey), sortModesList()]);
+      setItems(list ?? []);
+      setSortModes(modes ?? []);
+    } catch (err) {
+      logToConsole("error", "读取 Provider 灰度失败", { error: String(err) });
+      toast(`读取灰度失败：${String(err)}`);
+    }
+  }, [cliKey]);
+
+  useEffect(() => {
+    if (!open) return;
+    setProviderId(providers[providers.length - 1]?.id ?? null);
+    setModeId(null);
+    void refresh();
+  }, [open, cliKey, providers, refresh]);
+
+  async function start() {
+    if (saving || providerId == null) return;
+    setSaving(true);
+    try {
+      const started = await providerCanaryStart({
+        cliKey,
+        modeId,
+        providerId,
+        trafficPercent,
+        durationHours,
+      });
+      if (!started) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      logToConsole("info", "开始 Provider 灰度", { canary_id: started.id });
+      toast("灰度已开始");
+      await refresh();
+    } catch (err) {
+      logToConsole("error", "开始 Provider 灰度失败", { error: String(err) });
+      toast(`开始灰度失败：${String(err)}`);
+    } finally {
+      setSaving(false);
+    }
+  }
+
+  async function cancel(canaryId: number) {
+    try {
+      await providerCanaryCancel(canaryId);
+      await refresh();
+    } catch (err) {
+      toast(`取消灰度失败：${String(err)}`);
+    }
+  }
+
+  return (
+    <Dialog
+      open={open}
+      onOpenChange={(nextOpen) => {
+        onOpenChange(nextOpen);
+        if (!nextOpen) onFinished?.();
+      }}
+      title="Provider 灰度"
+      description="按比例为新 Provider 分配流量；到期后根据其错误率与原首选 Provider 对比自动晋升或降级"
+      className="max-w-4xl"
+    >
+      <div className="space-y-4">
+        <div className="grid gap-3 sm:grid-cols-4">
+          <FormField label="灰度 Provider">
+            <Select
+              value={providerId ?? ""}
+              onChange={(e) => setProviderId(Number(e.currentTarget.value) || null)}
+              disabled={saving}
+            >
+              {providers.map((p) => (
+                <option key={p.id} value={p.id}>
+                  {p.name}
+                </option>
+              ))}
+            </Select>
+          </FormField>
+          <FormField label="排序模式">
+            <Select
+              value={modeId ?? ""}
+              onChange={(e) => setModeId(Number(e.currentTarget.value) || null)}
+              disabled={saving}
+            >
+              <option value="">默认顺序</option>
+              {sortModes.map((mode) => (
+                <option key={mode.id} value={mode.id}>
+                  {mode.name}
+                </option>
+              ))}
+            </Select>
+          </FormField>
+          <FormField label="流量比例（%）">
+            <Input
+              type="number"
+              value={trafficPercent}
+              min={1}
+              max={50}
+              onChange={(e) => setTrafficPercent(e.currentTarget.valueAsNumber || 1)}
+              disabled={saving}
+            />
+          </FormField>
+          <FormField label="观察时长（小时）">
+            <Input
+              type="number"
+              value={durationHours}
+              min={1}
+              max={336}
+              onChange={(e) => setDurationHours(e.currentTarget.valueAsNumber || 1)}
+              disabled={saving}
+            />
+          </FormField>
+        </div>
+        <div className="flex justify-end">
+          <Button
+            onClick={() => void start()}
+            variant="primary"
+            size="sm"
+            disabled={saving || providerId == null}
+          >
+            {saving ? "处理中…" : "开始灰度"}
+          </Button>
+        </div>
+
+        {items.length === 0 ? (
+          <div className="text-xs text-slate-400">暂无灰度记录</div>
+        ) : (
+          <div className="space-y-2">
+            {items.map(({ canary, canary_stats, incumbent_stats }) => {
+              const status = STATUS_LABELS[canary.status] ?? STATUS_LABELS.cancelled;
+              return (
+                <div
+                  key={canary.id}
+                  className="flex items-center gap-3 rounded-lg border border-slate-200 px-3 py-2 text-xs"
+                >
+                  <span className={cn("rounded px-1.5 py-0.5", status.tone)}>{status.label}</span>
+                  <div className="min-w-0 flex-1">
+                    <div className="font-medium text-slate-800">
+                      {providerName(canary.provider_id)}
+                      <span className="ml-2 text-slate-500">
+                        {canary.traffic_percent}% · 对比 {providerName(canary.incumbent_provider_id)}
+                      </span>
+                    </div>
+                    <div className="mt-0.5 text-slate-500">
+                      失败率 {formatStats(canary_stats)} vs {formatStats(incumbent_stats)} · 截止{" "}
+                      {formatUnixSeconds(canary.ends_at)}
+                      {canary.decision_reason ? ` · ${canary.decision_reason}` : ""}
+                    </div>
+                  </div>
+                  {canary.status === "running" ? (
+                    <Button onClick={() => void cancel(canary.id)} variant="secondary" size="sm">
+                      取消
+                    </Button>
+                  ) : null}
+                </div>
+              );
+            })}
+          </div>
+        )}
+      </div>
+    </Dialog>
+  );
+}
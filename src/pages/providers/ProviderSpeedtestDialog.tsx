@@ -0,0 +1,182 @@
+// Usage: Used by ProvidersView to benchmark selected providers with the same streaming prompt.
+
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { logToConsole } from "../../services/consoleLog";
+import {
+  providersSpeedtest,
+  type CliKey,
+  type ProviderSpeedtestResult,
+  type ProviderSummary,
+} from "../../services/providers";
+import { Button } from "../../ui/Button";
+import { Dialog } from "../../ui/Dialog";
+import { Input } from "../../ui/Input";
+import { cn } from "../../utils/cn";
+
+const MAX_SELECTED = 16;
+
+export type ProviderSpeedtestDialogProps = {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  cliKey: CliKey;
+  providers: ProviderSummary[];
+};
+
+const TH_CLASS = "border-b border-slate-200 bg-slate-50/60 px-3 py-2 text-left";
+const TD_CLASS = "border-b border-slate-200 px-3 py-2 text-xs text-slate-800";
+
+function formatMs(value: number | null) {
+  return value == null ? "—" : `${value}ms`;
+}
+
+export function ProviderSpeedtestDialog({
+  open,
+  onOpenChange,
+  cliKey,
+  providers,
+}: ProviderSpeedtestDialogProps) {
+  const [selected, setSelected] = useState<number[]>([]);
+  const [model, setModel] = useState("");
+  const [running, setRunning] = useState(false);
+  const [results, setResults] = useState<ProviderSpeedtestResult[]>([]);
+
+  useEffect(() => {
+    if (!open) return;
+    setSelected(providers.filter((p) => p.enabled).map((p) => p.id).slice(0, MAX_SELECTED));
+    setResults([]);
+  }, [open, cliKey, providers]);
+
+  function toggle(providerId: number) {
+    setSelected((prev) => {
+      if (prev.includes(providerId)) return prev.filter((id) => id !== providerId);
+      if (prev.length >= MAX_SELECTED) {
+        toast(`最多同时测速 ${MAX_SELECTED} 个 Provider`);
+        return prev;
+      }
+      return [...prev, providerId];
+    });
+  }
+
+  async function run() {
+    if (running || selected.length === 0) return;
+    setRunning(true);
+    try {
+      const providerIds = providers.filter((p) => selected.includes(p.id)).map((p) => p.id);
+      const list = await providersSpeedtest({ cliKey, providerIds, model: model.trim() || null });
+      if (!list) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      setResults(list);
+      logToConsole("info", "Provider 测速完成", { cli_key: cliKey, count: list.length });
+    } catch (err) {
+      logToConsole("error", "Provider 测速失败", { error: String(err) });
+      toast(`测速失败：${String(err)}`);
+    } finally {
+      setRunning(false);
+    }
+  }
+
+  const fastestTps = Math.max(0, ...results.map((r) => r.tokens_per_second ?? 0));
+
+  return (
+    <Dialog
+      open={open}
+      onOpenChange={(nextOpen) => {
+        if (!nextOpen && running) return;
+        onOpenChange(nextOpen);
+      }}
+      title="Provider 测速"
+      description="向所选 Provider 并发发送相同的小型流式请求，对比首字节时间与输出速度（会产生少量用量）"
+      className="max-w-4xl"
+    >
+      <div className="space-y-4">
+        <div className="flex flex-wrap gap-2">
+          {providers.map((provider) => {
+            const checked = selected.includes(provider.id);
+            return (
+              <button
+                key={provider.id}
+                type="button"
+                onClick={() => toggle(provider.id)}
+                disabled={running}
+                className={cn(
+                  "rounded-lg border px-2.5 py-1 text-xs",
+                  checked
+                    ? "border-accent bg-accent/10 text-accent"
+                    : "border-slate-200 text-slate-600 hover:bg-slate-50"
+                )}
+              >
+                {provider.name}
+              </button>
+            );
+          })}
+        </div>
+
+        <div className="flex items-center gap-2">
+          <Input
+            value={model}
+            onChange={(e) => setModel(e.currentTarget.value)}
+            placeholder="模型（留空使用默认测速模型）"
+            className="h-8 flex-1 font-mono text-sm"
+            disabled={running}
+          />
+          <Button
+            onClick={() => void run()}
+            variant="primary"
+            size="sm"
+            disabled={running || selected.length === 0}
+            className="h-8"
+          >
+            {running ? "测速中…" : `开始测速（${selected.length}）`}
+          </Button>
+        </div>
+
+        {results.length > 0 ? (
+          <table className="w-full border-separate border-spacing-0 text-xs">
+            <thead>
+              <tr className="text-slate-500">
+                <th className={TH_CLASS}>Provider</th>
+                <th className={TH_CLASS}>模型</th>
+                <th className={TH_CLASS}>首字节</th>
+                <th className={TH_CLASS}>总耗时</th>
+                <th className={TH_CLASS}>输出 Tokens</th>
+                <th className={TH_CLASS}>Tokens/s</th>
+              </tr>
+            </thead>
+            <tbody>
+              {results.map((result) => (
+                <tr key={result.provider_id}>
+                  <td className={TD_CLASS}>
+                    <div className="font-medium">{result.provider_name}</div>
+                    {result.error ? (
+                      <div className="mt-0.5 max-w-xs truncate text-rose-500" title={result.error}>
+                        {result.error}
+                      </div>
+                    ) : null}
+                  </td>
+                  <td className={cn(TD_CLASS, "font-mono")}>{result.model}</td>
+                  <td className={cn(TD_CLASS, "font-mono")}>{formatMs(result.ttfb_ms)}</td>
+                  <td className={cn(TD_CLASS, "font-mono")}>{formatMs(result.total_ms)}</td>
+                  <td className={cn(TD_CLASS, "font-mono")}>{result.output_tokens ?? "—"}</td>
+                  <td
+                    className={cn(
+                      TD_CLASS,
+                      "font-mono",
+                      result.tokens_per_second != null &&
+                        result.tokens_per_second === fastestTps &&
+                        "font-semibold text-emerald-600"
+                    )}
+                  >
+                    {result.tokens_per_second == null ? "—" : result.tokens_per_second.toFixed(1)}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        ) : null}
+      </div>
+    </Dialog>
+  );
+}
@@ -42,6 +42,9 @@ import { formatCountdownSeconds, formatUnixSeconds } from "../../utils/formatter
 import { hasTauriRuntime } from "../../services/tauriInvoke";
 import { providerBaseUrlSummary } from "./baseUrl";
 import { ProviderEditorDialog } from "./ProviderEditorDialog";
+import { ProviderAbTestDialog } from "./ProviderAbTestDialog";
+import { ProviderCanaryDialog } from "./ProviderCanaryDialog";
+import { ProviderSpeedtestDialog } from "./ProviderSpeedtestDialog";
 import { FlaskConical } from "lucide-react";
 
 const CIRCUIT_EVENT_REFRESH_THROTTLE_MS = 1000;
@@ -154,6 +157,14 @@ function SortableProviderCard({
                   Claude Models
                 </span>
               ) : null}
+              {provider.exclude_from_stats ? (
+                <span
+                  className="shrink-0 rounded-full bg-amber-50 px-2 py-0.5 font-mono text-[10px] text-amber-700"
+                  title="测试供应商：请求不计入用量统计"
+                >
+                  不计入统计
+                </span>
+              ) : null}
             </div>
             <div
               className="mt-1 truncate font-mono text-xs text-slate-500 cursor-default"
@@ -283,6 +294,9 @@ export function ProvidersView({
   const [deleting, setDeleting] = useState(false);
 
   const [validateDialogOpen, setValidateDialogOpen] = useState(false);
+  const [speedtestOpen, setSpeedtestOpen] = useState(false);
+  const [canaryOpen, setCanaryOpen] = useState(false);
+  const [abTestOpen, setAbTestOpen] = useState(false);
   const [validateProvider, setValidateProvider] = useState<ProviderSummary | null>(null);
 
   useEffect(() => {
@@ -621,6 +635,33 @@ export function ProvidersView({
               </Button>
             ) : null}
 
+            <Button
+              onClick={() => setCanaryOpen(true)}
+              variant="secondary"
+              size="sm"
+              disabled={providers.length < 2}
+            >
+              灰度
+            </Button>
+
+            <Button
+              onClick={() => setAbTestOpen(true)}
+              variant="secondary"
+              size="sm"
+              disabled={providers.length < 2}
+            >
+              A/B
+            </Button>
+
+            <Button
+              onClick={() => setSpeedtestOpen(true)}
+              variant="secondary"
+              size="sm"
+              disabled={providers.length === 0}
+            >
+              测速
+            </Button>
+
             <Button
               onClick={() => {
                 setCreateCliKeyLocked(activeCli);
@@ -679,6 +720,31 @@ export function ProvidersView({
           if (!open) setValidateProvider(null);
         }}
         provider={validateProvider}
+        onProviderUpdated={(next) =>
+          setProviders((prev) => prev.map((p) => (p.id === next.id ? next : p)))
+        }
+      />
+
+      <ProviderCanaryDialog
+        open={canaryOpen}
+        onOpenChange={setCanaryOpen}
+        cliKey={activeCli}
+        providers={providers}
+        onFinished={() => void refreshProviders(activeCli)}
+      />
+
+      <ProviderAbTestDialog
+        open={abTestOpen}
+        onOpenChange={setAbTestOpen}
+        cliKey={activeCli}
+        providers={providers}
+      />
+
+      <ProviderSpeedtestDialog
+        open={speedtestOpen}
+        onOpenChange={setSpeedtestOpen}
+        cliKey={activeCli}
+        providers={providers}
       />
 
       {createCliKeyLocked ? (
@@ -0,0 +1,205 @@
+// Usage: Used by SortModesView to import a shared sort mode (providers matched by name).
+
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import { cliShortLabel } from "../../constants/clis";
+import { logToConsole } from "../../services/consoleLog";
+import {
+  sortModeImport,
+  sortModeImportPreview,
+  type SortModeImportMatchKind,
+  type SortModeImportPreview,
+  type SortModeSummary,
+} from "../../services/sortModes";
+import { Button } from "../../ui/Button";
+import { Dialog } from "../../ui/Dialog";
+import { FormField } from "../../ui/FormField";
+import { Input } from "../../ui/Input";
+import { Textarea } from "../../ui/Textarea";
+import { cn } from "../../utils/cn";
+
+const MATCH_KIND_LABELS: Record<SortModeImportMatchKind, string> = {
+  exact: "精确",
+  normalized: "名称近似",
+  fuzzy: "模糊",
+  unmatched: "未匹配",
+};
+
+const MATCH_KIND_TONES: Record<SortModeImportMatchKind, string> = {
+  exact: "text-emerald-600",
+  normalized: "text-emerald-600",
+  fuzzy: "text-amber-600",
+  unmatched: "text-rose-500",
+};
+
+export type SortModeImportDialogProps = {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  onImported: (mode: SortModeSummary) => void;
+};
+
+export function SortModeImportDialog({
+  open,
+  onOpenChange,
+  onImported,
+}: SortModeImportDialogProps) {
+  const [text, setText] = useState("");
+  const [name, setName] = useState("");
+  const [preview, setPreview] = useState<SortModeImportPreview | null>(null);
+  const [previewing, setPreviewing] = useState(false);
+  const [importing, setImporting] = useState(false);
+
+  useEffect(() => {
+    if (!open) return;
+    setText("");
+    setName("");
+    setPreview(null);
+  }, [open]);
+
+  async function loadPreview() {
+    if (previewing) return;
+    if (!text.trim()) {
+      toast("请粘贴排序模板分享内容");
+      return;
+    }
+    setPreviewing(true);
+    try {
+      const next = await sortModeImportPreview({ text });
+      if (!next) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      setPreview(next);
+      setName(next.name);
+    } catch (err) {
+      setPreview(null);
+      toast(`解析失败：${String(err)}`);
+    } finally {
+      setPreviewing(false);
+    }
+  }
+
+  async function importMode() {
+    if (importing || !preview) return;
+    setImporting(true);
+    try {
+      const result = await sortModeImport({ text, name: name.trim() || null });
+      if (!result) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      const unmatched = result.matches.filter((m) => m.kind === "unmatched").length;
+      logToConsole("info", "导入排序模板", {
+        mode_id: result.mode.id,
+        name: result.mode.name,
+        matched: result.matches.length - unmatched,
+        unmatched,
+      });
+      onImported(result.mode);
+      onOpenChange(false);
+      toast(
+        unmatched > 0
+          ? `已导入「${result.mode.name}」，${unmatched} 个供应商未匹配已跳过`
+          : `已导入「${result.mode.name}」`
+      );
+    } catch (err) {
+      logToConsole("error", "导入排序模板失败", { error: String(err) });
+      toast(`导入失败：${String(err)}`);
+    } finally {
+      setImporting(false);
+    }
+  }
+
+  const matchedCount = preview?.matches.filter((m) => m.provider_id != null).length ?? 0;
+
+  return (
+    <Dialog
+      open={open}
+      onOpenChange={(next) => {
+        if (!next && importing) return;
+        onOpenChange(next);
+      }}
+      title="导入排序模板"
+      description="粘贴他人导出的排序模板；供应商按名称匹配本机同 CLI 的供应商，不包含 Base URL 与 API Key。"
+      className="max-w-2xl"
+    >
+      <div className="space-y-4">
+        <FormField label="分享内容">
+          <Textarea
+            mono
+            rows={6}
+            value={text}
+            onChange={(e) => {
+              setText(e.currentTarget.value);
+              setPreview(null);
+            }}
+            placeholder='{ "format": "aio-coding-hub.sort-mode", ... }'
+            className="text-xs"
+            disabled={importing}
+          />
+        </FormField>
+
+        {preview ? (
+          <>
+            <FormField label="名称" hint="与现有模板重名时自动追加序号">
+              <Input
+                value={name}
+                onChange={(e) => setName(e.currentTarget.value)}
+                disabled={importing}
+              />
+            </FormField>
+
+            <div className="space-y-1">
+              <div className="text-xs text-slate-500">
+                匹配 {matchedCount} / {preview.matches.length} 个供应商；未匹配的条目将跳过
+              </div>
+              <div className="max-h-64 overflow-y-auto rounded-lg border border-slate-200">
+                {preview.matches.length === 0 ? (
+                  <div className="px-3 py-2 text-sm text-slate-500">模板中没有供应商</div>
+                ) : (
+                  preview.matches.map((match, index) => (
+                    <div
+                      key={`${match.cli_key}-${index}`}
+                      className="flex items-center gap-3 border-b border-slate-100 px-3 py-1.5 text-sm last:border-b-0"
+                    >
+                      <span className="w-14 shrink-0 text-xs text-slate-500">
+                        {cliShortLabel(match.cli_key)}
+                      </span>
+                      <span className="min-w-0 flex-1 truncate font-mono">{match.name}</span>
+                      <span className="min-w-0 flex-1 truncate text-slate-600">
+                        {match.provider_name ? `→ ${match.provider_name}` : "—"}
+                      </span>
+                      <span
+                        className={cn(
+                          "w-16 shrink-0 text-right text-xs",
+                          MATCH_KIND_TONES[match.kind]
+                        )}
+                      >
+                        {MATCH_KIND_LABELS[match.kind]}
+                      </span>
+                    </div>
+                  ))
+                )}
+              </div>
+            </div>
+          </>
+        ) : null}
+
+        <div className="flex items-center justify-end gap-2 border-t border-slate-100 pt-3">
+          <Button onClick={() => onOpenChange(false)} variant="secondary" disabled={importing}>
+            取消
+          </Button>
+          {preview ? (
+            <Button onClick={importMode} variant="primary" disabled={importing}>
+              {importing ? "导入中…" : "导入"}
+            </Button>
+          ) : (
+            <Button onClick={loadPreview} variant="primary" disabled={previewing}>
+              {previewing ? "解析中…" : "预览匹配"}
+            </Button>
+          )}
+        </div>
+      </div>
+    </Dialog>
+  );
+}
@@ -24,10 +24,15 @@ import {
   sortModeActiveList,
   sortModeCreate,
   sortModeDelete,
+  sortModeExport,
+  sortModeProviderWeightSet,
+  sortModeProviderWeightsList,
   sortModeProvidersList,
   sortModeProvidersSetOrder,
   sortModeRename,
+  sortModeStrategySet,
   sortModesList,
+  type SortModeStrategy,
   type SortModeSummary,
 } from "../../services/sortModes";
 import { Button } from "../../ui/Button";
@@ -35,14 +40,25 @@ import { Card } from "../../ui/Card";
 import { Dialog } from "../../ui/Dialog";
 import { FormField } from "../../ui/FormField";
 import { Input } from "../../ui/Input";
+import { Select } from "../../ui/Select";
 import { cn } from "../../utils/cn";
 import { providerBaseUrlSummary } from "./baseUrl";
+import { SortModeImportDialog } from "./SortModeImportDialog";
+
+const STRATEGY_HINTS: Record<SortModeStrategy, string> = {
+  ordered: "按列表顺序路由，失败后依次切换。",
+  weighted: "按权重随机选择首个 Provider（权重 0 仅作兜底）；同一会话保持首次选择。",
+  round_robin: "每次请求轮换首个 Provider；同一会话保持首次选择。",
+};
 
 type SortableModeProviderRowProps = {
   providerId: number;
   provider: ProviderSummary | null;
   disabled: boolean;
   onRemove: (providerId: number) => void;
+  // Only shown for the weighted strategy.
+  weight: number | null;
+  onWeightCommit: (providerId: number, weight: number) => void;
 };
 
 function SortableModeProviderRow({
@@ -50,7 +66,14 @@ function SortableModeProviderRow({
   provider,
   disabled,
   onRemove,
+  weight,
+  onWeightCommit,
 }: SortableModeProviderRowProps) {
+  const [weightText, setWeightText] = useState(weight == null ? "" : String(weight));
+  useEffect(() => {
+    setWeightText(weight == null ? "" : String(weight));
+  }, [weight]);
+
   const { attributes, listeners, setNodeRef, transform, transition, isDragging } = useSortable({
     id: providerId,
     disabled,
@@ -98,6 +121,29 @@ function SortableModeProviderRow({
           className="flex flex-wrap items-center gap-2"
           onPointerDown={(e) => e.stopPropagation()}
         >
+          {weight != null ? (
+            <label className="flex items-center gap-1 text-xs text-slate-500">
+              权重
+              <Input
+                type="number"
+                min={0}
+                max={100}
+                value={weightText}
+                onChange={(e) => setWeightText(e.currentTarget.value)}
+                onBlur={() => {
+                  const next = Number(weightText);
+                  if (!Number.isInteger(next) || next < 0 || next > 100) {
+                    toast("权重需为 0-100 的整数");
+                    setWeightText(String(weight));
+                    return;
+                  }
+                  if (next !== weight) onWeightCommit(providerId, next);
+                }}
+                disabled={disabled}
+                className="h-8 w-20"
+              />
+            </label>
+          ) : null}
           <Button
             onClick={() => onRemove(providerId)}
             variant="secondary"
@@ -151,6 +197,8 @@ export function SortModesView({
   const [modeProvidersLoading, setModeProvidersLoading] = useState(false);
   const [modeProvidersAvailable, setModeProvidersAvailable] = useState<boolean | null>(null);
   const [modeProvidersSaving, setModeProvidersSaving] = useState(false);
+  const [modeProviderWeights, setModeProviderWeights] = useState<Record<number, number>>({});
+  const [strategySaving, setStrategySaving] = useState(false);
 
   const [createModeDialogOpen, setCreateModeDialogOpen] = useState(false);
   const [createModeName, setCreateModeName] = useState("");
@@ -163,6 +211,9 @@ export function SortModesView({
   const [deleteModeTarget, setDeleteModeTarget] = useState<SortModeSummary | null>(null);
   const [deleteModeDeleting, setDeleteModeDeleting] = useState(false);
 
+  const [importModeDialogOpen, setImportModeDialogOpen] = useState(false);
+  const [exportingModeId, setExportingModeId] = useState<number | null>(null);
+
   const sensors = useSensors(
     useSensor(PointerSensor, {
       activationConstraint: { distance: 8 },
@@ -291,6 +342,34 @@ export function SortModesView({
     };
   }, [activeCli, activeModeId]);
 
+  const selectedStrategy = selectedMode?.strategy ?? "ordered";
+
+  useEffect(() => {
+    if (activeModeId == null || selectedStrategy !== "weighted") {
+      setModeProviderWeights({});
+      return;
+    }
+
+    let cancelled = false;
+    sortModeProviderWeightsList({ mode_id: activeModeId, cli_key: activeCli })
+      .then((rows) => {
+        if (cancelled || !rows) return;
+        setModeProviderWeights(Object.fromEntries(rows.map((r) => [r.provider_id, r.weight])));
+      })
+      .catch((err) => {
+        if (cancelled) return;
+        logToConsole("error", "读取排序模板权重失败", {
+          error: String(err),
+          mode_id: activeModeId,
+          cli: activeCli,
+        });
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, [activeCli, activeModeId, selectedStrategy, modeProviderIds]);
+
   useEffect(() => {
     if (!createModeDialogOpen) return;
     setCreateModeName("");
@@ -393,6 +472,25 @@ export function SortModesView({
     }
   }
 
+  async function exportSortMode(mode: SortModeSummary) {
+    if (exportingModeId != null) return;
+    setExportingModeId(mode.id);
+    try {
+      const text = await sortModeExport({ mode_id: mode.id });
+      if (text == null) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      await navigator.clipboard.writeText(text);
+      toast("已复制排序模板分享内容（不含 Base URL 与 API Key）");
+    } catch (err) {
+      logToConsole("error", "导出排序模板失败", { error: String(err), mode_id: mode.id });
+      toast(`导出失败：${String(err)}`);
+    } finally {
+      setExportingModeId(null);
+    }
+  }
+
   async function persistModeProvidersOrder(
     modeId: number,
     cliKey: CliKey,
@@ -435,6 +533,57 @@ export function SortModesView({
     }
   }
 
+  async function changeModeStrategy(strategy: SortModeStrategy) {
+    if (!selectedMode || strategySaving) return;
+    setStrategySaving(true);
+    try {
+      const saved = await sortModeStrategySet({ mode_id: selectedMode.id, strategy });
+      if (!saved) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      setSortModes((prev) => prev.map((m) => (m.id === saved.id ? saved : m)));
+      toast("路由策略已更新");
+    } catch (err) {
+      logToConsole("error", "更新排序模板路由策略失败", {
+        error: String(err),
+        mode_id: selectedMode.id,
+      });
+      toast(`路由策略更新失败：${String(err)}`);
+    } finally {
+      setStrategySaving(false);
+    }
+  }
+
+  async function commitProviderWeight(providerId: number, weight: number) {
+    const modeId = activeModeIdRef.current;
+    if (modeId == null) return;
+    const cliKey = activeCliRef.current;
+    try {
+      const rows = await sortModeProviderWeightSet({
+        mode_id: modeId,
+        cli_key: cliKey,
+        provider_id: providerId,
+        weight,
+      });
+      if (!rows) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      if (activeModeIdRef.current === modeId && activeCliRef.current === cliKey) {
+        setModeProviderWeights(Object.fromEntries(rows.map((r) => [r.provider_id, r.weight])));
+      }
+    } catch (err) {
+      logToConsole("error", "更新 Provider 权重失败", {
+        error: String(err),
+        mode_id: modeId,
+        cli: cliKey,
+        provider_id: providerId,
+      });
+      toast(`权重更新失败：${String(err)}`);
+    }
+  }
+
   function addProviderToMode(providerId: number) {
     if (activeModeIdRef.current == null) return;
     const modeId = activeModeIdRef.current;
@@ -513,11 +662,23 @@ export function SortModesView({
             >
               刷新
             </Button>
+            <Button onClick={() => setImportModeDialogOpen(true)} variant="secondary" size="sm">
+              导入
+            </Button>
             <Button onClick={() => setCreateModeDialogOpen(true)} variant="primary" size="sm">
               新建排序模板
             </Button>
             {selectedMode ? (
               <>
+                <Button
+                  onClick={() => void exportSortMode(selectedMode)}
+                  variant="secondary"
+                  size="sm"
+                  disabled={exportingModeId != null}
+                  title="复制供应商顺序与权重（按名称引用，不含 Base URL 与 API Key）"
+                >
+                  导出
+                </Button>
                 <Button onClick={() => setRenameModeDialogOpen(true)} variant="secondary" size="sm">
                   重命名
                 </Button>
@@ -637,7 +798,27 @@ export function SortModesView({
                     ? "请选择一个自定义排序模板进行编辑；Default 的顺序请在「供应商」视图调整。"
                     : "严格子集：激活后仅使用该列表中的 Provider 参与路由（仍会过滤未启用）。"}
                 </div>
+                {selectedMode ? (
+                  <div className="mt-1 text-xs text-slate-500">
+                    {STRATEGY_HINTS[selectedStrategy]}
+                  </div>
+                ) : null}
               </div>
+              {selectedMode ? (
+                <Select
+                  value={selectedStrategy}
+                  onChange={(e) =>
+                    void changeModeStrategy(e.currentTarget.value as SortModeStrategy)
+                  }
+                  disabled={strategySaving}
+                  className="w-32 shrink-0"
+                  title="路由策略（对所有 CLI 生效）"
+                >
+                  <option value="ordered">顺序</option>
+                  <option value="weighted">加权随机</option>
+                  <option value="round_robin">轮询</option>
+                </Select>
+              ) : null}
             </div>
 
             <div className="mt-3 lg:min-h-0 lg:flex-1 lg:overflow-auto lg:pr-1">
@@ -672,6 +853,12 @@ export function SortModesView({
                           provider={providersById[providerId] ?? null}
                           disabled={modeProvidersSaving}
                           onRemove={removeProviderFromMode}
+                          weight={
+                            selectedStrategy === "weighted"
+                              ? (modeProviderWeights[providerId] ?? 1)
+                              : null
+                          }
+                          onWeightCommit={(id, weight) => void commitProviderWeight(id, weight)}
                         />
                       ))}
                     </div>
@@ -748,6 +935,15 @@ export function SortModesView({
         </div>
       </Dialog>
 
+      <SortModeImportDialog
+        open={importModeDialogOpen}
+        onOpenChange={setImportModeDialogOpen}
+        onImported={(mode) => {
+          setSortModes((prev) => [...prev, mode]);
+          selectEditingMode(mode.id);
+        }}
+      />
+
       <Dialog
         open={!!deleteModeTarget}
         onOpenChange={(open) => {
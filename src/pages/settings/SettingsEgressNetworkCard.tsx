@@ -0,0 +1,237 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
+import type { AppAboutInfo } from "../../services/appAbout";
+import { logToConsole } from "../../services/consoleLog";
+import {
+  settingsGet,
+  type EgressHostOverride,
+  type EgressIpFamily,
+  type EgressNetworkConfig,
+} from "../../services/settings";
+import {
+  gatewayNetworkTest,
+  settingsEgressNetworkSet,
+  type NetworkTestResult,
+} from "../../services/settingsEgressNetwork";
+import { Button } from "../../ui/Button";
+import { Card } from "../../ui/Card";
+import { Input } from "../../ui/Input";
+import { Select } from "../../ui/Select";
+import { SettingsRow } from "../../ui/SettingsRow";
+import { Textarea } from "../../ui/Textarea";
+
+const DEFAULT_EGRESS_NETWORK: EgressNetworkConfig = {
+  proxy: "system",
+  host_overrides: [],
+  ip_family: "auto",
+};
+
+const ROUTE_LABELS: Record<NetworkTestResult["route"], string> = {
+  direct: "直连",
+  system: "系统代理",
+  proxy: "代理",
+};
+
+function formatHostOverrides(items: EgressHostOverride[]) {
+  return items.map((item) => [item.host, ...item.ips].join(" ")).join("\n");
+}
+
+function parseHostOverrides(text: string): EgressHostOverride[] {
+  return text
+    .split("\n")
+    .map((line) => line.trim())
+    .filter((line) => line && !line.startsWith("#"))
+    .map((line) => {
+      const [host, ...ips] = line.split(/\s+/);
+      return { host, ips };
+    });
+}
+
+export function SettingsEgressNetworkCard({ about }: { about: AppAboutInfo | null }) {
+  const [network, setNetwork] = useState<EgressNetworkConfig>(DEFAULT_EGRESS_NETWORK);
+  const [proxyText, setProxyText] = useState(DEFAULT_EGRESS_NETWORK.proxy);
+  const [overridesText, setOverridesText] = useState("");
+  const [testUrl, setTestUrl] = useState("https://api.anthropic.com");
+  const [testing, setTesting] = useState(false);
+  const [testResult, setTestResult] = useState<NetworkTestResult | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    settingsGet()
+      .then((settings) => {
+        if (cancelled || !settings) return;
+        const next = settings.egress_network ?? DEFAULT_EGRESS_NETWORK;
+        setNetwork(next);
+        setProxyText(next.proxy);
+        setOverridesText(formatHostOverrides(next.host_overrides));
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  async function commitNetwork(next: EgressNetworkConfig) {
+    const previous = network;
+    const unchanged =
+      next.proxy === previous.proxy &&
+      next.ip_family === previous.ip_family &&
+      formatHostOverrides(next.host_overrides) === formatHostOverrides(previous.host_overrides);
+    if (unchanged) return;
+    const resetInputs = () => {
+      setNetwork(previous);
+      setProxyText(previous.proxy);
+      setOverridesText(formatHostOverrides(previous.host_overrides));
+    };
+    setNetwork(next);
+    try {
+      const updated = await settingsEgressNetworkSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        resetInputs();
+        return;
+      }
+      const saved = updated.egress_network ?? next;
+      setNetwork(saved);
+      setProxyText(saved.proxy);
+      setOverridesText(formatHostOverrides(saved.host_overrides));
+      logToConsole("info", "更新出站网络设置", {
+        proxy: saved.proxy === "system" || saved.proxy === "none" ? saved.proxy : "custom",
+        host_overrides: saved.host_overrides.length,
+        ip_family: saved.ip_family,
+      });
+    } catch (err) {
+      logToConsole("error", "更新出站网络设置失败", { error: String(err) });
+      toast(`更新出站网络设置失败：${String(err)}`);
+      resetInputs();
+    }
+  }
+
+  async function runTest() {
+    const url = testUrl.trim();
+    if (!url || testing) return;
+    setTesting(true);
+    setTestResult(null);
+    try {
+      const result = await gatewayNetworkTest(url);
+      if (!result) {
+        toast("仅在 Tauri Desktop 环境可用");
+        return;
+      }
+      setTestResult(result);
+      logToConsole(result.ok ? "info" : "warn", "出站网络测试", {
+        url: result.url,
+        route: result.route,
+        status: result.status,
+        elapsed_ms: result.elapsed_ms,
+        error: result.error,
+      });
+    } catch (err) {
+      logToConsole("error", "出站网络测试失败", { error: String(err) });
+      toast(`出站网络测试失败：${String(err)}`);
+    } finally {
+      setTesting(false);
+    }
+  }
+
+  return (
+    <Card>
+      <div className="mb-4 font-semibold text-slate-900">出站网络</div>
+      <div className="divide-y divide-slate-100">
+        <SettingsRow label="出站代理">
+          <Input
+            value={proxyText}
+            onChange={(e) => setProxyText(e.currentTarget.value)}
+            onBlur={() => void commitNetwork({ ...network, proxy: proxyText.trim() || "system" })}
+            onKeyDown={(e) => {
+              if (e.key === "Enter") e.currentTarget.blur();
+            }}
+            placeholder="system"
+            mono
+            className="w-72 text-xs"
+            disabled={!about}
+            title="system = 系统代理环境变量；none = 直连；或 http(s):// / socks5(h):// 代理地址。供应商出站代理留空 / system 时使用此设置"
+          />
+        </SettingsRow>
+        <SettingsRow label="IP 协议">
+          <Select
+            value={network.ip_family}
+            onChange={(e) =>
+              void commitNetwork({
+                ...network,
+                ip_family: e.currentTarget.value as EgressIpFamily,
+              })
+            }
+            disabled={!about}
+            className="w-36"
+          >
+            <option value="auto">自动</option>
+            <option value="ipv4">仅 IPv4</option>
+            <option value="ipv6">仅 IPv6</option>
+          </Select>
+        </SettingsRow>
+        <SettingsRow label="域名解析覆盖">
+          <Textarea
+            value={overridesText}
+            onChange={(e) => setOverridesText(e.currentTarget.value)}
+            onBlur={() =>
+              void commitNetwork({
+                ...network,
+                host_overrides: parseHostOverrides(overridesText),
+              })
+            }
+            placeholder={"每行：域名 IP [IP…]\n如 api.example.com 203.0.113.7 2001:db8::7"}
+            rows={3}
+            mono
+            className="w-72 text-xs"
+            disabled={!about}
+          />
+        </SettingsRow>
+        <SettingsRow label="连通性测试">
+          <div className="flex items-center gap-2">
+            <Input
+              value={testUrl}
+              onChange={(e) => setTestUrl(e.currentTarget.value)}
+              onKeyDown={(e) => {
+                if (e.key === "Enter") void runTest();
+              }}
+              placeholder="https://api.anthropic.com"
+              mono
+              className="w-52 text-xs"
+              disabled={!about}
+            />
+            <Button
+              onClick={() => void runTest()}
+              variant="secondary"
+              size="sm"
+              disabled={testing || !about || !testUrl.trim()}
+            >
+              {testing ? "测试中…" : "测试"}
+            </Button>
+          </div>
+        </SettingsRow>
+        {testResult ? (
+          <div className="space-y-1 py-3 text-xs text-slate-600">
+            <div>
+              <span className={testResult.ok ? "text-emerald-600" : "text-rose-600"}>
+                {testResult.ok ? `可达（HTTP ${testResult.status}）` : "不可达"}
+              </span>
+              <span className="ml-2">
+                {ROUTE_LABELS[testResult.route]} · {testResult.elapsed_ms} ms
+              </span>
+            </div>
+            <div className="font-mono">
+              解析：
+              {testResult.resolved_addrs.length > 0
+                ? testResult.resolved_addrs.join(", ")
+                : (testResult.dns_error ?? "-")}
+            </div>
+            {testResult.error ? (
+              <div className="break-all font-mono text-rose-600">{testResult.error}</div>
+            ) : null}
+          </div>
+        ) : null}
+      </div>
+    </Card>
+  );
+}
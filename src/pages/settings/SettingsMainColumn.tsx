@@ -137,8 +137,13 @@ export function SettingsMainColumn({
                     toast("停止失败：当前环境不可用或 command 未注册");
                     return;
                   }
-                  logToConsole("info", "停止本地网关");
-                  toast("本地网关已停止");
+                  const drain = status.last_drain;
+                  logToConsole("info", "停止本地网关", { drain });
+                  toast(
+                    drain && drain.drained + drain.aborted > 0
+                      ? `本地网关已停止（排空 ${drain.drained} 个请求，中断 ${drain.aborted} 个）`
+                      : "本地网关已停止"
+                  );
                 }}
                 variant="secondary"
                 size="sm"
@@ -24,6 +24,7 @@ import { SettingsAboutCard } from "./SettingsAboutCard";
 import { SettingsDataManagementCard } from "./SettingsDataManagementCard";
 import { SettingsDataSyncCard } from "./SettingsDataSyncCard";
 import { SettingsDialogs } from "./SettingsDialogs";
+import { SettingsEgressNetworkCard } from "./SettingsEgressNetworkCard";
 import { SettingsUpdateCard } from "./SettingsUpdateCard";
 
 type AvailableStatus = "checking" | "available" | "unavailable";
@@ -271,6 +272,8 @@ export function SettingsSidebar({ updateMeta }: SettingsSidebarProps) {
           checkUpdate={checkUpdate}
         />
 
+        <SettingsEgressNetworkCard about={about} />
+
         <SettingsDataManagementCard
           about={about}
           dbDiskUsageAvailable={dbDiskUsageAvailable}
@@ -1,7 +1,24 @@
+import { useEffect, useState } from "react";
+import { toast } from "sonner";
 import type { AppAboutInfo } from "../../services/appAbout";
+import { logToConsole } from "../../services/consoleLog";
+import { settingsGet, type UpdateChannel, type UpdateMirrorConfig } from "../../services/settings";
+import {
+  settingsUpdateBackgroundDownloadSet,
+  settingsUpdateChannelSet,
+  settingsUpdateMirrorSet,
+  updaterRollback,
+  updaterRollbackInfoGet,
+} from "../../services/updater";
 import { Button } from "../../ui/Button";
 import { Card } from "../../ui/Card";
+import { Input } from "../../ui/Input";
+import { Select } from "../../ui/Select";
 import { SettingsRow } from "../../ui/SettingsRow";
+import { Switch } from "../../ui/Switch";
+import { Textarea } from "../../ui/Textarea";
+
+const DEFAULT_UPDATE_MIRROR: UpdateMirrorConfig = { mirrors: [], timeout_secs: 15 };
 
 export function SettingsUpdateCard({
   about,
@@ -12,6 +29,135 @@ export function SettingsUpdateCard({
   checkingUpdate: boolean;
   checkUpdate: () => Promise<void>;
 }) {
+  const [channel, setChannel] = useState<UpdateChannel>("stable");
+  const [backgroundDownload, setBackgroundDownload] = useState<boolean>(true);
+  const [mirror, setMirror] = useState<UpdateMirrorConfig>(DEFAULT_UPDATE_MIRROR);
+  const [mirrorsText, setMirrorsText] = useState("");
+  const [mirrorTimeout, setMirrorTimeout] = useState(DEFAULT_UPDATE_MIRROR.timeout_secs);
+  const [previousVersion, setPreviousVersion] = useState<string | null>(null);
+  const [rollingBack, setRollingBack] = useState(false);
+
+  useEffect(() => {
+    let cancelled = false;
+    settingsGet()
+      .then((settings) => {
+        if (cancelled || !settings) return;
+        setChannel(settings.update_channel ?? "stable");
+        setBackgroundDownload(settings.update_background_download ?? true);
+        const nextMirror = settings.update_mirror ?? DEFAULT_UPDATE_MIRROR;
+        setMirror(nextMirror);
+        setMirrorsText(nextMirror.mirrors.join("\n"));
+        setMirrorTimeout(nextMirror.timeout_secs);
+      })
+      .catch(() => {});
+    updaterRollbackInfoGet()
+      .then((info) => {
+        if (cancelled || !info) return;
+        setPreviousVersion(info.previous_version);
+      })
+      .catch(() => {});
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  async function commitChannel(next: UpdateChannel) {
+    const previous = channel;
+    setChannel(next);
+    try {
+      const updated = await settingsUpdateChannelSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setChannel(previous);
+        return;
+      }
+      logToConsole("info", "更新更新通道", { channel: next });
+      toast(next === "beta" ? "已切换到 Beta 通道" : "已切换到稳定版通道");
+    } catch (err) {
+      logToConsole("error", "更新更新通道失败", { error: String(err) });
+      toast("切换更新通道失败：请稍后重试");
+      setChannel(previous);
+    }
+  }
+
+  async function commitBackgroundDownload(next: boolean) {
+    setBackgroundDownload(next);
+    try {
+      const updated = await settingsUpdateBackgroundDownloadSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        setBackgroundDownload(!next);
+        return;
+      }
+      logToConsole("info", "更新后台下载更新设置", { enabled: next });
+    } catch (err) {
+      logToConsole("error", "更新后台下载更新设置失败", { error: String(err) });
+      toast("更新设置失败：请稍后重试");
+      setBackgroundDownload(!next);
+    }
+  }
+
+  async function commitMirror(next: UpdateMirrorConfig) {
+    const previous = mirror;
+    const unchanged =
+      next.timeout_secs === previous.timeout_secs &&
+      next.mirrors.join("\n") === previous.mirrors.join("\n");
+    if (unchanged) return;
+    const resetInputs = () => {
+      setMirrorsText(previous.mirrors.join("\n"));
+      setMirrorTimeout(previous.timeout_secs);
+    };
+    try {
+      const updated = await settingsUpdateMirrorSet(next);
+      if (!updated) {
+        toast("仅在 Tauri Desktop 环境可用");
+        resetInputs();
+        return;
+      }
+      const saved = updated.update_mirror ?? next;
+      setMirror(saved);
+      setMirrorsText(saved.mirrors.join("\n"));
+      setMirrorTimeout(saved.timeout_secs);
+      logToConsole("info", "更新下载镜像源", saved);
+    } catch (err) {
+      logToConsole("error", "更新下载镜像源失败", { error: String(err) });
+      toast(`更新下载镜像源失败：${String(err)}`);
+      resetInputs();
+    }
+  }
+
+  function parseMirrors(text: string) {
+    return text
+      .split("\n")
+      .map((line) => line.trim())
+      .filter(Boolean);
+  }
+
+  async function rollback() {
+    if (!previousVersion || rollingBack) return;
+    const ok = window.confirm(
+      `确认回滚到 v${previousVersion}？将使用升级前保留（或重新下载）的安装包，完成后自动重启（网关会先停止）。`
+    );
+    if (!ok) return;
+
+    setRollingBack(true);
+    const toastId = toast.loading(`正在准备 v${previousVersion}…`);
+    try {
+      const target = await updaterRollback();
+      if (!target) {
+        toast("仅在 Tauri Desktop 环境可用", { id: toastId });
+        return;
+      }
+      logToConsole("info", "回滚版本", { target });
+      toast.loading(`已就绪 v${target}，正在重启…`, { id: toastId });
+    } catch (err) {
+      logToConsole("error", "回滚版本失败", { error: String(err) });
+      toast(`回滚失败：${String(err)}`, { id: toastId });
+    } finally {
+      setRollingBack(false);
+    }
+  }
+
   return (
     <Card>
       <div className="mb-4 font-semibold text-slate-900">软件更新</div>
@@ -26,6 +172,78 @@ export function SettingsUpdateCard({
             {checkingUpdate ? "检查中…" : about?.run_mode === "portable" ? "打开" : "检查"}
           </Button>
         </SettingsRow>
+        {about?.run_mode !== "portable" ? (
+          <SettingsRow label="更新通道">
+            <Select
+              value={channel}
+              onChange={(e) => void commitChannel(e.currentTarget.value as UpdateChannel)}
+              disabled={!about}
+              className="w-36"
+            >
+              <option value="stable">稳定版</option>
+              <option value="beta">Beta（抢先体验）</option>
+            </Select>
+          </SettingsRow>
+        ) : null}
+        {about?.run_mode !== "portable" ? (
+          <SettingsRow label="后台下载，退出时安装">
+            <Switch
+              checked={backgroundDownload}
+              onCheckedChange={(checked) => void commitBackgroundDownload(checked)}
+              disabled={!about}
+            />
+          </SettingsRow>
+        ) : null}
+        {about?.run_mode !== "portable" ? (
+          <SettingsRow label="下载镜像源">
+            <Textarea
+              value={mirrorsText}
+              onChange={(e) => setMirrorsText(e.currentTarget.value)}
+              onBlur={() => void commitMirror({ ...mirror, mirrors: parseMirrors(mirrorsText) })}
+              placeholder={"每行一个，GitHub 不可用时按顺序回退\n如 https://ghproxy.example/"}
+              rows={3}
+              mono
+              className="w-72 text-xs"
+              disabled={!about}
+            />
+          </SettingsRow>
+        ) : null}
+        {about?.run_mode !== "portable" ? (
+          <SettingsRow label="回退超时">
+            <div className="flex items-center gap-2">
+              <Input
+                type="number"
+                value={mirrorTimeout}
+                onChange={(e) => {
+                  const next = e.currentTarget.valueAsNumber;
+                  if (Number.isFinite(next)) setMirrorTimeout(next);
+                }}
+                onBlur={() => void commitMirror({ ...mirror, timeout_secs: mirrorTimeout })}
+                onKeyDown={(e) => {
+                  if (e.key === "Enter") e.currentTarget.blur();
+                }}
+                style={{ width: "5rem" }}
+                min={3}
+                max={300}
+                disabled={!about}
+                title="检查超时 / 下载无数据超过该时长后切换到下一个下载源"
+              />
+              <span className="w-8 text-sm text-slate-500">秒</span>
+            </div>
+          </SettingsRow>
+        ) : null}
+        {about?.run_mode !== "portable" && previousVersion ? (
+          <SettingsRow label="回滚版本">
+            <Button
+              onClick={() => void rollback()}
+              variant="secondary"
+              size="sm"
+              disabled={rollingBack || !about}
+            >
+              {rollingBack ? "回滚中…" : `回滚到 v${previousVersion}`}
+            </Button>
+          </SettingsRow>
+        ) : null}
       </div>
     </Card>
   );
@@ -0,0 +1,63 @@
+// Usage: Suggest Claude model slots (main / reasoning / haiku / sonnet / opus) from validation results.
+
+import type { ClaudeModels } from "./providers";
+
+export type ClaudeModelSlotKey = keyof ClaudeModels;
+
+export type ClaudeModelValidationOutcome = {
+  model: string;
+  pass: boolean;
+  // Whether the run produced thinking output (thinking block / thinking chars).
+  thinking: boolean;
+};
+
+export const CLAUDE_MODEL_SLOTS: Array<{ key: ClaudeModelSlotKey; label: string }> = [
+  { key: "main_model", label: "主模型" },
+  { key: "reasoning_model", label: "推理模型(Thinking)" },
+  { key: "haiku_model", label: "Haiku 默认模型" },
+  { key: "sonnet_model", label: "Sonnet 默认模型" },
+  { key: "opus_model", label: "Opus 默认模型" },
+];
+
+/**
+ * 根据验证结果推荐模型槽位。
+ * - `outcomes` 需按时间倒序；同一模型只看最近一次结果（最近一次失败则不推荐）
+ * - Haiku / Sonnet / Opus 取名称包含对应系列的最近通过模型
+ * - 主模型优先 Sonnet，其次 Opus / Haiku / 任一通过模型；推理模型取最近一次产出 thinking 的通过模型
+ * - 没有可推荐的槽位返回空对象（调用方保持原值）
+ */
+export function suggestClaudeModelSlots(outcomes: ClaudeModelValidationOutcome[]): ClaudeModels {
+  const latest = new Map<string, ClaudeModelValidationOutcome>();
+  for (const outcome of outcomes) {
+    const model = outcome.model.trim();
+    if (!model || latest.has(model)) continue;
+    latest.set(model, { ...outcome, model });
+  }
+  const passed = [...latest.values()].filter((o) => o.pass);
+  const pick = (family: string) =>
+    passed.find((o) => o.model.toLowerCase().includes(family))?.model;
+
+  const out: ClaudeModels = {};
+  const haiku = pick("haiku");
+  const sonnet = pick("sonnet");
+  const opus = pick("opus");
+  if (haiku) out.haiku_model = haiku;
+  if (sonnet) out.sonnet_model = sonnet;
+  if (opus) out.opus_model = opus;
+
+  const main = sonnet ?? opus ?? haiku ?? passed[0]?.model;
+  if (main) out.main_model = main;
+  const reasoning = passed.find((o) => o.thinking)?.model;
+  if (reasoning) out.reasoning_model = reasoning;
+  return out;
+}
+
+/** 合并推荐值与当前配置；返回实际会变化的槽位。 */
+export function diffClaudeModelSlots(current: ClaudeModels, suggested: ClaudeModels) {
+  return CLAUDE_MODEL_SLOTS.flatMap(({ key, label }) => {
+    const next = suggested[key]?.trim();
+    const prev = current[key]?.trim() || null;
+    if (!next || next === prev) return [];
+    return [{ key, label, prev, next }];
+  });
+}
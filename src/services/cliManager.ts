@@ -181,3 +181,50 @@ export async function cliManagerClaudeSettingsGet() {
 export async function cliManagerClaudeSettingsSet(patch: ClaudeSettingsPatch) {
   return invokeTauriOrNull<ClaudeSettingsState>("cli_manager_claude_settings_set", { patch });
 }
+
+export type ClaudeHookEvent = "PreToolUse" | "PostToolUse" | "Stop";
+
+export type ClaudeHookInput = {
+  // new hooks must use a ClaudeHookEvent; other events already in settings.json are listed as-is
+  event: string;
+  matcher: string | null;
+  command: string;
+  timeout_secs: number | null;
+};
+
+export type ClaudeHookEntry = ClaudeHookInput & {
+  // set when the hook matches a built-in template exactly
+  template_id: string | null;
+};
+
+export type ClaudeHookTemplateState = {
+  id: string;
+  name: string;
+  description: string;
+  event: ClaudeHookEvent;
+  matcher: string | null;
+  command: string;
+  installed: boolean;
+};
+
+export type ClaudeHooksState = {
+  settings_path: string;
+  backup_dir: string;
+  hooks: ClaudeHookEntry[];
+  templates: ClaudeHookTemplateState[];
+};
+
+export async function cliManagerClaudeHooksGet() {
+  return invokeTauriOrNull<ClaudeHooksState>("cli_manager_claude_hooks_get");
+}
+
+export async function cliManagerClaudeHookCreate(hook: ClaudeHookInput) {
+  return invokeTauriOrNull<ClaudeHooksState>("cli_manager_claude_hook_create", { hook });
+}
+
+export async function cliManagerClaudeHookSetEnabled(hook: ClaudeHookInput, enabled: boolean) {
+  return invokeTauriOrNull<ClaudeHooksState>("cli_manager_claude_hook_set_enabled", {
+    hook,
+    enabled,
+  });
+}
@@ -64,6 +64,16 @@ export type CostTopRequestRowV1 = {
   created_at: number;
 };
 
+export type CostPrefixAffinityV1 = {
+  // successful requests with a stable prompt prefix
+  requests_eligible: number;
+  requests_hit: number;
+  hit_cache_read_tokens: number;
+  hit_prompt_tokens: number;
+  miss_cache_read_tokens: number;
+  miss_prompt_tokens: number;
+};
+
 export type CostBackfillReportV1 = {
   scanned: number;
   updated: number;
@@ -75,6 +85,19 @@ export type CostBackfillReportV1 = {
   max_rows: number;
 };
 
+export type ProviderSpendRowV1 = {
+  provider_id: number;
+  daily_cost_usd: number;
+  monthly_cost_usd: number;
+};
+
+export type ProviderSpendReportV1 = {
+  // unix seconds of the next local day / month rollover
+  daily_reset_at: number;
+  monthly_reset_at: number;
+  rows: ProviderSpendRowV1[];
+};
+
 export async function costSummaryV1(
   period: CostPeriod,
   input?: {
@@ -181,6 +204,26 @@ export async function costTopRequestsV1(
   });
 }
 
+export async function costPrefixAffinityV1(
+  period: CostPeriod,
+  input?: {
+    startTs?: number | null;
+    endTs?: number | null;
+    cliKey?: CliKey | null;
+    providerId?: number | null;
+    model?: string | null;
+  }
+) {
+  return invokeTauriOrNull<CostPrefixAffinityV1>("cost_prefix_affinity_v1", {
+    period,
+    startTs: input?.startTs ?? null,
+    endTs: input?.endTs ?? null,
+    cliKey: input?.cliKey ?? null,
+    providerId: input?.providerId ?? null,
+    model: input?.model ?? null,
+  });
+}
+
 export async function costScatterCliProviderModelV1(
   period: CostPeriod,
   input?: {
@@ -227,3 +270,7 @@ export async function costBackfillMissingV1(
     maxRows: input?.maxRows ?? null,
   });
 }
+
+export async function costProviderSpendV1(cliKey: CliKey) {
+  return invokeTauriOrNull<ProviderSpendReportV1>("cost_provider_spend_v1", { cliKey });
+}
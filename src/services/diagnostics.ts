@@ -0,0 +1,37 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+
+export type CrashReportSummary = {
+  file_name: string;
+  kind: string;
+  created_at: number;
+  size_bytes: number;
+};
+
+export type DiagnosticBundleExport = {
+  path: string;
+  size_bytes: number;
+  crash_reports_included: number;
+};
+
+export type IssueDraft = {
+  title: string;
+  body: string;
+};
+
+export async function crashReportsList() {
+  return invokeTauriOrNull<CrashReportSummary[]>("crash_reports_list");
+}
+
+export async function crashReportsClear() {
+  return invokeTauriOrNull<number>("crash_reports_clear");
+}
+
+export async function diagnosticBundleExport(includeCrashReports: boolean) {
+  return invokeTauriOrNull<DiagnosticBundleExport>("diagnostic_bundle_export", {
+    includeCrashReports,
+  });
+}
+
+export async function issueDraftGenerate(traceId: string) {
+  return invokeTauriOrNull<IssueDraft>("issue_draft_generate", { traceId });
+}
@@ -7,6 +7,71 @@ export type GatewayStatus = {
   port: number | null;
   base_url: string | null;
   listen_addr: string | null;
+  extra_listeners: GatewayListenerStatus[];
+  last_drain: GatewayDrainReport | null;
+  pause?: GatewayPauseStatus;
+  health?: GatewayHealthSummary | null;
+};
+
+export type GatewayCliHealth = {
+  cli_key: string;
+  enabled_providers: number;
+  open_circuits: number;
+};
+
+export type GatewayQueueDepth = {
+  queued: number;
+  capacity: number;
+};
+
+export type GatewayLastError = {
+  at: number;
+  cli_key: string;
+  error_code: string;
+};
+
+export type GatewayHealthSummary = {
+  clis: GatewayCliHealth[];
+  request_log_queue: GatewayQueueDepth;
+  attempt_log_queue: GatewayQueueDepth;
+  last_error: GatewayLastError | null;
+};
+
+export type GatewayPauseStatus = {
+  paused: boolean;
+  since: number | null;
+  reason: string | null;
+};
+
+export type GatewayDrainReport = {
+  drained: number;
+  aborted: number;
+  waited_ms: number;
+};
+
+export type GatewaySelfTestCheck = {
+  target: string;
+  path: string;
+  ok: boolean;
+  status: number | null;
+  error_code: string | null;
+  error: string | null;
+  duration_ms: number;
+};
+
+export type GatewaySelfTestReport = {
+  ok: boolean;
+  base_url: string;
+  checked_at: number;
+  health: GatewaySelfTestCheck;
+  clis: GatewaySelfTestCheck[];
+};
+
+export type GatewayListenerStatus = {
+  cli_key: string;
+  port: number;
+  base_url: string;
+  listen_addr: string;
 };
 
 export type GatewayActiveSession = {
@@ -32,6 +97,68 @@ export type GatewayProviderCircuitStatus = {
   cooldown_until: number | null;
 };
 
+export type GatewayRouteExcludedReason =
+  | "disabled"
+  | "not_in_sort_mode"
+  | "circuit_open"
+  | "circuit_cooldown"
+  | "quota_exceeded"
+  | "beyond_max_providers_to_try";
+
+export type GatewayRouteNote =
+  | "session_bound_provider"
+  | "canary_selected"
+  | "canary_fallback"
+  | "ab_test_arm"
+  | "load_balanced"
+  | "latency_ranked";
+
+export type GatewayRouteCandidate = {
+  provider_id: number;
+  provider_name: string;
+  position: number | null;
+  excluded_reason: GatewayRouteExcludedReason | null;
+  notes: GatewayRouteNote[];
+  circuit_state: string;
+  available_at: number | null;
+  upstream_model: string | null;
+};
+
+export type GatewayRouteExplain = {
+  cli_key: string;
+  model: string | null;
+  session_id: string | null;
+  gateway_running: boolean;
+  sort_mode_id: number | null;
+  sort_mode_source: "session_binding" | "active";
+  schedule_rule_id: number | null;
+  schedule_mode_id: number | null;
+  session_bound_provider_id: number | null;
+  max_providers_to_try: number;
+  candidates: GatewayRouteCandidate[];
+};
+
+export type GatewayBufferedEvent = {
+  seq: number;
+  kind: "request_start" | "attempt" | "request" | "circuit";
+  ts_ms: number;
+  payload: unknown;
+};
+
+export type GatewayInflightRequest = {
+  trace_id: string;
+  cli_key: string;
+  method: string;
+  path: string;
+  provider_id: number | null;
+  provider_name: string | null;
+  attempt: number;
+  phase: "connecting" | "streaming";
+  started_at_ms: number;
+  elapsed_ms: number;
+  bytes_streamed: number;
+};
+
 async function invokeGatewayOrNull<T>(
   title: string,
   cmd: string,
@@ -83,6 +210,59 @@ export async function gatewaySessionsList(limit?: number) {
   );
 }
 
+export async function gatewayInflightList() {
+  return invokeGatewayOrNull<GatewayInflightRequest[]>(
+    "获取在途请求失败",
+    "gateway_inflight_list"
+  );
+}
+
+export async function gatewayRequestCancel(traceId: string) {
+  return invokeGatewayOrNull<boolean>(
+    "取消在途请求失败",
+    "gateway_request_cancel",
+    { traceId },
+    { traceId }
+  );
+}
+
+export async function gatewaySelfTestGet() {
+  return invokeGatewayOrNull<GatewaySelfTestReport | null>(
+    "读取网关自检结果失败",
+    "gateway_self_test_get"
+  );
+}
+
+export async function gatewaySelfTestRun() {
+  return invokeGatewayOrNull<GatewaySelfTestReport>("网关自检失败", "gateway_self_test_run");
+}
+
+export async function gatewayPauseSet(paused: boolean, reason?: string | null) {
+  return invokeGatewayOrNull<GatewayStatus>(
+    paused ? "暂停网关失败" : "恢复网关失败",
+    "gateway_pause_set",
+    { paused, reason: reason ?? null },
+    { paused }
+  );
+}
+
+export async function gatewayRouteExplain(input: {
+  cliKey: string;
+  model?: string | null;
+  sessionId?: string | null;
+}) {
+  const args = {
+    cliKey: input.cliKey,
+    model: input.model ?? null,
+    sessionId: input.sessionId ?? null,
+  };
+  return invokeGatewayOrNull<GatewayRouteExplain>(
+    "路由预演失败",
+    "gateway_route_explain",
+    args
+  );
+}
+
 export async function gatewayCircuitStatus(cliKey: string) {
   return invokeGatewayOrNull<GatewayProviderCircuitStatus[]>(
     "获取熔断器状态失败",
@@ -109,3 +289,16 @@ export async function gatewayCircuitResetCli(cliKey: string) {
     { cliKey }
   );
 }
+
+export async function gatewayErrorCacheClear() {
+  return invokeGatewayOrNull<number>("清除网关错误缓存失败", "gateway_error_cache_clear");
+}
+
+export async function gatewayRecentEvents(afterSeq?: number, limit?: number) {
+  return invokeGatewayOrNull<GatewayBufferedEvent[]>(
+    "获取最近网关事件失败",
+    "gateway_recent_events",
+    { afterSeq: afterSeq ?? null, limit: limit ?? null },
+    { afterSeq, limit }
+  );
+}
@@ -1,4 +1,5 @@
 import { logToConsole, shouldLogToConsole } from "./consoleLog";
+import { gatewayRecentEvents } from "./gateway";
 import { hasTauriRuntime } from "./tauriInvoke";
 import { ingestTraceAttempt, ingestTraceRequest, ingestTraceStart } from "./traceStore";
 import { ingestCacheAnomalyRequest, ingestCacheAnomalyRequestStart } from "./cacheAnomalyMonitor";
@@ -114,10 +115,16 @@ function circuitReasonText(reason: string | null | undefined) {
       return "失败次数达到阈值";
     case "OPEN_EXPIRED":
       return "熔断到期";
+    case "RECOVERY_CONFIRMED":
+      return "熔断到期后首个请求成功";
     case "SKIP_OPEN":
       return "熔断中已跳过";
     case "SKIP_COOLDOWN":
       return "冷却中已跳过";
+    case "SKIP_RATE_LIMITED":
+      return "限流中已跳过";
+    case "QUOTA_EXCEEDED":
+      return "超出预算已跳过";
     default:
       return r;
   }
@@ -145,6 +152,28 @@ function computeOutputTokensPerSecond(payload: GatewayRequestEvent) {
   return output / (generationMs / 1000);
 }
 
+// Replays the backend ring buffer into the trace store so the Home page is populated right away
+// (first open, or after the window was recreated and live events were missed).
+async function replayRecentGatewayEvents() {
+  const events = await gatewayRecentEvents();
+  if (!events) return;
+  for (const event of events) {
+    switch (event.kind) {
+      case "request_start":
+        ingestTraceStart(event.payload as GatewayRequestStartEvent);
+        break;
+      case "attempt":
+        ingestTraceAttempt(event.payload as GatewayAttemptEvent);
+        break;
+      case "request":
+        ingestTraceRequest(event.payload as GatewayRequestEvent);
+        break;
+      default:
+        break;
+    }
+  }
+}
+
 export async function listenGatewayEvents(): Promise<() => void> {
   if (!hasTauriRuntime()) return () => {};
 
@@ -249,7 +278,9 @@ export async function listenGatewayEvents(): Promise<() => void> {
     const title =
       payload.error_code === "GW_PORT_IN_USE"
         ? "端口被占用，已自动切换（GW_PORT_IN_USE）"
-        : `网关日志：${payload.error_code}`;
+        : payload.error_code === "GW_EXTRA_LISTENER_BIND_FAILED"
+          ? "专用端口绑定失败（GW_EXTRA_LISTENER_BIND_FAILED）"
+          : `网关日志：${payload.error_code}`;
 
     logToConsole(normalizeLogLevel(payload.level), title, {
       error_code: payload.error_code,
@@ -295,6 +326,19 @@ export async function listenGatewayEvents(): Promise<() => void> {
       return;
     }
 
+    if (payload.reason === "RECOVERY_CONFIRMED") {
+      logToConsole("info", `Provider 已恢复：${provider}`, {
+        trace_id: payload.trace_id,
+        cli: payload.cli_key,
+        provider_id: payload.provider_id,
+        provider_name: payload.provider_name,
+        base_url: payload.base_url,
+        reason,
+        ts: payload.ts,
+      });
+      return;
+    }
+
     const dedupKey = [
       payload.cli_key,
       payload.provider_id,
@@ -327,6 +371,8 @@ export async function listenGatewayEvents(): Promise<() => void> {
     });
   });
 
+  await replayRecentGatewayEvents();
+
   return () => {
     unlistenRequestStart();
     unlistenAttempt();
@@ -4,6 +4,7 @@
  * 用法：
  * - 在任意页面：`await noticeSend({ level: "info", body: "..." })`
  * - `title` 为空时，Rust 会按 level 生成默认标题并追加固定前缀
+ * - `deliverToChannels: true` 时同时投递到设置中订阅了 general 事件的外部渠道
  */
 
 import { invokeTauriOrNull } from "./tauriInvoke";
@@ -14,9 +15,38 @@ export type NoticeSendParams = {
   level: NoticeLevel;
   title?: string;
   body: string;
+  deliverToChannels?: boolean;
 };
 
 export async function noticeSend(params: NoticeSendParams): Promise<boolean> {
   const ok = await invokeTauriOrNull<boolean>("notice_send", params);
   return ok === true;
 }
+
+export type NoticeHistoryRow = {
+  id: number;
+  created_at: number;
+  event_kind: string;
+  level: NoticeLevel;
+  title: string;
+  body: string;
+  channel_id: string;
+  channel_kind: string;
+  status: "delivered" | "failed";
+  error: string | null;
+};
+
+export async function noticeHistoryList(params?: { limit?: number; beforeId?: number }) {
+  return invokeTauriOrNull<NoticeHistoryRow[]>("notice_history_list", {
+    limit: params?.limit,
+    beforeId: params?.beforeId,
+  });
+}
+
+export async function noticeHistoryClear() {
+  return invokeTauriOrNull<number>("notice_history_clear");
+}
+
+export async function noticeTestChannel(channelId: string) {
+  return invokeTauriOrNull<boolean>("notice_test_channel", { channelId });
+}
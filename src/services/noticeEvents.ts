@@ -4,18 +4,56 @@
  * 用法：
  * - 在 `src/App.tsx` 启动时调用 `listenNoticeEvents()`（只需要注册一次）
  * - 权限请求由 Settings 页面负责；此监听器仅在已授权时发送通知
+ * - 带 `action` 的通知（如熔断触发）额外弹出应用内提示，提供操作按钮（桌面系统通知不支持按钮回调）
+ * - `local_detail`（如上游响应摘要）只在本机显示，后端不会投递到外部渠道
  */
 
+import { toast } from "sonner";
 import { logToConsole } from "./consoleLog";
+import { gatewayCircuitResetProvider } from "./gateway";
 import type { NoticeLevel } from "./notice";
 import { hasTauriRuntime } from "./tauriInvoke";
 
+export type NoticeAction = { kind: "reset_provider"; provider_id: number; label: string };
+
 export type NoticeEventPayload = {
   level: NoticeLevel;
   title: string;
   body: string;
+  action?: NoticeAction;
+  local_detail?: string;
 };
 
+function displayBody(payload: NoticeEventPayload) {
+  return payload.local_detail ? `${payload.body}\n${payload.local_detail}` : payload.body;
+}
+
+async function runNoticeAction(action: NoticeAction) {
+  switch (action.kind) {
+    case "reset_provider": {
+      const ok = await gatewayCircuitResetProvider(action.provider_id);
+      if (ok) toast("已重置供应商熔断状态");
+      return;
+    }
+  }
+}
+
+function showActionToast(payload: NoticeEventPayload, action: NoticeAction) {
+  toast(payload.title, {
+    description: displayBody(payload),
+    duration: 15_000,
+    action: {
+      label: action.label,
+      onClick: () => {
+        void runNoticeAction(action).catch((err) => {
+          logToConsole("error", "执行通知操作失败", { error: String(err), action });
+          toast(`操作失败：${String(err)}`);
+        });
+      },
+    },
+  });
+}
+
 export async function listenNoticeEvents(): Promise<() => void> {
   if (!hasTauriRuntime()) return () => {};
 
@@ -27,12 +65,13 @@ export async function listenNoticeEvents(): Promise<() => void> {
   const unlisten = await listen<NoticeEventPayload>("notice:notify", async (event) => {
     const payload = event.payload;
     if (!payload) return;
+    if (payload.action) showActionToast(payload, payload.action);
 
     try {
       const permissionGranted = await isPermissionGranted();
       if (!permissionGranted) return;
 
-      await sendNotification({ title: payload.title, body: payload.body });
+      await sendNotification({ title: payload.title, body: displayBody(payload) });
     } catch (err) {
       logToConsole("error", "发送系统通知失败", {
         error: String(err),
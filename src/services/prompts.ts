@@ -55,3 +55,24 @@ export async function promptSetEnabled(promptId: number, enabled: boolean) {
 export async function promptDelete(promptId: number) {
   return invokeTauriOrNull<boolean>("prompt_delete", { promptId });
 }
+
+export type CodexProjectAgentsState = {
+  path: string;
+  exists: boolean;
+  // content between the aio-coding-hub markers; null when the block is absent
+  managed_content: string | null;
+  has_user_content: boolean;
+};
+
+export async function promptCodexProjectAgentsGet(projectDir: string) {
+  return invokeTauriOrNull<CodexProjectAgentsState>("prompt_codex_project_agents_get", {
+    projectDir,
+  });
+}
+
+export async function promptCodexProjectAgentsSet(projectDir: string, content: string | null) {
+  return invokeTauriOrNull<CodexProjectAgentsState>("prompt_codex_project_agents_set", {
+    projectDir,
+    content,
+  });
+}
@@ -0,0 +1,60 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { CliKey } from "./providers";
+
+export type ProviderAbTestStatus = "running" | "completed" | "cancelled";
+
+export type ProviderAbTest = {
+  id: number;
+  cli_key: CliKey;
+  provider_a_id: number;
+  provider_b_id: number;
+  target_sessions: number;
+  status: ProviderAbTestStatus;
+  started_at: number;
+  completed_at: number | null;
+};
+
+export type AbArmReport = {
+  provider_id: number;
+  sessions: number;
+  requests: number;
+  failures: number;
+  error_rate: number | null;
+  avg_duration_ms: number | null;
+  avg_ttfb_ms: number | null;
+  total_cost_usd: number;
+  avg_cost_usd_per_session: number | null;
+  retries: number;
+};
+
+export type ProviderAbTestReport = {
+  test: ProviderAbTest;
+  arm_a: AbArmReport;
+  arm_b: AbArmReport;
+};
+
+export async function providerAbTestsList(cliKey: CliKey) {
+  return invokeTauriOrNull<ProviderAbTest[]>("provider_ab_tests_list", { cliKey });
+}
+
+export async function providerAbTestStart(input: {
+  cliKey: CliKey;
+  providerAId: number;
+  providerBId: number;
+  targetSessions: number;
+}) {
+  return invokeTauriOrNull<ProviderAbTest>("provider_ab_test_start", {
+    cliKey: input.cliKey,
+    providerAId: input.providerAId,
+    providerBId: input.providerBId,
+    targetSessions: input.targetSessions,
+  });
+}
+
+export async function providerAbTestCancel(testId: number) {
+  return invokeTauriOrNull<ProviderAbTest>("provider_ab_test_cancel", { testId });
+}
+
+export async function providerAbTestReport(testId: number) {
+  return invokeTauriOrNull<ProviderAbTestReport>("provider_ab_test_report", { testId });
+}
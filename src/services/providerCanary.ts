@@ -0,0 +1,64 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { CliKey } from "./providers";
+
+export type ProviderCanaryStatus =
+  | "running"
+  | "promoted"
+  | "demoted"
+  | "inconclusive"
+  | "cancelled";
+
+export type ProviderCanary = {
+  id: number;
+  cli_key: CliKey;
+  mode_id: number | null;
+  provider_id: number;
+  incumbent_provider_id: number | null;
+  traffic_percent: number;
+  max_error_rate_delta: number;
+  min_samples: number;
+  status: ProviderCanaryStatus;
+  started_at: number;
+  ends_at: number;
+  decided_at: number | null;
+  decision_reason: string | null;
+};
+
+export type CanaryArmStats = {
+  requests: number;
+  failures: number;
+};
+
+export type ProviderCanaryView = {
+  canary: ProviderCanary;
+  canary_stats: CanaryArmStats;
+  incumbent_stats: CanaryArmStats | null;
+};
+
+export async function providerCanariesList(cliKey: CliKey) {
+  return invokeTauriOrNull<ProviderCanaryView[]>("provider_canaries_list", { cliKey });
+}
+
+export async function providerCanaryStart(input: {
+  cliKey: CliKey;
+  modeId: number | null;
+  providerId: number;
+  trafficPercent: number;
+  durationHours: number;
+  maxErrorRateDelta?: number | null;
+  minSamples?: number | null;
+}) {
+  return invokeTauriOrNull<ProviderCanary>("provider_canary_start", {
+    cliKey: input.cliKey,
+    modeId: input.modeId,
+    providerId: input.providerId,
+    trafficPercent: input.trafficPercent,
+    durationHours: input.durationHours,
+    maxErrorRateDelta: input.maxErrorRateDelta ?? null,
+    minSamples: input.minSamples ?? null,
+  });
+}
+
+export async function providerCanaryCancel(canaryId: number) {
+  return invokeTauriOrNull<ProviderCanary>("provider_canary_cancel", { canaryId });
+}
@@ -10,6 +10,65 @@ export type ClaudeModels = {
   opus_model?: string | null;
 };
 
+export type ResponseFixerOverride = {
+  enabled?: boolean | null;
+  fix_encoding?: boolean | null;
+  fix_sse_format?: boolean | null;
+  fix_truncated_json?: boolean | null;
+  fix_tool_args?: boolean | null;
+  dry_run?: boolean | null;
+  max_json_depth?: number | null;
+  max_fix_size?: number | null;
+};
+
+export type ProviderWireApi = "native" | "chat_completions";
+
+export type RequestSigningAlgorithm = "none" | "hmac_sha256" | "hmac_sha512";
+
+// HMAC over "{timestamp}.{body}" of the final upstream body, sent in header_name
+export type RequestSigning = {
+  algorithm: RequestSigningAlgorithm;
+  // always blank in ProviderSummary; blank on update keeps the stored secret
+  secret: string;
+  encoding: "hex" | "base64";
+  header_name: string;
+  // {signature} / {timestamp} placeholders
+  header_template: string;
+  // optional; "" = not sent
+  timestamp_header: string;
+};
+
+// custom TLS material for relays behind TLS interception / requiring mTLS
+export type UpstreamTls = {
+  // extra trusted root CAs (PEM bundle), added to the system roots
+  ca_bundle_pem: string;
+  // client certificate chain (PEM) for mTLS; requires client_key_pem
+  client_cert_pem: string;
+  // always blank in ProviderSummary; blank on update keeps the stored key
+  client_key_pem: string;
+};
+
+// add: only when the request lacks the header; override: always set; remove: drop it
+export type ProviderHeaderAction = "add" | "override" | "remove";
+
+export type ProviderHeaderRule = {
+  action: ProviderHeaderAction;
+  name: string;
+  // add / override value; {api_key} is replaced with the provider key
+  value: string;
+};
+
+// set: write `value` at `path`; delete: drop the field / element; rename: move the key to `to`
+export type BodyPatchOp = "set" | "delete" | "rename";
+
+export type BodyPatchRule = {
+  op: BodyPatchOp;
+  // `$.metadata`, `generationConfig.temperature`, `messages[*].cache_control`
+  path: string;
+  value: unknown;
+  to: string;
+};
+
 export type ProviderSummary = {
   id: number;
   cli_key: CliKey;
@@ -17,6 +76,27 @@ export type ProviderSummary = {
   base_urls: string[];
   base_url_mode: "order" | "ping";
   claude_models: ClaudeModels;
+  response_fixer: ResponseFixerOverride;
+  disable_response_storage: boolean;
+  wire_api: ProviderWireApi;
+  exclude_from_stats: boolean;
+  // 0 = unlimited
+  rpm_limit: number;
+  tpm_limit: number;
+  daily_quota_usd: number;
+  monthly_quota_usd: number;
+  // request body limit (KB) the gateway trims conversations to; 0 = off
+  context_guard_limit_kb: number;
+  // outbound proxy: "system" (env proxy, loopback bypassed) | "none" | http(s):// / socks5:// URL
+  upstream_proxy: string;
+  // forward the client's request body untouched (no rewriting, no response fixing)
+  passthrough: boolean;
+  request_signing: RequestSigning;
+  upstream_tls: UpstreamTls;
+  // outbound header rules, applied after the provider auth headers
+  header_rules: ProviderHeaderRule[];
+  // request body mutations, applied in order before forwarding
+  body_patch_rules: BodyPatchRule[];
   enabled: boolean;
   priority: number;
   cost_multiplier: number;
@@ -39,6 +119,21 @@ export async function providerUpsert(input: {
   cost_multiplier: number;
   priority?: number | null;
   claude_models?: ClaudeModels | null;
+  response_fixer?: ResponseFixerOverride | null;
+  disable_response_storage?: boolean | null;
+  wire_api?: ProviderWireApi | null;
+  exclude_from_stats?: boolean | null;
+  rpm_limit?: number | null;
+  tpm_limit?: number | null;
+  daily_quota_usd?: number | null;
+  monthly_quota_usd?: number | null;
+  context_guard_limit_kb?: number | null;
+  upstream_proxy?: string | null;
+  passthrough?: boolean | null;
+  request_signing?: RequestSigning | null;
+  upstream_tls?: UpstreamTls | null;
+  header_rules?: ProviderHeaderRule[] | null;
+  body_patch_rules?: BodyPatchRule[] | null;
 }) {
   return invokeTauriOrNull<ProviderSummary>("provider_upsert", {
     providerId: input.provider_id ?? null,
@@ -51,11 +146,53 @@ export async function providerUpsert(input: {
     costMultiplier: input.cost_multiplier,
     priority: input.priority ?? null,
     claudeModels: input.claude_models ?? null,
+    responseFixer: input.response_fixer ?? null,
+    disableResponseStorage: input.disable_response_storage ?? null,
+    wireApi: input.wire_api ?? null,
+    excludeFromStats: input.exclude_from_stats ?? null,
+    rpmLimit: input.rpm_limit ?? null,
+    tpmLimit: input.tpm_limit ?? null,
+    dailyQuotaUsd: input.daily_quota_usd ?? null,
+    monthlyQuotaUsd: input.monthly_quota_usd ?? null,
+    contextGuardLimitKb: input.context_guard_limit_kb ?? null,
+    upstreamProxy: input.upstream_proxy ?? null,
+    passthrough: input.passthrough ?? null,
+    requestSigning: input.request_signing ?? null,
+    upstreamTls: input.upstream_tls ?? null,
+    headerRules: input.header_rules ?? null,
+    bodyPatchRules: input.body_patch_rules ?? null,
   });
 }
 
-export async function baseUrlPingMs(baseUrl: string) {
-  return invokeTauriOrNull<number>("base_url_ping_ms", { baseUrl });
+export async function baseUrlPingMs(baseUrl: string, providerId?: number | null) {
+  return invokeTauriOrNull<number>("base_url_ping_ms", {
+    baseUrl,
+    providerId: providerId ?? null,
+  });
+}
+
+export type BaseUrlLatencyPoint = {
+  base_url: string;
+  bucket_ts: number;
+  samples: number;
+  failures: number;
+  avg_ms: number | null;
+  min_ms: number | null;
+  max_ms: number | null;
+};
+
+export async function baseUrlProbeHistory(input: {
+  providerId?: number | null;
+  baseUrl?: string | null;
+  hours?: number | null;
+  bucketMinutes?: number | null;
+}) {
+  return invokeTauriOrNull<BaseUrlLatencyPoint[]>("base_url_probe_history", {
+    providerId: input.providerId ?? null,
+    baseUrl: input.baseUrl ?? null,
+    hours: input.hours ?? null,
+    bucketMinutes: input.bucketMinutes ?? null,
+  });
 }
 
 export async function providerSetEnabled(providerId: number, enabled: boolean) {
@@ -65,13 +202,63 @@ export async function providerSetEnabled(providerId: number, enabled: boolean) {
   });
 }
 
+export async function providerClaudeModelsSet(providerId: number, claudeModels: ClaudeModels) {
+  return invokeTauriOrNull<ProviderSummary>("provider_claude_models_set", {
+    providerId,
+    claudeModels,
+  });
+}
+
 export async function providerDelete(providerId: number) {
   return invokeTauriOrNull<boolean>("provider_delete", { providerId });
 }
 
+export type ProviderImportFormat = "env" | "claude_settings" | "one_api_channel" | "json" | "text";
+
+export type ProviderImportDraft = {
+  format: ProviderImportFormat;
+  // null = not detected; keep the form's current CLI
+  cli_key: CliKey | null;
+  name: string | null;
+  base_urls: string[];
+  api_key: string | null;
+  claude_models: ClaudeModels;
+  warnings: string[];
+};
+
+export async function providerImportFromText(text: string) {
+  return invokeTauriOrNull<ProviderImportDraft>("provider_import_from_text", { text });
+}
+
 export async function providersReorder(cliKey: CliKey, orderedProviderIds: number[]) {
   return invokeTauriOrNull<ProviderSummary[]>("providers_reorder", {
     cliKey,
     orderedProviderIds,
   });
 }
+
+export type ProviderSpeedtestResult = {
+  provider_id: number;
+  provider_name: string;
+  base_url: string;
+  model: string;
+  ok: boolean;
+  status: number | null;
+  ttfb_ms: number | null;
+  total_ms: number;
+  output_tokens: number | null;
+  tokens_per_second: number | null;
+  error: string | null;
+};
+
+export async function providersSpeedtest(input: {
+  cliKey: CliKey;
+  providerIds: number[];
+  model?: string | null;
+}) {
+  return invokeTauriOrNull<ProviderSpeedtestResult[]>("providers_speedtest", {
+    cliKey: input.cliKey,
+    providerIds: input.providerIds,
+    model: input.model ?? null,
+  });
+}
@@ -26,10 +26,13 @@ export type RequestLogSummary = {
   cache_read_input_tokens: number | null;
   cache_creation_input_tokens: number | null;
   cache_creation_5m_input_tokens: number | null;
+  estimated_input_tokens?: number | null;
   cost_usd: number | null;
   cost_multiplier: number;
   created_at_ms: number | null;
   created_at: number;
+  client_id: string | null;
+  client_trace_id?: string | null;
 };
 
 export type RequestLogRouteHop = {
@@ -62,12 +65,64 @@ export type RequestLogDetail = {
   cache_read_input_tokens: number | null;
   cache_creation_input_tokens: number | null;
   cache_creation_5m_input_tokens: number | null;
+  estimated_input_tokens?: number | null;
   usage_json: string | null;
   requested_model: string | null;
   cost_usd: number | null;
   cost_multiplier: number;
   created_at_ms: number | null;
   created_at: number;
+  client_id: string | null;
+  client_trace_id?: string | null;
+};
+
+export type SseTranscriptBlock = {
+  kind: "text" | "thinking" | "tool_call";
+  text: string;
+  tool_call_id: string | null;
+  tool_name: string | null;
+};
+
+export type SseTranscript = {
+  trace_id: string;
+  cli_key: string;
+  created_at: number;
+  truncated: boolean;
+  event_count: number;
+  model: string | null;
+  stop_reason: string | null;
+  blocks: SseTranscriptBlock[];
+  errors: string[];
+};
+
+export type ClientUsageAggregate = {
+  client_id: string;
+  request_count: number;
+  total_tokens: number;
+  total_cost_usd: number;
+  last_seen_at: number;
+};
+
+export type ResponseFixerDryRunAggregate = {
+  provider_id: number;
+  provider_name: string;
+  analyzed_count: number;
+  would_apply_count: number;
+  fixer_counts: Record<string, number>;
+  last_seen_at: number;
+};
+
+export type UsageEstimateDriftAggregate = {
+  provider_id: number;
+  provider_name: string;
+  cli_key: CliKey;
+  sample_count: number;
+  reported_input_tokens: number;
+  estimated_input_tokens: number;
+  ratio: number;
+  inflated_count: number;
+  flagged: boolean;
+  last_seen_at: number;
 };
 
 export type RequestAttemptLog = {
@@ -99,6 +154,48 @@ export async function requestLogsListAll(limit?: number) {
   return invokeTauriOrNull<RequestLogSummary[]>("request_logs_list_all", { limit });
 }
 
+export async function requestLogsListBefore(
+  cliKey: CliKey,
+  beforeCreatedAtMs: number,
+  beforeId: number,
+  limit?: number
+) {
+  return invokeTauriOrNull<RequestLogSummary[]>("request_logs_list_before", {
+    cliKey,
+    beforeCreatedAtMs,
+    beforeId,
+    limit,
+  });
+}
+
+export async function requestLogsListBeforeAll(
+  beforeCreatedAtMs: number,
+  beforeId: number,
+  limit?: number
+) {
+  return invokeTauriOrNull<RequestLogSummary[]>("request_logs_list_before_all", {
+    beforeCreatedAtMs,
+    beforeId,
+    limit,
+  });
+}
+
+// newest: created_at_ms desc; cost_desc: highest cost first (rows without a computed cost excluded)
+export type RequestLogSort = "newest" | "cost_desc";
+
+export type RequestLogListQuery = {
+  cli_key?: CliKey | null;
+  final_provider_id?: number | null;
+  min_cost_usd?: number | null;
+  sort?: RequestLogSort;
+  // id of the last row of the previous page (same query)
+  before_id?: number | null;
+};
+
+export async function requestLogsListFiltered(query: RequestLogListQuery, limit?: number) {
+  return invokeTauriOrNull<RequestLogSummary[]>("request_logs_list_filtered", { query, limit });
+}
+
 export async function requestLogsListAfterId(cliKey: CliKey, afterId: number, limit?: number) {
   return invokeTauriOrNull<RequestLogSummary[]>("request_logs_list_after_id", {
     cliKey,
@@ -124,9 +221,39 @@ export async function requestLogGetByTraceId(traceId: string) {
   });
 }
 
+export async function requestLogsByClientTraceId(clientTraceId: string, limit?: number) {
+  return invokeTauriOrNull<RequestLogSummary[]>("request_logs_by_client_trace_id", {
+    clientTraceId,
+    limit,
+  });
+}
+
 export async function requestAttemptLogsByTraceId(traceId: string, limit?: number) {
   return invokeTauriOrNull<RequestAttemptLog[]>("request_attempt_logs_by_trace_id", {
     traceId,
     limit,
   });
 }
+
+export async function requestSseTranscriptGet(traceId: string) {
+  return invokeTauriOrNull<SseTranscript | null>("request_sse_transcript_get", { traceId });
+}
+
+export async function requestLogsClientUsage(days?: number) {
+  return invokeTauriOrNull<ClientUsageAggregate[]>("request_logs_client_usage", {
+    days: days ?? null,
+  });
+}
+
+export async function requestLogsResponseFixerDryRunSummary(days?: number) {
+  return invokeTauriOrNull<ResponseFixerDryRunAggregate[]>(
+    "request_logs_response_fixer_dry_run_summary",
+    { days: days ?? null }
+  );
+}
+
+export async function requestLogsUsageEstimateDrift(days?: number) {
+  return invokeTauriOrNull<UsageEstimateDriftAggregate[]>("request_logs_usage_estimate_drift", {
+    days: days ?? null,
+  });
+}
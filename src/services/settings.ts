@@ -1,13 +1,176 @@
 import { invokeTauriOrNull } from "./tauriInvoke";
+import type { CliKey } from "./providers";
 
 export type GatewayListenMode = "localhost" | "wsl_auto" | "lan" | "custom";
 
+export type AppLanguage = "zh-CN" | "en";
+
 export type WslTargetCli = {
   claude: boolean;
   codex: boolean;
   gemini: boolean;
 };
 
+export type CliStreamCaps = {
+  claude: number;
+  codex: number;
+  gemini: number;
+};
+
+export type RetryBackoffStrategy = "linear" | "exponential";
+
+export type RetryBackoffConfig = {
+  strategy: RetryBackoffStrategy;
+  base_ms: number;
+  max_ms: number;
+  multiplier: number;
+  jitter_percent: number;
+  statuses: number[];
+};
+
+export type AttemptLogRetentionConfig = {
+  // 0 = follow log_retention_days
+  retention_days: number;
+  // 0 = never compact successful traces
+  compact_after_days: number;
+};
+
+export type UpdateMirrorConfig = {
+  // tried in order after GitHub; `{url}` = original URL, otherwise the URL is appended
+  mirrors: string[];
+  // check timeout / download stall timeout before falling back
+  timeout_secs: number;
+};
+
+export type EgressIpFamily = "auto" | "ipv4" | "ipv6";
+
+export type EgressHostOverride = {
+  host: string;
+  ips: string[];
+};
+
+export type EgressNetworkConfig = {
+  // "system" (proxy env vars) | "none" | http(s):// / socks5(h):// URL; providers set to "system" follow it
+  proxy: string;
+  host_overrides: EgressHostOverride[];
+  ip_family: EgressIpFamily;
+};
+
+export type LatencyRoutingConfig = {
+  enabled: boolean;
+  // samples older than this are ignored; newer samples weigh more
+  window_minutes: number;
+};
+
+export type RecentErrorCacheConfig = {
+  // cap for replaying a cached "all providers unavailable"; 0 = never replay
+  error_max_ttl_seconds: number;
+  // identical requests within this window share one trace id
+  trace_dedup_ttl_seconds: number;
+};
+
+export type HedgedRequestsConfig = {
+  enabled: boolean;
+  // start the next provider when the first has not responded after this long
+  delay_ms: number;
+};
+
+export type StreamToolPauseGraceConfig = {
+  enabled: boolean;
+  // stream idle timeout while Claude waits on server-side tool use (ping, no message_delta yet)
+  idle_timeout_seconds: number;
+};
+
+export type WslDistroOverride = {
+  distro: string;
+  enabled: boolean;
+  targets: WslTargetCli;
+};
+
+export type GatewayExtraListener = {
+  port: number;
+  cli_key: CliKey;
+  enabled: boolean;
+};
+
+export type GatewayClientKey = {
+  id: string;
+  name: string;
+  key: string;
+  enabled: boolean;
+  rate_limit_per_minute: number;
+};
+
+export type InterceptPredicateOp =
+  | "exists"
+  | "equals"
+  | "equals_ignore_case"
+  | "contains"
+  | "len_eq";
+
+export type InterceptBodyPredicate = {
+  pointer: string;
+  op: InterceptPredicateOp;
+  value?: unknown;
+};
+
+export type InterceptRule = {
+  id: string;
+  name: string;
+  enabled: boolean;
+  cli_key: string;
+  method: string;
+  path: string;
+  body_predicates: InterceptBodyPredicate[];
+  response_status: number;
+  response_content_type: string;
+  response_body: string;
+};
+
+export type HeaderSanitizeRule = {
+  id: string;
+  name: string;
+  enabled: boolean;
+  cli_key: string;
+  provider_ids: number[];
+  headers: string[];
+};
+
+export type FailoverRuleDecision = "retry_same_provider" | "switch_provider" | "abort";
+
+export type FailoverRule = {
+  id: string;
+  name: string;
+  enabled: boolean;
+  cli_key: string;
+  provider_ids: number[];
+  statuses: number[];
+  body_regex: string;
+  decision: FailoverRuleDecision;
+};
+
+export type RedactionBuiltin = "api_key" | "aws_secret" | "email";
+
+export type UpdateChannel = "stable" | "beta";
+
+export type InflightDedupMode = "off" | "attach" | "reject";
+
+export type NoticeChannelKind = "webhook" | "telegram" | "slack" | "feishu" | "dingtalk";
+
+export type NoticeEventKind = "circuit_breaker" | "budget" | "general";
+
+export type NoticeChannelConfig = {
+  id: string;
+  name: string;
+  enabled: boolean;
+  kind: NoticeChannelKind;
+  url: string;
+  bot_token: string;
+  chat_id: string;
+  secret: string;
+  events: NoticeEventKind[];
+};
+
 export type AppSettings = {
   schema_version: number;
   preferred_port: number;
@@ -38,6 +201,54 @@ export type AppSettings = {
   response_fixer_fix_truncated_json: boolean;
   response_fixer_max_json_depth: number;
   response_fixer_max_fix_size: number;
+  ui_language: AppLanguage;
+  enable_low_power_mode: boolean;
+  low_power_idle_minutes: number;
+  notice_channels: NoticeChannelConfig[];
+  notice_quiet_hours_enabled: boolean;
+  notice_quiet_hours_start: string;
+  notice_quiet_hours_end: string;
+  notice_quiet_hours_summary: boolean;
+  enable_circuit_recovery_notice: boolean;
+  wsl_distro_overrides: WslDistroOverride[];
+  gateway_access_token: string;
+  gateway_tls_enabled: boolean;
+  gateway_tls_hosts: string[];
+  gateway_extra_listeners: GatewayExtraListener[];
+  gateway_client_keys: GatewayClientKey[];
+  gateway_ip_allowlist: string[];
+  gateway_drain_timeout_seconds: number;
+  update_channel: UpdateChannel;
+  update_background_download: boolean;
+  update_mirror?: UpdateMirrorConfig;
+  response_fixer_fix_tool_args: boolean;
+  response_fixer_dry_run?: boolean;
+  intercept_rules?: InterceptRule[];
+  inflight_dedup_mode?: InflightDedupMode;
+  priority_lanes_enabled?: boolean;
+  background_lane_max_concurrency?: number;
+  background_lane_queue_timeout_seconds?: number;
+  background_lane_provider_id?: number | null;
+  header_sanitize_rules?: HeaderSanitizeRule[];
+  failover_rules?: FailoverRule[];
+  enable_request_redaction?: boolean;
+  request_redaction_builtins?: RedactionBuiltin[];
+  request_redaction_custom_patterns?: string[];
+  latency_probe_interval_minutes: number;
+  enable_prompt_cache_injection?: boolean;
+  exclude_reasoning_from_logs?: boolean;
+  cli_max_concurrent_streams?: CliStreamCaps;
+  retry_backoff?: RetryBackoffConfig;
+  forward_trace_headers?: boolean;
+  capture_sse_events?: boolean;
+  attempt_log_retention?: AttemptLogRetentionConfig;
+  latency_routing?: LatencyRoutingConfig;
+  response_cache_ttl_seconds?: number;
+  response_cache_completions?: boolean;
+  egress_network?: EgressNetworkConfig;
+  recent_error_cache?: RecentErrorCacheConfig;
+  hedged_requests?: HedgedRequestsConfig;
+  stream_tool_pause_grace?: StreamToolPauseGraceConfig;
 };
 
 export async function settingsGet() {
@@ -0,0 +1,10 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, AttemptLogRetentionConfig } from "./settings";
+
+export async function settingsAttemptLogRetentionSet(
+  attemptLogRetention: AttemptLogRetentionConfig
+) {
+  return invokeTauriOrNull<AppSettings>("settings_attempt_log_retention_set", {
+    attemptLogRetention,
+  });
+}
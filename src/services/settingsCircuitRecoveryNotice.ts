@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export async function settingsCircuitRecoveryNoticeSet(enable: boolean) {
+  return invokeTauriOrNull<AppSettings>("settings_circuit_recovery_notice_set", {
+    enableCircuitRecoveryNotice: enable,
+  });
+}
@@ -0,0 +1,23 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, EgressNetworkConfig } from "./settings";
+
+export type NetworkTestResult = {
+  url: string;
+  route: "direct" | "system" | "proxy";
+  // local resolution with host overrides / IP family applied
+  resolved_addrs: string[];
+  dns_error: string | null;
+  // any HTTP response counts as reachable
+  ok: boolean;
+  status: number | null;
+  elapsed_ms: number;
+  error: string | null;
+};
+
+export async function settingsEgressNetworkSet(egressNetwork: EgressNetworkConfig) {
+  return invokeTauriOrNull<AppSettings>("settings_egress_network_set", { egressNetwork });
+}
+
+export async function gatewayNetworkTest(url: string) {
+  return invokeTauriOrNull<NetworkTestResult>("gateway_network_test", { url });
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, FailoverRule } from "./settings";
+
+export async function settingsFailoverRulesSet(rules: FailoverRule[]) {
+  return invokeTauriOrNull<AppSettings>("settings_failover_rules_set", {
+    failoverRules: rules,
+  });
+}
@@ -0,0 +1,12 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export async function settingsGatewayAccessTokenSet(token: string) {
+  return invokeTauriOrNull<AppSettings>("settings_gateway_access_token_set", {
+    gatewayAccessToken: token,
+  });
+}
+
+export async function gatewayAccessTokenGenerate() {
+  return invokeTauriOrNull<string>("gateway_access_token_generate");
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, GatewayClientKey } from "./settings";
+
+export async function settingsGatewayClientKeysSet(keys: GatewayClientKey[]) {
+  return invokeTauriOrNull<AppSettings>("settings_gateway_client_keys_set", {
+    gatewayClientKeys: keys,
+  });
+}
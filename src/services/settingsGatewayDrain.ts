@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export async function settingsGatewayDrainTimeoutSet(seconds: number) {
+  return invokeTauriOrNull<AppSettings>("settings_gateway_drain_timeout_set", {
+    gatewayDrainTimeoutSeconds: seconds,
+  });
+}
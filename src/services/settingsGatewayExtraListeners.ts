@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, GatewayExtraListener } from "./settings";
+
+export async function settingsGatewayExtraListenersSet(listeners: GatewayExtraListener[]) {
+  return invokeTauriOrNull<AppSettings>("settings_gateway_extra_listeners_set", {
+    gatewayExtraListeners: listeners,
+  });
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export async function settingsGatewayIpAllowlistSet(entries: string[]) {
+  return invokeTauriOrNull<AppSettings>("settings_gateway_ip_allowlist_set", {
+    gatewayIpAllowlist: entries,
+  });
+}
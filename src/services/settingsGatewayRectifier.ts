@@ -10,6 +10,8 @@ export type GatewayRectifierSettingsPatch = {
   response_fixer_fix_truncated_json: boolean;
   response_fixer_max_json_depth: number;
   response_fixer_max_fix_size: number;
+  response_fixer_fix_tool_args: boolean;
+  response_fixer_dry_run: boolean;
 };
 
 export async function settingsGatewayRectifierSet(input: GatewayRectifierSettingsPatch) {
@@ -22,5 +24,7 @@ export async function settingsGatewayRectifierSet(input: GatewayRectifierSetting
     responseFixerFixTruncatedJson: input.response_fixer_fix_truncated_json,
     responseFixerMaxJsonDepth: input.response_fixer_max_json_depth,
     responseFixerMaxFixSize: input.response_fixer_max_fix_size,
+    responseFixerFixToolArgs: input.response_fixer_fix_tool_args,
+    responseFixerDryRun: input.response_fixer_dry_run,
   });
 }
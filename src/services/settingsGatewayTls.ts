@@ -0,0 +1,23 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export type GatewayTlsCaExport = {
+  path: string;
+  pem: string;
+};
+
+export async function settingsGatewayTlsSet(enabled: boolean) {
+  return invokeTauriOrNull<AppSettings>("settings_gateway_tls_set", {
+    gatewayTlsEnabled: enabled,
+  });
+}
+
+export async function settingsGatewayTlsHostsSet(hosts: string[]) {
+  return invokeTauriOrNull<AppSettings>("settings_gateway_tls_hosts_set", {
+    gatewayTlsHosts: hosts,
+  });
+}
+
+export async function gatewayTlsCaExport() {
+  return invokeTauriOrNull<GatewayTlsCaExport>("gateway_tls_ca_export");
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, HeaderSanitizeRule } from "./settings";
+
+export async function settingsHeaderSanitizeRulesSet(rules: HeaderSanitizeRule[]) {
+  return invokeTauriOrNull<AppSettings>("settings_header_sanitize_rules_set", {
+    headerSanitizeRules: rules,
+  });
+}
@@ -0,0 +1,6 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, HedgedRequestsConfig } from "./settings";
+
+export async function settingsHedgedRequestsSet(hedgedRequests: HedgedRequestsConfig) {
+  return invokeTauriOrNull<AppSettings>("settings_hedged_requests_set", { hedgedRequests });
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, InflightDedupMode } from "./settings";
+
+export async function settingsInflightDedupModeSet(mode: InflightDedupMode) {
+  return invokeTauriOrNull<AppSettings>("settings_inflight_dedup_mode_set", {
+    inflightDedupMode: mode,
+  });
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, InterceptRule } from "./settings";
+
+export async function settingsInterceptRulesSet(rules: InterceptRule[]) {
+  return invokeTauriOrNull<AppSettings>("settings_intercept_rules_set", {
+    interceptRules: rules,
+  });
+}
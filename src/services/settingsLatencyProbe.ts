@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export async function settingsLatencyProbeIntervalSet(latencyProbeIntervalMinutes: number) {
+  return invokeTauriOrNull<AppSettings>("settings_latency_probe_interval_set", {
+    latencyProbeIntervalMinutes,
+  });
+}
@@ -0,0 +1,6 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, LatencyRoutingConfig } from "./settings";
+
+export async function settingsLatencyRoutingSet(latencyRouting: LatencyRoutingConfig) {
+  return invokeTauriOrNull<AppSettings>("settings_latency_routing_set", { latencyRouting });
+}
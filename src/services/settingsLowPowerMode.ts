@@ -0,0 +1,24 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export type PowerStatus = {
+  low_power_enabled: boolean;
+  on_battery: boolean | null;
+  idle_seconds: number;
+  idle: boolean;
+  suspended: boolean;
+};
+
+export async function settingsLowPowerModeSet(input: {
+  enableLowPowerMode: boolean;
+  lowPowerIdleMinutes: number;
+}) {
+  return invokeTauriOrNull<AppSettings>("settings_low_power_mode_set", {
+    enableLowPowerMode: input.enableLowPowerMode,
+    lowPowerIdleMinutes: input.lowPowerIdleMinutes,
+  });
+}
+
+export async function powerStatusGet() {
+  return invokeTauriOrNull<PowerStatus>("power_status_get");
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, NoticeChannelConfig } from "./settings";
+
+export async function settingsNoticeChannelsSet(channels: NoticeChannelConfig[]) {
+  return invokeTauriOrNull<AppSettings>("settings_notice_channels_set", {
+    noticeChannels: channels,
+  });
+}
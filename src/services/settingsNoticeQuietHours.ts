@@ -0,0 +1,13 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export type NoticeQuietHoursInput = {
+  enabled: boolean;
+  start: string;
+  end: string;
+  summary: boolean;
+};
+
+export async function settingsNoticeQuietHoursSet(input: NoticeQuietHoursInput) {
+  return invokeTauriOrNull<AppSettings>("settings_notice_quiet_hours_set", input);
+}
@@ -0,0 +1,13 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export type PriorityLanesInput = {
+  priorityLanesEnabled: boolean;
+  backgroundLaneMaxConcurrency: number;
+  backgroundLaneQueueTimeoutSeconds: number;
+  backgroundLaneProviderId: number | null;
+};
+
+export async function settingsPriorityLanesSet(input: PriorityLanesInput) {
+  return invokeTauriOrNull<AppSettings>("settings_priority_lanes_set", input);
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export async function settingsPromptCacheInjectionSet(enablePromptCacheInjection: boolean) {
+  return invokeTauriOrNull<AppSettings>("settings_prompt_cache_injection_set", {
+    enablePromptCacheInjection,
+  });
+}
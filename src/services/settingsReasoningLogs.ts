@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export async function settingsExcludeReasoningFromLogsSet(excludeReasoningFromLogs: boolean) {
+  return invokeTauriOrNull<AppSettings>("settings_exclude_reasoning_from_logs_set", {
+    excludeReasoningFromLogs,
+  });
+}
@@ -0,0 +1,6 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, RecentErrorCacheConfig } from "./settings";
+
+export async function settingsRecentErrorCacheSet(recentErrorCache: RecentErrorCacheConfig) {
+  return invokeTauriOrNull<AppSettings>("settings_recent_error_cache_set", { recentErrorCache });
+}
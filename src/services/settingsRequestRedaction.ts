@@ -0,0 +1,12 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, RedactionBuiltin } from "./settings";
+
+export type RequestRedactionInput = {
+  enableRequestRedaction: boolean;
+  requestRedactionBuiltins: RedactionBuiltin[];
+  requestRedactionCustomPatterns: string[];
+};
+
+export async function settingsRequestRedactionSet(input: RequestRedactionInput) {
+  return invokeTauriOrNull<AppSettings>("settings_request_redaction_set", input);
+}
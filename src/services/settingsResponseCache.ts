@@ -0,0 +1,14 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export async function settingsResponseCacheTtlSet(responseCacheTtlSeconds: number) {
+  return invokeTauriOrNull<AppSettings>("settings_response_cache_ttl_set", {
+    responseCacheTtlSeconds,
+  });
+}
+
+export async function settingsResponseCacheCompletionsSet(responseCacheCompletions: boolean) {
+  return invokeTauriOrNull<AppSettings>("settings_response_cache_completions_set", {
+    responseCacheCompletions,
+  });
+}
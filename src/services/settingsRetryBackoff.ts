@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, RetryBackoffConfig } from "./settings";
+
+export async function settingsRetryBackoffSet(retryBackoff: RetryBackoffConfig) {
+  return invokeTauriOrNull<AppSettings>("settings_retry_backoff_set", {
+    retryBackoff,
+  });
+}
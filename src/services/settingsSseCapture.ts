@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export async function settingsCaptureSseEventsSet(captureSseEvents: boolean) {
+  return invokeTauriOrNull<AppSettings>("settings_capture_sse_events_set", {
+    captureSseEvents,
+  });
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, CliStreamCaps } from "./settings";
+
+export async function settingsCliStreamCapsSet(cliMaxConcurrentStreams: CliStreamCaps) {
+  return invokeTauriOrNull<AppSettings>("settings_cli_stream_caps_set", {
+    cliMaxConcurrentStreams,
+  });
+}
@@ -0,0 +1,10 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, StreamToolPauseGraceConfig } from "./settings";
+
+export async function settingsStreamToolPauseGraceSet(
+  streamToolPauseGrace: StreamToolPauseGraceConfig
+) {
+  return invokeTauriOrNull<AppSettings>("settings_stream_tool_pause_grace_set", {
+    streamToolPauseGrace,
+  });
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings } from "./settings";
+
+export async function settingsForwardTraceHeadersSet(forwardTraceHeaders: boolean) {
+  return invokeTauriOrNull<AppSettings>("settings_forward_trace_headers_set", {
+    forwardTraceHeaders,
+  });
+}
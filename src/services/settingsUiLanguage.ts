@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppLanguage, AppSettings } from "./settings";
+
+export async function settingsUiLanguageSet(language: AppLanguage) {
+  return invokeTauriOrNull<AppSettings>("settings_ui_language_set", {
+    uiLanguage: language,
+  });
+}
@@ -0,0 +1,8 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, WslDistroOverride } from "./settings";
+
+export async function settingsWslDistroOverridesSet(overrides: WslDistroOverride[]) {
+  return invokeTauriOrNull<AppSettings>("settings_wsl_distro_overrides_set", {
+    wslDistroOverrides: overrides,
+  });
+}
@@ -1,9 +1,22 @@
 import { invokeTauriOrNull } from "./tauriInvoke";
 import type { CliKey } from "./providers";
 
+export type SortModeSettingsOverride = {
+  failover_max_attempts_per_provider?: number | null;
+  failover_max_providers_to_try?: number | null;
+  upstream_first_byte_timeout_seconds?: number | null;
+  upstream_stream_idle_timeout_seconds?: number | null;
+  upstream_request_timeout_non_streaming_seconds?: number | null;
+  enable_response_fixer?: boolean | null;
+};
+
+export type SortModeStrategy = "ordered" | "weighted" | "round_robin";
+
 export type SortModeSummary = {
   id: number;
   name: string;
+  settings: SortModeSettingsOverride;
+  strategy: SortModeStrategy;
   created_at: number;
   updated_at: number;
 };
@@ -31,12 +44,76 @@ export async function sortModeRename(input: { mode_id: number; name: string }) {
   });
 }
 
+export async function sortModeSettingsSet(input: {
+  mode_id: number;
+  settings: SortModeSettingsOverride;
+}) {
+  return invokeTauriOrNull<SortModeSummary>("sort_mode_settings_set", {
+    modeId: input.mode_id,
+    settings: input.settings,
+  });
+}
+
+export async function sortModeStrategySet(input: {
+  mode_id: number;
+  strategy: SortModeStrategy;
+}) {
+  return invokeTauriOrNull<SortModeSummary>("sort_mode_strategy_set", {
+    modeId: input.mode_id,
+    strategy: input.strategy,
+  });
+}
+
 export async function sortModeDelete(input: { mode_id: number }) {
   return invokeTauriOrNull<boolean>("sort_mode_delete", {
     modeId: input.mode_id,
   });
 }
 
+export type SortModeImportMatchKind = "exact" | "normalized" | "fuzzy" | "unmatched";
+
+export type SortModeImportMatch = {
+  cli_key: CliKey;
+  // provider name as written in the shared text
+  name: string;
+  weight: number;
+  kind: SortModeImportMatchKind;
+  provider_id: number | null;
+  provider_name: string | null;
+};
+
+export type SortModeImportPreview = {
+  name: string;
+  strategy: SortModeStrategy;
+  settings: SortModeSettingsOverride;
+  matches: SortModeImportMatch[];
+};
+
+export type SortModeImportResult = {
+  mode: SortModeSummary;
+  matches: SortModeImportMatch[];
+};
+
+// JSON share text; providers are referenced by name only (no base URLs / keys)
+export async function sortModeExport(input: { mode_id: number }) {
+  return invokeTauriOrNull<string>("sort_mode_export", {
+    modeId: input.mode_id,
+  });
+}
+
+export async function sortModeImportPreview(input: { text: string }) {
+  return invokeTauriOrNull<SortModeImportPreview>("sort_mode_import_preview", {
+    text: input.text,
+  });
+}
+
+export async function sortModeImport(input: { text: string; name?: string | null }) {
+  return invokeTauriOrNull<SortModeImportResult>("sort_mode_import", {
+    text: input.text,
+    name: input.name ?? null,
+  });
+}
+
 export async function sortModeActiveList() {
   return invokeTauriOrNull<SortModeActiveRow[]>("sort_mode_active_list");
 }
@@ -66,3 +143,82 @@ export async function sortModeProvidersSetOrder(input: {
     orderedProviderIds: input.ordered_provider_ids,
   });
 }
+
+export type SortModeProviderWeight = {
+  provider_id: number;
+  weight: number;
+};
+
+export async function sortModeProviderWeightsList(input: { mode_id: number; cli_key: CliKey }) {
+  return invokeTauriOrNull<SortModeProviderWeight[]>("sort_mode_provider_weights_list", {
+    modeId: input.mode_id,
+    cliKey: input.cli_key,
+  });
+}
+
+export async function sortModeProviderWeightSet(input: {
+  mode_id: number;
+  cli_key: CliKey;
+  provider_id: number;
+  weight: number;
+}) {
+  return invokeTauriOrNull<SortModeProviderWeight[]>("sort_mode_provider_weight_set", {
+    modeId: input.mode_id,
+    cliKey: input.cli_key,
+    providerId: input.provider_id,
+    weight: input.weight,
+  });
+}
+
+export type SortModeScheduleRule = {
+  id: number;
+  cli_key: CliKey;
+  mode_id: number | null;
+  start: string;
+  end: string;
+  enabled: boolean;
+  created_at: number;
+  updated_at: number;
+};
+
+export type SortModeScheduleRuleInput = {
+  mode_id: number | null;
+  start: string;
+  end: string;
+  enabled: boolean;
+};
+
+export type SortModeSwitchEvent = {
+  id: number;
+  created_at: number;
+  cli_key: CliKey;
+  from_mode_id: number | null;
+  to_mode_id: number | null;
+  schedule_id: number | null;
+};
+
+export async function sortModeScheduleList(input?: { cli_key?: CliKey | null }) {
+  return invokeTauriOrNull<SortModeScheduleRule[]>("sort_mode_schedule_list", {
+    cliKey: input?.cli_key ?? null,
+  });
+}
+
+export async function sortModeScheduleSet(input: {
+  cli_key: CliKey;
+  rules: SortModeScheduleRuleInput[];
+}) {
+  return invokeTauriOrNull<SortModeScheduleRule[]>("sort_mode_schedule_set", {
+    cliKey: input.cli_key,
+    rules: input.rules,
+  });
+}
+
+export async function sortModeSwitchEventsList(input?: {
+  cli_key?: CliKey | null;
+  limit?: number | null;
+}) {
+  return invokeTauriOrNull<SortModeSwitchEvent[]>("sort_mode_switch_events_list", {
+    cliKey: input?.cli_key ?? null,
+    limit: input?.limit ?? null,
+  });
+}
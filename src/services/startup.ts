@@ -31,6 +31,10 @@ export async function startupSyncModelPricesOnce(): Promise<void> {
   try {
     const report = await modelPricesSyncBasellm(false);
     if (!report) return;
+    if (report.status === "skipped_low_power") {
+      logToConsole("info", "初始化：低功耗模式，跳过模型定价同步");
+      return;
+    }
 
     markModelPricesSyncedOnce();
     notifyModelPricesUpdated();
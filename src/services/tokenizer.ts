@@ -0,0 +1,17 @@
+import { invokeTauriOrNull } from "./tauriInvoke";
+
+export type ModelFamily = "claude" | "open_ai" | "gemini" | "other";
+
+export type TokenEstimate = {
+  tokens: number;
+  chars: number;
+  model_family: ModelFamily;
+};
+
+export async function tokensEstimate(text: string, asRequest = false, model?: string) {
+  return invokeTauriOrNull<TokenEstimate>("tokens_estimate", {
+    text,
+    asRequest,
+    model: model ?? null,
+  });
+}
@@ -1,4 +1,5 @@
 import { hasTauriRuntime, invokeTauriOrNull } from "./tauriInvoke";
+import type { AppSettings, UpdateChannel, UpdateMirrorConfig } from "./settings";
 
 export type UpdaterCheckUpdate = {
   rid: number;
@@ -6,12 +7,13 @@ export type UpdaterCheckUpdate = {
   currentVersion?: string;
   date?: string;
   body?: string;
+  channel?: UpdateChannel;
 };
 
 export type UpdaterCheckResult = UpdaterCheckUpdate | null;
 
 export type UpdaterDownloadEvent =
-  | { event: "started"; data?: { contentLength?: number } }
+  | { event: "started"; data?: { contentLength?: number; source?: string } }
   | { event: "progress"; data?: { chunkLength?: number } }
   | { event: "finished"; data?: unknown };
 
@@ -37,11 +39,12 @@ export function parseUpdaterCheckResult(value: unknown): UpdaterCheckResult {
     currentVersion: asOptionalString(obj.currentVersion),
     date: asOptionalString(obj.date),
     body: asOptionalString(obj.body),
+    channel: obj.channel === "beta" || obj.channel === "stable" ? obj.channel : undefined,
   };
 }
 
 export async function updaterCheck(): Promise<UpdaterCheckResult> {
-  const raw = await invokeTauriOrNull<unknown>("plugin:updater|check");
+  const raw = await invokeTauriOrNull<unknown>("updater_check");
   return parseUpdaterCheckResult(raw);
 }
 
@@ -54,7 +57,10 @@ function parseUpdaterDownloadEvent(value: unknown): UpdaterDownloadEvent | null
   if (event === "started") {
     const startedData =
       data && typeof data === "object"
-        ? { contentLength: asOptionalNumber((data as Record<string, unknown>).contentLength) }
+        ? {
+            contentLength: asOptionalNumber((data as Record<string, unknown>).contentLength),
+            source: asOptionalString((data as Record<string, unknown>).source),
+          }
         : undefined;
     return { event, data: startedData };
   }
@@ -71,7 +77,6 @@ function parseUpdaterDownloadEvent(value: unknown): UpdaterDownloadEvent | null
 export async function updaterDownloadAndInstall(options: {
   rid: number;
   onEvent?: (event: UpdaterDownloadEvent) => void;
-  timeoutMs?: number;
 }): Promise<boolean | null> {
   if (!hasTauriRuntime()) return null;
 
@@ -84,14 +89,69 @@ export async function updaterDownloadAndInstall(options: {
     onEvent?.(evt);
   });
 
-  const args: Record<string, unknown> = {
-    rid: options.rid,
-    onEvent: channel,
-  };
-  if (typeof options.timeoutMs === "number" && Number.isFinite(options.timeoutMs)) {
-    args.timeout = options.timeoutMs;
-  }
-
-  await invoke("plugin:updater|download_and_install", args);
+  // Backend command (not the plugin one) so the download can fall back to configured mirrors.
+  await invoke("updater_download_and_install", { rid: options.rid, onEvent: channel });
   return true;
 }
+
+export async function settingsUpdateChannelSet(channel: UpdateChannel) {
+  return invokeTauriOrNull<AppSettings>("settings_update_channel_set", {
+    updateChannel: channel,
+  });
+}
+
+export async function settingsUpdateMirrorSet(updateMirror: UpdateMirrorConfig) {
+  return invokeTauriOrNull<AppSettings>("settings_update_mirror_set", { updateMirror });
+}
+
+export type UpdaterBackgroundDownloadPhase = "idle" | "downloading" | "ready" | "failed";
+
+export type UpdaterBackgroundDownloadState = {
+  phase: UpdaterBackgroundDownloadPhase;
+  version: string | null;
+  downloaded_bytes: number;
+  total_bytes: number | null;
+  // host of the source currently downloading (GitHub or a mirror)
+  source: string | null;
+  error: string | null;
+};
+
+export async function updaterBackgroundDownloadStart(rid: number) {
+  return invokeTauriOrNull<UpdaterBackgroundDownloadState>("updater_background_download_start", {
+    rid,
+  });
+}
+
+export async function updaterBackgroundDownloadStateGet() {
+  return invokeTauriOrNull<UpdaterBackgroundDownloadState>("updater_background_download_state");
+}
+
+export async function listenUpdaterBackgroundDownload(
+  onState: (state: UpdaterBackgroundDownloadState) => void
+): Promise<() => void> {
+  if (!hasTauriRuntime()) return () => {};
+  const { listen } = await import("@tauri-apps/api/event");
+  return listen<UpdaterBackgroundDownloadState>("updater:download", (event) => {
+    if (!event.payload) return;
+    onState(event.payload);
+  });
+}
+
+export async function settingsUpdateBackgroundDownloadSet(enabled: boolean) {
+  return invokeTauriOrNull<AppSettings>("settings_update_background_download_set", {
+    updateBackgroundDownload: enabled,
+  });
+}
+
+export type UpdaterRollbackInfo = {
+  current_version: string;
+  previous_version: string | null;
+};
+
+export async function updaterRollbackInfoGet() {
+  return invokeTauriOrNull<UpdaterRollbackInfo>("updater_rollback_info");
+}
+
+export async function updaterRollback() {
+  return invokeTauriOrNull<string>("updater_rollback");
+}
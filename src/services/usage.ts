@@ -10,6 +10,8 @@ export type UsageSummary = {
   requests_with_usage: number;
   requests_success: number;
   requests_failed: number;
+  // 529 overloaded upstream attempts (per attempt, including failed-over ones)
+  upstream_overloaded: number;
   avg_duration_ms: number | null;
   avg_ttfb_ms: number | null;
   avg_output_tokens_per_second: number | null;
@@ -79,6 +81,45 @@ export type UsageLeaderboardRow = {
   cost_usd: number | null;
 };
 
+export type UsageToolGroupBy = "tool" | "day" | "session";
+
+export type UsageToolRow = {
+  group_key: string | null;
+  tool_name: string;
+  is_mcp: boolean;
+  call_count: number;
+  session_count: number;
+  last_seen_at: number;
+};
+
+export type CodexSessionCompletionSummary = {
+  completions: number;
+  sessions: number;
+  generated: number;
+  reused_fingerprint_cache: number;
+  completed_missing_fields: number;
+  header_changes: number;
+  body_changes: number;
+};
+
+export type CodexSessionCompletionSessionRow = {
+  session_id: string;
+  completions: number;
+  generated: number;
+  reused_fingerprint_cache: number;
+  completed_missing_fields: number;
+  first_seen_at: number;
+  last_seen_at: number;
+  last_action: string;
+  last_source: string;
+  request_count: number;
+};
+
+export type CodexSessionCompletionReport = {
+  summary: CodexSessionCompletionSummary;
+  sessions: CodexSessionCompletionSessionRow[];
+};
+
 export async function usageSummary(range: UsageRange, input?: { cliKey?: CliKey | null }) {
   return invokeTauriOrNull<UsageSummary>("usage_summary", {
     range,
@@ -138,3 +179,22 @@ export async function usageLeaderboardV2(
     limit: input?.limit,
   });
 }
+
+export async function usageToolUsage(
+  range: UsageRange,
+  input?: { cliKey?: CliKey | null; groupBy?: UsageToolGroupBy; limit?: number }
+) {
+  return invokeTauriOrNull<UsageToolRow[]>("usage_tool_usage", {
+    range,
+    cliKey: input?.cliKey ?? null,
+    groupBy: input?.groupBy ?? "tool",
+    limit: input?.limit,
+  });
+}
+
+export async function usageCodexSessionCompletions(range: UsageRange, input?: { limit?: number }) {
+  return invokeTauriOrNull<CodexSessionCompletionReport>("usage_codex_session_completions", {
+    range,
+    limit: input?.limit,
+  });
+}
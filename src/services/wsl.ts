@@ -6,11 +6,20 @@ export type WslDetection = {
   distros: string[];
 };
 
+export type WslDistroConfiguredState = {
+  proxy_origin: string;
+  configured_at: number;
+  claude: boolean;
+  codex: boolean;
+  gemini: boolean;
+};
+
 export type WslDistroConfigStatus = {
   distro: string;
   claude: boolean;
   codex: boolean;
   gemini: boolean;
+  last_configured: WslDistroConfiguredState | null;
 };
 
 export type WslConfigureCliReport = {
@@ -25,10 +34,41 @@ export type WslConfigureDistroReport = {
   results: WslConfigureCliReport[];
 };
 
+export type WslFirewallReport = {
+  ok: boolean;
+  message: string;
+  rule_name: string;
+  remote_subnet: string | null;
+};
+
+export type WslPortProxyMapping = {
+  listen_address: string;
+  listen_port: number;
+  connect_address: string;
+  connect_port: number;
+};
+
+export type WslPortProxyReport = {
+  ok: boolean;
+  message: string;
+  mapping: WslPortProxyMapping | null;
+};
+
+export type WslNetworkingMode = "nat" | "mirrored";
+
+export type WslNetworkingDiagnostic = {
+  mode: WslNetworkingMode;
+  wslconfig_path: string | null;
+  message: string;
+};
+
 export type WslConfigureReport = {
   ok: boolean;
   message: string;
   distros: WslConfigureDistroReport[];
+  firewall: WslFirewallReport | null;
+  networking: WslNetworkingDiagnostic | null;
+  port_proxy: WslPortProxyReport | null;
 };
 
 export async function wslDetect() {
@@ -39,6 +79,18 @@ export async function wslHostAddressGet() {
   return invokeTauriOrNull<string | null>("wsl_host_address_get");
 }
 
+export async function wslNetworkingGet() {
+  return invokeTauriOrNull<WslNetworkingDiagnostic>("wsl_networking_get");
+}
+
+export async function wslPortProxyGet() {
+  return invokeTauriOrNull<WslPortProxyMapping | null>("wsl_port_proxy_get");
+}
+
+export async function wslPortProxyRemove() {
+  return invokeTauriOrNull<boolean>("wsl_port_proxy_remove");
+}
+
 export async function wslConfigStatusGet(distros?: string[]) {
   return invokeTauriOrNull<WslDistroConfigStatus[]>(
     "wsl_config_status_get",
@@ -46,8 +98,16 @@ export async function wslConfigStatusGet(distros?: string[]) {
   );
 }
 
-export async function wslConfigureClients(input: { targets: WslTargetCli }) {
+export async function wslConfigureClients(input: {
+  targets: WslTargetCli;
+  distros?: string[];
+  configureFirewall?: boolean;
+  portProxy?: boolean;
+}) {
   return invokeTauriOrNull<WslConfigureReport>("wsl_configure_clients", {
     targets: input.targets,
+    distros: input.distros ?? null,
+    configureFirewall: input.configureFirewall ?? false,
+    portProxy: input.portProxy ?? false,
   });
 }
@@ -4,6 +4,7 @@ import { AIO_RELEASES_URL, AIO_REPO_URL } from "../constants/urls";
 import { useGatewayMeta } from "../hooks/useGatewayMeta";
 import { updateDialogSetOpen, useUpdateMeta } from "../hooks/useUpdateMeta";
 import { cn } from "../utils/cn";
+import { formatRelativeTimeFromUnixSeconds } from "../utils/formatters";
 
 type NavItem = {
   to: string;
@@ -46,6 +47,22 @@ export function Sidebar() {
 
   const portText = gatewayAvailable === "available" ? String(gateway?.port ?? preferredPort) : "—";
 
+  const health = gateway?.running ? (gateway.health ?? null) : null;
+  const enabledProviders = health?.clis.reduce((sum, cli) => sum + cli.enabled_providers, 0) ?? 0;
+  const openCircuits = health?.clis.reduce((sum, cli) => sum + cli.open_circuits, 0) ?? 0;
+  const healthTitle = health
+    ? [
+        ...health.clis.map(
+          (cli) => `${cli.cli_key}：启用 ${cli.enabled_providers}，熔断 ${cli.open_circuits}`
+        ),
+        `请求日志队列：${health.request_log_queue.queued}/${health.request_log_queue.capacity}`,
+        `尝试日志队列：${health.attempt_log_queue.queued}/${health.attempt_log_queue.capacity}`,
+        health.last_error
+          ? `最近错误：${health.last_error.cli_key} ${health.last_error.error_code}`
+          : "最近错误：无",
+      ].join("\n")
+    : undefined;
+
   async function openReleases() {
     try {
       await openUrl(AIO_RELEASES_URL);
@@ -143,6 +160,38 @@ export function Sidebar() {
               <span className="font-mono text-slate-700">{portText}</span>
             </div>
           </div>
+          {health ? (
+            <div title={healthTitle}>
+              <div className="mt-2 grid grid-cols-[1fr_auto] items-center gap-3">
+                <span>供应商 / 熔断</span>
+                <div className="flex w-24 justify-center">
+                  <span
+                    className={cn(
+                      "font-mono",
+                      openCircuits > 0 ? "text-amber-600" : "text-slate-700"
+                    )}
+                  >
+                    {enabledProviders} / {openCircuits}
+                  </span>
+                </div>
+              </div>
+              <div className="mt-2 grid grid-cols-[1fr_auto] items-center gap-3">
+                <span>最近错误</span>
+                <div className="flex w-24 justify-center">
+                  <span
+                    className={cn(
+                      "truncate",
+                      health.last_error ? "text-rose-600" : "text-slate-700"
+                    )}
+                  >
+                    {health.last_error
+                      ? formatRelativeTimeFromUnixSeconds(health.last_error.at)
+                      : "无"}
+                  </span>
+                </div>
+              </div>
+            </div>
+          ) : null}
         </div>
       </div>
     </aside>